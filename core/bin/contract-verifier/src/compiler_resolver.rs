@@ -0,0 +1,625 @@
+//! Resolution of compiler versions for verification requests. Versions may be detected automatically
+//! from the `pragma` directives in the source code, and compiler binaries missing locally may be fetched
+//! from a configured mirror. Fetched binaries are only used if their SHA-256 checksums match the checksums
+//! pinned in [`CompilerResolver::CHECKSUMS_FILE`].
+
+use std::{
+    cmp::Reverse,
+    collections::HashMap,
+    fs,
+    io::{self, Write as _},
+    os::unix::fs::PermissionsExt as _,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context as _;
+use lazy_static::lazy_static;
+use regex::Regex;
+use semver::{Version, VersionReq};
+use sha2::{Digest, Sha256};
+use zksync_config::ContractVerifierConfig;
+use zksync_types::contract_verification_api::{
+    CompilerType, CompilerVersions, SourceCodeData, VerificationIncomingRequest,
+    AUTO_COMPILER_VERSION,
+};
+
+use crate::error::ContractVerifierError;
+
+lazy_static! {
+    static ref SOLIDITY_PRAGMA: Regex = Regex::new(r"pragma\s+solidity\s+([^;]+);").unwrap();
+    static ref VYPER_PRAGMA: Regex =
+        Regex::new(r"#\s*(?:@version|pragma\s+version)\s+([^\r\n]+)").unwrap();
+    static ref OPERATOR_WHITESPACE: Regex = Regex::new(r"(>=|<=|>|<|=|\^|~)\s+").unwrap();
+    static ref SEMVER: Regex = Regex::new(r"\d+\.\d+\.\d+").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Compiler {
+    ZkSolc,
+    Solc,
+    ZkVyper,
+    Vyper,
+}
+
+impl Compiler {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ZkSolc => "zksolc",
+            Self::Solc => "solc",
+            Self::ZkVyper => "zkvyper",
+            Self::Vyper => "vyper",
+        }
+    }
+}
+
+/// Version requirement extracted from a `pragma` directive.
+#[derive(Debug, Clone, PartialEq)]
+enum VersionRequirement {
+    Exact(Version),
+    Range(VersionReq),
+}
+
+impl VersionRequirement {
+    fn parse(pragma: &str) -> Option<Self> {
+        let pragma = OPERATOR_WHITESPACE.replace_all(pragma.trim(), "$1");
+        let comparators: Vec<_> = pragma.split_whitespace().collect();
+        if let [comparator] = comparators.as_slice() {
+            let version = comparator.strip_prefix('=').unwrap_or(comparator);
+            if let Ok(version) = Version::parse(version) {
+                return Some(Self::Exact(version));
+            }
+        }
+
+        // Unlike in `semver`, comparators without an operator denote exact versions in pragmas.
+        let comparators: Vec<_> = comparators
+            .into_iter()
+            .map(|comparator| {
+                if comparator.starts_with(|ch: char| ch.is_ascii_digit()) {
+                    format!("={comparator}")
+                } else {
+                    comparator.to_owned()
+                }
+            })
+            .collect();
+        VersionReq::parse(&comparators.join(", "))
+            .ok()
+            .map(Self::Range)
+    }
+
+    fn matches(&self, version: &Version) -> bool {
+        match self {
+            Self::Exact(exact) => exact == version,
+            Self::Range(req) => req.matches(version),
+        }
+    }
+}
+
+/// Extracts the semantic version from a compiler version name, e.g. `0.8.24` from `zkVM-0.8.24-1.0.0`.
+fn parse_version_name(name: &str) -> Option<Version> {
+    let version = SEMVER.find(name)?;
+    Version::parse(version.as_str()).ok()
+}
+
+/// Checks that a version name can be safely used as a directory name.
+fn is_valid_version_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '.' | '-' | '_' | '+'))
+}
+
+fn source_texts(source_code_data: &SourceCodeData) -> Vec<&str> {
+    match source_code_data {
+        SourceCodeData::SolSingleFile(source) | SourceCodeData::YulSingleFile(source) => {
+            vec![source.as_str()]
+        }
+        SourceCodeData::StandardJsonInput(input) => input
+            .get("sources")
+            .and_then(serde_json::Value::as_object)
+            .map(|sources| {
+                sources
+                    .values()
+                    .filter_map(|source| source.get("content")?.as_str())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        SourceCodeData::VyperMultiFile(sources) => sources.values().map(String::as_str).collect(),
+    }
+}
+
+/// Returns compiler version requirements declared in the source code. Requirements that cannot be parsed are skipped.
+fn detect_requirements(source_code_data: &SourceCodeData) -> Vec<VersionRequirement> {
+    let pragma = match source_code_data.compiler_type() {
+        CompilerType::Solc => &*SOLIDITY_PRAGMA,
+        CompilerType::Vyper => &*VYPER_PRAGMA,
+    };
+    source_texts(source_code_data)
+        .into_iter()
+        .flat_map(|source| pragma.captures_iter(source))
+        .filter_map(|captures| {
+            let pragma = captures.get(1)?.as_str();
+            let requirement = VersionRequirement::parse(pragma);
+            if requirement.is_none() {
+                tracing::info!("Skipping unsupported version pragma `{pragma}`");
+            }
+            requirement
+        })
+        .collect()
+}
+
+/// Parses pinned checksums in the `sha256sum` output format, i.e. `{hex_checksum}  {path}` lines.
+/// Empty lines and lines starting with `#` are skipped.
+fn parse_checksums(raw: &str) -> anyhow::Result<HashMap<String, [u8; 32]>> {
+    let mut checksums = HashMap::new();
+    for (i, line) in raw.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (checksum, path) = line
+            .split_once(char::is_whitespace)
+            .with_context(|| format!("line #{}: no path", i + 1))?;
+        // `sha256sum` prefixes paths with `*` in the binary mode.
+        let path = path.trim_start();
+        let path = path.strip_prefix('*').unwrap_or(path);
+        let mut parsed_checksum = [0_u8; 32];
+        hex::decode_to_slice(checksum, &mut parsed_checksum)
+            .with_context(|| format!("line #{}: invalid checksum", i + 1))?;
+        checksums.insert(path.to_owned(), parsed_checksum);
+    }
+    Ok(checksums)
+}
+
+/// Resolves compiler versions for verification requests and provides paths to compiler binaries.
+/// Binaries are looked up in `etc/{compiler}-bin/{version}/{compiler}` relative to the workspace.
+#[derive(Debug)]
+pub(crate) struct CompilerResolver {
+    home: PathBuf,
+    download_url: Option<String>,
+    max_candidates: usize,
+    client: reqwest::Client,
+}
+
+impl CompilerResolver {
+    /// Path to the file with pinned SHA-256 checksums of compiler binaries relative to the workspace.
+    /// The file uses the `sha256sum` output format with paths relative to the download URL
+    /// (e.g., `{checksum}  zksolc-bin/v1.4.1/zksolc`), so it can be generated by running
+    /// `sha256sum */*/*` in the mirror root. Binaries without a pinned checksum are never fetched.
+    pub const CHECKSUMS_FILE: &'static str = "etc/contract-verifier/compiler-checksums.txt";
+
+    pub fn new(home: &Path, config: &ContractVerifierConfig) -> Self {
+        Self {
+            home: home.to_owned(),
+            download_url: config.compilers_download_url.clone(),
+            max_candidates: config.max_compiler_candidates(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    fn relative_binary_path(compiler: Compiler, version: &str) -> String {
+        format!("{name}-bin/{version}/{name}", name = compiler.name())
+    }
+
+    /// Returns the pinned checksum for a compiler binary. The checksums file is re-read on each call
+    /// so that new compiler versions can be pinned without a restart; fetching binaries is rare, so this is cheap.
+    fn pinned_checksum(&self, relative_path: &str) -> anyhow::Result<Option<[u8; 32]>> {
+        let checksums_path = self.home.join(Self::CHECKSUMS_FILE);
+        let raw = match fs::read_to_string(&checksums_path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| format!("failed reading {checksums_path:?}"));
+            }
+        };
+        let checksums = parse_checksums(&raw)
+            .with_context(|| format!("invalid checksums file {checksums_path:?}"))?;
+        Ok(checksums.get(relative_path).copied())
+    }
+
+    fn binary_path(&self, compiler: Compiler, version: &str) -> PathBuf {
+        self.home
+            .join("etc")
+            .join(format!("{}-bin", compiler.name()))
+            .join(version)
+            .join(compiler.name())
+    }
+
+    /// Returns locally installed versions of the compiler, starting from the newest one.
+    fn installed_versions(&self, compiler: Compiler) -> Vec<String> {
+        let dir = self
+            .home
+            .join("etc")
+            .join(format!("{}-bin", compiler.name()));
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return vec![];
+        };
+        let mut versions: Vec<_> = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                if !entry.file_type().ok()?.is_dir() {
+                    return None;
+                }
+                let name = entry.file_name().into_string().ok()?;
+                entry.path().join(compiler.name()).exists().then_some(name)
+            })
+            .collect();
+        versions.sort_unstable_by_key(|name| Reverse((parse_version_name(name), name.clone())));
+        versions
+    }
+
+    /// Returns versions of the compiler satisfying all requirements, starting from the newest one.
+    /// If the requirements specify an exact version, it's returned even if it's not installed locally.
+    fn matching_versions(
+        &self,
+        compiler: Compiler,
+        requirements: &[VersionRequirement],
+    ) -> Vec<String> {
+        let exact_version = requirements
+            .iter()
+            .find_map(|requirement| match requirement {
+                VersionRequirement::Exact(version) => Some(version),
+                VersionRequirement::Range(_) => None,
+            });
+        let installed_versions = self.installed_versions(compiler);
+        let mut versions: Vec<_> = installed_versions
+            .into_iter()
+            .filter(|name| {
+                parse_version_name(name).map_or(false, |version| {
+                    requirements
+                        .iter()
+                        .all(|requirement| requirement.matches(&version))
+                })
+            })
+            .collect();
+
+        if let Some(exact_version) = exact_version {
+            let is_satisfiable = requirements
+                .iter()
+                .all(|requirement| requirement.matches(exact_version));
+            let exact_version = exact_version.to_string();
+            if is_satisfiable && !versions.contains(&exact_version) {
+                versions.insert(0, exact_version);
+            }
+        }
+        versions
+    }
+
+    /// Returns compiler versions to try for the request, in the order of preference. If the request specifies
+    /// both versions explicitly, returns them as is; otherwise, returns combinations of matching versions.
+    pub fn candidate_versions(
+        &self,
+        request: &VerificationIncomingRequest,
+    ) -> Result<Vec<CompilerVersions>, ContractVerifierError> {
+        let compiler_versions = &request.compiler_versions;
+        let (zk_compiler, compiler) = match compiler_versions.compiler_type() {
+            CompilerType::Solc => (Compiler::ZkSolc, Compiler::Solc),
+            CompilerType::Vyper => (Compiler::ZkVyper, Compiler::Vyper),
+        };
+
+        let zk_compiler_version = compiler_versions.zk_compiler_version();
+        let zk_compiler_versions = if zk_compiler_version == AUTO_COMPILER_VERSION {
+            self.installed_versions(zk_compiler)
+        } else {
+            vec![zk_compiler_version]
+        };
+        if zk_compiler_versions.is_empty() {
+            return Err(ContractVerifierError::NoMatchingCompilerVersion(
+                zk_compiler.name().to_owned(),
+            ));
+        }
+
+        let compiler_version = compiler_versions.compiler_version();
+        let compiler_versions = if compiler_version == AUTO_COMPILER_VERSION {
+            let requirements = detect_requirements(&request.source_code_data);
+            tracing::debug!(
+                "Detected {} requirements: {requirements:?}",
+                compiler.name()
+            );
+            self.matching_versions(compiler, &requirements)
+        } else {
+            vec![compiler_version]
+        };
+        if compiler_versions.is_empty() {
+            return Err(ContractVerifierError::NoMatchingCompilerVersion(
+                compiler.name().to_owned(),
+            ));
+        }
+
+        let candidates = zk_compiler_versions
+            .iter()
+            .flat_map(|zk_version| {
+                compiler_versions
+                    .iter()
+                    .map(move |version| match zk_compiler {
+                        Compiler::ZkSolc => CompilerVersions::Solc {
+                            compiler_zksolc_version: zk_version.clone(),
+                            compiler_solc_version: version.clone(),
+                        },
+                        _ => CompilerVersions::Vyper {
+                            compiler_zkvyper_version: zk_version.clone(),
+                            compiler_vyper_version: version.clone(),
+                        },
+                    })
+            })
+            .take(self.max_candidates)
+            .collect();
+        Ok(candidates)
+    }
+
+    /// Returns the path to the compiler binary, fetching it if it's missing locally and a download URL is configured.
+    pub async fn binary(
+        &self,
+        compiler: Compiler,
+        version: &str,
+    ) -> Result<PathBuf, ContractVerifierError> {
+        let unknown_version = || {
+            ContractVerifierError::UnknownCompilerVersion(
+                compiler.name().to_owned(),
+                version.to_owned(),
+            )
+        };
+        if !is_valid_version_name(version) {
+            return Err(unknown_version());
+        }
+        let path = self.binary_path(compiler, version);
+        if path.exists() {
+            return Ok(path);
+        }
+        let Some(download_url) = &self.download_url else {
+            return Err(unknown_version());
+        };
+
+        let relative_path = Self::relative_binary_path(compiler, version);
+        let expected_checksum = self.pinned_checksum(&relative_path).map_err(|err| {
+            tracing::error!("Failed loading pinned compiler checksums: {err:#}");
+            ContractVerifierError::InternalError
+        })?;
+        let Some(expected_checksum) = expected_checksum else {
+            tracing::warn!(
+                "Not fetching {} {version} since it has no pinned checksum in `{}`",
+                compiler.name(),
+                Self::CHECKSUMS_FILE
+            );
+            return Err(unknown_version());
+        };
+
+        let url = format!("{}/{relative_path}", download_url.trim_end_matches('/'));
+        tracing::info!("Fetching {} {version} from {url}", compiler.name());
+        let response = self
+            .client
+            .get(&url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status);
+        let bytes = match response {
+            Ok(response) => response.bytes().await,
+            Err(err) => Err(err),
+        };
+        let bytes = bytes.map_err(|err| {
+            tracing::warn!("Failed fetching {} {version}: {err}", compiler.name());
+            unknown_version()
+        })?;
+
+        let actual_checksum: [u8; 32] = Sha256::digest(&bytes).into();
+        if actual_checksum != expected_checksum {
+            tracing::error!(
+                "Checksum mismatch for {} {version} fetched from {url}: expected {}, got {}",
+                compiler.name(),
+                hex::encode(expected_checksum),
+                hex::encode(actual_checksum)
+            );
+            return Err(unknown_version());
+        }
+
+        Self::save_binary(&path, &bytes).map_err(|err| {
+            tracing::error!(
+                "Failed saving {} {version} to {path:?}: {err}",
+                compiler.name()
+            );
+            ContractVerifierError::InternalError
+        })?;
+        tracing::info!("Cached {} {version} at {path:?}", compiler.name());
+        Ok(path)
+    }
+
+    fn save_binary(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+        let dir = path.parent().expect("binary path has no parent");
+        fs::create_dir_all(dir)?;
+        // Write to a temporary file first so that concurrent jobs never observe a partially written binary.
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        file.write_all(bytes)?;
+        file.as_file()
+            .set_permissions(fs::Permissions::from_mode(0o755))?;
+        file.persist(path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_binaries(home: &Path, compiler: Compiler, versions: &[&str]) {
+        for version in versions {
+            let dir = home
+                .join("etc")
+                .join(format!("{}-bin", compiler.name()))
+                .join(version);
+            fs::create_dir_all(&dir).unwrap();
+            fs::write(dir.join(compiler.name()), "").unwrap();
+        }
+    }
+
+    fn mock_config() -> ContractVerifierConfig {
+        ContractVerifierConfig {
+            compilation_timeout: 30,
+            polling_interval: None,
+            prometheus_port: 3314,
+            threads_per_server: None,
+            port: 3070,
+            url: "127.0.0.1:3070".to_owned(),
+            compilers_download_url: None,
+            max_compiler_candidates: Some(4),
+        }
+    }
+
+    fn mock_request(
+        source_code: &str,
+        zksolc_version: &str,
+        solc_version: &str,
+    ) -> VerificationIncomingRequest {
+        serde_json::from_value(serde_json::json!({
+            "contractAddress": "0x0000000000000000000000000000000000010000",
+            "sourceCode": source_code,
+            "contractName": "Test",
+            "compilerZksolcVersion": zksolc_version,
+            "compilerSolcVersion": solc_version,
+            "optimizationUsed": true,
+            "optimizerMode": null,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn parsing_version_requirements() {
+        let version = |s: &str| Version::parse(s).unwrap();
+
+        assert_eq!(
+            VersionRequirement::parse("0.8.24"),
+            Some(VersionRequirement::Exact(version("0.8.24")))
+        );
+        assert_eq!(
+            VersionRequirement::parse("= 0.8.24"),
+            Some(VersionRequirement::Exact(version("0.8.24")))
+        );
+
+        let caret = VersionRequirement::parse("^0.8.0").unwrap();
+        assert!(caret.matches(&version("0.8.24")));
+        assert!(!caret.matches(&version("0.9.0")));
+
+        let range = VersionRequirement::parse(">= 0.7.0 <0.8.20").unwrap();
+        assert!(range.matches(&version("0.7.6")));
+        assert!(range.matches(&version("0.8.19")));
+        assert!(!range.matches(&version("0.8.20")));
+
+        assert_eq!(VersionRequirement::parse("^0.7.0 || ^0.8.0"), None);
+    }
+
+    #[test]
+    fn parsing_version_names() {
+        let version = |s: &str| Some(Version::parse(s).unwrap());
+
+        assert_eq!(parse_version_name("0.8.24"), version("0.8.24"));
+        assert_eq!(parse_version_name("v1.4.1"), version("1.4.1"));
+        assert_eq!(parse_version_name("zkVM-0.8.24-1.0.0"), version("0.8.24"));
+        assert_eq!(parse_version_name("vm-1.5.0-a167aa3"), version("1.5.0"));
+        assert_eq!(parse_version_name("latest"), None);
+
+        assert!(is_valid_version_name("zkVM-0.8.24-1.0.0"));
+        assert!(!is_valid_version_name("../0.8.24"));
+        assert!(!is_valid_version_name("0.8.24/solc"));
+    }
+
+    #[test]
+    fn parsing_checksums() {
+        let raw = "\
+            # Pinned compiler checksums\n\
+            e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  zksolc-bin/v1.4.1/zksolc\n\
+            \n\
+            0000000000000000000000000000000000000000000000000000000000000001 *solc-bin/0.8.24/solc\n";
+        let checksums = parse_checksums(raw).unwrap();
+        assert_eq!(checksums.len(), 2);
+        assert_eq!(
+            checksums["zksolc-bin/v1.4.1/zksolc"],
+            <[u8; 32]>::from(Sha256::digest(b""))
+        );
+        assert_eq!(checksums["solc-bin/0.8.24/solc"][31], 1);
+
+        let err = parse_checksums("not-a-checksum  zksolc-bin/v1.4.1/zksolc").unwrap_err();
+        assert!(format!("{err:#}").contains("line #1"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn binaries_without_pinned_checksums_are_not_fetched() {
+        let home = tempfile::tempdir().unwrap();
+        let config = ContractVerifierConfig {
+            // The URL is never accessed.
+            compilers_download_url: Some("http://127.0.0.1:1".to_owned()),
+            ..mock_config()
+        };
+        let resolver = CompilerResolver::new(home.path(), &config);
+        let err = resolver.binary(Compiler::Solc, "0.8.24").await.unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::UnknownCompilerVersion(..)),
+            "{err:?}"
+        );
+
+        let checksums_path = home.path().join(CompilerResolver::CHECKSUMS_FILE);
+        fs::create_dir_all(checksums_path.parent().unwrap()).unwrap();
+        fs::write(&checksums_path, "invalid").unwrap();
+        let err = resolver.binary(Compiler::Solc, "0.8.24").await.unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::InternalError),
+            "{err:?}"
+        );
+        assert!(!resolver.binary_path(Compiler::Solc, "0.8.24").exists());
+    }
+
+    #[test]
+    fn resolving_candidate_versions() {
+        let home = tempfile::tempdir().unwrap();
+        create_binaries(
+            home.path(),
+            Compiler::ZkSolc,
+            &["v1.3.23", "v1.4.1", "v1.4.0"],
+        );
+        create_binaries(
+            home.path(),
+            Compiler::Solc,
+            &["0.7.6", "0.8.19", "0.8.24", "zkVM-0.8.24-1.0.0"],
+        );
+        let resolver = CompilerResolver::new(home.path(), &mock_config());
+
+        let source = "pragma solidity ^0.8.0; contract Test {}";
+        let request = mock_request(source, "v1.4.1", "0.8.24");
+        let candidates = resolver.candidate_versions(&request).unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].compiler_version(), "0.8.24");
+
+        let request = mock_request(source, "v1.4.1", AUTO_COMPILER_VERSION);
+        let candidates = resolver.candidate_versions(&request).unwrap();
+        let solc_versions: Vec<_> = candidates
+            .iter()
+            .map(CompilerVersions::compiler_version)
+            .collect();
+        assert_eq!(solc_versions, ["zkVM-0.8.24-1.0.0", "0.8.24", "0.8.19"]);
+
+        let request = mock_request(source, AUTO_COMPILER_VERSION, AUTO_COMPILER_VERSION);
+        let candidates = resolver.candidate_versions(&request).unwrap();
+        assert_eq!(candidates.len(), 4); // limited by `max_compiler_candidates`
+        assert_eq!(candidates[0].zk_compiler_version(), "v1.4.1");
+        assert_eq!(candidates[3].zk_compiler_version(), "v1.4.0");
+
+        // Exact versions are returned even if they aren't installed, so that they can be fetched.
+        let request = mock_request(
+            "pragma solidity 0.8.25; contract Test {}",
+            "v1.4.1",
+            AUTO_COMPILER_VERSION,
+        );
+        let candidates = resolver.candidate_versions(&request).unwrap();
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].compiler_version(), "0.8.25");
+
+        let request = mock_request(
+            "pragma solidity ^0.6.0; contract Test {}",
+            "v1.4.1",
+            AUTO_COMPILER_VERSION,
+        );
+        let err = resolver.candidate_versions(&request).unwrap_err();
+        assert!(
+            matches!(err, ContractVerifierError::NoMatchingCompilerVersion(_)),
+            "{err:?}"
+        );
+    }
+}
@@ -14,6 +14,8 @@ pub enum ContractVerifierError {
     CompilationError(serde_json::Value),
     #[error("Unknown {0} version: {1}")]
     UnknownCompilerVersion(String, String),
+    #[error("No {0} version matching the source code is available")]
+    NoMatchingCompilerVersion(String),
     #[error("Contract with {0} name is missing in sources")]
     MissingContract(String),
     #[error("There is no {0} source file")]
@@ -23,3 +25,17 @@ pub enum ContractVerifierError {
     #[error("Failed to deserialize standard JSON input")]
     FailedToDeserializeInput,
 }
+
+impl ContractVerifierError {
+    /// Checks whether the error may be caused by the specific compiler versions used, i.e., whether compiling
+    /// the request with other versions may succeed.
+    pub(crate) fn is_version_specific(&self) -> bool {
+        matches!(
+            self,
+            Self::BytecodeMismatch
+                | Self::CompilerError(..)
+                | Self::CompilationError(_)
+                | Self::UnknownCompilerVersion(..)
+        )
+    }
+}
@@ -16,6 +16,7 @@ use zksync_utils::{wait_for_tasks::ManagedTasks, workspace_dir_or_current_dir};
 
 use crate::verifier::ContractVerifier;
 
+mod compiler_resolver;
 pub mod error;
 mod metrics;
 pub mod verifier;
@@ -1,6 +1,7 @@
 use std::{
     collections::HashMap,
     path::Path,
+    sync::Arc,
     time::{Duration, Instant},
 };
 
@@ -16,14 +17,15 @@ use zksync_env_config::FromEnv;
 use zksync_queued_job_processor::{async_trait, JobProcessor};
 use zksync_types::{
     contract_verification_api::{
-        CompilationArtifacts, CompilerType, DeployContractCalldata, SourceCodeData,
-        VerificationInfo, VerificationRequest,
+        CompilationArtifacts, CompilerType, CompilerVersions, DeployContractCalldata,
+        SourceCodeData, VerificationInfo, VerificationRequest, VerificationTarget,
     },
     Address,
 };
 use zksync_utils::workspace_dir_or_current_dir;
 
 use crate::{
+    compiler_resolver::{Compiler, CompilerResolver},
     error::ContractVerifierError,
     metrics::API_CONTRACT_VERIFIER_METRICS,
     zksolc_utils::{Optimizer, Settings, Source, StandardJson, ZkSolc, ZkSolcInput, ZkSolcOutput},
@@ -34,10 +36,6 @@ lazy_static! {
     static ref DEPLOYER_CONTRACT: Contract = zksync_contracts::deployer_contract();
 }
 
-fn home_path() -> &'static Path {
-    workspace_dir_or_current_dir()
-}
-
 #[derive(Debug)]
 enum ConstructorArgs {
     Check(Vec<u8>),
@@ -48,13 +46,16 @@ enum ConstructorArgs {
 pub struct ContractVerifier {
     config: ContractVerifierConfig,
     connection_pool: ConnectionPool<Core>,
+    compiler_resolver: Arc<CompilerResolver>,
 }
 
 impl ContractVerifier {
     pub fn new(config: ContractVerifierConfig, connection_pool: ConnectionPool<Core>) -> Self {
+        let compiler_resolver = CompilerResolver::new(workspace_dir_or_current_dir(), &config);
         Self {
             config,
             connection_pool,
+            compiler_resolver: Arc::new(compiler_resolver),
         }
     }
 
@@ -62,32 +63,50 @@ impl ContractVerifier {
         storage: &mut Connection<'_, Core>,
         mut request: VerificationRequest,
         config: ContractVerifierConfig,
+        compiler_resolver: &CompilerResolver,
     ) -> Result<VerificationInfo, ContractVerifierError> {
-        let artifacts = Self::compile(request.clone(), config).await?;
+        let candidate_versions = compiler_resolver.candidate_versions(&request.req)?;
 
         // Bytecode should be present because it is checked when accepting request.
-        let (deployed_bytecode, creation_tx_calldata) = storage
-            .contract_verification_dal()
-            .get_contract_info_for_verification(request.req.contract_address).await
-            .unwrap()
-            .ok_or_else(|| {
-                tracing::warn!("Contract is missing in DB for already accepted verification request. Contract address: {:#?}", request.req.contract_address);
-                ContractVerifierError::InternalError
-            })?;
-        let constructor_args = Self::decode_constructor_arguments_from_calldata(
-            creation_tx_calldata,
-            request.req.contract_address,
-        );
+        let (deployed_bytecode, constructor_args) = match request.req.target {
+            VerificationTarget::Address { contract_address } => {
+                let (deployed_bytecode, creation_tx_calldata) = storage
+                    .contract_verification_dal()
+                    .get_contract_info_for_verification(contract_address).await
+                    .unwrap()
+                    .ok_or_else(|| {
+                        tracing::warn!("Contract is missing in DB for already accepted verification request. Contract address: {contract_address:#?}");
+                        ContractVerifierError::InternalError
+                    })?;
+                let constructor_args = Self::decode_constructor_arguments_from_calldata(
+                    creation_tx_calldata,
+                    contract_address,
+                );
+                (deployed_bytecode, constructor_args)
+            }
+            VerificationTarget::BytecodeHash { bytecode_hash } => {
+                let bytecode = storage
+                    .factory_deps_dal()
+                    .get_sealed_factory_dep(bytecode_hash)
+                    .await
+                    .unwrap()
+                    .ok_or_else(|| {
+                        tracing::warn!("Bytecode is missing in DB for already accepted verification request. Bytecode hash: {bytecode_hash:?}");
+                        ContractVerifierError::InternalError
+                    })?;
+                // There is no deployment transaction to take constructor arguments from.
+                (bytecode, ConstructorArgs::Ignore)
+            }
+        };
 
-        if artifacts.bytecode != deployed_bytecode {
-            tracing::info!(
-                "Bytecode mismatch req {}, deployed: 0x{}, compiled 0x{}",
-                request.id,
-                hex::encode(deployed_bytecode),
-                hex::encode(artifacts.bytecode)
-            );
-            return Err(ContractVerifierError::BytecodeMismatch);
-        }
+        let artifacts = Self::compile_matching(
+            &mut request,
+            candidate_versions,
+            &deployed_bytecode,
+            &config,
+            compiler_resolver,
+        )
+        .await?;
 
         match constructor_args {
             ConstructorArgs::Check(args) => {
@@ -107,9 +126,62 @@ impl ContractVerifier {
         })
     }
 
+    /// Compiles the request with each of the candidate compiler versions until the produced bytecode matches
+    /// the deployed one. On success, `request` is updated to specify the matching versions. Errors that don't depend
+    /// on compiler versions (e.g., a missing contract or a compilation timeout) are returned immediately.
+    async fn compile_matching(
+        request: &mut VerificationRequest,
+        candidate_versions: Vec<CompilerVersions>,
+        deployed_bytecode: &[u8],
+        config: &ContractVerifierConfig,
+        compiler_resolver: &CompilerResolver,
+    ) -> Result<CompilationArtifacts, ContractVerifierError> {
+        let mut last_error = None;
+        for compiler_versions in candidate_versions {
+            request.req.compiler_versions = compiler_versions;
+            let compilation_result =
+                Self::compile(request.clone(), config.clone(), compiler_resolver).await;
+            let error = match compilation_result {
+                Ok(artifacts) if artifacts.bytecode == deployed_bytecode => return Ok(artifacts),
+                Ok(artifacts) => {
+                    tracing::info!(
+                        "Bytecode mismatch req {}, compiler versions {:?}, deployed: 0x{}, compiled 0x{}",
+                        request.id,
+                        request.req.compiler_versions,
+                        hex::encode(deployed_bytecode),
+                        hex::encode(artifacts.bytecode)
+                    );
+                    ContractVerifierError::BytecodeMismatch
+                }
+                Err(err) if !err.is_version_specific() => {
+                    tracing::info!(
+                        "Failed compiling req {} with compiler versions {:?}: {err}; not trying other versions",
+                        request.id,
+                        request.req.compiler_versions
+                    );
+                    return Err(err);
+                }
+                Err(err) => {
+                    tracing::info!(
+                        "Failed compiling req {} with compiler versions {:?}: {err}",
+                        request.id,
+                        request.req.compiler_versions
+                    );
+                    err
+                }
+            };
+            // A bytecode mismatch is more informative than failures with other candidate versions.
+            if !matches!(last_error, Some(ContractVerifierError::BytecodeMismatch)) {
+                last_error = Some(error);
+            }
+        }
+        Err(last_error.expect("no candidate compiler versions"))
+    }
+
     async fn compile_zksolc(
         request: VerificationRequest,
         config: ContractVerifierConfig,
+        compiler_resolver: &CompilerResolver,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
         // Users may provide either just contract name or
         // source file name and contract name joined with ":".
@@ -124,29 +196,18 @@ impl ContractVerifier {
             };
         let input = Self::build_zksolc_input(request.clone(), file_name.clone())?;
 
-        let zksolc_path = Path::new(&home_path())
-            .join("etc")
-            .join("zksolc-bin")
-            .join(request.req.compiler_versions.zk_compiler_version())
-            .join("zksolc");
-        if !zksolc_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "zksolc".to_string(),
-                request.req.compiler_versions.zk_compiler_version(),
-            ));
-        }
-
-        let solc_path = Path::new(&home_path())
-            .join("etc")
-            .join("solc-bin")
-            .join(request.req.compiler_versions.compiler_version())
-            .join("solc");
-        if !solc_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "solc".to_string(),
-                request.req.compiler_versions.compiler_version(),
-            ));
-        }
+        let zksolc_path = compiler_resolver
+            .binary(
+                Compiler::ZkSolc,
+                &request.req.compiler_versions.zk_compiler_version(),
+            )
+            .await?;
+        let solc_path = compiler_resolver
+            .binary(
+                Compiler::Solc,
+                &request.req.compiler_versions.compiler_version(),
+            )
+            .await?;
 
         let zksolc = ZkSolc::new(zksolc_path, solc_path);
 
@@ -211,6 +272,7 @@ impl ContractVerifier {
     async fn compile_zkvyper(
         request: VerificationRequest,
         config: ContractVerifierConfig,
+        compiler_resolver: &CompilerResolver,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
         // Users may provide either just contract name or
         // source file name and contract name joined with ":".
@@ -222,29 +284,18 @@ impl ContractVerifier {
             };
         let input = Self::build_zkvyper_input(request.clone())?;
 
-        let zkvyper_path = Path::new(&home_path())
-            .join("etc")
-            .join("zkvyper-bin")
-            .join(request.req.compiler_versions.zk_compiler_version())
-            .join("zkvyper");
-        if !zkvyper_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "zkvyper".to_string(),
-                request.req.compiler_versions.zk_compiler_version(),
-            ));
-        }
-
-        let vyper_path = Path::new(&home_path())
-            .join("etc")
-            .join("vyper-bin")
-            .join(request.req.compiler_versions.compiler_version())
-            .join("vyper");
-        if !vyper_path.exists() {
-            return Err(ContractVerifierError::UnknownCompilerVersion(
-                "vyper".to_string(),
-                request.req.compiler_versions.compiler_version(),
-            ));
-        }
+        let zkvyper_path = compiler_resolver
+            .binary(
+                Compiler::ZkVyper,
+                &request.req.compiler_versions.zk_compiler_version(),
+            )
+            .await?;
+        let vyper_path = compiler_resolver
+            .binary(
+                Compiler::Vyper,
+                &request.req.compiler_versions.compiler_version(),
+            )
+            .await?;
 
         let zkvyper = ZkVyper::new(zkvyper_path, vyper_path);
 
@@ -277,10 +328,11 @@ impl ContractVerifier {
     async fn compile(
         request: VerificationRequest,
         config: ContractVerifierConfig,
+        compiler_resolver: &CompilerResolver,
     ) -> Result<CompilationArtifacts, ContractVerifierError> {
         match request.req.source_code_data.compiler_type() {
-            CompilerType::Solc => Self::compile_zksolc(request, config).await,
-            CompilerType::Vyper => Self::compile_zkvyper(request, config).await,
+            CompilerType::Solc => Self::compile_zksolc(request, config, compiler_resolver).await,
+            CompilerType::Vyper => Self::compile_zkvyper(request, config, compiler_resolver).await,
         }
     }
 
@@ -515,6 +567,7 @@ impl JobProcessor for ContractVerifier {
         started_at: Instant,
     ) -> tokio::task::JoinHandle<anyhow::Result<()>> {
         let connection_pool = self.connection_pool.clone();
+        let compiler_resolver = self.compiler_resolver.clone();
         tokio::task::spawn(async move {
             tracing::info!("Started to process request with id = {}", job.id);
 
@@ -523,7 +576,8 @@ impl JobProcessor for ContractVerifier {
             let mut connection = connection_pool.connection().await.unwrap();
 
             let job_id = job.id;
-            let verification_result = Self::verify(&mut connection, job, config).await;
+            let verification_result =
+                Self::verify(&mut connection, job, config, &compiler_resolver).await;
             Self::process_result(&mut connection, job_id, verification_result).await;
 
             API_CONTRACT_VERIFIER_METRICS
@@ -0,0 +1,348 @@
+//! Admin HTTP server allowing node operators to control pruning, state keeper cache maintenance and log directives
+//! at runtime, and to inspect the consensus component and slow API calls.
+
+use std::{net::SocketAddr, ops, sync::Arc};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+use serde::{Deserialize, Serialize};
+use tokio::sync::watch;
+use vlog::{LogDirectivesError, LogDirectivesHandle};
+use zksync_config::configs::consensus::ConsensusConfig;
+use zksync_node_api_server::web3::slow_calls::SlowCallsSampler;
+use zksync_node_consensus::{PeerInfo, PeersHandle};
+use zksync_node_db_pruner::{DbPrunerHandle, ProtectRangeError};
+use zksync_node_sync::SyncState;
+use zksync_state::RocksdbCompactionHandle;
+use zksync_types::{api::SlowCallInfo, L1BatchNumber, L2BlockNumber};
+
+/// Inclusive L1 batch range as represented in requests / responses of the admin server.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct L1BatchRange {
+    start: L1BatchNumber,
+    end: L1BatchNumber,
+}
+
+impl From<ops::RangeInclusive<L1BatchNumber>> for L1BatchRange {
+    fn from(range: ops::RangeInclusive<L1BatchNumber>) -> Self {
+        Self {
+            start: *range.start(),
+            end: *range.end(),
+        }
+    }
+}
+
+impl From<L1BatchRange> for ops::RangeInclusive<L1BatchNumber> {
+    fn from(range: L1BatchRange) -> Self {
+        range.start..=range.end
+    }
+}
+
+/// Consensus component information and controls available to the admin server.
+#[derive(Debug, Clone)]
+pub(crate) struct ConsensusInfo {
+    config: ConsensusConfig,
+    sync_state: SyncState,
+    peers: PeersHandle,
+}
+
+impl ConsensusInfo {
+    pub fn new(config: ConsensusConfig, sync_state: SyncState, peers: PeersHandle) -> Self {
+        Self {
+            config,
+            sync_state,
+            peers,
+        }
+    }
+}
+
+/// Gossip peers and block dissemination status of the consensus node.
+#[derive(Debug, Serialize)]
+struct ConsensusStatus {
+    public_addr: String,
+    gossip_dynamic_inbound_limit: usize,
+    peers: Vec<PeerInfo>,
+    banned_peers: Vec<String>,
+    main_node_block: L2BlockNumber,
+    local_block: L2BlockNumber,
+    /// Number of L2 blocks this node lags behind the main node.
+    block_lag: u32,
+}
+
+/// Gossip peer as represented in requests of the admin server.
+#[derive(Debug, Deserialize)]
+struct Peer {
+    key: String,
+}
+
+/// Log directives as represented in requests / responses of the admin server.
+#[derive(Debug, Serialize, Deserialize)]
+struct LogDirectives {
+    directives: String,
+}
+
+/// Currently active and initial log directives.
+#[derive(Debug, Serialize)]
+struct LogDirectivesStatus {
+    current: String,
+    initial: String,
+}
+
+/// Query params for the slow calls endpoint.
+#[derive(Debug, Deserialize)]
+struct SlowCallsQuery {
+    method: Option<String>,
+}
+
+type HandlerResult<T> = Result<T, (StatusCode, String)>;
+
+fn internal_error(err: impl Into<anyhow::Error>) -> (StatusCode, String) {
+    let err = err.into();
+    tracing::warn!("Error processing admin request: {err:#}");
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{err:#}"))
+}
+
+async fn trigger_pruning(pruner: State<DbPrunerHandle>) -> StatusCode {
+    tracing::info!("Pruning was triggered via admin server");
+    pruner.trigger_pruning();
+    StatusCode::ACCEPTED
+}
+
+async fn get_protected_ranges(
+    pruner: State<DbPrunerHandle>,
+) -> HandlerResult<Json<Vec<L1BatchRange>>> {
+    let ranges = pruner
+        .protected_l1_batch_ranges()
+        .await
+        .map_err(internal_error)?;
+    Ok(Json(ranges.into_iter().map(L1BatchRange::from).collect()))
+}
+
+async fn protect_range(
+    pruner: State<DbPrunerHandle>,
+    Json(range): Json<L1BatchRange>,
+) -> HandlerResult<StatusCode> {
+    match pruner.protect_l1_batch_range(range.into()).await {
+        Ok(true) => {
+            tracing::info!("Protected L1 batch range {range:?} from pruning");
+            Ok(StatusCode::CREATED)
+        }
+        Ok(false) => Ok(StatusCode::OK),
+        Err(err @ (ProtectRangeError::EmptyRange(_) | ProtectRangeError::AlreadyPruned { .. })) => {
+            Err((StatusCode::BAD_REQUEST, err.to_string()))
+        }
+        Err(ProtectRangeError::Internal(err)) => Err(internal_error(err)),
+    }
+}
+
+async fn unprotect_range(
+    pruner: State<DbPrunerHandle>,
+    Json(range): Json<L1BatchRange>,
+) -> HandlerResult<StatusCode> {
+    let removed = pruner
+        .unprotect_l1_batch_range(range.into())
+        .await
+        .map_err(internal_error)?;
+    if removed {
+        tracing::info!("Removed pruning protection from L1 batch range {range:?}");
+        Ok(StatusCode::OK)
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            format!("L1 batch range {range:?} is not protected"),
+        ))
+    }
+}
+
+async fn trigger_compaction(compaction: State<RocksdbCompactionHandle>) -> StatusCode {
+    tracing::info!("State keeper cache compaction was triggered via admin server");
+    compaction.trigger_compaction();
+    StatusCode::ACCEPTED
+}
+
+async fn get_log_directives(
+    handle: State<LogDirectivesHandle>,
+) -> HandlerResult<Json<LogDirectivesStatus>> {
+    let current = handle.current().map_err(internal_error)?;
+    Ok(Json(LogDirectivesStatus {
+        current,
+        initial: handle.initial().to_owned(),
+    }))
+}
+
+fn log_directives_error(err: LogDirectivesError) -> (StatusCode, String) {
+    match err {
+        LogDirectivesError::Parse(_) => (StatusCode::BAD_REQUEST, err.to_string()),
+        LogDirectivesError::Reload(_) => internal_error(err),
+    }
+}
+
+async fn set_log_directives(
+    handle: State<LogDirectivesHandle>,
+    Json(directives): Json<LogDirectives>,
+) -> HandlerResult<StatusCode> {
+    tracing::info!(
+        "Log directives were changed to `{}` via admin server",
+        directives.directives
+    );
+    handle
+        .set(&directives.directives)
+        .map_err(log_directives_error)?;
+    Ok(StatusCode::OK)
+}
+
+async fn reset_log_directives(handle: State<LogDirectivesHandle>) -> HandlerResult<StatusCode> {
+    tracing::info!("Log directives were reset via admin server");
+    handle.reset().map_err(log_directives_error)?;
+    Ok(StatusCode::OK)
+}
+
+async fn get_consensus_status(info: State<ConsensusInfo>) -> Json<ConsensusStatus> {
+    let config = &info.config;
+    let main_node_block = info.sync_state.get_main_node_block();
+    let local_block = info.sync_state.get_local_block();
+    Json(ConsensusStatus {
+        public_addr: config.public_addr.0.clone(),
+        gossip_dynamic_inbound_limit: config.gossip_dynamic_inbound_limit,
+        peers: info.peers.peers(),
+        banned_peers: info.peers.banned_peers(),
+        main_node_block,
+        local_block,
+        block_lag: main_node_block.0.saturating_sub(local_block.0),
+    })
+}
+
+async fn get_consensus_peers(info: State<ConsensusInfo>) -> Json<Vec<PeerInfo>> {
+    Json(info.peers.peers())
+}
+
+async fn get_banned_peers(info: State<ConsensusInfo>) -> Json<Vec<String>> {
+    Json(info.peers.banned_peers())
+}
+
+async fn ban_peer(info: State<ConsensusInfo>, Json(peer): Json<Peer>) -> HandlerResult<StatusCode> {
+    let banned = info
+        .peers
+        .ban(&peer.key)
+        .map_err(|err| (StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+    if banned {
+        tracing::info!("Banned consensus peer {} via admin server", peer.key);
+        Ok(StatusCode::CREATED)
+    } else {
+        Ok(StatusCode::OK)
+    }
+}
+
+async fn unban_peer(
+    info: State<ConsensusInfo>,
+    Json(peer): Json<Peer>,
+) -> HandlerResult<StatusCode> {
+    let unbanned = info
+        .peers
+        .unban(&peer.key)
+        .map_err(|err| (StatusCode::BAD_REQUEST, format!("{err:#}")))?;
+    if unbanned {
+        tracing::info!("Unbanned consensus peer {} via admin server", peer.key);
+        Ok(StatusCode::OK)
+    } else {
+        Err((
+            StatusCode::NOT_FOUND,
+            format!("consensus peer {} is not banned", peer.key),
+        ))
+    }
+}
+
+async fn get_slow_calls(
+    sampler: State<Arc<SlowCallsSampler>>,
+    Query(query): Query<SlowCallsQuery>,
+) -> Json<Vec<SlowCallInfo>> {
+    Json(sampler.calls(query.method.as_deref()))
+}
+
+fn router(
+    pruner: Option<DbPrunerHandle>,
+    compaction: RocksdbCompactionHandle,
+    consensus: Option<ConsensusInfo>,
+    log_directives: Option<LogDirectivesHandle>,
+    slow_calls: Option<Arc<SlowCallsSampler>>,
+) -> Router {
+    let mut router = Router::new()
+        .route("/state_keeper_cache/compact", post(trigger_compaction))
+        .with_state(compaction);
+    if let Some(pruner) = pruner {
+        let pruning_router = Router::new()
+            .route("/pruning/trigger", post(trigger_pruning))
+            .route(
+                "/pruning/protected_ranges",
+                get(get_protected_ranges)
+                    .post(protect_range)
+                    .delete(unprotect_range),
+            )
+            .with_state(pruner);
+        router = router.merge(pruning_router);
+    }
+    if let Some(consensus) = consensus {
+        let consensus_router = Router::new()
+            .route("/consensus/status", get(get_consensus_status))
+            .route("/consensus/peers", get(get_consensus_peers))
+            .route(
+                "/consensus/banned_peers",
+                get(get_banned_peers).post(ban_peer).delete(unban_peer),
+            )
+            .with_state(consensus);
+        router = router.merge(consensus_router);
+    }
+    if let Some(log_directives) = log_directives {
+        let log_directives_router = Router::new()
+            .route(
+                "/log_directives",
+                get(get_log_directives)
+                    .put(set_log_directives)
+                    .delete(reset_log_directives),
+            )
+            .with_state(log_directives);
+        router = router.merge(log_directives_router);
+    }
+    if let Some(slow_calls) = slow_calls {
+        let slow_calls_router = Router::new()
+            .route("/api/slow_calls", get(get_slow_calls))
+            .with_state(slow_calls);
+        router = router.merge(slow_calls_router);
+    }
+    router
+}
+
+/// Runs the admin server until a stop signal is received.
+pub(crate) async fn run_server(
+    bind_address: SocketAddr,
+    pruner: Option<DbPrunerHandle>,
+    compaction: RocksdbCompactionHandle,
+    consensus: Option<ConsensusInfo>,
+    log_directives: Option<LogDirectivesHandle>,
+    slow_calls: Option<Arc<SlowCallsSampler>>,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    tracing::info!("Starting admin server on {bind_address}");
+    axum::Server::try_bind(&bind_address)
+        .with_context(|| format!("failed binding admin server to {bind_address}"))?
+        .serve(
+            router(pruner, compaction, consensus, log_directives, slow_calls).into_make_service(),
+        )
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!(
+                    "Stop signal sender for admin server was dropped without sending a signal"
+                );
+            }
+            tracing::info!("Stop signal received, admin server is shutting down");
+        })
+        .await
+        .context("admin server failed")?;
+    tracing::info!("Admin server shut down");
+    Ok(())
+}
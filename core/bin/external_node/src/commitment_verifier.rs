@@ -0,0 +1,315 @@
+//! Verification of L1 batch commitments computed by the node against the main node.
+
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{L1BatchNumber, H256};
+use zksync_web3_decl::{
+    client::{DynClient, L2},
+    error::{ClientRpcContext, EnrichedClientResult},
+    namespaces::ZksNamespaceClient,
+};
+
+use crate::metrics::EN_METRICS;
+
+/// Health details reported by [`CommitmentVerifier`].
+#[derive(Debug, Default, Serialize)]
+struct CommitmentVerifierDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_checked_batch: Option<L1BatchNumber>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    mismatched_batches: Vec<L1BatchNumber>,
+}
+
+impl CommitmentVerifierDetails {
+    fn health(&self) -> Health {
+        let status = if self.mismatched_batches.is_empty() {
+            HealthStatus::Ready
+        } else {
+            HealthStatus::Affected
+        };
+        Health::from(status).with_details(self)
+    }
+}
+
+/// Compares L1 batch commitments computed by the local commitment generator with the commitments returned
+/// by the main node. Mismatches are reported via metrics and health check details; the verifier does not stop the node.
+#[derive(Debug)]
+pub(crate) struct CommitmentVerifier {
+    pool: ConnectionPool<Core>,
+    main_node_client: Box<DynClient<L2>>,
+    health_updater: HealthUpdater,
+    details: CommitmentVerifierDetails,
+    poll_interval: Duration,
+}
+
+impl CommitmentVerifier {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(pool: ConnectionPool<Core>, main_node_client: Box<DynClient<L2>>) -> Self {
+        Self {
+            pool,
+            main_node_client: main_node_client.for_component("commitment_verifier"),
+            health_updater: ReactiveHealthCheck::new("commitment_verifier").1,
+            details: CommitmentVerifierDetails::default(),
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Returns a health check for this verifier.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn remote_commitment(&self, number: L1BatchNumber) -> EnrichedClientResult<Option<H256>> {
+        let details = self
+            .main_node_client
+            .get_l1_batch_details(number)
+            .rpc_context("get_l1_batch_details")
+            .with_arg("number", &number)
+            .await?;
+        Ok(details.and_then(|details| details.commitment))
+    }
+
+    /// Returns the first L1 batch to check. This is the batch after the persisted cursor, or the first batch
+    /// executed by the node if no batches were checked yet.
+    async fn first_batch_to_check(&self) -> anyhow::Result<L1BatchNumber> {
+        let mut storage = self.pool.connection_tagged("commitment_verifier").await?;
+        let last_checked_batch = storage
+            .commitment_verifier_dal()
+            .get_last_checked_l1_batch()
+            .await?;
+        let mut first_batch = if let Some(number) = last_checked_batch {
+            number + 1
+        } else {
+            let snapshot_recovery = storage
+                .snapshot_recovery_dal()
+                .get_applied_snapshot_status()
+                .await?;
+            // The genesis batch doesn't have a commitment computed by the commitment generator, and the snapshot batch
+            // isn't executed by the node.
+            snapshot_recovery.map_or(L1BatchNumber(1), |status| status.l1_batch_number + 1)
+        };
+
+        // Checked batches may have been reverted, in which case they should be checked again once re-executed.
+        let sealed_l1_batch = storage.blocks_dal().get_sealed_l1_batch_number().await?;
+        if let Some(sealed_l1_batch) = sealed_l1_batch {
+            first_batch = first_batch.min(sealed_l1_batch + 1);
+        }
+        // Pruned batches cannot be checked.
+        let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+        if let Some(last_pruned_l1_batch) = pruning_info.last_soft_pruned_l1_batch {
+            first_batch = first_batch.max(last_pruned_l1_batch + 1);
+        }
+        Ok(first_batch.max(L1BatchNumber(1)))
+    }
+
+    /// Checks the specified L1 batch. Returns `Ok(false)` if the check cannot be performed yet because
+    /// either the local or the remote commitment is not available.
+    async fn check_batch(&mut self, number: L1BatchNumber) -> anyhow::Result<bool> {
+        let mut storage = self.pool.connection_tagged("commitment_verifier").await?;
+        let local_commitment = storage.blocks_dal().get_l1_batch_commitment(number).await?;
+        drop(storage);
+        let Some(local_commitment) = local_commitment else {
+            return Ok(false);
+        };
+
+        let remote_commitment = match self.remote_commitment(number).await {
+            Ok(Some(commitment)) => commitment,
+            Ok(None) => {
+                tracing::debug!("Commitment for L1 batch #{number} is not available on main node");
+                return Ok(false);
+            }
+            Err(err) if err.is_transient() => {
+                tracing::warn!("Transient error getting commitment for L1 batch #{number} from main node: {err}");
+                return Ok(false);
+            }
+            Err(err) => return Err(err.into()),
+        };
+
+        if local_commitment == remote_commitment {
+            tracing::info!("Commitment for L1 batch #{number} matches main node");
+        } else {
+            tracing::warn!(
+                "Commitment for L1 batch #{number} differs from main node; local: {local_commitment:?}, \
+                 main node: {remote_commitment:?}"
+            );
+            EN_METRICS.commitment_mismatches.inc();
+            self.details.mismatched_batches.push(number);
+        }
+        let mut storage = self.pool.connection_tagged("commitment_verifier").await?;
+        storage
+            .commitment_verifier_dal()
+            .set_last_checked_l1_batch(number)
+            .await?;
+        EN_METRICS
+            .last_verified_commitment_batch
+            .set(number.0.into());
+        self.details.last_checked_batch = Some(number);
+        self.health_updater.update(self.details.health());
+        Ok(true)
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater.update(self.details.health());
+        let mut batch_number = self.first_batch_to_check().await?;
+        tracing::info!("Starting commitment verifier from L1 batch #{batch_number}");
+
+        while !*stop_receiver.borrow_and_update() {
+            if self.check_batch(batch_number).await? {
+                batch_number += 1;
+                continue;
+            }
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, commitment verifier is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use zksync_health_check::CheckHealth;
+    use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+    use zksync_types::{api, snapshots::SnapshotRecoveryStatus, L2BlockNumber, ProtocolVersionId};
+    use zksync_web3_decl::client::MockClient;
+
+    use super::*;
+
+    fn mock_main_node_client(commitment: H256) -> MockClient<L2> {
+        MockClient::builder(L2::default())
+            .method("zks_getL1BatchDetails", move |number: L1BatchNumber| {
+                if number > L1BatchNumber(0) {
+                    return Ok(None);
+                }
+                Ok(Some(api::L1BatchDetails {
+                    number,
+                    base: api::BlockDetailsBase {
+                        timestamp: 0,
+                        l1_tx_count: 0,
+                        l2_tx_count: 0,
+                        root_hash: None,
+                        status: api::BlockStatus::Sealed,
+                        commit_tx_hash: None,
+                        committed_at: None,
+                        prove_tx_hash: None,
+                        proven_at: None,
+                        execute_tx_hash: None,
+                        executed_at: None,
+                        l1_gas_price: 0,
+                        l2_fair_gas_price: 0,
+                        base_system_contracts_hashes: Default::default(),
+                    },
+                    commitment: Some(commitment),
+                }))
+            })
+            .build()
+    }
+
+    #[tokio::test]
+    async fn verifying_commitments() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        let local_commitment = storage
+            .blocks_dal()
+            .get_l1_batch_commitment(L1BatchNumber(0))
+            .await
+            .unwrap()
+            .expect("no genesis commitment");
+        drop(storage);
+
+        let client = mock_main_node_client(local_commitment);
+        let mut verifier = CommitmentVerifier::new(pool.clone(), Box::new(client));
+        assert!(verifier.check_batch(L1BatchNumber(0)).await.unwrap());
+        assert!(verifier.details.mismatched_batches.is_empty());
+        let health = verifier.health_check().check_health().await;
+        assert_matches!(health.status(), HealthStatus::Ready);
+        // The batch is not present either locally or on the main node.
+        assert!(!verifier.check_batch(L1BatchNumber(1)).await.unwrap());
+
+        let client = mock_main_node_client(H256::repeat_byte(0xff));
+        let mut verifier = CommitmentVerifier::new(pool, Box::new(client));
+        assert!(verifier.check_batch(L1BatchNumber(0)).await.unwrap());
+        assert_eq!(verifier.details.mismatched_batches, [L1BatchNumber(0)]);
+        let health = verifier.health_check().check_health().await;
+        assert_matches!(health.status(), HealthStatus::Affected);
+    }
+
+    #[tokio::test]
+    async fn first_batch_to_check_after_genesis() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+
+        let client = mock_main_node_client(H256::zero());
+        let verifier = CommitmentVerifier::new(pool.clone(), Box::new(client));
+        assert_eq!(
+            verifier.first_batch_to_check().await.unwrap(),
+            L1BatchNumber(1)
+        );
+
+        // Emulate checked batches reverted by the node.
+        storage
+            .commitment_verifier_dal()
+            .set_last_checked_l1_batch(L1BatchNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            verifier.first_batch_to_check().await.unwrap(),
+            L1BatchNumber(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn first_batch_to_check_after_snapshot_recovery() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        let snapshot_recovery = SnapshotRecoveryStatus {
+            l1_batch_number: L1BatchNumber(23),
+            l1_batch_root_hash: H256::zero(),
+            l1_batch_timestamp: 0,
+            l2_block_number: L2BlockNumber(42),
+            l2_block_hash: H256::zero(),
+            l2_block_timestamp: 0,
+            protocol_version: ProtocolVersionId::latest(),
+            storage_logs_chunks_processed: vec![true],
+        };
+        storage
+            .snapshot_recovery_dal()
+            .insert_initial_recovery_status(&snapshot_recovery)
+            .await
+            .unwrap();
+
+        let client = mock_main_node_client(H256::zero());
+        let verifier = CommitmentVerifier::new(pool.clone(), Box::new(client));
+        assert_eq!(
+            verifier.first_batch_to_check().await.unwrap(),
+            L1BatchNumber(24)
+        );
+
+        storage
+            .commitment_verifier_dal()
+            .set_last_checked_l1_batch(L1BatchNumber(24))
+            .await
+            .unwrap();
+        // There are no sealed batches in storage, so the cursor is used as is.
+        assert_eq!(
+            verifier.first_batch_to_check().await.unwrap(),
+            L1BatchNumber(25)
+        );
+    }
+}
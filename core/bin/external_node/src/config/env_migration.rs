@@ -0,0 +1,195 @@
+//! Detection and migration of legacy env variables configuring the node.
+
+use std::{collections::BTreeMap, fmt::Write as _};
+
+use super::{observability::OBSOLETE_VAR_NAMES, ConfigurationSource, Environment};
+
+/// Legacy names of env variables still accepted by the node, mapped to their canonical names. Legacy names
+/// for the same variable are adjacent and ordered by precedence.
+const ENV_ALIASES: &[(&str, &str)] = &[
+    ("EN_MAX_TX_SIZE", "EN_MAX_TX_SIZE_BYTES"),
+    (
+        "EN_PUBSUB_POLLING_INTERVAL",
+        "EN_PUBSUB_POLLING_INTERVAL_MS",
+    ),
+    (
+        "EN_MEMPOOL_CACHE_UPDATE_INTERVAL",
+        "EN_MEMPOOL_CACHE_UPDATE_INTERVAL_MS",
+    ),
+    (
+        "EN_METADATA_CALCULATOR_DELAY",
+        "EN_MERKLE_TREE_PROCESSING_DELAY_MS",
+    ),
+    (
+        "EN_MAX_L1_BATCHES_PER_TREE_ITER",
+        "EN_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+    ),
+    (
+        "EN_MAX_BLOCKS_PER_TREE_BATCH",
+        "EN_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER",
+    ),
+    (
+        "EN_MINIBLOCK_SEAL_QUEUE_CAPACITY",
+        "EN_L2_BLOCK_SEAL_QUEUE_CAPACITY",
+    ),
+];
+
+/// Env variables that are no longer supported, together with instructions on replacing them.
+const DEPRECATED_VARS: &[(&str, &str)] = &[(
+    "EN_TRANSACTIONS_PER_SEC_LIMIT",
+    "the option has no effect; use `EN_API_RATE_LIMIT_METHODS` or `EN_API_RATE_LIMIT_PER_IP_REQUESTS_PER_SECOND` \
+     to limit API requests",
+)];
+
+/// Prefixes of env variables read by the node.
+const NODE_VAR_PREFIXES: &[&str] = &["EN_", "DATABASE_"];
+
+/// Conflicting values set for a variable using several of its names.
+#[derive(Debug)]
+struct AliasConflict {
+    canonical_name: &'static str,
+    /// Names and values of the set variables, starting from the one that takes precedence.
+    values: Vec<(&'static str, String)>,
+}
+
+/// Env variables configuring the node with legacy names replaced by canonical ones.
+#[derive(Debug)]
+pub(crate) struct EnvMigration {
+    vars: BTreeMap<String, String>,
+    renamed: Vec<(&'static str, &'static str)>,
+    conflicts: Vec<AliasConflict>,
+    deprecated: Vec<(&'static str, &'static str)>,
+}
+
+impl EnvMigration {
+    pub fn from_env() -> Self {
+        Self::new(&Environment)
+    }
+
+    pub(super) fn new(source: &impl ConfigurationSource) -> Self {
+        let mut vars: BTreeMap<_, _> = source
+            .vars()
+            .filter_map(|(name, value)| {
+                let name = name.into_string().ok()?;
+                let is_node_var = NODE_VAR_PREFIXES
+                    .iter()
+                    .any(|prefix| name.starts_with(prefix));
+                is_node_var.then_some((name, value.into_string().ok()?))
+            })
+            .collect();
+        let mut this = Self {
+            vars: BTreeMap::new(),
+            renamed: vec![],
+            conflicts: vec![],
+            deprecated: vec![],
+        };
+
+        let aliases = ENV_ALIASES.iter().chain(OBSOLETE_VAR_NAMES);
+        let mut canonical_names: Vec<_> = aliases.clone().map(|&(_, name)| name).collect();
+        canonical_names.dedup();
+        for canonical_name in canonical_names {
+            // The canonical name takes precedence over the legacy ones.
+            let mut values: Vec<_> = vars
+                .remove(canonical_name)
+                .map(|value| (canonical_name, value))
+                .into_iter()
+                .collect();
+            for &(alias, _) in aliases.clone().filter(|&&(_, name)| name == canonical_name) {
+                let value = vars.remove(alias).or_else(|| {
+                    // Obsolete observability variables don't have the node prefix.
+                    (!alias.starts_with("EN_"))
+                        .then(|| source.var(alias))
+                        .flatten()
+                });
+                if let Some(value) = value {
+                    this.renamed.push((alias, canonical_name));
+                    values.push((alias, value));
+                }
+            }
+
+            let Some((_, value)) = values.first() else {
+                continue;
+            };
+            vars.insert(canonical_name.to_owned(), value.clone());
+            if values.iter().any(|(_, other_value)| other_value != value) {
+                this.conflicts.push(AliasConflict {
+                    canonical_name,
+                    values,
+                });
+            }
+        }
+
+        for &(name, replacement) in DEPRECATED_VARS {
+            if vars.remove(name).is_some() {
+                this.deprecated.push((name, replacement));
+            }
+        }
+        this.vars = vars;
+        this
+    }
+
+    /// Returns node env variables with canonical names.
+    pub fn vars(&self) -> impl Iterator<Item = (String, String)> + '_ {
+        self.vars
+            .iter()
+            .map(|(name, value)| (name.clone(), value.clone()))
+    }
+
+    /// Returns an error if deprecated variables are set.
+    pub fn ensure_no_deprecated_vars(&self) -> anyhow::Result<()> {
+        if self.deprecated.is_empty() {
+            return Ok(());
+        }
+        let mut message = "deprecated env variables are set:".to_owned();
+        for (name, replacement) in &self.deprecated {
+            write!(message, " `{name}` ({replacement});")?;
+        }
+        anyhow::bail!("{message} unset them to start the node");
+    }
+
+    /// Lists issues with node env variables in a human-readable form.
+    pub fn issues(&self) -> Vec<String> {
+        let renamed = self.renamed.iter().map(|(alias, canonical_name)| {
+            format!("`{alias}` is a legacy name of `{canonical_name}`; rename the variable")
+        });
+        let conflicts = self.conflicts.iter().map(|conflict| {
+            let values: Vec<_> = conflict
+                .values
+                .iter()
+                .map(|(name, value)| format!("`{name}={value}`"))
+                .collect();
+            format!(
+                "conflicting values are set for `{}`: {}; the first value is used",
+                conflict.canonical_name,
+                values.join(", ")
+            )
+        });
+        let deprecated = self
+            .deprecated
+            .iter()
+            .map(|(name, replacement)| format!("`{name}` is deprecated: {replacement}"));
+        renamed.chain(conflicts).chain(deprecated).collect()
+    }
+
+    /// Logs issues with node env variables. Should be called once logging is initialized.
+    pub fn report(&self) {
+        for issue in self.issues() {
+            tracing::warn!("Node configuration: {issue}");
+        }
+        if !self.renamed.is_empty() {
+            tracing::warn!(
+                "Legacy env variable names are used to configure the node. Run the node with `--migrate-env` \
+                 to get env variables with canonical names"
+            );
+        }
+    }
+
+    /// Renders node env variables with canonical names as an env file.
+    pub fn to_env_file(&self) -> String {
+        let mut env = String::new();
+        for (name, value) in &self.vars {
+            writeln!(env, "{name}={value}").unwrap();
+        }
+        env
+    }
+}
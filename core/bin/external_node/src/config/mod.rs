@@ -1,31 +1,44 @@
 use std::{
+    collections::HashMap,
     env,
     ffi::OsString,
+    fmt,
+    net::IpAddr,
     num::{NonZeroU32, NonZeroU64, NonZeroUsize},
+    path::PathBuf,
+    str::FromStr,
     time::Duration,
 };
 
 use anyhow::Context;
-use serde::Deserialize;
+use serde::{de, Deserialize, Deserializer};
 use zksync_config::{
     configs::{
-        api::{MaxResponseSize, MaxResponseSizeOverrides},
+        api::{MaxResponseSize, MaxResponseSizeOverrides, MethodRateLimits},
+        chain::StateKeeperConfig,
         consensus::{ConsensusConfig, ConsensusSecrets},
+        object_store::ObjectStoreMode,
     },
-    ObjectStoreConfig,
+    ObjectStoreConfig, SnapshotsCreatorConfig,
 };
-use zksync_core_leftovers::temp_config_store::decode_yaml_repr;
+use zksync_core_leftovers::temp_config_store::{decode_secrets_yaml_repr, decode_yaml_repr};
 #[cfg(test)]
 use zksync_dal::{ConnectionPool, Core};
+use zksync_health_check::ComponentHealthOverride;
 use zksync_node_api_server::{
     tx_sender::TxSenderConfig,
-    web3::{state::InternalApiConfig, Namespace},
+    web3::{
+        state::{InternalApiConfig, UpdatableApiConfigValues},
+        CorsConfig, FiltersStorage, MethodFilter, Namespace,
+    },
 };
 use zksync_protobuf_config::proto;
 use zksync_snapshots_applier::SnapshotsApplierConfig;
+use zksync_state::RocksdbMaintenanceConfig;
+use zksync_state_keeper::AdaptiveSealQueueConfig;
 use zksync_types::{
     api::BridgeAddresses, commitment::L1BatchCommitmentMode, url::SensitiveUrl, Address, L1ChainId,
-    L2ChainId, ETHEREUM_ADDRESS,
+    L2ChainId, VmVersion, ETHEREUM_ADDRESS,
 };
 use zksync_web3_decl::{
     client::{DynClient, L2},
@@ -34,8 +47,10 @@ use zksync_web3_decl::{
     namespaces::{EnNamespaceClient, ZksNamespaceClient},
 };
 
+pub(crate) use self::env_migration::EnvMigration;
 use crate::config::observability::ObservabilityENConfig;
 
+mod env_migration;
 pub(crate) mod observability;
 #[cfg(test)]
 mod tests;
@@ -67,7 +82,7 @@ impl ConfigurationSource for Environment {
 }
 
 /// This part of the external node config is fetched directly from the main node.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Deserialize)]
 pub(crate) struct RemoteENConfig {
     pub bridgehub_proxy_addr: Option<Address>,
     pub state_transition_proxy_addr: Option<Address>,
@@ -170,8 +185,23 @@ impl RemoteENConfig {
         })
     }
 
+    /// Returns config values that can be updated while the API server is running.
+    pub fn updatable_api_config_values(&self) -> UpdatableApiConfigValues {
+        UpdatableApiConfigValues {
+            bridge_addresses: BridgeAddresses {
+                l1_erc20_default_bridge: self.l1_erc20_bridge_proxy_addr,
+                l2_erc20_default_bridge: self.l2_erc20_bridge_addr,
+                l1_shared_default_bridge: self.l1_shared_bridge_proxy_addr,
+                l2_shared_default_bridge: self.l2_shared_bridge_addr,
+                l1_weth_bridge: self.l1_weth_bridge_addr,
+                l2_weth_bridge: self.l2_weth_bridge_addr,
+            },
+            l2_testnet_paymaster_addr: self.l2_testnet_paymaster_addr,
+        }
+    }
+
     #[cfg(test)]
-    fn mock() -> Self {
+    pub(crate) fn mock() -> Self {
         Self {
             bridgehub_proxy_addr: None,
             state_transition_proxy_addr: None,
@@ -191,12 +221,277 @@ impl RemoteENConfig {
     }
 }
 
+/// Overrides for [`RemoteENConfig`] values intended for test environments (e.g., custom devnets
+/// with contracts deployed at non-standard addresses). Overrides are applied on top of the values fetched
+/// from the main node, both on node start and on each config refresh.
+///
+/// Since overridden addresses can break the node in subtle ways (e.g., make it return wrong bridge addresses to users),
+/// overrides must be explicitly acknowledged with the `i_know_what_i_am_doing` flag, and they are never allowed
+/// for Ethereum mainnet.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+pub(crate) struct RemoteENConfigOverrides {
+    #[serde(default)]
+    pub i_know_what_i_am_doing: bool,
+    pub bridgehub_proxy_addr: Option<Address>,
+    pub state_transition_proxy_addr: Option<Address>,
+    pub transparent_proxy_admin_addr: Option<Address>,
+    pub diamond_proxy_addr: Option<Address>,
+    pub l1_shared_bridge_proxy_addr: Option<Address>,
+    pub l2_shared_bridge_addr: Option<Address>,
+    pub l1_erc20_bridge_proxy_addr: Option<Address>,
+    pub l2_erc20_bridge_addr: Option<Address>,
+    pub l1_weth_bridge_addr: Option<Address>,
+    pub l2_weth_bridge_addr: Option<Address>,
+    pub l2_testnet_paymaster_addr: Option<Address>,
+    pub base_token_addr: Option<Address>,
+}
+
+impl RemoteENConfigOverrides {
+    fn from_env() -> anyhow::Result<Self> {
+        envy::prefixed("EN_OVERRIDE_")
+            .from_env()
+            .context("could not load external node config (remote config overrides)")
+    }
+
+    fn is_empty(&self) -> bool {
+        let Self {
+            i_know_what_i_am_doing: _,
+            bridgehub_proxy_addr,
+            state_transition_proxy_addr,
+            transparent_proxy_admin_addr,
+            diamond_proxy_addr,
+            l1_shared_bridge_proxy_addr,
+            l2_shared_bridge_addr,
+            l1_erc20_bridge_proxy_addr,
+            l2_erc20_bridge_addr,
+            l1_weth_bridge_addr,
+            l2_weth_bridge_addr,
+            l2_testnet_paymaster_addr,
+            base_token_addr,
+        } = self;
+        [
+            bridgehub_proxy_addr,
+            state_transition_proxy_addr,
+            transparent_proxy_admin_addr,
+            diamond_proxy_addr,
+            l1_shared_bridge_proxy_addr,
+            l2_shared_bridge_addr,
+            l1_erc20_bridge_proxy_addr,
+            l2_erc20_bridge_addr,
+            l1_weth_bridge_addr,
+            l2_weth_bridge_addr,
+            l2_testnet_paymaster_addr,
+            base_token_addr,
+        ]
+        .iter()
+        .all(|addr| addr.is_none())
+    }
+
+    fn validate(&self, l1_chain_id: L1ChainId) -> anyhow::Result<()> {
+        if self.is_empty() {
+            return Ok(());
+        }
+        anyhow::ensure!(
+            self.i_know_what_i_am_doing,
+            "Remote config overrides are set, but are not acknowledged. Overrides are intended for test environments only; \
+             if you really want to use them, set `EN_OVERRIDE_I_KNOW_WHAT_I_AM_DOING=true`"
+        );
+        anyhow::ensure!(
+            l1_chain_id != L1ChainId(1),
+            "Remote config overrides are not allowed for Ethereum mainnet"
+        );
+        Ok(())
+    }
+
+    /// Applies overrides to the provided config. Returns descriptions of the values that were changed.
+    pub fn apply(&self, config: &mut RemoteENConfig) -> Vec<String> {
+        let mut changes = vec![];
+        Self::apply_value(
+            &mut changes,
+            "diamond_proxy_addr",
+            &mut config.diamond_proxy_addr,
+            self.diamond_proxy_addr,
+        );
+        Self::apply_value(
+            &mut changes,
+            "base_token_addr",
+            &mut config.base_token_addr,
+            self.base_token_addr,
+        );
+        Self::apply_value(
+            &mut changes,
+            "bridgehub_proxy_addr",
+            &mut config.bridgehub_proxy_addr,
+            self.bridgehub_proxy_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "state_transition_proxy_addr",
+            &mut config.state_transition_proxy_addr,
+            self.state_transition_proxy_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "transparent_proxy_admin_addr",
+            &mut config.transparent_proxy_admin_addr,
+            self.transparent_proxy_admin_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "l1_shared_bridge_proxy_addr",
+            &mut config.l1_shared_bridge_proxy_addr,
+            self.l1_shared_bridge_proxy_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "l2_shared_bridge_addr",
+            &mut config.l2_shared_bridge_addr,
+            self.l2_shared_bridge_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "l1_erc20_bridge_proxy_addr",
+            &mut config.l1_erc20_bridge_proxy_addr,
+            self.l1_erc20_bridge_proxy_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "l2_erc20_bridge_addr",
+            &mut config.l2_erc20_bridge_addr,
+            self.l2_erc20_bridge_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "l1_weth_bridge_addr",
+            &mut config.l1_weth_bridge_addr,
+            self.l1_weth_bridge_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "l2_weth_bridge_addr",
+            &mut config.l2_weth_bridge_addr,
+            self.l2_weth_bridge_addr.map(Some),
+        );
+        Self::apply_value(
+            &mut changes,
+            "l2_testnet_paymaster_addr",
+            &mut config.l2_testnet_paymaster_addr,
+            self.l2_testnet_paymaster_addr.map(Some),
+        );
+        changes
+    }
+
+    fn apply_value<T: Copy + fmt::Debug + PartialEq>(
+        changes: &mut Vec<String>,
+        name: &str,
+        target: &mut T,
+        value: Option<T>,
+    ) {
+        if let Some(value) = value {
+            if *target != value {
+                changes.push(format!("`{name}`: {target:?} -> {value:?}"));
+                *target = value;
+            }
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub(crate) enum BlockFetcher {
     ServerAPI,
     Consensus,
 }
 
+/// Storage backend for installed API filters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ApiFiltersStorage {
+    /// Filters are stored in memory of each API server.
+    #[default]
+    Memory,
+    /// Filters are stored in Postgres and are shared among all API servers using the same database.
+    Postgres,
+}
+
+/// Per-component health check overrides, keyed by the component name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct HealthCheckOverrides(HashMap<String, ComponentHealthOverride>);
+
+impl FromStr for HealthCheckOverrides {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut overrides = HashMap::new();
+        for part in s.split(',').map(str::trim).filter(|part| !part.is_empty()) {
+            let (component, spec) = part.split_once('=').with_context(|| {
+                format!("Part `{part}` doesn't have form <component>=[<weight>][:<time_limit_ms>]")
+            })?;
+            let component = component.trim();
+            let (weight, time_limit_ms) = match spec.split_once(':') {
+                Some((weight, time_limit_ms)) => (weight.trim(), Some(time_limit_ms.trim())),
+                None => (spec.trim(), None),
+            };
+
+            let readiness_weight = if weight.is_empty() {
+                None
+            } else {
+                let weight: f64 = weight.parse().with_context(|| {
+                    format!(
+                        "`{weight}` specified for component `{component}` is not a valid weight"
+                    )
+                })?;
+                anyhow::ensure!(
+                    weight.is_finite() && weight >= 0.0,
+                    "Weight for component `{component}` must be non-negative"
+                );
+                Some(weight)
+            };
+            let hard_time_limit = time_limit_ms
+                .map(|ms| {
+                    ms.parse().map(Duration::from_millis).with_context(|| {
+                        format!("`{ms}` specified for component `{component}` is not a valid time limit")
+                    })
+                })
+                .transpose()?;
+
+            let component_override = ComponentHealthOverride {
+                readiness_weight,
+                hard_time_limit,
+            };
+            if overrides
+                .insert(component.to_owned(), component_override)
+                .is_some()
+            {
+                anyhow::bail!("Override for component `{component}` is redefined");
+            }
+        }
+        Ok(Self(overrides))
+    }
+}
+
+impl<'de> Deserialize<'de> for HealthCheckOverrides {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParseVisitor;
+
+        impl<'v> de::Visitor<'v> for ParseVisitor {
+            type Value = HealthCheckOverrides;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("comma-separated list of <component>=[<weight>][:<time_limit_ms>] tuples, such as: main_node_http_rpc=0.5:5000,tree_api_http_client=0")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ParseVisitor)
+    }
+}
+
 /// This part of the external node config is completely optional to provide.
 /// It can tweak limits of the API, delay intervals of certain components, etc.
 /// If any of the fields are not provided, the default values will be used.
@@ -221,20 +516,33 @@ pub(crate) struct OptionalENConfig {
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
     /// This is a temporary solution to mitigate API request resulting in thousands of DB queries.
     pub vm_execution_cache_misses_limit: Option<usize>,
-    /// Note: Deprecated option, no longer in use. Left to display a warning in case someone used them.
-    pub transactions_per_sec_limit: Option<u32>,
     /// Limit for fee history block range.
     #[serde(default = "OptionalENConfig::default_fee_history_limit")]
     pub fee_history_limit: u64,
     /// Maximum number of requests in a single batch JSON RPC request. Default is 500.
     #[serde(default = "OptionalENConfig::default_max_batch_request_size")]
     pub max_batch_request_size: usize,
+    /// Maximum number of calls from a single batch JSON RPC request executed concurrently by the HTTP server.
+    /// If not set, calls in batches are executed sequentially.
+    pub batch_request_parallelism: Option<NonZeroUsize>,
+    /// Time budget in milliseconds for executing a batch JSON RPC request concurrently. Calls that don't complete
+    /// within the budget receive an error response, while completed calls are returned as usual. Only used
+    /// if `batch_request_parallelism` is set. Default is 10 seconds.
+    #[serde(default = "OptionalENConfig::default_batch_request_time_budget_ms")]
+    batch_request_time_budget_ms: u64,
     /// Maximum response body size in MiBs. Default is 10 MiB.
     #[serde(default = "OptionalENConfig::default_max_response_body_size_mb")]
     pub max_response_body_size_mb: usize,
     /// Method-specific overrides in MiBs for the maximum response body size.
     #[serde(default = "MaxResponseSizeOverrides::empty")]
     max_response_body_size_overrides_mb: MaxResponseSizeOverrides,
+    /// Maximum response body size in MiBs for `debug_trace*` methods, which can return large traces.
+    /// Can be overridden for specific methods via `max_response_body_size_overrides_mb`. 0 means no limit. Default is 100 MiB.
+    #[serde(default = "OptionalENConfig::default_debug_trace_max_response_body_size_mb")]
+    pub debug_trace_max_response_body_size_mb: usize,
+    /// Rate limits for the API servers. Loaded separately from env variables with the `EN_API_RATE_LIMIT_` prefix.
+    #[serde(skip)]
+    pub api_rate_limit: ApiRateLimitConfig,
 
     // Other API config settings
     /// Interval between polling DB for Web3 subscriptions.
@@ -246,6 +554,13 @@ pub(crate) struct OptionalENConfig {
     /// Tx nonce: how far ahead from the committed nonce can it be.
     #[serde(default = "OptionalENConfig::default_max_nonce_ahead")]
     pub max_nonce_ahead: u32,
+    /// Max gas limit for L2 transactions submitted to the node. If not set, the limit is not checked locally
+    /// and transactions exceeding it are only rejected by the main node. Should be set to the value used by the main node.
+    pub max_allowed_l2_tx_gas_limit: Option<u64>,
+    /// Computational gas limit for the account and paymaster validation of transactions submitted to the node.
+    /// If not set, validation is not limited locally, and transactions exceeding the limit are only rejected
+    /// by the main node. Should be set to the value used by the main node.
+    pub validation_computational_gas_limit: Option<u32>,
     /// Max number of VM instances to be concurrently spawned by the API server.
     /// This option can be tweaked down if the API server is running out of memory.
     #[serde(default = "OptionalENConfig::default_vm_concurrency_limit")]
@@ -262,6 +577,14 @@ pub(crate) struct OptionalENConfig {
     latest_values_cache_size_mb: usize,
     /// Enabled JSON RPC API namespaces.
     api_namespaces: Option<Vec<Namespace>>,
+    /// Allowlist of JSON-RPC methods exposed by the API servers in addition to `api_namespaces`. Each entry is either
+    /// a full method name (e.g., `eth_getLogs`) or a prefix followed by `*` (e.g., `debug_trace*`). If not set,
+    /// all methods in the enabled namespaces are exposed.
+    api_enabled_methods: Option<Vec<String>>,
+    /// Denylist of JSON-RPC methods not exposed by the API servers, in the same format as `api_enabled_methods`.
+    /// Takes precedence over `api_enabled_methods`. Calling a disabled method results in a "method not found" error.
+    #[serde(default)]
+    api_disabled_methods: Vec<String>,
     /// Whether to support HTTP methods that install filters and query filter changes.
     /// WS methods are unaffected.
     ///
@@ -270,8 +593,29 @@ pub(crate) struct OptionalENConfig {
     /// there are multiple nodes behind a load balancer the client cannot reliably
     /// query the previously created filter as the request might get routed to a
     /// different node.
+    /// Alternatively, filters can be stored in Postgres by setting `filters_storage` to `postgres`.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Storage for installed filters: `memory` (default) or `postgres`. Filters stored in Postgres are shared
+    /// among all nodes using the same database, so they work correctly behind a load balancer.
+    #[serde(default)]
+    pub filters_storage: ApiFiltersStorage,
+    /// Time-to-live for filters stored in Postgres. Filters not polled for longer than this interval are removed.
+    /// Default is 5 minutes.
+    #[serde(default = "OptionalENConfig::default_filters_ttl_sec")]
+    filters_ttl_sec: u64,
+    /// Origins allowed to access the API servers from browsers, e.g. `https://app.example.com`. If not set, any origin
+    /// is allowed. The WS server rejects handshakes with an `Origin` header not in this list.
+    pub cors_allowed_origins: Option<Vec<String>>,
+    /// Request headers allowed for cross-origin requests to the HTTP server in addition to `Content-Type`.
+    #[serde(default)]
+    pub cors_allowed_headers: Vec<String>,
+    /// Time in seconds during which browsers can cache results of CORS preflight requests. Not set by default.
+    cors_max_age_sec: Option<u64>,
+    /// Enables paginated logs retrieval via `zks_getLogsPaged`, and continuation cursors in `eth_getLogs` errors
+    /// caused by exceeding `req_entities_limit`.
+    #[serde(default)]
+    pub logs_pagination_enabled: bool,
     /// Polling period for mempool cache update - how often the mempool cache is updated from the database.
     /// Default is 50 milliseconds.
     #[serde(
@@ -282,10 +626,29 @@ pub(crate) struct OptionalENConfig {
     /// Maximum number of transactions to be stored in the mempool cache.
     #[serde(default = "OptionalENConfig::default_mempool_cache_size")]
     pub mempool_cache_size: usize,
+    /// WebSocket URL of the main node API. If set, the mempool cache is updated based on the `newPendingTransactions`
+    /// subscription to the main node instead of polling Postgres, which makes pending transactions visible
+    /// almost immediately and reduces DB load. `mempool_cache_update_interval` is ignored in this case.
+    pub mempool_cache_main_node_ws_url: Option<SensitiveUrl>,
+    /// Maximum size in MiBs of the cache for API responses that don't change once the corresponding block is final
+    /// (blocks requested by hash, transaction receipts and L1 batch details for executed batches). The cache is shared
+    /// by the HTTP and WS servers. Default is 0, which disables the cache.
+    #[serde(default)]
+    pub response_cache_size_mb: usize,
+    /// Time-to-live in seconds for entries in the API response cache. Default is 10 minutes.
+    #[serde(default = "OptionalENConfig::default_response_cache_ttl_sec")]
+    response_cache_ttl_sec: u64,
+    /// Redis URL used to share the API response cache among multiple nodes. If set, the cache is enabled
+    /// even if `response_cache_size_mb` is 0; in this case, responses are only cached in Redis.
+    pub response_cache_redis_url: Option<SensitiveUrl>,
     /// Enables extended tracing of RPC calls. This may negatively impact performance for nodes under high load
     /// (hundreds or thousands RPS).
     #[serde(default = "OptionalENConfig::default_extended_api_tracing")]
     pub extended_rpc_tracing: bool,
+    /// Number of the slowest calls retained (together with their redacted params) for each RPC method. The sampled calls
+    /// can be retrieved via `GET /api/slow_calls` on the admin server (see `pruning_admin_port`), which only listens
+    /// on localhost. Sampling is disabled by default.
+    pub slow_calls_sample_size: Option<NonZeroUsize>,
 
     // Health checks
     /// Time limit in milliseconds to mark a health check as slow and log the corresponding warning.
@@ -294,6 +657,33 @@ pub(crate) struct OptionalENConfig {
     /// Time limit in milliseconds to abort a health check and return "not ready" status for the corresponding component.
     /// If not specified, the default value in the health check crate will be used.
     healthcheck_hard_time_limit_ms: Option<u64>,
+    /// Per-component overrides for health checks, as a comma-separated list of `<component>=[<weight>][:<time_limit_ms>]`
+    /// entries, e.g. `main_node_http_rpc=0.5:5000,tree_api_http_client=0`. The weight determines the component contribution
+    /// to the node readiness (`/readyz` endpoint): the node isn't ready if the total weight of unhealthy components reaches 1.
+    /// By default, the weight is 1 for components gating readiness and 0 for other components. The time limit overrides
+    /// `healthcheck_hard_time_limit_ms` for the component.
+    #[serde(default)]
+    healthcheck_component_overrides: HealthCheckOverrides,
+    /// Interval in milliseconds between active probes of external dependencies: the main node, L1 and the block data
+    /// archive object store (if the archive is enabled). Each dependency is reported as a separate health check component
+    /// (`main_node_http_rpc`, `ethereum_http_rpc` and `block_data_archive_object_store`). Default is 10 seconds.
+    #[serde(default = "OptionalENConfig::default_dependency_probe_interval_ms")]
+    dependency_probe_interval_ms: u64,
+    /// Timeout in milliseconds for a single probe of an external dependency. A timed out probe marks the dependency
+    /// as unavailable. Default is 10 seconds.
+    #[serde(default = "OptionalENConfig::default_dependency_probe_timeout_ms")]
+    dependency_probe_timeout_ms: u64,
+    /// Latency budget in milliseconds for main node probes. Slower responses mark the main node as affected.
+    /// Default is 1 second.
+    #[serde(default = "OptionalENConfig::default_main_node_probe_latency_budget_ms")]
+    main_node_probe_latency_budget_ms: u64,
+    /// Latency budget in milliseconds for L1 client probes. Slower responses mark L1 as affected. Default is 2 seconds.
+    #[serde(default = "OptionalENConfig::default_eth_client_probe_latency_budget_ms")]
+    eth_client_probe_latency_budget_ms: u64,
+    /// Latency budget in milliseconds for object store probes. Slower responses mark the object store as affected.
+    /// Default is 2 seconds.
+    #[serde(default = "OptionalENConfig::default_object_store_probe_latency_budget_ms")]
+    object_store_probe_latency_budget_ms: u64,
 
     // Gas estimation config
     /// The factor by which to scale the gas limit.
@@ -302,6 +692,10 @@ pub(crate) struct OptionalENConfig {
     /// The max possible number of gas that `eth_estimateGas` is allowed to overestimate.
     #[serde(default = "OptionalENConfig::default_estimate_gas_acceptable_overestimation")]
     pub estimate_gas_acceptable_overestimation: u32,
+    /// Whether to estimate gas using a single metered VM execution instead of a binary search over the gas limit.
+    /// If the estimate cannot be verified, estimation falls back to the binary search.
+    #[serde(default)]
+    pub estimate_gas_single_pass: bool,
     /// The multiplier to use when suggesting gas price. Should be higher than one,
     /// otherwise if the L1 prices soar, the suggested gas price won't be sufficient to be included in block.
     #[serde(default = "OptionalENConfig::default_gas_price_scale_factor")]
@@ -353,6 +747,25 @@ pub(crate) struct OptionalENConfig {
     database_long_connection_threshold_ms: Option<u64>,
     /// Threshold in milliseconds to denote a DB query as "slow" and log its details. If not specified, such logging will be disabled.
     database_slow_query_threshold_ms: Option<u64>,
+    /// Timeout in milliseconds for a single statement executed using the main DB connection pool. Statements running
+    /// longer are aborted by Postgres. If not specified, statements are not timed out.
+    database_statement_timeout_ms: Option<u64>,
+    /// Share of slow queries (in range `(0, 1]`; see `database_slow_query_threshold_ms`) executed using the main
+    /// DB connection pool over a window after which the database is considered overloaded. In this case, the connection pool health check reports the database
+    /// as not ready, and the API servers reject requests requiring DB access until the share of slow queries decreases.
+    /// If not specified, the database is never considered overloaded.
+    pub database_slow_query_circuit_breaker_ratio: Option<f64>,
+    /// Minimum number of DB queries in a window for the database to be considered overloaded. Default is 100.
+    #[serde(default = "OptionalENConfig::default_database_slow_query_circuit_breaker_min_queries")]
+    pub database_slow_query_circuit_breaker_min_queries: u64,
+    /// Window in milliseconds over which slow DB queries are counted. Default is 10 seconds.
+    #[serde(default = "OptionalENConfig::default_database_slow_query_circuit_breaker_window_ms")]
+    database_slow_query_circuit_breaker_window_ms: NonZeroU64,
+    /// Maximum replication lag of the read replica of the database (see `DATABASE_REPLICA_URL`) in milliseconds.
+    /// If the replica lags more, read-only queries from the API servers are served by the primary database.
+    /// Default is 5 seconds.
+    #[serde(default = "OptionalENConfig::default_database_replica_max_lag_ms")]
+    database_replica_max_lag_ms: u64,
 
     // Other config settings
     /// Capacity of the queue for asynchronous L2 block sealing. Once this many L2 blocks are queued,
@@ -363,6 +776,19 @@ pub(crate) struct OptionalENConfig {
         default = "OptionalENConfig::default_l2_block_seal_queue_capacity"
     )]
     pub l2_block_seal_queue_capacity: usize,
+    /// Upper bound for the capacity of the L2 block seal queue. If set to a value greater than `l2_block_seal_queue_capacity`,
+    /// the queue capacity is adjusted at runtime between these two values based on the Postgres write latency.
+    /// This allows absorbing bursts of L2 blocks (e.g., during catch-up) without unbounded memory growth.
+    pub l2_block_seal_queue_max_capacity: Option<usize>,
+    /// Postgres write latency of a single L2 block (in ms) above which the adaptive seal queue is shrunk.
+    /// Only used if `l2_block_seal_queue_max_capacity` is set.
+    pub l2_block_seal_queue_target_latency_ms: Option<u64>,
+    /// Maximum number of L2 blocks fetched from the main node ahead of the state keeper. Blocks are fetched
+    /// concurrently and decoded in a separate stage; once this many blocks are in flight, fetching pauses until
+    /// the state keeper catches up. Larger values improve catch-up throughput on high-latency links at the cost
+    /// of memory. Default is 30.
+    #[serde(default = "OptionalENConfig::default_fetcher_prefetch_depth")]
+    pub fetcher_prefetch_depth: NonZeroUsize,
     /// Configures whether to persist protective reads when persisting L1 batches in the state keeper.
     /// Protective reads are never required by full nodes so far, not until such a node runs a full Merkle tree
     /// (presumably, to participate in L1 batch proving).
@@ -374,9 +800,40 @@ pub(crate) struct OptionalENConfig {
     // This is intentionally not a part of `RemoteENConfig` because fetching this info from the main node would defeat
     // its purpose; the consistency checker assumes that the main node may provide false information.
     pub contracts_diamond_proxy_addr: Option<Address>,
+    /// Enables comparing L1 batch commitments computed by the node with the commitments returned by the main node.
+    /// Mismatches are reported via metrics and the `commitment_verifier` health check component.
+    #[serde(default)]
+    pub commitment_verification_enabled: bool,
     /// Number of requests per second allocated for the main node HTTP client. Default is 100 requests.
     #[serde(default = "OptionalENConfig::default_main_node_rate_limit_rps")]
     pub main_node_rate_limit_rps: NonZeroUsize,
+    /// Whether to adaptively decrease the main node rate limit if the main node responds with HTTP 429 (Too Many Requests)
+    /// or 5xx statuses. The decreased limit is gradually restored to `main_node_rate_limit_rps`. Enabled by default.
+    #[serde(default = "OptionalENConfig::default_main_node_adaptive_rate_limit")]
+    pub main_node_adaptive_rate_limit: bool,
+    /// Interval between re-fetching the configuration from the main node. Bridge and testnet paymaster addresses
+    /// returned by the API are updated if they change on the main node; changes to other values (e.g., chain IDs
+    /// or the diamond proxy address) are not applied and are reported as errors. If set to 0, the configuration
+    /// is only fetched on node start. Default is 5 minutes.
+    #[serde(default = "OptionalENConfig::default_remote_config_refresh_interval_sec")]
+    remote_config_refresh_interval_sec: u64,
+    /// Number of consecutive transient errors (e.g., timeouts or connection errors) returned by the active main node URL
+    /// after which the node fails over to another URL. Only has effect if multiple main node URLs are configured.
+    /// Default is 3 errors.
+    #[serde(default = "OptionalENConfig::default_main_node_failover_max_errors")]
+    pub main_node_failover_max_errors: NonZeroUsize,
+    /// Number of L1 providers that must return the same response for a critical read for it to be accepted.
+    /// Only has effect if multiple Ethereum node URLs are configured. If not specified, the simple majority
+    /// of providers is required.
+    pub eth_client_quorum: Option<NonZeroUsize>,
+    /// Maximum number of L1 batches that can be automatically rolled back if a reorg compared to the main node
+    /// is detected. If the reorg is deeper, the node will exit with an error, and the rollback must be performed manually
+    /// (e.g., using the block reverter tool). If not specified, the number of rolled back L1 batches is not limited.
+    pub max_auto_rollback_batches: Option<u32>,
+    /// If set, the node will not roll back its state if a reorg is detected; instead, it will log the L1 batches
+    /// that would be rolled back and exit with an error.
+    #[serde(default)]
+    pub auto_rollback_dry_run: bool,
 
     #[serde(default)]
     pub l1_batch_commit_data_generator_mode: L1BatchCommitmentMode,
@@ -411,6 +868,12 @@ pub(crate) struct OptionalENConfig {
     /// If set to 0, L1 batches will not be retained based on their timestamp. The default value is 1 hour.
     #[serde(default = "OptionalENConfig::default_pruning_data_retention_sec")]
     pruning_data_retention_sec: u64,
+    /// Port of the admin HTTP server allowing to trigger pruning, to protect L1 batch ranges from pruning,
+    /// and to trigger compaction of the state keeper RocksDB cache. The server listens on the loopback interface only.
+    /// If not set, the admin server is not started. Pruning endpoints are only available if pruning is enabled;
+    /// the consensus status endpoint is only available if consensus is configured; the slow API calls endpoint
+    /// is only available if `slow_calls_sample_size` is set.
+    pub pruning_admin_port: Option<u16>,
 }
 
 impl OptionalENConfig {
@@ -418,6 +881,10 @@ impl OptionalENConfig {
         10_000
     }
 
+    const fn default_filters_ttl_sec() -> u64 {
+        5 * 60
+    }
+
     const fn default_subscriptions_limit() -> usize {
         10_000
     }
@@ -501,14 +968,46 @@ impl OptionalENConfig {
         500 // The default limit is chosen to be reasonably permissive.
     }
 
+    const fn default_batch_request_time_budget_ms() -> u64 {
+        10_000
+    }
+
     const fn default_max_response_body_size_mb() -> usize {
         10
     }
 
+    const fn default_debug_trace_max_response_body_size_mb() -> usize {
+        100
+    }
+
     const fn default_l2_block_seal_queue_capacity() -> usize {
         10
     }
 
+    fn default_fetcher_prefetch_depth() -> NonZeroUsize {
+        NonZeroUsize::new(30).unwrap()
+    }
+
+    const fn default_dependency_probe_interval_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_dependency_probe_timeout_ms() -> u64 {
+        10_000
+    }
+
+    const fn default_main_node_probe_latency_budget_ms() -> u64 {
+        1_000
+    }
+
+    const fn default_eth_client_probe_latency_budget_ms() -> u64 {
+        2_000
+    }
+
+    const fn default_object_store_probe_latency_budget_ms() -> u64 {
+        2_000
+    }
+
     const fn default_protective_reads_persistence_enabled() -> bool {
         true
     }
@@ -521,6 +1020,10 @@ impl OptionalENConfig {
         10_000
     }
 
+    const fn default_response_cache_ttl_sec() -> u64 {
+        600
+    }
+
     const fn default_extended_api_tracing() -> bool {
         true
     }
@@ -529,6 +1032,30 @@ impl OptionalENConfig {
         NonZeroUsize::new(100).unwrap()
     }
 
+    const fn default_main_node_adaptive_rate_limit() -> bool {
+        true
+    }
+
+    const fn default_remote_config_refresh_interval_sec() -> u64 {
+        300
+    }
+
+    const fn default_database_slow_query_circuit_breaker_min_queries() -> u64 {
+        100
+    }
+
+    fn default_database_slow_query_circuit_breaker_window_ms() -> NonZeroU64 {
+        NonZeroU64::new(10_000).unwrap()
+    }
+
+    const fn default_database_replica_max_lag_ms() -> u64 {
+        5_000
+    }
+
+    fn default_main_node_failover_max_errors() -> NonZeroUsize {
+        NonZeroUsize::new(3).unwrap()
+    }
+
     fn default_snapshots_recovery_postgres_max_concurrency() -> NonZeroUsize {
         SnapshotsApplierConfig::default().max_concurrency
     }
@@ -546,15 +1073,52 @@ impl OptionalENConfig {
     }
 
     fn from_env() -> anyhow::Result<Self> {
-        envy::prefixed("EN_")
+        // Legacy names are accepted by the config, but may conflict with canonical ones, so they are resolved beforehand.
+        let env = EnvMigration::from_env();
+        env.ensure_no_deprecated_vars()?;
+        let mut config: Self = envy::prefixed("EN_")
+            .from_iter(env.vars())
+            .context("could not load external node config")?;
+        config.api_rate_limit = envy::prefixed("EN_API_RATE_LIMIT_")
             .from_env()
-            .context("could not load external node config")
+            .context("could not load external node config (API rate limit params)")?;
+        Ok(config)
+    }
+
+    pub fn filters_storage(&self) -> FiltersStorage {
+        match self.filters_storage {
+            ApiFiltersStorage::Memory => FiltersStorage::Memory,
+            ApiFiltersStorage::Postgres => FiltersStorage::Postgres {
+                ttl: Duration::from_secs(self.filters_ttl_sec),
+            },
+        }
+    }
+
+    pub fn cors_config(&self) -> CorsConfig {
+        CorsConfig {
+            allowed_origins: self.cors_allowed_origins.clone(),
+            allowed_headers: self.cors_allowed_headers.clone(),
+            max_age: self.cors_max_age_sec.map(Duration::from_secs),
+        }
     }
 
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.pubsub_polling_interval_ms)
     }
 
+    /// Returns the size of the API response cache in bytes.
+    pub fn response_cache_size(&self) -> u64 {
+        (self.response_cache_size_mb * BYTES_IN_MEGABYTE) as u64
+    }
+
+    pub fn response_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.response_cache_ttl_sec)
+    }
+
+    pub fn batch_request_time_budget(&self) -> Duration {
+        Duration::from_millis(self.batch_request_time_budget_ms)
+    }
+
     pub fn merkle_tree_processing_delay(&self) -> Duration {
         Duration::from_millis(self.merkle_tree_processing_delay_ms)
     }
@@ -599,17 +1163,59 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn statement_timeout(&self) -> Option<Duration> {
+        self.database_statement_timeout_ms
+            .map(Duration::from_millis)
+    }
+
+    pub fn slow_query_circuit_breaker_window(&self) -> Duration {
+        Duration::from_millis(self.database_slow_query_circuit_breaker_window_ms.get())
+    }
+
+    pub fn replica_max_lag(&self) -> Duration {
+        Duration::from_millis(self.database_replica_max_lag_ms)
+    }
+
     pub fn api_namespaces(&self) -> Vec<Namespace> {
         self.api_namespaces
             .clone()
             .unwrap_or_else(|| Namespace::DEFAULT.to_vec())
     }
 
+    pub fn api_method_filter(&self) -> MethodFilter {
+        MethodFilter {
+            enabled: self.api_enabled_methods.clone(),
+            disabled: self.api_disabled_methods.clone(),
+        }
+    }
+
     pub fn max_response_body_size(&self) -> MaxResponseSize {
+        const DEBUG_TRACE_METHODS: &[&str] = &[
+            "debug_traceBlockByNumber",
+            "debug_traceBlockByNumber.callFlatTracer",
+            "debug_traceBlockByHash",
+            "debug_traceTransaction",
+            "debug_traceCall",
+        ];
+
         let scale = NonZeroUsize::new(BYTES_IN_MEGABYTE).unwrap();
+        let debug_trace_size = NonZeroUsize::new(self.debug_trace_max_response_body_size_mb)
+            .map_or(NonZeroUsize::MAX, |size| size.saturating_mul(scale));
+        let explicit_overrides = self.max_response_body_size_overrides_mb.scale(scale);
+        // Explicit overrides take precedence over the default ones for tracing methods.
+        let overrides = DEBUG_TRACE_METHODS
+            .iter()
+            .map(|&method_name| (method_name, debug_trace_size))
+            .chain(explicit_overrides.iter().map(|(method_name, size)| {
+                (
+                    method_name,
+                    NonZeroUsize::new(size).expect("zero size override"),
+                )
+            }))
+            .collect();
         MaxResponseSize {
             global: self.max_response_body_size_mb * BYTES_IN_MEGABYTE,
-            overrides: self.max_response_body_size_overrides_mb.scale(scale),
+            overrides,
         }
     }
 
@@ -623,10 +1229,44 @@ impl OptionalENConfig {
             .map(Duration::from_millis)
     }
 
+    pub fn dependency_probe_interval(&self) -> Duration {
+        Duration::from_millis(self.dependency_probe_interval_ms)
+    }
+
+    pub fn dependency_probe_timeout(&self) -> Duration {
+        Duration::from_millis(self.dependency_probe_timeout_ms)
+    }
+
+    pub fn main_node_probe_latency_budget(&self) -> Duration {
+        Duration::from_millis(self.main_node_probe_latency_budget_ms)
+    }
+
+    pub fn eth_client_probe_latency_budget(&self) -> Duration {
+        Duration::from_millis(self.eth_client_probe_latency_budget_ms)
+    }
+
+    pub fn object_store_probe_latency_budget(&self) -> Duration {
+        Duration::from_millis(self.object_store_probe_latency_budget_ms)
+    }
+
+    pub fn healthcheck_component_overrides(
+        &self,
+    ) -> impl Iterator<Item = (String, ComponentHealthOverride)> + '_ {
+        self.healthcheck_component_overrides
+            .0
+            .iter()
+            .map(|(component, &component_override)| (component.clone(), component_override))
+    }
+
     pub fn mempool_cache_update_interval(&self) -> Duration {
         Duration::from_millis(self.mempool_cache_update_interval_ms)
     }
 
+    pub fn remote_config_refresh_interval(&self) -> Option<Duration> {
+        (self.remote_config_refresh_interval_sec > 0)
+            .then(|| Duration::from_secs(self.remote_config_refresh_interval_sec))
+    }
+
     pub fn pruning_removal_delay(&self) -> Duration {
         Duration::from_secs(self.pruning_removal_delay_sec.get())
     }
@@ -635,6 +1275,16 @@ impl OptionalENConfig {
         Duration::from_secs(self.pruning_data_retention_sec)
     }
 
+    /// Returns the config for the adaptive L2 block seal queue, or `None` if the queue capacity is static.
+    pub fn l2_block_seal_queue_adaptive_config(&self) -> Option<AdaptiveSealQueueConfig> {
+        AdaptiveSealQueueConfig::from_state_keeper_config(&StateKeeperConfig {
+            l2_block_seal_queue_capacity: self.l2_block_seal_queue_capacity,
+            l2_block_seal_queue_max_capacity: self.l2_block_seal_queue_max_capacity,
+            l2_block_seal_queue_target_latency_ms: self.l2_block_seal_queue_target_latency_ms,
+            ..StateKeeperConfig::default()
+        })
+    }
+
     #[cfg(test)]
     fn mock() -> Self {
         // Set all values to their defaults
@@ -642,6 +1292,22 @@ impl OptionalENConfig {
     }
 }
 
+/// Rate limits for the JSON-RPC API servers. Limits are enforced by each server (HTTP and WS) independently.
+#[derive(Debug, Default, Deserialize)]
+pub(crate) struct ApiRateLimitConfig {
+    /// Per-method limits in requests per second, specified as a comma-separated list of `<method>=<limit>` pairs
+    /// (e.g., `eth_call=100,eth_getLogs=10`). Limits are shared among all clients of a server.
+    #[serde(default)]
+    pub methods: MethodRateLimits,
+    /// Maximum number of HTTP requests per second from a single client IP. Client IPs are remote addresses
+    /// of connections, unless a connection originates from one of `trusted_proxies`.
+    pub per_ip_requests_per_second: Option<NonZeroU32>,
+    /// IPs of trusted reverse proxies, specified as a comma-separated list. For connections from these IPs, client IPs
+    /// are determined from the `X-Forwarded-For` / `X-Real-IP` headers set by the proxy.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+}
+
 /// This part of the external node config is required for its operation.
 #[derive(Debug, Deserialize)]
 pub(crate) struct RequiredENConfig {
@@ -658,10 +1324,14 @@ pub(crate) struct RequiredENConfig {
     pub ws_port: u16,
     /// Port on which the healthcheck REST server is listening.
     pub healthcheck_port: u16,
-    /// Address of the Ethereum node API.
-    pub eth_client_url: SensitiveUrl,
+    /// Address of the Ethereum node API. May be specified as a comma-separated list of URLs of different L1 providers;
+    /// in this case, critical reads (e.g., chain ID and commit transactions checked by the consistency checker)
+    /// are cross-checked among all providers, and other reads are performed using the first provider.
+    pub eth_client_url: Vec<SensitiveUrl>,
     /// Main node URL - used by external node to proxy transactions to, query state from, etc.
-    pub main_node_url: SensitiveUrl,
+    /// May be specified as a comma-separated list of URLs; in this case, the first URL is used initially,
+    /// and the node fails over to other URLs if the active one returns repeated transient errors.
+    pub main_node_url: Vec<SensitiveUrl>,
     /// Path to the database data directory that serves state cache.
     pub state_cache_path: String,
     /// Fast SSD path. Used as a RocksDB dir for the Merkle tree (*new* implementation).
@@ -684,8 +1354,8 @@ impl RequiredENConfig {
             ws_port: 0,
             healthcheck_port: 0,
             // L1 and L2 clients must be instantiated before accessing mocks, so these values don't matter
-            eth_client_url: "http://localhost".parse().unwrap(),
-            main_node_url: "http://localhost".parse().unwrap(),
+            eth_client_url: vec!["http://localhost".parse().unwrap()],
+            main_node_url: vec!["http://localhost".parse().unwrap()],
             state_cache_path: temp_dir
                 .path()
                 .join("state_keeper_cache")
@@ -704,6 +1374,9 @@ impl RequiredENConfig {
 #[derive(Debug, Deserialize)]
 pub(crate) struct PostgresConfig {
     database_url: SensitiveUrl,
+    /// URL of a read replica of the database. If specified, read-only queries from the API servers
+    /// are served by the replica unless it lags behind the primary database.
+    database_replica_url: Option<SensitiveUrl>,
     pub max_connections: u32,
 }
 
@@ -714,6 +1387,11 @@ impl PostgresConfig {
                 .context("DATABASE_URL env variable is not set")?
                 .parse()
                 .context("DATABASE_URL env variable is not a valid Postgres URL")?,
+            database_replica_url: env::var("DATABASE_REPLICA_URL")
+                .ok()
+                .map(|url| url.parse())
+                .transpose()
+                .context("DATABASE_REPLICA_URL env variable is not a valid Postgres URL")?,
             max_connections: env::var("DATABASE_POOL_SIZE")
                 .context("DATABASE_POOL_SIZE env variable is not set")?
                 .parse()
@@ -725,10 +1403,15 @@ impl PostgresConfig {
         self.database_url.clone()
     }
 
+    pub fn database_replica_url(&self) -> Option<SensitiveUrl> {
+        self.database_replica_url.clone()
+    }
+
     #[cfg(test)]
     fn mock(test_pool: &ConnectionPool<Core>) -> Self {
         Self {
             database_url: test_pool.database_url().clone(),
+            database_replica_url: None,
             max_connections: test_pool.max_size(),
         }
     }
@@ -745,11 +1428,148 @@ pub(crate) struct ExperimentalENConfig {
     /// Maximum number of files concurrently opened by state keeper cache RocksDB. Useful to fit into OS limits; can be used
     /// as a rudimentary way to control RAM usage of the cache.
     pub state_keeper_db_max_open_files: Option<NonZeroU32>,
+    /// Interval between maintenance checks of the state keeper RocksDB cache in milliseconds. On each check,
+    /// compaction-related metrics are reported, and a compaction may be scheduled. The default value is 60 seconds.
+    #[serde(default = "ExperimentalENConfig::default_state_keeper_db_maintenance_interval_ms")]
+    state_keeper_db_maintenance_interval_ms: NonZeroU64,
+    /// Estimated amount of data pending compaction in the state keeper RocksDB cache (in MB) that triggers
+    /// a manual compaction of the cache once it is idle. If not specified (the default), compactions are not scheduled
+    /// automatically; they can still be triggered via the admin server.
+    state_keeper_db_compaction_threshold_mb: Option<NonZeroU64>,
+    /// Write rate to the state keeper RocksDB cache (in KB/s) at or below which the cache is considered idle,
+    /// so that a scheduled compaction can start. The default value is 64 KB/s.
+    #[serde(
+        default = "ExperimentalENConfig::default_state_keeper_db_compaction_idle_write_rate_kb"
+    )]
+    state_keeper_db_compaction_idle_write_rate_kb: u64,
+    /// Minimum interval between scheduled compactions of the state keeper RocksDB cache in seconds.
+    /// The default value is 1 hour.
+    #[serde(default = "ExperimentalENConfig::default_state_keeper_db_compaction_min_interval_sec")]
+    state_keeper_db_compaction_min_interval_sec: u64,
+    /// If specified, the state keeper RocksDB cache is warmed up after snapshot recovery from storage log chunks
+    /// in the snapshot object store (configured via `EN_SNAPSHOTS_OBJECT_STORE_*` env variables) rather than
+    /// from Postgres, with this value being the maximum number of chunks fetched in parallel. Should not be changed
+    /// while the cache is being recovered.
+    pub state_keeper_db_snapshot_warm_up_concurrency: Option<NonZeroUsize>,
 
     // Commitment generator
     /// Maximum degree of parallelism during commitment generation, i.e., the maximum number of L1 batches being processed in parallel.
     /// If not specified, commitment generator will use a value roughly equal to the number of CPU cores with some clamping applied.
     pub commitment_generator_max_parallelism: Option<NonZeroU32>,
+
+    // Merkle tree
+    /// If specified, Merkle tree changes are persisted to RocksDB in a background thread, with this value
+    /// being the maximum number of L1 batches queued for persistence. This allows the tree to process L1 batches
+    /// while previous ones are being persisted. By default, persistence is performed sequentially.
+    pub merkle_tree_parallel_persistence_buffer: Option<NonZeroUsize>,
+    /// Interval between checking chunks of L1 batches by the incremental Merkle tree consistency check in milliseconds.
+    /// If not specified (the default), the consistency check is disabled.
+    merkle_tree_consistency_check_interval_ms: Option<NonZeroU64>,
+    /// Number of L1 batches checked at a time by the incremental Merkle tree consistency check. The default value is 10.
+    #[serde(default = "ExperimentalENConfig::default_merkle_tree_consistency_check_chunk_size")]
+    pub merkle_tree_consistency_check_chunk_size: NonZeroU32,
+    /// Interval between analyses of the Merkle tree structure in seconds. Each analysis walks the latest tree version
+    /// and exports structural statistics (node counts by level, average leaf level and leaf distribution skew)
+    /// as metrics. If not specified (the default), the analysis is disabled.
+    merkle_tree_analysis_interval_sec: Option<NonZeroU64>,
+    /// Maximum size of the Merkle tree RocksDB instance in megabytes. If the tree exceeds this size, the oldest
+    /// tree versions are pruned even if the corresponding L1 batches are retained in Postgres. Tree versions are never
+    /// pruned past the last L1 batch executed on L1. Only has effect if pruning is enabled. If not specified (the default), the tree is only pruned according to Postgres pruning.
+    merkle_tree_max_size_mb: Option<NonZeroU64>,
+
+    // Snapshot creation
+    /// Interval between snapshot creation attempts in seconds. If not specified (the default), the node doesn't create snapshots.
+    /// Snapshots are stored in the object store configured via `EN_SNAPSHOTS_OBJECT_STORE_*` env variables.
+    snapshots_creation_interval_sec: Option<NonZeroU64>,
+    /// Number of storage logs in a single snapshot chunk. The default value is 1,000,000.
+    #[serde(default = "ExperimentalENConfig::default_snapshots_creation_storage_logs_chunk_size")]
+    pub snapshots_creation_storage_logs_chunk_size: u64,
+    /// Maximum number of concurrent Postgres queries during snapshot creation. The default value is 25.
+    #[serde(default = "ExperimentalENConfig::default_snapshots_creation_concurrent_queries_count")]
+    pub snapshots_creation_concurrent_queries_count: u32,
+
+    // Leader election
+    /// Enables leader election among multiple node processes sharing the same Postgres database. Only the leader process
+    /// runs components writing to Postgres (state keeper, Merkle tree etc.); other processes are replicas serving
+    /// the JSON-RPC API only. If the leader process exits, one of the replicas becomes the leader.
+    /// Leadership is coordinated via a Postgres advisory lock.
+    #[serde(default)]
+    pub leader_election_enabled: bool,
+    /// Interval between attempts to acquire leadership by replicas, and between checks that the leader still
+    /// holds leadership, in milliseconds. The default value is 5 seconds.
+    #[serde(default = "ExperimentalENConfig::default_leader_election_interval_ms")]
+    leader_election_interval_ms: NonZeroU64,
+
+    // Seal criteria verification
+    /// Enables verification of the main node's sealing decisions against the seal criteria configured below.
+    /// The node still follows the main node; deviations are logged and reported as metrics, which allows chain
+    /// operators to validate their seal criteria settings on replayed blocks.
+    #[serde(default)]
+    pub seal_criteria_verification_enabled: bool,
+    /// Maximum number of transactions in an L1 batch.
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_transaction_slots")]
+    seal_criteria_transaction_slots: usize,
+    /// Maximum L2 block payload size in bytes.
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_l2_block_max_payload_size")]
+    seal_criteria_l2_block_max_payload_size: usize,
+    /// Gas limit of the bootloader used to derive the gas-based criteria.
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_max_single_tx_gas")]
+    seal_criteria_max_single_tx_gas: u32,
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_percentage")]
+    seal_criteria_reject_tx_at_geometry_percentage: f64,
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_percentage")]
+    seal_criteria_reject_tx_at_eth_params_percentage: f64,
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_percentage")]
+    seal_criteria_reject_tx_at_gas_percentage: f64,
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_percentage")]
+    seal_criteria_close_block_at_geometry_percentage: f64,
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_percentage")]
+    seal_criteria_close_block_at_eth_params_percentage: f64,
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_percentage")]
+    seal_criteria_close_block_at_gas_percentage: f64,
+    /// Maximum amount of pubdata in an L1 batch in bytes.
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_max_pubdata_per_batch")]
+    seal_criteria_max_pubdata_per_batch: u64,
+    /// Maximum number of circuits of each type in an L1 batch.
+    #[serde(default = "ExperimentalENConfig::default_seal_criteria_max_circuits_per_batch")]
+    seal_criteria_max_circuits_per_batch: usize,
+
+    // L1 commit verification
+    /// Enables verifying each L1 batch received from the main node against the calldata of its commit transaction
+    /// on L1. Batch number and timestamp are checked before the batch is executed, and the number of L1 transactions
+    /// before the executed batch is persisted; the state root is compared once the batch is processed by the Merkle tree.
+    /// On a mismatch, the node stops. Batches not committed on L1 yet are not verified by this check.
+    #[serde(default)]
+    pub l1_commit_verification_enabled: bool,
+
+    // Block data archive
+    /// Enables exporting block data (L2 blocks, transactions and receipts) for L1 batches executed on L1
+    /// to the object store configured via `EN_BLOCK_DATA_ARCHIVE_OBJECT_STORE_*` env variables. If pruning is enabled,
+    /// L1 batches are only pruned after they are exported.
+    #[serde(default)]
+    pub block_data_archive_export_enabled: bool,
+    /// Enables serving pruned L2 blocks, transactions and receipts via the JSON-RPC API from the block data archive.
+    #[serde(default)]
+    pub block_data_archive_fallback_enabled: bool,
+    /// Number of archived L1 batches cached in memory by the API server. The default value is 16.
+    #[serde(default = "ExperimentalENConfig::default_block_data_archive_cache_capacity")]
+    pub block_data_archive_cache_capacity: NonZeroUsize,
+
+    // Shadow VM
+    /// VM version used to additionally execute each synced L1 batch in the shadow mode (e.g., `vm1_5_0_increased_bootloader_memory`).
+    /// Divergences between the shadow and canonical VM outputs (execution results, gas, events and storage writes)
+    /// are persisted to Postgres and reported via metrics. Shadow execution doesn't influence the canonical
+    /// execution results, but it roughly doubles the VM load of the state keeper.
+    #[serde(default)]
+    pub shadow_vm_version: Option<VmVersion>,
+
+    // Consensus
+    /// Path to the file persisting the highest consensus view signed by the validator key of this node. Required
+    /// if the consensus secrets contain a validator key. The file must be stored outside of Postgres and
+    /// must not be shared with other nodes, so that the node never signs twice in the same view, even if
+    /// its database is recreated or recovered from a snapshot.
+    #[serde(default)]
+    pub consensus_validator_watermark_path: Option<PathBuf>,
 }
 
 impl ExperimentalENConfig {
@@ -757,13 +1577,115 @@ impl ExperimentalENConfig {
         128
     }
 
+    fn default_state_keeper_db_maintenance_interval_ms() -> NonZeroU64 {
+        NonZeroU64::new(60_000).unwrap()
+    }
+
+    const fn default_state_keeper_db_compaction_idle_write_rate_kb() -> u64 {
+        64
+    }
+
+    const fn default_state_keeper_db_compaction_min_interval_sec() -> u64 {
+        3_600
+    }
+
+    fn default_merkle_tree_consistency_check_chunk_size() -> NonZeroU32 {
+        NonZeroU32::new(10).unwrap()
+    }
+
+    const fn default_snapshots_creation_storage_logs_chunk_size() -> u64 {
+        1_000_000
+    }
+
+    const fn default_snapshots_creation_concurrent_queries_count() -> u32 {
+        25
+    }
+
+    fn default_leader_election_interval_ms() -> NonZeroU64 {
+        NonZeroU64::new(5_000).unwrap()
+    }
+
+    const fn default_seal_criteria_transaction_slots() -> usize {
+        250
+    }
+
+    const fn default_seal_criteria_l2_block_max_payload_size() -> usize {
+        1_000_000
+    }
+
+    const fn default_seal_criteria_max_single_tx_gas() -> u32 {
+        6_000_000
+    }
+
+    const fn default_seal_criteria_percentage() -> f64 {
+        0.95
+    }
+
+    const fn default_seal_criteria_max_pubdata_per_batch() -> u64 {
+        100_000
+    }
+
+    const fn default_seal_criteria_max_circuits_per_batch() -> usize {
+        24_100
+    }
+
+    fn default_block_data_archive_cache_capacity() -> NonZeroUsize {
+        NonZeroUsize::new(16).unwrap()
+    }
+
     #[cfg(test)]
     fn mock() -> Self {
         Self {
             state_keeper_db_block_cache_capacity_mb:
                 Self::default_state_keeper_db_block_cache_capacity_mb(),
             state_keeper_db_max_open_files: None,
+            state_keeper_db_maintenance_interval_ms:
+                Self::default_state_keeper_db_maintenance_interval_ms(),
+            state_keeper_db_compaction_threshold_mb: None,
+            state_keeper_db_compaction_idle_write_rate_kb:
+                Self::default_state_keeper_db_compaction_idle_write_rate_kb(),
+            state_keeper_db_compaction_min_interval_sec:
+                Self::default_state_keeper_db_compaction_min_interval_sec(),
+            state_keeper_db_snapshot_warm_up_concurrency: None,
             commitment_generator_max_parallelism: None,
+            merkle_tree_parallel_persistence_buffer: None,
+            merkle_tree_consistency_check_interval_ms: None,
+            merkle_tree_consistency_check_chunk_size:
+                Self::default_merkle_tree_consistency_check_chunk_size(),
+            merkle_tree_analysis_interval_sec: None,
+            merkle_tree_max_size_mb: None,
+            snapshots_creation_interval_sec: None,
+            snapshots_creation_storage_logs_chunk_size:
+                Self::default_snapshots_creation_storage_logs_chunk_size(),
+            snapshots_creation_concurrent_queries_count:
+                Self::default_snapshots_creation_concurrent_queries_count(),
+            leader_election_enabled: false,
+            leader_election_interval_ms: Self::default_leader_election_interval_ms(),
+            seal_criteria_verification_enabled: false,
+            l1_commit_verification_enabled: false,
+            seal_criteria_transaction_slots: Self::default_seal_criteria_transaction_slots(),
+            seal_criteria_l2_block_max_payload_size:
+                Self::default_seal_criteria_l2_block_max_payload_size(),
+            seal_criteria_max_single_tx_gas: Self::default_seal_criteria_max_single_tx_gas(),
+            seal_criteria_reject_tx_at_geometry_percentage: Self::default_seal_criteria_percentage(
+            ),
+            seal_criteria_reject_tx_at_eth_params_percentage:
+                Self::default_seal_criteria_percentage(),
+            seal_criteria_reject_tx_at_gas_percentage: Self::default_seal_criteria_percentage(),
+            seal_criteria_close_block_at_geometry_percentage:
+                Self::default_seal_criteria_percentage(),
+            seal_criteria_close_block_at_eth_params_percentage:
+                Self::default_seal_criteria_percentage(),
+            seal_criteria_close_block_at_gas_percentage: Self::default_seal_criteria_percentage(),
+            seal_criteria_max_pubdata_per_batch: Self::default_seal_criteria_max_pubdata_per_batch(
+            ),
+            seal_criteria_max_circuits_per_batch:
+                Self::default_seal_criteria_max_circuits_per_batch(),
+            block_data_archive_export_enabled: false,
+            block_data_archive_fallback_enabled: false,
+            block_data_archive_cache_capacity: Self::default_block_data_archive_cache_capacity(),
+            shadow_vm_version: None,
+            consensus_validator_watermark_path: None,
         }
     }
 
@@ -771,6 +1693,84 @@ impl ExperimentalENConfig {
     pub fn state_keeper_db_block_cache_capacity(&self) -> usize {
         self.state_keeper_db_block_cache_capacity_mb * BYTES_IN_MEGABYTE
     }
+
+    /// Returns the maintenance config for the state keeper RocksDB cache.
+    pub fn state_keeper_db_maintenance_config(&self) -> RocksdbMaintenanceConfig {
+        RocksdbMaintenanceConfig {
+            poll_interval: Duration::from_millis(
+                self.state_keeper_db_maintenance_interval_ms.get(),
+            ),
+            pending_compaction_bytes_threshold: self
+                .state_keeper_db_compaction_threshold_mb
+                .map(|threshold| threshold.get() * BYTES_IN_MEGABYTE as u64),
+            idle_write_rate: self.state_keeper_db_compaction_idle_write_rate_kb * 1_024,
+            min_compaction_interval: Duration::from_secs(
+                self.state_keeper_db_compaction_min_interval_sec,
+            ),
+        }
+    }
+
+    /// Returns the interval between checked chunks for the Merkle tree consistency check, or `None` if the check is disabled.
+    pub fn merkle_tree_consistency_check_interval(&self) -> Option<Duration> {
+        self.merkle_tree_consistency_check_interval_ms
+            .map(|interval| Duration::from_millis(interval.get()))
+    }
+
+    /// Returns the interval between Merkle tree structure analyses, or `None` if the analysis is disabled.
+    pub fn merkle_tree_analysis_interval(&self) -> Option<Duration> {
+        self.merkle_tree_analysis_interval_sec
+            .map(|interval| Duration::from_secs(interval.get()))
+    }
+
+    /// Returns the maximum Merkle tree size in bytes, or `None` if the tree size is not limited.
+    pub fn merkle_tree_max_size(&self) -> Option<u64> {
+        self.merkle_tree_max_size_mb
+            .map(|size| size.get().saturating_mul(BYTES_IN_MEGABYTE as u64))
+    }
+
+    /// Returns the interval between snapshot creation attempts, or `None` if snapshot creation is disabled.
+    pub fn snapshots_creation_interval(&self) -> Option<Duration> {
+        self.snapshots_creation_interval_sec
+            .map(|interval| Duration::from_secs(interval.get()))
+    }
+
+    pub fn leader_election_interval(&self) -> Duration {
+        Duration::from_millis(self.leader_election_interval_ms.get())
+    }
+
+    /// Returns the seal criteria to verify the main node's sealing decisions against, or `None`
+    /// if the verification is disabled. Only the parameters used by the seal criteria are meaningful.
+    pub fn seal_criteria_verification_config(&self) -> Option<StateKeeperConfig> {
+        if !self.seal_criteria_verification_enabled {
+            return None;
+        }
+        Some(StateKeeperConfig {
+            transaction_slots: self.seal_criteria_transaction_slots,
+            l2_block_max_payload_size: self.seal_criteria_l2_block_max_payload_size,
+            max_single_tx_gas: self.seal_criteria_max_single_tx_gas,
+            reject_tx_at_geometry_percentage: self.seal_criteria_reject_tx_at_geometry_percentage,
+            reject_tx_at_eth_params_percentage: self
+                .seal_criteria_reject_tx_at_eth_params_percentage,
+            reject_tx_at_gas_percentage: self.seal_criteria_reject_tx_at_gas_percentage,
+            close_block_at_geometry_percentage: self
+                .seal_criteria_close_block_at_geometry_percentage,
+            close_block_at_eth_params_percentage: self
+                .seal_criteria_close_block_at_eth_params_percentage,
+            close_block_at_gas_percentage: self.seal_criteria_close_block_at_gas_percentage,
+            max_pubdata_per_batch: self.seal_criteria_max_pubdata_per_batch,
+            max_circuits_per_batch: self.seal_criteria_max_circuits_per_batch,
+            ..StateKeeperConfig::default()
+        })
+    }
+
+    pub fn snapshots_creator_config(&self) -> SnapshotsCreatorConfig {
+        SnapshotsCreatorConfig {
+            storage_logs_chunk_size: self.snapshots_creation_storage_logs_chunk_size,
+            concurrent_queries_count: self.snapshots_creation_concurrent_queries_count,
+            // The object store is configured separately
+            object_store: None,
+        }
+    }
 }
 
 pub(crate) fn read_consensus_secrets() -> anyhow::Result<Option<ConsensusSecrets>> {
@@ -779,7 +1779,7 @@ pub(crate) fn read_consensus_secrets() -> anyhow::Result<Option<ConsensusSecrets
     };
     let cfg = std::fs::read_to_string(&path).context(path)?;
     Ok(Some(
-        decode_yaml_repr::<proto::secrets::ConsensusSecrets>(&cfg)
+        decode_secrets_yaml_repr::<proto::secrets::ConsensusSecrets>(&cfg)
             .context("failed decoding YAML")?,
     ))
 }
@@ -794,10 +1794,21 @@ pub(crate) fn read_consensus_config() -> anyhow::Result<Option<ConsensusConfig>>
     ))
 }
 
-/// Configuration for snapshot recovery. Loaded optionally, only if snapshot recovery is enabled.
+/// Reads configuration of the object store for the block data archive. Loaded optionally, only if exporting
+/// or serving archived block data is enabled.
+pub(crate) fn read_block_data_archive_object_store_config() -> anyhow::Result<ObjectStoreConfig> {
+    envy::prefixed("EN_BLOCK_DATA_ARCHIVE_OBJECT_STORE_")
+        .from_env::<ObjectStoreConfig>()
+        .context("failed loading block data archive object store config from env variables")
+}
+
+/// Configuration of the object store for snapshot recovery and creation. Loaded optionally, only if either is enabled.
 #[derive(Debug)]
 pub(crate) struct SnapshotsRecoveryConfig {
     pub snapshots_object_store: ObjectStoreConfig,
+    /// Read-only mirrors of the snapshot object store used during recovery if an object cannot be fetched
+    /// from the main store (or from a previous mirror), in the order of preference.
+    pub snapshots_object_store_mirrors: Vec<ObjectStoreConfig>,
 }
 
 impl SnapshotsRecoveryConfig {
@@ -805,8 +1816,76 @@ impl SnapshotsRecoveryConfig {
         let snapshots_object_store = envy::prefixed("EN_SNAPSHOTS_OBJECT_STORE_")
             .from_env::<ObjectStoreConfig>()
             .context("failed loading snapshot object store config from env variables")?;
+        let snapshots_object_store_mirrors = match env::var("EN_SNAPSHOTS_OBJECT_STORE_MIRRORS") {
+            Ok(mirrors) => Self::parse_mirrors(&mirrors, snapshots_object_store.max_retries)
+                .context("EN_SNAPSHOTS_OBJECT_STORE_MIRRORS")?,
+            Err(_) => vec![],
+        };
         Ok(Self {
             snapshots_object_store,
+            snapshots_object_store_mirrors,
+        })
+    }
+
+    /// Parses a comma-separated list of object store mirrors. Each mirror is specified by a URL, with the following
+    /// supported schemes: `http(s)://` (HTTP mirror), `gs://` (public GCS bucket), `s3://` (S3-compatible bucket;
+    /// see [`Self::parse_s3_mirror()`]) and `file://` (local directory).
+    fn parse_mirrors(mirrors: &str, max_retries: u16) -> anyhow::Result<Vec<ObjectStoreConfig>> {
+        let mirrors = mirrors
+            .split(',')
+            .map(str::trim)
+            .filter(|url| !url.is_empty());
+        mirrors
+            .map(|url| {
+                let mode = if url.starts_with("http://") || url.starts_with("https://") {
+                    ObjectStoreMode::HttpMirror {
+                        http_base_url: url.to_owned(),
+                    }
+                } else if let Some(bucket) = url.strip_prefix("gs://") {
+                    ObjectStoreMode::GCSAnonymousReadOnly {
+                        bucket_base_url: bucket.to_owned(),
+                    }
+                } else if url.starts_with("s3://") {
+                    Self::parse_s3_mirror(url)?
+                } else if let Some(path) = url.strip_prefix("file://") {
+                    ObjectStoreMode::FileBacked {
+                        file_backed_base_path: path.to_owned(),
+                    }
+                } else {
+                    anyhow::bail!("unsupported object store mirror URL: `{url}`");
+                };
+                Ok(ObjectStoreConfig {
+                    mode,
+                    max_retries,
+                    integrity_envelope: false,
+                    encryption_keys_file_path: None,
+                    allow_plaintext_reads: false,
+                })
+            })
+            .collect()
+    }
+
+    /// Parses an S3 mirror URL in the `s3://{bucket_name}?region={region}&endpoint={endpoint}` format. Both params
+    /// are optional; the region defaults to `us-east-1`, and the endpoint defaults to the AWS S3 endpoint for the region.
+    fn parse_s3_mirror(url: &str) -> anyhow::Result<ObjectStoreMode> {
+        let parsed_url = url::Url::parse(url).with_context(|| format!("invalid S3 URL `{url}`"))?;
+        let bucket_name = parsed_url
+            .host_str()
+            .filter(|name| !name.is_empty())
+            .with_context(|| format!("S3 URL `{url}` doesn't specify a bucket"))?;
+        let mut region = None;
+        let mut endpoint = None;
+        for (name, value) in parsed_url.query_pairs() {
+            match &*name {
+                "region" => region = Some(value.into_owned()),
+                "endpoint" => endpoint = Some(value.into_owned()),
+                _ => anyhow::bail!("unsupported param `{name}` in S3 URL `{url}`"),
+            }
+        }
+        Ok(ObjectStoreMode::S3 {
+            s3_bucket_name: bucket_name.to_owned(),
+            s3_region: region.unwrap_or_else(|| "us-east-1".to_owned()),
+            s3_endpoint: endpoint,
         })
     }
 }
@@ -822,6 +1901,8 @@ pub struct ApiComponentConfig {
 #[derive(Debug, Deserialize)]
 pub struct TreeComponentConfig {
     pub api_port: Option<u16>,
+    /// Port of the gRPC Merkle tree API. If not set, the gRPC API server is not started.
+    pub grpc_api_port: Option<u16>,
 }
 
 /// External Node Config contains all the configuration required for the EN operation.
@@ -836,14 +1917,18 @@ pub(crate) struct ExternalNodeConfig<R = RemoteENConfig> {
     pub consensus: Option<ConsensusConfig>,
     pub api_component: ApiComponentConfig,
     pub tree_component: TreeComponentConfig,
+    pub remote_overrides: RemoteENConfigOverrides,
     pub remote: R,
 }
 
 impl ExternalNodeConfig<()> {
     /// Parses the local part of node configuration from the environment.
     pub fn new() -> anyhow::Result<Self> {
+        let required = RequiredENConfig::from_env()?;
+        let remote_overrides = RemoteENConfigOverrides::from_env()?;
+        remote_overrides.validate(required.l1_chain_id)?;
         Ok(Self {
-            required: RequiredENConfig::from_env()?,
+            required,
             postgres: PostgresConfig::from_env()?,
             optional: OptionalENConfig::from_env()?,
             observability: ObservabilityENConfig::from_env()?,
@@ -857,6 +1942,7 @@ impl ExternalNodeConfig<()> {
             tree_component: envy::prefixed("EN_TREE_")
                 .from_env::<TreeComponentConfig>()
                 .context("could not load external node config (tree component params)")?,
+            remote_overrides,
             remote: (),
         })
     }
@@ -866,9 +1952,15 @@ impl ExternalNodeConfig<()> {
         self,
         main_node_client: &DynClient<L2>,
     ) -> anyhow::Result<ExternalNodeConfig> {
-        let remote = RemoteENConfig::fetch(main_node_client)
+        let mut remote = RemoteENConfig::fetch(main_node_client)
             .await
             .context("Unable to fetch required config values from the main node")?;
+        for change in self.remote_overrides.apply(&mut remote) {
+            tracing::warn!(
+                "Overriding remote config value fetched from the main node: {change}. \
+                 This is only safe for test environments!"
+            );
+        }
         Ok(ExternalNodeConfig {
             required: self.required,
             postgres: self.postgres,
@@ -878,6 +1970,7 @@ impl ExternalNodeConfig<()> {
             consensus: self.consensus,
             tree_component: self.tree_component,
             api_component: self.api_component,
+            remote_overrides: self.remote_overrides,
             remote,
         })
     }
@@ -890,6 +1983,7 @@ impl ExternalNodeConfig {
             required: RequiredENConfig::mock(temp_dir),
             postgres: PostgresConfig::mock(test_pool),
             optional: OptionalENConfig::mock(),
+            remote_overrides: RemoteENConfigOverrides::default(),
             remote: RemoteENConfig::mock(),
             observability: ObservabilityENConfig::default(),
             experimental: ExperimentalENConfig::mock(),
@@ -897,7 +1991,10 @@ impl ExternalNodeConfig {
             api_component: ApiComponentConfig {
                 tree_api_remote_url: None,
             },
-            tree_component: TreeComponentConfig { api_port: None },
+            tree_component: TreeComponentConfig {
+                api_port: None,
+                grpc_api_port: None,
+            },
         }
     }
 }
@@ -929,6 +2026,7 @@ impl From<&ExternalNodeConfig> for InternalApiConfig {
             fee_history_limit: config.optional.fee_history_limit,
             base_token_address: Some(config.remote.base_token_addr),
             filters_disabled: config.optional.filters_disabled,
+            logs_pagination_enabled: config.optional.logs_pagination_enabled,
             dummy_verifier: config.remote.dummy_verifier,
             l1_batch_commit_data_generator_mode: config.remote.l1_batch_commit_data_generator_mode,
         }
@@ -946,13 +2044,20 @@ impl From<&ExternalNodeConfig> for TxSenderConfig {
             gas_price_scale_factor: config.optional.gas_price_scale_factor,
             max_nonce_ahead: config.optional.max_nonce_ahead,
             vm_execution_cache_misses_limit: config.optional.vm_execution_cache_misses_limit,
-            // We set these values to the maximum since we don't know the actual values
+            // If not configured, we set these values to the maximum since we don't know the actual values
             // and they will be enforced by the main node anyway.
-            max_allowed_l2_tx_gas_limit: u64::MAX,
-            validation_computational_gas_limit: u32::MAX,
+            max_allowed_l2_tx_gas_limit: config
+                .optional
+                .max_allowed_l2_tx_gas_limit
+                .unwrap_or(u64::MAX),
+            validation_computational_gas_limit: config
+                .optional
+                .validation_computational_gas_limit
+                .unwrap_or(u32::MAX),
             chain_id: config.required.l2_chain_id,
             // Does not matter for EN.
             whitelisted_tokens_for_aa: Default::default(),
+            estimate_gas_single_pass: config.optional.estimate_gas_single_pass,
         }
     }
 }
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, time::Duration};
+use std::{collections::HashMap, path::PathBuf, time::Duration};
 
 use anyhow::Context as _;
 use prometheus_exporter::PrometheusExporterConfig;
@@ -11,7 +11,7 @@ use super::{ConfigurationSource, Environment};
 #[derive(Debug, Default, Deserialize)]
 pub(crate) struct ObservabilityENConfig {
     /// Port to bind the Prometheus exporter server to. If not specified, the server will not be launched.
-    /// If the push gateway URL is specified, it will prevail.
+    /// If the push gateway URL or the OpenTelemetry metrics endpoint is specified, it will prevail.
     pub prometheus_port: Option<u16>,
     /// Prometheus push gateway to push metrics to. Overrides `prometheus_port`. A full URL must be specified
     /// including `job_id` and other path segments; it will be used verbatim as the URL to push data to.
@@ -19,6 +19,12 @@ pub(crate) struct ObservabilityENConfig {
     /// Interval between pushing metrics to the Prometheus push gateway.
     #[serde(default = "ObservabilityENConfig::default_prometheus_push_interval_ms")]
     pub prometheus_push_interval_ms: u64,
+    /// OpenTelemetry collector endpoint (OTLP over HTTP) to export metrics to, e.g. `http://127.0.0.1:4318/v1/metrics`.
+    /// Overrides `prometheus_port` and `prometheus_pushgateway_url`.
+    pub opentelemetry_metrics_endpoint: Option<String>,
+    /// Interval between exporting metrics to the OpenTelemetry collector.
+    #[serde(default = "ObservabilityENConfig::default_opentelemetry_metrics_export_interval_ms")]
+    pub opentelemetry_metrics_export_interval_ms: u64,
     /// Sentry URL to send panics to.
     pub sentry_url: Option<String>,
     /// Environment to use when sending data to Sentry.
@@ -26,23 +32,50 @@ pub(crate) struct ObservabilityENConfig {
     /// Log format to use: either `plain` (default) or `json`.
     #[serde(default)]
     pub log_format: LogFormat,
+    /// Path to a file with `RUST_LOG`-style directives applied when the node receives `SIGUSR1`. If the file
+    /// is not specified, missing or empty, the signal resets directives to the ones set on node start.
+    pub log_directives_path: Option<PathBuf>,
+    /// OpenTelemetry collector endpoint (OTLP over HTTP) to export traces to. If not specified, traces are not exported.
+    pub opentelemetry_endpoint: Option<String>,
+    /// Maximum level of exported spans: `info`, `debug` (default) or `trace`. VM execution, storage and Postgres query
+    /// spans have the `debug` level.
+    #[serde(default = "ObservabilityENConfig::default_opentelemetry_level")]
+    pub opentelemetry_level: String,
+    /// Fraction of traces exported via OpenTelemetry, from 0 to 1. Default is 0.1; increasing it
+    /// may lead to noticeable overhead for busy nodes.
+    #[serde(default = "ObservabilityENConfig::default_opentelemetry_sampling_ratio")]
+    pub opentelemetry_sampling_ratio: f64,
 }
 
+/// Obsolete names of observability env variables mapped to their current names.
+pub(super) const OBSOLETE_VAR_NAMES: &[(&str, &str)] = &[
+    ("MISC_SENTRY_URL", "EN_SENTRY_URL"),
+    ("MISC_LOG_FORMAT", "EN_LOG_FORMAT"),
+    ("MISC_OTLP_URL", "EN_OPENTELEMETRY_ENDPOINT"),
+];
+
 impl ObservabilityENConfig {
     const fn default_prometheus_push_interval_ms() -> u64 {
         10_000
     }
 
+    const fn default_opentelemetry_metrics_export_interval_ms() -> u64 {
+        10_000
+    }
+
+    fn default_opentelemetry_level() -> String {
+        "debug".to_owned()
+    }
+
+    const fn default_opentelemetry_sampling_ratio() -> f64 {
+        0.1
+    }
+
     pub(super) fn from_env() -> envy::Result<Self> {
         Self::new(&Environment)
     }
 
     pub(super) fn new(source: &impl ConfigurationSource) -> envy::Result<Self> {
-        const OBSOLETE_VAR_NAMES: &[(&str, &str)] = &[
-            ("MISC_SENTRY_URL", "EN_SENTRY_URL"),
-            ("MISC_LOG_FORMAT", "EN_LOG_FORMAT"),
-        ];
-
         let en_vars = source.vars().filter_map(|(name, value)| {
             let name = name.into_string().ok()?;
             if !name.starts_with("EN_") {
@@ -65,6 +98,19 @@ impl ObservabilityENConfig {
     }
 
     pub fn prometheus(&self) -> Option<PrometheusExporterConfig> {
+        if let Some(endpoint) = &self.opentelemetry_metrics_endpoint {
+            if self.prometheus_port.is_some() || self.prometheus_pushgateway_url.is_some() {
+                tracing::info!("OpenTelemetry metrics endpoint is specified together with Prometheus port or push gateway URL; the OpenTelemetry endpoint will be used");
+            }
+            let export_interval =
+                Duration::from_millis(self.opentelemetry_metrics_export_interval_ms);
+            return Some(PrometheusExporterConfig::otlp(
+                endpoint.clone(),
+                "zksync-external-node".to_owned(),
+                export_interval,
+            ));
+        }
+
         match (self.prometheus_port, &self.prometheus_pushgateway_url) {
             (_, Some(url)) => {
                 if self.prometheus_port.is_some() {
@@ -78,6 +124,14 @@ impl ObservabilityENConfig {
         }
     }
 
+    /// Returns the OpenTelemetry collector endpoint, if traces should be exported.
+    pub fn otlp_endpoint(&self) -> Option<&str> {
+        // Some legacy deployments use `unset` as an equivalent of `None`.
+        self.opentelemetry_endpoint
+            .as_deref()
+            .filter(|&url| url != "unset")
+    }
+
     pub fn build_observability(&self) -> anyhow::Result<vlog::ObservabilityGuard> {
         let mut builder = vlog::ObservabilityBuilder::new().with_log_format(self.log_format);
         // Some legacy deployments use `unset` as an equivalent of `None`.
@@ -88,6 +142,20 @@ impl ObservabilityENConfig {
                 .context("Invalid Sentry URL")?
                 .with_sentry_environment(self.sentry_environment.clone());
         }
+        if let Some(endpoint) = self.otlp_endpoint() {
+            anyhow::ensure!(
+                (0.0..=1.0).contains(&self.opentelemetry_sampling_ratio),
+                "OpenTelemetry sampling ratio must be in [0, 1]"
+            );
+            builder = builder
+                .with_opentelemetry(
+                    &self.opentelemetry_level,
+                    endpoint.to_owned(),
+                    "zksync-external-node".to_owned(),
+                )
+                .context("Invalid OpenTelemetry level")?
+                .with_opentelemetry_sampling_ratio(self.opentelemetry_sampling_ratio);
+        }
         let guard = builder.build();
 
         // Report whether sentry is running after the logging subsystem was initialized.
@@ -96,6 +164,13 @@ impl ObservabilityENConfig {
         } else {
             tracing::info!("No sentry URL was provided");
         }
+        if let Some(endpoint) = self.otlp_endpoint() {
+            tracing::info!(
+                "Exporting {} of traces with level {} to OpenTelemetry collector at {endpoint}",
+                self.opentelemetry_sampling_ratio,
+                self.opentelemetry_level
+            );
+        }
         Ok(guard)
     }
 }
@@ -1,6 +1,6 @@
 //! Tests for EN configuration.
 
-use std::collections::HashMap;
+use std::{collections::HashMap, net::Ipv6Addr, path::Path};
 
 use assert_matches::assert_matches;
 
@@ -44,10 +44,45 @@ fn parsing_observability_config() {
     assert_eq!(config.sentry_environment.unwrap(), "mainnet - mainnet2");
     assert_matches!(config.log_format, vlog::LogFormat::Plain);
     assert_eq!(config.prometheus_push_interval_ms, 10_000);
+    assert_eq!(config.opentelemetry_metrics_endpoint, None);
+    assert_eq!(config.opentelemetry_metrics_export_interval_ms, 10_000);
+    assert_eq!(config.opentelemetry_endpoint, None);
+    assert_eq!(config.opentelemetry_level, "debug");
+    assert_eq!(config.opentelemetry_sampling_ratio, 0.1);
+    assert_eq!(config.log_directives_path, None);
 
     env_vars.0.insert("MISC_LOG_FORMAT", "json");
+    env_vars
+        .0
+        .insert("EN_LOG_DIRECTIVES_PATH", "/etc/zksync/log_directives");
+    env_vars.0.insert(
+        "EN_OPENTELEMETRY_ENDPOINT",
+        "http://127.0.0.1:4318/v1/traces",
+    );
+    env_vars.0.insert("EN_OPENTELEMETRY_SAMPLING_RATIO", "0.01");
+    env_vars.0.insert(
+        "EN_OPENTELEMETRY_METRICS_ENDPOINT",
+        "http://127.0.0.1:4318/v1/metrics",
+    );
+    env_vars
+        .0
+        .insert("EN_OPENTELEMETRY_METRICS_EXPORT_INTERVAL_MS", "5000");
     let config = ObservabilityENConfig::new(&env_vars).unwrap();
     assert_matches!(config.log_format, vlog::LogFormat::Json);
+    assert_eq!(
+        config.opentelemetry_endpoint.unwrap(),
+        "http://127.0.0.1:4318/v1/traces"
+    );
+    assert_eq!(config.opentelemetry_sampling_ratio, 0.01);
+    assert_eq!(
+        config.opentelemetry_metrics_endpoint.as_deref(),
+        Some("http://127.0.0.1:4318/v1/metrics")
+    );
+    assert_eq!(config.opentelemetry_metrics_export_interval_ms, 5_000);
+    assert_eq!(
+        config.log_directives_path.as_deref(),
+        Some(Path::new("/etc/zksync/log_directives"))
+    );
 
     // If both the canonical and obsolete vars are specified, the canonical one should prevail.
     env_vars.0.insert("EN_LOG_FORMAT", "plain");
@@ -70,6 +105,16 @@ fn using_unset_sentry_url() {
 fn parsing_optional_config_from_empty_env() {
     let config: OptionalENConfig = envy::prefixed("EN_").from_iter([]).unwrap();
     assert_eq!(config.filters_limit, 10_000);
+    assert_eq!(config.filters_storage(), FiltersStorage::Memory);
+    assert_eq!(config.slow_calls_sample_size, None);
+    assert_eq!(config.batch_request_parallelism, None);
+    assert_eq!(config.response_cache_size(), 0);
+    assert_eq!(config.response_cache_ttl(), Duration::from_secs(600));
+    assert!(config.response_cache_redis_url.is_none());
+    assert_eq!(config.batch_request_time_budget(), Duration::from_secs(10));
+    assert_eq!(config.cors_config(), CorsConfig::default());
+    assert_eq!(config.api_method_filter(), MethodFilter::default());
+    assert!(config.mempool_cache_main_node_ws_url.is_none());
     assert_eq!(config.subscriptions_limit, 10_000);
     assert_eq!(config.fee_history_limit, 1_024);
     assert_eq!(config.polling_interval(), Duration::from_millis(200));
@@ -79,6 +124,24 @@ fn parsing_optional_config_from_empty_env() {
         Duration::from_millis(100)
     );
     assert_eq!(config.max_nonce_ahead, 50);
+    assert!(config.main_node_adaptive_rate_limit);
+    assert_eq!(config.statement_timeout(), None);
+    assert_eq!(config.database_slow_query_circuit_breaker_ratio, None);
+    assert_eq!(
+        config.slow_query_circuit_breaker_window(),
+        Duration::from_secs(10)
+    );
+    assert_eq!(config.replica_max_lag(), Duration::from_secs(5));
+    assert_eq!(config.healthcheck_component_overrides().count(), 0);
+    assert_eq!(
+        config.remote_config_refresh_interval(),
+        Some(Duration::from_secs(300))
+    );
+    assert!(!config.commitment_verification_enabled);
+    assert_eq!(config.max_auto_rollback_batches, None);
+    assert!(!config.auto_rollback_dry_run);
+    assert_eq!(config.max_allowed_l2_tx_gas_limit, None);
+    assert_eq!(config.validation_computational_gas_limit, None);
     assert_eq!(config.estimate_gas_scale_factor, 1.2);
     assert_eq!(config.vm_concurrency_limit, 2_048);
     assert_eq!(config.factory_deps_cache_size(), 128 * BYTES_IN_MEGABYTE);
@@ -92,14 +155,37 @@ fn parsing_optional_config_from_empty_env() {
         config.max_response_body_size().global,
         10 * BYTES_IN_MEGABYTE
     );
+    let debug_trace_size = NonZeroUsize::new(100 * BYTES_IN_MEGABYTE).unwrap();
     assert_eq!(
         config.max_response_body_size().overrides,
-        MaxResponseSizeOverrides::empty()
+        MaxResponseSizeOverrides::from_iter([
+            ("debug_traceBlockByNumber", debug_trace_size),
+            ("debug_traceBlockByNumber.callFlatTracer", debug_trace_size),
+            ("debug_traceBlockByHash", debug_trace_size),
+            ("debug_traceTransaction", debug_trace_size),
+            ("debug_traceCall", debug_trace_size),
+        ])
     );
     assert_eq!(
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitmentMode::Rollup
     );
+    assert_eq!(config.l2_block_seal_queue_adaptive_config(), None);
+    assert_eq!(config.fetcher_prefetch_depth.get(), 30);
+    assert_eq!(config.dependency_probe_interval(), Duration::from_secs(10));
+    assert_eq!(config.dependency_probe_timeout(), Duration::from_secs(10));
+    assert_eq!(
+        config.main_node_probe_latency_budget(),
+        Duration::from_secs(1)
+    );
+    assert_eq!(
+        config.eth_client_probe_latency_budget(),
+        Duration::from_secs(2)
+    );
+    assert_eq!(
+        config.object_store_probe_latency_budget(),
+        Duration::from_secs(2)
+    );
 }
 
 #[test]
@@ -107,12 +193,46 @@ fn parsing_optional_config_from_env() {
     let env_vars = [
         ("EN_FILTERS_DISABLED", "true"),
         ("EN_FILTERS_LIMIT", "5000"),
+        ("EN_FILTERS_STORAGE", "postgres"),
+        ("EN_FILTERS_TTL_SEC", "60"),
+        ("EN_SLOW_CALLS_SAMPLE_SIZE", "10"),
+        ("EN_BATCH_REQUEST_PARALLELISM", "8"),
+        ("EN_BATCH_REQUEST_TIME_BUDGET_MS", "2500"),
+        ("EN_RESPONSE_CACHE_SIZE_MB", "64"),
+        ("EN_RESPONSE_CACHE_TTL_SEC", "60"),
+        ("EN_RESPONSE_CACHE_REDIS_URL", "redis://127.0.0.1:6379/"),
+        (
+            "EN_CORS_ALLOWED_ORIGINS",
+            "https://app.example.com,https://wallet.example.com",
+        ),
+        ("EN_CORS_ALLOWED_HEADERS", "Authorization"),
+        ("EN_CORS_MAX_AGE_SEC", "600"),
+        (
+            "EN_API_ENABLED_METHODS",
+            "eth_*,net_*,web3_*,debug_traceCall",
+        ),
+        ("EN_API_DISABLED_METHODS", "eth_getLogs"),
+        ("EN_MEMPOOL_CACHE_MAIN_NODE_WS_URL", "ws://127.0.0.1:3051/"),
         ("EN_SUBSCRIPTIONS_LIMIT", "20000"),
         ("EN_FEE_HISTORY_LIMIT", "1000"),
         ("EN_PUBSUB_POLLING_INTERVAL", "500"),
         ("EN_MAX_TX_SIZE", "1048576"),
         ("EN_METADATA_CALCULATOR_DELAY", "50"),
         ("EN_MAX_NONCE_AHEAD", "100"),
+        ("EN_MAIN_NODE_ADAPTIVE_RATE_LIMIT", "false"),
+        ("EN_DATABASE_STATEMENT_TIMEOUT_MS", "5000"),
+        ("EN_DATABASE_SLOW_QUERY_CIRCUIT_BREAKER_RATIO", "0.5"),
+        ("EN_DATABASE_REPLICA_MAX_LAG_MS", "1000"),
+        (
+            "EN_HEALTHCHECK_COMPONENT_OVERRIDES",
+            "main_node_http_rpc=0.5:5000, tree_api_http_client=0,ethereum_http_rpc=:1000",
+        ),
+        ("EN_REMOTE_CONFIG_REFRESH_INTERVAL_SEC", "0"),
+        ("EN_COMMITMENT_VERIFICATION_ENABLED", "true"),
+        ("EN_MAX_AUTO_ROLLBACK_BATCHES", "10"),
+        ("EN_AUTO_ROLLBACK_DRY_RUN", "true"),
+        ("EN_MAX_ALLOWED_L2_TX_GAS_LIMIT", "4000000000"),
+        ("EN_VALIDATION_COMPUTATIONAL_GAS_LIMIT", "300000"),
         ("EN_ESTIMATE_GAS_SCALE_FACTOR", "1.5"),
         ("EN_VM_CONCURRENCY_LIMIT", "1000"),
         ("EN_FACTORY_DEPS_CACHE_SIZE_MB", "64"),
@@ -122,9 +242,19 @@ fn parsing_optional_config_from_env() {
         ("EN_MAX_RESPONSE_BODY_SIZE_MB", "1"),
         (
             "EN_MAX_RESPONSE_BODY_SIZE_OVERRIDES_MB",
-            "zks_getProof=100,eth_call=2",
+            "zks_getProof=100,eth_call=2,debug_traceTransaction=200",
         ),
+        ("EN_DEBUG_TRACE_MAX_RESPONSE_BODY_SIZE_MB", "50"),
         ("EN_L1_BATCH_COMMIT_DATA_GENERATOR_MODE", "Validium"),
+        ("EN_L2_BLOCK_SEAL_QUEUE_CAPACITY", "5"),
+        ("EN_L2_BLOCK_SEAL_QUEUE_MAX_CAPACITY", "50"),
+        ("EN_L2_BLOCK_SEAL_QUEUE_TARGET_LATENCY_MS", "250"),
+        ("EN_FETCHER_PREFETCH_DEPTH", "100"),
+        ("EN_DEPENDENCY_PROBE_INTERVAL_MS", "5000"),
+        ("EN_DEPENDENCY_PROBE_TIMEOUT_MS", "3000"),
+        ("EN_MAIN_NODE_PROBE_LATENCY_BUDGET_MS", "500"),
+        ("EN_ETH_CLIENT_PROBE_LATENCY_BUDGET_MS", "1500"),
+        ("EN_OBJECT_STORE_PROBE_LATENCY_BUDGET_MS", "2500"),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -133,6 +263,59 @@ fn parsing_optional_config_from_env() {
     let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
     assert!(config.filters_disabled);
     assert_eq!(config.filters_limit, 5_000);
+    assert_eq!(
+        config.filters_storage(),
+        FiltersStorage::Postgres {
+            ttl: Duration::from_secs(60)
+        }
+    );
+    assert_eq!(config.slow_calls_sample_size, NonZeroUsize::new(10));
+    assert_eq!(config.batch_request_parallelism, NonZeroUsize::new(8));
+    assert_eq!(config.response_cache_size(), 64 << 20);
+    assert_eq!(config.response_cache_ttl(), Duration::from_secs(60));
+    assert_eq!(
+        config
+            .response_cache_redis_url
+            .as_ref()
+            .unwrap()
+            .expose_str(),
+        "redis://127.0.0.1:6379/"
+    );
+    assert_eq!(
+        config.batch_request_time_budget(),
+        Duration::from_millis(2_500)
+    );
+    assert_eq!(
+        config.cors_config(),
+        CorsConfig {
+            allowed_origins: Some(vec![
+                "https://app.example.com".to_owned(),
+                "https://wallet.example.com".to_owned(),
+            ]),
+            allowed_headers: vec!["Authorization".to_owned()],
+            max_age: Some(Duration::from_secs(600)),
+        }
+    );
+    assert_eq!(
+        config.api_method_filter(),
+        MethodFilter {
+            enabled: Some(vec![
+                "eth_*".to_owned(),
+                "net_*".to_owned(),
+                "web3_*".to_owned(),
+                "debug_traceCall".to_owned(),
+            ]),
+            disabled: vec!["eth_getLogs".to_owned()],
+        }
+    );
+    assert_eq!(
+        config
+            .mempool_cache_main_node_ws_url
+            .as_ref()
+            .unwrap()
+            .expose_str(),
+        "ws://127.0.0.1:3051/"
+    );
     assert_eq!(config.subscriptions_limit, 20_000);
     assert_eq!(config.fee_history_limit, 1_000);
     assert_eq!(config.polling_interval(), Duration::from_millis(500));
@@ -142,6 +325,43 @@ fn parsing_optional_config_from_env() {
         Duration::from_millis(50)
     );
     assert_eq!(config.max_nonce_ahead, 100);
+    assert!(!config.main_node_adaptive_rate_limit);
+    assert_eq!(config.statement_timeout(), Some(Duration::from_secs(5)));
+    assert_eq!(config.database_slow_query_circuit_breaker_ratio, Some(0.5));
+    assert_eq!(config.replica_max_lag(), Duration::from_secs(1));
+    let healthcheck_overrides: HashMap<_, _> = config.healthcheck_component_overrides().collect();
+    assert_eq!(
+        healthcheck_overrides,
+        HashMap::from([
+            (
+                "main_node_http_rpc".to_owned(),
+                ComponentHealthOverride {
+                    readiness_weight: Some(0.5),
+                    hard_time_limit: Some(Duration::from_secs(5)),
+                }
+            ),
+            (
+                "tree_api_http_client".to_owned(),
+                ComponentHealthOverride {
+                    readiness_weight: Some(0.0),
+                    hard_time_limit: None,
+                }
+            ),
+            (
+                "ethereum_http_rpc".to_owned(),
+                ComponentHealthOverride {
+                    readiness_weight: None,
+                    hard_time_limit: Some(Duration::from_secs(1)),
+                }
+            ),
+        ])
+    );
+    assert_eq!(config.remote_config_refresh_interval(), None);
+    assert!(config.commitment_verification_enabled);
+    assert_eq!(config.max_auto_rollback_batches, Some(10));
+    assert!(config.auto_rollback_dry_run);
+    assert_eq!(config.max_allowed_l2_tx_gas_limit, Some(4_000_000_000));
+    assert_eq!(config.validation_computational_gas_limit, Some(300_000));
     assert_eq!(config.estimate_gas_scale_factor, 1.5);
     assert_eq!(config.vm_concurrency_limit, 1_000);
     assert_eq!(config.factory_deps_cache_size(), 64 * BYTES_IN_MEGABYTE);
@@ -163,13 +383,53 @@ fn parsing_optional_config_from_env() {
             (
                 "eth_call",
                 NonZeroUsize::new(2 * BYTES_IN_MEGABYTE).unwrap()
-            )
+            ),
+            (
+                "debug_traceBlockByNumber",
+                NonZeroUsize::new(50 * BYTES_IN_MEGABYTE).unwrap()
+            ),
+            (
+                "debug_traceBlockByNumber.callFlatTracer",
+                NonZeroUsize::new(50 * BYTES_IN_MEGABYTE).unwrap()
+            ),
+            (
+                "debug_traceBlockByHash",
+                NonZeroUsize::new(50 * BYTES_IN_MEGABYTE).unwrap()
+            ),
+            (
+                "debug_traceTransaction",
+                NonZeroUsize::new(200 * BYTES_IN_MEGABYTE).unwrap()
+            ),
+            (
+                "debug_traceCall",
+                NonZeroUsize::new(50 * BYTES_IN_MEGABYTE).unwrap()
+            ),
         ])
     );
     assert_eq!(
         config.l1_batch_commit_data_generator_mode,
         L1BatchCommitmentMode::Validium
     );
+    assert_eq!(
+        config.l2_block_seal_queue_adaptive_config(),
+        AdaptiveSealQueueConfig::new(5, 50)
+            .map(|config| config.with_target_seal_latency(Duration::from_millis(250)))
+    );
+    assert_eq!(config.fetcher_prefetch_depth.get(), 100);
+    assert_eq!(config.dependency_probe_interval(), Duration::from_secs(5));
+    assert_eq!(config.dependency_probe_timeout(), Duration::from_secs(3));
+    assert_eq!(
+        config.main_node_probe_latency_budget(),
+        Duration::from_millis(500)
+    );
+    assert_eq!(
+        config.eth_client_probe_latency_budget(),
+        Duration::from_millis(1_500)
+    );
+    assert_eq!(
+        config.object_store_probe_latency_budget(),
+        Duration::from_millis(2_500)
+    );
 }
 
 #[test]
@@ -191,11 +451,80 @@ fn parsing_renamed_optional_parameters() {
     assert_eq!(config.merkle_tree_max_l1_batches_per_iter, 15);
 }
 
+#[test]
+fn migrating_legacy_env_vars() {
+    let env_vars = MockEnvironment::new(&[
+        ("EN_MAX_TX_SIZE", "1048576"),
+        ("EN_METADATA_CALCULATOR_DELAY", "50"),
+        ("EN_MERKLE_TREE_PROCESSING_DELAY_MS", "100"),
+        ("EN_MAX_L1_BATCHES_PER_TREE_ITER", "10"),
+        ("EN_MAX_BLOCKS_PER_TREE_BATCH", "10"),
+        ("EN_L1_CHAIN_ID", "9"),
+        ("DATABASE_POOL_SIZE", "50"),
+        ("MISC_LOG_FORMAT", "json"),
+        ("RUST_LOG", "info"),
+    ]);
+    let env = EnvMigration::new(&env_vars);
+    env.ensure_no_deprecated_vars().unwrap();
+
+    assert_eq!(
+        env.to_env_file(),
+        "DATABASE_POOL_SIZE=50\n\
+         EN_L1_CHAIN_ID=9\n\
+         EN_LOG_FORMAT=json\n\
+         EN_MAX_TX_SIZE_BYTES=1048576\n\
+         EN_MERKLE_TREE_MAX_L1_BATCHES_PER_ITER=10\n\
+         EN_MERKLE_TREE_PROCESSING_DELAY_MS=100\n"
+    );
+    let issues = env.issues();
+    assert_eq!(issues.len(), 6, "{issues:#?}");
+    let conflicts: Vec<_> = issues
+        .iter()
+        .filter(|issue| issue.starts_with("conflicting values"))
+        .collect();
+    assert_eq!(conflicts.len(), 1, "{issues:#?}");
+    assert!(
+        conflicts[0].contains(
+            "`EN_MERKLE_TREE_PROCESSING_DELAY_MS=100`, `EN_METADATA_CALCULATOR_DELAY=50`"
+        ),
+        "{issues:#?}"
+    );
+
+    let config: OptionalENConfig = envy::prefixed("EN_").from_iter(env.vars()).unwrap();
+    assert_eq!(config.max_tx_size_bytes, 1_048_576);
+    assert_eq!(config.merkle_tree_processing_delay_ms, 100);
+    assert_eq!(config.merkle_tree_max_l1_batches_per_iter, 10);
+}
+
+#[test]
+fn deprecated_env_vars_are_rejected() {
+    let env_vars = MockEnvironment::new(&[("EN_TRANSACTIONS_PER_SEC_LIMIT", "100")]);
+    let env = EnvMigration::new(&env_vars);
+    let err = env.ensure_no_deprecated_vars().unwrap_err().to_string();
+    assert!(err.contains("EN_TRANSACTIONS_PER_SEC_LIMIT"), "{err}");
+    assert!(err.contains("EN_API_RATE_LIMIT_METHODS"), "{err}");
+    assert_eq!(env.vars().count(), 0);
+}
+
 #[test]
 fn parsing_experimental_config_from_empty_env() {
     let config: ExperimentalENConfig = envy::prefixed("EN_EXPERIMENTAL_").from_iter([]).unwrap();
     assert_eq!(config.state_keeper_db_block_cache_capacity(), 128 << 20);
     assert_eq!(config.state_keeper_db_max_open_files, None);
+    let maintenance_config = config.state_keeper_db_maintenance_config();
+    assert_eq!(maintenance_config.poll_interval, Duration::from_secs(60));
+    assert_eq!(maintenance_config.pending_compaction_bytes_threshold, None);
+    assert_eq!(maintenance_config.idle_write_rate, 64 << 10);
+    assert_eq!(config.snapshots_creation_interval(), None);
+    assert!(!config.leader_election_enabled);
+    assert_eq!(config.leader_election_interval(), Duration::from_secs(5));
+    assert!(config.seal_criteria_verification_config().is_none());
+    assert!(!config.l1_commit_verification_enabled);
+    assert!(!config.block_data_archive_export_enabled);
+    assert!(!config.block_data_archive_fallback_enabled);
+    assert_eq!(config.block_data_archive_cache_capacity.get(), 16);
+    assert_eq!(config.shadow_vm_version, None);
+    assert_eq!(config.consensus_validator_watermark_path, None);
 }
 
 #[test]
@@ -206,6 +535,33 @@ fn parsing_experimental_config_from_env() {
             "64",
         ),
         ("EN_EXPERIMENTAL_STATE_KEEPER_DB_MAX_OPEN_FILES", "100"),
+        (
+            "EN_EXPERIMENTAL_STATE_KEEPER_DB_COMPACTION_THRESHOLD_MB",
+            "1024",
+        ),
+        (
+            "EN_EXPERIMENTAL_STATE_KEEPER_DB_COMPACTION_MIN_INTERVAL_SEC",
+            "600",
+        ),
+        ("EN_EXPERIMENTAL_SNAPSHOTS_CREATION_INTERVAL_SEC", "3600"),
+        (
+            "EN_EXPERIMENTAL_SNAPSHOTS_CREATION_STORAGE_LOGS_CHUNK_SIZE",
+            "100000",
+        ),
+        ("EN_EXPERIMENTAL_SEAL_CRITERIA_VERIFICATION_ENABLED", "true"),
+        ("EN_EXPERIMENTAL_L1_COMMIT_VERIFICATION_ENABLED", "true"),
+        ("EN_EXPERIMENTAL_SEAL_CRITERIA_TRANSACTION_SLOTS", "750"),
+        (
+            "EN_EXPERIMENTAL_SEAL_CRITERIA_CLOSE_BLOCK_AT_GAS_PERCENTAGE",
+            "0.9",
+        ),
+        ("EN_EXPERIMENTAL_BLOCK_DATA_ARCHIVE_EXPORT_ENABLED", "true"),
+        ("EN_EXPERIMENTAL_BLOCK_DATA_ARCHIVE_CACHE_CAPACITY", "4"),
+        ("EN_EXPERIMENTAL_SHADOW_VM_VERSION", "vm1_4_2"),
+        (
+            "EN_EXPERIMENTAL_CONSENSUS_VALIDATOR_WATERMARK_PATH",
+            "/db/consensus_watermark.json",
+        ),
     ];
     let env_vars = env_vars
         .into_iter()
@@ -216,4 +572,225 @@ fn parsing_experimental_config_from_env() {
         .unwrap();
     assert_eq!(config.state_keeper_db_block_cache_capacity(), 64 << 20);
     assert_eq!(config.state_keeper_db_max_open_files, NonZeroU32::new(100));
+    let maintenance_config = config.state_keeper_db_maintenance_config();
+    assert_eq!(
+        maintenance_config.pending_compaction_bytes_threshold,
+        Some(1 << 30)
+    );
+    assert_eq!(
+        maintenance_config.min_compaction_interval,
+        Duration::from_secs(600)
+    );
+    assert_eq!(
+        config.snapshots_creation_interval(),
+        Some(Duration::from_secs(3_600))
+    );
+    let creator_config = config.snapshots_creator_config();
+    assert_eq!(creator_config.storage_logs_chunk_size, 100_000);
+    assert_eq!(creator_config.concurrent_queries_count, 25);
+
+    assert!(config.l1_commit_verification_enabled);
+    let seal_criteria = config.seal_criteria_verification_config().unwrap();
+    assert_eq!(seal_criteria.transaction_slots, 750);
+    assert_eq!(seal_criteria.close_block_at_gas_percentage, 0.9);
+    assert_eq!(seal_criteria.close_block_at_geometry_percentage, 0.95);
+    assert_eq!(seal_criteria.max_pubdata_per_batch, 100_000);
+
+    assert!(config.block_data_archive_export_enabled);
+    assert!(!config.block_data_archive_fallback_enabled);
+    assert_eq!(config.block_data_archive_cache_capacity.get(), 4);
+    assert_eq!(config.shadow_vm_version, Some(VmVersion::Vm1_4_2));
+    assert_eq!(
+        config.consensus_validator_watermark_path.unwrap(),
+        Path::new("/db/consensus_watermark.json")
+    );
+}
+
+#[test]
+fn parsing_api_rate_limit_config_from_env() {
+    let config: ApiRateLimitConfig = envy::prefixed("EN_API_RATE_LIMIT_").from_iter([]).unwrap();
+    assert_eq!(config.methods.iter().len(), 0);
+    assert_eq!(config.per_ip_requests_per_second, None);
+    assert_eq!(config.trusted_proxies, []);
+
+    let env_vars = [
+        ("EN_API_RATE_LIMIT_METHODS", "eth_call=100,eth_getLogs=10"),
+        ("EN_API_RATE_LIMIT_PER_IP_REQUESTS_PER_SECOND", "50"),
+        ("EN_API_RATE_LIMIT_TRUSTED_PROXIES", "10.0.0.1,::1"),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+
+    let config: ApiRateLimitConfig = envy::prefixed("EN_API_RATE_LIMIT_")
+        .from_iter(env_vars)
+        .unwrap();
+    assert_eq!(config.methods.get("eth_call"), NonZeroU32::new(100));
+    assert_eq!(config.methods.get("eth_getLogs"), NonZeroU32::new(10));
+    assert_eq!(config.methods.get("eth_blockNumber"), None);
+    assert_eq!(config.per_ip_requests_per_second, NonZeroU32::new(50));
+    assert_eq!(
+        config.trusted_proxies,
+        [
+            IpAddr::from([10, 0, 0, 1]),
+            IpAddr::from(Ipv6Addr::LOCALHOST)
+        ]
+    );
+}
+
+#[test]
+fn parsing_required_config_with_multiple_main_node_urls() {
+    let env_vars = [
+        ("EN_L1_CHAIN_ID", "9"),
+        ("EN_L2_CHAIN_ID", "270"),
+        ("EN_HTTP_PORT", "3060"),
+        ("EN_WS_PORT", "3061"),
+        ("EN_HEALTHCHECK_PORT", "3081"),
+        ("EN_ETH_CLIENT_URL", "http://127.0.0.1:8545/"),
+        ("EN_MAIN_NODE_URL", "http://127.0.0.1:3050/"),
+        ("EN_STATE_CACHE_PATH", "/db/state_keeper"),
+        ("EN_MERKLE_TREE_PATH", "/db/tree"),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+
+    let config: RequiredENConfig = envy::prefixed("EN_").from_iter(env_vars.clone()).unwrap();
+    assert_eq!(
+        config.main_node_url,
+        ["http://127.0.0.1:3050/".parse::<SensitiveUrl>().unwrap()]
+    );
+
+    let env_vars = env_vars.map(|(name, value)| {
+        if name == "EN_MAIN_NODE_URL" {
+            (
+                name,
+                "http://127.0.0.1:3050/,https://backup.example.com/".to_owned(),
+            )
+        } else {
+            (name, value)
+        }
+    });
+    let config: RequiredENConfig = envy::prefixed("EN_").from_iter(env_vars).unwrap();
+    assert_eq!(
+        config.main_node_url,
+        [
+            "http://127.0.0.1:3050/".parse::<SensitiveUrl>().unwrap(),
+            "https://backup.example.com/"
+                .parse::<SensitiveUrl>()
+                .unwrap(),
+        ]
+    );
+}
+
+#[test]
+fn parsing_snapshots_object_store_mirrors() {
+    let mirrors = SnapshotsRecoveryConfig::parse_mirrors(
+        "https://mirror.example.com/snapshots, gs://snapshots-bucket,\
+         s3://snapshots?region=eu-west-1,s3://snapshots?endpoint=http://127.0.0.1:9000,\
+         file:///mnt/snapshots",
+        3,
+    )
+    .unwrap();
+    let modes: Vec<_> = mirrors.iter().map(|config| &config.mode).collect();
+    assert_eq!(
+        modes,
+        [
+            &ObjectStoreMode::HttpMirror {
+                http_base_url: "https://mirror.example.com/snapshots".to_owned(),
+            },
+            &ObjectStoreMode::GCSAnonymousReadOnly {
+                bucket_base_url: "snapshots-bucket".to_owned(),
+            },
+            &ObjectStoreMode::S3 {
+                s3_bucket_name: "snapshots".to_owned(),
+                s3_region: "eu-west-1".to_owned(),
+                s3_endpoint: None,
+            },
+            &ObjectStoreMode::S3 {
+                s3_bucket_name: "snapshots".to_owned(),
+                s3_region: "us-east-1".to_owned(),
+                s3_endpoint: Some("http://127.0.0.1:9000".to_owned()),
+            },
+            &ObjectStoreMode::FileBacked {
+                file_backed_base_path: "/mnt/snapshots".to_owned(),
+            },
+        ]
+    );
+    assert!(mirrors.iter().all(|config| config.max_retries == 3));
+
+    let err = SnapshotsRecoveryConfig::parse_mirrors("ftp://bucket", 3).unwrap_err();
+    assert!(err.to_string().contains("unsupported"), "{err}");
+    let err = SnapshotsRecoveryConfig::parse_mirrors("s3://bucket?acl=public", 3).unwrap_err();
+    assert!(format!("{err:#}").contains("unsupported param"), "{err:#}");
+}
+
+#[test]
+fn parsing_and_applying_remote_config_overrides() {
+    let overrides: RemoteENConfigOverrides = envy::prefixed("EN_OVERRIDE_").from_iter([]).unwrap();
+    assert_eq!(overrides, RemoteENConfigOverrides::default());
+    overrides.validate(L1ChainId(1)).unwrap();
+
+    let env_vars = [
+        (
+            "EN_OVERRIDE_L2_TESTNET_PAYMASTER_ADDR",
+            "0x1010101010101010101010101010101010101010",
+        ),
+        (
+            "EN_OVERRIDE_DIAMOND_PROXY_ADDR",
+            "0x0101010101010101010101010101010101010101",
+        ),
+        (
+            "EN_OVERRIDE_L1_WETH_BRIDGE_ADDR",
+            "0x2020202020202020202020202020202020202020",
+        ),
+    ];
+    let env_vars = env_vars
+        .into_iter()
+        .map(|(name, value)| (name.to_owned(), value.to_owned()));
+    let mut overrides: RemoteENConfigOverrides = envy::prefixed("EN_OVERRIDE_")
+        .from_iter(env_vars.clone())
+        .unwrap();
+    assert!(!overrides.i_know_what_i_am_doing);
+    let err = overrides.validate(L1ChainId(9)).unwrap_err();
+    assert!(err.to_string().contains("I_KNOW_WHAT_I_AM_DOING"), "{err}");
+
+    overrides = envy::prefixed("EN_OVERRIDE_")
+        .from_iter(env_vars.chain([(
+            "EN_OVERRIDE_I_KNOW_WHAT_I_AM_DOING".to_owned(),
+            "true".to_owned(),
+        )]))
+        .unwrap();
+    assert!(overrides.i_know_what_i_am_doing);
+    overrides.validate(L1ChainId(9)).unwrap();
+    let err = overrides.validate(L1ChainId(1)).unwrap_err();
+    assert!(err.to_string().contains("mainnet"), "{err}");
+
+    let mut config = RemoteENConfig::mock();
+    let changes = overrides.apply(&mut config);
+    // The diamond proxy address in the mock config is the same as the overridden one.
+    assert_eq!(changes.len(), 2, "{changes:?}");
+    assert_eq!(config.diamond_proxy_addr, Address::repeat_byte(1));
+    assert_eq!(
+        config.l2_testnet_paymaster_addr,
+        Some(Address::repeat_byte(0x10))
+    );
+    assert_eq!(config.l1_weth_bridge_addr, Some(Address::repeat_byte(0x20)));
+    // Other values are not changed.
+    assert_eq!(config.base_token_addr, Address::repeat_byte(4));
+    assert_eq!(config.l2_erc20_bridge_addr, Some(Address::repeat_byte(3)));
+}
+
+#[test]
+fn parsing_invalid_healthcheck_overrides() {
+    let invalid_overrides = [
+        "main_node_http_rpc",
+        "main_node_http_rpc=what",
+        "main_node_http_rpc=-1",
+        "main_node_http_rpc=1:soon",
+        "main_node_http_rpc=1,main_node_http_rpc=0",
+    ];
+    for overrides in invalid_overrides {
+        overrides.parse::<HealthCheckOverrides>().unwrap_err();
+    }
 }
@@ -0,0 +1,172 @@
+//! Periodic refresh of the node configuration fetched from the main node.
+
+use std::{fmt, time::Duration};
+
+use tokio::sync::watch;
+use zksync_node_api_server::web3::state::UpdatableApiConfig;
+use zksync_types::{L1ChainId, L2ChainId};
+use zksync_web3_decl::{
+    client::{DynClient, L2},
+    error::ClientRpcContext,
+    namespaces::{EthNamespaceClient, ZksNamespaceClient},
+};
+
+use crate::{
+    config::{ExternalNodeConfig, RemoteENConfig, RemoteENConfigOverrides},
+    metrics::EN_METRICS,
+};
+
+fn check_drift<T: fmt::Debug + PartialEq>(name: &'static str, local: &T, remote: &T) {
+    if local != remote {
+        tracing::error!(
+            "Main node returned changed value for `{name}`, which is not expected to change: {remote:?}; \
+             the node continues using the local value {local:?}"
+        );
+        EN_METRICS.remote_config_drift[&name].inc();
+    }
+}
+
+/// Periodically re-fetches [`RemoteENConfig`] from the main node. Values that can legitimately change
+/// (bridge and testnet paymaster addresses) are propagated to the API servers. Changes of other values are not applied;
+/// they are logged as errors and reported via metrics. [Overrides](RemoteENConfigOverrides) are applied
+/// to the fetched config, so that refreshing doesn't revert overridden values.
+#[derive(Debug)]
+pub(crate) struct RemoteConfigRefresher {
+    main_node_client: Box<DynClient<L2>>,
+    config: RemoteENConfig,
+    overrides: RemoteENConfigOverrides,
+    l1_chain_id: L1ChainId,
+    l2_chain_id: L2ChainId,
+    api_config: UpdatableApiConfig,
+    refresh_interval: Duration,
+}
+
+impl RemoteConfigRefresher {
+    pub fn new(
+        main_node_client: Box<DynClient<L2>>,
+        config: &ExternalNodeConfig,
+        refresh_interval: Duration,
+    ) -> Self {
+        Self {
+            main_node_client: main_node_client.for_component("remote_config_refresher"),
+            config: config.remote.clone(),
+            overrides: config.remote_overrides.clone(),
+            l1_chain_id: config.required.l1_chain_id,
+            l2_chain_id: config.required.l2_chain_id,
+            api_config: UpdatableApiConfig::new(config.remote.updatable_api_config_values()),
+            refresh_interval,
+        }
+    }
+
+    /// Returns a handle to config values that should be provided to the API servers.
+    pub fn api_config(&self) -> UpdatableApiConfig {
+        self.api_config.clone()
+    }
+
+    async fn refresh(&mut self) -> anyhow::Result<()> {
+        let mut fetched = RemoteENConfig::fetch(self.main_node_client.as_ref()).await?;
+        // Overrides were already logged on node start.
+        self.overrides.apply(&mut fetched);
+        check_drift(
+            "diamond_proxy_addr",
+            &self.config.diamond_proxy_addr,
+            &fetched.diamond_proxy_addr,
+        );
+        check_drift(
+            "base_token_addr",
+            &self.config.base_token_addr,
+            &fetched.base_token_addr,
+        );
+
+        let l1_chain_id = self
+            .main_node_client
+            .l1_chain_id()
+            .rpc_context("l1_chain_id")
+            .await?;
+        check_drift(
+            "l1_chain_id",
+            &self.l1_chain_id,
+            &L1ChainId(l1_chain_id.as_u64()),
+        );
+        let l2_chain_id = self
+            .main_node_client
+            .chain_id()
+            .rpc_context("chain_id")
+            .await?;
+        check_drift(
+            "l2_chain_id",
+            &self.l2_chain_id.as_u64(),
+            &l2_chain_id.as_u64(),
+        );
+
+        let values = fetched.updatable_api_config_values();
+        if self.api_config.update(values.clone()) {
+            tracing::info!("Updated API config values using values from main node: {values:?}");
+            EN_METRICS.remote_config_updates.inc();
+        }
+        Ok(())
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        // The config was just fetched on node start, so we start with waiting.
+        while tokio::time::timeout(self.refresh_interval, stop_receiver.changed())
+            .await
+            .is_err()
+        {
+            if let Err(err) = self.refresh().await {
+                tracing::warn!("Failed refreshing config from main node: {err:#}");
+            }
+        }
+        tracing::info!("Stop signal received, remote config refresher is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{api::BridgeAddresses, Address, U64};
+    use zksync_web3_decl::client::MockClient;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn refreshing_config() {
+        let config = RemoteENConfig::mock();
+        let paymaster_addr = Address::repeat_byte(0x10);
+        let main_node_client = MockClient::builder(L2::default())
+            .method("zks_getBridgeContracts", || {
+                Ok(BridgeAddresses {
+                    l1_erc20_default_bridge: Some(Address::repeat_byte(2)),
+                    l2_erc20_default_bridge: Some(Address::repeat_byte(3)),
+                    l1_shared_default_bridge: Some(Address::repeat_byte(5)),
+                    l2_shared_default_bridge: Some(Address::repeat_byte(3)),
+                    l1_weth_bridge: None,
+                    l2_weth_bridge: None,
+                })
+            })
+            .method("zks_getTestnetPaymaster", move || Ok(Some(paymaster_addr)))
+            .method("zks_getMainContract", || Ok(Address::repeat_byte(1)))
+            .method("zks_getBaseTokenL1Address", || Ok(Address::repeat_byte(4)))
+            .method("zks_L1ChainId", || Ok(U64::from(9)))
+            .method("eth_chainId", || Ok(U64::from(270)))
+            .build();
+
+        let mut refresher = RemoteConfigRefresher {
+            main_node_client: Box::new(main_node_client),
+            api_config: UpdatableApiConfig::new(config.updatable_api_config_values()),
+            config,
+            overrides: RemoteENConfigOverrides::default(),
+            l1_chain_id: L1ChainId(9),
+            l2_chain_id: L2ChainId::default(),
+            refresh_interval: Duration::from_secs(1),
+        };
+        refresher.refresh().await.unwrap();
+
+        let values = refresher.api_config().get();
+        assert_eq!(values.l2_testnet_paymaster_addr, Some(paymaster_addr));
+        assert_eq!(
+            values.bridge_addresses.l2_shared_default_bridge,
+            Some(Address::repeat_byte(3))
+        );
+    }
+}
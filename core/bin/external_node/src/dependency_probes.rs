@@ -0,0 +1,320 @@
+//! Active probes of external dependencies of the node (the main node, L1 and object stores).
+//!
+//! Each dependency is probed in the background with its own health check component, so that node orchestrators
+//! can distinguish between the node being broken and an upstream service being unavailable or slow.
+
+use std::{
+    fmt,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_eth_client::EthInterface;
+use zksync_health_check::{async_trait, Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError};
+use zksync_web3_decl::{
+    client::{DynClient, L1, L2},
+    namespaces::EthNamespaceClient,
+};
+
+use crate::metrics::{DependencyProbeOutcome, DEPENDENCY_METRICS};
+
+/// External dependency of the node probed by [`DependencyProbeTask`].
+#[async_trait]
+pub(crate) trait DependencyProbe: fmt::Debug + Send + Sync + 'static {
+    /// Name of the health check component for the dependency.
+    fn name(&self) -> &'static str;
+
+    /// Health status reported if the dependency is unavailable.
+    fn unavailable_status(&self) -> HealthStatus;
+
+    /// Performs a single lightweight request to the dependency.
+    async fn probe(&self) -> anyhow::Result<()>;
+}
+
+/// Probe of the main node JSON-RPC.
+#[derive(Debug)]
+pub(crate) struct MainNodeProbe(Box<DynClient<L2>>);
+
+impl From<Box<DynClient<L2>>> for MainNodeProbe {
+    fn from(client: Box<DynClient<L2>>) -> Self {
+        Self(client.for_component("main_node_health_check"))
+    }
+}
+
+#[async_trait]
+impl DependencyProbe for MainNodeProbe {
+    fn name(&self) -> &'static str {
+        "main_node_http_rpc"
+    }
+
+    fn unavailable_status(&self) -> HealthStatus {
+        HealthStatus::NotReady
+    }
+
+    async fn probe(&self) -> anyhow::Result<()> {
+        self.0.get_block_number().await?;
+        Ok(())
+    }
+}
+
+/// Probe of the Ethereum client.
+#[derive(Debug)]
+pub(crate) struct EthClientProbe(Box<DynClient<L1>>);
+
+impl From<Box<DynClient<L1>>> for EthClientProbe {
+    fn from(client: Box<DynClient<L1>>) -> Self {
+        Self(client.for_component("ethereum_health_check"))
+    }
+}
+
+#[async_trait]
+impl DependencyProbe for EthClientProbe {
+    fn name(&self) -> &'static str {
+        "ethereum_http_rpc"
+    }
+
+    fn unavailable_status(&self) -> HealthStatus {
+        // Unlike main node client, losing connection to L1 is not fatal for the node
+        HealthStatus::Affected
+    }
+
+    async fn probe(&self) -> anyhow::Result<()> {
+        self.0.block_number().await?;
+        Ok(())
+    }
+}
+
+/// Probe of the object store used for the block data archive.
+#[derive(Debug)]
+pub(crate) struct BlockDataArchiveProbe(Arc<dyn ObjectStore>);
+
+impl BlockDataArchiveProbe {
+    /// Key that isn't expected to exist in the store; the store is considered available if it reports that
+    /// the key is missing.
+    const PROBE_KEY: &'static str = "health_check_probe";
+}
+
+impl From<Arc<dyn ObjectStore>> for BlockDataArchiveProbe {
+    fn from(store: Arc<dyn ObjectStore>) -> Self {
+        Self(store)
+    }
+}
+
+#[async_trait]
+impl DependencyProbe for BlockDataArchiveProbe {
+    fn name(&self) -> &'static str {
+        "block_data_archive_object_store"
+    }
+
+    fn unavailable_status(&self) -> HealthStatus {
+        HealthStatus::Affected
+    }
+
+    async fn probe(&self) -> anyhow::Result<()> {
+        match self
+            .0
+            .get_raw(Bucket::BlockDataArchive, Self::PROBE_KEY)
+            .await
+        {
+            Ok(_) | Err(ObjectStoreError::KeyNotFound(_)) => Ok(()),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Health details reported by [`DependencyProbeTask`].
+#[derive(Debug, Serialize)]
+struct DependencyHealthDetails {
+    /// Always `true`; distinguishes external dependencies from components of the node itself.
+    external: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u64>,
+    latency_budget_ms: u64,
+    consecutive_failures: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Task periodically probing an external dependency and reporting its health. A dependency is healthy
+/// if it responds within the latency budget; slow responses mark it as affected, and failed or timed out probes
+/// report [`DependencyProbe::unavailable_status()`].
+#[derive(Debug)]
+pub(crate) struct DependencyProbeTask {
+    probe: Box<dyn DependencyProbe>,
+    interval: Duration,
+    timeout: Duration,
+    latency_budget: Duration,
+    health_updater: HealthUpdater,
+}
+
+impl DependencyProbeTask {
+    pub fn new(
+        probe: Box<dyn DependencyProbe>,
+        interval: Duration,
+        timeout: Duration,
+        latency_budget: Duration,
+    ) -> Self {
+        let (_, health_updater) = ReactiveHealthCheck::new(probe.name());
+        Self {
+            probe,
+            interval,
+            timeout,
+            latency_budget,
+            health_updater,
+        }
+    }
+
+    /// Returns health check associated with this probe.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    async fn probe_once(&self, consecutive_failures: &mut u64) -> Health {
+        let name = self.probe.name();
+        let started_at = Instant::now();
+        let result = tokio::time::timeout(self.timeout, self.probe.probe()).await;
+        let latency = started_at.elapsed();
+
+        let (outcome, error) = match result {
+            Ok(Ok(())) if latency <= self.latency_budget => (DependencyProbeOutcome::Ok, None),
+            Ok(Ok(())) => {
+                tracing::warn!(
+                    "Probe of `{name}` took {latency:?}, exceeding the latency budget {:?}",
+                    self.latency_budget
+                );
+                (DependencyProbeOutcome::Slow, None)
+            }
+            Ok(Err(err)) => {
+                tracing::warn!("Probe of `{name}` failed: {err:#}");
+                (DependencyProbeOutcome::Failed, Some(format!("{err:#}")))
+            }
+            Err(_) => {
+                tracing::warn!("Probe of `{name}` timed out after {:?}", self.timeout);
+                let error = format!("timed out after {:?}", self.timeout);
+                (DependencyProbeOutcome::TimedOut, Some(error))
+            }
+        };
+        DEPENDENCY_METRICS.probes[&(name, outcome)].inc();
+
+        let status = match outcome {
+            DependencyProbeOutcome::Ok => HealthStatus::Ready,
+            DependencyProbeOutcome::Slow => HealthStatus::Affected,
+            DependencyProbeOutcome::Failed | DependencyProbeOutcome::TimedOut => {
+                self.probe.unavailable_status()
+            }
+        };
+        let latency_ms = if error.is_none() {
+            DEPENDENCY_METRICS.probe_latency[&name].observe(latency);
+            *consecutive_failures = 0;
+            Some(latency.as_millis() as u64)
+        } else {
+            *consecutive_failures += 1;
+            None
+        };
+        Health::from(status).with_details(DependencyHealthDetails {
+            external: true,
+            latency_ms,
+            latency_budget_ms: self.latency_budget.as_millis() as u64,
+            consecutive_failures: *consecutive_failures,
+            error,
+        })
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut consecutive_failures = 0;
+        while !*stop_receiver.borrow_and_update() {
+            let health = self.probe_once(&mut consecutive_failures).await;
+            self.health_updater.update(health);
+
+            if tokio::time::timeout(self.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!(
+            "Stop signal received, probe of `{}` is shutting down",
+            self.probe.name()
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU8, Ordering};
+
+    use super::*;
+
+    /// Probe returning outcomes based on the configured mode: 0 – success, 1 – slow success, 2 – error.
+    #[derive(Debug)]
+    struct MockProbe(Arc<AtomicU8>);
+
+    #[async_trait]
+    impl DependencyProbe for MockProbe {
+        fn name(&self) -> &'static str {
+            "mock"
+        }
+
+        fn unavailable_status(&self) -> HealthStatus {
+            HealthStatus::NotReady
+        }
+
+        async fn probe(&self) -> anyhow::Result<()> {
+            match self.0.load(Ordering::SeqCst) {
+                0 => Ok(()),
+                1 => {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    Ok(())
+                }
+                _ => Err(anyhow::anyhow!("unavailable")),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn probing_dependency() {
+        let mode = Arc::new(AtomicU8::new(0));
+        let task = DependencyProbeTask::new(
+            Box::new(MockProbe(mode.clone())),
+            Duration::from_millis(10),
+            Duration::from_secs(10),
+            Duration::from_millis(10),
+        );
+        let mut health_check = task.health_check();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let task = tokio::spawn(task.run(stop_receiver));
+
+        let health = health_check
+            .wait_for(|health| health.status() == HealthStatus::Ready)
+            .await;
+        assert_eq!(health.details().unwrap()["external"], true);
+
+        mode.store(1, Ordering::SeqCst);
+        let health = health_check
+            .wait_for(|health| health.status() == HealthStatus::Affected)
+            .await;
+        assert!(health.details().unwrap()["latency_ms"].as_u64().unwrap() >= 20);
+
+        mode.store(2, Ordering::SeqCst);
+        let health = health_check
+            .wait_for(|health| health.status() == HealthStatus::NotReady)
+            .await;
+        let details = health.details().unwrap();
+        assert!(details["consecutive_failures"].as_u64().unwrap() >= 1);
+        assert_eq!(details["error"], "unavailable");
+
+        mode.store(0, Ordering::SeqCst);
+        let health = health_check
+            .wait_for(|health| health.status() == HealthStatus::Ready)
+            .await;
+        assert_eq!(health.details().unwrap()["consecutive_failures"], 0);
+
+        stop_sender.send_replace(true);
+        task.await.unwrap().unwrap();
+    }
+}
@@ -5,7 +5,6 @@ use std::time::Duration;
 use futures::FutureExt;
 use tokio::sync::watch;
 use zksync_eth_client::EthInterface;
-use zksync_health_check::{async_trait, CheckHealth, Health, HealthStatus};
 use zksync_types::{L1ChainId, L2ChainId};
 use zksync_web3_decl::{
     client::{DynClient, L1, L2},
@@ -13,63 +12,6 @@ use zksync_web3_decl::{
     namespaces::{EthNamespaceClient, ZksNamespaceClient},
 };
 
-/// Main node health check.
-#[derive(Debug)]
-pub(crate) struct MainNodeHealthCheck(Box<DynClient<L2>>);
-
-impl From<Box<DynClient<L2>>> for MainNodeHealthCheck {
-    fn from(client: Box<DynClient<L2>>) -> Self {
-        Self(client.for_component("main_node_health_check"))
-    }
-}
-
-#[async_trait]
-impl CheckHealth for MainNodeHealthCheck {
-    fn name(&self) -> &'static str {
-        "main_node_http_rpc"
-    }
-
-    async fn check_health(&self) -> Health {
-        if let Err(err) = self.0.get_block_number().await {
-            tracing::warn!("Health-check call to main node HTTP RPC failed: {err}");
-            let details = serde_json::json!({
-                "error": err.to_string(),
-            });
-            return Health::from(HealthStatus::NotReady).with_details(details);
-        }
-        HealthStatus::Ready.into()
-    }
-}
-
-/// Ethereum client health check.
-#[derive(Debug)]
-pub(crate) struct EthClientHealthCheck(Box<DynClient<L1>>);
-
-impl From<Box<DynClient<L1>>> for EthClientHealthCheck {
-    fn from(client: Box<DynClient<L1>>) -> Self {
-        Self(client.for_component("ethereum_health_check"))
-    }
-}
-
-#[async_trait]
-impl CheckHealth for EthClientHealthCheck {
-    fn name(&self) -> &'static str {
-        "ethereum_http_rpc"
-    }
-
-    async fn check_health(&self) -> Health {
-        if let Err(err) = self.0.block_number().await {
-            tracing::warn!("Health-check call to Ethereum HTTP RPC failed: {err}");
-            let details = serde_json::json!({
-                "error": err.to_string(),
-            });
-            // Unlike main node client, losing connection to L1 is not fatal for the node
-            return Health::from(HealthStatus::Affected).with_details(details);
-        }
-        HealthStatus::Ready.into()
-    }
-}
-
 /// Task that validates chain IDs using main node and Ethereum clients.
 #[derive(Debug)]
 pub(crate) struct ValidateChainIdsTask {
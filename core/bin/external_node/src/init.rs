@@ -1,15 +1,21 @@
 //! EN initialization logic.
 
-use std::time::Instant;
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
-use zksync_dal::{ConnectionPool, Core, CoreDal};
+use tokio::sync::watch;
+use zksync_dal::{pruning_dal::NodeMode, ConnectionPool, Core, CoreDal};
 use zksync_health_check::AppHealthCheck;
+use zksync_merkle_tree::{domain::ZkSyncTreeReader, Database, RocksDBWrapper};
 use zksync_node_sync::genesis::perform_genesis_if_needed;
 use zksync_object_store::ObjectStoreFactory;
 use zksync_shared_metrics::{SnapshotRecoveryStage, APP_METRICS};
 use zksync_snapshots_applier::{SnapshotsApplierConfig, SnapshotsApplierTask};
-use zksync_types::{L1BatchNumber, L2ChainId};
+use zksync_storage::RocksDB;
+use zksync_types::{L1BatchNumber, L2ChainId, H256};
 use zksync_web3_decl::client::{DynClient, L2};
 
 use crate::config::SnapshotsRecoveryConfig;
@@ -85,18 +91,8 @@ pub(crate) async fn ensure_storage_initialized(
             );
 
             tracing::warn!("Proceeding with snapshot recovery. This is an experimental feature; use at your own risk");
-            let recovery_config = SnapshotsRecoveryConfig::new()?;
-            let blob_store = ObjectStoreFactory::new(recovery_config.snapshots_object_store)
-                .create_store()
-                .await;
-
-            let config = SnapshotsApplierConfig::default();
-            let snapshots_applier_task = SnapshotsApplierTask::new(
-                config,
-                pool,
-                Box::new(main_node_client.for_component("snapshot_recovery")),
-                blob_store,
-            );
+            let snapshots_applier_task =
+                create_snapshots_applier_task(pool, main_node_client).await?;
             app_health.insert_component(snapshots_applier_task.health_check())?;
 
             let recovery_started_at = Instant::now();
@@ -114,3 +110,191 @@ pub(crate) async fn ensure_storage_initialized(
     }
     Ok(())
 }
+
+async fn create_snapshots_applier_task(
+    pool: ConnectionPool<Core>,
+    main_node_client: Box<DynClient<L2>>,
+) -> anyhow::Result<SnapshotsApplierTask> {
+    let recovery_config = SnapshotsRecoveryConfig::new()?;
+    let blob_store = ObjectStoreFactory::new(recovery_config.snapshots_object_store)
+        .create_store()
+        .await;
+    let mut mirror_blob_stores = vec![];
+    for mirror_config in recovery_config.snapshots_object_store_mirrors {
+        tracing::info!("Using snapshot object store mirror: {mirror_config:?}");
+        let mirror = ObjectStoreFactory::new(mirror_config).create_store().await;
+        mirror_blob_stores.push(mirror);
+    }
+
+    let config = SnapshotsApplierConfig::default();
+    Ok(SnapshotsApplierTask::new(
+        config,
+        pool,
+        Box::new(main_node_client.for_component("snapshot_recovery")),
+        blob_store,
+    )
+    .with_fallback_blob_stores(mirror_blob_stores))
+}
+
+/// Verifies the node storage recovered from a snapshot against the snapshot data. If `merkle_tree_path` is specified,
+/// also verifies the Merkle tree at this path. Returns an error if any discrepancies are found.
+pub(crate) async fn verify_snapshot_recovery(
+    pool: ConnectionPool<Core>,
+    main_node_client: Box<DynClient<L2>>,
+    merkle_tree_path: Option<&Path>,
+) -> anyhow::Result<()> {
+    let snapshots_applier_task = create_snapshots_applier_task(pool, main_node_client).await?;
+    let mut report = snapshots_applier_task
+        .verify()
+        .await
+        .context("snapshot recovery verification failed")?;
+    if let Some(merkle_tree_path) = merkle_tree_path {
+        let tree_root_hash = tree_root_hash(merkle_tree_path, report.l1_batch_number).await?;
+        report.check_tree_root_hash(tree_root_hash);
+    } else {
+        tracing::info!("Node doesn't run the Merkle tree; skipping tree verification");
+    }
+    if !report.is_consistent() {
+        anyhow::bail!(
+            "Node storage is inconsistent with the snapshot for L1 batch #{}: found {} discrepancies",
+            report.l1_batch_number,
+            report.discrepancy_count
+        );
+    }
+    tracing::info!(
+        "Node storage is consistent with the snapshot for L1 batch #{}: checked {} factory deps and {} storage logs",
+        report.l1_batch_number,
+        report.factory_deps_checked,
+        report.storage_logs_checked
+    );
+    Ok(())
+}
+
+/// Returns the Merkle tree root hash at the specified L1 batch, or `None` if the tree doesn't contain this batch.
+async fn tree_root_hash(
+    merkle_tree_path: &Path,
+    l1_batch_number: L1BatchNumber,
+) -> anyhow::Result<Option<H256>> {
+    if !merkle_tree_path.exists() {
+        tracing::warn!("Merkle tree at {merkle_tree_path:?} doesn't exist");
+        return Ok(None);
+    }
+
+    let merkle_tree_path = merkle_tree_path.to_owned();
+    let root_hash = tokio::task::spawn_blocking(move || {
+        let db = RocksDBWrapper::new(&merkle_tree_path).context("failed opening Merkle tree")?;
+        if db.manifest().is_some_and(|manifest| manifest.recovered_version().is_some()) {
+            tracing::warn!("Merkle tree recovery is not completed");
+            return Ok(None);
+        }
+        let reader = ZkSyncTreeReader::new(db);
+        if let Some(min_l1_batch_number) = reader.min_l1_batch_number() {
+            anyhow::ensure!(
+                min_l1_batch_number <= l1_batch_number,
+                "Merkle tree is pruned up to L1 batch #{min_l1_batch_number}, so it cannot be verified \
+                 against the snapshot for L1 batch #{l1_batch_number}"
+            );
+        }
+        Ok(reader
+            .pin_l1_batch(l1_batch_number)
+            .ok()
+            .map(|version| version.root_hash()))
+    })
+    .await
+    .context("panicked reading Merkle tree")??;
+    tokio::task::spawn_blocking(RocksDB::await_rocksdb_termination)
+        .await
+        .context("error waiting for RocksDB instances to drop")?;
+    Ok(root_hash)
+}
+
+/// Waits until the node storage is initialized by another node process sharing the same Postgres, either via genesis
+/// or snapshot recovery. Returns early if a stop signal is received.
+pub(crate) async fn wait_for_storage_initialization(
+    pool: &ConnectionPool<Core>,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    loop {
+        let mut storage = pool.connection_tagged("en").await?;
+        let genesis_l1_batch = storage
+            .blocks_dal()
+            .get_l1_batch_header(L1BatchNumber(0))
+            .await?;
+        let snapshot_recovery = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?;
+        drop(storage);
+
+        let is_recovered = snapshot_recovery
+            .is_some_and(|status| status.storage_logs_chunks_left_to_process() == 0);
+        if genesis_l1_batch.is_some() || is_recovered {
+            return Ok(());
+        }
+
+        tracing::info!("Node storage is not initialized yet; waiting for the leader node process to initialize it");
+        if tokio::time::timeout(POLL_INTERVAL, stop_receiver.changed())
+            .await
+            .is_ok()
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// Checks the configured node mode (archive / pruned) against the mode persisted in Postgres. If the modes differ,
+/// the persisted mode is changed only if `allow_switch` is set; otherwise, an error is returned.
+pub(crate) async fn validate_node_mode(
+    pool: &ConnectionPool<Core>,
+    pruning_enabled: bool,
+    allow_switch: bool,
+) -> anyhow::Result<()> {
+    let configured_mode = if pruning_enabled {
+        NodeMode::Pruned
+    } else {
+        NodeMode::Archive
+    };
+
+    let mut storage = pool.connection_tagged("en").await?;
+    let pruning_info = storage.pruning_dal().get_pruning_info().await?;
+    let persisted_mode = storage.pruning_dal().get_node_mode().await?;
+    let persisted_mode = persisted_mode.unwrap_or_else(|| {
+        // The mode wasn't persisted yet (e.g., the node was launched by a version not persisting the mode).
+        // If the node has pruned any data, it was evidently run with pruning enabled.
+        if pruning_info.last_soft_pruned_l1_batch.is_some() {
+            NodeMode::Pruned
+        } else {
+            configured_mode
+        }
+    });
+
+    if persisted_mode != configured_mode {
+        anyhow::ensure!(
+            allow_switch,
+            "Node is configured to run in {configured_mode} mode, while it previously ran in {persisted_mode} mode. \
+             If this is intended, restart the node with the `--switch-node-mode` command-line arg; otherwise, \
+             change the `EN_PRUNING_ENABLED` env variable"
+        );
+
+        match configured_mode {
+            NodeMode::Pruned => {
+                tracing::warn!(
+                    "Switching node mode from {persisted_mode} to {configured_mode}; old data will be pruned"
+                );
+            }
+            NodeMode::Archive => {
+                tracing::warn!(
+                    "Switching node mode from {persisted_mode} to {configured_mode}; the node will retain all new data, \
+                     but the already pruned data (L1 batches <= {:?}) will not be restored",
+                    pruning_info.last_hard_pruned_l1_batch
+                );
+            }
+        }
+    } else {
+        tracing::info!("Node runs in {configured_mode} mode");
+    }
+    storage.pruning_dal().set_node_mode(configured_mode).await?;
+    Ok(())
+}
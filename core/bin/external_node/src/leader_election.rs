@@ -0,0 +1,104 @@
+//! Leader election for multiple external node processes sharing the same Postgres database.
+
+use std::time::Duration;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+
+/// Key of the Postgres advisory lock used for leader election.
+const LEADER_LOCK_KEY: i64 = 0x0065_6e5f_6c65_6164; // `en_lead` in ASCII
+
+/// Role of the node process determined by leader election.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ProcessRole {
+    /// The node runs all configured components, including ones writing to Postgres (state keeper, tree etc.).
+    Leader,
+    /// The node only serves the JSON-RPC API from the shared Postgres until it becomes the leader.
+    Replica,
+}
+
+/// Leader election based on a session-level Postgres advisory lock. The lock is held by a dedicated connection
+/// for the entire lifetime of the leader process, so that it's released by Postgres if the process crashes.
+#[derive(Debug)]
+pub(crate) struct LeaderElection {
+    pool: ConnectionPool<Core>,
+    poll_interval: Duration,
+    role_sender: watch::Sender<Option<ProcessRole>>,
+}
+
+impl LeaderElection {
+    /// Creates a new leader election. `pool` should contain a single connection, which will be held
+    /// by the election task.
+    pub fn new(pool: ConnectionPool<Core>, poll_interval: Duration) -> Self {
+        Self {
+            pool,
+            poll_interval,
+            role_sender: watch::channel(None).0,
+        }
+    }
+
+    /// Subscribes to changes of the node role. The role is `None` until the first attempt to acquire leadership
+    /// completes. Once the node becomes the leader, it remains the leader until the process exits.
+    pub fn subscribe(&self) -> watch::Receiver<Option<ProcessRole>> {
+        self.role_sender.subscribe()
+    }
+
+    /// Runs the election until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the leader loses the lock (e.g., because the connection holding it was broken).
+    /// In this case, the node must stop since another process may have become the leader.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut connection = self.pool.connection_tagged("leader_election").await?;
+        loop {
+            let acquired = connection
+                .system_dal()
+                .try_acquire_advisory_lock(LEADER_LOCK_KEY)
+                .await?;
+            if acquired {
+                break;
+            }
+            if self
+                .role_sender
+                .send_replace(Some(ProcessRole::Replica))
+                .is_none()
+            {
+                tracing::info!(
+                    "Leader lock is held by another node process; running as a replica serving the API only"
+                );
+            }
+
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                tracing::info!("Stop signal received, leader election is shutting down");
+                return Ok(());
+            }
+        }
+
+        tracing::info!("Acquired leader lock; this node process is now the leader");
+        self.role_sender.send_replace(Some(ProcessRole::Leader));
+
+        while tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+            .await
+            .is_err()
+        {
+            let holds_lock = connection
+                .system_dal()
+                .holds_advisory_lock(LEADER_LOCK_KEY)
+                .await
+                .context("failed checking leader lock")?;
+            anyhow::ensure!(holds_lock, "Leader lock was lost");
+        }
+
+        tracing::info!("Stop signal received, releasing leader lock");
+        connection
+            .system_dal()
+            .release_advisory_lock(LEADER_LOCK_KEY)
+            .await?;
+        Ok(())
+    }
+}
@@ -1,9 +1,21 @@
-use std::{collections::HashSet, net::Ipv4Addr, str::FromStr, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    future::{self, Future},
+    io,
+    net::Ipv4Addr,
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use clap::Parser;
 use metrics::EN_METRICS;
+use snapshots_creator::{SnapshotCreator, MIN_CHUNK_COUNT};
 use tokio::{
+    signal,
     sync::{oneshot, watch, RwLock},
     task::{self, JoinHandle},
 };
@@ -12,14 +24,19 @@ use zksync_commitment_generator::{
     validation_task::L1BatchCommitmentModeValidationTask, CommitmentGenerator,
 };
 use zksync_concurrency::{ctx, scope};
-use zksync_config::configs::{api::MerkleTreeApiConfig, database::MerkleTreeMode};
+use zksync_config::{
+    configs::{api::MerkleTreeApiConfig, database::MerkleTreeMode},
+    SnapshotsCreatorConfig,
+};
 use zksync_consistency_checker::ConsistencyChecker;
 use zksync_core_leftovers::setup_sigint_handler;
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core, CoreDal};
 use zksync_db_connection::{
-    connection_pool::ConnectionPoolBuilder, healthcheck::ConnectionPoolHealthCheck,
+    circuit_breaker::SlowQueryCircuitBreakerConfig, connection_pool::ConnectionPoolBuilder,
+    healthcheck::ConnectionPoolHealthCheck,
 };
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
+use zksync_house_keeper::{partition_manager::PartitionManager, periodic_job::PeriodicJob};
 use zksync_metadata_calculator::{
     api_server::{TreeApiClient, TreeApiHttpClient},
     MetadataCalculator, MetadataCalculatorConfig,
@@ -28,40 +45,63 @@ use zksync_node_api_server::{
     execution_sandbox::VmConcurrencyLimiter,
     healthcheck::HealthCheckHandle,
     tx_sender::{proxy::TxProxy, ApiContracts, TxSenderBuilder},
-    web3::{mempool_cache::MempoolCache, ApiBuilder, Namespace},
+    web3::{
+        mempool_cache::MempoolCache, response_cache::ResponseCache, slow_calls::SlowCallsSampler,
+        state::UpdatableApiConfig, ApiBuilder, Namespace,
+    },
 };
+use zksync_node_block_data_archive::{BlockDataArchiveReader, BlockDataExporter};
 use zksync_node_consensus as consensus;
 use zksync_node_db_pruner::{DbPruner, DbPrunerConfig};
 use zksync_node_fee_model::l1_gas_price::MainNodeFeeParamsFetcher;
 use zksync_node_sync::{
     batch_status_updater::BatchStatusUpdater, external_io::ExternalIO,
-    tree_data_fetcher::TreeDataFetcher, ActionQueue, SyncState,
+    tree_data_fetcher::TreeDataFetcher, ActionQueue, L1CommitVerifier, SyncState,
 };
+use zksync_object_store::ObjectStoreFactory;
 use zksync_reorg_detector::ReorgDetector;
-use zksync_state::{PostgresStorageCaches, RocksdbStorageOptions};
+use zksync_state::{PostgresStorageCaches, RocksdbCompactionHandle, RocksdbStorageOptions};
 use zksync_state_keeper::{
-    seal_criteria::NoopSealer, AsyncRocksdbCache, BatchExecutor, MainBatchExecutor, OutputHandler,
-    StateKeeperPersistence, ZkSyncStateKeeper,
+    seal_criteria::{ConditionalSealer, NoopSealer, VerifyingSealer},
+    AsyncRocksdbCache, BatchExecutor, MainBatchExecutor, OutputHandler, StateKeeperPersistence,
+    ZkSyncStateKeeper,
 };
 use zksync_storage::RocksDB;
-use zksync_types::L2ChainId;
+use zksync_types::{L1BatchNumber, L2ChainId};
 use zksync_utils::wait_for_tasks::ManagedTasks;
 use zksync_web3_decl::{
-    client::{Client, DynClient, L1, L2},
+    client::{Client, DynClient, FailoverClient, QuorumClient, L1, L2},
     jsonrpsee,
     namespaces::EnNamespaceClient,
 };
 
 use crate::{
-    config::ExternalNodeConfig,
-    helpers::{EthClientHealthCheck, MainNodeHealthCheck, ValidateChainIdsTask},
-    init::ensure_storage_initialized,
+    commitment_verifier::CommitmentVerifier,
+    config::{
+        read_block_data_archive_object_store_config, EnvMigration, ExternalNodeConfig,
+        SnapshotsRecoveryConfig,
+    },
+    config_refresher::RemoteConfigRefresher,
+    dependency_probes::{
+        BlockDataArchiveProbe, DependencyProbe, DependencyProbeTask, EthClientProbe, MainNodeProbe,
+    },
+    helpers::ValidateChainIdsTask,
+    init::{
+        ensure_storage_initialized, validate_node_mode, verify_snapshot_recovery,
+        wait_for_storage_initialization,
+    },
+    leader_election::{LeaderElection, ProcessRole},
     metrics::RUST_METRICS,
 };
 
+mod admin;
+mod commitment_verifier;
 mod config;
+mod config_refresher;
+mod dependency_probes;
 mod helpers;
 mod init;
+mod leader_election;
 mod metadata;
 mod metrics;
 #[cfg(test)]
@@ -76,11 +116,12 @@ async fn build_state_keeper(
     config: &ExternalNodeConfig,
     connection_pool: ConnectionPool<Core>,
     main_node_client: Box<DynClient<L2>>,
+    l1_commit_verifier: Option<L1CommitVerifier>,
     output_handler: OutputHandler,
     stop_receiver: watch::Receiver<bool>,
     chain_id: L2ChainId,
     task_handles: &mut Vec<JoinHandle<anyhow::Result<()>>>,
-) -> anyhow::Result<ZkSyncStateKeeper> {
+) -> anyhow::Result<(ZkSyncStateKeeper, RocksdbCompactionHandle)> {
     // We only need call traces on the external node if the `debug_` namespace is enabled.
     let save_call_traces = config.optional.api_namespaces().contains(&Namespace::Debug);
 
@@ -88,18 +129,36 @@ async fn build_state_keeper(
         block_cache_capacity: config.experimental.state_keeper_db_block_cache_capacity(),
         max_open_files: config.experimental.state_keeper_db_max_open_files,
     };
-    let (storage_factory, task) =
+    let (storage_factory, mut task) =
         AsyncRocksdbCache::new(connection_pool.clone(), state_keeper_db_path, cache_options);
+    if let Some(concurrency) = config
+        .experimental
+        .state_keeper_db_snapshot_warm_up_concurrency
+    {
+        let object_store_config = SnapshotsRecoveryConfig::new()?.snapshots_object_store;
+        let blob_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        task = task.with_snapshot_blob_store(blob_store, concurrency);
+    }
     let mut stop_receiver_clone = stop_receiver.clone();
     task_handles.push(tokio::spawn(async move {
         let result = task.run(stop_receiver_clone.clone()).await;
         stop_receiver_clone.changed().await?;
         result
     }));
-    let batch_executor_base: Box<dyn BatchExecutor> =
-        Box::new(MainBatchExecutor::new(save_call_traces, true));
+    let maintenance_task =
+        storage_factory.maintenance_task(config.experimental.state_keeper_db_maintenance_config());
+    let compaction_handle = maintenance_task.compaction_handle();
+    task_handles.push(tokio::spawn(maintenance_task.run(stop_receiver.clone())));
+    let mut batch_executor = MainBatchExecutor::new(save_call_traces, true);
+    if let Some(vm_version) = config.experimental.shadow_vm_version {
+        tracing::info!("Executing L1 batches in the shadow VM {vm_version:?}");
+        batch_executor = batch_executor.with_shadow_vm(vm_version, connection_pool.clone());
+    }
+    let batch_executor_base: Box<dyn BatchExecutor> = Box::new(batch_executor);
 
-    let io = ExternalIO::new(
+    let mut io = ExternalIO::new(
         connection_pool,
         action_queue,
         Box::new(main_node_client.for_component("external_io")),
@@ -107,15 +166,28 @@ async fn build_state_keeper(
     )
     .await
     .context("Failed initializing I/O for external node state keeper")?;
+    if let Some(verifier) = l1_commit_verifier {
+        io = io.with_l1_commit_verification(verifier);
+    }
 
-    Ok(ZkSyncStateKeeper::new(
+    let sealer: Arc<dyn ConditionalSealer> =
+        if let Some(seal_criteria) = config.experimental.seal_criteria_verification_config() {
+            tracing::info!("Verifying main node sealing decisions against {seal_criteria:?}");
+            io = io.with_seal_verification(&seal_criteria);
+            Arc::new(VerifyingSealer::new(seal_criteria))
+        } else {
+            Arc::new(NoopSealer)
+        };
+
+    let state_keeper = ZkSyncStateKeeper::new(
         stop_receiver,
         Box::new(io),
         batch_executor_base,
         output_handler,
-        Arc::new(NoopSealer),
+        sealer,
         Arc::new(storage_factory),
-    ))
+    );
+    Ok((state_keeper, compaction_handle))
 }
 
 async fn run_tree(
@@ -139,6 +211,10 @@ async fn run_tree(
             .merkle_tree_include_indices_and_filters_in_block_cache,
         memtable_capacity: config.optional.merkle_tree_memtable_capacity(),
         stalled_writes_timeout: config.optional.merkle_tree_stalled_writes_timeout(),
+        tree_persistence_buffer: config
+            .experimental
+            .merkle_tree_parallel_persistence_buffer
+            .map(NonZeroUsize::get),
     };
 
     let max_concurrency = config
@@ -168,13 +244,30 @@ async fn run_tree(
     if config.optional.pruning_enabled {
         tracing::warn!("Proceeding with node state pruning for the Merkle tree. This is an experimental feature; use at your own risk");
 
-        let pruning_task =
+        let mut pruning_task =
             metadata_calculator.pruning_task(config.optional.pruning_removal_delay() / 2);
+        if let Some(max_tree_size) = config.experimental.merkle_tree_max_size() {
+            pruning_task = pruning_task.with_max_tree_size(max_tree_size);
+        }
         app_health.insert_component(pruning_task.health_check())?;
         let pruning_task_handle = tokio::spawn(pruning_task.run(stop_receiver.clone()));
         task_futures.push(pruning_task_handle);
     }
 
+    if let Some(interval) = config.experimental.merkle_tree_consistency_check_interval() {
+        let consistency_task = metadata_calculator.consistency_task(
+            config.experimental.merkle_tree_consistency_check_chunk_size,
+            interval,
+        );
+        app_health.insert_component(consistency_task.health_check())?;
+        task_futures.push(tokio::spawn(consistency_task.run(stop_receiver.clone())));
+    }
+
+    if let Some(interval) = config.experimental.merkle_tree_analysis_interval() {
+        let analysis_task = metadata_calculator.analysis_task(interval);
+        task_futures.push(tokio::spawn(analysis_task.run(stop_receiver.clone())));
+    }
+
     if let Some(api_config) = api_config {
         let address = (Ipv4Addr::UNSPECIFIED, api_config.port).into();
         let tree_reader = metadata_calculator.tree_reader();
@@ -187,6 +280,20 @@ async fn run_tree(
                 .run_api_server(address, stop_receiver)
                 .await
         }));
+
+        if let Some(grpc_port) = api_config.grpc_port {
+            let address = (Ipv4Addr::UNSPECIFIED, grpc_port).into();
+            let tree_reader = metadata_calculator.tree_reader();
+            let stop_receiver = stop_receiver.clone();
+            task_futures.push(tokio::spawn(async move {
+                tree_reader
+                    .wait()
+                    .await
+                    .context("Cannot initialize tree reader")?
+                    .run_grpc_server(address, stop_receiver)
+                    .await
+            }));
+        }
     }
 
     let tree_handle = task::spawn(metadata_calculator.run(stop_receiver));
@@ -205,20 +312,30 @@ async fn run_core(
     app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
     singleton_pool_builder: &ConnectionPoolBuilder<Core>,
+    slow_calls_sampler: Option<Arc<SlowCallsSampler>>,
 ) -> anyhow::Result<SyncState> {
     // Create components.
     let sync_state = SyncState::default();
     app_health.insert_custom_component(Arc::new(sync_state.clone()))?;
     let (action_queue_sender, action_queue) = ActionQueue::new();
 
-    let (persistence, miniblock_sealer) = StateKeeperPersistence::new(
-        connection_pool.clone(),
-        config
-            .remote
-            .l2_shared_bridge_addr
-            .expect("L2 shared bridge address is not set"),
-        config.optional.l2_block_seal_queue_capacity,
-    );
+    let l2_shared_bridge_addr = config
+        .remote
+        .l2_shared_bridge_addr
+        .expect("L2 shared bridge address is not set");
+    let (persistence, miniblock_sealer) =
+        match config.optional.l2_block_seal_queue_adaptive_config() {
+            Some(queue_config) => StateKeeperPersistence::new_adaptive(
+                connection_pool.clone(),
+                l2_shared_bridge_addr,
+                queue_config,
+            ),
+            None => StateKeeperPersistence::new(
+                connection_pool.clone(),
+                l2_shared_bridge_addr,
+                config.optional.l2_block_seal_queue_capacity,
+            ),
+        };
     task_handles.push(tokio::spawn(miniblock_sealer.run()));
 
     let mut persistence = persistence.with_tx_insertion();
@@ -229,14 +346,32 @@ async fn run_core(
         persistence = persistence.without_protective_reads();
     }
 
-    let output_handler =
-        OutputHandler::new(Box::new(persistence)).with_handler(Box::new(sync_state.clone()));
-    let state_keeper = build_state_keeper(
+    let l1_commit_verifier = config.experimental.l1_commit_verification_enabled.then(|| {
+        tracing::info!(
+            "Verifying L1 batches against their commit transactions on L1 before execution"
+        );
+        L1CommitVerifier::new(
+            main_node_client.clone(),
+            eth_client.clone(),
+            config.remote.diamond_proxy_addr,
+        )
+    });
+    let output_handler = match &l1_commit_verifier {
+        // The checker must run before persistence, so that divergent L1 batches aren't persisted.
+        Some(verifier) => OutputHandler::new(Box::new(
+            verifier.executed_batch_checker(connection_pool.clone()),
+        ))
+        .with_handler(Box::new(persistence)),
+        None => OutputHandler::new(Box::new(persistence)),
+    };
+    let output_handler = output_handler.with_handler(Box::new(sync_state.clone()));
+    let (state_keeper, compaction_handle) = build_state_keeper(
         action_queue,
         config.required.state_cache_path.clone(),
         config,
         connection_pool.clone(),
         main_node_client.clone(),
+        l1_commit_verifier,
         output_handler,
         stop_receiver.clone(),
         config.required.l2_chain_id,
@@ -244,12 +379,27 @@ async fn run_core(
     )
     .await?;
 
+    let consensus_peers = config
+        .consensus
+        .as_ref()
+        .map(consensus::PeersHandle::new)
+        .transpose()
+        .context("consensus::PeersHandle::new()")?;
     task_handles.push(tokio::spawn({
+        let validator_watermark_path = config.experimental.consensus_validator_watermark_path.clone();
+        let fetcher_prefetch_depth = config.optional.fetcher_prefetch_depth;
         let config = config.consensus.clone();
         let secrets =
             config::read_consensus_secrets().context("config::read_consensus_secrets()")?;
         let cfg = match (config, secrets) {
-            (Some(cfg), Some(secrets)) => Some((cfg, secrets)),
+            (Some(cfg), Some(secrets)) => {
+                anyhow::ensure!(
+                    secrets.validator_key.is_none() || validator_watermark_path.is_some(),
+                    "Consensus secrets contain a validator key, but `EN_EXPERIMENTAL_CONSENSUS_VALIDATOR_WATERMARK_PATH` \
+                     is not set; it's required to prevent double-signing"
+                );
+                Some((cfg, secrets))
+            }
             (Some(_), None) => {
                 anyhow::bail!("Consensus config is specified, but secrets are missing")
             }
@@ -260,9 +410,12 @@ async fn run_core(
             }
         };
 
+        let (health_check, health_updater) = ReactiveHealthCheck::new("consensus");
+        app_health.insert_component(health_check)?;
         let pool = connection_pool.clone();
         let sync_state = sync_state.clone();
         let main_node_client = main_node_client.clone();
+        let consensus_peers = consensus_peers.clone();
         let mut stop_receiver = stop_receiver.clone();
         async move {
             // We instantiate the root context here, since the consensus task is the only user of the
@@ -275,10 +428,14 @@ async fn run_core(
                 s.spawn_bg(consensus::era::run_en(
                     ctx,
                     cfg,
+                    validator_watermark_path,
                     pool,
                     sync_state,
                     main_node_client,
                     action_queue_sender,
+                    health_updater,
+                    fetcher_prefetch_depth,
+                    consensus_peers,
                 ));
                 ctx.wait(stop_receiver.wait_for(|stop| *stop)).await??;
                 Ok(())
@@ -288,6 +445,22 @@ async fn run_core(
         }
     }));
 
+    if config.experimental.block_data_archive_export_enabled {
+        tracing::warn!("Proceeding with block data export. This is an experimental feature; use at your own risk");
+
+        let object_store_config = read_block_data_archive_object_store_config()?;
+        let blob_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        let exporter = BlockDataExporter::new(
+            connection_pool.clone(),
+            blob_store,
+            config.required.l2_chain_id,
+        );
+        task_handles.push(tokio::spawn(exporter.run(stop_receiver.clone())));
+    }
+
+    let mut db_pruner_handle = None;
     if config.optional.pruning_enabled {
         tracing::warn!("Proceeding with node state pruning for Postgres. This is an experimental feature; use at your own risk");
 
@@ -295,7 +468,7 @@ async fn run_core(
         tracing::info!(
             "Configured pruning of batches after they become {minimum_l1_batch_age:?} old"
         );
-        let db_pruner = DbPruner::new(
+        let mut db_pruner = DbPruner::new(
             DbPrunerConfig {
                 removal_delay: config.optional.pruning_removal_delay(),
                 pruned_batch_chunk_size: config.optional.pruning_chunk_size,
@@ -303,10 +476,68 @@ async fn run_core(
             },
             connection_pool.clone(),
         );
+        if config.experimental.block_data_archive_export_enabled {
+            db_pruner = db_pruner.require_archived_block_data();
+        }
         app_health.insert_component(db_pruner.health_check())?;
+        db_pruner_handle = Some(db_pruner.handle());
         task_handles.push(tokio::spawn(db_pruner.run(stop_receiver.clone())));
     }
 
+    const PARTITION_MANAGER_INTERVAL_MS: u64 = 60_000;
+    let partition_manager = PartitionManager::new(
+        connection_pool.clone(),
+        PartitionManager::DEFAULT_PARTITION_SIZE,
+        PARTITION_MANAGER_INTERVAL_MS,
+    );
+    task_handles.push(tokio::spawn(partition_manager.run(stop_receiver.clone())));
+
+    if let Some(port) = config.optional.pruning_admin_port {
+        let bind_address = (Ipv4Addr::LOCALHOST, port).into();
+        let consensus_info = config
+            .consensus
+            .clone()
+            .zip(consensus_peers)
+            .map(|(cfg, peers)| admin::ConsensusInfo::new(cfg, sync_state.clone(), peers));
+        task_handles.push(tokio::spawn(admin::run_server(
+            bind_address,
+            db_pruner_handle,
+            compaction_handle,
+            consensus_info,
+            vlog::log_directives_handle(),
+            slow_calls_sampler,
+            stop_receiver.clone(),
+        )));
+    }
+
+    if let Some(interval) = config.experimental.snapshots_creation_interval() {
+        tracing::warn!("Proceeding with snapshot creation. This is an experimental feature; use at your own risk");
+
+        let creator_config = config.experimental.snapshots_creator_config();
+        let object_store_config = SnapshotsRecoveryConfig::new()?.snapshots_object_store;
+        let blob_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        let master_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a connection pool for snapshot creator")?;
+        let replica_pool = ConnectionPool::<Core>::builder(
+            config.postgres.database_url(),
+            creator_config.concurrent_queries_count,
+        )
+        .build()
+        .await
+        .context("failed to build a connection pool for snapshot creator")?;
+        let creator = SnapshotCreator::new(blob_store, master_pool, replica_pool);
+        task_handles.push(tokio::spawn(run_snapshot_creator(
+            creator,
+            creator_config,
+            interval,
+            stop_receiver.clone(),
+        )));
+    }
+
     let sk_handle = task::spawn(state_keeper.run());
     let remote_diamond_proxy_addr = config.remote.diamond_proxy_addr;
     let diamond_proxy_addr = if let Some(addr) = config.optional.contracts_diamond_proxy_addr {
@@ -367,6 +598,13 @@ async fn run_core(
     app_health.insert_component(commitment_generator.health_check())?;
     let commitment_generator_handle = tokio::spawn(commitment_generator.run(stop_receiver.clone()));
 
+    if config.optional.commitment_verification_enabled {
+        let commitment_verifier =
+            CommitmentVerifier::new(connection_pool.clone(), main_node_client.clone());
+        app_health.insert_component(commitment_verifier.health_check())?;
+        task_handles.push(tokio::spawn(commitment_verifier.run(stop_receiver.clone())));
+    }
+
     let updater_handle = task::spawn(batch_status_updater.run(stop_receiver.clone()));
 
     task_handles.extend([
@@ -379,6 +617,36 @@ async fn run_core(
     Ok(sync_state)
 }
 
+/// Periodically creates snapshots until a stop signal is received. Errors during snapshot creation are logged
+/// and do not stop the node; creation is retried after `interval`.
+async fn run_snapshot_creator(
+    creator: SnapshotCreator,
+    config: SnapshotsCreatorConfig,
+    interval: Duration,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    while !*stop_receiver.borrow_and_update() {
+        tokio::select! {
+            result = creator.run(config.clone(), MIN_CHUNK_COUNT) => {
+                if let Err(err) = result {
+                    tracing::warn!("Failed creating snapshot: {err:#}");
+                }
+            }
+            // The snapshot creator is fault-tolerant, so it's safe to interrupt it
+            _ = stop_receiver.changed() => break,
+        }
+
+        if tokio::time::timeout(interval, stop_receiver.changed())
+            .await
+            .is_ok()
+        {
+            break;
+        }
+    }
+    tracing::info!("Stop signal received, snapshot creator is shutting down");
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 async fn run_api(
     task_handles: &mut Vec<JoinHandle<anyhow::Result<()>>>,
@@ -391,6 +659,8 @@ async fn run_api(
     main_node_client: Box<DynClient<L2>>,
     singleton_pool_builder: &ConnectionPoolBuilder<Core>,
     fee_params_fetcher: Arc<MainNodeFeeParamsFetcher>,
+    updatable_api_config: Option<UpdatableApiConfig>,
+    slow_calls_sampler: Option<Arc<SlowCallsSampler>>,
     components: &HashSet<Component>,
 ) -> anyhow::Result<()> {
     let tree_reader = match tree_reader {
@@ -432,10 +702,6 @@ async fn run_api(
     let tx_sender_builder =
         TxSenderBuilder::new(config.into(), connection_pool.clone(), Arc::new(tx_proxy));
 
-    if config.optional.transactions_per_sec_limit.is_some() {
-        tracing::warn!("`transactions_per_sec_limit` option is deprecated and ignored");
-    };
-
     let max_concurrency = config.optional.vm_concurrency_limit;
     let (vm_concurrency_limiter, vm_barrier) = VmConcurrencyLimiter::new(max_concurrency);
     let mut storage_caches = PostgresStorageCaches::new(
@@ -455,6 +721,7 @@ async fn run_api(
     let whitelisted_tokens_for_aa_cache = Arc::new(RwLock::new(Vec::new()));
     let whitelisted_tokens_for_aa_cache_clone = whitelisted_tokens_for_aa_cache.clone();
     let mut stop_receiver_for_task = stop_receiver.clone();
+    let main_node_client = main_node_client.for_component("aa_tokens_fetcher");
     task_handles.push(task::spawn(async move {
         while !*stop_receiver_for_task.borrow_and_update() {
             match main_node_client.whitelisted_tokens_for_aa().await {
@@ -490,22 +757,70 @@ async fn run_api(
         .await;
 
     let mempool_cache = MempoolCache::new(config.optional.mempool_cache_size);
-    let mempool_cache_update_task = mempool_cache.update_task(
-        connection_pool.clone(),
-        config.optional.mempool_cache_update_interval(),
-    );
-    task_handles.push(tokio::spawn(
-        mempool_cache_update_task.run(stop_receiver.clone()),
-    ));
+    if let Some(main_node_ws_url) = &config.optional.mempool_cache_main_node_ws_url {
+        tracing::info!(
+            "Updating mempool cache based on pending transactions subscription to main node"
+        );
+        let mempool_cache_task =
+            mempool_cache.main_node_subscription_task(main_node_ws_url.clone());
+        task_handles.push(tokio::spawn(mempool_cache_task.run(stop_receiver.clone())));
+    } else {
+        let mempool_cache_update_task = mempool_cache.update_task(
+            connection_pool.clone(),
+            config.optional.mempool_cache_update_interval(),
+        );
+        task_handles.push(tokio::spawn(
+            mempool_cache_update_task.run(stop_receiver.clone()),
+        ));
+    }
 
     // The refresh interval should be several times lower than the pruning removal delay, so that
     // soft-pruning will timely propagate to the API server.
     let pruning_info_refresh_interval = config.optional.pruning_removal_delay() / 5;
+    if config.observability.otlp_endpoint().is_some() && !config.optional.extended_rpc_tracing {
+        tracing::warn!(
+            "OpenTelemetry export is enabled, but extended RPC tracing is disabled; \
+             spans for RPC requests will not be produced. Set `EN_EXTENDED_RPC_TRACING=true` to enable them"
+        );
+    }
+
+    let block_data_archive = if config.experimental.block_data_archive_fallback_enabled {
+        let object_store_config = read_block_data_archive_object_store_config()?;
+        let blob_store = ObjectStoreFactory::new(object_store_config)
+            .create_store()
+            .await;
+        Some(Arc::new(BlockDataArchiveReader::new(
+            blob_store,
+            config.experimental.block_data_archive_cache_capacity,
+        )))
+    } else {
+        None
+    };
+
+    let response_cache_size = config.optional.response_cache_size();
+    let response_cache_redis_url = config.optional.response_cache_redis_url.as_ref();
+    let response_cache = if response_cache_size > 0 || response_cache_redis_url.is_some() {
+        let ttl = config.optional.response_cache_ttl();
+        tracing::info!("Using API response cache with size {response_cache_size}B and TTL {ttl:?}");
+        let mut cache = ResponseCache::new(response_cache_size, ttl);
+        if let Some(redis_url) = response_cache_redis_url {
+            tracing::info!("Sharing API response cache via Redis at {redis_url:?}");
+            cache = cache
+                .with_redis(redis_url, config.required.l2_chain_id)
+                .await
+                .context("failed initializing Redis backend for API response cache")?;
+        }
+        Some(cache)
+    } else {
+        None
+    };
 
     if components.contains(&Component::HttpApi) {
         let mut builder = ApiBuilder::jsonrpsee_backend(config.into(), connection_pool.clone())
             .http(config.required.http_port)
             .with_filter_limit(config.optional.filters_limit)
+            .with_filters_storage(config.optional.filters_storage())
+            .with_cors(config.optional.cors_config())
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
             .with_pruning_info_refresh_interval(pruning_info_refresh_interval)
@@ -514,10 +829,35 @@ async fn run_api(
             .with_sync_state(sync_state.clone())
             .with_mempool_cache(mempool_cache.clone())
             .with_extended_tracing(config.optional.extended_rpc_tracing)
+            .with_method_rate_limits(config.optional.api_rate_limit.methods.clone())
+            .with_method_filter(config.optional.api_method_filter())
             .enable_api_namespaces(config.optional.api_namespaces());
         if let Some(tree_reader) = &tree_reader {
             builder = builder.with_tree_api(tree_reader.clone());
         }
+        if let Some(archive) = &block_data_archive {
+            builder = builder.with_block_data_archive(archive.clone());
+        }
+        if let Some(cache) = &response_cache {
+            builder = builder.with_response_cache(cache.clone());
+        }
+        if let Some(api_config) = &updatable_api_config {
+            builder = builder.with_updatable_config(api_config.clone());
+        }
+        if let Some(limit) = config.optional.api_rate_limit.per_ip_requests_per_second {
+            builder = builder
+                .with_per_ip_requests_per_second_limit(limit)
+                .with_trusted_proxies(config.optional.api_rate_limit.trusted_proxies.clone());
+        }
+        if let Some(sampler) = &slow_calls_sampler {
+            builder = builder.with_slow_calls_sampler(sampler.clone());
+        }
+        if let Some(parallelism) = config.optional.batch_request_parallelism {
+            builder = builder.with_batch_request_parallelism(
+                parallelism,
+                config.optional.batch_request_time_budget(),
+            );
+        }
 
         let http_server_handles = builder
             .build()
@@ -533,6 +873,8 @@ async fn run_api(
         let mut builder = ApiBuilder::jsonrpsee_backend(config.into(), connection_pool.clone())
             .ws(config.required.ws_port)
             .with_filter_limit(config.optional.filters_limit)
+            .with_filters_storage(config.optional.filters_storage())
+            .with_cors(config.optional.cors_config())
             .with_subscriptions_limit(config.optional.subscriptions_limit)
             .with_batch_request_size_limit(config.optional.max_batch_request_size)
             .with_response_body_size_limit(config.optional.max_response_body_size())
@@ -543,10 +885,29 @@ async fn run_api(
             .with_sync_state(sync_state)
             .with_mempool_cache(mempool_cache)
             .with_extended_tracing(config.optional.extended_rpc_tracing)
+            .with_method_rate_limits(config.optional.api_rate_limit.methods.clone())
+            .with_method_filter(config.optional.api_method_filter())
             .enable_api_namespaces(config.optional.api_namespaces());
         if let Some(tree_reader) = tree_reader {
             builder = builder.with_tree_api(tree_reader);
         }
+        if let Some(archive) = block_data_archive {
+            builder = builder.with_block_data_archive(archive);
+        }
+        if let Some(cache) = response_cache {
+            builder = builder.with_response_cache(cache);
+        }
+        if let Some(api_config) = updatable_api_config {
+            builder = builder.with_updatable_config(api_config);
+        }
+        if let Some(limit) = config.optional.api_rate_limit.per_ip_requests_per_second {
+            builder = builder
+                .with_per_ip_requests_per_second_limit(limit)
+                .with_trusted_proxies(config.optional.api_rate_limit.trusted_proxies.clone());
+        }
+        if let Some(sampler) = &slow_calls_sampler {
+            builder = builder.with_slow_calls_sampler(sampler.clone());
+        }
 
         let ws_server_handles = builder
             .build()
@@ -572,11 +933,113 @@ async fn init_tasks(
     app_health: &AppHealthCheck,
     stop_receiver: watch::Receiver<bool>,
     components: &HashSet<Component>,
+    is_tree_node: bool,
 ) -> anyhow::Result<()> {
-    let protocol_version_update_task =
-        EN_METRICS.run_protocol_version_updates(connection_pool.clone(), stop_receiver.clone());
-    task_handles.push(tokio::spawn(protocol_version_update_task));
+    // Slow API calls are only exposed via the admin server, which is run together with the core component.
+    let has_admin_server =
+        components.contains(&Component::Core) && config.optional.pruning_admin_port.is_some();
+    let slow_calls_sampler = config
+        .optional
+        .slow_calls_sample_size
+        .filter(|_| has_admin_server)
+        .map(|sample_size| Arc::new(SlowCallsSampler::new(sample_size)));
+    if config.optional.slow_calls_sample_size.is_some() && slow_calls_sampler.is_none() {
+        tracing::warn!("Slow calls sampling is configured, but the admin server is disabled; sampling is disabled");
+    }
 
+    let (tree_reader, core_sync_state) = init_component_tasks(
+        config,
+        connection_pool.clone(),
+        singleton_pool_builder.clone(),
+        main_node_client.clone(),
+        eth_client,
+        task_handles,
+        app_health,
+        stop_receiver.clone(),
+        components,
+        slow_calls_sampler.clone(),
+    )
+    .await?;
+
+    let sync_state = if let Some(sync_state) = core_sync_state {
+        sync_state
+    } else {
+        let sync_state = SyncState::default();
+        // The sync state is only used by the API servers, so a tree node doesn't need to update it.
+        if !is_tree_node {
+            task_handles.push(tokio::spawn(sync_state.clone().run_updater(
+                connection_pool.clone(),
+                main_node_client.clone(),
+                stop_receiver.clone(),
+            )));
+        }
+        sync_state
+    };
+
+    if connection_pool.replica_url().is_some() {
+        const REPLICA_LAG_CHECK_INTERVAL: Duration = Duration::from_secs(1);
+
+        task_handles.push(tokio::spawn(
+            connection_pool
+                .clone()
+                .run_replica_lag_monitor(REPLICA_LAG_CHECK_INTERVAL, stop_receiver.clone()),
+        ));
+    }
+
+    let remote_config_refresh_interval = config
+        .optional
+        .remote_config_refresh_interval()
+        .filter(|_| !is_tree_node);
+    let updatable_api_config = if let Some(interval) = remote_config_refresh_interval {
+        let refresher = RemoteConfigRefresher::new(main_node_client.clone(), config, interval);
+        let api_config = refresher.api_config();
+        task_handles.push(tokio::spawn(refresher.run(stop_receiver.clone())));
+        Some(api_config)
+    } else {
+        None
+    };
+
+    if components.contains(&Component::HttpApi) || components.contains(&Component::WsApi) {
+        let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
+        run_api(
+            task_handles,
+            config,
+            app_health,
+            connection_pool,
+            stop_receiver.clone(),
+            sync_state,
+            tree_reader,
+            main_node_client,
+            &singleton_pool_builder,
+            fee_params_fetcher.clone(),
+            updatable_api_config,
+            slow_calls_sampler,
+            components,
+        )
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Starts tasks for the non-API components: the Merkle tree, tree data fetcher and core components.
+/// Unlike [`init_tasks()`], doesn't start tasks shared by all components of the node process, so it can be used
+/// to start components after a replica node process is promoted to the leader.
+///
+/// Returns a reader for the Merkle tree and the sync state updated by the core component, if these components are run.
+#[allow(clippy::too_many_arguments)]
+async fn init_component_tasks(
+    config: &ExternalNodeConfig,
+    connection_pool: ConnectionPool<Core>,
+    singleton_pool_builder: ConnectionPoolBuilder<Core>,
+    main_node_client: Box<DynClient<L2>>,
+    eth_client: Box<DynClient<L1>>,
+    task_handles: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+    app_health: &AppHealthCheck,
+    stop_receiver: watch::Receiver<bool>,
+    components: &HashSet<Component>,
+    slow_calls_sampler: Option<Arc<SlowCallsSampler>>,
+) -> anyhow::Result<(Option<Arc<dyn TreeApiClient>>, Option<SyncState>)> {
     // Run the components.
     let tree_pool = singleton_pool_builder
         .build()
@@ -600,6 +1063,7 @@ async fn init_tasks(
                     .tree_component
                     .api_port
                     .context("should contain tree api port")?,
+                grpc_port: config.tree_component.grpc_api_port,
             })
         } else {
             None
@@ -630,48 +1094,23 @@ async fn init_tasks(
     }
 
     let sync_state = if components.contains(&Component::Core) {
-        run_core(
+        let sync_state = run_core(
             config,
-            connection_pool.clone(),
-            main_node_client.clone(),
+            connection_pool,
+            main_node_client,
             eth_client,
             task_handles,
             app_health,
-            stop_receiver.clone(),
+            stop_receiver,
             &singleton_pool_builder,
+            slow_calls_sampler,
         )
-        .await?
+        .await?;
+        Some(sync_state)
     } else {
-        let sync_state = SyncState::default();
-
-        task_handles.push(tokio::spawn(sync_state.clone().run_updater(
-            connection_pool.clone(),
-            main_node_client.clone(),
-            stop_receiver.clone(),
-        )));
-
-        sync_state
+        None
     };
-
-    if components.contains(&Component::HttpApi) || components.contains(&Component::WsApi) {
-        let fee_params_fetcher = Arc::new(MainNodeFeeParamsFetcher::new(main_node_client.clone()));
-        run_api(
-            task_handles,
-            config,
-            app_health,
-            connection_pool,
-            stop_receiver.clone(),
-            sync_state,
-            tree_reader,
-            main_node_client,
-            &singleton_pool_builder,
-            fee_params_fetcher.clone(),
-            components,
-        )
-        .await?;
-    }
-
-    Ok(())
+    Ok((tree_reader, sync_state))
 }
 
 async fn shutdown_components(
@@ -687,6 +1126,20 @@ async fn shutdown_components(
     Ok(())
 }
 
+/// Outputs env variables with canonical names, reporting issues with the original variables to stderr.
+fn migrate_env(env_migration: &EnvMigration, output_path: Option<&Path>) -> anyhow::Result<()> {
+    for issue in env_migration.issues() {
+        eprintln!("warning: {issue}");
+    }
+    let env = env_migration.to_env_file();
+    match output_path {
+        Some(path) => std::fs::write(path, env)
+            .with_context(|| format!("failed writing env file to {}", path.display()))?,
+        None => print!("{env}"),
+    }
+    Ok(())
+}
+
 /// External node for zkSync Era.
 #[derive(Debug, Parser)]
 #[command(author = "Matter Labs", version)]
@@ -698,6 +1151,18 @@ struct Cli {
     /// do not use unless you know what you're doing.
     #[arg(long)]
     enable_consensus: bool,
+    /// Allows switching the node mode (archive / pruned, as specified by `EN_PRUNING_ENABLED`) compared to the mode
+    /// the node previously ran in. Without this flag, the node refuses to start if the mode has changed.
+    #[arg(long)]
+    switch_node_mode: bool,
+    /// Verifies the node storage recovered from a snapshot against the snapshot data without modifying it, and exits.
+    /// If the node runs the Merkle tree, the tree is verified as well. Exits with an error if any discrepancies are found.
+    #[arg(long)]
+    verify_snapshot_recovery: bool,
+    /// Prints env variables configuring the node with legacy names replaced by canonical ones and exits.
+    /// If a path is specified, the variables are written to this file instead.
+    #[arg(long, value_name = "PATH")]
+    migrate_env: Option<Option<PathBuf>>,
 
     /// Comma-separated list of components to launch.
     #[arg(long, default_value = "all")]
@@ -715,6 +1180,14 @@ pub enum Component {
 }
 
 impl Component {
+    fn is_api(self) -> bool {
+        matches!(self, Self::HttpApi | Self::WsApi)
+    }
+
+    fn is_tree(self) -> bool {
+        matches!(self, Self::Tree | Self::TreeApi)
+    }
+
     fn components_from_str(s: &str) -> anyhow::Result<&[Component]> {
         match s {
             "api" => Ok(&[Component::HttpApi, Component::WsApi]),
@@ -724,6 +1197,7 @@ impl Component {
             "tree_api" => Ok(&[Component::TreeApi]),
             "tree_fetcher" => Ok(&[Component::TreeFetcher]),
             "core" => Ok(&[Component::Core]),
+            "tree_node" => Ok(&[Component::Tree, Component::TreeApi]),
             "all" => Ok(&[
                 Component::HttpApi,
                 Component::WsApi,
@@ -736,50 +1210,169 @@ impl Component {
 }
 
 #[derive(Debug, Clone)]
-struct ComponentsToRun(HashSet<Component>);
+struct ComponentsToRun(
+    HashSet<Component>,
+    /// Whether the components are specified with the `tree_node` profile.
+    bool,
+);
+
+impl ComponentsToRun {
+    /// Checks whether the node runs in the tree node profile, i.e., the node only runs the Merkle tree
+    /// and its API against Postgres shared with another node process running core components. The profile
+    /// must be requested explicitly; running tree components without it retains the usual node behavior.
+    fn is_tree_node(&self) -> bool {
+        self.1
+    }
+}
 
 impl FromStr for ComponentsToRun {
     type Err = anyhow::Error;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut has_tree_node_profile = false;
         let components = s
             .split(',')
             .try_fold(HashSet::new(), |mut acc, component_str| {
-                let components = Component::components_from_str(component_str.trim())?;
+                let component_str = component_str.trim();
+                has_tree_node_profile |= component_str == "tree_node";
+                let components = Component::components_from_str(component_str)?;
                 acc.extend(components);
                 Ok::<_, Self::Err>(acc)
             })?;
-        Ok(Self(components))
+        if has_tree_node_profile {
+            anyhow::ensure!(
+                components.iter().all(|component| component.is_tree()),
+                "`tree_node` profile cannot be combined with API or core components"
+            );
+        }
+        Ok(Self(components, has_tree_node_profile))
+    }
+}
+
+/// Validates configuration of a tree node. A tree node doesn't own the shared Postgres, so it must not
+/// initialize or revert node storage, or participate in leader election.
+fn validate_tree_node(opt: &Cli, config: &ExternalNodeConfig) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !opt.revert_pending_l1_batch,
+        "Reverting L1 batches is not supported by the tree node; run it on the node process running core components"
+    );
+    anyhow::ensure!(
+        !opt.enable_consensus,
+        "Consensus cannot be enabled for the tree node since it doesn't run core components"
+    );
+    anyhow::ensure!(
+        !config.experimental.leader_election_enabled,
+        "Leader election is not supported by the tree node"
+    );
+    if opt.components.0.contains(&Component::TreeApi) {
+        anyhow::ensure!(
+            config.tree_component.api_port.is_some(),
+            "Tree API port (`EN_TREE_API_PORT`) must be specified for the tree node running the tree API"
+        );
+    }
+    Ok(())
+}
+
+/// Main node client components issuing opportunistic requests, which are delayed by rate limiting in favor
+/// of sync-critical requests (e.g., fetching blocks).
+const LOW_PRIORITY_MAIN_NODE_COMPONENTS: [&str; 5] = [
+    "main_node_health_check",
+    "commitment_verifier",
+    "fee_params_fetcher",
+    "aa_tokens_fetcher",
+    "remote_config_refresher",
+];
+
+/// Builds a client for the main node. If multiple main node URLs are configured, the returned client
+/// will fail over between them.
+fn build_main_node_client(config: &ExternalNodeConfig<()>) -> anyhow::Result<Box<DynClient<L2>>> {
+    let main_node_urls = &config.required.main_node_url;
+    anyhow::ensure!(
+        !main_node_urls.is_empty(),
+        "At least one main node URL must be specified"
+    );
+    tracing::info!("Main node URLs are: {main_node_urls:?}");
+
+    let upstreams = main_node_urls.iter().enumerate().map(|(i, url)| {
+        let client = Client::http(url.clone())
+            .with_context(|| format!("failed creating JSON-RPC client for main node #{i}"))?
+            .for_network(config.required.l2_chain_id.into())
+            .with_allowed_requests_per_second(config.optional.main_node_rate_limit_rps)
+            .with_adaptive_rate_limit(config.optional.main_node_adaptive_rate_limit)
+            .with_low_priority_components(LOW_PRIORITY_MAIN_NODE_COMPONENTS)
+            .build();
+        Ok(Box::new(client) as Box<DynClient<L2>>)
+    });
+    let mut upstreams = upstreams.collect::<anyhow::Result<Vec<_>>>()?;
+    if upstreams.len() == 1 {
+        return Ok(upstreams.pop().unwrap());
     }
+
+    let client = FailoverClient::new(upstreams)
+        .with_max_consecutive_errors(config.optional.main_node_failover_max_errors);
+    Ok(Box::new(client))
+}
+
+/// Builds a client for L1. If multiple L1 provider URLs are configured, the returned client
+/// will cross-check critical reads among them.
+fn build_eth_client(config: &ExternalNodeConfig<()>) -> anyhow::Result<Box<DynClient<L1>>> {
+    let eth_client_urls = &config.required.eth_client_url;
+    anyhow::ensure!(
+        !eth_client_urls.is_empty(),
+        "At least one Ethereum node URL must be specified"
+    );
+
+    let upstreams = eth_client_urls.iter().enumerate().map(|(i, url)| {
+        let client = Client::http(url.clone())
+            .with_context(|| format!("failed creating JSON-RPC client for Ethereum node #{i}"))?
+            .for_network(config.required.l1_chain_id.into())
+            .build();
+        Ok(Box::new(client) as Box<DynClient<L1>>)
+    });
+    let mut upstreams = upstreams.collect::<anyhow::Result<Vec<_>>>()?;
+    if upstreams.len() == 1 {
+        return Ok(upstreams.pop().unwrap());
+    }
+
+    let upstream_count = upstreams.len();
+    let mut client = QuorumClient::new(upstreams);
+    if let Some(quorum) = config.optional.eth_client_quorum {
+        anyhow::ensure!(
+            quorum.get() <= upstream_count,
+            "L1 client quorum ({quorum}) exceeds the number of Ethereum node URLs ({upstream_count})"
+        );
+        client = client.with_quorum(quorum);
+    }
+    tracing::info!(
+        "Using {upstream_count} L1 providers with cross-checking critical reads: {client:?}"
+    );
+    Ok(Box::new(client))
 }
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     // Initial setup.
     let opt = Cli::parse();
+    let env_migration = EnvMigration::from_env();
+    if let Some(output_path) = &opt.migrate_env {
+        return migrate_env(&env_migration, output_path.as_deref());
+    }
 
     let mut config = ExternalNodeConfig::new().context("Failed to load node configuration")?;
     if !opt.enable_consensus {
         config.consensus = None;
     }
     let _guard = config.observability.build_observability()?;
+    if let Some(handle) = vlog::log_directives_handle() {
+        let path = config.observability.log_directives_path.clone();
+        tokio::spawn(reload_log_directives_on_signal(handle, path));
+    }
+    env_migration.report();
 
     // Build L1 and L2 clients.
-    let main_node_url = &config.required.main_node_url;
-    tracing::info!("Main node URL is: {main_node_url:?}");
-    let main_node_client = Client::http(main_node_url.clone())
-        .context("failed creating JSON-RPC client for main node")?
-        .for_network(config.required.l2_chain_id.into())
-        .with_allowed_requests_per_second(config.optional.main_node_rate_limit_rps)
-        .build();
-    let main_node_client = Box::new(main_node_client) as Box<DynClient<L2>>;
-
-    let eth_client_url = &config.required.eth_client_url;
-    let eth_client = Client::http(eth_client_url.clone())
-        .context("failed creating JSON-RPC client for Ethereum")?
-        .for_network(config.required.l1_chain_id.into())
-        .build();
-    let eth_client = Box::new(eth_client);
+    let main_node_client = build_main_node_client(&config)?;
+
+    let eth_client = build_eth_client(&config)?;
 
     let config = config
         .fetch_remote(main_node_client.as_ref())
@@ -795,11 +1388,25 @@ async fn main() -> anyhow::Result<()> {
     RUST_METRICS.initialize();
     EN_METRICS.observe_config(&config);
 
+    let slow_query_circuit_breaker = config
+        .optional
+        .database_slow_query_circuit_breaker_ratio
+        .map(|ratio| SlowQueryCircuitBreakerConfig {
+            slow_query_ratio: ratio,
+            min_queries: config
+                .optional
+                .database_slow_query_circuit_breaker_min_queries,
+            window: config.optional.slow_query_circuit_breaker_window(),
+        });
     let singleton_pool_builder = ConnectionPool::singleton(config.postgres.database_url());
     let connection_pool = ConnectionPool::<Core>::builder(
         config.postgres.database_url(),
         config.postgres.max_connections,
     )
+    .set_statement_timeout(config.optional.statement_timeout())
+    .set_replica_url(config.postgres.database_replica_url())
+    .set_max_replica_lag(config.optional.replica_max_lag())
+    .set_slow_query_circuit_breaker(slow_query_circuit_breaker)
     .build()
     .await
     .context("failed to build a connection_pool")?;
@@ -816,6 +1423,42 @@ async fn main() -> anyhow::Result<()> {
     .await
 }
 
+/// Changes log directives each time the node receives `SIGUSR1`. Directives are read from the file at `path`;
+/// if the file is not configured, missing or empty, directives are reset to the initial ones.
+async fn reload_log_directives_on_signal(
+    handle: vlog::LogDirectivesHandle,
+    path: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let mut signals = signal::unix::signal(signal::unix::SignalKind::user_defined1())
+        .context("failed installing SIGUSR1 handler")?;
+    while signals.recv().await.is_some() {
+        let directives = match &path {
+            Some(path) => match tokio::fs::read_to_string(path).await {
+                Ok(directives) => directives,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+                Err(err) => {
+                    tracing::warn!("Failed reading log directives from {path:?}: {err}");
+                    continue;
+                }
+            },
+            None => String::new(),
+        };
+        let directives = directives.trim();
+
+        let result = if directives.is_empty() {
+            tracing::info!("Received SIGUSR1, resetting log directives");
+            handle.reset()
+        } else {
+            tracing::info!("Received SIGUSR1, applying log directives `{directives}`");
+            handle.set(directives)
+        };
+        if let Err(err) = result {
+            tracing::warn!("Failed changing log directives: {err}");
+        }
+    }
+    Ok(())
+}
+
 /// Environment for the node encapsulating its interactions. Used in EN tests to mock signal sending etc.
 trait NodeEnvironment {
     /// Sets the SIGINT handler, returning a future that will resolve when a signal is sent.
@@ -846,17 +1489,66 @@ async fn run_node(
 ) -> anyhow::Result<()> {
     tracing::warn!("The external node is in the alpha phase, and should be used with caution.");
     tracing::info!("Started the external node");
+    let is_tree_node = opt.components.is_tree_node();
+    if is_tree_node {
+        validate_tree_node(opt, config)?;
+        tracing::info!(
+            "Running the node in the tree node profile with components {:?}; \
+             the node will not initialize or revert node storage, and expects another node process sharing Postgres \
+             to run core components",
+            opt.components.0
+        );
+    }
     let (stop_sender, mut stop_receiver) = watch::channel(false);
     let stop_sender = Arc::new(stop_sender);
 
-    let app_health = Arc::new(AppHealthCheck::new(
+    let app_health = AppHealthCheck::new(
         config.optional.healthcheck_slow_time_limit(),
         config.optional.healthcheck_hard_time_limit(),
-    ));
-    app_health.insert_custom_component(Arc::new(MainNodeHealthCheck::from(
-        main_node_client.clone(),
-    )))?;
-    app_health.insert_custom_component(Arc::new(EthClientHealthCheck::from(eth_client.clone())))?;
+    )
+    .with_component_overrides(config.optional.healthcheck_component_overrides());
+    let app_health = Arc::new(app_health);
+    // The tree node doesn't interact with the main node or L1, so its health doesn't depend on them.
+    let mut dependency_probes = vec![];
+    if !is_tree_node {
+        let optional = &config.optional;
+        let main_node_probe: Box<dyn DependencyProbe> =
+            Box::new(MainNodeProbe::from(main_node_client.clone()));
+        dependency_probes.push((main_node_probe, optional.main_node_probe_latency_budget()));
+        let eth_client_probe: Box<dyn DependencyProbe> =
+            Box::new(EthClientProbe::from(eth_client.clone()));
+        dependency_probes.push((eth_client_probe, optional.eth_client_probe_latency_budget()));
+
+        let experimental = &config.experimental;
+        if experimental.block_data_archive_export_enabled
+            || experimental.block_data_archive_fallback_enabled
+        {
+            let object_store_config = read_block_data_archive_object_store_config()?;
+            let blob_store = ObjectStoreFactory::new(object_store_config)
+                .create_store()
+                .await;
+            let object_store_probe: Box<dyn DependencyProbe> =
+                Box::new(BlockDataArchiveProbe::from(blob_store));
+            dependency_probes.push((
+                object_store_probe,
+                optional.object_store_probe_latency_budget(),
+            ));
+        }
+    }
+    let dependency_probes: Vec<_> = dependency_probes
+        .into_iter()
+        .map(|(probe, latency_budget)| {
+            DependencyProbeTask::new(
+                probe,
+                config.optional.dependency_probe_interval(),
+                config.optional.dependency_probe_timeout(),
+                latency_budget,
+            )
+        })
+        .collect();
+    for probe in &dependency_probes {
+        app_health.insert_component(probe.health_check())?;
+    }
     app_health.insert_custom_component(Arc::new(ConnectionPoolHealthCheck::new(
         connection_pool.clone(),
     )))?;
@@ -866,6 +1558,19 @@ async fn run_node(
         ([0, 0, 0, 0], config.required.healthcheck_port).into(),
         app_health.clone(),
     );
+    // Verification is performed before any other node logic (e.g., storage initialization or leader election),
+    // so that it doesn't write to Postgres and can be run on any node process, including replicas.
+    if opt.verify_snapshot_recovery {
+        let merkle_tree_path = opt
+            .components
+            .0
+            .contains(&Component::Tree)
+            .then(|| Path::new(&config.required.merkle_tree_path));
+        let result =
+            verify_snapshot_recovery(connection_pool, main_node_client, merkle_tree_path).await;
+        healthcheck_handle.stop().await;
+        return result;
+    }
     // Start exporting metrics at the very start so that e.g., snapshot recovery metrics are timely reported.
     let prometheus_task = if let Some(prometheus) = config.observability.prometheus() {
         tracing::info!("Starting Prometheus exporter with configuration: {prometheus:?}");
@@ -899,40 +1604,89 @@ async fn run_node(
         Ok(())
     });
 
-    let validate_chain_ids_task = ValidateChainIdsTask::new(
-        config.required.l1_chain_id,
-        config.required.l2_chain_id,
-        eth_client.clone(),
-        main_node_client.clone(),
+    let mut task_handles = vec![metrics_task];
+    task_handles.extend(prometheus_task);
+    task_handles.extend(
+        dependency_probes
+            .into_iter()
+            .map(|probe| tokio::spawn(probe.run(stop_receiver.clone()))),
     );
-    let validate_chain_ids_task = tokio::spawn(validate_chain_ids_task.run(stop_receiver.clone()));
-
-    let version_sync_task_pool = connection_pool.clone();
-    let version_sync_task_main_node_client = main_node_client.clone();
-    let mut stop_receiver_for_version_sync = stop_receiver.clone();
-    let version_sync_task = tokio::spawn(async move {
-        version_sync_task::sync_versions(
-            version_sync_task_pool,
-            version_sync_task_main_node_client,
-        )
-        .await?;
+    if !is_tree_node {
+        let validate_chain_ids_task = ValidateChainIdsTask::new(
+            config.required.l1_chain_id,
+            config.required.l2_chain_id,
+            eth_client.clone(),
+            main_node_client.clone(),
+        );
+        task_handles.push(tokio::spawn(
+            validate_chain_ids_task.run(stop_receiver.clone()),
+        ));
+
+        let version_sync_task_pool = connection_pool.clone();
+        let version_sync_task_main_node_client = main_node_client.clone();
+        let mut stop_receiver_for_version_sync = stop_receiver.clone();
+        task_handles.push(tokio::spawn(async move {
+            version_sync_task::sync_versions(
+                version_sync_task_pool,
+                version_sync_task_main_node_client,
+            )
+            .await?;
 
-        stop_receiver_for_version_sync.changed().await.ok();
-        Ok(())
-    });
-    let mut task_handles = vec![metrics_task, validate_chain_ids_task, version_sync_task];
-    task_handles.extend(prometheus_task);
+            stop_receiver_for_version_sync.changed().await.ok();
+            Ok(())
+        }));
+    }
+    let protocol_version_update_task =
+        EN_METRICS.run_protocol_version_updates(connection_pool.clone(), stop_receiver.clone());
+    task_handles.push(tokio::spawn(protocol_version_update_task));
 
-    // Make sure that the node storage is initialized either via genesis or snapshot recovery.
-    ensure_storage_initialized(
-        connection_pool.clone(),
-        main_node_client.clone(),
-        &app_health,
-        config.required.l2_chain_id,
-        config.optional.snapshots_recovery_enabled,
-    )
-    .await?;
-    let sigint_receiver = env.setup_sigint_handler();
+    let mut replica_role_receiver = None;
+    if config.experimental.leader_election_enabled {
+        tracing::warn!("Leader election among node processes sharing Postgres is enabled. This is an experimental feature; use at your own risk");
+
+        let election_pool = singleton_pool_builder
+            .build()
+            .await
+            .context("failed to build a connection pool for leader election")?;
+        let election = LeaderElection::new(
+            election_pool,
+            config.experimental.leader_election_interval(),
+        );
+        let mut role_receiver = election.subscribe();
+        task_handles.push(tokio::spawn(election.run(stop_receiver.clone())));
+        let role = *role_receiver
+            .wait_for(Option::is_some)
+            .await
+            .context("leader election stopped unexpectedly")?;
+        if role == Some(ProcessRole::Replica) {
+            replica_role_receiver = Some(role_receiver);
+        }
+    }
+
+    let sigint_receiver;
+    if replica_role_receiver.is_some() || is_tree_node {
+        sigint_receiver = env.setup_sigint_handler();
+        // Replicas and tree nodes cannot initialize storage themselves, so they wait for the leader
+        // / core node process to do it.
+        wait_for_storage_initialization(&connection_pool, stop_receiver.clone()).await?;
+    } else {
+        // Make sure that the node storage is initialized either via genesis or snapshot recovery.
+        ensure_storage_initialized(
+            connection_pool.clone(),
+            main_node_client.clone(),
+            &app_health,
+            config.required.l2_chain_id,
+            config.optional.snapshots_recovery_enabled,
+        )
+        .await?;
+        validate_node_mode(
+            &connection_pool,
+            config.optional.pruning_enabled,
+            opt.switch_node_mode,
+        )
+        .await?;
+        sigint_receiver = env.setup_sigint_handler();
+    }
     // Spawn reacting to signals in a separate task so that the node is responsive to signals right away
     // (e.g., during the initial reorg detection).
     tokio::spawn({
@@ -944,6 +1698,109 @@ async fn run_node(
         }
     });
 
+    if let Some(role_receiver) = replica_role_receiver {
+        // Only run API components until this node process becomes the leader.
+        let (api_components, leader_components): (HashSet<_>, HashSet<_>) = opt
+            .components
+            .0
+            .iter()
+            .copied()
+            .partition(|component| component.is_api());
+        init_tasks(
+            config,
+            connection_pool.clone(),
+            singleton_pool_builder.clone(),
+            main_node_client.clone(),
+            eth_client.clone(),
+            &mut task_handles,
+            &app_health,
+            stop_receiver.clone(),
+            &api_components,
+            false,
+        )
+        .await
+        .context("init_tasks")?;
+        env.set_app_health(app_health.clone());
+
+        let promotion = promote_replica(
+            role_receiver,
+            config,
+            opt,
+            connection_pool,
+            singleton_pool_builder,
+            main_node_client,
+            eth_client,
+            &app_health,
+            stop_receiver.clone(),
+            &leader_components,
+        );
+        return wait_for_tasks(
+            ManagedTasks::new(task_handles),
+            promotion,
+            stop_sender,
+            stop_receiver,
+            healthcheck_handle,
+        )
+        .await;
+    }
+
+    // Reorgs are detected and reverted by the node process running core components. The Merkle tree
+    // truncates itself on restart if it's ahead of Postgres, so the tree node doesn't need to handle reorgs.
+    if !is_tree_node
+        && !detect_reorg_and_revert(
+            config,
+            opt.revert_pending_l1_batch,
+            &connection_pool,
+            main_node_client.clone(),
+            &app_health,
+            stop_receiver.clone(),
+            &mut task_handles,
+        )
+        .await?
+    {
+        healthcheck_handle.stop().await;
+        return Ok(());
+    }
+
+    init_tasks(
+        config,
+        connection_pool,
+        singleton_pool_builder,
+        main_node_client,
+        eth_client,
+        &mut task_handles,
+        &app_health,
+        stop_receiver.clone(),
+        &opt.components.0,
+        is_tree_node,
+    )
+    .await
+    .context("init_tasks")?;
+
+    env.set_app_health(app_health);
+
+    let tasks = ManagedTasks::new(task_handles);
+    wait_for_tasks(
+        tasks,
+        future::pending(),
+        stop_sender,
+        stop_receiver,
+        healthcheck_handle,
+    )
+    .await
+}
+
+/// Detects a reorg compared to the main node on node start, reverts node storage if necessary, and starts
+/// the reorg detector. Returns `false` if a stop signal was received during the initial reorg detection.
+async fn detect_reorg_and_revert(
+    config: &ExternalNodeConfig,
+    revert_pending_l1_batch: bool,
+    connection_pool: &ConnectionPool<Core>,
+    main_node_client: Box<DynClient<L2>>,
+    app_health: &AppHealthCheck,
+    stop_receiver: watch::Receiver<bool>,
+    task_handles: &mut Vec<JoinHandle<anyhow::Result<()>>>,
+) -> anyhow::Result<bool> {
     // Revert the storage if needed.
     let mut reverter = BlockReverter::new(NodeRole::External, connection_pool.clone());
     // Reverting executed batches is more-or-less safe for external nodes.
@@ -953,7 +1810,7 @@ async fn run_node(
         .enable_rolling_back_merkle_tree(config.required.merkle_tree_path.clone())
         .enable_rolling_back_state_keeper_cache(config.required.state_cache_path.clone());
 
-    let mut reorg_detector = ReorgDetector::new(main_node_client.clone(), connection_pool.clone());
+    let mut reorg_detector = ReorgDetector::new(main_node_client, connection_pool.clone());
     // We're checking for the reorg in the beginning because we expect that if reorg is detected during
     // the node lifecycle, the node will exit the same way as it does with any other critical error,
     // and would restart. Then, on the 2nd launch reorg would be detected here, then processed and the node
@@ -961,20 +1818,20 @@ async fn run_node(
     match reorg_detector.run_once(stop_receiver.clone()).await {
         Ok(()) if *stop_receiver.borrow() => {
             tracing::info!("Stop signal received during initial reorg detection; shutting down");
-            healthcheck_handle.stop().await;
-            return Ok(());
+            return Ok(false);
         }
         Ok(()) => {
             tracing::info!("Successfully checked no reorg compared to the main node");
         }
         Err(zksync_reorg_detector::Error::ReorgDetected(last_correct_l1_batch)) => {
+            check_auto_rollback(config, connection_pool, last_correct_l1_batch).await?;
             tracing::info!("Reverting to l1 batch number {last_correct_l1_batch}");
             reverter.roll_back(last_correct_l1_batch).await?;
             tracing::info!("Revert successfully completed");
         }
         Err(err) => return Err(err).context("reorg_detector.check_consistency()"),
     }
-    if opt.revert_pending_l1_batch {
+    if revert_pending_l1_batch {
         tracing::info!("Reverting pending L1 batch");
         let mut connection = connection_pool.connection().await?;
         let sealed_l1_batch_number = connection
@@ -990,39 +1847,145 @@ async fn run_node(
     }
 
     app_health.insert_component(reorg_detector.health_check().clone())?;
-    task_handles.push(tokio::spawn({
-        let stop = stop_receiver.clone();
-        async move {
-            reorg_detector
-                .run(stop)
-                .await
-                .context("reorg_detector.run()")
-        }
+    task_handles.push(tokio::spawn(async move {
+        reorg_detector
+            .run(stop_receiver)
+            .await
+            .context("reorg_detector.run()")
     }));
+    Ok(true)
+}
 
-    init_tasks(
+/// Checks whether the node state can be automatically rolled back to `last_correct_l1_batch` after a detected reorg.
+async fn check_auto_rollback(
+    config: &ExternalNodeConfig,
+    connection_pool: &ConnectionPool<Core>,
+    last_correct_l1_batch: L1BatchNumber,
+) -> anyhow::Result<()> {
+    let mut connection = connection_pool.connection().await?;
+    let sealed_l1_batch_number = connection
+        .blocks_dal()
+        .get_sealed_l1_batch_number()
+        .await?
+        .unwrap_or(last_correct_l1_batch);
+    drop(connection);
+    let rollback_depth = sealed_l1_batch_number
+        .0
+        .saturating_sub(last_correct_l1_batch.0);
+
+    if config.optional.auto_rollback_dry_run {
+        anyhow::bail!(
+            "Reorg detected; rolling back would remove {rollback_depth} L1 batch(es) \
+             #{}..=#{sealed_l1_batch_number}. Not rolling back since dry-run mode is enabled",
+            last_correct_l1_batch + 1
+        );
+    }
+    if let Some(max_batches) = config.optional.max_auto_rollback_batches {
+        anyhow::ensure!(
+            rollback_depth <= max_batches,
+            "Reorg detected, but rolling back to L1 batch #{last_correct_l1_batch} would remove {rollback_depth} \
+             L1 batches, which exceeds the configured limit ({max_batches}). Roll back the node state manually \
+             or increase the limit"
+        );
+    }
+    Ok(())
+}
+
+/// Waits until this replica node process becomes the leader, and then starts the remaining (non-API) components.
+/// Returns handles for the started tasks, or `None` if the node was stopped before it became the leader.
+#[allow(clippy::too_many_arguments)]
+async fn promote_replica(
+    mut role_receiver: watch::Receiver<Option<ProcessRole>>,
+    config: &ExternalNodeConfig,
+    opt: &Cli,
+    connection_pool: ConnectionPool<Core>,
+    singleton_pool_builder: ConnectionPoolBuilder<Core>,
+    main_node_client: Box<DynClient<L2>>,
+    eth_client: Box<DynClient<L1>>,
+    app_health: &AppHealthCheck,
+    stop_receiver: watch::Receiver<bool>,
+    components: &HashSet<Component>,
+) -> anyhow::Result<Option<Vec<JoinHandle<anyhow::Result<()>>>>> {
+    let is_leader = role_receiver
+        .wait_for(|role| *role == Some(ProcessRole::Leader))
+        .await
+        .is_ok();
+    if !is_leader {
+        // Leader election was stopped, which happens on node shutdown.
+        return Ok(None);
+    }
+    tracing::info!("Replica node process became the leader; starting components {components:?}");
+
+    validate_node_mode(
+        &connection_pool,
+        config.optional.pruning_enabled,
+        opt.switch_node_mode,
+    )
+    .await?;
+    let mut task_handles = vec![];
+    if !detect_reorg_and_revert(
+        config,
+        false,
+        &connection_pool,
+        main_node_client.clone(),
+        app_health,
+        stop_receiver.clone(),
+        &mut task_handles,
+    )
+    .await?
+    {
+        return Ok(None);
+    }
+    // Tasks shared by all components (e.g., the sync state updater and remote config refresher) were already started
+    // together with the API. The API servers were started without the admin server, so slow calls aren't sampled.
+    init_component_tasks(
         config,
         connection_pool,
         singleton_pool_builder,
         main_node_client,
         eth_client,
         &mut task_handles,
-        &app_health,
-        stop_receiver.clone(),
-        &opt.components.0,
+        app_health,
+        stop_receiver,
+        components,
+        None,
     )
     .await
-    .context("init_tasks")?;
-
-    env.set_app_health(app_health);
-
-    let mut tasks = ManagedTasks::new(task_handles);
-    tokio::select! {
-        // We don't want to log unnecessary warnings in `tasks.wait_single()` if we have received a stop signal.
-        biased;
+    .context("init_component_tasks")?;
+    Ok(Some(task_handles))
+}
 
-        _ = stop_receiver.changed() => {},
-        () = tasks.wait_single() => {},
+/// Waits until a task exits or a stop signal is received, and then shuts the node down. `extra_tasks` can produce
+/// additional tasks to manage after the node has started (e.g., ones started after a replica is promoted to the leader).
+async fn wait_for_tasks(
+    mut tasks: ManagedTasks,
+    extra_tasks: impl Future<Output = anyhow::Result<Option<Vec<JoinHandle<anyhow::Result<()>>>>>>,
+    stop_sender: Arc<watch::Sender<bool>>,
+    mut stop_receiver: watch::Receiver<bool>,
+    healthcheck_handle: HealthCheckHandle,
+) -> anyhow::Result<()> {
+    tokio::pin!(extra_tasks);
+    let mut extra_tasks_started = false;
+    let mut extra_tasks_result = Ok(());
+    loop {
+        tokio::select! {
+            // We don't want to log unnecessary warnings in `tasks.wait_single()` if we have received a stop signal.
+            biased;
+
+            _ = stop_receiver.changed() => break,
+            () = tasks.wait_single() => break,
+            res = &mut extra_tasks, if !extra_tasks_started => {
+                extra_tasks_started = true;
+                match res {
+                    Ok(Some(task_handles)) => tasks.extend(task_handles),
+                    Ok(None) => { /* the node is stopping */ }
+                    Err(err) => {
+                        extra_tasks_result = Err(err);
+                        break;
+                    }
+                }
+            }
+        }
     }
 
     // Reaching this point means that either some actor exited unexpectedly or we received a stop signal.
@@ -1030,5 +1993,5 @@ async fn run_node(
     stop_sender.send_replace(true);
     shutdown_components(tasks, healthcheck_handle).await?;
     tracing::info!("Stopped");
-    Ok(())
+    extra_tasks_result
 }
@@ -1,7 +1,10 @@
 use std::time::Duration;
 
 use tokio::sync::watch;
-use vise::{EncodeLabelSet, Gauge, Info, Metrics};
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Gauge, Histogram, Info, LabeledFamily,
+    Metrics,
+};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 
 use crate::{
@@ -26,6 +29,15 @@ pub(crate) struct ExternalNodeMetrics {
     info: Info<ExternalNodeInfo>,
     /// Current protocol version.
     protocol_version: Gauge<u64>,
+    /// Number of L1 batches with the locally computed commitment differing from the main node.
+    pub commitment_mismatches: Counter,
+    /// Last L1 batch with the locally computed commitment verified against the main node.
+    pub last_verified_commitment_batch: Gauge<u64>,
+    /// Number of times the main node returned a changed value for a config value that is not expected to change.
+    #[metrics(labels = ["value"])]
+    pub remote_config_drift: LabeledFamily<&'static str, Counter>,
+    /// Number of times config values updatable at runtime (e.g., bridge addresses) were changed by the main node.
+    pub remote_config_updates: Counter,
 }
 
 impl ExternalNodeMetrics {
@@ -75,6 +87,34 @@ impl ExternalNodeMetrics {
 #[vise::register]
 pub(crate) static EN_METRICS: vise::Global<ExternalNodeMetrics> = vise::Global::new();
 
+/// Outcome of a probe of an external dependency.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(crate) enum DependencyProbeOutcome {
+    /// Dependency has responded within the latency budget.
+    Ok,
+    /// Dependency has responded, but exceeded the latency budget.
+    Slow,
+    /// Dependency has returned an error.
+    Failed,
+    /// Dependency hasn't responded within the probe timeout.
+    TimedOut,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_dependency")]
+pub(crate) struct DependencyMetrics {
+    /// Latency of successful probes of external dependencies (the main node, L1 etc.).
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["dependency"])]
+    pub probe_latency: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Number of probes of external dependencies grouped by the outcome.
+    #[metrics(labels = ["dependency", "outcome"])]
+    pub probes: LabeledFamily<(&'static str, DependencyProbeOutcome), Counter, 2>,
+}
+
+#[vise::register]
+pub(crate) static DEPENDENCY_METRICS: vise::Global<DependencyMetrics> = vise::Global::new();
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "rust")]
 pub(crate) struct RustMetrics {
@@ -2,6 +2,7 @@
 
 use assert_matches::assert_matches;
 use test_casing::test_casing;
+use zksync_dal::pruning_dal::NodeMode;
 use zksync_eth_client::clients::MockEthereum;
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_types::{
@@ -83,9 +84,13 @@ struct TestEnvironmentHandles {
 
 // The returned components have the fully implemented health check life cycle (i.e., signal their shutdown).
 fn expected_health_components(components: &ComponentsToRun) -> Vec<&'static str> {
-    let mut output = vec!["reorg_detector"];
+    let mut output = vec![];
+    // Reorgs are not detected by the tree node, and it doesn't depend on the main node or L1.
+    if !components.is_tree_node() {
+        output.extend(["reorg_detector", "main_node_http_rpc", "ethereum_http_rpc"]);
+    }
     if components.0.contains(&Component::Core) {
-        output.extend(["consistency_checker", "commitment_generator"]);
+        output.extend(["consistency_checker", "commitment_generator", "consensus"]);
     }
     if components.0.contains(&Component::Tree) {
         output.push("tree");
@@ -128,7 +133,7 @@ fn mock_eth_client(diamond_proxy_addr: Address) -> MockClient<L1> {
     mock.build().into_client()
 }
 
-#[test_casing(5, ["all", "core", "api", "tree", "tree,tree_api"])]
+#[test_casing(6, ["all", "core", "api", "tree", "tree,tree_api", "tree_node"])]
 #[tokio::test]
 #[tracing::instrument] // Add args to the test logs
 async fn external_node_basics(components_str: &'static str) {
@@ -156,6 +161,8 @@ async fn external_node_basics(components_str: &'static str) {
     let opt = Cli {
         revert_pending_l1_batch: false,
         enable_consensus: false,
+        switch_node_mode: false,
+        verify_snapshot_recovery: false,
         components,
     };
     let mut config = ExternalNodeConfig::mock(&temp_dir, &connection_pool);
@@ -174,6 +181,7 @@ async fn external_node_basics(components_str: &'static str) {
             Ok(api::L1BatchDetails {
                 number: L1BatchNumber(0),
                 base: block_details_base(genesis_params.root_hash),
+                commitment: None,
             })
         })
         .method("eth_blockNumber", || Ok(U64::from(0)))
@@ -249,6 +257,29 @@ async fn external_node_basics(components_str: &'static str) {
     }
 }
 
+#[test]
+fn parsing_tree_node_components() {
+    let components: ComponentsToRun = "tree_node".parse().unwrap();
+    assert_eq!(
+        components.0,
+        HashSet::from([Component::Tree, Component::TreeApi])
+    );
+    assert!(components.is_tree_node());
+    // Tree components without the explicit profile run with the usual node behavior.
+    for components_str in ["tree", "tree,tree_api", "tree,api"] {
+        let components: ComponentsToRun = components_str.parse().unwrap();
+        assert!(!components.is_tree_node(), "{components_str}");
+    }
+
+    for components_str in ["tree_node,api", "tree_node,core", "all,tree_node"] {
+        let err = components_str.parse::<ComponentsToRun>().unwrap_err();
+        assert!(
+            err.to_string().contains("`tree_node` profile"),
+            "{components_str}: {err}"
+        );
+    }
+}
+
 #[tokio::test]
 async fn node_reacts_to_stop_signal_during_initial_reorg_detection() {
     let _guard = vlog::ObservabilityBuilder::new().build(); // Enable logging to simplify debugging
@@ -265,6 +296,8 @@ async fn node_reacts_to_stop_signal_during_initial_reorg_detection() {
     let opt = Cli {
         revert_pending_l1_batch: false,
         enable_consensus: false,
+        switch_node_mode: false,
+        verify_snapshot_recovery: false,
         components: "core".parse().unwrap(),
     };
     let mut config = ExternalNodeConfig::mock(&temp_dir, &connection_pool);
@@ -310,3 +343,34 @@ async fn node_reacts_to_stop_signal_during_initial_reorg_detection() {
     env_handles.sigint_sender.send(()).unwrap();
     node_handle.await.unwrap().unwrap();
 }
+
+#[tokio::test]
+async fn validating_node_mode() {
+    let connection_pool = ConnectionPool::test_pool().await;
+
+    // The mode is persisted on the first run.
+    validate_node_mode(&connection_pool, false, false)
+        .await
+        .unwrap();
+    let mut storage = connection_pool.connection().await.unwrap();
+    let mode = storage.pruning_dal().get_node_mode().await.unwrap();
+    assert_eq!(mode, Some(NodeMode::Archive));
+
+    let err = validate_node_mode(&connection_pool, true, false)
+        .await
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("--switch-node-mode"), "{err}");
+    let mode = storage.pruning_dal().get_node_mode().await.unwrap();
+    assert_eq!(mode, Some(NodeMode::Archive));
+
+    validate_node_mode(&connection_pool, true, true)
+        .await
+        .unwrap();
+    let mode = storage.pruning_dal().get_node_mode().await.unwrap();
+    assert_eq!(mode, Some(NodeMode::Pruned));
+    // Restarting the node with the same configuration doesn't require the flag.
+    validate_node_mode(&connection_pool, true, false)
+        .await
+        .unwrap();
+}
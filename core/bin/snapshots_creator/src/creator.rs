@@ -56,17 +56,33 @@ impl SnapshotProgress {
     }
 }
 
-/// Creator of a single storage snapshot.
+/// Creator of storage snapshots. Each [run](Self::run()) creates (or resumes creating) a single snapshot.
 #[derive(Debug)]
-pub(crate) struct SnapshotCreator {
-    pub blob_store: Arc<dyn ObjectStore>,
-    pub master_pool: ConnectionPool<Core>,
-    pub replica_pool: ConnectionPool<Core>,
+pub struct SnapshotCreator {
+    pub(crate) blob_store: Arc<dyn ObjectStore>,
+    pub(crate) master_pool: ConnectionPool<Core>,
+    pub(crate) replica_pool: ConnectionPool<Core>,
     #[cfg(test)]
-    pub event_listener: Box<dyn HandleEvent>,
+    pub(crate) event_listener: Box<dyn HandleEvent>,
 }
 
 impl SnapshotCreator {
+    /// Creates a snapshot creator. `master_pool` is used to persist snapshot metadata, and `replica_pool`
+    /// to read snapshot data; these may point to the same database.
+    pub fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        master_pool: ConnectionPool<Core>,
+        replica_pool: ConnectionPool<Core>,
+    ) -> Self {
+        Self {
+            blob_store,
+            master_pool,
+            replica_pool,
+            #[cfg(test)]
+            event_listener: Box::new(()),
+        }
+    }
+
     async fn connect_to_replica(&self) -> DalResult<Connection<'_, Core>> {
         self.replica_pool
             .connection_tagged("snapshots_creator")
@@ -111,9 +127,9 @@ impl SnapshotCreator {
             l1_batch_number,
             chunk_id,
         };
-        let filename = self
+        let (filename, checksum) = self
             .blob_store
-            .put(key, &storage_logs_chunk)
+            .put_with_checksum(key, &storage_logs_chunk)
             .await
             .context("Error storing storage logs chunk in blob store")?;
         let output_filepath_prefix = self
@@ -128,7 +144,12 @@ impl SnapshotCreator {
             .await?;
         master_conn
             .snapshots_dal()
-            .add_storage_logs_filepath_for_snapshot(l1_batch_number, chunk_id, &output_filepath)
+            .add_storage_logs_filepath_for_snapshot(
+                l1_batch_number,
+                chunk_id,
+                &output_filepath,
+                checksum,
+            )
             .await?;
         #[cfg(test)]
         self.event_listener.on_chunk_saved();
@@ -273,8 +294,10 @@ impl SnapshotCreator {
         }
     }
 
+    /// Creates a snapshot for the L1 batch preceding the latest sealed one, or resumes creating a pending snapshot.
+    /// Does nothing if the snapshot for the target L1 batch is already created.
     pub async fn run(
-        self,
+        &self,
         config: SnapshotsCreatorConfig,
         min_chunk_count: u64,
     ) -> anyhow::Result<()> {
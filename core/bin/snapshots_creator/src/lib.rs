@@ -0,0 +1,20 @@
+//! Snapshot creator component. Can be used as a standalone utility (see the crate binary) or embedded into a node.
+//!
+//! # Assumptions
+//!
+//! The snapshot creator is fault-tolerant; if it stops in the middle of creating a snapshot,
+//! this snapshot will be continued from roughly the same point after the restart. If this is
+//! undesired, remove the `snapshots` table record corresponding to the pending snapshot.
+//!
+//! It is assumed that the snapshot creator is run as a singleton (no more than 1 instance
+//! at a time).
+
+pub use crate::creator::SnapshotCreator;
+
+mod creator;
+mod metrics;
+#[cfg(test)]
+mod tests;
+
+/// Minimum number of storage log chunks to produce.
+pub const MIN_CHUNK_COUNT: u64 = 10;
@@ -10,8 +10,11 @@ use std::{
 };
 
 use rand::{thread_rng, Rng};
-use zksync_dal::{Connection, CoreDal};
-use zksync_object_store::ObjectStore;
+use zksync_config::SnapshotsCreatorConfig;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_object_store::{
+    serialized_object_checksum, ObjectStore, ObjectStoreFactory, StoredObject,
+};
 use zksync_types::{
     block::{L1BatchHeader, L1BatchTreeData, L2BlockHeader},
     snapshots::{
@@ -292,13 +295,24 @@ async fn persisting_snapshot_metadata() {
         snapshot_metadata.storage_logs_filepaths.len(),
         MIN_CHUNK_COUNT as usize
     );
-    for path in &snapshot_metadata.storage_logs_filepaths {
+    let object_store = object_store_factory.create_store().await;
+    let chunks = snapshot_metadata
+        .storage_logs_filepaths
+        .iter()
+        .zip(&snapshot_metadata.storage_logs_checksums);
+    for (path, checksum) in chunks {
         let path = path
             .as_ref()
             .unwrap()
             .strip_prefix("storage_logs_snapshots/")
             .unwrap();
         assert!(path.ends_with(".proto.gzip"));
+
+        let raw_chunk = object_store
+            .get_raw(SnapshotStorageLogsChunk::BUCKET, path)
+            .await
+            .unwrap();
+        assert_eq!(*checksum, Some(serialized_object_checksum(&raw_chunk)));
     }
 }
 
@@ -3,7 +3,7 @@ use std::io::Write;
 use zksync_config::configs::DatabaseSecrets;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_env_config::FromEnv;
-use zksync_types::contract_verification_api::SourceCodeData;
+use zksync_types::contract_verification_api::{SourceCodeData, VerificationTarget};
 
 #[tokio::main]
 async fn main() {
@@ -21,7 +21,14 @@ async fn main() {
 
     std::fs::create_dir_all("./verified_sources").unwrap();
     for req in reqs {
-        let dir = format!("./verified_sources/{:?}", req.req.contract_address);
+        let dir = match req.req.target {
+            VerificationTarget::Address { contract_address } => {
+                format!("./verified_sources/{contract_address:?}")
+            }
+            VerificationTarget::BytecodeHash { bytecode_hash } => {
+                format!("./verified_sources/bytecodes/{bytecode_hash:?}")
+            }
+        };
         if std::path::Path::new(&dir).exists() {
             continue;
         }
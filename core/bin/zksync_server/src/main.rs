@@ -13,22 +13,24 @@ use zksync_config::{
         house_keeper::HouseKeeperConfig,
         ContractsConfig, DatabaseSecrets, FriProofCompressorConfig, FriProverConfig,
         FriProverGatewayConfig, FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig,
-        L1Secrets, ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig, Secrets,
+        L1Secrets, ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig,
+        ProtectiveReadsVerifierConfig, Secrets, VmReplayConfig,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, EthConfig, EthWatchConfig, GasAdjusterConfig,
     GenesisConfig, ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
 };
 use zksync_core_leftovers::{
     genesis_init, initialize_components, is_genesis_needed, setup_sigint_handler,
-    temp_config_store::{decode_yaml_repr, TempConfigStore},
+    temp_config_store::{decode_secrets_yaml_repr, decode_yaml_repr, TempConfigStore},
     Component, Components,
 };
 use zksync_env_config::FromEnv;
 use zksync_eth_client::clients::Client;
+use zksync_node_framework::service::DescriptionFormat;
 use zksync_storage::RocksDB;
 use zksync_utils::wait_for_tasks::ManagedTasks;
 
-use crate::node_builder::MainNodeBuilder;
+use crate::node_builder::{resolve_components, MainNodeBuilder};
 
 mod config;
 mod node_builder;
@@ -45,7 +47,9 @@ struct Cli {
     /// Rebuild tree.
     #[arg(long)]
     rebuild_tree: bool,
-    /// Comma-separated list of components to launch.
+    /// Comma-separated list of components to launch. Components required by the selected ones (e.g., `tree`
+    /// for `tree_api`) are launched automatically. Components prefixed with `-` are excluded; e.g.,
+    /// `api,-contract_verification_api` launches all API components except for the contract verification API.
     #[arg(
         long,
         default_value = "api,tree,eth,state_keeper,housekeeper,tee_verifier_input_producer,commitment_generator"
@@ -54,7 +58,8 @@ struct Cli {
     /// Path to the yaml config. If set, it will be used instead of env vars.
     #[arg(long)]
     config_path: Option<std::path::PathBuf>,
-    /// Path to the yaml with secrets. If set, it will be used instead of env vars.
+    /// Path to the yaml with secrets. If set, it will be used instead of env vars. Secrets may be specified
+    /// as `env://<VAR>` or `file://<path>` references.
     #[arg(long)]
     secrets_path: Option<std::path::PathBuf>,
     /// Path to the yaml with contracts. If set, it will be used instead of env vars.
@@ -69,21 +74,37 @@ struct Cli {
     /// Run the node using the node framework.
     #[arg(long)]
     use_node_framework: bool,
+    /// Print the dependency graph of node framework layers, resources and tasks in the specified format
+    /// (`dot` or `json`) and exit without starting any tasks. Implies `--use-node-framework`.
+    #[arg(long)]
+    describe_framework: Option<DescriptionFormat>,
+    /// Port of the HTTP server allowing to disable and re-enable optional components (e.g., tree API) at runtime.
+    /// The server has no authentication and only listens on localhost. Only used with the node framework.
+    #[arg(long)]
+    task_control_port: Option<u16>,
 }
 
 #[derive(Debug, Clone)]
-struct ComponentsToRun(Vec<Component>);
+struct ComponentsToRun {
+    selected: Vec<Component>,
+    excluded: Vec<Component>,
+}
 
 impl FromStr for ComponentsToRun {
     type Err = String;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let components = s.split(',').try_fold(vec![], |mut acc, component_str| {
-            let components = Components::from_str(component_str.trim())?;
-            acc.extend(components.0);
-            Ok::<_, String>(acc)
-        })?;
-        Ok(Self(components))
+        let mut selected = vec![];
+        let mut excluded = vec![];
+        for component_str in s.split(',') {
+            let component_str = component_str.trim();
+            if let Some(component_str) = component_str.strip_prefix('-') {
+                excluded.extend(Components::from_str(component_str)?.0);
+            } else {
+                selected.extend(Components::from_str(component_str)?.0);
+            }
+        }
+        Ok(Self { selected, excluded })
     }
 }
 
@@ -148,7 +169,7 @@ async fn main() -> anyhow::Result<()> {
         Some(path) => {
             let yaml =
                 std::fs::read_to_string(&path).with_context(|| path.display().to_string())?;
-            decode_yaml_repr::<zksync_protobuf_config::proto::secrets::Secrets>(&yaml)
+            decode_secrets_yaml_repr::<zksync_protobuf_config::proto::secrets::Secrets>(&yaml)
                 .context("failed decoding secrets YAML config")?
         }
         None => Secrets {
@@ -211,22 +232,29 @@ async fn main() -> anyhow::Result<()> {
     let components = if opt.rebuild_tree {
         vec![Component::Tree]
     } else {
-        opt.components.0
+        resolve_components(&opt.components.selected, &opt.components.excluded)?
     };
 
     // If the node framework is used, run the node.
-    if opt.use_node_framework {
+    if opt.use_node_framework || opt.describe_framework.is_some() {
         // We run the node from a different thread, since the current thread is in tokio context.
         std::thread::spawn(move || -> anyhow::Result<()> {
-            let node = MainNodeBuilder::new(
+            let mut builder = MainNodeBuilder::new(
                 configs,
                 wallets,
                 genesis,
                 contracts_config,
                 secrets,
                 consensus,
-            )
-            .build(components)?;
+            );
+            if let Some(port) = opt.task_control_port {
+                builder = builder.with_task_control_port(port);
+            }
+            let node = builder.build(components)?;
+            if let Some(format) = opt.describe_framework {
+                println!("{}", node.describe().render(format));
+                return Ok(());
+            }
             node.run()?;
             Ok(())
         })
@@ -306,5 +334,7 @@ fn load_env_config() -> anyhow::Result<TempConfigStore> {
         object_store_config: ObjectStoreConfig::from_env().ok(),
         observability: ObservabilityConfig::from_env().ok(),
         snapshot_creator: SnapshotsCreatorConfig::from_env().ok(),
+        protective_reads_verifier_config: ProtectiveReadsVerifierConfig::from_env().ok(),
+        vm_replay_config: VmReplayConfig::from_env().ok(),
     })
 }
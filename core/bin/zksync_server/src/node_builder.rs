@@ -1,6 +1,8 @@
 //! This module provides a "builder" for the main node,
 //! as well as an interface to run the node with the specified components.
 
+use std::num::NonZeroUsize;
+
 use anyhow::Context;
 use prometheus_exporter::PrometheusExporterConfig;
 use zksync_config::{
@@ -14,6 +16,7 @@ use zksync_node_api_server::{
     web3::{state::InternalApiConfig, Namespace},
 };
 use zksync_node_framework::{
+    components::{self, ComponentDeps},
     implementations::layers::{
         circuit_breaker_checker::CircuitBreakerCheckerLayer,
         commitment_generator::CommitmentGeneratorLayer,
@@ -21,6 +24,7 @@ use zksync_node_framework::{
         contract_verification_api::ContractVerificationApiLayer,
         eth_sender::{EthTxAggregatorLayer, EthTxManagerLayer},
         eth_watch::EthWatchLayer,
+        explorer_api::ExplorerApiLayer,
         healtcheck_server::HealthCheckLayer,
         house_keeper::HouseKeeperLayer,
         l1_gas::SequencerL1GasLayer,
@@ -30,15 +34,18 @@ use zksync_node_framework::{
         pools_layer::PoolsLayerBuilder,
         prometheus_exporter::PrometheusExporterLayer,
         proof_data_handler::ProofDataHandlerLayer,
+        protective_reads_verifier::ProtectiveReadsVerifierLayer,
         query_eth_client::QueryEthClientLayer,
         sigint::SigintHandlerLayer,
         state_keeper::{
             main_batch_executor::MainBatchExecutorLayer, mempool_io::MempoolIOLayer,
             StateKeeperLayer,
         },
+        task_control_server::TaskControlServerLayer,
         tee_verifier_input_producer::TeeVerifierInputProducerLayer,
+        vm_runner_replay::VmReplayLayer,
         web3_api::{
-            caches::MempoolCacheLayer,
+            caches::{MempoolCacheLayer, ResponseCacheLayer},
             server::{Web3ServerLayer, Web3ServerOptionalConfig},
             tree_api_client::TreeApiClientLayer,
             tx_sender::{PostgresStorageCachesConfig, TxSenderLayer},
@@ -47,6 +54,36 @@ use zksync_node_framework::{
     },
     service::{ZkStackService, ZkStackServiceBuilder},
 };
+use zksync_vm_runner::{ProtectiveReadsVerifierConfig, VmReplayConfig};
+
+/// Dependencies between the main node components.
+fn component_deps(component: Component) -> ComponentDeps<Component> {
+    match component {
+        Component::TreeApi => ComponentDeps {
+            requires: &[Component::Tree],
+            after: &[],
+        },
+        // API uses resources provided by the state keeper (conditional sealer) and the Merkle tree (tree API client)
+        // if these components are present.
+        Component::HttpApi | Component::WsApi => ComponentDeps {
+            requires: &[],
+            after: &[Component::StateKeeper, Component::Tree],
+        },
+        _ => ComponentDeps::NONE,
+    }
+}
+
+/// Resolves the main node components selected by the operator: removes `excluded` components, includes components
+/// required by the selected ones, and orders the components so that they can be passed to [`MainNodeBuilder::build()`].
+pub fn resolve_components(
+    selected: &[Component],
+    excluded: &[Component],
+) -> anyhow::Result<Vec<Component>> {
+    let components = components::resolve_components(selected, excluded, component_deps)
+        .context("invalid set of components")?;
+    tracing::info!("Resolved components: {components:?}");
+    Ok(components)
+}
 
 /// Macro that looks into a path to fetch an optional config,
 /// and clones it into a variable.
@@ -64,6 +101,7 @@ pub struct MainNodeBuilder {
     contracts_config: ContractsConfig,
     secrets: Secrets,
     consensus_config: Option<ConsensusConfig>,
+    task_control_port: Option<u16>,
 }
 
 impl MainNodeBuilder {
@@ -83,9 +121,23 @@ impl MainNodeBuilder {
             contracts_config,
             secrets,
             consensus_config,
+            task_control_port: None,
         }
     }
 
+    /// Enables the HTTP server allowing to disable and re-enable optional tasks at runtime.
+    pub fn with_task_control_port(mut self, port: u16) -> Self {
+        self.task_control_port = Some(port);
+        self
+    }
+
+    fn add_task_control_server_layer(mut self) -> anyhow::Result<Self> {
+        if let Some(port) = self.task_control_port {
+            self.node.add_layer(TaskControlServerLayer::new(port));
+        }
+        Ok(self)
+    }
+
     fn add_sigint_handler_layer(mut self) -> anyhow::Result<Self> {
         self.node.add_layer(SigintHandlerLayer);
         Ok(self)
@@ -105,7 +157,7 @@ impl MainNodeBuilder {
 
     fn add_prometheus_exporter_layer(mut self) -> anyhow::Result<Self> {
         let prom_config = try_load_config!(self.configs.prometheus_config);
-        let prom_config = PrometheusExporterConfig::pull(prom_config.listener_port);
+        let prom_config = PrometheusExporterConfig::from_config(&prom_config, "zksync-server")?;
         self.node.add_layer(PrometheusExporterLayer(prom_config));
         Ok(self)
     }
@@ -249,6 +301,17 @@ impl MainNodeBuilder {
             rpc_config.mempool_cache_size(),
             rpc_config.mempool_cache_update_interval(),
         ));
+        if rpc_config.response_cache_enabled() {
+            let mut response_cache_layer = ResponseCacheLayer::new(
+                rpc_config.response_cache_size(),
+                rpc_config.response_cache_ttl(),
+            );
+            if let Some(redis_url) = rpc_config.response_cache_redis_url {
+                response_cache_layer =
+                    response_cache_layer.with_redis(redis_url, self.genesis_config.l2_chain_id);
+            }
+            self.node.add_layer(response_cache_layer);
+        }
         Ok(self)
     }
 
@@ -381,11 +444,23 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
+    fn add_explorer_api_layer(mut self) -> anyhow::Result<Self> {
+        let config = try_load_config!(self.configs.api_config).explorer;
+        self.node.add_layer(ExplorerApiLayer::new(
+            config,
+            self.genesis_config.l2_chain_id,
+        ));
+        Ok(self)
+    }
+
     fn add_consensus_layer(mut self) -> anyhow::Result<Self> {
         self.node.add_layer(ConsensusLayer {
             mode: ConsensusMode::Main,
             config: self.consensus_config.clone(),
             secrets: self.secrets.consensus.clone(),
+            validator_watermark_path: None,
+            // Blocks are only fetched by external nodes.
+            fetcher_prefetch_depth: NonZeroUsize::new(1).unwrap(),
         });
 
         Ok(self)
@@ -399,7 +474,28 @@ impl MainNodeBuilder {
         Ok(self)
     }
 
-    pub fn build(mut self, mut components: Vec<Component>) -> anyhow::Result<ZkStackService> {
+    fn add_protective_reads_verifier_layer(mut self) -> anyhow::Result<Self> {
+        let config = try_load_config!(self.configs.protective_reads_verifier_config);
+        let config = ProtectiveReadsVerifierConfig::from_node_config(
+            &config,
+            self.genesis_config.l2_chain_id,
+        )?;
+        self.node
+            .add_layer(ProtectiveReadsVerifierLayer::new(config));
+
+        Ok(self)
+    }
+
+    fn add_vm_replay_layer(mut self) -> anyhow::Result<Self> {
+        let config = try_load_config!(self.configs.vm_replay_config);
+        let config = VmReplayConfig::from_node_config(&config, self.genesis_config.l2_chain_id)?;
+        self.node
+            .add_layer(VmReplayLayer::with_protective_reads_verification(config));
+
+        Ok(self)
+    }
+
+    pub fn build(mut self, components: Vec<Component>) -> anyhow::Result<ZkStackService> {
         // Add "base" layers (resources and helper tasks).
         self = self
             .add_sigint_handler_layer()?
@@ -407,19 +503,13 @@ impl MainNodeBuilder {
             .add_object_store_layer()?
             .add_circuit_breaker_checker_layer()?
             .add_healthcheck_layer()?
+            .add_task_control_server_layer()?
             .add_prometheus_exporter_layer()?
             .add_query_eth_client_layer()?
             .add_sequencer_l1_gas_layer()?;
 
-        // Sort the components, so that the components they may depend on each other are added in the correct order.
-        components.sort_unstable_by_key(|component| match component {
-            // API consumes the resources provided by other layers (multiple ones), so it has to come the last.
-            Component::HttpApi | Component::WsApi => 1,
-            // Default priority.
-            _ => 0,
-        });
-
-        // Add "component-specific" layers.
+        // Add "component-specific" layers. Components are expected to be ordered by `resolve_components()`,
+        // so that components providing resources come before the components using them.
         // Note that the layers are added only once, so it's fine to add the same layer multiple times.
         for component in &components {
             match component {
@@ -440,6 +530,9 @@ impl MainNodeBuilder {
                 Component::ContractVerificationApi => {
                     self = self.add_contract_verification_api_layer()?;
                 }
+                Component::ExplorerApi => {
+                    self = self.add_explorer_api_layer()?;
+                }
                 Component::Tree => {
                     let with_tree_api = components.contains(&Component::TreeApi);
                     self = self.add_metadata_calculator_layer(with_tree_api)?;
@@ -460,7 +553,9 @@ impl MainNodeBuilder {
                         .add_eth_tx_aggregator_layer()?;
                 }
                 Component::EthTxManager => {
-                    self = self.add_eth_tx_manager_layer()?;
+                    self = self
+                        .add_pk_signing_client_layer()?
+                        .add_eth_tx_manager_layer()?;
                 }
                 Component::StateKeeper => {
                     self = self.add_state_keeper_layer()?;
@@ -480,6 +575,12 @@ impl MainNodeBuilder {
                 Component::CommitmentGenerator => {
                     self = self.add_commitment_generator_layer()?;
                 }
+                Component::VmRunnerProtectiveReadsVerifier => {
+                    self = self.add_protective_reads_verifier_layer()?;
+                }
+                Component::VmRunnerReplay => {
+                    self = self.add_vm_replay_layer()?;
+                }
             }
         }
         Ok(self.node.build()?)
@@ -46,6 +46,15 @@ impl Add for JobCountStatistics {
     }
 }
 
+/// Latency statistics for jobs that are queued or in progress. Ages are measured from job creation.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PendingJobsLatencyStats {
+    pub pending: usize,
+    pub oldest_age_secs: u64,
+    /// Number of pending jobs older than the SLA of the corresponding stage.
+    pub over_sla: usize,
+}
+
 #[derive(Debug)]
 pub struct StuckJobs {
     pub id: u64,
@@ -653,6 +653,13 @@ pub struct Transaction {
         skip_serializing_if = "Option::is_none"
     )]
     pub max_priority_fee_per_gas: Option<U256>,
+    /// Versioned hashes of blobs attached to an EIP-4844 transaction
+    #[serde(
+        rename = "blobVersionedHashes",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub blob_versioned_hashes: Option<Vec<H256>>,
 }
 
 /// "Receipt" of an executed transaction: details of its execution.
@@ -9,7 +9,7 @@ use std::{
 
 use anyhow::Context as _;
 use serde::{de, Deserialize, Deserializer};
-use zksync_basic_types::{Address, H256};
+use zksync_basic_types::{url::SensitiveUrl, Address, H256};
 
 pub use crate::configs::PrometheusConfig;
 
@@ -24,6 +24,8 @@ pub struct ApiConfig {
     pub healthcheck: HealthCheckConfig,
     /// Configuration options for Merkle tree API.
     pub merkle_tree: MerkleTreeApiConfig,
+    /// Configuration options for the embedded explorer API.
+    pub explorer: ExplorerApiConfig,
 }
 
 /// Response size limits for specific RPC methods.
@@ -123,6 +125,86 @@ impl<'de> Deserialize<'de> for MaxResponseSizeOverrides {
     }
 }
 
+/// Rate limits for specific RPC methods, measured in requests per second.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MethodRateLimits(HashMap<String, NonZeroU32>);
+
+impl<S: Into<String>> FromIterator<(S, NonZeroU32)> for MethodRateLimits {
+    fn from_iter<I: IntoIterator<Item = (S, NonZeroU32)>>(iter: I) -> Self {
+        Self(
+            iter.into_iter()
+                .map(|(method_name, limit)| (method_name.into(), limit))
+                .collect(),
+        )
+    }
+}
+
+impl FromStr for MethodRateLimits {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut limits = HashMap::new();
+        for part in s.split(',') {
+            let (method_name, limit) = part
+                .split_once('=')
+                .with_context(|| format!("Part `{part}` doesn't have form <method_name>=<int>"))?;
+            let method_name = method_name.trim();
+            let limit = limit.trim();
+            let limit = limit.parse().with_context(|| {
+                format!("`{limit}` specified for method `{method_name}` is not a valid rate limit")
+            })?;
+
+            if let Some(prev_limit) = limits.insert(method_name.to_owned(), limit) {
+                anyhow::bail!(
+                    "Rate limit for `{method_name}` is redefined from {prev_limit} to {limit}"
+                );
+            }
+        }
+        Ok(Self(limits))
+    }
+}
+
+impl MethodRateLimits {
+    pub fn empty() -> Self {
+        Self(HashMap::new())
+    }
+
+    /// Gets the limit in requests per second for the specified method, or `None` if it's not set.
+    pub fn get(&self, method_name: &str) -> Option<NonZeroU32> {
+        self.0.get(method_name).copied()
+    }
+
+    /// Iterates over all limits.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = (&str, NonZeroU32)> + '_ {
+        self.0
+            .iter()
+            .map(|(method_name, &limit)| (method_name.as_str(), limit))
+    }
+}
+
+impl<'de> Deserialize<'de> for MethodRateLimits {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ParseVisitor;
+
+        impl<'v> de::Visitor<'v> for ParseVisitor {
+            type Value = MethodRateLimits;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+                formatter.write_str("comma-separated list of <method_name>=<requests_per_second> tuples, such as: eth_call=100,eth_getLogs=10")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(E::custom)
+            }
+        }
+
+        deserializer.deserialize_str(ParseVisitor)
+    }
+}
+
 /// Response size limits for JSON-RPC servers.
 #[derive(Debug)]
 pub struct MaxResponseSize {
@@ -154,6 +236,11 @@ pub struct Web3JsonRpcConfig {
     /// different node.
     #[serde(default)]
     pub filters_disabled: bool,
+    /// Whether to enable paginated logs retrieval. If enabled, `eth_getLogs` errors caused by exceeding
+    /// `req_entities_limit` carry a continuation cursor that can be passed to `zks_getLogsPaged`,
+    /// and `zks_getLogsPaged` is available.
+    #[serde(default)]
+    pub logs_pagination_enabled: bool,
     /// Max possible limit of filters to be in the state at once.
     pub filters_limit: Option<u32>,
     /// Max possible limit of subscriptions to be in the state at once.
@@ -173,6 +260,10 @@ pub struct Web3JsonRpcConfig {
     pub estimate_gas_scale_factor: f64,
     /// The max possible number of gas that `eth_estimateGas` is allowed to overestimate.
     pub estimate_gas_acceptable_overestimation: u32,
+    /// Whether to estimate gas using a single metered VM execution (verified by one more execution with the suggested limit)
+    /// instead of a binary search over the gas limit. If verification fails, estimation falls back to the binary search.
+    #[serde(default)]
+    pub estimate_gas_single_pass: bool,
     ///  Max possible size of an ABI encoded tx (in bytes).
     pub max_tx_size: usize,
     /// Max number of cache misses during one VM execution. If the number of cache misses exceeds this value, the API server panics.
@@ -209,6 +300,14 @@ pub struct Web3JsonRpcConfig {
     pub mempool_cache_update_interval: Option<u64>,
     /// Maximum number of transactions to be stored in the mempool cache. Default is 10000.
     pub mempool_cache_size: Option<usize>,
+    /// Maximum size in MiBs of the in-memory cache for API responses that don't change once the corresponding block
+    /// is final. Default is 0, which disables in-memory caching.
+    pub response_cache_size_mb: Option<usize>,
+    /// Time-to-live in seconds for entries in the API response cache. Default is 10 minutes.
+    pub response_cache_ttl_sec: Option<u64>,
+    /// Redis URL used to share the API response cache among multiple nodes. If set, the cache is enabled
+    /// even if `response_cache_size_mb` is not set.
+    pub response_cache_redis_url: Option<SensitiveUrl>,
     /// List of L2 token addresses that are white-listed to use by paymasters
     /// (additionally to natively bridged tokens).
     #[serde(default)]
@@ -227,6 +326,7 @@ impl Web3JsonRpcConfig {
             ws_url: "ws://localhost:3051".into(),
             req_entities_limit: Some(10000),
             filters_disabled: false,
+            logs_pagination_enabled: false,
             filters_limit: Some(10000),
             subscriptions_limit: Some(10000),
             pubsub_polling_interval: Some(200),
@@ -236,6 +336,7 @@ impl Web3JsonRpcConfig {
             account_pks: Default::default(),
             estimate_gas_scale_factor: 1.2,
             estimate_gas_acceptable_overestimation: 1000,
+            estimate_gas_single_pass: false,
             max_tx_size: 1000000,
             vm_execution_cache_misses_limit: Default::default(),
             vm_concurrency_limit: Default::default(),
@@ -249,6 +350,9 @@ impl Web3JsonRpcConfig {
             websocket_requests_per_minute_limit: Default::default(),
             mempool_cache_update_interval: Default::default(),
             mempool_cache_size: Default::default(),
+            response_cache_size_mb: Default::default(),
+            response_cache_ttl_sec: Default::default(),
+            response_cache_redis_url: None,
             tree_api_url: None,
             whitelisted_tokens_for_aa: Default::default(),
         }
@@ -342,6 +446,20 @@ impl Web3JsonRpcConfig {
     pub fn mempool_cache_size(&self) -> usize {
         self.mempool_cache_size.unwrap_or(10_000)
     }
+
+    /// Returns the max size of the in-memory API response cache in bytes.
+    pub fn response_cache_size(&self) -> u64 {
+        (self.response_cache_size_mb.unwrap_or(0) * super::BYTES_IN_MEGABYTE) as u64
+    }
+
+    pub fn response_cache_ttl(&self) -> Duration {
+        Duration::from_secs(self.response_cache_ttl_sec.unwrap_or(600))
+    }
+
+    /// Checks whether the API response cache is enabled.
+    pub fn response_cache_enabled(&self) -> bool {
+        self.response_cache_size() > 0 || self.response_cache_redis_url.is_some()
+    }
 }
 
 #[derive(Debug, Deserialize, Clone, PartialEq)]
@@ -390,6 +508,10 @@ pub struct MerkleTreeApiConfig {
     /// Port to bind the Merkle tree API server to.
     #[serde(default = "MerkleTreeApiConfig::default_port")]
     pub port: u16,
+    /// Port to bind the gRPC Merkle tree API server to. The gRPC server runs alongside the HTTP server
+    /// and supports streaming proofs and tree node dumps. If not set, the gRPC server is not started.
+    #[serde(default)]
+    pub grpc_port: Option<u16>,
 }
 
 impl MerkleTreeApiConfig {
@@ -398,6 +520,41 @@ impl MerkleTreeApiConfig {
     }
 }
 
+/// Configuration for the embedded explorer API. The explorer API is a minimal REST API for devnets, which exposes
+/// account info, account transactions and token transfers without running a full block explorer stack.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ExplorerApiConfig {
+    /// Port to bind the explorer API server to.
+    #[serde(default = "ExplorerApiConfig::default_port")]
+    pub port: u16,
+    /// Maximum number of items returned on a single page of paginated endpoints.
+    #[serde(default = "ExplorerApiConfig::default_max_page_size")]
+    pub max_page_size: usize,
+}
+
+impl Default for ExplorerApiConfig {
+    fn default() -> Self {
+        Self {
+            port: Self::default_port(),
+            max_page_size: Self::default_max_page_size(),
+        }
+    }
+}
+
+impl ExplorerApiConfig {
+    const fn default_port() -> u16 {
+        3_080
+    }
+
+    const fn default_max_page_size() -> usize {
+        100
+    }
+
+    pub fn bind_addr(&self) -> SocketAddr {
+        SocketAddr::new("0.0.0.0".parse().unwrap(), self.port)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -421,4 +578,20 @@ mod tests {
         assert_eq!(scaled.get("zks_getProof"), Some(32_000));
         assert_eq!(scaled.get("eth_blockNumber"), None);
     }
+
+    #[test]
+    fn parsing_method_rate_limits() {
+        let limits: MethodRateLimits = "eth_call=100, eth_getLogs = 10".parse().unwrap();
+        assert_eq!(limits.iter().len(), 2);
+        assert_eq!(limits.get("eth_call"), NonZeroU32::new(100));
+        assert_eq!(limits.get("eth_getLogs"), NonZeroU32::new(10));
+        assert_eq!(limits.get("eth_blockNumber"), None);
+
+        let err = "eth_call=0".parse::<MethodRateLimits>().unwrap_err();
+        assert!(err.to_string().contains("eth_call"), "{err}");
+        let err = "eth_call=1,eth_call=2"
+            .parse::<MethodRateLimits>()
+            .unwrap_err();
+        assert!(err.to_string().contains("redefined"), "{err}");
+    }
 }
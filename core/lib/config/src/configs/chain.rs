@@ -63,6 +63,14 @@ pub struct StateKeeperConfig {
     /// 0 means that sealing is synchronous; this is mostly useful for performance comparison, testing etc.
     #[serde(alias = "miniblock_seal_queue_capacity")]
     pub l2_block_seal_queue_capacity: usize,
+    /// Upper bound for the capacity of the L2 block seal queue. If set to a value greater than `l2_block_seal_queue_capacity`,
+    /// the queue capacity is adjusted at runtime between these two values based on the Postgres write latency.
+    #[serde(default)]
+    pub l2_block_seal_queue_max_capacity: Option<usize>,
+    /// Postgres write latency of a single L2 block (in ms) above which the adaptive seal queue is shrunk.
+    /// Only used if `l2_block_seal_queue_max_capacity` is set.
+    #[serde(default)]
+    pub l2_block_seal_queue_target_latency_ms: Option<u64>,
     /// The max payload size threshold (in bytes) that triggers sealing of an L2 block.
     #[serde(alias = "miniblock_max_payload_size")]
     pub l2_block_max_payload_size: usize,
@@ -141,6 +149,8 @@ impl StateKeeperConfig {
             block_commit_deadline_ms: 2500,
             l2_block_commit_deadline_ms: 1000,
             l2_block_seal_queue_capacity: 10,
+            l2_block_seal_queue_max_capacity: None,
+            l2_block_seal_queue_target_latency_ms: None,
             l2_block_max_payload_size: 1_000_000,
             max_single_tx_gas: 6000000,
             max_allowed_l2_tx_gas_limit: 4000000000,
@@ -16,6 +16,14 @@ pub struct ContractVerifierConfig {
     pub threads_per_server: Option<u16>,
     pub port: u16,
     pub url: String,
+    /// Base URL of a mirror from which missing compiler binaries are fetched. Binaries are expected
+    /// at `{url}/{compiler}-bin/{version}/{compiler}`, i.e., using the same layout as the local compiler directories.
+    /// Only binaries with checksums pinned in `etc/contract-verifier/compiler-checksums.txt` are fetched; fetched binaries
+    /// are verified against these checksums and cached locally. If not set, only locally installed compilers are used.
+    pub compilers_download_url: Option<String>,
+    /// Max number of compiler version combinations tried for a request with automatically detected
+    /// compiler versions. Trying versions stops early on errors not related to compiler versions.
+    pub max_compiler_candidates: Option<usize>,
 }
 
 impl ContractVerifierConfig {
@@ -26,6 +34,11 @@ impl ContractVerifierConfig {
     pub fn polling_interval(&self) -> Duration {
         Duration::from_millis(self.polling_interval.unwrap_or(1000))
     }
+
+    pub fn max_compiler_candidates(&self) -> usize {
+        self.max_compiler_candidates.unwrap_or(10).max(1)
+    }
+
     pub fn bind_addr(&self) -> SocketAddr {
         SocketAddr::new(IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0)), self.port)
     }
@@ -143,7 +143,8 @@ pub struct PostgresConfig {
     pub statement_timeout_sec: Option<u64>,
     /// Threshold in milliseconds for the DB connection lifetime to denote it as long-living and log its details.
     pub long_connection_threshold_ms: Option<u64>,
-    /// Threshold in milliseconds to denote a DB query as "slow" and log its details.
+    /// Threshold in milliseconds to denote a DB query as "slow" and log its details, including (rate-limited)
+    /// `EXPLAIN` output.
     pub slow_query_threshold_ms: Option<u64>,
     pub test_server_url: Option<String>,
     pub test_prover_url: Option<String>,
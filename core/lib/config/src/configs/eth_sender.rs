@@ -40,6 +40,10 @@ impl EthConfig {
                 l1_batch_min_age_before_execute_seconds: None,
                 max_acceptable_priority_fee_in_gwei: 100000000000,
                 pubdata_sending_mode: PubdataSendingMode::Calldata,
+                fee_bump_percent: None,
+                resubmission_interval_blocks: None,
+                stuck_tx_threshold_blocks: None,
+                repair_nonce_gaps: false,
             }),
             gas_adjuster: Some(GasAdjusterConfig {
                 default_priority_fee_per_gas: 1000000000,
@@ -54,10 +58,15 @@ impl EthConfig {
                 num_samples_for_blob_base_fee_estimate: 10,
                 internal_pubdata_pricing_multiplier: 1.0,
                 max_blob_base_fee: None,
+                blob_fee_strategy: BlobFeeStrategy::Median,
+                blob_fee_percentile: None,
+                blob_fee_ewma_alpha: None,
+                blob_fee_cap: None,
             }),
             watcher: Some(EthWatchConfig {
                 confirmations_for_eth_event: None,
                 eth_node_poll_interval: 0,
+                additional_sources: vec![],
             }),
         }
     }
@@ -83,6 +92,20 @@ pub enum PubdataSendingMode {
     Blobs,
 }
 
+/// Strategy used by `GasAdjuster` to derive the blob base fee from the collected samples.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Default)]
+pub enum BlobFeeStrategy {
+    /// Median of the collected samples.
+    #[default]
+    Median,
+    /// Configurable percentile of the collected samples (see `blob_fee_percentile`).
+    Percentile,
+    /// Exponentially weighted moving average of the samples (see `blob_fee_ewma_alpha`).
+    Ewma,
+    /// Latest sample capped by `blob_fee_cap`.
+    Capped,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct SenderConfig {
     pub aggregated_proof_sizes: Vec<usize>,
@@ -116,9 +139,28 @@ pub struct SenderConfig {
 
     /// The mode in which we send pubdata, either Calldata or Blobs
     pub pubdata_sending_mode: PubdataSendingMode,
+
+    /// Percentage by which the priority fee of a non-blob transaction is increased on each resubmission.
+    /// Values below 10% are raised to 10%, since L1 nodes reject cheaper replacements.
+    #[serde(default)]
+    pub fee_bump_percent: Option<u64>,
+    /// Minimum number of L1 blocks between two consecutive resubmissions of the same transaction.
+    #[serde(default)]
+    pub resubmission_interval_blocks: Option<u64>,
+    /// Number of L1 blocks after which a transaction that hasn't been mined is reported as stuck.
+    #[serde(default)]
+    pub stuck_tx_threshold_blocks: Option<u64>,
+    /// Whether to reassign nonces of the pending transactions if the operator nonce on L1 is lower
+    /// than the nonce of the first pending transaction (e.g., after the operator key rotation).
+    #[serde(default)]
+    pub repair_nonce_gaps: bool,
 }
 
 impl SenderConfig {
+    const DEFAULT_FEE_BUMP_PERCENT: u64 = 20;
+    const MIN_FEE_BUMP_PERCENT: u64 = 10;
+    const DEFAULT_STUCK_TX_THRESHOLD_BLOCKS: u64 = 50;
+
     /// Converts `self.tx_poll_period` into `Duration`.
     pub fn tx_poll_period(&self) -> Duration {
         Duration::from_secs(self.tx_poll_period)
@@ -129,6 +171,21 @@ impl SenderConfig {
         Duration::from_secs(self.aggregate_tx_poll_period)
     }
 
+    pub fn fee_bump_percent(&self) -> u64 {
+        self.fee_bump_percent
+            .unwrap_or(Self::DEFAULT_FEE_BUMP_PERCENT)
+            .max(Self::MIN_FEE_BUMP_PERCENT)
+    }
+
+    pub fn resubmission_interval_blocks(&self) -> u64 {
+        self.resubmission_interval_blocks.unwrap_or(1).max(1)
+    }
+
+    pub fn stuck_tx_threshold_blocks(&self) -> u64 {
+        self.stuck_tx_threshold_blocks
+            .unwrap_or(Self::DEFAULT_STUCK_TX_THRESHOLD_BLOCKS)
+    }
+
     // Don't load private key, if it's not required.
     #[deprecated]
     pub fn private_key(&self) -> anyhow::Result<Option<K256PrivateKey>> {
@@ -180,6 +237,18 @@ pub struct GasAdjusterConfig {
     pub internal_pubdata_pricing_multiplier: f64,
     /// Max blob base fee that is allowed to be used.
     pub max_blob_base_fee: Option<u64>,
+    /// Strategy used to derive the blob base fee from the collected samples.
+    #[serde(default)]
+    pub blob_fee_strategy: BlobFeeStrategy,
+    /// Percentile (0..=100) used by the `Percentile` blob fee strategy. Defaults to 75.
+    #[serde(default)]
+    pub blob_fee_percentile: Option<u32>,
+    /// Smoothing factor (0..=1] used by the `Ewma` blob fee strategy. Defaults to 0.3.
+    #[serde(default)]
+    pub blob_fee_ewma_alpha: Option<f64>,
+    /// Cap used by the `Capped` blob fee strategy. Defaults to `max_blob_base_fee`.
+    #[serde(default)]
+    pub blob_fee_cap: Option<u64>,
 }
 
 impl GasAdjusterConfig {
@@ -196,6 +265,21 @@ impl GasAdjusterConfig {
         self.max_blob_base_fee.unwrap_or(u64::MAX)
     }
 
+    pub fn blob_fee_percentile(&self) -> u32 {
+        self.blob_fee_percentile.unwrap_or(75).min(100)
+    }
+
+    pub fn blob_fee_ewma_alpha(&self) -> f64 {
+        self.blob_fee_ewma_alpha
+            .unwrap_or(0.3)
+            .clamp(f64::EPSILON, 1.0)
+    }
+
+    pub fn blob_fee_cap(&self) -> u64 {
+        self.blob_fee_cap
+            .unwrap_or_else(|| self.max_blob_base_fee())
+    }
+
     pub const fn default_num_samples_for_blob_base_fee_estimate() -> usize {
         10
     }
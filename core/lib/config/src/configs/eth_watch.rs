@@ -1,6 +1,7 @@
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
+use zksync_basic_types::Address;
 
 /// Configuration for the Ethereum watch crate.
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
@@ -11,6 +12,10 @@ pub struct EthWatchConfig {
     /// How often we want to poll the Ethereum node.
     /// Value in milliseconds.
     pub eth_node_poll_interval: u64,
+    /// L1 contracts to track in addition to the main diamond proxy (e.g., during bridgehub migrations).
+    /// Each source is polled independently and has its own persisted cursor.
+    #[serde(default)]
+    pub additional_sources: Vec<EthWatchSourceConfig>,
 }
 
 impl EthWatchConfig {
@@ -19,3 +24,17 @@ impl EthWatchConfig {
         Duration::from_millis(self.eth_node_poll_interval)
     }
 }
+
+/// Additional L1 contract emitting priority operations and governance upgrades.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct EthWatchSourceConfig {
+    /// Address of the diamond proxy emitting priority operations. Also identifies the source cursor.
+    pub diamond_proxy_addr: Address,
+    /// Address of the governance contract scheduling protocol upgrades.
+    pub governance_addr: Address,
+    /// Address of the state transition manager targeted by upgrades. Only present for post-shared bridge chains.
+    pub state_transition_manager_addr: Option<Address>,
+    /// Amount of confirmations for events emitted by this source.
+    /// If not specified, events will be processed once their block is finalized.
+    pub confirmations_for_eth_event: Option<u64>,
+}
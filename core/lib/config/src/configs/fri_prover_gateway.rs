@@ -6,6 +6,10 @@ use serde::Deserialize;
 pub struct FriProverGatewayConfig {
     pub api_url: String,
     pub api_poll_duration_secs: u16,
+    /// Whether to use the streaming (WebSocket-based) protocol to receive jobs from and submit proofs to the server.
+    /// If the server doesn't support streaming, the gateway falls back to polling the HTTP API.
+    #[serde(default)]
+    pub streaming_enabled: bool,
 
     /// Configurations for prometheus
     pub prometheus_listener_port: u16,
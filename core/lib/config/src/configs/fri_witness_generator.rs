@@ -1,4 +1,4 @@
-use std::time::Duration;
+use std::{path::Path, time::Duration};
 
 // Built-in uses
 // External uses
@@ -23,6 +23,17 @@ pub struct FriWitnessGeneratorConfig {
 
     // whether to write to public GCS bucket for https://github.com/matter-labs/era-boojum-validator-cli
     pub shall_save_to_public_bucket: bool,
+
+    /// Memory budget for generated artifacts (e.g., basic circuits) buffered before they are uploaded
+    /// to the object store. Artifacts exceeding the budget are spilled to the local disk. Default is 64 MiB.
+    pub artifacts_memory_budget_mb: Option<u32>,
+    /// Disk budget for spilled artifacts. If both the memory and disk budgets are exhausted, witness generation
+    /// waits for uploads. Default is 4 GiB.
+    pub artifacts_disk_budget_mb: Option<u32>,
+    /// Directory used to spill artifacts exceeding the memory budget. If not set, the system temporary directory is used.
+    /// Stale spilled artifacts are removed from this directory on startup, so it must not be shared
+    /// with other witness generator instances.
+    pub spill_dir: Option<String>,
 }
 
 #[derive(Debug)]
@@ -85,4 +96,26 @@ impl FriWitnessGeneratorConfig {
     pub fn last_l1_batch_to_process(&self) -> u32 {
         self.last_l1_batch_to_process.unwrap_or(u32::MAX)
     }
+
+    pub fn artifacts_memory_budget_bytes(&self) -> usize {
+        const DEFAULT_MEMORY_BUDGET_MB: u32 = 64;
+
+        let budget_mb = self
+            .artifacts_memory_budget_mb
+            .unwrap_or(DEFAULT_MEMORY_BUDGET_MB);
+        (budget_mb as usize) << 20
+    }
+
+    pub fn artifacts_disk_budget_bytes(&self) -> usize {
+        const DEFAULT_DISK_BUDGET_MB: u32 = 4_096;
+
+        let budget_mb = self
+            .artifacts_disk_budget_mb
+            .unwrap_or(DEFAULT_DISK_BUDGET_MB);
+        (budget_mb as usize) << 20
+    }
+
+    pub fn spill_dir(&self) -> Option<&Path> {
+        self.spill_dir.as_deref().map(Path::new)
+    }
 }
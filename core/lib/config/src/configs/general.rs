@@ -5,7 +5,7 @@ use crate::{
         house_keeper::HouseKeeperConfig,
         FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
         FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, ObservabilityConfig,
-        PrometheusConfig, ProofDataHandlerConfig,
+        PrometheusConfig, ProofDataHandlerConfig, ProtectiveReadsVerifierConfig, VmReplayConfig,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, EthConfig, PostgresConfig, SnapshotsCreatorConfig,
 };
@@ -32,4 +32,6 @@ pub struct GeneralConfig {
     pub eth: Option<EthConfig>,
     pub snapshot_creator: Option<SnapshotsCreatorConfig>,
     pub observability: Option<ObservabilityConfig>,
+    pub protective_reads_verifier_config: Option<ProtectiveReadsVerifierConfig>,
+    pub vm_replay_config: Option<VmReplayConfig>,
 }
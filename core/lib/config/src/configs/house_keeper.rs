@@ -1,3 +1,5 @@
+use std::time::Duration;
+
 use serde::Deserialize;
 
 /// Configuration for the house keeper.
@@ -17,9 +19,32 @@ pub struct HouseKeeperConfig {
     pub prover_job_archiver_archive_after_secs: Option<u64>,
     pub fri_gpu_prover_archiver_archiving_interval_ms: Option<u64>,
     pub fri_gpu_prover_archiver_archive_after_secs: Option<u64>,
+    /// Interval between runs of the prover job monitor. The monitor is disabled if not set.
+    pub prover_job_monitor_interval_ms: Option<u64>,
+    /// Jobs that are in progress for longer than this threshold are requeued by the prover job monitor.
+    /// Stuck jobs are not requeued if not set.
+    pub prover_job_monitor_stuck_job_threshold_secs: Option<u64>,
+    /// Maximum expected time between creating a witness generation job and processing it.
+    pub witness_generation_sla_secs: Option<u64>,
+    /// Maximum expected time between creating a prover job and processing it.
+    pub proving_sla_secs: Option<u64>,
+    /// Maximum expected time between creating a proof compression job and processing it.
+    pub proof_compression_sla_secs: Option<u64>,
+}
+
+/// SLAs for the stages of the prover pipeline, measured from job creation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ProverStageSlas {
+    pub witness_generation: Duration,
+    pub proving: Duration,
+    pub proof_compression: Duration,
 }
 
 impl HouseKeeperConfig {
+    const DEFAULT_WITNESS_GENERATION_SLA: Duration = Duration::from_secs(1_800);
+    const DEFAULT_PROVING_SLA: Duration = Duration::from_secs(3_600);
+    const DEFAULT_PROOF_COMPRESSION_SLA: Duration = Duration::from_secs(1_800);
+
     pub fn prover_job_archiver_params(&self) -> Option<(u64, u64)> {
         self.prover_job_archiver_archiving_interval_ms
             .zip(self.prover_job_archiver_archive_after_secs)
@@ -29,4 +54,23 @@ impl HouseKeeperConfig {
         self.fri_gpu_prover_archiver_archiving_interval_ms
             .zip(self.fri_gpu_prover_archiver_archive_after_secs)
     }
+
+    pub fn prover_stage_slas(&self) -> ProverStageSlas {
+        ProverStageSlas {
+            witness_generation: self
+                .witness_generation_sla_secs
+                .map_or(Self::DEFAULT_WITNESS_GENERATION_SLA, Duration::from_secs),
+            proving: self
+                .proving_sla_secs
+                .map_or(Self::DEFAULT_PROVING_SLA, Duration::from_secs),
+            proof_compression: self
+                .proof_compression_sla_secs
+                .map_or(Self::DEFAULT_PROOF_COMPRESSION_SLA, Duration::from_secs),
+        }
+    }
+
+    pub fn prover_job_monitor_stuck_job_threshold(&self) -> Option<Duration> {
+        self.prover_job_monitor_stuck_job_threshold_secs
+            .map(Duration::from_secs)
+    }
 }
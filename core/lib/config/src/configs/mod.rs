@@ -5,7 +5,7 @@ pub use self::{
     contracts::{ContractsConfig, EcosystemContracts},
     database::{DBConfig, PostgresConfig},
     eth_sender::{EthConfig, GasAdjusterConfig},
-    eth_watch::EthWatchConfig,
+    eth_watch::{EthWatchConfig, EthWatchSourceConfig},
     experimental::ExperimentalDBConfig,
     fri_proof_compressor::FriProofCompressorConfig,
     fri_prover::FriProverConfig,
@@ -19,7 +19,8 @@ pub use self::{
     proof_data_handler::ProofDataHandlerConfig,
     secrets::{DatabaseSecrets, L1Secrets, Secrets},
     snapshots_creator::SnapshotsCreatorConfig,
-    utils::PrometheusConfig,
+    utils::{PrometheusConfig, PrometheusExportMode},
+    vm_runner::{ProtectiveReadsVerifierConfig, VmReplayConfig},
 };
 
 pub mod api;
@@ -46,6 +47,7 @@ pub mod proof_data_handler;
 pub mod secrets;
 pub mod snapshots_creator;
 pub mod utils;
+pub mod vm_runner;
 pub mod wallets;
 
 const BYTES_IN_MEGABYTE: usize = 1_024 * 1_024;
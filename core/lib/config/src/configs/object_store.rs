@@ -7,6 +7,20 @@ pub struct ObjectStoreConfig {
     pub mode: ObjectStoreMode,
     #[serde(default = "ObjectStoreConfig::default_max_retries")]
     pub max_retries: u16,
+    /// Whether to wrap stored objects into an integrity envelope with a SHA-256 checksum verified on each read.
+    /// Always enabled if `encryption_keys_file_path` is set.
+    #[serde(default)]
+    pub integrity_envelope: bool,
+    /// Path to the file with AES-256-GCM keys used to encrypt stored objects on the client side. Each non-empty line
+    /// of the file has `{key_id}:{base64_key}` format. The first key is used to encrypt new objects; all keys
+    /// can be used to decrypt objects, which allows rotating keys without re-encrypting existing objects.
+    #[serde(default)]
+    pub encryption_keys_file_path: Option<String>,
+    /// Whether to read objects not wrapped into an envelope if encryption keys are configured. Such objects
+    /// are neither encrypted nor integrity-checked, so this should only be enabled temporarily while migrating
+    /// an existing store to encryption.
+    #[serde(default)]
+    pub allow_plaintext_reads: bool,
 }
 
 impl ObjectStoreConfig {
@@ -31,4 +45,30 @@ pub enum ObjectStoreMode {
     FileBacked {
         file_backed_base_path: String,
     },
+    /// Read-only store fetching objects via HTTP(S) from `{http_base_url}/{bucket}/{key}`. Can be used
+    /// with static mirrors of other stores, such as public S3-compatible buckets or CDNs.
+    HttpMirror {
+        http_base_url: String,
+    },
+    /// Azure Blob Storage container. Objects from all buckets are stored in the same container, using bucket names
+    /// as blob name prefixes. Credentials are taken from the `AZURE_STORAGE_KEY` (shared account key)
+    /// or `AZURE_STORAGE_SAS_TOKEN` env variables; if neither is set, the container is accessed anonymously.
+    AzureBlob {
+        azure_account_name: String,
+        azure_container_name: String,
+        /// Custom Blob service endpoint, e.g. for the Azurite emulator. If not specified,
+        /// `https://{azure_account_name}.blob.core.windows.net` is used.
+        azure_endpoint: Option<String>,
+    },
+    /// Bucket in an S3-compatible store (AWS S3, MinIO, Cloudflare R2 etc.). Objects from all buckets are stored
+    /// in the same S3 bucket, using bucket names as key prefixes. Credentials are resolved using the default AWS
+    /// credentials chain (e.g., the `AWS_ACCESS_KEY_ID` and `AWS_SECRET_ACCESS_KEY` env variables); if no credentials
+    /// are resolved, the bucket is accessed anonymously.
+    S3 {
+        s3_bucket_name: String,
+        s3_region: String,
+        /// Custom endpoint for S3-compatible stores other than AWS S3. If not specified,
+        /// `https://s3.{s3_region}.amazonaws.com` is used. Objects are always accessed using path-style addressing.
+        s3_endpoint: Option<String>,
+    },
 }
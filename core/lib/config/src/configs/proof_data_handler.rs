@@ -6,10 +6,35 @@ use serde::Deserialize;
 pub struct ProofDataHandlerConfig {
     pub http_port: u16,
     pub proof_generation_timeout_in_secs: u16,
+    /// Maximum size of a single chunk of a resumable proof upload.
+    pub upload_chunk_size_limit_bytes: Option<usize>,
+    /// Time after which a resumable proof upload that doesn't make progress is discarded.
+    pub upload_session_timeout_in_secs: Option<u64>,
+    /// Interval between checks of stored proofs against the object store.
+    pub reconciliation_interval_in_secs: Option<u64>,
 }
 
 impl ProofDataHandlerConfig {
+    const DEFAULT_UPLOAD_CHUNK_SIZE_LIMIT_BYTES: usize = 1 << 20;
+    const DEFAULT_UPLOAD_SESSION_TIMEOUT: Duration = Duration::from_secs(3_600);
+    const DEFAULT_RECONCILIATION_INTERVAL: Duration = Duration::from_secs(600);
+
     pub fn proof_generation_timeout(&self) -> Duration {
         Duration::from_secs(self.proof_generation_timeout_in_secs as u64)
     }
+
+    pub fn upload_chunk_size_limit_bytes(&self) -> usize {
+        self.upload_chunk_size_limit_bytes
+            .unwrap_or(Self::DEFAULT_UPLOAD_CHUNK_SIZE_LIMIT_BYTES)
+    }
+
+    pub fn upload_session_timeout(&self) -> Duration {
+        self.upload_session_timeout_in_secs
+            .map_or(Self::DEFAULT_UPLOAD_SESSION_TIMEOUT, Duration::from_secs)
+    }
+
+    pub fn reconciliation_interval(&self) -> Duration {
+        self.reconciliation_interval_in_secs
+            .map_or(Self::DEFAULT_RECONCILIATION_INTERVAL, Duration::from_secs)
+    }
 }
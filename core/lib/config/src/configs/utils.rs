@@ -2,6 +2,19 @@ use std::{env, time::Duration};
 
 use serde::Deserialize;
 
+/// Mode of exporting metrics.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrometheusExportMode {
+    /// Metrics are scraped from the HTTP server listening on `listener_port`.
+    #[default]
+    Pull,
+    /// Metrics are periodically pushed to the Prometheus push gateway at `pushgateway_url`.
+    Push,
+    /// Metrics are periodically exported to an OpenTelemetry collector at `otlp_endpoint` using OTLP over HTTP.
+    Otlp,
+}
+
 #[derive(Debug, Deserialize, Clone, PartialEq)]
 pub struct PrometheusConfig {
     /// Port to which the Prometheus exporter server is listening.
@@ -10,6 +23,15 @@ pub struct PrometheusConfig {
     pub pushgateway_url: String,
     /// Push interval in ms.
     pub push_interval_ms: Option<u64>,
+    /// Mode of exporting metrics. Only used by the components that support all modes (e.g., the main node);
+    /// other components have hard-coded modes.
+    #[serde(default)]
+    pub export_mode: PrometheusExportMode,
+    /// OTLP endpoint of an OpenTelemetry collector, e.g. `http://127.0.0.1:4318/v1/metrics`.
+    /// Required for the OTLP export mode.
+    pub otlp_endpoint: Option<String>,
+    /// Interval in ms between exporting metrics via OTLP. Default is 10s.
+    pub otlp_export_interval_ms: Option<u64>,
 }
 
 impl PrometheusConfig {
@@ -17,6 +39,10 @@ impl PrometheusConfig {
         Duration::from_millis(self.push_interval_ms.unwrap_or(100))
     }
 
+    pub fn otlp_export_interval(&self) -> Duration {
+        Duration::from_millis(self.otlp_export_interval_ms.unwrap_or(10_000))
+    }
+
     /// Returns the full endpoint URL for the push gateway.
     pub fn gateway_endpoint(&self) -> String {
         let gateway_url = &self.pushgateway_url;
@@ -0,0 +1,66 @@
+use serde::Deserialize;
+use zksync_basic_types::L1BatchNumber;
+
+use crate::configs::BYTES_IN_MEGABYTE;
+
+/// Configuration of the protective reads verifier, a VM runner component recomputing protective reads
+/// for sealed L1 batches and comparing them with the protective reads persisted by the state keeper.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ProtectiveReadsVerifierConfig {
+    /// Path to the RocksDB data directory that serves state cache.
+    #[serde(default = "ProtectiveReadsVerifierConfig::default_db_path")]
+    pub db_path: String,
+    /// Maximum number of L1 batches that can be loaded / processed ahead of the latest verified batch.
+    pub window_size: u32,
+    /// First L1 batch to verify if the verifier has no persisted progress. Must not be the genesis batch.
+    pub first_processed_batch: L1BatchNumber,
+    /// Maximum number of L1 batches executed concurrently. If not set, all batches within `window_size`
+    /// are executed concurrently.
+    pub max_concurrent_batches: Option<usize>,
+    /// Approximate memory budget (in megabytes) for input data of concurrently executed L1 batches.
+    /// If not set, the memory used by executed batches is not limited.
+    pub execution_memory_budget_mb: Option<usize>,
+}
+
+impl ProtectiveReadsVerifierConfig {
+    fn default_db_path() -> String {
+        "./db/protective_reads_verifier".to_owned()
+    }
+
+    pub fn execution_memory_budget_bytes(&self) -> Option<usize> {
+        self.execution_memory_budget_mb
+            .map(|budget| budget.saturating_mul(BYTES_IN_MEGABYTE))
+    }
+}
+
+/// Configuration of the VM runner replaying a range of historical L1 batches.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct VmReplayConfig {
+    /// Path to the RocksDB data directory that serves state cache. The cache must not be ahead
+    /// of the first replayed batch.
+    #[serde(default = "VmReplayConfig::default_db_path")]
+    pub db_path: String,
+    /// Maximum number of L1 batches that can be loaded / processed ahead of the latest replayed batch.
+    pub window_size: u32,
+    /// First replayed L1 batch (inclusive). Must not be the genesis batch.
+    pub first_l1_batch: L1BatchNumber,
+    /// Last replayed L1 batch (inclusive).
+    pub last_l1_batch: L1BatchNumber,
+    /// Maximum number of L1 batches executed concurrently. If not set, all batches within `window_size`
+    /// are executed concurrently.
+    pub max_concurrent_batches: Option<usize>,
+    /// Approximate memory budget (in megabytes) for input data of concurrently executed L1 batches.
+    /// If not set, the memory used by executed batches is not limited.
+    pub execution_memory_budget_mb: Option<usize>,
+}
+
+impl VmReplayConfig {
+    fn default_db_path() -> String {
+        "./db/vm_runner_replay".to_owned()
+    }
+
+    pub fn execution_memory_budget_bytes(&self) -> Option<usize> {
+        self.execution_memory_budget_mb
+            .map(|budget| budget.saturating_mul(BYTES_IN_MEGABYTE))
+    }
+}
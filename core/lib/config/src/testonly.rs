@@ -3,7 +3,7 @@ use std::num::NonZeroUsize;
 use rand::{distributions::Distribution, Rng};
 use zksync_basic_types::{
     basic_fri_types::CircuitIdRoundTuple, commitment::L1BatchCommitmentMode, network::Network,
-    L1ChainId, L2ChainId,
+    L1BatchNumber, L1ChainId, L2ChainId,
 };
 use zksync_consensus_utils::EncodeDist;
 
@@ -46,6 +46,7 @@ impl Distribution<configs::ApiConfig> for EncodeDist {
             prometheus: self.sample(rng),
             healthcheck: self.sample(rng),
             merkle_tree: self.sample(rng),
+            explorer: self.sample(rng),
         }
     }
 }
@@ -59,6 +60,7 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             ws_url: self.sample(rng),
             req_entities_limit: self.sample(rng),
             filters_disabled: self.sample(rng),
+            logs_pagination_enabled: self.sample(rng),
             filters_limit: self.sample(rng),
             subscriptions_limit: self.sample(rng),
             pubsub_polling_interval: self.sample(rng),
@@ -68,6 +70,7 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             account_pks: self.sample_opt(|| self.sample_range(rng).map(|_| rng.gen()).collect()),
             estimate_gas_scale_factor: self.sample(rng),
             estimate_gas_acceptable_overestimation: self.sample(rng),
+            estimate_gas_single_pass: self.sample(rng),
             max_tx_size: self.sample(rng),
             vm_execution_cache_misses_limit: self.sample(rng),
             vm_concurrency_limit: self.sample(rng),
@@ -93,6 +96,13 @@ impl Distribution<configs::api::Web3JsonRpcConfig> for EncodeDist {
             tree_api_url: self.sample(rng),
             mempool_cache_update_interval: self.sample(rng),
             mempool_cache_size: self.sample(rng),
+            response_cache_size_mb: self.sample(rng),
+            response_cache_ttl_sec: self.sample(rng),
+            response_cache_redis_url: self.sample_opt(|| {
+                format!("redis://127.0.0.1:{}/", rng.gen::<u16>())
+                    .parse()
+                    .unwrap()
+            }),
             whitelisted_tokens_for_aa: self.sample_range(rng).map(|_| rng.gen()).collect(),
         }
     }
@@ -121,6 +131,16 @@ impl Distribution<configs::api::MerkleTreeApiConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::api::MerkleTreeApiConfig {
         configs::api::MerkleTreeApiConfig {
             port: self.sample(rng),
+            grpc_port: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::api::ExplorerApiConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::api::ExplorerApiConfig {
+        configs::api::ExplorerApiConfig {
+            port: self.sample(rng),
+            max_page_size: self.sample(rng),
         }
     }
 }
@@ -131,6 +151,20 @@ impl Distribution<configs::PrometheusConfig> for EncodeDist {
             listener_port: self.sample(rng),
             pushgateway_url: self.sample(rng),
             push_interval_ms: self.sample(rng),
+            export_mode: self.sample(rng),
+            otlp_endpoint: self.sample(rng),
+            otlp_export_interval_ms: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::utils::PrometheusExportMode> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::utils::PrometheusExportMode {
+        type T = configs::utils::PrometheusExportMode;
+        match rng.gen_range(0..3) {
+            0 => T::Pull,
+            1 => T::Push,
+            _ => T::Otlp,
         }
     }
 }
@@ -153,6 +187,8 @@ impl Distribution<configs::chain::StateKeeperConfig> for EncodeDist {
             block_commit_deadline_ms: self.sample(rng),
             l2_block_commit_deadline_ms: self.sample(rng),
             l2_block_seal_queue_capacity: self.sample(rng),
+            l2_block_seal_queue_max_capacity: self.sample(rng),
+            l2_block_seal_queue_target_latency_ms: self.sample(rng),
             l2_block_max_payload_size: self.sample(rng),
             max_single_tx_gas: self.sample(rng),
             max_allowed_l2_tx_gas_limit: self.sample(rng),
@@ -222,6 +258,8 @@ impl Distribution<configs::ContractVerifierConfig> for EncodeDist {
             threads_per_server: self.sample(rng),
             port: self.sample(rng),
             url: self.sample(rng),
+            compilers_download_url: self.sample(rng),
+            max_compiler_candidates: self.sample(rng),
         }
     }
 }
@@ -347,6 +385,18 @@ impl Distribution<configs::eth_sender::PubdataSendingMode> for EncodeDist {
     }
 }
 
+impl Distribution<configs::eth_sender::BlobFeeStrategy> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::eth_sender::BlobFeeStrategy {
+        type T = configs::eth_sender::BlobFeeStrategy;
+        match rng.gen_range(0..4) {
+            0 => T::Median,
+            1 => T::Percentile,
+            2 => T::Ewma,
+            _ => T::Capped,
+        }
+    }
+}
+
 impl Distribution<configs::eth_sender::SenderConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::eth_sender::SenderConfig {
         configs::eth_sender::SenderConfig {
@@ -367,6 +417,10 @@ impl Distribution<configs::eth_sender::SenderConfig> for EncodeDist {
             l1_batch_min_age_before_execute_seconds: self.sample(rng),
             max_acceptable_priority_fee_in_gwei: self.sample(rng),
             pubdata_sending_mode: PubdataSendingMode::Calldata,
+            fee_bump_percent: self.sample(rng),
+            resubmission_interval_blocks: self.sample(rng),
+            stuck_tx_threshold_blocks: self.sample(rng),
+            repair_nonce_gaps: self.sample(rng),
         }
     }
 }
@@ -386,6 +440,10 @@ impl Distribution<configs::eth_sender::GasAdjusterConfig> for EncodeDist {
             num_samples_for_blob_base_fee_estimate: self.sample(rng),
             internal_pubdata_pricing_multiplier: self.sample(rng),
             max_blob_base_fee: self.sample(rng),
+            blob_fee_strategy: self.sample(rng),
+            blob_fee_percentile: self.sample(rng),
+            blob_fee_ewma_alpha: self.sample(rng),
+            blob_fee_cap: self.sample(rng),
         }
     }
 }
@@ -395,6 +453,18 @@ impl Distribution<configs::EthWatchConfig> for EncodeDist {
         configs::EthWatchConfig {
             confirmations_for_eth_event: self.sample(rng),
             eth_node_poll_interval: self.sample(rng),
+            additional_sources: self.sample_collect(rng),
+        }
+    }
+}
+
+impl Distribution<configs::EthWatchSourceConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::EthWatchSourceConfig {
+        configs::EthWatchSourceConfig {
+            diamond_proxy_addr: rng.gen(),
+            governance_addr: rng.gen(),
+            state_transition_manager_addr: self.sample_opt(|| rng.gen()),
+            confirmations_for_eth_event: self.sample(rng),
         }
     }
 }
@@ -449,6 +519,7 @@ impl Distribution<configs::FriProverGatewayConfig> for EncodeDist {
         configs::FriProverGatewayConfig {
             api_url: self.sample(rng),
             api_poll_duration_secs: self.sample(rng),
+            streaming_enabled: self.sample(rng),
             prometheus_listener_port: self.sample(rng),
             prometheus_pushgateway_url: self.sample(rng),
             prometheus_push_interval_ms: self.sample(rng),
@@ -547,6 +618,9 @@ impl Distribution<configs::FriWitnessGeneratorConfig> for EncodeDist {
             max_attempts: self.sample(rng),
             last_l1_batch_to_process: self.sample(rng),
             shall_save_to_public_bucket: self.sample(rng),
+            artifacts_memory_budget_mb: self.sample(rng),
+            artifacts_disk_budget_mb: self.sample(rng),
+            spill_dir: self.sample(rng),
         }
     }
 }
@@ -582,6 +656,11 @@ impl Distribution<configs::house_keeper::HouseKeeperConfig> for EncodeDist {
             prover_job_archiver_archive_after_secs: self.sample(rng),
             fri_gpu_prover_archiver_archiving_interval_ms: self.sample(rng),
             fri_gpu_prover_archiver_archive_after_secs: self.sample(rng),
+            prover_job_monitor_interval_ms: self.sample(rng),
+            prover_job_monitor_stuck_job_threshold_secs: self.sample(rng),
+            witness_generation_sla_secs: self.sample(rng),
+            proving_sla_secs: self.sample(rng),
+            proof_compression_sla_secs: self.sample(rng),
         }
     }
 }
@@ -589,7 +668,7 @@ impl Distribution<configs::house_keeper::HouseKeeperConfig> for EncodeDist {
 impl Distribution<configs::object_store::ObjectStoreMode> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::object_store::ObjectStoreMode {
         type T = configs::object_store::ObjectStoreMode;
-        match rng.gen_range(0..4) {
+        match rng.gen_range(0..7) {
             0 => T::GCS {
                 bucket_base_url: self.sample(rng),
             },
@@ -600,6 +679,19 @@ impl Distribution<configs::object_store::ObjectStoreMode> for EncodeDist {
             2 => T::FileBacked {
                 file_backed_base_path: self.sample(rng),
             },
+            3 => T::HttpMirror {
+                http_base_url: self.sample(rng),
+            },
+            4 => T::AzureBlob {
+                azure_account_name: self.sample(rng),
+                azure_container_name: self.sample(rng),
+                azure_endpoint: self.sample(rng),
+            },
+            5 => T::S3 {
+                s3_bucket_name: self.sample(rng),
+                s3_region: self.sample(rng),
+                s3_endpoint: self.sample(rng),
+            },
             _ => T::GCSAnonymousReadOnly {
                 bucket_base_url: self.sample(rng),
             },
@@ -612,6 +704,9 @@ impl Distribution<configs::ObjectStoreConfig> for EncodeDist {
         configs::ObjectStoreConfig {
             mode: self.sample(rng),
             max_retries: self.sample(rng),
+            integrity_envelope: self.sample(rng),
+            encryption_keys_file_path: self.sample(rng),
+            allow_plaintext_reads: self.sample(rng),
         }
     }
 }
@@ -621,6 +716,9 @@ impl Distribution<configs::ProofDataHandlerConfig> for EncodeDist {
         configs::ProofDataHandlerConfig {
             http_port: self.sample(rng),
             proof_generation_timeout_in_secs: self.sample(rng),
+            upload_chunk_size_limit_bytes: self.sample(rng),
+            upload_session_timeout_in_secs: self.sample(rng),
+            reconciliation_interval_in_secs: self.sample(rng),
         }
     }
 }
@@ -635,6 +733,31 @@ impl Distribution<configs::SnapshotsCreatorConfig> for EncodeDist {
     }
 }
 
+impl Distribution<configs::ProtectiveReadsVerifierConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::ProtectiveReadsVerifierConfig {
+        configs::ProtectiveReadsVerifierConfig {
+            db_path: self.sample(rng),
+            window_size: self.sample(rng),
+            first_processed_batch: L1BatchNumber(rng.gen()),
+            max_concurrent_batches: self.sample(rng),
+            execution_memory_budget_mb: self.sample(rng),
+        }
+    }
+}
+
+impl Distribution<configs::VmReplayConfig> for EncodeDist {
+    fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::VmReplayConfig {
+        configs::VmReplayConfig {
+            db_path: self.sample(rng),
+            window_size: self.sample(rng),
+            first_l1_batch: L1BatchNumber(rng.gen()),
+            last_l1_batch: L1BatchNumber(rng.gen()),
+            max_concurrent_batches: self.sample(rng),
+            execution_memory_budget_mb: self.sample(rng),
+        }
+    }
+}
+
 impl Distribution<configs::ObservabilityConfig> for EncodeDist {
     fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> configs::ObservabilityConfig {
         configs::ObservabilityConfig {
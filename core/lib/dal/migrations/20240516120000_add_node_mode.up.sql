@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS node_mode
+(
+    -- Ensures that the table contains at most one row
+    id         BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    mode       TEXT      NOT NULL CHECK (mode IN ('Archive', 'Pruned')),
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS pruning_protected_l1_batches
+(
+    start_l1_batch BIGINT    NOT NULL,
+    end_l1_batch   BIGINT    NOT NULL CHECK (end_l1_batch >= start_l1_batch),
+    created_at     TIMESTAMP NOT NULL,
+    PRIMARY KEY (start_l1_batch, end_l1_batch)
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS vm_runner_checkpoints
+(
+    component       TEXT      NOT NULL,
+    l1_batch_number BIGINT    NOT NULL,
+    phase           TEXT      NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL,
+    PRIMARY KEY (component, l1_batch_number)
+);
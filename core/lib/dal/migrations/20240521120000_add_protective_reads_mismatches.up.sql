@@ -0,0 +1,10 @@
+CREATE TABLE IF NOT EXISTS protective_reads_mismatches
+(
+    l1_batch_number BIGINT    NOT NULL,
+    address         BYTEA     NOT NULL,
+    key             BYTEA     NOT NULL,
+    -- Either 'missing' (recomputed, but not persisted by the state keeper), or 'unexpected' (persisted, but not recomputed).
+    kind            TEXT      NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    PRIMARY KEY (l1_batch_number, address, key)
+);
@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS api_filters
+(
+    id               BYTEA PRIMARY KEY,
+    -- Serialized filter, including its current polling position.
+    filter           JSONB     NOT NULL,
+    created_at       TIMESTAMP NOT NULL,
+    last_accessed_at TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS api_filters_last_accessed_at_idx ON api_filters (last_accessed_at);
@@ -0,0 +1,42 @@
+-- Converts a partitioned table back to a regular one. Unlike the up migration, this copies all data.
+CREATE FUNCTION pg_temp.unpartition(tbl TEXT) RETURNS VOID AS $$
+DECLARE
+    partitioned_tbl TEXT := tbl || '_partitioned';
+    pk_def TEXT;
+    fk_defs TEXT[];
+    fk_def TEXT;
+    index_defs TEXT[];
+    index_def TEXT;
+BEGIN
+    SELECT pg_get_constraintdef(oid) INTO pk_def
+    FROM pg_constraint
+    WHERE conrelid = tbl::regclass AND contype = 'p';
+    SELECT array_agg(format('CONSTRAINT %I %s', conname, pg_get_constraintdef(oid))) INTO fk_defs
+    FROM pg_constraint
+    WHERE conrelid = tbl::regclass AND contype = 'f';
+    SELECT array_agg(indexdef) INTO index_defs
+    FROM pg_indexes
+    WHERE schemaname = 'public' AND tablename = tbl AND indexname <> tbl || '_pkey';
+
+    EXECUTE format('ALTER TABLE %I RENAME TO %I', tbl, partitioned_tbl);
+    EXECUTE format('CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS)', tbl, partitioned_tbl);
+    EXECUTE format('INSERT INTO %I SELECT * FROM %I', tbl, partitioned_tbl);
+    -- Also drops all partitions together with their indexes.
+    EXECUTE format('DROP TABLE %I', partitioned_tbl);
+
+    EXECUTE format('ALTER TABLE %I ADD %s', tbl, pk_def);
+    FOREACH index_def IN ARRAY COALESCE(index_defs, '{}') LOOP
+        EXECUTE index_def;
+    END LOOP;
+    FOREACH fk_def IN ARRAY COALESCE(fk_defs, '{}') LOOP
+        EXECUTE format('ALTER TABLE %I ADD %s', tbl, fk_def);
+    END LOOP;
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT pg_temp.unpartition('events');
+SELECT pg_temp.unpartition('storage_logs');
+
+DROP PROCEDURE IF EXISTS drop_l2_block_partition;
+DROP FUNCTION IF EXISTS create_l2_block_partition;
+DROP TABLE IF EXISTS l2_block_partitions;
@@ -0,0 +1,135 @@
+-- Bookkeeping for partitions of tables partitioned by L2 block number. Only partitions covering a specific
+-- L2 block range are recorded; default partitions are not.
+CREATE TABLE IF NOT EXISTS l2_block_partitions
+(
+    partition_name TEXT PRIMARY KEY,
+    table_name     TEXT      NOT NULL,
+    -- Inclusive lower bound of the partition.
+    from_miniblock BIGINT    NOT NULL,
+    -- Exclusive upper bound of the partition.
+    to_miniblock   BIGINT    NOT NULL,
+    created_at     TIMESTAMP NOT NULL
+);
+CREATE INDEX IF NOT EXISTS l2_block_partitions_table_name_idx ON l2_block_partitions (table_name, from_miniblock);
+
+-- Converts a table to a table partitioned by `miniblock_number`. Existing data is not copied; instead, the existing table
+-- is attached as the `*_legacy` partition covering all L2 blocks present in it. The partition range is proven by a `CHECK`
+-- constraint validated before attaching, so that attaching doesn't need to scan the table under an exclusive lock.
+-- Partitions for newer L2 blocks are managed by the node; rows not covered by these partitions go to the `*_default`
+-- partition.
+CREATE FUNCTION pg_temp.partition_by_miniblock_number(tbl TEXT) RETURNS VOID AS $$
+DECLARE
+    legacy_tbl TEXT := tbl || '_legacy';
+    upper_bound BIGINT;
+    range_check TEXT := tbl || '_legacy_range_check';
+    pk_def TEXT;
+    fk_defs TEXT[];
+    fk_def TEXT;
+    index_defs TEXT[];
+    index_def TEXT;
+    index_name TEXT;
+BEGIN
+    EXECUTE format('SELECT COALESCE(MAX(miniblock_number) + 1, 0) FROM %I', tbl) INTO upper_bound;
+    SELECT pg_get_constraintdef(oid) INTO pk_def
+    FROM pg_constraint
+    WHERE conrelid = tbl::regclass AND contype = 'p';
+    -- Foreign keys are not copied by `CREATE TABLE ... (LIKE ...)`, so they are recreated on the partitioned table.
+    SELECT array_agg(format('CONSTRAINT %I %s', conname, pg_get_constraintdef(oid))) INTO fk_defs
+    FROM pg_constraint
+    WHERE conrelid = tbl::regclass AND contype = 'f';
+    SELECT array_agg(indexdef) INTO index_defs
+    FROM pg_indexes
+    WHERE schemaname = 'public' AND tablename = tbl AND indexname <> tbl || '_pkey';
+
+    -- Free up the table and index names so that they can be used by the partitioned table.
+    FOR index_name IN
+        SELECT indexname FROM pg_indexes
+        WHERE schemaname = 'public' AND tablename = tbl AND indexname <> tbl || '_pkey'
+    LOOP
+        EXECUTE format('ALTER INDEX %I RENAME TO %I', index_name, index_name || '_legacy');
+    END LOOP;
+    EXECUTE format('ALTER TABLE %I RENAME CONSTRAINT %I TO %I', tbl, tbl || '_pkey', legacy_tbl || '_pkey');
+    EXECUTE format('ALTER TABLE %I RENAME TO %I', tbl, legacy_tbl);
+
+    EXECUTE format(
+        'CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS) PARTITION BY RANGE (miniblock_number)',
+        tbl, legacy_tbl
+    );
+    EXECUTE format('ALTER TABLE %I ADD %s', tbl, pk_def);
+    EXECUTE format(
+        'ALTER TABLE %I ADD CONSTRAINT %I CHECK (miniblock_number IS NOT NULL AND miniblock_number < %s) NOT VALID',
+        legacy_tbl, range_check, upper_bound
+    );
+    EXECUTE format('ALTER TABLE %I VALIDATE CONSTRAINT %I', legacy_tbl, range_check);
+    EXECUTE format(
+        'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (MINVALUE) TO (%s)',
+        tbl, legacy_tbl, upper_bound
+    );
+    -- The constraint is implied by the partition bounds once the partition is attached.
+    EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', legacy_tbl, range_check);
+    -- Indexes of the legacy partition are attached to the created indexes rather than being rebuilt.
+    FOREACH index_def IN ARRAY COALESCE(index_defs, '{}') LOOP
+        EXECUTE index_def;
+    END LOOP;
+    EXECUTE format('CREATE TABLE %I PARTITION OF %I DEFAULT', tbl || '_default', tbl);
+    -- Foreign keys are propagated to all partitions; the existing foreign keys of the legacy partition are reused.
+    FOREACH fk_def IN ARRAY COALESCE(fk_defs, '{}') LOOP
+        EXECUTE format('ALTER TABLE %I ADD %s', tbl, fk_def);
+    END LOOP;
+
+    INSERT INTO l2_block_partitions (partition_name, table_name, from_miniblock, to_miniblock, created_at)
+    VALUES (legacy_tbl, tbl, 0, upper_bound, NOW());
+END;
+$$ LANGUAGE plpgsql;
+
+SELECT pg_temp.partition_by_miniblock_number('events');
+SELECT pg_temp.partition_by_miniblock_number('storage_logs');
+
+-- Creates a partition of a table partitioned by L2 block number covering `[from_miniblock, to_miniblock)` and records it
+-- in `l2_block_partitions`. Rows in this range are moved from the default partition to the created partition. Returns
+-- the name of the created partition.
+CREATE OR REPLACE FUNCTION create_l2_block_partition(tbl TEXT, from_miniblock BIGINT, to_miniblock BIGINT)
+RETURNS TEXT AS $$
+DECLARE
+    partition_tbl TEXT := tbl || '_' || from_miniblock;
+    range_check TEXT := tbl || '_' || from_miniblock || '_range_check';
+BEGIN
+    IF tbl NOT IN ('events', 'storage_logs') THEN
+        RAISE EXCEPTION 'Table `%` is not partitioned by L2 block number', tbl;
+    END IF;
+    IF from_miniblock < 0 OR to_miniblock <= from_miniblock THEN
+        RAISE EXCEPTION 'Invalid L2 block range for partition: [%, %)', from_miniblock, to_miniblock;
+    END IF;
+
+    EXECUTE format('CREATE TABLE %I (LIKE %I INCLUDING DEFAULTS)', partition_tbl, tbl);
+    EXECUTE format(
+        'WITH moved AS (DELETE FROM %I WHERE miniblock_number >= $1 AND miniblock_number < $2 RETURNING *) '
+        'INSERT INTO %I SELECT * FROM moved',
+        tbl || '_default', partition_tbl
+    ) USING from_miniblock, to_miniblock;
+    EXECUTE format(
+        'ALTER TABLE %I ADD CONSTRAINT %I CHECK (miniblock_number IS NOT NULL AND miniblock_number >= %s AND miniblock_number < %s)',
+        partition_tbl, range_check, from_miniblock, to_miniblock
+    );
+    EXECUTE format(
+        'ALTER TABLE %I ATTACH PARTITION %I FOR VALUES FROM (%s) TO (%s)',
+        tbl, partition_tbl, from_miniblock, to_miniblock
+    );
+    EXECUTE format('ALTER TABLE %I DROP CONSTRAINT %I', partition_tbl, range_check);
+
+    INSERT INTO l2_block_partitions (partition_name, table_name, from_miniblock, to_miniblock, created_at)
+    VALUES (partition_tbl, tbl, from_miniblock, to_miniblock, NOW());
+    RETURN partition_tbl;
+END;
+$$ LANGUAGE plpgsql;
+
+-- Drops a partition recorded in `l2_block_partitions` together with all its data.
+CREATE OR REPLACE PROCEDURE drop_l2_block_partition(partition_tbl TEXT) AS $$
+BEGIN
+    DELETE FROM l2_block_partitions WHERE partition_name = partition_tbl;
+    IF NOT FOUND THEN
+        RAISE EXCEPTION 'Partition `%` is not managed', partition_tbl;
+    END IF;
+    EXECUTE format('DROP TABLE %I', partition_tbl);
+END;
+$$ LANGUAGE plpgsql;
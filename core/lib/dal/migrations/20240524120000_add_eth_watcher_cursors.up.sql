@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS eth_watcher_cursors
+(
+    -- Address of the L1 contract the events are polled from.
+    contract_address        BYTEA PRIMARY KEY,
+    last_processed_l1_block BIGINT    NOT NULL,
+    created_at              TIMESTAMP NOT NULL,
+    updated_at              TIMESTAMP NOT NULL
+);
@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS bytecodes_verification_info;
+
+DELETE FROM contract_verification_requests WHERE contract_address IS NULL;
+ALTER TABLE contract_verification_requests DROP CONSTRAINT IF EXISTS contract_verification_requests_target_check;
+ALTER TABLE contract_verification_requests DROP COLUMN IF EXISTS bytecode_hash;
+ALTER TABLE contract_verification_requests ALTER COLUMN contract_address SET NOT NULL;
@@ -0,0 +1,13 @@
+-- Requests may target either a deployed contract or a bytecode known only as a factory dependency.
+ALTER TABLE contract_verification_requests ALTER COLUMN contract_address DROP NOT NULL;
+ALTER TABLE contract_verification_requests ADD COLUMN IF NOT EXISTS bytecode_hash BYTEA;
+ALTER TABLE contract_verification_requests ADD CONSTRAINT contract_verification_requests_target_check
+    CHECK ((contract_address IS NULL) <> (bytecode_hash IS NULL));
+
+CREATE TABLE IF NOT EXISTS bytecodes_verification_info
+(
+    bytecode_hash     BYTEA PRIMARY KEY,
+    verification_info JSONB     NOT NULL,
+    created_at        TIMESTAMP NOT NULL,
+    updated_at        TIMESTAMP NOT NULL
+);
@@ -0,0 +1,3 @@
+DROP TABLE IF EXISTS proof_upload_sessions;
+
+ALTER TABLE proof_generation_details DROP COLUMN IF EXISTS proof_blob_sha256;
@@ -0,0 +1,13 @@
+-- Checksum of the serialized proof as stored in the object store; `NULL` for proofs saved before it was introduced.
+ALTER TABLE proof_generation_details ADD COLUMN IF NOT EXISTS proof_blob_sha256 BYTEA;
+
+CREATE TABLE IF NOT EXISTS proof_upload_sessions
+(
+    l1_batch_number BIGINT PRIMARY KEY REFERENCES l1_batches (number) ON DELETE CASCADE,
+    total_size      BIGINT    NOT NULL,
+    sha256          BYTEA     NOT NULL,
+    received_bytes  BIGINT    NOT NULL,
+    chunk_offsets   BIGINT[]  NOT NULL,
+    created_at      TIMESTAMP NOT NULL,
+    updated_at      TIMESTAMP NOT NULL
+);
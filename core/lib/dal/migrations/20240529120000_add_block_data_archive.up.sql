@@ -0,0 +1,7 @@
+-- L1 batches whose block data (blocks, transactions and receipts) was exported to the block data archive.
+-- Has no foreign key to `l1_batches` so that it's retained after the batches are pruned.
+CREATE TABLE IF NOT EXISTS block_data_archive
+(
+    l1_batch_number BIGINT PRIMARY KEY,
+    created_at      TIMESTAMP NOT NULL
+);
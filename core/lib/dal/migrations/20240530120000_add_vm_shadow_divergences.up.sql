@@ -0,0 +1,16 @@
+-- Divergences between the canonical VM and a shadow VM version detected when re-executing synced L1 batches.
+-- Has no foreign key to `l1_batches` so that reports are retained after the batches are pruned.
+CREATE TABLE IF NOT EXISTS vm_shadow_divergences
+(
+    id                   BIGSERIAL PRIMARY KEY,
+    l1_batch_number      BIGINT    NOT NULL,
+    tx_hash              BYTEA,
+    canonical_vm_version TEXT      NOT NULL,
+    shadow_vm_version    TEXT      NOT NULL,
+    kind                 TEXT      NOT NULL,
+    details              JSONB     NOT NULL,
+    created_at           TIMESTAMP NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS vm_shadow_divergences_l1_batch_number_idx
+    ON vm_shadow_divergences (l1_batch_number);
@@ -0,0 +1,6 @@
+-- SHA-256 checksums of storage log chunks as stored in the object store, aligned with `storage_logs_filepaths`.
+-- An empty value means that the chunk is not produced yet, or that it was produced before checksums were introduced.
+ALTER TABLE snapshots ADD COLUMN IF NOT EXISTS storage_logs_checksums BYTEA[];
+UPDATE snapshots
+SET storage_logs_checksums = ARRAY_FILL(''::BYTEA, ARRAY[CARDINALITY(storage_logs_filepaths)]);
+ALTER TABLE snapshots ALTER COLUMN storage_logs_checksums SET NOT NULL;
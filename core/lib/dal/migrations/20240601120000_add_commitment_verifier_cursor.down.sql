@@ -0,0 +1 @@
+DROP TABLE IF EXISTS commitment_verifier_cursor;
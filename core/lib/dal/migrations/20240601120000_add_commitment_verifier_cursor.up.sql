@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS commitment_verifier_cursor
+(
+    -- Ensures that the table contains at most one row
+    id                    BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    last_checked_l1_batch BIGINT    NOT NULL,
+    created_at            TIMESTAMP NOT NULL,
+    updated_at            TIMESTAMP NOT NULL
+);
@@ -0,0 +1,290 @@
+//! Web3 API filters persisted in Postgres, so that they can be shared among API server replicas.
+
+use std::time::Duration;
+
+use zksync_db_connection::{
+    connection::Connection, error::DalResult, instrument::InstrumentExt,
+    utils::pg_interval_from_duration,
+};
+use zksync_types::H256;
+
+use crate::Core;
+
+#[derive(Debug)]
+pub struct ApiFiltersDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl ApiFiltersDal<'_, '_> {
+    /// Inserts a new filter. Returns `false` if a filter with the same ID already exists.
+    pub async fn insert_filter(&mut self, id: H256, filter: &serde_json::Value) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            INSERT INTO
+                api_filters (id, filter, created_at, last_accessed_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (id) DO NOTHING
+            "#,
+            id.as_bytes(),
+            filter
+        )
+        .instrument("insert_filter")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Returns the filter with the specified ID and marks it as accessed. Filters that weren't accessed
+    /// for longer than `ttl` are considered expired and are not returned.
+    pub async fn get_filter(
+        &mut self,
+        id: H256,
+        ttl: Duration,
+    ) -> DalResult<Option<serde_json::Value>> {
+        let ttl = pg_interval_from_duration(ttl);
+        let row = sqlx::query!(
+            r#"
+            UPDATE api_filters
+            SET
+                last_accessed_at = NOW()
+            WHERE
+                id = $1
+                AND last_accessed_at > NOW() - $2::INTERVAL
+            RETURNING
+                filter
+            "#,
+            id.as_bytes(),
+            &ttl
+        )
+        .instrument("get_filter")
+        .with_arg("id", &id)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| row.filter))
+    }
+
+    /// Atomically replaces the filter with the specified ID if it's equal to `prev_filter`. Returns `false`
+    /// if the filter doesn't exist or was concurrently updated (e.g., by another API server polling the same filter).
+    pub async fn update_filter(
+        &mut self,
+        id: H256,
+        prev_filter: &serde_json::Value,
+        filter: &serde_json::Value,
+    ) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE api_filters
+            SET
+                filter = $3,
+                last_accessed_at = NOW()
+            WHERE
+                id = $1
+                AND filter = $2
+            "#,
+            id.as_bytes(),
+            prev_filter,
+            filter
+        )
+        .instrument("update_filter")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Removes the filter with the specified ID. Returns `false` if the filter doesn't exist.
+    pub async fn remove_filter(&mut self, id: H256) -> DalResult<bool> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM api_filters
+            WHERE
+                id = $1
+            "#,
+            id.as_bytes()
+        )
+        .instrument("remove_filter")
+        .with_arg("id", &id)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    /// Removes least recently accessed filters so that at most `retained_count` filters remain.
+    /// Returns the number of removed filters.
+    pub async fn remove_least_recently_used_filters(
+        &mut self,
+        retained_count: usize,
+    ) -> DalResult<u64> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM api_filters
+            WHERE
+                id IN (
+                    SELECT
+                        id
+                    FROM
+                        api_filters
+                    ORDER BY
+                        last_accessed_at DESC
+                    OFFSET
+                        $1
+                )
+            "#,
+            retained_count as i64
+        )
+        .instrument("remove_least_recently_used_filters")
+        .with_arg("retained_count", &retained_count)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    /// Removes filters that weren't accessed for longer than `ttl`. Returns the number of removed filters.
+    pub async fn remove_expired_filters(&mut self, ttl: Duration) -> DalResult<u64> {
+        let pg_ttl = pg_interval_from_duration(ttl);
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM api_filters
+            WHERE
+                last_accessed_at <= NOW() - $1::INTERVAL
+            "#,
+            &pg_ttl
+        )
+        .instrument("remove_expired_filters")
+        .with_arg("ttl", &ttl)
+        .execute(self.storage)
+        .await?;
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn filters_basics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let ttl = Duration::from_secs(60);
+        let id = H256::repeat_byte(1);
+        let filter = serde_json::json!({ "type": "blocks", "from_block": 1 });
+
+        assert!(conn
+            .api_filters_dal()
+            .insert_filter(id, &filter)
+            .await
+            .unwrap());
+        assert!(!conn
+            .api_filters_dal()
+            .insert_filter(id, &filter)
+            .await
+            .unwrap());
+        let stored_filter = conn.api_filters_dal().get_filter(id, ttl).await.unwrap();
+        assert_eq!(stored_filter, Some(filter));
+
+        let new_filter = serde_json::json!({ "type": "blocks", "from_block": 5 });
+        assert!(conn
+            .api_filters_dal()
+            .update_filter(id, &filter, &new_filter)
+            .await
+            .unwrap());
+        let stored_filter = conn.api_filters_dal().get_filter(id, ttl).await.unwrap();
+        assert_eq!(stored_filter, Some(new_filter.clone()));
+
+        // Updating a concurrently modified filter should fail.
+        let concurrent_filter = serde_json::json!({ "type": "blocks", "from_block": 3 });
+        assert!(!conn
+            .api_filters_dal()
+            .update_filter(id, &filter, &concurrent_filter)
+            .await
+            .unwrap());
+        let stored_filter = conn.api_filters_dal().get_filter(id, ttl).await.unwrap();
+        assert_eq!(stored_filter, Some(new_filter));
+
+        let missing_id = H256::repeat_byte(2);
+        let missing_filter = conn
+            .api_filters_dal()
+            .get_filter(missing_id, ttl)
+            .await
+            .unwrap();
+        assert_eq!(missing_filter, None);
+        assert!(!conn
+            .api_filters_dal()
+            .remove_filter(missing_id)
+            .await
+            .unwrap());
+
+        assert!(conn.api_filters_dal().remove_filter(id).await.unwrap());
+        let stored_filter = conn.api_filters_dal().get_filter(id, ttl).await.unwrap();
+        assert_eq!(stored_filter, None);
+    }
+
+    #[tokio::test]
+    async fn removing_least_recently_used_filters() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let ttl = Duration::from_secs(60);
+        let filter = serde_json::json!({ "type": "blocks", "from_block": 1 });
+        let ids: Vec<_> = (1..=3).map(H256::repeat_byte).collect();
+        for &id in &ids {
+            conn.api_filters_dal()
+                .insert_filter(id, &filter)
+                .await
+                .unwrap();
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        // Access the first filter so that it becomes the most recently used one.
+        conn.api_filters_dal()
+            .get_filter(ids[0], ttl)
+            .await
+            .unwrap();
+
+        let removed_count = conn
+            .api_filters_dal()
+            .remove_least_recently_used_filters(2)
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 1);
+        for (i, &id) in ids.iter().enumerate() {
+            let stored_filter = conn.api_filters_dal().get_filter(id, ttl).await.unwrap();
+            assert_eq!(stored_filter.is_some(), i != 1, "{i}");
+        }
+    }
+
+    #[tokio::test]
+    async fn removing_expired_filters() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let id = H256::repeat_byte(1);
+        let filter = serde_json::json!({ "type": "blocks", "from_block": 1 });
+        conn.api_filters_dal()
+            .insert_filter(id, &filter)
+            .await
+            .unwrap();
+
+        let removed_count = conn
+            .api_filters_dal()
+            .remove_expired_filters(Duration::from_secs(60))
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 0);
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        let expired_filter = conn
+            .api_filters_dal()
+            .get_filter(id, Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(expired_filter, None);
+        let removed_count = conn
+            .api_filters_dal()
+            .remove_expired_filters(Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(removed_count, 1);
+    }
+}
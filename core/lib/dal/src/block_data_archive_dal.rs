@@ -0,0 +1,124 @@
+//! Bookkeeping for the block data archive, i.e. per-L1 batch block data exported to an object store
+//! so that it can be served after it's pruned from Postgres.
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::{L1BatchNumber, L2BlockNumber};
+
+use crate::Core;
+
+#[derive(Debug)]
+pub struct BlockDataArchiveDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl BlockDataArchiveDal<'_, '_> {
+    /// Marks block data for the specified L1 batch as exported to the archive.
+    pub async fn insert_archived_l1_batch(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                block_data_archive (l1_batch_number, created_at)
+            VALUES
+                ($1, NOW())
+            ON CONFLICT (l1_batch_number) DO NOTHING
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("insert_archived_l1_batch")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the number of the latest L1 batch exported to the archive.
+    pub async fn get_last_archived_l1_batch(&mut self) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                block_data_archive
+            "#
+        )
+        .instrument("get_last_archived_l1_batch")
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns a lower bound for the L1 batch containing the specified L2 block. Unlike L2 blocks and L1 batches,
+    /// transactions are retained after pruning (albeit without most of their data), so this method works
+    /// for pruned L2 blocks as well.
+    ///
+    /// The bound is the L1 batch of the latest transaction in an L2 block at or before the specified one.
+    /// Since all L1 batches except for genesis contain transactions, the L2 block belongs either to this
+    /// L1 batch or to the next one (the latter only if it's an empty L2 block starting the batch).
+    pub async fn get_l1_batch_lower_bound_for_l2_block(
+        &mut self,
+        l2_block_number: L2BlockNumber,
+    ) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number
+            FROM
+                transactions
+            WHERE
+                miniblock_number <= $1
+                AND l1_batch_number IS NOT NULL
+            ORDER BY
+                miniblock_number DESC,
+                index_in_block DESC
+            LIMIT
+                1
+            "#,
+            i64::from(l2_block_number.0)
+        )
+        .instrument("get_l1_batch_lower_bound_for_l2_block")
+        .with_arg("l2_block_number", &l2_block_number)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row
+            .and_then(|row| row.l1_batch_number)
+            .map(|number| L1BatchNumber(number as u32)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn archived_l1_batches_basics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.block_data_archive_dal();
+        assert_eq!(dal.get_last_archived_l1_batch().await.unwrap(), None);
+
+        dal.insert_archived_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        dal.insert_archived_l1_batch(L1BatchNumber(2))
+            .await
+            .unwrap();
+        // Repeated insertion is a no-op.
+        dal.insert_archived_l1_batch(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_last_archived_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(2))
+        );
+
+        let lower_bound = dal
+            .get_l1_batch_lower_bound_for_l2_block(L2BlockNumber(10))
+            .await
+            .unwrap();
+        assert_eq!(lower_bound, None);
+    }
+}
@@ -1708,6 +1708,29 @@ impl BlocksDal<'_, '_> {
         .map(|hash| H256::from_slice(&hash)))
     }
 
+    pub async fn get_l1_batch_commitment(
+        &mut self,
+        number: L1BatchNumber,
+    ) -> DalResult<Option<H256>> {
+        Ok(sqlx::query!(
+            r#"
+            SELECT
+                commitment
+            FROM
+                l1_batches
+            WHERE
+                number = $1
+            "#,
+            i64::from(number.0)
+        )
+        .instrument("get_l1_batch_commitment")
+        .with_arg("number", &number)
+        .fetch_optional(self.storage)
+        .await?
+        .and_then(|row| row.commitment)
+        .map(|commitment| H256::from_slice(&commitment)))
+    }
+
     pub async fn get_l1_batch_state_root_and_timestamp(
         &mut self,
         number: L1BatchNumber,
@@ -1,3 +1,5 @@
+use std::ops;
+
 use zksync_db_connection::{
     connection::Connection, error::DalResult, instrument::InstrumentExt, interpolate_query,
     match_query_as,
@@ -599,6 +601,60 @@ impl BlocksWeb3Dal<'_, '_> {
         Ok(result)
     }
 
+    /// Returns effective priority fees and gas used for transactions in the specified L2 block range
+    /// (inclusive). Data is grouped by L2 block in the ascending block number order; L2 blocks without transactions
+    /// are omitted.
+    pub async fn get_fee_history_rewards_data(
+        &mut self,
+        block_range: ops::RangeInclusive<L2BlockNumber>,
+    ) -> DalResult<Vec<(L2BlockNumber, Vec<(U256, u64)>)>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                transactions.miniblock_number AS "miniblock_number!",
+                transactions.effective_gas_price AS "effective_gas_price!",
+                miniblocks.base_fee_per_gas,
+                transactions.gas_limit AS "gas_limit!",
+                transactions.refunded_gas
+            FROM
+                transactions
+                INNER JOIN miniblocks ON miniblocks.number = transactions.miniblock_number
+            WHERE
+                transactions.miniblock_number BETWEEN $1 AND $2
+                AND transactions.effective_gas_price IS NOT NULL
+                AND transactions.gas_limit IS NOT NULL
+            ORDER BY
+                transactions.miniblock_number,
+                transactions.index_in_block
+            "#,
+            i64::from(block_range.start().0),
+            i64::from(block_range.end().0)
+        )
+        .instrument("get_fee_history_rewards_data")
+        .with_arg("block_range", &block_range)
+        .fetch_all(self.storage)
+        .await?;
+
+        let mut result: Vec<(L2BlockNumber, Vec<_>)> = vec![];
+        for row in rows {
+            let block_number = L2BlockNumber(row.miniblock_number as u32);
+            let base_fee_per_gas = bigdecimal_to_u256(row.base_fee_per_gas);
+            let priority_fee =
+                bigdecimal_to_u256(row.effective_gas_price).saturating_sub(base_fee_per_gas);
+            let gas_used = bigdecimal_to_u256(row.gas_limit)
+                .saturating_sub(U256::from(row.refunded_gas as u64));
+            let gas_used = u64::try_from(gas_used).unwrap_or(u64::MAX);
+
+            match result.last_mut() {
+                Some((number, block_data)) if *number == block_number => {
+                    block_data.push((priority_fee, gas_used));
+                }
+                _ => result.push((block_number, vec![(priority_fee, gas_used)])),
+            }
+        }
+        Ok(result)
+    }
+
     pub async fn get_block_details(
         &mut self,
         block_number: L2BlockNumber,
@@ -696,7 +752,8 @@ impl BlocksWeb3Dal<'_, '_> {
                 mb.l1_gas_price,
                 mb.l2_fair_gas_price,
                 l1_batches.bootloader_code_hash,
-                l1_batches.default_aa_code_hash
+                l1_batches.default_aa_code_hash,
+                l1_batches.commitment
             FROM
                 l1_batches
                 INNER JOIN mb ON TRUE
@@ -980,4 +1037,54 @@ mod tests {
             assert_eq!(*trace, expected_trace);
         }
     }
+
+    #[tokio::test]
+    async fn getting_fee_history_rewards_data() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        for number in [1, 2] {
+            conn.blocks_dal()
+                .insert_l2_block(&create_l2_block_header(number))
+                .await
+                .unwrap();
+        }
+
+        let mut tx_results = vec![];
+        for priority_fee in [5_u32, 0] {
+            let mut tx = mock_l2_transaction();
+            tx.common_data.fee.max_priority_fee_per_gas = priority_fee.into();
+            conn.transactions_dal()
+                .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+                .await
+                .unwrap();
+            tx_results.push(mock_execution_result(tx));
+        }
+        conn.transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                L2BlockNumber(1),
+                &tx_results,
+                100.into(),
+                ProtocolVersionId::latest(),
+                false,
+            )
+            .await
+            .unwrap();
+
+        let rewards_data = conn
+            .blocks_web3_dal()
+            .get_fee_history_rewards_data(L2BlockNumber(0)..=L2BlockNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(
+            rewards_data,
+            [(
+                L2BlockNumber(1),
+                vec![(5.into(), 1_000_000), (0.into(), 1_000_000)]
+            )]
+        );
+    }
 }
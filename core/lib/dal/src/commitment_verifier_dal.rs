@@ -0,0 +1,81 @@
+//! Cursor of the commitment verifier run by external nodes.
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::L1BatchNumber;
+
+use crate::Core;
+
+#[derive(Debug)]
+pub struct CommitmentVerifierDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl CommitmentVerifierDal<'_, '_> {
+    /// Returns the last L1 batch checked by the commitment verifier, or `None` if no batches were checked yet.
+    pub async fn get_last_checked_l1_batch(&mut self) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_checked_l1_batch
+            FROM
+                commitment_verifier_cursor
+            "#
+        )
+        .instrument("get_last_checked_l1_batch")
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| L1BatchNumber(row.last_checked_l1_batch as u32)))
+    }
+
+    /// Saves the last L1 batch checked by the commitment verifier.
+    pub async fn set_last_checked_l1_batch(&mut self, number: L1BatchNumber) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                commitment_verifier_cursor (last_checked_l1_batch, created_at, updated_at)
+            VALUES
+                ($1, NOW(), NOW())
+            ON CONFLICT (id) DO
+            UPDATE
+            SET
+                last_checked_l1_batch = $1,
+                updated_at = NOW()
+            "#,
+            i64::from(number.0)
+        )
+        .instrument("set_last_checked_l1_batch")
+        .with_arg("number", &number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn commitment_verifier_cursor_basics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.commitment_verifier_dal();
+        assert_eq!(dal.get_last_checked_l1_batch().await.unwrap(), None);
+
+        dal.set_last_checked_l1_batch(L1BatchNumber(3))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_last_checked_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(3))
+        );
+        dal.set_last_checked_l1_batch(L1BatchNumber(5))
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_last_checked_l1_batch().await.unwrap(),
+            Some(L1BatchNumber(5))
+        );
+    }
+}
@@ -10,9 +10,10 @@ use zksync_db_connection::connection::Connection;
 use zksync_types::{
     contract_verification_api::{
         DeployContractCalldata, VerificationIncomingRequest, VerificationInfo, VerificationRequest,
-        VerificationRequestStatus,
+        VerificationRequestStatus, VerificationTarget,
     },
     get_code_key, Address, CONTRACT_DEPLOYER_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH,
+    H256,
 };
 
 use crate::{models::storage_verification_request::StorageVerificationRequest, Core};
@@ -76,16 +77,21 @@ impl ContractVerificationDal<'_, '_> {
                     constructor_arguments,
                     is_system,
                     force_evmla,
+                    bytecode_hash,
                     status,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, 'queued', NOW(), NOW())
+                ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, 'queued', NOW(), NOW())
             RETURNING
                 id
             "#,
-            query.contract_address.as_bytes(),
+            query
+                .target
+                .contract_address()
+                .as_ref()
+                .map(Address::as_bytes),
             // Serialization should always succeed.
             serde_json::to_string(&query.source_code_data).unwrap(),
             query.contract_name,
@@ -96,6 +102,7 @@ impl ContractVerificationDal<'_, '_> {
             query.constructor_arguments.0,
             query.is_system,
             query.force_evmla,
+            query.target.bytecode_hash().as_ref().map(H256::as_bytes),
         )
         .fetch_one(self.storage.conn())
         .await
@@ -154,7 +161,8 @@ impl ContractVerificationDal<'_, '_> {
                 optimizer_mode,
                 constructor_arguments,
                 is_system,
-                force_evmla
+                force_evmla,
+                bytecode_hash
             "#,
             &processing_timeout
         )
@@ -189,26 +197,54 @@ impl ContractVerificationDal<'_, '_> {
         .execute(transaction.conn())
         .await?;
 
-        let address = verification_info.request.req.contract_address;
+        let target = verification_info.request.req.target;
         // Serialization should always succeed.
         let verification_info_json = serde_json::to_value(verification_info)
             .expect("Failed to serialize verification info into serde_json");
-        sqlx::query!(
-            r#"
-            INSERT INTO
-                contracts_verification_info (address, verification_info)
-            VALUES
-                ($1, $2)
-            ON CONFLICT (address) DO
-            UPDATE
-            SET
-                verification_info = $2
-            "#,
-            address.as_bytes(),
-            &verification_info_json
-        )
-        .execute(transaction.conn())
-        .await?;
+        match target {
+            VerificationTarget::Address { contract_address } => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO
+                        contracts_verification_info (address, verification_info)
+                    VALUES
+                        ($1, $2)
+                    ON CONFLICT (address) DO
+                    UPDATE
+                    SET
+                        verification_info = $2
+                    "#,
+                    contract_address.as_bytes(),
+                    &verification_info_json
+                )
+                .execute(transaction.conn())
+                .await?;
+            }
+            VerificationTarget::BytecodeHash { bytecode_hash } => {
+                sqlx::query!(
+                    r#"
+                    INSERT INTO
+                        bytecodes_verification_info (
+                            bytecode_hash,
+                            verification_info,
+                            created_at,
+                            updated_at
+                        )
+                    VALUES
+                        ($1, $2, NOW(), NOW())
+                    ON CONFLICT (bytecode_hash) DO
+                    UPDATE
+                    SET
+                        verification_info = $2,
+                        updated_at = NOW()
+                    "#,
+                    bytecode_hash.as_bytes(),
+                    &verification_info_json
+                )
+                .execute(transaction.conn())
+                .await?;
+            }
+        }
 
         transaction.commit().await.context("commit()")?;
         Ok(())
@@ -475,7 +511,8 @@ impl ContractVerificationDal<'_, '_> {
                 optimizer_mode,
                 constructor_arguments,
                 is_system,
-                force_evmla
+                force_evmla,
+                bytecode_hash
             FROM
                 contract_verification_requests
             WHERE
@@ -517,4 +554,116 @@ impl ContractVerificationDal<'_, '_> {
         };
         Ok(Some(serde_json::from_value(info).context("invalid info")?))
     }
+
+    /// Returns the verification info for a bytecode verified by its hash.
+    pub async fn get_bytecode_verification_info(
+        &mut self,
+        bytecode_hash: H256,
+    ) -> anyhow::Result<Option<VerificationInfo>> {
+        let Some(row) = sqlx::query!(
+            r#"
+            SELECT
+                verification_info
+            FROM
+                bytecodes_verification_info
+            WHERE
+                bytecode_hash = $1
+            "#,
+            bytecode_hash.as_bytes(),
+        )
+        .fetch_optional(self.storage.conn())
+        .await?
+        else {
+            return Ok(None);
+        };
+        Ok(Some(
+            serde_json::from_value(row.verification_info).context("invalid info")?,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use zksync_types::contract_verification_api::{
+        CompilationArtifacts, CompilerVersions, SourceCodeData,
+    };
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    fn mock_request(target: VerificationTarget) -> VerificationIncomingRequest {
+        VerificationIncomingRequest {
+            target,
+            source_code_data: SourceCodeData::SolSingleFile("contract Test {}".to_owned()),
+            contract_name: "Test".to_owned(),
+            compiler_versions: CompilerVersions::Solc {
+                compiler_zksolc_version: "v1.4.1".to_owned(),
+                compiler_solc_version: "0.8.24".to_owned(),
+            },
+            optimization_used: true,
+            optimizer_mode: None,
+            constructor_arguments: Default::default(),
+            is_system: false,
+            force_evmla: false,
+        }
+    }
+
+    #[tokio::test]
+    async fn verifying_bytecode_by_hash() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let bytecode_hash = H256::repeat_byte(1);
+        let target = VerificationTarget::BytecodeHash { bytecode_hash };
+
+        let id = conn
+            .contract_verification_dal()
+            .add_contract_verification_request(mock_request(target))
+            .await
+            .unwrap();
+        let request = conn
+            .contract_verification_dal()
+            .get_next_queued_verification_request(Duration::from_secs(600))
+            .await
+            .unwrap()
+            .expect("no queued request");
+        assert_eq!(request.id, id);
+        assert_eq!(request.req.target, target);
+
+        let info = VerificationInfo {
+            request,
+            artifacts: CompilationArtifacts {
+                bytecode: vec![0; 32],
+                abi: serde_json::Value::Array(vec![]),
+            },
+            verified_at: Utc::now(),
+        };
+        conn.contract_verification_dal()
+            .save_verification_info(info)
+            .await
+            .unwrap();
+
+        let info = conn
+            .contract_verification_dal()
+            .get_bytecode_verification_info(bytecode_hash)
+            .await
+            .unwrap()
+            .expect("no verification info");
+        assert_eq!(info.request.req.target, target);
+        let status = conn
+            .contract_verification_dal()
+            .get_verification_request_status(id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(status.status, "successful");
+
+        // Bytecode verification shouldn't be visible by address.
+        let is_verified = conn
+            .contract_verification_dal()
+            .is_contract_verified(Address::zero())
+            .await
+            .unwrap();
+        assert!(!is_verified);
+    }
 }
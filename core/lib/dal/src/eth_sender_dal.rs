@@ -568,6 +568,50 @@ impl EthSenderDal<'_, '_> {
         Ok(nonce.map(|n| n + 1))
     }
 
+    /// Reassigns nonces of all unconfirmed transactions of the operator account, so that they form
+    /// a contiguous sequence starting from `first_nonce` (in the order of transaction IDs).
+    /// Returns the number of updated transactions.
+    ///
+    /// # Params
+    /// * `from_address`: the custom operator address, or `None` for the main operator
+    ///   (see [`Self::get_next_nonce()`]).
+    pub async fn reassign_nonces(
+        &mut self,
+        from_address: Option<Address>,
+        first_nonce: u64,
+    ) -> sqlx::Result<u64> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE eth_txs
+            SET
+                nonce = renumbered.new_nonce,
+                updated_at = NOW()
+            FROM
+                (
+                    SELECT
+                        id,
+                        $1 + ROW_NUMBER() OVER (
+                            ORDER BY
+                                id
+                        ) - 1 AS new_nonce
+                    FROM
+                        eth_txs
+                    WHERE
+                        confirmed_eth_tx_history_id IS NULL
+                        AND NOT has_failed
+                        AND from_addr IS NOT DISTINCT FROM $2
+                ) AS renumbered
+            WHERE
+                eth_txs.id = renumbered.id
+            "#,
+            first_nonce as i64,
+            from_address.as_ref().map(Address::as_bytes)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
     pub async fn mark_failed_transaction(&mut self, eth_tx_id: u32) -> sqlx::Result<()> {
         sqlx::query!(
             r#"
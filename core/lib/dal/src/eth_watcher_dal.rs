@@ -0,0 +1,104 @@
+//! Cursors of the Ethereum watcher, tracked independently for each polled L1 contract.
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::Address;
+
+use crate::Core;
+
+#[derive(Debug)]
+pub struct EthWatcherDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl EthWatcherDal<'_, '_> {
+    /// Returns the last L1 block processed for the specified contract, or `None` if the contract
+    /// wasn't polled yet.
+    pub async fn get_last_processed_l1_block(
+        &mut self,
+        contract_address: Address,
+    ) -> DalResult<Option<u64>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                last_processed_l1_block
+            FROM
+                eth_watcher_cursors
+            WHERE
+                contract_address = $1
+            "#,
+            contract_address.as_bytes()
+        )
+        .instrument("get_last_processed_l1_block")
+        .with_arg("contract_address", &contract_address)
+        .fetch_optional(self.storage)
+        .await?;
+        Ok(row.map(|row| row.last_processed_l1_block as u64))
+    }
+
+    /// Saves the last L1 block processed for the specified contract.
+    pub async fn set_last_processed_l1_block(
+        &mut self,
+        contract_address: Address,
+        l1_block_number: u64,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                eth_watcher_cursors (contract_address, last_processed_l1_block, created_at, updated_at)
+            VALUES
+                ($1, $2, NOW(), NOW())
+            ON CONFLICT (contract_address) DO
+            UPDATE
+            SET
+                last_processed_l1_block = excluded.last_processed_l1_block,
+                updated_at = NOW()
+            "#,
+            contract_address.as_bytes(),
+            l1_block_number as i64
+        )
+        .instrument("set_last_processed_l1_block")
+        .with_arg("contract_address", &contract_address)
+        .with_arg("l1_block_number", &l1_block_number)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn eth_watcher_cursors_are_independent() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let first = Address::repeat_byte(1);
+        let second = Address::repeat_byte(2);
+
+        let mut dal = conn.eth_watcher_dal();
+        assert_eq!(dal.get_last_processed_l1_block(first).await.unwrap(), None);
+
+        dal.set_last_processed_l1_block(first, 10).await.unwrap();
+        dal.set_last_processed_l1_block(second, 5).await.unwrap();
+        assert_eq!(
+            dal.get_last_processed_l1_block(first).await.unwrap(),
+            Some(10)
+        );
+        assert_eq!(
+            dal.get_last_processed_l1_block(second).await.unwrap(),
+            Some(5)
+        );
+
+        dal.set_last_processed_l1_block(first, 20).await.unwrap();
+        assert_eq!(
+            dal.get_last_processed_l1_block(first).await.unwrap(),
+            Some(20)
+        );
+        assert_eq!(
+            dal.get_last_processed_l1_block(second).await.unwrap(),
+            Some(5)
+        );
+    }
+}
@@ -5,11 +5,16 @@ use sqlx::{
 };
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
 use zksync_types::{
-    api::{GetLogsFilter, Log},
+    api::{GetLogsFilter, Log, PageCursor},
     Address, L2BlockNumber, H256,
 };
+use zksync_utils::address_to_h256;
 
-use crate::{models::storage_event::StorageWeb3Log, Core};
+use crate::{
+    models::storage_event::StorageWeb3Log,
+    pagination::{KeysetPage, KeysetPagination},
+    Core,
+};
 
 #[derive(Debug)]
 pub struct EventsWeb3Dal<'a, 'c> {
@@ -114,11 +119,35 @@ impl EventsWeb3Dal<'_, '_> {
         Ok(logs)
     }
 
+    /// Returns a page of logs for the given filter, starting from the position specified in `pagination`
+    /// (if it is within the filter block range).
+    pub async fn get_logs_page(
+        &mut self,
+        mut filter: GetLogsFilter,
+        pagination: KeysetPagination,
+    ) -> DalResult<KeysetPage<Log>> {
+        if let Some(start) = pagination.start {
+            if start.block_number >= filter.from_block {
+                filter.from_block = start.block_number;
+                filter.from_log_index = start.index_in_block;
+            }
+        }
+        let limit = usize::try_from(pagination.query_limit()).unwrap_or(usize::MAX);
+        let logs = self.get_logs(filter, limit).await?;
+        Ok(pagination.into_page(logs, log_position))
+    }
+
     fn build_get_logs_where_clause(&self, filter: &GetLogsFilter) -> (String, u8) {
         let mut arg_index = 1;
 
         let mut where_sql = format!("(miniblock_number >= {})", filter.from_block.0);
         where_sql += &format!(" AND (miniblock_number <= {})", filter.to_block.0);
+        if filter.from_log_index > 0 {
+            where_sql += &format!(
+                " AND (miniblock_number > {} OR event_index_in_block >= {})",
+                filter.from_block.0, filter.from_log_index
+            );
+        }
 
         // Add filters for address (like `address = ANY($1)` or `address = $1`)
         if let Some(filter_sql) =
@@ -239,6 +268,92 @@ impl EventsWeb3Dal<'_, '_> {
         let logs = db_logs.into_iter().map(Into::into).collect();
         Ok(logs)
     }
+
+    /// Returns ERC-20 `Transfer` events from or to the specified account, from the most recent to the oldest one.
+    /// Events are paginated using their position in the chain. The topic filter must match the predicate
+    /// of partial indexes on `events`, so the `Transfer` topic is inlined into the query.
+    pub async fn get_token_transfer_logs(
+        &mut self,
+        account: Address,
+        pagination: KeysetPagination,
+    ) -> DalResult<KeysetPage<Log>> {
+        let account_topic = address_to_h256(&account);
+        let (start_block, start_index) = pagination.desc_start();
+        let db_logs: Vec<StorageWeb3Log> = sqlx::query_as!(
+            StorageWeb3Log,
+            r#"
+            WITH
+                events_select AS (
+                    SELECT
+                        address,
+                        topic1,
+                        topic2,
+                        topic3,
+                        topic4,
+                        value,
+                        miniblock_number,
+                        tx_hash,
+                        tx_index_in_block,
+                        event_index_in_block,
+                        event_index_in_tx
+                    FROM
+                        events
+                    WHERE
+                        topic1 = '\xddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef'
+                        AND (
+                            topic2 = $1
+                            OR topic3 = $1
+                        )
+                        AND (miniblock_number, event_index_in_block) <= ($2, $3)
+                    ORDER BY
+                        miniblock_number DESC,
+                        event_index_in_block DESC
+                    LIMIT
+                        $4
+                )
+            SELECT
+                miniblocks.hash AS "block_hash?",
+                address AS "address!",
+                topic1 AS "topic1!",
+                topic2 AS "topic2!",
+                topic3 AS "topic3!",
+                topic4 AS "topic4!",
+                value AS "value!",
+                miniblock_number AS "miniblock_number!",
+                miniblocks.l1_batch_number AS "l1_batch_number?",
+                tx_hash AS "tx_hash!",
+                tx_index_in_block AS "tx_index_in_block!",
+                event_index_in_block AS "event_index_in_block!",
+                event_index_in_tx AS "event_index_in_tx!"
+            FROM
+                events_select
+                INNER JOIN miniblocks ON events_select.miniblock_number = miniblocks.number
+            ORDER BY
+                miniblock_number DESC,
+                event_index_in_block DESC
+            "#,
+            account_topic.as_bytes(),
+            start_block,
+            start_index,
+            pagination.query_limit()
+        )
+        .instrument("get_token_transfer_logs")
+        .with_arg("account", &account)
+        .with_arg("pagination", &pagination)
+        .fetch_all(self.storage)
+        .await?;
+        let logs = db_logs.into_iter().map(Log::from).collect();
+        Ok(pagination.into_page(logs, log_position))
+    }
+}
+
+/// Returns the position of a log returned by one of [`EventsWeb3Dal`] methods.
+fn log_position(log: &Log) -> PageCursor {
+    // Block number and log index are always set for logs loaded from the DB.
+    PageCursor {
+        block_number: L2BlockNumber(log.block_number.unwrap().as_u32()),
+        index_in_block: log.log_index.unwrap().as_u32(),
+    }
 }
 
 #[cfg(test)]
@@ -255,6 +370,7 @@ mod tests {
         let events_web3_dal = EventsWeb3Dal { storage };
         let filter = GetLogsFilter {
             from_block: L2BlockNumber(100),
+            from_log_index: 0,
             to_block: L2BlockNumber(200),
             addresses: vec![Address::from_low_u64_be(123)],
             topics: vec![(0, vec![H256::from_low_u64_be(456)])],
@@ -276,6 +392,7 @@ mod tests {
         let events_web3_dal = EventsWeb3Dal { storage };
         let filter = GetLogsFilter {
             from_block: L2BlockNumber(10),
+            from_log_index: 0,
             to_block: L2BlockNumber(400),
             addresses: vec![
                 Address::from_low_u64_be(123),
@@ -310,6 +427,7 @@ mod tests {
         let events_web3_dal = EventsWeb3Dal { storage };
         let filter = GetLogsFilter {
             from_block: L2BlockNumber(10),
+            from_log_index: 0,
             to_block: L2BlockNumber(400),
             addresses: vec![],
             topics: vec![(2, vec![H256::from_low_u64_be(789)])],
@@ -324,4 +442,27 @@ mod tests {
         assert_eq!(actual_sql, expected_sql);
         assert_eq!(actual_arg_index, expected_arg_index);
     }
+
+    #[tokio::test]
+    async fn test_build_get_logs_with_log_index_where_clause() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let storage = &mut connection_pool.connection().await.unwrap();
+        let events_web3_dal = EventsWeb3Dal { storage };
+        let filter = GetLogsFilter {
+            from_block: L2BlockNumber(10),
+            from_log_index: 5,
+            to_block: L2BlockNumber(400),
+            addresses: vec![],
+            topics: vec![],
+        };
+
+        let expected_sql = "(miniblock_number >= 10) AND (miniblock_number <= 400) \
+             AND (miniblock_number > 10 OR event_index_in_block >= 5)";
+        let expected_arg_index = 1;
+
+        let (actual_sql, actual_arg_index) = events_web3_dal.build_get_logs_where_clause(&filter);
+
+        assert_eq!(actual_sql, expected_sql);
+        assert_eq!(actual_arg_index, expected_arg_index);
+    }
 }
@@ -12,9 +12,12 @@ pub use zksync_db_connection::{
 };
 
 use crate::{
-    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal, consensus_dal::ConsensusDal,
+    api_filters_dal::ApiFiltersDal, block_data_archive_dal::BlockDataArchiveDal,
+    blocks_dal::BlocksDal, blocks_web3_dal::BlocksWeb3Dal,
+    commitment_verifier_dal::CommitmentVerifierDal, consensus_dal::ConsensusDal,
     contract_verification_dal::ContractVerificationDal, eth_sender_dal::EthSenderDal,
-    events_dal::EventsDal, events_web3_dal::EventsWeb3Dal, factory_deps_dal::FactoryDepsDal,
+    eth_watcher_dal::EthWatcherDal, events_dal::EventsDal, events_web3_dal::EventsWeb3Dal,
+    factory_deps_dal::FactoryDepsDal, partitions_dal::PartitionsDal,
     proof_generation_dal::ProofGenerationDal, protocol_versions_dal::ProtocolVersionsDal,
     protocol_versions_web3_dal::ProtocolVersionsWeb3Dal, pruning_dal::PruningDal,
     snapshot_recovery_dal::SnapshotRecoveryDal, snapshots_creator_dal::SnapshotsCreatorDal,
@@ -23,21 +26,28 @@ use crate::{
     sync_dal::SyncDal, system_dal::SystemDal,
     tee_verifier_input_producer_dal::TeeVerifierInputProducerDal, tokens_dal::TokensDal,
     tokens_web3_dal::TokensWeb3Dal, transactions_dal::TransactionsDal,
-    transactions_web3_dal::TransactionsWeb3Dal,
+    transactions_web3_dal::TransactionsWeb3Dal, vm_runner_dal::VmRunnerDal,
+    vm_shadow_dal::VmShadowDal,
 };
 
+pub mod api_filters_dal;
+pub mod block_data_archive_dal;
 pub mod blocks_dal;
 pub mod blocks_web3_dal;
+pub mod commitment_verifier_dal;
 pub mod consensus;
 pub mod consensus_dal;
 pub mod contract_verification_dal;
 pub mod eth_sender_dal;
+pub mod eth_watcher_dal;
 pub mod events_dal;
 pub mod events_web3_dal;
 pub mod factory_deps_dal;
 pub mod helpers;
 pub mod metrics;
 mod models;
+pub mod pagination;
+pub mod partitions_dal;
 pub mod proof_generation_dal;
 pub mod protocol_versions_dal;
 pub mod protocol_versions_web3_dal;
@@ -55,6 +65,8 @@ pub mod tokens_dal;
 pub mod tokens_web3_dal;
 pub mod transactions_dal;
 pub mod transactions_web3_dal;
+pub mod vm_runner_dal;
+pub mod vm_shadow_dal;
 
 #[cfg(test)]
 mod tests;
@@ -119,6 +131,20 @@ where
     fn snapshot_recovery_dal(&mut self) -> SnapshotRecoveryDal<'_, 'a>;
 
     fn pruning_dal(&mut self) -> PruningDal<'_, 'a>;
+
+    fn partitions_dal(&mut self) -> PartitionsDal<'_, 'a>;
+
+    fn vm_runner_dal(&mut self) -> VmRunnerDal<'_, 'a>;
+
+    fn api_filters_dal(&mut self) -> ApiFiltersDal<'_, 'a>;
+
+    fn eth_watcher_dal(&mut self) -> EthWatcherDal<'_, 'a>;
+
+    fn commitment_verifier_dal(&mut self) -> CommitmentVerifierDal<'_, 'a>;
+
+    fn block_data_archive_dal(&mut self) -> BlockDataArchiveDal<'_, 'a>;
+
+    fn vm_shadow_dal(&mut self) -> VmShadowDal<'_, 'a>;
 }
 
 #[derive(Clone, Debug)]
@@ -229,4 +255,32 @@ impl<'a> CoreDal<'a> for Connection<'a, Core> {
     fn pruning_dal(&mut self) -> PruningDal<'_, 'a> {
         PruningDal { storage: self }
     }
+
+    fn partitions_dal(&mut self) -> PartitionsDal<'_, 'a> {
+        PartitionsDal { storage: self }
+    }
+
+    fn vm_runner_dal(&mut self) -> VmRunnerDal<'_, 'a> {
+        VmRunnerDal { storage: self }
+    }
+
+    fn api_filters_dal(&mut self) -> ApiFiltersDal<'_, 'a> {
+        ApiFiltersDal { storage: self }
+    }
+
+    fn eth_watcher_dal(&mut self) -> EthWatcherDal<'_, 'a> {
+        EthWatcherDal { storage: self }
+    }
+
+    fn commitment_verifier_dal(&mut self) -> CommitmentVerifierDal<'_, 'a> {
+        CommitmentVerifierDal { storage: self }
+    }
+
+    fn block_data_archive_dal(&mut self) -> BlockDataArchiveDal<'_, 'a> {
+        BlockDataArchiveDal { storage: self }
+    }
+
+    fn vm_shadow_dal(&mut self) -> VmShadowDal<'_, 'a> {
+        VmShadowDal { storage: self }
+    }
 }
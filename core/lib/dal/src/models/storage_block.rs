@@ -346,6 +346,7 @@ pub struct StorageL1BatchDetails {
     pub l2_fair_gas_price: i64,
     pub bootloader_code_hash: Option<Vec<u8>>,
     pub default_aa_code_hash: Option<Vec<u8>>,
+    pub commitment: Option<Vec<u8>>,
 }
 
 impl From<StorageL1BatchDetails> for api::L1BatchDetails {
@@ -393,6 +394,7 @@ impl From<StorageL1BatchDetails> for api::L1BatchDetails {
         api::L1BatchDetails {
             base,
             number: L1BatchNumber(details.number as u32),
+            commitment: details.commitment.as_deref().map(H256::from_slice),
         }
     }
 }
@@ -1,15 +1,15 @@
 use zksync_types::{
     contract_verification_api::{
         CompilerType, CompilerVersions, SourceCodeData, VerificationIncomingRequest,
-        VerificationRequest,
+        VerificationRequest, VerificationTarget,
     },
-    Address,
+    Address, H256,
 };
 
 #[derive(Debug, Clone, sqlx::FromRow)]
 pub struct StorageVerificationRequest {
     pub id: i64,
-    pub contract_address: Vec<u8>,
+    pub contract_address: Option<Vec<u8>>,
     pub source_code: String,
     pub contract_name: String,
     pub zk_compiler_version: String,
@@ -19,6 +19,7 @@ pub struct StorageVerificationRequest {
     pub constructor_arguments: Vec<u8>,
     pub is_system: bool,
     pub force_evmla: bool,
+    pub bytecode_hash: Option<Vec<u8>>,
 }
 
 impl From<StorageVerificationRequest> for VerificationRequest {
@@ -34,10 +35,19 @@ impl From<StorageVerificationRequest> for VerificationRequest {
                 compiler_vyper_version: value.compiler_version,
             },
         };
+        // The database ensures that exactly one of `contract_address` and `bytecode_hash` is set.
+        let target = match value.bytecode_hash {
+            Some(bytecode_hash) => VerificationTarget::BytecodeHash {
+                bytecode_hash: H256::from_slice(&bytecode_hash),
+            },
+            None => VerificationTarget::Address {
+                contract_address: Address::from_slice(&value.contract_address.unwrap()),
+            },
+        };
         VerificationRequest {
             id: value.id as usize,
             req: VerificationIncomingRequest {
-                contract_address: Address::from_slice(&value.contract_address),
+                target,
                 source_code_data,
                 contract_name: value.contract_name,
                 compiler_versions,
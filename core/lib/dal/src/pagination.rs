@@ -0,0 +1,100 @@
+//! Keyset (aka cursor) pagination helpers.
+//!
+//! Unlike `OFFSET`-based pagination, keyset pagination doesn't require Postgres to scan all skipped rows, so its cost
+//! doesn't depend on the page position. Paginated items are ordered by their position in the chain
+//! (the L2 block number and the index in the block), which is expressed as a [`PageCursor`].
+
+use zksync_types::api::PageCursor;
+
+/// Keyset pagination params.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeysetPagination {
+    /// Position of the first item to return (inclusive). If not specified, items are returned from the start
+    /// of the ordering.
+    pub start: Option<PageCursor>,
+    /// Maximum number of items to return.
+    pub limit: usize,
+}
+
+impl KeysetPagination {
+    pub fn new(start: Option<PageCursor>, limit: usize) -> Self {
+        Self { start, limit }
+    }
+
+    /// Returns the limit to use in a DB query. An extra item is requested in order to determine the start
+    /// of the next page.
+    pub(crate) fn query_limit(&self) -> i64 {
+        i64::try_from(self.limit)
+            .unwrap_or(i64::MAX)
+            .saturating_add(1)
+    }
+
+    /// Returns the start position for a query ordering items by the descending position,
+    /// as `(block_number, index_in_block)` to be used in a `(block_number, index_in_block) <= ($1, $2)` condition.
+    pub(crate) fn desc_start(&self) -> (i64, i32) {
+        match self.start {
+            Some(cursor) => (
+                cursor.block_number.0.into(),
+                i32::try_from(cursor.index_in_block).unwrap_or(i32::MAX),
+            ),
+            None => (i64::MAX, i32::MAX),
+        }
+    }
+
+    /// Converts items returned by a query with the [limit](Self::query_limit()) into a page.
+    pub(crate) fn into_page<T>(
+        self,
+        mut items: Vec<T>,
+        position: impl FnOnce(&T) -> PageCursor,
+    ) -> KeysetPage<T> {
+        let next = if items.len() > self.limit {
+            let next = position(&items[self.limit]);
+            items.truncate(self.limit);
+            Some(next)
+        } else {
+            None
+        };
+        KeysetPage { items, next }
+    }
+}
+
+/// Page of items returned by a keyset-paginated query.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeysetPage<T> {
+    pub items: Vec<T>,
+    /// Position of the first item on the next page, or `None` if this is the last page.
+    pub next: Option<PageCursor>,
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::L2BlockNumber;
+
+    use super::*;
+
+    fn cursor(block_number: u32, index_in_block: u32) -> PageCursor {
+        PageCursor {
+            block_number: L2BlockNumber(block_number),
+            index_in_block,
+        }
+    }
+
+    #[test]
+    fn converting_items_into_page() {
+        let pagination = KeysetPagination::new(None, 2);
+        assert_eq!(pagination.query_limit(), 3);
+        assert_eq!(pagination.desc_start(), (i64::MAX, i32::MAX));
+
+        let page = pagination.into_page(vec![(5, 1), (5, 0), (3, 2)], |&(block, index)| {
+            cursor(block, index)
+        });
+        assert_eq!(page.items, [(5, 1), (5, 0)]);
+        assert_eq!(page.next, Some(cursor(3, 2)));
+
+        let pagination = KeysetPagination::new(page.next, 2);
+        assert_eq!(pagination.desc_start(), (3, 2));
+        let page = pagination.into_page(vec![(3, 2)], |_| unreachable!());
+        assert_eq!(page.items, [(3, 2)]);
+        assert_eq!(page.next, None);
+    }
+}
@@ -0,0 +1,218 @@
+//! Management of partitions for the tables partitioned by L2 block number.
+
+use std::ops;
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::L2BlockNumber;
+
+use crate::Core;
+
+/// Table partitioned by L2 block number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PartitionedTable {
+    Events,
+    StorageLogs,
+}
+
+impl PartitionedTable {
+    pub const ALL: [Self; 2] = [Self::Events, Self::StorageLogs];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Events => "events",
+            Self::StorageLogs => "storage_logs",
+        }
+    }
+
+    /// Returns whether partitions fully covered by hard pruning can be dropped. This is not the case for `storage_logs`:
+    /// hard pruning retains the latest log for each storage slot, so pruned partitions may still contain live state.
+    pub fn can_drop_pruned_partitions(self) -> bool {
+        matches!(self, Self::Events)
+    }
+}
+
+/// Partition of a [`PartitionedTable`] covering a contiguous range of L2 blocks.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TablePartition {
+    pub name: String,
+    pub l2_blocks: ops::Range<L2BlockNumber>,
+}
+
+#[derive(Debug)]
+pub struct PartitionsDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl PartitionsDal<'_, '_> {
+    /// Returns partitions of the specified table ordered by the covered L2 blocks. The default partition is not returned.
+    pub async fn get_partitions(
+        &mut self,
+        table: PartitionedTable,
+    ) -> DalResult<Vec<TablePartition>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                partition_name,
+                from_miniblock,
+                to_miniblock
+            FROM
+                l2_block_partitions
+            WHERE
+                table_name = $1
+            ORDER BY
+                from_miniblock
+            "#,
+            table.name()
+        )
+        .instrument("get_partitions")
+        .with_arg("table", &table)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| TablePartition {
+                name: row.partition_name,
+                l2_blocks: L2BlockNumber(row.from_miniblock as u32)
+                    ..L2BlockNumber(row.to_miniblock as u32),
+            })
+            .collect())
+    }
+
+    /// Returns the least L2 block number of the rows in the default partition of the specified table.
+    /// Normally, the default partition is empty, but it may contain rows if partitions weren't created in time
+    /// (e.g., during snapshot recovery).
+    pub async fn get_min_l2_block_in_default_partition(
+        &mut self,
+        table: PartitionedTable,
+    ) -> DalResult<Option<L2BlockNumber>> {
+        // Table names cannot be parameterized, so each table has a dedicated query.
+        let min_l2_block = match table {
+            PartitionedTable::Events => {
+                sqlx::query_scalar!(
+                    r#"
+                    SELECT
+                        MIN(miniblock_number)
+                    FROM
+                        events_default
+                    "#
+                )
+                .instrument("get_min_l2_block_in_default_partition")
+                .with_arg("table", &table)
+                .fetch_one(self.storage)
+                .await?
+            }
+            PartitionedTable::StorageLogs => {
+                sqlx::query_scalar!(
+                    r#"
+                    SELECT
+                        MIN(miniblock_number)
+                    FROM
+                        storage_logs_default
+                    "#
+                )
+                .instrument("get_min_l2_block_in_default_partition")
+                .with_arg("table", &table)
+                .fetch_one(self.storage)
+                .await?
+            }
+        };
+        Ok(min_l2_block.map(|number| L2BlockNumber(number as u32)))
+    }
+
+    /// Creates a partition of the specified table covering the specified range of L2 blocks. Rows in this range
+    /// are moved from the default partition to the created partition.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the range overlaps with an existing partition.
+    pub async fn create_partition(
+        &mut self,
+        table: PartitionedTable,
+        l2_blocks: ops::Range<L2BlockNumber>,
+    ) -> DalResult<TablePartition> {
+        let partition_name = sqlx::query_scalar!(
+            r#"
+            SELECT
+                create_l2_block_partition ($1, $2, $3) AS "partition_name!"
+            "#,
+            table.name(),
+            i64::from(l2_blocks.start.0),
+            i64::from(l2_blocks.end.0)
+        )
+        .instrument("create_partition")
+        .with_arg("table", &table)
+        .with_arg("l2_blocks", &l2_blocks)
+        .fetch_one(self.storage)
+        .await?;
+
+        Ok(TablePartition {
+            name: partition_name,
+            l2_blocks,
+        })
+    }
+
+    /// Drops the specified partition together with all its data.
+    pub async fn drop_partition(
+        &mut self,
+        table: PartitionedTable,
+        partition: &TablePartition,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            CALL drop_l2_block_partition ($1)
+            "#,
+            partition.name
+        )
+        .instrument("drop_partition")
+        .with_arg("table", &table)
+        .with_arg("partition", &partition.name)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn creating_and_dropping_partitions() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+
+        for table in PartitionedTable::ALL {
+            // The legacy partition is created by the migration.
+            let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+            assert_eq!(partitions.len(), 1, "{partitions:?}");
+            let legacy_partition = &partitions[0];
+            assert_eq!(legacy_partition.name, format!("{}_legacy", table.name()));
+            let start = legacy_partition.l2_blocks.end;
+
+            let min_l2_block = conn
+                .partitions_dal()
+                .get_min_l2_block_in_default_partition(table)
+                .await
+                .unwrap();
+            assert_eq!(min_l2_block, None);
+
+            let partition = conn
+                .partitions_dal()
+                .create_partition(table, start..start + 100)
+                .await
+                .unwrap();
+            let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+            assert_eq!(partitions.len(), 2);
+            assert_eq!(partitions[1], partition);
+
+            conn.partitions_dal()
+                .drop_partition(table, &partition)
+                .await
+                .unwrap();
+            let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+            assert_eq!(partitions.len(), 1);
+        }
+    }
+}
@@ -3,7 +3,7 @@ use std::time::Duration;
 
 use strum::{Display, EnumString};
 use zksync_db_connection::{connection::Connection, utils::pg_interval_from_duration};
-use zksync_types::L1BatchNumber;
+use zksync_types::{L1BatchNumber, H256};
 
 use crate::{Core, SqlxError};
 
@@ -12,6 +12,51 @@ pub struct ProofGenerationDal<'a, 'c> {
     pub(crate) storage: &'a mut Connection<'c, Core>,
 }
 
+/// State of a resumable proof upload.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProofUploadSession {
+    pub l1_batch_number: L1BatchNumber,
+    pub total_size: u64,
+    pub sha256: H256,
+    pub received_bytes: u64,
+    /// Offsets of the received chunks in the order they were received.
+    pub chunk_offsets: Vec<u64>,
+}
+
+#[derive(Debug)]
+struct StorageProofUploadSession {
+    l1_batch_number: i64,
+    total_size: i64,
+    sha256: Vec<u8>,
+    received_bytes: i64,
+    chunk_offsets: Vec<i64>,
+}
+
+impl From<StorageProofUploadSession> for ProofUploadSession {
+    fn from(row: StorageProofUploadSession) -> Self {
+        Self {
+            l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+            total_size: row.total_size as u64,
+            sha256: H256::from_slice(&row.sha256),
+            received_bytes: row.received_bytes as u64,
+            chunk_offsets: row
+                .chunk_offsets
+                .into_iter()
+                .map(|offset| offset as u64)
+                .collect(),
+        }
+    }
+}
+
+/// Proof that was saved by the proof data handler, but wasn't yet proven on L1.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GeneratedProofArtifacts {
+    pub l1_batch_number: L1BatchNumber,
+    pub proof_blob_url: String,
+    /// `None` for proofs saved before checksums were recorded.
+    pub proof_blob_sha256: Option<H256>,
+}
+
 #[derive(Debug, EnumString, Display)]
 enum ProofGenerationJobStatus {
     #[strum(serialize = "ready_to_be_proven")]
@@ -73,6 +118,7 @@ impl ProofGenerationDal<'_, '_> {
         &mut self,
         block_number: L1BatchNumber,
         proof_blob_url: &str,
+        proof_blob_sha256: H256,
     ) -> Result<(), SqlxError> {
         sqlx::query!(
             r#"
@@ -80,11 +126,13 @@ impl ProofGenerationDal<'_, '_> {
             SET
                 status = 'generated',
                 proof_blob_url = $1,
+                proof_blob_sha256 = $2,
                 updated_at = NOW()
             WHERE
-                l1_batch_number = $2
+                l1_batch_number = $3
             "#,
             proof_blob_url,
+            proof_blob_sha256.as_bytes(),
             i64::from(block_number.0)
         )
         .execute(self.storage.conn())
@@ -185,4 +233,313 @@ impl ProofGenerationDal<'_, '_> {
 
         result
     }
+
+    /// Returns proofs with the `generated` status for batches that weren't proven on L1 yet.
+    pub async fn get_generated_proofs_not_proven_on_l1(
+        &mut self,
+    ) -> sqlx::Result<Vec<GeneratedProofArtifacts>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                proof_generation_details.l1_batch_number,
+                proof_generation_details.proof_blob_url,
+                proof_generation_details.proof_blob_sha256
+            FROM
+                proof_generation_details
+                INNER JOIN l1_batches ON l1_batches.number = proof_generation_details.l1_batch_number
+            WHERE
+                proof_generation_details.status = 'generated'
+                AND l1_batches.eth_prove_tx_id IS NULL
+            ORDER BY
+                proof_generation_details.l1_batch_number ASC
+            "#,
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|row| {
+                Some(GeneratedProofArtifacts {
+                    l1_batch_number: L1BatchNumber(row.l1_batch_number as u32),
+                    proof_blob_url: row.proof_blob_url?,
+                    proof_blob_sha256: row.proof_blob_sha256.as_deref().map(H256::from_slice),
+                })
+            })
+            .collect())
+    }
+
+    /// Returns a generated proof job back to the queue so that the proof is generated anew.
+    /// Returns `false` if the job doesn't have the `generated` status.
+    pub async fn requeue_generated_proof(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE proof_generation_details
+            SET
+                status = 'ready_to_be_proven',
+                proof_blob_url = NULL,
+                proof_blob_sha256 = NULL,
+                prover_taken_at = NULL,
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+                AND status = 'generated'
+            "#,
+            i64::from(block_number.0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn get_proof_upload(
+        &mut self,
+        block_number: L1BatchNumber,
+    ) -> sqlx::Result<Option<ProofUploadSession>> {
+        let row = sqlx::query_as!(
+            StorageProofUploadSession,
+            r#"
+            SELECT
+                l1_batch_number,
+                total_size,
+                sha256,
+                received_bytes,
+                chunk_offsets
+            FROM
+                proof_upload_sessions
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(block_number.0)
+        )
+        .fetch_optional(self.storage.conn())
+        .await?;
+
+        Ok(row.map(Into::into))
+    }
+
+    /// Starts a new upload session for the batch, replacing the existing session if any.
+    pub async fn start_proof_upload(
+        &mut self,
+        block_number: L1BatchNumber,
+        total_size: u64,
+        sha256: H256,
+    ) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                proof_upload_sessions (
+                    l1_batch_number,
+                    total_size,
+                    sha256,
+                    received_bytes,
+                    chunk_offsets,
+                    created_at,
+                    updated_at
+                )
+            VALUES
+                ($1, $2, $3, 0, '{}', NOW(), NOW())
+            ON CONFLICT (l1_batch_number) DO
+            UPDATE
+            SET
+                total_size = $2,
+                sha256 = $3,
+                received_bytes = 0,
+                chunk_offsets = '{}',
+                created_at = NOW(),
+                updated_at = NOW()
+            "#,
+            i64::from(block_number.0),
+            total_size as i64,
+            sha256.as_bytes()
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Records a received chunk. Returns `false` if the session doesn't exist or `offset`
+    /// doesn't match the number of already received bytes (e.g., because of a concurrent upload).
+    pub async fn record_proof_upload_chunk(
+        &mut self,
+        block_number: L1BatchNumber,
+        offset: u64,
+        len: u64,
+    ) -> sqlx::Result<bool> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE proof_upload_sessions
+            SET
+                received_bytes = received_bytes + $3,
+                chunk_offsets = ARRAY_APPEND(chunk_offsets, $2),
+                updated_at = NOW()
+            WHERE
+                l1_batch_number = $1
+                AND received_bytes = $2
+            "#,
+            i64::from(block_number.0),
+            offset as i64,
+            len as i64
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(result.rows_affected() == 1)
+    }
+
+    pub async fn remove_proof_upload(&mut self, block_number: L1BatchNumber) -> sqlx::Result<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM proof_upload_sessions
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(block_number.0)
+        )
+        .execute(self.storage.conn())
+        .await?;
+        Ok(())
+    }
+
+    /// Returns upload sessions that didn't receive any data for longer than `timeout`.
+    pub async fn get_stale_proof_uploads(
+        &mut self,
+        timeout: Duration,
+    ) -> sqlx::Result<Vec<ProofUploadSession>> {
+        let timeout = pg_interval_from_duration(timeout);
+        let rows = sqlx::query_as!(
+            StorageProofUploadSession,
+            r#"
+            SELECT
+                l1_batch_number,
+                total_size,
+                sha256,
+                received_bytes,
+                chunk_offsets
+            FROM
+                proof_upload_sessions
+            WHERE
+                updated_at < NOW() - $1::INTERVAL
+            ORDER BY
+                l1_batch_number ASC
+            "#,
+            &timeout
+        )
+        .fetch_all(self.storage.conn())
+        .await?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{block::L1BatchHeader, ProtocolVersion, ProtocolVersionId};
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    async fn prepare_l1_batch(conn: &mut Connection<'_, Core>, number: L1BatchNumber) {
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        let header = L1BatchHeader::new(number, 0, Default::default(), ProtocolVersionId::latest());
+        conn.blocks_dal()
+            .insert_mock_l1_batch(&header)
+            .await
+            .unwrap();
+        conn.proof_generation_dal()
+            .insert_proof_generation_details(number, "proof_gen_data.bin")
+            .await;
+    }
+
+    #[tokio::test]
+    async fn requeueing_generated_proof() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let batch = L1BatchNumber(1);
+        prepare_l1_batch(&mut conn, batch).await;
+
+        let mut dal = conn.proof_generation_dal();
+        assert!(!dal.requeue_generated_proof(batch).await.unwrap());
+        assert_eq!(
+            dal.get_next_block_to_be_proven(Duration::from_secs(60))
+                .await,
+            Some(batch)
+        );
+        let sha256 = H256::repeat_byte(1);
+        dal.save_proof_artifacts_metadata(batch, "proof.bin", sha256)
+            .await
+            .unwrap();
+
+        let generated = dal.get_generated_proofs_not_proven_on_l1().await.unwrap();
+        assert_eq!(
+            generated,
+            [GeneratedProofArtifacts {
+                l1_batch_number: batch,
+                proof_blob_url: "proof.bin".to_owned(),
+                proof_blob_sha256: Some(sha256),
+            }]
+        );
+
+        assert!(dal.requeue_generated_proof(batch).await.unwrap());
+        assert!(dal
+            .get_generated_proofs_not_proven_on_l1()
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(dal.get_oldest_unpicked_batch().await, Some(batch));
+    }
+
+    #[tokio::test]
+    async fn proof_upload_session_lifecycle() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let batch = L1BatchNumber(1);
+        prepare_l1_batch(&mut conn, batch).await;
+
+        let mut dal = conn.proof_generation_dal();
+        assert_eq!(dal.get_proof_upload(batch).await.unwrap(), None);
+        assert!(!dal.record_proof_upload_chunk(batch, 0, 10).await.unwrap());
+
+        let sha256 = H256::repeat_byte(2);
+        dal.start_proof_upload(batch, 25, sha256).await.unwrap();
+        assert!(dal.record_proof_upload_chunk(batch, 0, 10).await.unwrap());
+        // Out-of-order chunk must be rejected.
+        assert!(!dal.record_proof_upload_chunk(batch, 5, 10).await.unwrap());
+        assert!(dal.record_proof_upload_chunk(batch, 10, 10).await.unwrap());
+
+        let session = dal.get_proof_upload(batch).await.unwrap().unwrap();
+        assert_eq!(
+            session,
+            ProofUploadSession {
+                l1_batch_number: batch,
+                total_size: 25,
+                sha256,
+                received_bytes: 20,
+                chunk_offsets: vec![0, 10],
+            }
+        );
+        assert!(dal
+            .get_stale_proof_uploads(Duration::from_secs(60))
+            .await
+            .unwrap()
+            .is_empty());
+        assert_eq!(
+            dal.get_stale_proof_uploads(Duration::ZERO).await.unwrap(),
+            [session]
+        );
+
+        // Restarting the upload resets the progress.
+        dal.start_proof_upload(batch, 30, sha256).await.unwrap();
+        let session = dal.get_proof_upload(batch).await.unwrap().unwrap();
+        assert_eq!(session.received_bytes, 0);
+        assert!(session.chunk_offsets.is_empty());
+
+        dal.remove_proof_upload(batch).await.unwrap();
+        assert_eq!(dal.get_proof_upload(batch).await.unwrap(), None);
+    }
 }
@@ -1,4 +1,4 @@
-use std::ops;
+use std::{fmt, ops, str::FromStr};
 
 use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
 use zksync_types::{L1BatchNumber, L2BlockNumber};
@@ -34,6 +34,43 @@ pub struct HardPruningStats {
     pub deleted_l2_to_l1_logs: u64,
 }
 
+/// Mode of the node with respect to pruning. The mode is persisted in Postgres, so that the node
+/// is able to detect changes in its pruning configuration.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NodeMode {
+    /// Node retains all data it has.
+    Archive,
+    /// Node prunes old data.
+    Pruned,
+}
+
+impl NodeMode {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Archive => "Archive",
+            Self::Pruned => "Pruned",
+        }
+    }
+}
+
+impl fmt::Display for NodeMode {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for NodeMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Archive" => Ok(Self::Archive),
+            "Pruned" => Ok(Self::Pruned),
+            _ => Err(anyhow::anyhow!("unknown node mode: `{s}`")),
+        }
+    }
+}
+
 #[derive(Debug, sqlx::Type)]
 #[sqlx(type_name = "prune_type")]
 enum PruneType {
@@ -443,4 +480,127 @@ impl PruningDal<'_, '_> {
         .await?;
         Ok(())
     }
+
+    /// Returns the persisted node mode, or `None` if the mode was never persisted.
+    pub async fn get_node_mode(&mut self) -> DalResult<Option<NodeMode>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                mode
+            FROM
+                node_mode
+            "#
+        )
+        .instrument("get_node_mode")
+        .fetch_optional(self.storage)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+        // The set of allowed values is enforced by a DB constraint, so parsing shouldn't fail.
+        let mode = row
+            .mode
+            .parse()
+            .expect("invalid node mode persisted in Postgres");
+        Ok(Some(mode))
+    }
+
+    /// Persists the node mode, overwriting the previously persisted mode (if any).
+    pub async fn set_node_mode(&mut self, mode: NodeMode) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                node_mode (mode, created_at, updated_at)
+            VALUES
+                ($1, NOW(), NOW())
+            ON CONFLICT (id) DO
+            UPDATE
+            SET
+                mode = $1,
+                updated_at = NOW()
+            "#,
+            mode.as_str()
+        )
+        .instrument("set_node_mode")
+        .with_arg("mode", &mode)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns all L1 batch ranges protected from pruning, ordered by the range start.
+    pub async fn get_protected_l1_batch_ranges(
+        &mut self,
+    ) -> DalResult<Vec<ops::RangeInclusive<L1BatchNumber>>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                start_l1_batch,
+                end_l1_batch
+            FROM
+                pruning_protected_l1_batches
+            ORDER BY
+                start_l1_batch,
+                end_l1_batch
+            "#
+        )
+        .instrument("get_protected_l1_batch_ranges")
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                L1BatchNumber(row.start_l1_batch as u32)..=L1BatchNumber(row.end_l1_batch as u32)
+            })
+            .collect())
+    }
+
+    /// Protects the specified L1 batch range from pruning. Returns `false` if the range was already protected.
+    pub async fn protect_l1_batch_range(
+        &mut self,
+        range: ops::RangeInclusive<L1BatchNumber>,
+    ) -> DalResult<bool> {
+        let inserted = sqlx::query!(
+            r#"
+            INSERT INTO
+                pruning_protected_l1_batches (start_l1_batch, end_l1_batch, created_at)
+            VALUES
+                ($1, $2, NOW())
+            ON CONFLICT (start_l1_batch, end_l1_batch) DO NOTHING
+            "#,
+            i64::from(range.start().0),
+            i64::from(range.end().0)
+        )
+        .instrument("protect_l1_batch_range")
+        .with_arg("range", &range)
+        .execute(self.storage)
+        .await?
+        .rows_affected();
+        Ok(inserted > 0)
+    }
+
+    /// Removes protection from the specified L1 batch range. Returns `false` if the range was not protected.
+    pub async fn unprotect_l1_batch_range(
+        &mut self,
+        range: ops::RangeInclusive<L1BatchNumber>,
+    ) -> DalResult<bool> {
+        let deleted = sqlx::query!(
+            r#"
+            DELETE FROM pruning_protected_l1_batches
+            WHERE
+                start_l1_batch = $1
+                AND end_l1_batch = $2
+            "#,
+            i64::from(range.start().0),
+            i64::from(range.end().0)
+        )
+        .instrument("unprotect_l1_batch_range")
+        .with_arg("range", &range)
+        .execute(self.storage)
+        .await?
+        .rows_affected();
+        Ok(deleted > 0)
+    }
 }
@@ -520,3 +520,86 @@ async fn transactions_are_handled_correctly_after_pruning() {
         .unwrap();
     assert!(transaction_details.is_none(), "{transaction_details:?}");
 }
+
+#[tokio::test]
+async fn persisting_node_mode() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+
+    assert_eq!(conn.pruning_dal().get_node_mode().await.unwrap(), None);
+
+    conn.pruning_dal()
+        .set_node_mode(NodeMode::Archive)
+        .await
+        .unwrap();
+    assert_eq!(
+        conn.pruning_dal().get_node_mode().await.unwrap(),
+        Some(NodeMode::Archive)
+    );
+
+    conn.pruning_dal()
+        .set_node_mode(NodeMode::Pruned)
+        .await
+        .unwrap();
+    assert_eq!(
+        conn.pruning_dal().get_node_mode().await.unwrap(),
+        Some(NodeMode::Pruned)
+    );
+}
+
+#[tokio::test]
+async fn protecting_l1_batch_ranges() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+
+    let ranges = conn
+        .pruning_dal()
+        .get_protected_l1_batch_ranges()
+        .await
+        .unwrap();
+    assert!(ranges.is_empty(), "{ranges:?}");
+
+    let range = L1BatchNumber(10)..=L1BatchNumber(20);
+    let other_range = L1BatchNumber(5)..=L1BatchNumber(5);
+    for range in [range.clone(), other_range.clone()] {
+        let inserted = conn
+            .pruning_dal()
+            .protect_l1_batch_range(range)
+            .await
+            .unwrap();
+        assert!(inserted);
+    }
+    let inserted = conn
+        .pruning_dal()
+        .protect_l1_batch_range(range.clone())
+        .await
+        .unwrap();
+    assert!(!inserted);
+
+    let ranges = conn
+        .pruning_dal()
+        .get_protected_l1_batch_ranges()
+        .await
+        .unwrap();
+    assert_eq!(ranges, [other_range.clone(), range.clone()]);
+
+    let deleted = conn
+        .pruning_dal()
+        .unprotect_l1_batch_range(range.clone())
+        .await
+        .unwrap();
+    assert!(deleted);
+    let deleted = conn
+        .pruning_dal()
+        .unprotect_l1_batch_range(range)
+        .await
+        .unwrap();
+    assert!(!deleted);
+
+    let ranges = conn
+        .pruning_dal()
+        .get_protected_l1_batch_ranges()
+        .await
+        .unwrap();
+    assert_eq!(ranges, [other_range]);
+}
@@ -5,7 +5,7 @@ use zksync_db_connection::{
 };
 use zksync_types::{
     snapshots::{AllSnapshots, SnapshotMetadata, SnapshotVersion},
-    L1BatchNumber,
+    L1BatchNumber, H256,
 };
 
 use crate::Core;
@@ -15,6 +15,7 @@ struct StorageSnapshotMetadata {
     version: i32,
     l1_batch_number: i64,
     storage_logs_filepaths: Vec<String>,
+    storage_logs_checksums: Vec<Vec<u8>>,
     factory_deps_filepath: String,
 }
 
@@ -33,6 +34,16 @@ impl TryFrom<StorageSnapshotMetadata> for SnapshotMetadata {
                 .into_iter()
                 .map(|path| (!path.is_empty()).then_some(path))
                 .collect(),
+            storage_logs_checksums: row
+                .storage_logs_checksums
+                .into_iter()
+                .map(|checksum| match checksum.len() {
+                    0 => Ok(None),
+                    32 => Ok(Some(H256::from_slice(&checksum))),
+                    len => Err(anyhow::anyhow!("unexpected checksum length: {len}")),
+                })
+                .collect::<anyhow::Result<_>>()
+                .decode_column("storage_logs_checksums")?,
             factory_deps_filepath: row.factory_deps_filepath,
         })
     }
@@ -58,12 +69,21 @@ impl SnapshotsDal<'_, '_> {
                     VERSION,
                     l1_batch_number,
                     storage_logs_filepaths,
+                    storage_logs_checksums,
                     factory_deps_filepath,
                     created_at,
                     updated_at
                 )
             VALUES
-                ($1, $2, ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]), $4, NOW(), NOW())
+                (
+                    $1,
+                    $2,
+                    ARRAY_FILL(''::TEXT, ARRAY[$3::INTEGER]),
+                    ARRAY_FILL(''::BYTEA, ARRAY[$3::INTEGER]),
+                    $4,
+                    NOW(),
+                    NOW()
+                )
             "#,
             version as i32,
             l1_batch_number.0 as i32,
@@ -79,17 +99,21 @@ impl SnapshotsDal<'_, '_> {
         Ok(())
     }
 
+    /// Records a produced storage logs chunk together with the SHA-256 checksum of its serialized representation
+    /// in the object store.
     pub async fn add_storage_logs_filepath_for_snapshot(
         &mut self,
         l1_batch_number: L1BatchNumber,
         chunk_id: u64,
         storage_logs_filepath: &str,
+        storage_logs_checksum: H256,
     ) -> DalResult<()> {
         sqlx::query!(
             r#"
             UPDATE snapshots
             SET
                 storage_logs_filepaths[$2] = $3,
+                storage_logs_checksums[$2] = $4,
                 updated_at = NOW()
             WHERE
                 l1_batch_number = $1
@@ -97,6 +121,7 @@ impl SnapshotsDal<'_, '_> {
             l1_batch_number.0 as i32,
             chunk_id as i32 + 1,
             storage_logs_filepath,
+            storage_logs_checksum.as_bytes(),
         )
         .instrument("add_storage_logs_filepath_for_snapshot")
         .with_arg("l1_batch_number", &l1_batch_number)
@@ -143,7 +168,8 @@ impl SnapshotsDal<'_, '_> {
                 VERSION,
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                storage_logs_filepaths,
+                storage_logs_checksums
             FROM
                 snapshots
             ORDER BY
@@ -170,7 +196,8 @@ impl SnapshotsDal<'_, '_> {
                 VERSION,
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                storage_logs_filepaths,
+                storage_logs_checksums
             FROM
                 snapshots
             WHERE
@@ -201,7 +228,8 @@ impl SnapshotsDal<'_, '_> {
                 VERSION,
                 l1_batch_number,
                 factory_deps_filepath,
-                storage_logs_filepaths
+                storage_logs_filepaths,
+                storage_logs_checksums
             "#,
             last_retained_l1_batch_number.0 as i32
         )
@@ -218,7 +246,7 @@ impl SnapshotsDal<'_, '_> {
 
 #[cfg(test)]
 mod tests {
-    use zksync_types::{snapshots::SnapshotVersion, L1BatchNumber};
+    use zksync_types::{snapshots::SnapshotVersion, L1BatchNumber, H256};
 
     use crate::{ConnectionPool, Core, CoreDal};
 
@@ -248,6 +276,7 @@ mod tests {
                 l1_batch_number,
                 i,
                 "gs:///bucket/chunk.bin",
+                H256::repeat_byte(i as u8),
             )
             .await
             .unwrap();
@@ -287,6 +316,7 @@ mod tests {
                 l1_batch_number,
                 i,
                 "gs:///bucket/chunk.bin",
+                H256::repeat_byte(i as u8),
             )
             .await
             .unwrap();
@@ -319,6 +349,10 @@ mod tests {
             deleted_snapshots[0].storage_logs_filepaths,
             snapshot_metadata.storage_logs_filepaths
         );
+        assert_eq!(
+            deleted_snapshots[0].storage_logs_checksums,
+            snapshot_metadata.storage_logs_checksums
+        );
 
         let deleted_snapshot_metadata = dal.get_snapshot_metadata(l1_batch_number).await.unwrap();
         assert!(
@@ -346,37 +380,53 @@ mod tests {
         .expect("Failed to add snapshot");
 
         let storage_log_filepaths = ["gs:///bucket/test_file1.bin", "gs:///bucket/test_file2.bin"];
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 1, storage_log_filepaths[1])
-            .await
-            .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            1,
+            storage_log_filepaths[1],
+            H256::repeat_byte(2),
+        )
+        .await
+        .unwrap();
 
-        let files = dal
+        let snapshot_metadata = dal
             .get_snapshot_metadata(l1_batch_number)
             .await
             .expect("Failed to retrieve snapshot")
-            .unwrap()
-            .storage_logs_filepaths;
+            .unwrap();
         assert_eq!(
-            files,
+            snapshot_metadata.storage_logs_filepaths,
             [None, Some("gs:///bucket/test_file2.bin".to_string())]
         );
+        assert_eq!(
+            snapshot_metadata.storage_logs_checksums,
+            [None, Some(H256::repeat_byte(2))]
+        );
 
-        dal.add_storage_logs_filepath_for_snapshot(l1_batch_number, 0, storage_log_filepaths[0])
-            .await
-            .unwrap();
+        dal.add_storage_logs_filepath_for_snapshot(
+            l1_batch_number,
+            0,
+            storage_log_filepaths[0],
+            H256::repeat_byte(1),
+        )
+        .await
+        .unwrap();
 
-        let files = dal
+        let snapshot_metadata = dal
             .get_snapshot_metadata(l1_batch_number)
             .await
             .expect("Failed to retrieve snapshot")
-            .unwrap()
-            .storage_logs_filepaths;
+            .unwrap();
         assert_eq!(
-            files,
+            snapshot_metadata.storage_logs_filepaths,
             [
                 Some("gs:///bucket/test_file1.bin".to_string()),
                 Some("gs:///bucket/test_file2.bin".to_string())
             ]
         );
+        assert_eq!(
+            snapshot_metadata.storage_logs_checksums,
+            [Some(H256::repeat_byte(1)), Some(H256::repeat_byte(2))]
+        );
     }
 }
@@ -44,6 +44,71 @@ impl SystemDal<'_, '_> {
         })
     }
 
+    /// Tries to acquire a session-level advisory lock with the specified `key` without waiting.
+    /// Returns `true` if the lock was acquired. The lock is held until it's explicitly released
+    /// (see [`Self::release_advisory_lock()`]) or the connection is closed, so it should only be used
+    /// with dedicated long-living connections.
+    pub async fn try_acquire_advisory_lock(&mut self, key: i64) -> DalResult<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                PG_TRY_ADVISORY_LOCK($1) AS "acquired!"
+            "#,
+            key
+        )
+        .instrument("try_acquire_advisory_lock")
+        .with_arg("key", &key)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.acquired)
+    }
+
+    /// Checks whether the session-level advisory lock with the specified `key` is held by this connection.
+    // Locks acquired with a single `BIGINT` key have `objsubid = 1`; ones with two `INT` keys have `objsubid = 2`
+    // and may have the same `classid` and `objid`.
+    pub async fn holds_advisory_lock(&mut self, key: i64) -> DalResult<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                EXISTS (
+                    SELECT
+                        1
+                    FROM
+                        pg_locks
+                    WHERE
+                        locktype = 'advisory'
+                        AND granted
+                        AND pid = PG_BACKEND_PID()
+                        AND objsubid = 1
+                        AND ((classid::BIGINT << 32) | objid::BIGINT) = $1
+                ) AS "held!"
+            "#,
+            key
+        )
+        .instrument("holds_advisory_lock")
+        .with_arg("key", &key)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.held)
+    }
+
+    /// Releases the session-level advisory lock with the specified `key`. Returns `false` if the lock
+    /// was not held by this connection.
+    pub async fn release_advisory_lock(&mut self, key: i64) -> DalResult<bool> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                PG_ADVISORY_UNLOCK($1) AS "released!"
+            "#,
+            key
+        )
+        .instrument("release_advisory_lock")
+        .with_arg("key", &key)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.released)
+    }
+
     pub(crate) async fn get_table_sizes(&mut self) -> DalResult<HashMap<String, TableSize>> {
         let rows = sqlx::query!(
             r#"
@@ -4,8 +4,10 @@ use zksync_db_connection::{
     match_query_as,
 };
 use zksync_types::{
-    api, api::TransactionReceipt, Address, L2BlockNumber, L2ChainId, Transaction,
-    ACCOUNT_CODE_STORAGE_ADDRESS, FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
+    api,
+    api::{PageCursor, TransactionReceipt},
+    Address, L2BlockNumber, L2ChainId, Transaction, ACCOUNT_CODE_STORAGE_ADDRESS,
+    FAILED_CONTRACT_DEPLOYMENT_BYTECODE_HASH, H256, U256,
 };
 
 use crate::{
@@ -13,6 +15,7 @@ use crate::{
         StorageApiTransaction, StorageTransaction, StorageTransactionDetails,
         StorageTransactionReceipt,
     },
+    pagination::{KeysetPage, KeysetPagination},
     Core, CoreDal,
 };
 
@@ -236,6 +239,65 @@ impl TransactionsWeb3Dal<'_, '_> {
             .next())
     }
 
+    /// Returns executed transactions initiated by the specified account, from the most recent to the oldest one.
+    /// Transactions are paginated using their position in the chain.
+    pub async fn get_account_transactions(
+        &mut self,
+        initiator_address: Address,
+        pagination: KeysetPagination,
+        chain_id: L2ChainId,
+    ) -> DalResult<KeysetPage<api::Transaction>> {
+        let (start_block, start_index) = pagination.desc_start();
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                hash,
+                miniblock_number AS "miniblock_number!",
+                index_in_block AS "index_in_block!"
+            FROM
+                transactions
+            WHERE
+                initiator_address = $1
+                AND miniblock_number IS NOT NULL
+                AND data != '{}'::jsonb
+                AND (miniblock_number, index_in_block) <= ($2, $3)
+            ORDER BY
+                miniblock_number DESC,
+                index_in_block DESC
+            LIMIT
+                $4
+            "#,
+            initiator_address.as_bytes(),
+            start_block,
+            start_index,
+            pagination.query_limit()
+        )
+        .instrument("get_account_transactions")
+        .with_arg("initiator_address", &initiator_address)
+        .with_arg("pagination", &pagination)
+        .fetch_all(self.storage)
+        .await?;
+
+        let rows = pagination.into_page(rows, |row| PageCursor {
+            block_number: L2BlockNumber(row.miniblock_number as u32),
+            index_in_block: row.index_in_block as u32,
+        });
+        let hashes: Vec<_> = rows
+            .items
+            .iter()
+            .map(|row| H256::from_slice(&row.hash))
+            .collect();
+        let mut transactions = self.get_transactions(&hashes, chain_id).await?;
+        transactions.sort_unstable_by(|tx, other_tx| {
+            (other_tx.block_number, other_tx.transaction_index)
+                .cmp(&(tx.block_number, tx.transaction_index))
+        });
+        Ok(KeysetPage {
+            items: transactions,
+            next: rows.next,
+        })
+    }
+
     pub async fn get_transaction_details(
         &mut self,
         hash: H256,
@@ -512,6 +574,87 @@ mod tests {
         assert!(web3_tx.unwrap().is_none());
     }
 
+    #[tokio::test]
+    async fn getting_account_transactions() {
+        let connection_pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = connection_pool.connection().await.unwrap();
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        let tx = mock_l2_transaction();
+        let tx_hash = tx.hash();
+        let initiator_address = tx.initiator_account();
+        prepare_transactions(&mut conn, vec![tx, mock_l2_transaction()]).await;
+
+        let page = conn
+            .transactions_web3_dal()
+            .get_account_transactions(
+                initiator_address,
+                KeysetPagination::new(None, 10),
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].hash, tx_hash);
+        assert_eq!(page.items[0].from, Some(initiator_address));
+        assert_eq!(page.next, None);
+
+        let page = conn
+            .transactions_web3_dal()
+            .get_account_transactions(
+                initiator_address,
+                KeysetPagination::new(None, 0),
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+        let tx_position = PageCursor {
+            block_number: L2BlockNumber(1),
+            index_in_block: 0,
+        };
+        assert_eq!(page.next, Some(tx_position));
+
+        let page = conn
+            .transactions_web3_dal()
+            .get_account_transactions(
+                initiator_address,
+                KeysetPagination::new(page.next, 10),
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        assert_eq!(page.items.len(), 1);
+        assert_eq!(page.items[0].hash, tx_hash);
+
+        let before_tx = PageCursor {
+            block_number: L2BlockNumber(0),
+            index_in_block: u32::MAX,
+        };
+        let page = conn
+            .transactions_web3_dal()
+            .get_account_transactions(
+                initiator_address,
+                KeysetPagination::new(Some(before_tx), 10),
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+        let page = conn
+            .transactions_web3_dal()
+            .get_account_transactions(
+                Address::repeat_byte(1),
+                KeysetPagination::new(None, 10),
+                L2ChainId::from(270),
+            )
+            .await
+            .unwrap();
+        assert!(page.items.is_empty());
+    }
+
     #[tokio::test]
     async fn getting_receipts() {
         let connection_pool = ConnectionPool::<Core>::test_pool().await;
@@ -0,0 +1,366 @@
+//! Data persisted by VM runner components: per-batch checkpoints and protective read mismatches.
+
+use std::{fmt, str::FromStr};
+
+use zksync_db_connection::{
+    connection::Connection,
+    error::{DalResult, SqlxContext},
+    instrument::InstrumentExt,
+};
+use zksync_types::{AccountTreeId, Address, L1BatchNumber, StorageKey, H256};
+
+use crate::Core;
+
+/// Processing phase of an L1 batch in a VM runner component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum VmRunnerBatchPhase {
+    /// Batch data is loaded, and the batch is being executed.
+    Loaded,
+    /// Batch is executed, and its output is being handled.
+    Executed,
+    /// Batch output is handled and committed. Outputs may be committed out of order, so earlier batches
+    /// are not necessarily committed.
+    Committed,
+}
+
+impl VmRunnerBatchPhase {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Loaded => "loaded",
+            Self::Executed => "executed",
+            Self::Committed => "committed",
+        }
+    }
+}
+
+impl fmt::Display for VmRunnerBatchPhase {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VmRunnerBatchPhase {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "loaded" => Ok(Self::Loaded),
+            "executed" => Ok(Self::Executed),
+            "committed" => Ok(Self::Committed),
+            _ => Err(format!("unknown VM runner batch phase: `{s}`")),
+        }
+    }
+}
+
+/// Kind of mismatch between protective reads persisted by the state keeper and ones recomputed by
+/// the protective reads verifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProtectiveReadMismatchKind {
+    /// Read was recomputed, but wasn't persisted by the state keeper.
+    Missing,
+    /// Read was persisted by the state keeper, but wasn't recomputed.
+    Unexpected,
+}
+
+impl ProtectiveReadMismatchKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Missing => "missing",
+            Self::Unexpected => "unexpected",
+        }
+    }
+}
+
+impl FromStr for ProtectiveReadMismatchKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "missing" => Ok(Self::Missing),
+            "unexpected" => Ok(Self::Unexpected),
+            _ => Err(format!("unknown protective read mismatch kind: `{s}`")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct VmRunnerDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl VmRunnerDal<'_, '_> {
+    /// Sets the processing phase of the specified L1 batch for a VM runner component.
+    pub async fn set_checkpoint(
+        &mut self,
+        component: &str,
+        l1_batch_number: L1BatchNumber,
+        phase: VmRunnerBatchPhase,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                vm_runner_checkpoints (component, l1_batch_number, phase, created_at, updated_at)
+            VALUES
+                ($1, $2, $3, NOW(), NOW())
+            ON CONFLICT (component, l1_batch_number) DO
+            UPDATE
+            SET
+                phase = $3,
+                updated_at = NOW()
+            "#,
+            component,
+            i64::from(l1_batch_number.0),
+            phase.as_str()
+        )
+        .instrument("set_vm_runner_checkpoint")
+        .with_arg("component", &component)
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("phase", &phase)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Removes checkpoints for L1 batches preceding `before_l1_batch` for a VM runner component.
+    pub async fn prune_checkpoints(
+        &mut self,
+        component: &str,
+        before_l1_batch: L1BatchNumber,
+    ) -> DalResult<()> {
+        sqlx::query!(
+            r#"
+            DELETE FROM vm_runner_checkpoints
+            WHERE
+                component = $1
+                AND l1_batch_number < $2
+            "#,
+            component,
+            i64::from(before_l1_batch.0)
+        )
+        .instrument("prune_vm_runner_checkpoints")
+        .with_arg("component", &component)
+        .with_arg("before_l1_batch", &before_l1_batch)
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns the latest committed L1 batch for a VM runner component, or `None` if the component
+    /// has no committed checkpoints.
+    pub async fn get_latest_committed_batch(
+        &mut self,
+        component: &str,
+    ) -> DalResult<Option<L1BatchNumber>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                MAX(l1_batch_number) AS "number"
+            FROM
+                vm_runner_checkpoints
+            WHERE
+                component = $1
+                AND phase = 'committed'
+            "#,
+            component
+        )
+        .instrument("get_latest_committed_vm_runner_batch")
+        .with_arg("component", &component)
+        .fetch_one(self.storage)
+        .await?;
+        Ok(row.number.map(|number| L1BatchNumber(number as u32)))
+    }
+
+    /// Returns all checkpoints for a VM runner component ordered by the L1 batch number.
+    pub async fn get_checkpoints(
+        &mut self,
+        component: &str,
+    ) -> DalResult<Vec<(L1BatchNumber, VmRunnerBatchPhase)>> {
+        sqlx::query!(
+            r#"
+            SELECT
+                l1_batch_number,
+                phase
+            FROM
+                vm_runner_checkpoints
+            WHERE
+                component = $1
+            ORDER BY
+                l1_batch_number
+            "#,
+            component
+        )
+        .try_map(|row| {
+            let phase = row.phase.parse().decode_column("phase")?;
+            Ok((L1BatchNumber(row.l1_batch_number as u32), phase))
+        })
+        .instrument("get_vm_runner_checkpoints")
+        .with_arg("component", &component)
+        .fetch_all(self.storage)
+        .await
+    }
+
+    /// Records protective read mismatches for the specified L1 batch. Mismatches that are already recorded
+    /// are ignored.
+    pub async fn insert_protective_reads_mismatches(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        mismatches: &[(StorageKey, ProtectiveReadMismatchKind)],
+    ) -> DalResult<()> {
+        let mut addresses = Vec::with_capacity(mismatches.len());
+        let mut keys = Vec::with_capacity(mismatches.len());
+        let mut kinds = Vec::with_capacity(mismatches.len());
+        for (storage_key, kind) in mismatches {
+            addresses.push(storage_key.address().as_bytes());
+            keys.push(storage_key.key().as_bytes());
+            kinds.push(kind.as_str());
+        }
+
+        sqlx::query!(
+            r#"
+            INSERT INTO
+                protective_reads_mismatches (l1_batch_number, address, key, kind, created_at)
+            SELECT
+                $1,
+                u.address,
+                u.key,
+                u.kind,
+                NOW()
+            FROM
+                UNNEST($2::bytea[], $3::bytea[], $4::TEXT[]) AS u (address, key, kind)
+            ON CONFLICT (l1_batch_number, address, key) DO NOTHING
+            "#,
+            i64::from(l1_batch_number.0),
+            &addresses as &[&[u8]],
+            &keys as &[&[u8]],
+            &kinds as &[&str]
+        )
+        .instrument("insert_protective_reads_mismatches")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .with_arg("mismatches.len", &mismatches.len())
+        .execute(self.storage)
+        .await?;
+        Ok(())
+    }
+
+    /// Returns protective read mismatches recorded for the specified L1 batch.
+    pub async fn get_protective_reads_mismatches(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Vec<(StorageKey, ProtectiveReadMismatchKind)>> {
+        sqlx::query!(
+            r#"
+            SELECT
+                address,
+                key,
+                kind
+            FROM
+                protective_reads_mismatches
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                address,
+                key
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .try_map(|row| {
+            let storage_key = StorageKey::new(
+                AccountTreeId::new(Address::from_slice(&row.address)),
+                H256::from_slice(&row.key),
+            );
+            let kind = row.kind.parse().decode_column("kind")?;
+            Ok((storage_key, kind))
+        })
+        .instrument("get_protective_reads_mismatches")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn setting_and_pruning_checkpoints() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_runner_dal();
+
+        let latest = dal.get_latest_committed_batch("test").await.unwrap();
+        assert_eq!(latest, None);
+
+        dal.set_checkpoint("test", L1BatchNumber(1), VmRunnerBatchPhase::Loaded)
+            .await
+            .unwrap();
+        dal.set_checkpoint("test", L1BatchNumber(2), VmRunnerBatchPhase::Loaded)
+            .await
+            .unwrap();
+        dal.set_checkpoint("test", L1BatchNumber(1), VmRunnerBatchPhase::Committed)
+            .await
+            .unwrap();
+        dal.set_checkpoint("other", L1BatchNumber(5), VmRunnerBatchPhase::Committed)
+            .await
+            .unwrap();
+
+        let latest = dal.get_latest_committed_batch("test").await.unwrap();
+        assert_eq!(latest, Some(L1BatchNumber(1)));
+        let checkpoints = dal.get_checkpoints("test").await.unwrap();
+        assert_eq!(
+            checkpoints,
+            [
+                (L1BatchNumber(1), VmRunnerBatchPhase::Committed),
+                (L1BatchNumber(2), VmRunnerBatchPhase::Loaded)
+            ]
+        );
+
+        dal.prune_checkpoints("test", L1BatchNumber(2))
+            .await
+            .unwrap();
+        let checkpoints = dal.get_checkpoints("test").await.unwrap();
+        assert_eq!(
+            checkpoints,
+            [(L1BatchNumber(2), VmRunnerBatchPhase::Loaded)]
+        );
+        let latest = dal.get_latest_committed_batch("other").await.unwrap();
+        assert_eq!(latest, Some(L1BatchNumber(5)));
+    }
+
+    #[tokio::test]
+    async fn inserting_protective_reads_mismatches() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_runner_dal();
+
+        let missing_key =
+            StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        let unexpected_key = StorageKey::new(
+            AccountTreeId::new(Address::repeat_byte(2)),
+            H256::repeat_byte(3),
+        );
+        let mismatches = [
+            (missing_key, ProtectiveReadMismatchKind::Missing),
+            (unexpected_key, ProtectiveReadMismatchKind::Unexpected),
+        ];
+        dal.insert_protective_reads_mismatches(L1BatchNumber(1), &mismatches)
+            .await
+            .unwrap();
+        // Repeated insertion should be a no-op.
+        dal.insert_protective_reads_mismatches(L1BatchNumber(1), &mismatches[..1])
+            .await
+            .unwrap();
+
+        let stored = dal
+            .get_protective_reads_mismatches(L1BatchNumber(1))
+            .await
+            .unwrap();
+        assert_eq!(stored, mismatches);
+        let stored = dal
+            .get_protective_reads_mismatches(L1BatchNumber(2))
+            .await
+            .unwrap();
+        assert_eq!(stored, []);
+    }
+}
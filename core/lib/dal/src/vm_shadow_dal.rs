@@ -0,0 +1,161 @@
+//! Storage for divergences between the canonical VM and a shadow VM version executing the same L1 batches.
+
+use zksync_db_connection::{connection::Connection, error::DalResult, instrument::InstrumentExt};
+use zksync_types::{L1BatchNumber, H256};
+
+use crate::Core;
+
+/// Divergence between the canonical and shadow VM detected when executing an L1 batch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct VmDivergence {
+    /// Hash of the diverged transaction, or `None` if the divergence was detected when finalizing the batch.
+    pub tx_hash: Option<H256>,
+    /// Kind of the divergence, e.g. `gas` or `storage_writes`.
+    pub kind: String,
+    /// Divergence details in a free-form JSON format.
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug)]
+pub struct VmShadowDal<'a, 'c> {
+    pub(crate) storage: &'a mut Connection<'c, Core>,
+}
+
+impl VmShadowDal<'_, '_> {
+    /// Saves divergences for the specified L1 batch, replacing previously saved ones (e.g., if the batch was re-executed
+    /// after a node restart).
+    pub async fn insert_divergences(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+        canonical_vm_version: &str,
+        shadow_vm_version: &str,
+        divergences: &[VmDivergence],
+    ) -> DalResult<()> {
+        let mut transaction = self.storage.start_transaction().await?;
+        sqlx::query!(
+            r#"
+            DELETE FROM vm_shadow_divergences
+            WHERE
+                l1_batch_number = $1
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("insert_divergences#delete")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .execute(&mut transaction)
+        .await?;
+
+        for divergence in divergences {
+            sqlx::query!(
+                r#"
+                INSERT INTO
+                    vm_shadow_divergences (
+                        l1_batch_number,
+                        tx_hash,
+                        canonical_vm_version,
+                        shadow_vm_version,
+                        kind,
+                        details,
+                        created_at
+                    )
+                VALUES
+                    ($1, $2, $3, $4, $5, $6, NOW())
+                "#,
+                i64::from(l1_batch_number.0),
+                divergence.tx_hash.as_ref().map(H256::as_bytes),
+                canonical_vm_version,
+                shadow_vm_version,
+                &divergence.kind,
+                &divergence.details
+            )
+            .instrument("insert_divergences")
+            .with_arg("l1_batch_number", &l1_batch_number)
+            .with_arg("kind", &divergence.kind)
+            .execute(&mut transaction)
+            .await?;
+        }
+        transaction.commit().await
+    }
+
+    /// Returns divergences saved for the specified L1 batch.
+    pub async fn get_divergences(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> DalResult<Vec<VmDivergence>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT
+                tx_hash,
+                kind,
+                details
+            FROM
+                vm_shadow_divergences
+            WHERE
+                l1_batch_number = $1
+            ORDER BY
+                id
+            "#,
+            i64::from(l1_batch_number.0)
+        )
+        .instrument("get_divergences")
+        .with_arg("l1_batch_number", &l1_batch_number)
+        .fetch_all(self.storage)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| VmDivergence {
+                tx_hash: row.tx_hash.as_deref().map(H256::from_slice),
+                kind: row.kind,
+                details: row.details,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::{ConnectionPool, CoreDal};
+
+    #[tokio::test]
+    async fn inserting_divergences() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut dal = conn.vm_shadow_dal();
+        let l1_batch_number = L1BatchNumber(1);
+        assert_eq!(dal.get_divergences(l1_batch_number).await.unwrap(), []);
+
+        let divergences = [
+            VmDivergence {
+                tx_hash: Some(H256::repeat_byte(1)),
+                kind: "gas".to_owned(),
+                details: json!({ "canonical": 100, "shadow": 101 }),
+            },
+            VmDivergence {
+                tx_hash: None,
+                kind: "events".to_owned(),
+                details: json!({}),
+            },
+        ];
+        dal.insert_divergences(l1_batch_number, "vm1_5_0", "vm1_4_2", &divergences)
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_divergences(l1_batch_number).await.unwrap(),
+            divergences
+        );
+
+        // Repeated insertion replaces previously saved divergences.
+        dal.insert_divergences(l1_batch_number, "vm1_5_0", "vm1_4_2", &divergences[1..])
+            .await
+            .unwrap();
+        assert_eq!(
+            dal.get_divergences(l1_batch_number).await.unwrap(),
+            divergences[1..]
+        );
+        assert_eq!(dal.get_divergences(L1BatchNumber(2)).await.unwrap(), []);
+    }
+}
@@ -0,0 +1,177 @@
+//! Circuit breaker tripped by a high rate of slow DB queries.
+
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::metrics::REQUEST_METRICS;
+
+/// Configuration of the slow query circuit breaker for a connection pool; see
+/// [`ConnectionPoolBuilder::set_slow_query_circuit_breaker()`](crate::connection_pool::ConnectionPoolBuilder::set_slow_query_circuit_breaker()).
+#[derive(Debug, Clone, Copy)]
+pub struct SlowQueryCircuitBreakerConfig {
+    /// Share of slow queries in the [`Self::window`] (in range `(0, 1]`) after which the circuit breaker is opened.
+    pub slow_query_ratio: f64,
+    /// Minimum number of queries in the window for the circuit breaker to be opened.
+    pub min_queries: u64,
+    /// Length of the window over which slow queries are counted.
+    pub window: Duration,
+}
+
+#[derive(Debug)]
+struct CircuitBreakerState {
+    window_start: Option<Instant>,
+    total_queries: u64,
+    slow_queries: u64,
+    is_open: bool,
+}
+
+/// Circuit breaker that opens if the share of slow queries (as determined by the slow query threshold set in
+/// [`GlobalConnectionPoolConfig`](crate::connection_pool::GlobalConnectionPoolConfig)) over a window
+/// exceeds the configured ratio. The breaker stays open for the following window, after which it is re-evaluated.
+///
+/// A circuit breaker is scoped to a single [`ConnectionPool`](crate::connection_pool::ConnectionPool) and only observes
+/// queries executed on connections from this pool. The breaker doesn't affect DB queries by itself; consumers
+/// (e.g., API servers) should query [`ConnectionPool::is_overloaded()`](crate::connection_pool::ConnectionPool::is_overloaded())
+/// and shed load if it is open.
+#[derive(Debug)]
+pub(crate) struct SlowQueryCircuitBreaker {
+    config: SlowQueryCircuitBreakerConfig,
+    state: Mutex<CircuitBreakerState>,
+}
+
+impl SlowQueryCircuitBreaker {
+    pub(crate) fn new(config: SlowQueryCircuitBreakerConfig) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            config.slow_query_ratio > 0.0 && config.slow_query_ratio <= 1.0,
+            "slow query ratio must be in (0, 1] range"
+        );
+        anyhow::ensure!(!config.window.is_zero(), "window must be positive");
+        Ok(Self {
+            config,
+            state: Mutex::new(CircuitBreakerState {
+                window_start: None,
+                total_queries: 0,
+                slow_queries: 0,
+                is_open: false,
+            }),
+        })
+    }
+
+    /// Records a completed query.
+    pub(crate) fn observe_query(&self, is_slow: bool) {
+        let config = &self.config;
+        let now = Instant::now();
+        let mut state = self
+            .state
+            .lock()
+            .expect("circuit breaker state is poisoned");
+        let window_start = *state.window_start.get_or_insert(now);
+        if now.duration_since(window_start) >= config.window {
+            let was_open = state.is_open;
+            state.is_open = state.total_queries >= config.min_queries
+                && state.slow_queries as f64
+                    >= config.slow_query_ratio * state.total_queries as f64;
+            if state.is_open {
+                tracing::warn!(
+                    "Opening slow query circuit breaker: {} out of {} queries in the last {:?} were slow",
+                    state.slow_queries,
+                    state.total_queries,
+                    config.window
+                );
+                if !was_open {
+                    REQUEST_METRICS.slow_query_circuit_breaker_trips.inc();
+                }
+            } else if was_open {
+                tracing::info!("Closing slow query circuit breaker");
+            }
+            REQUEST_METRICS
+                .slow_query_circuit_breaker_open
+                .set(state.is_open.into());
+
+            state.window_start = Some(now);
+            state.total_queries = 0;
+            state.slow_queries = 0;
+        }
+        state.total_queries += 1;
+        state.slow_queries += u64::from(is_slow);
+    }
+
+    /// Checks whether the circuit breaker is open.
+    pub(crate) fn is_open(&self) -> bool {
+        let config = &self.config;
+        let state = self
+            .state
+            .lock()
+            .expect("circuit breaker state is poisoned");
+        // If there were no queries since the breaker was opened, it is not re-evaluated, so we check staleness here.
+        state.is_open
+            && state
+                .window_start
+                .is_some_and(|start| start.elapsed() < config.window)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circuit_breaker_basics() {
+        let window = Duration::from_millis(50);
+        let breaker = SlowQueryCircuitBreaker::new(SlowQueryCircuitBreakerConfig {
+            slow_query_ratio: 0.5,
+            min_queries: 4,
+            window,
+        })
+        .unwrap();
+        for i in 0..4 {
+            breaker.observe_query(i % 2 == 0);
+        }
+        assert!(!breaker.is_open());
+
+        std::thread::sleep(window);
+        breaker.observe_query(false);
+        assert!(breaker.is_open());
+
+        // The breaker should close after a window with insufficient number of queries.
+        std::thread::sleep(window);
+        breaker.observe_query(false);
+        assert!(!breaker.is_open());
+    }
+
+    #[test]
+    fn circuit_breaker_is_closed_after_idle_window() {
+        let window = Duration::from_millis(50);
+        let breaker = SlowQueryCircuitBreaker::new(SlowQueryCircuitBreakerConfig {
+            slow_query_ratio: 1.0,
+            min_queries: 1,
+            window,
+        })
+        .unwrap();
+        breaker.observe_query(true);
+        std::thread::sleep(window);
+        breaker.observe_query(true);
+        assert!(breaker.is_open());
+
+        std::thread::sleep(window);
+        assert!(!breaker.is_open());
+    }
+
+    #[test]
+    fn invalid_circuit_breaker_config() {
+        let config = SlowQueryCircuitBreakerConfig {
+            slow_query_ratio: 0.0,
+            min_queries: 1,
+            window: Duration::from_secs(1),
+        };
+        SlowQueryCircuitBreaker::new(config).unwrap_err();
+        let config = SlowQueryCircuitBreakerConfig {
+            window: Duration::ZERO,
+            slow_query_ratio: 0.5,
+            ..config
+        };
+        SlowQueryCircuitBreaker::new(config).unwrap_err();
+    }
+}
@@ -10,10 +10,12 @@ use std::{
 };
 
 use sqlx::{
-    pool::PoolConnection, types::chrono, Connection as _, PgConnection, Postgres, Transaction,
+    pool::PoolConnection, types::chrono, Connection as _, PgConnection, PgPool, Postgres,
+    Transaction,
 };
 
 use crate::{
+    circuit_breaker::SlowQueryCircuitBreaker,
     connection_pool::ConnectionPool,
     error::{DalConnectionError, DalResult},
     metrics::CONNECTION_METRICS,
@@ -155,6 +157,11 @@ pub trait DbMarker: 'static + Send + Sync + Clone {}
 #[derive(Debug)]
 pub struct Connection<'a, DB: DbMarker> {
     inner: ConnectionInner<'a>,
+    /// Pool the connection was acquired from. Used to run auxiliary queries (e.g., `EXPLAIN` for slow queries)
+    /// without affecting the connection.
+    pool: PgPool,
+    /// Circuit breaker of the pool the connection was acquired from.
+    slow_query_circuit_breaker: Option<&'a SlowQueryCircuitBreaker>,
     _marker: std::marker::PhantomData<DB>,
 }
 
@@ -163,9 +170,11 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
     /// This method borrows one of the connections from the pool, and releases it
     /// after `drop`.
     pub(crate) fn from_pool(
+        pool: &PgPool,
         connection: PoolConnection<Postgres>,
         tags: Option<ConnectionTags>,
         traced_connections: Option<&'a TracedConnections>,
+        slow_query_circuit_breaker: Option<&'a SlowQueryCircuitBreaker>,
     ) -> Self {
         let created_at = Instant::now();
         let inner = ConnectionInner::Pooled(PooledConnection {
@@ -179,11 +188,15 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         });
         Self {
             inner,
+            pool: pool.clone(),
+            slow_query_circuit_breaker,
             _marker: Default::default(),
         }
     }
 
     pub async fn start_transaction(&mut self) -> DalResult<Connection<'_, DB>> {
+        let pool = self.pool.clone();
+        let slow_query_circuit_breaker = self.slow_query_circuit_breaker;
         let (conn, tags) = self.conn_and_tags();
         let inner = ConnectionInner::Transaction {
             transaction: conn
@@ -194,6 +207,8 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         };
         Ok(Connection {
             inner,
+            pool,
+            slow_query_circuit_breaker,
             _marker: Default::default(),
         })
     }
@@ -222,6 +237,14 @@ impl<'a, DB: DbMarker> Connection<'a, DB> {
         self.conn_and_tags().0
     }
 
+    pub(crate) fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    pub(crate) fn slow_query_circuit_breaker(&self) -> Option<&SlowQueryCircuitBreaker> {
+        self.slow_query_circuit_breaker
+    }
+
     pub fn conn_and_tags(&mut self) -> (&mut PgConnection, Option<&ConnectionTags>) {
         match &mut self.inner {
             ConnectionInner::Pooled(pooled) => (&mut pooled.connection, pooled.tags.as_ref()),
@@ -4,7 +4,7 @@ use std::{
     marker::PhantomData,
     panic::Location,
     sync::{
-        atomic::{AtomicU64, Ordering},
+        atomic::{AtomicBool, AtomicU64, Ordering},
         Arc,
     },
     time::Duration,
@@ -16,12 +16,14 @@ use sqlx::{
     pool::PoolConnection,
     postgres::{PgConnectOptions, PgPool, PgPoolOptions, Postgres},
 };
+use tokio::sync::watch;
 use zksync_basic_types::url::SensitiveUrl;
 
 use crate::{
+    circuit_breaker::{SlowQueryCircuitBreaker, SlowQueryCircuitBreakerConfig},
     connection::{Connection, ConnectionTags, DbMarker, TracedConnections},
     error::{DalConnectionError, DalResult},
-    metrics::CONNECTION_METRICS,
+    metrics::{ReplicaFallbackReason, CONNECTION_METRICS},
 };
 
 /// Builder for [`ConnectionPool`]s.
@@ -31,6 +33,9 @@ pub struct ConnectionPoolBuilder<DB: DbMarker> {
     max_size: u32,
     acquire_timeout: Duration,
     statement_timeout: Option<Duration>,
+    replica_url: Option<SensitiveUrl>,
+    max_replica_lag: Duration,
+    slow_query_circuit_breaker: Option<SlowQueryCircuitBreakerConfig>,
     _db: PhantomData<DB>,
 }
 
@@ -42,6 +47,12 @@ impl<DB: DbMarker> fmt::Debug for ConnectionPoolBuilder<DB> {
             .field("max_size", &self.max_size)
             .field("acquire_timeout", &self.acquire_timeout)
             .field("statement_timeout", &self.statement_timeout)
+            .field("replica_url", &self.replica_url)
+            .field("max_replica_lag", &self.max_replica_lag)
+            .field(
+                "slow_query_circuit_breaker",
+                &self.slow_query_circuit_breaker,
+            )
             .field("db", &any::type_name::<DB>())
             .finish()
     }
@@ -72,46 +83,102 @@ impl<DB: DbMarker> ConnectionPoolBuilder<DB> {
         self
     }
 
+    /// Sets the URL of a read replica of the database. If set, connections acquired via
+    /// [`ConnectionPool::read_connection_tagged()`] will be served by the replica (with the same max pool size
+    /// and timeouts as for the primary database) unless it lags behind the primary by more than
+    /// [the max lag](Self::set_max_replica_lag()). Replica lag is only checked by
+    /// [`ConnectionPool::run_replica_lag_monitor()`], which should be run alongside the pool.
+    pub fn set_replica_url(&mut self, replica_url: Option<SensitiveUrl>) -> &mut Self {
+        self.replica_url =
+            replica_url.map(|url| url.with_sensitive_query_params(&["user", "password"]));
+        self
+    }
+
+    /// Sets the maximum replication lag of the read replica after which read-only connections
+    /// are served by the primary database. The default value is 5 seconds.
+    pub fn set_max_replica_lag(&mut self, max_lag: Duration) -> &mut Self {
+        self.max_replica_lag = max_lag;
+        self
+    }
+
+    /// Sets the circuit breaker that opens if the share of slow queries executed on connections from the pool
+    /// exceeds the configured ratio. If not specified, the circuit breaker is disabled.
+    pub fn set_slow_query_circuit_breaker(
+        &mut self,
+        config: Option<SlowQueryCircuitBreakerConfig>,
+    ) -> &mut Self {
+        self.slow_query_circuit_breaker = config;
+        self
+    }
+
     /// Returns the maximum number of connections that can be allocated by the pool.
     pub fn max_size(&self) -> u32 {
         self.max_size
     }
 
-    /// Builds a connection pool from this builder.
-    pub async fn build(&self) -> anyhow::Result<ConnectionPool<DB>> {
+    async fn connect(&self, database_url: &SensitiveUrl) -> anyhow::Result<PgPool> {
         let options = PgPoolOptions::new()
             .max_connections(self.max_size)
             .acquire_timeout(self.acquire_timeout);
-        let mut connect_options: PgConnectOptions = self
-            .database_url
+        let mut connect_options: PgConnectOptions = database_url
             .expose_str()
             .parse()
             .context("Failed parsing database URL")?;
         if let Some(timeout) = self.statement_timeout {
-            let timeout_string = format!("{}s", timeout.as_secs());
+            let timeout_string = format!("{}ms", timeout.as_millis());
             connect_options = connect_options.options([("statement_timeout", timeout_string)]);
         }
-        let pool = options
+        options
             .connect_with(connect_options)
             .await
-            .context("Failed connecting to database")?;
+            .context("Failed connecting to database")
+    }
+
+    /// Builds a connection pool from this builder.
+    pub async fn build(&self) -> anyhow::Result<ConnectionPool<DB>> {
+        let slow_query_circuit_breaker = self
+            .slow_query_circuit_breaker
+            .map(SlowQueryCircuitBreaker::new)
+            .transpose()
+            .context("invalid slow query circuit breaker config")?;
+        let pool = self.connect(&self.database_url).await?;
+        let replica = if let Some(replica_url) = &self.replica_url {
+            let pool = self
+                .connect(replica_url)
+                .await
+                .context("failed connecting to read replica")?;
+            Some(Arc::new(Replica {
+                inner: pool,
+                database_url: replica_url.clone(),
+                max_lag: self.max_replica_lag,
+                is_lagging: AtomicBool::new(false),
+            }))
+        } else {
+            None
+        };
         tracing::info!("Created DB pool with parameters {self:?}");
         Ok(ConnectionPool {
             database_url: self.database_url.clone(),
             inner: pool,
             max_size: self.max_size,
+            replica,
+            slow_query_circuit_breaker: slow_query_circuit_breaker.map(Arc::new),
             traced_connections: None,
             _db: PhantomData,
         })
     }
 
-    /// Builds a connection pool that has a single connection.
+    /// Builds a connection pool that has a single connection. The read replica and the slow query circuit breaker,
+    /// if set, are ignored.
     pub async fn build_singleton(&self) -> anyhow::Result<ConnectionPool<DB>> {
         let singleton_builder = Self {
             database_url: self.database_url.clone(),
             max_size: 1,
             acquire_timeout: self.acquire_timeout,
             statement_timeout: self.statement_timeout,
+            replica_url: None,
+            max_replica_lag: self.max_replica_lag,
+            slow_query_circuit_breaker: None,
             _db: PhantomData,
         };
         singleton_builder.build().await
@@ -248,12 +315,65 @@ impl GlobalConnectionPoolConfig {
     }
 }
 
+/// Read replica of the database used by a [`ConnectionPool`].
+#[derive(Debug)]
+struct Replica {
+    inner: PgPool,
+    database_url: SensitiveUrl,
+    max_lag: Duration,
+    is_lagging: AtomicBool,
+}
+
+impl Replica {
+    /// Returns replication lag, or `None` if the database is not a replica.
+    async fn lag(&self) -> sqlx::Result<Option<Duration>> {
+        // If all received WAL is replayed, the replica is up to date even if the last replayed transaction is old
+        // (e.g., if there's no load on the primary database).
+        let lag_secs = sqlx::query_scalar::<_, Option<f64>>(
+            "SELECT CASE \
+                WHEN pg_last_wal_receive_lsn() = pg_last_wal_replay_lsn() THEN 0 \
+                ELSE EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp()) \
+            END::FLOAT8",
+        )
+        .fetch_one(&self.inner)
+        .await?;
+        Ok(lag_secs.map(|secs| Duration::from_secs_f64(secs.max(0.0))))
+    }
+
+    async fn update_lag(&self) {
+        let is_lagging = match self.lag().await {
+            Ok(lag) => {
+                let lag = lag.unwrap_or_default();
+                CONNECTION_METRICS.replica_lag.set(lag);
+                lag > self.max_lag
+            }
+            Err(err) => {
+                tracing::warn!("Failed checking DB replica lag: {err}");
+                true
+            }
+        };
+        let was_lagging = self.is_lagging.swap(is_lagging, Ordering::Relaxed);
+        if is_lagging && !was_lagging {
+            tracing::warn!(
+                "DB replica lags behind primary by more than {:?}; serving read-only connections from primary",
+                self.max_lag
+            );
+        } else if !is_lagging && was_lagging {
+            tracing::info!(
+                "DB replica has caught up with primary; serving read-only connections from replica"
+            );
+        }
+    }
+}
+
 /// Pool of reusable database connections.
 #[derive(Clone)]
 pub struct ConnectionPool<DB: DbMarker> {
     pub(crate) inner: PgPool,
     database_url: SensitiveUrl,
     max_size: u32,
+    replica: Option<Arc<Replica>>,
+    slow_query_circuit_breaker: Option<Arc<SlowQueryCircuitBreaker>>,
     pub(crate) traced_connections: Option<Arc<TracedConnections>>,
     _db: PhantomData<DB>,
 }
@@ -266,6 +386,11 @@ impl<DB: DbMarker> fmt::Debug for ConnectionPool<DB> {
             .field("options", &self.inner.options())
             .field("size", &self.inner.size())
             .field("num_idle", &self.inner.num_idle())
+            .field("replica", &self.replica)
+            .field(
+                "slow_query_circuit_breaker",
+                &self.slow_query_circuit_breaker,
+            )
             .field("db", &any::type_name::<DB>())
             .field("traced_connections", &self.traced_connections)
             .finish()
@@ -316,6 +441,9 @@ impl<DB: DbMarker> ConnectionPool<DB> {
             max_size: max_pool_size,
             acquire_timeout: Duration::from_secs(30), // Default value used by `sqlx`
             statement_timeout: None,
+            replica_url: None,
+            max_replica_lag: Duration::from_secs(5),
+            slow_query_circuit_breaker: None,
             _db: PhantomData,
         }
     }
@@ -332,6 +460,12 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         &self.database_url
     }
 
+    /// Returns the read replica URL for this pool, if any. Similar to [`Self::database_url()`], the URL
+    /// may include authentication info.
+    pub fn replica_url(&self) -> Option<&SensitiveUrl> {
+        self.replica.as_ref().map(|replica| &replica.database_url)
+    }
+
     /// Returns the maximum number of connections in this pool specified during its creation.
     /// This number may be distinct from the current number of connections in the pool (including
     /// idle ones).
@@ -339,6 +473,14 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         self.max_size
     }
 
+    /// Checks whether the slow query circuit breaker of this pool is open, i.e., whether the database is overloaded
+    /// with slow queries. Always returns `false` if the circuit breaker is not configured for the pool.
+    pub fn is_overloaded(&self) -> bool {
+        self.slow_query_circuit_breaker
+            .as_ref()
+            .is_some_and(|breaker| breaker.is_open())
+    }
+
     /// Creates a `Connection` entity over a recoverable connection.
     /// Upon a database outage connection will block the thread until
     /// it will be able to recover the connection (or, if connection cannot
@@ -372,6 +514,85 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         }
     }
 
+    /// Same as [`Self::connection_tagged()`], but the returned connection may be served by the read replica
+    /// of the database if it is configured and doesn't lag behind the primary database. Hence, the connection
+    /// must only be used for read-only queries, and it may observe data that is slightly stale.
+    ///
+    /// If there is no replica, or it lags, or a connection to it cannot be acquired, this method falls back
+    /// to acquiring a connection to the primary database.
+    #[track_caller]
+    pub fn read_connection_tagged(
+        &self,
+        requester: &'static str,
+    ) -> impl Future<Output = DalResult<Connection<'_, DB>>> + '_ {
+        let location = Location::caller();
+        async move {
+            let tags = ConnectionTags {
+                requester,
+                location,
+            };
+            self.read_connection_inner(tags).await
+        }
+    }
+
+    async fn read_connection_inner(&self, tags: ConnectionTags) -> DalResult<Connection<'_, DB>> {
+        let Some(replica) = &self.replica else {
+            return self.connection_inner(Some(tags)).await;
+        };
+        if replica.is_lagging.load(Ordering::Relaxed) {
+            CONNECTION_METRICS.replica_fallbacks[&ReplicaFallbackReason::Lag].inc();
+            return self.connection_inner(Some(tags)).await;
+        }
+
+        let acquire_latency = CONNECTION_METRICS.acquire.start();
+        match replica.inner.acquire().await {
+            Ok(conn) => {
+                let elapsed = acquire_latency.observe();
+                CONNECTION_METRICS.acquire_tagged[&tags.requester].observe(elapsed);
+                Ok(Connection::<DB>::from_pool(
+                    &replica.inner,
+                    conn,
+                    Some(tags),
+                    self.traced_connections.as_deref(),
+                    self.slow_query_circuit_breaker.as_deref(),
+                ))
+            }
+            Err(err) => {
+                Self::report_connection_error(&err);
+                tracing::warn!(
+                    "Failed to get connection to DB replica ({tags}), falling back to primary DB: {err}"
+                );
+                CONNECTION_METRICS.replica_fallbacks[&ReplicaFallbackReason::Error].inc();
+                self.connection_inner(Some(tags)).await
+            }
+        }
+    }
+
+    /// Periodically checks replication lag of the read replica, switching read-only connections
+    /// to the primary database if the lag exceeds the configured limit (and back once the replica catches up).
+    /// Returns immediately if the pool has no replica.
+    pub async fn run_replica_lag_monitor(
+        self,
+        check_interval: Duration,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        let Some(replica) = self.replica else {
+            return Ok(());
+        };
+
+        while !*stop_receiver.borrow_and_update() {
+            replica.update_lag().await;
+            if tokio::time::timeout(check_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, DB replica lag monitor is shutting down");
+        Ok(())
+    }
+
     async fn connection_inner(
         &self,
         tags: Option<ConnectionTags>,
@@ -384,9 +605,11 @@ impl<DB: DbMarker> ConnectionPool<DB> {
         }
 
         Ok(Connection::<DB>::from_pool(
+            &self.inner,
             conn,
             tags,
             self.traced_connections.as_deref(),
+            self.slow_query_circuit_breaker.as_deref(),
         ))
     }
 
@@ -418,6 +641,7 @@ impl<DB: DbMarker> ConnectionPool<DB> {
                 "Failed to get connection to DB ({tags_display}), backing off for {backoff_interval:?}: {connection_err}"
             );
             tokio::time::sleep(backoff_interval).await;
+            CONNECTION_METRICS.pool_acquire_retries.inc();
         }
 
         // Attempting to get the pooled connection for the last time
@@ -437,7 +661,7 @@ mod tests {
     use assert_matches::assert_matches;
 
     use super::*;
-    use crate::utils::InternalMarker;
+    use crate::{instrument::InstrumentExt, utils::InternalMarker};
 
     #[tokio::test]
     async fn setting_statement_timeout() {
@@ -465,4 +689,93 @@ mod tests {
             sqlx::Error::Database(db_err) if db_err.message().contains("statement timeout")
         );
     }
+
+    #[tokio::test]
+    async fn using_read_replica() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db::<InternalMarker>(2)
+            .await
+            .unwrap()
+            .database_url;
+
+        // We don't have a real replica in tests, so we use the same DB as both primary and replica.
+        let pool = ConnectionPool::<InternalMarker>::builder(db_url.clone(), 2)
+            .set_replica_url(Some(db_url))
+            .set_max_replica_lag(Duration::from_millis(10))
+            .build()
+            .await
+            .unwrap();
+        let replica = pool.replica.clone().unwrap();
+        assert_eq!(replica.lag().await.unwrap(), None); // not a replica
+        replica.update_lag().await;
+        assert!(!replica.is_lagging.load(Ordering::Relaxed));
+
+        let mut conn = pool.read_connection_tagged("test").await.unwrap();
+        sqlx::query("SELECT 1")
+            .map(drop)
+            .fetch_one(conn.conn())
+            .await
+            .unwrap();
+        drop(conn);
+
+        // Connections should be served by the primary DB if the replica lags.
+        replica.is_lagging.store(true, Ordering::Relaxed);
+        let mut conn = pool.read_connection_tagged("test").await.unwrap();
+        sqlx::query("SELECT 1")
+            .map(drop)
+            .fetch_one(conn.conn())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn slow_query_circuit_breaker_is_scoped_to_pool() {
+        let db_url = TestTemplate::empty()
+            .unwrap()
+            .create_db::<InternalMarker>(2)
+            .await
+            .unwrap()
+            .database_url;
+
+        let window = Duration::from_millis(500);
+        let pool = ConnectionPool::<InternalMarker>::singleton(db_url.clone())
+            .set_slow_query_circuit_breaker(Some(SlowQueryCircuitBreakerConfig {
+                slow_query_ratio: 1.0,
+                min_queries: 1,
+                window,
+            }))
+            .build()
+            .await
+            .unwrap();
+        let other_pool = ConnectionPool::<InternalMarker>::singleton(db_url)
+            .build()
+            .await
+            .unwrap();
+
+        let mut conn = pool.connection().await.unwrap();
+        // Default slow query threshold is 100ms.
+        sqlx::query("SELECT pg_sleep(0.2)")
+            .instrument("slow")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        tokio::time::sleep(window).await;
+        sqlx::query("SELECT pg_sleep(0.2)")
+            .instrument("slow")
+            .execute(&mut conn)
+            .await
+            .unwrap();
+        assert!(pool.is_overloaded());
+
+        // Slow queries on one pool must not affect other pools.
+        assert!(!other_pool.is_overloaded());
+        let mut other_conn = other_pool.connection().await.unwrap();
+        sqlx::query("SELECT 1")
+            .instrument("fast")
+            .execute(&mut other_conn)
+            .await
+            .unwrap();
+        assert!(!other_pool.is_overloaded());
+    }
 }
@@ -7,6 +7,7 @@ use crate::{connection::DbMarker, connection_pool::ConnectionPool};
 struct ConnectionPoolHealthDetails {
     pool_size: u32,
     max_size: u32,
+    slow_query_circuit_breaker_open: bool,
 }
 
 impl ConnectionPoolHealthDetails {
@@ -14,6 +15,7 @@ impl ConnectionPoolHealthDetails {
         Self {
             pool_size: pool.inner.size(),
             max_size: pool.max_size(),
+            slow_query_circuit_breaker_open: pool.is_overloaded(),
         }
     }
 }
@@ -44,7 +46,13 @@ impl<DB: DbMarker + Send + Sync + 'static> CheckHealth for ConnectionPoolHealthC
         match self.connection_pool.connection().await {
             Ok(_) => {
                 let details = ConnectionPoolHealthDetails::new(&self.connection_pool);
-                Health::from(HealthStatus::Ready).with_details(details)
+                // The database is considered unhealthy if it is overloaded with slow queries.
+                let status = if details.slow_query_circuit_breaker_open {
+                    HealthStatus::NotReady
+                } else {
+                    HealthStatus::Ready
+                };
+                Health::from(status).with_details(details)
             }
             Err(err) => {
                 tracing::warn!("Failed acquiring DB connection for health check: {err:?}");
@@ -4,21 +4,30 @@
 //!
 //! - Report query latency as a metric
 //! - Report slow and failing queries as metrics
+//! - Report the number of rows returned or affected by queries, and [fingerprints](QueryFingerprint)
+//!   of slow statements as metrics
 //! - Log slow and failing queries together with their arguments, which makes it easier to debug.
+//! - Log `EXPLAIN` output for slow queries (rate-limited per statement fingerprint). `EXPLAIN` is captured
+//!   in the background on a separate connection.
 //!
 //! The entry point for instrumentation is the [`InstrumentExt`] trait. After it is imported into the scope,
 //! its `instrument()` method can be placed on the output of `query*` functions or macros. You can then call
 //! [`Instrumented`] methods on the returned struct, e.g. to [report query latency](Instrumented::report_latency())
 //! and/or [to add logged args](Instrumented::with_arg()) for a query.
 
-use std::{fmt, future::Future, panic::Location};
+use std::{
+    cell::Cell, collections::HashMap, fmt, future::Future, hash::Hasher, panic::Location,
+    sync::Mutex, time::Duration,
+};
 
 use sqlx::{
-    postgres::{PgCopyIn, PgQueryResult, PgRow},
+    pool::PoolConnection,
+    postgres::{PgCopyIn, PgPool, PgQueryResult, PgRow},
     query::{Map, Query, QueryAs, QueryScalar},
-    FromRow, IntoArguments, PgConnection, Postgres,
+    Connection as _, Execute, FromRow, IntoArguments, PgConnection, Postgres,
 };
 use tokio::time::Instant;
+use tracing::Instrument as _;
 
 use crate::{
     connection::{Connection, ConnectionTags, DbMarker},
@@ -30,6 +39,283 @@ use crate::{
 
 type ThreadSafeDebug<'a> = dyn fmt::Debug + Send + Sync + 'a;
 
+thread_local! {
+    static QUERY_TIME_ON_THREAD: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Returns the total time spent executing instrumented queries which have completed on the current thread.
+///
+/// Can be used to measure DB time spent by a future by comparing the values before and after each poll
+/// of the future; queries are accounted for when the poll completing them returns.
+pub fn query_time_on_current_thread() -> Duration {
+    QUERY_TIME_ON_THREAD.with(Cell::get)
+}
+
+/// Stable fingerprint of an SQL statement.
+///
+/// The fingerprint is a 64-bit FNV-1a hash of the normalized statement: comments are removed, whitespace is collapsed,
+/// ASCII chars are lowercased, and string / numeric literals are replaced with a placeholder. Thus, the fingerprint
+/// doesn't change with query formatting or inlined values, but does change with any other statement change.
+/// Unlike `std` hashers, the hash function is fixed, so fingerprints are stable across builds and can be compared
+/// between different node versions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QueryFingerprint(u64);
+
+impl QueryFingerprint {
+    /// Computes the fingerprint of the provided SQL statement.
+    pub fn new(sql: &str) -> Self {
+        let mut hasher = FnvHasher::default();
+        let mut chars = sql.chars().peekable();
+        let mut pending_space = false;
+        let mut prev_char: Option<char> = None;
+
+        while let Some(ch) = chars.next() {
+            let normalized = match ch {
+                '-' if chars.peek() == Some(&'-') => {
+                    // Skip a line comment.
+                    chars.find(|&ch| ch == '\n');
+                    pending_space = true;
+                    continue;
+                }
+                '\'' => {
+                    // Skip a string literal; `''` inside a literal is an escaped quote.
+                    while let Some(ch) = chars.next() {
+                        if ch == '\'' && chars.next_if_eq(&'\'').is_none() {
+                            break;
+                        }
+                    }
+                    '?'
+                }
+                '0'..='9' if pending_space || !prev_char.is_some_and(is_ident_char) => {
+                    while chars
+                        .next_if(|ch| ch.is_ascii_digit() || *ch == '.')
+                        .is_some()
+                    {}
+                    '?'
+                }
+                _ if ch.is_whitespace() => {
+                    pending_space = true;
+                    continue;
+                }
+                _ => ch.to_ascii_lowercase(),
+            };
+
+            if pending_space && prev_char.is_some() {
+                hasher.write_u8(b' ');
+            }
+            pending_space = false;
+            let mut buffer = [0_u8; 4];
+            hasher.write(normalized.encode_utf8(&mut buffer).as_bytes());
+            prev_char = Some(normalized);
+        }
+        Self(hasher.finish())
+    }
+}
+
+impl fmt::Display for QueryFingerprint {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{:016x}", self.0)
+    }
+}
+
+fn is_ident_char(ch: char) -> bool {
+    ch.is_alphanumeric() || ch == '_' || ch == '$'
+}
+
+/// 64-bit FNV-1a hasher.
+#[derive(Debug)]
+struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 ^= u64::from(byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Rate limiter for capturing `EXPLAIN` output of slow queries, so that a burst of slow queries doesn't overload
+/// the database with `EXPLAIN`s or spam logs.
+#[derive(Debug)]
+struct ExplainRateLimiter {
+    last_explained: Mutex<Option<HashMap<QueryFingerprint, std::time::Instant>>>,
+}
+
+impl ExplainRateLimiter {
+    /// Minimum interval between capturing `EXPLAIN` output for the same statement.
+    const INTERVAL: Duration = Duration::from_secs(600);
+    /// Maximum number of tracked statements; the tracked statements are reset once it's exceeded.
+    const MAX_TRACKED_STATEMENTS: usize = 10_000;
+
+    const fn new() -> Self {
+        Self {
+            last_explained: Mutex::new(None),
+        }
+    }
+
+    fn should_explain(&self, fingerprint: QueryFingerprint) -> bool {
+        let now = std::time::Instant::now();
+        let mut last_explained = self.last_explained.lock().expect("poisoned");
+        let last_explained = last_explained.get_or_insert_with(HashMap::new);
+        if last_explained.len() >= Self::MAX_TRACKED_STATEMENTS {
+            last_explained.clear();
+        }
+
+        match last_explained.get(&fingerprint) {
+            Some(&timestamp) if now.duration_since(timestamp) < Self::INTERVAL => false,
+            _ => {
+                last_explained.insert(fingerprint, now);
+                true
+            }
+        }
+    }
+}
+
+static EXPLAIN_RATE_LIMITER: ExplainRateLimiter = ExplainRateLimiter::new();
+
+/// Slow query for which `EXPLAIN` output should be logged.
+#[derive(Debug)]
+#[must_use = "EXPLAIN output should be logged using `explain()`"]
+struct SlowQuery<'q> {
+    name: &'static str,
+    fingerprint: QueryFingerprint,
+    sql: &'q str,
+}
+
+impl<'q> SlowQuery<'q> {
+    /// Minimum server version (as reported by `server_version_num`) supporting `EXPLAIN (GENERIC_PLAN)`.
+    const MIN_GENERIC_PLAN_VERSION: i32 = 160_000;
+    /// Timeout for capturing `EXPLAIN` output.
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn new(name: &'static str, fingerprint: QueryFingerprint, sql: &'q str) -> Option<Self> {
+        let statement_kind = sql
+            .trim_start()
+            .split(|ch: char| !ch.is_ascii_alphabetic())
+            .next()?
+            .to_ascii_lowercase();
+        let is_explainable = matches!(
+            statement_kind.as_str(),
+            "select" | "with" | "insert" | "update" | "delete" | "values"
+        );
+        (is_explainable && EXPLAIN_RATE_LIMITER.should_explain(fingerprint)).then_some(Self {
+            name,
+            fingerprint,
+            sql,
+        })
+    }
+
+    /// Logs `EXPLAIN` output for the query in the background. `EXPLAIN` is executed on a separate idle connection
+    /// from `pool` (if there is one) in a transaction that is rolled back, so it cannot affect the caller.
+    fn explain(self, pool: &PgPool) {
+        let Some(conn) = pool.try_acquire() else {
+            tracing::info!(
+                "Not capturing EXPLAIN output for slow query {name} [fingerprint={fingerprint}] since \
+                 there are no idle connections in the pool",
+                name = self.name,
+                fingerprint = self.fingerprint
+            );
+            return;
+        };
+        let Self {
+            name,
+            fingerprint,
+            sql,
+        } = self;
+        let sql = sql.to_owned();
+        tokio::spawn(async move {
+            let explain_result =
+                tokio::time::timeout(Self::TIMEOUT, Self::capture_plan(conn, &sql));
+            match explain_result.await {
+                Ok(Ok(Some(plan))) => {
+                    tracing::info!(
+                        "EXPLAIN output for slow query {name} [fingerprint={fingerprint}]:\n{plan}"
+                    );
+                    REQUEST_METRICS.request_explained[&name].inc();
+                }
+                Ok(Ok(None)) => {
+                    tracing::info!(
+                        "Not capturing EXPLAIN output for slow query {name} [fingerprint={fingerprint}] since \
+                         explaining parameterized queries requires Postgres 16+"
+                    );
+                }
+                Ok(Err(err)) => {
+                    tracing::info!(
+                        "Failed capturing EXPLAIN output for slow query {name} [fingerprint={fingerprint}]: {err}"
+                    );
+                }
+                Err(_) => {
+                    tracing::info!(
+                        "Timed out capturing EXPLAIN output for slow query {name} [fingerprint={fingerprint}]"
+                    );
+                }
+            }
+        });
+    }
+
+    /// Returns `None` if the query cannot be explained by the server.
+    async fn capture_plan(
+        mut conn: PoolConnection<Postgres>,
+        sql: &str,
+    ) -> sqlx::Result<Option<String>> {
+        let has_params = sql
+            .as_bytes()
+            .windows(2)
+            .any(|window| window[0] == b'$' && window[1].is_ascii_digit());
+        let explain_sql = if has_params {
+            // `GENERIC_PLAN` allows explaining parameterized statements without knowing param values,
+            // but is only supported starting from Postgres 16.
+            let server_version =
+                sqlx::query_scalar::<_, i32>("SELECT current_setting('server_version_num')::int")
+                    .fetch_one(&mut *conn)
+                    .await?;
+            if server_version < Self::MIN_GENERIC_PLAN_VERSION {
+                return Ok(None);
+            }
+            format!("EXPLAIN (GENERIC_PLAN) {sql}")
+        } else {
+            format!("EXPLAIN {sql}")
+        };
+
+        let mut transaction = conn.begin().await?;
+        let plan = sqlx::query_scalar::<_, String>(&explain_sql)
+            .persistent(false)
+            .fetch_all(&mut *transaction)
+            .await;
+        transaction.rollback().await?;
+        Ok(Some(plan?.join("\n")))
+    }
+}
+
+/// Outcome of an executed query.
+struct QueryOutcome<'q> {
+    is_slow: bool,
+    /// Set if the query is slow and should be explained.
+    slow_query: Option<SlowQuery<'q>>,
+}
+
+/// Reports the query outcome to the slow query circuit breaker of the connection pool (if any),
+/// and logs `EXPLAIN` output for a slow query, if any.
+fn report_query_outcome<DB: DbMarker>(outcome: QueryOutcome<'_>, storage: &Connection<'_, DB>) {
+    if let Some(circuit_breaker) = storage.slow_query_circuit_breaker() {
+        circuit_breaker.observe_query(outcome.is_slow);
+    }
+    if let Some(slow_query) = outcome.slow_query {
+        slow_query.explain(storage.pool());
+    }
+}
+
 /// Logged arguments for an SQL query.
 #[derive(Debug, Clone, Default)]
 struct QueryArgs<'a> {
@@ -62,6 +348,10 @@ impl fmt::Display for QueryArgs<'_> {
     }
 }
 
+fn optional_row_count<T>(row: &Option<T>) -> usize {
+    usize::from(row.is_some())
+}
+
 /// Extension trait for instrumenting `sqlx::query!` outputs.
 pub trait InstrumentExt: Sized {
     /// Instruments a query, assigning it the provided name.
@@ -200,11 +490,29 @@ impl<'a> InstrumentedData<'a> {
         }
     }
 
-    async fn fetch<R>(
+    /// Executes the query future. Returns the query output and the [`QueryOutcome`] to be reported
+    /// via [`report_query_outcome()`].
+    async fn fetch<'q, R>(
         self,
+        sql: &'q str,
         connection_tags: Option<&ConnectionTags>,
         query_future: impl Future<Output = Result<R, sqlx::Error>>,
-    ) -> DalResult<R> {
+        row_count: impl FnOnce(&R) -> usize,
+    ) -> (DalResult<R>, QueryOutcome<'q>) {
+        // Allows to trace queries as a part of a larger operation (e.g., an API request).
+        let span = tracing::debug_span!("db_query", query = self.name);
+        self.fetch_inner(sql, connection_tags, query_future, row_count)
+            .instrument(span)
+            .await
+    }
+
+    async fn fetch_inner<'q, R>(
+        self,
+        sql: &'q str,
+        connection_tags: Option<&ConnectionTags>,
+        query_future: impl Future<Output = Result<R, sqlx::Error>>,
+        row_count: impl FnOnce(&R) -> usize,
+    ) -> (DalResult<R>, QueryOutcome<'q>) {
         let Self {
             name,
             location,
@@ -225,8 +533,12 @@ impl<'a> InstrumentedData<'a> {
             Err(_) => {
                 let connection_tags = ConnectionTags::display(connection_tags);
                 if slow_query_reporting_enabled {
+                    // Fingerprints are only computed for slow and failed queries to keep the hot path cheap.
+                    let fingerprint = QueryFingerprint::new(sql);
+                    REQUEST_METRICS.request_fingerprint[&(name, fingerprint.to_string())].inc();
                     tracing::warn!(
-                        "Query {name}{args} called at {file}:{line} [{connection_tags}] is executing for more than {slow_query_threshold:?}",
+                        "Query {name}{args} called at {file}:{line} [{connection_tags}] [fingerprint={fingerprint}] \
+                         is executing for more than {slow_query_threshold:?}",
                         file = location.file(),
                         line = location.line()
                     );
@@ -238,32 +550,50 @@ impl<'a> InstrumentedData<'a> {
         };
 
         let elapsed = started_at.elapsed();
+        QUERY_TIME_ON_THREAD.with(|time| time.set(time.get() + elapsed));
         if report_latency {
             REQUEST_METRICS.request[&name].observe(elapsed);
         }
 
         let connection_tags_display = ConnectionTags::display(connection_tags);
-        if let Err(err) = &output {
-            tracing::warn!(
-                "Query {name}{args} called at {file}:{line} [{connection_tags_display}] has resulted in error: {err}",
-                file = location.file(),
-                line = location.line()
-            );
-            REQUEST_METRICS.request_error[&name].inc();
-        } else if is_slow {
-            tracing::info!(
-                "Slow query {name}{args} called at {file}:{line} [{connection_tags_display}] has finished after {elapsed:?}",
-                file = location.file(),
-                line = location.line()
-            );
+        let mut slow_query = None;
+        match &output {
+            Err(err) => {
+                let fingerprint = QueryFingerprint::new(sql);
+                tracing::warn!(
+                    "Query {name}{args} called at {file}:{line} [{connection_tags_display}] [fingerprint={fingerprint}] \
+                     has resulted in error: {err}",
+                    file = location.file(),
+                    line = location.line()
+                );
+                REQUEST_METRICS.request_error[&name].inc();
+            }
+            Ok(output) => {
+                REQUEST_METRICS.request_rows[&name].observe(row_count(output));
+                if is_slow {
+                    let fingerprint = QueryFingerprint::new(sql);
+                    tracing::info!(
+                        "Slow query {name}{args} called at {file}:{line} [{connection_tags_display}] [fingerprint={fingerprint}] \
+                         has finished after {elapsed:?}",
+                        file = location.file(),
+                        line = location.line()
+                    );
+                    slow_query = SlowQuery::new(name, fingerprint, sql);
+                }
+            }
         }
 
-        output.map_err(|err| {
+        let output = output.map_err(|err| {
             DalRequestError::new(err, name, location)
                 .with_args(args.to_owned())
                 .with_connection_tags(connection_tags.cloned())
                 .into()
-        })
+        });
+        let outcome = QueryOutcome {
+            is_slow,
+            slow_query,
+        };
+        (output, outcome)
     }
 }
 
@@ -278,6 +608,12 @@ impl<'a> InstrumentedData<'a> {
 ///   included in the case of a slow query, plus the error info.
 /// - Slow and erroneous queries are also reported using metrics (`dal.request.slow` and `dal.request.error`,
 ///   respectively). The query name is included as a metric label; args are not included for obvious reasons.
+/// - For all successful queries, the number of returned / affected rows is reported as a metric. Slow statements
+///   are also counted per [`QueryFingerprint`].
+/// - For slow successful queries, `EXPLAIN` output is logged with the `INFO` level. `EXPLAIN` is rate-limited
+///   per statement fingerprint, runs in the background on a separate idle connection, and is not captured
+///   for queries marked with [`Self::expect_slow_query()`]. Parameterized queries are only explained
+///   on Postgres 16+.
 #[derive(Debug, Clone)]
 pub struct Instrumented<'a, Q> {
     query: Q,
@@ -353,7 +689,7 @@ impl<'a, Q> Instrumented<'a, Q> {
 
 impl<'q, A> Instrumented<'_, Query<'q, Postgres, A>>
 where
-    A: 'q + IntoArguments<'q, Postgres>,
+    A: 'q + Send + IntoArguments<'q, Postgres>,
 {
     /// Executes an SQL statement using this query.
     pub async fn execute<DB: DbMarker>(
@@ -361,7 +697,12 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<PgQueryResult> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.execute(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.execute(&mut *conn);
+        let row_count = |result: &PgQueryResult| result.rows_affected() as usize;
+        let (output, outcome) = self.data.fetch(sql, tags, query_future, row_count).await;
+        report_query_outcome(outcome, storage);
+        output
     }
 
     /// Fetches an optional row using this query.
@@ -370,13 +711,20 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<PgRow>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_optional(&mut *conn);
+        let (output, outcome) = self
+            .data
+            .fetch(sql, tags, query_future, optional_row_count)
+            .await;
+        report_query_outcome(outcome, storage);
+        output
     }
 }
 
 impl<'q, O, A> Instrumented<'_, QueryAs<'q, Postgres, O, A>>
 where
-    A: 'q + IntoArguments<'q, Postgres>,
+    A: 'q + Send + IntoArguments<'q, Postgres>,
     O: Send + Unpin + for<'r> FromRow<'r, PgRow>,
 {
     /// Fetches all rows using this query and collects them into a `Vec`.
@@ -385,13 +733,17 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Vec<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_all(&mut *conn);
+        let (output, outcome) = self.data.fetch(sql, tags, query_future, Vec::len).await;
+        report_query_outcome(outcome, storage);
+        output
     }
 }
 
 impl<'q, O, A> Instrumented<'_, QueryScalar<'q, Postgres, O, A>>
 where
-    A: 'q + IntoArguments<'q, Postgres>,
+    A: 'q + Send + IntoArguments<'q, Postgres>,
     O: Send + Unpin,
     (O,): for<'r> FromRow<'r, PgRow>,
 {
@@ -401,13 +753,24 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_optional(&mut *conn);
+        let (output, outcome) = self
+            .data
+            .fetch(sql, tags, query_future, optional_row_count)
+            .await;
+        report_query_outcome(outcome, storage);
+        output
     }
 
     /// Fetches a single row using this query.
     pub async fn fetch_one<DB: DbMarker>(self, storage: &mut Connection<'_, DB>) -> DalResult<O> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_one(&mut *conn);
+        let (output, outcome) = self.data.fetch(sql, tags, query_future, |_: &O| 1).await;
+        report_query_outcome(outcome, storage);
+        output
     }
 }
 
@@ -423,13 +786,24 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Option<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_optional(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_optional(&mut *conn);
+        let (output, outcome) = self
+            .data
+            .fetch(sql, tags, query_future, optional_row_count)
+            .await;
+        report_query_outcome(outcome, storage);
+        output
     }
 
     /// Fetches a single row using this query.
     pub async fn fetch_one<DB: DbMarker>(self, storage: &mut Connection<'_, DB>) -> DalResult<O> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_one(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_one(&mut *conn);
+        let (output, outcome) = self.data.fetch(sql, tags, query_future, |_: &O| 1).await;
+        report_query_outcome(outcome, storage);
+        output
     }
 
     /// Fetches all rows using this query and collects them into a `Vec`.
@@ -438,7 +812,11 @@ where
         storage: &mut Connection<'_, DB>,
     ) -> DalResult<Vec<O>> {
         let (conn, tags) = storage.conn_and_tags();
-        self.data.fetch(tags, self.query.fetch_all(conn)).await
+        let sql = self.query.sql();
+        let query_future = self.query.fetch_all(&mut *conn);
+        let (output, outcome) = self.data.fetch(sql, tags, query_future, Vec::len).await;
+        report_query_outcome(outcome, storage);
+        output
     }
 }
 
@@ -488,6 +866,48 @@ mod tests {
             .unwrap_err();
     }
 
+    #[test]
+    fn query_fingerprints() {
+        let fingerprint = QueryFingerprint::new("SELECT 1 FROM blocks WHERE number = $1");
+        // Fingerprints must be stable across builds.
+        assert_eq!(fingerprint.to_string(), "3890dd814c0daffc");
+
+        let formatted_sql = "
+            -- Selects a block
+            select 42
+            FROM   blocks
+            WHERE  number = $1
+        ";
+        assert_eq!(QueryFingerprint::new(formatted_sql), fingerprint);
+        assert_eq!(
+            QueryFingerprint::new("SELECT 'it''s' FROM blocks WHERE number = $1"),
+            fingerprint
+        );
+
+        let other_fingerprints = [
+            "SELECT 1 FROM blocks WHERE number = $2",
+            "SELECT 1 FROM l1_batches WHERE number = $1",
+            "SELECT 1 FROM blocks WHERE hash = $1",
+        ];
+        for sql in other_fingerprints {
+            assert_ne!(QueryFingerprint::new(sql), fingerprint, "{sql}");
+        }
+        // Digits in identifiers are not literals.
+        assert_ne!(
+            QueryFingerprint::new("SELECT l1_batch_number FROM blocks"),
+            QueryFingerprint::new("SELECT l2_batch_number FROM blocks")
+        );
+    }
+
+    #[test]
+    fn explaining_slow_queries_is_rate_limited() {
+        let rate_limiter = ExplainRateLimiter::new();
+        let fingerprint = QueryFingerprint::new("SELECT 1");
+        assert!(rate_limiter.should_explain(fingerprint));
+        assert!(!rate_limiter.should_explain(fingerprint));
+        assert!(rate_limiter.should_explain(QueryFingerprint::new("SELECT 2 + 2")));
+    }
+
     #[tokio::test]
     async fn instrumenting_slow_query() {
         let pool = ConnectionPool::<InternalMarker>::test_pool().await;
@@ -503,4 +923,29 @@ mod tests {
             .await
             .unwrap();
     }
+
+    #[tokio::test]
+    async fn slow_query_does_not_affect_caller_transaction() {
+        let pool = ConnectionPool::<InternalMarker>::test_pool().await;
+        let mut conn = pool.connection().await.unwrap();
+        let mut transaction = conn.start_transaction().await.unwrap();
+        sqlx::query("CREATE TEMPORARY TABLE slow_test (value INT)")
+            .instrument("create_table")
+            .execute(&mut transaction)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO slow_test SELECT 1 FROM pg_sleep(1.5)")
+            .instrument("slow_insert")
+            .execute(&mut transaction)
+            .await
+            .unwrap();
+
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM slow_test")
+            .instrument("count")
+            .fetch_one(&mut transaction)
+            .await
+            .unwrap();
+        assert_eq!(count, 1);
+        transaction.commit().await.unwrap();
+    }
 }
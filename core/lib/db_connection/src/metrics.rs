@@ -1,10 +1,12 @@
 use std::{thread, time::Duration};
 
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Histogram, LabeledFamily,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
     LatencyObserver, Metrics, Unit,
 };
 
+const ROW_COUNT_BUCKETS: Buckets = Buckets::exponential(1.0..=1_048_576.0, 4.0);
+
 /// Request-related DB metrics.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "sql")]
@@ -18,6 +20,20 @@ pub(crate) struct RequestMetrics {
     /// Counter of errored DB requests.
     #[metrics(labels = ["method"])]
     pub request_error: LabeledFamily<&'static str, Counter>,
+    /// Number of rows returned or affected by a DB request.
+    #[metrics(buckets = ROW_COUNT_BUCKETS, labels = ["method"])]
+    pub request_rows: LabeledFamily<&'static str, Histogram<usize>>,
+    /// Counter of slow DB requests labeled by the fingerprint of the executed statement. Allows to detect
+    /// statement changes for a method and to correlate metrics with `EXPLAIN` output in logs.
+    #[metrics(labels = ["method", "fingerprint"])]
+    pub request_fingerprint: LabeledFamily<(&'static str, String), Counter, 2>,
+    /// Number of `EXPLAIN` outputs captured for slow DB requests.
+    #[metrics(labels = ["method"])]
+    pub request_explained: LabeledFamily<&'static str, Counter>,
+    /// Whether the slow query circuit breaker is currently open (1) or closed (0).
+    pub slow_query_circuit_breaker_open: Gauge<u64>,
+    /// Number of times the slow query circuit breaker was opened.
+    pub slow_query_circuit_breaker_trips: Counter,
 }
 
 #[vise::register]
@@ -67,6 +83,16 @@ impl From<&sqlx::Error> for ConnectionErrorKind {
     }
 }
 
+/// Reason for serving a read-only connection by the primary database instead of the read replica.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum ReplicaFallbackReason {
+    /// Replica lags behind the primary database.
+    Lag,
+    /// Failed acquiring a connection to the replica.
+    Error,
+}
+
 const POOL_SIZE_BUCKETS: Buckets = Buckets::linear(0.0..=100.0, 10.0);
 
 /// Connection-related metrics.
@@ -87,9 +113,16 @@ pub(crate) struct ConnectionMetrics {
     pub pool_idle: Histogram<usize>,
     /// Number of errors occurred when acquiring a DB connection.
     pub pool_acquire_error: Family<ConnectionErrorKind, Counter>,
+    /// Number of retries when acquiring a DB connection.
+    pub pool_acquire_retries: Counter,
     /// Lifetime of a DB connection, tagged with the requester label.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds, labels = ["requester"])]
     pub lifetime: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Replication lag of the read replica.
+    #[metrics(unit = Unit::Seconds)]
+    pub replica_lag: Gauge<Duration>,
+    /// Number of read-only connections served by the primary database instead of the read replica.
+    pub replica_fallbacks: Family<ReplicaFallbackReason, Counter>,
 }
 
 #[vise::register]
@@ -1,9 +1,10 @@
 use anyhow::Context as _;
 use zksync_config::configs::{
     api::{
-        ContractVerificationApiConfig, HealthCheckConfig, MerkleTreeApiConfig, Web3JsonRpcConfig,
+        ContractVerificationApiConfig, ExplorerApiConfig, HealthCheckConfig, MerkleTreeApiConfig,
+        Web3JsonRpcConfig,
     },
-    ApiConfig, PrometheusConfig,
+    ApiConfig, PrometheusConfig, PrometheusExportMode,
 };
 
 use crate::{envy_load, FromEnv};
@@ -15,6 +16,7 @@ impl FromEnv for ApiConfig {
             prometheus: PrometheusConfig::from_env().context("PrometheusConfig")?,
             healthcheck: HealthCheckConfig::from_env().context("HealthCheckConfig")?,
             merkle_tree: MerkleTreeApiConfig::from_env().context("MerkleTreeApiConfig")?,
+            explorer: ExplorerApiConfig::from_env().context("ExplorerApiConfig")?,
         })
     }
 }
@@ -44,6 +46,12 @@ impl FromEnv for MerkleTreeApiConfig {
     }
 }
 
+impl FromEnv for ExplorerApiConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("explorer_api", "API_EXPLORER_")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::num::{NonZeroU32, NonZeroUsize};
@@ -62,6 +70,7 @@ mod tests {
                 ws_url: "ws://127.0.0.1:3051".into(),
                 req_entities_limit: Some(10000),
                 filters_disabled: false,
+                logs_pagination_enabled: true,
                 filters_limit: Some(10000),
                 subscriptions_limit: Some(10000),
                 pubsub_polling_interval: Some(200),
@@ -74,6 +83,7 @@ mod tests {
                 estimate_gas_scale_factor: 1.0f64,
                 gas_price_scale_factor: 1.2,
                 estimate_gas_acceptable_overestimation: 1000,
+                estimate_gas_single_pass: true,
                 max_tx_size: 1000000,
                 vm_execution_cache_misses_limit: None,
                 vm_concurrency_limit: Some(512),
@@ -94,6 +104,9 @@ mod tests {
                 tree_api_url: None,
                 mempool_cache_update_interval: Some(50),
                 mempool_cache_size: Some(10000),
+                response_cache_size_mb: Some(64),
+                response_cache_ttl_sec: Some(60),
+                response_cache_redis_url: Some("redis://127.0.0.1:6379/".parse().unwrap()),
                 whitelisted_tokens_for_aa: vec![
                     addr("0x0000000000000000000000000000000000000001"),
                     addr("0x0000000000000000000000000000000000000002"),
@@ -103,13 +116,23 @@ mod tests {
                 listener_port: 3312,
                 pushgateway_url: "http://127.0.0.1:9091".into(),
                 push_interval_ms: Some(100),
+                export_mode: PrometheusExportMode::Otlp,
+                otlp_endpoint: Some("http://127.0.0.1:4318/v1/metrics".into()),
+                otlp_export_interval_ms: Some(5_000),
             },
             healthcheck: HealthCheckConfig {
                 port: 8081,
                 slow_time_limit_ms: Some(250),
                 hard_time_limit_ms: Some(2_000),
             },
-            merkle_tree: MerkleTreeApiConfig { port: 8082 },
+            merkle_tree: MerkleTreeApiConfig {
+                port: 8082,
+                grpc_port: Some(8083),
+            },
+            explorer: ExplorerApiConfig {
+                port: 8084,
+                max_page_size: 50,
+            },
         }
     }
 
@@ -123,6 +146,7 @@ mod tests {
             API_WEB3_JSON_RPC_WS_URL="ws://127.0.0.1:3051"
             API_WEB3_JSON_RPC_REQ_ENTITIES_LIMIT=10000
             API_WEB3_JSON_RPC_FILTERS_DISABLED=false
+            API_WEB3_JSON_RPC_LOGS_PAGINATION_ENABLED=true
             API_WEB3_JSON_RPC_FILTERS_LIMIT=10000
             API_WEB3_JSON_RPC_SUBSCRIPTIONS_LIMIT=10000
             API_WEB3_JSON_RPC_PUBSUB_POLLING_INTERVAL=200
@@ -133,6 +157,7 @@ mod tests {
             API_WEB3_JSON_RPC_WHITELISTED_TOKENS_FOR_AA="0x0000000000000000000000000000000000000001,0x0000000000000000000000000000000000000002"
             API_WEB3_JSON_RPC_ESTIMATE_GAS_SCALE_FACTOR=1.0
             API_WEB3_JSON_RPC_ESTIMATE_GAS_ACCEPTABLE_OVERESTIMATION=1000
+            API_WEB3_JSON_RPC_ESTIMATE_GAS_SINGLE_PASS=true
             API_WEB3_JSON_RPC_MAX_TX_SIZE=1000000
             API_WEB3_JSON_RPC_VM_CONCURRENCY_LIMIT=512
             API_WEB3_JSON_RPC_FACTORY_DEPS_CACHE_SIZE_MB=128
@@ -143,6 +168,9 @@ mod tests {
             API_WEB3_JSON_RPC_WEBSOCKET_REQUESTS_PER_MINUTE_LIMIT=10
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_SIZE=10000
             API_WEB3_JSON_RPC_MEMPOOL_CACHE_UPDATE_INTERVAL=50
+            API_WEB3_JSON_RPC_RESPONSE_CACHE_SIZE_MB=64
+            API_WEB3_JSON_RPC_RESPONSE_CACHE_TTL_SEC=60
+            API_WEB3_JSON_RPC_RESPONSE_CACHE_REDIS_URL="redis://127.0.0.1:6379/"
             API_CONTRACT_VERIFICATION_PORT="3070"
             API_CONTRACT_VERIFICATION_URL="http://127.0.0.1:3070"
             API_WEB3_JSON_RPC_MAX_RESPONSE_BODY_SIZE_MB=10
@@ -150,10 +178,16 @@ mod tests {
             API_PROMETHEUS_LISTENER_PORT="3312"
             API_PROMETHEUS_PUSHGATEWAY_URL="http://127.0.0.1:9091"
             API_PROMETHEUS_PUSH_INTERVAL_MS=100
+            API_PROMETHEUS_EXPORT_MODE="otlp"
+            API_PROMETHEUS_OTLP_ENDPOINT="http://127.0.0.1:4318/v1/metrics"
+            API_PROMETHEUS_OTLP_EXPORT_INTERVAL_MS=5000
             API_HEALTHCHECK_PORT=8081
             API_HEALTHCHECK_SLOW_TIME_LIMIT_MS=250
             API_HEALTHCHECK_HARD_TIME_LIMIT_MS=2000
             API_MERKLE_TREE_PORT=8082
+            API_MERKLE_TREE_GRPC_PORT=8083
+            API_EXPLORER_PORT=8084
+            API_EXPLORER_MAX_PAGE_SIZE=50
         "#;
         lock.set_env(config);
 
@@ -77,6 +77,8 @@ mod tests {
             block_commit_deadline_ms: 2500,
             l2_block_commit_deadline_ms: 1000,
             l2_block_seal_queue_capacity: 10,
+            l2_block_seal_queue_max_capacity: Some(50),
+            l2_block_seal_queue_target_latency_ms: Some(500),
             l2_block_max_payload_size: 1_000_000,
             max_single_tx_gas: 1_000_000,
             max_allowed_l2_tx_gas_limit: 2_000_000_000,
@@ -123,6 +125,8 @@ mod tests {
             CHAIN_STATE_KEEPER_BLOCK_COMMIT_DEADLINE_MS="2500"
             CHAIN_STATE_KEEPER_MINIBLOCK_COMMIT_DEADLINE_MS="1000"
             CHAIN_STATE_KEEPER_MINIBLOCK_SEAL_QUEUE_CAPACITY="10"
+            CHAIN_STATE_KEEPER_L2_BLOCK_SEAL_QUEUE_MAX_CAPACITY="50"
+            CHAIN_STATE_KEEPER_L2_BLOCK_SEAL_QUEUE_TARGET_LATENCY_MS="500"
             CHAIN_STATE_KEEPER_MINIBLOCK_MAX_PAYLOAD_SIZE="1000000"
             CHAIN_STATE_KEEPER_MINIMAL_L2_GAS_PRICE="100000000"
             CHAIN_STATE_KEEPER_COMPUTE_OVERHEAD_PART="0.0"
@@ -23,6 +23,8 @@ mod tests {
             threads_per_server: Some(128),
             port: 3070,
             url: "127.0.0.1:3070".to_string(),
+            compilers_download_url: Some("https://compilers.example.com".to_string()),
+            max_compiler_candidates: Some(5),
         }
     }
 
@@ -36,6 +38,8 @@ mod tests {
             CONTRACT_VERIFIER_PORT=3070
             CONTRACT_VERIFIER_URL=127.0.0.1:3070
             CONTRACT_VERIFIER_THREADS_PER_SERVER=128
+            CONTRACT_VERIFIER_COMPILERS_DOWNLOAD_URL=https://compilers.example.com
+            CONTRACT_VERIFIER_MAX_COMPILER_CANDIDATES=5
 
         "#;
         lock.set_env(config);
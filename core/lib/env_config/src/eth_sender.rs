@@ -41,7 +41,9 @@ impl FromEnv for GasAdjusterConfig {
 
 #[cfg(test)]
 mod tests {
-    use zksync_config::configs::eth_sender::{ProofSendingMode, PubdataSendingMode};
+    use zksync_config::configs::eth_sender::{
+        BlobFeeStrategy, ProofSendingMode, PubdataSendingMode,
+    };
 
     use super::*;
     use crate::test_utils::{hash, EnvMutex};
@@ -70,6 +72,10 @@ mod tests {
                     l1_batch_min_age_before_execute_seconds: Some(1000),
                     max_acceptable_priority_fee_in_gwei: 100_000_000_000,
                     pubdata_sending_mode: PubdataSendingMode::Calldata,
+                    fee_bump_percent: Some(25),
+                    resubmission_interval_blocks: Some(3),
+                    stuck_tx_threshold_blocks: None,
+                    repair_nonce_gaps: true,
                 }),
                 gas_adjuster: Some(GasAdjusterConfig {
                     default_priority_fee_per_gas: 20000000000,
@@ -84,10 +90,15 @@ mod tests {
                     num_samples_for_blob_base_fee_estimate: 10,
                     internal_pubdata_pricing_multiplier: 1.0,
                     max_blob_base_fee: None,
+                    blob_fee_strategy: BlobFeeStrategy::Percentile,
+                    blob_fee_percentile: Some(90),
+                    blob_fee_ewma_alpha: None,
+                    blob_fee_cap: None,
                 }),
                 watcher: Some(EthWatchConfig {
                     confirmations_for_eth_event: Some(0),
                     eth_node_poll_interval: 300,
+                    additional_sources: vec![],
                 }),
             },
             L1Secrets {
@@ -109,6 +120,9 @@ mod tests {
             ETH_SENDER_SENDER_MAX_TXS_IN_FLIGHT="3"
             ETH_SENDER_SENDER_OPERATOR_PRIVATE_KEY="0x27593fea79697e947890ecbecce7901b0008345e5d7259710d0dd5e500d040be"
             ETH_SENDER_SENDER_PROOF_SENDING_MODE="SkipEveryProof"
+            ETH_SENDER_SENDER_FEE_BUMP_PERCENT="25"
+            ETH_SENDER_SENDER_RESUBMISSION_INTERVAL_BLOCKS="3"
+            ETH_SENDER_SENDER_REPAIR_NONCE_GAPS="true"
             ETH_SENDER_GAS_ADJUSTER_DEFAULT_PRIORITY_FEE_PER_GAS="20000000000"
             ETH_SENDER_GAS_ADJUSTER_MAX_BASE_FEE_SAMPLES="10000"
             ETH_SENDER_GAS_ADJUSTER_PRICING_FORMULA_PARAMETER_A="1.5"
@@ -118,6 +132,8 @@ mod tests {
             ETH_SENDER_GAS_ADJUSTER_MAX_L1_GAS_PRICE="100000000"
             ETH_SENDER_GAS_ADJUSTER_MAX_BLOB_BASE_FEE_SAMPLES="10"
             ETH_SENDER_GAS_ADJUSTER_INTERNAL_PUBDATA_PRICING_MULTIPLIER="1.0"
+            ETH_SENDER_GAS_ADJUSTER_BLOB_FEE_STRATEGY="Percentile"
+            ETH_SENDER_GAS_ADJUSTER_BLOB_FEE_PERCENTILE="90"
             ETH_SENDER_WAIT_FOR_PROOFS="false"
             ETH_SENDER_SENDER_AGGREGATED_PROOF_SIZES="1,5"
             ETH_SENDER_SENDER_MAX_AGGREGATED_BLOCKS_TO_COMMIT="3"
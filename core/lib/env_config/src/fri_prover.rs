@@ -41,6 +41,9 @@ mod tests {
                     gcs_credential_file_path: "/path/to/credentials.json".to_owned(),
                 },
                 max_retries: 5,
+                integrity_envelope: false,
+                encryption_keys_file_path: None,
+                allow_plaintext_reads: false,
             }),
             availability_check_interval_in_secs: Some(1_800),
         }
@@ -26,6 +26,9 @@ mod tests {
             max_attempts: 4,
             last_l1_batch_to_process: None,
             shall_save_to_public_bucket: true,
+            artifacts_memory_budget_mb: Some(512),
+            artifacts_disk_budget_mb: Some(2_048),
+            spill_dir: Some("/tmp/witness-spill".to_owned()),
         }
     }
 
@@ -41,6 +44,9 @@ mod tests {
             FRI_WITNESS_SCHEDULER_GENERATION_TIMEOUT_IN_SECS=900
             FRI_WITNESS_MAX_ATTEMPTS=4
             FRI_WITNESS_SHALL_SAVE_TO_PUBLIC_BUCKET=true
+            FRI_WITNESS_ARTIFACTS_MEMORY_BUDGET_MB=512
+            FRI_WITNESS_ARTIFACTS_DISK_BUDGET_MB=2048
+            FRI_WITNESS_SPILL_DIR="/tmp/witness-spill"
         "#;
         lock.set_env(config);
 
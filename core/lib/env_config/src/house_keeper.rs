@@ -33,6 +33,11 @@ mod tests {
             fri_gpu_prover_archiver_archiving_interval_ms: Some(86_400_000),
             // 48 hours
             fri_gpu_prover_archiver_archive_after_secs: Some(172_800),
+            prover_job_monitor_interval_ms: Some(60_000),
+            prover_job_monitor_stuck_job_threshold_secs: Some(7_200),
+            witness_generation_sla_secs: Some(1_800),
+            proving_sla_secs: Some(3_600),
+            proof_compression_sla_secs: None,
         }
     }
 
@@ -56,6 +61,10 @@ mod tests {
             HOUSE_KEEPER_PROVER_JOB_ARCHIVER_ARCHIVE_AFTER_SECS="172800"
             HOUSE_KEEPER_FRI_GPU_PROVER_ARCHIVER_ARCHIVING_INTERVAL_MS="86400000"
             HOUSE_KEEPER_FRI_GPU_PROVER_ARCHIVER_ARCHIVE_AFTER_SECS="172800"
+            HOUSE_KEEPER_PROVER_JOB_MONITOR_INTERVAL_MS="60000"
+            HOUSE_KEEPER_PROVER_JOB_MONITOR_STUCK_JOB_THRESHOLD_SECS="7200"
+            HOUSE_KEEPER_WITNESS_GENERATION_SLA_SECS="1800"
+            HOUSE_KEEPER_PROVING_SLA_SECS="3600"
         "#;
         lock.set_env(config);
 
@@ -20,6 +20,7 @@ mod observability;
 mod proof_data_handler;
 mod snapshots_creator;
 mod utils;
+mod vm_runner;
 
 mod genesis;
 #[cfg(test)]
@@ -56,6 +56,9 @@ mod tests {
                 gcs_credential_file_path: "/path/to/credentials.json".to_owned(),
             },
             max_retries: 5,
+            integrity_envelope: false,
+            encryption_keys_file_path: None,
+            allow_plaintext_reads: false,
         }
     }
 
@@ -90,6 +93,85 @@ mod tests {
         );
     }
 
+    #[test]
+    fn http_mirror_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="HttpMirror"
+            OBJECT_STORE_HTTP_BASE_URL="https://mirror.example.com/snapshots"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::HttpMirror {
+                http_base_url: "https://mirror.example.com/snapshots".to_owned(),
+            }
+        );
+    }
+
+    #[test]
+    fn azure_blob_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="AzureBlob"
+            OBJECT_STORE_AZURE_ACCOUNT_NAME="zksync"
+            OBJECT_STORE_AZURE_CONTAINER_NAME="artifacts"
+            OBJECT_STORE_AZURE_ENDPOINT="http://127.0.0.1:10000/devstoreaccount1"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::AzureBlob {
+                azure_account_name: "zksync".to_owned(),
+                azure_container_name: "artifacts".to_owned(),
+                azure_endpoint: Some("http://127.0.0.1:10000/devstoreaccount1".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn s3_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="S3"
+            OBJECT_STORE_S3_BUCKET_NAME="artifacts"
+            OBJECT_STORE_S3_REGION="us-east-1"
+            OBJECT_STORE_S3_ENDPOINT="http://127.0.0.1:9000"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert_eq!(
+            actual.mode,
+            ObjectStoreMode::S3 {
+                s3_bucket_name: "artifacts".to_owned(),
+                s3_region: "us-east-1".to_owned(),
+                s3_endpoint: Some("http://127.0.0.1:9000".to_owned()),
+            }
+        );
+    }
+
+    #[test]
+    fn encrypted_config_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            OBJECT_STORE_MODE="FileBacked"
+            OBJECT_STORE_FILE_BACKED_BASE_PATH="artifacts"
+            OBJECT_STORE_INTEGRITY_ENVELOPE="true"
+            OBJECT_STORE_ENCRYPTION_KEYS_FILE_PATH="/path/to/keys.txt"
+            OBJECT_STORE_ALLOW_PLAINTEXT_READS="true"
+        "#;
+        lock.set_env(config);
+        let actual = ObjectStoreConfig::from_env().unwrap();
+        assert!(actual.integrity_envelope);
+        assert_eq!(
+            actual.encryption_keys_file_path.as_deref(),
+            Some("/path/to/keys.txt")
+        );
+        assert!(actual.allow_plaintext_reads);
+    }
+
     #[test]
     fn public_bucket_config_from_env() {
         let mut lock = MUTEX.lock();
@@ -19,6 +19,9 @@ mod tests {
         ProofDataHandlerConfig {
             http_port: 3320,
             proof_generation_timeout_in_secs: 18000,
+            upload_chunk_size_limit_bytes: Some(2_097_152),
+            upload_session_timeout_in_secs: Some(1800),
+            reconciliation_interval_in_secs: Some(300),
         }
     }
 
@@ -27,6 +30,9 @@ mod tests {
         let config = r#"
             PROOF_DATA_HANDLER_PROOF_GENERATION_TIMEOUT_IN_SECS="18000"
             PROOF_DATA_HANDLER_HTTP_PORT="3320"
+            PROOF_DATA_HANDLER_UPLOAD_CHUNK_SIZE_LIMIT_BYTES="2097152"
+            PROOF_DATA_HANDLER_UPLOAD_SESSION_TIMEOUT_IN_SECS="1800"
+            PROOF_DATA_HANDLER_RECONCILIATION_INTERVAL_IN_SECS="300"
         "#;
         let mut lock = MUTEX.lock();
         lock.set_env(config);
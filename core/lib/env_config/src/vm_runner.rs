@@ -0,0 +1,83 @@
+use zksync_config::configs::{ProtectiveReadsVerifierConfig, VmReplayConfig};
+
+use crate::{envy_load, FromEnv};
+
+impl FromEnv for ProtectiveReadsVerifierConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load(
+            "vm_runner.protective_reads_verifier",
+            "VM_RUNNER_PROTECTIVE_READS_VERIFIER_",
+        )
+    }
+}
+
+impl FromEnv for VmReplayConfig {
+    fn from_env() -> anyhow::Result<Self> {
+        envy_load("vm_runner.replay", "VM_RUNNER_REPLAY_")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_basic_types::L1BatchNumber;
+
+    use super::*;
+    use crate::test_utils::EnvMutex;
+
+    static MUTEX: EnvMutex = EnvMutex::new();
+
+    #[test]
+    fn protective_reads_verifier_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            VM_RUNNER_PROTECTIVE_READS_VERIFIER_DB_PATH="/db/protective_reads_verifier"
+            VM_RUNNER_PROTECTIVE_READS_VERIFIER_WINDOW_SIZE="50"
+            VM_RUNNER_PROTECTIVE_READS_VERIFIER_FIRST_PROCESSED_BATCH="123"
+            VM_RUNNER_PROTECTIVE_READS_VERIFIER_MAX_CONCURRENT_BATCHES="4"
+            VM_RUNNER_PROTECTIVE_READS_VERIFIER_EXECUTION_MEMORY_BUDGET_MB="512"
+        "#;
+        lock.set_env(config);
+
+        let actual = ProtectiveReadsVerifierConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            ProtectiveReadsVerifierConfig {
+                db_path: "/db/protective_reads_verifier".to_owned(),
+                window_size: 50,
+                first_processed_batch: L1BatchNumber(123),
+                max_concurrent_batches: Some(4),
+                execution_memory_budget_mb: Some(512),
+            }
+        );
+        assert_eq!(actual.execution_memory_budget_bytes(), Some(512 << 20));
+    }
+
+    #[test]
+    fn vm_replay_from_env() {
+        let mut lock = MUTEX.lock();
+        let config = r#"
+            VM_RUNNER_REPLAY_WINDOW_SIZE="10"
+            VM_RUNNER_REPLAY_FIRST_L1_BATCH="1"
+            VM_RUNNER_REPLAY_LAST_L1_BATCH="100"
+        "#;
+        lock.set_env(config);
+        lock.remove_env(&[
+            "VM_RUNNER_REPLAY_DB_PATH",
+            "VM_RUNNER_REPLAY_MAX_CONCURRENT_BATCHES",
+            "VM_RUNNER_REPLAY_EXECUTION_MEMORY_BUDGET_MB",
+        ]);
+
+        let actual = VmReplayConfig::from_env().unwrap();
+        assert_eq!(
+            actual,
+            VmReplayConfig {
+                db_path: "./db/vm_runner_replay".to_owned(),
+                window_size: 10,
+                first_l1_batch: L1BatchNumber(1),
+                last_l1_batch: L1BatchNumber(100),
+                max_concurrent_batches: None,
+                execution_memory_budget_mb: None,
+            }
+        );
+    }
+}
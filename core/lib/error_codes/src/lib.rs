@@ -0,0 +1,193 @@
+//! Catalog of stable error codes for node components.
+//!
+//! Error codes are reported in logs (as the `error_code` field), in the `component_errors` metric, and in `data`
+//! of JSON-RPC errors, so that alerting rules and documentation can refer to precise failures instead of matching
+//! error messages. Codes are never reused or changed once released; see the crate README for the full catalog.
+
+use std::fmt;
+
+use serde::{Serialize, Serializer};
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+
+#[cfg(test)]
+mod tests;
+
+/// Node component reporting an error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub enum Component {
+    /// Synchronization with the main node (only used by the external node).
+    Sync,
+    /// Merkle tree and its API.
+    Tree,
+    /// JSON-RPC API server.
+    Api,
+}
+
+impl Component {
+    fn code_prefix(self) -> &'static str {
+        match self {
+            Self::Sync => "SYNC",
+            Self::Tree => "TREE",
+            Self::Api => "API",
+        }
+    }
+}
+
+/// Stable error code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorCode {
+    /// Error communicating with the main node via JSON-RPC.
+    SyncMainNodeRpc,
+    /// Internal error in the sync layer.
+    SyncInternal,
+    /// Requested tree version (= L1 batch) is missing in the tree.
+    TreeNoVersion,
+    /// Tree API is temporarily unavailable, e.g. because the tree is initializing.
+    TreeApiUnavailable,
+    /// Internal tree error.
+    TreeInternal,
+    /// Requested block doesn't exist yet.
+    ApiNoBlock,
+    /// Requested block or L1 batch is pruned.
+    ApiPruned,
+    /// Submitted transaction was rejected.
+    ApiTransactionRejected,
+    /// Submitted transaction cannot be deserialized.
+    ApiTransactionSerialization,
+    /// Error proxying a call to the main node.
+    ApiProxy,
+    /// Logs filter contains too many topics.
+    ApiTooManyTopics,
+    /// Logs filter doesn't exist.
+    ApiFilterNotFound,
+    /// Logs query returned too many results.
+    ApiLogsLimitExceeded,
+    /// Logs filter specifies both a block hash and a block range.
+    ApiInvalidFilterBlockHash,
+    /// Reward percentiles for fee history are invalid.
+    ApiInvalidRewardPercentiles,
+    /// Too many transactions in a fee estimation batch.
+    ApiTooManyTransactions,
+    /// Method is disabled by node configuration.
+    ApiMethodNotImplemented,
+    /// Server sheds load because its storage is overloaded.
+    ApiServerOverloaded,
+    /// State override for a simulated transaction is invalid.
+    ApiInvalidStateOverride,
+    /// Requested tracer is not supported by the node.
+    ApiUnknownTracer,
+    /// Too many storage keys requested in a single proof request.
+    ApiTooManyStorageKeys,
+    /// Internal API server error.
+    ApiInternal,
+}
+
+impl ErrorCode {
+    /// All error codes in the catalog.
+    pub const ALL: &'static [Self] = &[
+        Self::SyncMainNodeRpc,
+        Self::SyncInternal,
+        Self::TreeNoVersion,
+        Self::TreeApiUnavailable,
+        Self::TreeInternal,
+        Self::ApiNoBlock,
+        Self::ApiPruned,
+        Self::ApiTransactionRejected,
+        Self::ApiTransactionSerialization,
+        Self::ApiProxy,
+        Self::ApiTooManyTopics,
+        Self::ApiFilterNotFound,
+        Self::ApiLogsLimitExceeded,
+        Self::ApiInvalidFilterBlockHash,
+        Self::ApiInvalidRewardPercentiles,
+        Self::ApiTooManyTransactions,
+        Self::ApiMethodNotImplemented,
+        Self::ApiServerOverloaded,
+        Self::ApiInternal,
+        Self::ApiInvalidStateOverride,
+        Self::ApiUnknownTracer,
+        Self::ApiTooManyStorageKeys,
+    ];
+
+    /// Returns the string representation of this code, e.g. `API-002`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::SyncMainNodeRpc => "SYNC-001",
+            Self::SyncInternal => "SYNC-002",
+            Self::TreeNoVersion => "TREE-001",
+            Self::TreeApiUnavailable => "TREE-002",
+            Self::TreeInternal => "TREE-003",
+            Self::ApiNoBlock => "API-001",
+            Self::ApiPruned => "API-002",
+            Self::ApiTransactionRejected => "API-003",
+            Self::ApiTransactionSerialization => "API-004",
+            Self::ApiProxy => "API-005",
+            Self::ApiTooManyTopics => "API-006",
+            Self::ApiFilterNotFound => "API-007",
+            Self::ApiLogsLimitExceeded => "API-008",
+            Self::ApiInvalidFilterBlockHash => "API-009",
+            Self::ApiInvalidRewardPercentiles => "API-010",
+            Self::ApiTooManyTransactions => "API-011",
+            Self::ApiMethodNotImplemented => "API-012",
+            Self::ApiServerOverloaded => "API-013",
+            Self::ApiInternal => "API-014",
+            Self::ApiInvalidStateOverride => "API-015",
+            Self::ApiUnknownTracer => "API-016",
+            Self::ApiTooManyStorageKeys => "API-017",
+        }
+    }
+
+    /// Returns the component this code belongs to.
+    pub fn component(self) -> Component {
+        match self {
+            Self::SyncMainNodeRpc | Self::SyncInternal => Component::Sync,
+            Self::TreeNoVersion | Self::TreeApiUnavailable | Self::TreeInternal => Component::Tree,
+            _ => Component::Api,
+        }
+    }
+
+    /// Increments the error counter for this code. Components should call this once per reported error.
+    pub fn observe(self) {
+        let labels = ErrorLabels {
+            component: self.component(),
+            code: self.as_str(),
+        };
+        METRICS.errors[&labels].inc();
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str(self.as_str())
+    }
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Error with a stable [`ErrorCode`].
+pub trait HasErrorCode {
+    /// Returns the code for this error.
+    fn error_code(&self) -> ErrorCode;
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+struct ErrorLabels {
+    component: Component,
+    code: &'static str,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "component")]
+struct ErrorMetrics {
+    /// Number of errors reported by node components, labeled by the stable error code.
+    errors: Family<ErrorLabels, Counter>,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ErrorMetrics> = vise::Global::new();
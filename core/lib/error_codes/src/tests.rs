@@ -0,0 +1,28 @@
+//! Tests for the error code catalog.
+
+use std::collections::HashSet;
+
+use super::*;
+
+#[test]
+fn error_codes_are_unique_and_well_formed() {
+    let mut codes = HashSet::new();
+    for &code in ErrorCode::ALL {
+        assert!(codes.insert(code.as_str()), "duplicate code: {code}");
+
+        let (prefix, number) = code.as_str().split_once('-').unwrap();
+        assert_eq!(prefix, code.component().code_prefix(), "{code:?}");
+        assert_eq!(number.len(), 3, "{code:?}");
+        assert!(number.bytes().all(|ch| ch.is_ascii_digit()), "{code:?}");
+    }
+}
+
+#[test]
+fn error_codes_are_documented() {
+    const README: &str = include_str!("../README.md");
+
+    for &code in ErrorCode::ALL {
+        let row_start = format!("| `{code}` |");
+        assert!(README.contains(&row_start), "{code:?} is not documented");
+    }
+}
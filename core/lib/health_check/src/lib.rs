@@ -45,6 +45,12 @@ impl HealthStatus {
         matches!(self, Self::Ready | Self::Affected)
     }
 
+    /// Checks whether a component is alive according to this status. Unlike [`Self::is_healthy()`],
+    /// a component that isn't ready yet (e.g., one performing snapshot recovery) is considered alive.
+    pub fn is_alive(self) -> bool {
+        !matches!(self, Self::ShutDown | Self::Panicked)
+    }
+
     fn priority_for_aggregation(self) -> usize {
         match self {
             Self::Ready => 0,
@@ -103,12 +109,23 @@ pub enum AppHealthCheckError {
     RedefinedComponent(&'static str),
 }
 
+/// Overrides for health checks of a specific component in [`AppHealthCheck`].
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ComponentHealthOverride {
+    /// Weight of the component for application readiness; see [`AppHealth::is_ready()`]. If not set, the weight is 1
+    /// for components [gating readiness](CheckHealth::gates_readiness()) and 0 for other components.
+    pub readiness_weight: Option<f64>,
+    /// Time limit to abort a health check for the component. If not set, the application-wide limit is used.
+    pub hard_time_limit: Option<Duration>,
+}
+
 /// Application health check aggregating health from multiple components.
 #[derive(Debug)]
 pub struct AppHealthCheck {
     components: Mutex<Vec<Arc<dyn CheckHealth>>>,
     slow_time_limit: Duration,
     hard_time_limit: Duration,
+    component_overrides: HashMap<String, ComponentHealthOverride>,
 }
 
 impl Default for AppHealthCheck {
@@ -141,9 +158,39 @@ impl AppHealthCheck {
             components: Mutex::default(),
             slow_time_limit,
             hard_time_limit,
+            component_overrides: HashMap::new(),
         }
     }
 
+    /// Sets overrides for readiness weights and time limits of specific components, keyed by the component name.
+    #[must_use]
+    pub fn with_component_overrides(
+        mut self,
+        overrides: impl IntoIterator<Item = (String, ComponentHealthOverride)>,
+    ) -> Self {
+        self.component_overrides.extend(overrides);
+        tracing::debug!(
+            "Set component overrides for app health: {:?}",
+            self.component_overrides
+        );
+        self
+    }
+
+    fn readiness_weight(&self, check: &dyn CheckHealth) -> f64 {
+        let default_weight = if check.gates_readiness() { 1.0 } else { 0.0 };
+        self.component_overrides
+            .get(check.name())
+            .and_then(|overrides| overrides.readiness_weight)
+            .unwrap_or(default_weight)
+    }
+
+    fn hard_time_limit(&self, check: &dyn CheckHealth) -> Duration {
+        self.component_overrides
+            .get(check.name())
+            .and_then(|overrides| overrides.hard_time_limit)
+            .unwrap_or(self.hard_time_limit)
+    }
+
     /// Inserts health check for a component.
     ///
     /// # Errors
@@ -190,10 +237,14 @@ impl AppHealthCheck {
             Self::check_health_with_time_limit(
                 check.as_ref(),
                 self.slow_time_limit,
-                self.hard_time_limit,
+                self.hard_time_limit(check.as_ref()),
             )
         });
         let components: HashMap<_, _> = future::join_all(check_futures).await.into_iter().collect();
+        let readiness_weights = health_checks
+            .iter()
+            .map(|check| (check.name(), self.readiness_weight(check.as_ref())))
+            .collect();
 
         let aggregated_status = components
             .values()
@@ -202,7 +253,11 @@ impl AppHealthCheck {
             .unwrap_or(HealthStatus::Ready);
         let inner = aggregated_status.into();
 
-        let health = AppHealth { inner, components };
+        let health = AppHealth {
+            inner,
+            components,
+            readiness_weights,
+        };
         if !health.inner.status.is_healthy() {
             // Only log non-ready application health so that logs are not spammed without a reason.
             tracing::debug!("Aggregated application health: {health:?}");
@@ -282,6 +337,8 @@ pub struct AppHealth {
     #[serde(flatten)]
     inner: Health,
     components: HashMap<&'static str, Health>,
+    #[serde(skip)]
+    readiness_weights: HashMap<&'static str, f64>,
 }
 
 impl AppHealth {
@@ -289,6 +346,26 @@ impl AppHealth {
         self.inner.status.is_healthy()
     }
 
+    /// Checks whether the application is alive, i.e., none of its components has shut down or panicked.
+    /// Components that are not ready yet do not affect liveness.
+    pub fn is_alive(&self) -> bool {
+        self.components
+            .values()
+            .all(|health| health.status.is_alive())
+    }
+
+    /// Checks whether the application is ready to serve requests. The application is not ready if the total
+    /// readiness weight of unhealthy components reaches 1.
+    pub fn is_ready(&self) -> bool {
+        let unready_weight: f64 = self
+            .components
+            .iter()
+            .filter(|(_, health)| !health.status.is_healthy())
+            .map(|(name, _)| self.readiness_weights.get(name).copied().unwrap_or(1.0))
+            .sum();
+        unready_weight < 1.0
+    }
+
     /// Returns a reference to the overall health of the application.
     pub fn inner(&self) -> &Health {
         &self.inner
@@ -307,6 +384,12 @@ pub trait CheckHealth: Send + Sync + 'static {
     fn name(&self) -> &'static str;
     /// Checks health of the component.
     async fn check_health(&self) -> Health;
+
+    /// Whether the component gates application readiness, i.e., whether the application isn't ready
+    /// if the component is unhealthy. Components not gating readiness still affect application liveness.
+    fn gates_readiness(&self) -> bool {
+        true
+    }
 }
 
 impl fmt::Debug for dyn CheckHealth {
@@ -327,6 +410,10 @@ impl<T: CheckHealth + ?Sized> CheckHealth for Arc<T> {
     async fn check_health(&self) -> Health {
         (**self).check_health().await
     }
+
+    fn gates_readiness(&self) -> bool {
+        (**self).gates_readiness()
+    }
 }
 
 /// Basic implementation of [`CheckHealth`] trait that can be updated using a matching [`HealthUpdater`].
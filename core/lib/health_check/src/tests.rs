@@ -148,3 +148,93 @@ fn adding_duplicate_component() {
         .unwrap_err();
     assert_matches!(err, AppHealthCheckError::RedefinedComponent("test"));
 }
+
+#[derive(Debug)]
+struct SlowHealthCheck;
+
+#[async_trait]
+impl CheckHealth for SlowHealthCheck {
+    fn name(&self) -> &'static str {
+        "slow"
+    }
+
+    async fn check_health(&self) -> Health {
+        tokio::time::sleep(Duration::from_secs(10)).await;
+        HealthStatus::Ready.into()
+    }
+
+    fn gates_readiness(&self) -> bool {
+        false
+    }
+}
+
+#[tokio::test]
+async fn liveness_and_readiness() {
+    let (first_check, first_updater) = ReactiveHealthCheck::new("first");
+    let (second_check, second_updater) = ReactiveHealthCheck::new("second");
+    let checks = AppHealthCheck {
+        components: Mutex::new(vec![Arc::new(first_check), Arc::new(second_check)]),
+        ..AppHealthCheck::default()
+    }
+    .with_component_overrides([
+        (
+            "first".to_owned(),
+            ComponentHealthOverride {
+                readiness_weight: Some(0.5),
+                hard_time_limit: None,
+            },
+        ),
+        (
+            "second".to_owned(),
+            ComponentHealthOverride {
+                readiness_weight: Some(0.5),
+                hard_time_limit: None,
+            },
+        ),
+    ]);
+
+    // Components that are not ready (e.g., performing snapshot recovery) are alive.
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_alive());
+    assert!(!app_health.is_ready());
+
+    first_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_alive());
+    assert!(app_health.is_ready());
+
+    second_updater.update(HealthStatus::Ready.into());
+    let app_health = checks.check_health().await;
+    assert!(app_health.is_alive());
+    assert!(app_health.is_ready());
+
+    drop(second_updater);
+    let app_health = checks.check_health().await;
+    assert!(!app_health.is_alive());
+    assert!(app_health.is_ready());
+}
+
+#[tokio::test]
+async fn component_not_gating_readiness() {
+    let (check, updater) = ReactiveHealthCheck::new("reactive");
+    updater.update(HealthStatus::Ready.into());
+    let checks = AppHealthCheck {
+        components: Mutex::new(vec![Arc::new(check), Arc::new(SlowHealthCheck)]),
+        ..AppHealthCheck::default()
+    }
+    .with_component_overrides([(
+        "slow".to_owned(),
+        ComponentHealthOverride {
+            readiness_weight: None,
+            hard_time_limit: Some(Duration::from_millis(10)),
+        },
+    )]);
+
+    let app_health = checks.check_health().await;
+    // The slow check should time out according to the component-specific time limit.
+    assert_matches!(app_health.components["slow"].status, HealthStatus::NotReady);
+    assert!(!app_health.is_healthy());
+    assert!(app_health.is_alive());
+    assert!(app_health.is_ready());
+}
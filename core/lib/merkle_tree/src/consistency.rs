@@ -99,17 +99,66 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
         // much in memory.
         let root_key = Nibbles::EMPTY.with_version(version);
         let leaf_data = validate_indices.then(|| LeafConsistencyData::new(leaf_count));
-        self.validate_node(&root_node, root_key, leaf_data.as_ref())?;
+        self.validate_node(&root_node, root_key, 0, leaf_data.as_ref())?;
         if let Some(leaf_data) = leaf_data {
             leaf_data.validate_count()?;
         }
         Ok(())
     }
 
+    /// Incrementally verifies the internal tree consistency as stored in the database. Unlike
+    /// [`Self::verify_consistency()`], this method checks tree versions one by one, starting from `start_version`
+    /// and checking at most `max_versions` versions. For each version, only nodes created in this version
+    /// are checked; nodes inherited from previous versions are assumed to be checked when checking these versions.
+    /// Thus, checking all versions of a tree is approximately as expensive as checking all nodes in the database
+    /// once, and the check can be split into chunks and resumed from the returned version.
+    ///
+    /// Nodes inherited from versions preceding `start_version` are **not** checked, so to check the entire tree,
+    /// `start_version` should be 0 (which is impossible after the tree is pruned). Leaf indices are not checked
+    /// either.
+    ///
+    /// # Return value
+    ///
+    /// Returns the next version to be checked. It is equal to `start_version` if there are no versions to check
+    /// (e.g., all versions of the tree are already checked). If `start_version` exceeds the number of versions
+    /// in the tree (e.g., because the tree was truncated), the number of versions is returned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error (the first encountered one if there are multiple).
+    pub fn verify_consistency_incremental(
+        &self,
+        start_version: u64,
+        max_versions: u64,
+    ) -> Result<u64, ConsistencyError> {
+        let Some(manifest) = self.db.try_manifest()? else {
+            return Ok(0);
+        };
+        let start_version = start_version.min(manifest.version_count);
+        let end_version = start_version
+            .saturating_add(max_versions)
+            .min(manifest.version_count);
+
+        for version in start_version..end_version {
+            let root = self
+                .db
+                .try_root(version)?
+                .ok_or(ConsistencyError::MissingRoot(version))?;
+            if let Root::Filled { node, .. } = root {
+                let root_key = Nibbles::EMPTY.with_version(version);
+                self.validate_node(&node, root_key, version, None)?;
+            }
+        }
+        Ok(end_version)
+    }
+
+    /// Validates the node and its descendants. Descendants with version lower than `min_checked_version`
+    /// are not checked; their hashes are taken from the parent node.
     fn validate_node(
         &self,
         node: &Node,
         key: NodeKey,
+        min_checked_version: u64,
         leaf_data: Option<&LeafConsistencyData>,
     ) -> Result<ValueHash, ConsistencyError> {
         match node {
@@ -148,6 +197,9 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
                 children
                     .into_par_iter()
                     .try_for_each(|(nibble, child_ref)| {
+                        if child_ref.version < min_checked_version {
+                            return Ok(());
+                        }
                         let child_key = key
                             .nibbles
                             .push(nibble)
@@ -163,7 +215,8 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
 
                         // Recursion here is OK; the tree isn't that deep (approximately 8 nibbles for a tree with
                         // approximately 1B entries).
-                        let child_hash = self.validate_node(&child, child_key, leaf_data)?;
+                        let child_hash =
+                            self.validate_node(&child, child_key, min_checked_version, leaf_data)?;
                         if child_hash == child_ref.hash {
                             Ok(())
                         } else {
@@ -318,6 +371,49 @@ mod tests {
         thread_pool.install(|| MerkleTree::new(db).verify_consistency(0, true))
     }
 
+    #[test]
+    fn incremental_consistency_checks() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        for i in 0..10_u64 {
+            let entries = (0..10).map(|j| {
+                let key = Key::from(i * 100 + j);
+                TreeEntry::new(key, i * 10 + j + 1, ValueHash::from_low_u64_be(j))
+            });
+            tree.extend(entries.collect());
+        }
+
+        assert_eq!(tree.verify_consistency_incremental(0, 3).unwrap(), 3);
+        assert_eq!(tree.verify_consistency_incremental(3, 5).unwrap(), 8);
+        assert_eq!(tree.verify_consistency_incremental(8, 5).unwrap(), 10);
+        assert_eq!(tree.verify_consistency_incremental(10, 5).unwrap(), 10);
+        // Start version exceeding the number of versions is clamped.
+        assert_eq!(tree.verify_consistency_incremental(20, 5).unwrap(), 10);
+    }
+
+    #[test]
+    fn incremental_consistency_check_only_checks_new_nodes() {
+        let mut tree = MerkleTree::new(prepare_database());
+        tree.extend(vec![TreeEntry::new(
+            U256([0, 0, 0, 0x_beef_0000_0000_0000]),
+            3,
+            H256([3; 32]),
+        )]);
+
+        let leaf_key = tree.db.nodes_mut().find_map(|(key, node)| {
+            (key.version == 0 && matches!(node, Node::Leaf(_))).then_some(*key)
+        });
+        let leaf_key = leaf_key.unwrap();
+        tree.db.remove_node(&leaf_key);
+
+        // The broken node is created in version 0, so it isn't checked for version 1.
+        assert_eq!(tree.verify_consistency_incremental(1, 1).unwrap(), 2);
+        let err = tree.verify_consistency_incremental(0, 2).unwrap_err();
+        assert_matches!(
+            err,
+            ConsistencyError::MissingNode { key, is_leaf: true } if key == leaf_key
+        );
+    }
+
     #[test]
     fn missing_version_error() {
         let mut db = prepare_database();
@@ -1,5 +1,7 @@
 //! Tying the Merkle tree implementation to the problem domain.
 
+use std::io;
+
 use rayon::{ThreadPool, ThreadPoolBuilder};
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 use zksync_prover_interface::inputs::{PrepareBasicCircuitsJob, StorageLogMetadata};
@@ -7,12 +9,14 @@ use zksync_types::{L1BatchNumber, StorageKey};
 
 use crate::{
     consistency::ConsistencyError,
-    storage::{PatchSet, Patched, RocksDBWrapper},
+    storage::{MaybeParallel, PatchSet, Patched, RocksDBWrapper},
     types::{
-        Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, ValueHash,
-        TREE_DEPTH,
+        Key, Root, TreeEntry, TreeEntryWithProof, TreeInstruction, TreeLogEntry, TreeMultiProof,
+        TreeRangeProof, ValueHash, TREE_DEPTH,
     },
-    BlockOutput, HashTree, MerkleTree, MerkleTreePruner, MerkleTreePrunerHandle, NoVersionError,
+    BlockOutput, ExportedVersionInfo, HashTree, MerkleTree, MerkleTreePruner,
+    MerkleTreePrunerHandle, NoVersionError, RangeProofError, TreeStructureStats, TreeTransferError,
+    TreeVersionPin, TreeVersionPins,
 };
 
 /// Metadata for the current tree state.
@@ -38,12 +42,16 @@ enum TreeMode {
 /// [`Self::process_l1_batches()`] and [`Self::revert_logs()`] in RAM without saving them
 /// to RocksDB. The accumulated changes can be saved to RocksDB via [`Self::save()`]
 /// or discarded via [`Self::reset()`].
+///
+/// If [parallel persistence](Self::use_parallel_persistence()) is enabled, saved changes are written to RocksDB
+/// in a background thread; use [`Self::wait_for_persistence()`] to wait until they are actually written.
 #[derive(Debug)]
 pub struct ZkSyncTree {
-    tree: MerkleTree<Patched<RocksDBWrapper>>,
+    tree: MerkleTree<Patched<MaybeParallel<RocksDBWrapper>>>,
     thread_pool: Option<ThreadPool>,
     mode: TreeMode,
     pruning_enabled: bool,
+    version_pins: TreeVersionPins,
 }
 
 impl ZkSyncTree {
@@ -92,10 +100,11 @@ impl ZkSyncTree {
 
     fn new_with_mode(db: RocksDBWrapper, mode: TreeMode) -> Self {
         Self {
-            tree: MerkleTree::new(Patched::new(db)),
+            tree: MerkleTree::new(Patched::new(MaybeParallel::Sequential(db))),
             thread_pool: None,
             mode,
             pruning_enabled: false,
+            version_pins: TreeVersionPins::default(),
         }
     }
 
@@ -111,15 +120,33 @@ impl ZkSyncTree {
             "pruner was already obtained for the tree"
         );
         self.pruning_enabled = true;
-        let db = self.tree.db.inner().clone();
-        MerkleTreePruner::new(db)
+        let db = self.tree.db.inner().inner().clone();
+        let (mut pruner, handle) = MerkleTreePruner::new(db);
+        pruner.set_version_pins(self.version_pins.clone());
+        (pruner, handle)
+    }
+
+    /// Persists the next L1 batch to be checked by the incremental consistency check
+    /// (see [`ZkSyncTreeReader::verify_consistency_incremental()`]). Unlike tree changes, the cursor
+    /// is written to RocksDB immediately.
+    pub fn save_consistency_cursor(&mut self, next_l1_batch: L1BatchNumber) {
+        self.tree
+            .db
+            .inner()
+            .inner()
+            .save_consistency_cursor(next_l1_batch.0.into());
     }
 
     /// Returns a readonly handle to the tree. The handle **does not** see uncommitted changes to the tree,
-    /// only ones flushed to RocksDB.
+    /// only ones flushed to RocksDB. If parallel persistence is enabled, the handle may lag behind saved changes
+    /// until they are persisted by the background thread. Versions [pinned](ZkSyncTreeReader::pin_l1_batch())
+    /// via the handle are not pruned by the [pruner](Self::pruner()) of this tree.
     pub fn reader(&self) -> ZkSyncTreeReader {
-        let db = self.tree.db.inner().clone();
-        ZkSyncTreeReader(MerkleTree::new(db))
+        let db = self.tree.db.inner().inner().clone();
+        ZkSyncTreeReader {
+            tree: MerkleTree::new(db),
+            version_pins: self.version_pins.clone(),
+        }
     }
 
     /// Sets the chunk size for multi-get operations. The requested keys will be split
@@ -134,6 +161,7 @@ impl ZkSyncTree {
         self.tree
             .db
             .inner_mut()
+            .inner_mut()
             .set_multi_get_chunk_size(chunk_size);
     }
 
@@ -146,6 +174,28 @@ impl ZkSyncTree {
         self.thread_pool = Some(Self::create_thread_pool(thread_count));
     }
 
+    /// Signals that the tree should persist saved changes to RocksDB in a background thread. This allows
+    /// the tree to process the following L1 batches while the previous ones are being persisted.
+    /// `buffer_capacity` is the maximum number of saved changesets queued for persistence; if the queue is full,
+    /// [`Self::save()`] blocks until there is free space in it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if spawning the persistence thread fails.
+    pub fn use_parallel_persistence(&mut self, buffer_capacity: usize) {
+        self.tree.db.inner_mut().parallelize(buffer_capacity);
+    }
+
+    /// Blocks until all saved changes are persisted to RocksDB. This is a no-op
+    /// if [parallel persistence](Self::use_parallel_persistence()) is not enabled.
+    ///
+    /// # Panics
+    ///
+    /// Propagates a panic from the persistence thread, if any.
+    pub fn wait_for_persistence(&mut self) {
+        self.tree.db.inner_mut().wait_sync();
+    }
+
     /// Returns the current root hash of this tree.
     pub fn root_hash(&self) -> ValueHash {
         self.tree.latest_root_hash()
@@ -339,7 +389,8 @@ impl ZkSyncTree {
         self.tree.truncate_recent_versions(retained_version_count);
     }
 
-    /// Saves the accumulated changes in the tree to RocksDB.
+    /// Saves the accumulated changes in the tree to RocksDB. If parallel persistence is enabled, the changes
+    /// are queued for persistence instead, and this method only blocks if the persistence queue is full.
     pub fn save(&mut self) {
         let mut l1_batch_numbers = self.tree.db.patched_versions();
         l1_batch_numbers.sort_unstable();
@@ -355,35 +406,45 @@ impl ZkSyncTree {
 
 /// Readonly handle to a [`ZkSyncTree`].
 #[derive(Debug)]
-pub struct ZkSyncTreeReader(MerkleTree<RocksDBWrapper>);
+pub struct ZkSyncTreeReader {
+    tree: MerkleTree<RocksDBWrapper>,
+    version_pins: TreeVersionPins,
+}
 
 // While cloning `MerkleTree` is logically unsound, cloning a reader is reasonable since it is readonly.
 impl Clone for ZkSyncTreeReader {
     fn clone(&self) -> Self {
-        Self(MerkleTree::new(self.0.db.clone()))
+        Self {
+            tree: MerkleTree::new(self.tree.db.clone()),
+            version_pins: self.version_pins.clone(),
+        }
     }
 }
 
 impl ZkSyncTreeReader {
-    /// Creates a tree reader based on the provided database.
+    /// Creates a tree reader based on the provided database. Versions pinned via the created reader are not consulted
+    /// by tree pruners; use [`ZkSyncTree::reader()`] to obtain a reader sharing pins with the pruner.
     pub fn new(db: RocksDBWrapper) -> Self {
-        Self(MerkleTree::new(db))
+        Self {
+            tree: MerkleTree::new(db),
+            version_pins: TreeVersionPins::default(),
+        }
     }
 
     /// Returns a reference to the database this.
     pub fn db(&self) -> &RocksDBWrapper {
-        &self.0.db
+        &self.tree.db
     }
 
     /// Returns the current root hash of this tree.
     pub fn root_hash(&self) -> ValueHash {
-        self.0.latest_root_hash()
+        self.tree.latest_root_hash()
     }
 
     /// Returns the next L1 batch number that should be processed by the tree.
     #[allow(clippy::missing_panics_doc)]
     pub fn next_l1_batch_number(&self) -> L1BatchNumber {
-        let number = self.0.latest_version().map_or(0, |version| {
+        let number = self.tree.latest_version().map_or(0, |version| {
             u32::try_from(version + 1).expect("integer overflow for L1 batch number")
         });
         L1BatchNumber(number)
@@ -392,14 +453,40 @@ impl ZkSyncTreeReader {
     /// Returns the minimum L1 batch number retained by the tree.
     #[allow(clippy::missing_panics_doc)]
     pub fn min_l1_batch_number(&self) -> Option<L1BatchNumber> {
-        self.0.first_retained_version().map(|version| {
+        self.tree.first_retained_version().map(|version| {
             L1BatchNumber(u32::try_from(version).expect("integer overflow for L1 batch number"))
         })
     }
 
     /// Returns the number of leaves in the tree.
     pub fn leaf_count(&self) -> u64 {
-        self.0.latest_root().leaf_count()
+        self.tree.latest_root().leaf_count()
+    }
+
+    /// Returns a read-only view of the tree for the specified L1 batch. The tree version is guaranteed not to be pruned
+    /// while the view is alive, so it can be used for long sequences of reads (e.g., obtaining many proofs).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version is missing or is being pruned.
+    pub fn pin_l1_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<PinnedTreeVersion, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        let no_version_err = || NoVersionError {
+            missing_version: version,
+            version_count: self.tree.latest_version().map_or(0, |version| version + 1),
+        };
+        let pin = self.version_pins.pin(version).ok_or_else(no_version_err)?;
+        // The version may have been pruned before it was pinned, or not exist at all.
+        let root_hash = self.tree.root_hash(version).ok_or_else(no_version_err)?;
+        Ok(PinnedTreeVersion {
+            reader: self.clone(),
+            l1_batch_number,
+            root_hash,
+            _pin: pin,
+        })
     }
 
     /// Reads entries together with Merkle proofs with the specified keys from the tree. The entries are returned
@@ -414,7 +501,52 @@ impl ZkSyncTreeReader {
         keys: &[Key],
     ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
         let version = u64::from(l1_batch_number.0);
-        self.0.entries_with_proofs(version, keys)
+        self.tree.entries_with_proofs(version, keys)
+    }
+
+    /// Reads entries with the specified keys from the tree together with a multiproof for all of them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries_with_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        keys: &[Key],
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        let version = u64::from(l1_batch_number.0);
+        self.tree.entries_with_multiproof(version, keys)
+    }
+
+    /// Creates a range proof for the inclusive key range `start_key..=end_key`; see [`MerkleTree::range_proof()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing, the range is invalid or contains too many entries.
+    pub fn range_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        start_key: Key,
+        end_key: Key,
+    ) -> Result<TreeRangeProof, RangeProofError> {
+        let version = u64::from(l1_batch_number.0);
+        self.tree.range_proof(version, start_key, end_key)
+    }
+
+    /// Exports the tree version for the specified L1 batch in the portable format;
+    /// see [`MerkleTree::export_version()`].
+    ///
+    /// # Errors
+    ///
+    /// Proxies errors from [`MerkleTree::export_version()`].
+    pub fn export_l1_batch(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        writer: impl io::Write,
+        chunk_size: usize,
+    ) -> Result<ExportedVersionInfo, TreeTransferError> {
+        let version = u64::from(l1_batch_number.0);
+        self.tree.export_version(version, writer, chunk_size)
     }
 
     /// Verifies consistency of the tree at the specified L1 batch number.
@@ -427,6 +559,109 @@ impl ZkSyncTreeReader {
         l1_batch_number: L1BatchNumber,
     ) -> Result<(), ConsistencyError> {
         let version = l1_batch_number.0.into();
-        self.0.verify_consistency(version, true)
+        self.tree.verify_consistency(version, true)
+    }
+
+    /// Incrementally verifies consistency of the tree for L1 batches starting from `start_l1_batch`, checking
+    /// at most `max_l1_batches` batches. See [`MerkleTree::verify_consistency_incremental()`] for details.
+    ///
+    /// # Return value
+    ///
+    /// Returns the next L1 batch to be checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first encountered verification error, should one occur.
+    #[allow(clippy::missing_panics_doc)]
+    pub fn verify_consistency_incremental(
+        &self,
+        start_l1_batch: L1BatchNumber,
+        max_l1_batches: u32,
+    ) -> Result<L1BatchNumber, ConsistencyError> {
+        let next_version = self
+            .tree
+            .verify_consistency_incremental(start_l1_batch.0.into(), max_l1_batches.into())?;
+        let next_version =
+            u32::try_from(next_version).expect("integer overflow for L1 batch number");
+        Ok(L1BatchNumber(next_version))
+    }
+
+    /// Returns the next L1 batch to be checked by the incremental consistency check, as persisted
+    /// via [`ZkSyncTree::save_consistency_cursor()`].
+    #[allow(clippy::missing_panics_doc)]
+    pub fn consistency_cursor(&self) -> Option<L1BatchNumber> {
+        let next_version = self.tree.db.consistency_cursor()?;
+        let next_version =
+            u32::try_from(next_version).expect("integer overflow for L1 batch number");
+        Some(L1BatchNumber(next_version))
+    }
+}
+
+/// Read-only view of a specific [`ZkSyncTree`] version obtained via [`ZkSyncTreeReader::pin_l1_batch()`].
+/// The version is guaranteed not to be pruned while the view is alive.
+#[derive(Debug)]
+pub struct PinnedTreeVersion {
+    reader: ZkSyncTreeReader,
+    l1_batch_number: L1BatchNumber,
+    root_hash: ValueHash,
+    _pin: TreeVersionPin,
+}
+
+impl PinnedTreeVersion {
+    /// Returns the L1 batch number corresponding to the pinned version.
+    pub fn l1_batch_number(&self) -> L1BatchNumber {
+        self.l1_batch_number
+    }
+
+    /// Returns the root hash of the pinned version.
+    pub fn root_hash(&self) -> ValueHash {
+        self.root_hash
+    }
+
+    /// Reads entries together with Merkle proofs with the specified keys; see [`ZkSyncTreeReader::entries_with_proofs()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version was removed by a tree revert.
+    pub fn entries_with_proofs(
+        &self,
+        keys: &[Key],
+    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
+        self.reader.entries_with_proofs(self.l1_batch_number, keys)
+    }
+
+    /// Reads entries with the specified keys together with a multiproof for all of them;
+    /// see [`ZkSyncTreeReader::entries_with_multiproof()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version was removed by a tree revert.
+    pub fn entries_with_multiproof(&self, keys: &[Key]) -> Result<TreeMultiProof, NoVersionError> {
+        self.reader
+            .entries_with_multiproof(self.l1_batch_number, keys)
+    }
+
+    /// Creates a range proof for the inclusive key range `start_key..=end_key`; see [`ZkSyncTreeReader::range_proof()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version was removed by a tree revert, the range is invalid
+    /// or contains too many entries.
+    pub fn range_proof(
+        &self,
+        start_key: Key,
+        end_key: Key,
+    ) -> Result<TreeRangeProof, RangeProofError> {
+        self.reader
+            .range_proof(self.l1_batch_number, start_key, end_key)
+    }
+    /// Walks the pinned version and collects its structural statistics; see [`MerkleTree::structure_stats()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree version was removed by a tree revert.
+    pub fn structure_stats(&self) -> Result<TreeStructureStats, NoVersionError> {
+        let version = u64::from(self.l1_batch_number.0);
+        self.reader.tree.structure_stats(version)
     }
 }
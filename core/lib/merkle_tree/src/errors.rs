@@ -22,6 +22,9 @@ pub enum DeserializeErrorKind {
     /// Bit mask specifying a child kind in an internal tree node is invalid.
     #[error("invalid bit mask specifying a child kind in an internal tree node")]
     InvalidChildKind,
+    /// Unexpected data after the end of the deserialized value.
+    #[error("unexpected trailing data")]
+    TrailingData,
 
     /// Missing required tag in the tree manifest.
     #[error("missing required tag `{0}` in tree manifest")]
@@ -73,6 +76,8 @@ pub enum ErrorContext {
     LeafIndex,
     /// Version of a child in an internal node.
     Version,
+    /// Tree multiproof.
+    MultiProof,
 }
 
 impl fmt::Display for ErrorContext {
@@ -87,6 +92,7 @@ impl fmt::Display for ErrorContext {
             Self::LeafCount => formatter.write_str("number of leaf nodes"),
             Self::LeafIndex => formatter.write_str("leaf index"),
             Self::Version => formatter.write_str("version of a child"),
+            Self::MultiProof => formatter.write_str("tree multiproof"),
         }
     }
 }
@@ -166,6 +172,48 @@ impl fmt::Display for NoVersionError {
 
 impl error::Error for NoVersionError {}
 
+/// Error creating a [range proof](crate::TreeRangeProof).
+#[derive(Debug)]
+pub enum RangeProofError {
+    /// Requested tree version is missing.
+    NoVersion(NoVersionError),
+    /// Range start is greater than the range end.
+    InvalidRange,
+    /// Range contains more entries than allowed for a single proof.
+    TooManyEntries {
+        /// Maximum number of entries in a proof.
+        limit: usize,
+    },
+}
+
+impl From<NoVersionError> for RangeProofError {
+    fn from(err: NoVersionError) -> Self {
+        Self::NoVersion(err)
+    }
+}
+
+impl fmt::Display for RangeProofError {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoVersion(err) => fmt::Display::fmt(err, formatter),
+            Self::InvalidRange => formatter.write_str("range start is greater than range end"),
+            Self::TooManyEntries { limit } => write!(
+                formatter,
+                "range contains more than {limit} entries; narrow down the range"
+            ),
+        }
+    }
+}
+
+impl error::Error for RangeProofError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::NoVersion(err) => Some(err),
+            Self::InvalidRange | Self::TooManyEntries { .. } => None,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_types::U256;
@@ -4,8 +4,11 @@ use crate::{
     hasher::HasherWithStats,
     recovery::MerkleTreeRecovery,
     storage::{LoadAncestorsResult, SortedKeys, WorkingPatchSet},
-    types::{Nibbles, Node, ProfiledTreeOperation, TreeEntry, TreeEntryWithProof},
-    Database, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, ValueHash,
+    types::{
+        Nibbles, Node, ProfiledTreeOperation, Root, TreeEntry, TreeEntryWithProof, TreeMultiProof,
+        TreeRangeProof, KEY_SIZE,
+    },
+    Database, HashTree, Key, MerkleTree, NoVersionError, PruneDatabase, RangeProofError, ValueHash,
 };
 
 impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
@@ -59,6 +62,156 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
             },
         )
     }
+
+    /// Reads entries with the specified keys from the tree together with a [multiproof](TreeMultiProof)
+    /// for all of them. The entries in the returned proof are ordered by key; duplicate keys are removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    pub fn entries_with_multiproof(
+        &self,
+        version: u64,
+        leaf_keys: &[Key],
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        let mut sorted_keys = leaf_keys.to_vec();
+        sorted_keys.sort_unstable();
+        sorted_keys.dedup();
+        let proofs = self.entries_with_proofs(version, &sorted_keys)?;
+        Ok(TreeMultiProof::from_proofs(&self.hasher, &proofs))
+    }
+
+    /// Maximum number of entries strictly inside the range returned by [`Self::range_proof()`].
+    pub const MAX_RANGE_PROOF_ENTRIES: usize = 1_024;
+
+    /// Creates a [range proof](TreeRangeProof) for the inclusive key range `start_key..=end_key`.
+    /// The proof contains all non-empty entries in the range, so the range should be reasonably narrow;
+    /// ranges with more than [`Self::MAX_RANGE_PROOF_ENTRIES`] inner entries are rejected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing, `start_key > end_key`, or the range contains
+    /// too many entries.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is missing nodes reachable from the root of the specified version.
+    pub fn range_proof(
+        &self,
+        version: u64,
+        start_key: Key,
+        end_key: Key,
+    ) -> Result<TreeRangeProof, RangeProofError> {
+        self.range_proof_with_limit(version, start_key, end_key, Self::MAX_RANGE_PROOF_ENTRIES)
+    }
+
+    fn range_proof_with_limit(
+        &self,
+        version: u64,
+        start_key: Key,
+        end_key: Key,
+        max_entries: usize,
+    ) -> Result<TreeRangeProof, RangeProofError> {
+        if start_key > end_key {
+            return Err(RangeProofError::InvalidRange);
+        }
+
+        let mut proofs = self.entries_with_proofs(version, &[start_key, end_key])?;
+        let end = proofs.pop().unwrap();
+        let start = proofs.pop().unwrap();
+        // ^ Both `unwrap()`s are safe: `entries_with_proofs()` returns an entry for each requested key
+        let entries = if start_key == end_key {
+            vec![]
+        } else {
+            let root = self.db.root(version).unwrap();
+            // ^ `unwrap()` is safe: the root was successfully loaded by `entries_with_proofs()`
+            load_range_entries(&self.db, root, start_key, end_key, max_entries)
+                .ok_or(RangeProofError::TooManyEntries { limit: max_entries })?
+        };
+        Ok(TreeRangeProof {
+            start,
+            end,
+            entries,
+        })
+    }
+}
+
+/// Checks whether the subtree with the specified nibbles prefix intersects the `start_key..=end_key` range.
+fn intersects_range(nibbles: &Nibbles, start_key: Key, end_key: Key) -> bool {
+    let min_key = Key::from_big_endian(nibbles.bytes());
+    let free_bits = 4 * (2 * KEY_SIZE - nibbles.nibble_count());
+    let max_key = if free_bits == 0 {
+        min_key
+    } else {
+        min_key | ((Key::one() << free_bits) - 1)
+    };
+    min_key <= end_key && max_key >= start_key
+}
+
+/// Loads all non-empty entries with keys strictly between `start_key` and `end_key`, ordered by key.
+/// The tree is traversed level by level, so that nodes on each level are loaded in a single batch.
+/// Returns `None` as soon as more than `max_entries` entries are encountered.
+fn load_range_entries(
+    db: &impl Database,
+    root: Root,
+    start_key: Key,
+    end_key: Key,
+    max_entries: usize,
+) -> Option<Vec<TreeEntry>> {
+    let is_inner_key = |key: Key| key > start_key && key < end_key;
+
+    let mut entries = vec![];
+    let mut level = match root {
+        Root::Empty => vec![],
+        Root::Filled {
+            node: Node::Leaf(leaf),
+            ..
+        } => {
+            if is_inner_key(leaf.full_key) {
+                entries.push(leaf.into());
+            }
+            vec![]
+        }
+        Root::Filled {
+            node: Node::Internal(node),
+            ..
+        } => vec![(Nibbles::EMPTY, node)],
+    };
+
+    while !level.is_empty() {
+        let mut child_keys = vec![];
+        for (nibbles, node) in &level {
+            for (nibble, child_ref) in node.children() {
+                let child_nibbles = nibbles.push(nibble).unwrap();
+                // ^ `unwrap()` is safe: internal nodes are never located at the last tree level
+                if intersects_range(&child_nibbles, start_key, end_key) {
+                    let child_key = child_nibbles.with_version(child_ref.version);
+                    child_keys.push((child_key, child_ref.is_leaf));
+                }
+            }
+        }
+
+        let children = db.tree_nodes(&child_keys);
+        level = Vec::with_capacity(children.len());
+        for ((child_key, _), child) in child_keys.iter().zip(children) {
+            match child.unwrap_or_else(|| panic!("Node at {child_key} is missing")) {
+                Node::Leaf(leaf) => {
+                    if is_inner_key(leaf.full_key) {
+                        entries.push(leaf.into());
+                        if entries.len() > max_entries {
+                            return None;
+                        }
+                    }
+                }
+                Node::Internal(node) => level.push((child_key.nibbles, node)),
+            }
+        }
+    }
+    if entries.len() > max_entries {
+        return None; // can happen if the root is a leaf
+    }
+    entries.sort_unstable_by_key(|entry: &TreeEntry| entry.key);
+    Some(entries)
 }
 
 fn load_and_transform_entries<T>(
@@ -115,9 +268,27 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
 
 #[cfg(test)]
 mod tests {
+    use assert_matches::assert_matches;
+    use zksync_crypto::hasher::{blake2::Blake2Hasher, Hasher};
+
     use super::*;
     use crate::PatchSet;
 
+    fn create_tree_with_random_keys(entry_count: u64) -> (MerkleTree<PatchSet>, Vec<Key>) {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        let mut keys: Vec<_> = (0..entry_count)
+            .map(|i| Key::from_big_endian(Blake2Hasher.hash_bytes(&i.to_le_bytes()).as_bytes()))
+            .collect();
+        let entries = keys
+            .iter()
+            .zip(1..)
+            .map(|(&key, i)| TreeEntry::new(key, i, ValueHash::from_low_u64_be(i)))
+            .collect();
+        tree.extend(entries);
+        keys.sort_unstable();
+        (tree, keys)
+    }
+
     #[test]
     fn entries_in_empty_tree() {
         let mut tree = MerkleTree::new(PatchSet::default());
@@ -153,4 +324,159 @@ mod tests {
         assert!(entries[1].base.is_empty());
         entries[1].verify(&tree.hasher, output.root_hash);
     }
+
+    #[test]
+    fn multiproof_basics() {
+        let (tree, keys) = create_tree_with_random_keys(100);
+        let root_hash = tree.latest_root_hash();
+        let missing_key = Key::from(123);
+        let requested_keys = [keys[50], keys[3], missing_key, keys[51], keys[3], keys[99]];
+
+        let proof = tree.entries_with_multiproof(0, &requested_keys).unwrap();
+        assert_eq!(proof.entries.len(), 5);
+        assert_eq!(proof.entries[0].key, missing_key);
+        assert!(proof.entries[0].is_empty());
+        let expected_entries = tree.entries(0, &[keys[3], keys[50], keys[51], keys[99]]);
+        assert_eq!(proof.entries[1..], expected_entries.unwrap());
+        proof.verify(&tree.hasher, root_hash).unwrap();
+
+        let individual_proofs = tree.entries_with_proofs(0, &requested_keys).unwrap();
+        let individual_hash_count: usize = individual_proofs
+            .iter()
+            .map(|proof| proof.merkle_path.len())
+            .sum();
+        assert!(
+            proof.sibling_hashes.len() < individual_hash_count,
+            "{proof:?}"
+        );
+
+        let mut buffer = vec![];
+        proof.serialize(&mut buffer);
+        let proof_copy = TreeMultiProof::deserialize(&buffer).unwrap();
+        assert_eq!(proof_copy, proof);
+        proof_copy.verify(&tree.hasher, root_hash).unwrap();
+    }
+
+    #[test]
+    fn multiproof_for_single_key_and_empty_tree() {
+        let (tree, keys) = create_tree_with_random_keys(1);
+        let proof = tree.entries_with_multiproof(0, &keys).unwrap();
+        assert!(proof.sibling_hashes.is_empty());
+        proof.verify(&tree.hasher, tree.latest_root_hash()).unwrap();
+
+        let mut tree = MerkleTree::new(PatchSet::default());
+        tree.extend(vec![]);
+        let proof = tree.entries_with_multiproof(0, &[Key::from(1)]).unwrap();
+        assert!(proof.entries[0].is_empty());
+        proof
+            .verify(&tree.hasher, tree.hasher.empty_tree_hash())
+            .unwrap();
+    }
+
+    #[test]
+    fn invalid_multiproofs() {
+        let (tree, keys) = create_tree_with_random_keys(100);
+        let root_hash = tree.latest_root_hash();
+        let proof = tree.entries_with_multiproof(0, &keys[10..20]).unwrap();
+
+        let mut bogus_proof = proof.clone();
+        bogus_proof.entries[3].value = ValueHash::repeat_byte(0xff);
+        let err = bogus_proof.verify(&tree.hasher, root_hash).unwrap_err();
+        assert!(
+            err.to_string().contains("root_hash == trusted_root_hash"),
+            "{err}"
+        );
+
+        let mut bogus_proof = proof.clone();
+        bogus_proof.entries.swap(0, 1);
+        let err = bogus_proof.verify(&tree.hasher, root_hash).unwrap_err();
+        assert!(err.to_string().contains("not ordered"), "{err}");
+
+        let mut bogus_proof = proof.clone();
+        bogus_proof.sibling_hashes.pop();
+        let err = bogus_proof.verify(&tree.hasher, root_hash).unwrap_err();
+        assert!(
+            err.to_string().contains("not enough sibling hashes"),
+            "{err}"
+        );
+
+        let mut bogus_proof = proof;
+        bogus_proof.empty_siblings.push(true);
+        let err = bogus_proof.verify(&tree.hasher, root_hash).unwrap_err();
+        assert!(
+            err.to_string().contains("redundant sibling hashes"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn range_proof_basics() {
+        let (tree, keys) = create_tree_with_random_keys(100);
+        let root_hash = tree.latest_root_hash();
+
+        // Range with existing boundary keys.
+        let proof = tree.range_proof(0, keys[10], keys[20]).unwrap();
+        assert_eq!(proof.start.base.key, keys[10]);
+        assert_eq!(proof.end.base.key, keys[20]);
+        let inner_keys: Vec<_> = proof.entries.iter().map(|entry| entry.key).collect();
+        assert_eq!(inner_keys, keys[11..20]);
+        proof.verify(&tree.hasher, root_hash).unwrap();
+
+        // Range with missing boundary keys.
+        let proof = tree.range_proof(0, keys[10] + 1, keys[20] - 1).unwrap();
+        assert!(proof.start.base.is_empty());
+        assert!(proof.end.base.is_empty());
+        assert_eq!(proof.entries.len(), 9);
+        proof.verify(&tree.hasher, root_hash).unwrap();
+
+        // Single-key range.
+        let proof = tree.range_proof(0, keys[10], keys[10]).unwrap();
+        assert!(proof.entries.is_empty());
+        proof.verify(&tree.hasher, root_hash).unwrap();
+
+        // Full range.
+        let proof = tree.range_proof(0, Key::zero(), Key::MAX).unwrap();
+        assert_eq!(proof.entries.len(), 100);
+        proof.verify(&tree.hasher, root_hash).unwrap();
+    }
+
+    #[test]
+    fn invalid_range_proofs() {
+        let (tree, keys) = create_tree_with_random_keys(100);
+
+        let err = tree.range_proof(0, keys[20], keys[10]).unwrap_err();
+        assert_matches!(err, RangeProofError::InvalidRange);
+        let err = tree.range_proof(1, keys[10], keys[20]).unwrap_err();
+        assert_matches!(err, RangeProofError::NoVersion(_));
+
+        let proof = tree
+            .range_proof_with_limit(0, keys[10], keys[20], 9)
+            .unwrap();
+        assert_eq!(proof.entries.len(), 9);
+        let err = tree
+            .range_proof_with_limit(0, keys[10], keys[21], 9)
+            .unwrap_err();
+        assert_matches!(err, RangeProofError::TooManyEntries { limit: 9 });
+        assert!(err.to_string().contains("more than 9 entries"), "{err}");
+    }
+
+    #[test]
+    fn non_membership_range_proof() {
+        let (tree, keys) = create_tree_with_random_keys(100);
+        let root_hash = tree.latest_root_hash();
+
+        let proof = tree.range_proof(0, keys[10] + 1, keys[11] - 1).unwrap();
+        assert!(proof.is_empty());
+        proof.verify(&tree.hasher, root_hash).unwrap();
+
+        // Check that omitting an existing entry from the proof is detected.
+        let mut bogus_proof = tree.range_proof(0, keys[10] + 1, keys[12] - 1).unwrap();
+        assert_eq!(bogus_proof.entries.len(), 1);
+        bogus_proof.entries.clear();
+        let err = bogus_proof.verify(&tree.hasher, root_hash).unwrap_err();
+        assert!(
+            err.to_string().contains("root_hash == trusted_root_hash"),
+            "{err}"
+        );
+    }
 }
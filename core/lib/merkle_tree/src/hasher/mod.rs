@@ -3,7 +3,7 @@
 use std::{fmt, iter};
 
 use once_cell::sync::Lazy;
-use zksync_crypto::hasher::{blake2::Blake2Hasher, Hasher};
+use zksync_crypto::hasher::{blake2::Blake2Hasher, keccak::KeccakHasher, Hasher};
 
 pub(crate) use self::nodes::{InternalNodeCache, MerklePath};
 pub use self::proofs::TreeRangeDigest;
@@ -111,16 +111,24 @@ impl HashTree for () {
     }
 }
 
+fn hash_leaf_bytes<H: Hasher<Hash = ValueHash>>(
+    hasher: &H,
+    value_hash: &ValueHash,
+    leaf_index: u64,
+) -> ValueHash {
+    let mut bytes = [0_u8; 40];
+    bytes[..8].copy_from_slice(&leaf_index.to_be_bytes());
+    bytes[8..].copy_from_slice(value_hash.as_ref());
+    hasher.hash_bytes(&bytes)
+}
+
 impl HashTree for Blake2Hasher {
     fn name(&self) -> &'static str {
         "blake2s256"
     }
 
     fn hash_leaf(&self, value_hash: &ValueHash, leaf_index: u64) -> ValueHash {
-        let mut bytes = [0_u8; 40];
-        bytes[..8].copy_from_slice(&leaf_index.to_be_bytes());
-        bytes[8..].copy_from_slice(value_hash.as_ref());
-        self.hash_bytes(&bytes)
+        hash_leaf_bytes(self, value_hash, leaf_index)
     }
 
     /// Compresses the hashes of 2 children in a branch node.
@@ -130,15 +138,39 @@ impl HashTree for Blake2Hasher {
 
     /// Returns the hash of an empty subtree with the given depth.
     fn empty_subtree_hash(&self, depth: usize) -> ValueHash {
-        static EMPTY_TREE_HASHES: Lazy<Vec<ValueHash>> = Lazy::new(compute_empty_tree_hashes);
+        static EMPTY_TREE_HASHES: Lazy<Vec<ValueHash>> =
+            Lazy::new(|| compute_empty_tree_hashes(&Blake2Hasher));
+        EMPTY_TREE_HASHES[depth]
+    }
+}
+
+/// Keccak-256 hasher. Uses the same leaf and branch node layout as [`Blake2Hasher`].
+impl HashTree for KeccakHasher {
+    fn name(&self) -> &'static str {
+        "keccak256"
+    }
+
+    fn hash_leaf(&self, value_hash: &ValueHash, leaf_index: u64) -> ValueHash {
+        hash_leaf_bytes(self, value_hash, leaf_index)
+    }
+
+    fn hash_branch(&self, lhs: &ValueHash, rhs: &ValueHash) -> ValueHash {
+        self.compress(lhs, rhs)
+    }
+
+    fn empty_subtree_hash(&self, depth: usize) -> ValueHash {
+        static EMPTY_TREE_HASHES: Lazy<Vec<ValueHash>> =
+            Lazy::new(|| compute_empty_tree_hashes(&KeccakHasher));
         EMPTY_TREE_HASHES[depth]
     }
 }
 
-fn compute_empty_tree_hashes() -> Vec<ValueHash> {
-    let empty_leaf_hash = Blake2Hasher.hash_bytes(&[0_u8; 40]);
+/// Computes empty subtree hashes for depths `0..=TREE_DEPTH`. The empty leaf is hashed
+/// as a leaf with zero value and zero index.
+fn compute_empty_tree_hashes(hasher: &dyn HashTree) -> Vec<ValueHash> {
+    let empty_leaf_hash = hasher.hash_leaf(&ValueHash::zero(), 0);
     iter::successors(Some(empty_leaf_hash), |hash| {
-        Some(Blake2Hasher.hash_branch(hash, hash))
+        Some(hasher.hash_branch(hash, hash))
     })
     .take(TREE_DEPTH + 1)
     .collect()
@@ -281,4 +313,27 @@ mod tests {
         let folded_hash = hasher.inner.fold_merkle_path(&merkle_path, leaf.into());
         assert_eq!(folded_hash, expected_hash);
     }
+
+    #[test]
+    fn empty_subtree_hashes_are_consistent() {
+        let hashers: [&dyn HashTree; 2] = [&Blake2Hasher, &KeccakHasher];
+        for hasher in hashers {
+            let empty_leaf_hash = hasher.hash_leaf(&ValueHash::zero(), 0);
+            assert_eq!(hasher.empty_subtree_hash(0), empty_leaf_hash);
+            for depth in 1..=TREE_DEPTH {
+                let child_hash = hasher.empty_subtree_hash(depth - 1);
+                let expected_hash = hasher.hash_branch(&child_hash, &child_hash);
+                assert_eq!(
+                    hasher.empty_subtree_hash(depth),
+                    expected_hash,
+                    "{}",
+                    hasher.name()
+                );
+            }
+        }
+        assert_ne!(
+            KeccakHasher.empty_tree_hash(),
+            Blake2Hasher.empty_tree_hash()
+        );
+    }
 }
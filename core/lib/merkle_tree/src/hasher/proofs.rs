@@ -2,13 +2,13 @@
 
 use std::mem;
 
-use anyhow::ensure;
+use anyhow::{ensure, Context as _};
 
 use crate::{
     hasher::{HashTree, HasherWithStats},
     types::{
         BlockOutputWithProofs, Key, LeafNode, TreeEntry, TreeEntryWithProof, TreeInstruction,
-        TreeLogEntry, ValueHash, TREE_DEPTH,
+        TreeLogEntry, TreeMultiProof, TreeRangeProof, ValueHash, TREE_DEPTH,
     },
     utils,
 };
@@ -96,6 +96,202 @@ impl TreeEntryWithProof {
     }
 }
 
+/// Folds a level of the tree (nodes identified by their keys shifted by the processed depth, ordered by key)
+/// up to the root. `merge` is called for each node at each depth with the node key (shifted by the depth),
+/// the node value and the value of its sibling, if the sibling is present on the same level.
+fn fold_levels<T: Copy>(
+    mut level: Vec<(Key, T)>,
+    mut merge: impl FnMut(usize, Key, T, Option<T>) -> anyhow::Result<T>,
+) -> anyhow::Result<T> {
+    for depth in 0..TREE_DEPTH {
+        let mut next_level = Vec::with_capacity(level.len());
+        let mut i = 0;
+        while i < level.len() {
+            let (key, value) = level[i];
+            let parent_key = key >> 1;
+            let sibling = level
+                .get(i + 1)
+                .filter(|(next_key, _)| *next_key >> 1 == parent_key);
+            let merged = if let Some(&(_, sibling_value)) = sibling {
+                // Since `level` is ordered, `key` is the left child, and `sibling` is the right one.
+                i += 2;
+                merge(depth, key, value, Some(sibling_value))?
+            } else {
+                i += 1;
+                merge(depth, key, value, None)?
+            };
+            next_level.push((parent_key, merged));
+        }
+        level = next_level;
+    }
+    ensure!(level.len() == 1, "multiple roots after folding tree levels");
+    Ok(level[0].1)
+}
+
+impl TreeMultiProof {
+    /// Creates a multiproof from proofs for the entries with unique keys ordered by key.
+    pub(crate) fn from_proofs(hasher: &dyn HashTree, proofs: &[TreeEntryWithProof]) -> Self {
+        let mut empty_siblings = vec![];
+        let mut sibling_hashes = vec![];
+        if !proofs.is_empty() {
+            let level = proofs
+                .iter()
+                .enumerate()
+                .map(|(i, proof)| (proof.base.key, i))
+                .collect();
+            fold_levels(level, |depth, _, proof_idx, sibling_idx| {
+                if sibling_idx.is_none() {
+                    // The sibling must be taken from the Merkle path of any entry in the subtree.
+                    let merkle_path = &proofs[proof_idx].merkle_path;
+                    let skipped_len = TREE_DEPTH - merkle_path.len();
+                    let sibling_hash = depth
+                        .checked_sub(skipped_len)
+                        .map(|idx| merkle_path[idx])
+                        .filter(|hash| *hash != hasher.empty_subtree_hash(depth));
+                    empty_siblings.push(sibling_hash.is_none());
+                    sibling_hashes.extend(sibling_hash);
+                }
+                Ok(proof_idx)
+            })
+            .expect("folding proof indices cannot fail");
+        }
+
+        Self {
+            entries: proofs.iter().map(|proof| proof.base).collect(),
+            empty_siblings,
+            sibling_hashes,
+        }
+    }
+
+    /// Verifies this multiproof.
+    ///
+    /// # Errors
+    ///
+    /// As the errors are not actionable, a string error with the failing condition is returned.
+    pub fn verify(
+        &self,
+        hasher: &dyn HashTree,
+        trusted_root_hash: ValueHash,
+    ) -> anyhow::Result<()> {
+        ensure!(!self.entries.is_empty(), "multiproof contains no entries");
+        for window in self.entries.windows(2) {
+            ensure!(
+                window[0].key < window[1].key,
+                "entries in multiproof are not ordered by key"
+            );
+        }
+        for entry in &self.entries {
+            ensure!(
+                entry.leaf_index != 0 || entry.value.is_zero(),
+                "invalid missing value specification for key 0x{:x}: leaf index is zero, but value is non-default",
+                entry.key
+            );
+        }
+
+        let level = self
+            .entries
+            .iter()
+            .map(|entry| (entry.key, hasher.hash_leaf(&entry.value, entry.leaf_index)))
+            .collect();
+        let mut empty_siblings = self.empty_siblings.iter().copied();
+        let mut sibling_hashes = self.sibling_hashes.iter();
+        let root_hash = fold_levels(level, |depth, key, hash, sibling_hash| {
+            let Some(sibling_hash) = sibling_hash else {
+                let is_empty = empty_siblings
+                    .next()
+                    .context("not enough sibling hashes in multiproof")?;
+                let sibling_hash = if is_empty {
+                    hasher.empty_subtree_hash(depth)
+                } else {
+                    *sibling_hashes
+                        .next()
+                        .context("not enough sibling hashes in multiproof")?
+                };
+                return Ok(if key.bit(0) {
+                    hasher.hash_branch(&sibling_hash, &hash)
+                } else {
+                    hasher.hash_branch(&hash, &sibling_hash)
+                });
+            };
+            Ok(hasher.hash_branch(&hash, &sibling_hash))
+        })?;
+
+        ensure!(
+            empty_siblings.next().is_none() && sibling_hashes.next().is_none(),
+            "multiproof contains redundant sibling hashes"
+        );
+        ensure!(
+            root_hash == trusted_root_hash,
+            "Condition failed: `root_hash == trusted_root_hash` ({root_hash:?} vs {trusted_root_hash:?})"
+        );
+        Ok(())
+    }
+}
+
+impl TreeRangeProof {
+    /// Verifies this range proof.
+    ///
+    /// # Errors
+    ///
+    /// As the errors are not actionable, a string error with the failing condition is returned.
+    pub fn verify(
+        &self,
+        hasher: &dyn HashTree,
+        trusted_root_hash: ValueHash,
+    ) -> anyhow::Result<()> {
+        let (start, end) = (&self.start.base, &self.end.base);
+        for entry in [start, end] {
+            ensure!(
+                entry.leaf_index != 0 || entry.value.is_zero(),
+                "invalid missing value specification for key 0x{:x}: leaf index is zero, but value is non-default",
+                entry.key
+            );
+        }
+        ensure!(self.start.merkle_path.len() <= TREE_DEPTH);
+        ensure!(self.end.merkle_path.len() <= TREE_DEPTH);
+        ensure!(
+            start.key <= end.key,
+            "range start is greater than range end"
+        );
+        if start.key == end.key {
+            ensure!(
+                self.entries.is_empty(),
+                "single-key range proof contains inner entries"
+            );
+            let root_hash = hasher.fold_merkle_path(&self.start.merkle_path, *start);
+            ensure!(
+                root_hash == trusted_root_hash,
+                "Condition failed: `root_hash == trusted_root_hash` ({root_hash:?} vs {trusted_root_hash:?})"
+            );
+            return Ok(());
+        }
+
+        let mut prev_key = start.key;
+        for entry in &self.entries {
+            ensure!(
+                !entry.is_empty(),
+                "range proof contains an empty inner entry"
+            );
+            ensure!(
+                entry.key > prev_key && entry.key < end.key,
+                "inner entries in range proof are not ordered or are outside the range"
+            );
+            prev_key = entry.key;
+        }
+
+        let mut digest = TreeRangeDigest::new(hasher, start.key, &self.start);
+        for &entry in &self.entries {
+            digest.update(entry);
+        }
+        let root_hash = digest.finalize(&self.end);
+        ensure!(
+            root_hash == trusted_root_hash,
+            "Condition failed: `root_hash == trusted_root_hash` ({root_hash:?} vs {trusted_root_hash:?})"
+        );
+        Ok(())
+    }
+}
+
 /// Range digest in a Merkle tree allowing to compute its root hash based on the provided entries.
 ///
 /// - The entries must be ordered by key. I.e., the first entry must have the numerically smallest key,
@@ -12,19 +12,28 @@
 //! - [`PatchSet`] is an in-memory implementation useful for testing / benchmarking
 //! - [`Patched`] is a wrapper combining the persistent backend and a [`PatchSet`]. It's used
 //!   in `ZkSyncTree` to accumulate changes before flushing them to RocksDB.
+//! - [`ParallelDatabase`] is a wrapper persisting changes to the wrapped database in a background
+//!   thread, so that the tree can compute new versions while previous ones are being persisted.
+//!
+//! A single tree version can be exported into a portable file format and imported into an empty RocksDB
+//! instance; see [`MerkleTree::export_version()`] and [`RocksDBWrapper::import_version()`].
 //!
 //! The hashing backend is abstracted via the [`HashTree`] trait, which has the following
 //! implementations:
 //!
 //! - [`Blake2Hasher`] is the main implementation based on Blake2s-256
+//! - [`KeccakHasher`](zksync_crypto::hasher::keccak::KeccakHasher) is an alternative implementation based on Keccak-256
 //! - `()` provides a no-op implementation useful for benchmarking.
 //!
+//! The hasher name is recorded in the tree manifest when the tree is created, and is checked
+//! each time the tree is loaded; see [`MerkleTree::with_hasher()`].
+//!
 //! # Tree hashing specification
 //!
 //! A tree is hashed as if it was a full binary Merkle tree with `2^256` leaves:
 //!
 //! - Hash of a vacant leaf is `hash([0_u8; 40])`, where `hash` is the hash function used
-//!   (Blake2s-256).
+//!   (Blake2s-256 by default).
 //! - Hash of an occupied leaf is `hash(u64::to_be_bytes(leaf_index) ++ value_hash)`,
 //!   where `leaf_index` is a 1-based index of the leaf key provided when the leaf is inserted / updated,
 //!   `++` is byte concatenation.
@@ -49,16 +58,17 @@
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 
 pub use crate::{
-    errors::NoVersionError,
+    errors::{NoVersionError, RangeProofError},
     hasher::{HashTree, TreeRangeDigest},
-    pruning::{MerkleTreePruner, MerkleTreePrunerHandle},
+    pruning::{MerkleTreePruner, MerkleTreePrunerHandle, TreeVersionPin, TreeVersionPins},
     storage::{
-        Database, MerkleTreeColumnFamily, PatchSet, Patched, PruneDatabase, PrunePatchSet,
-        RocksDBWrapper,
+        Database, ExportedVersionInfo, MerkleTreeColumnFamily, ParallelDatabase, PatchSet, Patched,
+        PruneDatabase, PrunePatchSet, RocksDBWrapper, TreeTransferError,
     },
+    structure::TreeStructureStats,
     types::{
         BlockOutput, BlockOutputWithProofs, Key, TreeEntry, TreeEntryWithProof, TreeInstruction,
-        TreeLogEntry, TreeLogEntryWithProof, ValueHash,
+        TreeLogEntry, TreeLogEntryWithProof, TreeMultiProof, TreeRangeProof, ValueHash,
     },
 };
 use crate::{hasher::HasherWithStats, storage::Storage, types::Root};
@@ -72,6 +82,7 @@ mod metrics;
 mod pruning;
 pub mod recovery;
 mod storage;
+mod structure;
 mod types;
 mod utils;
 
@@ -241,7 +252,7 @@ impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
     }
 }
 
-impl<DB: PruneDatabase> MerkleTree<DB> {
+impl<DB: PruneDatabase, H: HashTree> MerkleTree<DB, H> {
     /// Returns the first retained version of the tree.
     pub fn first_retained_version(&self) -> Option<u64> {
         match self.db.min_stale_key_version() {
@@ -256,6 +267,8 @@ impl<DB: PruneDatabase> MerkleTree<DB> {
 
 #[cfg(test)]
 mod tests {
+    use zksync_crypto::hasher::keccak::KeccakHasher;
+
     use super::*;
     use crate::types::TreeTags;
 
@@ -300,4 +313,29 @@ mod tests {
 
         MerkleTree::new(db);
     }
+
+    #[test]
+    fn tree_with_keccak_hasher() {
+        let entries = vec![TreeEntry::new(Key::from(1), 1, ValueHash::repeat_byte(1))];
+        let mut tree = MerkleTree::with_hasher(PatchSet::default(), KeccakHasher);
+        let output = tree.extend(entries.clone());
+        let blake2_output = MerkleTree::new(PatchSet::default()).extend(entries);
+        assert_ne!(output.root_hash, blake2_output.root_hash);
+
+        let tree = MerkleTree::with_hasher(tree.db, KeccakHasher);
+        assert_eq!(tree.latest_root_hash(), output.root_hash);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mismatch between the provided tree hasher `keccak256`")]
+    fn configured_hasher_mismatch() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        tree.extend(vec![TreeEntry::new(
+            Key::from(1),
+            1,
+            ValueHash::repeat_byte(1),
+        )]);
+
+        MerkleTree::with_hasher(tree.db, KeccakHasher);
+    }
 }
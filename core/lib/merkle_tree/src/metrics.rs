@@ -395,3 +395,35 @@ pub(crate) struct RecoveryMetrics {
 
 #[vise::register]
 pub(crate) static RECOVERY_METRICS: Global<RecoveryMetrics> = Global::new();
+
+/// Reason for the tree to wait on the persistence thread.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum PersistenceWaitReason {
+    /// Waiting for free space in the persistence queue.
+    Backpressure,
+    /// Waiting for all queued patches to be persisted.
+    Sync,
+    /// Waiting for queued patches affecting pruned data to be persisted.
+    Prune,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "merkle_tree_parallel_persistence")]
+pub(crate) struct ParallelPersistenceMetrics {
+    /// Number of patches queued for persistence.
+    pub queue_len: Gauge<usize>,
+    /// Latency of persisting a single patch in the persistence thread.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub apply_patch_latency: Histogram<Duration>,
+    /// Latency between queuing a patch and it being persisted. Bounded by the persistence buffer capacity
+    /// multiplied by the patch persistence latency.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub patch_latency: Histogram<Duration>,
+    /// Time the tree spends waiting on the persistence thread.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub wait_latency: Family<PersistenceWaitReason, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(crate) static PARALLEL_PERSISTENCE_METRICS: Global<ParallelPersistenceMetrics> = Global::new();
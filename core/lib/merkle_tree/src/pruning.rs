@@ -1,10 +1,11 @@
 //! Tree pruning logic.
 
 use std::{
+    collections::{btree_map, BTreeMap},
     fmt,
     sync::{
         atomic::{AtomicU64, Ordering},
-        mpsc, Arc, Weak,
+        mpsc, Arc, Mutex, MutexGuard, PoisonError, Weak,
     },
     time::Duration,
 };
@@ -52,6 +53,90 @@ impl MerkleTreePrunerHandle {
     }
 }
 
+#[derive(Debug, Default)]
+struct TreeVersionPinsInner {
+    /// Pinned versions together with the number of pins for each of them.
+    pinned_versions: BTreeMap<u64, usize>,
+    /// Versions below this one may be (partially) removed by the pruner and thus cannot be pinned.
+    min_pinnable_version: u64,
+}
+
+/// Registry of tree versions pinned by tree readers. A [`MerkleTreePruner`] using the registry never prunes
+/// pinned versions, which allows readers to perform long sequences of reads for a specific version without racing
+/// the pruner.
+///
+/// Cloned registries share the same set of pins.
+#[derive(Debug, Clone, Default)]
+pub struct TreeVersionPins(Arc<Mutex<TreeVersionPinsInner>>);
+
+impl TreeVersionPins {
+    fn lock(&self) -> MutexGuard<'_, TreeVersionPinsInner> {
+        // The guarded state is always consistent, so it's safe to ignore poisoning.
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Pins the specified tree version. Returns `None` if the version cannot be pinned because the pruner
+    /// may have already started removing it. Note that pinning doesn't check whether the version exists
+    /// in the tree; this should be checked separately after obtaining the pin.
+    pub fn pin(&self, version: u64) -> Option<TreeVersionPin> {
+        let mut inner = self.lock();
+        if version < inner.min_pinnable_version {
+            return None;
+        }
+        *inner.pinned_versions.entry(version).or_default() += 1;
+        drop(inner);
+
+        Some(TreeVersionPin {
+            pins: self.clone(),
+            version,
+        })
+    }
+
+    /// Returns the minimum currently pinned version.
+    pub fn min_pinned_version(&self) -> Option<u64> {
+        self.lock().pinned_versions.keys().next().copied()
+    }
+
+    /// Limits the target retained version for pruning so that pinned versions are retained, and prevents
+    /// pinning the versions that will be pruned.
+    fn limit_retained_version(&self, target_retained_version: u64) -> u64 {
+        let mut inner = self.lock();
+        let target_retained_version = match inner.pinned_versions.keys().next() {
+            Some(&min_pinned_version) => target_retained_version.min(min_pinned_version),
+            None => target_retained_version,
+        };
+        inner.min_pinnable_version = inner.min_pinnable_version.max(target_retained_version);
+        target_retained_version
+    }
+}
+
+/// Pin of a tree version obtained via [`TreeVersionPins::pin()`]. The version is unpinned once the pin is dropped.
+#[must_use = "Version is unpinned once the pin is dropped"]
+#[derive(Debug)]
+pub struct TreeVersionPin {
+    pins: TreeVersionPins,
+    version: u64,
+}
+
+impl TreeVersionPin {
+    /// Returns the pinned version.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+}
+
+impl Drop for TreeVersionPin {
+    fn drop(&mut self) {
+        let mut inner = self.pins.lock();
+        if let btree_map::Entry::Occupied(mut entry) = inner.pinned_versions.entry(self.version) {
+            *entry.get_mut() -= 1;
+            if *entry.get() == 0 {
+                entry.remove();
+            }
+        }
+    }
+}
+
 /// Component responsible for Merkle tree pruning, i.e. removing nodes not referenced by new versions
 /// of the tree. A pruner should be instantiated using a [`Clone`] of the tree database, possibly
 /// configured and then [`run()`](Self::run()) on its own thread. [`MerkleTreePrunerHandle`] provides
@@ -63,9 +148,11 @@ impl MerkleTreePrunerHandle {
 /// stale keys are recorded in a separate column family. A pruner takes stale keys that were produced
 /// by a certain range of tree versions, and removes the corresponding nodes from the tree
 /// (in RocksDB, this uses simple pointwise `delete_cf()` operations). The range of versions
-/// depends on pruning policies; for now, it's passed via the pruner handle.
+/// depends on pruning policies; for now, it's passed via the pruner handle. Versions pinned
+/// in [`TreeVersionPins`] passed to the pruner are never pruned.
 pub struct MerkleTreePruner<DB> {
     db: DB,
+    version_pins: TreeVersionPins,
     target_pruned_key_count: usize,
     poll_interval: Duration,
     aborted_receiver: mpsc::Receiver<()>,
@@ -79,6 +166,7 @@ impl<DB> fmt::Debug for MerkleTreePruner<DB> {
             .field("target_pruned_key_count", &self.target_pruned_key_count)
             .field("poll_interval", &self.poll_interval)
             .field("target_retained_version", &self.target_retained_version)
+            .field("version_pins", &self.version_pins)
             .finish_non_exhaustive()
     }
 }
@@ -98,6 +186,7 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
         };
         let this = Self {
             db,
+            version_pins: TreeVersionPins::default(),
             target_pruned_key_count: 500_000,
             poll_interval: Duration::from_secs(60),
             aborted_receiver,
@@ -106,6 +195,19 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
         (this, handle)
     }
 
+    /// Creates a new handle for this pruner, e.g. to [`run()`](Self::run()) the pruner again after it was aborted.
+    /// The target retained version is carried over. The previous handle (if any) no longer controls the pruner.
+    pub fn renew_handle(&mut self) -> MerkleTreePrunerHandle {
+        let (aborted_sender, aborted_receiver) = mpsc::channel();
+        let target_retained_version = self.target_retained_version.load(Ordering::Relaxed);
+        self.target_retained_version = Arc::new(AtomicU64::new(target_retained_version));
+        self.aborted_receiver = aborted_receiver;
+        MerkleTreePrunerHandle {
+            _aborted_sender: aborted_sender,
+            target_retained_version: Arc::downgrade(&self.target_retained_version),
+        }
+    }
+
     /// Sets the target number of stale keys pruned on a single iteration. This limits the size of
     /// a produced RocksDB `WriteBatch` and the RAM consumption of the pruner. At the same time,
     /// larger values can lead to more efficient RocksDB compaction.
@@ -123,6 +225,12 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
         self.poll_interval = poll_interval;
     }
 
+    /// Sets the registry of versions that must not be pruned. The registry should be shared with tree readers
+    /// pinning the versions they read.
+    pub fn set_version_pins(&mut self, version_pins: TreeVersionPins) {
+        self.version_pins = version_pins;
+    }
+
     /// Returns max version number that can be safely pruned, so that there is at least one version present after pruning.
     #[doc(hidden)] // Used in integration tests; logically private
     pub fn last_prunable_version(&self) -> Option<u64> {
@@ -142,6 +250,9 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
             return None;
         }
         let target_retained_version = last_prunable_version?.min(target_retained_version);
+        let target_retained_version = self
+            .version_pins
+            .limit_retained_version(target_retained_version);
         let stale_key_new_versions = min_stale_key_version..=target_retained_version;
         if stale_key_new_versions.is_empty() {
             tracing::debug!(
@@ -197,7 +308,11 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
     }
 
     /// Runs this pruner indefinitely until it is aborted.
-    pub fn run(mut self) {
+    ///
+    /// # Return value
+    ///
+    /// Returns the aborted pruner, so that it can be restarted with a [renewed handle](Self::renew_handle()).
+    pub fn run(mut self) -> Self {
         tracing::info!("Started Merkle tree pruner {self:?}");
 
         let mut wait_interval = Duration::ZERO;
@@ -222,6 +337,8 @@ impl<DB: PruneDatabase> MerkleTreePruner<DB> {
                 self.poll_interval
             };
         }
+        tracing::info!("Merkle tree pruner was aborted");
+        self
     }
 }
 
@@ -292,6 +409,37 @@ mod tests {
         }
     }
 
+    #[test]
+    fn pinned_versions_are_not_pruned() {
+        let mut db = create_db();
+        let version_pins = TreeVersionPins::default();
+        let pin = version_pins.pin(2).unwrap();
+        let other_pin = version_pins.pin(3).unwrap();
+        assert_eq!(version_pins.min_pinned_version(), Some(2));
+
+        let (mut pruner, _handle) = MerkleTreePruner::new(&mut db);
+        pruner.set_version_pins(version_pins.clone());
+        let stats = pruner.prune_up_to(4).unwrap();
+        assert_eq!(stats.target_retained_version, 2);
+        assert_eq!(stats.deleted_stale_key_versions, 1..3);
+        // Versions that may be pruned cannot be pinned.
+        assert!(version_pins.pin(1).is_none());
+        assert!(version_pins.pin(2).is_some());
+
+        drop(pin);
+        assert_eq!(version_pins.min_pinned_version(), Some(3));
+        let stats = pruner.prune_up_to(4).unwrap();
+        assert_eq!(stats.target_retained_version, 3);
+        assert!(version_pins.pin(2).is_none());
+
+        drop(other_pin);
+        assert_eq!(version_pins.min_pinned_version(), None);
+        let stats = pruner.prune_up_to(4).unwrap();
+        assert_eq!(stats.target_retained_version, 4);
+        assert!(db.root_mut(3).is_none());
+        assert!(db.root_mut(4).is_some());
+    }
+
     #[test]
     fn pruner_is_aborted_immediately_when_requested() {
         let (mut pruner, pruner_handle) = MerkleTreePruner::new(PatchSet::default());
@@ -304,6 +452,27 @@ mod tests {
         assert!(start.elapsed() < Duration::from_secs(10));
     }
 
+    #[test]
+    fn pruner_can_be_restarted_with_renewed_handle() {
+        let (mut pruner, pruner_handle) = MerkleTreePruner::new(PatchSet::default());
+        pruner.set_poll_interval(Duration::from_secs(30));
+        pruner_handle.set_target_retained_version(2).unwrap();
+        let join_handle = thread::spawn(|| pruner.run());
+        drop(pruner_handle);
+        let mut pruner = join_handle.join().unwrap();
+
+        let pruner_handle = pruner.renew_handle();
+        // The target retained version is carried over to the new handle.
+        assert_eq!(pruner_handle.set_target_retained_version(4).unwrap(), 2);
+        assert_eq!(pruner.target_retained_version.load(Ordering::Relaxed), 4);
+
+        let join_handle = thread::spawn(|| pruner.run());
+        drop(pruner_handle);
+        let start = Instant::now();
+        join_handle.join().unwrap();
+        assert!(start.elapsed() < Duration::from_secs(10));
+    }
+
     fn generate_key_value_pairs(indexes: impl Iterator<Item = u64>) -> Vec<TreeEntry> {
         indexes
             .map(|i| TreeEntry::new(Key::from(i), i + 1, ValueHash::from_low_u64_be(i)))
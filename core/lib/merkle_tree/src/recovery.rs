@@ -35,17 +35,98 @@
 //! before extending the tree; these nodes are guaranteed to be the *only* DB reads necessary
 //! to insert new entries.
 
-use std::time::Instant;
+use std::{
+    any::Any,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
 
 use zksync_crypto::hasher::blake2::Blake2Hasher;
 
 use crate::{
+    errors::DeserializeError,
     hasher::{HashTree, HasherWithStats},
     metrics::{RecoveryStage, RECOVERY_METRICS},
-    storage::{PatchSet, PruneDatabase, PrunePatchSet, Storage},
-    types::{Key, Manifest, Root, TreeEntry, TreeTags, ValueHash},
+    storage::{
+        Database, NodeKeys, ParallelDatabase, PatchSet, PruneDatabase, PrunePatchSet,
+        RocksDBWrapper, Storage,
+    },
+    types::{
+        Key, Manifest, Node, NodeKey, ProfiledTreeOperation, Root, TreeEntry, TreeTags, ValueHash,
+    },
 };
 
+/// Timings for extending a tree with a single chunk of recovery entries. Can be used to tune recovery parameters
+/// (e.g., the chunk size or the [multi-get chunk size](RocksDBWrapper::set_multi_get_chunk_size())) at runtime.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RecoveryChunkStats {
+    /// Number of entries in the chunk.
+    pub entry_count: usize,
+    /// Total time spent on extending the tree.
+    pub total_latency: Duration,
+    /// Time spent on loading tree nodes using multi-get database operations.
+    pub multi_get_latency: Duration,
+    /// Time spent on applying the produced patch to the database. If [parallel persistence](MerkleTreeRecovery::parallelize())
+    /// is enabled, this is the time spent waiting for space in the persistence queue, which is a good indicator
+    /// of stalled database writes.
+    pub write_latency: Duration,
+}
+
+/// Database wrapper measuring latency of multi-get operations.
+#[derive(Debug)]
+struct MultiGetTimer<'a, DB: ?Sized> {
+    inner: &'a DB,
+    latency: Mutex<Duration>,
+}
+
+impl<'a, DB: Database + ?Sized> MultiGetTimer<'a, DB> {
+    fn new(inner: &'a DB) -> Self {
+        Self {
+            inner,
+            latency: Mutex::default(),
+        }
+    }
+
+    fn into_latency(self) -> Duration {
+        self.latency
+            .into_inner()
+            .expect("multi-get timer is poisoned")
+    }
+}
+
+impl<DB: Database + ?Sized> Database for MultiGetTimer<'_, DB> {
+    fn try_manifest(&self) -> Result<Option<Manifest>, DeserializeError> {
+        self.inner.try_manifest()
+    }
+
+    fn try_root(&self, version: u64) -> Result<Option<Root>, DeserializeError> {
+        self.inner.try_root(version)
+    }
+
+    fn try_tree_node(
+        &self,
+        key: &NodeKey,
+        is_leaf: bool,
+    ) -> Result<Option<Node>, DeserializeError> {
+        self.inner.try_tree_node(key, is_leaf)
+    }
+
+    fn tree_nodes(&self, keys: &NodeKeys) -> Vec<Option<Node>> {
+        let started_at = Instant::now();
+        let nodes = self.inner.tree_nodes(keys);
+        *self.latency.lock().expect("multi-get timer is poisoned") += started_at.elapsed();
+        nodes
+    }
+
+    fn start_profiling(&self, operation: ProfiledTreeOperation) -> Box<dyn Any> {
+        self.inner.start_profiling(operation)
+    }
+
+    fn apply_patch(&mut self, _patch: PatchSet) {
+        unreachable!("multi-get timer is only used for reads");
+    }
+}
+
 /// Handle to a Merkle tree during its recovery.
 #[derive(Debug)]
 pub struct MerkleTreeRecovery<DB, H = Blake2Hasher> {
@@ -134,7 +215,7 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
     /// Extends a tree with a chunk of linearly ordered entries.
     ///
     /// Entries must be ordered by increasing `key`, and the key of the first entry must be greater
-    /// than [`Self::last_processed_key()`].
+    /// than [`Self::last_processed_key()`]. Returns timings for the processed chunk.
     ///
     /// # Panics
     ///
@@ -148,24 +229,35 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
             %entries.key_range = entries_key_range(&entries),
         ),
     )]
-    pub fn extend_linear(&mut self, entries: Vec<TreeEntry>) {
+    pub fn extend_linear(&mut self, entries: Vec<TreeEntry>) -> RecoveryChunkStats {
         tracing::debug!("Started extending tree");
-        RECOVERY_METRICS.chunk_size.observe(entries.len());
+        let started_at = Instant::now();
+        let entry_count = entries.len();
+        RECOVERY_METRICS.chunk_size.observe(entry_count);
 
         let stage_latency = RECOVERY_METRICS.stage_latency[&RecoveryStage::Extend].start();
-        let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
+        let db = MultiGetTimer::new(&self.db);
+        let storage = Storage::new(&db, &self.hasher, self.recovered_version, false);
         let patch = storage.extend_during_linear_recovery(entries);
+        let multi_get_latency = db.into_latency();
         let stage_latency = stage_latency.observe();
         tracing::debug!("Finished processing keys; took {stage_latency:?}");
 
         let stage_latency = RECOVERY_METRICS.stage_latency[&RecoveryStage::ApplyPatch].start();
         self.db.apply_patch(patch);
-        let stage_latency = stage_latency.observe();
-        tracing::debug!("Finished persisting to DB; took {stage_latency:?}");
+        let write_latency = stage_latency.observe();
+        tracing::debug!("Finished persisting to DB; took {write_latency:?}");
+
+        RecoveryChunkStats {
+            entry_count,
+            total_latency: started_at.elapsed(),
+            multi_get_latency,
+            write_latency,
+        }
     }
 
     /// Extends a tree with a chunk of entries. Unlike [`Self::extend_linear()`], entries may be
-    /// ordered in any way you like.
+    /// ordered in any way you like. Returns timings for the processed chunk.
     #[tracing::instrument(
         level = "debug",
         skip_all,
@@ -174,20 +266,31 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
             entries.len = entries.len(),
         ),
     )]
-    pub fn extend_random(&mut self, entries: Vec<TreeEntry>) {
+    pub fn extend_random(&mut self, entries: Vec<TreeEntry>) -> RecoveryChunkStats {
         tracing::debug!("Started extending tree");
-        RECOVERY_METRICS.chunk_size.observe(entries.len());
+        let started_at = Instant::now();
+        let entry_count = entries.len();
+        RECOVERY_METRICS.chunk_size.observe(entry_count);
 
         let stage_latency = RECOVERY_METRICS.stage_latency[&RecoveryStage::Extend].start();
-        let storage = Storage::new(&self.db, &self.hasher, self.recovered_version, false);
+        let db = MultiGetTimer::new(&self.db);
+        let storage = Storage::new(&db, &self.hasher, self.recovered_version, false);
         let patch = storage.extend_during_random_recovery(entries);
+        let multi_get_latency = db.into_latency();
         let stage_latency = stage_latency.observe();
         tracing::debug!("Finished processing keys; took {stage_latency:?}");
 
         let stage_latency = RECOVERY_METRICS.stage_latency[&RecoveryStage::ApplyPatch].start();
         self.db.apply_patch(patch);
-        let stage_latency = stage_latency.observe();
-        tracing::debug!("Finished persisting to DB; took {stage_latency:?}");
+        let write_latency = stage_latency.observe();
+        tracing::debug!("Finished persisting to DB; took {write_latency:?}");
+
+        RecoveryChunkStats {
+            entry_count,
+            total_latency: started_at.elapsed(),
+            multi_get_latency,
+            write_latency,
+        }
     }
 
     /// Finalizes the recovery process marking it as complete in the tree manifest.
@@ -238,6 +341,50 @@ impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<DB, H> {
     }
 }
 
+impl<DB: PruneDatabase + Clone + 'static, H: HashTree> MerkleTreeRecovery<DB, H> {
+    /// Switches recovery to persist changes to the database in a background thread, with at most `buffer_capacity`
+    /// chunks queued for persistence. See [`ParallelDatabase`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if spawning the persistence thread fails.
+    pub fn parallelize(
+        self,
+        buffer_capacity: usize,
+    ) -> MerkleTreeRecovery<ParallelDatabase<DB>, H> {
+        MerkleTreeRecovery {
+            db: ParallelDatabase::new(self.db, buffer_capacity),
+            hasher: self.hasher,
+            recovered_version: self.recovered_version,
+        }
+    }
+}
+
+impl<DB: PruneDatabase, H: HashTree> MerkleTreeRecovery<ParallelDatabase<DB>, H> {
+    /// Returns the maximum number of chunks queued for persistence.
+    pub fn persistence_buffer_capacity(&self) -> usize {
+        self.db.buffer_capacity()
+    }
+
+    /// Changes the maximum number of chunks queued for persistence.
+    pub fn set_persistence_buffer_capacity(&mut self, buffer_capacity: usize) {
+        self.db.set_buffer_capacity(buffer_capacity);
+    }
+}
+
+impl<H: HashTree> MerkleTreeRecovery<ParallelDatabase<RocksDBWrapper>, H> {
+    /// Sets the chunk size for multi-get operations performed during recovery.
+    /// See [`RocksDBWrapper::set_multi_get_chunk_size()`] for details.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `chunk_size` is zero.
+    pub fn set_multi_get_chunk_size(&mut self, chunk_size: usize) {
+        assert!(chunk_size > 0, "Multi-get chunk size must be positive");
+        self.db.inner_mut().set_multi_get_chunk_size(chunk_size);
+    }
+}
+
 fn entries_key_range(entries: &[TreeEntry]) -> String {
     let (Some(first), Some(last)) = (entries.first(), entries.last()) else {
         return "(empty)".to_owned();
@@ -274,6 +421,37 @@ mod tests {
         assert_eq!(tree.root(42), Some(Root::Empty));
     }
 
+    #[test]
+    fn parallel_recovery() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        let mut recovery = MerkleTreeRecovery::new(db, 42).parallelize(1);
+        let entries: Vec<_> = (0..100_u64)
+            .map(|i| TreeEntry::new(Key::from(i) << 128, i + 1, ValueHash::from_low_u64_be(i)))
+            .collect();
+        for (i, chunk) in entries.chunks(10).enumerate() {
+            if i == 5 {
+                recovery.set_persistence_buffer_capacity(3);
+                recovery.set_multi_get_chunk_size(2);
+            }
+            let stats = recovery.extend_random(chunk.to_vec());
+            assert_eq!(stats.entry_count, 10);
+            assert!(stats.total_latency >= stats.multi_get_latency + stats.write_latency);
+        }
+        assert_eq!(recovery.persistence_buffer_capacity(), 3);
+        let root_hash = recovery.root_hash();
+
+        let db = recovery.finalize().into_inner();
+        let tree = MerkleTree::new(db);
+        assert_eq!(tree.latest_version(), Some(42));
+        assert_eq!(tree.latest_root_hash(), root_hash);
+        tree.verify_consistency(42, true).unwrap();
+
+        let mut expected_tree = MerkleTree::new(PatchSet::default());
+        expected_tree.extend(entries);
+        assert_eq!(root_hash, expected_tree.latest_root_hash());
+    }
+
     #[test]
     fn recovering_tree_with_single_node() {
         let mut recovery = MerkleTreeRecovery::new(PatchSet::default(), 42);
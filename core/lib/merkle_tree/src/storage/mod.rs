@@ -1,10 +1,15 @@
 //! Storage-related logic.
 
-pub(crate) use self::patch::{LoadAncestorsResult, WorkingPatchSet};
 pub use self::{
     database::{Database, NodeKeys, Patched, PruneDatabase, PrunePatchSet},
+    parallel::ParallelDatabase,
     patch::PatchSet,
     rocksdb::{MerkleTreeColumnFamily, RocksDBWrapper},
+    transfer::{ExportedVersionInfo, TreeTransferError},
+};
+pub(crate) use self::{
+    parallel::MaybeParallel,
+    patch::{LoadAncestorsResult, WorkingPatchSet},
 };
 use crate::{
     hasher::HashTree,
@@ -16,12 +21,14 @@ use crate::{
 };
 
 mod database;
+mod parallel;
 mod patch;
 mod proofs;
 mod rocksdb;
 mod serialization;
 #[cfg(test)]
 mod tests;
+mod transfer;
 
 /// Tree operation: either inserting a new version or updating an existing one (the latter is only
 /// used during tree recovery).
@@ -355,8 +362,12 @@ impl<'a, DB: Database + ?Sized> Storage<'a, DB> {
 
         let extend_patch_latency = BLOCK_TIMINGS.extend_patch.start();
         for (entry, parent_nibbles) in recovery_entries.into_iter().zip(parent_nibbles) {
-            self.updater.insert(entry, &parent_nibbles);
-            self.leaf_count += 1;
+            let (log, _) = self.updater.insert(entry, &parent_nibbles);
+            // Entries may be re-applied if recovery was interrupted in the middle of a chunk;
+            // such entries must not be counted as new leaves.
+            if matches!(log, TreeLogEntry::Inserted) {
+                self.leaf_count += 1;
+            }
         }
         let extend_patch_latency = extend_patch_latency.observe();
         tracing::debug!("Tree traversal stage took {extend_patch_latency:?}");
@@ -0,0 +1,847 @@
+//! Parallel persistence of tree changes.
+
+use std::{
+    any::Any,
+    collections::{HashSet, VecDeque},
+    panic,
+    sync::{mpsc, Arc, Condvar, Mutex, PoisonError},
+    thread,
+    time::Instant,
+};
+
+use crate::{
+    errors::DeserializeError,
+    metrics::{PersistenceWaitReason, PARALLEL_PERSISTENCE_METRICS},
+    storage::{
+        database::{Database, NodeKeys, PruneDatabase, PrunePatchSet},
+        patch::PatchSet,
+    },
+    types::{Manifest, Node, NodeKey, ProfiledTreeOperation, Root},
+};
+
+/// Command sent to the persistence thread.
+#[derive(Debug)]
+struct PersistenceCommand {
+    patch: Arc<PatchSet>,
+    queued_at: Instant,
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct PersistenceState {
+    /// Total number of patches persisted by the persistence thread.
+    persisted_count: u64,
+    /// Set once the persistence thread exits, either normally or because of a panic.
+    is_finished: bool,
+}
+
+/// Persistence progress shared between [`ParallelDatabase`] and its persistence thread.
+#[derive(Debug, Default)]
+struct PersistenceProgress {
+    state: Mutex<PersistenceState>,
+    state_changed: Condvar,
+}
+
+impl PersistenceProgress {
+    // The mutex is never held across code that can panic, so it's safe to ignore poisoning.
+    fn state(&self) -> PersistenceState {
+        *self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn update(&self, action: impl FnOnce(&mut PersistenceState)) {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        action(&mut state);
+        drop(state);
+        self.state_changed.notify_all();
+    }
+
+    /// Blocks until at least `patch_count` patches are persisted. Returns `false` if the persistence thread
+    /// has exited before that.
+    fn wait_for(&self, patch_count: u64) -> bool {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        let state = self
+            .state_changed
+            .wait_while(state, |state| {
+                state.persisted_count < patch_count && !state.is_finished
+            })
+            .unwrap_or_else(PoisonError::into_inner);
+        state.persisted_count >= patch_count
+    }
+}
+
+/// Marks the persistence thread as finished on drop, including when the thread panics.
+#[derive(Debug)]
+struct FinishGuard(Arc<PersistenceProgress>);
+
+impl Drop for FinishGuard {
+    fn drop(&mut self) {
+        self.0.update(|state| state.is_finished = true);
+    }
+}
+
+#[derive(Debug)]
+struct PersistenceThread<DB> {
+    command_receiver: mpsc::Receiver<PersistenceCommand>,
+    database: DB,
+    progress: Arc<PersistenceProgress>,
+}
+
+impl<DB: Database> PersistenceThread<DB> {
+    fn run(mut self) {
+        let _guard = FinishGuard(self.progress.clone());
+        while let Ok(command) = self.command_receiver.recv() {
+            let PersistenceCommand { patch, queued_at } = command;
+            let latency = PARALLEL_PERSISTENCE_METRICS.apply_patch_latency.start();
+            // The patch is shared with `ParallelDatabase`, which uses it to serve reads, so we have to clone it.
+            // Cloning happens on the persistence thread and thus doesn't block tree computations.
+            self.database.apply_patch(PatchSet::clone(&patch));
+            let latency = latency.observe();
+            PARALLEL_PERSISTENCE_METRICS
+                .patch_latency
+                .observe(queued_at.elapsed());
+            tracing::debug!(
+                "Persisted patch with manifest {:?} in {latency:?}",
+                patch.manifest
+            );
+            self.progress.update(|state| state.persisted_count += 1);
+        }
+        tracing::debug!("Persistence thread exited because the command sender was dropped");
+    }
+}
+
+/// Database wrapper that persists changes in a background thread.
+///
+/// Patches applied to the database are queued for the persistence thread. Until a patch is persisted,
+/// it is used to serve reads, so the tree can continue processing new versions while previous versions are being
+/// written to the wrapped database. Patches must have monotonically non-decreasing versions, or truncate
+/// the tree; i.e., they are patches produced by [`MerkleTree`](crate::MerkleTree) operations.
+///
+/// If the queue is full, [`Database::apply_patch()`] blocks until the persistence thread processes the oldest
+/// patch in the queue; thus, the buffer capacity bounds both the memory overhead and the lag of the wrapped database.
+/// The capacity can be changed at runtime using [`Self::set_buffer_capacity()`].
+/// [Pruning](PruneDatabase::prune()) only waits for the queued patches affecting pruned data.
+/// If the persistence thread panics, the panic is propagated on the next interaction with the database
+/// requiring the thread.
+///
+/// Dropping the database blocks until all queued patches are persisted.
+#[derive(Debug)]
+pub struct ParallelDatabase<DB> {
+    inner: DB,
+    command_sender: Option<mpsc::Sender<PersistenceCommand>>,
+    persistence_handle: Option<thread::JoinHandle<()>>,
+    progress: Arc<PersistenceProgress>,
+    /// Maximum number of patches queued for persistence.
+    buffer_capacity: usize,
+    /// Total number of patches sent to the persistence thread.
+    sent_count: u64,
+    /// Patches sent to the persistence thread, but not yet confirmed as persisted, ordered from the oldest
+    /// to the newest one.
+    patches: VecDeque<Arc<PatchSet>>,
+}
+
+impl<DB: Database + Clone + 'static> ParallelDatabase<DB> {
+    /// Wraps the provided database and spawns a persistence thread for it. `buffer_capacity` is the maximum number
+    /// of patches queued for persistence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if spawning a thread fails.
+    pub fn new(inner: DB, buffer_capacity: usize) -> Self {
+        let (command_sender, command_receiver) = mpsc::channel();
+        let progress = Arc::<PersistenceProgress>::default();
+        let persistence_thread = PersistenceThread {
+            command_receiver,
+            database: inner.clone(),
+            progress: progress.clone(),
+        };
+        let persistence_handle = thread::Builder::new()
+            .name("merkle-tree-persistence".to_owned())
+            .spawn(move || persistence_thread.run())
+            .expect("failed spawning Merkle tree persistence thread");
+
+        Self {
+            inner,
+            command_sender: Some(command_sender),
+            persistence_handle: Some(persistence_handle),
+            progress,
+            buffer_capacity,
+            sent_count: 0,
+            patches: VecDeque::new(),
+        }
+    }
+
+    /// Unwraps the wrapped database. Blocks until all patches applied to this database are persisted.
+    ///
+    /// # Panics
+    ///
+    /// Propagates a panic from the persistence thread, if any.
+    pub fn into_inner(self) -> DB {
+        let inner = self.inner.clone();
+        drop(self); // waits for the persistence thread to process all queued patches
+        inner
+    }
+}
+
+impl<DB: Database> ParallelDatabase<DB> {
+    /// Provides readonly access to the wrapped DB. The DB may not contain the latest changes applied
+    /// to this wrapper.
+    pub(crate) fn inner(&self) -> &DB {
+        &self.inner
+    }
+
+    /// Provides access to the wrapped DB. Should not be used to mutate DB data.
+    pub(crate) fn inner_mut(&mut self) -> &mut DB {
+        &mut self.inner
+    }
+
+    /// Returns the maximum number of patches queued for persistence.
+    pub fn buffer_capacity(&self) -> usize {
+        self.buffer_capacity
+    }
+
+    /// Changes the maximum number of patches queued for persistence. If the new capacity is lower than
+    /// the number of currently queued patches, the following [`Database::apply_patch()`] call will block
+    /// until the queue shrinks sufficiently.
+    pub fn set_buffer_capacity(&mut self, buffer_capacity: usize) {
+        self.buffer_capacity = buffer_capacity;
+    }
+
+    /// Returns the number of patches queued for persistence.
+    pub fn pending_patch_count(&self) -> usize {
+        let pending_count = self.sent_count - self.progress.state().persisted_count;
+        usize::try_from(pending_count).expect("pending patch count overflow")
+    }
+
+    /// Blocks until all patches applied to this database are persisted.
+    ///
+    /// # Panics
+    ///
+    /// Propagates a panic from the persistence thread, if any.
+    pub fn wait_sync(&mut self) {
+        self.wait_for_persistence(self.sent_count, PersistenceWaitReason::Sync);
+    }
+
+    /// Blocks until at least `patch_count` patches are persisted.
+    fn wait_for_persistence(&mut self, patch_count: u64, reason: PersistenceWaitReason) {
+        let latency = PARALLEL_PERSISTENCE_METRICS.wait_latency[&reason].start();
+        if !self.progress.wait_for(patch_count) {
+            self.propagate_persistence_panic();
+        }
+        latency.observe();
+        self.drop_persisted_patches();
+    }
+
+    /// Blocks until there's space in the persistence queue. The patch being persisted right now
+    /// is counted as queued.
+    fn wait_for_queue_space(&mut self) {
+        let capacity = self.buffer_capacity as u64;
+        if self.sent_count > capacity {
+            self.wait_for_persistence(
+                self.sent_count - capacity,
+                PersistenceWaitReason::Backpressure,
+            );
+        }
+    }
+
+    fn send_command(&mut self, command: PersistenceCommand) {
+        let sender = self
+            .command_sender
+            .as_ref()
+            .expect("command sender is only taken on drop");
+        if sender.send(command).is_err() {
+            self.propagate_persistence_panic();
+        }
+    }
+
+    fn propagate_persistence_panic(&mut self) -> ! {
+        let handle = self
+            .persistence_handle
+            .take()
+            .expect("persistence thread handle was already taken");
+        match handle.join() {
+            Err(panic) => panic::resume_unwind(panic),
+            Ok(()) => unreachable!("persistence thread exited while its command sender is alive"),
+        }
+    }
+
+    /// Removes patches confirmed as persisted from the front of the queue.
+    fn drop_persisted_patches(&mut self) {
+        let persisted_count = self.progress.state().persisted_count;
+        // The persistence thread processes patches in order, so persisted patches are at the front of the queue.
+        let first_queued_idx = self.sent_count - self.patches.len() as u64;
+        let persisted_in_queue = persisted_count.saturating_sub(first_queued_idx);
+        let persisted_in_queue = usize::try_from(persisted_in_queue)
+            .map_or(self.patches.len(), |count| count.min(self.patches.len()));
+        self.patches.drain(..persisted_in_queue);
+        PARALLEL_PERSISTENCE_METRICS
+            .queue_len
+            .set(self.pending_patch_count());
+    }
+
+    /// Returns the number of patches that need to be persisted before the specified pruning can be applied.
+    fn patch_count_for_pruning(&self, prune_patch: &PrunePatchSet) -> Option<u64> {
+        let pruned_versions = &prune_patch.deleted_stale_key_versions;
+        let last_affecting_idx = self.patches.iter().rposition(|patch| {
+            let has_pruned_stale_keys = patch
+                .stale_keys_by_version
+                .iter()
+                .any(|(version, keys)| pruned_versions.contains(version) && !keys.is_empty());
+            let has_pruned_nodes = prune_patch.pruned_node_keys.iter().any(|key| {
+                let Some(sub_patch) = patch.patches_by_version.get(&key.version) else {
+                    return false;
+                };
+                if key.is_empty() {
+                    sub_patch.root.is_some()
+                } else {
+                    sub_patch.nodes.contains_key(key)
+                }
+            });
+            has_pruned_stale_keys || has_pruned_nodes
+        })?;
+        let first_queued_idx = self.sent_count - self.patches.len() as u64;
+        Some(first_queued_idx + last_affecting_idx as u64 + 1)
+    }
+
+    /// Looks up a node in the queued patches. The newest patches take precedence since a version may have been
+    /// truncated and then re-created.
+    fn lookup_patches(&self, key: &NodeKey) -> Option<Node> {
+        self.patches.iter().rev().find_map(|patch| {
+            let sub_patch = patch.patches_by_version.get(&key.version)?;
+            sub_patch.nodes.get(key).cloned()
+        })
+    }
+}
+
+impl<DB: Database> Database for ParallelDatabase<DB> {
+    fn try_manifest(&self) -> Result<Option<Manifest>, DeserializeError> {
+        if let Some(latest_patch) = self.patches.back() {
+            // Each patch contains the full manifest, so the latest patch is authoritative.
+            Ok(Some(latest_patch.manifest.clone()))
+        } else {
+            self.inner.try_manifest()
+        }
+    }
+
+    fn try_root(&self, version: u64) -> Result<Option<Root>, DeserializeError> {
+        if let Some(latest_patch) = self.patches.back() {
+            if version >= latest_patch.manifest.version_count {
+                // The version is not created yet, or was truncated by one of queued patches.
+                return Ok(None);
+            }
+        }
+
+        let patch_root = self.patches.iter().rev().find_map(|patch| {
+            let sub_patch = patch.patches_by_version.get(&version)?;
+            sub_patch.root.clone()
+        });
+        if let Some(root) = patch_root {
+            return Ok(Some(root));
+        }
+        self.inner.try_root(version)
+    }
+
+    fn try_tree_node(
+        &self,
+        key: &NodeKey,
+        is_leaf: bool,
+    ) -> Result<Option<Node>, DeserializeError> {
+        if let Some(node) = self.lookup_patches(key) {
+            return Ok(Some(node));
+        }
+        self.inner.try_tree_node(key, is_leaf)
+    }
+
+    fn tree_nodes(&self, keys: &NodeKeys) -> Vec<Option<Node>> {
+        if self.patches.is_empty() {
+            return self.inner.tree_nodes(keys);
+        }
+
+        let patch_values: Vec<_> = keys
+            .iter()
+            .map(|(key, _)| self.lookup_patches(key))
+            .collect();
+        let db_keys: Vec<_> = keys
+            .iter()
+            .zip(&patch_values)
+            .filter_map(|(&key, patch_value)| patch_value.is_none().then_some(key))
+            .collect();
+        let mut db_values = self.inner.tree_nodes(&db_keys).into_iter();
+
+        let values = patch_values
+            .into_iter()
+            .map(|patch_value| patch_value.or_else(|| db_values.next().unwrap()));
+        values.collect()
+    }
+
+    fn start_profiling(&self, operation: ProfiledTreeOperation) -> Box<dyn Any> {
+        self.inner.start_profiling(operation)
+    }
+
+    fn apply_patch(&mut self, patch: PatchSet) {
+        self.wait_for_queue_space();
+        let patch = Arc::new(patch);
+        self.patches.push_back(patch.clone());
+        self.send_command(PersistenceCommand {
+            patch,
+            queued_at: Instant::now(),
+        });
+        self.sent_count += 1;
+        self.drop_persisted_patches();
+    }
+}
+
+impl<DB: PruneDatabase> PruneDatabase for ParallelDatabase<DB> {
+    fn min_stale_key_version(&self) -> Option<u64> {
+        let patches_min_version = self
+            .patches
+            .iter()
+            .flat_map(|patch| &patch.stale_keys_by_version)
+            .filter_map(|(&version, keys)| (!keys.is_empty()).then_some(version))
+            .min();
+        let inner_min_version = self.inner.min_stale_key_version();
+        match (patches_min_version, inner_min_version) {
+            (Some(patches_version), Some(inner_version)) => {
+                Some(patches_version.min(inner_version))
+            }
+            (version, None) | (None, version) => version,
+        }
+    }
+
+    fn stale_keys(&self, version: u64) -> Vec<NodeKey> {
+        let mut stale_keys = self.inner.stale_keys(version);
+        let patches_stale_keys = self
+            .patches
+            .iter()
+            .filter_map(|patch| patch.stale_keys_by_version.get(&version))
+            .flatten();
+        // Some patches may be already persisted, so we need to deduplicate keys.
+        let mut seen_keys: HashSet<_> = stale_keys.iter().copied().collect();
+        for &key in patches_stale_keys {
+            if seen_keys.insert(key) {
+                stale_keys.push(key);
+            }
+        }
+        stale_keys
+    }
+
+    fn prune(&mut self, patch: PrunePatchSet) {
+        // Pruning must be applied after all queued changes touching the pruned data; otherwise, these changes
+        // would restore pruned data once persisted. Other queued changes can be persisted concurrently.
+        if let Some(patch_count) = self.patch_count_for_pruning(&patch) {
+            self.wait_for_persistence(patch_count, PersistenceWaitReason::Prune);
+        }
+        self.inner.prune(patch);
+    }
+}
+
+impl<DB> Drop for ParallelDatabase<DB> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            return; // Don't wait for the persistence thread in order to not obscure the original panic
+        }
+        let Some(handle) = self.persistence_handle.take() else {
+            return; // The persistence thread has already panicked, and the panic was propagated
+        };
+        // Dropping the command sender makes the persistence thread exit after processing all queued commands.
+        self.command_sender = None;
+        if let Err(panic) = handle.join() {
+            panic::resume_unwind(panic);
+        }
+    }
+}
+
+/// Database that is either sequential or parallelized using [`ParallelDatabase`].
+#[derive(Debug)]
+pub(crate) enum MaybeParallel<DB> {
+    Sequential(DB),
+    Parallel(ParallelDatabase<DB>),
+}
+
+impl<DB: Database> MaybeParallel<DB> {
+    pub fn inner(&self) -> &DB {
+        match self {
+            Self::Sequential(db) => db,
+            Self::Parallel(db) => db.inner(),
+        }
+    }
+
+    pub fn inner_mut(&mut self) -> &mut DB {
+        match self {
+            Self::Sequential(db) => db,
+            Self::Parallel(db) => db.inner_mut(),
+        }
+    }
+
+    /// Blocks until all changes are persisted. No-op for a sequential database.
+    pub fn wait_sync(&mut self) {
+        if let Self::Parallel(db) = self {
+            db.wait_sync();
+        }
+    }
+}
+
+impl<DB: Database + Clone + 'static> MaybeParallel<DB> {
+    /// Switches this database to parallel persistence. No-op if the database is already parallel.
+    pub fn parallelize(&mut self, buffer_capacity: usize) {
+        if let Self::Sequential(db) = self {
+            *self = Self::Parallel(ParallelDatabase::new(db.clone(), buffer_capacity));
+        }
+    }
+}
+
+impl<DB: Database> Database for MaybeParallel<DB> {
+    fn try_manifest(&self) -> Result<Option<Manifest>, DeserializeError> {
+        match self {
+            Self::Sequential(db) => db.try_manifest(),
+            Self::Parallel(db) => db.try_manifest(),
+        }
+    }
+
+    fn try_root(&self, version: u64) -> Result<Option<Root>, DeserializeError> {
+        match self {
+            Self::Sequential(db) => db.try_root(version),
+            Self::Parallel(db) => db.try_root(version),
+        }
+    }
+
+    fn try_tree_node(
+        &self,
+        key: &NodeKey,
+        is_leaf: bool,
+    ) -> Result<Option<Node>, DeserializeError> {
+        match self {
+            Self::Sequential(db) => db.try_tree_node(key, is_leaf),
+            Self::Parallel(db) => db.try_tree_node(key, is_leaf),
+        }
+    }
+
+    fn tree_nodes(&self, keys: &NodeKeys) -> Vec<Option<Node>> {
+        match self {
+            Self::Sequential(db) => db.tree_nodes(keys),
+            Self::Parallel(db) => db.tree_nodes(keys),
+        }
+    }
+
+    fn start_profiling(&self, operation: ProfiledTreeOperation) -> Box<dyn Any> {
+        match self {
+            Self::Sequential(db) => db.start_profiling(operation),
+            Self::Parallel(db) => db.start_profiling(operation),
+        }
+    }
+
+    fn apply_patch(&mut self, patch: PatchSet) {
+        match self {
+            Self::Sequential(db) => db.apply_patch(patch),
+            Self::Parallel(db) => db.apply_patch(patch),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    use super::*;
+    use crate::{
+        types::TreeEntry, Key, MerkleTree, PatchSet, RocksDBWrapper, TreeInstruction, ValueHash,
+    };
+
+    fn generate_entries(range: std::ops::Range<u64>) -> Vec<TreeEntry> {
+        range
+            .map(|i| TreeEntry::new(Key::from(i), i + 1, ValueHash::from_low_u64_be(i)))
+            .collect()
+    }
+
+    /// Wrapper for a `PatchSet` that blocks patch persistence until allowed.
+    #[derive(Debug, Clone)]
+    struct BlockingDatabase {
+        inner: Arc<std::sync::Mutex<PatchSet>>,
+        persistence_allowed: Arc<AtomicBool>,
+    }
+
+    impl BlockingDatabase {
+        fn new() -> Self {
+            Self {
+                inner: Arc::default(),
+                persistence_allowed: Arc::new(AtomicBool::new(false)),
+            }
+        }
+    }
+
+    impl Database for BlockingDatabase {
+        fn try_manifest(&self) -> Result<Option<Manifest>, DeserializeError> {
+            self.inner.lock().unwrap().try_manifest()
+        }
+
+        fn try_root(&self, version: u64) -> Result<Option<Root>, DeserializeError> {
+            self.inner.lock().unwrap().try_root(version)
+        }
+
+        fn try_tree_node(
+            &self,
+            key: &NodeKey,
+            is_leaf: bool,
+        ) -> Result<Option<Node>, DeserializeError> {
+            self.inner.lock().unwrap().try_tree_node(key, is_leaf)
+        }
+
+        fn start_profiling(&self, _operation: ProfiledTreeOperation) -> Box<dyn Any> {
+            Box::new(())
+        }
+
+        fn apply_patch(&mut self, patch: PatchSet) {
+            while !self.persistence_allowed.load(Ordering::SeqCst) {
+                thread::yield_now();
+            }
+            self.inner.lock().unwrap().apply_patch(patch);
+        }
+    }
+
+    impl PruneDatabase for BlockingDatabase {
+        fn min_stale_key_version(&self) -> Option<u64> {
+            self.inner.lock().unwrap().min_stale_key_version()
+        }
+
+        fn stale_keys(&self, version: u64) -> Vec<NodeKey> {
+            self.inner.lock().unwrap().stale_keys(version)
+        }
+
+        fn prune(&mut self, patch: PrunePatchSet) {
+            self.inner.lock().unwrap().prune(patch);
+        }
+    }
+
+    #[test]
+    fn parallel_database_serves_reads_from_queued_patches() {
+        let db = BlockingDatabase::new();
+        let mut tree = MerkleTree::new(ParallelDatabase::new(db.clone(), 4));
+        let mut sequential_tree = MerkleTree::new(PatchSet::default());
+
+        for chunk_start in (0..100).step_by(25) {
+            let entries = generate_entries(chunk_start..chunk_start + 25);
+            let output = tree.extend(entries.clone());
+            let expected_output = sequential_tree.extend(entries);
+            assert_eq!(output.root_hash, expected_output.root_hash);
+        }
+        // None of the patches is persisted yet.
+        assert_eq!(tree.db.pending_patch_count(), 4);
+        assert!(db.try_manifest().unwrap().is_none());
+        assert_eq!(tree.latest_version(), Some(3));
+        for version in 0..4 {
+            assert_eq!(tree.root_hash(version), sequential_tree.root_hash(version));
+        }
+        assert_eq!(tree.root_hash(4), None);
+
+        let keys: Vec<_> = (0..100).map(Key::from).collect();
+        let entries = tree.entries_with_proofs(3, &keys).unwrap();
+        let expected_entries = sequential_tree.entries_with_proofs(3, &keys).unwrap();
+        for (entry, expected_entry) in entries.iter().zip(&expected_entries) {
+            assert_eq!(entry.base, expected_entry.base);
+            assert_eq!(entry.merkle_path, expected_entry.merkle_path);
+        }
+
+        db.persistence_allowed.store(true, Ordering::SeqCst);
+        tree.db.wait_sync();
+        assert_eq!(tree.db.pending_patch_count(), 0);
+        assert_eq!(db.try_manifest().unwrap().unwrap().version_count, 4);
+        let tree = MerkleTree::new(db);
+        for version in 0..4 {
+            assert_eq!(tree.root_hash(version), sequential_tree.root_hash(version));
+        }
+    }
+
+    #[test]
+    fn parallel_database_handles_truncation() {
+        let db = BlockingDatabase::new();
+        let mut tree = MerkleTree::new(ParallelDatabase::new(db.clone(), 8));
+        let mut sequential_tree = MerkleTree::new(PatchSet::default());
+
+        for chunk_start in (0..100).step_by(25) {
+            let entries = generate_entries(chunk_start..chunk_start + 25);
+            tree.extend(entries.clone());
+            sequential_tree.extend(entries);
+        }
+        tree.truncate_recent_versions(2);
+        sequential_tree.truncate_recent_versions(2);
+        assert_eq!(tree.latest_version(), Some(1));
+        assert_eq!(tree.root_hash(2), None);
+        assert_eq!(tree.root_hash(3), None);
+
+        let instructions: Vec<_> = generate_entries(200..210)
+            .into_iter()
+            .map(TreeInstruction::Write)
+            .collect();
+        let output = tree.extend_with_proofs(instructions.clone());
+        let expected_output = sequential_tree.extend_with_proofs(instructions);
+        assert_eq!(output.root_hash(), expected_output.root_hash());
+        assert_eq!(tree.latest_version(), Some(2));
+        assert_eq!(tree.root_hash(2), sequential_tree.root_hash(2));
+        assert_eq!(tree.root_hash(3), None);
+
+        db.persistence_allowed.store(true, Ordering::SeqCst);
+        tree.db.wait_sync();
+        let tree = MerkleTree::new(db);
+        assert_eq!(tree.latest_version(), Some(2));
+        assert_eq!(tree.root_hash(2), sequential_tree.root_hash(2));
+        tree.verify_consistency(2, true).unwrap();
+    }
+
+    #[test]
+    fn parallel_database_stale_keys() {
+        let db = BlockingDatabase::new();
+        let mut tree = MerkleTree::new(ParallelDatabase::new(db.clone(), 4));
+        let mut sequential_tree = MerkleTree::new(PatchSet::default());
+
+        for chunk_start in (0..60).step_by(20) {
+            let entries = generate_entries(chunk_start..chunk_start + 20);
+            tree.extend(entries.clone());
+            sequential_tree.extend(entries);
+        }
+        for version in 0..3 {
+            let mut stale_keys = tree.db.stale_keys(version);
+            let mut expected_stale_keys = sequential_tree.db.stale_keys(version);
+            stale_keys.sort_unstable();
+            expected_stale_keys.sort_unstable();
+            assert_eq!(stale_keys, expected_stale_keys);
+        }
+        assert_eq!(
+            tree.db.min_stale_key_version(),
+            sequential_tree.db.min_stale_key_version()
+        );
+
+        db.persistence_allowed.store(true, Ordering::SeqCst);
+        tree.db.wait_sync();
+        // Keys must not be duplicated after persistence.
+        let stale_keys = tree.db.stale_keys(2);
+        let expected_stale_keys = sequential_tree.db.stale_keys(2);
+        assert_eq!(stale_keys.len(), expected_stale_keys.len());
+    }
+
+    #[test]
+    fn pruning_only_waits_for_affecting_patches() {
+        let db = BlockingDatabase::new();
+        db.persistence_allowed.store(true, Ordering::SeqCst);
+        let mut tree = MerkleTree::new(ParallelDatabase::new(db.clone(), 4));
+        for chunk_start in (0..60).step_by(20) {
+            tree.extend(generate_entries(chunk_start..chunk_start + 20));
+        }
+        tree.db.wait_sync();
+
+        db.persistence_allowed.store(false, Ordering::SeqCst);
+        for chunk_start in (60..100).step_by(20) {
+            tree.extend(generate_entries(chunk_start..chunk_start + 20));
+        }
+        assert_eq!(tree.db.pending_patch_count(), 2);
+
+        // Pruning persisted versions must not wait for queued patches (otherwise, the test would deadlock).
+        let mut pruned_keys = tree.db.stale_keys(1);
+        pruned_keys.extend(tree.db.stale_keys(2));
+        assert!(!pruned_keys.is_empty());
+        tree.db.prune(PrunePatchSet::new(pruned_keys, 1..3));
+        assert_eq!(tree.db.pending_patch_count(), 2);
+        assert!(db.stale_keys(1).is_empty());
+        assert!(db.stale_keys(2).is_empty());
+
+        // Pruning versions from the queued patches must wait for these patches to be persisted.
+        let pruned_keys = tree.db.stale_keys(3);
+        assert!(!pruned_keys.is_empty());
+        let persistence_allowed = db.persistence_allowed.clone();
+        let unblocking_thread = thread::spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(50));
+            persistence_allowed.store(true, Ordering::SeqCst);
+        });
+        tree.db.prune(PrunePatchSet::new(pruned_keys, 3..4));
+        assert!(tree.db.pending_patch_count() <= 1);
+        assert!(db.stale_keys(3).is_empty());
+        unblocking_thread.join().unwrap();
+
+        tree.db.wait_sync();
+        assert_eq!(tree.db.pending_patch_count(), 0);
+        assert!(!db.stale_keys(4).is_empty());
+    }
+
+    #[test]
+    fn changing_buffer_capacity() {
+        let db = BlockingDatabase::new();
+        let mut tree = MerkleTree::new(ParallelDatabase::new(db.clone(), 4));
+        for chunk_start in (0..80).step_by(20) {
+            tree.extend(generate_entries(chunk_start..chunk_start + 20));
+        }
+        assert_eq!(tree.db.pending_patch_count(), 4);
+
+        tree.db.set_buffer_capacity(1);
+        assert_eq!(tree.db.buffer_capacity(), 1);
+        let persistence_allowed = db.persistence_allowed.clone();
+        let unblocking_thread = thread::spawn(move || {
+            thread::sleep(std::time::Duration::from_millis(50));
+            persistence_allowed.store(true, Ordering::SeqCst);
+        });
+        // Must block until the queue shrinks to the new capacity.
+        tree.extend(generate_entries(80..100));
+        assert!(tree.db.pending_patch_count() <= 2);
+        unblocking_thread.join().unwrap();
+
+        let db = tree.db.into_inner();
+        assert_eq!(db.try_manifest().unwrap().unwrap().version_count, 5);
+        let tree = MerkleTree::new(db);
+        tree.verify_consistency(4, true).unwrap();
+    }
+
+    #[test]
+    fn parallel_database_with_rocksdb() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        let mut tree = MerkleTree::new(ParallelDatabase::new(db, 1));
+        let mut sequential_tree = MerkleTree::new(PatchSet::default());
+
+        for chunk_start in (0..500).step_by(50) {
+            let entries = generate_entries(chunk_start..chunk_start + 50);
+            let output = tree.extend(entries.clone());
+            let expected_output = sequential_tree.extend(entries);
+            assert_eq!(output.root_hash, expected_output.root_hash);
+        }
+        drop(tree); // waits for all patches to be persisted
+
+        let db = RocksDBWrapper::new(temp_dir.path()).unwrap();
+        let tree = MerkleTree::new(db);
+        assert_eq!(tree.latest_version(), Some(9));
+        assert_eq!(tree.latest_root_hash(), sequential_tree.latest_root_hash());
+        tree.verify_consistency(9, true).unwrap();
+    }
+
+    #[derive(Debug, Clone)]
+    struct PanickingDatabase;
+
+    impl Database for PanickingDatabase {
+        fn try_manifest(&self) -> Result<Option<Manifest>, DeserializeError> {
+            Ok(None)
+        }
+
+        fn try_root(&self, _version: u64) -> Result<Option<Root>, DeserializeError> {
+            Ok(None)
+        }
+
+        fn try_tree_node(
+            &self,
+            _key: &NodeKey,
+            _is_leaf: bool,
+        ) -> Result<Option<Node>, DeserializeError> {
+            Ok(None)
+        }
+
+        fn start_profiling(&self, _operation: ProfiledTreeOperation) -> Box<dyn Any> {
+            Box::new(())
+        }
+
+        fn apply_patch(&mut self, _patch: PatchSet) {
+            panic!("oops");
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "oops")]
+    fn persistence_panic_is_propagated() {
+        let mut tree = MerkleTree::new(ParallelDatabase::new(PanickingDatabase, 1));
+        tree.extend(generate_entries(0..10));
+        tree.db.wait_sync();
+    }
+}
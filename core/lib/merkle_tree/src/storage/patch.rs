@@ -21,7 +21,7 @@ use crate::{
 
 /// Subset of a [`PatchSet`] corresponding to a specific version. All nodes in the subset
 /// have the same version.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub(super) struct PartialPatchSet {
     pub root: Option<Root>,
     // TODO (BFT-130): investigate most efficient ways to store key-value pairs:
@@ -38,7 +38,7 @@ impl PartialPatchSet {
 }
 
 /// Raw set of database changes.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct PatchSet {
     pub(super) manifest: Manifest,
     pub(super) patches_by_version: HashMap<u64, PartialPatchSet>,
@@ -78,6 +78,9 @@ impl RocksDBWrapper {
     // This key must not overlap with keys for nodes; easy to see that it's true,
     // since the minimum node key is [0, 0, 0, 0, 0, 0, 0, 0].
     const MANIFEST_KEY: &'static [u8] = &[0];
+    /// Key to store the cursor of the incremental consistency check. Similar to the manifest key,
+    /// it doesn't overlap with node keys.
+    const CONSISTENCY_CURSOR_KEY: &'static [u8] = &[1];
 
     /// Creates a new wrapper, initializing RocksDB at the specified directory.
     ///
@@ -105,6 +108,11 @@ impl RocksDBWrapper {
         self.multi_get_chunk_size = chunk_size;
     }
 
+    /// Returns the chunk size for multi-get operations.
+    pub fn multi_get_chunk_size(&self) -> usize {
+        self.multi_get_chunk_size
+    }
+
     fn raw_node(&self, key: &[u8]) -> Option<Vec<u8>> {
         self.db
             .get_cf(MerkleTreeColumnFamily::Tree, key)
@@ -135,7 +143,7 @@ impl RocksDBWrapper {
             .collect()
     }
 
-    fn deserialize_node(
+    pub(super) fn deserialize_node(
         raw_node: &[u8],
         key: &NodeKey,
         is_leaf: bool,
@@ -156,6 +164,67 @@ impl RocksDBWrapper {
         })
     }
 
+    /// Returns the next tree version to be checked by the incremental consistency check, as persisted
+    /// via [`Self::save_consistency_cursor()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics on RocksDB I/O errors or if the stored cursor is malformed.
+    pub fn consistency_cursor(&self) -> Option<u64> {
+        let raw_cursor = self.raw_node(Self::CONSISTENCY_CURSOR_KEY)?;
+        let raw_cursor: [u8; 8] = raw_cursor
+            .try_into()
+            .expect("malformed consistency check cursor in RocksDB");
+        Some(u64::from_be_bytes(raw_cursor))
+    }
+
+    /// Persists the next tree version to be checked by the incremental consistency check. The cursor is stored
+    /// separately from tree data and doesn't influence tree operation.
+    ///
+    /// # Panics
+    ///
+    /// Panics on RocksDB I/O errors.
+    pub fn save_consistency_cursor(&self, next_version: u64) {
+        let mut write_batch = self.db.new_write_batch();
+        write_batch.put_cf(
+            MerkleTreeColumnFamily::Tree,
+            Self::CONSISTENCY_CURSOR_KEY,
+            &next_version.to_be_bytes(),
+        );
+        self.db
+            .write(write_batch)
+            .expect("Failed writing a batch to RocksDB");
+    }
+
+    /// Writes raw nodes (i.e., serialized nodes keyed by their DB keys) to the tree column family
+    /// in a single batch. Used when importing tree data.
+    pub(super) fn write_raw_nodes<'a>(&self, nodes: impl Iterator<Item = (&'a [u8], &'a [u8])>) {
+        let mut write_batch = self.db.new_write_batch();
+        for (key, node) in nodes {
+            write_batch.put_cf(MerkleTreeColumnFamily::Tree, key, node);
+        }
+        self.db
+            .write(write_batch)
+            .expect("Failed writing a batch to RocksDB");
+    }
+
+    /// Writes the root for the specified version and the raw tree manifest in a single batch.
+    pub(super) fn write_raw_root_and_manifest(
+        &self,
+        version: u64,
+        raw_root: &[u8],
+        raw_manifest: &[u8],
+    ) {
+        let root_key = NodeKey::empty(version).to_db_key();
+        self.write_raw_nodes(
+            [
+                (root_key.as_slice(), raw_root),
+                (Self::MANIFEST_KEY, raw_manifest),
+            ]
+            .into_iter(),
+        );
+    }
+
     /// Returns the wrapped RocksDB instance.
     pub fn into_inner(self) -> RocksDB<MerkleTreeColumnFamily> {
         self.db
@@ -387,6 +456,28 @@ mod tests {
         assert_contains_exactly_keys(&db, &expected_keys);
     }
 
+    #[test]
+    fn consistency_cursor_is_not_affected_by_reverts() {
+        let dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+        let mut db = RocksDBWrapper::new(dir.path()).unwrap();
+        assert_eq!(db.consistency_cursor(), None);
+
+        let root = Root::new(2, Node::Internal(InternalNode::default()));
+        db.apply_patch(create_patch(0, root, generate_nodes(0, &[1, 2])));
+        db.save_consistency_cursor(1);
+        assert_eq!(db.consistency_cursor(), Some(1));
+
+        db.apply_patch(create_patch(0, Root::Empty, HashMap::new()));
+        assert_eq!(db.consistency_cursor(), Some(1));
+        let expected_keys = HashSet::from_iter([NodeKey::empty(0)]);
+        assert_contains_exactly_keys(&db, &expected_keys);
+
+        db.save_consistency_cursor(0);
+        drop(db);
+        let db = RocksDBWrapper::new(dir.path()).unwrap();
+        assert_eq!(db.consistency_cursor(), Some(0));
+    }
+
     fn assert_contains_exactly_keys(db: &RocksDBWrapper, expected_keys: &HashSet<NodeKey>) {
         let cf = MerkleTreeColumnFamily::Tree;
         let actual_keys: HashSet<_> = db
@@ -5,8 +5,8 @@ use std::str;
 use crate::{
     errors::{DeserializeError, DeserializeErrorKind, ErrorContext},
     types::{
-        ChildRef, InternalNode, Key, LeafNode, Manifest, Node, Root, TreeTags, ValueHash,
-        HASH_SIZE, KEY_SIZE,
+        ChildRef, InternalNode, Key, LeafNode, Manifest, Node, Root, TreeEntry, TreeMultiProof,
+        TreeTags, ValueHash, HASH_SIZE, KEY_SIZE,
     },
 };
 
@@ -305,12 +305,105 @@ impl Manifest {
     }
 }
 
+impl TreeMultiProof {
+    /// Deserializes a multiproof from the compact binary form produced by [`Self::serialize()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the input is malformed. The returned proof is not verified; use [`Self::verify()`]
+    /// to verify it.
+    pub fn deserialize(mut bytes: &[u8]) -> Result<Self, DeserializeError> {
+        Self::deserialize_inner(&mut bytes)
+            .map_err(|err| err.with_context(ErrorContext::MultiProof))
+    }
+
+    fn deserialize_inner(bytes: &mut &[u8]) -> Result<Self, DeserializeError> {
+        let entry_count = leb128::read::unsigned(bytes).map_err(DeserializeErrorKind::Leb128)?;
+        let entry_count =
+            usize::try_from(entry_count).map_err(|_| DeserializeErrorKind::UnexpectedEof)?;
+        // Each entry takes at least `KEY_SIZE + 1` bytes, so we can bound the capacity to prevent huge allocations.
+        let mut entries = Vec::with_capacity(entry_count.min(bytes.len() / (KEY_SIZE + 1)));
+        for _ in 0..entry_count {
+            let key = Key::from_big_endian(read_bytes(bytes, KEY_SIZE)?);
+            let leaf_index = leb128::read::unsigned(bytes).map_err(|err| {
+                DeserializeErrorKind::Leb128(err).with_context(ErrorContext::LeafIndex)
+            })?;
+            let value = if leaf_index == 0 {
+                ValueHash::zero()
+            } else {
+                ValueHash::from_slice(read_bytes(bytes, HASH_SIZE)?)
+            };
+            entries.push(TreeEntry::new(key, leaf_index, value));
+        }
+
+        let sibling_count = leb128::read::unsigned(bytes).map_err(DeserializeErrorKind::Leb128)?;
+        let sibling_count =
+            usize::try_from(sibling_count).map_err(|_| DeserializeErrorKind::UnexpectedEof)?;
+        if sibling_count > bytes.len() * 8 {
+            return Err(DeserializeErrorKind::UnexpectedEof.into());
+        }
+        let bitmap = read_bytes(bytes, (sibling_count + 7) / 8)?;
+        let empty_siblings: Vec<_> = (0..sibling_count)
+            .map(|i| bitmap[i / 8] & (1 << (i % 8)) != 0)
+            .collect();
+        let hash_count = empty_siblings.iter().filter(|&&is_empty| !is_empty).count();
+        let sibling_hashes = read_bytes(bytes, hash_count * HASH_SIZE)?
+            .chunks_exact(HASH_SIZE)
+            .map(ValueHash::from_slice)
+            .collect();
+        if !bytes.is_empty() {
+            return Err(DeserializeErrorKind::TrailingData.into());
+        }
+
+        Ok(Self {
+            entries,
+            empty_siblings,
+            sibling_hashes,
+        })
+    }
+
+    /// Serializes this multiproof into a compact binary form. Values of entries with zero leaf index
+    /// (i.e., missing entries) are not serialized and are assumed to be zero.
+    pub fn serialize(&self, buffer: &mut Vec<u8>) {
+        leb128::write::unsigned(buffer, self.entries.len() as u64).unwrap();
+        let mut key_bytes = [0_u8; KEY_SIZE];
+        for entry in &self.entries {
+            entry.key.to_big_endian(&mut key_bytes);
+            buffer.extend_from_slice(&key_bytes);
+            leb128::write::unsigned(buffer, entry.leaf_index).unwrap();
+            if entry.leaf_index != 0 {
+                buffer.extend_from_slice(entry.value.as_bytes());
+            }
+        }
+
+        leb128::write::unsigned(buffer, self.empty_siblings.len() as u64).unwrap();
+        for chunk in self.empty_siblings.chunks(8) {
+            let byte = chunk
+                .iter()
+                .enumerate()
+                .fold(0_u8, |acc, (i, &is_empty)| acc | (u8::from(is_empty) << i));
+            buffer.push(byte);
+        }
+        for hash in &self.sibling_hashes {
+            buffer.extend_from_slice(hash.as_bytes());
+        }
+    }
+}
+
+fn read_bytes<'a>(bytes: &mut &'a [u8], len: usize) -> Result<&'a [u8], DeserializeError> {
+    if bytes.len() < len {
+        return Err(DeserializeErrorKind::UnexpectedEof.into());
+    }
+    let (head, tail) = bytes.split_at(len);
+    *bytes = tail;
+    Ok(head)
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_types::H256;
 
     use super::*;
-    use crate::types::TreeEntry;
 
     #[test]
     fn serializing_manifest() {
@@ -460,4 +553,33 @@ mod tests {
         let root_copy = Root::deserialize(&buffer).unwrap();
         assert_eq!(root_copy, root);
     }
+
+    #[test]
+    fn serializing_multiproof() {
+        let proof = TreeMultiProof {
+            entries: vec![
+                TreeEntry::new(1.into(), 42, H256([4; 32])),
+                TreeEntry::new(513.into(), 0, H256::zero()),
+            ],
+            empty_siblings: vec![true, false, true, true, false, true, true, true, false],
+            sibling_hashes: vec![H256([1; 32]), H256([2; 32]), H256([3; 32])],
+        };
+        let mut buffer = vec![];
+        proof.serialize(&mut buffer);
+        // 1 byte for the entry count, 2 keys, 2 leaf indices, 1 value, 1 byte for the sibling count,
+        // 2 bytes for the bitmap, 3 hashes
+        assert_eq!(buffer.len(), 1 + 2 * 32 + 2 + 32 + 1 + 2 + 3 * 32);
+
+        let proof_copy = TreeMultiProof::deserialize(&buffer).unwrap();
+        assert_eq!(proof_copy, proof);
+
+        let err = TreeMultiProof::deserialize(&buffer[..buffer.len() - 1]).unwrap_err();
+        assert!(err.to_string().contains("unexpected end of input"), "{err}");
+        buffer.push(0);
+        let err = TreeMultiProof::deserialize(&buffer).unwrap_err();
+        assert!(
+            err.to_string().contains("unexpected trailing data"),
+            "{err}"
+        );
+    }
 }
@@ -808,3 +808,24 @@ fn recovery_pruning_equivalence(
         test_recovery_pruning_equivalence(kind, chunk_size, recovery_chunk_size, hasher);
     }
 }
+
+#[test]
+fn reapplying_entries_during_random_recovery() {
+    let entries: Vec<_> = (0..50_u64)
+        .map(|i| TreeEntry::new(U256::from(i) << 200, i + 1, ValueHash::from_low_u64_be(i)))
+        .collect();
+    let mut db = PatchSet::default();
+    let storage = Storage::new(&db, &Blake2Hasher, 0, false);
+    db.apply_patch(storage.extend_during_random_recovery(entries[..30].to_vec()));
+    let root = db.root(0).unwrap();
+    assert_eq!(root.leaf_count(), 30);
+
+    // Emulate recovery being resumed from the middle of a chunk.
+    let storage = Storage::new(&db, &Blake2Hasher, 0, false);
+    db.apply_patch(storage.extend_during_random_recovery(entries[20..30].to_vec()));
+    assert_eq!(db.root(0).unwrap(), root);
+
+    let storage = Storage::new(&db, &Blake2Hasher, 0, false);
+    db.apply_patch(storage.extend_during_random_recovery(entries[25..].to_vec()));
+    assert_eq!(db.root(0).unwrap().leaf_count(), 50);
+}
@@ -0,0 +1,580 @@
+//! Export / import of a single tree version in a portable file format.
+//!
+//! # Format
+//!
+//! An exported tree version consists of a header followed by a sequence of chunks. All integers
+//! are encoded as little-endian.
+//!
+//! - **Header:** [`MAGIC`] (4 bytes), format version (1 byte), header payload length (`u32`),
+//!   header payload, and a Blake2s-256 checksum of the header payload (32 bytes). The header payload
+//!   consists of the exported version (`u64`), the root hash (32 bytes), the length-prefixed (`u32`)
+//!   serialized tree manifest and the length-prefixed (`u32`) serialized tree root.
+//! - **Chunk:** number of nodes in the chunk (`u32`), payload length (`u32`), payload, and a Blake2s-256 checksum
+//!   of the payload (32 bytes). The payload is a sequence of nodes, each encoded as a length-prefixed (`u8`)
+//!   database node key, a node kind (1 byte; 1 for leaves, 0 for internal nodes) and the length-prefixed (`u32`)
+//!   serialized node.
+//! - **Terminating chunk:** a chunk with zero nodes, the payload of which is the total number of exported nodes
+//!   (`u64`).
+//!
+//! Nodes are exported in the depth-first order, so the export / import only needs to keep
+//! a small amount of data in memory.
+
+use std::io;
+
+use zksync_crypto::hasher::{blake2::Blake2Hasher, Hasher};
+
+use crate::{
+    errors::DeserializeError,
+    hasher::HashTree,
+    storage::{Database, RocksDBWrapper},
+    types::{Manifest, Nibbles, Node, NodeKey, Root, ValueHash, HASH_SIZE, KEY_SIZE},
+    MerkleTree,
+};
+
+/// Magic bytes at the start of an exported tree version.
+const MAGIC: [u8; 4] = *b"zkmt";
+/// Current format version.
+const FORMAT_VERSION: u8 = 1;
+/// Chunk size in bytes after which the exporter flushes the chunk regardless of its node count.
+const SOFT_MAX_PAYLOAD_LEN: usize = 16 << 20;
+/// Maximum accepted length of a chunk payload on import. Guards against allocating huge buffers
+/// if the length prefix is corrupted. Exported chunks are much smaller (see [`SOFT_MAX_PAYLOAD_LEN`]).
+const MAX_PAYLOAD_LEN: u32 = 64 << 20;
+
+/// Information about an exported tree version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExportedVersionInfo {
+    /// Exported tree version.
+    pub version: u64,
+    /// Root hash of the exported tree version.
+    pub root_hash: ValueHash,
+    /// Number of exported nodes, not including the root.
+    pub node_count: u64,
+}
+
+/// Errors that can occur during export / import of a tree version.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum TreeTransferError {
+    /// I/O error reading or writing data.
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// Error deserializing a tree node.
+    #[error("failed deserializing tree data: {0}")]
+    Deserialize(#[from] DeserializeError),
+    /// Exported tree version does not exist.
+    #[error("tree version {0} does not exist")]
+    MissingVersion(u64),
+    /// Node referenced by an exported version is missing.
+    #[error("missing node at {0}")]
+    MissingNode(NodeKey),
+    /// Imported data doesn't start with the expected magic bytes.
+    #[error("imported data is not an exported Merkle tree version")]
+    InvalidMagic,
+    /// Imported data has an unsupported format version.
+    #[error("unsupported format version: {0}")]
+    UnsupportedFormat(u8),
+    /// Checksum of a chunk doesn't match its contents.
+    #[error("checksum mismatch for chunk #{0} (chunk #0 is the header)")]
+    ChecksumMismatch(u64),
+    /// Imported data is malformed.
+    #[error("malformed imported data: {0}")]
+    Malformed(&'static str),
+    /// Import target database is not empty.
+    #[error("import target database is not empty")]
+    NonEmptyDatabase,
+}
+
+fn checksum(bytes: &[u8]) -> [u8; HASH_SIZE] {
+    Blake2Hasher.hash_bytes(bytes).0
+}
+
+/// Writes an exported tree version in chunks.
+#[derive(Debug)]
+struct ChunkWriter<W> {
+    writer: W,
+    chunk_size: usize,
+    buffer: Vec<u8>,
+    node_count_in_chunk: u32,
+    node_bytes: Vec<u8>,
+}
+
+impl<W: io::Write> ChunkWriter<W> {
+    fn new(writer: W, chunk_size: usize) -> Self {
+        Self {
+            writer,
+            chunk_size: chunk_size.max(1),
+            buffer: vec![],
+            node_count_in_chunk: 0,
+            node_bytes: vec![],
+        }
+    }
+
+    fn write_header(
+        &mut self,
+        version: u64,
+        root_hash: ValueHash,
+        manifest: &Manifest,
+        root: &Root,
+    ) -> io::Result<()> {
+        let mut payload = vec![];
+        payload.extend_from_slice(&version.to_le_bytes());
+        payload.extend_from_slice(root_hash.as_bytes());
+        manifest.serialize(&mut self.node_bytes);
+        write_len_prefixed(&mut payload, &self.node_bytes);
+        self.node_bytes.clear();
+        root.serialize(&mut self.node_bytes);
+        write_len_prefixed(&mut payload, &self.node_bytes);
+        self.node_bytes.clear();
+
+        self.writer.write_all(&MAGIC)?;
+        self.writer.write_all(&[FORMAT_VERSION])?;
+        self.writer
+            .write_all(&len_to_u32(payload.len()).to_le_bytes())?;
+        self.writer.write_all(&payload)?;
+        self.writer.write_all(&checksum(&payload))
+    }
+
+    fn write_node(&mut self, key: NodeKey, node: &Node) -> io::Result<()> {
+        let db_key = key.to_db_key();
+        self.buffer.push(u8::try_from(db_key.len()).unwrap());
+        // ^ `unwrap()` is safe: node keys are short
+        self.buffer.extend_from_slice(&db_key);
+        self.buffer.push(u8::from(matches!(node, Node::Leaf(_))));
+        node.serialize(&mut self.node_bytes);
+        write_len_prefixed(&mut self.buffer, &self.node_bytes);
+        self.node_bytes.clear();
+
+        self.node_count_in_chunk += 1;
+        if self.node_count_in_chunk as usize >= self.chunk_size
+            || self.buffer.len() >= SOFT_MAX_PAYLOAD_LEN
+        {
+            self.flush_chunk()?;
+        }
+        Ok(())
+    }
+
+    fn flush_chunk(&mut self) -> io::Result<()> {
+        if self.node_count_in_chunk == 0 {
+            return Ok(());
+        }
+        self.write_raw_chunk(self.node_count_in_chunk)?;
+        self.node_count_in_chunk = 0;
+        Ok(())
+    }
+
+    fn write_raw_chunk(&mut self, node_count: u32) -> io::Result<()> {
+        self.writer.write_all(&node_count.to_le_bytes())?;
+        self.writer
+            .write_all(&len_to_u32(self.buffer.len()).to_le_bytes())?;
+        self.writer.write_all(&self.buffer)?;
+        self.writer.write_all(&checksum(&self.buffer))?;
+        self.buffer.clear();
+        Ok(())
+    }
+
+    fn finish(mut self, total_node_count: u64) -> io::Result<()> {
+        self.flush_chunk()?;
+        self.buffer
+            .extend_from_slice(&total_node_count.to_le_bytes());
+        self.write_raw_chunk(0)?;
+        self.writer.flush()
+    }
+}
+
+fn len_to_u32(len: usize) -> u32 {
+    u32::try_from(len).expect("exported data is too large")
+}
+
+fn write_len_prefixed(buffer: &mut Vec<u8>, bytes: &[u8]) {
+    buffer.extend_from_slice(&len_to_u32(bytes.len()).to_le_bytes());
+    buffer.extend_from_slice(bytes);
+}
+
+/// Cursor over a byte slice used to parse chunk payloads.
+#[derive(Debug)]
+struct PayloadReader<'a>(&'a [u8]);
+
+impl<'a> PayloadReader<'a> {
+    fn read_bytes(&mut self, len: usize) -> Result<&'a [u8], TreeTransferError> {
+        if self.0.len() < len {
+            return Err(TreeTransferError::Malformed("unexpected end of chunk"));
+        }
+        let (bytes, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(bytes)
+    }
+
+    fn read_u8(&mut self) -> Result<u8, TreeTransferError> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    fn read_u32(&mut self) -> Result<u32, TreeTransferError> {
+        let bytes = self.read_bytes(4)?.try_into().unwrap();
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn read_u64(&mut self) -> Result<u64, TreeTransferError> {
+        let bytes = self.read_bytes(8)?.try_into().unwrap();
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn read_len_prefixed(&mut self) -> Result<&'a [u8], TreeTransferError> {
+        let len = self.read_u32()?;
+        self.read_bytes(len as usize)
+    }
+
+    fn ensure_empty(&self) -> Result<(), TreeTransferError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(TreeTransferError::Malformed(
+                "unexpected trailing data in chunk",
+            ))
+        }
+    }
+}
+
+/// Checks that `raw_key` is a well-formed DB key of a non-root node, so that it can be parsed
+/// with [`NodeKey::from_db_key()`] without panicking.
+fn is_valid_node_key(raw_key: &[u8]) -> bool {
+    let Some(&nibble_count) = raw_key.get(8) else {
+        return false;
+    };
+    let nibble_count = usize::from(nibble_count);
+    (1..=2 * KEY_SIZE).contains(&nibble_count) && raw_key.len() == 9 + (nibble_count + 1) / 2
+}
+
+fn read_u32(reader: &mut impl io::Read) -> io::Result<u32> {
+    let mut bytes = [0_u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+/// Reads a payload of the specified length followed by its checksum and verifies the checksum.
+fn read_checked_payload(
+    reader: &mut impl io::Read,
+    chunk_idx: u64,
+) -> Result<Vec<u8>, TreeTransferError> {
+    let len = read_u32(reader)?;
+    if len > MAX_PAYLOAD_LEN {
+        return Err(TreeTransferError::Malformed("chunk payload is too large"));
+    }
+    let mut payload = vec![0_u8; len as usize];
+    reader.read_exact(&mut payload)?;
+    let mut expected_checksum = [0_u8; HASH_SIZE];
+    reader.read_exact(&mut expected_checksum)?;
+    if checksum(&payload) != expected_checksum {
+        return Err(TreeTransferError::ChecksumMismatch(chunk_idx));
+    }
+    Ok(payload)
+}
+
+impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
+    /// Exports the specified version of the tree (i.e., all nodes reachable from its root) into the provided writer.
+    /// Nodes are written in chunks of at most `chunk_size` nodes, each protected by a checksum. The exported version
+    /// can be imported into an empty RocksDB instance using [`RocksDBWrapper::import_version()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version doesn't exist or is being recovered, if the tree is missing nodes,
+    /// or if writing data fails.
+    pub fn export_version(
+        &self,
+        version: u64,
+        writer: impl io::Write,
+        chunk_size: usize,
+    ) -> Result<ExportedVersionInfo, TreeTransferError> {
+        let manifest = self
+            .db
+            .try_manifest()?
+            .ok_or(TreeTransferError::MissingVersion(version))?;
+        if version >= manifest.version_count || manifest.recovered_version().is_some() {
+            return Err(TreeTransferError::MissingVersion(version));
+        }
+        let root = self
+            .db
+            .try_root(version)?
+            .ok_or(TreeTransferError::MissingVersion(version))?;
+        let root_hash = self
+            .root_hash(version)
+            .ok_or(TreeTransferError::MissingVersion(version))?;
+
+        // The imported tree only contains the exported version, so its manifest must end with it.
+        let exported_manifest = Manifest {
+            version_count: version + 1,
+            tags: manifest.tags,
+        };
+        let mut writer = ChunkWriter::new(writer, chunk_size);
+        writer.write_header(version, root_hash, &exported_manifest, &root)?;
+
+        let mut node_count = 0_u64;
+        let mut stack = vec![];
+        if let Root::Filled {
+            node: Node::Internal(node),
+            ..
+        } = root
+        {
+            stack.push((Nibbles::EMPTY, node));
+        }
+        while let Some((nibbles, node)) = stack.pop() {
+            let child_keys: Vec<_> = node
+                .children()
+                .map(|(nibble, child_ref)| {
+                    let child_nibbles = nibbles.push(nibble).ok_or(
+                        TreeTransferError::Malformed("internal node at terminal tree level"),
+                    )?;
+                    Ok((
+                        child_nibbles.with_version(child_ref.version),
+                        child_ref.is_leaf,
+                    ))
+                })
+                .collect::<Result<_, TreeTransferError>>()?;
+            let children = self.db.tree_nodes(&child_keys);
+
+            for ((child_key, _), child) in child_keys.into_iter().zip(children) {
+                let child = child.ok_or(TreeTransferError::MissingNode(child_key))?;
+                writer.write_node(child_key, &child)?;
+                node_count += 1;
+                if let Node::Internal(child) = child {
+                    stack.push((child_key.nibbles, child));
+                }
+            }
+        }
+        writer.finish(node_count)?;
+
+        Ok(ExportedVersionInfo {
+            version,
+            root_hash,
+            node_count,
+        })
+    }
+}
+
+impl RocksDBWrapper {
+    /// Imports a tree version exported with [`MerkleTree::export_version()`]. The database must be empty.
+    /// After the import, the database contains a tree with the imported version as the only retained version
+    /// (i.e., the tree looks like it was pruned up to this version).
+    ///
+    /// The tree manifest is written last, so if the import fails midway, the database will not contain
+    /// a valid tree. The returned root hash is taken from the imported data; to check the imported tree,
+    /// use [`MerkleTree::verify_consistency()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database is not empty, or if the imported data is malformed or cannot be read.
+    ///
+    /// # Panics
+    ///
+    /// Panics on RocksDB I/O errors.
+    pub fn import_version(
+        &mut self,
+        mut reader: impl io::Read,
+    ) -> Result<ExportedVersionInfo, TreeTransferError> {
+        if self.try_manifest()?.is_some() {
+            return Err(TreeTransferError::NonEmptyDatabase);
+        }
+
+        let mut magic = [0_u8; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(TreeTransferError::InvalidMagic);
+        }
+        let mut format_version = [0_u8; 1];
+        reader.read_exact(&mut format_version)?;
+        if format_version[0] != FORMAT_VERSION {
+            return Err(TreeTransferError::UnsupportedFormat(format_version[0]));
+        }
+
+        let header = read_checked_payload(&mut reader, 0)?;
+        let mut header = PayloadReader(&header);
+        let version = header.read_u64()?;
+        let root_hash = ValueHash::from_slice(header.read_bytes(HASH_SIZE)?);
+        let raw_manifest = header.read_len_prefixed()?.to_vec();
+        let manifest = Manifest::deserialize(&raw_manifest)?;
+        if manifest.version_count != version + 1 {
+            return Err(TreeTransferError::Malformed(
+                "manifest doesn't correspond to exported version",
+            ));
+        }
+        let raw_root = header.read_len_prefixed()?.to_vec();
+        Root::deserialize(&raw_root)?;
+        header.ensure_empty()?;
+
+        let mut node_count = 0_u64;
+        for chunk_idx in 1_u64.. {
+            let chunk_node_count = read_u32(&mut reader)?;
+            let payload = read_checked_payload(&mut reader, chunk_idx)?;
+            let mut payload = PayloadReader(&payload);
+            if chunk_node_count == 0 {
+                let expected_node_count = payload.read_u64()?;
+                payload.ensure_empty()?;
+                if expected_node_count != node_count {
+                    return Err(TreeTransferError::Malformed("node count mismatch"));
+                }
+                break;
+            }
+
+            // Each node occupies at least 1 byte in the payload, so this check limits the allocation below.
+            if chunk_node_count as usize > payload.0.len() {
+                return Err(TreeTransferError::Malformed(
+                    "node count exceeds chunk payload length",
+                ));
+            }
+            let mut nodes = Vec::with_capacity(chunk_node_count as usize);
+            for _ in 0..chunk_node_count {
+                let key_len = payload.read_u8()?;
+                let raw_key = payload.read_bytes(key_len.into())?;
+                if !is_valid_node_key(raw_key) {
+                    return Err(TreeTransferError::Malformed("invalid node key"));
+                }
+                let key = NodeKey::from_db_key(raw_key);
+                if key.version > version {
+                    return Err(TreeTransferError::Malformed(
+                        "node version is greater than exported version",
+                    ));
+                }
+                let is_leaf = payload.read_u8()? == 1;
+                let raw_node = payload.read_len_prefixed()?;
+                Self::deserialize_node(raw_node, &key, is_leaf)?;
+                nodes.push((raw_key, raw_node));
+            }
+            payload.ensure_empty()?;
+            self.write_raw_nodes(nodes.into_iter());
+            node_count += u64::from(chunk_node_count);
+        }
+
+        self.write_raw_root_and_manifest(version, &raw_root, &raw_manifest);
+        Ok(ExportedVersionInfo {
+            version,
+            root_hash,
+            node_count,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::{
+        storage::PatchSet,
+        types::{Key, TreeEntry},
+    };
+
+    fn generate_entries(indexes: impl Iterator<Item = u64>) -> Vec<TreeEntry> {
+        indexes
+            .map(|i| TreeEntry::new(Key::from(i), i + 1, ValueHash::from_low_u64_be(i)))
+            .collect()
+    }
+
+    fn create_tree() -> MerkleTree<PatchSet> {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        for chunk in generate_entries(0..200).chunks(50) {
+            tree.extend(chunk.to_vec());
+        }
+        tree
+    }
+
+    #[test]
+    fn exporting_and_importing_tree_version() {
+        let tree = create_tree();
+        let mut exported = vec![];
+        let info = tree.export_version(2, &mut exported, 32).unwrap();
+        assert_eq!(info.version, 2);
+        assert_eq!(info.root_hash, tree.root_hash(2).unwrap());
+        assert!(info.node_count > 150, "{info:?}");
+
+        let dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+        let mut db = RocksDBWrapper::new(dir.path()).unwrap();
+        let import_info = db.import_version(exported.as_slice()).unwrap();
+        assert_eq!(import_info, info);
+
+        let mut imported_tree = MerkleTree::new(db);
+        assert_eq!(imported_tree.latest_version(), Some(2));
+        assert_eq!(imported_tree.first_retained_version(), Some(2));
+        assert_eq!(imported_tree.root_hash(2), Some(info.root_hash));
+        imported_tree.verify_consistency(2, true).unwrap();
+
+        let keys: Vec<_> = (0..200).map(Key::from).collect();
+        assert_eq!(
+            imported_tree.entries(2, &keys).unwrap(),
+            tree.entries(2, &keys).unwrap()
+        );
+
+        // Check that the imported tree can be extended and produces the same root hash as the original tree.
+        let new_entries = generate_entries(150..200);
+        let output = imported_tree.extend(new_entries);
+        assert_eq!(output.root_hash, tree.root_hash(3).unwrap());
+        imported_tree.verify_consistency(3, true).unwrap();
+    }
+
+    #[test]
+    fn exporting_empty_tree() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        tree.extend(vec![]);
+        let mut exported = vec![];
+        let info = tree.export_version(0, &mut exported, 32).unwrap();
+        assert_eq!(info.node_count, 0);
+
+        let dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+        let mut db = RocksDBWrapper::new(dir.path()).unwrap();
+        db.import_version(exported.as_slice()).unwrap();
+        let imported_tree = MerkleTree::new(db);
+        assert_eq!(imported_tree.root_hash(0), Some(info.root_hash));
+    }
+
+    #[test]
+    fn exporting_missing_version() {
+        let tree = create_tree();
+        let err = tree.export_version(4, io::sink(), 32).unwrap_err();
+        assert_matches!(err, TreeTransferError::MissingVersion(4));
+    }
+
+    #[test]
+    fn corrupted_data_is_rejected_on_import() {
+        let tree = create_tree();
+        let mut exported = vec![];
+        tree.export_version(3, &mut exported, 32).unwrap();
+
+        let mut corrupted = exported.clone();
+        let last_idx = corrupted.len() - 100;
+        corrupted[last_idx] ^= 1;
+        let dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+        let mut db = RocksDBWrapper::new(dir.path()).unwrap();
+        let err = db.import_version(corrupted.as_slice()).unwrap_err();
+        assert_matches!(err, TreeTransferError::ChecksumMismatch(_));
+        assert_eq!(db.try_manifest().unwrap(), None);
+
+        let truncated = &exported[..exported.len() - 1];
+        let err = db.import_version(truncated).unwrap_err();
+        assert_matches!(err, TreeTransferError::Io(_));
+        assert_eq!(db.try_manifest().unwrap(), None);
+
+        let mut wrong_magic = exported.clone();
+        wrong_magic[0] = b'Z';
+        let err = db.import_version(wrong_magic.as_slice()).unwrap_err();
+        assert_matches!(err, TreeTransferError::InvalidMagic);
+
+        let mut huge_header = exported[..MAGIC.len() + 1].to_vec();
+        huge_header.extend_from_slice(&u32::MAX.to_le_bytes());
+        let err = db.import_version(huge_header.as_slice()).unwrap_err();
+        assert_matches!(err, TreeTransferError::Malformed(_));
+        assert_eq!(db.try_manifest().unwrap(), None);
+    }
+
+    #[test]
+    fn importing_into_non_empty_database_is_rejected() {
+        let tree = create_tree();
+        let mut exported = vec![];
+        tree.export_version(3, &mut exported, 32).unwrap();
+
+        let dir = TempDir::new().expect("failed creating temporary dir for RocksDB");
+        let mut db = RocksDBWrapper::new(dir.path()).unwrap();
+        db.import_version(exported.as_slice()).unwrap();
+        let err = db.import_version(exported.as_slice()).unwrap_err();
+        assert_matches!(err, TreeTransferError::NonEmptyDatabase);
+    }
+}
@@ -0,0 +1,262 @@
+//! Structural statistics for the Merkle tree.
+
+use rayon::prelude::*;
+
+use crate::{
+    types::{InternalNode, Nibbles, Node, NodeKey, Root},
+    Database, HashTree, MerkleTree, NoVersionError,
+};
+
+/// Number of children of an internal tree node.
+const CHILD_COUNT: usize = 16;
+
+/// Structural statistics for a specific version of the tree obtained via [`MerkleTree::structure_stats()`].
+///
+/// Levels are measured in nibbles; the root node is at level 0.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TreeStructureStats {
+    /// Number of internal nodes by level.
+    pub internal_nodes_by_level: Vec<u64>,
+    /// Number of leaves by level.
+    pub leaves_by_level: Vec<u64>,
+    /// Number of leaves by the first nibble of their key, i.e., in each of the subtrees rooted at a child
+    /// of the root node.
+    pub leaves_by_first_nibble: [u64; CHILD_COUNT],
+}
+
+impl TreeStructureStats {
+    /// Returns the total number of internal nodes.
+    pub fn internal_node_count(&self) -> u64 {
+        self.internal_nodes_by_level.iter().sum()
+    }
+
+    /// Returns the total number of leaves.
+    pub fn leaf_count(&self) -> u64 {
+        self.leaves_by_level.iter().sum()
+    }
+
+    /// Returns the average leaf level. This is equal to the average number of internal nodes that need
+    /// to be loaded to create a proof for an existing entry. Returns 0 for an empty tree.
+    #[allow(clippy::cast_precision_loss)] // acceptable for statistics
+    pub fn avg_leaf_level(&self) -> f64 {
+        let leaf_count = self.leaf_count();
+        if leaf_count == 0 {
+            return 0.0;
+        }
+        let level_sum: u64 = self
+            .leaves_by_level
+            .iter()
+            .zip(0_u64..)
+            .map(|(&count, level)| count * level)
+            .sum();
+        level_sum as f64 / leaf_count as f64
+    }
+
+    /// Returns the ratio of the maximum number of leaves in a subtree rooted at a child of the root node
+    /// to the average number of leaves in such a subtree. The ratio is close to 1 if leaf keys
+    /// are distributed uniformly, and is up to 16 if all keys share the first nibble. Returns 0 for an empty tree.
+    #[allow(clippy::cast_precision_loss)] // acceptable for statistics
+    pub fn leaf_distribution_skew(&self) -> f64 {
+        let leaf_count = self.leaf_count();
+        if leaf_count == 0 {
+            return 0.0;
+        }
+        let max_count = self
+            .leaves_by_first_nibble
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0);
+        max_count as f64 * CHILD_COUNT as f64 / leaf_count as f64
+    }
+
+    fn record_internal_node(&mut self, level: usize) {
+        Self::increment(&mut self.internal_nodes_by_level, level);
+    }
+
+    fn record_leaf(&mut self, level: usize, first_nibble: u8) {
+        Self::increment(&mut self.leaves_by_level, level);
+        self.leaves_by_first_nibble[usize::from(first_nibble)] += 1;
+    }
+
+    fn increment(counts: &mut Vec<u64>, level: usize) {
+        if counts.len() <= level {
+            counts.resize(level + 1, 0);
+        }
+        counts[level] += 1;
+    }
+
+    fn add_counts(counts: &mut Vec<u64>, other: &[u64]) {
+        if counts.len() < other.len() {
+            counts.resize(other.len(), 0);
+        }
+        for (count, &other_count) in counts.iter_mut().zip(other) {
+            *count += other_count;
+        }
+    }
+
+    fn merge(mut self, other: Self) -> Self {
+        Self::add_counts(
+            &mut self.internal_nodes_by_level,
+            &other.internal_nodes_by_level,
+        );
+        Self::add_counts(&mut self.leaves_by_level, &other.leaves_by_level);
+        for (count, other_count) in self
+            .leaves_by_first_nibble
+            .iter_mut()
+            .zip(other.leaves_by_first_nibble)
+        {
+            *count += other_count;
+        }
+        self
+    }
+}
+
+impl<DB: Database, H: HashTree> MerkleTree<DB, H> {
+    /// Walks the specified tree version and collects its [structural statistics](TreeStructureStats).
+    ///
+    /// This is an expensive operation that loads all nodes of the tree version from the database;
+    /// it should only be used in low-priority background tasks.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the tree `version` is missing.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a tree node is missing from the database or cannot be deserialized.
+    pub fn structure_stats(&self, version: u64) -> Result<TreeStructureStats, NoVersionError> {
+        let root = self.db.root(version).ok_or_else(|| {
+            let manifest = self.db.manifest().unwrap_or_default();
+            NoVersionError {
+                missing_version: version,
+                version_count: manifest.version_count,
+            }
+        })?;
+
+        let mut stats = TreeStructureStats::default();
+        match root {
+            Root::Empty => {}
+            Root::Filled {
+                node: Node::Leaf(leaf),
+                ..
+            } => stats.record_leaf(0, Nibbles::nibble(&leaf.full_key, 0)),
+            Root::Filled {
+                node: Node::Internal(node),
+                ..
+            } => {
+                stats.record_internal_node(0);
+                // Subtrees of the root node are walked in parallel; each subtree is walked depth-first
+                // in order to not keep much in memory.
+                let children = self.load_children(&node, Nibbles::EMPTY);
+                let subtree_stats = children
+                    .into_par_iter()
+                    .map(|(first_nibble, key, child)| {
+                        let mut stats = TreeStructureStats::default();
+                        self.walk_subtree(&child, key, first_nibble, &mut stats);
+                        stats
+                    })
+                    .reduce(TreeStructureStats::default, TreeStructureStats::merge);
+                stats = stats.merge(subtree_stats);
+            }
+        }
+        Ok(stats)
+    }
+
+    fn walk_subtree(
+        &self,
+        node: &Node,
+        key: NodeKey,
+        first_nibble: u8,
+        stats: &mut TreeStructureStats,
+    ) {
+        let level = key.nibbles.nibble_count();
+        match node {
+            Node::Leaf(_) => stats.record_leaf(level, first_nibble),
+            Node::Internal(node) => {
+                stats.record_internal_node(level);
+                for (_, child_key, child) in self.load_children(node, key.nibbles) {
+                    // Recursion is OK since the tree isn't that deep.
+                    self.walk_subtree(&child, child_key, first_nibble, stats);
+                }
+            }
+        }
+    }
+
+    /// Loads children of an internal node using a single DB query. Returns nibbles, keys and contents
+    /// of the children.
+    fn load_children(&self, node: &InternalNode, nibbles: Nibbles) -> Vec<(u8, NodeKey, Node)> {
+        let child_keys: Vec<_> = node
+            .children()
+            .map(|(nibble, child_ref)| {
+                let child_nibbles = nibbles
+                    .push(nibble)
+                    .unwrap_or_else(|| panic!("internal node at terminal tree level {nibbles}"));
+                (
+                    child_nibbles.with_version(child_ref.version),
+                    child_ref.is_leaf,
+                )
+            })
+            .collect();
+        let children = self.db.tree_nodes(&child_keys);
+        let nibbles = node.children().map(|(nibble, _)| nibble);
+        nibbles
+            .zip(child_keys)
+            .zip(children)
+            .map(|((nibble, (key, is_leaf)), child)| {
+                let child = child.unwrap_or_else(|| {
+                    let node_str = if is_leaf { "leaf" } else { "internal node" };
+                    panic!("missing {node_str} at {key}")
+                });
+                (nibble, key, child)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{types::TreeEntry, Key, PatchSet, ValueHash};
+
+    #[test]
+    fn structure_stats_for_empty_tree() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        tree.extend(vec![]);
+        let stats = tree.structure_stats(0).unwrap();
+        assert_eq!(stats, TreeStructureStats::default());
+        assert!(stats.avg_leaf_level().abs() < 1e-9);
+        assert!(stats.leaf_distribution_skew().abs() < 1e-9);
+
+        let err = tree.structure_stats(1).unwrap_err();
+        assert_eq!(err.missing_version, 1);
+    }
+
+    #[test]
+    fn structure_stats_basics() {
+        let mut tree = MerkleTree::new(PatchSet::default());
+        // The first two keys start with nibble 0 and differ only in the last nibble; the third key starts with nibble 1.
+        let keys = [Key::from(1_u64), Key::from(2_u64), Key::from(1_u64) << 252];
+        let entries = keys
+            .iter()
+            .zip(1..)
+            .map(|(&key, index)| TreeEntry::new(key, index, ValueHash::repeat_byte(1)));
+        tree.extend(entries.collect());
+
+        let stats = tree.structure_stats(0).unwrap();
+        assert_eq!(stats.leaf_count(), 3);
+        // The first two keys differ only in the last nibble, so their leaves are at the terminal level (64).
+        assert_eq!(stats.leaves_by_level.len(), 65);
+        assert_eq!(stats.leaves_by_level[1], 1);
+        assert_eq!(stats.leaves_by_level[64], 2);
+        assert_eq!(stats.internal_node_count(), 64);
+        assert!(stats
+            .internal_nodes_by_level
+            .iter()
+            .all(|&count| count == 1));
+        assert_eq!(stats.leaves_by_first_nibble[0], 2);
+        assert_eq!(stats.leaves_by_first_nibble[1], 1);
+        assert!((stats.avg_leaf_level() - 43.0).abs() < 1e-9);
+        assert!((stats.leaf_distribution_skew() - 32.0 / 3.0).abs() < 1e-9);
+    }
+}
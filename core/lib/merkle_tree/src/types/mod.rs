@@ -127,6 +127,47 @@ pub struct TreeEntryWithProof {
     pub merkle_path: Vec<ValueHash>,
 }
 
+/// Merkle multiproof for a set of entries in a Merkle tree. Compared to a set of [`TreeEntryWithProof`]s,
+/// a multiproof does not contain hashes that can be computed from the proven entries (e.g., hashes
+/// of common ancestors of several entries), and does not contain hashes of empty subtrees.
+///
+/// Multiproofs can be obtained via [`MerkleTree::entries_with_multiproof()`](crate::MerkleTree::entries_with_multiproof())
+/// and serialized into a compact binary form using [`Self::serialize()`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TreeMultiProof {
+    /// Proven entries ordered by key. Keys are unique. Entries for missing keys are [empty](TreeEntry::is_empty()).
+    pub entries: Vec<TreeEntry>,
+    /// For each hash of a subtree adjacent to the proven entries, in the order the hashes are consumed
+    /// during verification, specifies whether the subtree is empty.
+    pub(crate) empty_siblings: Vec<bool>,
+    /// Hashes of non-empty subtrees adjacent to the proven entries in the order they are consumed
+    /// during verification.
+    pub(crate) sibling_hashes: Vec<ValueHash>,
+}
+
+/// Merkle range proof for a contiguous range of keys in a Merkle tree. The proof contains all non-empty entries
+/// in the range, together with Merkle proofs for the boundary entries. As such, a range proof with no inner
+/// entries and empty boundary entries proves that the tree contains no entries in the range.
+///
+/// Range proofs can be obtained via [`MerkleTree::range_proof()`](crate::MerkleTree::range_proof()).
+#[derive(Debug, Clone)]
+pub struct TreeRangeProof {
+    /// Entry for the start of the range together with its Merkle proof.
+    pub start: TreeEntryWithProof,
+    /// Entry for the end of the range together with its Merkle proof. If the range consists of a single key,
+    /// this entry is equal to `start`.
+    pub end: TreeEntryWithProof,
+    /// Non-empty entries with keys strictly between the start and end keys, ordered by key.
+    pub entries: Vec<TreeEntry>,
+}
+
+impl TreeRangeProof {
+    /// Returns `true` if this proof proves that the tree contains no entries in the range.
+    pub fn is_empty(&self) -> bool {
+        self.start.base.is_empty() && self.end.base.is_empty() && self.entries.is_empty()
+    }
+}
+
 /// Output of inserting a block of entries into a Merkle tree.
 #[derive(Debug, PartialEq, Eq)]
 pub struct BlockOutput {
@@ -101,6 +101,80 @@ fn basic_workflow_multiblock() {
     assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(12));
 }
 
+#[test]
+fn pinned_tree_version_is_not_pruned() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let db = RocksDB::new(temp_dir.as_ref()).unwrap();
+    let mut tree = ZkSyncTree::new_lightweight(db.into());
+    for chunk in logs.chunks(20) {
+        tree.process_l1_batch(chunk);
+    }
+    tree.save();
+    let reader = tree.reader();
+    let (mut pruner, _handle) = tree.pruner();
+
+    let pinned = reader.pin_l1_batch(L1BatchNumber(2)).unwrap();
+    assert_eq!(pinned.l1_batch_number(), L1BatchNumber(2));
+    let keys: Vec<_> = logs[..40]
+        .iter()
+        .map(|instruction| instruction.key().hashed_key_u256())
+        .collect();
+    let expected_entries: Vec<_> = pinned
+        .entries_with_proofs(&keys)
+        .unwrap()
+        .into_iter()
+        .map(|entry| entry.base)
+        .collect();
+
+    let stats = pruner
+        .prune_up_to(pruner.last_prunable_version().unwrap())
+        .unwrap();
+    assert_eq!(stats.target_retained_version, 2);
+    assert!(reader.pin_l1_batch(L1BatchNumber(1)).is_err());
+
+    let entries = pinned.entries_with_proofs(&keys).unwrap();
+    for (entry, expected_entry) in entries.iter().zip(&expected_entries) {
+        assert_eq!(entry.base, *expected_entry);
+        entry.verify(&Blake2Hasher, pinned.root_hash());
+    }
+
+    drop(pinned);
+    let stats = pruner
+        .prune_up_to(pruner.last_prunable_version().unwrap())
+        .unwrap();
+    assert_eq!(stats.target_retained_version, 4);
+    let err = reader.pin_l1_batch(L1BatchNumber(2)).unwrap_err();
+    assert!(err.to_string().contains("pruned"), "{err}");
+    reader.pin_l1_batch(L1BatchNumber(4)).unwrap();
+}
+
+#[test]
+fn collecting_structure_stats_for_pinned_version() {
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let logs = gen_storage_logs();
+    let db = RocksDB::new(temp_dir.as_ref()).unwrap();
+    let mut tree = ZkSyncTree::new_lightweight(db.into());
+    tree.process_l1_batch(&logs[..50]);
+    tree.process_l1_batch(&logs[50..]);
+    tree.save();
+    let reader = tree.reader();
+
+    for (l1_batch_number, expected_leaf_count) in [(0, 50), (1, 100)] {
+        let pinned = reader.pin_l1_batch(L1BatchNumber(l1_batch_number)).unwrap();
+        let stats = pinned.structure_stats().unwrap();
+        assert_eq!(stats.leaf_count(), expected_leaf_count);
+        assert_eq!(
+            stats.leaves_by_first_nibble.iter().sum::<u64>(),
+            expected_leaf_count
+        );
+        assert_eq!(stats.internal_nodes_by_level[0], 1);
+        // Hashed keys are distributed uniformly, so leaves are located at the top levels of the tree.
+        assert!(stats.avg_leaf_level() > 1.0 && stats.avg_leaf_level() < 4.0);
+        assert!(stats.leaf_distribution_skew() >= 1.0);
+    }
+}
+
 #[test]
 fn tree_with_single_leaf_works_correctly() {
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
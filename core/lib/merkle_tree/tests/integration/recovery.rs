@@ -55,7 +55,7 @@ fn test_recovery_in_chunks(mut db: impl PruneDatabase, kind: RecoveryKind, chunk
         match kind {
             RecoveryKind::Linear => recovery.extend_linear(chunk.to_vec()),
             RecoveryKind::Random => recovery.extend_random(chunk.to_vec()),
-        }
+        };
         if i % 3 == 1 {
             recovery = MerkleTreeRecovery::new(&mut db, recovered_version);
             // ^ Simulate recovery interruption and restart
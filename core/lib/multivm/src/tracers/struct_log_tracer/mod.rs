@@ -0,0 +1,67 @@
+use std::sync::Arc;
+
+use once_cell::sync::OnceCell;
+use zksync_types::{
+    vm_trace::{StructLog, StructLogs},
+    Address,
+};
+
+use crate::glue::tracers::IntoOldVmTracer;
+
+pub mod vm_1_4_1;
+pub mod vm_1_4_2;
+pub mod vm_boojum_integration;
+pub mod vm_latest;
+pub mod vm_refunds_enhancement;
+pub mod vm_virtual_blocks;
+
+/// Tracer recording every VM step executed outside the bootloader, similar to the struct logger in Geth.
+///
+/// The number of recorded steps is limited; once the limit is reached, remaining steps are skipped
+/// and the output is marked as truncated.
+#[derive(Debug, Clone)]
+pub struct StructLogTracer {
+    logs: Vec<StructLog>,
+    max_steps: usize,
+    truncated: bool,
+    result: Arc<OnceCell<StructLogs>>,
+}
+
+impl StructLogTracer {
+    pub fn new(max_steps: usize, result: Arc<OnceCell<StructLogs>>) -> Self {
+        Self {
+            logs: vec![],
+            max_steps,
+            truncated: false,
+            result,
+        }
+    }
+
+    /// Records a single VM step. `depth` is the far call depth, with the bootloader frame having depth 0.
+    fn record_step(&mut self, pc: u16, op: String, gas: u32, depth: usize, address: Address) {
+        if depth == 0 {
+            return; // Bootloader steps are not a part of the transaction
+        }
+        if self.logs.len() >= self.max_steps {
+            self.truncated = true;
+            return;
+        }
+        self.logs.push(StructLog {
+            pc,
+            op,
+            gas,
+            depth,
+            address,
+        });
+    }
+
+    fn store_result(&mut self) {
+        let result = StructLogs {
+            logs: std::mem::take(&mut self.logs),
+            truncated: self.truncated,
+        };
+        self.result.set(result).ok();
+    }
+}
+
+impl IntoOldVmTracer for StructLogTracer {}
@@ -0,0 +1,38 @@
+use zk_evm_1_4_1::tracing::{BeforeExecutionData, VmLocalStateData};
+use zksync_state::{StoragePtr, WriteStorage};
+
+use crate::{
+    interface::{tracer::VmExecutionStopReason, traits::tracers::dyn_tracers::vm_1_4_1::DynTracer},
+    tracers::struct_log_tracer::StructLogTracer,
+    vm_1_4_1::{BootloaderState, HistoryMode, SimpleMemory, VmTracer, ZkSyncVmState},
+};
+
+impl<S, H: HistoryMode> DynTracer<S, SimpleMemory<H>> for StructLogTracer {
+    fn before_execution(
+        &mut self,
+        state: VmLocalStateData<'_>,
+        data: BeforeExecutionData,
+        _memory: &SimpleMemory<H>,
+        _storage: StoragePtr<S>,
+    ) {
+        let callstack = &state.vm_local_state.callstack;
+        self.record_step(
+            callstack.current.pc,
+            format!("{:?}", data.opcode.variant.opcode),
+            callstack.current.ergs_remaining,
+            callstack.inner.len(),
+            callstack.current.this_address,
+        );
+    }
+}
+
+impl<S: WriteStorage, H: HistoryMode> VmTracer<S, H> for StructLogTracer {
+    fn after_vm_execution(
+        &mut self,
+        _state: &mut ZkSyncVmState<S, H>,
+        _bootloader_state: &BootloaderState,
+        _stop_reason: VmExecutionStopReason,
+    ) {
+        self.store_result();
+    }
+}
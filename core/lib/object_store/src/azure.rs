@@ -0,0 +1,373 @@
+//! Azure Blob Storage-based [`ObjectStore`] implementation using the Blob service REST API.
+
+use std::{env, fmt};
+
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use hmac::{Hmac, Mac};
+use reqwest::{header, Client, Method, RequestBuilder, StatusCode, Url};
+use sha2::Sha256;
+
+use crate::{
+    metrics::AZURE_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+    rest::{response_error, send_with_retries},
+};
+
+/// Version of the Blob service REST API used by the store.
+const API_VERSION: &str = "2021-08-06";
+
+/// Credentials used to access an Azure storage account.
+#[derive(Clone)]
+pub(crate) enum AzureCredentials {
+    /// Shared key of the storage account. Requests are signed using the [Shared Key] scheme.
+    ///
+    /// [Shared Key]: https://learn.microsoft.com/en-us/rest/api/storageservices/authorize-with-shared-key
+    SharedKey(Vec<u8>),
+    /// Shared access signature appended to the query of each request.
+    SasToken(String),
+    /// No credentials; only works for reading from containers with public access.
+    Anonymous,
+}
+
+impl fmt::Debug for AzureCredentials {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // Credentials are intentionally not output.
+        formatter.write_str(match self {
+            Self::SharedKey(_) => "SharedKey(_)",
+            Self::SasToken(_) => "SasToken(_)",
+            Self::Anonymous => "Anonymous",
+        })
+    }
+}
+
+impl AzureCredentials {
+    const SHARED_KEY_VAR: &'static str = "AZURE_STORAGE_KEY";
+    const SAS_TOKEN_VAR: &'static str = "AZURE_STORAGE_SAS_TOKEN";
+
+    /// Reads credentials from the conventional env variables used by Azure tooling.
+    pub fn from_env() -> anyhow::Result<Self> {
+        if let Ok(key) = env::var(Self::SHARED_KEY_VAR) {
+            let key = BASE64.decode(key.trim()).map_err(|err| {
+                anyhow::anyhow!(
+                    "{} is not a valid base64 string: {err}",
+                    Self::SHARED_KEY_VAR
+                )
+            })?;
+            Ok(Self::SharedKey(key))
+        } else if let Ok(token) = env::var(Self::SAS_TOKEN_VAR) {
+            Ok(Self::SasToken(
+                token.trim().trim_start_matches('?').to_owned(),
+            ))
+        } else {
+            Ok(Self::Anonymous)
+        }
+    }
+}
+
+/// Object store persisting objects as block blobs in an Azure Blob Storage container.
+/// Objects are stored as `{bucket}/{key}` blobs.
+pub(crate) struct AzureBlobStorage {
+    account_name: String,
+    container_url: Url,
+    credentials: AzureCredentials,
+    max_retries: u16,
+    client: Client,
+}
+
+impl fmt::Debug for AzureBlobStorage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("AzureBlobStorage")
+            .field("account_name", &self.account_name)
+            .field("container_url", &self.container_url.as_str())
+            .field("credentials", &self.credentials)
+            .field("max_retries", &self.max_retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl AzureBlobStorage {
+    pub fn new(
+        account_name: &str,
+        container_name: &str,
+        endpoint: Option<&str>,
+        credentials: AzureCredentials,
+        max_retries: u16,
+    ) -> anyhow::Result<Self> {
+        let endpoint = endpoint.map_or_else(
+            || format!("https://{account_name}.blob.core.windows.net"),
+            |endpoint| endpoint.trim_end_matches('/').to_owned(),
+        );
+        let container_url = format!("{endpoint}/{container_name}");
+        let container_url = Url::parse(&container_url).map_err(|err| {
+            anyhow::anyhow!("invalid Azure container URL `{container_url}`: {err}")
+        })?;
+        Ok(Self {
+            account_name: account_name.to_owned(),
+            container_url,
+            credentials,
+            max_retries,
+            client: Client::new(),
+        })
+    }
+
+    fn blob_url(&self, bucket: Bucket, key: &str) -> Url {
+        let mut url = self.container_url.clone();
+        url.path_segments_mut()
+            .expect("container URL cannot be a base")
+            .push(bucket.as_str())
+            .extend(key.split('/'));
+        url
+    }
+
+    /// Computes the `Authorization` header value for the Shared Key scheme.
+    fn shared_key_signature(
+        &self,
+        key: &[u8],
+        method: &Method,
+        url: &Url,
+        content_length: usize,
+        ms_headers: &[(&str, &str)],
+    ) -> String {
+        // Empty content length must be represented by an empty string.
+        let content_length = if content_length == 0 {
+            String::new()
+        } else {
+            content_length.to_string()
+        };
+        let mut string_to_sign = format!(
+            // Method, Content-Encoding, Content-Language, Content-Length, Content-MD5, Content-Type, Date,
+            // If-Modified-Since, If-Match, If-None-Match, If-Unmodified-Since, Range
+            "{method}\n\n\n{content_length}\n\n\n\n\n\n\n\n\n"
+        );
+        let mut ms_headers = ms_headers.to_vec();
+        ms_headers.sort_unstable();
+        for (name, value) in ms_headers {
+            string_to_sign += &format!("{name}:{value}\n");
+        }
+        string_to_sign += &format!("/{}{}", self.account_name, url.path());
+        let mut query_pairs: Vec<_> = url
+            .query_pairs()
+            .map(|(name, value)| (name.to_lowercase(), value.into_owned()))
+            .collect();
+        query_pairs.sort_unstable();
+        for (name, value) in query_pairs {
+            string_to_sign += &format!("\n{name}:{value}");
+        }
+
+        let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC accepts keys of any size");
+        mac.update(string_to_sign.as_bytes());
+        let signature = BASE64.encode(mac.finalize().into_bytes());
+        format!("SharedKey {}:{signature}", self.account_name)
+    }
+
+    fn request(&self, method: Method, mut url: Url, body: Option<Vec<u8>>) -> RequestBuilder {
+        let date = chrono::Utc::now()
+            .format("%a, %d %b %Y %H:%M:%S GMT")
+            .to_string();
+        let is_put = method == Method::PUT;
+        let mut ms_headers = vec![("x-ms-date", date.as_str()), ("x-ms-version", API_VERSION)];
+        if is_put {
+            ms_headers.push(("x-ms-blob-type", "BlockBlob"));
+        }
+
+        let authorization = match &self.credentials {
+            AzureCredentials::SharedKey(key) => {
+                let content_length = body.as_ref().map_or(0, Vec::len);
+                Some(self.shared_key_signature(key, &method, &url, content_length, &ms_headers))
+            }
+            AzureCredentials::SasToken(token) => {
+                url.set_query(Some(token));
+                None
+            }
+            AzureCredentials::Anonymous => None,
+        };
+
+        let mut request = self.client.request(method, url);
+        for (name, value) in ms_headers {
+            request = request.header(name, value);
+        }
+        if let Some(authorization) = authorization {
+            request = request.header(header::AUTHORIZATION, authorization);
+        }
+        if let Some(body) = body {
+            request = request.body(body);
+        } else if is_put {
+            request = request.header(header::CONTENT_LENGTH, 0);
+        }
+        request
+    }
+}
+
+#[async_trait]
+impl ObjectStore for AzureBlobStorage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = AZURE_METRICS.start_fetch(bucket);
+        let url = self.blob_url(bucket, key);
+        let description = format!("fetching blob `{url}`");
+        let response = send_with_retries(self.max_retries, &description, || {
+            self.request(Method::GET, url.clone(), None)
+        })
+        .await?;
+
+        if response.status() == StatusCode::NOT_FOUND {
+            let err = format!("blob `{url}` is not found");
+            return Err(ObjectStoreError::KeyNotFound(err.into()));
+        }
+        if !response.status().is_success() {
+            return Err(response_error(response));
+        }
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        fetch_latency.observe();
+        Ok(bytes.to_vec())
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = AZURE_METRICS.start_store(bucket);
+        let url = self.blob_url(bucket, key);
+        let description = format!("storing blob `{url}`");
+        let response = send_with_retries(self.max_retries, &description, || {
+            self.request(Method::PUT, url.clone(), Some(value.clone()))
+        })
+        .await?;
+
+        if !response.status().is_success() {
+            return Err(response_error(response));
+        }
+        store_latency.observe();
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        let url = self.blob_url(bucket, key);
+        let description = format!("removing blob `{url}`");
+        let response = send_with_retries(self.max_retries, &description, || {
+            self.request(Method::DELETE, url.clone(), None)
+        })
+        .await?;
+
+        // Removing a non-existing blob is not an error.
+        if response.status().is_success() || response.status() == StatusCode::NOT_FOUND {
+            Ok(())
+        } else {
+            Err(response_error(response))
+        }
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!("{}/{bucket}", self.container_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Well-known account used by the Azurite emulator.
+    const AZURITE_ACCOUNT: &str = "devstoreaccount1";
+    const AZURITE_KEY: &str =
+        "Eby8vdM02xNOcqFlqUwJPLlmEtlCDXJ1OUzFT50uSRZ6IFsuFq2UVErCz4I6tq/K1SZFPTOtr/KBHBeksoGMGw==";
+
+    fn azurite_store() -> AzureBlobStorage {
+        let endpoint = env::var("AZURITE_BLOB_ENDPOINT")
+            .unwrap_or_else(|_| format!("http://127.0.0.1:10000/{AZURITE_ACCOUNT}"));
+        let key = BASE64.decode(AZURITE_KEY).unwrap();
+        AzureBlobStorage::new(
+            AZURITE_ACCOUNT,
+            "zksync-test",
+            Some(&endpoint),
+            AzureCredentials::SharedKey(key),
+            1,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn building_blob_urls() {
+        let store =
+            AzureBlobStorage::new("zksync", "artifacts", None, AzureCredentials::Anonymous, 3)
+                .unwrap();
+        assert_eq!(
+            store
+                .blob_url(Bucket::StorageSnapshot, "snapshot.proto.gzip")
+                .as_str(),
+            "https://zksync.blob.core.windows.net/artifacts/storage_logs_snapshots/snapshot.proto.gzip"
+        );
+        assert_eq!(
+            store.storage_prefix_raw(Bucket::StorageSnapshot),
+            "https://zksync.blob.core.windows.net/artifacts/storage_logs_snapshots"
+        );
+
+        let store = azurite_store();
+        assert_eq!(
+            store.blob_url(Bucket::ProverJobsFri, "1/2.bin").as_str(),
+            "http://127.0.0.1:10000/devstoreaccount1/zksync-test/prover_jobs_fri/1/2.bin"
+        );
+    }
+
+    #[test]
+    fn shared_key_signature() {
+        let store = azurite_store();
+        let key = BASE64.decode(AZURITE_KEY).unwrap();
+        let url = store.blob_url(Bucket::ProverJobsFri, "test.bin");
+        let ms_headers = [
+            ("x-ms-version", API_VERSION),
+            ("x-ms-date", "Mon, 27 May 2024 12:00:00 GMT"),
+        ];
+        let signature = store.shared_key_signature(&key, &Method::GET, &url, 0, &ms_headers);
+
+        let string_to_sign = "GET\n\n\n\n\n\n\n\n\n\n\n\n\
+            x-ms-date:Mon, 27 May 2024 12:00:00 GMT\n\
+            x-ms-version:2021-08-06\n\
+            /devstoreaccount1/devstoreaccount1/zksync-test/prover_jobs_fri/test.bin";
+        let mut mac = Hmac::<Sha256>::new_from_slice(&key).unwrap();
+        mac.update(string_to_sign.as_bytes());
+        let expected = BASE64.encode(mac.finalize().into_bytes());
+        assert_eq!(signature, format!("SharedKey devstoreaccount1:{expected}"));
+    }
+
+    /// Integration test against the Azurite emulator (e.g., run via `docker run -p 10000:10000
+    /// mcr.microsoft.com/azure-storage/azurite azurite-blob --blobHost 0.0.0.0`).
+    #[tokio::test]
+    #[ignore = "requires Azurite emulator"]
+    async fn azurite_roundtrip() {
+        let store = azurite_store();
+        // Create the container if it doesn't exist.
+        let mut container_url = store.container_url.clone();
+        container_url.set_query(Some("restype=container"));
+        let response = store
+            .request(Method::PUT, container_url, None)
+            .send()
+            .await
+            .unwrap();
+        assert!(
+            response.status().is_success() || response.status() == StatusCode::CONFLICT,
+            "{response:?}"
+        );
+
+        let bucket = Bucket::ProverJobsFri;
+        store.remove_raw(bucket, "test.bin").await.unwrap();
+        let err = store.get_raw(bucket, "test.bin").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+
+        store
+            .put_raw(bucket, "test.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let value = store.get_raw(bucket, "test.bin").await.unwrap();
+        assert_eq!(value, [1, 2, 3]);
+
+        store.remove_raw(bucket, "test.bin").await.unwrap();
+        let err = store.get_raw(bucket, "test.bin").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+}
@@ -0,0 +1,442 @@
+//! Integrity envelope and client-side encryption for stored objects.
+//!
+//! Objects are wrapped into an envelope with the following layout:
+//!
+//! - Magic bytes `b"ZKOE"`
+//! - Envelope format version (1 byte; currently, 1)
+//! - Length of the encryption key ID (1 byte; 0 if the object isn't encrypted)
+//! - UTF-8 encryption key ID
+//! - AES-256-GCM nonce (12 bytes; only if the object is encrypted)
+//! - Object bytes, or AES-256-GCM ciphertext including the authentication tag
+//! - SHA-256 checksum of all preceding bytes (32 bytes)
+//!
+//! Encryption authenticates the envelope header together with the object location (bucket and key),
+//! so that encrypted objects cannot be swapped with each other unnoticed.
+//!
+//! Envelope errors (checksum mismatches, truncated envelopes, decryption failures etc.) are reported
+//! as [`ObjectStoreError::Corrupted`] since retrying the read will not fix them.
+
+use std::{fmt, fs, str};
+
+use aes_gcm::{
+    aead::{Aead, KeyInit, Payload},
+    Aes256Gcm, Nonce,
+};
+use anyhow::Context as _;
+use async_trait::async_trait;
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use sha2::{Digest, Sha256};
+
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+
+const MAGIC: &[u8; 4] = b"ZKOE";
+const VERSION: u8 = 1;
+const NONCE_LEN: usize = 12;
+const CHECKSUM_LEN: usize = 32;
+const KEY_LEN: usize = 32;
+
+struct EncryptionKey {
+    id: String,
+    cipher: Aes256Gcm,
+}
+
+/// AES-256-GCM keys used for client-side encryption of stored objects. The first key is used to encrypt
+/// new objects; all keys can be used to decrypt existing ones.
+pub(crate) struct EncryptionKeys {
+    keys: Vec<EncryptionKey>,
+}
+
+impl fmt::Debug for EncryptionKeys {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let ids = self.keys.iter().map(|key| &key.id);
+        formatter.debug_list().entries(ids).finish()
+    }
+}
+
+impl EncryptionKeys {
+    /// Loads keys from a file with `{key_id}:{base64_key}` lines. Empty lines and lines starting with `#` are skipped.
+    pub fn from_file(path: &str) -> anyhow::Result<Self> {
+        let contents = fs::read_to_string(path)
+            .with_context(|| format!("failed reading encryption keys from `{path}`"))?;
+        Self::parse(&contents).with_context(|| format!("invalid encryption keys file `{path}`"))
+    }
+
+    fn parse(contents: &str) -> anyhow::Result<Self> {
+        let lines = contents
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('#'));
+        let mut keys = Vec::<EncryptionKey>::new();
+        for (i, line) in lines.enumerate() {
+            let (id, key) = line.split_once(':').with_context(|| {
+                format!("key #{i} is not in the `{{key_id}}:{{base64_key}}` format")
+            })?;
+            let id = id.trim();
+            anyhow::ensure!(
+                !id.is_empty() && id.len() <= usize::from(u8::MAX),
+                "ID of key #{i} must have length 1..=255"
+            );
+            anyhow::ensure!(
+                keys.iter().all(|existing| existing.id != id),
+                "key ID `{id}` is not unique"
+            );
+            let key = BASE64
+                .decode(key.trim())
+                .with_context(|| format!("key `{id}` is not a valid base64 string"))?;
+            anyhow::ensure!(
+                key.len() == KEY_LEN,
+                "key `{id}` has length {} bytes; expected {KEY_LEN} bytes",
+                key.len()
+            );
+            let cipher = Aes256Gcm::new_from_slice(&key).expect("key length checked above");
+            keys.push(EncryptionKey {
+                id: id.to_owned(),
+                cipher,
+            });
+        }
+        anyhow::ensure!(!keys.is_empty(), "no keys specified");
+        Ok(Self { keys })
+    }
+
+    fn current(&self) -> &EncryptionKey {
+        &self.keys[0]
+    }
+
+    fn get(&self, id: &str) -> Option<&EncryptionKey> {
+        self.keys.iter().find(|key| key.id == id)
+    }
+}
+
+fn envelope_error(bucket: Bucket, key: &str, message: impl fmt::Display) -> ObjectStoreError {
+    let message = format!("invalid envelope for object `{key}` in bucket `{bucket}`: {message}");
+    ObjectStoreError::Corrupted(message.into())
+}
+
+fn associated_data(header: &[u8], bucket: Bucket, key: &str) -> Vec<u8> {
+    let mut data = header.to_vec();
+    data.extend_from_slice(bucket.as_str().as_bytes());
+    data.push(b'/');
+    data.extend_from_slice(key.as_bytes());
+    data
+}
+
+/// [`ObjectStore`] wrapping objects into an integrity envelope (and optionally encrypting them) before
+/// passing them to the underlying store.
+///
+/// Objects not wrapped into an envelope (e.g., ones stored before the envelope was enabled) are returned as is
+/// if encryption is disabled, so that the integrity envelope can be enabled for existing stores. If encryption
+/// is enabled, such objects are rejected unless plaintext reads are explicitly allowed
+/// (see [`Self::with_plaintext_reads()`]).
+#[derive(Debug)]
+pub(crate) struct EnvelopeObjectStore<S> {
+    inner: S,
+    keys: Option<EncryptionKeys>,
+    allow_plaintext_reads: bool,
+}
+
+impl<S: ObjectStore> EnvelopeObjectStore<S> {
+    pub fn new(inner: S, keys: Option<EncryptionKeys>) -> Self {
+        Self {
+            inner,
+            keys,
+            allow_plaintext_reads: false,
+        }
+    }
+
+    /// Allows reading objects not wrapped into an envelope even if encryption is enabled. Intended to be used
+    /// only while migrating an existing store to encryption.
+    pub fn with_plaintext_reads(mut self, allow: bool) -> Self {
+        self.allow_plaintext_reads = allow;
+        self
+    }
+
+    fn seal(&self, bucket: Bucket, key: &str, value: &[u8]) -> Result<Vec<u8>, ObjectStoreError> {
+        let mut envelope = Vec::with_capacity(value.len() + 64);
+        envelope.extend_from_slice(MAGIC);
+        envelope.push(VERSION);
+        if let Some(keys) = &self.keys {
+            let encryption_key = keys.current();
+            let key_id_len = u8::try_from(encryption_key.id.len()).expect("checked when parsing");
+            envelope.push(key_id_len);
+            envelope.extend_from_slice(encryption_key.id.as_bytes());
+            let nonce: [u8; NONCE_LEN] = rand::random();
+            envelope.extend_from_slice(&nonce);
+
+            let payload = Payload {
+                msg: value,
+                aad: &associated_data(&envelope, bucket, key),
+            };
+            let ciphertext = encryption_key
+                .cipher
+                .encrypt(Nonce::from_slice(&nonce), payload)
+                .map_err(|_| envelope_error(bucket, key, "encryption failed"))?;
+            envelope.extend_from_slice(&ciphertext);
+        } else {
+            envelope.push(0);
+            envelope.extend_from_slice(value);
+        }
+
+        let checksum = Sha256::digest(&envelope);
+        envelope.extend_from_slice(&checksum);
+        Ok(envelope)
+    }
+
+    fn open(&self, bucket: Bucket, key: &str, bytes: Vec<u8>) -> Result<Vec<u8>, ObjectStoreError> {
+        if !bytes.starts_with(MAGIC) {
+            if self.keys.is_some() && !self.allow_plaintext_reads {
+                return Err(envelope_error(
+                    bucket,
+                    key,
+                    "object is not encrypted, and plaintext reads are not allowed",
+                ));
+            }
+            tracing::trace!("Object `{key}` in bucket `{bucket}` is not wrapped in an envelope");
+            return Ok(bytes);
+        }
+
+        let body_len = bytes
+            .len()
+            .checked_sub(CHECKSUM_LEN)
+            .filter(|&len| len >= MAGIC.len())
+            .ok_or_else(|| envelope_error(bucket, key, "envelope is truncated"))?;
+        let (body, checksum) = bytes.split_at(body_len);
+        if Sha256::digest(body).as_slice() != checksum {
+            return Err(envelope_error(bucket, key, "checksum mismatch"));
+        }
+
+        let truncated = || envelope_error(bucket, key, "envelope is truncated");
+        let (&version, rest) = body[MAGIC.len()..].split_first().ok_or_else(truncated)?;
+        if version != VERSION {
+            return Err(envelope_error(
+                bucket,
+                key,
+                format_args!("unsupported version {version}"),
+            ));
+        }
+        let (&key_id_len, rest) = rest.split_first().ok_or_else(truncated)?;
+        let key_id_len = usize::from(key_id_len);
+        if key_id_len == 0 {
+            return Ok(rest.to_vec());
+        }
+        if rest.len() < key_id_len + NONCE_LEN {
+            return Err(truncated());
+        }
+        let (key_id, rest) = rest.split_at(key_id_len);
+        let (nonce, ciphertext) = rest.split_at(NONCE_LEN);
+        let header = &body[..body.len() - ciphertext.len()];
+
+        let key_id = str::from_utf8(key_id)
+            .map_err(|_| envelope_error(bucket, key, "encryption key ID is not UTF-8"))?;
+        let keys = self.keys.as_ref().ok_or_else(|| {
+            envelope_error(
+                bucket,
+                key,
+                "object is encrypted, but no keys are configured",
+            )
+        })?;
+        let encryption_key = keys.get(key_id).ok_or_else(|| {
+            envelope_error(
+                bucket,
+                key,
+                format_args!("unknown encryption key `{key_id}`"),
+            )
+        })?;
+        let payload = Payload {
+            msg: ciphertext,
+            aad: &associated_data(header, bucket, key),
+        };
+        encryption_key
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), payload)
+            .map_err(|_| envelope_error(bucket, key, "decryption failed"))
+    }
+}
+
+#[async_trait]
+impl<S: ObjectStore> ObjectStore for EnvelopeObjectStore<S> {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let bytes = self.inner.get_raw(bucket, key).await?;
+        self.open(bucket, key, bytes)
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let envelope = self.seal(bucket, key, &value)?;
+        self.inner.put_raw(bucket, key, envelope).await
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        self.inner.remove_raw(bucket, key).await
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        self.inner.storage_prefix_raw(bucket)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockStore;
+
+    const KEYS: &str = "
+        # Current key
+        new:AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=
+        old:ICEiIyQlJicoKSorLC0uLzAxMjM0NTY3ODk6Ozw9Pj8=
+    ";
+
+    fn keys(contents: &str) -> Option<EncryptionKeys> {
+        Some(EncryptionKeys::parse(contents).unwrap())
+    }
+
+    #[test]
+    fn parsing_keys() {
+        let keys = EncryptionKeys::parse(KEYS).unwrap();
+        assert_eq!(format!("{keys:?}"), r#"["new", "old"]"#);
+        assert_eq!(keys.current().id, "new");
+        assert!(keys.get("old").is_some());
+
+        for invalid in ["", "no_separator", ":AQID", "short:AQID", "k:!!!"] {
+            EncryptionKeys::parse(invalid).unwrap_err();
+        }
+        let duplicate = "k:AQIDBAUGBwgJCgsMDQ4PEBESExQVFhcYGRobHB0eHyA=\n".repeat(2);
+        EncryptionKeys::parse(&duplicate).unwrap_err();
+    }
+
+    #[tokio::test]
+    async fn integrity_envelope_roundtrip() {
+        let store = EnvelopeObjectStore::new(MockStore::default(), None);
+        store
+            .put_raw(Bucket::ProverJobs, "test", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let raw = store
+            .inner
+            .get_raw(Bucket::ProverJobs, "test")
+            .await
+            .unwrap();
+        assert!(raw.starts_with(MAGIC));
+        assert_eq!(raw.len(), MAGIC.len() + 2 + 3 + CHECKSUM_LEN);
+
+        let value = store.get_raw(Bucket::ProverJobs, "test").await.unwrap();
+        assert_eq!(value, [1, 2, 3]);
+
+        let mut corrupted = raw;
+        corrupted[MAGIC.len() + 2] ^= 1;
+        store
+            .inner
+            .put_raw(Bucket::ProverJobs, "test", corrupted)
+            .await
+            .unwrap();
+        let err = store.get_raw(Bucket::ProverJobs, "test").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Corrupted(_)), "{err}");
+        assert!(err.to_string().contains("checksum mismatch"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn legacy_objects_are_read_as_is_without_encryption() {
+        let store = EnvelopeObjectStore::new(MockStore::default(), None);
+        store
+            .inner
+            .put_raw(Bucket::ProverJobs, "legacy", vec![4, 5])
+            .await
+            .unwrap();
+        let value = store.get_raw(Bucket::ProverJobs, "legacy").await.unwrap();
+        assert_eq!(value, [4, 5]);
+    }
+
+    #[tokio::test]
+    async fn plaintext_objects_are_rejected_with_encryption() {
+        let store = EnvelopeObjectStore::new(MockStore::default(), keys(KEYS));
+        store
+            .inner
+            .put_raw(Bucket::ProverJobs, "legacy", vec![4, 5])
+            .await
+            .unwrap();
+        let err = store
+            .get_raw(Bucket::ProverJobs, "legacy")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Corrupted(_)), "{err}");
+        assert!(err.to_string().contains("not encrypted"), "{err}");
+
+        let store = store.with_plaintext_reads(true);
+        let value = store.get_raw(Bucket::ProverJobs, "legacy").await.unwrap();
+        assert_eq!(value, [4, 5]);
+    }
+
+    #[tokio::test]
+    async fn encryption_roundtrip() {
+        let store = EnvelopeObjectStore::new(MockStore::default(), keys(KEYS));
+        let value = b"secret artifact".to_vec();
+        store
+            .put_raw(Bucket::ProverJobs, "test", value.clone())
+            .await
+            .unwrap();
+        let raw = store
+            .inner
+            .get_raw(Bucket::ProverJobs, "test")
+            .await
+            .unwrap();
+        assert!(!raw.windows(value.len()).any(|window| window == value));
+
+        let decrypted = store.get_raw(Bucket::ProverJobs, "test").await.unwrap();
+        assert_eq!(decrypted, value);
+
+        // Moving an object to another key must be detected.
+        store
+            .inner
+            .put_raw(Bucket::ProverJobs, "other", raw.clone())
+            .await
+            .unwrap();
+        let err = store
+            .get_raw(Bucket::ProverJobs, "other")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("decryption failed"), "{err}");
+
+        // Encrypted objects cannot be read without keys.
+        let plain_store = EnvelopeObjectStore::new(store.inner, None);
+        let err = plain_store
+            .get_raw(Bucket::ProverJobs, "test")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("no keys"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn key_rotation() {
+        let old_keys = "old:ICEiIyQlJicoKSorLC0uLzAxMjM0NTY3ODk6Ozw9Pj8=";
+        let old_store = EnvelopeObjectStore::new(MockStore::default(), keys(old_keys));
+        old_store
+            .put_raw(Bucket::ProverJobs, "test", vec![1; 100])
+            .await
+            .unwrap();
+
+        let store = EnvelopeObjectStore::new(old_store.inner, keys(KEYS));
+        let value = store.get_raw(Bucket::ProverJobs, "test").await.unwrap();
+        assert_eq!(value, [1; 100]);
+        store
+            .put_raw(Bucket::ProverJobs, "test", vec![2; 100])
+            .await
+            .unwrap();
+        let raw = store
+            .inner
+            .get_raw(Bucket::ProverJobs, "test")
+            .await
+            .unwrap();
+        assert_eq!(&raw[MAGIC.len() + 2..MAGIC.len() + 5], b"new");
+
+        // The old key is no longer sufficient to read the re-encrypted object.
+        let old_store = EnvelopeObjectStore::new(store.inner, keys(old_keys));
+        let err = old_store
+            .get_raw(Bucket::ProverJobs, "test")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown encryption key"), "{err}");
+    }
+}
@@ -32,8 +32,10 @@ impl FileBackedObjectStore {
             Bucket::NodeAggregationWitnessJobsFri,
             Bucket::SchedulerWitnessJobsFri,
             Bucket::ProofsFri,
+            Bucket::ProofUploads,
             Bucket::StorageSnapshot,
             Bucket::TeeVerifierInput,
+            Bucket::BlockDataArchive,
         ] {
             let bucket_path = format!("{base_dir}/{bucket}");
             fs::create_dir_all(&bucket_path)
@@ -3,6 +3,11 @@
 //!
 //! - File-based storage saving blobs as separate files in the local filesystem
 //! - GCS-based storage
+//! - Azure Blob Storage-based storage
+//! - Read-only storage fetching blobs from an HTTP mirror
+//!
+//! Stored blobs can optionally be wrapped into an integrity envelope with a checksum
+//! and encrypted on the client side (see [`ObjectStoreConfig`](zksync_config::ObjectStoreConfig)).
 //!
 //! These implementations are not exposed externally. Instead, a store trait object
 //! can be constructed using an [`ObjectStoreFactory`] based on the configuration.
@@ -23,12 +28,17 @@
     clippy::doc_markdown
 )]
 
+mod azure;
+mod envelope;
 mod file;
 mod gcs;
 mod metrics;
+mod mirror;
 mod mock;
 mod objects;
 mod raw;
+mod rest;
+mod s3;
 
 // Re-export `bincode` crate so that client binaries can conveniently use it.
 pub use bincode;
@@ -39,6 +49,6 @@ pub mod _reexports {
 }
 
 pub use self::{
-    objects::StoredObject,
+    objects::{serialized_object_checksum, StoredObject},
     raw::{Bucket, ObjectStore, ObjectStoreError, ObjectStoreFactory},
 };
@@ -29,3 +29,51 @@ impl GcsMetrics {
 
 #[vise::register]
 pub(crate) static GCS_METRICS: vise::Global<GcsMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store_azure")]
+pub(crate) struct AzureMetrics {
+    /// Latency to fetch an object from Azure Blob Storage.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    fetching_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency to store an object in Azure Blob Storage.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    storing_time: LabeledFamily<&'static str, Histogram<Duration>>,
+}
+
+impl AzureMetrics {
+    pub fn start_fetch(&self, bucket: Bucket) -> LatencyObserver<'_> {
+        self.fetching_time[&bucket.as_str()].start()
+    }
+
+    pub fn start_store(&self, bucket: Bucket) -> LatencyObserver<'_> {
+        self.storing_time[&bucket.as_str()].start()
+    }
+}
+
+#[vise::register]
+pub(crate) static AZURE_METRICS: vise::Global<AzureMetrics> = vise::Global::new();
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_object_store_s3")]
+pub(crate) struct S3Metrics {
+    /// Latency to fetch an object from an S3-compatible store.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    fetching_time: LabeledFamily<&'static str, Histogram<Duration>>,
+    /// Latency to store an object in an S3-compatible store.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["bucket"])]
+    storing_time: LabeledFamily<&'static str, Histogram<Duration>>,
+}
+
+impl S3Metrics {
+    pub fn start_fetch(&self, bucket: Bucket) -> LatencyObserver<'_> {
+        self.fetching_time[&bucket.as_str()].start()
+    }
+
+    pub fn start_store(&self, bucket: Bucket) -> LatencyObserver<'_> {
+        self.storing_time[&bucket.as_str()].start()
+    }
+}
+
+#[vise::register]
+pub(crate) static S3_METRICS: vise::Global<S3Metrics> = vise::Global::new();
@@ -0,0 +1,133 @@
+//! Read-only [`ObjectStore`] implementation fetching objects from an HTTP mirror.
+
+use std::{fmt, time::Duration};
+
+use async_trait::async_trait;
+use reqwest::{Client, StatusCode};
+
+use crate::raw::{Bucket, ObjectStore, ObjectStoreError};
+
+/// Object store fetching objects via HTTP(S) `GET` requests to `{base_url}/{bucket}/{key}`. This allows using
+/// static mirrors of other stores, e.g. public S3-compatible buckets or CDNs. Mutating operations are not supported.
+pub(crate) struct HttpMirrorObjectStore {
+    base_url: String,
+    max_retries: u16,
+    client: Client,
+}
+
+impl fmt::Debug for HttpMirrorObjectStore {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("HttpMirrorObjectStore")
+            .field("base_url", &self.base_url)
+            .field("max_retries", &self.max_retries)
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpMirrorObjectStore {
+    pub fn new(base_url: &str, max_retries: u16) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_owned(),
+            max_retries,
+            client: Client::new(),
+        }
+    }
+
+    fn object_url(&self, bucket: Bucket, key: &str) -> String {
+        format!("{}/{bucket}/{key}", self.base_url)
+    }
+
+    async fn get_once(&self, url: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            let err = format!("object at `{url}` is not found");
+            return Err(ObjectStoreError::KeyNotFound(err.into()));
+        }
+        let bytes = response
+            .error_for_status()
+            .map_err(|err| ObjectStoreError::Other(err.into()))?
+            .bytes()
+            .await
+            .map_err(|err| ObjectStoreError::Other(err.into()))?;
+        Ok(bytes.to_vec())
+    }
+}
+
+#[async_trait]
+impl ObjectStore for HttpMirrorObjectStore {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let url = self.object_url(bucket, key);
+        let mut retries = 0;
+        let mut backoff = Duration::from_secs(1);
+        loop {
+            match self.get_once(&url).await {
+                Err(ObjectStoreError::Other(err)) if retries < self.max_retries => {
+                    retries += 1;
+                    tracing::warn!(
+                        %err,
+                        "Failed fetching `{url}` {retries}/{}, retrying",
+                        self.max_retries
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn put_raw(
+        &self,
+        _bucket: Bucket,
+        _key: &str,
+        _value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        Err(ObjectStoreError::Other(
+            "HTTP mirror object store is read-only".into(),
+        ))
+    }
+
+    async fn remove_raw(&self, _bucket: Bucket, _key: &str) -> Result<(), ObjectStoreError> {
+        Err(ObjectStoreError::Other(
+            "HTTP mirror object store is read-only".into(),
+        ))
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!("{}/{bucket}", self.base_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn building_object_urls() {
+        let store = HttpMirrorObjectStore::new("https://mirror.example.com/snapshots/", 3);
+        assert_eq!(
+            store.object_url(Bucket::StorageSnapshot, "snapshot.proto.gzip"),
+            "https://mirror.example.com/snapshots/storage_logs_snapshots/snapshot.proto.gzip"
+        );
+        assert_eq!(
+            store.storage_prefix_raw(Bucket::StorageSnapshot),
+            "https://mirror.example.com/snapshots/storage_logs_snapshots"
+        );
+    }
+
+    #[tokio::test]
+    async fn mutating_operations_are_rejected() {
+        let store = HttpMirrorObjectStore::new("https://mirror.example.com", 3);
+        let err = store
+            .put_raw(Bucket::StorageSnapshot, "test", vec![1, 2, 3])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ObjectStoreError::Other(_)), "{err}");
+    }
+}
@@ -5,12 +5,13 @@ use std::io::{Read, Write};
 use anyhow::Context;
 use flate2::{read::GzDecoder, write::GzEncoder, Compression};
 use prost::Message;
+use sha2::{Digest, Sha256};
 use zksync_protobuf::{decode, ProtoFmt};
 use zksync_types::{
     snapshots::{
         SnapshotFactoryDependencies, SnapshotStorageLogsChunk, SnapshotStorageLogsStorageKey,
     },
-    L1BatchNumber,
+    L1BatchNumber, H256,
 };
 
 use crate::raw::{BoxedError, Bucket, ObjectStore, ObjectStoreError};
@@ -117,6 +118,12 @@ impl StoredObject for SnapshotStorageLogsChunk {
     }
 }
 
+/// Computes the SHA-256 checksum of a serialized object (i.e., of the bytes produced by [`StoredObject::serialize()`]).
+/// Can be used to verify objects fetched from untrusted stores against checksums obtained from a trusted source.
+pub fn serialized_object_checksum(bytes: &[u8]) -> H256 {
+    H256(Sha256::digest(bytes).into())
+}
+
 impl dyn ObjectStore + '_ {
     /// Fetches the value for the given key if it exists.
     ///
@@ -147,6 +154,23 @@ impl dyn ObjectStore + '_ {
         Ok(key)
     }
 
+    /// Same as [`Self::put()`], but additionally returns the [checksum](serialized_object_checksum()) of the stored value.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if serialization or the insertion / replacement operation fails.
+    pub async fn put_with_checksum<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        value: &V,
+    ) -> Result<(String, H256), ObjectStoreError> {
+        let key = V::encode_key(key);
+        let bytes = value.serialize().map_err(ObjectStoreError::Serialization)?;
+        let checksum = serialized_object_checksum(&bytes);
+        self.put_raw(V::BUCKET, &key, bytes).await?;
+        Ok((key, checksum))
+    }
+
     /// Removes a value associated with the key.
     ///
     /// # Errors
@@ -224,9 +248,18 @@ mod tests {
                 },
             ],
         };
-        store.put(key, &storage_logs).await.unwrap();
+        let (_, checksum) = store.put_with_checksum(key, &storage_logs).await.unwrap();
         let reconstructed_storage_logs = store.get(key).await.unwrap();
         assert_eq!(storage_logs, reconstructed_storage_logs);
+
+        let raw_storage_logs = store
+            .get_raw(
+                SnapshotStorageLogsChunk::BUCKET,
+                &SnapshotStorageLogsChunk::encode_key(key),
+            )
+            .await
+            .unwrap();
+        assert_eq!(serialized_object_checksum(&raw_storage_logs), checksum);
     }
 
     #[tokio::test]
@@ -4,9 +4,13 @@ use async_trait::async_trait;
 use zksync_config::configs::object_store::{ObjectStoreConfig, ObjectStoreMode};
 
 use crate::{
+    azure::{AzureBlobStorage, AzureCredentials},
+    envelope::{EncryptionKeys, EnvelopeObjectStore},
     file::FileBackedObjectStore,
     gcs::{GoogleCloudStorage, GoogleCloudStorageAuthMode},
+    mirror::HttpMirrorObjectStore,
     mock::MockStore,
+    s3::S3Storage,
 };
 
 /// Bucket for [`ObjectStore`] in which objects can be placed.
@@ -23,8 +27,10 @@ pub enum Bucket {
     NodeAggregationWitnessJobsFri,
     SchedulerWitnessJobsFri,
     ProofsFri,
+    ProofUploads,
     StorageSnapshot,
     TeeVerifierInput,
+    BlockDataArchive,
 }
 
 impl Bucket {
@@ -40,8 +46,10 @@ impl Bucket {
             Self::NodeAggregationWitnessJobsFri => "node_aggregation_witness_jobs_fri",
             Self::SchedulerWitnessJobsFri => "scheduler_witness_jobs_fri",
             Self::ProofsFri => "proofs_fri",
+            Self::ProofUploads => "proof_uploads",
             Self::StorageSnapshot => "storage_logs_snapshots",
             Self::TeeVerifierInput => "tee_verifier_inputs",
+            Self::BlockDataArchive => "block_data_archive",
         }
     }
 }
@@ -62,6 +70,9 @@ pub enum ObjectStoreError {
     KeyNotFound(BoxedError),
     /// Object (de)serialization failed.
     Serialization(BoxedError),
+    /// Object was fetched, but its contents are corrupted (e.g., fail checksum verification) or cannot be
+    /// decrypted. Unlike [`Self::Other`], such errors are not transient and should not be retried.
+    Corrupted(BoxedError),
     /// Other error has occurred when accessing the store (e.g., a network error).
     Other(BoxedError),
 }
@@ -71,6 +82,7 @@ impl fmt::Display for ObjectStoreError {
         match self {
             Self::KeyNotFound(err) => write!(formatter, "key not found: {err}"),
             Self::Serialization(err) => write!(formatter, "serialization error: {err}"),
+            Self::Corrupted(err) => write!(formatter, "corrupted object: {err}"),
             Self::Other(err) => write!(formatter, "other error: {err}"),
         }
     }
@@ -79,9 +91,10 @@ impl fmt::Display for ObjectStoreError {
 impl error::Error for ObjectStoreError {
     fn source(&self) -> Option<&(dyn error::Error + 'static)> {
         match self {
-            Self::KeyNotFound(err) | Self::Serialization(err) | Self::Other(err) => {
-                Some(err.as_ref())
-            }
+            Self::KeyNotFound(err)
+            | Self::Serialization(err)
+            | Self::Corrupted(err)
+            | Self::Other(err) => Some(err.as_ref()),
         }
     }
 }
@@ -184,6 +197,12 @@ impl ObjectStoreFactory {
     }
 
     /// Creates an [`ObjectStore`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the Azure Blob Storage-backed implementation is configured, and either the store
+    /// configuration or credentials provided via env variables are invalid. Also panics if encryption keys
+    /// are configured but cannot be loaded.
     pub async fn create_store(&self) -> Arc<dyn ObjectStore> {
         match &self.origin {
             ObjectStoreOrigin::Config(config) => Self::create_from_config(config).await,
@@ -192,6 +211,30 @@ impl ObjectStoreFactory {
     }
 
     async fn create_from_config(config: &ObjectStoreConfig) -> Arc<dyn ObjectStore> {
+        let store = Self::create_raw_store(config).await;
+        if let Some(keys_path) = &config.encryption_keys_file_path {
+            let keys = EncryptionKeys::from_file(keys_path)
+                .expect("failed loading object store encryption keys");
+            tracing::info!("Enabled client-side encryption for object store with keys {keys:?}");
+            if config.allow_plaintext_reads {
+                tracing::warn!(
+                    "Reading objects not wrapped into an envelope is allowed; this should only be enabled \
+                     while migrating an existing store to encryption"
+                );
+            }
+            Arc::new(
+                EnvelopeObjectStore::new(store, Some(keys))
+                    .with_plaintext_reads(config.allow_plaintext_reads),
+            )
+        } else if config.integrity_envelope {
+            tracing::info!("Enabled integrity envelope for object store");
+            Arc::new(EnvelopeObjectStore::new(store, None))
+        } else {
+            store
+        }
+    }
+
+    async fn create_raw_store(config: &ObjectStoreConfig) -> Arc<dyn ObjectStore> {
         match &config.mode {
             ObjectStoreMode::GCS { bucket_base_url } => {
                 tracing::trace!(
@@ -237,6 +280,44 @@ impl ObjectStoreFactory {
                 .await;
                 Arc::new(store)
             }
+            ObjectStoreMode::HttpMirror { http_base_url } => {
+                tracing::trace!("Initialized HttpMirror store");
+                let store = HttpMirrorObjectStore::new(http_base_url, config.max_retries);
+                Arc::new(store)
+            }
+            ObjectStoreMode::AzureBlob {
+                azure_account_name,
+                azure_container_name,
+                azure_endpoint,
+            } => {
+                let credentials = AzureCredentials::from_env()
+                    .expect("failed reading Azure Blob Storage credentials");
+                tracing::trace!("Initialized AzureBlob store with {credentials:?} credentials");
+                let store = AzureBlobStorage::new(
+                    azure_account_name,
+                    azure_container_name,
+                    azure_endpoint.as_deref(),
+                    credentials,
+                    config.max_retries,
+                )
+                .expect("invalid Azure Blob Storage configuration");
+                Arc::new(store)
+            }
+            ObjectStoreMode::S3 {
+                s3_bucket_name,
+                s3_region,
+                s3_endpoint,
+            } => {
+                let store = S3Storage::new(
+                    s3_bucket_name,
+                    s3_region,
+                    s3_endpoint.as_deref(),
+                    config.max_retries,
+                )
+                .await;
+                tracing::trace!("Initialized S3 store: {store:?}");
+                Arc::new(store)
+            }
         }
     }
 }
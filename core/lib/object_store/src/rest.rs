@@ -0,0 +1,53 @@
+//! Helpers shared by object stores accessed via HTTP REST APIs.
+
+use std::time::Duration;
+
+use reqwest::{RequestBuilder, StatusCode};
+
+use crate::raw::ObjectStoreError;
+
+/// Sends a request built by `build_request`, retrying on transient errors (network errors, 5xx
+/// and throttling responses) with an exponential backoff.
+pub(crate) async fn send_with_retries(
+    max_retries: u16,
+    description: &str,
+    build_request: impl Fn() -> RequestBuilder,
+) -> Result<reqwest::Response, ObjectStoreError> {
+    let mut retries = 0;
+    let mut backoff = Duration::from_secs(1);
+    loop {
+        let err = match build_request().send().await {
+            Ok(response) if !is_transient_status(response.status()) => return Ok(response),
+            Ok(response) => {
+                format!("server responded with status {}", response.status())
+            }
+            Err(err) => err.to_string(),
+        };
+
+        if retries >= max_retries {
+            let err = format!("failed {description} after {retries} retries: {err}");
+            return Err(ObjectStoreError::Other(err.into()));
+        }
+        retries += 1;
+        tracing::warn!(
+            "Failed {description} ({retries}/{max_retries}), retrying in {backoff:?}: {err}"
+        );
+        tokio::time::sleep(backoff).await;
+        backoff *= 2;
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS
+}
+
+/// Converts an unsuccessful response into an error.
+pub(crate) fn response_error(response: reqwest::Response) -> ObjectStoreError {
+    match response.error_for_status() {
+        Ok(response) => {
+            let err = format!("unexpected response status {}", response.status());
+            ObjectStoreError::Other(err.into())
+        }
+        Err(err) => ObjectStoreError::Other(err.into()),
+    }
+}
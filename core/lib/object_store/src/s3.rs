@@ -0,0 +1,230 @@
+//! [`ObjectStore`] implementation for S3-compatible stores (AWS S3, MinIO, Cloudflare R2 etc.)
+//! based on the AWS SDK.
+
+use std::fmt;
+
+use async_trait::async_trait;
+use aws_config::BehaviorVersion;
+use aws_sdk_s3::{
+    config::{retry::RetryConfig, ProvideCredentials, Region},
+    error::{DisplayErrorContext, SdkError},
+    primitives::ByteStream,
+    Client,
+};
+
+use crate::{
+    metrics::S3_METRICS,
+    raw::{Bucket, ObjectStore, ObjectStoreError},
+};
+
+/// Object store persisting objects in a bucket of an S3-compatible store. Objects are stored with `{bucket}/{key}` keys.
+/// Requests use path-style addressing, which is supported by all S3-compatible stores.
+///
+/// Credentials are resolved using the default AWS credentials chain (env variables, shared config files,
+/// web identity tokens, instance metadata etc.). If no credentials can be resolved, requests are sent unsigned,
+/// which only works for reading from buckets with public access.
+pub(crate) struct S3Storage {
+    bucket_name: String,
+    endpoint: String,
+    is_anonymous: bool,
+    client: Client,
+}
+
+impl fmt::Debug for S3Storage {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("S3Storage")
+            .field("bucket_name", &self.bucket_name)
+            .field("endpoint", &self.endpoint)
+            .field("is_anonymous", &self.is_anonymous)
+            .finish_non_exhaustive()
+    }
+}
+
+impl S3Storage {
+    pub async fn new(
+        bucket_name: &str,
+        region: &str,
+        endpoint: Option<&str>,
+        max_retries: u16,
+    ) -> Self {
+        let endpoint = endpoint.map_or_else(
+            || format!("https://s3.{region}.amazonaws.com"),
+            |endpoint| endpoint.trim_end_matches('/').to_owned(),
+        );
+        let loader = || {
+            aws_config::defaults(BehaviorVersion::latest())
+                .region(Region::new(region.to_owned()))
+                .endpoint_url(&endpoint)
+                // The SDK makes the initial attempt plus retries.
+                .retry_config(RetryConfig::standard().with_max_attempts(u32::from(max_retries) + 1))
+        };
+
+        let mut sdk_config = loader().load().await;
+        let credentials = match sdk_config.credentials_provider() {
+            Some(provider) => provider.provide_credentials().await.map(drop),
+            None => Ok(()),
+        };
+        let is_anonymous = if let Err(err) = credentials {
+            tracing::info!(
+                "Failed resolving S3 credentials ({}); falling back to anonymous access",
+                DisplayErrorContext(&err)
+            );
+            sdk_config = loader().no_credentials().load().await;
+            true
+        } else {
+            false
+        };
+
+        let s3_config = aws_sdk_s3::config::Builder::from(&sdk_config)
+            .force_path_style(true)
+            .build();
+        Self {
+            bucket_name: bucket_name.to_owned(),
+            endpoint,
+            is_anonymous,
+            client: Client::from_conf(s3_config),
+        }
+    }
+
+    fn object_key(bucket: Bucket, key: &str) -> String {
+        format!("{bucket}/{key}")
+    }
+}
+
+fn sdk_error<E>(description: String, err: SdkError<E>) -> ObjectStoreError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    // The SDK has already retried transient errors, but the error is still considered transient,
+    // since it may be caused by a temporary outage.
+    let err = format!("failed {description}: {}", DisplayErrorContext(&err));
+    ObjectStoreError::Other(err.into())
+}
+
+#[async_trait]
+impl ObjectStore for S3Storage {
+    async fn get_raw(&self, bucket: Bucket, key: &str) -> Result<Vec<u8>, ObjectStoreError> {
+        let fetch_latency = S3_METRICS.start_fetch(bucket);
+        let key = Self::object_key(bucket, key);
+        let response = self
+            .client
+            .get_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .send()
+            .await;
+        let output = match response {
+            Ok(output) => output,
+            Err(SdkError::ServiceError(err)) if err.err().is_no_such_key() => {
+                let err = format!("object `{key}` is not found");
+                return Err(ObjectStoreError::KeyNotFound(err.into()));
+            }
+            Err(err) => return Err(sdk_error(format!("fetching object `{key}`"), err)),
+        };
+        let bytes = output.body.collect().await.map_err(|err| {
+            let err = format!("failed reading object `{key}`: {err}");
+            ObjectStoreError::Other(err.into())
+        })?;
+        fetch_latency.observe();
+        Ok(bytes.into_bytes().to_vec())
+    }
+
+    async fn put_raw(
+        &self,
+        bucket: Bucket,
+        key: &str,
+        value: Vec<u8>,
+    ) -> Result<(), ObjectStoreError> {
+        let store_latency = S3_METRICS.start_store(bucket);
+        let key = Self::object_key(bucket, key);
+        self.client
+            .put_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .body(ByteStream::from(value))
+            .send()
+            .await
+            .map_err(|err| sdk_error(format!("storing object `{key}`"), err))?;
+        store_latency.observe();
+        Ok(())
+    }
+
+    async fn remove_raw(&self, bucket: Bucket, key: &str) -> Result<(), ObjectStoreError> {
+        // Removing a non-existing object is not an error in S3.
+        let key = Self::object_key(bucket, key);
+        self.client
+            .delete_object()
+            .bucket(&self.bucket_name)
+            .key(&key)
+            .send()
+            .await
+            .map_err(|err| sdk_error(format!("removing object `{key}`"), err))?;
+        Ok(())
+    }
+
+    fn storage_prefix_raw(&self, bucket: Bucket) -> String {
+        format!("{}/{}/{bucket}", self.endpoint, self.bucket_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    /// Creates a store connected to a MinIO server. Credentials must be provided via the conventional
+    /// `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` env variables (`minioadmin` for a default MinIO setup).
+    async fn minio_store() -> S3Storage {
+        let endpoint =
+            env::var("MINIO_ENDPOINT").unwrap_or_else(|_| "http://127.0.0.1:9000".to_owned());
+        S3Storage::new("zksync-test", "us-east-1", Some(&endpoint), 1).await
+    }
+
+    #[test]
+    fn object_keys() {
+        assert_eq!(
+            S3Storage::object_key(Bucket::ProverJobsFri, "1/a b+c.bin"),
+            "prover_jobs_fri/1/a b+c.bin"
+        );
+    }
+
+    /// Integration test against a MinIO server (e.g., run via `docker run -p 9000:9000 minio/minio server /data`).
+    #[tokio::test]
+    #[ignore = "requires MinIO server"]
+    async fn minio_roundtrip() {
+        let store = minio_store().await;
+        assert!(!store.is_anonymous);
+        // Create the bucket if it doesn't exist.
+        if let Err(err) = store
+            .client
+            .create_bucket()
+            .bucket(&store.bucket_name)
+            .send()
+            .await
+        {
+            let err = err.into_service_error();
+            assert!(
+                err.is_bucket_already_owned_by_you() || err.is_bucket_already_exists(),
+                "{err:?}"
+            );
+        }
+
+        let bucket = Bucket::ProverJobsFri;
+        store.remove_raw(bucket, "test.bin").await.unwrap();
+        let err = store.get_raw(bucket, "test.bin").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+
+        store
+            .put_raw(bucket, "test.bin", vec![1, 2, 3])
+            .await
+            .unwrap();
+        let value = store.get_raw(bucket, "test.bin").await.unwrap();
+        assert_eq!(value, [1, 2, 3]);
+
+        store.remove_raw(bucket, "test.bin").await.unwrap();
+        let err = store.get_raw(bucket, "test.bin").await.unwrap_err();
+        assert!(matches!(err, ObjectStoreError::KeyNotFound(_)), "{err}");
+    }
+}
@@ -5,6 +5,9 @@ use metrics_exporter_prometheus::{Matcher, PrometheusBuilder};
 use tokio::sync::watch;
 use vise::MetricsCollection;
 use vise_exporter::MetricsExporter;
+use zksync_config::configs::{PrometheusConfig, PrometheusExportMode};
+
+mod otlp;
 
 fn configure_legacy_exporter(builder: PrometheusBuilder) -> PrometheusBuilder {
     // in seconds
@@ -37,6 +40,11 @@ enum PrometheusTransport {
         gateway_uri: String,
         interval: Duration,
     },
+    Otlp {
+        endpoint: String,
+        service_name: String,
+        interval: Duration,
+    },
 }
 
 /// Configuration of a Prometheus exporter.
@@ -66,6 +74,45 @@ impl PrometheusExporterConfig {
         }
     }
 
+    /// Creates an exporter that will periodically export metrics to the specified OpenTelemetry collector endpoint
+    /// using OTLP over HTTP (e.g., `http://localhost:4318/v1/metrics`). Only metrics defined using the new metrics façade
+    /// (`vise`) are exported.
+    pub const fn otlp(endpoint: String, service_name: String, interval: Duration) -> Self {
+        Self {
+            transport: PrometheusTransport::Otlp {
+                endpoint,
+                service_name,
+                interval,
+            },
+            use_new_facade: true,
+        }
+    }
+
+    /// Creates an exporter based on the export mode specified in the provided `config`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the OTLP export mode is selected, but the OTLP endpoint is not specified.
+    pub fn from_config(config: &PrometheusConfig, service_name: &str) -> anyhow::Result<Self> {
+        Ok(match config.export_mode {
+            PrometheusExportMode::Pull => Self::pull(config.listener_port),
+            PrometheusExportMode::Push => {
+                Self::push(config.gateway_endpoint(), config.push_interval())
+            }
+            PrometheusExportMode::Otlp => {
+                let endpoint = config
+                    .otlp_endpoint
+                    .clone()
+                    .context("OTLP endpoint must be specified for the OTLP export mode")?;
+                Self::otlp(
+                    endpoint,
+                    service_name.to_owned(),
+                    config.otlp_export_interval(),
+                )
+            }
+        })
+    }
+
     /// Disables the new metrics façade (`vise`), which is on by default.
     #[must_use]
     pub fn without_new_facade(self) -> Self {
@@ -93,6 +140,22 @@ impl PrometheusExporterConfig {
         mut stop_receiver: watch::Receiver<bool>,
     ) -> anyhow::Result<()> {
         let registry = MetricsCollection::lazy().collect();
+        if let PrometheusTransport::Otlp {
+            endpoint,
+            service_name,
+            interval,
+        } = &self.transport
+        {
+            return otlp::export_metrics(
+                registry,
+                endpoint,
+                service_name,
+                *interval,
+                stop_receiver,
+            )
+            .await;
+        }
+
         let metrics_exporter = MetricsExporter::new(registry.into())
             .with_legacy_exporter(configure_legacy_exporter)
             .with_graceful_shutdown(async move {
@@ -116,6 +179,7 @@ impl PrometheusExporterConfig {
                     .context("Failed parsing Prometheus push gateway endpoint")?;
                 metrics_exporter.push_to_gateway(endpoint, interval).await;
             }
+            PrometheusTransport::Otlp { .. } => unreachable!("handled above"),
         }
         Ok(())
     }
@@ -132,6 +196,9 @@ impl PrometheusExporterConfig {
             } => PrometheusBuilder::new()
                 .with_push_gateway(gateway_uri, interval, None, None)
                 .context("PrometheusBuilder::with_push_gateway()")?,
+            PrometheusTransport::Otlp { .. } => {
+                anyhow::bail!("OTLP export is not supported without the new metrics façade")
+            }
         };
         let builder = configure_legacy_exporter(builder);
         let (recorder, exporter) = builder.build().context("PrometheusBuilder::build()")?;
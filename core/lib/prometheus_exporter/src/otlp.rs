@@ -0,0 +1,487 @@
+//! Export of metrics to an OpenTelemetry collector using OTLP over HTTP.
+//!
+//! Metrics are collected from the `vise` registry in the OpenMetrics text format and converted
+//! to OpenTelemetry metrics data, which is exported using the `opentelemetry-otlp` exporter. Counters are exported
+//! as cumulative monotonic sums, histograms as cumulative explicit-bucket histograms, and all other metric types
+//! as gauges.
+
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    time::{Duration, SystemTime},
+};
+
+use anyhow::Context as _;
+use opentelemetry::{
+    metrics::Unit,
+    sdk::{
+        metrics::{
+            data::{
+                Aggregation, DataPoint, Gauge, Histogram, HistogramDataPoint, Metric,
+                ResourceMetrics, ScopeMetrics, Sum, Temporality,
+            },
+            exporter::PushMetricsExporter,
+            reader::{DefaultAggregationSelector, DefaultTemporalitySelector},
+        },
+        AttributeSet, Resource,
+    },
+    InstrumentationLibrary, KeyValue,
+};
+use opentelemetry_otlp::WithExportConfig;
+use opentelemetry_semantic_conventions::resource::SERVICE_NAME;
+use tokio::sync::watch;
+use vise::{Format, Registry};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MetricKind {
+    Counter,
+    Gauge,
+    Histogram,
+    Other,
+}
+
+impl MetricKind {
+    fn parse(s: &str) -> Self {
+        match s {
+            "counter" => Self::Counter,
+            "gauge" => Self::Gauge,
+            "histogram" => Self::Histogram,
+            _ => Self::Other,
+        }
+    }
+}
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, PartialEq)]
+struct Sample {
+    name: String,
+    labels: Labels,
+    value: f64,
+}
+
+#[derive(Debug)]
+struct MetricFamily {
+    name: String,
+    kind: MetricKind,
+    help: String,
+    unit: String,
+    samples: Vec<Sample>,
+}
+
+impl MetricFamily {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_owned(),
+            kind: MetricKind::Other,
+            help: String::new(),
+            unit: String::new(),
+            samples: vec![],
+        }
+    }
+}
+
+fn parse_label_value(
+    chars: &mut std::iter::Peekable<std::str::Chars<'_>>,
+) -> anyhow::Result<String> {
+    anyhow::ensure!(chars.next() == Some('"'), "label value must be quoted");
+    let mut value = String::new();
+    loop {
+        match chars.next().context("unterminated label value")? {
+            '"' => return Ok(value),
+            '\\' => match chars.next().context("unterminated escape sequence")? {
+                'n' => value.push('\n'),
+                other => value.push(other),
+            },
+            ch => value.push(ch),
+        }
+    }
+}
+
+fn parse_sample(line: &str) -> anyhow::Result<Sample> {
+    let name_end = line
+        .find(|ch: char| ch == '{' || ch.is_whitespace())
+        .context("sample has no value")?;
+    let name = line[..name_end].to_owned();
+    let mut chars = line[name_end..].chars().peekable();
+
+    let mut labels = vec![];
+    if chars.peek() == Some(&'{') {
+        chars.next();
+        loop {
+            while chars
+                .peek()
+                .is_some_and(|ch| *ch == ',' || ch.is_whitespace())
+            {
+                chars.next();
+            }
+            if chars.peek() == Some(&'}') {
+                chars.next();
+                break;
+            }
+            let mut label_name = String::new();
+            while let Some(ch) = chars.next_if(|&ch| ch != '=') {
+                label_name.push(ch);
+            }
+            anyhow::ensure!(
+                chars.next() == Some('='),
+                "label `{label_name}` has no value"
+            );
+            let label_value = parse_label_value(&mut chars)?;
+            labels.push((label_name.trim().to_owned(), label_value));
+        }
+    }
+
+    let rest: String = chars.collect();
+    // The value may be followed by a timestamp, which we ignore.
+    let value = rest
+        .split_whitespace()
+        .next()
+        .context("sample has no value")?;
+    let value = match value {
+        "+Inf" => f64::INFINITY,
+        "-Inf" => f64::NEG_INFINITY,
+        _ => value
+            .parse()
+            .with_context(|| format!("invalid sample value `{value}`"))?,
+    };
+    Ok(Sample {
+        name,
+        labels,
+        value,
+    })
+}
+
+/// Parses metrics in the OpenMetrics text format.
+fn parse_open_metrics(text: &str) -> anyhow::Result<Vec<MetricFamily>> {
+    let mut families = Vec::<MetricFamily>::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut parts = comment.trim_start().splitn(3, ' ');
+            let (Some(directive), Some(name)) = (parts.next(), parts.next()) else {
+                continue; // e.g., `# EOF`
+            };
+            let value = parts.next().unwrap_or("");
+            if families.last().map_or(true, |family| family.name != name) {
+                families.push(MetricFamily::new(name));
+            }
+            let family = families.last_mut().unwrap();
+            match directive {
+                "HELP" => family.help = value.to_owned(),
+                "TYPE" => family.kind = MetricKind::parse(value),
+                "UNIT" => family.unit = value.to_owned(),
+                _ => { /* unknown directives are ignored */ }
+            }
+        } else {
+            let sample =
+                parse_sample(line).with_context(|| format!("failed parsing sample `{line}`"))?;
+            let family = families
+                .last_mut()
+                .filter(|family| sample.name.starts_with(&family.name))
+                .with_context(|| format!("sample `{line}` has no metadata"))?;
+            family.samples.push(sample);
+        }
+    }
+    Ok(families)
+}
+
+fn attributes(labels: &[(String, String)]) -> AttributeSet {
+    let attributes: Vec<_> = labels
+        .iter()
+        .map(|(name, value)| KeyValue::new(name.clone(), value.clone()))
+        .collect();
+    AttributeSet::from(attributes.as_slice())
+}
+
+#[derive(Debug, Default)]
+struct HistogramPoint {
+    buckets: Vec<(f64, f64)>,
+    sum: f64,
+    count: f64,
+}
+
+fn histogram_points(family: &MetricFamily) -> BTreeMap<Labels, HistogramPoint> {
+    let mut points = BTreeMap::<Labels, HistogramPoint>::new();
+    for sample in &family.samples {
+        let suffix = &sample.name[family.name.len()..];
+        let mut labels = sample.labels.clone();
+        let le = labels
+            .iter()
+            .position(|(name, _)| name == "le")
+            .map(|idx| labels.remove(idx).1);
+        let point = points.entry(labels).or_default();
+        match (suffix, le) {
+            ("_bucket", Some(le)) => {
+                let bound = if le == "+Inf" {
+                    f64::INFINITY
+                } else {
+                    le.parse().unwrap_or(f64::INFINITY)
+                };
+                point.buckets.push((bound, sample.value));
+            }
+            ("_sum", _) => point.sum = sample.value,
+            ("_count", _) => point.count = sample.value,
+            _ => { /* e.g., `_created` samples */ }
+        }
+    }
+    points
+}
+
+fn convert_family(
+    family: &MetricFamily,
+    start_time: SystemTime,
+    time: SystemTime,
+) -> Option<Metric> {
+    if family.samples.is_empty() {
+        return None;
+    }
+
+    let data: Box<dyn Aggregation> = match family.kind {
+        MetricKind::Histogram => {
+            let data_points = histogram_points(family)
+                .into_iter()
+                .map(|(labels, mut point)| {
+                    point.buckets.sort_by(|(x, _), (y, _)| x.total_cmp(y));
+                    // OpenMetrics buckets are cumulative, while OTLP buckets are not.
+                    let mut prev_count = 0.0;
+                    let bucket_counts = point
+                        .buckets
+                        .iter()
+                        .map(|&(_, count)| {
+                            let bucket_count = (count - prev_count).max(0.0);
+                            prev_count = count;
+                            bucket_count as u64
+                        })
+                        .collect();
+                    let bounds = point
+                        .buckets
+                        .iter()
+                        .map(|&(bound, _)| bound)
+                        .filter(|bound| bound.is_finite())
+                        .collect();
+                    HistogramDataPoint {
+                        attributes: attributes(&labels),
+                        start_time,
+                        time,
+                        count: point.count as u64,
+                        bounds,
+                        bucket_counts,
+                        min: None,
+                        max: None,
+                        sum: point.sum,
+                        exemplars: vec![],
+                    }
+                })
+                .collect();
+            Box::new(Histogram {
+                data_points,
+                temporality: Temporality::Cumulative,
+            })
+        }
+        MetricKind::Counter => {
+            let data_points = family
+                .samples
+                .iter()
+                .filter(|sample| sample.name.ends_with("_total") || sample.name == family.name)
+                .map(|sample| DataPoint {
+                    attributes: attributes(&sample.labels),
+                    start_time: Some(start_time),
+                    time: Some(time),
+                    value: sample.value,
+                    exemplars: vec![],
+                })
+                .collect();
+            Box::new(Sum {
+                data_points,
+                temporality: Temporality::Cumulative,
+                is_monotonic: true,
+            })
+        }
+        MetricKind::Gauge | MetricKind::Other => {
+            let data_points = family
+                .samples
+                .iter()
+                .map(|sample| DataPoint {
+                    attributes: attributes(&sample.labels),
+                    start_time: None,
+                    time: Some(time),
+                    value: sample.value,
+                    exemplars: vec![],
+                })
+                .collect();
+            Box::new(Gauge { data_points })
+        }
+    };
+
+    Some(Metric {
+        name: Cow::Owned(family.name.clone()),
+        description: Cow::Owned(family.help.clone()),
+        unit: Unit::new(family.unit.clone()),
+        data,
+    })
+}
+
+/// Converts metrics in the OpenMetrics text format to OpenTelemetry metrics data.
+fn open_metrics_to_otlp(
+    text: &str,
+    service_name: &str,
+    start_time: SystemTime,
+    time: SystemTime,
+) -> anyhow::Result<ResourceMetrics> {
+    let families = parse_open_metrics(text)?;
+    let metrics = families
+        .iter()
+        .filter_map(|family| convert_family(family, start_time, time))
+        .collect();
+    Ok(ResourceMetrics {
+        resource: Resource::new([KeyValue::new(SERVICE_NAME, service_name.to_owned())]),
+        scope_metrics: vec![ScopeMetrics {
+            scope: InstrumentationLibrary::new(
+                "vise",
+                None::<&'static str>,
+                None::<&'static str>,
+                None,
+            ),
+            metrics,
+        }],
+    })
+}
+
+/// Periodically exports metrics from the `registry` to the specified OTLP endpoint until a stop signal is received.
+pub(crate) async fn export_metrics(
+    registry: Registry,
+    endpoint: &str,
+    service_name: &str,
+    interval: Duration,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let exporter = opentelemetry_otlp::new_exporter()
+        .http()
+        .with_endpoint(endpoint)
+        .build_metrics_exporter(
+            Box::new(DefaultAggregationSelector::new()),
+            Box::new(DefaultTemporalitySelector::new()),
+        )
+        .context("failed building OTLP metrics exporter")?;
+    let start_time = SystemTime::now();
+    tracing::info!("Started exporting metrics to OTLP endpoint {endpoint} every {interval:?}");
+
+    loop {
+        let stop_requested = tokio::time::timeout(interval, stop_receiver.changed())
+            .await
+            .is_ok();
+
+        let mut buffer = String::new();
+        registry
+            .encode(&mut buffer, Format::OpenMetrics)
+            .context("failed encoding metrics")?;
+        let mut metrics =
+            open_metrics_to_otlp(&buffer, service_name, start_time, SystemTime::now())?;
+        match exporter.export(&mut metrics).await {
+            Ok(()) => tracing::trace!("Exported metrics to OTLP endpoint {endpoint}"),
+            Err(err) => {
+                tracing::warn!("Failed exporting metrics to OTLP endpoint {endpoint}: {err}")
+            }
+        }
+
+        if stop_requested {
+            tracing::info!("Stop signal received, stopping OTLP metrics export");
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::UNIX_EPOCH;
+
+    use super::*;
+
+    const METRICS: &str = r#"
+# HELP server_requests Number of processed requests.
+# TYPE server_requests counter
+server_requests_total{method="eth_call"} 3
+server_requests_total{method="eth_getLogs",label="with \"quotes\""} 1
+# HELP server_latency_seconds Request latency.
+# TYPE server_latency_seconds histogram
+# UNIT server_latency_seconds seconds
+server_latency_seconds_sum 0.5
+server_latency_seconds_count 3
+server_latency_seconds_bucket{le="0.1"} 1
+server_latency_seconds_bucket{le="1.0"} 3
+server_latency_seconds_bucket{le="+Inf"} 3
+# TYPE server_queue_size gauge
+server_queue_size 42
+# EOF
+"#;
+
+    #[test]
+    fn parsing_samples() {
+        let sample = parse_sample(r#"test{a="1",b="x\\y\"z"} 1.5 1700000000"#).unwrap();
+        assert_eq!(
+            sample,
+            Sample {
+                name: "test".to_owned(),
+                labels: vec![
+                    ("a".to_owned(), "1".to_owned()),
+                    ("b".to_owned(), r#"x\y"z"#.to_owned()),
+                ],
+                value: 1.5,
+            }
+        );
+
+        let sample = parse_sample("test +Inf").unwrap();
+        assert!(sample.labels.is_empty());
+        assert_eq!(sample.value, f64::INFINITY);
+
+        parse_sample("test").unwrap_err();
+        parse_sample(r#"test{a="1} 1"#).unwrap_err();
+    }
+
+    #[test]
+    fn converting_metrics() {
+        let start_time = UNIX_EPOCH + Duration::from_secs(1);
+        let time = UNIX_EPOCH + Duration::from_secs(2);
+        let resource_metrics = open_metrics_to_otlp(METRICS, "test", start_time, time).unwrap();
+
+        assert_eq!(
+            resource_metrics.resource.get(SERVICE_NAME),
+            Some("test".into())
+        );
+        let metrics = &resource_metrics.scope_metrics[0].metrics;
+        assert_eq!(metrics.len(), 3);
+
+        let counter = &metrics[0];
+        assert_eq!(counter.name, "server_requests");
+        let sum = counter.data.as_any().downcast_ref::<Sum<f64>>().unwrap();
+        assert!(sum.is_monotonic);
+        assert_eq!(sum.temporality, Temporality::Cumulative);
+        assert_eq!(sum.data_points.len(), 2);
+        assert_eq!(sum.data_points[0].value, 3.0);
+        assert_eq!(sum.data_points[0].start_time, Some(start_time));
+        assert_eq!(sum.data_points[0].time, Some(time));
+        let label_value = sum.data_points[1]
+            .attributes
+            .iter()
+            .find_map(|(key, value)| (key.as_str() == "label").then(|| value.as_str()));
+        assert_eq!(label_value.as_deref(), Some(r#"with "quotes""#));
+
+        let histogram = &metrics[1];
+        assert_eq!(histogram.unit.as_str(), "seconds");
+        let histogram = histogram
+            .data
+            .as_any()
+            .downcast_ref::<Histogram<f64>>()
+            .unwrap();
+        let data_point = &histogram.data_points[0];
+        assert_eq!(data_point.count, 3);
+        assert_eq!(data_point.sum, 0.5);
+        assert_eq!(data_point.bucket_counts, [1, 2, 0]);
+        assert_eq!(data_point.bounds, [0.1, 1.0]);
+
+        let gauge = &metrics[2];
+        assert_eq!(gauge.name, "server_queue_size");
+        let gauge = gauge.data.as_any().downcast_ref::<Gauge<f64>>().unwrap();
+        assert_eq!(gauge.data_points[0].value, 42.0);
+    }
+}
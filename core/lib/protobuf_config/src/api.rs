@@ -1,6 +1,7 @@
 use std::num::NonZeroUsize;
 
 use anyhow::Context as _;
+use zksync_basic_types::url::SensitiveUrl;
 use zksync_config::configs::{api, ApiConfig};
 use zksync_protobuf::{
     repr::{read_required_repr, ProtoRepr},
@@ -17,6 +18,13 @@ impl ProtoRepr for proto::Api {
             prometheus: read_required_repr(&self.prometheus).context("prometheus")?,
             healthcheck: read_required_repr(&self.healthcheck).context("healthcheck")?,
             merkle_tree: read_required_repr(&self.merkle_tree).context("merkle_tree")?,
+            explorer: self
+                .explorer
+                .as_ref()
+                .map(ProtoRepr::read)
+                .transpose()
+                .context("explorer")?
+                .unwrap_or_default(),
         })
     }
 
@@ -26,6 +34,7 @@ impl ProtoRepr for proto::Api {
             prometheus: Some(ProtoRepr::build(&this.prometheus)),
             healthcheck: Some(ProtoRepr::build(&this.healthcheck)),
             merkle_tree: Some(ProtoRepr::build(&this.merkle_tree)),
+            explorer: Some(ProtoRepr::build(&this.explorer)),
         }
     }
 }
@@ -81,6 +90,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             ws_url: required(&self.ws_url).context("ws_url")?.clone(),
             req_entities_limit: self.req_entities_limit,
             filters_disabled: self.filters_disabled.unwrap_or(false),
+            logs_pagination_enabled: self.logs_pagination_enabled.unwrap_or(false),
             filters_limit: self.filters_limit,
             subscriptions_limit: self.subscriptions_limit,
             pubsub_polling_interval: self.pubsub_polling_interval,
@@ -95,6 +105,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 &self.estimate_gas_acceptable_overestimation,
             )
             .context("acceptable_overestimation")?,
+            estimate_gas_single_pass: self.estimate_gas_single_pass.unwrap_or(false),
             max_tx_size: required(&self.max_tx_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_tx_size")?,
@@ -147,6 +158,18 @@ impl ProtoRepr for proto::Web3JsonRpc {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("mempool_cache_size")?,
+            response_cache_size_mb: self
+                .response_cache_size_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("response_cache_size_mb")?,
+            response_cache_ttl_sec: self.response_cache_ttl_sec,
+            response_cache_redis_url: self
+                .response_cache_redis_url
+                .as_deref()
+                .map(str::parse::<SensitiveUrl>)
+                .transpose()
+                .context("response_cache_redis_url")?,
             whitelisted_tokens_for_aa: self
                 .whitelisted_tokens_for_aa
                 .iter()
@@ -165,8 +188,15 @@ impl ProtoRepr for proto::Web3JsonRpc {
             ws_url: Some(this.ws_url.clone()),
             req_entities_limit: this.req_entities_limit,
             filters_disabled: Some(this.filters_disabled),
+            logs_pagination_enabled: Some(this.logs_pagination_enabled),
             mempool_cache_update_interval: this.mempool_cache_update_interval,
             mempool_cache_size: this.mempool_cache_size.map(|x| x.try_into().unwrap()),
+            response_cache_size_mb: this.response_cache_size_mb.map(|x| x.try_into().unwrap()),
+            response_cache_ttl_sec: this.response_cache_ttl_sec,
+            response_cache_redis_url: this
+                .response_cache_redis_url
+                .as_ref()
+                .map(|url| url.expose_str().to_string()),
             filters_limit: this.filters_limit,
             subscriptions_limit: this.subscriptions_limit,
             pubsub_polling_interval: this.pubsub_polling_interval,
@@ -182,6 +212,7 @@ impl ProtoRepr for proto::Web3JsonRpc {
             estimate_gas_acceptable_overestimation: Some(
                 this.estimate_gas_acceptable_overestimation,
             ),
+            estimate_gas_single_pass: Some(this.estimate_gas_single_pass),
             max_tx_size: Some(this.max_tx_size.try_into().unwrap()),
             vm_execution_cache_misses_limit: this
                 .vm_execution_cache_misses_limit
@@ -255,11 +286,46 @@ impl ProtoRepr for proto::MerkleTreeApi {
             port: required(&self.port)
                 .and_then(|p| Ok((*p).try_into()?))
                 .context("port")?,
+            grpc_port: self
+                .grpc_port
+                .map(|p| p.try_into())
+                .transpose()
+                .context("grpc_port")?,
         })
     }
     fn build(this: &Self::Type) -> Self {
         Self {
             port: Some(this.port.into()),
+            grpc_port: this.grpc_port.map(Into::into),
+        }
+    }
+}
+
+impl ProtoRepr for proto::ExplorerApi {
+    type Type = api::ExplorerApiConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        let default = Self::Type::default();
+        Ok(Self::Type {
+            port: self
+                .port
+                .map(|port| port.try_into())
+                .transpose()
+                .context("port")?
+                .unwrap_or(default.port),
+            max_page_size: self
+                .max_page_size
+                .map(|size| size.try_into())
+                .transpose()
+                .context("max_page_size")?
+                .unwrap_or(default.max_page_size),
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            port: Some(this.port.into()),
+            max_page_size: Some(this.max_page_size.try_into().unwrap()),
         }
     }
 }
@@ -37,6 +37,12 @@ impl ProtoRepr for proto::StateKeeper {
             l2_block_seal_queue_capacity: required(&self.miniblock_seal_queue_capacity)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("miniblock_seal_queue_capacity")?,
+            l2_block_seal_queue_max_capacity: self
+                .l2_block_seal_queue_max_capacity
+                .map(|x| x.try_into())
+                .transpose()
+                .context("l2_block_seal_queue_max_capacity")?,
+            l2_block_seal_queue_target_latency_ms: self.l2_block_seal_queue_target_latency_ms,
             l2_block_max_payload_size: required(&self.miniblock_max_payload_size)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("miniblock_max_payload_size")?,
@@ -97,6 +103,10 @@ impl ProtoRepr for proto::StateKeeper {
                 this.l2_block_seal_queue_capacity.try_into().unwrap(),
             ),
             miniblock_max_payload_size: Some(this.l2_block_max_payload_size.try_into().unwrap()),
+            l2_block_seal_queue_max_capacity: this
+                .l2_block_seal_queue_max_capacity
+                .map(|x| x.try_into().unwrap()),
+            l2_block_seal_queue_target_latency_ms: this.l2_block_seal_queue_target_latency_ms,
             max_single_tx_gas: Some(this.max_single_tx_gas),
             max_allowed_l2_tx_gas_limit: Some(this.max_allowed_l2_tx_gas_limit),
             reject_tx_at_geometry_percentage: Some(this.reject_tx_at_geometry_percentage),
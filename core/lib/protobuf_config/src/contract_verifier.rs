@@ -23,6 +23,12 @@ impl ProtoRepr for proto::ContractVerifier {
                 .map(|a| a.try_into())
                 .transpose()
                 .context("threads_per_server")?,
+            compilers_download_url: self.compilers_download_url.clone(),
+            max_compiler_candidates: self
+                .max_compiler_candidates
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_compiler_candidates")?,
         })
     }
 
@@ -34,6 +40,8 @@ impl ProtoRepr for proto::ContractVerifier {
             polling_interval: this.polling_interval,
             threads_per_server: this.threads_per_server.map(|a| a as u32),
             prometheus_port: Some(this.prometheus_port.into()),
+            compilers_download_url: this.compilers_download_url.clone(),
+            max_compiler_candidates: this.max_compiler_candidates.map(|x| x as u64),
         }
     }
 }
@@ -2,7 +2,7 @@ use anyhow::Context as _;
 use zksync_config::configs::{self};
 use zksync_protobuf::{required, ProtoRepr};
 
-use crate::{proto::eth as proto, read_optional_repr};
+use crate::{parse_h160, proto::eth as proto, read_optional_repr};
 
 impl proto::ProofSendingMode {
     fn new(x: &configs::eth_sender::ProofSendingMode) -> Self {
@@ -42,6 +42,28 @@ impl proto::PubdataSendingMode {
     }
 }
 
+impl proto::BlobFeeStrategy {
+    fn new(x: &configs::eth_sender::BlobFeeStrategy) -> Self {
+        use configs::eth_sender::BlobFeeStrategy as From;
+        match x {
+            From::Median => Self::Median,
+            From::Percentile => Self::Percentile,
+            From::Ewma => Self::Ewma,
+            From::Capped => Self::Capped,
+        }
+    }
+
+    fn parse(&self) -> configs::eth_sender::BlobFeeStrategy {
+        use configs::eth_sender::BlobFeeStrategy as To;
+        match self {
+            Self::Median => To::Median,
+            Self::Percentile => To::Percentile,
+            Self::Ewma => To::Ewma,
+            Self::Capped => To::Capped,
+        }
+    }
+}
+
 impl ProtoRepr for proto::Eth {
     type Type = configs::eth_sender::EthConfig;
 
@@ -109,6 +131,10 @@ impl ProtoRepr for proto::Sender {
                 .and_then(|x| Ok(proto::PubdataSendingMode::try_from(*x)?))
                 .context("pubdata_sending_mode")?
                 .parse(),
+            fee_bump_percent: self.fee_bump_percent,
+            resubmission_interval_blocks: self.resubmission_interval_blocks,
+            stuck_tx_threshold_blocks: self.stuck_tx_threshold_blocks,
+            repair_nonce_gaps: self.repair_nonce_gaps.unwrap_or_default(),
         })
     }
 
@@ -139,6 +165,10 @@ impl ProtoRepr for proto::Sender {
             pubdata_sending_mode: Some(
                 proto::PubdataSendingMode::new(&this.pubdata_sending_mode).into(),
             ),
+            fee_bump_percent: this.fee_bump_percent,
+            resubmission_interval_blocks: this.resubmission_interval_blocks,
+            stuck_tx_threshold_blocks: this.stuck_tx_threshold_blocks,
+            repair_nonce_gaps: Some(this.repair_nonce_gaps),
         }
     }
 }
@@ -172,6 +202,15 @@ impl ProtoRepr for proto::GasAdjuster {
             )
             .context("internal_pubdata_pricing_multiplier")?,
             max_blob_base_fee: self.max_blob_base_fee,
+            blob_fee_strategy: self
+                .blob_fee_strategy
+                .map(proto::BlobFeeStrategy::try_from)
+                .transpose()
+                .context("blob_fee_strategy")?
+                .map_or_else(Default::default, |x| x.parse()),
+            blob_fee_percentile: self.blob_fee_percentile,
+            blob_fee_ewma_alpha: self.blob_fee_ewma_alpha,
+            blob_fee_cap: self.blob_fee_cap,
         })
     }
 
@@ -193,6 +232,10 @@ impl ProtoRepr for proto::GasAdjuster {
             ),
             internal_pubdata_pricing_multiplier: Some(this.internal_pubdata_pricing_multiplier),
             max_blob_base_fee: this.max_blob_base_fee,
+            blob_fee_strategy: Some(proto::BlobFeeStrategy::new(&this.blob_fee_strategy).into()),
+            blob_fee_percentile: this.blob_fee_percentile,
+            blob_fee_ewma_alpha: this.blob_fee_ewma_alpha,
+            blob_fee_cap: this.blob_fee_cap,
         }
     }
 }
@@ -205,6 +248,13 @@ impl ProtoRepr for proto::EthWatch {
             confirmations_for_eth_event: self.confirmations_for_eth_event,
             eth_node_poll_interval: *required(&self.eth_node_poll_interval)
                 .context("eth_node_poll_interval")?,
+            additional_sources: self
+                .additional_sources
+                .iter()
+                .enumerate()
+                .map(|(i, x)| x.read().context(i))
+                .collect::<Result<_, _>>()
+                .context("additional_sources")?,
         })
     }
 
@@ -212,6 +262,44 @@ impl ProtoRepr for proto::EthWatch {
         Self {
             confirmations_for_eth_event: this.confirmations_for_eth_event,
             eth_node_poll_interval: Some(this.eth_node_poll_interval),
+            additional_sources: this
+                .additional_sources
+                .iter()
+                .map(ProtoRepr::build)
+                .collect(),
+        }
+    }
+}
+
+impl ProtoRepr for proto::EthWatchSource {
+    type Type = configs::EthWatchSourceConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            diamond_proxy_addr: required(&self.diamond_proxy_addr)
+                .and_then(|x| parse_h160(x))
+                .context("diamond_proxy_addr")?,
+            governance_addr: required(&self.governance_addr)
+                .and_then(|x| parse_h160(x))
+                .context("governance_addr")?,
+            state_transition_manager_addr: self
+                .state_transition_manager_addr
+                .as_ref()
+                .map(|x| parse_h160(x))
+                .transpose()
+                .context("state_transition_manager_addr")?,
+            confirmations_for_eth_event: self.confirmations_for_eth_event,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            diamond_proxy_addr: Some(format!("{:?}", this.diamond_proxy_addr)),
+            governance_addr: Some(format!("{:?}", this.governance_addr)),
+            state_transition_manager_addr: this
+                .state_transition_manager_addr
+                .map(|addr| format!("{addr:?}")),
+            confirmations_for_eth_event: this.confirmations_for_eth_event,
         }
     }
 }
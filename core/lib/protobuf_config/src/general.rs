@@ -37,6 +37,9 @@ impl ProtoRepr for proto::GeneralConfig {
             snapshot_creator: read_optional_repr(&self.snapshot_creator)
                 .context("snapshot_creator")?,
             observability: read_optional_repr(&self.observability).context("observability")?,
+            protective_reads_verifier_config: read_optional_repr(&self.protective_reads_verifier)
+                .context("protective_reads_verifier")?,
+            vm_replay_config: read_optional_repr(&self.vm_replay).context("vm_replay")?,
         })
     }
 
@@ -68,6 +71,11 @@ impl ProtoRepr for proto::GeneralConfig {
             eth: this.eth.as_ref().map(ProtoRepr::build),
             snapshot_creator: this.snapshot_creator.as_ref().map(ProtoRepr::build),
             observability: this.observability.as_ref().map(ProtoRepr::build),
+            protective_reads_verifier: this
+                .protective_reads_verifier_config
+                .as_ref()
+                .map(ProtoRepr::build),
+            vm_replay: this.vm_replay_config.as_ref().map(ProtoRepr::build),
         }
     }
 }
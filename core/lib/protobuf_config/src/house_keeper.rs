@@ -49,6 +49,12 @@ impl ProtoRepr for proto::HouseKeeper {
                 .fri_gpu_prover_archiver_archiving_interval_ms,
             fri_gpu_prover_archiver_archive_after_secs: self
                 .fri_gpu_prover_archiver_archive_after_secs,
+            prover_job_monitor_interval_ms: self.prover_job_monitor_interval_ms,
+            prover_job_monitor_stuck_job_threshold_secs: self
+                .prover_job_monitor_stuck_job_threshold_secs,
+            witness_generation_sla_secs: self.witness_generation_sla_secs,
+            proving_sla_secs: self.proving_sla_secs,
+            proof_compression_sla_secs: self.proof_compression_sla_secs,
         })
     }
 
@@ -83,6 +89,12 @@ impl ProtoRepr for proto::HouseKeeper {
                 .fri_gpu_prover_archiver_archiving_interval_ms,
             fri_gpu_prover_archiver_archive_after_secs: this
                 .fri_gpu_prover_archiver_archive_after_secs,
+            prover_job_monitor_interval_ms: this.prover_job_monitor_interval_ms,
+            prover_job_monitor_stuck_job_threshold_secs: this
+                .prover_job_monitor_stuck_job_threshold_secs,
+            witness_generation_sla_secs: this.witness_generation_sla_secs,
+            proving_sla_secs: this.proving_sla_secs,
+            proof_compression_sla_secs: this.proof_compression_sla_secs,
         }
     }
 }
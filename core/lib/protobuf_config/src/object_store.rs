@@ -37,6 +37,25 @@ impl ProtoRepr for proto::ObjectStore {
                     .context("file_backed_base_path")?
                     .clone(),
             },
+            proto::object_store::Mode::HttpMirror(mode) => ObjectStoreMode::HttpMirror {
+                http_base_url: required(&mode.http_base_url)
+                    .context("http_base_url")?
+                    .clone(),
+            },
+            proto::object_store::Mode::AzureBlob(mode) => ObjectStoreMode::AzureBlob {
+                azure_account_name: required(&mode.account_name)
+                    .context("account_name")?
+                    .clone(),
+                azure_container_name: required(&mode.container_name)
+                    .context("container_name")?
+                    .clone(),
+                azure_endpoint: mode.endpoint.clone(),
+            },
+            proto::object_store::Mode::S3(mode) => ObjectStoreMode::S3 {
+                s3_bucket_name: required(&mode.bucket_name).context("bucket_name")?.clone(),
+                s3_region: required(&mode.region).context("region")?.clone(),
+                s3_endpoint: mode.endpoint.clone(),
+            },
         };
 
         Ok(Self::Type {
@@ -44,6 +63,9 @@ impl ProtoRepr for proto::ObjectStore {
             max_retries: required(&self.max_retries)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("max_retries")?,
+            integrity_envelope: self.integrity_envelope.unwrap_or(false),
+            encryption_keys_file_path: self.encryption_keys_file_path.clone(),
+            allow_plaintext_reads: self.allow_plaintext_reads.unwrap_or(false),
         })
     }
 
@@ -75,11 +97,37 @@ impl ProtoRepr for proto::ObjectStore {
             } => proto::object_store::Mode::FileBacked(proto::object_store::FileBacked {
                 file_backed_base_path: Some(file_backed_base_path.clone()),
             }),
+            ObjectStoreMode::HttpMirror { http_base_url } => {
+                proto::object_store::Mode::HttpMirror(proto::object_store::HttpMirror {
+                    http_base_url: Some(http_base_url.clone()),
+                })
+            }
+            ObjectStoreMode::AzureBlob {
+                azure_account_name,
+                azure_container_name,
+                azure_endpoint,
+            } => proto::object_store::Mode::AzureBlob(proto::object_store::AzureBlob {
+                account_name: Some(azure_account_name.clone()),
+                container_name: Some(azure_container_name.clone()),
+                endpoint: azure_endpoint.clone(),
+            }),
+            ObjectStoreMode::S3 {
+                s3_bucket_name,
+                s3_region,
+                s3_endpoint,
+            } => proto::object_store::Mode::S3(proto::object_store::S3 {
+                bucket_name: Some(s3_bucket_name.clone()),
+                region: Some(s3_region.clone()),
+                endpoint: s3_endpoint.clone(),
+            }),
         };
 
         Self {
             mode: Some(mode),
             max_retries: Some(this.max_retries.into()),
+            integrity_envelope: Some(this.integrity_envelope),
+            encryption_keys_file_path: this.encryption_keys_file_path.clone(),
+            allow_plaintext_reads: Some(this.allow_plaintext_reads),
         }
     }
 }
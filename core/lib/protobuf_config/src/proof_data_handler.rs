@@ -14,6 +14,13 @@ impl ProtoRepr for proto::ProofDataHandler {
             proof_generation_timeout_in_secs: required(&self.proof_generation_timeout_in_secs)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("proof_generation_timeout_in_secs")?,
+            upload_chunk_size_limit_bytes: self
+                .upload_chunk_size_limit_bytes
+                .map(|x| x.try_into())
+                .transpose()
+                .context("upload_chunk_size_limit_bytes")?,
+            upload_session_timeout_in_secs: self.upload_session_timeout_in_secs,
+            reconciliation_interval_in_secs: self.reconciliation_interval_in_secs,
         })
     }
 
@@ -21,6 +28,9 @@ impl ProtoRepr for proto::ProofDataHandler {
         Self {
             http_port: Some(this.http_port.into()),
             proof_generation_timeout_in_secs: Some(this.proof_generation_timeout_in_secs.into()),
+            upload_chunk_size_limit_bytes: this.upload_chunk_size_limit_bytes.map(|x| x as u64),
+            upload_session_timeout_in_secs: this.upload_session_timeout_in_secs,
+            reconciliation_interval_in_secs: this.reconciliation_interval_in_secs,
         }
     }
 }
@@ -136,6 +136,7 @@ impl ProtoRepr for proto::ProverGateway {
             api_poll_duration_secs: required(&self.api_poll_duration_secs)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("api_poll_duration_secs")?,
+            streaming_enabled: self.streaming_enabled.unwrap_or(false),
             prometheus_listener_port: required(&self.prometheus_listener_port)
                 .and_then(|x| Ok((*x).try_into()?))
                 .context("prometheus_listener_port")?,
@@ -150,6 +151,7 @@ impl ProtoRepr for proto::ProverGateway {
         Self {
             api_url: Some(this.api_url.clone()),
             api_poll_duration_secs: Some(this.api_poll_duration_secs.into()),
+            streaming_enabled: Some(this.streaming_enabled),
             prometheus_listener_port: Some(this.prometheus_listener_port.into()),
             prometheus_pushgateway_url: Some(this.prometheus_pushgateway_url.clone()),
             prometheus_push_interval_ms: this.prometheus_push_interval_ms,
@@ -193,6 +195,9 @@ impl ProtoRepr for proto::WitnessGenerator {
                 .map(|x| x.try_into())
                 .transpose()
                 .context("scheduler_generation_timeout_in_secs")?,
+            artifacts_memory_budget_mb: self.artifacts_memory_budget_mb,
+            artifacts_disk_budget_mb: self.artifacts_disk_budget_mb,
+            spill_dir: self.spill_dir.clone(),
         })
     }
 
@@ -213,6 +218,9 @@ impl ProtoRepr for proto::WitnessGenerator {
             scheduler_generation_timeout_in_secs: this
                 .scheduler_generation_timeout_in_secs
                 .map(|x| x.into()),
+            artifacts_memory_budget_mb: this.artifacts_memory_budget_mb,
+            artifacts_disk_budget_mb: this.artifacts_disk_budget_mb,
+            spill_dir: this.spill_dir.clone(),
         }
     }
 }
@@ -42,6 +42,8 @@ fn test_encoding() {
     test_encode_all_formats::<ReprConv<proto::prover::ProofDataHandler>>(rng);
     test_encode_all_formats::<ReprConv<proto::snapshot_creator::SnapshotsCreator>>(rng);
     test_encode_all_formats::<ReprConv<proto::observability::Observability>>(rng);
+    test_encode_all_formats::<ReprConv<proto::vm_runner::ProtectiveReadsVerifier>>(rng);
+    test_encode_all_formats::<ReprConv<proto::vm_runner::VmReplay>>(rng);
 }
 
 pub fn decode_yaml_repr<T: ProtoRepr>(
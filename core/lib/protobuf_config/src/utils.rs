@@ -1,9 +1,29 @@
 use anyhow::Context as _;
-use zksync_config::configs::PrometheusConfig;
+use zksync_config::configs::{PrometheusConfig, PrometheusExportMode};
 use zksync_protobuf::{repr::ProtoRepr, required};
 
 use crate::proto::utils as proto;
 
+impl proto::PrometheusExportMode {
+    fn new(x: &PrometheusExportMode) -> Self {
+        use PrometheusExportMode as From;
+        match x {
+            From::Pull => Self::Pull,
+            From::Push => Self::Push,
+            From::Otlp => Self::Otlp,
+        }
+    }
+
+    fn parse(&self) -> PrometheusExportMode {
+        use PrometheusExportMode as To;
+        match self {
+            Self::Pull => To::Pull,
+            Self::Push => To::Push,
+            Self::Otlp => To::Otlp,
+        }
+    }
+}
+
 impl ProtoRepr for proto::Prometheus {
     type Type = PrometheusConfig;
     fn read(&self) -> anyhow::Result<Self::Type> {
@@ -15,6 +35,14 @@ impl ProtoRepr for proto::Prometheus {
                 .context("pushgateway_url")?
                 .clone(),
             push_interval_ms: self.push_interval_ms,
+            export_mode: self
+                .export_mode
+                .map(proto::PrometheusExportMode::try_from)
+                .transpose()
+                .context("export_mode")?
+                .map_or_else(PrometheusExportMode::default, |mode| mode.parse()),
+            otlp_endpoint: self.otlp_endpoint.clone(),
+            otlp_export_interval_ms: self.otlp_export_interval_ms,
         })
     }
 
@@ -23,6 +51,9 @@ impl ProtoRepr for proto::Prometheus {
             listener_port: Some(this.listener_port.into()),
             pushgateway_url: Some(this.pushgateway_url.clone()),
             push_interval_ms: this.push_interval_ms,
+            export_mode: Some(proto::PrometheusExportMode::new(&this.export_mode).into()),
+            otlp_endpoint: this.otlp_endpoint.clone(),
+            otlp_export_interval_ms: this.otlp_export_interval_ms,
         }
     }
 }
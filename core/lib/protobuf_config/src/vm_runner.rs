@@ -0,0 +1,80 @@
+use anyhow::Context as _;
+use zksync_basic_types::L1BatchNumber;
+use zksync_config::configs;
+use zksync_protobuf::{repr::ProtoRepr, required};
+
+use crate::proto::vm_runner as proto;
+
+impl ProtoRepr for proto::ProtectiveReadsVerifier {
+    type Type = configs::ProtectiveReadsVerifierConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            db_path: required(&self.db_path).context("db_path")?.clone(),
+            window_size: *required(&self.window_size).context("window_size")?,
+            first_processed_batch: L1BatchNumber(
+                *required(&self.first_processed_batch).context("first_processed_batch")?,
+            ),
+            max_concurrent_batches: self
+                .max_concurrent_batches
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_concurrent_batches")?,
+            execution_memory_budget_mb: self
+                .execution_memory_budget_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("execution_memory_budget_mb")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            db_path: Some(this.db_path.clone()),
+            window_size: Some(this.window_size),
+            first_processed_batch: Some(this.first_processed_batch.0),
+            max_concurrent_batches: this.max_concurrent_batches.map(|x| x.try_into().unwrap()),
+            execution_memory_budget_mb: this
+                .execution_memory_budget_mb
+                .map(|x| x.try_into().unwrap()),
+        }
+    }
+}
+
+impl ProtoRepr for proto::VmReplay {
+    type Type = configs::VmReplayConfig;
+
+    fn read(&self) -> anyhow::Result<Self::Type> {
+        Ok(Self::Type {
+            db_path: required(&self.db_path).context("db_path")?.clone(),
+            window_size: *required(&self.window_size).context("window_size")?,
+            first_l1_batch: L1BatchNumber(
+                *required(&self.first_l1_batch).context("first_l1_batch")?,
+            ),
+            last_l1_batch: L1BatchNumber(*required(&self.last_l1_batch).context("last_l1_batch")?),
+            max_concurrent_batches: self
+                .max_concurrent_batches
+                .map(|x| x.try_into())
+                .transpose()
+                .context("max_concurrent_batches")?,
+            execution_memory_budget_mb: self
+                .execution_memory_budget_mb
+                .map(|x| x.try_into())
+                .transpose()
+                .context("execution_memory_budget_mb")?,
+        })
+    }
+
+    fn build(this: &Self::Type) -> Self {
+        Self {
+            db_path: Some(this.db_path.clone()),
+            window_size: Some(this.window_size),
+            first_l1_batch: Some(this.first_l1_batch.0),
+            last_l1_batch: Some(this.last_l1_batch.0),
+            max_concurrent_batches: this.max_concurrent_batches.map(|x| x.try_into().unwrap()),
+            execution_memory_budget_mb: this
+                .execution_memory_budget_mb
+                .map(|x| x.try_into().unwrap()),
+        }
+    }
+}
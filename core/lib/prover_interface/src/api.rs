@@ -5,11 +5,17 @@ use serde::{Deserialize, Serialize};
 use zksync_types::{
     basic_fri_types::Eip4844Blobs,
     protocol_version::{L1VerifierConfig, ProtocolVersionId},
-    L1BatchNumber,
+    L1BatchNumber, H256,
 };
 
 use crate::{inputs::PrepareBasicCircuitsJob, outputs::L1BatchProofForL1};
 
+/// Header with the hex-encoded SHA-256 digest of the request / response body.
+pub const CONTENT_SHA256_HEADER: &str = "x-content-sha256";
+
+/// Path to the WebSocket endpoint of the streaming protocol.
+pub const PROOF_STREAM_PATH: &str = "/proof_stream";
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ProofGenerationData {
     pub l1_batch_number: L1BatchNumber,
@@ -40,3 +46,45 @@ pub enum SubmitProofResponse {
     Success,
     Error(String),
 }
+
+/// Starts or resumes a chunked upload of a JSON-serialized [`SubmitProofRequest`].
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StartProofUploadRequest {
+    pub total_size: u64,
+    /// SHA-256 digest of the entire serialized request.
+    pub sha256: H256,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ProofUploadResponse {
+    /// Number of bytes received so far; the next chunk must start at this offset.
+    Success {
+        received_bytes: u64,
+    },
+    Error(String),
+}
+
+/// Message sent by the server over a streaming connection. Messages are JSON-serialized and sent as text frames.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum ServerStreamMessage {
+    /// New proof generation job. The server doesn't push the next job until this one is acknowledged
+    /// with [`GatewayStreamMessage::JobAck`].
+    Job(Box<ProofGenerationData>),
+    /// Acknowledges a proof submitted with [`GatewayStreamMessage::SubmitProof`].
+    ProofAck {
+        l1_batch_number: L1BatchNumber,
+        response: SubmitProofResponse,
+    },
+}
+
+/// Message sent by the prover gateway over a streaming connection. Messages are JSON-serialized and sent as text frames.
+#[derive(Debug, Serialize, Deserialize)]
+pub enum GatewayStreamMessage {
+    /// Acknowledges that the job for the specified L1 batch pushed via [`ServerStreamMessage::Job`] was processed.
+    JobAck { l1_batch_number: L1BatchNumber },
+    /// Submits a proof; equivalent to the `/submit_proof` HTTP endpoint.
+    SubmitProof {
+        l1_batch_number: L1BatchNumber,
+        request: SubmitProofRequest,
+    },
+}
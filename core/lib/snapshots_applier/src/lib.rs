@@ -1,6 +1,15 @@
 //! Logic for applying application-level snapshots to Postgres storage.
 
-use std::{collections::HashMap, fmt, num::NonZeroUsize, sync::Arc, time::Duration};
+use std::{
+    collections::HashMap,
+    fmt,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
@@ -8,7 +17,9 @@ use serde::Serialize;
 use tokio::sync::Semaphore;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError, SqlxError};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
-use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_object_store::{
+    serialized_object_checksum, ObjectStore, ObjectStoreError, StoredObject,
+};
 use zksync_types::{
     api,
     snapshots::{
@@ -26,20 +37,34 @@ use zksync_web3_decl::{
     namespaces::{EnNamespaceClient, SnapshotsNamespaceClient, ZksNamespaceClient},
 };
 
-use self::metrics::{InitialStage, StorageLogsChunksStage, METRICS};
+pub use self::verification::{SnapshotDiscrepancy, SnapshotVerificationReport};
+use self::{
+    metrics::{InitialStage, RecoveryPhase, StorageLogsChunksStage, METRICS},
+    verification::SnapshotsVerifier,
+};
 
 mod metrics;
 #[cfg(test)]
 mod tests;
+mod verification;
 
 #[derive(Debug, Serialize)]
 struct SnapshotsApplierHealthDetails {
     snapshot_l2_block: L2BlockNumber,
     snapshot_l1_batch: L1BatchNumber,
+    phase: RecoveryPhase,
     factory_deps_recovered: bool,
     storage_logs_chunk_count: usize,
     storage_logs_chunks_left_to_process: usize,
     tokens_recovered: bool,
+    /// Total size of snapshot objects downloaded in the current recovery attempt.
+    downloaded_bytes: u64,
+    /// Average download throughput since the start of the current recovery attempt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    download_bytes_per_second: Option<u64>,
+    /// Estimated time left to recover storage logs, based on the rate of chunk processing in the current attempt.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    storage_logs_eta_sec: Option<u64>,
 }
 
 impl SnapshotsApplierHealthDetails {
@@ -54,10 +79,14 @@ impl SnapshotsApplierHealthDetails {
         Ok(Self {
             snapshot_l2_block: status.l2_block_number,
             snapshot_l1_batch: status.l1_batch_number,
+            phase: RecoveryPhase::Done,
             factory_deps_recovered: true,
             storage_logs_chunk_count: status.storage_logs_chunks_processed.len(),
             storage_logs_chunks_left_to_process: 0,
             tokens_recovered: true,
+            downloaded_bytes: 0,
+            download_bytes_per_second: None,
+            storage_logs_eta_sec: None,
         })
     }
 
@@ -79,7 +108,9 @@ enum SnapshotsApplierError {
 impl SnapshotsApplierError {
     fn object_store(err: ObjectStoreError, context: String) -> Self {
         match err {
-            ObjectStoreError::KeyNotFound(_) | ObjectStoreError::Serialization(_) => {
+            ObjectStoreError::KeyNotFound(_)
+            | ObjectStoreError::Serialization(_)
+            | ObjectStoreError::Corrupted(_) => {
                 Self::Fatal(anyhow::Error::from(err).context(context))
             }
             ObjectStoreError::Other(_) => {
@@ -128,6 +159,11 @@ pub trait SnapshotsApplierMainNodeClient: fmt::Debug + Send + Sync {
 
     async fn fetch_newest_snapshot(&self) -> EnrichedClientResult<Option<SnapshotHeader>>;
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>>;
+
     async fn fetch_tokens(
         &self,
         at_l2_block: L2BlockNumber,
@@ -164,9 +200,16 @@ impl SnapshotsApplierMainNodeClient for Box<DynClient<L2>> {
         let Some(newest_snapshot) = snapshots.snapshots_l1_batch_numbers.first() else {
             return Ok(None);
         };
-        self.get_snapshot_by_l1_batch_number(*newest_snapshot)
+        self.fetch_snapshot(*newest_snapshot).await
+    }
+
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        self.get_snapshot_by_l1_batch_number(l1_batch_number)
             .rpc_context("get_snapshot_by_l1_batch_number")
-            .with_arg("number", newest_snapshot)
+            .with_arg("number", &l1_batch_number)
             .await
     }
 
@@ -230,7 +273,8 @@ pub struct SnapshotsApplierTask {
     health_updater: HealthUpdater,
     connection_pool: ConnectionPool<Core>,
     main_node_client: Box<dyn SnapshotsApplierMainNodeClient>,
-    blob_store: Arc<dyn ObjectStore>,
+    /// Object stores to fetch snapshot data from, in the order of preference.
+    blob_stores: Vec<Arc<dyn ObjectStore>>,
 }
 
 impl SnapshotsApplierTask {
@@ -245,10 +289,21 @@ impl SnapshotsApplierTask {
             health_updater: ReactiveHealthCheck::new("snapshot_recovery").1,
             connection_pool,
             main_node_client,
-            blob_store,
+            blob_stores: vec![blob_store],
         }
     }
 
+    /// Adds fallback object stores (e.g., mirrors of the main store). If a snapshot object cannot be fetched
+    /// from a store or is corrupted, it is fetched from the next store in order.
+    #[must_use]
+    pub fn with_fallback_blob_stores(
+        mut self,
+        blob_stores: impl IntoIterator<Item = Arc<dyn ObjectStore>>,
+    ) -> Self {
+        self.blob_stores.extend(blob_stores);
+        self
+    }
+
     /// Returns the health check for snapshot recovery.
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
@@ -271,7 +326,7 @@ impl SnapshotsApplierTask {
             let result = SnapshotsApplier::load_snapshot(
                 &self.connection_pool,
                 self.main_node_client.as_ref(),
-                &self.blob_store,
+                &self.blob_stores,
                 &self.health_updater,
                 self.config.max_concurrency.get(),
             )
@@ -279,6 +334,8 @@ impl SnapshotsApplierTask {
 
             match result {
                 Ok((strategy, final_status)) => {
+                    METRICS.set_phase(RecoveryPhase::Done);
+                    METRICS.storage_logs_eta.set(Duration::ZERO);
                     let health_details = SnapshotsApplierHealthDetails::done(&final_status)?;
                     self.health_updater
                         .update(Health::from(HealthStatus::Ready).with_details(health_details));
@@ -310,6 +367,29 @@ impl SnapshotsApplierTask {
         tracing::error!("Snapshot recovery run out of retries; last error: {last_error:?}");
         Err(last_error)
     }
+
+    /// Verifies the node storage recovered from a snapshot against the snapshot data without writing anything.
+    /// Snapshot objects are re-fetched from the object stores and compared with factory deps, storage logs
+    /// and initial writes in Postgres; snapshot recovery metadata (including the Merkle tree root hash
+    /// the tree is recovered to) is compared with the data returned by the main node. The node Merkle tree
+    /// is not accessed; it can be checked separately using [`SnapshotVerificationReport::check_tree_root_hash()`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the node wasn't recovered from a snapshot, recovery isn't completed, or verification
+    /// cannot proceed (e.g., snapshot objects cannot be fetched). Discrepancies are not errors; they are
+    /// returned in the report.
+    pub async fn verify(&self) -> anyhow::Result<SnapshotVerificationReport> {
+        let verifier = SnapshotsVerifier {
+            connection_pool: &self.connection_pool,
+            main_node_client: self.main_node_client.as_ref(),
+            blob_stores: &self.blob_stores,
+            max_concurrency: self.config.max_concurrency.get(),
+        };
+        verifier.verify().await.map_err(|err| match err {
+            SnapshotsApplierError::Fatal(err) | SnapshotsApplierError::Retryable(err) => err,
+        })
+    }
 }
 
 /// Strategy determining how snapshot recovery should proceed.
@@ -444,17 +524,143 @@ impl SnapshotRecoveryStrategy {
     }
 }
 
+/// Fetches checksums of storage log chunks for the snapshot with the specified `status` from the main node.
+/// Checksums are indexed by chunk ID; a checksum is `None` if it's not provided by the main node (e.g., because
+/// the snapshot was created by an older node version).
+async fn fetch_storage_logs_checksums(
+    main_node_client: &dyn SnapshotsApplierMainNodeClient,
+    status: &SnapshotRecoveryStatus,
+) -> Result<Vec<Option<H256>>, SnapshotsApplierError> {
+    let l1_batch_number = status.l1_batch_number;
+    let mut checksums = vec![None; status.storage_logs_chunks_processed.len()];
+    let Some(snapshot) = main_node_client.fetch_snapshot(l1_batch_number).await? else {
+        tracing::warn!(
+            "Snapshot for L1 batch #{l1_batch_number} is not available on main node; \
+             storage log chunks will not be verified against checksums"
+        );
+        return Ok(checksums);
+    };
+
+    for chunk in snapshot.storage_logs_chunks {
+        let chunk_id = chunk.chunk_id;
+        let checksum = usize::try_from(chunk_id)
+            .ok()
+            .and_then(|idx| checksums.get_mut(idx))
+            .with_context(|| {
+                format!(
+                    "snapshot returned by main node has unexpected storage logs chunk #{chunk_id}"
+                )
+            })?;
+        *checksum = chunk.checksum;
+    }
+    let missing_count = checksums
+        .iter()
+        .filter(|checksum| checksum.is_none())
+        .count();
+    if missing_count > 0 {
+        tracing::warn!(
+            "Main node doesn't provide checksums for {missing_count} storage log chunk(s); \
+             these chunks will not be verified against checksums"
+        );
+    }
+    Ok(checksums)
+}
+
+/// Fetches an object from the object stores, trying them in order. If the object cannot be fetched from a store,
+/// or it is corrupted (i.e., doesn't match the `checksum` provided by the main node, fails deserialization or
+/// `validate_fn`), the object is fetched from the next store.
+async fn fetch_object<V: StoredObject>(
+    blob_stores: &[Arc<dyn ObjectStore>],
+    downloaded_bytes: &AtomicU64,
+    key: V::Key<'_>,
+    checksum: Option<H256>,
+    validate_fn: impl Fn(&V) -> anyhow::Result<()>,
+) -> Result<V, SnapshotsApplierError> {
+    let mut errors = Vec::with_capacity(blob_stores.len());
+    let encoded_key = V::encode_key(key);
+    for (i, blob_store) in blob_stores.iter().enumerate() {
+        let fetch_result = blob_store.get_raw(V::BUCKET, &encoded_key).await;
+        let fetch_result = fetch_result.and_then(|bytes| {
+            let byte_count = bytes.len() as u64;
+            downloaded_bytes.fetch_add(byte_count, Ordering::Relaxed);
+            METRICS.downloaded.inc_by(byte_count);
+            if let Some(expected_checksum) = checksum {
+                let actual_checksum = serialized_object_checksum(&bytes);
+                if actual_checksum != expected_checksum {
+                    // Retrying the same store is unlikely to help; the object is fetched from the next store instead.
+                    let err = format!(
+                        "checksum mismatch: expected {expected_checksum:?}, got {actual_checksum:?}"
+                    );
+                    return Err(ObjectStoreError::Corrupted(err.into()));
+                }
+            }
+            V::deserialize(bytes).map_err(ObjectStoreError::Serialization)
+        });
+        let result = match fetch_result {
+            Ok(value) => validate_fn(&value).map(|()| value).map_err(|err| {
+                let context = format!("invalid object `{encoded_key}` in object store #{i}");
+                SnapshotsApplierError::Fatal(err.context(context))
+            }),
+            Err(err) => {
+                let context = format!("cannot fetch `{encoded_key}` from object store #{i}");
+                Err(SnapshotsApplierError::object_store(err, context))
+            }
+        };
+        match result {
+            Ok(value) => return Ok(value),
+            Err(err) => {
+                if i + 1 < blob_stores.len() {
+                    tracing::warn!("{err:#}; falling back to the next object store");
+                }
+                errors.push(err);
+            }
+        }
+    }
+
+    // If any of the stores has returned a transient error, recovery can succeed on retry.
+    let retryable_idx = errors
+        .iter()
+        .position(|err| matches!(err, SnapshotsApplierError::Retryable(_)));
+    Err(errors.swap_remove(retryable_idx.unwrap_or(0)))
+}
+
+/// Performs basic sanity check for a storage logs chunk.
+fn validate_storage_logs_chunk(
+    storage_logs: &[SnapshotStorageLog],
+    snapshot_l1_batch: L1BatchNumber,
+) -> anyhow::Result<()> {
+    for log in storage_logs {
+        anyhow::ensure!(
+            log.enumeration_index > 0,
+            "invalid storage log with zero enumeration_index: {log:?}"
+        );
+        anyhow::ensure!(
+            log.l1_batch_number_of_initial_write <= snapshot_l1_batch,
+            "invalid storage log with `l1_batch_number_of_initial_write` from the future: {log:?}"
+        );
+    }
+    Ok(())
+}
+
 /// Applying application-level storage snapshots to the Postgres storage.
 #[derive(Debug)]
 struct SnapshotsApplier<'a> {
     connection_pool: &'a ConnectionPool<Core>,
     main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
-    blob_store: &'a dyn ObjectStore,
+    blob_stores: &'a [Arc<dyn ObjectStore>],
     applied_snapshot_status: SnapshotRecoveryStatus,
     health_updater: &'a HealthUpdater,
     max_concurrency: usize,
     factory_deps_recovered: bool,
     tokens_recovered: bool,
+    /// Start of the current recovery attempt.
+    started_at: Instant,
+    /// Total size of snapshot objects downloaded in the current recovery attempt.
+    downloaded_bytes: AtomicU64,
+    /// Number of storage log chunks processed in the current recovery attempt.
+    processed_chunk_count: AtomicUsize,
+    /// Checksums of storage log chunks provided by the main node, indexed by chunk ID.
+    storage_logs_checksums: Vec<Option<H256>>,
 }
 
 impl<'a> SnapshotsApplier<'a> {
@@ -462,13 +668,14 @@ impl<'a> SnapshotsApplier<'a> {
     async fn load_snapshot(
         connection_pool: &'a ConnectionPool<Core>,
         main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
-        blob_store: &'a dyn ObjectStore,
+        blob_stores: &'a [Arc<dyn ObjectStore>],
         health_updater: &'a HealthUpdater,
         max_concurrency: usize,
     ) -> Result<(SnapshotRecoveryStrategy, SnapshotRecoveryStatus), SnapshotsApplierError> {
         // While the recovery is in progress, the node is healthy (no error has occurred),
         // but is affected (its usual APIs don't work).
         health_updater.update(HealthStatus::Affected.into());
+        METRICS.set_phase(RecoveryPhase::FetchingMetadata);
 
         let mut storage = connection_pool
             .connection_tagged("snapshots_applier")
@@ -483,16 +690,23 @@ impl<'a> SnapshotsApplier<'a> {
             SnapshotRecoveryStrategy::New => true,
             SnapshotRecoveryStrategy::Resumed => false,
         };
+        // Checksums are not persisted, so they are re-fetched when resuming recovery.
+        let storage_logs_checksums =
+            fetch_storage_logs_checksums(main_node_client, &applied_snapshot_status).await?;
 
         let mut this = Self {
             connection_pool,
             main_node_client,
-            blob_store,
+            blob_stores,
             applied_snapshot_status,
             health_updater,
             max_concurrency,
             factory_deps_recovered: !created_from_scratch,
             tokens_recovered: false,
+            started_at: Instant::now(),
+            downloaded_bytes: AtomicU64::new(0),
+            processed_chunk_count: AtomicUsize::new(0),
+            storage_logs_checksums,
         };
 
         METRICS.storage_logs_chunks_count.set(
@@ -547,17 +761,48 @@ impl<'a> SnapshotsApplier<'a> {
     }
 
     fn update_health(&self) {
+        // We don't use `self.applied_snapshot_status` here because it's not updated during recovery
+        let chunks_left = METRICS.storage_logs_chunks_left_to_process.get();
+        let phase = if !self.factory_deps_recovered {
+            RecoveryPhase::RecoveringFactoryDeps
+        } else if chunks_left > 0 {
+            RecoveryPhase::RecoveringStorageLogs
+        } else if !self.tokens_recovered {
+            RecoveryPhase::RecoveringTokens
+        } else {
+            RecoveryPhase::Done
+        };
+        METRICS.set_phase(phase);
+
+        let elapsed = self.started_at.elapsed();
+        let downloaded_bytes = self.downloaded_bytes.load(Ordering::Relaxed);
+        let download_bytes_per_second = (elapsed.as_secs() > 0)
+            .then(|| (downloaded_bytes as f64 / elapsed.as_secs_f64()) as u64);
+        if let Some(throughput) = download_bytes_per_second {
+            METRICS.download_throughput.set(throughput);
+        }
+
+        let processed_chunk_count = self.processed_chunk_count.load(Ordering::Relaxed);
+        let storage_logs_eta = (processed_chunk_count > 0)
+            .then(|| elapsed.mul_f64(chunks_left as f64 / processed_chunk_count as f64));
+        if let Some(eta) = storage_logs_eta {
+            METRICS.storage_logs_eta.set(eta);
+        }
+
         let details = SnapshotsApplierHealthDetails {
             snapshot_l2_block: self.applied_snapshot_status.l2_block_number,
             snapshot_l1_batch: self.applied_snapshot_status.l1_batch_number,
+            phase,
             factory_deps_recovered: self.factory_deps_recovered,
             tokens_recovered: self.tokens_recovered,
             storage_logs_chunk_count: self
                 .applied_snapshot_status
                 .storage_logs_chunks_processed
                 .len(),
-            // We don't use `self.applied_snapshot_status` here because it's not updated during recovery
-            storage_logs_chunks_left_to_process: METRICS.storage_logs_chunks_left_to_process.get(),
+            storage_logs_chunks_left_to_process: chunks_left,
+            downloaded_bytes,
+            download_bytes_per_second,
+            storage_logs_eta_sec: storage_logs_eta.map(|eta| eta.as_secs()),
         };
         let status = if details.is_done() {
             HealthStatus::Ready
@@ -568,6 +813,22 @@ impl<'a> SnapshotsApplier<'a> {
             .update(Health::from(status).with_details(details));
     }
 
+    async fn fetch_object<V: StoredObject>(
+        &self,
+        key: V::Key<'_>,
+        checksum: Option<H256>,
+        validate_fn: impl Fn(&V) -> anyhow::Result<()>,
+    ) -> Result<V, SnapshotsApplierError> {
+        fetch_object(
+            self.blob_stores,
+            &self.downloaded_bytes,
+            key,
+            checksum,
+            validate_fn,
+        )
+        .await
+    }
+
     async fn recover_factory_deps(
         &mut self,
         storage: &mut Connection<'_, Core>,
@@ -577,12 +838,7 @@ impl<'a> SnapshotsApplier<'a> {
         tracing::debug!("Fetching factory dependencies from object store");
         let l1_batch_number = self.applied_snapshot_status.l1_batch_number;
         let factory_deps: SnapshotFactoryDependencies =
-            self.blob_store.get(l1_batch_number).await.map_err(|err| {
-                let context = format!(
-                    "cannot fetch factory deps for L1 batch #{l1_batch_number} from object store"
-                );
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+            self.fetch_object(l1_batch_number, None, |_| Ok(())).await?;
         tracing::debug!(
             "Fetched {} factory dependencies from object store",
             factory_deps.factory_deps.len()
@@ -651,14 +907,14 @@ impl<'a> SnapshotsApplier<'a> {
             chunk_id,
             l1_batch_number: self.applied_snapshot_status.l1_batch_number,
         };
-        let storage_snapshot_chunk: SnapshotStorageLogsChunk =
-            self.blob_store.get(storage_key).await.map_err(|err| {
-                let context =
-                    format!("cannot fetch storage logs {storage_key:?} from object store");
-                SnapshotsApplierError::object_store(err, context)
-            })?;
+        let checksum = self.storage_logs_checksums[chunk_id as usize];
+        let storage_snapshot_chunk: SnapshotStorageLogsChunk = self
+            .fetch_object(storage_key, checksum, |chunk: &SnapshotStorageLogsChunk| {
+                let snapshot_l1_batch = self.applied_snapshot_status.l1_batch_number;
+                validate_storage_logs_chunk(&chunk.storage_logs, snapshot_l1_batch)
+            })
+            .await?;
         let storage_logs = &storage_snapshot_chunk.storage_logs;
-        self.validate_storage_logs_chunk(storage_logs)?;
         let latency = latency.observe();
         tracing::info!(
             "Loaded {} storage logs from GCS for chunk {chunk_id} in {latency:?}",
@@ -687,30 +943,14 @@ impl<'a> SnapshotsApplier<'a> {
         storage_transaction.commit().await?;
 
         let chunks_left = METRICS.storage_logs_chunks_left_to_process.dec_by(1) - 1;
+        self.processed_chunk_count.fetch_add(1, Ordering::Relaxed);
+        self.update_health();
         let latency = latency.observe();
         tracing::info!("Saved storage logs for chunk {chunk_id} in {latency:?}, there are {chunks_left} left to process");
 
         Ok(())
     }
 
-    /// Performs basic sanity check for a storage logs chunk.
-    fn validate_storage_logs_chunk(
-        &self,
-        storage_logs: &[SnapshotStorageLog],
-    ) -> anyhow::Result<()> {
-        for log in storage_logs {
-            anyhow::ensure!(
-                log.enumeration_index > 0,
-                "invalid storage log with zero enumeration_index: {log:?}"
-            );
-            anyhow::ensure!(
-                log.l1_batch_number_of_initial_write <= self.applied_snapshot_status.l1_batch_number,
-                "invalid storage log with `l1_batch_number_of_initial_write` from the future: {log:?}"
-            );
-        }
-        Ok(())
-    }
-
     async fn recover_storage_logs(&self) -> Result<(), SnapshotsApplierError> {
         let effective_concurrency =
             (self.connection_pool.max_size() as usize).min(self.max_concurrency);
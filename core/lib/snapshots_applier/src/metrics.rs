@@ -2,7 +2,10 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit};
+use serde::Serialize;
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
@@ -18,6 +21,28 @@ pub(crate) enum InitialStage {
     ApplyFactoryDeps,
 }
 
+/// Phase of snapshot recovery.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet, Serialize)]
+#[metrics(label = "phase", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RecoveryPhase {
+    FetchingMetadata,
+    RecoveringFactoryDeps,
+    RecoveringStorageLogs,
+    RecoveringTokens,
+    Done,
+}
+
+impl RecoveryPhase {
+    const ALL: [Self; 5] = [
+        Self::FetchingMetadata,
+        Self::RecoveringFactoryDeps,
+        Self::RecoveringStorageLogs,
+        Self::RecoveringTokens,
+        Self::Done,
+    ];
+}
+
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "snapshots_applier")]
 pub(crate) struct SnapshotsApplierMetrics {
@@ -26,6 +51,16 @@ pub(crate) struct SnapshotsApplierMetrics {
 
     /// Number of chunks left to apply.
     pub storage_logs_chunks_left_to_process: Gauge<usize>,
+    /// Current phase of recovery: 1 for the current phase, 0 for all other phases.
+    pub phase: Family<RecoveryPhase, Gauge<u64>>,
+    /// Total size of snapshot objects downloaded from the object store.
+    #[metrics(unit = Unit::Bytes)]
+    pub downloaded: Counter,
+    /// Average download throughput (in bytes per second) since the start of the current recovery attempt.
+    pub download_throughput: Gauge<u64>,
+    /// Estimated time left to recover storage logs.
+    #[metrics(unit = Unit::Seconds)]
+    pub storage_logs_eta: Gauge<Duration>,
 
     /// Total latency of applying snapshot.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
@@ -40,5 +75,13 @@ pub(crate) struct SnapshotsApplierMetrics {
     pub storage_logs_chunks_duration: Family<StorageLogsChunksStage, Histogram<Duration>>,
 }
 
+impl SnapshotsApplierMetrics {
+    pub fn set_phase(&self, phase: RecoveryPhase) {
+        for other_phase in RecoveryPhase::ALL {
+            self.phase[&other_phase].set(u64::from(other_phase == phase));
+        }
+    }
+}
+
 #[vise::register]
 pub(crate) static METRICS: vise::Global<SnapshotsApplierMetrics> = vise::Global::new();
@@ -9,7 +9,7 @@ use assert_matches::assert_matches;
 use test_casing::test_casing;
 use tokio::sync::Barrier;
 use zksync_health_check::CheckHealth;
-use zksync_object_store::ObjectStoreFactory;
+use zksync_object_store::{Bucket, ObjectStoreFactory};
 use zksync_types::{
     api::{BlockDetails, L1BatchDetails},
     block::L1BatchHeader,
@@ -67,10 +67,11 @@ async fn snapshots_creator_can_successfully_recover_db(
     let task_health = task.health_check();
     let stats = task.run().await.unwrap();
     assert!(stats.done_work);
-    assert_matches!(
-        task_health.check_health().await.status(),
-        HealthStatus::Ready
-    );
+    let health = task_health.check_health().await;
+    assert_matches!(health.status(), HealthStatus::Ready);
+    let health_details = health.details().unwrap();
+    assert_eq!(health_details["phase"], "done");
+    assert_eq!(health_details["storage_logs_chunks_left_to_process"], 0);
 
     let mut storage = pool.connection().await.unwrap();
     let mut recovery_dal = storage.snapshot_recovery_dal();
@@ -167,6 +168,14 @@ async fn health_status_immediately_after_task_start() {
             future::pending().await
         }
 
+        async fn fetch_snapshot(
+            &self,
+            _l1_batch_number: L1BatchNumber,
+        ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+            self.0.wait().await;
+            future::pending().await
+        }
+
         async fn fetch_tokens(
             &self,
             _at_l2_block: L2BlockNumber,
@@ -333,6 +342,94 @@ async fn applier_returns_error_after_too_many_object_store_retries() {
     }));
 }
 
+#[tokio::test]
+async fn applier_falls_back_to_next_object_store() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (fallback_object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    // The primary store misses most objects and has a corrupted storage logs chunk.
+    let object_store = ObjectStoreFactory::mock().create_store().await;
+    let corrupted_chunk_key = SnapshotStorageLogsChunk::encode_key(SnapshotStorageLogsStorageKey {
+        l1_batch_number: expected_status.l1_batch_number,
+        chunk_id: 0,
+    });
+    object_store
+        .put_raw(
+            Bucket::StorageSnapshot,
+            &corrupted_chunk_key,
+            b"corrupted".to_vec(),
+        )
+        .await
+        .unwrap();
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client),
+        object_store,
+    )
+    .with_fallback_blob_stores([fallback_object_store]);
+    task.run().await.unwrap();
+
+    let mut storage = pool.connection().await.unwrap();
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+}
+
+#[tokio::test]
+async fn applier_verifies_storage_logs_chunk_checksums() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 100);
+    let (fallback_object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+    let (object_store, _) = prepare_clients(&expected_status, &storage_logs).await;
+
+    // Substitute a storage logs chunk in the primary store with a valid chunk; this can only be detected
+    // by comparing checksums.
+    let chunk_key = SnapshotStorageLogsStorageKey {
+        l1_batch_number: expected_status.l1_batch_number,
+        chunk_id: 0,
+    };
+    let substituted_chunk = SnapshotStorageLogsChunk {
+        storage_logs: random_storage_logs(expected_status.l1_batch_number, 10),
+    };
+    object_store
+        .put(chunk_key, &substituted_chunk)
+        .await
+        .unwrap();
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client.clone()),
+        object_store.clone(),
+    );
+    let err = task.run().await.unwrap_err();
+    assert!(format!("{err:#}").contains("checksum mismatch"), "{err:#}");
+
+    // Recovery is resumed using the fallback store.
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client),
+        object_store,
+    )
+    .with_fallback_blob_stores([fallback_object_store]);
+    task.run().await.unwrap();
+
+    let mut storage = pool.connection().await.unwrap();
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+}
+
 #[tokio::test]
 async fn recovering_tokens() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -389,3 +486,85 @@ async fn recovering_tokens() {
     );
     task.run().await.unwrap();
 }
+
+#[tokio::test]
+async fn verifying_recovered_storage() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let expected_status = mock_recovery_status();
+    let storage_logs = random_storage_logs(expected_status.l1_batch_number, 200);
+    let (object_store, client) = prepare_clients(&expected_status, &storage_logs).await;
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client.clone()),
+        object_store.clone(),
+    );
+    // Verification is impossible before recovery.
+    task.verify().await.unwrap_err();
+    task.run().await.unwrap();
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(client.clone()),
+        object_store,
+    );
+    let mut report = task.verify().await.unwrap();
+    assert!(report.is_consistent(), "{report:?}");
+    assert_eq!(report.l1_batch_number, expected_status.l1_batch_number);
+    assert_eq!(report.factory_deps_checked, 1);
+    assert_eq!(report.storage_logs_checked, storage_logs.len() as u64);
+    report.check_tree_root_hash(Some(expected_status.l1_batch_root_hash));
+    assert!(report.is_consistent(), "{report:?}");
+    report.check_tree_root_hash(None);
+    assert_eq!(
+        report.discrepancies,
+        [SnapshotDiscrepancy::TreeRootHashMismatch {
+            expected: expected_status.l1_batch_root_hash,
+            actual: None,
+        }]
+    );
+
+    // Emulate a snapshot with a modified storage log and a main node with a different root hash.
+    let mut modified_logs = storage_logs.clone();
+    modified_logs[0].value = H256::repeat_byte(0xff);
+    let (modified_object_store, mut modified_client) =
+        prepare_clients(&expected_status, &modified_logs).await;
+    let l1_batch_details = modified_client
+        .fetch_l1_batch_responses
+        .get_mut(&expected_status.l1_batch_number)
+        .unwrap();
+    l1_batch_details.base.root_hash = Some(H256::repeat_byte(0xfe));
+
+    let task = SnapshotsApplierTask::new(
+        SnapshotsApplierConfig::for_tests(),
+        pool.clone(),
+        Box::new(modified_client),
+        modified_object_store,
+    );
+    let report = task.verify().await.unwrap();
+    assert!(!report.is_consistent());
+    assert_eq!(report.discrepancy_count, 2, "{report:?}");
+    assert!(report.discrepancies.iter().any(|discrepancy| matches!(
+        discrepancy,
+        SnapshotDiscrepancy::RecoveryStatus {
+            field: "l1_batch_root_hash",
+            ..
+        }
+    )));
+    let expected_discrepancy = SnapshotDiscrepancy::StorageValueMismatch {
+        hashed_key: storage_logs[0].key.hashed_key(),
+        expected: H256::repeat_byte(0xff),
+        actual: storage_logs[0].value,
+    };
+    assert!(report.discrepancies.contains(&expected_discrepancy));
+
+    // Verification must not modify Postgres.
+    let mut storage = pool.connection().await.unwrap();
+    let all_storage_logs = storage
+        .storage_logs_dal()
+        .dump_all_storage_logs_for_tests()
+        .await;
+    assert_eq!(all_storage_logs.len(), storage_logs.len());
+}
@@ -49,6 +49,16 @@ impl SnapshotsApplierMainNodeClient for MockMainNodeClient {
         Ok(self.fetch_newest_snapshot_response.clone())
     }
 
+    async fn fetch_snapshot(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> EnrichedClientResult<Option<SnapshotHeader>> {
+        let snapshot = self.fetch_newest_snapshot_response.as_ref();
+        Ok(snapshot
+            .filter(|snapshot| snapshot.l1_batch_number == l1_batch_number)
+            .cloned())
+    }
+
     async fn fetch_tokens(
         &self,
         _at_l2_block: L2BlockNumber,
@@ -162,6 +172,7 @@ fn l1_batch_details(number: L1BatchNumber, root_hash: H256) -> api::L1BatchDetai
     api::L1BatchDetails {
         number,
         base: block_details_base(root_hash),
+        commitment: None,
     }
 }
 
@@ -227,10 +238,12 @@ pub(super) fn mock_snapshot_header(status: &SnapshotRecoveryStatus) -> SnapshotH
             SnapshotStorageLogsChunkMetadata {
                 chunk_id: 0,
                 filepath: "file0".to_string(),
+                checksum: None,
             },
             SnapshotStorageLogsChunkMetadata {
                 chunk_id: 1,
                 filepath: "file1".to_string(),
+                checksum: None,
             },
         ],
         factory_deps_filepath: "some_filepath".to_string(),
@@ -260,6 +273,7 @@ pub(super) async fn prepare_clients(
         .div_ceil(status.storage_logs_chunks_processed.len());
     assert!(chunk_size > 0);
 
+    let mut snapshot_header = mock_snapshot_header(status);
     for (chunk_id, chunk) in logs.chunks(chunk_size).enumerate() {
         let chunk_storage_logs = SnapshotStorageLogsChunk {
             storage_logs: chunk.to_vec(),
@@ -268,13 +282,14 @@ pub(super) async fn prepare_clients(
             l1_batch_number: status.l1_batch_number,
             chunk_id: chunk_id as u64,
         };
-        object_store
-            .put(chunk_key, &chunk_storage_logs)
+        let (_, checksum) = object_store
+            .put_with_checksum(chunk_key, &chunk_storage_logs)
             .await
             .unwrap();
+        snapshot_header.storage_logs_chunks[chunk_id].checksum = Some(checksum);
     }
 
-    client.fetch_newest_snapshot_response = Some(mock_snapshot_header(status));
+    client.fetch_newest_snapshot_response = Some(snapshot_header);
     client.fetch_l1_batch_responses.insert(
         status.l1_batch_number,
         l1_batch_details(status.l1_batch_number, status.l1_batch_root_hash),
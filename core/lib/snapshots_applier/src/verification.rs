@@ -0,0 +1,377 @@
+//! Verification of a node recovered from a snapshot against the snapshot data.
+
+use std::{
+    collections::HashSet,
+    sync::{atomic::AtomicU64, Arc, Mutex},
+    time::Instant,
+};
+
+use anyhow::Context as _;
+use tokio::sync::Semaphore;
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_object_store::ObjectStore;
+use zksync_types::{
+    snapshots::{
+        SnapshotFactoryDependencies, SnapshotRecoveryStatus, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
+    },
+    L1BatchNumber, L2BlockNumber, H256,
+};
+use zksync_utils::{bytecode::hash_bytecode, h256_to_u256};
+
+use crate::{
+    fetch_object, fetch_storage_logs_checksums, validate_storage_logs_chunk, SnapshotsApplierError,
+    SnapshotsApplierMainNodeClient,
+};
+
+/// Maximum number of discrepancies retained in [`SnapshotVerificationReport::discrepancies`].
+const MAX_REPORTED_DISCREPANCIES: usize = 1_000;
+
+/// Discrepancy between the snapshot data and the node state.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnapshotDiscrepancy {
+    /// Snapshot recovery metadata persisted in Postgres differs from the data returned by the main node.
+    RecoveryStatus {
+        field: &'static str,
+        expected: String,
+        actual: String,
+    },
+    /// Factory dependency from the snapshot is missing in Postgres.
+    MissingFactoryDep { bytecode_hash: H256 },
+    /// Factory dependency in Postgres has a different bytecode than the one in the snapshot.
+    FactoryDepMismatch { bytecode_hash: H256 },
+    /// Storage log from the snapshot is missing in Postgres at the snapshot L2 block.
+    MissingStorageLog { hashed_key: H256 },
+    StorageValueMismatch {
+        hashed_key: H256,
+        expected: H256,
+        actual: H256,
+    },
+    /// Initial write for a snapshot storage log is missing or has a different L1 batch / enumeration index.
+    InitialWriteMismatch {
+        hashed_key: H256,
+        expected: (L1BatchNumber, u64),
+        actual: Option<(L1BatchNumber, u64)>,
+    },
+    /// Postgres contains a different number of storage logs at the snapshot L2 block than the snapshot.
+    StorageLogCountMismatch { expected: u64, actual: u64 },
+    /// Merkle tree root hash at the snapshot L1 batch differs from the snapshot one. `actual` is `None`
+    /// if the tree doesn't contain the snapshot L1 batch (e.g., if tree recovery is not completed).
+    TreeRootHashMismatch {
+        expected: H256,
+        actual: Option<H256>,
+    },
+}
+
+/// Report produced by [`SnapshotsApplierTask::verify()`](crate::SnapshotsApplierTask::verify()).
+#[derive(Debug)]
+pub struct SnapshotVerificationReport {
+    pub l1_batch_number: L1BatchNumber,
+    pub l2_block_number: L2BlockNumber,
+    pub l1_batch_root_hash: H256,
+    pub factory_deps_checked: usize,
+    pub storage_logs_checked: u64,
+    /// Total number of found discrepancies.
+    pub discrepancy_count: u64,
+    /// Found discrepancies. To bound memory usage, only the first 1,000 discrepancies are retained.
+    pub discrepancies: Vec<SnapshotDiscrepancy>,
+}
+
+impl SnapshotVerificationReport {
+    fn new(status: &SnapshotRecoveryStatus) -> Self {
+        Self {
+            l1_batch_number: status.l1_batch_number,
+            l2_block_number: status.l2_block_number,
+            l1_batch_root_hash: status.l1_batch_root_hash,
+            factory_deps_checked: 0,
+            storage_logs_checked: 0,
+            discrepancy_count: 0,
+            discrepancies: vec![],
+        }
+    }
+
+    /// Checks whether the node state is consistent with the snapshot.
+    pub fn is_consistent(&self) -> bool {
+        self.discrepancy_count == 0
+    }
+
+    /// Checks the root hash of the node Merkle tree at the snapshot L1 batch. `None` means that the tree
+    /// doesn't contain the snapshot L1 batch.
+    pub fn check_tree_root_hash(&mut self, actual: Option<H256>) {
+        if actual != Some(self.l1_batch_root_hash) {
+            self.push(SnapshotDiscrepancy::TreeRootHashMismatch {
+                expected: self.l1_batch_root_hash,
+                actual,
+            });
+        }
+    }
+
+    fn push(&mut self, discrepancy: SnapshotDiscrepancy) {
+        tracing::warn!("Found snapshot discrepancy: {discrepancy:?}");
+        self.discrepancy_count += 1;
+        if self.discrepancies.len() < MAX_REPORTED_DISCREPANCIES {
+            self.discrepancies.push(discrepancy);
+        }
+    }
+
+    fn check_field<T: PartialEq + std::fmt::Debug>(
+        &mut self,
+        field: &'static str,
+        expected: T,
+        actual: T,
+    ) {
+        if expected != actual {
+            self.push(SnapshotDiscrepancy::RecoveryStatus {
+                field,
+                expected: format!("{expected:?}"),
+                actual: format!("{actual:?}"),
+            });
+        }
+    }
+}
+
+/// Verifies the storage of a node recovered from a snapshot. Never writes to Postgres.
+#[derive(Debug)]
+pub(crate) struct SnapshotsVerifier<'a> {
+    pub connection_pool: &'a ConnectionPool<Core>,
+    pub main_node_client: &'a dyn SnapshotsApplierMainNodeClient,
+    pub blob_stores: &'a [Arc<dyn ObjectStore>],
+    pub max_concurrency: usize,
+}
+
+impl SnapshotsVerifier<'_> {
+    pub async fn verify(&self) -> Result<SnapshotVerificationReport, SnapshotsApplierError> {
+        let started_at = Instant::now();
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let status = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await?
+            .context("node was not recovered from a snapshot; nothing to verify")?;
+        drop(storage);
+        let chunks_left = status.storage_logs_chunks_left_to_process();
+        if chunks_left > 0 {
+            let err = anyhow::anyhow!(
+                "snapshot recovery is not completed: {chunks_left} storage log chunks are left to process"
+            );
+            return Err(SnapshotsApplierError::Fatal(err));
+        }
+        tracing::info!("Verifying node storage against snapshot: {status:?}");
+
+        let mut report = SnapshotVerificationReport::new(&status);
+        self.verify_recovery_status(&status, &mut report).await?;
+        let downloaded_bytes = AtomicU64::new(0);
+        self.verify_factory_deps(&status, &downloaded_bytes, &mut report)
+            .await?;
+
+        let report = Mutex::new(report);
+        let effective_concurrency =
+            (self.connection_pool.max_size() as usize).min(self.max_concurrency);
+        let semaphore = Semaphore::new(effective_concurrency);
+        let checksums = fetch_storage_logs_checksums(self.main_node_client, &status).await?;
+        let tasks = checksums
+            .into_iter()
+            .enumerate()
+            .map(|(chunk_id, checksum)| {
+                self.verify_storage_logs_chunk(
+                    &status,
+                    chunk_id as u64,
+                    checksum,
+                    &semaphore,
+                    &downloaded_bytes,
+                    &report,
+                )
+            });
+        futures::future::try_join_all(tasks).await?;
+        let mut report = report.into_inner().expect("report mutex poisoned");
+
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let storage_logs_count = storage
+            .storage_logs_dal()
+            .get_storage_logs_row_count(status.l2_block_number)
+            .await?;
+        if storage_logs_count != report.storage_logs_checked {
+            report.push(SnapshotDiscrepancy::StorageLogCountMismatch {
+                expected: report.storage_logs_checked,
+                actual: storage_logs_count,
+            });
+        }
+
+        tracing::info!(
+            "Verified {} factory deps and {} storage logs in {:?}; found {} discrepancies",
+            report.factory_deps_checked,
+            report.storage_logs_checked,
+            started_at.elapsed(),
+            report.discrepancy_count
+        );
+        Ok(report)
+    }
+
+    async fn verify_recovery_status(
+        &self,
+        status: &SnapshotRecoveryStatus,
+        report: &mut SnapshotVerificationReport,
+    ) -> Result<(), SnapshotsApplierError> {
+        let l1_batch_number = status.l1_batch_number;
+        let l1_batch = self
+            .main_node_client
+            .fetch_l1_batch_details(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} is missing on main node"))?;
+        report.check_field(
+            "l1_batch_root_hash",
+            l1_batch.base.root_hash,
+            Some(status.l1_batch_root_hash),
+        );
+        report.check_field(
+            "l1_batch_timestamp",
+            l1_batch.base.timestamp,
+            status.l1_batch_timestamp,
+        );
+
+        let l2_block_number = status.l2_block_number;
+        let l2_block = self
+            .main_node_client
+            .fetch_l2_block_details(l2_block_number)
+            .await?
+            .with_context(|| format!("L2 block #{l2_block_number} is missing on main node"))?;
+        report.check_field(
+            "l2_block_hash",
+            l2_block.base.root_hash,
+            Some(status.l2_block_hash),
+        );
+        report.check_field(
+            "l2_block_timestamp",
+            l2_block.base.timestamp,
+            status.l2_block_timestamp,
+        );
+        report.check_field(
+            "l2_block_l1_batch_number",
+            l2_block.l1_batch_number,
+            l1_batch_number,
+        );
+        Ok(())
+    }
+
+    async fn verify_factory_deps(
+        &self,
+        status: &SnapshotRecoveryStatus,
+        downloaded_bytes: &AtomicU64,
+        report: &mut SnapshotVerificationReport,
+    ) -> Result<(), SnapshotsApplierError> {
+        let factory_deps: SnapshotFactoryDependencies = fetch_object(
+            self.blob_stores,
+            downloaded_bytes,
+            status.l1_batch_number,
+            None,
+            |_| Ok(()),
+        )
+        .await?;
+        let factory_deps: Vec<_> = factory_deps
+            .factory_deps
+            .into_iter()
+            .map(|dep| (hash_bytecode(&dep.bytecode.0), dep.bytecode.0))
+            .collect();
+        let hashes: HashSet<_> = factory_deps.iter().map(|(hash, _)| *hash).collect();
+
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let persisted_deps = storage.factory_deps_dal().get_factory_deps(&hashes).await;
+        for (bytecode_hash, bytecode) in &factory_deps {
+            match persisted_deps.get(&h256_to_u256(*bytecode_hash)) {
+                None => report.push(SnapshotDiscrepancy::MissingFactoryDep {
+                    bytecode_hash: *bytecode_hash,
+                }),
+                Some(persisted) if !persisted.iter().flatten().eq(bytecode) => {
+                    report.push(SnapshotDiscrepancy::FactoryDepMismatch {
+                        bytecode_hash: *bytecode_hash,
+                    });
+                }
+                Some(_) => { /* factory dep is consistent */ }
+            }
+        }
+        report.factory_deps_checked = factory_deps.len();
+        Ok(())
+    }
+
+    async fn verify_storage_logs_chunk(
+        &self,
+        status: &SnapshotRecoveryStatus,
+        chunk_id: u64,
+        checksum: Option<H256>,
+        semaphore: &Semaphore,
+        downloaded_bytes: &AtomicU64,
+        report: &Mutex<SnapshotVerificationReport>,
+    ) -> Result<(), SnapshotsApplierError> {
+        // `unwrap()` is safe: the semaphore is never closed
+        let _permit = semaphore.acquire().await.unwrap();
+
+        let storage_key = SnapshotStorageLogsStorageKey {
+            chunk_id,
+            l1_batch_number: status.l1_batch_number,
+        };
+        let chunk: SnapshotStorageLogsChunk = fetch_object(
+            self.blob_stores,
+            downloaded_bytes,
+            storage_key,
+            checksum,
+            |chunk| validate_storage_logs_chunk(&chunk.storage_logs, status.l1_batch_number),
+        )
+        .await?;
+        let hashed_keys: Vec<_> = chunk
+            .storage_logs
+            .iter()
+            .map(|log| log.key.hashed_key())
+            .collect();
+
+        let mut storage = self
+            .connection_pool
+            .connection_tagged("snapshots_applier")
+            .await?;
+        let values = storage
+            .storage_logs_dal()
+            .get_storage_values(&hashed_keys, status.l2_block_number)
+            .await?;
+        let initial_writes = storage
+            .storage_logs_dal()
+            .get_l1_batches_and_indices_for_initial_writes(&hashed_keys)
+            .await?;
+        drop(storage);
+
+        let mut report = report.lock().expect("report mutex poisoned");
+        for (log, hashed_key) in chunk.storage_logs.iter().zip(hashed_keys) {
+            match values.get(&hashed_key).copied().flatten() {
+                None => report.push(SnapshotDiscrepancy::MissingStorageLog { hashed_key }),
+                Some(actual) if actual != log.value => {
+                    report.push(SnapshotDiscrepancy::StorageValueMismatch {
+                        hashed_key,
+                        expected: log.value,
+                        actual,
+                    });
+                }
+                Some(_) => { /* value is consistent */ }
+            }
+
+            let expected = (log.l1_batch_number_of_initial_write, log.enumeration_index);
+            let actual = initial_writes.get(&hashed_key).copied();
+            if actual != Some(expected) {
+                report.push(SnapshotDiscrepancy::InitialWriteMismatch {
+                    hashed_key,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        report.storage_logs_checked += chunk.storage_logs.len() as u64;
+        tracing::info!("Verified storage logs chunk {chunk_id}");
+        Ok(())
+    }
+}
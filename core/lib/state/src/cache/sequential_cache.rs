@@ -92,6 +92,12 @@ impl<K: Ord + Copy, V: Clone> SequentialCache<K, V> {
         result
     }
 
+    /// Removes all entries from the cache.
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.report_size();
+    }
+
     /// Returns the last key in the cache.
     pub fn get_last_key(&self) -> Option<K> {
         self.data.back().map(|&(key, _)| key)
@@ -172,4 +178,17 @@ mod tests {
         assert_eq!(cache.query(1), None);
         assert_eq!(cache.query(3), Some(vec![(4, "Four".to_string())]));
     }
+
+    #[test]
+    fn clearing_cache() {
+        let mut cache = SequentialCache::<u32, String>::new("clearing_cache", 2);
+        cache.insert(vec![(1, "One".to_string())]).unwrap();
+        cache.clear();
+        assert_eq!(cache.query(0), None);
+        assert_eq!(cache.get_last_key(), None);
+
+        // Keys smaller than the ones inserted before clearing should be accepted.
+        cache.insert(vec![(0, "Zero".to_string())]).unwrap();
+        assert_eq!(cache.get_last_key(), Some(0));
+    }
 }
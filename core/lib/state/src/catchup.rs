@@ -1,9 +1,10 @@
-use std::{sync::Arc, time::Instant};
+use std::{num::NonZeroUsize, sync::Arc, time::Instant};
 
 use anyhow::Context;
 use once_cell::sync::OnceCell;
 use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core};
+use zksync_object_store::ObjectStore;
 use zksync_shared_metrics::{SnapshotRecoveryStage, APP_METRICS};
 use zksync_storage::RocksDB;
 use zksync_types::L1BatchNumber;
@@ -21,6 +22,7 @@ pub struct AsyncCatchupTask {
     state_keeper_db_options: RocksdbStorageOptions,
     rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
     to_l1_batch_number: Option<L1BatchNumber>,
+    snapshot_blob_store: Option<(Arc<dyn ObjectStore>, NonZeroUsize)>,
 }
 
 impl AsyncCatchupTask {
@@ -39,9 +41,23 @@ impl AsyncCatchupTask {
             state_keeper_db_options,
             rocksdb_cell,
             to_l1_batch_number,
+            snapshot_blob_store: None,
         }
     }
 
+    /// Warms up RocksDB after snapshot recovery from storage log chunks in the provided snapshot object store
+    /// instead of Postgres, fetching up to `max_concurrency` chunks in parallel.
+    /// See [`RocksdbStorageBuilder::with_snapshot_blob_store()`](crate::RocksdbStorageBuilder::with_snapshot_blob_store())
+    /// for details.
+    pub fn with_snapshot_blob_store(
+        mut self,
+        blob_store: Arc<dyn ObjectStore>,
+        max_concurrency: NonZeroUsize,
+    ) -> Self {
+        self.snapshot_blob_store = Some((blob_store, max_concurrency));
+        self
+    }
+
     /// Block until RocksDB cache instance is caught up with Postgres.
     ///
     /// # Errors
@@ -57,6 +73,9 @@ impl AsyncCatchupTask {
         )
         .await
         .context("Failed creating RocksDB storage builder")?;
+        if let Some((blob_store, max_concurrency)) = self.snapshot_blob_store {
+            rocksdb_builder = rocksdb_builder.with_snapshot_blob_store(blob_store, max_concurrency);
+        }
 
         let mut connection = self.pool.connection_tagged("state_keeper").await?;
         let was_recovered_from_snapshot = rocksdb_builder
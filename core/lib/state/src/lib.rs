@@ -36,7 +36,8 @@ pub use self::{
     in_memory::IN_MEMORY_STORAGE_DEFAULT_NETWORK_ID,
     postgres::{PostgresStorage, PostgresStorageCaches, PostgresStorageCachesTask},
     rocksdb::{
-        RocksdbStorage, RocksdbStorageBuilder, RocksdbStorageOptions, StateKeeperColumnFamily,
+        RocksdbCompactionHandle, RocksdbMaintenanceConfig, RocksdbMaintenanceTask, RocksdbStorage,
+        RocksdbStorageBuilder, RocksdbStorageOptions, StateKeeperColumnFamily,
     },
     shadow_storage::ShadowStorage,
     storage_factory::{BatchDiff, PgOrRocksdbStorage, ReadStorageFactory, RocksdbWithMemory},
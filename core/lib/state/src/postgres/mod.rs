@@ -487,6 +487,7 @@ impl ReadStorage for PostgresStorage<'_> {
         let cached_value = values_cache.and_then(|cache| cache.get(self.l2_block_number, &key));
 
         let value = cached_value.unwrap_or_else(|| {
+            let _span = tracing::debug_span!("storage_read", method = "read_value").entered();
             let mut dal = self.connection.storage_web3_dal();
             let value = self
                 .rt_handle
@@ -523,6 +524,7 @@ impl ReadStorage for PostgresStorage<'_> {
         }
 
         let l1_batch_number = cached_value.or_else(|| {
+            let _span = tracing::debug_span!("storage_read", method = "is_write_initial").entered();
             let mut dal = self.connection.storage_web3_dal();
             let value = self
                 .rt_handle
@@ -560,6 +562,7 @@ impl ReadStorage for PostgresStorage<'_> {
             .and_then(|caches| caches.factory_deps.get(&hash));
 
         let value = cached_value.or_else(|| {
+            let _span = tracing::debug_span!("storage_read", method = "load_factory_dep").entered();
             let mut dal = self.connection.storage_web3_dal();
             let value = self
                 .rt_handle
@@ -0,0 +1,232 @@
+//! Background maintenance of the state keeper RocksDB cache.
+
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context as _;
+use once_cell::sync::OnceCell;
+use tokio::sync::{watch, Notify};
+use zksync_storage::{db::NamedColumnFamily, RocksDB};
+
+use super::{
+    metrics::{CompactionReason, MAINTENANCE_METRICS},
+    StateKeeperColumnFamily,
+};
+
+/// Configuration of [`RocksdbMaintenanceTask`].
+#[derive(Debug, Clone)]
+pub struct RocksdbMaintenanceConfig {
+    /// Interval between checking the RocksDB state.
+    pub poll_interval: Duration,
+    /// Minimum estimated number of bytes pending compaction for a scheduled compaction to start.
+    /// If `None`, scheduled compactions are disabled; compactions can still be triggered manually via
+    /// [`RocksdbCompactionHandle`].
+    pub pending_compaction_bytes_threshold: Option<u64>,
+    /// Write rate (in bytes per second) at or below which the cache is considered idle. Scheduled compactions
+    /// only start when the cache is idle.
+    pub idle_write_rate: u64,
+    /// Minimum interval between scheduled compactions.
+    pub min_compaction_interval: Duration,
+}
+
+impl Default for RocksdbMaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            poll_interval: Duration::from_secs(60),
+            pending_compaction_bytes_threshold: None,
+            idle_write_rate: 64 * 1_024,
+            min_compaction_interval: Duration::from_secs(3_600),
+        }
+    }
+}
+
+/// Handle allowing to trigger compaction of the state keeper RocksDB cache on demand.
+#[derive(Debug, Clone)]
+pub struct RocksdbCompactionHandle {
+    trigger: Arc<Notify>,
+}
+
+impl RocksdbCompactionHandle {
+    /// Makes the maintenance task compact the cache immediately, regardless of the cache activity.
+    /// If the cache is currently being compacted, another compaction will start right after the current one.
+    /// Has no effect until the cache is initialized.
+    pub fn trigger_compaction(&self) {
+        self.trigger.notify_one();
+    }
+}
+
+/// Task performing maintenance of the state keeper RocksDB cache: reporting compaction-related metrics
+/// and scheduling manual compactions when the cache is idle (i.e., its write rate is low).
+///
+/// The task starts doing its job once the cache is initialized, i.e., after [`AsyncCatchupTask`](crate::AsyncCatchupTask)
+/// catches the cache up with Postgres.
+#[derive(Debug)]
+#[must_use = "Task should `run()` in a managed Tokio task"]
+pub struct RocksdbMaintenanceTask {
+    config: RocksdbMaintenanceConfig,
+    rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
+    trigger: Arc<Notify>,
+}
+
+impl RocksdbMaintenanceTask {
+    /// Creates a new maintenance task for the cache that will be placed in the provided cell.
+    pub fn new(
+        config: RocksdbMaintenanceConfig,
+        rocksdb_cell: Arc<OnceCell<RocksDB<StateKeeperColumnFamily>>>,
+    ) -> Self {
+        Self {
+            config,
+            rocksdb_cell,
+            trigger: Arc::new(Notify::new()),
+        }
+    }
+
+    /// Returns a handle allowing to trigger compactions on demand.
+    pub fn compaction_handle(&self) -> RocksdbCompactionHandle {
+        RocksdbCompactionHandle {
+            trigger: self.trigger.clone(),
+        }
+    }
+
+    /// Runs the task until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates errors from compaction tasks.
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut last_check: Option<(Instant, u64)> = None;
+        let mut last_compaction: Option<Instant> = None;
+
+        while !*stop_receiver.borrow_and_update() {
+            let is_triggered = tokio::select! {
+                () = self.trigger.notified() => true,
+                _ = tokio::time::sleep(self.config.poll_interval) => false,
+                _ = stop_receiver.changed() => break,
+            };
+
+            let Some(rocksdb) = self.rocksdb_cell.get() else {
+                if is_triggered {
+                    tracing::info!(
+                        "State keeper cache compaction was triggered, but the cache is not initialized yet"
+                    );
+                }
+                continue;
+            };
+
+            let now = Instant::now();
+            let written_bytes = rocksdb.written_bytes();
+            let write_rate = last_check.map(|(checked_at, prev_written_bytes)| {
+                let elapsed_ms = now.duration_since(checked_at).as_millis().max(1);
+                let delta = written_bytes.saturating_sub(prev_written_bytes);
+                u64::try_from(u128::from(delta) * 1_000 / elapsed_ms).unwrap_or(u64::MAX)
+            });
+            last_check = Some((now, written_bytes));
+
+            let pending_compaction_bytes = rocksdb.pending_compaction_bytes();
+            MAINTENANCE_METRICS
+                .pending_compaction_bytes
+                .set(pending_compaction_bytes);
+            if let Some(write_rate) = write_rate {
+                MAINTENANCE_METRICS.write_rate.set(write_rate);
+            }
+            if rocksdb.is_write_stopped() {
+                tracing::warn!(
+                    "Writes to state keeper cache are stopped; {pending_compaction_bytes} bytes are pending compaction"
+                );
+            }
+
+            let reason = if is_triggered {
+                tracing::info!("State keeper cache compaction was triggered manually");
+                CompactionReason::Manual
+            } else if self.should_schedule_compaction(
+                pending_compaction_bytes,
+                write_rate,
+                last_compaction,
+            ) {
+                tracing::info!(
+                    "Scheduling state keeper cache compaction: {pending_compaction_bytes} bytes pending compaction, \
+                     write rate is {write_rate:?} B/s"
+                );
+                CompactionReason::Scheduled
+            } else {
+                continue;
+            };
+
+            Self::compact(rocksdb.clone(), reason).await?;
+            last_compaction = Some(Instant::now());
+            // Do not count writes performed during compaction towards the write rate.
+            last_check = None;
+        }
+
+        tracing::info!("Stop signal received, state keeper cache maintenance is shutting down");
+        Ok(())
+    }
+
+    fn should_schedule_compaction(
+        &self,
+        pending_compaction_bytes: u64,
+        write_rate: Option<u64>,
+        last_compaction: Option<Instant>,
+    ) -> bool {
+        let Some(threshold) = self.config.pending_compaction_bytes_threshold else {
+            return false;
+        };
+        let is_idle = write_rate.is_some_and(|rate| rate <= self.config.idle_write_rate);
+        let is_compaction_allowed = last_compaction.map_or(true, |ts| {
+            ts.elapsed() >= self.config.min_compaction_interval
+        });
+        pending_compaction_bytes >= threshold && is_idle && is_compaction_allowed
+    }
+
+    async fn compact(
+        rocksdb: RocksDB<StateKeeperColumnFamily>,
+        reason: CompactionReason,
+    ) -> anyhow::Result<()> {
+        let latency = MAINTENANCE_METRICS.compaction_latency[&reason].start();
+        tokio::task::spawn_blocking(move || {
+            for &cf in StateKeeperColumnFamily::ALL {
+                let started_at = Instant::now();
+                rocksdb.compact_cf(cf);
+                tracing::debug!(
+                    "Compacted column family `{}` of state keeper cache in {:?}",
+                    cf.name(),
+                    started_at.elapsed()
+                );
+            }
+        })
+        .await
+        .context("panicked compacting state keeper cache")?;
+        let latency = latency.observe();
+        tracing::info!("Compacted state keeper cache in {latency:?}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scheduling_compaction() {
+        let config = RocksdbMaintenanceConfig {
+            pending_compaction_bytes_threshold: Some(1_000),
+            idle_write_rate: 100,
+            ..RocksdbMaintenanceConfig::default()
+        };
+        let task = RocksdbMaintenanceTask::new(config, Arc::default());
+
+        assert!(task.should_schedule_compaction(1_000, Some(100), None));
+        assert!(!task.should_schedule_compaction(999, Some(0), None));
+        // Unknown or high write rate
+        assert!(!task.should_schedule_compaction(1_000, None, None));
+        assert!(!task.should_schedule_compaction(1_000, Some(101), None));
+        // Too early after the previous compaction
+        let last_compaction = Some(Instant::now());
+        assert!(!task.should_schedule_compaction(1_000, Some(0), last_compaction));
+
+        let task = RocksdbMaintenanceTask::new(RocksdbMaintenanceConfig::default(), Arc::default());
+        assert!(!task.should_schedule_compaction(u64::MAX, Some(0), None));
+    }
+}
@@ -30,6 +30,7 @@ pub(super) enum RecoveryStage {
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
 pub(super) enum ChunkRecoveryStage {
+    FetchChunk,
     LoadEntries,
     SaveEntries,
 }
@@ -51,3 +52,28 @@ pub(super) struct RocksdbRecoveryMetrics {
 
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<RocksdbRecoveryMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(super) enum CompactionReason {
+    Scheduled,
+    Manual,
+}
+
+/// Metrics for background maintenance of the state keeper cache.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_state_keeper_secondary_storage_maintenance")]
+pub(super) struct RocksdbMaintenanceMetrics {
+    /// Estimated number of bytes pending compaction across all column families.
+    #[metrics(unit = Unit::Bytes)]
+    pub pending_compaction_bytes: Gauge<u64>,
+    /// Average write rate to the cache since the previous maintenance check, in bytes per second.
+    pub write_rate: Gauge<u64>,
+    /// Latency of manual compactions of the entire cache.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub compaction_latency: Family<CompactionReason, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(super) static MAINTENANCE_METRICS: vise::Global<RocksdbMaintenanceMetrics> =
+    vise::Global::new();
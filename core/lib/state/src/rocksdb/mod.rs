@@ -22,8 +22,9 @@ use std::{
     collections::HashMap,
     convert::TryInto,
     mem,
-    num::NonZeroU32,
+    num::{NonZeroU32, NonZeroUsize},
     path::{Path, PathBuf},
+    sync::Arc,
     time::Instant,
 };
 
@@ -31,14 +32,22 @@ use anyhow::Context as _;
 use itertools::{Either, Itertools};
 use tokio::sync::watch;
 use zksync_dal::{Connection, Core, CoreDal, DalError};
+use zksync_object_store::ObjectStore;
 use zksync_storage::{db::NamedColumnFamily, RocksDB, RocksDBOptions};
 use zksync_types::{L1BatchNumber, StorageKey, StorageValue, H256};
 
+pub use self::maintenance::{
+    RocksdbCompactionHandle, RocksdbMaintenanceConfig, RocksdbMaintenanceTask,
+};
 #[cfg(test)]
 use self::tests::RocksdbStorageEventListener;
-use self::{metrics::METRICS, recovery::Strategy};
+use self::{
+    metrics::METRICS,
+    recovery::{SnapshotChunksSource, Strategy},
+};
 use crate::{InMemoryStorage, ReadStorage};
 
+mod maintenance;
 mod metrics;
 mod recovery;
 #[cfg(test)]
@@ -159,6 +168,8 @@ impl RocksdbStorageOptions {
 pub struct RocksdbStorage {
     db: RocksDB<StateKeeperColumnFamily>,
     pending_patch: InMemoryStorage,
+    /// Source of storage logs for snapshot recovery other than Postgres.
+    snapshot_chunks_source: Option<SnapshotChunksSource>,
     /// Test-only listeners to events produced by the storage.
     #[cfg(test)]
     listener: RocksdbStorageEventListener,
@@ -175,11 +186,30 @@ impl RocksdbStorageBuilder {
         RocksdbStorageBuilder(RocksdbStorage {
             db: value,
             pending_patch: InMemoryStorage::default(),
+            snapshot_chunks_source: None,
             #[cfg(test)]
             listener: RocksdbStorageEventListener::default(),
         })
     }
 
+    /// Configures the storage to be warmed up after snapshot recovery from storage log chunks of the snapshot
+    /// stored in `blob_store`, rather than from Postgres. Up to `max_concurrency` chunks are fetched in parallel;
+    /// chunks missing in the object store are recovered from Postgres.
+    ///
+    /// Because chunks must be the same for the entire recovery, this option should not be changed while the storage
+    /// is being recovered.
+    pub fn with_snapshot_blob_store(
+        mut self,
+        blob_store: Arc<dyn ObjectStore>,
+        max_concurrency: NonZeroUsize,
+    ) -> Self {
+        self.0.snapshot_chunks_source = Some(SnapshotChunksSource {
+            blob_store,
+            max_concurrency,
+        });
+        self
+    }
+
     /// Returns the last processed l1 batch number + 1.
     ///
     /// # Panics
@@ -304,6 +334,7 @@ impl RocksdbStorage {
             Ok(Self {
                 db,
                 pending_patch: InMemoryStorage::default(),
+                snapshot_chunks_source: None,
                 #[cfg(test)]
                 listener: RocksdbStorageEventListener::default(),
             })
@@ -1,12 +1,17 @@
 //! Logic for [`RocksdbStorage`] related to snapshot recovery.
 
-use std::ops;
+use std::{num::NonZeroUsize, ops, sync::Arc};
 
 use anyhow::Context as _;
+use futures::{stream, StreamExt};
 use tokio::sync::watch;
 use zksync_dal::{storage_logs_dal::StorageRecoveryLogEntry, Connection, Core, CoreDal, DalError};
+use zksync_object_store::{ObjectStore, ObjectStoreError};
 use zksync_types::{
-    snapshots::{uniform_hashed_keys_chunk, SnapshotRecoveryStatus},
+    snapshots::{
+        uniform_hashed_keys_chunk, SnapshotRecoveryStatus, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
+    },
     L1BatchNumber, L2BlockNumber, H256,
 };
 
@@ -22,6 +27,65 @@ pub(super) enum Strategy {
     Genesis,
 }
 
+/// Object store containing storage log chunks of the snapshot that the node was recovered from. Used to warm up
+/// the storage after snapshot recovery without loading storage logs from Postgres.
+#[derive(Debug, Clone)]
+pub(super) struct SnapshotChunksSource {
+    pub blob_store: Arc<dyn ObjectStore>,
+    pub max_concurrency: NonZeroUsize,
+}
+
+impl SnapshotChunksSource {
+    /// Fetches a storage logs chunk from the object store. Returns `Ok(None)` if the chunk is missing.
+    async fn fetch_chunk(
+        blob_store: Arc<dyn ObjectStore>,
+        snapshot_l1_batch: L1BatchNumber,
+        key_chunk: ops::RangeInclusive<H256>,
+        chunk_id: u64,
+    ) -> anyhow::Result<Option<Vec<(H256, (H256, u64))>>> {
+        let latency = RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::FetchChunk].start();
+        let storage_key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_l1_batch,
+            chunk_id,
+        };
+        let chunk = match blob_store
+            .get::<SnapshotStorageLogsChunk>(storage_key)
+            .await
+        {
+            Ok(chunk) => chunk,
+            Err(ObjectStoreError::KeyNotFound(_)) => return Ok(None),
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed fetching snapshot storage logs chunk {chunk_id}")
+                })
+            }
+        };
+        let latency = latency.observe();
+        tracing::debug!(
+            "Fetched {} storage logs for chunk {chunk_id} from object store in {latency:?}",
+            chunk.storage_logs.len()
+        );
+
+        chunk
+            .storage_logs
+            .into_iter()
+            .map(|log| {
+                let hashed_key = log.key.hashed_key();
+                anyhow::ensure!(
+                    key_chunk.contains(&hashed_key),
+                    "storage log {log:?} in snapshot chunk {chunk_id} is outside the expected hashed key range {key_chunk:?}"
+                );
+                anyhow::ensure!(
+                    log.enumeration_index > 0,
+                    "storage log {log:?} in snapshot chunk {chunk_id} has zero enumeration index"
+                );
+                Ok((hashed_key, (log.value, log.enumeration_index)))
+            })
+            .collect::<anyhow::Result<_>>()
+            .map(Some)
+    }
+}
+
 #[derive(Debug)]
 struct KeyChunk {
     id: u64,
@@ -88,17 +152,26 @@ impl RocksdbStorage {
         if *stop_receiver.borrow() {
             return Err(RocksdbSyncError::Interrupted);
         }
-        let key_chunks =
-            Self::load_key_chunks(storage, snapshot_recovery, desired_log_chunk_size).await?;
+        // Snapshot chunks use uniform hashed key ranges as well, so if chunks are fetched from the object store,
+        // we use the same chunking as the snapshot.
+        let chunk_count = match &self.snapshot_chunks_source {
+            Some(_) => snapshot_recovery.storage_logs_chunks_processed.len() as u64,
+            None => {
+                Self::estimate_chunk_count(storage, snapshot_recovery, desired_log_chunk_size)
+                    .await?
+            }
+        };
+        let key_chunks = Self::load_key_chunks(storage, snapshot_recovery, chunk_count).await?;
 
         RECOVERY_METRICS.recovered_chunk_count.set(0);
+        let mut chunks_to_recover = Vec::with_capacity(key_chunks.len());
         for key_chunk in key_chunks {
             if *stop_receiver.borrow() {
                 return Err(RocksdbSyncError::Interrupted);
             }
 
             let chunk_id = key_chunk.id;
-            let Some(chunk_start) = key_chunk.start_entry else {
+            let Some(chunk_start) = &key_chunk.start_entry else {
                 tracing::info!("Chunk {chunk_id} (hashed key range {key_chunk:?}) doesn't have entries in Postgres; skipping");
                 RECOVERY_METRICS.recovered_chunk_count.inc_by(1);
                 continue;
@@ -119,24 +192,29 @@ impl RocksdbStorage {
                     return Err(err.into());
                 }
                 tracing::info!("Chunk {chunk_id} (hashed key range {key_chunk:?}) is already recovered; skipping");
+                RECOVERY_METRICS.recovered_chunk_count.inc_by(1);
             } else {
-                self.recover_logs_chunk(
-                    storage,
-                    snapshot_recovery.l2_block_number,
-                    key_chunk.key_range.clone(),
-                )
-                .await
-                .with_context(|| {
-                    format!(
-                        "failed recovering logs chunk {chunk_id} (hashed key range {:?})",
-                        key_chunk.key_range
-                    )
-                })?;
+                chunks_to_recover.push(key_chunk);
+            }
+        }
 
-                #[cfg(test)]
-                (self.listener.on_logs_chunk_recovered.write().await)(chunk_id);
+        if let Some(source) = self.snapshot_chunks_source.clone() {
+            self.recover_chunks_from_object_store(
+                storage,
+                snapshot_recovery,
+                source,
+                chunks_to_recover,
+                stop_receiver,
+            )
+            .await?;
+        } else {
+            for key_chunk in chunks_to_recover {
+                if *stop_receiver.borrow() {
+                    return Err(RocksdbSyncError::Interrupted);
+                }
+                self.recover_logs_chunk(storage, snapshot_recovery.l2_block_number, &key_chunk)
+                    .await?;
             }
-            RECOVERY_METRICS.recovered_chunk_count.inc_by(1);
         }
 
         tracing::info!("All chunks recovered; finalizing recovery process");
@@ -174,11 +252,82 @@ impl RocksdbStorage {
         Ok(())
     }
 
-    async fn load_key_chunks(
+    /// Recovers chunks by fetching them from the object store with the snapshot. Chunks are fetched in parallel
+    /// and saved to RocksDB sequentially in the order of chunk IDs, so that recovery remains fault-tolerant.
+    async fn recover_chunks_from_object_store(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        snapshot_recovery: &SnapshotRecoveryStatus,
+        source: SnapshotChunksSource,
+        key_chunks: Vec<KeyChunk>,
+        stop_receiver: &watch::Receiver<bool>,
+    ) -> Result<(), RocksdbSyncError> {
+        tracing::info!(
+            "Recovering {} chunks from snapshot object store with {} max concurrency",
+            key_chunks.len(),
+            source.max_concurrency
+        );
+        let snapshot_l1_batch = snapshot_recovery.l1_batch_number;
+        let blob_store = source.blob_store;
+        let fetched_chunks = key_chunks.into_iter().map(|key_chunk| {
+            // Spawn fetching so that it progresses while previous chunks are being saved.
+            let fetch_task = tokio::spawn(SnapshotChunksSource::fetch_chunk(
+                blob_store.clone(),
+                snapshot_l1_batch,
+                key_chunk.key_range.clone(),
+                key_chunk.id,
+            ));
+            async move { (key_chunk, fetch_task.await) }
+        });
+        let mut fetched_chunks =
+            stream::iter(fetched_chunks).buffered(source.max_concurrency.get());
+
+        while let Some((key_chunk, fetch_result)) = fetched_chunks.next().await {
+            if *stop_receiver.borrow() {
+                return Err(RocksdbSyncError::Interrupted);
+            }
+            let chunk_id = key_chunk.id;
+            let entries = fetch_result
+                .context("panicked fetching snapshot storage logs chunk")?
+                .with_context(|| {
+                    format!(
+                        "failed fetching logs chunk {chunk_id} (hashed key range {:?}) from object store",
+                        key_chunk.key_range
+                    )
+                })?;
+            let Some(entries) = entries else {
+                tracing::warn!(
+                    "Chunk {chunk_id} (hashed key range {:?}) is missing in the snapshot object store; recovering it from Postgres",
+                    key_chunk.key_range
+                );
+                self.recover_logs_chunk(storage, snapshot_recovery.l2_block_number, &key_chunk)
+                    .await?;
+                continue;
+            };
+
+            let latency = RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::SaveEntries].start();
+            let entry_count = entries.len();
+            self.pending_patch.state = entries.into_iter().collect();
+            self.save(None)
+                .await
+                .with_context(|| format!("failed saving storage logs chunk {chunk_id}"))?;
+            let latency = latency.observe();
+            tracing::info!(
+                "Recovered hashed key chunk {:?} with {entry_count} entries from object store (saved in {latency:?})",
+                key_chunk.key_range
+            );
+            #[cfg(test)]
+            (self.listener.on_logs_chunk_recovered.write().await)(chunk_id);
+            RECOVERY_METRICS.recovered_chunk_count.inc_by(1);
+        }
+        Ok(())
+    }
+
+    async fn estimate_chunk_count(
         storage: &mut Connection<'_, Core>,
         snapshot_recovery: &SnapshotRecoveryStatus,
         desired_log_chunk_size: u64,
-    ) -> anyhow::Result<Vec<KeyChunk>> {
+    ) -> anyhow::Result<u64> {
         let snapshot_l2_block = snapshot_recovery.l2_block_number;
         let log_count = storage
             .storage_logs_dal()
@@ -191,7 +340,15 @@ impl RocksdbStorage {
         tracing::info!(
             "Estimated the number of chunks for recovery based on {log_count} logs: {chunk_count}"
         );
+        Ok(chunk_count)
+    }
 
+    async fn load_key_chunks(
+        storage: &mut Connection<'_, Core>,
+        snapshot_recovery: &SnapshotRecoveryStatus,
+        chunk_count: u64,
+    ) -> anyhow::Result<Vec<KeyChunk>> {
+        let snapshot_l2_block = snapshot_recovery.l2_block_number;
         let latency = RECOVERY_METRICS.latency[&RecoveryStage::LoadChunkStarts].start();
         let key_chunks: Vec<_> = (0..chunk_count)
             .map(|chunk_id| uniform_hashed_keys_chunk(chunk_id, chunk_count))
@@ -223,6 +380,31 @@ impl RocksdbStorage {
     }
 
     async fn recover_logs_chunk(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        snapshot_l2_block: L2BlockNumber,
+        key_chunk: &KeyChunk,
+    ) -> anyhow::Result<()> {
+        let chunk_id = key_chunk.id;
+        self.recover_logs_chunk_from_postgres(
+            storage,
+            snapshot_l2_block,
+            key_chunk.key_range.clone(),
+        )
+        .await
+        .with_context(|| {
+            format!(
+                "failed recovering logs chunk {chunk_id} (hashed key range {:?})",
+                key_chunk.key_range
+            )
+        })?;
+        #[cfg(test)]
+        (self.listener.on_logs_chunk_recovered.write().await)(chunk_id);
+        RECOVERY_METRICS.recovered_chunk_count.inc_by(1);
+        Ok(())
+    }
+
+    async fn recover_logs_chunk_from_postgres(
         &mut self,
         storage: &mut Connection<'_, Core>,
         snapshot_l2_block: L2BlockNumber,
@@ -7,7 +7,14 @@ use tempfile::TempDir;
 use test_casing::test_casing;
 use tokio::sync::RwLock;
 use zksync_dal::{ConnectionPool, Core};
-use zksync_types::{L2BlockNumber, StorageLog};
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::{
+    snapshots::{
+        uniform_hashed_keys_chunk, SnapshotStorageLog, SnapshotStorageLogsChunk,
+        SnapshotStorageLogsStorageKey,
+    },
+    L2BlockNumber, StorageLog,
+};
 
 use super::*;
 use crate::test_utils::{
@@ -440,3 +447,87 @@ async fn recovery_fault_tolerance() {
         assert!(!storage.is_write_initial(&log.key));
     }
 }
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn snapshot_recovery_from_object_store(with_missing_chunk: bool) {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let (snapshot_recovery, mut storage_logs) =
+        prepare_postgres_for_snapshot_recovery(&mut conn).await;
+    // Sort logs in the same order as enum indices are assigned (by full `StorageKey`).
+    storage_logs.sort_unstable_by_key(|log| log.key);
+
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let chunk_count = snapshot_recovery.storage_logs_chunks_processed.len() as u64;
+    let mut missing_chunk_id = None;
+    for chunk_id in 0..chunk_count {
+        let key_range = uniform_hashed_keys_chunk(chunk_id, chunk_count);
+        let chunk_logs: Vec<_> = storage_logs
+            .iter()
+            .enumerate()
+            .filter(|(_, log)| key_range.contains(&log.key.hashed_key()))
+            .map(|(i, log)| SnapshotStorageLog {
+                key: log.key,
+                value: log.value,
+                l1_batch_number_of_initial_write: snapshot_recovery.l1_batch_number,
+                enumeration_index: i as u64 + 1,
+            })
+            .collect();
+        if with_missing_chunk && missing_chunk_id.is_none() && !chunk_logs.is_empty() {
+            missing_chunk_id = Some(chunk_id);
+            continue;
+        }
+
+        let key = SnapshotStorageLogsStorageKey {
+            l1_batch_number: snapshot_recovery.l1_batch_number,
+            chunk_id,
+        };
+        let chunk = SnapshotStorageLogsChunk {
+            storage_logs: chunk_logs,
+        };
+        blob_store.put(key, &chunk).await.unwrap();
+    }
+
+    let dir = TempDir::new().expect("cannot create temporary dir for state keeper");
+    let mut storage = RocksdbStorage::new(dir.path().into(), RocksdbStorageOptions::default())
+        .await
+        .unwrap();
+    storage.snapshot_chunks_source = Some(SnapshotChunksSource {
+        blob_store,
+        max_concurrency: NonZeroUsize::new(4).unwrap(),
+    });
+    let recovered_chunk_ids = Arc::new(std::sync::Mutex::new(vec![]));
+    let recovered_chunk_ids_ = recovered_chunk_ids.clone();
+    storage.listener.on_logs_chunk_recovered = Arc::new(RwLock::new(move |chunk_id| {
+        recovered_chunk_ids_.lock().unwrap().push(chunk_id);
+    }));
+
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let (_, next_l1_batch) = storage
+        .ensure_ready(
+            &mut conn,
+            RocksdbStorage::DESIRED_LOG_CHUNK_SIZE,
+            &stop_receiver,
+        )
+        .await
+        .unwrap();
+    assert_eq!(next_l1_batch, snapshot_recovery.l1_batch_number + 1);
+
+    // Chunks must be saved in order, including ones recovered from Postgres.
+    let recovered_chunk_ids = recovered_chunk_ids.lock().unwrap().clone();
+    assert!(!recovered_chunk_ids.is_empty());
+    assert!(recovered_chunk_ids.windows(2).all(|ids| ids[0] < ids[1]));
+    if let Some(missing_chunk_id) = missing_chunk_id {
+        assert!(recovered_chunk_ids.contains(&missing_chunk_id));
+    }
+
+    for (i, log) in storage_logs.iter().enumerate() {
+        assert_eq!(storage.read_value(&log.key), log.value);
+        let expected_index = i as u64 + 1;
+        assert_eq!(
+            storage.get_enumeration_index(&log.key),
+            Some(expected_index)
+        );
+    }
+}
@@ -104,6 +104,8 @@ pub(crate) struct RocksDBInner {
     db: DB,
     db_name: &'static str,
     cf_names: HashSet<&'static str>,
+    /// Total size of write batches successfully written to the DB since it was opened.
+    written_bytes: AtomicU64,
     _registry_entry: RegistryEntry,
     // Importantly, `Cache`s must be dropped after `DB`, so we place them as the last field
     // (fields in a struct are dropped in the declaration order).
@@ -407,6 +409,7 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
             db,
             db_name: CF::DB_NAME,
             cf_names,
+            written_bytes: AtomicU64::new(0),
             _registry_entry: RegistryEntry::new(),
             _caches: caches,
         });
@@ -550,13 +553,66 @@ impl<CF: NamedColumnFamily> RocksDB<CF> {
     }
 
     fn write_inner(&self, raw_batch: rocksdb::WriteBatch) -> Result<(), rocksdb::Error> {
+        let batch_size = raw_batch.size_in_bytes() as u64;
         if self.sync_writes {
             let mut options = WriteOptions::new();
             options.set_sync(true);
-            self.inner.db.write_opt(raw_batch, &options)
+            self.inner.db.write_opt(raw_batch, &options)?;
         } else {
-            self.inner.db.write(raw_batch)
+            self.inner.db.write(raw_batch)?;
         }
+        self.inner
+            .written_bytes
+            .fetch_add(batch_size, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Returns the total size of write batches written to this DB since it was opened, in bytes.
+    /// Can be used to estimate write activity for the DB.
+    pub fn written_bytes(&self) -> u64 {
+        self.inner.written_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Returns the estimated total number of bytes that compactions need to rewrite to get all levels
+    /// of all column families down to their target size. Large values indicate that writes may be stalled soon.
+    pub fn pending_compaction_bytes(&self) -> u64 {
+        CF::ALL
+            .iter()
+            .filter_map(|&cf| {
+                let cf = self.column_family(cf);
+                self.inner
+                    .int_property(cf, properties::ESTIMATE_PENDING_COMPACTION_BYTES)
+            })
+            .sum()
+    }
+
+    /// Returns the total size of SST files belonging to the current version of this DB across all column families,
+    /// in bytes. Unlike the total DB size, this excludes obsolete SST files pending deletion.
+    pub fn live_sst_size(&self) -> u64 {
+        CF::ALL
+            .iter()
+            .filter_map(|&cf| {
+                let cf = self.column_family(cf);
+                self.inner.int_property(cf, properties::LIVE_SST_FILES_SIZE)
+            })
+            .sum()
+    }
+
+    /// Checks whether writes to any column family of this DB are currently stopped.
+    pub fn is_write_stopped(&self) -> bool {
+        CF::ALL.iter().any(|&cf| {
+            let cf = self.column_family(cf);
+            self.inner.int_property(cf, properties::IS_WRITE_STOPPED) == Some(1)
+        })
+    }
+
+    /// Manually compacts the entire key range of the specified column family. This is a blocking
+    /// operation that may take a long time for large column families.
+    pub fn compact_cf(&self, cf: CF) {
+        let cf = self.column_family(cf);
+        self.inner
+            .db
+            .compact_range_cf::<&[u8], &[u8]>(cf, None, None);
     }
 
     fn column_family(&self, cf: CF) -> &ColumnFamily {
@@ -1034,6 +1090,39 @@ mod tests {
         assert_eq!(value, b"value2");
     }
 
+    #[test]
+    fn manual_compaction() {
+        let temp_dir = TempDir::new().unwrap();
+        let db = RocksDB::<NewColumnFamilies>::new(temp_dir.path())
+            .unwrap()
+            .with_sync_writes();
+        assert_eq!(db.written_bytes(), 0);
+
+        for i in 0_u32..100 {
+            let mut batch = db.new_write_batch();
+            batch.put_cf(NewColumnFamilies::Default, &i.to_be_bytes(), b"value");
+            if i % 2 == 0 {
+                batch.delete_cf(NewColumnFamilies::Default, &(i / 2).to_be_bytes());
+            }
+            db.write(batch).unwrap();
+        }
+        assert!(db.written_bytes() > 100 * 9);
+        assert!(!db.is_write_stopped());
+
+        for &cf in NewColumnFamilies::ALL {
+            db.compact_cf(cf);
+        }
+        assert_eq!(db.pending_compaction_bytes(), 0);
+        let value = db
+            .get_cf(NewColumnFamilies::Default, &99_u32.to_be_bytes())
+            .unwrap();
+        assert_eq!(value.unwrap(), b"value");
+        let value = db
+            .get_cf(NewColumnFamilies::Default, &0_u32.to_be_bytes())
+            .unwrap();
+        assert_eq!(value, None);
+    }
+
     #[test]
     fn profiling_basics() {
         let temp_dir = TempDir::new().unwrap();
@@ -46,3 +46,17 @@ pub struct SyncBlock {
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ConsensusGenesis(pub serde_json::Value);
+
+/// Status of snapshot recovery of the node as persisted in Postgres.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRecoveryStatus {
+    /// Number of the L1 batch the node was recovered from.
+    pub l1_batch_number: L1BatchNumber,
+    /// Number of the last L2 block in the recovery L1 batch.
+    pub l2_block_number: L2BlockNumber,
+    /// Total number of storage log chunks in the snapshot.
+    pub storage_logs_chunk_count: usize,
+    /// Number of storage log chunks already applied.
+    pub storage_logs_chunks_processed: usize,
+}
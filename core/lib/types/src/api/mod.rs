@@ -1,3 +1,5 @@
+use std::{collections::HashMap, fmt, str::FromStr};
+
 use chrono::{DateTime, Utc};
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use strum::Display;
@@ -12,7 +14,7 @@ pub use crate::transaction_request::{
 };
 use crate::{
     protocol_version::L1VerifierConfig,
-    vm_trace::{Call, CallType},
+    vm_trace::{Call, CallType, StructLog},
     Address, L2BlockNumber, ProtocolVersionId,
 };
 
@@ -189,7 +191,7 @@ pub struct L2ToL1LogProof {
 }
 
 /// A struct with the two default bridge contracts.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BridgeAddresses {
     pub l1_shared_default_bridge: Option<Address>,
@@ -581,11 +583,89 @@ pub struct TransactionDetails {
 #[derive(Debug, Clone)]
 pub struct GetLogsFilter {
     pub from_block: L2BlockNumber,
+    /// Index of the first log in `from_block` to return; logs in `from_block` with lower indices are skipped.
+    pub from_log_index: u32,
     pub to_block: L2BlockNumber,
     pub addresses: Vec<Address>,
     pub topics: Vec<(u32, Vec<H256>)>,
 }
 
+/// Continuation cursor for keyset-paginated retrieval of items (e.g., logs or transactions) ordered by their position
+/// in the chain, i.e. by the L2 block number and the index of the item in the block. Points to the first item
+/// that wasn't returned.
+///
+/// Serialized as an opaque hex string; clients should not rely on its contents.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PageCursor {
+    pub block_number: L2BlockNumber,
+    /// Index of the item in the block.
+    pub index_in_block: u32,
+}
+
+/// Continuation cursor for paginated logs retrieval. The index in the block is the log index.
+pub type LogsCursor = PageCursor;
+
+impl fmt::Display for PageCursor {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            formatter,
+            "0x{:08x}{:08x}",
+            self.block_number.0, self.index_in_block
+        )
+    }
+}
+
+impl FromStr for PageCursor {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse = || {
+            let cursor = s.strip_prefix("0x")?;
+            if cursor.len() != 16 {
+                return None;
+            }
+            let (block_number, index_in_block) = cursor.split_at(8);
+            Some(Self {
+                block_number: L2BlockNumber(u32::from_str_radix(block_number, 16).ok()?),
+                index_in_block: u32::from_str_radix(index_in_block, 16).ok()?,
+            })
+        };
+        parse().ok_or_else(|| format!("invalid page cursor: `{s}`"))
+    }
+}
+
+impl Serialize for PageCursor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for PageCursor {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let cursor = String::deserialize(deserializer)?;
+        cursor.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Page of logs returned by `zks_getLogsPaged` and `zks_getTokenTransfersPaged`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogsPage {
+    pub logs: Vec<Log>,
+    /// Cursor to pass to the next call to get the remaining logs; `None` if all logs matching the filter
+    /// were returned.
+    pub cursor: Option<LogsCursor>,
+}
+
+/// Page of transactions returned by `zks_getAccountTransactionsPaged`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsPage {
+    pub transactions: Vec<Transaction>,
+    /// Cursor to pass to the next call to get the remaining transactions; `None` if all transactions were returned.
+    pub cursor: Option<PageCursor>,
+}
+
 /// Result of debugging block
 /// For some reasons geth returns result as {result: DebugCall}
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -594,6 +674,36 @@ pub struct ResultDebugCall {
     pub result: DebugCall,
 }
 
+/// Output of the struct log tracer for a single transaction.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugStructLogs {
+    pub gas: U256,
+    pub failed: bool,
+    pub return_value: Bytes,
+    pub struct_logs: Vec<StructLog>,
+    /// Set if the trace was truncated because the transaction executed too many steps.
+    #[serde(default)]
+    pub truncated: bool,
+}
+
+/// Trace of a single transaction; its form depends on the tracer specified in [`TracerConfig`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DebugTrace {
+    Call(DebugCall),
+    StructLogs(DebugStructLogs),
+    /// Output of a custom tracer; its format is defined by the tracer.
+    Custom(serde_json::Value),
+}
+
+/// Result of tracing a block with an arbitrary tracer. Has the same format as [`ResultDebugCall`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultDebugTrace {
+    pub result: DebugTrace,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub enum DebugCallType {
     Call,
@@ -658,6 +768,11 @@ pub struct ProtocolVersion {
 #[serde(rename_all = "camelCase")]
 pub enum SupportedTracers {
     CallTracer,
+    /// Tracer recording every VM step executed by the transaction.
+    StructLogTracer,
+    /// Custom tracer registered on the node, identified by its name.
+    #[serde(untagged)]
+    Custom(String),
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -674,6 +789,64 @@ pub struct TracerConfig {
     pub tracer_config: CallTracerConfig,
 }
 
+/// State overrides for a single account used when simulating a transaction.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OverrideAccount {
+    /// Base token balance.
+    pub balance: Option<U256>,
+    /// Transaction nonce. The deployment nonce of the account is retained.
+    pub nonce: Option<U256>,
+    /// Account bytecode; must be a valid EraVM bytecode.
+    pub code: Option<Bytes>,
+    /// Replaces the entire account storage; slots not mentioned are read as zeros.
+    pub state: Option<HashMap<H256, H256>>,
+    /// Replaces the specified storage slots, retaining all other slots.
+    pub state_diff: Option<HashMap<H256, H256>>,
+}
+
+/// State overrides for accounts keyed by the account address.
+pub type StateOverride = HashMap<Address, OverrideAccount>;
+
+/// Helper struct for [`SimulationBlockId`].
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1BatchNumberObject {
+    pub l1_batch_number: L1BatchNumber,
+}
+
+/// Historical state to simulate a transaction against.
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum SimulationBlockId {
+    /// State after the last L2 block in the specified L1 batch.
+    L1Batch(L1BatchNumberObject),
+    /// State after the specified L2 block.
+    Block(BlockIdVariant),
+}
+
+/// Options for `zks_simulateTransaction`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SimulationOptions {
+    /// Tracer to use; the call tracer by default.
+    pub tracer: Option<SupportedTracers>,
+    #[serde(default)]
+    pub tracer_config: CallTracerConfig,
+    /// Overrides applied to the historical state before the simulation.
+    pub state_override: Option<StateOverride>,
+}
+
+/// Result of `zks_simulateTransaction`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionSimulation {
+    /// L2 block in the context of which the transaction was executed. Unless the block is pending,
+    /// the transaction observes the state after this block.
+    pub l2_block_number: L2BlockNumber,
+    pub trace: DebugTrace,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub enum BlockStatus {
@@ -718,6 +891,9 @@ pub struct L1BatchDetails {
     pub number: L1BatchNumber,
     #[serde(flatten)]
     pub base: BlockDetailsBase,
+    /// L1 batch commitment. `None` if the commitment is not computed yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub commitment: Option<H256>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -736,6 +912,14 @@ pub struct Proof {
     pub storage_proof: Vec<StorageProof>,
 }
 
+/// Account and its storage keys to get proofs for in `zks_getProofs`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProofRequest {
+    pub address: Address,
+    pub keys: Vec<H256>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionDetailedResult {
@@ -751,3 +935,16 @@ pub struct ApiStorageLog {
     pub key: U256,
     pub written_value: U256,
 }
+
+/// Information about a slow JSON-RPC call sampled by the API server.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlowCallInfo {
+    pub method: String,
+    /// Raw call params; `null` if the call had no params.
+    pub params: serde_json::Value,
+    pub started_at: DateTime<Utc>,
+    pub latency_ms: u64,
+    /// Total time spent on DB queries during the call.
+    pub db_time_ms: u64,
+}
@@ -7,7 +7,7 @@ use serde::{
 };
 
 pub use crate::Execute as ExecuteData;
-use crate::{web3::Bytes, Address};
+use crate::{web3::Bytes, Address, H256};
 
 #[derive(Debug, Clone, Serialize)]
 #[serde(tag = "codeFormat", content = "sourceCode")]
@@ -130,7 +130,8 @@ impl<'de> Visitor<'de> for SourceCodeVisitor {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct VerificationIncomingRequest {
-    pub contract_address: Address,
+    #[serde(flatten)]
+    pub target: VerificationTarget,
     #[serde(flatten)]
     pub source_code_data: SourceCodeData,
     pub contract_name: String,
@@ -146,12 +147,56 @@ pub struct VerificationIncomingRequest {
     pub force_evmla: bool,
 }
 
+/// Bytecode the source code is verified against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum VerificationTarget {
+    /// Contract deployed at the specified address.
+    #[serde(rename_all = "camelCase")]
+    Address { contract_address: Address },
+    /// Bytecode known by its hash, e.g. a factory dependency that may not be deployed by any transaction.
+    /// Constructor arguments are not checked for such requests.
+    #[serde(rename_all = "camelCase")]
+    BytecodeHash { bytecode_hash: H256 },
+}
+
+impl VerificationTarget {
+    pub fn contract_address(&self) -> Option<Address> {
+        match self {
+            Self::Address { contract_address } => Some(*contract_address),
+            Self::BytecodeHash { .. } => None,
+        }
+    }
+
+    pub fn bytecode_hash(&self) -> Option<H256> {
+        match self {
+            Self::Address { .. } => None,
+            Self::BytecodeHash { bytecode_hash } => Some(*bytecode_hash),
+        }
+    }
+}
+
+impl fmt::Display for VerificationTarget {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Address { contract_address } => write!(formatter, "address {contract_address:?}"),
+            Self::BytecodeHash { bytecode_hash } => {
+                write!(formatter, "bytecode hash {bytecode_hash:?}")
+            }
+        }
+    }
+}
+
 #[derive(Debug, Eq, PartialEq, Clone, Copy)]
 pub enum CompilerType {
     Solc,
     Vyper,
 }
 
+/// Compiler version placeholder requesting the contract verifier to detect the version automatically,
+/// e.g. from the `pragma` directives in the source code.
+pub const AUTO_COMPILER_VERSION: &str = "auto";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum CompilerVersions {
@@ -243,7 +288,8 @@ pub enum DeployContractCalldata {
 
 #[cfg(test)]
 mod tests {
-    use super::SourceCodeData;
+    use super::{SourceCodeData, VerificationIncomingRequest, VerificationTarget};
+    use crate::{Address, H256};
 
     #[test]
     fn source_code_deserialization() {
@@ -275,4 +321,58 @@ mod tests {
             serde_json::from_str::<SourceCodeData>(type_not_specified_object_str);
         assert!(type_not_specified_object_result.is_err());
     }
+
+    #[test]
+    fn verification_target_deserialization() {
+        let address_request_str = r#"{
+            "contractAddress": "0x0000000000000000000000000000000000008006",
+            "sourceCode": "text",
+            "contractName": "ContractDeployer",
+            "compilerZksolcVersion": "v1.4.1",
+            "compilerSolcVersion": "0.8.24",
+            "optimizationUsed": true,
+            "optimizerMode": null,
+            "isSystem": true
+        }"#;
+        let request: VerificationIncomingRequest =
+            serde_json::from_str(address_request_str).unwrap();
+        assert_eq!(
+            request.target,
+            VerificationTarget::Address {
+                contract_address: Address::from_low_u64_be(0x8006)
+            }
+        );
+        let serialized = serde_json::to_value(&request).unwrap();
+        assert!(serialized.get("contractAddress").is_some());
+        assert!(serialized.get("bytecodeHash").is_none());
+
+        let bytecode_hash = H256::repeat_byte(1);
+        let bytecode_request_str = format!(
+            r#"{{
+                "bytecodeHash": "{bytecode_hash:?}",
+                "sourceCode": "text",
+                "contractName": "Factory",
+                "compilerZksolcVersion": "v1.4.1",
+                "compilerSolcVersion": "0.8.24",
+                "optimizationUsed": true,
+                "optimizerMode": null
+            }}"#
+        );
+        let request: VerificationIncomingRequest =
+            serde_json::from_str(&bytecode_request_str).unwrap();
+        assert_eq!(
+            request.target,
+            VerificationTarget::BytecodeHash { bytecode_hash }
+        );
+
+        let missing_target_str = r#"{
+            "sourceCode": "text",
+            "contractName": "Factory",
+            "compilerZksolcVersion": "v1.4.1",
+            "compilerSolcVersion": "0.8.24",
+            "optimizationUsed": true,
+            "optimizerMode": null
+        }"#;
+        serde_json::from_str::<VerificationIncomingRequest>(missing_target_str).unwrap_err();
+    }
 }
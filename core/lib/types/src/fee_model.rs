@@ -2,7 +2,7 @@ use serde::{Deserialize, Serialize};
 use zksync_config::configs::chain::{FeeModelVersion, StateKeeperConfig};
 use zksync_system_constants::L1_GAS_PER_PUBDATA_BYTE;
 
-use crate::ProtocolVersionId;
+use crate::{ProtocolVersionId, U256};
 
 /// Fee input to be provided into the VM. It contains two options:
 /// - `L1Pegged`: L1 gas price is provided to the VM, and the pubdata price is derived from it. Using this option is required for the
@@ -258,3 +258,64 @@ impl FeeParams {
         })
     }
 }
+
+/// Detailed derivation of the current fee parameters, as returned by `zks_getFeeModelBreakdown`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeModelBreakdown {
+    /// Fee model parameters, as returned by `zks_getFeeParams`.
+    pub fee_params: FeeParams,
+    /// Batch fee input derived from `fee_params` without any scaling.
+    pub batch_fee_input: PubdataIndependentBatchFeeModelInput,
+    /// Split of the batch overhead among L2 gas and pubdata. Only present for the `V2` fee model.
+    pub batch_overhead: Option<BatchOverheadBreakdown>,
+    /// Inputs used to derive the L1 gas and pubdata prices. Only present if the node estimates these prices
+    /// itself (i.e., on the main node); otherwise, the prices are taken from the main node as-is.
+    pub l1_prices: Option<L1PricesBreakdown>,
+}
+
+/// Split of the batch overhead among L2 gas and pubdata for the `V2` fee model.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOverheadBreakdown {
+    /// Overall cost of closing an L1 batch, in wei.
+    pub l1_batch_overhead_wei: U256,
+    /// Part of the overhead covered by each unit of L2 gas, in wei. Included into the fair L2 gas price.
+    pub overhead_per_l2_gas: u64,
+    /// Part of the overhead covered by each pubdata byte, in wei. Included into the fair pubdata price.
+    pub overhead_per_pubdata_byte: u64,
+}
+
+/// Inputs used by the main node to derive the L1 gas and pubdata prices.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct L1PricesBreakdown {
+    /// Median base fee over the recent L1 blocks, in wei.
+    pub median_base_fee: u64,
+    /// Base fee of the last processed L1 block, in wei.
+    pub last_base_fee: u64,
+    /// Priority fee added to the base fee, in wei.
+    pub priority_fee: u64,
+    /// Multiplier applied to the sum of base and priority fees.
+    pub l1_pricing_multiplier: f64,
+    /// L1 gas price enforced by the config, if any.
+    pub enforced_l1_gas_price: Option<u64>,
+    /// Upper bound for the L1 gas price.
+    pub max_l1_gas_price: u64,
+    /// Resulting L1 gas price, in wei.
+    pub effective_l1_gas_price: u64,
+    /// Whether pubdata is published in blobs (as opposed to calldata).
+    pub uses_blobs: bool,
+    /// Name of the strategy used to estimate the blob base fee.
+    pub blob_fee_strategy: String,
+    /// Blob base fee estimated from the recent L1 blocks, in wei. Only used if `uses_blobs` is set.
+    pub blob_base_fee: U256,
+    /// Multiplier applied to the blob base fee.
+    pub pubdata_pricing_multiplier: f64,
+    /// Pubdata price enforced by the config, if any.
+    pub enforced_pubdata_price: Option<u64>,
+    /// Upper bound for the blob base fee.
+    pub max_blob_base_fee: u64,
+    /// Resulting price of a pubdata byte, in wei.
+    pub effective_pubdata_price: u64,
+}
@@ -38,6 +38,9 @@ pub struct SnapshotMetadata {
     /// Paths to the storage log blobs. Ordered by the chunk ID. If a certain chunk is not produced yet,
     /// the corresponding path is `None`.
     pub storage_logs_filepaths: Vec<Option<String>>,
+    /// SHA-256 checksums of the storage log blobs, aligned with `storage_logs_filepaths`. `None` if a chunk
+    /// is not produced yet, or if it was produced without a checksum.
+    pub storage_logs_checksums: Vec<Option<H256>>,
 }
 
 impl SnapshotMetadata {
@@ -69,6 +72,10 @@ pub struct SnapshotStorageLogsChunkMetadata {
     pub chunk_id: u64,
     // can be either be a file available under HTTP(s) or local filesystem path
     pub filepath: String,
+    /// SHA-256 checksum of the chunk as stored in the object store (i.e., after serialization and compression).
+    /// Can be absent for snapshots created by older node versions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub checksum: Option<H256>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
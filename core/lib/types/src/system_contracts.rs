@@ -196,3 +196,10 @@ pub fn get_system_smart_contracts_from_dir(path: PathBuf) -> Vec<DeployedContrac
         })
         .collect::<Vec<_>>()
 }
+
+/// Checks whether the specified address hosts one of the default system contracts.
+pub fn is_system_contract_address(address: Address) -> bool {
+    SYSTEM_CONTRACT_LIST
+        .iter()
+        .any(|(_, _, contract_address, _)| *contract_address == address)
+}
@@ -51,6 +51,30 @@ pub enum MemoryDirection {
     Write,
 }
 
+/// Single VM step recorded by the struct log tracer.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StructLog {
+    /// Program counter before executing the opcode.
+    pub pc: u16,
+    /// Executed opcode.
+    pub op: String,
+    /// Gas (ergs) remaining in the current frame before executing the opcode.
+    pub gas: u32,
+    /// Depth of the far call stack; 1 corresponds to the top-level call of the transaction.
+    pub depth: usize,
+    /// Address of the contract executing the opcode.
+    pub address: Address,
+}
+
+/// Struct logs collected during transaction execution.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StructLogs {
+    pub logs: Vec<StructLog>,
+    /// Set if some steps were not recorded because the step limit was reached.
+    pub truncated: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ContractSourceDebugInfo {
     pub assembly_code: String,
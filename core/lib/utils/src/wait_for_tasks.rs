@@ -33,6 +33,14 @@ impl ManagedTasks {
         self
     }
 
+    /// Adds more tasks to the managed set, e.g. ones started after the initial set of tasks.
+    pub fn extend(
+        &mut self,
+        task_handles: impl IntoIterator<Item = JoinHandle<anyhow::Result<()>>>,
+    ) {
+        self.task_handles.extend(task_handles);
+    }
+
     /// Waits until a single managed task terminates, no matter the outcome.
     pub async fn wait_single(&mut self) {
         let (result, completed_index, _) = future::select_all(&mut self.task_handles).await;
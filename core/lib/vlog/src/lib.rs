@@ -28,6 +28,10 @@ use tracing_subscriber::{
     EnvFilter, Layer,
 };
 
+pub use crate::log_directives::{log_directives_handle, LogDirectivesError, LogDirectivesHandle};
+
+mod log_directives;
+
 type TracingLayer<Inner> =
     Layered<Filtered<OpenTelemetryLayer<Inner, Tracer>, EnvFilter, Inner>, Inner>;
 
@@ -144,6 +148,7 @@ pub struct ObservabilityBuilder {
     sentry_url: Option<Dsn>,
     sentry_environment: Option<String>,
     opentelemetry_options: Option<OpenTelemetryOptions>,
+    opentelemetry_sampling_ratio: Option<f64>,
 }
 
 /// Guard for the observability subsystem.
@@ -208,10 +213,19 @@ impl ObservabilityBuilder {
         Ok(self)
     }
 
+    /// Sets the fraction of traces exported via OpenTelemetry. Sampling decisions are made for root spans
+    /// and are inherited by child spans, so that exported traces are complete.
+    /// If not set, all traces are exported.
+    pub fn with_opentelemetry_sampling_ratio(mut self, ratio: f64) -> Self {
+        self.opentelemetry_sampling_ratio = Some(ratio);
+        self
+    }
+
     fn add_opentelemetry_layer<S>(
         opentelemetry_level: OpenTelemetryLevel,
         otlp_endpoint: String,
         service_name: String,
+        sampling_ratio: Option<f64>,
         subscriber: S,
     ) -> TracingLayer<S>
     where
@@ -230,6 +244,10 @@ impl ObservabilityBuilder {
             .add_directive("otel=debug".parse().unwrap());
 
         let resource = vec![KeyValue::new(SERVICE_NAME, service_name)];
+        let sampler = match sampling_ratio {
+            Some(ratio) => Sampler::ParentBased(Box::new(Sampler::TraceIdRatioBased(ratio))),
+            None => Sampler::AlwaysOn,
+        };
 
         let tracer = opentelemetry_otlp::new_pipeline()
             .tracing()
@@ -240,7 +258,7 @@ impl ObservabilityBuilder {
             )
             .with_trace_config(
                 trace::config()
-                    .with_sampler(Sampler::AlwaysOn)
+                    .with_sampler(sampler)
                     .with_id_generator(RandomIdGenerator::default())
                     .with_resource(Resource::new(resource)),
             )
@@ -256,24 +274,26 @@ impl ObservabilityBuilder {
 
     /// Initializes the observability subsystem.
     pub fn build(self) -> ObservabilityGuard {
-        // Initialize logs.
-
+        // Initialize logs. The filter is applied to the logging layer only, so that it doesn't affect
+        // spans exported via OpenTelemetry, which have a separate filter.
         let env_filter = if let Some(log_directives) = self.log_directives {
             tracing_subscriber::EnvFilter::new(log_directives)
         } else {
             tracing_subscriber::EnvFilter::from_default_env()
         };
+        // The filter can be changed at runtime via the handle installed below.
+        let (env_filter, directives_handle) = LogDirectivesHandle::new(env_filter);
 
         match self.log_format {
             LogFormat::Plain => {
                 let subscriber = tracing_subscriber::registry()
-                    .with(env_filter)
-                    .with(fmt::Layer::default());
+                    .with(fmt::Layer::default().with_filter(env_filter));
                 if let Some(opts) = self.opentelemetry_options {
                     let subscriber = Self::add_opentelemetry_layer(
                         opts.opentelemetry_level,
                         opts.otlp_endpoint,
                         opts.service_name,
+                        self.opentelemetry_sampling_ratio,
                         subscriber,
                     );
                     subscriber.init()
@@ -283,18 +303,20 @@ impl ObservabilityBuilder {
             }
             LogFormat::Json => {
                 let timer = tracing_subscriber::fmt::time::UtcTime::rfc_3339();
-                let subscriber = tracing_subscriber::registry().with(env_filter).with(
+                let subscriber = tracing_subscriber::registry().with(
                     fmt::Layer::default()
                         .with_file(true)
                         .with_line_number(true)
                         .with_timer(timer)
-                        .json(),
+                        .json()
+                        .with_filter(env_filter),
                 );
                 if let Some(opts) = self.opentelemetry_options {
                     let subscriber = Self::add_opentelemetry_layer(
                         opts.opentelemetry_level,
                         opts.otlp_endpoint,
                         opts.service_name,
+                        self.opentelemetry_sampling_ratio,
                         subscriber,
                     );
                     subscriber.init()
@@ -303,6 +325,7 @@ impl ObservabilityBuilder {
                 }
             }
         };
+        directives_handle.install();
 
         // Check whether we need to change the default panic handler.
         // Note that this must happen before we initialize Sentry, since otherwise
@@ -0,0 +1,124 @@
+//! Runtime reconfiguration of log directives.
+
+use std::{fmt, sync::OnceLock};
+
+use tracing_subscriber::{filter::ParseError, reload, EnvFilter, Registry};
+
+static LOG_DIRECTIVES: OnceLock<LogDirectivesHandle> = OnceLock::new();
+
+pub(crate) type ReloadableFilter = reload::Layer<EnvFilter, Registry>;
+
+/// Errors that can occur when changing log directives.
+#[derive(Debug)]
+pub enum LogDirectivesError {
+    /// Directives cannot be parsed.
+    Parse(ParseError),
+    /// Filter cannot be reloaded, e.g. because the subscriber was dropped.
+    Reload(reload::Error),
+}
+
+impl fmt::Display for LogDirectivesError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "invalid log directives: {err}"),
+            Self::Reload(err) => write!(f, "failed reloading log filter: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for LogDirectivesError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Parse(err) => Some(err),
+            Self::Reload(err) => Some(err),
+        }
+    }
+}
+
+/// Handle allowing to change `RUST_LOG`-style directives for logs at runtime, e.g. to enable debug logs
+/// for a single module during an incident. Directives only filter the logging layer; spans exported via OpenTelemetry
+/// are filtered separately according to the configured OpenTelemetry level.
+#[derive(Debug, Clone)]
+pub struct LogDirectivesHandle {
+    inner: reload::Handle<EnvFilter, Registry>,
+    initial: String,
+}
+
+impl LogDirectivesHandle {
+    pub(crate) fn new(filter: EnvFilter) -> (ReloadableFilter, Self) {
+        let initial = filter.to_string();
+        let (layer, inner) = reload::Layer::new(filter);
+        (layer, Self { inner, initial })
+    }
+
+    /// Installs this handle as the global one returned by [`log_directives_handle()`].
+    pub(crate) fn install(self) {
+        if LOG_DIRECTIVES.set(self).is_err() {
+            tracing::warn!("Log directives handle is already installed");
+        }
+    }
+
+    /// Returns directives set when the observability subsystem was initialized.
+    pub fn initial(&self) -> &str {
+        &self.initial
+    }
+
+    /// Returns currently active directives.
+    pub fn current(&self) -> Result<String, LogDirectivesError> {
+        self.inner
+            .with_current(ToString::to_string)
+            .map_err(LogDirectivesError::Reload)
+    }
+
+    /// Replaces currently active directives. Previously set directives are discarded rather than merged.
+    pub fn set(&self, directives: &str) -> Result<(), LogDirectivesError> {
+        let filter = EnvFilter::try_new(directives).map_err(LogDirectivesError::Parse)?;
+        self.inner
+            .reload(filter)
+            .map_err(LogDirectivesError::Reload)?;
+        tracing::info!("Changed log directives to `{directives}`");
+        Ok(())
+    }
+
+    /// Resets directives to the [initial ones](Self::initial()).
+    pub fn reset(&self) -> Result<(), LogDirectivesError> {
+        self.set(&self.initial)
+    }
+}
+
+/// Returns a handle to change log directives at runtime. Returns `None` if the observability subsystem
+/// was not initialized.
+pub fn log_directives_handle() -> Option<LogDirectivesHandle> {
+    LOG_DIRECTIVES.get().cloned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn setting_and_resetting_directives() {
+        let (_filter, handle) = LogDirectivesHandle::new(EnvFilter::new("info"));
+        assert_eq!(handle.initial(), "info");
+        assert_eq!(handle.current().unwrap(), "info");
+
+        handle.set("zksync_node_sync=debug").unwrap();
+        assert_eq!(handle.current().unwrap(), "zksync_node_sync=debug");
+        assert_eq!(handle.initial(), "info");
+
+        let err = handle.set("zksync_node_sync=what").unwrap_err();
+        assert!(matches!(err, LogDirectivesError::Parse(_)), "{err:?}");
+        assert_eq!(handle.current().unwrap(), "zksync_node_sync=debug");
+
+        handle.reset().unwrap();
+        assert_eq!(handle.current().unwrap(), "info");
+    }
+
+    #[test]
+    fn setting_directives_after_filter_is_dropped() {
+        let (filter, handle) = LogDirectivesHandle::new(EnvFilter::new("info"));
+        drop(filter);
+        let err = handle.set("debug").unwrap_err();
+        assert!(matches!(err, LogDirectivesError::Reload(_)), "{err:?}");
+    }
+}
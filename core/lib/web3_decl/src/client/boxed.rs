@@ -11,11 +11,11 @@ use serde::de::DeserializeOwned;
 
 use super::{ForNetwork, Network, TaggedClient};
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct RawParams(pub(super) Option<Box<JsonRawValue>>);
 
 impl RawParams {
-    fn new(params: impl ToRpcParams) -> Result<Self, serde_json::Error> {
+    pub(super) fn new(params: impl ToRpcParams) -> Result<Self, serde_json::Error> {
         params.to_rpc_params().map(Self)
     }
 }
@@ -128,29 +128,39 @@ impl<Net: Network> ClientT for &DynClient<Net> {
         R: DeserializeOwned + fmt::Debug + 'a,
     {
         let raw_responses = (**self).generic_batch_request(batch).await?;
-        let mut successful_calls = 0;
-        let mut failed_calls = 0;
-        let mut responses = Vec::with_capacity(raw_responses.len());
-        for raw_response in raw_responses {
-            responses.push(match raw_response {
-                Ok(json) => {
-                    successful_calls += 1;
-                    Ok(serde_json::from_value::<R>(json)?)
-                }
-                Err(err) => {
-                    failed_calls += 1;
-                    Err(err)
-                }
-            })
-        }
-        Ok(BatchResponse::new(
-            successful_calls,
-            responses,
-            failed_calls,
-        ))
+        deserialize_batch_response(raw_responses)
     }
 }
 
+/// Deserializes successful responses in a generic batch response.
+pub(super) fn deserialize_batch_response<'a, R>(
+    raw_responses: BatchResponse<'a, serde_json::Value>,
+) -> Result<BatchResponse<'a, R>, Error>
+where
+    R: DeserializeOwned + fmt::Debug + 'a,
+{
+    let mut successful_calls = 0;
+    let mut failed_calls = 0;
+    let mut responses = Vec::with_capacity(raw_responses.len());
+    for raw_response in raw_responses {
+        responses.push(match raw_response {
+            Ok(json) => {
+                successful_calls += 1;
+                Ok(serde_json::from_value::<R>(json)?)
+            }
+            Err(err) => {
+                failed_calls += 1;
+                Err(err)
+            }
+        })
+    }
+    Ok(BatchResponse::new(
+        successful_calls,
+        responses,
+        failed_calls,
+    ))
+}
+
 // Delegates to the above `&DynClient<Net>` implementation.
 #[async_trait]
 impl<Net: Network> ClientT for Box<DynClient<Net>> {
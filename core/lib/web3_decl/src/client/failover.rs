@@ -0,0 +1,339 @@
+//! Client wrapper failing over between several upstream clients.
+
+use std::{
+    fmt,
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc,
+    },
+};
+
+use async_trait::async_trait;
+use jsonrpsee::core::{
+    client::{BatchResponse, ClientT, Error},
+    params::BatchRequestBuilder,
+    traits::ToRpcParams,
+};
+use serde::de::DeserializeOwned;
+
+use super::{
+    boxed::{deserialize_batch_response, RawParams},
+    metrics::FAILOVER_METRICS,
+    DynClient, ForNetwork, Network, TaggedClient,
+};
+
+/// Method used to probe upstreams during failover. It's supported both by L1 and L2 nodes and is cheap to serve.
+const PROBE_METHOD: &str = "eth_chainId";
+
+#[derive(Debug, Default)]
+struct FailoverState {
+    active_upstream: AtomicUsize,
+    consecutive_errors: AtomicUsize,
+    is_failing_over: AtomicBool,
+}
+
+/// Client wrapping several upstream clients for the same [`Network`] and failing over between them.
+///
+/// At any given time, all requests are routed to a single active upstream. Once the active upstream returns
+/// the configured number of consecutive transient errors (i.e., transport errors or timeouts), other upstreams
+/// are health-checked in the round-robin order, and the first healthy one becomes active. Application-level
+/// errors (e.g., invalid params) do not count towards failover. Requests are never retried by the client itself;
+/// it's expected that callers retry transient errors, which is already the case for all client users.
+///
+/// Cloned clients share the failover state, so the client should be created once and shared among node components.
+pub struct FailoverClient<Net: Network> {
+    upstreams: Arc<[Box<DynClient<Net>>]>,
+    state: Arc<FailoverState>,
+    max_consecutive_errors: usize,
+    component_name: &'static str,
+    network: Net,
+}
+
+impl<Net: Network> Clone for FailoverClient<Net> {
+    fn clone(&self) -> Self {
+        Self {
+            upstreams: self.upstreams.clone(),
+            state: self.state.clone(),
+            max_consecutive_errors: self.max_consecutive_errors,
+            component_name: self.component_name,
+            network: self.network,
+        }
+    }
+}
+
+impl<Net: Network> fmt::Debug for FailoverClient<Net> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("FailoverClient")
+            .field("upstreams", &self.upstreams)
+            .field("active_upstream", &self.active_upstream())
+            .field("max_consecutive_errors", &self.max_consecutive_errors)
+            .field("component_name", &self.component_name)
+            .field("network", &self.network)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<Net: Network> FailoverClient<Net> {
+    /// Default number of consecutive transient errors after which the client fails over to another upstream.
+    pub const DEFAULT_MAX_CONSECUTIVE_ERRORS: NonZeroUsize = match NonZeroUsize::new(3) {
+        Some(value) => value,
+        None => unreachable!(),
+    };
+
+    /// Creates a client over the specified upstreams. The first upstream is initially active.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upstreams` is empty.
+    pub fn new(upstreams: Vec<Box<DynClient<Net>>>) -> Self {
+        assert!(
+            !upstreams.is_empty(),
+            "at least one upstream must be provided"
+        );
+        let network = upstreams[0].network();
+        FAILOVER_METRICS.upstreams[&network.metric_label()].set(upstreams.len());
+        FAILOVER_METRICS.active_upstream[&network.metric_label()].set(0);
+        Self {
+            upstreams: upstreams.into(),
+            state: Arc::default(),
+            max_consecutive_errors: Self::DEFAULT_MAX_CONSECUTIVE_ERRORS.get(),
+            component_name: "",
+            network,
+        }
+    }
+
+    /// Sets the number of consecutive transient errors returned by the active upstream after which
+    /// the client will fail over to another upstream.
+    #[must_use]
+    pub fn with_max_consecutive_errors(mut self, max_errors: NonZeroUsize) -> Self {
+        self.max_consecutive_errors = max_errors.get();
+        self
+    }
+
+    /// Returns the total number of upstreams.
+    pub fn upstream_count(&self) -> usize {
+        self.upstreams.len()
+    }
+
+    /// Returns the zero-based index of the currently active upstream.
+    pub fn active_upstream(&self) -> usize {
+        self.state.active_upstream.load(Ordering::Relaxed)
+    }
+
+    fn is_transient(err: &Error) -> bool {
+        matches!(err, Error::Transport(_) | Error::RequestTimeout)
+    }
+
+    async fn inspect_call_result<T>(&self, upstream_idx: usize, call_result: &Result<T, Error>) {
+        match call_result {
+            Err(err) if Self::is_transient(err) => {
+                let error_count = self
+                    .state
+                    .consecutive_errors
+                    .fetch_add(1, Ordering::Relaxed)
+                    + 1;
+                if error_count >= self.max_consecutive_errors {
+                    self.fail_over(upstream_idx).await;
+                }
+            }
+            _ => {
+                self.state.consecutive_errors.store(0, Ordering::Relaxed);
+            }
+        }
+    }
+
+    /// Fails over from the `failed_idx` upstream. If the active upstream has already been changed concurrently,
+    /// or if another failover is in progress, does nothing.
+    async fn fail_over(&self, failed_idx: usize) {
+        if self.upstreams.len() == 1 {
+            return;
+        }
+        if self
+            .state
+            .is_failing_over
+            .compare_exchange(false, true, Ordering::AcqRel, Ordering::Relaxed)
+            .is_err()
+        {
+            return;
+        }
+        if self.active_upstream() != failed_idx {
+            self.state.is_failing_over.store(false, Ordering::Release);
+            return;
+        }
+
+        let network_label = self.network.metric_label();
+        tracing::warn!(
+            network = network_label,
+            "Upstream #{failed_idx} returned {} consecutive transient errors; failing over",
+            self.max_consecutive_errors
+        );
+        let candidates = (1..self.upstreams.len()).map(|i| (failed_idx + i) % self.upstreams.len());
+        let mut new_idx = None;
+        for candidate_idx in candidates {
+            let candidate = &self.upstreams[candidate_idx];
+            match candidate
+                .generic_request(PROBE_METHOD, RawParams(None))
+                .await
+            {
+                Ok(_) => {
+                    new_idx = Some(candidate_idx);
+                    break;
+                }
+                Err(err) => {
+                    tracing::info!(
+                        network = network_label,
+                        "Health check for upstream #{candidate_idx} failed: {err}"
+                    );
+                }
+            }
+        }
+
+        if let Some(new_idx) = new_idx {
+            tracing::info!(
+                network = network_label,
+                "Switched active upstream from #{failed_idx} to #{new_idx}"
+            );
+            self.state.active_upstream.store(new_idx, Ordering::Relaxed);
+            FAILOVER_METRICS.active_upstream[&network_label].set(new_idx);
+            FAILOVER_METRICS.failovers[&network_label].inc();
+        } else {
+            tracing::warn!(
+                network = network_label,
+                "All upstreams are unhealthy; keeping upstream #{failed_idx} active"
+            );
+            FAILOVER_METRICS.failed_failovers[&network_label].inc();
+        }
+        self.state.consecutive_errors.store(0, Ordering::Relaxed);
+        self.state.is_failing_over.store(false, Ordering::Release);
+    }
+}
+
+impl<Net: Network> ForNetwork for FailoverClient<Net> {
+    type Net = Net;
+
+    fn network(&self) -> Self::Net {
+        self.network
+    }
+
+    fn component(&self) -> &'static str {
+        self.component_name
+    }
+}
+
+impl<Net: Network> TaggedClient for FailoverClient<Net> {
+    fn set_component(&mut self, component_name: &'static str) {
+        self.component_name = component_name;
+        self.upstreams = self
+            .upstreams
+            .iter()
+            .map(|upstream| upstream.clone().for_component(component_name))
+            .collect();
+    }
+}
+
+// Requests are routed via object-safe methods of the upstream clients since the response is inspected
+// after the call, which involves `await`ing; generic responses are not required to be `Send`.
+#[async_trait]
+impl<Net: Network> ClientT for FailoverClient<Net> {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
+    where
+        Params: ToRpcParams + Send,
+    {
+        let upstream_idx = self.active_upstream();
+        let call_result = self.upstreams[upstream_idx]
+            .generic_notification(method, RawParams::new(params)?)
+            .await;
+        self.inspect_call_result(upstream_idx, &call_result).await;
+        call_result
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let upstream_idx = self.active_upstream();
+        let call_result = self.upstreams[upstream_idx]
+            .generic_request(method, RawParams::new(params)?)
+            .await;
+        self.inspect_call_result(upstream_idx, &call_result).await;
+        serde_json::from_value(call_result?).map_err(Error::ParseError)
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, Error>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        let upstream_idx = self.active_upstream();
+        let call_result = self.upstreams[upstream_idx]
+            .generic_batch_request(batch)
+            .await;
+        self.inspect_call_result(upstream_idx, &call_result).await;
+        deserialize_batch_response(call_result?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::U64;
+
+    use super::*;
+    use crate::{
+        client::{MockClient, L2},
+        namespaces::EthNamespaceClient,
+    };
+
+    fn mock_upstream(block_number: u64, is_healthy: Arc<AtomicBool>) -> Box<DynClient<L2>> {
+        let client = MockClient::builder(L2::default())
+            .method("eth_blockNumber", move || {
+                if is_healthy.load(Ordering::Relaxed) {
+                    Ok(U64::from(block_number))
+                } else {
+                    Err(Error::RequestTimeout)
+                }
+            })
+            .method(PROBE_METHOD, || Ok(U64::from(270)))
+            .build();
+        Box::new(client)
+    }
+
+    #[tokio::test]
+    async fn failing_over_on_repeated_errors() {
+        let first_is_healthy = Arc::new(AtomicBool::new(true));
+        let client = FailoverClient::new(vec![
+            mock_upstream(1, first_is_healthy.clone()),
+            mock_upstream(2, Arc::new(AtomicBool::new(true))),
+        ]);
+        let client = Box::new(client) as Box<DynClient<L2>>;
+        assert_eq!(client.get_block_number().await.unwrap(), 1.into());
+
+        first_is_healthy.store(false, Ordering::Relaxed);
+        for _ in 0..FailoverClient::<L2>::DEFAULT_MAX_CONSECUTIVE_ERRORS.get() {
+            client.get_block_number().await.unwrap_err();
+        }
+        assert_eq!(client.get_block_number().await.unwrap(), 2.into());
+
+        // Failover state must be shared among clones.
+        let cloned_client = client.clone().for_component("test");
+        assert_eq!(cloned_client.get_block_number().await.unwrap(), 2.into());
+    }
+
+    #[tokio::test]
+    async fn application_errors_do_not_trigger_failover() {
+        let client = FailoverClient::new(vec![
+            mock_upstream(1, Arc::new(AtomicBool::new(true))),
+            mock_upstream(2, Arc::new(AtomicBool::new(true))),
+        ]);
+        for _ in 0..10 {
+            // Method is not mocked, so the client will return "method not found" errors.
+            client.gas_price().await.unwrap_err();
+        }
+        assert_eq!(client.active_upstream(), 0);
+        assert_eq!(client.get_block_number().await.unwrap(), 1.into());
+    }
+}
@@ -4,11 +4,11 @@ use std::time::Duration;
 
 use jsonrpsee::{core::client, http_client::transport};
 use vise::{
-    Buckets, Counter, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Histogram, Info,
-    LabeledFamily, Metrics, Unit,
+    Buckets, Counter, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram,
+    Info, LabeledFamily, Metrics, Unit,
 };
 
-use super::{AcquireStats, CallOrigin, SharedRateLimit};
+use super::{AcquireStats, CallOrigin, CallPriority, SharedRateLimit};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
 pub(super) struct RequestLabels {
@@ -17,6 +17,12 @@ pub(super) struct RequestLabels {
     pub method: String,
 }
 
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct PriorityLabels {
+    pub network: String,
+    pub priority: CallPriority,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
 pub(super) struct RpcErrorLabels {
     pub network: String,
@@ -67,6 +73,17 @@ pub(super) struct L2ClientMetrics {
     /// Latency of rate-limiting logic for rate-limited requests.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub rate_limit_latency: Family<RequestLabels, Histogram<Duration>>,
+    /// Number of calls (counting each request in a batch separately) by call priority.
+    pub calls: Family<PriorityLabels, Counter>,
+    /// Latency of rate-limiting logic for rate-limited requests by call priority.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub priority_rate_limit_latency: Family<PriorityLabels, Histogram<Duration>>,
+    /// Current number of requests allowed per second. Only reported for adaptive rate limits.
+    #[metrics(labels = ["network"])]
+    pub current_rate_limit: LabeledFamily<String, Gauge<f64>>,
+    /// Number of times the adaptive rate limit was decreased because the server signaled overload.
+    #[metrics(labels = ["network"])]
+    pub rate_limit_backoffs: LabeledFamily<String, Counter>,
     /// Number of calls that resulted in an RPC-level error.
     pub rpc_errors: Family<RpcErrorLabels, Counter>,
     /// Number of calls that resulted in an HTTP-level error.
@@ -91,13 +108,27 @@ impl L2ClientMetrics {
         }
     }
 
+    pub fn observe_call(&self, network: &str, priority: CallPriority, request_count: usize) {
+        let labels = PriorityLabels {
+            network: network.to_owned(),
+            priority,
+        };
+        self.calls[&labels].inc_by(request_count as u64);
+    }
+
     pub fn observe_rate_limit_latency(
         &self,
         network: &str,
         component: &'static str,
         origin: CallOrigin<'_>,
+        priority: CallPriority,
         stats: &AcquireStats,
     ) {
+        let priority_labels = PriorityLabels {
+            network: network.to_owned(),
+            priority,
+        };
+        self.priority_rate_limit_latency[&priority_labels].observe(stats.total_sleep_time);
         for method in origin.distinct_method_names() {
             let request_labels = RequestLabels {
                 network: network.to_owned(),
@@ -211,3 +242,44 @@ impl L2ClientMetrics {
 
 #[vise::register]
 pub(super) static METRICS: vise::Global<L2ClientMetrics> = vise::Global::new();
+
+/// Metrics for [`FailoverClient`](super::FailoverClient).
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "l2_client_failover")]
+pub(super) struct FailoverMetrics {
+    /// Total number of upstreams configured for the client.
+    #[metrics(labels = ["network"])]
+    pub upstreams: LabeledFamily<String, Gauge<usize>>,
+    /// Zero-based index of the currently active upstream.
+    #[metrics(labels = ["network"])]
+    pub active_upstream: LabeledFamily<String, Gauge<usize>>,
+    /// Number of successful failovers to another upstream.
+    #[metrics(labels = ["network"])]
+    pub failovers: LabeledFamily<String, Counter>,
+    /// Number of failover attempts that didn't find a healthy upstream.
+    #[metrics(labels = ["network"])]
+    pub failed_failovers: LabeledFamily<String, Counter>,
+}
+
+#[vise::register]
+pub(super) static FAILOVER_METRICS: vise::Global<FailoverMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct QuorumLabels {
+    pub network: String,
+    pub method: String,
+}
+
+/// Metrics for [`QuorumClient`](super::QuorumClient). Unlike other client metrics, these are transport-neutral
+/// since the quorum client is used for L1 as well.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "rpc_client_quorum")]
+pub(super) struct QuorumMetrics {
+    /// Number of verified requests for which upstreams returned divergent responses.
+    pub divergences: Family<QuorumLabels, Counter>,
+    /// Number of verified requests for which the quorum was not reached.
+    pub no_quorum: Family<QuorumLabels, Counter>,
+}
+
+#[vise::register]
+pub(super) static QUORUM_METRICS: vise::Global<QuorumMetrics> = vise::Global::new();
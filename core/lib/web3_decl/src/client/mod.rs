@@ -10,6 +10,10 @@
 //!   where it's possible.
 //! - [`BoxedL2Client`] is a generic client (essentially, a wrapper around a trait object). Use it for dependency injection
 //!   instead of `L2Client`. Both `L2Client` and `MockL2Client` are convertible to `BoxedL2Client`.
+//! - [`FailoverClient`] wraps several boxed clients for the same network and fails over between them
+//!   on repeated transient errors.
+//! - [`QuorumClient`] wraps several boxed clients for the same network and cross-checks responses for critical reads
+//!   among them.
 
 use std::{
     any,
@@ -27,25 +31,30 @@ use jsonrpsee::{
         params::BatchRequestBuilder,
         traits::ToRpcParams,
     },
-    http_client::{HttpClient, HttpClientBuilder},
+    http_client::{transport, HttpClient, HttpClientBuilder},
     ws_client,
 };
 use serde::de::DeserializeOwned;
 use tokio::time::Instant;
+use vise::EncodeLabelValue;
 use zksync_types::url::SensitiveUrl;
 
 use self::metrics::{L2ClientMetrics, METRICS};
 pub use self::{
     boxed::{DynClient, ObjectSafeClient},
+    failover::FailoverClient,
     mock::MockClient,
     network::{ForNetwork, Network, TaggedClient, L1, L2},
+    quorum::QuorumClient,
     shared::Shared,
 };
 
 mod boxed;
+mod failover;
 mod metrics;
 mod mock;
 mod network;
+mod quorum;
 mod shared;
 #[cfg(test)]
 mod tests;
@@ -90,6 +99,16 @@ impl fmt::Display for CallOrigin<'_> {
     }
 }
 
+/// Priority of calls issued by a client component. Low-priority calls cannot use a share of the rate limit
+/// reserved for normal-priority calls, so that opportunistic calls (e.g., periodic config refreshes) do not delay
+/// critical ones (e.g., fetching blocks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub enum CallPriority {
+    Normal,
+    Low,
+}
+
 /// Trait encapsulating requirements for the client base.
 pub trait ClientBase: ClientT + Clone + fmt::Debug + Send + Sync + 'static {}
 
@@ -114,6 +133,7 @@ pub struct Client<Net, C = HttpClient> {
     inner: C,
     url: SensitiveUrl,
     rate_limit: SharedRateLimit,
+    low_priority_components: Arc<HashSet<&'static str>>,
     component_name: &'static str,
     metrics: &'static L2ClientMetrics,
     network: Net,
@@ -131,6 +151,7 @@ impl<Net: fmt::Debug, C: 'static> fmt::Debug for Client<Net, C> {
             .field("inner", &any::type_name::<C>())
             .field("url", &self.url)
             .field("rate_limit", &self.rate_limit)
+            .field("low_priority_components", &self.low_priority_components)
             .field("component_name", &self.component_name)
             .field("network", &self.network)
             .finish_non_exhaustive()
@@ -158,16 +179,27 @@ impl<Net: Network> WsClient<Net> {
 }
 
 impl<Net: Network, C: ClientBase> Client<Net, C> {
+    fn priority(&self) -> CallPriority {
+        if self.low_priority_components.contains(self.component_name) {
+            CallPriority::Low
+        } else {
+            CallPriority::Normal
+        }
+    }
+
     async fn limit_rate(&self, origin: CallOrigin<'_>) -> Result<(), Error> {
         const RATE_LIMIT_TIMEOUT: Duration = Duration::from_secs(10);
 
+        let priority = self.priority();
         let rate_limit_result = tokio::time::timeout(
             RATE_LIMIT_TIMEOUT,
-            self.rate_limit.acquire(origin.request_count()),
+            self.rate_limit.acquire(origin.request_count(), priority),
         )
         .await;
 
         let network_label = self.network.metric_label();
+        self.metrics
+            .observe_call(&network_label, priority, origin.request_count());
         let stats = match rate_limit_result {
             Err(_) => {
                 self.metrics.observe_rate_limit_timeout(
@@ -186,14 +218,21 @@ impl<Net: Network, C: ClientBase> Client<Net, C> {
                 );
                 return Err(Error::RequestTimeout);
             }
-            Ok(stats) if !stats.was_waiting => return Ok(()),
             Ok(stats) => stats,
         };
+        if self.rate_limit.adaptive {
+            self.metrics.current_rate_limit[&network_label]
+                .set(stats.current_limit.requests_per_second());
+        }
+        if !stats.was_waiting {
+            return Ok(());
+        }
 
         self.metrics.observe_rate_limit_latency(
             &network_label,
             self.component_name,
             origin,
+            priority,
             &stats,
         );
         tracing::debug!(
@@ -217,9 +256,38 @@ impl<Net: Network, C: ClientBase> Client<Net, C> {
             let network_label = self.network.metric_label();
             self.metrics
                 .observe_error(&network_label, self.component_name, origin, err);
+            self.back_off_if_overloaded(err);
         }
         call_result
     }
+
+    /// Decreases the rate limit if the error signals that the server is overloaded (HTTP 429 or 5xx responses).
+    fn back_off_if_overloaded(&self, err: &Error) {
+        let Error::Transport(err) = err else {
+            return;
+        };
+        let Some(transport::Error::RequestFailure { status_code }) =
+            err.downcast_ref::<transport::Error>()
+        else {
+            return;
+        };
+        if *status_code != 429 && *status_code < 500 {
+            return;
+        }
+
+        if let Some(new_limit) = self.rate_limit.back_off() {
+            let network_label = self.network.metric_label();
+            self.metrics.rate_limit_backoffs[&network_label].inc();
+            self.metrics.current_rate_limit[&network_label].set(new_limit.requests_per_second());
+            tracing::info!(
+                network = network_label,
+                component = self.component_name,
+                "Decreased rate limit to {} reqs/{:?} after receiving HTTP status {status_code} from server",
+                new_limit.requests,
+                new_limit.window
+            );
+        }
+    }
 }
 
 impl<Net: Network, C: ClientBase> ForNetwork for Client<Net, C> {
@@ -270,7 +338,11 @@ impl<Net: Network, C: ClientBase> ClientT for Client<Net, C> {
     {
         let origin = CallOrigin::BatchRequest(&batch);
         self.limit_rate(origin).await?;
-        self.inner.batch_request(batch).await
+        let call_result = self.inner.batch_request(batch).await;
+        if let Err(err) = &call_result {
+            self.back_off_if_overloaded(err);
+        }
+        call_result
     }
 }
 
@@ -313,6 +385,8 @@ pub struct ClientBuilder<Net, C = HttpClient> {
     client: C,
     url: SensitiveUrl,
     rate_limit: (usize, Duration),
+    adaptive_rate_limit: bool,
+    low_priority_components: HashSet<&'static str>,
     network: Net,
 }
 
@@ -323,6 +397,8 @@ impl<Net: fmt::Debug, C: 'static> fmt::Debug for ClientBuilder<Net, C> {
             .field("client", &any::type_name::<C>())
             .field("url", &self.url)
             .field("rate_limit", &self.rate_limit)
+            .field("adaptive_rate_limit", &self.adaptive_rate_limit)
+            .field("low_priority_components", &self.low_priority_components)
             .field("network", &self.network)
             .finish_non_exhaustive()
     }
@@ -335,6 +411,8 @@ impl<Net: Network, C: ClientBase> ClientBuilder<Net, C> {
             client,
             url,
             rate_limit: (1, Duration::ZERO),
+            adaptive_rate_limit: false,
+            low_priority_components: HashSet::new(),
             network: Net::default(),
         }
     }
@@ -361,6 +439,24 @@ impl<Net: Network, C: ClientBase> ClientBuilder<Net, C> {
         self
     }
 
+    /// Makes the rate limit adaptive. If enabled, the allowed request rate is halved each time the server responds
+    /// with HTTP 429 (Too Many Requests) or a 5xx status, and is gradually restored to the configured value afterwards.
+    /// Has no effect if the rate limit is not set.
+    pub fn with_adaptive_rate_limit(mut self, adaptive: bool) -> Self {
+        self.adaptive_rate_limit = adaptive;
+        self
+    }
+
+    /// Marks calls from the specified components (as set by [`ObjectSafeClient::for_component()`]) as having
+    /// [low priority](CallPriority::Low).
+    pub fn with_low_priority_components(
+        mut self,
+        components: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.low_priority_components.extend(components);
+        self
+    }
+
     /// Builds the client.
     pub fn build(self) -> Client<Net, C> {
         tracing::info!(
@@ -369,13 +465,15 @@ impl<Net: Network, C: ClientBase> ClientBuilder<Net, C> {
             self.client,
             self.rate_limit
         );
-        let rate_limit = SharedRateLimit::new(self.rate_limit.0, self.rate_limit.1);
+        let mut rate_limit = SharedRateLimit::new(self.rate_limit.0, self.rate_limit.1);
+        rate_limit.adaptive = self.adaptive_rate_limit && !rate_limit.rate_limit_window.is_zero();
         METRICS.observe_config(self.network.metric_label(), &rate_limit);
 
         Client {
             inner: self.client,
             url: self.url,
             rate_limit,
+            low_priority_components: Arc::new(self.low_priority_components),
             component_name: "",
             metrics: &METRICS,
             network: self.network,
@@ -383,14 +481,30 @@ impl<Net: Network, C: ClientBase> ClientBuilder<Net, C> {
     }
 }
 
+/// Current parameters of a (potentially adaptive) rate limit.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct CurrentRateLimit {
+    /// Number of requests allowed per window.
+    requests: usize,
+    window: Duration,
+}
+
+impl CurrentRateLimit {
+    fn requests_per_second(&self) -> f64 {
+        self.requests as f64 / self.window.as_secs_f64()
+    }
+}
+
 #[derive(Debug, Default)]
 #[must_use = "stats should be reported"]
 struct AcquireStats {
     was_waiting: bool,
     total_sleep_time: Duration,
+    /// Rate limit at the time of acquiring.
+    current_limit: CurrentRateLimit,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy)]
 enum SharedRateLimitState {
     Limited {
         until: Instant,
@@ -401,11 +515,51 @@ enum SharedRateLimitState {
     },
 }
 
+impl SharedRateLimitState {
+    fn until(&self) -> Instant {
+        match self {
+            Self::Limited { until } | Self::Ready { until, .. } => *until,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct SharedRateLimitInner {
+    state: SharedRateLimitState,
+    /// Number of requests allowed per window. Equals the configured limit unless the limit was decreased
+    /// by the adaptive logic.
+    current_limit: usize,
+    /// Duration of the rate limiting window. Equals the configured window unless it was increased by the adaptive logic
+    /// (which happens once `current_limit` cannot be decreased further).
+    current_window: Duration,
+    /// Number of requests that can be used by low-priority calls, measured in fractions of a request
+    /// (`1 / LOW_PRIORITY_RESERVE_DIVISOR`). Each window adds the share of requests not reserved for normal-priority calls,
+    /// so low-priority calls are throttled even if a window only allows a single request.
+    low_priority_allowance: usize,
+    /// Last time `current_limit` or `current_window` was changed.
+    last_adjusted_at: Option<Instant>,
+}
+
+impl SharedRateLimitInner {
+    fn current(&self) -> CurrentRateLimit {
+        CurrentRateLimit {
+            requests: self.current_limit,
+            window: self.current_window,
+        }
+    }
+
+    /// Allowance required for a low-priority call with the specified number of requests.
+    fn low_priority_cost(&self, request_count: usize) -> usize {
+        request_count.min(self.current_limit) * SharedRateLimit::LOW_PRIORITY_RESERVE_DIVISOR
+    }
+}
+
 #[derive(Debug)]
 struct SharedRateLimit {
     rate_limit: usize,
     rate_limit_window: Duration,
-    state: Arc<Mutex<SharedRateLimitState>>,
+    adaptive: bool,
+    inner: Arc<Mutex<SharedRateLimitInner>>,
 }
 
 impl Clone for SharedRateLimit {
@@ -413,52 +567,113 @@ impl Clone for SharedRateLimit {
         Self {
             rate_limit: self.rate_limit,
             rate_limit_window: self.rate_limit_window,
-            state: self.state.clone(),
+            adaptive: self.adaptive,
+            inner: self.inner.clone(),
         }
     }
 }
 
 impl SharedRateLimit {
+    /// Minimum interval between increasing the adaptive rate limit.
+    const RECOVERY_INTERVAL: Duration = Duration::from_secs(1);
+    /// A quarter of requests is reserved for normal-priority calls.
+    const LOW_PRIORITY_RESERVE_DIVISOR: usize = 4;
+    /// Maximum factor by which the adaptive logic can increase the rate limiting window.
+    const MAX_WINDOW_MULTIPLIER: u32 = 16;
+
     fn new(rate_limit: usize, rate_limit_window: Duration) -> Self {
-        let state = Arc::new(Mutex::new(SharedRateLimitState::Ready {
-            until: Instant::now(),
-            remaining_requests: rate_limit,
-        }));
+        let inner = SharedRateLimitInner {
+            state: SharedRateLimitState::Ready {
+                until: Instant::now(),
+                remaining_requests: rate_limit,
+            },
+            current_limit: rate_limit,
+            current_window: rate_limit_window,
+            low_priority_allowance: 0,
+            last_adjusted_at: None,
+        };
         Self {
             rate_limit,
             rate_limit_window,
-            state,
+            adaptive: false,
+            inner: Arc::new(Mutex::new(inner)),
+        }
+    }
+
+    /// Starts a new rate limiting window, restoring a part of the adaptive limit if it was decreased sufficiently long ago.
+    fn start_window(&self, inner: &mut SharedRateLimitInner, now: Instant) {
+        let can_recover = inner
+            .last_adjusted_at
+            .is_some_and(|adjusted_at| now.duration_since(adjusted_at) >= Self::RECOVERY_INTERVAL);
+        if can_recover {
+            // Undo back-offs in the reverse order: first shrink the window, then increase the number of requests.
+            if inner.current_window > self.rate_limit_window {
+                inner.current_window = (inner.current_window / 2).max(self.rate_limit_window);
+                inner.last_adjusted_at = Some(now);
+            } else if inner.current_limit < self.rate_limit {
+                let step = (self.rate_limit / 10).max(1);
+                inner.current_limit = (inner.current_limit + step).min(self.rate_limit);
+                inner.last_adjusted_at = Some(now);
+            }
         }
+
+        // Replenish the low-priority allowance for all windows elapsed since the previous window has started.
+        let elapsed_windows = if inner.current_window.is_zero() {
+            1
+        } else {
+            let elapsed = now.saturating_duration_since(inner.state.until());
+            let elapsed_windows = elapsed.as_nanos() / inner.current_window.as_nanos() + 1;
+            usize::try_from(elapsed_windows).unwrap_or(usize::MAX)
+        };
+        let divisor = Self::LOW_PRIORITY_RESERVE_DIVISOR;
+        let replenished = inner
+            .current_limit
+            .saturating_mul(divisor - 1)
+            .saturating_mul(elapsed_windows.min(divisor));
+        inner.low_priority_allowance = inner
+            .low_priority_allowance
+            .saturating_add(replenished)
+            .min(inner.current_limit.saturating_mul(divisor));
+
+        inner.state = SharedRateLimitState::Ready {
+            until: now + inner.current_window,
+            remaining_requests: inner.current_limit,
+        };
     }
 
     /// Acquires the specified number of requests waiting if necessary. If the number of requests exceeds
     /// the capacity of the limiter, it is saturated (i.e., this method cannot hang indefinitely or panic
-    /// in this case).
+    /// in this case). Low-priority calls additionally wait if they have used up their share of requests,
+    /// which excludes the requests reserved for normal-priority calls.
     ///
     /// This implementation is similar to [`RateLimit`] middleware in Tower, but is shared among client instances.
     ///
     /// [`RateLimit`]: https://docs.rs/tower/latest/tower/limit/struct.RateLimit.html
-    async fn acquire(&self, request_count: usize) -> AcquireStats {
+    async fn acquire(&self, request_count: usize, priority: CallPriority) -> AcquireStats {
         let mut stats = AcquireStats::default();
-        let mut state = loop {
+        let is_low_priority = priority == CallPriority::Low && !self.rate_limit_window.is_zero();
+        let mut inner = loop {
             // A separate scope is required to not hold a mutex guard across the `await` point,
             // which is not only semantically incorrect, but also makes the future `!Send` (unfortunately,
             // async Rust doesn't seem to understand non-lexical lifetimes).
             let now = Instant::now();
             let until = {
-                let mut state = self.state.lock().expect("state is poisoned");
-                match &*state {
-                    SharedRateLimitState::Ready { .. } => break state,
-                    SharedRateLimitState::Limited { until } if *until <= now => {
-                        // At this point, local time is `>= until`; thus, the state should be reset.
-                        // Because we hold an exclusive lock on `state`, there's no risk of a data race.
-                        *state = SharedRateLimitState::Ready {
-                            until: now + self.rate_limit_window,
-                            remaining_requests: self.rate_limit,
-                        };
-                        break state;
+                let mut inner = self.inner.lock().expect("state is poisoned");
+                if inner.state.until() <= now {
+                    // At this point, local time is `>= until`; thus, the state should be reset.
+                    // Because we hold an exclusive lock on `state`, there's no risk of a data race.
+                    self.start_window(&mut inner, now);
+                }
+                match inner.state {
+                    SharedRateLimitState::Ready { .. }
+                        if !is_low_priority
+                            || inner.low_priority_allowance
+                                >= inner.low_priority_cost(request_count) =>
+                    {
+                        break inner;
                     }
-                    SharedRateLimitState::Limited { until } => *until,
+                    SharedRateLimitState::Ready { until, .. }
+                    | SharedRateLimitState::Limited { until } => until,
                 }
             };
 
@@ -466,24 +681,69 @@ impl SharedRateLimit {
             stats.total_sleep_time += until.duration_since(now);
             tokio::time::sleep_until(until).await;
         };
+
         let SharedRateLimitState::Ready {
             until,
             remaining_requests,
-        } = &mut *state
+        } = inner.state
         else {
             unreachable!();
         };
+        if is_low_priority {
+            inner.low_priority_allowance -= inner.low_priority_cost(request_count);
+        }
+
+        let remaining_requests = remaining_requests.saturating_sub(request_count);
+        inner.state = if remaining_requests == 0 {
+            SharedRateLimitState::Limited { until }
+        } else {
+            SharedRateLimitState::Ready {
+                until,
+                remaining_requests,
+            }
+        };
+        stats.current_limit = inner.current();
+        stats
+    }
+
+    /// Halves the rate limit in response to the server signaling overload. The number of requests per window is halved
+    /// while possible; afterwards, the window is doubled (up to [`Self::MAX_WINDOW_MULTIPLIER`] times the configured one),
+    /// so that backing off works for low limits as well.
+    ///
+    /// Returns the new limit, or `None` if the limit wasn't changed. The limit is decreased at most once per window
+    /// so that multiple failed requests sent in the same window don't decrease it multiple times.
+    fn back_off(&self) -> Option<CurrentRateLimit> {
+        if !self.adaptive {
+            return None;
+        }
 
         let now = Instant::now();
-        // Reset the period if it has elapsed.
-        if now >= *until {
-            *until = now + self.rate_limit_window;
-            *remaining_requests = self.rate_limit;
+        let mut inner = self.inner.lock().expect("state is poisoned");
+        let recently_adjusted = inner
+            .last_adjusted_at
+            .is_some_and(|adjusted_at| now.duration_since(adjusted_at) < inner.current_window);
+        if recently_adjusted {
+            return None;
         }
-        *remaining_requests = remaining_requests.saturating_sub(request_count);
-        if *remaining_requests == 0 {
-            *state = SharedRateLimitState::Limited { until: *until };
+
+        if inner.current_limit > 1 {
+            let new_limit = inner.current_limit / 2;
+            inner.current_limit = new_limit;
+            inner.low_priority_allowance = inner
+                .low_priority_allowance
+                .min(new_limit * Self::LOW_PRIORITY_RESERVE_DIVISOR);
+            if let SharedRateLimitState::Ready {
+                remaining_requests, ..
+            } = &mut inner.state
+            {
+                *remaining_requests = (*remaining_requests).min(new_limit);
+            }
+        } else if inner.current_window < self.rate_limit_window * Self::MAX_WINDOW_MULTIPLIER {
+            inner.current_window *= 2;
+        } else {
+            return None;
         }
-        stats
+        inner.last_adjusted_at = Some(now);
+        Some(inner.current())
     }
 }
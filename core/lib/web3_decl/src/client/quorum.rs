@@ -0,0 +1,408 @@
+//! Client wrapper cross-checking critical reads across several upstream clients.
+
+use std::{collections::HashSet, fmt, num::NonZeroUsize, sync::Arc};
+
+use async_trait::async_trait;
+use futures::future;
+use jsonrpsee::core::{
+    client::{BatchResponse, ClientT, Error},
+    params::BatchRequestBuilder,
+    traits::ToRpcParams,
+    JsonRawValue,
+};
+use serde::de::DeserializeOwned;
+
+use super::{
+    boxed::{deserialize_batch_response, RawParams},
+    metrics::{QuorumLabels, QUORUM_METRICS},
+    DynClient, ForNetwork, Network, TaggedClient,
+};
+
+/// Client wrapping several upstream clients for the same [`Network`] (e.g., several L1 providers) and cross-checking
+/// responses for critical reads among them.
+///
+/// Requests to *verified* methods are sent to all upstreams concurrently; the response is returned only if it is
+/// returned by at least the quorum number of upstreams. Divergent responses are logged and reported via metrics
+/// (even if the quorum is reached), so that operators can alert on them. If the quorum is not reached, a transport error
+/// is returned, i.e., the error is considered transient by the client users; this is intentional, since divergence
+/// may be caused by a lagging provider.
+///
+/// All other requests (notifications, batch requests and requests to methods not in the verified set) are
+/// routed to the first (primary) upstream only. This is necessary for methods returning volatile data
+/// (e.g., `eth_blockNumber`), which cannot be meaningfully compared across providers. For the same reason,
+/// `eth_getLogs` requests are only verified if they reference a block hash or an explicit block number range.
+pub struct QuorumClient<Net: Network> {
+    upstreams: Arc<[Box<DynClient<Net>>]>,
+    quorum: usize,
+    verified_methods: Arc<HashSet<&'static str>>,
+    component_name: &'static str,
+    network: Net,
+}
+
+impl<Net: Network> Clone for QuorumClient<Net> {
+    fn clone(&self) -> Self {
+        Self {
+            upstreams: self.upstreams.clone(),
+            quorum: self.quorum,
+            verified_methods: self.verified_methods.clone(),
+            component_name: self.component_name,
+            network: self.network,
+        }
+    }
+}
+
+impl<Net: Network> fmt::Debug for QuorumClient<Net> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("QuorumClient")
+            .field("upstreams", &self.upstreams)
+            .field("quorum", &self.quorum)
+            .field("verified_methods", &self.verified_methods)
+            .field("component_name", &self.component_name)
+            .field("network", &self.network)
+            .finish()
+    }
+}
+
+impl<Net: Network> QuorumClient<Net> {
+    /// Methods verified by default. These are methods returning data that should be identical
+    /// for all providers (as long as they are synced), and which are used for critical reads.
+    ///
+    /// Methods depending on the block tag (e.g., `eth_call`) are not included, since their results
+    /// for the `latest` block legitimately differ among providers at different chain heads. `eth_getLogs` is included,
+    /// but is only verified for filters not referencing block tags (see [`Self::is_verified_request()`]).
+    pub const DEFAULT_VERIFIED_METHODS: &'static [&'static str] = &[
+        "eth_chainId",
+        "eth_getLogs",
+        "eth_getTransactionByHash",
+        "eth_getTransactionReceipt",
+    ];
+
+    /// Creates a client over the specified upstreams. The first upstream is used as the primary one.
+    /// The quorum is set to the simple majority of upstreams, and verified methods are set to [`Self::DEFAULT_VERIFIED_METHODS`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `upstreams` is empty.
+    pub fn new(upstreams: Vec<Box<DynClient<Net>>>) -> Self {
+        assert!(
+            !upstreams.is_empty(),
+            "at least one upstream must be provided"
+        );
+        let network = upstreams[0].network();
+        Self {
+            quorum: upstreams.len() / 2 + 1,
+            upstreams: upstreams.into(),
+            verified_methods: Arc::new(Self::DEFAULT_VERIFIED_METHODS.iter().copied().collect()),
+            component_name: "",
+            network,
+        }
+    }
+
+    /// Sets the number of upstreams that must return the same response for it to be accepted.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quorum` exceeds the number of upstreams.
+    #[must_use]
+    pub fn with_quorum(mut self, quorum: NonZeroUsize) -> Self {
+        assert!(
+            quorum.get() <= self.upstreams.len(),
+            "quorum {quorum} exceeds the number of upstreams ({})",
+            self.upstreams.len()
+        );
+        self.quorum = quorum.get();
+        self
+    }
+
+    /// Sets methods responses for which will be cross-checked among upstreams.
+    #[must_use]
+    pub fn with_verified_methods(
+        mut self,
+        methods: impl IntoIterator<Item = &'static str>,
+    ) -> Self {
+        self.verified_methods = Arc::new(methods.into_iter().collect());
+        self
+    }
+
+    /// Checks whether a request should be cross-checked among upstreams.
+    fn is_verified_request(&self, method: &str, params: &RawParams) -> bool {
+        if self.upstreams.len() == 1 || !self.verified_methods.contains(method) {
+            return false;
+        }
+        if method == "eth_getLogs" {
+            return params.0.as_deref().map_or(false, is_pinned_logs_filter);
+        }
+        true
+    }
+
+    async fn quorum_request(
+        &self,
+        method: &str,
+        params: RawParams,
+    ) -> Result<serde_json::Value, Error> {
+        let requests = self
+            .upstreams
+            .iter()
+            .map(|upstream| upstream.generic_request(method, params.clone()));
+        let results = future::join_all(requests).await;
+
+        let network_label = self.network.metric_label();
+        // Responses grouped by value, together with indices of upstreams that returned them.
+        let mut groups: Vec<(serde_json::Value, Vec<usize>)> = vec![];
+        let mut first_error = None;
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(value) => {
+                    let matching_group = groups.iter_mut().find(|(val, _)| *val == value);
+                    if let Some((_, indices)) = matching_group {
+                        indices.push(i);
+                    } else {
+                        groups.push((value, vec![i]));
+                    }
+                }
+                Err(err) => {
+                    tracing::info!(
+                        network = network_label,
+                        component = self.component_name,
+                        "Request `{method}` to upstream #{i} failed: {err}"
+                    );
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+
+        let labels = QuorumLabels {
+            network: network_label.clone(),
+            method: method.to_owned(),
+        };
+        if groups.len() > 1 {
+            let upstream_groups: Vec<_> = groups.iter().map(|(_, indices)| indices).collect();
+            tracing::error!(
+                network = network_label,
+                component = self.component_name,
+                "Upstreams returned divergent responses for request `{method}`; upstreams grouped by response: {upstream_groups:?}"
+            );
+            QUORUM_METRICS.divergences[&labels].inc();
+        }
+
+        let best_group = groups.into_iter().max_by_key(|(_, indices)| indices.len());
+        match best_group {
+            Some((value, indices)) if indices.len() >= self.quorum => Ok(value),
+            Some((_, indices)) => {
+                QUORUM_METRICS.no_quorum[&labels].inc();
+                Err(Error::Transport(anyhow::anyhow!(
+                    "no quorum for request `{method}`: at most {} upstreams returned the same response, \
+                     while the quorum is {}",
+                    indices.len(),
+                    self.quorum
+                )))
+            }
+            None => Err(first_error.expect("no responses and no errors")),
+        }
+    }
+}
+
+impl<Net: Network> ForNetwork for QuorumClient<Net> {
+    type Net = Net;
+
+    fn network(&self) -> Self::Net {
+        self.network
+    }
+
+    fn component(&self) -> &'static str {
+        self.component_name
+    }
+}
+
+impl<Net: Network> TaggedClient for QuorumClient<Net> {
+    fn set_component(&mut self, component_name: &'static str) {
+        self.component_name = component_name;
+        self.upstreams = self
+            .upstreams
+            .iter()
+            .map(|upstream| upstream.clone().for_component(component_name))
+            .collect();
+    }
+}
+
+#[async_trait]
+impl<Net: Network> ClientT for QuorumClient<Net> {
+    async fn notification<Params>(&self, method: &str, params: Params) -> Result<(), Error>
+    where
+        Params: ToRpcParams + Send,
+    {
+        self.upstreams[0]
+            .generic_notification(method, RawParams::new(params)?)
+            .await
+    }
+
+    async fn request<R, Params>(&self, method: &str, params: Params) -> Result<R, Error>
+    where
+        R: DeserializeOwned,
+        Params: ToRpcParams + Send,
+    {
+        let params = RawParams::new(params)?;
+        let raw_response = if self.is_verified_request(method, &params) {
+            self.quorum_request(method, params).await?
+        } else {
+            self.upstreams[0].generic_request(method, params).await?
+        };
+        serde_json::from_value(raw_response).map_err(Error::ParseError)
+    }
+
+    async fn batch_request<'a, R>(
+        &self,
+        batch: BatchRequestBuilder<'a>,
+    ) -> Result<BatchResponse<'a, R>, Error>
+    where
+        R: DeserializeOwned + fmt::Debug + 'a,
+    {
+        let raw_responses = self.upstreams[0].generic_batch_request(batch).await?;
+        deserialize_batch_response(raw_responses)
+    }
+}
+
+/// Checks whether `eth_getLogs` params reference a block hash or an explicit block number range, i.e.,
+/// whether responses should be identical for all synced providers. Omitted range bounds default to `latest`.
+fn is_pinned_logs_filter(params: &JsonRawValue) -> bool {
+    let Ok(params) = serde_json::from_str::<Vec<serde_json::Value>>(params.get()) else {
+        return false;
+    };
+    let [filter] = params.as_slice() else {
+        return false;
+    };
+    if filter.get("blockHash").is_some() {
+        return true;
+    }
+    let is_block_number = |bound: Option<&serde_json::Value>| {
+        bound
+            .and_then(serde_json::Value::as_str)
+            .map_or(false, |bound| bound.starts_with("0x"))
+    };
+    is_block_number(filter.get("fromBlock")) && is_block_number(filter.get("toBlock"))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use jsonrpsee::rpc_params;
+    use zksync_types::U64;
+
+    use super::*;
+    use crate::client::{MockClient, L1};
+
+    fn mock_upstream(chain_id: u64, block_number: u64) -> Box<DynClient<L1>> {
+        let client = MockClient::builder(L1::default())
+            .method("eth_chainId", move || Ok(U64::from(chain_id)))
+            .method("eth_blockNumber", move || Ok(U64::from(block_number)))
+            .build();
+        Box::new(client)
+    }
+
+    async fn chain_id(client: &QuorumClient<L1>) -> Result<U64, Error> {
+        client.request("eth_chainId", rpc_params![]).await
+    }
+
+    #[tokio::test]
+    async fn verified_requests_require_quorum() {
+        let client = QuorumClient::new(vec![
+            mock_upstream(9, 100),
+            mock_upstream(9, 101),
+            mock_upstream(5, 102),
+        ]);
+        // 2 of 3 upstreams agree on the chain ID
+        assert_eq!(chain_id(&client).await.unwrap(), U64::from(9));
+
+        let client = client.with_quorum(NonZeroUsize::new(3).unwrap());
+        let err = chain_id(&client).await.unwrap_err();
+        assert_matches!(err, Error::Transport(_));
+    }
+
+    #[tokio::test]
+    async fn responses_are_compared_in_full() {
+        let receipt = serde_json::json!({
+            "transactionHash": "0x01",
+            "status": "0x1",
+            "logs": [],
+        });
+        let extended_receipt = serde_json::json!({
+            "transactionHash": "0x01",
+            "status": "0x1",
+            "logs": [],
+            "blobGasUsed": "0x0",
+        });
+        let upstreams = [receipt.clone(), extended_receipt, receipt]
+            .into_iter()
+            .map(|receipt| {
+                let client = MockClient::builder(L1::default())
+                    .method("eth_getTransactionReceipt", move |_hash: String| {
+                        Ok(receipt.clone())
+                    })
+                    .build();
+                Box::new(client) as Box<DynClient<L1>>
+            });
+        let client =
+            QuorumClient::new(upstreams.collect()).with_quorum(NonZeroUsize::new(3).unwrap());
+
+        let err = client
+            .request::<serde_json::Value, _>("eth_getTransactionReceipt", rpc_params!["0x01"])
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::Transport(_));
+
+        let client = client.with_quorum(NonZeroUsize::new(2).unwrap());
+        let receipt: serde_json::Value = client
+            .request("eth_getTransactionReceipt", rpc_params!["0x01"])
+            .await
+            .unwrap();
+        assert!(receipt.get("blobGasUsed").is_none());
+    }
+
+    #[tokio::test]
+    async fn unverified_requests_are_routed_to_primary_upstream() {
+        let client = QuorumClient::new(vec![mock_upstream(9, 100), mock_upstream(9, 101)]);
+        let block_number: U64 = client
+            .request("eth_blockNumber", rpc_params![])
+            .await
+            .unwrap();
+        assert_eq!(block_number, U64::from(100));
+    }
+
+    #[tokio::test]
+    async fn logs_requests_are_verified_only_for_pinned_ranges() {
+        let upstreams = [vec!["0x01"], vec!["0x02"]].into_iter().map(|logs| {
+            let client = MockClient::builder(L1::default())
+                .method("eth_getLogs", move |_filter: serde_json::Value| {
+                    Ok(logs.clone())
+                })
+                .build();
+            Box::new(client) as Box<DynClient<L1>>
+        });
+        let client = QuorumClient::new(upstreams.collect());
+
+        let pinned_filters = [
+            serde_json::json!({ "fromBlock": "0x1", "toBlock": "0x10" }),
+            serde_json::json!({ "blockHash": "0x01" }),
+        ];
+        for filter in pinned_filters {
+            let err = client
+                .request::<Vec<String>, _>("eth_getLogs", rpc_params![filter])
+                .await
+                .unwrap_err();
+            assert_matches!(err, Error::Transport(_));
+        }
+
+        let volatile_filters = [
+            serde_json::json!({ "fromBlock": "0x1", "toBlock": "latest" }),
+            serde_json::json!({ "fromBlock": "0x1" }),
+        ];
+        for filter in volatile_filters {
+            let logs: Vec<String> = client
+                .request("eth_getLogs", rpc_params![filter])
+                .await
+                .unwrap();
+            assert_eq!(logs, ["0x01"]);
+        }
+    }
+}
@@ -13,7 +13,7 @@ use test_casing::test_casing;
 use zksync_types::{L2ChainId, U64};
 
 use super::{
-    metrics::{HttpErrorLabels, RequestLabels, RpcErrorLabels},
+    metrics::{HttpErrorLabels, PriorityLabels, RequestLabels, RpcErrorLabels},
     *,
 };
 
@@ -21,7 +21,7 @@ use super::{
 struct MockService(Arc<Mutex<Vec<Instant>>>);
 
 async fn poll_service(limiter: &SharedRateLimit, service: &MockService) {
-    let _ = limiter.acquire(1).await;
+    let _ = limiter.acquire(1, CallPriority::Normal).await;
     service.0.lock().unwrap().push(Instant::now());
 }
 
@@ -82,6 +82,123 @@ async fn no_op_rate_limiting() {
     assert_eq!(diffs, [Duration::ZERO; 9]);
 }
 
+#[tokio::test]
+async fn rate_limiting_with_low_priority_calls() {
+    tokio::time::pause();
+
+    let limiter = SharedRateLimit::new(4, Duration::from_secs(1));
+    for _ in 0..3 {
+        let stats = limiter.acquire(1, CallPriority::Low).await;
+        assert!(!stats.was_waiting, "{stats:?}");
+    }
+    // The last request in the window is reserved for normal-priority calls.
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert!(!stats.was_waiting, "{stats:?}");
+
+    let limiter = SharedRateLimit::new(4, Duration::from_secs(1));
+    for _ in 0..3 {
+        let _ = limiter.acquire(1, CallPriority::Low).await;
+    }
+    let stats = limiter.acquire(1, CallPriority::Low).await;
+    assert!(stats.was_waiting, "{stats:?}");
+    assert!(stats.total_sleep_time > Duration::ZERO, "{stats:?}");
+    assert!(
+        stats.total_sleep_time <= Duration::from_secs(1),
+        "{stats:?}"
+    );
+}
+
+#[tokio::test]
+async fn rate_limiting_with_low_priority_calls_and_single_request_window() {
+    tokio::time::pause();
+
+    // Low-priority calls can use at most 3 of each 4 windows, even though each window allows a single request.
+    let limiter = SharedRateLimit::new(1, Duration::from_secs(1));
+    let start = Instant::now();
+    let mut call_times = vec![];
+    for _ in 0..6 {
+        let _ = limiter.acquire(1, CallPriority::Low).await;
+        call_times.push(Instant::now() - start);
+    }
+    assert_eq!(call_times, [1, 2, 3, 5, 6, 7].map(Duration::from_secs));
+}
+
+#[tokio::test]
+async fn adaptive_rate_limiting() {
+    tokio::time::pause();
+
+    let mut limiter = SharedRateLimit::new(8, Duration::from_secs(1));
+    assert_eq!(limiter.back_off(), None); // adaptivity is disabled
+    limiter.adaptive = true;
+
+    assert_eq!(limiter.back_off().map(|limit| limit.requests), Some(4));
+    // The limit is decreased at most once per window.
+    assert_eq!(limiter.back_off(), None);
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert_eq!(stats.current_limit.requests, 4);
+    for _ in 0..3 {
+        let stats = limiter.acquire(1, CallPriority::Normal).await;
+        assert!(!stats.was_waiting, "{stats:?}");
+    }
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert!(stats.was_waiting, "{stats:?}");
+
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    assert_eq!(limiter.back_off().map(|limit| limit.requests), Some(2));
+    tokio::time::sleep(Duration::from_secs(1)).await;
+    // The limit should be partially restored when a new window starts.
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert_eq!(stats.current_limit.requests, 3);
+    for _ in 0..10 {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+        let _ = limiter.acquire(1, CallPriority::Normal).await;
+    }
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert_eq!(stats.current_limit.requests, 8);
+    assert_eq!(stats.current_limit.window, Duration::from_secs(1));
+}
+
+#[tokio::test]
+async fn adaptive_rate_limiting_with_single_request_window() {
+    tokio::time::pause();
+
+    let window = Duration::from_millis(100);
+    let mut limiter = SharedRateLimit::new(1, window);
+    limiter.adaptive = true;
+
+    // The number of requests cannot be decreased, so the window is increased instead.
+    assert_eq!(
+        limiter.back_off(),
+        Some(CurrentRateLimit {
+            requests: 1,
+            window: window * 2,
+        })
+    );
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert!(!stats.was_waiting, "{stats:?}");
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert_eq!(stats.total_sleep_time, window * 2);
+
+    for multiplier in [4, 8, 16] {
+        tokio::time::sleep(window * 16).await;
+        let new_limit = limiter.back_off().unwrap();
+        assert_eq!(new_limit.window, window * multiplier);
+    }
+    tokio::time::sleep(window * 16).await;
+    // The window cannot be increased further.
+    assert_eq!(limiter.back_off(), None);
+
+    // The window should be gradually restored when new windows start.
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert_eq!(stats.current_limit.window, window * 8);
+    for _ in 0..5 {
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        let _ = limiter.acquire(1, CallPriority::Normal).await;
+    }
+    let stats = limiter.acquire(1, CallPriority::Normal).await;
+    assert_eq!(stats.current_limit.window, window);
+}
+
 fn timestamp_diffs(timestamps: &[Instant]) -> Vec<Duration> {
     let diffs = timestamps.windows(2).map(|window| match window {
         [prev, next] => *next - *prev,
@@ -272,3 +389,51 @@ async fn wrapping_mock_client() {
     };
     assert!(metrics.http_errors.contains(&labels), "{metrics:?}");
 }
+
+#[tokio::test]
+async fn adaptive_rate_limiting_with_mock_client() {
+    tokio::time::pause();
+
+    let client = MockClient::builder(L2::default())
+        .method("ok", || Ok("ok"))
+        .method("rate_limit", || {
+            let http_err = transport::Error::RequestFailure { status_code: 429 };
+            Err::<(), _>(Error::Transport(http_err.into()))
+        })
+        .build();
+
+    let mut client = ClientBuilder::<L2, _>::new(client, "http://localhost".parse().unwrap())
+        .for_network(L2ChainId::default().into())
+        .with_allowed_requests_per_second(NonZeroUsize::new(100).unwrap())
+        .with_adaptive_rate_limit(true)
+        .with_low_priority_components(["background"])
+        .build();
+    client.set_component("background");
+    assert_eq!(client.priority(), CallPriority::Low);
+    client.set_component("test");
+    assert_eq!(client.priority(), CallPriority::Normal);
+
+    let metrics = &*Box::leak(Box::default());
+    client.metrics = metrics;
+
+    client
+        .request::<String, _>("rate_limit", rpc_params![])
+        .await
+        .unwrap_err();
+    let network_label = "l2_270".to_string();
+    assert_eq!(metrics.rate_limit_backoffs[&network_label].get(), 1);
+    // The limit is decreased from 5 to 2 requests per 50ms window.
+    let current_rate_limit = metrics.current_rate_limit[&network_label].get();
+    assert!(
+        (current_rate_limit - 40.0).abs() < 1e-6,
+        "{current_rate_limit}"
+    );
+
+    let output: String = client.request("ok", rpc_params![]).await.unwrap();
+    assert_eq!(output, "ok");
+    let labels = PriorityLabels {
+        network: network_label,
+        priority: CallPriority::Normal,
+    };
+    assert_eq!(metrics.calls[&labels].get(), 2);
+}
@@ -14,7 +14,11 @@ use std::{
 use jsonrpsee::{core::ClientError, types::error::ErrorCode};
 use pin_project_lite::pin_project;
 use thiserror::Error;
-use zksync_types::{api::SerializationTransactionError, L1BatchNumber, L2BlockNumber};
+use zksync_error_codes::{self as error_codes, HasErrorCode};
+use zksync_types::{
+    api::{LogsCursor, SerializationTransactionError},
+    L1BatchNumber, L2BlockNumber,
+};
 
 /// Server-side representation of the RPC error.
 #[derive(Debug, Error)]
@@ -35,10 +39,22 @@ pub enum Web3Error {
     TooManyTopics,
     #[error("Filter not found")]
     FilterNotFound,
+    /// The last field is a cursor to retrieve logs in the requested block range with `zks_getLogsPaged`.
+    /// It's only set if logs pagination is enabled on the server.
     #[error("Query returned more than {0} results. Try with this block range [{1:#x}, {2:#x}].")]
-    LogsLimitExceeded(usize, u32, u32),
+    LogsLimitExceeded(usize, u32, u32, Option<LogsCursor>),
     #[error("invalid filter: if blockHash is supplied fromBlock and toBlock must not be")]
     InvalidFilterBlockHash,
+    #[error("invalid reward percentiles: values must be in [0, 100] and monotonically increasing")]
+    InvalidRewardPercentiles,
+    #[error("Too many transactions in fee estimation batch; the limit is {0}")]
+    TooManyTransactions(usize),
+    #[error("Invalid state override: {0}")]
+    InvalidStateOverride(String),
+    #[error("Unknown tracer: {0}")]
+    UnknownTracer(String),
+    #[error("Too many storage keys requested; the limit is {0}")]
+    TooManyStorageKeys(usize),
     /// Weaker form of a "method not found" error; the method implementation is technically present,
     /// but the node configuration prevents the method from functioning.
     #[error("Method not implemented")]
@@ -46,10 +62,40 @@ pub enum Web3Error {
     /// Unavailability caused by node configuration is returned as [`Self::MethodNotImplemented`].
     #[error("Tree API is temporarily unavailable")]
     TreeApiUnavailable,
+    /// The server sheds load because its storage is overloaded.
+    #[error("Server is overloaded; try again later")]
+    ServerOverloaded,
     #[error("Internal error")]
     InternalError(#[from] anyhow::Error),
 }
 
+impl HasErrorCode for Web3Error {
+    fn error_code(&self) -> error_codes::ErrorCode {
+        use error_codes::ErrorCode as Code;
+
+        match self {
+            Self::NoBlock => Code::ApiNoBlock,
+            Self::PrunedBlock(_) | Self::PrunedL1Batch(_) => Code::ApiPruned,
+            Self::ProxyError(_) => Code::ApiProxy,
+            Self::SubmitTransactionError(..) => Code::ApiTransactionRejected,
+            Self::SerializationError(_) => Code::ApiTransactionSerialization,
+            Self::TooManyTopics => Code::ApiTooManyTopics,
+            Self::FilterNotFound => Code::ApiFilterNotFound,
+            Self::LogsLimitExceeded(..) => Code::ApiLogsLimitExceeded,
+            Self::InvalidFilterBlockHash => Code::ApiInvalidFilterBlockHash,
+            Self::InvalidRewardPercentiles => Code::ApiInvalidRewardPercentiles,
+            Self::TooManyTransactions(_) => Code::ApiTooManyTransactions,
+            Self::InvalidStateOverride(_) => Code::ApiInvalidStateOverride,
+            Self::UnknownTracer(_) => Code::ApiUnknownTracer,
+            Self::TooManyStorageKeys(_) => Code::ApiTooManyStorageKeys,
+            Self::MethodNotImplemented => Code::ApiMethodNotImplemented,
+            Self::TreeApiUnavailable => Code::TreeApiUnavailable,
+            Self::ServerOverloaded => Code::ApiServerOverloaded,
+            Self::InternalError(_) => Code::ApiInternal,
+        }
+    }
+}
+
 /// Client RPC error with additional details: the method name and arguments of the called method.
 ///
 /// The wrapped error can be accessed using [`AsRef`].
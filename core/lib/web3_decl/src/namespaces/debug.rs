@@ -2,7 +2,7 @@
 use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{BlockId, BlockNumber, DebugCall, DebugTrace, ResultDebugTrace, TracerConfig},
     debug_flat_call::DebugCallFlat,
     transaction_request::CallRequest,
 };
@@ -26,7 +26,7 @@ pub trait DebugNamespace {
         &self,
         block: BlockNumber,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Vec<ResultDebugCall>>;
+    ) -> RpcResult<Vec<ResultDebugTrace>>;
 
     #[method(name = "traceBlockByNumber.callFlatTracer")]
     async fn trace_block_by_number_flat(
@@ -40,7 +40,7 @@ pub trait DebugNamespace {
         &self,
         hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Vec<ResultDebugCall>>;
+    ) -> RpcResult<Vec<ResultDebugTrace>>;
 
     #[method(name = "traceCall")]
     async fn trace_call(
@@ -55,5 +55,5 @@ pub trait DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> RpcResult<Option<DebugCall>>;
+    ) -> RpcResult<Option<DebugTrace>>;
 }
@@ -31,6 +31,13 @@ pub trait EnNamespace {
     #[method(name = "syncTokens")]
     async fn sync_tokens(&self, block_number: Option<L2BlockNumber>) -> RpcResult<Vec<TokenInfo>>;
 
+    /// Returns the snapshot recovery status of the node, or `null` if the node wasn't recovered from a snapshot.
+    ///
+    /// Since the API server is started after snapshot recovery, this method doesn't report progress
+    /// of an ongoing recovery; such progress is reported by the healthcheck server and Prometheus metrics.
+    #[method(name = "snapshotRecoveryStatus")]
+    async fn snapshot_recovery_status(&self) -> RpcResult<Option<en::SnapshotRecoveryStatus>>;
+
     /// Get genesis configuration
     #[method(name = "genesisConfig")]
     async fn genesis_config(&self) -> RpcResult<GenesisConfig>;
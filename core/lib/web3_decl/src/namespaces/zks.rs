@@ -5,18 +5,19 @@ use jsonrpsee::core::RpcResult;
 use jsonrpsee::proc_macros::rpc;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Proof, ProtocolVersion,
-        TransactionDetailedResult, TransactionDetails,
+        BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, LogsCursor, LogsPage,
+        PageCursor, Proof, ProofRequest, ProtocolVersion, SimulationBlockId, SimulationOptions,
+        TransactionDetailedResult, TransactionDetails, TransactionSimulation, TransactionsPage,
     },
     fee::Fee,
-    fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
+    fee_model::{FeeModelBreakdown, FeeParams, PubdataIndependentBatchFeeModelInput},
     transaction_request::CallRequest,
     Address, L1BatchNumber, L2BlockNumber, H256, U256, U64,
 };
 
 use crate::{
     client::{ForNetwork, L2},
-    types::{Bytes, Token},
+    types::{Bytes, Filter, Token},
 };
 
 #[cfg_attr(
@@ -31,6 +32,9 @@ pub trait ZksNamespace {
     #[method(name = "estimateFee")]
     async fn estimate_fee(&self, req: CallRequest) -> RpcResult<Fee>;
 
+    #[method(name = "estimateFeeBatch")]
+    async fn estimate_fee_batch(&self, reqs: Vec<CallRequest>) -> RpcResult<Vec<Fee>>;
+
     #[method(name = "estimateGasL1ToL2")]
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256>;
 
@@ -109,6 +113,9 @@ pub trait ZksNamespace {
     #[method(name = "getFeeParams")]
     async fn get_fee_params(&self) -> RpcResult<FeeParams>;
 
+    #[method(name = "getFeeModelBreakdown")]
+    async fn get_fee_model_breakdown(&self) -> RpcResult<FeeModelBreakdown>;
+
     #[method(name = "getProtocolVersion")]
     async fn get_protocol_version(
         &self,
@@ -123,12 +130,57 @@ pub trait ZksNamespace {
         l1_batch_number: L1BatchNumber,
     ) -> RpcResult<Option<Proof>>;
 
+    /// Returns proofs for multiple accounts at the specified L1 batch. Proofs for all accounts are obtained
+    /// with a single Merkle tree traversal.
+    #[method(name = "getProofs")]
+    async fn get_proofs(
+        &self,
+        accounts: Vec<ProofRequest>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<Vec<Proof>>>;
+
     #[method(name = "getBatchFeeInput")]
     async fn get_batch_fee_input(&self) -> RpcResult<PubdataIndependentBatchFeeModelInput>;
 
+    #[method(name = "getLogsPaged")]
+    async fn get_logs_paged(
+        &self,
+        filter: Filter,
+        cursor: Option<LogsCursor>,
+    ) -> RpcResult<LogsPage>;
+
+    /// Returns executed transactions initiated by the specified account, from the most recent to the oldest one.
+    /// Pass the returned cursor to the next call to get the next page.
+    #[method(name = "getAccountTransactionsPaged")]
+    async fn get_account_transactions_paged(
+        &self,
+        address: Address,
+        cursor: Option<PageCursor>,
+    ) -> RpcResult<TransactionsPage>;
+
+    /// Returns ERC-20 / ERC-721 `Transfer` events from or to the specified account, from the most recent
+    /// to the oldest one. Pass the returned cursor to the next call to get the next page.
+    #[method(name = "getTokenTransfersPaged")]
+    async fn get_token_transfers_paged(
+        &self,
+        address: Address,
+        cursor: Option<PageCursor>,
+    ) -> RpcResult<LogsPage>;
+
     #[method(name = "sendRawTransactionWithDetailedOutput")]
     async fn send_raw_transaction_with_detailed_output(
         &self,
         tx_bytes: Bytes,
     ) -> RpcResult<TransactionDetailedResult>;
+
+    /// Executes a transaction against historical state, optionally with state overrides, and returns its trace.
+    /// Unlike `eth_call`, the historical state can be specified by an L1 batch, and reverts are returned as a part
+    /// of the trace rather than as errors.
+    #[method(name = "simulateTransaction")]
+    async fn simulate_transaction(
+        &self,
+        req: CallRequest,
+        block: SimulationBlockId,
+        options: Option<SimulationOptions>,
+    ) -> RpcResult<TransactionSimulation>;
 }
@@ -43,12 +43,13 @@ use zksync_eth_watch::{EthHttpQueryClient, EthWatch};
 use zksync_health_check::{AppHealthCheck, HealthStatus, ReactiveHealthCheck};
 use zksync_house_keeper::{
     blocks_state_reporter::L1BatchMetricsReporter,
+    partition_manager::PartitionManager,
     periodic_job::PeriodicJob,
     prover::{
         FriGpuProverArchiver, FriProofCompressorJobRetryManager, FriProofCompressorQueueReporter,
         FriProverJobRetryManager, FriProverJobsArchiver, FriProverQueueReporter,
-        FriWitnessGeneratorJobRetryManager, FriWitnessGeneratorQueueReporter,
-        WaitingToQueuedFriWitnessJobMover,
+        FriWitnessGeneratorJobRetryManager, FriWitnessGeneratorQueueReporter, ProverJobMonitor,
+        ProverStageMaxAttempts, WaitingToQueuedFriWitnessJobMover,
     },
 };
 use zksync_metadata_calculator::{
@@ -57,7 +58,10 @@ use zksync_metadata_calculator::{
 use zksync_node_api_server::{
     healthcheck::HealthCheckHandle,
     tx_sender::{build_tx_sender, TxSenderConfig},
-    web3::{self, mempool_cache::MempoolCache, state::InternalApiConfig, Namespace},
+    web3::{
+        self, mempool_cache::MempoolCache, response_cache::ResponseCache, state::InternalApiConfig,
+        Namespace,
+    },
 };
 use zksync_node_fee_model::{
     l1_gas_price::GasAdjusterSingleton, BatchFeeModelInputProvider, MainNodeFeeInputProvider,
@@ -69,7 +73,8 @@ use zksync_shared_metrics::{InitStage, APP_METRICS};
 use zksync_state::{PostgresStorageCaches, RocksdbStorageOptions};
 use zksync_state_keeper::{
     create_state_keeper, io::seal_logic::l2_block_seal_subtasks::L2BlockSealProcess,
-    AsyncRocksdbCache, MempoolFetcher, MempoolGuard, OutputHandler, StateKeeperPersistence,
+    AdaptiveSealQueueConfig, AsyncRocksdbCache, MempoolFetcher, MempoolGuard, OutputHandler,
+    StateKeeperPersistence,
 };
 use zksync_tee_verifier_input_producer::TeeVerifierInputProducer;
 use zksync_types::{ethabi::Contract, fee_model::FeeModelConfig, Address, L2ChainId};
@@ -123,7 +128,7 @@ pub fn setup_sigint_handler() -> oneshot::Receiver<()> {
     sigint_receiver
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Component {
     /// Public Web3 API running on HTTP server.
     HttpApi,
@@ -131,6 +136,8 @@ pub enum Component {
     WsApi,
     /// REST API for contract verification.
     ContractVerificationApi,
+    /// Minimal block explorer REST API.
+    ExplorerApi,
     /// Metadata calculator.
     Tree,
     /// Merkle tree API.
@@ -153,6 +160,10 @@ pub enum Component {
     Consensus,
     /// Component generating commitment for L1 batches.
     CommitmentGenerator,
+    /// VM runner recomputing protective reads for sealed L1 batches and comparing them with the persisted ones.
+    VmRunnerProtectiveReadsVerifier,
+    /// VM runner replaying a range of historical L1 batches and verifying their protective reads.
+    VmRunnerReplay,
 }
 
 #[derive(Debug)]
@@ -171,6 +182,7 @@ impl FromStr for Components {
             "http_api" => Ok(Components(vec![Component::HttpApi])),
             "ws_api" => Ok(Components(vec![Component::WsApi])),
             "contract_verification_api" => Ok(Components(vec![Component::ContractVerificationApi])),
+            "explorer_api" => Ok(Components(vec![Component::ExplorerApi])),
             "tree" => Ok(Components(vec![Component::Tree])),
             "tree_api" => Ok(Components(vec![Component::TreeApi])),
             "state_keeper" => Ok(Components(vec![Component::StateKeeper])),
@@ -189,6 +201,10 @@ impl FromStr for Components {
             "proof_data_handler" => Ok(Components(vec![Component::ProofDataHandler])),
             "consensus" => Ok(Components(vec![Component::Consensus])),
             "commitment_generator" => Ok(Components(vec![Component::CommitmentGenerator])),
+            "vm_runner_protective_reads_verifier" => {
+                Ok(Components(vec![Component::VmRunnerProtectiveReadsVerifier]))
+            }
+            "vm_runner_replay" => Ok(Components(vec![Component::VmRunnerReplay])),
             other => Err(format!("{} is not a valid component name", other)),
         }
     }
@@ -293,7 +309,7 @@ pub async fn initialize_components(
         .prometheus_config
         .clone()
         .context("prometheus_config")?;
-    let prom_config = PrometheusExporterConfig::pull(prom_config.listener_port);
+    let prom_config = PrometheusExporterConfig::from_config(&prom_config, "zksync-server")?;
 
     let (prometheus_health_check, prometheus_health_updater) =
         ReactiveHealthCheck::new("prometheus_exporter");
@@ -348,6 +364,10 @@ pub async fn initialize_components(
         task_futures.push(tokio::spawn(
             mempool_cache_update_task.run(stop_receiver.clone()),
         ));
+        let response_cache =
+            build_response_cache(&api_config.web3_json_rpc, genesis_config.l2_chain_id)
+                .await
+                .context("build_response_cache()")?;
 
         if components.contains(&Component::HttpApi) {
             storage_caches = Some(
@@ -385,6 +405,7 @@ pub async fn initialize_components(
                 state_keeper_config.save_call_traces,
                 storage_caches.clone().unwrap(),
                 mempool_cache.clone(),
+                response_cache.clone(),
             )
             .await
             .context("run_http_api")?;
@@ -433,6 +454,7 @@ pub async fn initialize_components(
                 stop_receiver.clone(),
                 storage_caches,
                 mempool_cache,
+                response_cache,
             )
             .await
             .context("run_ws_api")?;
@@ -465,6 +487,21 @@ pub async fn initialize_components(
         }
     }
 
+    if components.contains(&Component::ExplorerApi) {
+        let started_at = Instant::now();
+        tracing::info!("initializing explorer REST API");
+        let api_config = configs.api_config.clone().context("api_config")?;
+        task_futures.push(tokio::spawn(zksync_node_explorer_api::start_server(
+            replica_connection_pool.clone(),
+            api_config.explorer,
+            l2_chain_id,
+            stop_receiver.clone(),
+        )));
+        let elapsed = started_at.elapsed();
+        APP_METRICS.init_latency[&InitStage::ExplorerApi].set(elapsed);
+        tracing::info!("initialized explorer REST API in {elapsed:?}");
+    }
+
     let object_store_config = configs
         .prover_config
         .clone()
@@ -701,6 +738,7 @@ pub async fn initialize_components(
             Box::new(eth_client),
             eth_client_blobs,
         );
+        app_health.insert_component(eth_tx_manager_actor.health_check())?;
         task_futures.extend([tokio::spawn(
             eth_tx_manager_actor.run(stop_receiver.clone()),
         )]);
@@ -779,6 +817,12 @@ pub async fn initialize_components(
         ));
     }
 
+    if components.contains(&Component::VmRunnerProtectiveReadsVerifier)
+        || components.contains(&Component::VmRunnerReplay)
+    {
+        anyhow::bail!("VM runner components are only supported with the node framework (`--use-node-framework`)");
+    }
+
     // Run healthcheck server for all components.
     let db_health_check = ConnectionPoolHealthCheck::new(replica_connection_pool);
     app_health.insert_custom_component(Arc::new(db_health_check))?;
@@ -827,13 +871,22 @@ async fn add_state_keeper_to_task_futures(
     .build()
     .await
     .context("failed to build l2_block_sealer_pool")?;
-    let (persistence, l2_block_sealer) = StateKeeperPersistence::new(
-        l2_block_sealer_pool,
-        contracts_config
-            .l2_shared_bridge_addr
-            .context("`l2_shared_bridge_addr` config is missing")?,
-        state_keeper_config.l2_block_seal_queue_capacity,
-    );
+    let l2_shared_bridge_addr = contracts_config
+        .l2_shared_bridge_addr
+        .context("`l2_shared_bridge_addr` config is missing")?;
+    let (persistence, l2_block_sealer) =
+        match AdaptiveSealQueueConfig::from_state_keeper_config(&state_keeper_config) {
+            Some(queue_config) => StateKeeperPersistence::new_adaptive(
+                l2_block_sealer_pool,
+                l2_shared_bridge_addr,
+                queue_config,
+            ),
+            None => StateKeeperPersistence::new(
+                l2_block_sealer_pool,
+                l2_shared_bridge_addr,
+                state_keeper_config.l2_block_seal_queue_capacity,
+            ),
+        };
     task_futures.push(tokio::spawn(l2_block_sealer.run()));
 
     // One (potentially held long-term) connection for `AsyncCatchupTask` and another connection
@@ -900,14 +953,14 @@ pub async fn start_eth_watch(
     stop_receiver: watch::Receiver<bool>,
 ) -> anyhow::Result<JoinHandle<anyhow::Result<()>>> {
     let eth_client = EthHttpQueryClient::new(
-        eth_gateway,
+        eth_gateway.clone(),
         diamond_proxy_addr,
         state_transition_manager_addr,
         governance.1,
         config.confirmations_for_eth_event,
     );
 
-    let eth_watch = EthWatch::new(
+    let mut eth_watch = EthWatch::new(
         diamond_proxy_addr,
         state_transition_manager_addr,
         &governance.0,
@@ -916,6 +969,23 @@ pub async fn start_eth_watch(
         config.poll_interval(),
     )
     .await?;
+    for source in &config.additional_sources {
+        let source_client = EthHttpQueryClient::new(
+            eth_gateway.clone(),
+            source.diamond_proxy_addr,
+            source.state_transition_manager_addr,
+            source.governance_addr,
+            source.confirmations_for_eth_event,
+        );
+        eth_watch = eth_watch
+            .with_additional_source(
+                source.diamond_proxy_addr,
+                source.state_transition_manager_addr,
+                Box::new(source_client),
+            )
+            .await
+            .with_context(|| format!("cannot add eth_watch source {source:?}"))?;
+    }
 
     Ok(tokio::spawn(eth_watch.run(stop_receiver)))
 }
@@ -1018,6 +1088,20 @@ async fn run_tree(
                 .run_api_server(address, stop_receiver)
                 .await
         }));
+
+        if let Some(grpc_port) = api_config.grpc_port {
+            let address = (Ipv4Addr::UNSPECIFIED, grpc_port).into();
+            let tree_reader = metadata_calculator.tree_reader();
+            let stop_receiver = stop_receiver.clone();
+            task_futures.push(tokio::spawn(async move {
+                tree_reader
+                    .wait()
+                    .await
+                    .context("Cannot initialize tree reader")?
+                    .run_grpc_server(address, stop_receiver)
+                    .await
+            }));
+        }
     }
 
     let tree_health_check = metadata_calculator.tree_health_check();
@@ -1103,6 +1187,20 @@ async fn add_house_keeper_to_task_futures(
     let task = l1_batch_metrics_reporter.run(stop_receiver.clone());
     task_futures.push(tokio::spawn(task));
 
+    // Partitions are managed using DDL statements, so they require the master pool.
+    const PARTITION_MANAGER_INTERVAL_MS: u64 = 60_000;
+    let master_pool = ConnectionPool::<Core>::singleton(secrets.master_url()?)
+        .build()
+        .await
+        .context("failed to build a master_pool for partition manager")?;
+    let partition_manager = PartitionManager::new(
+        master_pool,
+        PartitionManager::DEFAULT_PARTITION_SIZE,
+        PARTITION_MANAGER_INTERVAL_MS,
+    );
+    let task = partition_manager.run(stop_receiver.clone());
+    task_futures.push(tokio::spawn(task));
+
     // All FRI Prover related components are configured below.
     let fri_prover_config = configs.prover_config.clone().context("fri_prover_config")?;
     let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
@@ -1190,6 +1288,23 @@ async fn add_house_keeper_to_task_futures(
     let task = fri_proof_compressor_stats_reporter.run(stop_receiver.clone());
     task_futures.push(tokio::spawn(task));
 
+    if let Some(monitoring_interval_ms) = house_keeper_config.prover_job_monitor_interval_ms {
+        let max_attempts = ProverStageMaxAttempts {
+            witness_generation: fri_witness_gen_config.max_attempts,
+            proving: fri_prover_config.max_attempts,
+            proof_compression: proof_compressor_config.max_attempts,
+        };
+        let prover_job_monitor = ProverJobMonitor::new(
+            prover_connection_pool.clone(),
+            house_keeper_config.prover_stage_slas(),
+            house_keeper_config.prover_job_monitor_stuck_job_threshold(),
+            max_attempts,
+            monitoring_interval_ms,
+        );
+        let task = prover_job_monitor.run(stop_receiver.clone());
+        task_futures.push(tokio::spawn(task));
+    }
+
     let fri_proof_compressor_retry_manager = FriProofCompressorJobRetryManager::new(
         proof_compressor_config.max_attempts,
         proof_compressor_config.generation_timeout(),
@@ -1237,6 +1352,7 @@ async fn run_http_api(
     with_debug_namespace: bool,
     storage_caches: PostgresStorageCaches,
     mempool_cache: MempoolCache,
+    response_cache: Option<ResponseCache>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1271,6 +1387,9 @@ async fn run_http_api(
             .with_vm_barrier(vm_barrier)
             .with_mempool_cache(mempool_cache)
             .enable_api_namespaces(namespaces);
+    if let Some(response_cache) = response_cache {
+        api_builder = api_builder.with_response_cache(response_cache);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
@@ -1302,6 +1421,7 @@ async fn run_ws_api(
     stop_receiver: watch::Receiver<bool>,
     storage_caches: PostgresStorageCaches,
     mempool_cache: MempoolCache,
+    response_cache: Option<ResponseCache>,
 ) -> anyhow::Result<()> {
     let (tx_sender, vm_barrier) = build_tx_sender(
         tx_sender_config,
@@ -1339,6 +1459,9 @@ async fn run_ws_api(
             .with_vm_barrier(vm_barrier)
             .with_mempool_cache(mempool_cache)
             .enable_api_namespaces(namespaces);
+    if let Some(response_cache) = response_cache {
+        api_builder = api_builder.with_response_cache(response_cache);
+    }
     if let Some(tree_api_url) = api_config.web3_json_rpc.tree_api_url() {
         let tree_api = Arc::new(TreeApiHttpClient::new(tree_api_url));
         api_builder = api_builder.with_tree_api(tree_api.clone());
@@ -1355,6 +1478,24 @@ async fn run_ws_api(
     Ok(())
 }
 
+async fn build_response_cache(
+    web3_config: &Web3JsonRpcConfig,
+    chain_id: L2ChainId,
+) -> anyhow::Result<Option<ResponseCache>> {
+    if !web3_config.response_cache_enabled() {
+        return Ok(None);
+    }
+    let size = web3_config.response_cache_size();
+    let ttl = web3_config.response_cache_ttl();
+    tracing::info!("Using API response cache with size {size}B and TTL {ttl:?}");
+    let mut cache = ResponseCache::new(size, ttl);
+    if let Some(redis_url) = &web3_config.response_cache_redis_url {
+        tracing::info!("Sharing API response cache via Redis at {redis_url:?}");
+        cache = cache.with_redis(redis_url, chain_id).await?;
+    }
+    Ok(Some(cache))
+}
+
 async fn circuit_breakers_for_components(
     components: &[Component],
     database_secrets: &DatabaseSecrets,
@@ -11,12 +11,15 @@ use zksync_config::{
         FriProofCompressorConfig, FriProverConfig, FriProverGatewayConfig,
         FriWitnessGeneratorConfig, FriWitnessVectorGeneratorConfig, GeneralConfig,
         ObservabilityConfig, PrometheusConfig, ProofDataHandlerConfig,
+        ProtectiveReadsVerifierConfig, VmReplayConfig,
     },
     ApiConfig, ContractVerifierConfig, DBConfig, EthConfig, EthWatchConfig, GasAdjusterConfig,
     ObjectStoreConfig, PostgresConfig, SnapshotsCreatorConfig,
 };
 use zksync_protobuf::{repr::ProtoRepr, ProtoFmt};
 
+mod secret_refs;
+
 pub fn decode_yaml<T: ProtoFmt>(yaml: &str) -> anyhow::Result<T> {
     let d = serde_yaml::Deserializer::from_str(yaml);
     let this: T = zksync_protobuf::serde::deserialize(d)?;
@@ -28,6 +31,15 @@ pub fn decode_yaml_repr<T: ProtoRepr>(yaml: &str) -> anyhow::Result<T::Type> {
     let this: T = zksync_protobuf::serde::deserialize_proto_with_options(d, false)?;
     this.read()
 }
+
+/// Same as [`decode_yaml_repr()`], but additionally resolves `env://<VAR>` and `file://<path>` secret references
+/// among string values before decoding. Should be used for configs containing secrets.
+pub fn decode_secrets_yaml_repr<T: ProtoRepr>(yaml: &str) -> anyhow::Result<T::Type> {
+    let mut value: serde_yaml::Value = serde_yaml::from_str(yaml)?;
+    secret_refs::resolve_secret_refs(&mut value)?;
+    let this: T = zksync_protobuf::serde::deserialize_proto_with_options(value, false)?;
+    this.read()
+}
 //
 // TODO (QIT-22): This structure is going to be removed when components will be responsible for their own configs.
 /// A temporary config store allowing to pass deserialized configs from `zksync_server` to `zksync_core`.
@@ -61,6 +73,8 @@ pub struct TempConfigStore {
     pub object_store_config: Option<ObjectStoreConfig>,
     pub observability: Option<ObservabilityConfig>,
     pub snapshot_creator: Option<SnapshotsCreatorConfig>,
+    pub protective_reads_verifier_config: Option<ProtectiveReadsVerifierConfig>,
+    pub vm_replay_config: Option<VmReplayConfig>,
 }
 
 impl TempConfigStore {
@@ -86,6 +100,8 @@ impl TempConfigStore {
             eth: self.eth_sender_config.clone(),
             snapshot_creator: self.snapshot_creator.clone(),
             observability: self.observability.clone(),
+            protective_reads_verifier_config: self.protective_reads_verifier_config.clone(),
+            vm_replay_config: self.vm_replay_config.clone(),
         }
     }
 
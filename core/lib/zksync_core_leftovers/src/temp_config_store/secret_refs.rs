@@ -0,0 +1,107 @@
+//! Resolution of secret references in YAML configs.
+
+use std::{env, fs};
+
+use anyhow::Context as _;
+
+/// Resolves secret references among string values in `value`, recursively. A string value can reference a secret
+/// using one of the following URIs:
+///
+/// - `env://<VAR>` reads the environment variable `VAR`.
+/// - `file://<path>` reads the file at `path`, trimming whitespace around the contents.
+///
+/// Other secret providers supported by the tooling (Vault, cloud secret managers) cannot be accessed by the node,
+/// so such references must be resolved by the tooling (e.g., into env variables) before launching the node.
+pub(super) fn resolve_secret_refs(value: &mut serde_yaml::Value) -> anyhow::Result<()> {
+    match value {
+        serde_yaml::Value::String(s) => {
+            if let Some(secret) = resolve_secret_ref(s)? {
+                *s = secret;
+            }
+        }
+        serde_yaml::Value::Sequence(values) => {
+            for value in values {
+                resolve_secret_refs(value)?;
+            }
+        }
+        serde_yaml::Value::Mapping(map) => {
+            for (_, value) in map.iter_mut() {
+                resolve_secret_refs(value)?;
+            }
+        }
+        serde_yaml::Value::Tagged(tagged) => resolve_secret_refs(&mut tagged.value)?,
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Returns `Ok(None)` if `value` is not a secret reference.
+fn resolve_secret_ref(value: &str) -> anyhow::Result<Option<String>> {
+    const UNSUPPORTED_SCHEMES: &[&str] = &["vault", "aws-sm", "gcp-sm"];
+
+    let Some((scheme, path)) = value.split_once("://") else {
+        return Ok(None);
+    };
+    match scheme {
+        "env" => {
+            let secret =
+                env::var(path).with_context(|| format!("failed reading env variable `{path}`"))?;
+            Ok(Some(secret))
+        }
+        "file" => {
+            let secret = fs::read_to_string(path)
+                .with_context(|| format!("failed reading secret file `{path}`"))?;
+            Ok(Some(secret.trim().to_owned()))
+        }
+        _ if UNSUPPORTED_SCHEMES.contains(&scheme) => {
+            // Don't output the entire value in case it's not a reference after all.
+            anyhow::bail!(
+                "`{scheme}://` secret references cannot be resolved by the node; resolve them before launching the node"
+            )
+        }
+        _ => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write as _;
+
+    use super::*;
+
+    #[test]
+    fn resolving_secret_refs() {
+        let mut secret_file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(secret_file, "  file_secret  ").unwrap();
+        let var_name = "ZKSYNC_TEST_SECRET_REFS_DB_URL";
+        env::set_var(var_name, "postgres://localhost/zksync");
+
+        let yaml = format!(
+            "database:\n  server_url: env://{var_name}\n  prover_url: postgres://localhost/prover\n\
+             l1:\n  l1_rpc_url: file://{}\n",
+            secret_file.path().display()
+        );
+        let mut value: serde_yaml::Value = serde_yaml::from_str(&yaml).unwrap();
+        resolve_secret_refs(&mut value).unwrap();
+        assert_eq!(
+            value["database"]["server_url"].as_str(),
+            Some("postgres://localhost/zksync")
+        );
+        assert_eq!(
+            value["database"]["prover_url"].as_str(),
+            Some("postgres://localhost/prover")
+        );
+        assert_eq!(value["l1"]["l1_rpc_url"].as_str(), Some("file_secret"));
+    }
+
+    #[test]
+    fn unsupported_secret_refs_are_rejected() {
+        let mut value = serde_yaml::Value::String("vault://secret/chain#db_url".to_owned());
+        let err = resolve_secret_refs(&mut value).unwrap_err();
+        assert!(err.to_string().contains("vault://"), "{err}");
+
+        let mut value =
+            serde_yaml::Value::String("env://ZKSYNC_TEST_SECRET_REFS_MISSING".to_owned());
+        resolve_secret_refs(&mut value).unwrap_err();
+    }
+}
@@ -34,11 +34,14 @@ use zksync_types::{
 use zksync_utils::{h256_to_u256, time::seconds_since_epoch, u256_to_h256};
 
 use super::{
+    storage::StorageWithOverrides,
     vm_metrics::{self, SandboxStage, SANDBOX_METRICS},
     BlockArgs, TxExecutionArgs, TxSharedArgs, VmPermit,
 };
 
-type BoxedVm<'a> = Box<VmInstance<StorageView<PostgresStorage<'a>>, HistoryDisabled>>;
+/// Storage used by the VM in the API sandbox.
+pub type SandboxStorage<'a> = StorageView<StorageWithOverrides<PostgresStorage<'a>>>;
+type BoxedVm<'a> = Box<VmInstance<SandboxStorage<'a>, HistoryDisabled>>;
 
 #[derive(Debug)]
 struct Sandbox<'a> {
@@ -46,7 +49,7 @@ struct Sandbox<'a> {
     l1_batch_env: L1BatchEnv,
     execution_args: &'a TxExecutionArgs,
     l2_block_info_to_reset: Option<StoredL2BlockInfo>,
-    storage_view: StorageView<PostgresStorage<'a>>,
+    storage_view: SandboxStorage<'a>,
 }
 
 impl<'a> Sandbox<'a> {
@@ -80,15 +83,21 @@ impl<'a> Sandbox<'a> {
         )
         .await?;
 
+        let storage_l2_block_number = if block_args.state_before_block {
+            resolved_block_info.state_l2_block_number - 1
+        } else {
+            resolved_block_info.state_l2_block_number
+        };
         let storage = PostgresStorage::new_async(
             Handle::current(),
             connection,
-            resolved_block_info.state_l2_block_number,
+            storage_l2_block_number,
             false,
         )
         .await
         .context("cannot create `PostgresStorage`")?
         .with_caches(shared_args.caches.clone());
+        let storage = StorageWithOverrides::new(storage, execution_args.state_override.as_ref());
 
         let storage_view = StorageView::new(storage);
         let (system_env, l1_batch_env) = Self::prepare_env(
@@ -259,7 +268,7 @@ impl<'a> Sandbox<'a> {
         mut self,
         tx: &Transaction,
         adjust_pubdata_price: bool,
-    ) -> (BoxedVm<'a>, StoragePtr<StorageView<PostgresStorage<'a>>>) {
+    ) -> (BoxedVm<'a>, StoragePtr<SandboxStorage<'a>>) {
         self.setup_storage_view(tx);
         let protocol_version = self.system_env.version;
         if adjust_pubdata_price {
@@ -296,7 +305,7 @@ pub(super) fn apply_vm_in_sandbox<T>(
     tx: Transaction,
     block_args: BlockArgs,
     apply: impl FnOnce(
-        &mut VmInstance<StorageView<PostgresStorage<'_>>, HistoryDisabled>,
+        &mut VmInstance<SandboxStorage<'_>, HistoryDisabled>,
         Transaction,
         ProtocolVersionId,
     ) -> T,
@@ -403,13 +412,13 @@ impl StoredL2BlockInfo {
 }
 
 #[derive(Debug)]
-struct ResolvedBlockInfo {
+pub(super) struct ResolvedBlockInfo {
     state_l2_block_number: L2BlockNumber,
     state_l2_block_hash: H256,
     vm_l1_batch_number: L1BatchNumber,
     l1_batch_timestamp: u64,
     protocol_version: ProtocolVersionId,
-    historical_fee_input: Option<BatchFeeInput>,
+    pub(super) historical_fee_input: Option<BatchFeeInput>,
 }
 
 impl BlockArgs {
@@ -429,7 +438,7 @@ impl BlockArgs {
         )
     }
 
-    async fn resolve_block_info(
+    pub(super) async fn resolve_block_info(
         &self,
         connection: &mut Connection<'_, Core>,
     ) -> anyhow::Result<ResolvedBlockInfo> {
@@ -470,12 +479,9 @@ impl BlockArgs {
                 .context("resolved L2 block disappeared from storage")?
         };
 
-        let historical_fee_input = if !self.is_estimate_like() {
-            let l2_block_header = connection
-                .blocks_dal()
-                .get_l2_block_header(self.resolved_block_number)
-                .await?
-                .context("resolved L2 block is not in storage")?;
+        // Historical fee input is used when executing in a past block, and when replaying a block
+        // (even if it's specified as `latest`), so that the execution matches the original one.
+        let historical_fee_input = if self.state_before_block || !self.is_estimate_like() {
             Some(l2_block_header.batch_fee_input)
         } else {
             None
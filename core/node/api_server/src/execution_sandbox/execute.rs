@@ -10,8 +10,8 @@ use multivm::{
 use tracing::{span, Level};
 use zksync_dal::{ConnectionPool, Core};
 use zksync_types::{
-    fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon, Nonce,
-    PackedEthSignature, Transaction, U256,
+    api::StateOverride, fee::TransactionExecutionMetrics, l2::L2Tx, ExecuteTransactionCommon,
+    Nonce, PackedEthSignature, Transaction, U256,
 };
 
 use super::{
@@ -26,6 +26,8 @@ pub(crate) struct TxExecutionArgs {
     pub added_balance: U256,
     pub enforced_base_fee: Option<u64>,
     pub missed_storage_invocation_limit: usize,
+    /// Overrides applied to the VM state before execution.
+    pub state_override: Option<StateOverride>,
 }
 
 impl TxExecutionArgs {
@@ -36,6 +38,7 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(tx.common_data.fee.max_fee_per_gas.as_u64()),
             missed_storage_invocation_limit: usize::MAX,
+            state_override: None,
         }
     }
 
@@ -50,6 +53,31 @@ impl TxExecutionArgs {
             added_balance: U256::zero(),
             enforced_base_fee: Some(enforced_base_fee),
             missed_storage_invocation_limit,
+            state_override: None,
+        }
+    }
+
+    fn for_simulation(
+        enforced_base_fee: u64,
+        vm_execution_cache_misses_limit: Option<usize>,
+        state_override: Option<StateOverride>,
+    ) -> Self {
+        Self {
+            state_override,
+            ..Self::for_eth_call(enforced_base_fee, vm_execution_cache_misses_limit)
+        }
+    }
+
+    /// Args for re-executing a transaction included into an L2 block. Unlike other args, these don't alter
+    /// the VM state or environment, so that the execution matches the original one as closely as possible.
+    fn for_replay() -> Self {
+        Self {
+            execution_mode: TxExecutionMode::VerifyExecute,
+            enforced_nonce: None,
+            added_balance: U256::zero(),
+            enforced_base_fee: None,
+            missed_storage_invocation_limit: usize::MAX,
+            state_override: None,
         }
     }
 
@@ -73,6 +101,7 @@ impl TxExecutionArgs {
             enforced_nonce: tx.nonce(),
             added_balance,
             enforced_base_fee: Some(base_fee),
+            state_override: None,
         }
     }
 }
@@ -124,8 +153,10 @@ impl TransactionExecutor {
             .as_ref()
             .map_or(0, |deps| deps.len() as u16);
 
+        // Blocking tasks don't inherit the current span, so we specify the parent explicitly to keep traces connected.
+        let parent_span = tracing::Span::current();
         let (published_bytecodes, execution_result) = tokio::task::spawn_blocking(move || {
-            let span = span!(Level::DEBUG, "execute_in_sandbox").entered();
+            let span = span!(parent: &parent_span, Level::DEBUG, "execute_in_sandbox").entered();
             let result = apply::apply_vm_in_sandbox(
                 vm_permit,
                 shared_args,
@@ -201,4 +232,105 @@ impl TransactionExecutor {
             .await?;
         Ok(output.vm)
     }
+
+    /// Simulates a transaction against the state specified by `block_args` with optional state overrides.
+    /// Unlike [`Self::execute_tx_eth_call()`], the transaction gas limit is retained unless it exceeds
+    /// the `eth_call` limit.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn simulate_tx(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool<Core>,
+        mut tx: L2Tx,
+        block_args: BlockArgs,
+        vm_execution_cache_misses_limit: Option<usize>,
+        state_override: Option<StateOverride>,
+        custom_tracers: Vec<ApiTracer>,
+    ) -> anyhow::Result<VmExecutionResultAndLogs> {
+        let enforced_base_fee = tx.common_data.fee.max_fee_per_gas.as_u64();
+        let execution_args = TxExecutionArgs::for_simulation(
+            enforced_base_fee,
+            vm_execution_cache_misses_limit,
+            state_override,
+        );
+
+        if tx.common_data.signature.is_empty() {
+            tx.common_data.signature = PackedEthSignature::default().serialize_packed().into();
+        }
+        let gas_limit = &mut tx.common_data.fee.gas_limit;
+        if gas_limit.is_zero() || *gas_limit > ETH_CALL_GAS_LIMIT.into() {
+            *gas_limit = ETH_CALL_GAS_LIMIT.into();
+        }
+
+        let output = self
+            .execute_tx_in_sandbox(
+                vm_permit,
+                shared_args,
+                false,
+                execution_args,
+                connection_pool,
+                tx.into(),
+                block_args,
+                custom_tracers,
+            )
+            .await?;
+        Ok(output.vm)
+    }
+
+    /// Re-executes transactions from an L2 block in a single VM instance, in the provided order.
+    /// `txs` must be a prefix of the block transactions; `block_args` must be
+    /// [prepared for replay](BlockArgs::for_block_replay()). Returns execution results for all transactions.
+    #[tracing::instrument(skip_all, fields(l2_block = %block_args.resolved_block_number()))]
+    pub async fn replay_l2_block_txs(
+        &self,
+        vm_permit: VmPermit,
+        shared_args: TxSharedArgs,
+        connection_pool: ConnectionPool<Core>,
+        block_args: BlockArgs,
+        txs: Vec<(Transaction, Vec<ApiTracer>)>,
+    ) -> anyhow::Result<Vec<VmExecutionResultAndLogs>> {
+        if let Self::Mock(mock_executor) = self {
+            return txs
+                .iter()
+                .map(|(tx, _)| Ok(mock_executor.execute_tx(tx, &block_args)?.vm))
+                .collect();
+        }
+        let Some((first_tx, _)) = txs.first() else {
+            return Ok(vec![]);
+        };
+        let first_tx = first_tx.clone();
+
+        let parent_span = tracing::Span::current();
+        tokio::task::spawn_blocking(move || {
+            let _span = span!(parent: &parent_span, Level::DEBUG, "replay_in_sandbox").entered();
+            let execution_args = TxExecutionArgs::for_replay();
+            apply::apply_vm_in_sandbox(
+                vm_permit,
+                shared_args,
+                false,
+                &execution_args,
+                &connection_pool,
+                first_tx,
+                block_args,
+                |vm, _, _| {
+                    let results = txs.into_iter().map(|(tx, custom_tracers)| {
+                        let custom_tracers: Vec<_> = custom_tracers
+                            .into_iter()
+                            .map(|tracer| tracer.into_boxed())
+                            .collect();
+                        let (_, result) = vm.inspect_transaction_with_bytecode_compression(
+                            custom_tracers.into(),
+                            tx,
+                            true,
+                        );
+                        result
+                    });
+                    results.collect()
+                },
+            )
+        })
+        .await
+        .context("transaction replay panicked")?
+    }
 }
@@ -13,9 +13,15 @@ use zksync_types::{
 };
 
 use self::vm_metrics::SandboxStage;
+pub use self::{
+    apply::SandboxStorage,
+    storage::StorageWithOverrides,
+    tracers::{ApiTracerRegistry, CustomTracerFactory},
+};
 pub(super) use self::{
     error::SandboxExecutionError,
     execute::{TransactionExecutor, TxExecutionArgs},
+    storage::validate_state_override,
     tracers::ApiTracer,
     validate::ValidationError,
     vm_metrics::{SubmitTxStage, SANDBOX_METRICS},
@@ -26,6 +32,7 @@ use super::tx_sender::MultiVMBaseSystemContracts;
 mod apply;
 mod error;
 mod execute;
+mod storage;
 pub mod testonly;
 #[cfg(test)]
 mod tests;
@@ -345,6 +352,9 @@ pub(crate) struct BlockArgs {
     block_id: api::BlockId,
     resolved_block_number: L2BlockNumber,
     l1_batch_timestamp_s: Option<u64>,
+    /// If set, the VM state is taken as of the start of the resolved block (i.e., the end of the previous block)
+    /// instead of its end. Used to re-execute transactions from the block.
+    state_before_block: bool,
 }
 
 impl BlockArgs {
@@ -354,6 +364,7 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             l1_batch_timestamp_s: None,
+            state_before_block: false,
         })
     }
 
@@ -400,9 +411,30 @@ impl BlockArgs {
             block_id,
             resolved_block_number,
             l1_batch_timestamp_s: Some(l1_batch_timestamp),
+            state_before_block: false,
         })
     }
 
+    /// Converts these args to ones suitable for replaying transactions of the resolved L2 block:
+    /// transactions are executed in the context of the block, on top of the state as of its start.
+    pub fn for_block_replay(self) -> Self {
+        assert!(
+            !matches!(
+                self.block_id,
+                api::BlockId::Number(api::BlockNumber::Pending)
+            ),
+            "pending block cannot be replayed"
+        );
+        assert!(
+            self.resolved_block_number > L2BlockNumber(0),
+            "genesis block cannot be replayed"
+        );
+        Self {
+            state_before_block: true,
+            ..self
+        }
+    }
+
     pub fn resolved_block_number(&self) -> L2BlockNumber {
         self.resolved_block_number
     }
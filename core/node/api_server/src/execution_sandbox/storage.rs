@@ -0,0 +1,211 @@
+//! VM storage with state overrides used for transaction simulation.
+
+use std::collections::{HashMap, HashSet};
+
+use zksync_state::ReadStorage;
+use zksync_types::{
+    api::{OverrideAccount, StateOverride},
+    get_code_key, get_known_code_key, get_nonce_key,
+    utils::{decompose_full_nonce, nonces_to_full_nonce, storage_key_for_eth_balance},
+    AccountTreeId, StorageKey, StorageValue, H256, U256,
+};
+use zksync_utils::{
+    bytecode::{hash_bytecode, validate_bytecode},
+    h256_to_u256, u256_to_h256,
+};
+
+/// Checks that the provided state override can be applied. Returns a human-readable description of the first
+/// encountered issue.
+pub(crate) fn validate_state_override(state_override: &StateOverride) -> Result<(), String> {
+    for (address, overrides) in state_override {
+        let OverrideAccount {
+            nonce,
+            code,
+            state,
+            state_diff,
+            ..
+        } = overrides;
+        if state.is_some() && state_diff.is_some() {
+            return Err(format!(
+                "both `state` and `stateDiff` are specified for account {address:?}"
+            ));
+        }
+        if nonce.is_some_and(|nonce| nonce.bits() > 128) {
+            return Err(format!("nonce for account {address:?} is too large"));
+        }
+        if let Some(code) = code {
+            validate_bytecode(&code.0)
+                .map_err(|err| format!("invalid bytecode for account {address:?}: {err}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// [`ReadStorage`] wrapper applying a [`StateOverride`] on top of the wrapped storage.
+#[derive(Debug)]
+pub struct StorageWithOverrides<S> {
+    storage_handle: S,
+    overridden_slots: HashMap<StorageKey, H256>,
+    /// Overridden transaction nonces keyed by the nonce storage key. Deployment nonces are read from the wrapped storage.
+    overridden_nonces: HashMap<StorageKey, U256>,
+    overridden_factory_deps: HashMap<H256, Vec<u8>>,
+    /// Accounts which storage is replaced entirely.
+    overridden_accounts: HashSet<AccountTreeId>,
+}
+
+impl<S: ReadStorage> StorageWithOverrides<S> {
+    /// Wraps the provided storage. The state override must be [validated](validate_state_override()) beforehand.
+    pub fn new(storage_handle: S, state_override: Option<&StateOverride>) -> Self {
+        let mut this = Self {
+            storage_handle,
+            overridden_slots: HashMap::new(),
+            overridden_nonces: HashMap::new(),
+            overridden_factory_deps: HashMap::new(),
+            overridden_accounts: HashSet::new(),
+        };
+        for (address, overrides) in state_override.into_iter().flatten() {
+            if let Some(balance) = overrides.balance {
+                let balance_key = storage_key_for_eth_balance(address);
+                this.overridden_slots
+                    .insert(balance_key, u256_to_h256(balance));
+            }
+            if let Some(nonce) = overrides.nonce {
+                this.overridden_nonces.insert(get_nonce_key(address), nonce);
+            }
+            if let Some(code) = &overrides.code {
+                let code_hash = hash_bytecode(&code.0);
+                this.overridden_slots
+                    .insert(get_code_key(address), code_hash);
+                this.overridden_slots
+                    .insert(get_known_code_key(&code_hash), H256::from_low_u64_be(1));
+                this.overridden_factory_deps
+                    .insert(code_hash, code.0.clone());
+            }
+
+            let account = AccountTreeId::new(*address);
+            if overrides.state.is_some() {
+                this.overridden_accounts.insert(account);
+            }
+            let slots = overrides
+                .state
+                .iter()
+                .chain(&overrides.state_diff)
+                .flatten();
+            for (&slot, &value) in slots {
+                this.overridden_slots
+                    .insert(StorageKey::new(account, slot), value);
+            }
+        }
+        this
+    }
+}
+
+impl<S: ReadStorage> ReadStorage for StorageWithOverrides<S> {
+    fn read_value(&mut self, key: &StorageKey) -> StorageValue {
+        if let Some(value) = self.overridden_slots.get(key) {
+            return *value;
+        }
+        if let Some(&nonce) = self.overridden_nonces.get(key) {
+            let full_nonce = h256_to_u256(self.storage_handle.read_value(key));
+            let (_, deployment_nonce) = decompose_full_nonce(full_nonce);
+            return u256_to_h256(nonces_to_full_nonce(nonce, deployment_nonce));
+        }
+        if self.overridden_accounts.contains(key.account()) {
+            return H256::zero();
+        }
+        self.storage_handle.read_value(key)
+    }
+
+    fn is_write_initial(&mut self, key: &StorageKey) -> bool {
+        self.storage_handle.is_write_initial(key)
+    }
+
+    fn load_factory_dep(&mut self, hash: H256) -> Option<Vec<u8>> {
+        if let Some(dep) = self.overridden_factory_deps.get(&hash) {
+            return Some(dep.clone());
+        }
+        self.storage_handle.load_factory_dep(hash)
+    }
+
+    fn get_enumeration_index(&mut self, key: &StorageKey) -> Option<u64> {
+        self.storage_handle.get_enumeration_index(key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_state::InMemoryStorage;
+    use zksync_types::{web3::Bytes, Address};
+
+    use super::*;
+
+    #[test]
+    fn applying_state_override() {
+        let address = Address::repeat_byte(1);
+        let other_slot = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(2));
+        let nonce_key = get_nonce_key(&address);
+        let mut storage = InMemoryStorage::default();
+        storage.set_value(other_slot, H256::repeat_byte(0xff));
+        // Deployment nonce = 3, transaction nonce = 5
+        storage.set_value(
+            nonce_key,
+            u256_to_h256(nonces_to_full_nonce(5.into(), 3.into())),
+        );
+
+        let code = vec![0; 32];
+        let overrides = OverrideAccount {
+            balance: Some(1_000.into()),
+            nonce: Some(10.into()),
+            code: Some(Bytes(code.clone())),
+            state: Some(HashMap::from([(
+                H256::repeat_byte(1),
+                H256::repeat_byte(3),
+            )])),
+            state_diff: None,
+        };
+        let state_override = StateOverride::from([(address, overrides)]);
+        validate_state_override(&state_override).unwrap();
+        let mut storage = StorageWithOverrides::new(storage, Some(&state_override));
+
+        let balance = storage.read_value(&storage_key_for_eth_balance(&address));
+        assert_eq!(h256_to_u256(balance), 1_000.into());
+        let full_nonce = h256_to_u256(storage.read_value(&nonce_key));
+        assert_eq!(decompose_full_nonce(full_nonce), (10.into(), 3.into()));
+
+        let code_hash = storage.read_value(&get_code_key(&address));
+        assert_eq!(code_hash, hash_bytecode(&code));
+        assert!(storage.is_bytecode_known(&code_hash));
+        assert_eq!(storage.load_factory_dep(code_hash), Some(code));
+
+        let slot = StorageKey::new(AccountTreeId::new(address), H256::repeat_byte(1));
+        assert_eq!(storage.read_value(&slot), H256::repeat_byte(3));
+        // Other slots are reset since `state` replaces the entire storage.
+        assert_eq!(storage.read_value(&other_slot), H256::zero());
+    }
+
+    #[test]
+    fn validating_state_override() {
+        let address = Address::repeat_byte(1);
+        let overrides = OverrideAccount {
+            state: Some(HashMap::new()),
+            state_diff: Some(HashMap::new()),
+            ..OverrideAccount::default()
+        };
+        let err = validate_state_override(&StateOverride::from([(address, overrides)]));
+        assert!(err.unwrap_err().contains("stateDiff"));
+
+        let overrides = OverrideAccount {
+            code: Some(Bytes(vec![0; 64])),
+            ..OverrideAccount::default()
+        };
+        let err = validate_state_override(&StateOverride::from([(address, overrides)]));
+        assert!(err.unwrap_err().contains("bytecode"));
+
+        let overrides = OverrideAccount {
+            nonce: Some(U256::MAX),
+            ..OverrideAccount::default()
+        };
+        let err = validate_state_override(&StateOverride::from([(address, overrides)]));
+        assert!(err.unwrap_err().contains("nonce"));
+    }
+}
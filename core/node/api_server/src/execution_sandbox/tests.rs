@@ -1,9 +1,14 @@
 //! Tests for the VM execution sandbox.
 
 use assert_matches::assert_matches;
+use multivm::{
+    tracers::CallTracer, vm_latest::HistoryDisabled, MultiVMTracer, MultiVmTracerPointer,
+};
+use once_cell::sync::OnceCell;
 use zksync_dal::ConnectionPool;
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{create_l2_block, create_l2_transaction, prepare_recovery_snapshot};
+use zksync_types::block::L2BlockHeader;
 
 use super::*;
 use crate::{execution_sandbox::apply::apply_vm_in_sandbox, tx_sender::ApiContracts};
@@ -68,6 +73,44 @@ async fn creating_block_args() {
     assert_matches!(err, BlockArgsError::Missing);
 }
 
+#[tokio::test]
+async fn replayed_block_uses_historical_fee_input() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let l2_block = L2BlockHeader {
+        batch_fee_input: BatchFeeInput::l1_pegged(12_345, 678),
+        ..create_l2_block(1)
+    };
+    storage
+        .blocks_dal()
+        .insert_l2_block(&l2_block)
+        .await
+        .unwrap();
+
+    let start_info = BlockStartInfo::new(&mut storage, Duration::MAX)
+        .await
+        .unwrap();
+    let latest_block = api::BlockId::Number(api::BlockNumber::Latest);
+    let block_args = BlockArgs::new(&mut storage, latest_block, &start_info)
+        .await
+        .unwrap();
+    let block_info = block_args.resolve_block_info(&mut storage).await.unwrap();
+    assert_eq!(block_info.historical_fee_input, None);
+
+    let block_info = block_args
+        .for_block_replay()
+        .resolve_block_info(&mut storage)
+        .await
+        .unwrap();
+    assert_eq!(
+        block_info.historical_fee_input,
+        Some(l2_block.batch_fee_input)
+    );
+}
+
 #[tokio::test]
 async fn creating_block_args_after_snapshot_recovery() {
     let pool = ConnectionPool::<Core>::test_pool().await;
@@ -204,3 +247,37 @@ async fn test_instantiating_vm(pool: ConnectionPool<Core>, block_args: BlockArgs
     .expect("VM instantiation panicked")
     .expect("VM instantiation errored");
 }
+
+#[derive(Debug)]
+struct TestTracerFactory;
+
+impl CustomTracerFactory for TestTracerFactory {
+    fn create_tracer<'a>(
+        &self,
+        _output: Arc<OnceCell<serde_json::Value>>,
+    ) -> MultiVmTracerPointer<SandboxStorage<'a>, HistoryDisabled> {
+        CallTracer::new(Arc::default()).into_tracer_pointer()
+    }
+}
+
+#[test]
+fn registering_custom_tracers() {
+    let registry = ApiTracerRegistry::default();
+    registry
+        .register("testTracer", Arc::new(TestTracerFactory))
+        .unwrap();
+    assert!(registry.get("testTracer").is_some());
+    assert!(registry.get("otherTracer").is_none());
+
+    // Registered tracers are shared among registry clones.
+    let registry_clone = registry.clone();
+    let err = registry_clone
+        .register("testTracer", Arc::new(TestTracerFactory))
+        .unwrap_err();
+    assert!(err.to_string().contains("already registered"), "{err}");
+
+    let err = registry
+        .register("callTracer", Arc::new(TestTracerFactory))
+        .unwrap_err();
+    assert!(err.to_string().contains("built-in"), "{err}");
+}
@@ -1,25 +1,108 @@
-use std::sync::Arc;
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    fmt,
+    sync::{Arc, RwLock},
+};
 
-use multivm::{tracers::CallTracer, vm_latest::HistoryMode, MultiVMTracer, MultiVmTracerPointer};
+use anyhow::Context as _;
+use multivm::{
+    tracers::{CallTracer, StructLogTracer},
+    vm_latest::HistoryDisabled,
+    MultiVMTracer, MultiVmTracerPointer,
+};
 use once_cell::sync::OnceCell;
-use zksync_state::WriteStorage;
-use zksync_types::vm_trace::Call;
+use zksync_types::{
+    api::SupportedTracers,
+    vm_trace::{Call, StructLogs},
+};
+
+use super::apply::SandboxStorage;
+
+/// Factory of a custom tracer that can be registered in an [`ApiTracerRegistry`] and selected by name
+/// in `debug_trace*` methods.
+///
+/// The created tracers must implement [`MultiVMTracer`], so that they work with all VM versions used to execute transactions
+/// in the API sandbox.
+pub trait CustomTracerFactory: fmt::Debug + Send + Sync + 'static {
+    /// Creates a tracer for a single traced transaction. The tracer must set `output` once the transaction is executed;
+    /// if it doesn't, the trace is returned as `null`.
+    fn create_tracer<'a>(
+        &self,
+        output: Arc<OnceCell<serde_json::Value>>,
+    ) -> MultiVmTracerPointer<SandboxStorage<'a>, HistoryDisabled>;
+}
+
+/// Registry of [custom tracers](CustomTracerFactory) registered by node components. Registered tracers can be selected
+/// by specifying their name as `tracer` in `debug_trace*` methods options.
+///
+/// The registry is cheaply cloneable; all clones share registered tracers.
+#[derive(Debug, Clone, Default)]
+pub struct ApiTracerRegistry(Arc<RwLock<HashMap<String, Arc<dyn CustomTracerFactory>>>>);
+
+impl ApiTracerRegistry {
+    /// Registers a tracer with the specified name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is already taken by a built-in or a previously registered tracer.
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        factory: Arc<dyn CustomTracerFactory>,
+    ) -> anyhow::Result<()> {
+        let name = name.into();
+        let parsed_name: SupportedTracers =
+            serde_json::from_value(name.clone().into()).context("failed parsing tracer name")?;
+        anyhow::ensure!(
+            matches!(parsed_name, SupportedTracers::Custom(_)),
+            "tracer name `{name}` is taken by a built-in tracer"
+        );
+
+        let mut tracers = self.0.write().expect("tracer registry is poisoned");
+        match tracers.entry(name) {
+            Entry::Occupied(entry) => {
+                anyhow::bail!("tracer `{}` is already registered", entry.key());
+            }
+            Entry::Vacant(entry) => {
+                tracing::info!(
+                    "Registered custom API tracer `{}`: {factory:?}",
+                    entry.key()
+                );
+                entry.insert(factory);
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, name: &str) -> Option<Arc<dyn CustomTracerFactory>> {
+        let tracers = self.0.read().expect("tracer registry is poisoned");
+        tracers.get(name).cloned()
+    }
+}
 
 /// Custom tracers supported by our API
 #[derive(Debug)]
 pub(crate) enum ApiTracer {
     CallTracer(Arc<OnceCell<Vec<Call>>>),
+    StructLogTracer {
+        max_steps: usize,
+        result: Arc<OnceCell<StructLogs>>,
+    },
+    /// Tracer from an [`ApiTracerRegistry`].
+    Custom {
+        factory: Arc<dyn CustomTracerFactory>,
+        output: Arc<OnceCell<serde_json::Value>>,
+    },
 }
 
 impl ApiTracer {
-    pub fn into_boxed<
-        S: WriteStorage,
-        H: HistoryMode + multivm::HistoryMode<Vm1_5_0 = H> + 'static,
-    >(
-        self,
-    ) -> MultiVmTracerPointer<S, H> {
+    pub fn into_boxed<'a>(self) -> MultiVmTracerPointer<SandboxStorage<'a>, HistoryDisabled> {
         match self {
             ApiTracer::CallTracer(tracer) => CallTracer::new(tracer.clone()).into_tracer_pointer(),
+            ApiTracer::StructLogTracer { max_steps, result } => {
+                StructLogTracer::new(max_steps, result).into_tracer_pointer()
+            }
+            ApiTracer::Custom { factory, output } => factory.create_tracer(output),
         }
     }
 }
@@ -62,8 +62,10 @@ impl TransactionExecutor {
         let execution_args = TxExecutionArgs::for_validation(&tx);
         let tx: Transaction = tx.into();
 
+        // Blocking tasks don't inherit the current span, so we specify the parent explicitly to keep traces connected.
+        let parent_span = tracing::Span::current();
         let validation_result = tokio::task::spawn_blocking(move || {
-            let span = tracing::debug_span!("validate_in_sandbox").entered();
+            let span = tracing::debug_span!(parent: &parent_span, "validate_in_sandbox").entered();
             let result = apply::apply_vm_in_sandbox(
                 vm_permit,
                 shared_args,
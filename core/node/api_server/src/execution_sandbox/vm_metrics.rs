@@ -2,7 +2,8 @@ use std::time::Duration;
 
 use multivm::interface::{VmExecutionResultAndLogs, VmMemoryMetrics};
 use vise::{
-    Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver, Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
+    Metrics,
 };
 use zksync_shared_metrics::InteractionType;
 use zksync_state::StorageViewMetrics;
@@ -154,6 +155,8 @@ pub(crate) struct SandboxMetrics {
     submit_tx: Family<SubmitTxStage, Histogram<Duration>>,
     #[metrics(buckets = Buckets::linear(0.0..=30.0, 3.0))]
     pub estimate_gas_binary_search_iterations: Histogram<usize>,
+    /// Number of single-pass gas estimations that have failed verification and fell back to the binary search.
+    pub estimate_gas_single_pass_fallbacks: Counter,
 }
 
 impl SandboxMetrics {
@@ -4,16 +4,40 @@ use axum::{extract::State, http::StatusCode, routing::get, Json, Router};
 use tokio::sync::watch;
 use zksync_health_check::{AppHealth, AppHealthCheck};
 
-async fn check_health(
-    app_health_check: State<Arc<AppHealthCheck>>,
-) -> (StatusCode, Json<AppHealth>) {
-    let response = app_health_check.check_health().await;
-    let response_code = if response.is_healthy() {
+fn health_response(health: AppHealth, is_ok: bool) -> (StatusCode, Json<AppHealth>) {
+    let response_code = if is_ok {
         StatusCode::OK
     } else {
         StatusCode::SERVICE_UNAVAILABLE
     };
-    (response_code, Json(response))
+    (response_code, Json(health))
+}
+
+async fn check_health(
+    app_health_check: State<Arc<AppHealthCheck>>,
+) -> (StatusCode, Json<AppHealth>) {
+    let response = app_health_check.check_health().await;
+    let is_healthy = response.is_healthy();
+    health_response(response, is_healthy)
+}
+
+/// Liveness check. Fails only if a component has shut down or panicked; components that are not ready yet
+/// (e.g., because of snapshot recovery in progress) are considered alive.
+async fn check_liveness(
+    app_health_check: State<Arc<AppHealthCheck>>,
+) -> (StatusCode, Json<AppHealth>) {
+    let response = app_health_check.check_health().await;
+    let is_alive = response.is_alive();
+    health_response(response, is_alive)
+}
+
+/// Readiness check. Takes into account only components gating readiness, according to their weights.
+async fn check_readiness(
+    app_health_check: State<Arc<AppHealthCheck>>,
+) -> (StatusCode, Json<AppHealth>) {
+    let response = app_health_check.check_health().await;
+    let is_ready = response.is_ready();
+    health_response(response, is_ready)
 }
 
 async fn run_server(
@@ -27,6 +51,8 @@ async fn run_server(
 
     let app = Router::new()
         .route("/health", get(check_health))
+        .route("/healthz", get(check_liveness))
+        .route("/readyz", get(check_readiness))
         .with_state(app_health_check);
 
     axum::Server::bind(bind_address)
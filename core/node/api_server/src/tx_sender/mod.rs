@@ -1,6 +1,6 @@
 //! Helper module to submit transactions into the zkSync Network.
 
-use std::{sync::Arc, time::Instant};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
 use anyhow::Context as _;
 use multivm::{
@@ -24,13 +24,14 @@ use zksync_state_keeper::{
     SequencerSealer,
 };
 use zksync_types::{
+    api::StateOverride,
     fee::{Fee, TransactionExecutionMetrics},
     fee_model::BatchFeeInput,
     get_code_key, get_intrinsic_constants,
     l2::{error::TxCheckError::TxDuplication, L2Tx},
     utils::storage_key_for_eth_balance,
     AccountTreeId, Address, ExecuteTransactionCommon, L2ChainId, Nonce, PackedEthSignature,
-    ProtocolVersionId, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
+    ProtocolVersionId, StorageKey, Transaction, VmVersion, H160, H256, MAX_L2_TX_GAS_LIMIT,
     MAX_NEW_FACTORY_DEPS, U256,
 };
 use zksync_utils::h256_to_u256;
@@ -39,7 +40,7 @@ pub(super) use self::result::SubmitTxError;
 use self::{master_pool_sink::MasterPoolSink, tx_sink::TxSink};
 use crate::{
     execution_sandbox::{
-        BlockArgs, SubmitTxStage, TransactionExecutor, TxExecutionArgs, TxSharedArgs,
+        ApiTracer, BlockArgs, SubmitTxStage, TransactionExecutor, TxExecutionArgs, TxSharedArgs,
         VmConcurrencyBarrier, VmConcurrencyLimiter, VmPermit, SANDBOX_METRICS,
     },
     tx_sender::result::ApiCallResult,
@@ -275,6 +276,7 @@ pub struct TxSenderConfig {
     pub validation_computational_gas_limit: u32,
     pub chain_id: L2ChainId,
     pub whitelisted_tokens_for_aa: Vec<Address>,
+    pub estimate_gas_single_pass: bool,
 }
 
 impl TxSenderConfig {
@@ -294,6 +296,7 @@ impl TxSenderConfig {
                 .validation_computational_gas_limit,
             chain_id,
             whitelisted_tokens_for_aa: web3_json_config.whitelisted_tokens_for_aa.clone(),
+            estimate_gas_single_pass: web3_json_config.estimate_gas_single_pass,
         }
     }
 }
@@ -326,6 +329,81 @@ impl std::fmt::Debug for TxSender {
     }
 }
 
+/// Multiplier applied to the gas metered during single-pass gas estimation. Accounts for the gas that is required
+/// to be available, but is not spent, e.g. because of the 63/64 rule for far calls, or because of refunds.
+const SINGLE_PASS_GAS_CORRECTION_FACTOR: f64 = 1.1;
+
+/// Returns the gas limit (including the overhead) that the transaction is executed with during gas estimation.
+fn gas_limit_with_overhead(
+    tx: &Transaction,
+    tx_gas_limit: u64,
+    gas_price_per_pubdata: u32,
+    vm_version: VmVersion,
+) -> u64 {
+    let gas_limit_with_overhead = tx_gas_limit
+        + derive_overhead(
+            tx_gas_limit,
+            gas_price_per_pubdata,
+            tx.encoding_len(),
+            tx.tx_format() as u8,
+            vm_version,
+        ) as u64;
+    // We need to ensure that we never use a gas limit that is higher than the maximum allowed
+    gas_limit_with_overhead.min(get_max_batch_gas_limit(vm_version))
+}
+
+/// Snapshot of the pending state shared by fee estimations for one or more transactions.
+#[derive(Debug, Clone, Copy)]
+struct FeeEstimationSnapshot {
+    block_args: BlockArgs,
+    protocol_version: ProtocolVersionId,
+    max_gas_limit: u64,
+    fee_input: BatchFeeInput,
+}
+
+/// Code hashes and base token balances of transaction initiators, loaded in a single query.
+#[derive(Debug)]
+struct InitiatorAccounts(HashMap<H256, H256>);
+
+impl InitiatorAccounts {
+    async fn load(
+        connection: &mut Connection<'_, Core>,
+        txs: &[Transaction],
+    ) -> anyhow::Result<Self> {
+        let mut hashed_keys: Vec<_> = txs
+            .iter()
+            .flat_map(|tx| {
+                let initiator = tx.initiator_account();
+                [
+                    get_code_key(&initiator).hashed_key(),
+                    storage_key_for_eth_balance(&initiator).hashed_key(),
+                ]
+            })
+            .collect();
+        hashed_keys.sort_unstable();
+        hashed_keys.dedup();
+
+        let values = connection
+            .storage_web3_dal()
+            .get_values(&hashed_keys)
+            .await
+            .context("failed getting code hashes and balances for initiator accounts")?;
+        Ok(Self(values))
+    }
+
+    fn value(&self, key: &StorageKey) -> H256 {
+        self.0.get(&key.hashed_key()).copied().unwrap_or_default()
+    }
+
+    fn code_hash(&self, account: &Address) -> H256 {
+        self.value(&get_code_key(account))
+    }
+
+    fn balance(&self, account: &Address) -> U256 {
+        h256_to_u256(self.value(&storage_key_for_eth_balance(account)))
+    }
+}
+
 impl TxSender {
     pub(crate) fn vm_concurrency_limiter(&self) -> Arc<VmConcurrencyLimiter> {
         Arc::clone(&self.0.vm_concurrency_limiter)
@@ -651,16 +729,8 @@ impl TxSender {
         base_fee: u64,
         vm_version: VmVersion,
     ) -> anyhow::Result<(VmExecutionResultAndLogs, TransactionExecutionMetrics)> {
-        let gas_limit_with_overhead = tx_gas_limit
-            + derive_overhead(
-                tx_gas_limit,
-                gas_price_per_pubdata,
-                tx.encoding_len(),
-                tx.tx_format() as u8,
-                vm_version,
-            ) as u64;
-        // We need to ensure that we never use a gas limit that is higher than the maximum allowed
-        let forced_gas_limit = gas_limit_with_overhead.min(get_max_batch_gas_limit(vm_version));
+        let forced_gas_limit =
+            gas_limit_with_overhead(&tx, tx_gas_limit, gas_price_per_pubdata, vm_version);
 
         match &mut tx.common_data {
             ExecuteTransactionCommon::L1(l1_common_data) => {
@@ -703,6 +773,63 @@ impl TxSender {
         Ok((execution_output.vm, execution_output.metrics))
     }
 
+    /// Uses binary search to find the minimal computational gas limit (i.e., excluding `additional_gas_for_pubdata`)
+    /// under which the transaction succeeds.
+    #[allow(clippy::too_many_arguments)]
+    async fn estimate_gas_binary_search(
+        &self,
+        vm_permit: VmPermit,
+        tx: &Transaction,
+        additional_gas_for_pubdata: u64,
+        acceptable_overestimation: u64,
+        gas_per_pubdata_byte: u64,
+        fee_input: BatchFeeInput,
+        block_args: BlockArgs,
+        base_fee: u64,
+        vm_version: VmVersion,
+    ) -> anyhow::Result<u64> {
+        let mut lower_bound = 0;
+        let mut upper_bound = MAX_L2_TX_GAS_LIMIT;
+        let mut number_of_iterations = 0usize;
+        while lower_bound + acceptable_overestimation < upper_bound {
+            let mid = (lower_bound + upper_bound) / 2;
+            // There is no way to distinct between errors due to out of gas
+            // or normal execution errors, so we just hope that increasing the
+            // gas limit will make the transaction successful
+            let iteration_started_at = Instant::now();
+            let try_gas_limit = additional_gas_for_pubdata + mid;
+            let (result, _) = self
+                .estimate_gas_step(
+                    vm_permit.clone(),
+                    tx.clone(),
+                    try_gas_limit,
+                    gas_per_pubdata_byte as u32,
+                    fee_input,
+                    block_args,
+                    base_fee,
+                    vm_version,
+                )
+                .await
+                .context("estimate_gas step failed")?;
+
+            if result.result.is_failed() {
+                lower_bound = mid + 1;
+            } else {
+                upper_bound = mid;
+            }
+
+            tracing::trace!(
+                "iteration {number_of_iterations} took {:?}. lower_bound: {lower_bound}, upper_bound: {upper_bound}",
+                iteration_started_at.elapsed()
+            );
+            number_of_iterations += 1;
+        }
+        SANDBOX_METRICS
+            .estimate_gas_binary_search_iterations
+            .observe(number_of_iterations);
+        Ok(upper_bound)
+    }
+
     async fn shared_args_for_gas_estimate(&self, fee_input: BatchFeeInput) -> TxSharedArgs {
         let config = &self.0.sender_config;
 
@@ -724,12 +851,31 @@ impl TxSender {
     ))]
     pub async fn get_txs_fee_in_wei(
         &self,
-        mut tx: Transaction,
+        tx: Transaction,
         estimated_fee_scale_factor: f64,
         acceptable_overestimation: u64,
     ) -> Result<Fee, SubmitTxError> {
-        let estimation_started_at = Instant::now();
+        let mut fees = self
+            .get_txs_fee_in_wei_batch(
+                vec![tx],
+                estimated_fee_scale_factor,
+                acceptable_overestimation,
+            )
+            .await?;
+        Ok(fees.pop().expect("fee estimated for single tx"))
+    }
 
+    /// Estimates fees for multiple transactions. All transactions are estimated against the same snapshot
+    /// of the pending state (block args, protocol version and fee input), and storage reads for transaction initiators
+    /// are batched. Transactions are estimated independently, i.e., state changes made by a transaction
+    /// are not visible to the following transactions.
+    #[tracing::instrument(level = "debug", skip_all, fields(tx_count = txs.len()))]
+    pub async fn get_txs_fee_in_wei_batch(
+        &self,
+        txs: Vec<Transaction>,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u64,
+    ) -> Result<Vec<Fee>, SubmitTxError> {
         let mut connection = self.acquire_replica_connection().await?;
         let block_args = BlockArgs::pending(&mut connection).await?;
         let protocol_version = connection
@@ -737,11 +883,56 @@ impl TxSender {
             .pending_protocol_version()
             .await
             .context("failed getting pending protocol version")?;
-        let max_gas_limit = get_max_batch_gas_limit(protocol_version.into());
+        let initiator_accounts = InitiatorAccounts::load(&mut connection, &txs).await?;
         drop(connection);
 
+        let snapshot = FeeEstimationSnapshot {
+            block_args,
+            protocol_version,
+            max_gas_limit: get_max_batch_gas_limit(protocol_version.into()),
+            fee_input: self.scaled_batch_fee_input().await?,
+        };
+
+        // Acquire the vm token for the whole duration of the estimation.
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let mut fees = Vec::with_capacity(txs.len());
+        for tx in txs {
+            let fee = self
+                .estimate_fee_for_snapshot(
+                    &snapshot,
+                    &initiator_accounts,
+                    vm_permit.clone(),
+                    tx,
+                    estimated_fee_scale_factor,
+                    acceptable_overestimation,
+                )
+                .await?;
+            fees.push(fee);
+        }
+        Ok(fees)
+    }
+
+    async fn estimate_fee_for_snapshot(
+        &self,
+        snapshot: &FeeEstimationSnapshot,
+        initiator_accounts: &InitiatorAccounts,
+        vm_permit: VmPermit,
+        mut tx: Transaction,
+        estimated_fee_scale_factor: f64,
+        acceptable_overestimation: u64,
+    ) -> Result<Fee, SubmitTxError> {
+        let estimation_started_at = Instant::now();
+        let FeeEstimationSnapshot {
+            block_args,
+            protocol_version,
+            max_gas_limit,
+            fee_input,
+        } = *snapshot;
+
         let fee_input = adjust_pubdata_price_for_tx(
-            self.scaled_batch_fee_input().await?,
+            fee_input,
             tx.gas_per_pubdata_byte_limit(),
             // We do not have to adjust the params to the `gasPrice` of the transaction, since
             // its gas price will be amended later on to suit the `fee_input`
@@ -764,25 +955,12 @@ impl TxSender {
             }
         }
 
-        let hashed_key = get_code_key(&tx.initiator_account());
         // If the default account does not have enough funds for transferring `tx.value`, without taking into account the fee,
         // there is no sense to estimate the fee.
-        let account_code_hash = self
-            .acquire_replica_connection()
-            .await?
-            .storage_web3_dal()
-            .get_value(&hashed_key)
-            .await
-            .with_context(|| {
-                format!(
-                    "failed getting code hash for account {:?}",
-                    tx.initiator_account()
-                )
-            })?;
-
+        let initiator = tx.initiator_account();
         if !tx.is_l1()
-            && account_code_hash == H256::zero()
-            && tx.execute.value > self.get_balance(&tx.initiator_account()).await?
+            && initiator_accounts.code_hash(&initiator) == H256::zero()
+            && tx.execute.value > initiator_accounts.balance(&initiator)
         {
             tracing::info!(
                 "fee estimation failed on validation step.
@@ -800,24 +978,20 @@ impl TxSender {
             }
         }
 
-        // Acquire the vm token for the whole duration of the binary search.
-        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
-        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
-
+        let single_pass = self.0.sender_config.estimate_gas_single_pass;
         // When the pubdata cost grows very high, the total gas limit required may become very high as well. If
         // we do binary search over any possible gas limit naively, we may end up with a very high number of iterations,
         // which affects performance.
         //
         // To optimize for this case, we first calculate the amount of gas needed to cover for the pubdata. After that, we
         // need to do a smaller binary search that is focused on computational gas limit only.
-        let additional_gas_for_pubdata = if tx.is_l1() {
-            // For L1 transactions the pubdata priced in such a way that the maximal computational
-            // gas limit should be enough to cover for the pubdata as well, so no additional gas is provided there.
-            0u64
+        //
+        // For L2 transactions, we estimate the amount of gas needed to cover for the pubdata by creating a transaction with infinite gas limit.
+        // And getting how much pubdata it used. The same execution is used to meter gas for single-pass estimation,
+        // so if it's enabled, the execution is performed for L1 transactions as well.
+        let max_gas_limit_result = if tx.is_l1() && !single_pass {
+            None
         } else {
-            // For L2 transactions, we estimate the amount of gas needed to cover for the pubdata by creating a transaction with infinite gas limit.
-            // And getting how much pubdata it used.
-
             // In theory, if the transaction has failed with such large gas limit, we could have returned an API error here right away,
             // but doing it later on keeps the code more lean.
             let (result, _) = self
@@ -833,33 +1007,54 @@ impl TxSender {
                 )
                 .await
                 .context("estimate_gas step failed")?;
+            Some(result)
+        };
 
+        let additional_gas_for_pubdata = match &max_gas_limit_result {
+            // For L1 transactions the pubdata priced in such a way that the maximal computational
+            // gas limit should be enough to cover for the pubdata as well, so no additional gas is provided there.
+            _ if tx.is_l1() => 0u64,
             // It is assumed that there is no overflow here
-            (result.statistics.pubdata_published as u64) * gas_per_pubdata_byte
+            Some(result) => (result.statistics.pubdata_published as u64) * gas_per_pubdata_byte,
+            None => {
+                unreachable!("execution with max gas limit is always performed for L2 transactions")
+            }
         };
 
-        // We are using binary search to find the minimal values of gas_limit under which
-        // the transaction succeeds
-        let mut lower_bound = 0;
-        let mut upper_bound = MAX_L2_TX_GAS_LIMIT;
+        let single_pass_upper_bound = match max_gas_limit_result {
+            Some(result) if single_pass => {
+                let executed_gas_limit = gas_limit_with_overhead(
+                    &tx,
+                    max_gas_limit,
+                    gas_per_pubdata_byte as u32,
+                    protocol_version.into(),
+                );
+                let gas_used = executed_gas_limit.saturating_sub(result.refunds.gas_refunded);
+                // If the transaction fails with the max gas limit, it will fail with any other gas limit as well.
+                result.into_api_call_result()?;
+
+                let computational_gas = gas_used.saturating_sub(additional_gas_for_pubdata);
+                let upper_bound =
+                    (computational_gas as f64 * SINGLE_PASS_GAS_CORRECTION_FACTOR) as u64;
+                Some(upper_bound.min(MAX_L2_TX_GAS_LIMIT))
+            }
+            _ => None,
+        };
         tracing::trace!(
-            "preparation took {:?}, starting binary search",
+            "preparation took {:?}, single-pass upper bound: {single_pass_upper_bound:?}",
             estimation_started_at.elapsed()
         );
 
-        let mut number_of_iterations = 0usize;
-        while lower_bound + acceptable_overestimation < upper_bound {
-            let mid = (lower_bound + upper_bound) / 2;
-            // There is no way to distinct between errors due to out of gas
-            // or normal execution errors, so we just hope that increasing the
-            // gas limit will make the transaction successful
-            let iteration_started_at = Instant::now();
-            let try_gas_limit = additional_gas_for_pubdata + mid;
-            let (result, _) = self
+        // The final execution with the suggested gas limit doubles as the verification of the single-pass estimate.
+        let mut single_pass_output = None;
+        if let Some(upper_bound) = single_pass_upper_bound {
+            let suggested_gas_limit = ((upper_bound + additional_gas_for_pubdata) as f64
+                * estimated_fee_scale_factor) as u64;
+            let (result, tx_metrics) = self
                 .estimate_gas_step(
                     vm_permit.clone(),
                     tx.clone(),
-                    try_gas_limit,
+                    suggested_gas_limit,
                     gas_per_pubdata_byte as u32,
                     fee_input,
                     block_args,
@@ -867,39 +1062,52 @@ impl TxSender {
                     protocol_version.into(),
                 )
                 .await
-                .context("estimate_gas step failed")?;
+                .context("single-pass estimate_gas step failed")?;
 
             if result.result.is_failed() {
-                lower_bound = mid + 1;
+                tracing::debug!(
+                    "single-pass estimate {suggested_gas_limit} is insufficient; falling back to binary search"
+                );
+                SANDBOX_METRICS.estimate_gas_single_pass_fallbacks.inc();
             } else {
-                upper_bound = mid;
+                single_pass_output = Some((suggested_gas_limit, result, tx_metrics));
             }
-
-            tracing::trace!(
-                "iteration {number_of_iterations} took {:?}. lower_bound: {lower_bound}, upper_bound: {upper_bound}",
-                iteration_started_at.elapsed()
-            );
-            number_of_iterations += 1;
         }
-        SANDBOX_METRICS
-            .estimate_gas_binary_search_iterations
-            .observe(number_of_iterations);
 
-        let suggested_gas_limit =
-            ((upper_bound + additional_gas_for_pubdata) as f64 * estimated_fee_scale_factor) as u64;
-        let (result, tx_metrics) = self
-            .estimate_gas_step(
-                vm_permit,
-                tx.clone(),
-                suggested_gas_limit,
-                gas_per_pubdata_byte as u32,
-                fee_input,
-                block_args,
-                base_fee,
-                protocol_version.into(),
-            )
-            .await
-            .context("final estimate_gas step failed")?;
+        let (suggested_gas_limit, result, tx_metrics) = if let Some(output) = single_pass_output {
+            output
+        } else {
+            let upper_bound = self
+                .estimate_gas_binary_search(
+                    vm_permit.clone(),
+                    &tx,
+                    additional_gas_for_pubdata,
+                    acceptable_overestimation,
+                    gas_per_pubdata_byte,
+                    fee_input,
+                    block_args,
+                    base_fee,
+                    protocol_version.into(),
+                )
+                .await?;
+
+            let suggested_gas_limit = ((upper_bound + additional_gas_for_pubdata) as f64
+                * estimated_fee_scale_factor) as u64;
+            let (result, tx_metrics) = self
+                .estimate_gas_step(
+                    vm_permit,
+                    tx.clone(),
+                    suggested_gas_limit,
+                    gas_per_pubdata_byte as u32,
+                    fee_input,
+                    block_args,
+                    base_fee,
+                    protocol_version.into(),
+                )
+                .await
+                .context("final estimate_gas step failed")?;
+            (suggested_gas_limit, result, tx_metrics)
+        };
 
         result.into_api_call_result()?;
         self.ensure_tx_executable(&tx, &tx_metrics, false)?;
@@ -986,6 +1194,36 @@ impl TxSender {
             .into_api_call_result()
     }
 
+    /// Simulates a transaction against historical state with optional state overrides. Unlike [`Self::eth_call()`],
+    /// returns the full VM output, so that reverts and halts can be inspected by the caller.
+    pub(super) async fn simulate_tx(
+        &self,
+        block_args: BlockArgs,
+        tx: L2Tx,
+        state_override: Option<StateOverride>,
+        custom_tracers: Vec<ApiTracer>,
+    ) -> Result<VmExecutionResultAndLogs, SubmitTxError> {
+        let vm_permit = self.0.vm_concurrency_limiter.acquire().await;
+        let vm_permit = vm_permit.ok_or(SubmitTxError::ServerShuttingDown)?;
+
+        let vm_execution_cache_misses_limit = self.0.sender_config.vm_execution_cache_misses_limit;
+        let output = self
+            .0
+            .executor
+            .simulate_tx(
+                vm_permit,
+                self.shared_args().await?,
+                self.0.replica_connection_pool.clone(),
+                tx,
+                block_args,
+                vm_execution_cache_misses_limit,
+                state_override,
+                custom_tracers,
+            )
+            .await?;
+        Ok(output)
+    }
+
     pub async fn gas_price(&self) -> anyhow::Result<u64> {
         let mut connection = self.acquire_replica_connection().await?;
         let protocol_version = connection
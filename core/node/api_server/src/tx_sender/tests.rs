@@ -1,7 +1,9 @@
 //! Tests for the transaction sender.
 
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+
 use assert_matches::assert_matches;
-use multivm::interface::ExecutionResult;
+use multivm::interface::{ExecutionResult, Refunds, VmRevertReason};
 use zksync_node_fee_model::MockBatchFeeParamsProvider;
 use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
 use zksync_node_test_utils::{create_l2_block, create_l2_transaction, prepare_recovery_snapshot};
@@ -158,3 +160,72 @@ async fn submitting_tx_requires_one_connection() {
         .unwrap()
         .expect("transaction is not persisted");
 }
+
+#[tokio::test]
+async fn estimating_gas_in_single_pass() {
+    const GAS_LIMIT_THRESHOLD: u64 = 100_000;
+
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(storage);
+
+    let metered_gas = Arc::new(AtomicU64::new(0));
+    let execution_count = Arc::new(AtomicUsize::new(0));
+    let mut tx_executor = MockTransactionExecutor::default();
+    tx_executor.set_tx_responses_with_logs({
+        let metered_gas = metered_gas.clone();
+        let execution_count = execution_count.clone();
+        move |tx, _| {
+            execution_count.fetch_add(1, Ordering::SeqCst);
+            let gas_limit = tx.gas_limit().as_u64();
+            let result = if gas_limit >= GAS_LIMIT_THRESHOLD {
+                ExecutionResult::Success { output: vec![] }
+            } else {
+                ExecutionResult::Revert {
+                    output: VmRevertReason::VmError,
+                }
+            };
+            let gas_used = metered_gas.load(Ordering::SeqCst);
+            VmExecutionResultAndLogs {
+                result,
+                logs: Default::default(),
+                statistics: Default::default(),
+                refunds: Refunds {
+                    gas_refunded: gas_limit.saturating_sub(gas_used),
+                    operator_suggested_refund: 0,
+                },
+            }
+        }
+    });
+    let (mut tx_sender, _) =
+        create_test_tx_sender(pool, L2ChainId::default(), tx_executor.into()).await;
+    Arc::get_mut(&mut tx_sender.0)
+        .unwrap()
+        .sender_config
+        .estimate_gas_single_pass = true;
+
+    let tx = create_l2_transaction(10, 100);
+    // The metered gas is close to the threshold, so the single-pass estimate should succeed right away.
+    metered_gas.store(95_000, Ordering::SeqCst);
+    let fee = tx_sender
+        .get_txs_fee_in_wei(tx.clone().into(), 1.2, 1_000)
+        .await
+        .unwrap();
+    assert!(fee.gas_limit >= GAS_LIMIT_THRESHOLD.into(), "{fee:?}");
+    // One execution with the max gas limit, and one with the suggested gas limit.
+    assert_eq!(execution_count.swap(0, Ordering::SeqCst), 2);
+
+    // The metered gas significantly underestimates the required gas limit, so estimation should fall back
+    // to the binary search.
+    metered_gas.store(50_000, Ordering::SeqCst);
+    let fee = tx_sender
+        .get_txs_fee_in_wei(tx.into(), 1.2, 1_000)
+        .await
+        .unwrap();
+    assert!(fee.gas_limit >= GAS_LIMIT_THRESHOLD.into(), "{fee:?}");
+    assert!(fee.gas_limit < (2 * GAS_LIMIT_THRESHOLD).into(), "{fee:?}");
+    assert!(execution_count.load(Ordering::SeqCst) > 3);
+}
@@ -0,0 +1,438 @@
+//! HTTP-level middleware executing JSON-RPC batch requests concurrently.
+
+use std::{
+    future::Future,
+    mem,
+    num::NonZeroUsize,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{future, stream, StreamExt, TryStreamExt};
+use hyper::body::Bytes;
+use serde_json::value::RawValue;
+use vise::{Buckets, Counter, Histogram, Metrics, Unit};
+use zksync_web3_decl::jsonrpsee::types::error::{OVERSIZED_RESPONSE_CODE, OVERSIZED_RESPONSE_MSG};
+
+use super::middleware::LIMIT_EXCEEDED_CODE;
+
+/// Maximum request body size processed by the server; matches the `jsonrpsee` default.
+/// Larger requests are forwarded as is, so that they are rejected by the server.
+const MAX_REQUEST_BODY_SIZE: u64 = 10 * 1_024 * 1_024;
+
+type HttpRequest = hyper::Request<hyper::Body>;
+type HttpResponse = hyper::Response<hyper::Body>;
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_parallel_batch")]
+struct ParallelBatchMetrics {
+    /// Number of calls in batch requests executed concurrently.
+    #[metrics(buckets = Buckets::exponential(1.0..=512.0, 2.0))]
+    size: Histogram<usize>,
+    /// Latency of executing batch requests.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    latency: Histogram<Duration>,
+    /// Number of calls in batch requests that did not complete within the time budget.
+    timed_out_calls: Counter,
+    /// Number of batch requests rejected because the combined response is too large.
+    oversized_responses: Counter,
+}
+
+#[vise::register]
+static METRICS: vise::Global<ParallelBatchMetrics> = vise::Global::new();
+
+/// Parameters of the concurrent batch execution shared among all service instances.
+#[derive(Debug)]
+struct ParallelBatchParams {
+    parallelism: NonZeroUsize,
+    time_budget: Duration,
+    max_batch_size: Option<usize>,
+    max_response_size: usize,
+}
+
+/// HTTP-level middleware layer executing calls in JSON-RPC batch requests concurrently, instead of sequentially
+/// as the server library does. Calls in a batch share a time budget. Calls not completed within the budget are
+/// canceled and receive an error response with the [`LIMIT_EXCEEDED_CODE`] code, while completed calls
+/// are returned as usual, so a client can retry only the failed calls.
+///
+/// Each call is dispatched to the inner service as a separate HTTP request, so that RPC-level middleware
+/// (rate limits, metrics etc.) applies to calls in the same way as for sequential execution. Requests that are not
+/// valid batches, or exceed the batch size limit, are forwarded as is so that they are handled (e.g., rejected)
+/// by the server.
+#[derive(Debug, Clone)]
+pub(crate) struct ParallelBatchLayer {
+    params: Arc<ParallelBatchParams>,
+}
+
+impl ParallelBatchLayer {
+    pub fn new(
+        parallelism: NonZeroUsize,
+        time_budget: Duration,
+        max_batch_size: Option<usize>,
+        max_response_size: usize,
+    ) -> Self {
+        Self {
+            params: Arc::new(ParallelBatchParams {
+                parallelism,
+                time_budget,
+                max_batch_size,
+                max_response_size,
+            }),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for ParallelBatchLayer {
+    type Service = ParallelBatchService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        ParallelBatchService {
+            inner,
+            params: self.params.clone(),
+        }
+    }
+}
+
+/// Service produced by [`ParallelBatchLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct ParallelBatchService<S> {
+    inner: S,
+    params: Arc<ParallelBatchParams>,
+}
+
+impl<S> tower::Service<HttpRequest> for ParallelBatchService<S>
+where
+    S: tower::Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+    S::Error: From<hyper::Error> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = HttpResponse;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: HttpRequest) -> Self::Future {
+        let is_small_post_request = request.method() == http::Method::POST
+            && content_length(request.headers()).is_some_and(|len| len <= MAX_REQUEST_BODY_SIZE);
+        if !is_small_post_request {
+            return Box::pin(self.inner.call(request));
+        }
+
+        // Use the service instance driven to readiness for the request, and leave its clone in its place.
+        let inner = self.inner.clone();
+        let inner = mem::replace(&mut self.inner, inner);
+        Box::pin(Self::handle(inner, self.params.clone(), request))
+    }
+}
+
+impl<S> ParallelBatchService<S>
+where
+    S: tower::Service<HttpRequest, Response = HttpResponse> + Clone + Send + 'static,
+    S::Error: From<hyper::Error> + Send + 'static,
+    S::Future: Send + 'static,
+{
+    async fn handle(
+        mut inner: S,
+        params: Arc<ParallelBatchParams>,
+        request: HttpRequest,
+    ) -> Result<HttpResponse, S::Error> {
+        let (parts, body) = request.into_parts();
+        let body = hyper::body::to_bytes(body).await?;
+        let calls = parse_batch(&body).filter(|calls| {
+            calls.len() > 1 && params.max_batch_size.map_or(true, |max| calls.len() <= max)
+        });
+        let Some(calls) = calls else {
+            let request = HttpRequest::from_parts(parts, hyper::Body::from(body));
+            return inner.call(request).await;
+        };
+
+        METRICS.size.observe(calls.len());
+        let latency = METRICS.latency.start();
+        let sub_requests: Vec<_> = calls
+            .into_iter()
+            .map(|call| {
+                let mut sub_request = HttpRequest::new(hyper::Body::from(call.get().to_owned()));
+                *sub_request.method_mut() = parts.method.clone();
+                *sub_request.uri_mut() = parts.uri.clone();
+                *sub_request.version_mut() = parts.version;
+                *sub_request.headers_mut() = parts.headers.clone();
+                sub_request
+                    .headers_mut()
+                    .remove(http::header::CONTENT_LENGTH);
+                (call, sub_request)
+            })
+            .collect();
+
+        let deadline = tokio::time::Instant::now() + params.time_budget;
+        let responses = stream::iter(sub_requests)
+            .map(move |(call, sub_request)| {
+                let call_future = Self::call_single(inner.clone(), sub_request);
+                async move {
+                    match tokio::time::timeout_at(deadline, call_future).await {
+                        Ok(response) => response,
+                        Err(_) => Ok(timed_out_response(call)),
+                    }
+                }
+            })
+            .buffered(params.parallelism.get())
+            .try_collect::<Vec<_>>()
+            .await?;
+        latency.observe();
+
+        let response_body = join_responses(responses.into_iter().flatten());
+        let response_body = if response_body.len() > params.max_response_size {
+            METRICS.oversized_responses.inc();
+            serde_json::json!({
+                "jsonrpc": "2.0",
+                "error": {
+                    "code": OVERSIZED_RESPONSE_CODE,
+                    "message": OVERSIZED_RESPONSE_MSG,
+                    "data": format!("Exceeded max limit of {}", params.max_response_size),
+                },
+                "id": null,
+            })
+            .to_string()
+        } else {
+            response_body
+        };
+        let mut response = HttpResponse::new(hyper::Body::from(response_body));
+        response.headers_mut().insert(
+            http::header::CONTENT_TYPE,
+            http::HeaderValue::from_static("application/json"),
+        );
+        Ok(response)
+    }
+
+    /// Executes a single call from a batch. Returns `None` if the call is a notification.
+    async fn call_single(mut inner: S, request: HttpRequest) -> Result<Option<Bytes>, S::Error> {
+        future::poll_fn(|cx| inner.poll_ready(cx)).await?;
+        let response = inner.call(request).await?;
+        let body = hyper::body::to_bytes(response.into_body()).await?;
+        Ok(if body.is_empty() { None } else { Some(body) })
+    }
+}
+
+fn content_length(headers: &http::HeaderMap) -> Option<u64> {
+    headers
+        .get(http::header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Parses calls in a batch request. Returns `None` if the body is not a JSON array.
+fn parse_batch(body: &[u8]) -> Option<Vec<&RawValue>> {
+    let is_array = body
+        .iter()
+        .find(|byte| !byte.is_ascii_whitespace())
+        .is_some_and(|&byte| byte == b'[');
+    if !is_array {
+        return None;
+    }
+    serde_json::from_slice(body).ok()
+}
+
+/// Creates an error response for a call that did not complete within the time budget. Returns `None` for notifications.
+fn timed_out_response(call: &RawValue) -> Option<Bytes> {
+    METRICS.timed_out_calls.inc();
+    let call: serde_json::Value = serde_json::from_str(call.get()).ok()?;
+    let id = call.get("id")?;
+    let response = serde_json::json!({
+        "jsonrpc": "2.0",
+        "error": {
+            "code": LIMIT_EXCEEDED_CODE,
+            "message": "Batch request time budget exceeded",
+        },
+        "id": id,
+    });
+    Some(response.to_string().into())
+}
+
+/// Joins responses for batch calls into a batch response. If all calls are notifications, returns an empty string,
+/// which is consistent with the server library.
+fn join_responses(responses: impl Iterator<Item = Bytes>) -> String {
+    let mut joined = String::new();
+    for response in responses {
+        joined.push(if joined.is_empty() { '[' } else { ',' });
+        // Responses are produced by the server library or by this middleware, so they are valid UTF-8.
+        joined.push_str(&String::from_utf8_lossy(&response));
+    }
+    if !joined.is_empty() {
+        joined.push(']');
+    }
+    joined
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mock server echoing the call ID after a delay specified in call params. Calls without an ID are notifications.
+    #[derive(Debug, Clone)]
+    struct MockServer;
+
+    impl tower::Service<HttpRequest> for MockServer {
+        type Response = HttpResponse;
+        type Error = Box<dyn std::error::Error + Send + Sync>;
+        type Future = Pin<Box<dyn Future<Output = Result<HttpResponse, Self::Error>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, request: HttpRequest) -> Self::Future {
+            Box::pin(async move {
+                let body = hyper::body::to_bytes(request.into_body()).await?;
+                let call: serde_json::Value = serde_json::from_slice(&body)?;
+                let response_body = if call.is_array() {
+                    // Mark batches forwarded as is, so that tests can distinguish them.
+                    "sequential".to_owned()
+                } else {
+                    let delay_ms = call["params"][0].as_u64().unwrap_or(0);
+                    tokio::time::sleep(Duration::from_millis(delay_ms)).await;
+                    match call.get("id") {
+                        Some(id) => {
+                            serde_json::json!({ "jsonrpc": "2.0", "result": delay_ms, "id": id })
+                                .to_string()
+                        }
+                        None => String::new(),
+                    }
+                };
+                Ok::<_, Self::Error>(HttpResponse::new(response_body.into()))
+            })
+        }
+    }
+
+    fn batch_request(body: &str) -> HttpRequest {
+        hyper::Request::post("/")
+            .header(http::header::CONTENT_LENGTH, body.len())
+            .header(http::header::CONTENT_TYPE, "application/json")
+            .body(body.to_owned().into())
+            .unwrap()
+    }
+
+    async fn send(service: &mut ParallelBatchService<MockServer>, body: &str) -> serde_json::Value {
+        let response = tower::Service::call(service, batch_request(body))
+            .await
+            .unwrap();
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        if body.as_ref() == b"sequential" {
+            return "sequential".into();
+        }
+        if body.is_empty() {
+            return serde_json::Value::Null;
+        }
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    fn create_service(max_response_size: usize) -> ParallelBatchService<MockServer> {
+        let layer = ParallelBatchLayer::new(
+            NonZeroUsize::new(4).unwrap(),
+            Duration::from_millis(500),
+            Some(10),
+            max_response_size,
+        );
+        tower::Layer::layer(&layer, MockServer)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn executing_batch_concurrently() {
+        let mut service = create_service(usize::MAX);
+        let started_at = tokio::time::Instant::now();
+        let response = send(
+            &mut service,
+            r#"[
+                {"jsonrpc":"2.0","method":"test","params":[300],"id":1},
+                {"jsonrpc":"2.0","method":"test","params":[200],"id":"2"},
+                {"jsonrpc":"2.0","method":"test","params":[100]},
+                {"jsonrpc":"2.0","method":"test","params":[300],"id":3}
+            ]"#,
+        )
+        .await;
+        // Calls are executed concurrently, so the batch takes as much time as the slowest call.
+        let elapsed = started_at.elapsed();
+        assert!(elapsed >= Duration::from_millis(300), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(400), "{elapsed:?}");
+        assert_eq!(
+            response,
+            serde_json::json!([
+                { "jsonrpc": "2.0", "result": 300, "id": 1 },
+                { "jsonrpc": "2.0", "result": 200, "id": "2" },
+                { "jsonrpc": "2.0", "result": 300, "id": 3 },
+            ])
+        );
+
+        let response = send(
+            &mut service,
+            r#"[{"jsonrpc":"2.0","method":"test","params":[10]},{"jsonrpc":"2.0","method":"test"}]"#,
+        )
+        .await;
+        assert_eq!(response, serde_json::Value::Null);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn batch_time_budget() {
+        let mut service = create_service(usize::MAX);
+        let started_at = tokio::time::Instant::now();
+        let response = send(
+            &mut service,
+            r#"[
+                {"jsonrpc":"2.0","method":"test","params":[100],"id":1},
+                {"jsonrpc":"2.0","method":"test","params":[1000],"id":2},
+                {"jsonrpc":"2.0","method":"test","params":[1000]}
+            ]"#,
+        )
+        .await;
+        let elapsed = started_at.elapsed();
+        assert!(elapsed >= Duration::from_millis(500), "{elapsed:?}");
+        assert!(elapsed < Duration::from_millis(600), "{elapsed:?}");
+        assert_eq!(response[0]["result"], 100);
+        assert_eq!(response[1]["id"], 2);
+        assert_eq!(response[1]["error"]["code"], LIMIT_EXCEEDED_CODE);
+        assert_eq!(response.as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn forwarding_requests_as_is() {
+        let mut service = create_service(usize::MAX);
+        // Single call
+        let response = send(
+            &mut service,
+            r#"{"jsonrpc":"2.0","method":"test","params":[1],"id":1}"#,
+        )
+        .await;
+        assert_eq!(response["result"], 1);
+
+        // Single-call batch
+        let response = send(
+            &mut service,
+            r#"[{"jsonrpc":"2.0","method":"test","id":1}]"#,
+        )
+        .await;
+        assert_eq!(response, "sequential");
+
+        // Too large batch
+        let call = r#"{"jsonrpc":"2.0","method":"test","id":1}"#;
+        let batch = format!("[{}]", [call; 11].join(","));
+        let response = send(&mut service, &batch).await;
+        assert_eq!(response, "sequential");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn oversized_batch_response() {
+        let mut service = create_service(50);
+        let response = send(
+            &mut service,
+            r#"[{"jsonrpc":"2.0","method":"test","id":1},{"jsonrpc":"2.0","method":"test","id":2}]"#,
+        )
+        .await;
+        assert_eq!(response["error"]["code"], OVERSIZED_RESPONSE_CODE);
+        assert_eq!(response["id"], serde_json::Value::Null);
+    }
+}
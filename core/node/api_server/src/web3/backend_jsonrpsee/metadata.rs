@@ -1,6 +1,12 @@
 //! Method metadata.
 
-use std::{cell::RefCell, mem, sync::Arc, time::Instant};
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    mem,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use thread_local::ThreadLocal;
 use zksync_types::api;
@@ -11,7 +17,10 @@ use zksync_web3_decl::{
 
 #[cfg(test)]
 use super::testonly::RecordedMethodCalls;
-use crate::web3::metrics::{ObservedRpcParams, API_METRICS};
+use crate::web3::{
+    metrics::{ObservedRpcParams, API_METRICS},
+    slow_calls::SlowCallsSampler,
+};
 
 /// Metadata assigned to a JSON-RPC method call.
 #[derive(Debug, Clone)]
@@ -24,16 +33,19 @@ pub(crate) struct MethodMetadata {
     pub block_diff: Option<u32>,
     /// Did this call return an app-level error?
     pub has_app_error: bool,
+    /// Total time spent on DB queries during the call.
+    pub db_time: Duration,
 }
 
 impl MethodMetadata {
-    fn new(name: &'static str) -> Self {
+    pub(crate) fn new(name: &'static str) -> Self {
         Self {
             name,
             started_at: Instant::now(),
             block_id: None,
             block_diff: None,
             has_app_error: false,
+            db_time: Duration::ZERO,
         }
     }
 }
@@ -62,11 +74,20 @@ impl Drop for CurrentMethodGuard<'_> {
 #[derive(Debug, Default)]
 pub struct MethodTracer {
     inner: ThreadLocal<CurrentMethodInner>,
+    slow_calls: Option<Arc<SlowCallsSampler>>,
     #[cfg(test)]
     recorder: RecordedMethodCalls,
 }
 
 impl MethodTracer {
+    /// Creates a tracer that reports the slowest calls to the provided sampler.
+    pub(crate) fn with_slow_calls_sampler(sampler: Arc<SlowCallsSampler>) -> Self {
+        Self {
+            slow_calls: Some(sampler),
+            ..Self::default()
+        }
+    }
+
     /// Sets the block ID for the current JSON-RPC method call. It will be used as a metric label for method latency etc.
     ///
     /// This should be called inside JSON-RPC method handlers; otherwise, this method is a no-op.
@@ -91,11 +112,24 @@ impl MethodTracer {
     pub(super) fn new_call<'a>(
         self: &Arc<Self>,
         name: &'static str,
-        raw_params: ObservedRpcParams<'a>,
+        raw_params: Option<&Cow<'a, serde_json::value::RawValue>>,
+        trace_params: bool,
     ) -> MethodCall<'a> {
+        // Params are copied in full only if slow calls are sampled since the call latency is not known in advance.
+        let sampled_params = self
+            .slow_calls
+            .as_ref()
+            .and(raw_params)
+            .map(|params| params.get().into());
+        let params = if trace_params {
+            ObservedRpcParams::new(raw_params)
+        } else {
+            ObservedRpcParams::Unknown
+        };
         MethodCall {
             tracer: self.clone(),
-            params: raw_params,
+            params,
+            sampled_params,
             meta: MethodMetadata::new(name),
             is_completed: false,
         }
@@ -127,6 +161,7 @@ pub(super) struct MethodCall<'a> {
     tracer: Arc<MethodTracer>,
     meta: MethodMetadata,
     params: ObservedRpcParams<'a>,
+    sampled_params: Option<Box<str>>,
     is_completed: bool,
 }
 
@@ -150,6 +185,10 @@ impl MethodCall<'_> {
         }
     }
 
+    pub(super) fn observe_db_time(&mut self, db_time: Duration) {
+        self.meta.db_time += db_time;
+    }
+
     pub(super) fn observe_response(&mut self, response: &MethodResponse) {
         self.is_completed = true;
         let meta = &self.meta;
@@ -168,6 +207,9 @@ impl MethodCall<'_> {
             }
         }
         API_METRICS.observe_latency(meta, params);
+        if let Some(slow_calls) = &self.tracer.slow_calls {
+            slow_calls.observe(meta, self.sampled_params.take());
+        }
         #[cfg(test)]
         self.tracer.recorder.observe_response(meta, response);
     }
@@ -1,18 +1,21 @@
 use std::{
     cell::RefCell,
-    collections::HashSet,
+    collections::{HashMap, HashSet},
+    fmt,
     future::Future,
+    net::IpAddr,
     num::NonZeroU32,
     pin::Pin,
-    sync::Arc,
+    sync::{Arc, Weak},
     task::{Context, Poll},
     time::{Duration, Instant},
 };
 
+use futures::future;
 use governor::{
     clock::DefaultClock,
     middleware::NoOpMiddleware,
-    state::{InMemoryState, NotKeyed},
+    state::{keyed::DefaultKeyedStateStore, InMemoryState, NotKeyed},
     Quota, RateLimiter,
 };
 use once_cell::sync::OnceCell;
@@ -21,16 +24,22 @@ use rand::{rngs::SmallRng, RngCore, SeedableRng};
 use tokio::sync::watch;
 use tracing::instrument::{Instrument, Instrumented};
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram, Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, GaugeGuard, Histogram,
+    LabeledFamily, Metrics,
 };
+use zksync_config::configs::api::MethodRateLimits;
+use zksync_db_connection::instrument::query_time_on_current_thread;
 use zksync_web3_decl::jsonrpsee::{
     server::middleware::rpc::{layer::ResponseFuture, RpcServiceT},
     types::{error::ErrorCode, ErrorObject, Request},
     MethodResponse,
 };
 
-use super::metadata::{MethodCall, MethodTracer};
-use crate::web3::metrics::{ObservedRpcParams, API_METRICS};
+use super::{
+    metadata::{MethodCall, MethodTracer},
+    server::RemoteAddr,
+};
+use crate::web3::metrics::API_METRICS;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "transport", rename_all = "snake_case")]
@@ -53,6 +62,26 @@ struct LimitMiddlewareMetrics {
 #[vise::register]
 static METRICS: vise::Global<LimitMiddlewareMetrics> = vise::Global::new();
 
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_jsonrpc_backend_rate_limit")]
+struct RateLimitMetrics {
+    /// Number of requests rejected by per-method rate limits.
+    #[metrics(labels = ["method"])]
+    method_rejected: LabeledFamily<&'static str, Counter>,
+    /// Number of HTTP requests rejected by per-IP rate limits.
+    ip_rejected: Counter,
+}
+
+#[vise::register]
+static RATE_LIMIT_METRICS: vise::Global<RateLimitMetrics> = vise::Global::new();
+
+/// Error code returned for rate-limited requests, as per EIP-1474.
+pub(super) const LIMIT_EXCEEDED_CODE: i32 = -32005;
+
+type DirectRateLimiter = RateLimiter<NotKeyed, InMemoryState, DefaultClock, NoOpMiddleware>;
+type IpRateLimiter =
+    RateLimiter<IpAddr, DefaultKeyedStateStore<IpAddr>, DefaultClock, NoOpMiddleware>;
+
 /// A rate-limiting middleware.
 ///
 /// `jsonrpsee` will allocate the instance of this struct once per session.
@@ -105,6 +134,291 @@ where
     }
 }
 
+/// Token bucket rate limiters for specific RPC methods. Limiters are shared among all sessions
+/// of a server, i.e., limits apply to the server as a whole.
+pub(crate) struct MethodRateLimiters(HashMap<&'static str, DirectRateLimiter>);
+
+impl fmt::Debug for MethodRateLimiters {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.debug_set().entries(self.0.keys()).finish()
+    }
+}
+
+impl MethodRateLimiters {
+    /// Creates limiters for methods registered on the server. Limits for unknown methods are ignored
+    /// with a warning.
+    pub fn new(limits: &MethodRateLimits, registered_method_names: &HashSet<&'static str>) -> Self {
+        let limiters = limits.iter().filter_map(|(method_name, limit)| {
+            let Some(&method_name) = registered_method_names.get(method_name) else {
+                tracing::warn!(
+                    "Rate limit is set for method `{method_name}` not registered on the server; ignoring"
+                );
+                return None;
+            };
+            tracing::info!("Limiting method `{method_name}` to {limit} requests per second");
+            Some((method_name, RateLimiter::direct(Quota::per_second(limit))))
+        });
+        Self(limiters.collect())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// RPC-level middleware enforcing per-method rate limits. Rejected requests receive an error with
+/// the [`LIMIT_EXCEEDED_CODE`] code.
+#[derive(Debug)]
+pub(crate) struct MethodRateLimitMiddleware<S> {
+    inner: S,
+    limiters: Arc<MethodRateLimiters>,
+}
+
+impl<S> MethodRateLimitMiddleware<S> {
+    pub fn new(inner: S, limiters: Arc<MethodRateLimiters>) -> Self {
+        Self { inner, limiters }
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for MethodRateLimitMiddleware<S>
+where
+    S: Send + Sync + RpcServiceT<'a>,
+{
+    type Future = ResponseFuture<S::Future>;
+
+    fn call(&self, request: Request<'a>) -> Self::Future {
+        let limiter = self.limiters.0.get_key_value(request.method_name());
+        if let Some((&method_name, limiter)) = limiter {
+            if limiter.check().is_err() {
+                RATE_LIMIT_METRICS.method_rejected[&method_name].inc();
+                let rp = MethodResponse::error(
+                    request.id,
+                    ErrorObject::owned(
+                        LIMIT_EXCEEDED_CODE,
+                        format!("Rate limit exceeded for method `{method_name}`"),
+                        None::<()>,
+                    ),
+                );
+                return ResponseFuture::ready(rp);
+            }
+        }
+        ResponseFuture::future(self.inner.call(request))
+    }
+}
+
+/// HTTP-level middleware layer enforcing per-IP request quotas. Each HTTP request (including WebSocket handshakes)
+/// consumes a single token from the bucket of the client IP.
+///
+/// The client IP is the remote address of the connection, as provided by [`serve()`](super::serve). If the remote
+/// address is a trusted proxy, the client IP is determined from the `X-Forwarded-For` header (the rightmost entry
+/// not belonging to a trusted proxy) or the `X-Real-IP` header. Forwarding headers from other clients are ignored,
+/// so that they cannot be spoofed to evade the quota.
+#[derive(Clone)]
+pub(crate) struct IpRateLimitLayer {
+    limiter: Arc<IpRateLimiter>,
+    trusted_proxies: Arc<HashSet<IpAddr>>,
+}
+
+impl fmt::Debug for IpRateLimitLayer {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("IpRateLimitLayer")
+            .field("tracked_ips", &self.limiter.len())
+            .field("trusted_proxies", &self.trusted_proxies)
+            .finish()
+    }
+}
+
+impl IpRateLimitLayer {
+    pub fn new(requests_per_second: NonZeroU32, trusted_proxies: HashSet<IpAddr>) -> Self {
+        Self {
+            limiter: Arc::new(RateLimiter::keyed(Quota::per_second(requests_per_second))),
+            trusted_proxies: Arc::new(trusted_proxies),
+        }
+    }
+
+    /// Periodically removes stale IP entries from the limiter so that its memory usage stays bounded.
+    /// Terminates once all layer instances are dropped.
+    pub fn run_cleanup(&self, interval: Duration) -> impl Future<Output = ()> {
+        let limiter = Arc::downgrade(&self.limiter);
+        Self::cleanup(limiter, interval)
+    }
+
+    async fn cleanup(limiter: Weak<IpRateLimiter>, interval: Duration) {
+        loop {
+            tokio::time::sleep(interval).await;
+            let Some(limiter) = limiter.upgrade() else {
+                return;
+            };
+            limiter.retain_recent();
+            limiter.shrink_to_fit();
+        }
+    }
+
+    fn client_ip(&self, remote_ip: IpAddr, headers: &http::HeaderMap) -> IpAddr {
+        if !self.trusted_proxies.contains(&remote_ip) {
+            return remote_ip;
+        }
+
+        // Entries are appended by each proxy, so we walk them starting from the proxy closest to the server.
+        let mut forwarded_for = headers
+            .get_all("x-forwarded-for")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .rev()
+            .peekable();
+        if forwarded_for.peek().is_none() {
+            return headers
+                .get("x-real-ip")
+                .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+                .unwrap_or(remote_ip);
+        }
+
+        let mut client_ip = remote_ip;
+        for entry in forwarded_for {
+            let Ok(ip) = entry.trim().parse() else {
+                break;
+            };
+            client_ip = ip;
+            if !self.trusted_proxies.contains(&ip) {
+                break;
+            }
+        }
+        client_ip
+    }
+}
+
+impl<S> tower::Layer<S> for IpRateLimitLayer {
+    type Service = IpRateLimitService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        IpRateLimitService {
+            inner,
+            layer: self.clone(),
+        }
+    }
+}
+
+/// Service produced by [`IpRateLimitLayer`].
+#[derive(Clone)]
+pub(crate) struct IpRateLimitService<S> {
+    inner: S,
+    layer: IpRateLimitLayer,
+}
+
+impl<S: fmt::Debug> fmt::Debug for IpRateLimitService<S> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("IpRateLimitService")
+            .field("inner", &self.inner)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<S, ReqBody, ResBody> tower::Service<http::Request<ReqBody>> for IpRateLimitService<S>
+where
+    S: tower::Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    ResBody: From<String>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = future::Either<future::Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        let remote_addr = request.extensions().get::<RemoteAddr>().copied();
+        let Some(RemoteAddr(remote_addr)) = remote_addr else {
+            // The server wasn't started with `serve()`, so the client IP is unknown.
+            return future::Either::Right(self.inner.call(request));
+        };
+        let client_ip = self.layer.client_ip(remote_addr.ip(), request.headers());
+        if self.layer.limiter.check_key(&client_ip).is_err() {
+            RATE_LIMIT_METRICS.ip_rejected.inc();
+            tracing::debug!("Rejected HTTP request from {client_ip} due to per-IP rate limit");
+
+            let body = serde_json::json!({
+                "jsonrpc": "2.0",
+                "error": {
+                    "code": LIMIT_EXCEEDED_CODE,
+                    "message": "Rate limit exceeded for client IP",
+                },
+                "id": null,
+            });
+            let mut response = http::Response::new(ResBody::from(body.to_string()));
+            *response.status_mut() = http::StatusCode::TOO_MANY_REQUESTS;
+            response.headers_mut().insert(
+                http::header::CONTENT_TYPE,
+                http::HeaderValue::from_static("application/json"),
+            );
+            return future::Either::Left(future::ready(Ok(response)));
+        }
+        future::Either::Right(self.inner.call(request))
+    }
+}
+
+/// HTTP-level middleware layer rejecting requests with an `Origin` header not in the allowed list. Used for WebSocket
+/// handshakes, to which browsers don't apply CORS. Requests without an `Origin` header (e.g., from non-browser clients)
+/// are allowed.
+#[derive(Debug, Clone)]
+pub(crate) struct AllowedOriginsLayer {
+    allowed_origins: Arc<[http::HeaderValue]>,
+}
+
+impl AllowedOriginsLayer {
+    pub fn new(allowed_origins: Arc<[http::HeaderValue]>) -> Self {
+        Self { allowed_origins }
+    }
+}
+
+impl<S> tower::Layer<S> for AllowedOriginsLayer {
+    type Service = AllowedOriginsService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AllowedOriginsService {
+            inner,
+            allowed_origins: self.allowed_origins.clone(),
+        }
+    }
+}
+
+/// Service produced by [`AllowedOriginsLayer`].
+#[derive(Debug, Clone)]
+pub(crate) struct AllowedOriginsService<S> {
+    inner: S,
+    allowed_origins: Arc<[http::HeaderValue]>,
+}
+
+impl<S, ReqBody, ResBody> tower::Service<http::Request<ReqBody>> for AllowedOriginsService<S>
+where
+    S: tower::Service<http::Request<ReqBody>, Response = http::Response<ResBody>>,
+    ResBody: From<String>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = future::Either<future::Ready<Result<Self::Response, Self::Error>>, S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<ReqBody>) -> Self::Future {
+        if let Some(origin) = request.headers().get(http::header::ORIGIN) {
+            if !self.allowed_origins.contains(origin) {
+                tracing::debug!("Rejected HTTP request with disallowed origin {origin:?}");
+                let mut response =
+                    http::Response::new(ResBody::from("Origin not allowed".to_owned()));
+                *response.status_mut() = http::StatusCode::FORBIDDEN;
+                return future::Either::Left(future::ready(Ok(response)));
+            }
+        }
+        future::Either::Right(self.inner.call(request))
+    }
+}
+
 /// RPC-level middleware that adds [`MethodCall`] metadata to method logic. Method handlers can then access this metadata
 /// using [`MethodTracer`], which is a part of `RpcState`. When the handler completes or is dropped, the results are reported
 /// as metrics.
@@ -137,12 +451,9 @@ where
             .copied()
             .unwrap_or("");
 
-        let observed_params = if TRACE_PARAMS {
-            ObservedRpcParams::new(request.params.as_ref())
-        } else {
-            ObservedRpcParams::Unknown
-        };
-        let call = self.method_tracer.new_call(method_name, observed_params);
+        let call = self
+            .method_tracer
+            .new_call(method_name, request.params.as_ref(), TRACE_PARAMS);
         WithMethodCall::new(self.inner.call(request), call)
     }
 }
@@ -168,14 +479,17 @@ impl<F: Future<Output = MethodResponse>> Future for WithMethodCall<'_, F> {
     fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
         let projection = self.project();
         let guard = projection.call.set_as_current();
-        match projection.inner.poll(cx) {
-            Poll::Pending => Poll::Pending,
-            Poll::Ready(response) => {
-                drop(guard);
-                projection.call.observe_response(&response);
-                Poll::Ready(response)
-            }
+        let query_time_before_poll = query_time_on_current_thread();
+        let poll_result = projection.inner.poll(cx);
+        drop(guard);
+        projection
+            .call
+            .observe_db_time(query_time_on_current_thread() - query_time_before_poll);
+
+        if let Poll::Ready(response) = &poll_result {
+            projection.call.observe_response(response);
         }
+        poll_result
     }
 }
 
@@ -332,14 +646,23 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::time::Duration;
+    use std::{net::Ipv4Addr, time::Duration};
 
     use rand::{thread_rng, Rng};
     use test_casing::{test_casing, Product};
     use zksync_types::api;
-    use zksync_web3_decl::jsonrpsee::helpers::MethodResponseResult;
+    use zksync_web3_decl::jsonrpsee::{
+        core::{client::ClientT, ClientError},
+        helpers::MethodResponseResult,
+        http_client::HttpClient,
+        rpc_params,
+        server::{RpcServiceBuilder, ServerBuilder},
+        types::ErrorObjectOwned,
+        RpcModule,
+    };
 
     use super::*;
+    use crate::web3::backend_jsonrpsee::serve;
 
     #[test_casing(4, Product(([false, true], [false, true])))]
     #[tokio::test(flavor = "multi_thread")]
@@ -373,10 +696,7 @@ mod tests {
                 }
             };
 
-            WithMethodCall::new(
-                inner,
-                method_tracer.new_call("test", ObservedRpcParams::None),
-            )
+            WithMethodCall::new(inner, method_tracer.new_call("test", None, true))
         });
 
         if spawn_tasks {
@@ -412,4 +732,272 @@ mod tests {
         let elapsed = now.elapsed();
         assert!(elapsed >= Duration::from_millis(15), "{elapsed:?}");
     }
+
+    #[test]
+    fn extracting_client_ip() {
+        let proxy_ip = IpAddr::from([10, 0, 0, 100]);
+        let layer = IpRateLimitLayer::new(NonZeroU32::new(1).unwrap(), HashSet::from([proxy_ip]));
+        let client_ip = IpAddr::from([1, 2, 3, 4]);
+        let mut headers = http::HeaderMap::new();
+        assert_eq!(layer.client_ip(client_ip, &headers), client_ip);
+        assert_eq!(layer.client_ip(proxy_ip, &headers), proxy_ip);
+
+        headers.insert("x-real-ip", "10.0.0.1".parse().unwrap());
+        assert_eq!(
+            layer.client_ip(proxy_ip, &headers),
+            IpAddr::from([10, 0, 0, 1])
+        );
+        // Headers from untrusted clients are ignored.
+        assert_eq!(layer.client_ip(client_ip, &headers), client_ip);
+
+        headers.insert("x-forwarded-for", "1.2.3.4, 10.0.0.2".parse().unwrap());
+        assert_eq!(
+            layer.client_ip(proxy_ip, &headers),
+            IpAddr::from([10, 0, 0, 2])
+        );
+        assert_eq!(layer.client_ip(client_ip, &headers), client_ip);
+        // Trusted proxies in the forwarding chain are skipped.
+        headers.insert("x-forwarded-for", "1.2.3.4, 10.0.0.100".parse().unwrap());
+        assert_eq!(layer.client_ip(proxy_ip, &headers), client_ip);
+        headers.insert("x-forwarded-for", "garbage".parse().unwrap());
+        assert_eq!(layer.client_ip(proxy_ip, &headers), proxy_ip);
+    }
+
+    #[tokio::test]
+    async fn rate_limiting_methods_and_ips() {
+        let mut rpc_module = RpcModule::new(());
+        rpc_module
+            .register_method("test_limited", |_params, _ctx| {
+                Ok::<_, ErrorObjectOwned>("limited")
+            })
+            .unwrap();
+        rpc_module
+            .register_method("test_unlimited", |_params, _ctx| {
+                Ok::<_, ErrorObjectOwned>("unlimited")
+            })
+            .unwrap();
+        let registered_method_names = rpc_module.method_names().collect();
+        let limits = MethodRateLimits::from_iter([
+            ("test_limited", NonZeroU32::new(1).unwrap()),
+            ("test_unknown", NonZeroU32::new(1).unwrap()),
+        ]);
+        let limiters = Arc::new(MethodRateLimiters::new(&limits, &registered_method_names));
+        assert_eq!(limiters.0.len(), 1);
+
+        let rpc_middleware = RpcServiceBuilder::new()
+            .layer_fn(move |svc| MethodRateLimitMiddleware::new(svc, limiters.clone()));
+        let http_middleware = tower::ServiceBuilder::new().layer(IpRateLimitLayer::new(
+            NonZeroU32::new(3).unwrap(),
+            HashSet::new(),
+        ));
+        let service_builder = ServerBuilder::default()
+            .set_http_middleware(http_middleware)
+            .set_rpc_middleware(rpc_middleware)
+            .http_only()
+            .to_service_builder();
+        let (local_addr, server_handle) = serve(
+            service_builder,
+            (Ipv4Addr::LOCALHOST, 0).into(),
+            100,
+            rpc_module,
+        )
+        .unwrap();
+        let client = <HttpClient>::builder()
+            .build(format!("http://{local_addr}/"))
+            .unwrap();
+
+        let response: String = client.request("test_limited", rpc_params![]).await.unwrap();
+        assert_eq!(response, "limited");
+        let err = client
+            .request::<String, _>("test_limited", rpc_params![])
+            .await
+            .unwrap_err();
+        assert_matches::assert_matches!(
+            err,
+            ClientError::Call(err) if err.code() == LIMIT_EXCEEDED_CODE
+        );
+        // Requests without forwarding headers are limited by the remote address. The per-IP quota
+        // is already partially consumed by the `test_limited` calls above.
+        let response: String = client
+            .request("test_unlimited", rpc_params![])
+            .await
+            .unwrap();
+        assert_eq!(response, "unlimited");
+        let err = client
+            .request::<String, _>("test_unlimited", rpc_params![])
+            .await
+            .unwrap_err();
+        assert_matches::assert_matches!(err, ClientError::Transport(_));
+
+        // Forwarding headers from clients that are not trusted proxies must not allow to evade the limit.
+        for i in 0..3 {
+            let mut headers = http::HeaderMap::new();
+            let spoofed_ip = format!("1.2.3.{i}");
+            headers.insert("x-forwarded-for", spoofed_ip.parse().unwrap());
+            headers.insert("x-real-ip", spoofed_ip.parse().unwrap());
+            let spoofing_client = <HttpClient>::builder()
+                .set_headers(headers)
+                .build(format!("http://{local_addr}/"))
+                .unwrap();
+            spoofing_client
+                .request::<String, _>("test_unlimited", rpc_params![])
+                .await
+                .unwrap_err();
+        }
+
+        server_handle.stop().ok();
+    }
+
+    #[tokio::test]
+    async fn rate_limiting_ips_behind_trusted_proxy() {
+        let mut rpc_module = RpcModule::new(());
+        rpc_module
+            .register_method("test_method", |_params, _ctx| {
+                Ok::<_, ErrorObjectOwned>("ok")
+            })
+            .unwrap();
+        let trusted_proxies = HashSet::from([IpAddr::from(Ipv4Addr::LOCALHOST)]);
+        let http_middleware = tower::ServiceBuilder::new().layer(IpRateLimitLayer::new(
+            NonZeroU32::new(2).unwrap(),
+            trusted_proxies,
+        ));
+        let service_builder = ServerBuilder::default()
+            .set_http_middleware(http_middleware)
+            .http_only()
+            .to_service_builder();
+        let (local_addr, server_handle) = serve(
+            service_builder,
+            (Ipv4Addr::LOCALHOST, 0).into(),
+            100,
+            rpc_module,
+        )
+        .unwrap();
+
+        for client_ip in ["1.2.3.4", "5.6.7.8"] {
+            let mut headers = http::HeaderMap::new();
+            headers.insert("x-forwarded-for", client_ip.parse().unwrap());
+            let client = <HttpClient>::builder()
+                .set_headers(headers)
+                .build(format!("http://{local_addr}/"))
+                .unwrap();
+            for _ in 0..2 {
+                let response: String = client.request("test_method", rpc_params![]).await.unwrap();
+                assert_eq!(response, "ok");
+            }
+            client
+                .request::<String, _>("test_method", rpc_params![])
+                .await
+                .unwrap_err();
+        }
+
+        // Requests from the proxy itself are limited by its IP.
+        let client = <HttpClient>::builder()
+            .build(format!("http://{local_addr}/"))
+            .unwrap();
+        for _ in 0..2 {
+            let response: String = client.request("test_method", rpc_params![]).await.unwrap();
+            assert_eq!(response, "ok");
+        }
+        client
+            .request::<String, _>("test_method", rpc_params![])
+            .await
+            .unwrap_err();
+
+        server_handle.stop().ok();
+    }
+
+    #[tokio::test]
+    async fn limiting_connections() {
+        let mut rpc_module = RpcModule::new(());
+        rpc_module
+            .register_method("test_method", |_params, _ctx| {
+                Ok::<_, ErrorObjectOwned>("ok")
+            })
+            .unwrap();
+        let service_builder = ServerBuilder::default().http_only().to_service_builder();
+        let (local_addr, server_handle) = serve(
+            service_builder,
+            (Ipv4Addr::LOCALHOST, 0).into(),
+            1,
+            rpc_module,
+        )
+        .unwrap();
+
+        // Occupy the only connection slot.
+        let idle_connection = tokio::net::TcpStream::connect(local_addr).await.unwrap();
+        let client = <HttpClient>::builder()
+            .build(format!("http://{local_addr}/"))
+            .unwrap();
+        client
+            .request::<String, _>("test_method", rpc_params![])
+            .await
+            .unwrap_err();
+
+        drop(idle_connection);
+        let mut retries = 0;
+        let response: String = loop {
+            // The server may need some time to notice that the connection is closed.
+            match client.request("test_method", rpc_params![]).await {
+                Ok(response) => break response,
+                Err(err) if retries < 50 => {
+                    tracing::debug!("Request failed: {err}");
+                    retries += 1;
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                }
+                Err(err) => panic!("Request failed: {err}"),
+            }
+        };
+        assert_eq!(response, "ok");
+
+        server_handle.stop().ok();
+    }
+
+    #[tokio::test]
+    async fn rejecting_disallowed_origins() {
+        let mut rpc_module = RpcModule::new(());
+        rpc_module
+            .register_method("test_method", |_params, _ctx| {
+                Ok::<_, ErrorObjectOwned>("ok")
+            })
+            .unwrap();
+        let allowed_origins: Arc<[_]> =
+            Arc::new([http::HeaderValue::from_static("https://app.example.com")]);
+        let http_middleware =
+            tower::ServiceBuilder::new().layer(AllowedOriginsLayer::new(allowed_origins));
+        let server = ServerBuilder::default()
+            .set_http_middleware(http_middleware)
+            .http_only()
+            .build((Ipv4Addr::LOCALHOST, 0))
+            .await
+            .unwrap();
+        let local_addr = server.local_addr().unwrap();
+        let server_handle = server.start(rpc_module);
+
+        for origin in [None, Some("https://app.example.com")] {
+            let mut headers = http::HeaderMap::new();
+            if let Some(origin) = origin {
+                headers.insert(http::header::ORIGIN, origin.parse().unwrap());
+            }
+            let client = <HttpClient>::builder()
+                .set_headers(headers)
+                .build(format!("http://{local_addr}/"))
+                .unwrap();
+            let response: String = client.request("test_method", rpc_params![]).await.unwrap();
+            assert_eq!(response, "ok");
+        }
+
+        let mut headers = http::HeaderMap::new();
+        headers.insert(http::header::ORIGIN, "https://evil.com".parse().unwrap());
+        let client = <HttpClient>::builder()
+            .set_headers(headers)
+            .build(format!("http://{local_addr}/"))
+            .unwrap();
+        let err = client
+            .request::<String, _>("test_method", rpc_params![])
+            .await
+            .unwrap_err();
+        assert_matches::assert_matches!(err, ClientError::Transport(_));
+
+        server_handle.stop().ok();
+    }
 }
@@ -2,22 +2,29 @@
 //! Consists mostly of boilerplate code implementing the `jsonrpsee` server traits for the corresponding
 //! namespace structures defined in `zksync_core`.
 
+use zksync_error_codes::HasErrorCode;
 use zksync_web3_decl::{
     error::Web3Error,
     jsonrpsee::types::{error::ErrorCode, ErrorObjectOwned},
 };
 
 pub(crate) use self::{
+    batch::ParallelBatchLayer,
     metadata::{MethodMetadata, MethodTracer},
     middleware::{
-        CorrelationMiddleware, LimitMiddleware, MetadataLayer, ShutdownMiddleware, TrafficTracker,
+        AllowedOriginsLayer, CorrelationMiddleware, IpRateLimitLayer, LimitMiddleware,
+        MetadataLayer, MethodRateLimitMiddleware, MethodRateLimiters, ShutdownMiddleware,
+        TrafficTracker,
     },
+    server::serve,
 };
 use crate::tx_sender::SubmitTxError;
 
+mod batch;
 mod metadata;
 mod middleware;
 pub mod namespaces;
+mod server;
 #[cfg(test)]
 pub(crate) mod testonly;
 
@@ -25,10 +32,12 @@ impl MethodTracer {
     pub(crate) fn map_err(&self, err: Web3Error) -> ErrorObjectOwned {
         self.observe_error(&err);
 
-        let data = match &err {
-            Web3Error::SubmitTransactionError(_, data) => Some(format!("0x{}", hex::encode(data))),
-            Web3Error::ProxyError(_) => Some("0x".to_owned()),
-            _ => None,
+        // Errors that have established `data` keep it for compatibility; all other errors report their stable code.
+        let data: serde_json::Value = match &err {
+            Web3Error::SubmitTransactionError(_, data) => format!("0x{}", hex::encode(data)).into(),
+            Web3Error::ProxyError(_) => "0x".into(),
+            Web3Error::LogsLimitExceeded(.., Some(cursor)) => cursor.to_string().into(),
+            _ => serde_json::json!({ "errorCode": err.error_code() }),
         };
         let code = match err {
             Web3Error::MethodNotImplemented => ErrorCode::MethodNotFound.code(),
@@ -39,11 +48,18 @@ impl MethodTracer {
             | Web3Error::TooManyTopics
             | Web3Error::FilterNotFound
             | Web3Error::InvalidFilterBlockHash
-            | Web3Error::LogsLimitExceeded(_, _, _) => ErrorCode::InvalidParams.code(),
+            | Web3Error::InvalidRewardPercentiles
+            | Web3Error::TooManyTransactions(_)
+            | Web3Error::InvalidStateOverride(_)
+            | Web3Error::UnknownTracer(_)
+            | Web3Error::TooManyStorageKeys(_)
+            | Web3Error::LogsLimitExceeded(..) => ErrorCode::InvalidParams.code(),
             Web3Error::SubmitTransactionError(_, _)
             | Web3Error::SerializationError(_)
             | Web3Error::ProxyError(_) => 3,
             Web3Error::TreeApiUnavailable => 6,
+            // "Limit exceeded" error code as per EIP-1474
+            Web3Error::ServerOverloaded => -32005,
         };
         let message = match err {
             // Do not expose internal error details to the client.
@@ -53,7 +69,7 @@ impl MethodTracer {
             _ => err.to_string(),
         };
 
-        ErrorObjectOwned::owned(code, message, data)
+        ErrorObjectOwned::owned(code, message, Some(data))
     }
 }
 
@@ -31,6 +31,12 @@ impl EnNamespaceServer for EnNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn snapshot_recovery_status(&self) -> RpcResult<Option<en::SnapshotRecoveryStatus>> {
+        self.snapshot_recovery_status_impl()
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn genesis_config(&self) -> RpcResult<GenesisConfig> {
         self.genesis_config_impl()
             .await
@@ -3,11 +3,13 @@ use std::collections::HashMap;
 use itertools::Itertools;
 use zksync_types::{
     api::{
-        ApiStorageLog, BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Log, Proof,
-        ProtocolVersion, TransactionDetailedResult, TransactionDetails,
+        ApiStorageLog, BlockDetails, BridgeAddresses, L1BatchDetails, L2ToL1LogProof, Log,
+        LogsCursor, LogsPage, PageCursor, Proof, ProofRequest, ProtocolVersion, SimulationBlockId,
+        SimulationOptions, TransactionDetailedResult, TransactionDetails, TransactionSimulation,
+        TransactionsPage,
     },
     fee::Fee,
-    fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
+    fee_model::{FeeModelBreakdown, FeeParams, PubdataIndependentBatchFeeModelInput},
     transaction_request::CallRequest,
     web3::Bytes,
     Address, L1BatchNumber, L2BlockNumber, StorageLogQueryType, H256, U256, U64,
@@ -15,7 +17,7 @@ use zksync_types::{
 use zksync_web3_decl::{
     jsonrpsee::core::{async_trait, RpcResult},
     namespaces::ZksNamespaceServer,
-    types::Token,
+    types::{Filter, Token},
 };
 
 use crate::web3::ZksNamespace;
@@ -28,6 +30,12 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn estimate_fee_batch(&self, reqs: Vec<CallRequest>) -> RpcResult<Vec<Fee>> {
+        self.estimate_fee_batch_impl(reqs)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn estimate_gas_l1_to_l2(&self, req: CallRequest) -> RpcResult<U256> {
         self.estimate_l1_to_l2_gas_impl(req)
             .await
@@ -154,6 +162,10 @@ impl ZksNamespaceServer for ZksNamespace {
         Ok(self.get_fee_params_impl())
     }
 
+    async fn get_fee_model_breakdown(&self) -> RpcResult<FeeModelBreakdown> {
+        Ok(self.get_fee_model_breakdown_impl())
+    }
+
     async fn get_batch_fee_input(&self) -> RpcResult<PubdataIndependentBatchFeeModelInput> {
         self.get_batch_fee_input_impl()
             .await
@@ -180,11 +192,51 @@ impl ZksNamespaceServer for ZksNamespace {
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_proofs(
+        &self,
+        accounts: Vec<ProofRequest>,
+        l1_batch_number: L1BatchNumber,
+    ) -> RpcResult<Option<Vec<Proof>>> {
+        self.get_account_proofs_impl(accounts, l1_batch_number)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn get_base_token_l1_address(&self) -> RpcResult<Address> {
         self.get_base_token_l1_address_impl()
             .map_err(|err| self.current_method().map_err(err))
     }
 
+    async fn get_logs_paged(
+        &self,
+        filter: Filter,
+        cursor: Option<LogsCursor>,
+    ) -> RpcResult<LogsPage> {
+        self.get_logs_paged_impl(filter, cursor)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_account_transactions_paged(
+        &self,
+        address: Address,
+        cursor: Option<PageCursor>,
+    ) -> RpcResult<TransactionsPage> {
+        self.get_account_transactions_paged_impl(address, cursor)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
+    async fn get_token_transfers_paged(
+        &self,
+        address: Address,
+        cursor: Option<PageCursor>,
+    ) -> RpcResult<LogsPage> {
+        self.get_token_transfers_paged_impl(address, cursor)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
+
     async fn send_raw_transaction_with_detailed_output(
         &self,
         tx_bytes: Bytes,
@@ -215,4 +267,15 @@ impl ZksNamespaceServer for ZksNamespace {
             })
             .map_err(|err| self.current_method().map_err(err))
     }
+
+    async fn simulate_transaction(
+        &self,
+        req: CallRequest,
+        block: SimulationBlockId,
+        options: Option<SimulationOptions>,
+    ) -> RpcResult<TransactionSimulation> {
+        self.simulate_transaction_impl(req, block, options)
+            .await
+            .map_err(|err| self.current_method().map_err(err))
+    }
 }
@@ -0,0 +1,89 @@
+//! Serving logic for the `jsonrpsee` server that exposes connection details to HTTP middleware.
+
+use std::{error::Error as StdError, net::SocketAddr, sync::Arc};
+
+use anyhow::Context as _;
+use hyper::{
+    server::conn::AddrStream,
+    service::{make_service_fn, service_fn, Service},
+};
+use tokio::sync::Semaphore;
+use zksync_web3_decl::jsonrpsee::{
+    server::{stop_channel, ServerHandle, TowerService, TowerServiceBuilder},
+    Methods,
+};
+
+/// Remote address of the connection over which an HTTP request was received. Inserted into request extensions
+/// by [`serve()`], so that HTTP middleware can access it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct RemoteAddr(pub SocketAddr);
+
+/// Binds to the specified address and starts serving JSON-RPC requests. Unlike `Server::start()`, this inserts
+/// the [`RemoteAddr`] of the connection into extensions of each HTTP request (including WebSocket handshakes).
+/// Like `Server::start()`, this limits the number of concurrent connections to `max_connections`; excess connections
+/// are closed immediately.
+///
+/// Returns the local address the server is bound to, and a handle to stop the server.
+pub(crate) fn serve<RpcMiddleware, HttpMiddleware>(
+    service_builder: TowerServiceBuilder<RpcMiddleware, HttpMiddleware>,
+    addr: SocketAddr,
+    max_connections: usize,
+    methods: impl Into<Methods>,
+) -> anyhow::Result<(SocketAddr, ServerHandle)>
+where
+    RpcMiddleware: Clone + Send + 'static,
+    HttpMiddleware: Clone + Send + 'static,
+    TowerService<RpcMiddleware, HttpMiddleware>: Service<
+            hyper::Request<hyper::Body>,
+            Response = hyper::Response<hyper::Body>,
+            Error = Box<dyn StdError + Send + Sync>,
+        > + Send
+        + 'static,
+    <TowerService<RpcMiddleware, HttpMiddleware> as Service<hyper::Request<hyper::Body>>>::Future:
+        Send + 'static,
+{
+    let methods = methods.into();
+    let (stop_handle, server_handle) = stop_channel();
+    let server_stop_handle = stop_handle.clone();
+    let connection_permits = Arc::new(Semaphore::new(max_connections));
+
+    let make_service = make_service_fn(move |conn: &AddrStream| {
+        let remote_addr = RemoteAddr(conn.remote_addr());
+        // The permit is held by the connection service, i.e., until the connection is closed.
+        let permit = connection_permits.clone().try_acquire_owned();
+        let mut service = service_builder
+            .clone()
+            .build(methods.clone(), stop_handle.clone());
+        async move {
+            let Ok(permit) = permit else {
+                tracing::debug!(
+                    "Too many connections; closing connection from {}",
+                    remote_addr.0
+                );
+                anyhow::bail!("too many connections");
+            };
+            Ok(service_fn(
+                move |mut request: hyper::Request<hyper::Body>| {
+                    let _ = &permit;
+                    request.extensions_mut().insert(remote_addr);
+                    service.call(request)
+                },
+            ))
+        }
+    });
+
+    let server = hyper::Server::try_bind(&addr)
+        .with_context(|| format!("failed binding to {addr}"))?
+        .tcp_nodelay(true)
+        .serve(make_service);
+    let local_addr = server.local_addr();
+    // All `StopHandle`s are dropped once the server and all its connections are shut down, which resolves
+    // `ServerHandle::stopped()`.
+    let server = server.with_graceful_shutdown(server_stop_handle.shutdown());
+    tokio::spawn(async move {
+        if let Err(err) = server.await {
+            tracing::error!("JSON-RPC server on {local_addr} failed: {err}");
+        }
+    });
+    Ok((local_addr, server_handle))
+}
@@ -0,0 +1,121 @@
+//! Reward percentiles computation for `eth_feeHistory`.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use lru::LruCache;
+use zksync_types::{L2BlockNumber, U256};
+use zksync_web3_decl::error::Web3Error;
+
+/// Effective priority fees and gas used for all transactions in an L2 block, sorted by the priority fee.
+#[derive(Debug, Default)]
+pub(crate) struct BlockRewardsData(Vec<(U256, u64)>);
+
+impl BlockRewardsData {
+    pub fn new(mut txs: Vec<(U256, u64)>) -> Self {
+        txs.sort_by_key(|&(priority_fee, _)| priority_fee);
+        Self(txs)
+    }
+
+    /// Computes rewards for the specified percentiles. Similar to Geth, transactions are weighted by the gas used,
+    /// i.e. the reward for a percentile `p` is the priority fee of the first transaction (in the priority fee order)
+    /// with cumulative gas used reaching `p`% of the total gas used in the block.
+    ///
+    /// Percentiles are expected to be validated using [`validate_reward_percentiles()`].
+    pub fn rewards(&self, percentiles: &[f32]) -> Vec<U256> {
+        let Some(&(_, first_gas_used)) = self.0.first() else {
+            return vec![U256::zero(); percentiles.len()];
+        };
+
+        let total_gas_used: u128 = self.0.iter().map(|&(_, gas)| u128::from(gas)).sum();
+        let mut tx_index = 0;
+        let mut cumulative_gas_used = u128::from(first_gas_used);
+        percentiles
+            .iter()
+            .map(|&percentile| {
+                let threshold = (total_gas_used as f64 * f64::from(percentile) / 100.0) as u128;
+                while cumulative_gas_used < threshold && tx_index + 1 < self.0.len() {
+                    tx_index += 1;
+                    cumulative_gas_used += u128::from(self.0[tx_index].1);
+                }
+                self.0[tx_index].0
+            })
+            .collect()
+    }
+}
+
+/// Checks that reward percentiles are in the `[0, 100]` range and are monotonically increasing.
+pub(crate) fn validate_reward_percentiles(percentiles: &[f32]) -> Result<(), Web3Error> {
+    let is_in_range = percentiles
+        .iter()
+        .all(|percentile| (0.0..=100.0).contains(percentile));
+    let is_sorted = percentiles.windows(2).all(|window| window[0] <= window[1]);
+    if is_in_range && is_sorted {
+        Ok(())
+    } else {
+        Err(Web3Error::InvalidRewardPercentiles)
+    }
+}
+
+/// LRU cache of [`BlockRewardsData`] for sealed L2 blocks. Shared among all API requests served by a server.
+#[derive(Debug, Clone)]
+pub(crate) struct FeeHistoryCache(Arc<Mutex<LruCache<L2BlockNumber, Arc<BlockRewardsData>>>>);
+
+impl FeeHistoryCache {
+    pub fn new(capacity: NonZeroUsize) -> Self {
+        Self(Arc::new(Mutex::new(LruCache::new(capacity))))
+    }
+
+    pub fn get(&self, block_number: L2BlockNumber) -> Option<Arc<BlockRewardsData>> {
+        self.0
+            .lock()
+            .expect("fee history cache is poisoned")
+            .get(&block_number)
+            .cloned()
+    }
+
+    /// Inserts data for the specified L2 block. The caller is responsible for only inserting data for sealed blocks.
+    pub fn insert(&self, block_number: L2BlockNumber, data: Arc<BlockRewardsData>) {
+        self.0
+            .lock()
+            .expect("fee history cache is poisoned")
+            .put(block_number, data);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computing_rewards() {
+        let data =
+            BlockRewardsData::new(vec![(30.into(), 100), (10.into(), 300), (20.into(), 600)]);
+        // Sorted data: (10, 300), (20, 600), (30, 100); cumulative gas used: 300, 900, 1000.
+        let rewards = data.rewards(&[0.0, 25.0, 30.0, 30.5, 90.0, 90.5, 100.0]);
+        let expected_rewards: Vec<U256> = [10, 10, 10, 20, 20, 30, 30]
+            .into_iter()
+            .map(U256::from)
+            .collect();
+        assert_eq!(rewards, expected_rewards);
+
+        let empty_rewards = BlockRewardsData::default().rewards(&[10.0, 50.0]);
+        assert_eq!(empty_rewards, [U256::zero(); 2]);
+    }
+
+    #[test]
+    fn validating_reward_percentiles() {
+        validate_reward_percentiles(&[]).unwrap();
+        validate_reward_percentiles(&[0.0, 25.0, 25.0, 100.0]).unwrap();
+
+        for invalid_percentiles in [&[-1.0][..], &[101.0], &[50.0, 10.0], &[f32::NAN]] {
+            let err = validate_reward_percentiles(invalid_percentiles).unwrap_err();
+            assert!(
+                matches!(err, Web3Error::InvalidRewardPercentiles),
+                "{err:?}"
+            );
+        }
+    }
+}
@@ -1,16 +1,25 @@
 use std::{sync::Arc, time::Duration};
 
+use anyhow::Context as _;
 use chrono::NaiveDateTime;
 use tokio::sync::{watch, RwLock};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_state::SequentialCache;
-use zksync_types::H256;
+use zksync_types::{url::SensitiveUrl, H256};
+use zksync_web3_decl::{
+    client::{Client, WsClient, L2},
+    jsonrpsee::{
+        core::client::{Subscription, SubscriptionClientT},
+        rpc_params,
+    },
+};
 
 use super::metrics::MEMPOOL_CACHE_METRICS;
 
 /// Used for `eth_newPendingTransactionFilter` requests on API servers
 /// Stores all transactions accepted by the mempool and provides a way to query all that are newer than a given timestamp.
-/// Updates the cache based on interval passed in the constructor
+/// Updated either by polling Postgres ([`Self::update_task()`]) or by subscribing to pending transactions
+/// on the main node ([`Self::main_node_subscription_task()`]).
 #[derive(Debug, Clone)]
 pub struct MempoolCache(Arc<RwLock<SequentialCache<NaiveDateTime, H256>>>);
 
@@ -38,6 +47,18 @@ impl MempoolCache {
         }
     }
 
+    /// Returns a task that will update this cache in background based on the `newPendingTransactions` WebSocket
+    /// subscription to the main node. Transactions are keyed by the time they were received from the main node.
+    pub fn main_node_subscription_task(
+        &self,
+        main_node_ws_url: SensitiveUrl,
+    ) -> MempoolCacheSubscriptionTask {
+        MempoolCacheSubscriptionTask {
+            cache: self.0.clone(),
+            main_node_ws_url,
+        }
+    }
+
     /// Returns all transaction hashes that are newer than the given timestamp.
     /// Does not include the transactions that are exactly at the given timestamp.
     pub async fn get_tx_hashes_after(
@@ -48,6 +69,13 @@ impl MempoolCache {
     }
 }
 
+#[cfg(test)]
+impl MempoolCache {
+    pub(crate) async fn last_timestamp(&self) -> Option<NaiveDateTime> {
+        self.0.read().await.get_last_key()
+    }
+}
+
 /// Task updating [`MempoolCache`]. Should be spawned as a Tokio task (exactly one task for the cache).
 #[derive(Debug)]
 pub struct MempoolCacheUpdateTask {
@@ -88,3 +116,101 @@ impl MempoolCacheUpdateTask {
         }
     }
 }
+
+/// Task updating [`MempoolCache`] based on a WebSocket subscription to the main node. Should be spawned as a Tokio task
+/// (exactly one task for the cache).
+#[derive(Debug)]
+pub struct MempoolCacheSubscriptionTask {
+    cache: Arc<RwLock<SequentialCache<NaiveDateTime, H256>>>,
+    main_node_ws_url: SensitiveUrl,
+}
+
+impl MempoolCacheSubscriptionTask {
+    const RECONNECT_INTERVAL: Duration = Duration::from_secs(1);
+
+    async fn subscribe(&self) -> anyhow::Result<(WsClient<L2>, Subscription<H256>)> {
+        let client = Client::ws(self.main_node_ws_url.clone())
+            .await
+            .context("failed connecting to main node WS API")?
+            .build();
+        let subscription = client
+            .subscribe::<H256, _>(
+                "eth_subscribe",
+                rpc_params!["newPendingTransactions"],
+                "eth_unsubscribe",
+            )
+            .await
+            .context("failed subscribing to pending transactions on main node")?;
+        Ok((client, subscription))
+    }
+
+    async fn insert_tx_hash(&self, tx_hash: H256) -> anyhow::Result<()> {
+        let mut cache = self.cache.write().await;
+        let mut timestamp = chrono::Utc::now().naive_utc();
+        // Keys must be strictly increasing; otherwise, clients polling the cache could miss transactions
+        // received at the same timestamp.
+        if let Some(last_timestamp) = cache.get_last_key() {
+            timestamp = timestamp.max(last_timestamp + chrono::Duration::microseconds(1));
+        }
+        cache.insert(vec![(timestamp, tx_hash)])
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let mut is_first_connection = true;
+        while !*stop_receiver.borrow() {
+            let (_client, mut subscription) = match self.subscribe().await {
+                Ok(subscription) => subscription,
+                Err(err) => {
+                    tracing::warn!("Failed subscribing to main node pending transactions: {err:#}");
+                    MEMPOOL_CACHE_METRICS.subscription_errors.inc();
+                    if tokio::time::timeout(Self::RECONNECT_INTERVAL, stop_receiver.changed())
+                        .await
+                        .is_ok()
+                    {
+                        break;
+                    }
+                    continue;
+                }
+            };
+            if !is_first_connection {
+                // Transactions received by the main node while we were disconnected are missing from the cache.
+                // Clear the cache so that clients fall back to querying Postgres instead of silently missing them.
+                tracing::info!(
+                    "Resubscribed to main node pending transactions; clearing mempool cache"
+                );
+                self.cache.write().await.clear();
+            }
+            is_first_connection = false;
+
+            loop {
+                let tx_hash = tokio::select! {
+                    tx_hash = subscription.next() => tx_hash,
+                    _ = stop_receiver.changed() => {
+                        tracing::debug!("Stopping mempool cache updates");
+                        return Ok(());
+                    }
+                };
+                match tx_hash {
+                    Some(Ok(tx_hash)) => {
+                        MEMPOOL_CACHE_METRICS.subscription_tx_hashes.inc();
+                        self.insert_tx_hash(tx_hash).await?;
+                    }
+                    Some(Err(err)) => {
+                        tracing::warn!(
+                            "Failed parsing pending transaction notification from main node: {err}"
+                        );
+                    }
+                    None => {
+                        tracing::warn!(
+                            "Main node pending transactions subscription was closed; resubscribing"
+                        );
+                        MEMPOOL_CACHE_METRICS.subscription_errors.inc();
+                        break;
+                    }
+                }
+            }
+        }
+        tracing::debug!("Stopping mempool cache updates");
+        Ok(())
+    }
+}
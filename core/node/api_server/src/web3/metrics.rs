@@ -6,6 +6,7 @@ use vise::{
     Buckets, Counter, DurationAsSecs, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram,
     Info, LabeledFamily, Metrics, Unit,
 };
+use zksync_error_codes::HasErrorCode;
 use zksync_types::api;
 use zksync_web3_decl::error::Web3Error;
 
@@ -168,7 +169,13 @@ enum Web3ErrorKind {
     FilterNotFound,
     LogsLimitExceeded,
     InvalidFilterBlockHash,
+    InvalidRewardPercentiles,
+    TooManyTransactions,
+    InvalidStateOverride,
+    UnknownTracer,
+    TooManyStorageKeys,
     TreeApiUnavailable,
+    ServerOverloaded,
     Internal,
 }
 
@@ -184,7 +191,13 @@ impl Web3ErrorKind {
             Web3Error::FilterNotFound => Self::FilterNotFound,
             Web3Error::LogsLimitExceeded(..) => Self::LogsLimitExceeded,
             Web3Error::InvalidFilterBlockHash => Self::InvalidFilterBlockHash,
+            Web3Error::InvalidRewardPercentiles => Self::InvalidRewardPercentiles,
+            Web3Error::TooManyTransactions(_) => Self::TooManyTransactions,
+            Web3Error::InvalidStateOverride(_) => Self::InvalidStateOverride,
+            Web3Error::UnknownTracer(_) => Self::UnknownTracer,
+            Web3Error::TooManyStorageKeys(_) => Self::TooManyStorageKeys,
             Web3Error::TreeApiUnavailable => Self::TreeApiUnavailable,
+            Web3Error::ServerOverloaded => Self::ServerOverloaded,
             Web3Error::InternalError(_) | Web3Error::MethodNotImplemented => Self::Internal,
         }
     }
@@ -220,9 +233,11 @@ struct Web3ConfigLabels {
     subscriptions_limit: Option<usize>,
     #[metrics(unit = Unit::Bytes)]
     batch_request_size_limit: Option<usize>,
+    batch_request_parallelism: Option<usize>,
     #[metrics(unit = Unit::Bytes)]
     response_body_size_limit: Option<usize>,
     websocket_requests_per_minute_limit: Option<u32>,
+    per_ip_requests_per_second_limit: Option<u32>,
 }
 
 /// Roughly exponential buckets for the `web3_call_block_diff` metric. The distribution should be skewed towards lower values.
@@ -251,6 +266,9 @@ pub(crate) struct ApiMetrics {
     /// Serialized response size in bytes. Only recorded for successful responses.
     #[metrics(buckets = RESPONSE_SIZE_BUCKETS, labels = ["method"], unit = Unit::Bytes)]
     web3_call_response_size: LabeledFamily<&'static str, Histogram<usize>>,
+    /// Total time spent on DB queries during a Web3 call.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["method"], unit = Unit::Seconds)]
+    web3_call_db_time: LabeledFamily<&'static str, Histogram<Duration>>,
 
     /// Number of application errors grouped by error kind and method name. Only collected for errors that were successfully routed
     /// to a method (i.e., this method is defined).
@@ -284,6 +302,9 @@ impl ApiMetrics {
             filters_limit: optional.filters_limit,
             subscriptions_limit: optional.subscriptions_limit,
             batch_request_size_limit: optional.batch_request_size_limit,
+            batch_request_parallelism: optional
+                .batch_request_parallelism
+                .map(|(parallelism, _)| parallelism.get()),
             response_body_size_limit: optional
                 .response_body_size_limit
                 .as_ref()
@@ -291,6 +312,9 @@ impl ApiMetrics {
             websocket_requests_per_minute_limit: optional
                 .websocket_requests_per_minute_limit
                 .map(Into::into),
+            per_ip_requests_per_second_limit: optional
+                .per_ip_requests_per_second_limit
+                .map(Into::into),
         };
         tracing::info!("{transport:?} Web3 server is configured with options: {config_labels:?}");
         if self.web3_info[&transport].set(config_labels).is_err() {
@@ -309,6 +333,7 @@ impl ApiMetrics {
 
         let latency = meta.started_at.elapsed();
         self.web3_call[&MethodLabels::from(meta)].observe(latency);
+        self.web3_call_db_time[&meta.name].observe(meta.db_time);
         if let Some(block_diff) = meta.block_diff {
             self.web3_call_block_diff[&meta.name].observe(block_diff.into());
         }
@@ -384,13 +409,21 @@ impl ApiMetrics {
     }
 
     pub(super) fn observe_web3_error(&self, method: &'static str, err: &Web3Error) {
+        let error_code = err.error_code();
+        error_code.observe();
         // Log internal error details.
         match err {
             Web3Error::InternalError(err) => {
-                tracing::error!("Internal error in method `{method}`: {err:#}");
+                tracing::error!(
+                    error_code = error_code.as_str(),
+                    "Internal error in method `{method}`: {err:#}"
+                );
             }
             Web3Error::ProxyError(err) => {
-                tracing::warn!("Error proxying call to main node in method `{method}`: {err}");
+                tracing::warn!(
+                    error_code = error_code.as_str(),
+                    "Error proxying call to main node in method `{method}`: {err}"
+                );
             }
             _ => { /* do nothing */ }
         }
@@ -402,6 +435,7 @@ impl ApiMetrics {
         if self.web3_errors[&labels].inc() == 0 {
             // Only log the first error with the label to not spam logs.
             tracing::info!(
+                error_code = error_code.as_str(),
                 "Observed new error type for method `{}`: {:?}",
                 labels.method,
                 labels.kind
@@ -497,11 +531,42 @@ pub(super) struct MempoolCacheMetrics {
     /// Number of transactions loaded from the DB during the last cache update
     #[metrics(buckets = Buckets::exponential(1.0..=2048.0, 2.0))]
     pub tx_batch_size: Histogram<usize>,
+    /// Number of transaction hashes received from the main node subscription.
+    pub subscription_tx_hashes: Counter,
+    /// Number of errors establishing or maintaining the main node subscription.
+    pub subscription_errors: Counter,
 }
 
 #[vise::register]
 pub(super) static MEMPOOL_CACHE_METRICS: vise::Global<MempoolCacheMetrics> = vise::Global::new();
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(super) enum CachedResponseKind {
+    Block,
+    TransactionReceipt,
+    L1BatchDetails,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_response_cache")]
+pub(super) struct ResponseCacheMetrics {
+    /// Number of requests served from the response cache.
+    pub hits: Family<CachedResponseKind, Counter>,
+    /// Number of requests for which a response was not cached.
+    pub misses: Family<CachedResponseKind, Counter>,
+    /// Number of requests missing the in-memory cache that were served from Redis.
+    pub redis_hits: Family<CachedResponseKind, Counter>,
+    /// Number of errors communicating with Redis or decoding responses cached in it.
+    pub redis_errors: Counter,
+    /// Approximate total size of cached responses.
+    #[metrics(unit = Unit::Bytes)]
+    pub weighted_size: Gauge<u64>,
+}
+
+#[vise::register]
+pub(super) static RESPONSE_CACHE_METRICS: vise::Global<ResponseCacheMetrics> = vise::Global::new();
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
@@ -1,18 +1,28 @@
-use std::{collections::HashSet, net::SocketAddr, num::NonZeroU32, sync::Arc, time::Duration};
+use std::{
+    collections::HashSet,
+    net::{IpAddr, SocketAddr},
+    num::{NonZeroU32, NonZeroUsize},
+    sync::Arc,
+    time::Duration,
+};
 
 use anyhow::Context as _;
 use chrono::NaiveDateTime;
 use futures::future;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use tokio::{
-    sync::{mpsc, oneshot, watch, Mutex},
+    sync::{mpsc, oneshot, watch},
     task::JoinHandle,
 };
-use tower_http::{cors::CorsLayer, metrics::InFlightRequestsLayer};
-use zksync_config::configs::api::{MaxResponseSize, MaxResponseSizeOverrides};
+use tower_http::{
+    cors::{AllowHeaders, AllowOrigin, CorsLayer},
+    metrics::InFlightRequestsLayer,
+};
+use zksync_config::configs::api::{MaxResponseSize, MaxResponseSizeOverrides, MethodRateLimits};
 use zksync_dal::{helpers::wait_for_l1_batch, ConnectionPool, Core};
 use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_metadata_calculator::api_server::TreeApiClient;
+use zksync_node_block_data_archive::BlockDataArchiveReader;
 use zksync_node_sync::SyncState;
 use zksync_types::L2BlockNumber;
 use zksync_web3_decl::{
@@ -31,9 +41,11 @@ use zksync_web3_decl::{
 
 use self::{
     backend_jsonrpsee::{
-        CorrelationMiddleware, LimitMiddleware, MetadataLayer, MethodTracer, ShutdownMiddleware,
-        TrafficTracker,
+        serve, AllowedOriginsLayer, CorrelationMiddleware, IpRateLimitLayer, LimitMiddleware,
+        MetadataLayer, MethodRateLimitMiddleware, MethodRateLimiters, MethodTracer,
+        ParallelBatchLayer, ShutdownMiddleware, TrafficTracker,
     },
+    fee_history::FeeHistoryCache,
     mempool_cache::MempoolCache,
     metrics::API_METRICS,
     namespaces::{
@@ -41,18 +53,26 @@ use self::{
         ZksNamespace,
     },
     pubsub::{EthSubscribe, EthSubscriptionIdProvider, PubSubEvent},
-    state::{Filters, InternalApiConfig, RpcState, SealedL2BlockNumber},
+    response_cache::ResponseCache,
+    slow_calls::SlowCallsSampler,
+    state::{
+        prune_expired_filters, InstalledFilters, InternalApiConfig, RpcState, SealedL2BlockNumber,
+        UpdatableApiConfig,
+    },
 };
 use crate::{
-    execution_sandbox::{BlockStartInfo, VmConcurrencyBarrier},
+    execution_sandbox::{ApiTracerRegistry, BlockStartInfo, VmConcurrencyBarrier},
     tx_sender::TxSender,
 };
 
 pub mod backend_jsonrpsee;
+mod fee_history;
 pub mod mempool_cache;
 pub(super) mod metrics;
 pub mod namespaces;
 mod pubsub;
+pub mod response_cache;
+pub mod slow_calls;
 pub mod state;
 pub mod testonly;
 #[cfg(test)]
@@ -68,9 +88,12 @@ const NO_REQUESTS_WAIT_TIMEOUT: Duration = Duration::from_secs(30);
 /// Time interval with no requests sent to the API server to declare that traffic to the server is ceased,
 /// and start gracefully shutting down the server.
 const SHUTDOWN_INTERVAL_WITHOUT_REQUESTS: Duration = Duration::from_millis(500);
+/// Interval between removing stale entries from the per-IP rate limiter.
+const IP_RATE_LIMITER_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
 
 /// Represents all kinds of `Filter`.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
 pub(crate) enum TypedFilter {
     // Events from some block with additional filters
     Events(Filter, L2BlockNumber),
@@ -80,6 +103,148 @@ pub(crate) enum TypedFilter {
     PendingTransactions(NaiveDateTime),
 }
 
+/// Storage for filters installed via `eth_newFilter`, `eth_newBlockFilter` and `eth_newPendingTransactionFilter`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum FiltersStorage {
+    /// Filters are stored in memory of each API server. They are lost on server restart and are not shared
+    /// among API servers, so they don't work correctly behind a load balancer.
+    #[default]
+    Memory,
+    /// Filters are stored in Postgres and are shared among all API servers using the same database.
+    /// Filters that weren't polled for longer than `ttl` expire.
+    Postgres { ttl: Duration },
+}
+
+/// CORS configuration for the API servers.
+///
+/// For the HTTP server, this configures CORS response headers; browsers enforce the policy on their side. Since browsers
+/// don't apply CORS to WebSocket connections, the WS server rejects handshakes with an `Origin` not in the allowed list.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CorsConfig {
+    /// Allowed origins, e.g. `https://app.example.com`. If not set or contains `*`, requests from any origin are allowed.
+    pub allowed_origins: Option<Vec<String>>,
+    /// Request headers allowed in addition to `Content-Type`.
+    pub allowed_headers: Vec<String>,
+    /// Time during which browsers can cache results of preflight requests.
+    pub max_age: Option<Duration>,
+}
+
+impl CorsConfig {
+    /// Parses allowed origins. Returns `None` if any origin is allowed.
+    fn parse_allowed_origins(&self) -> anyhow::Result<Option<Arc<[http::HeaderValue]>>> {
+        let Some(origins) = &self.allowed_origins else {
+            return Ok(None);
+        };
+        if origins.iter().any(|origin| origin == "*") {
+            return Ok(None);
+        }
+        let origins = origins
+            .iter()
+            .map(|origin| {
+                http::HeaderValue::from_str(origin)
+                    .with_context(|| format!("invalid allowed CORS origin: {origin:?}"))
+            })
+            .collect::<anyhow::Result<_>>()?;
+        Ok(Some(origins))
+    }
+
+    fn http_layer(
+        &self,
+        allowed_origins: Option<&[http::HeaderValue]>,
+    ) -> anyhow::Result<CorsLayer> {
+        let allowed_headers = self
+            .allowed_headers
+            .iter()
+            .map(|header| {
+                http::HeaderName::from_bytes(header.as_bytes())
+                    .with_context(|| format!("invalid allowed CORS header: {header:?}"))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let layer = CorsLayer::new()
+            // Allow `POST` when accessing the resource
+            .allow_methods([http::Method::POST])
+            .allow_headers(AllowHeaders::list(
+                [http::header::CONTENT_TYPE]
+                    .into_iter()
+                    .chain(allowed_headers),
+            ));
+        let layer = if let Some(origins) = allowed_origins {
+            layer.allow_origin(AllowOrigin::list(origins.iter().cloned()))
+        } else {
+            layer.allow_origin(AllowOrigin::any())
+        };
+        Ok(if let Some(max_age) = self.max_age {
+            layer.max_age(max_age)
+        } else {
+            layer
+        })
+    }
+}
+
+/// Fine-grained filter for RPC methods exposed by the API servers. Applied on top of enabled [`Namespace`]s;
+/// filtered methods are not registered on the server, so calling them results in a "method not found" error.
+///
+/// Each pattern is either a full method name (e.g., `eth_getLogs`), or a method name prefix followed by `*`
+/// (e.g., `debug_trace*`).
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MethodFilter {
+    /// Allowlist of methods. If set, only methods matching at least one of the patterns are exposed.
+    pub enabled: Option<Vec<String>>,
+    /// Denylist of methods. Methods matching any of the patterns are not exposed, even if they are allowlisted.
+    pub disabled: Vec<String>,
+}
+
+impl MethodFilter {
+    fn validate(&self) -> anyhow::Result<()> {
+        let enabled = self.enabled.iter().flatten();
+        for pattern in enabled.chain(&self.disabled) {
+            anyhow::ensure!(!pattern.is_empty(), "empty RPC method pattern");
+            let name = pattern.strip_suffix('*').unwrap_or(pattern);
+            anyhow::ensure!(
+                !name.contains('*'),
+                "invalid RPC method pattern `{pattern}`: `*` is only allowed at the end of the pattern"
+            );
+        }
+        Ok(())
+    }
+
+    fn matches(pattern: &str, method_name: &str) -> bool {
+        match pattern.strip_suffix('*') {
+            Some(prefix) => method_name.starts_with(prefix),
+            None => method_name == pattern,
+        }
+    }
+
+    fn is_enabled(&self, method_name: &str) -> bool {
+        let is_allowed = self.enabled.as_ref().map_or(true, |patterns| {
+            patterns
+                .iter()
+                .any(|pattern| Self::matches(pattern, method_name))
+        });
+        is_allowed
+            && !self
+                .disabled
+                .iter()
+                .any(|pattern| Self::matches(pattern, method_name))
+    }
+
+    /// Removes filtered methods from the provided RPC module.
+    fn apply(&self, rpc: &mut RpcModule<()>) {
+        let filtered_methods: Vec<_> = rpc
+            .method_names()
+            .filter(|&method_name| !self.is_enabled(method_name))
+            .collect();
+        if filtered_methods.is_empty() {
+            return;
+        }
+        tracing::info!("Disabling RPC methods according to filter: {filtered_methods:?}");
+        for method_name in filtered_methods {
+            rpc.remove_method(method_name);
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy)]
 enum ApiTransport {
     WebSocket(SocketAddr),
@@ -125,13 +290,24 @@ struct OptionalApiParams {
     vm_barrier: Option<VmConcurrencyBarrier>,
     sync_state: Option<SyncState>,
     filters_limit: Option<usize>,
+    filters_storage: FiltersStorage,
     subscriptions_limit: Option<usize>,
     batch_request_size_limit: Option<usize>,
+    batch_request_parallelism: Option<(NonZeroUsize, Duration)>,
     response_body_size_limit: Option<MaxResponseSize>,
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
+    method_rate_limits: MethodRateLimits,
+    method_filter: MethodFilter,
+    per_ip_requests_per_second_limit: Option<NonZeroU32>,
+    trusted_proxies: HashSet<IpAddr>,
     tree_api: Option<Arc<dyn TreeApiClient>>,
+    block_data_archive: Option<Arc<BlockDataArchiveReader>>,
     mempool_cache: Option<MempoolCache>,
+    response_cache: Option<ResponseCache>,
+    tracer_registry: ApiTracerRegistry,
+    updatable_config: Option<UpdatableApiConfig>,
     extended_tracing: bool,
+    cors: CorsConfig,
     pub_sub_events_sender: Option<mpsc::UnboundedSender<PubSubEvent>>,
 }
 
@@ -222,6 +398,12 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets storage for installed filters. By default, filters are stored in memory.
+    pub fn with_filters_storage(mut self, storage: FiltersStorage) -> Self {
+        self.optional.filters_storage = storage;
+        self
+    }
+
     pub fn with_subscriptions_limit(mut self, subscriptions_limit: usize) -> Self {
         self.optional.subscriptions_limit = Some(subscriptions_limit);
         self
@@ -232,6 +414,19 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables concurrent execution of calls in batch requests for the HTTP server. At most `parallelism` calls
+    /// from a single batch are executed at the same time, and all calls must complete within `time_budget`;
+    /// calls that don't complete in time receive an error response. Batches in WebSocket sessions are always
+    /// executed sequentially.
+    pub fn with_batch_request_parallelism(
+        mut self,
+        parallelism: NonZeroUsize,
+        time_budget: Duration,
+    ) -> Self {
+        self.optional.batch_request_parallelism = Some((parallelism, time_budget));
+        self
+    }
+
     pub fn with_response_body_size_limit(mut self, max_response_size: MaxResponseSize) -> Self {
         self.optional.response_body_size_limit = Some(max_response_size);
         self
@@ -246,6 +441,38 @@ impl ApiBuilder {
         self
     }
 
+    /// Sets rate limits (in requests per second) for specific RPC methods. Limits apply to the server as a whole
+    /// (i.e., are shared among all clients).
+    pub fn with_method_rate_limits(mut self, limits: MethodRateLimits) -> Self {
+        self.optional.method_rate_limits = limits;
+        self
+    }
+
+    /// Sets a filter for exposed RPC methods. The filter is applied on top of [enabled namespaces](Self::enable_api_namespaces()).
+    pub fn with_method_filter(mut self, filter: MethodFilter) -> Self {
+        self.optional.method_filter = filter;
+        self
+    }
+
+    /// Sets the per-IP limit on the number of HTTP requests per second. Client IPs are the remote addresses
+    /// of connections, unless a connection originates from a [trusted proxy](Self::with_trusted_proxies).
+    pub fn with_per_ip_requests_per_second_limit(mut self, limit: NonZeroU32) -> Self {
+        self.optional.per_ip_requests_per_second_limit = Some(limit);
+        self
+    }
+
+    /// Sets IPs of trusted reverse proxies. For connections from these IPs, client IPs used for per-IP rate limiting
+    /// are determined from the `X-Forwarded-For` / `X-Real-IP` headers.
+    pub fn with_trusted_proxies(mut self, proxies: impl IntoIterator<Item = IpAddr>) -> Self {
+        self.optional.trusted_proxies = proxies.into_iter().collect();
+        self
+    }
+
+    pub fn with_cors(mut self, cors: CorsConfig) -> Self {
+        self.optional.cors = cors;
+        self
+    }
+
     pub fn with_sync_state(mut self, sync_state: SyncState) -> Self {
         self.optional.sync_state = Some(sync_state);
         self
@@ -272,11 +499,39 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables serving pruned L2 blocks, transactions and receipts from the block data archive.
+    pub fn with_block_data_archive(mut self, archive: Arc<BlockDataArchiveReader>) -> Self {
+        tracing::info!("Using block data archive for pruned data: {archive:?}");
+        self.optional.block_data_archive = Some(archive);
+        self
+    }
+
     pub fn with_mempool_cache(mut self, cache: MempoolCache) -> Self {
         self.optional.mempool_cache = Some(cache);
         self
     }
 
+    /// Sets a cache for responses that don't change once the corresponding block is final. The cache can be shared
+    /// among several servers.
+    pub fn with_response_cache(mut self, cache: ResponseCache) -> Self {
+        self.optional.response_cache = Some(cache);
+        self
+    }
+
+    /// Sets a registry of custom tracers that can be selected in `debug_trace*` methods. Tracers may be registered
+    /// after the server is built.
+    pub fn with_tracer_registry(mut self, registry: ApiTracerRegistry) -> Self {
+        self.optional.tracer_registry = registry;
+        self
+    }
+
+    /// Sets a handle allowing to update bridge and paymaster addresses while the server is running.
+    /// If not set, the values from the [`InternalApiConfig`] provided to the builder are used.
+    pub fn with_updatable_config(mut self, config: UpdatableApiConfig) -> Self {
+        self.optional.updatable_config = Some(config);
+        self
+    }
+
     pub fn with_extended_tracing(mut self, extended_tracing: bool) -> Self {
         self.optional.extended_tracing = extended_tracing;
         self
@@ -289,6 +544,14 @@ impl ApiBuilder {
         self
     }
 
+    /// Enables sampling of slow calls into the provided sampler, which can be shared among servers. The sampler
+    /// is not exposed via the JSON-RPC API; it should be queried via a private admin endpoint.
+    /// Note that this requires copying params for each call.
+    pub fn with_slow_calls_sampler(mut self, sampler: Arc<SlowCallsSampler>) -> Self {
+        self.method_tracer = Arc::new(MethodTracer::with_slow_calls_sampler(sampler));
+        self
+    }
+
     // Intended for tests only.
     #[doc(hidden)]
     fn with_method_tracer(mut self, method_tracer: Arc<MethodTracer>) -> Self {
@@ -305,6 +568,10 @@ impl ApiBuilder {
             ApiTransport::WebSocket(_) => "ws_api",
         };
         let (_, health_updater) = ReactiveHealthCheck::new(health_check_name);
+        self.optional
+            .method_filter
+            .validate()
+            .context("invalid RPC method filter")?;
 
         Ok(ApiServer {
             pool: self.pool,
@@ -346,11 +613,23 @@ impl ApiServer {
             if matches!(self.transport, ApiTransport::Http(_)) && self.config.filters_disabled {
                 None
             } else {
-                Some(Arc::new(Mutex::new(Filters::new(
+                Some(InstalledFilters::new(
+                    self.optional.filters_storage,
                     self.optional.filters_limit,
-                ))))
+                    self.pool.clone(),
+                ))
             };
 
+        let fee_history_cache_capacity = usize::try_from(self.config.fee_history_limit)
+            .ok()
+            .and_then(NonZeroUsize::new)
+            .unwrap_or(NonZeroUsize::MIN);
+        let fee_history_cache = FeeHistoryCache::new(fee_history_cache_capacity);
+        let updatable_config = self
+            .optional
+            .updatable_config
+            .unwrap_or_else(|| UpdatableApiConfig::new((&self.config).into()));
+
         Ok(RpcState {
             current_method: self.method_tracer,
             installed_filters,
@@ -358,10 +637,15 @@ impl ApiServer {
             tx_sender: self.tx_sender,
             sync_state: self.optional.sync_state,
             api_config: self.config,
+            updatable_config,
             start_info,
             mempool_cache: self.optional.mempool_cache,
+            response_cache: self.optional.response_cache,
+            tracer_registry: self.optional.tracer_registry,
+            fee_history_cache,
             last_sealed_l2_block,
             tree_api: self.optional.tree_api,
+            block_data_archive: self.optional.block_data_archive,
         })
     }
 
@@ -371,6 +655,7 @@ impl ApiServer {
         last_sealed_l2_block: SealedL2BlockNumber,
     ) -> anyhow::Result<RpcModule<()>> {
         let namespaces = self.namespaces.clone();
+        let method_filter = self.optional.method_filter.clone();
         let zksync_network_id = self.config.l2_chain_id;
         let rpc_state = self.build_rpc_state(last_sealed_l2_block).await?;
 
@@ -409,6 +694,7 @@ impl ApiServer {
             rpc.merge(SnapshotsNamespace::new(rpc_state).into_rpc())
                 .context("cannot merge snapshots namespace")?;
         }
+        method_filter.apply(&mut rpc);
         Ok(rpc)
     }
 
@@ -422,6 +708,15 @@ impl ApiServer {
                     "Filters limit is not supported when filters are disabled, ignoring"
                 );
             }
+        } else if matches!(
+            self.optional.filters_storage,
+            FiltersStorage::Postgres { .. }
+        ) {
+            if self.optional.filters_limit.is_some() {
+                tracing::warn!(
+                    "Filters limit is not supported for filters stored in Postgres, ignoring"
+                );
+            }
         } else if self.optional.filters_limit.is_none() {
             tracing::warn!("Filters limit is not set - unlimited filters are allowed");
         }
@@ -481,6 +776,15 @@ impl ApiServer {
         );
 
         let mut tasks = vec![tokio::spawn(sealed_l2_block_update_task)];
+        if let FiltersStorage::Postgres { ttl } = self.optional.filters_storage {
+            if !self.config.filters_disabled || matches!(transport, ApiTransport::WebSocket(_)) {
+                tasks.push(tokio::spawn(prune_expired_filters(
+                    self.updaters_pool.clone(),
+                    ttl,
+                    stop_receiver.clone(),
+                )));
+            }
+        }
         let pub_sub = if matches!(transport, ApiTransport::WebSocket(_))
             && self.namespaces.contains(&Namespace::Pubsub)
         {
@@ -623,10 +927,16 @@ impl ApiServer {
                 (u32::MAX, MaxResponseSizeOverrides::empty())
             };
         let websocket_requests_per_minute_limit = self.optional.websocket_requests_per_minute_limit;
+        let method_rate_limits = self.optional.method_rate_limits.clone();
+        let per_ip_requests_per_second_limit = self.optional.per_ip_requests_per_second_limit;
+        let trusted_proxies = self.optional.trusted_proxies.clone();
+        let batch_request_parallelism = self.optional.batch_request_parallelism;
+        let batch_request_size_limit = self.optional.batch_request_size_limit;
         let subscriptions_limit = self.optional.subscriptions_limit;
         let vm_barrier = self.optional.vm_barrier.clone();
         let health_updater = self.health_updater.clone();
         let method_tracer = self.method_tracer.clone();
+        let cors_config = self.optional.cors.clone();
 
         let extended_tracing = self.optional.extended_tracing;
         if extended_tracing {
@@ -640,16 +950,23 @@ impl ApiServer {
             registered_method_names.len()
         );
         let rpc = Self::override_method_response_sizes(rpc, &max_response_size_overrides)?;
-
-        // Setup CORS.
-        let cors = is_http.then(|| {
-            CorsLayer::new()
-                // Allow `POST` when accessing the resource
-                .allow_methods([http::Method::POST])
-                // Allow requests from any origin
-                .allow_origin(tower_http::cors::Any)
-                .allow_headers([http::header::CONTENT_TYPE])
-        });
+        let method_rate_limiters =
+            MethodRateLimiters::new(&method_rate_limits, &registered_method_names);
+        let method_rate_limiters =
+            (!method_rate_limiters.is_empty()).then(|| Arc::new(method_rate_limiters));
+
+        // Setup CORS for the HTTP server and origin checks for WebSocket handshakes.
+        let allowed_origins = cors_config.parse_allowed_origins()?;
+        let cors = if is_http {
+            Some(cors_config.http_layer(allowed_origins.as_deref())?)
+        } else {
+            None
+        };
+        let ws_origin_filter = if is_http {
+            None
+        } else {
+            allowed_origins.map(AllowedOriginsLayer::new)
+        };
         // Setup metrics for the number of in-flight requests.
         let (in_flight_requests, counter) = InFlightRequestsLayer::pair();
         tokio::spawn(
@@ -658,10 +975,38 @@ impl ApiServer {
                 future::ready(())
             }),
         );
+        // Setup per-IP rate limiting.
+        let ip_rate_limit = per_ip_requests_per_second_limit.map(|limit| {
+            tracing::info!(
+                "Limiting {transport_str} API server to {limit} requests per second per client IP"
+            );
+            let layer = IpRateLimitLayer::new(limit, trusted_proxies);
+            tokio::spawn(layer.run_cleanup(IP_RATE_LIMITER_CLEANUP_INTERVAL));
+            layer
+        });
+        // Setup concurrent execution of batch requests. WebSocket sessions are not affected by HTTP middleware,
+        // so this is only relevant for the HTTP server.
+        let parallel_batches = batch_request_parallelism
+            .filter(|_| is_http)
+            .map(|(parallelism, time_budget)| {
+                tracing::info!(
+                    "Executing up to {parallelism} calls in batch requests concurrently with time budget {time_budget:?} \
+                     for {transport_str} API server"
+                );
+                ParallelBatchLayer::new(
+                    parallelism,
+                    time_budget,
+                    batch_request_size_limit,
+                    response_body_size_limit as usize,
+                )
+            });
         // Assemble server middleware.
         let middleware = tower::ServiceBuilder::new()
             .layer(in_flight_requests)
-            .option_layer(cors);
+            .option_layer(ip_rate_limit)
+            .option_layer(cors)
+            .option_layer(ws_origin_filter)
+            .option_layer(parallel_batches);
 
         // Settings shared by HTTP and WS servers.
         let max_connections = !is_http
@@ -694,6 +1039,11 @@ impl ApiServer {
                 tower::layer::layer_fn(move |svc| {
                     LimitMiddleware::new(svc, websocket_requests_per_minute_limit)
                 })
+            }))
+            .option_layer(method_rate_limiters.map(|limiters| {
+                tower::layer::layer_fn(move |svc| {
+                    MethodRateLimitMiddleware::new(svc, limiters.clone())
+                })
             }));
 
         let server_builder = ServerBuilder::default()
@@ -703,26 +1053,32 @@ impl ApiServer {
             .set_batch_request_config(batch_request_config)
             .set_rpc_middleware(rpc_middleware);
 
-        let (local_addr, server_handle) = if is_http {
+        let server_builder = if is_http {
             // HTTP-specific settings
-            let server = server_builder
-                .http_only()
-                .build(addr)
-                .await
-                .context("Failed building HTTP JSON-RPC server")?;
-            (server.local_addr(), server.start(rpc))
+            server_builder.http_only()
         } else {
             // WS-specific settings
+            server_builder.set_id_provider(EthSubscriptionIdProvider)
+        };
+        let (local_addr, server_handle) = if per_ip_requests_per_second_limit.is_some() {
+            // Per-IP rate limiting requires remote addresses of connections, which `Server::start()` doesn't expose.
+            serve(
+                server_builder.to_service_builder(),
+                addr,
+                max_connections,
+                rpc,
+            )
+            .with_context(|| format!("Failed building {transport_str} JSON-RPC server"))?
+        } else {
             let server = server_builder
-                .set_id_provider(EthSubscriptionIdProvider)
                 .build(addr)
                 .await
-                .context("Failed building WS JSON-RPC server")?;
-            (server.local_addr(), server.start(rpc))
+                .with_context(|| format!("Failed building {transport_str} JSON-RPC server"))?;
+            let local_addr = server.local_addr().with_context(|| {
+                format!("Failed getting local address for {transport_str} JSON-RPC server")
+            })?;
+            (local_addr, server.start(rpc))
         };
-        let local_addr = local_addr.with_context(|| {
-            format!("Failed getting local address for {transport_str} JSON-RPC server")
-        })?;
         tracing::info!("Initialized {transport_str} API on {local_addr:?}");
         local_addr_sender.send(local_addr).ok();
         health_updater.update(HealthStatus::Ready.into());
@@ -1,27 +1,137 @@
 use std::sync::Arc;
 
 use anyhow::Context as _;
-use multivm::{interface::ExecutionResult, vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT};
+use multivm::{
+    interface::{ExecutionResult, VmExecutionResultAndLogs},
+    vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT,
+};
 use once_cell::sync::OnceCell;
 use zksync_dal::{CoreDal, DalError};
 use zksync_system_constants::MAX_ENCODED_TX_SIZE;
 use zksync_types::{
-    api::{BlockId, BlockNumber, DebugCall, ResultDebugCall, TracerConfig},
+    api::{
+        BlockId, BlockNumber, DebugCall, DebugStructLogs, DebugTrace, ResultDebugCall,
+        ResultDebugTrace, SupportedTracers, TracerConfig,
+    },
     debug_flat_call::{flatten_debug_calls, DebugCallFlat},
     fee_model::BatchFeeInput,
     l2::L2Tx,
     transaction_request::CallRequest,
-    vm_trace::Call,
-    AccountTreeId, H256,
+    vm_trace::{Call, StructLogs},
+    AccountTreeId, L2BlockNumber, Transaction, H256, U256,
 };
 use zksync_web3_decl::error::Web3Error;
 
 use crate::{
-    execution_sandbox::{ApiTracer, TxSharedArgs},
+    execution_sandbox::{ApiTracer, ApiTracerRegistry, TxSharedArgs},
     tx_sender::{ApiContracts, TxSenderConfig},
     web3::{backend_jsonrpsee::MethodTracer, state::RpcState},
 };
 
+/// Maximum number of VM steps recorded by the struct log tracer for a single transaction.
+const MAX_STRUCT_LOG_STEPS: usize = 100_000;
+
+/// Output of a tracer used when re-executing a transaction.
+#[derive(Debug)]
+pub(super) enum TraceOutput {
+    /// `None` if calls are not traced.
+    Calls(Option<Arc<OnceCell<Vec<Call>>>>),
+    StructLogs(Arc<OnceCell<StructLogs>>),
+    Custom(Arc<OnceCell<serde_json::Value>>),
+}
+
+impl TraceOutput {
+    /// Creates VM tracers for the specified tracer kind together with the output populated by them.
+    /// Custom tracers are looked up in the provided `registry`.
+    pub(super) fn new(
+        tracer: &SupportedTracers,
+        only_top_call: bool,
+        registry: &ApiTracerRegistry,
+    ) -> Result<(Vec<ApiTracer>, Self), Web3Error> {
+        Ok(match tracer {
+            // We don't need to trace calls if we only need the top call.
+            SupportedTracers::CallTracer if only_top_call => (vec![], Self::Calls(None)),
+            SupportedTracers::CallTracer => {
+                let result = Arc::new(OnceCell::default());
+                let tracer = ApiTracer::CallTracer(result.clone());
+                (vec![tracer], Self::Calls(Some(result)))
+            }
+            SupportedTracers::StructLogTracer => {
+                let result = Arc::new(OnceCell::default());
+                let tracer = ApiTracer::StructLogTracer {
+                    max_steps: MAX_STRUCT_LOG_STEPS,
+                    result: result.clone(),
+                };
+                (vec![tracer], Self::StructLogs(result))
+            }
+            SupportedTracers::Custom(name) => {
+                let factory = registry
+                    .get(name)
+                    .ok_or_else(|| Web3Error::UnknownTracer(name.clone()))?;
+                let output = Arc::new(OnceCell::default());
+                let tracer = ApiTracer::Custom {
+                    factory,
+                    output: output.clone(),
+                };
+                (vec![tracer], Self::Custom(output))
+            }
+        })
+    }
+
+    /// Converts this output into a trace. Must be called after the transaction is executed.
+    pub(super) fn into_debug_trace(
+        self,
+        result: VmExecutionResultAndLogs,
+        gas_limit: U256,
+        value: U256,
+        calldata: Vec<u8>,
+        only_top_call: bool,
+    ) -> DebugTrace {
+        let (output_bytes, revert_reason) = match result.result {
+            ExecutionResult::Success { output } => (output, None),
+            ExecutionResult::Revert { output } => (vec![], Some(output.to_string())),
+            ExecutionResult::Halt { reason } => (vec![], Some(reason.to_string())),
+        };
+        match self {
+            Self::Calls(calls) => {
+                let calls = calls
+                    .and_then(|calls| Arc::try_unwrap(calls).ok()?.into_inner())
+                    .unwrap_or_default();
+                let call = Call::new_high_level(
+                    gas_limit.as_u64(),
+                    result.statistics.gas_used,
+                    value,
+                    calldata,
+                    output_bytes,
+                    revert_reason,
+                    calls,
+                );
+                DebugTrace::Call(DebugNamespace::debug_call(call, only_top_call))
+            }
+            Self::StructLogs(logs) => {
+                let logs = Arc::try_unwrap(logs)
+                    .ok()
+                    .and_then(OnceCell::into_inner)
+                    .unwrap_or_default();
+                DebugTrace::StructLogs(DebugStructLogs {
+                    gas: U256::from(result.statistics.gas_used),
+                    failed: revert_reason.is_some(),
+                    return_value: output_bytes.into(),
+                    struct_logs: logs.logs,
+                    truncated: logs.truncated,
+                })
+            }
+            Self::Custom(output) => {
+                let output = Arc::try_unwrap(output)
+                    .ok()
+                    .and_then(OnceCell::into_inner)
+                    .unwrap_or_default();
+                DebugTrace::Custom(output)
+            }
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct DebugNamespace {
     batch_fee_input: BatchFeeInput,
@@ -61,33 +171,45 @@ impl DebugNamespace {
         &self,
         block_id: BlockId,
         options: Option<TracerConfig>,
-    ) -> Result<Vec<ResultDebugCall>, Web3Error> {
+    ) -> Result<Vec<ResultDebugTrace>, Web3Error> {
         self.current_method().set_block_id(block_id);
 
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+        let (tracer, only_top_call) = Self::tracer_params(options);
         let mut connection = self.state.acquire_connection().await?;
         let block_number = self.state.resolve_block(&mut connection, block_id).await?;
         self.current_method()
             .set_block_diff(self.state.last_sealed_l2_block.diff(block_number));
 
-        let call_traces = connection
-            .blocks_web3_dal()
-            .get_traces_for_l2_block(block_number)
+        if matches!(tracer, SupportedTracers::CallTracer) {
+            let call_traces = connection
+                .blocks_web3_dal()
+                .get_traces_for_l2_block(block_number)
+                .await
+                .map_err(DalError::generalize)?;
+            // Call traces are not persisted if the node didn't save them during sync (e.g., if the `debug` namespace
+            // was disabled at that time); in this case, we re-execute block transactions.
+            if !call_traces.is_empty() || block_number == L2BlockNumber(0) {
+                let call_traces = call_traces.into_iter().map(|call_trace| {
+                    let result = DebugTrace::Call(Self::debug_call(call_trace, only_top_call));
+                    ResultDebugTrace { result }
+                });
+                return Ok(call_traces.collect());
+            }
+        }
+
+        let txs = connection
+            .transactions_web3_dal()
+            .get_raw_l2_block_transactions(block_number)
             .await
             .map_err(DalError::generalize)?;
-        let call_trace = call_traces
+        drop(connection);
+        let traces = self
+            .replay_l2_block_txs(block_id, txs, 0, &tracer, only_top_call)
+            .await?;
+        Ok(traces
             .into_iter()
-            .map(|call_trace| {
-                let mut result: DebugCall = call_trace.into();
-                if only_top_call {
-                    result.calls = vec![];
-                }
-                ResultDebugCall { result }
-            })
-            .collect();
-        Ok(call_trace)
+            .map(|result| ResultDebugTrace { result })
+            .collect())
     }
 
     pub async fn debug_trace_block_flat_impl(
@@ -95,8 +217,19 @@ impl DebugNamespace {
         block_id: BlockId,
         options: Option<TracerConfig>,
     ) -> Result<Vec<DebugCallFlat>, Web3Error> {
-        let call_trace = self.debug_trace_block_impl(block_id, options).await?;
-        let call_trace_flat = flatten_debug_calls(call_trace);
+        // The flat output is only defined for call traces.
+        let options = TracerConfig {
+            tracer: SupportedTracers::CallTracer,
+            tracer_config: options
+                .map(|options| options.tracer_config)
+                .unwrap_or_default(),
+        };
+        let traces = self.debug_trace_block_impl(block_id, Some(options)).await?;
+        let call_trace = traces.into_iter().filter_map(|trace| match trace.result {
+            DebugTrace::Call(result) => Some(ResultDebugCall { result }),
+            DebugTrace::StructLogs(_) | DebugTrace::Custom(_) => None,
+        });
+        let call_trace_flat = flatten_debug_calls(call_trace.collect());
         Ok(call_trace_flat)
     }
 
@@ -104,23 +237,139 @@ impl DebugNamespace {
         &self,
         tx_hash: H256,
         options: Option<TracerConfig>,
-    ) -> Result<Option<DebugCall>, Web3Error> {
-        let only_top_call = options
-            .map(|options| options.tracer_config.only_top_call)
-            .unwrap_or(false);
+    ) -> Result<Option<DebugTrace>, Web3Error> {
+        let (tracer, only_top_call) = Self::tracer_params(options);
         let mut connection = self.state.acquire_connection().await?;
-        let call_trace = connection
-            .transactions_dal()
-            .get_call_trace(tx_hash)
+        if matches!(tracer, SupportedTracers::CallTracer) {
+            let call_trace = connection
+                .transactions_dal()
+                .get_call_trace(tx_hash)
+                .await
+                .map_err(DalError::generalize)?;
+            if let Some(call_trace) = call_trace {
+                let result = Self::debug_call(call_trace, only_top_call);
+                return Ok(Some(DebugTrace::Call(result)));
+            }
+        }
+
+        // Either the trace wasn't persisted, or a tracer other than the call one is requested;
+        // in both cases, we need to re-execute the transaction.
+        let tx = connection
+            .transactions_web3_dal()
+            .get_transaction_by_hash(tx_hash, self.sender_config().chain_id)
+            .await
+            .map_err(DalError::generalize)?;
+        let Some(block_number) = tx.and_then(|tx| tx.block_number) else {
+            return Ok(None); // The transaction is unknown or not included into an L2 block yet
+        };
+        let block_id = BlockId::Number(BlockNumber::Number(block_number));
+        let block_number = self.state.resolve_block(&mut connection, block_id).await?;
+        self.current_method().set_block_id(block_id);
+        self.current_method()
+            .set_block_diff(self.state.last_sealed_l2_block.diff(block_number));
+
+        let mut txs = connection
+            .transactions_web3_dal()
+            .get_raw_l2_block_transactions(block_number)
             .await
             .map_err(DalError::generalize)?;
-        Ok(call_trace.map(|call_trace| {
-            let mut result: DebugCall = call_trace.into();
-            if only_top_call {
-                result.calls = vec![];
+        drop(connection);
+        let tx_position = txs
+            .iter()
+            .position(|tx| tx.hash() == tx_hash)
+            .with_context(|| {
+                format!("transaction {tx_hash:?} is not in L2 block #{block_number}")
+            })?;
+        // Transactions after the traced one don't influence its execution.
+        txs.truncate(tx_position + 1);
+        let mut traces = self
+            .replay_l2_block_txs(block_id, txs, tx_position, &tracer, only_top_call)
+            .await?;
+        Ok(traces.pop())
+    }
+
+    fn tracer_params(options: Option<TracerConfig>) -> (SupportedTracers, bool) {
+        match options {
+            Some(options) => (options.tracer, options.tracer_config.only_top_call),
+            None => (SupportedTracers::CallTracer, false),
+        }
+    }
+
+    fn debug_call(call_trace: Call, only_top_call: bool) -> DebugCall {
+        let mut result: DebugCall = call_trace.into();
+        if only_top_call {
+            result.calls = vec![];
+        }
+        result
+    }
+
+    /// Re-executes the provided transactions from the specified L2 block, tracing transactions starting
+    /// from `traced_from` index. Returns traces for the traced transactions.
+    async fn replay_l2_block_txs(
+        &self,
+        block_id: BlockId,
+        txs: Vec<Transaction>,
+        traced_from: usize,
+        tracer: &SupportedTracers,
+        only_top_call: bool,
+    ) -> Result<Vec<DebugTrace>, Web3Error> {
+        if txs.is_empty() {
+            return Ok(vec![]);
+        }
+
+        let mut connection = self.state.acquire_connection().await?;
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id)
+            .await?
+            .for_block_replay();
+        drop(connection);
+
+        let mut traced_txs = vec![];
+        let mut txs_with_tracers = Vec::with_capacity(txs.len());
+        for (i, tx) in txs.into_iter().enumerate() {
+            if i < traced_from {
+                txs_with_tracers.push((tx, vec![]));
+                continue;
             }
-            result
-        }))
+            let (tracers, output) =
+                TraceOutput::new(tracer, only_top_call, &self.state.tracer_registry)?;
+            traced_txs.push((
+                tx.gas_limit(),
+                tx.execute.value,
+                tx.execute.calldata.clone(),
+                output,
+            ));
+            txs_with_tracers.push((tx, tracers));
+        }
+
+        let shared_args = self.shared_args().await;
+        let vm_permit = self
+            .state
+            .tx_sender
+            .vm_concurrency_limiter()
+            .acquire()
+            .await;
+        let vm_permit = vm_permit.context("cannot acquire VM permit")?;
+        let executor = &self.state.tx_sender.0.executor;
+        let results = executor
+            .replay_l2_block_txs(
+                vm_permit,
+                shared_args,
+                self.state.connection_pool.clone(),
+                block_args,
+                txs_with_tracers,
+            )
+            .await?;
+
+        let traced_results = results.into_iter().skip(traced_from);
+        let traces =
+            traced_results
+                .zip(traced_txs)
+                .map(|(result, (gas_limit, value, calldata, output))| {
+                    output.into_debug_trace(result, gas_limit, value, calldata, only_top_call)
+                });
+        Ok(traces.collect())
     }
 
     pub async fn debug_trace_call_impl(
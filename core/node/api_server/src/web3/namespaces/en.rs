@@ -62,6 +62,24 @@ impl EnNamespace {
             .map_err(DalError::generalize)?)
     }
 
+    pub async fn snapshot_recovery_status_impl(
+        &self,
+    ) -> Result<Option<en::SnapshotRecoveryStatus>, Web3Error> {
+        let mut storage = self.state.acquire_connection().await?;
+        let status = storage
+            .snapshot_recovery_dal()
+            .get_applied_snapshot_status()
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(status.map(|status| en::SnapshotRecoveryStatus {
+            l1_batch_number: status.l1_batch_number,
+            l2_block_number: status.l2_block_number,
+            storage_logs_chunk_count: status.storage_logs_chunks_processed.len(),
+            storage_logs_chunks_processed: status.storage_logs_chunks_processed.len()
+                - status.storage_logs_chunks_left_to_process(),
+        }))
+    }
+
     #[tracing::instrument(skip(self))]
     pub async fn get_ecosystem_contracts_impl(&self) -> Result<EcosystemContracts, Web3Error> {
         Ok(self
@@ -1,10 +1,12 @@
+use std::{collections::HashMap, ops, sync::Arc};
+
 use anyhow::Context as _;
-use zksync_dal::{CoreDal, DalError};
+use zksync_dal::{Connection, Core, CoreDal, DalError};
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockId, BlockNumber, GetLogsFilter, Transaction, TransactionId, TransactionReceipt,
-        TransactionVariant,
+        BlockId, BlockNumber, GetLogsFilter, LogsCursor, Transaction, TransactionId,
+        TransactionReceipt, TransactionVariant,
     },
     l2::{L2Tx, TransactionType},
     transaction_request::CallRequest,
@@ -19,7 +21,11 @@ use zksync_web3_decl::{
 };
 
 use crate::web3::{
-    backend_jsonrpsee::MethodTracer, metrics::API_METRICS, state::RpcState, TypedFilter,
+    backend_jsonrpsee::MethodTracer,
+    fee_history::{validate_reward_percentiles, BlockRewardsData},
+    metrics::API_METRICS,
+    state::RpcState,
+    TypedFilter,
 };
 
 pub const EVENT_TOPIC_NUMBER_LIMIT: usize = 4;
@@ -181,7 +187,7 @@ impl EthNamespace {
             .as_ref()
             .ok_or(Web3Error::MethodNotImplemented)?;
         // We clone the filter to not hold the filter lock for an extended period of time.
-        let maybe_filter = installed_filters.lock().await.get_and_update_stats(idx);
+        let maybe_filter = installed_filters.get_and_update_stats(idx).await?;
 
         let Some(TypedFilter::Events(filter, _)) = maybe_filter else {
             return Err(Web3Error::FilterNotFound);
@@ -206,12 +212,40 @@ impl EthNamespace {
         full_transactions: bool,
     ) -> Result<Option<Block<TransactionVariant>>, Web3Error> {
         self.current_method().set_block_id(block_id);
+        // Only blocks requested by hash are cached since block numbers may refer to different blocks after a revert.
+        let cache_key = match (&self.state.response_cache, block_id) {
+            (Some(cache), BlockId::Hash(hash)) => Some((cache, hash)),
+            _ => None,
+        };
+        if let Some((cache, hash)) = cache_key {
+            if let Some(block) = cache.get_block(hash, full_transactions).await {
+                return Ok(Some(block));
+            }
+        }
         let mut storage = self.state.acquire_connection().await?;
 
-        self.state
-            .start_info
-            .ensure_not_pruned(block_id, &mut storage)
-            .await?;
+        if let Some(archived) = self
+            .state
+            .ensure_not_pruned_or_archived(&mut storage, block_id)
+            .await?
+        {
+            let transactions = if full_transactions {
+                archived
+                    .transactions
+                    .into_iter()
+                    .map(TransactionVariant::Full)
+                    .collect()
+            } else {
+                archived
+                    .block
+                    .transactions
+                    .iter()
+                    .copied()
+                    .map(TransactionVariant::Hash)
+                    .collect()
+            };
+            return Ok(Some(archived.block.with_transactions(transactions)));
+        }
 
         let Some(block_number) = self
             .state
@@ -263,7 +297,17 @@ impl EthNamespace {
                 .collect()
         };
 
-        Ok(Some(block.with_transactions(transactions)))
+        let block = block.with_transactions(transactions);
+        if let Some((cache, hash)) = cache_key {
+            if self
+                .state
+                .is_l2_block_final(&mut storage, block_number)
+                .await?
+            {
+                cache.insert_block(hash, full_transactions, &block);
+            }
+        }
+        Ok(Some(block))
     }
 
     pub async fn get_block_transaction_count_impl(
@@ -273,10 +317,13 @@ impl EthNamespace {
         self.current_method().set_block_id(block_id);
         let mut storage = self.state.acquire_connection().await?;
 
-        self.state
-            .start_info
-            .ensure_not_pruned(block_id, &mut storage)
-            .await?;
+        if let Some(archived) = self
+            .state
+            .ensure_not_pruned_or_archived(&mut storage, block_id)
+            .await?
+        {
+            return Ok(Some((archived.block.transactions.len() as u64).into()));
+        }
 
         let Some(block_number) = self
             .state
@@ -304,10 +351,13 @@ impl EthNamespace {
         self.current_method().set_block_id(block_id);
         let mut storage = self.state.acquire_connection().await?;
 
-        self.state
-            .start_info
-            .ensure_not_pruned(block_id, &mut storage)
-            .await?;
+        if let Some(archived) = self
+            .state
+            .ensure_not_pruned_or_archived(&mut storage, block_id)
+            .await?
+        {
+            return Ok(Some(archived.receipts));
+        }
 
         let Some(block_number) = self
             .state
@@ -433,11 +483,15 @@ impl EthNamespace {
         let mut storage = self.state.acquire_connection().await?;
         let chain_id = self.state.api_config.l2_chain_id;
         let mut transaction = match id {
-            TransactionId::Hash(hash) => storage
-                .transactions_web3_dal()
-                .get_transaction_by_hash(hash, chain_id)
-                .await
-                .map_err(DalError::generalize)?,
+            TransactionId::Hash(hash) => {
+                let transaction = storage
+                    .transactions_web3_dal()
+                    .get_transaction_by_hash(hash, chain_id)
+                    .await
+                    .map_err(DalError::generalize)?;
+                self.lookup_archived_transaction(&mut storage, hash, transaction)
+                    .await?
+            }
 
             TransactionId::Block(block_id, idx) => {
                 let Ok(idx) = u32::try_from(idx) else {
@@ -469,13 +523,55 @@ impl EthNamespace {
         &self,
         hash: H256,
     ) -> Result<Option<TransactionReceipt>, Web3Error> {
+        let response_cache = self.state.response_cache.as_ref();
+        if let Some(cache) = response_cache {
+            if let Some(receipt) = cache.get_transaction_receipt(hash).await {
+                return Ok(Some(receipt));
+            }
+        }
+
         let mut storage = self.state.acquire_connection().await?;
         let receipts = storage
             .transactions_web3_dal()
             .get_transaction_receipts(&[hash])
             .await
             .context("get_transaction_receipts")?;
-        Ok(receipts.into_iter().next())
+        if let Some(receipt) = receipts.into_iter().next() {
+            if let Some(cache) = response_cache {
+                let block_number = RpcState::u64_to_block_number(receipt.block_number);
+                if self
+                    .state
+                    .is_l2_block_final(&mut storage, block_number)
+                    .await?
+                {
+                    cache.insert_transaction_receipt(&receipt);
+                }
+            }
+            return Ok(Some(receipt));
+        }
+
+        // Receipts are not returned for transactions in pruned L2 blocks.
+        let Some(archive) = &self.state.block_data_archive else {
+            return Ok(None);
+        };
+        Ok(archive.get_transaction_receipt(&mut storage, hash).await?)
+    }
+
+    /// Transactions in pruned L2 blocks are retained in the storage with erased data, so they are not returned
+    /// from the storage; such transactions are loaded from the block data archive, if it's configured.
+    async fn lookup_archived_transaction(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        hash: H256,
+        transaction: Option<Transaction>,
+    ) -> Result<Option<Transaction>, Web3Error> {
+        if transaction.is_some() {
+            return Ok(transaction);
+        }
+        let Some(archive) = &self.state.block_data_archive else {
+            return Ok(None);
+        };
+        Ok(archive.get_transaction(storage, hash).await?)
     }
 
     pub async fn new_block_filter_impl(&self) -> Result<U256, Web3Error> {
@@ -494,10 +590,9 @@ impl EthNamespace {
         let next_block_number = last_block_number + 1;
         drop(storage);
 
-        Ok(installed_filters
-            .lock()
+        installed_filters
+            .add(TypedFilter::Blocks(next_block_number))
             .await
-            .add(TypedFilter::Blocks(next_block_number)))
     }
 
     pub async fn new_filter_impl(&self, mut filter: Filter) -> Result<U256, Web3Error> {
@@ -514,10 +609,9 @@ impl EthNamespace {
 
         self.state.resolve_filter_block_hash(&mut filter).await?;
         let from_block = self.state.get_filter_from_block(&filter).await?;
-        Ok(installed_filters
-            .lock()
+        installed_filters
+            .add(TypedFilter::Events(filter, from_block))
             .await
-            .add(TypedFilter::Events(filter, from_block)))
     }
 
     pub async fn new_pending_transaction_filter_impl(&self) -> Result<U256, Web3Error> {
@@ -526,12 +620,11 @@ impl EthNamespace {
             .installed_filters
             .as_ref()
             .ok_or(Web3Error::MethodNotImplemented)?;
-        Ok(installed_filters
-            .lock()
-            .await
+        installed_filters
             .add(TypedFilter::PendingTransactions(
                 chrono::Utc::now().naive_utc(),
-            )))
+            ))
+            .await
     }
 
     pub async fn get_filter_changes_impl(&self, idx: U256) -> Result<FilterChanges, Web3Error> {
@@ -540,23 +633,28 @@ impl EthNamespace {
             .installed_filters
             .as_ref()
             .ok_or(Web3Error::MethodNotImplemented)?;
-        let mut filter = installed_filters
-            .lock()
-            .await
-            .get_and_update_stats(idx)
-            .ok_or(Web3Error::FilterNotFound)?;
+        loop {
+            let prev_filter = installed_filters
+                .get_and_update_stats(idx)
+                .await?
+                .ok_or(Web3Error::FilterNotFound)?;
+            let mut filter = prev_filter.clone();
 
-        match self.filter_changes(&mut filter).await {
-            Ok(changes) => {
-                installed_filters.lock().await.update(idx, filter);
-                Ok(changes)
-            }
-            Err(Web3Error::LogsLimitExceeded(..)) => {
-                // The filter was not being polled for a long time, so we remove it.
-                installed_filters.lock().await.remove(idx);
-                Err(Web3Error::FilterNotFound)
+            match self.filter_changes(&mut filter).await {
+                Ok(changes) => {
+                    if installed_filters.update(idx, &prev_filter, filter).await? {
+                        return Ok(changes);
+                    }
+                    // The filter was concurrently polled via another API server; retry with its updated state
+                    // so that the same changes are not returned twice.
+                }
+                Err(Web3Error::LogsLimitExceeded(..)) => {
+                    // The filter was not being polled for a long time, so we remove it.
+                    installed_filters.remove(idx).await?;
+                    return Err(Web3Error::FilterNotFound);
+                }
+                Err(err) => return Err(err),
             }
-            Err(err) => Err(err),
         }
     }
 
@@ -566,7 +664,7 @@ impl EthNamespace {
             .installed_filters
             .as_ref()
             .ok_or(Web3Error::MethodNotImplemented)?;
-        Ok(installed_filters.lock().await.remove(idx))
+        installed_filters.remove(idx).await
     }
 
     pub fn protocol_version(&self) -> String {
@@ -616,6 +714,7 @@ impl EthNamespace {
     ) -> Result<FeeHistory, Web3Error> {
         self.current_method()
             .set_block_id(BlockId::Number(newest_block));
+        validate_reward_percentiles(&reward_percentiles)?;
 
         // Limit `block_count`.
         let block_count = block_count
@@ -641,11 +740,14 @@ impl EthNamespace {
         let oldest_block = newest_l2_block.0 + 1 - base_fee_per_gas.len() as u32;
         // We do not store gas used ratio for blocks, returns array of zeroes as a placeholder.
         let gas_used_ratio = vec![0.0; base_fee_per_gas.len()];
-        // Effective priority gas price is currently 0.
-        let reward = Some(vec![
-            vec![U256::zero(); reward_percentiles.len()];
-            base_fee_per_gas.len()
-        ]);
+        let reward = if reward_percentiles.is_empty() {
+            vec![vec![]; base_fee_per_gas.len()]
+        } else {
+            let block_range = L2BlockNumber(oldest_block)..=newest_l2_block;
+            self.fee_history_rewards(&mut connection, block_range, &reward_percentiles)
+                .await?
+        };
+        let reward = Some(reward);
 
         // `base_fee_per_gas` for next L2 block cannot be calculated, appending last fee as a placeholder.
         base_fee_per_gas.push(*base_fee_per_gas.last().unwrap());
@@ -657,6 +759,52 @@ impl EthNamespace {
         })
     }
 
+    /// Computes rewards for each L2 block in the specified range, using cached data where possible.
+    async fn fee_history_rewards(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        block_range: ops::RangeInclusive<L2BlockNumber>,
+        reward_percentiles: &[f32],
+    ) -> Result<Vec<Vec<U256>>, Web3Error> {
+        let cache = &self.state.fee_history_cache;
+        let newest_block = *block_range.end();
+        let mut blocks_data: Vec<_> = (block_range.start().0..=newest_block.0)
+            .map(|number| cache.get(L2BlockNumber(number)))
+            .collect();
+
+        let first_missing_idx = blocks_data.iter().position(Option::is_none);
+        let last_missing_idx = blocks_data.iter().rposition(Option::is_none);
+        if let (Some(first_idx), Some(last_idx)) = (first_missing_idx, last_missing_idx) {
+            let first_missing_block = *block_range.start() + first_idx as u32;
+            let last_missing_block = *block_range.start() + last_idx as u32;
+            let fetched_data = connection
+                .blocks_web3_dal()
+                .get_fee_history_rewards_data(first_missing_block..=last_missing_block)
+                .await
+                .map_err(DalError::generalize)?;
+            let mut fetched_data: HashMap<_, _> = fetched_data.into_iter().collect();
+
+            for (idx, block_data) in blocks_data.iter_mut().enumerate() {
+                if block_data.is_some() {
+                    continue;
+                }
+                let block_number = *block_range.start() + idx as u32;
+                let txs = fetched_data.remove(&block_number).unwrap_or_default();
+                let data = Arc::new(BlockRewardsData::new(txs));
+                // The newest block may be pending, so we don't cache it to avoid caching incomplete data.
+                if block_number < newest_block {
+                    cache.insert(block_number, data.clone());
+                }
+                *block_data = Some(data);
+            }
+        }
+
+        Ok(blocks_data
+            .into_iter()
+            .map(|data| data.unwrap().rewards(reward_percentiles))
+            .collect())
+    }
+
     async fn filter_changes(
         &self,
         typed_filter: &mut TypedFilter,
@@ -712,22 +860,7 @@ impl EthNamespace {
             }
 
             TypedFilter::Events(filter, from_block) => {
-                let addresses = if let Some(addresses) = &filter.address {
-                    addresses.0.clone()
-                } else {
-                    vec![]
-                };
-                let topics = if let Some(topics) = &filter.topics {
-                    if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
-                        return Err(Web3Error::TooManyTopics);
-                    }
-                    let topics_by_idx = topics.iter().enumerate().filter_map(|(idx, topics)| {
-                        Some((idx as u32 + 1, topics.as_ref()?.0.clone()))
-                    });
-                    topics_by_idx.collect::<Vec<_>>()
-                } else {
-                    vec![]
-                };
+                let (addresses, topics) = logs_filter_params(filter)?;
 
                 let mut to_block = self
                     .state
@@ -747,6 +880,7 @@ impl EthNamespace {
                     to_block,
                     addresses,
                     topics,
+                    from_log_index: 0,
                 };
 
                 let mut storage = self.state.acquire_connection().await?;
@@ -763,10 +897,22 @@ impl EthNamespace {
                         .await
                         .map_err(DalError::generalize)?
                     {
+                        // If pagination is enabled, provide a cursor allowing to fetch logs using `zks_getLogsPaged`.
+                        // No logs are returned together with the error, so the cursor points to the start
+                        // of the requested range.
+                        let cursor =
+                            self.state
+                                .api_config
+                                .logs_pagination_enabled
+                                .then_some(LogsCursor {
+                                    block_number: *from_block,
+                                    index_in_block: 0,
+                                });
                         return Err(Web3Error::LogsLimitExceeded(
                             self.state.api_config.req_entities_limit,
                             from_block.0,
                             from_block.0.max(l2_block_number.0 - 1),
+                            cursor,
                         ));
                     }
                 }
@@ -783,6 +929,30 @@ impl EthNamespace {
     }
 }
 
+/// Extracts addresses and indexed topics from the provided logs filter.
+pub(super) fn logs_filter_params(
+    filter: &Filter,
+) -> Result<(Vec<Address>, Vec<(u32, Vec<H256>)>), Web3Error> {
+    let addresses = if let Some(addresses) = &filter.address {
+        addresses.0.clone()
+    } else {
+        vec![]
+    };
+    let topics = if let Some(topics) = &filter.topics {
+        if topics.len() > EVENT_TOPIC_NUMBER_LIMIT {
+            return Err(Web3Error::TooManyTopics);
+        }
+        let topics_by_idx = topics
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, topics)| Some((idx as u32 + 1, topics.as_ref()?.0.clone())));
+        topics_by_idx.collect::<Vec<_>>()
+    } else {
+        vec![]
+    };
+    Ok((addresses, topics))
+}
+
 // Bogus methods.
 // They are moved into a separate `impl` block so they don't make the actual implementation noisy.
 // This `impl` block contains methods that we *have* to implement for compliance, but don't really
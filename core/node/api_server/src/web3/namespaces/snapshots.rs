@@ -53,13 +53,16 @@ impl SnapshotsNamespace {
             return Ok(None);
         }
 
+        let checksums = snapshot_metadata.storage_logs_checksums;
         let chunks = snapshot_files
             .into_iter()
+            .zip(checksums)
             .enumerate()
-            .filter_map(|(chunk_id, filepath)| {
+            .filter_map(|(chunk_id, (filepath, checksum))| {
                 Some(SnapshotStorageLogsChunkMetadata {
                     chunk_id: chunk_id as u64,
                     filepath: filepath?,
+                    checksum,
                 })
             })
             .collect();
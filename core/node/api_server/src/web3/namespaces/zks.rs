@@ -2,17 +2,19 @@ use std::{collections::HashMap, convert::TryInto};
 
 use anyhow::Context as _;
 use multivm::interface::VmExecutionResultAndLogs;
-use zksync_dal::{Connection, Core, CoreDal, DalError};
+use zksync_dal::{pagination::KeysetPagination, Connection, Core, CoreDal, DalError};
 use zksync_metadata_calculator::api_server::TreeApiError;
 use zksync_mini_merkle_tree::MiniMerkleTree;
 use zksync_system_constants::DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE;
 use zksync_types::{
     api::{
-        BlockDetails, BridgeAddresses, GetLogsFilter, L1BatchDetails, L2ToL1LogProof, Proof,
-        ProtocolVersion, StorageProof, TransactionDetails,
+        BlockDetails, BlockId, BlockNumber, BridgeAddresses, GetLogsFilter, L1BatchDetails,
+        L1BatchNumberObject, L2ToL1LogProof, LogsCursor, LogsPage, PageCursor, Proof, ProofRequest,
+        ProtocolVersion, SimulationBlockId, SimulationOptions, StorageProof, SupportedTracers,
+        TransactionDetails, TransactionSimulation, TransactionsPage,
     },
     fee::Fee,
-    fee_model::{FeeParams, PubdataIndependentBatchFeeModelInput},
+    fee_model::{FeeModelBreakdown, FeeParams, PubdataIndependentBatchFeeModelInput},
     l1::L1Tx,
     l2::L2Tx,
     l2_to_l1_log::{l2_to_l1_logs_tree_size, L2ToL1Log},
@@ -26,10 +28,17 @@ use zksync_types::{
 use zksync_utils::{address_to_h256, h256_to_u256};
 use zksync_web3_decl::{
     error::Web3Error,
-    types::{Address, Token, H256},
+    types::{Address, Filter, Token, H256},
 };
 
-use crate::web3::{backend_jsonrpsee::MethodTracer, metrics::API_METRICS, RpcState};
+use super::{debug::TraceOutput, eth::logs_filter_params};
+use crate::{
+    execution_sandbox::validate_state_override,
+    web3::{backend_jsonrpsee::MethodTracer, metrics::API_METRICS, RpcState},
+};
+
+/// Maximum number of transactions in a single `zks_estimateFeeBatch` call.
+const MAX_FEE_ESTIMATION_BATCH_SIZE: usize = 16;
 
 #[derive(Debug)]
 pub(crate) struct ZksNamespace {
@@ -46,6 +55,36 @@ impl ZksNamespace {
     }
 
     pub async fn estimate_fee_impl(&self, request: CallRequest) -> Result<Fee, Web3Error> {
+        let tx = self.fee_estimation_tx(request).await?;
+        self.estimate_fee(tx).await
+    }
+
+    pub async fn estimate_fee_batch_impl(
+        &self,
+        requests: Vec<CallRequest>,
+    ) -> Result<Vec<Fee>, Web3Error> {
+        if requests.len() > MAX_FEE_ESTIMATION_BATCH_SIZE {
+            return Err(Web3Error::TooManyTransactions(
+                MAX_FEE_ESTIMATION_BATCH_SIZE,
+            ));
+        }
+
+        let mut txs = Vec::with_capacity(requests.len());
+        for request in requests {
+            txs.push(self.fee_estimation_tx(request).await?);
+        }
+        let scale_factor = self.state.api_config.estimate_gas_scale_factor;
+        let acceptable_overestimation =
+            self.state.api_config.estimate_gas_acceptable_overestimation;
+
+        Ok(self
+            .state
+            .tx_sender
+            .get_txs_fee_in_wei_batch(txs, scale_factor, acceptable_overestimation as u64)
+            .await?)
+    }
+
+    async fn fee_estimation_tx(&self, request: CallRequest) -> Result<Transaction, Web3Error> {
         let mut request_with_gas_per_pubdata_overridden = request;
         self.state
             .set_nonce_for_call_request(&mut request_with_gas_per_pubdata_overridden)
@@ -64,7 +103,7 @@ impl ZksNamespace {
         // not consider provided ones.
         tx.common_data.fee.max_priority_fee_per_gas = 0u64.into();
         tx.common_data.fee.gas_per_pubdata_limit = U256::from(DEFAULT_L2_TX_GAS_PER_PUBDATA_BYTE);
-        self.estimate_fee(tx.into()).await
+        Ok(tx.into())
     }
 
     pub async fn estimate_l1_to_l2_gas_impl(
@@ -109,11 +148,11 @@ impl ZksNamespace {
     }
 
     pub fn get_testnet_paymaster_impl(&self) -> Option<Address> {
-        self.state.api_config.l2_testnet_paymaster_addr
+        self.state.updatable_config.get().l2_testnet_paymaster_addr
     }
 
     pub fn get_bridge_contracts_impl(&self) -> BridgeAddresses {
-        self.state.api_config.bridge_addresses.clone()
+        self.state.updatable_config.get().bridge_addresses
     }
 
     pub fn l1_chain_id_impl(&self) -> U64 {
@@ -224,6 +263,7 @@ impl ZksNamespace {
                 .get_logs(
                     GetLogsFilter {
                         from_block: first_l2_block_of_l1_batch,
+                        from_log_index: 0,
                         to_block: block_number,
                         addresses: vec![L1_MESSENGER_ADDRESS],
                         topics: vec![(2, vec![address_to_h256(&sender)]), (3, vec![msg])],
@@ -422,11 +462,24 @@ impl ZksNamespace {
             .ensure_not_pruned(batch_number, &mut storage)
             .await?;
 
-        Ok(storage
+        let response_cache = self.state.response_cache.as_ref();
+        if let Some(cache) = response_cache {
+            if let Some(details) = cache.get_l1_batch_details(batch_number).await {
+                return Ok(Some(details));
+            }
+        }
+        let details = storage
             .blocks_web3_dal()
             .get_l1_batch_details(batch_number)
             .await
-            .map_err(DalError::generalize)?)
+            .map_err(DalError::generalize)?;
+        if let (Some(cache), Some(details)) = (response_cache, &details) {
+            // Details of an L1 batch don't change after it's executed on L1.
+            if details.base.executed_at.is_some() {
+                cache.insert_l1_batch_details(details);
+            }
+        }
+        Ok(details)
     }
 
     pub async fn get_bytecode_by_hash_impl(
@@ -450,6 +503,15 @@ impl ZksNamespace {
             .get_fee_model_params()
     }
 
+    #[tracing::instrument(skip(self))]
+    pub fn get_fee_model_breakdown_impl(&self) -> FeeModelBreakdown {
+        self.state
+            .tx_sender
+            .0
+            .batch_fee_input_provider
+            .get_fee_model_breakdown()
+    }
+
     pub async fn get_protocol_version_impl(
         &self,
         version_id: Option<u16>,
@@ -479,14 +541,42 @@ impl ZksNamespace {
         keys: Vec<H256>,
         l1_batch_number: L1BatchNumber,
     ) -> Result<Option<Proof>, Web3Error> {
+        let account = ProofRequest { address, keys };
+        let proofs = self
+            .get_account_proofs_impl(vec![account], l1_batch_number)
+            .await?;
+        Ok(proofs.map(|mut proofs| proofs.pop().expect("no proof for requested account")))
+    }
+
+    /// Gets proofs for all `accounts` with a single tree request. Returns `Ok(None)` if the tree doesn't
+    /// have the requested L1 batch yet, and [`Web3Error::PrunedL1Batch`] if the L1 batch is pruned
+    /// either in Postgres or in the tree. The total number of requested keys is capped by `req_entities_limit`.
+    pub async fn get_account_proofs_impl(
+        &self,
+        accounts: Vec<ProofRequest>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<Option<Vec<Proof>>, Web3Error> {
+        let keys_limit = self.state.api_config.req_entities_limit;
+        let key_count: usize = accounts.iter().map(|account| account.keys.len()).sum();
+        if key_count > keys_limit {
+            return Err(Web3Error::TooManyStorageKeys(keys_limit));
+        }
+
         let mut storage = self.state.acquire_connection().await?;
         self.state
             .start_info
             .ensure_not_pruned(l1_batch_number, &mut storage)
             .await?;
-        let hashed_keys = keys
+        drop(storage);
+
+        let hashed_keys = accounts
             .iter()
-            .map(|key| StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256())
+            .flat_map(|account| {
+                let address = account.address;
+                account.keys.iter().map(move |key| {
+                    StorageKey::new(AccountTreeId::new(address), *key).hashed_key_u256()
+                })
+            })
             .collect();
         let tree_api = self
             .state
@@ -498,13 +588,22 @@ impl ZksNamespace {
             Ok(proofs) => proofs,
             Err(TreeApiError::NotReady(_)) => return Err(Web3Error::TreeApiUnavailable),
             Err(TreeApiError::NoVersion(err)) => {
-                return if err.missing_version > err.version_count {
-                    Ok(None)
-                } else {
-                    Err(Web3Error::InternalError(anyhow::anyhow!(
-                        "L1 batch #{l1_batch_number} is pruned in Merkle tree, but not in Postgres"
-                    )))
+                if err.missing_version >= err.version_count {
+                    return Ok(None);
+                }
+                // The L1 batch is pruned in the tree, which may retain fewer versions than Postgres.
+                let tree_info = match tree_api.get_info().await {
+                    Ok(info) => info,
+                    Err(TreeApiError::NotReady(_)) => return Err(Web3Error::TreeApiUnavailable),
+                    Err(err) => {
+                        let err = anyhow::Error::new(err).context("failed getting tree info");
+                        return Err(Web3Error::InternalError(err));
+                    }
                 };
+                let first_retained_l1_batch = tree_info
+                    .min_l1_batch_number
+                    .context("Merkle tree has no retained versions")?;
+                return Err(Web3Error::PrunedL1Batch(first_retained_l1_batch));
             }
             Err(TreeApiError::Internal(err)) => return Err(Web3Error::InternalError(err)),
             Err(_) => {
@@ -515,21 +614,28 @@ impl ZksNamespace {
             }
         };
 
-        let storage_proof = proofs
+        let mut proofs = proofs.into_iter();
+        let account_proofs = accounts
             .into_iter()
-            .zip(keys)
-            .map(|(proof, key)| StorageProof {
-                key,
-                proof: proof.merkle_path,
-                value: proof.value,
-                index: proof.index,
+            .map(|account| {
+                let storage_proof = proofs
+                    .by_ref()
+                    .take(account.keys.len())
+                    .zip(account.keys)
+                    .map(|(proof, key)| StorageProof {
+                        key,
+                        proof: proof.merkle_path,
+                        value: proof.value,
+                        index: proof.index,
+                    })
+                    .collect();
+                Proof {
+                    address: account.address,
+                    storage_proof,
+                }
             })
             .collect();
-
-        Ok(Some(Proof {
-            address,
-            storage_proof,
-        }))
+        Ok(Some(account_proofs))
     }
 
     pub fn get_base_token_l1_address_impl(&self) -> Result<Address, Web3Error> {
@@ -553,6 +659,153 @@ impl ZksNamespace {
             .into_pubdata_independent())
     }
 
+    #[tracing::instrument(skip(self))]
+    pub async fn get_logs_paged_impl(
+        &self,
+        mut filter: Filter,
+        cursor: Option<LogsCursor>,
+    ) -> Result<LogsPage, Web3Error> {
+        if !self.state.api_config.logs_pagination_enabled {
+            return Err(Web3Error::MethodNotImplemented);
+        }
+
+        self.state.resolve_filter_block_hash(&mut filter).await?;
+        let (from_block, mut to_block) = self.state.resolve_filter_block_range(&filter).await?;
+        if matches!(filter.to_block, Some(BlockNumber::Number(_))) {
+            to_block = to_block.min(
+                self.state
+                    .resolve_filter_block_number(Some(BlockNumber::Latest))
+                    .await?,
+            );
+        }
+        if from_block > to_block {
+            return Ok(LogsPage {
+                logs: vec![],
+                cursor: None,
+            });
+        }
+
+        let (addresses, topics) = logs_filter_params(&filter)?;
+        let get_logs_filter = GetLogsFilter {
+            from_block,
+            to_block,
+            addresses,
+            topics,
+            from_log_index: 0,
+        };
+        let pagination = KeysetPagination::new(cursor, self.state.api_config.req_entities_limit);
+        let mut storage = self.state.acquire_connection().await?;
+        let page = storage
+            .events_web3_dal()
+            .get_logs_page(get_logs_filter, pagination)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(LogsPage {
+            logs: page.items,
+            cursor: page.next,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_account_transactions_paged_impl(
+        &self,
+        address: Address,
+        cursor: Option<PageCursor>,
+    ) -> Result<TransactionsPage, Web3Error> {
+        let pagination = KeysetPagination::new(cursor, self.state.api_config.req_entities_limit);
+        let mut storage = self.state.acquire_connection().await?;
+        let page = storage
+            .transactions_web3_dal()
+            .get_account_transactions(address, pagination, self.state.api_config.l2_chain_id)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(TransactionsPage {
+            transactions: page.items,
+            cursor: page.next,
+        })
+    }
+
+    #[tracing::instrument(skip(self))]
+    pub async fn get_token_transfers_paged_impl(
+        &self,
+        address: Address,
+        cursor: Option<PageCursor>,
+    ) -> Result<LogsPage, Web3Error> {
+        let pagination = KeysetPagination::new(cursor, self.state.api_config.req_entities_limit);
+        let mut storage = self.state.acquire_connection().await?;
+        let page = storage
+            .events_web3_dal()
+            .get_token_transfer_logs(address, pagination)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(LogsPage {
+            logs: page.items,
+            cursor: page.next,
+        })
+    }
+
+    pub async fn simulate_transaction_impl(
+        &self,
+        request: CallRequest,
+        block: SimulationBlockId,
+        options: Option<SimulationOptions>,
+    ) -> Result<TransactionSimulation, Web3Error> {
+        let options = options.unwrap_or_default();
+        if let Some(state_override) = &options.state_override {
+            validate_state_override(state_override).map_err(Web3Error::InvalidStateOverride)?;
+        }
+
+        let mut connection = self.state.acquire_connection().await?;
+        let block_id = match block {
+            SimulationBlockId::Block(block_id) => block_id.into(),
+            SimulationBlockId::L1Batch(L1BatchNumberObject { l1_batch_number }) => {
+                self.state
+                    .start_info
+                    .ensure_not_pruned(l1_batch_number, &mut connection)
+                    .await?;
+                let (_, last_l2_block) = connection
+                    .blocks_web3_dal()
+                    .get_l2_block_range_of_l1_batch(l1_batch_number)
+                    .await
+                    .map_err(DalError::generalize)?
+                    .ok_or(Web3Error::NoBlock)?;
+                BlockId::Number(BlockNumber::Number(last_l2_block.0.into()))
+            }
+        };
+        self.current_method().set_block_id(block_id);
+        let block_args = self
+            .state
+            .resolve_block_args(&mut connection, block_id)
+            .await?;
+        self.current_method().set_block_diff(
+            self.state
+                .last_sealed_l2_block
+                .diff_with_block_args(&block_args),
+        );
+        drop(connection);
+
+        let tx = L2Tx::from_request(request.into(), self.state.api_config.max_tx_size)?;
+        let (gas_limit, value, calldata) = (
+            tx.common_data.fee.gas_limit,
+            tx.execute.value,
+            tx.execute.calldata.clone(),
+        );
+        let tracer = options.tracer.unwrap_or(SupportedTracers::CallTracer);
+        let only_top_call = options.tracer_config.only_top_call;
+        let (tracers, output) =
+            TraceOutput::new(&tracer, only_top_call, &self.state.tracer_registry)?;
+        let result = self
+            .state
+            .tx_sender
+            .simulate_tx(block_args, tx, options.state_override, tracers)
+            .await?;
+        let trace = output.into_debug_trace(result, gas_limit, value, calldata, only_top_call);
+        Ok(TransactionSimulation {
+            l2_block_number: block_args.resolved_block_number(),
+            trace,
+        })
+    }
+
     #[tracing::instrument(skip(self, tx_bytes))]
     pub async fn send_raw_transaction_with_detailed_output_impl(
         &self,
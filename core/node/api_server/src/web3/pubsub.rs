@@ -64,7 +64,7 @@ impl PubSubNotifier {
         stop_receiver: &mut watch::Receiver<bool>,
     ) -> anyhow::Result<Option<L2BlockNumber>> {
         while !*stop_receiver.borrow_and_update() {
-            let mut storage = self.connection_pool.connection_tagged("api").await?;
+            let mut storage = self.connection_pool.read_connection_tagged("api").await?;
             if let Some(l2_block_number) = storage.blocks_dal().get_sealed_l2_block_number().await?
             {
                 return Ok(Some(l2_block_number));
@@ -137,7 +137,7 @@ impl PubSubNotifier {
         last_block_number: L2BlockNumber,
     ) -> anyhow::Result<Vec<BlockHeader>> {
         self.connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await?
             .blocks_web3_dal()
             .get_block_headers_after(last_block_number)
@@ -177,7 +177,7 @@ impl PubSubNotifier {
         last_time: NaiveDateTime,
     ) -> anyhow::Result<Vec<(NaiveDateTime, H256)>> {
         self.connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await?
             .transactions_web3_dal()
             .get_pending_txs_hashes_after(last_time, None)
@@ -222,7 +222,7 @@ impl PubSubNotifier {
 
     async fn new_logs(&self, last_block_number: L2BlockNumber) -> anyhow::Result<Vec<Log>> {
         self.connection_pool
-            .connection_tagged("api")
+            .read_connection_tagged("api")
             .await?
             .events_web3_dal()
             .get_all_logs(last_block_number)
@@ -0,0 +1,387 @@
+//! Cache for API responses that don't change once the corresponding data is final.
+
+use std::{fmt, time::Duration};
+
+use anyhow::Context as _;
+use serde::{de::DeserializeOwned, Serialize};
+use zksync_types::{
+    api::{Block, L1BatchDetails, TransactionReceipt, TransactionVariant},
+    url::SensitiveUrl,
+    L1BatchNumber, L2ChainId, H256,
+};
+
+use super::metrics::{CachedResponseKind, RESPONSE_CACHE_METRICS};
+
+/// Timeout for Redis lookups. If a lookup times out, the request is served from the node storage.
+const REDIS_GET_TIMEOUT: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum CacheKey {
+    BlockByHash { hash: H256, full_transactions: bool },
+    TransactionReceipt(H256),
+    L1BatchDetails(L1BatchNumber),
+}
+
+impl CacheKey {
+    fn kind(&self) -> CachedResponseKind {
+        match self {
+            Self::BlockByHash { .. } => CachedResponseKind::Block,
+            Self::TransactionReceipt(_) => CachedResponseKind::TransactionReceipt,
+            Self::L1BatchDetails(_) => CachedResponseKind::L1BatchDetails,
+        }
+    }
+
+    fn redis_key(&self, prefix: &str) -> String {
+        match self {
+            Self::BlockByHash {
+                hash,
+                full_transactions,
+            } => format!("{prefix}:block:{hash:?}:{full_transactions}"),
+            Self::TransactionReceipt(hash) => format!("{prefix}:receipt:{hash:?}"),
+            Self::L1BatchDetails(number) => format!("{prefix}:l1_batch_details:{}", number.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum CachedResponse {
+    Block(Block<TransactionVariant>),
+    TransactionReceipt(TransactionReceipt),
+    L1BatchDetails(L1BatchDetails),
+}
+
+impl CachedResponse {
+    fn to_json(&self) -> serde_json::Result<Vec<u8>> {
+        match self {
+            Self::Block(block) => serde_json::to_vec(block),
+            Self::TransactionReceipt(receipt) => serde_json::to_vec(receipt),
+            Self::L1BatchDetails(details) => serde_json::to_vec(details),
+        }
+    }
+
+    fn from_json(kind: CachedResponseKind, bytes: &[u8]) -> serde_json::Result<Self> {
+        fn parse<T: DeserializeOwned>(bytes: &[u8]) -> serde_json::Result<T> {
+            serde_json::from_slice(bytes)
+        }
+
+        Ok(match kind {
+            CachedResponseKind::Block => Self::Block(parse(bytes)?),
+            CachedResponseKind::TransactionReceipt => Self::TransactionReceipt(parse(bytes)?),
+            CachedResponseKind::L1BatchDetails => Self::L1BatchDetails(parse(bytes)?),
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+struct CacheEntry {
+    /// Approximate size of the entry in bytes.
+    size: u32,
+    response: CachedResponse,
+}
+
+impl CacheEntry {
+    fn new(response: CachedResponse, serializable: &impl Serialize) -> Self {
+        // Serialization shouldn't fail; if it does, the entry is evicted as soon as possible.
+        let size = serde_json::to_vec(serializable).map_or(u32::MAX, |bytes| {
+            u32::try_from(bytes.len()).unwrap_or(u32::MAX)
+        });
+        Self { size, response }
+    }
+
+    fn from_json(response: CachedResponse, bytes: &[u8]) -> Self {
+        let size = u32::try_from(bytes.len()).unwrap_or(u32::MAX);
+        Self { size, response }
+    }
+}
+
+/// Redis backend shared among API servers of multiple nodes.
+#[derive(Clone)]
+struct RedisBackend {
+    connection: redis::aio::ConnectionManager,
+    key_prefix: String,
+}
+
+impl RedisBackend {
+    async fn get(&self, key: &CacheKey) -> anyhow::Result<Option<Vec<u8>>> {
+        let redis_key = key.redis_key(&self.key_prefix);
+        let mut connection = self.connection.clone();
+        let get = redis::cmd("GET")
+            .arg(&redis_key)
+            .query_async::<_, Option<Vec<u8>>>(&mut connection);
+        tokio::time::timeout(REDIS_GET_TIMEOUT, get)
+            .await
+            .context("timed out getting cached response from Redis")?
+            .with_context(|| format!("failed getting `{redis_key}` from Redis"))
+    }
+
+    async fn set(&self, key: &CacheKey, value: Vec<u8>, ttl: Duration) -> anyhow::Result<()> {
+        let redis_key = key.redis_key(&self.key_prefix);
+        let mut connection = self.connection.clone();
+        redis::cmd("SET")
+            .arg(&redis_key)
+            .arg(value)
+            .arg("EX")
+            .arg(ttl.as_secs().max(1))
+            .query_async::<_, ()>(&mut connection)
+            .await
+            .with_context(|| format!("failed setting `{redis_key}` in Redis"))
+    }
+}
+
+/// Cache for API responses that are immutable once the corresponding L2 block or L1 batch is final
+/// (i.e., the L1 batch is executed on L1): blocks requested by hash, transaction receipts, and L1 batch details.
+/// Callers are responsible for only inserting final responses. The cache can be shared among API servers.
+///
+/// Responses are cached in memory and, optionally, in Redis, so that they can be shared among multiple nodes.
+/// Memory entries are weighted by the size of their JSON serialization; all entries expire after the configured TTL.
+/// Expiration bounds the time during which the cache can serve data already pruned from the node storage.
+#[derive(Clone)]
+pub struct ResponseCache {
+    memory: mini_moka::sync::Cache<CacheKey, CacheEntry>,
+    redis: Option<RedisBackend>,
+    ttl: Duration,
+}
+
+impl fmt::Debug for ResponseCache {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter
+            .debug_struct("ResponseCache")
+            .field("entry_count", &self.memory.entry_count())
+            .field("weighted_size", &self.memory.weighted_size())
+            .field("has_redis", &self.redis.is_some())
+            .field("ttl", &self.ttl)
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    /// Creates a cache with the specified max size of in-memory entries in bytes and TTL of entries.
+    pub fn new(max_size: u64, ttl: Duration) -> Self {
+        let memory = mini_moka::sync::Cache::builder()
+            .max_capacity(max_size)
+            .time_to_live(ttl)
+            .weigher(|_, entry: &CacheEntry| entry.size)
+            .build();
+        Self {
+            memory,
+            redis: None,
+            ttl,
+        }
+    }
+
+    /// Adds a Redis backend to this cache. Keys are namespaced by the chain ID, so the same Redis instance
+    /// can be shared among nodes of different chains.
+    pub async fn with_redis(
+        mut self,
+        url: &SensitiveUrl,
+        chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        let client = redis::Client::open(url.expose_str()).context("invalid Redis URL")?;
+        let connection = redis::aio::ConnectionManager::new(client)
+            .await
+            .context("failed connecting to Redis")?;
+        self.redis = Some(RedisBackend {
+            connection,
+            key_prefix: format!("zksync_api_response_cache:{}", chain_id.as_u64()),
+        });
+        Ok(self)
+    }
+
+    async fn get(&self, key: &CacheKey) -> Option<CachedResponse> {
+        let kind = key.kind();
+        if let Some(entry) = self.memory.get(key) {
+            RESPONSE_CACHE_METRICS.hits[&kind].inc();
+            return Some(entry.response);
+        }
+
+        if let Some(redis) = &self.redis {
+            match redis.get(key).await {
+                Ok(Some(bytes)) => match CachedResponse::from_json(kind, &bytes) {
+                    Ok(response) => {
+                        RESPONSE_CACHE_METRICS.redis_hits[&kind].inc();
+                        self.insert_into_memory(
+                            key.clone(),
+                            CacheEntry::from_json(response.clone(), &bytes),
+                        );
+                        return Some(response);
+                    }
+                    Err(err) => {
+                        tracing::warn!("Failed deserializing cached {kind:?} response: {err}");
+                        RESPONSE_CACHE_METRICS.redis_errors.inc();
+                    }
+                },
+                Ok(None) => { /* Not cached */ }
+                Err(err) => {
+                    tracing::warn!("Failed getting cached response from Redis: {err:#}");
+                    RESPONSE_CACHE_METRICS.redis_errors.inc();
+                }
+            }
+        }
+        RESPONSE_CACHE_METRICS.misses[&kind].inc();
+        None
+    }
+
+    fn insert_into_memory(&self, key: CacheKey, entry: CacheEntry) {
+        self.memory.insert(key, entry);
+        RESPONSE_CACHE_METRICS
+            .weighted_size
+            .set(self.memory.weighted_size());
+    }
+
+    fn insert(&self, key: CacheKey, entry: CacheEntry) {
+        if let Some(redis) = &self.redis {
+            match entry.response.to_json() {
+                Ok(bytes) => {
+                    let (redis, key, ttl) = (redis.clone(), key.clone(), self.ttl);
+                    // Redis writes are best-effort and shouldn't delay responses.
+                    tokio::spawn(async move {
+                        if let Err(err) = redis.set(&key, bytes, ttl).await {
+                            tracing::warn!("Failed caching response in Redis: {err:#}");
+                            RESPONSE_CACHE_METRICS.redis_errors.inc();
+                        }
+                    });
+                }
+                Err(err) => {
+                    tracing::warn!("Failed serializing response for Redis: {err}");
+                    RESPONSE_CACHE_METRICS.redis_errors.inc();
+                }
+            }
+        }
+        self.insert_into_memory(key, entry);
+    }
+
+    pub(crate) async fn get_block(
+        &self,
+        hash: H256,
+        full_transactions: bool,
+    ) -> Option<Block<TransactionVariant>> {
+        let key = CacheKey::BlockByHash {
+            hash,
+            full_transactions,
+        };
+        match self.get(&key).await? {
+            CachedResponse::Block(block) => Some(block),
+            _ => None,
+        }
+    }
+
+    /// Inserts a block with the specified hash. The block must be final.
+    pub(crate) fn insert_block(
+        &self,
+        hash: H256,
+        full_transactions: bool,
+        block: &Block<TransactionVariant>,
+    ) {
+        let key = CacheKey::BlockByHash {
+            hash,
+            full_transactions,
+        };
+        let entry = CacheEntry::new(CachedResponse::Block(block.clone()), block);
+        self.insert(key, entry);
+    }
+
+    pub(crate) async fn get_transaction_receipt(&self, hash: H256) -> Option<TransactionReceipt> {
+        match self.get(&CacheKey::TransactionReceipt(hash)).await? {
+            CachedResponse::TransactionReceipt(receipt) => Some(receipt),
+            _ => None,
+        }
+    }
+
+    /// Inserts a transaction receipt. The block containing the transaction must be final.
+    pub(crate) fn insert_transaction_receipt(&self, receipt: &TransactionReceipt) {
+        let key = CacheKey::TransactionReceipt(receipt.transaction_hash);
+        let entry = CacheEntry::new(CachedResponse::TransactionReceipt(receipt.clone()), receipt);
+        self.insert(key, entry);
+    }
+
+    pub(crate) async fn get_l1_batch_details(
+        &self,
+        number: L1BatchNumber,
+    ) -> Option<L1BatchDetails> {
+        match self.get(&CacheKey::L1BatchDetails(number)).await? {
+            CachedResponse::L1BatchDetails(details) => Some(details),
+            _ => None,
+        }
+    }
+
+    /// Inserts L1 batch details. The L1 batch must be executed on L1.
+    pub(crate) fn insert_l1_batch_details(&self, details: &L1BatchDetails) {
+        let key = CacheKey::L1BatchDetails(details.number);
+        let entry = CacheEntry::new(CachedResponse::L1BatchDetails(details.clone()), details);
+        self.insert(key, entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use assert_matches::assert_matches;
+    use zksync_types::api;
+
+    use super::*;
+
+    fn mock_receipt(hash: H256) -> TransactionReceipt {
+        TransactionReceipt {
+            transaction_hash: hash,
+            ..TransactionReceipt::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn caching_responses() {
+        let cache = ResponseCache::new(1 << 20, Duration::from_secs(60));
+        let hash = H256::repeat_byte(1);
+        assert!(cache.get_block(hash, false).await.is_none());
+
+        let block = api::Block {
+            hash,
+            ..api::Block::default()
+        };
+        cache.insert_block(hash, false, &block);
+        let cached_block = cache.get_block(hash, false).await.unwrap();
+        assert_eq!(cached_block.hash, hash);
+        // Blocks with full transactions are cached separately.
+        assert!(cache.get_block(hash, true).await.is_none());
+        // Keys for different response kinds don't collide.
+        assert!(cache.get_transaction_receipt(hash).await.is_none());
+
+        cache.insert_transaction_receipt(&mock_receipt(hash));
+        let receipt = cache.get_transaction_receipt(hash).await.unwrap();
+        assert_eq!(receipt.transaction_hash, hash);
+        assert!(cache.get_block(hash, false).await.is_some());
+    }
+
+    #[test]
+    fn redis_keys_are_distinct() {
+        let hash = H256::repeat_byte(1);
+        let keys = [
+            CacheKey::BlockByHash {
+                hash,
+                full_transactions: false,
+            },
+            CacheKey::BlockByHash {
+                hash,
+                full_transactions: true,
+            },
+            CacheKey::TransactionReceipt(hash),
+            CacheKey::L1BatchDetails(L1BatchNumber(1)),
+        ];
+        let redis_keys: HashSet<_> = keys.iter().map(|key| key.redis_key("test:270")).collect();
+        assert_eq!(redis_keys.len(), keys.len());
+        assert!(redis_keys.iter().all(|key| key.starts_with("test:270:")));
+    }
+
+    #[test]
+    fn cached_responses_roundtrip_via_json() {
+        let hash = H256::repeat_byte(1);
+        let response = CachedResponse::TransactionReceipt(mock_receipt(hash));
+        let bytes = response.to_json().unwrap();
+        let restored =
+            CachedResponse::from_json(CachedResponseKind::TransactionReceipt, &bytes).unwrap();
+        assert_matches!(
+            restored,
+            CachedResponse::TransactionReceipt(receipt) if receipt.transaction_hash == hash
+        );
+    }
+}
@@ -0,0 +1,184 @@
+//! Sampling of the slowest JSON-RPC calls.
+
+use std::{
+    collections::HashMap,
+    num::NonZeroUsize,
+    sync::Mutex,
+    time::{Duration, SystemTime},
+};
+
+use chrono::{DateTime, Utc};
+use zksync_types::api;
+
+use crate::web3::backend_jsonrpsee::MethodMetadata;
+
+/// Redacts call params, so that sampled calls don't leak potentially sensitive data (e.g., signed transactions
+/// or calldata). Only the params structure is retained: object keys, array lengths, booleans and nulls,
+/// and lengths of strings.
+fn redact_params(params: serde_json::Value) -> serde_json::Value {
+    use serde_json::Value;
+
+    match params {
+        Value::Null | Value::Bool(_) => params,
+        Value::Number(_) => Value::String("[redacted]".to_owned()),
+        Value::String(s) => Value::String(format!("[redacted; {} bytes]", s.len())),
+        Value::Array(items) => Value::Array(items.into_iter().map(redact_params).collect()),
+        Value::Object(fields) => Value::Object(
+            fields
+                .into_iter()
+                .map(|(key, value)| (key, redact_params(value)))
+                .collect(),
+        ),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct SampledCall {
+    /// Redacted call params.
+    params: serde_json::Value,
+    started_at: SystemTime,
+    latency: Duration,
+    db_time: Duration,
+}
+
+impl SampledCall {
+    fn to_info(&self, method: &str) -> api::SlowCallInfo {
+        api::SlowCallInfo {
+            method: method.to_owned(),
+            params: self.params.clone(),
+            started_at: DateTime::<Utc>::from(self.started_at),
+            latency_ms: self.latency.as_millis() as u64,
+            db_time_ms: self.db_time.as_millis() as u64,
+        }
+    }
+}
+
+/// Bounded buffer retaining the slowest calls (together with their redacted params) for each JSON-RPC method.
+/// The sampler can be shared among multiple API servers, and is intended to be queried via a private admin endpoint.
+#[derive(Debug)]
+pub struct SlowCallsSampler {
+    calls_per_method: NonZeroUsize,
+    /// Calls for each method sorted by decreasing latency.
+    calls: Mutex<HashMap<&'static str, Vec<SampledCall>>>,
+}
+
+impl SlowCallsSampler {
+    pub fn new(calls_per_method: NonZeroUsize) -> Self {
+        Self {
+            calls_per_method,
+            calls: Mutex::default(),
+        }
+    }
+
+    pub(crate) fn observe(&self, meta: &MethodMetadata, params: Option<Box<str>>) {
+        if meta.name.is_empty() {
+            return; // Unknown method
+        }
+
+        let latency = meta.started_at.elapsed();
+        let mut calls = self.calls.lock().expect("slow calls sampler is poisoned");
+        let method_calls = calls.entry(meta.name).or_default();
+        let pos = method_calls.partition_point(|call| call.latency >= latency);
+        if pos >= self.calls_per_method.get() {
+            return; // The call is faster than all sampled calls
+        }
+
+        method_calls.truncate(self.calls_per_method.get() - 1);
+        let params = params.map_or(serde_json::Value::Null, |raw| {
+            // Params were successfully parsed by `jsonrpsee`, so they should be valid JSON.
+            serde_json::from_str(&raw).unwrap_or_else(|_| serde_json::Value::String(raw.into()))
+        });
+        let call = SampledCall {
+            params: redact_params(params),
+            started_at: SystemTime::now() - latency,
+            latency,
+            db_time: meta.db_time,
+        };
+        method_calls.insert(pos, call);
+    }
+
+    /// Returns sampled calls for the specified method, or for all methods if `method` is `None`.
+    /// Calls are sorted by decreasing latency for each method.
+    pub fn calls(&self, method: Option<&str>) -> Vec<api::SlowCallInfo> {
+        let calls = self.calls.lock().expect("slow calls sampler is poisoned");
+        if let Some(method) = method {
+            let method_calls = calls.get(method).map(Vec::as_slice).unwrap_or_default();
+            return method_calls
+                .iter()
+                .map(|call| call.to_info(method))
+                .collect();
+        }
+
+        let mut methods: Vec<_> = calls.keys().copied().collect();
+        methods.sort_unstable();
+        methods
+            .into_iter()
+            .flat_map(|method| calls[method].iter().map(move |call| call.to_info(method)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    fn call_meta(name: &'static str, latency: Duration) -> MethodMetadata {
+        let mut meta = MethodMetadata::new(name);
+        meta.started_at = Instant::now() - latency;
+        meta
+    }
+
+    #[test]
+    fn sampling_slow_calls() {
+        let sampler = SlowCallsSampler::new(NonZeroUsize::new(2).unwrap());
+        let params = [r#"["0x1"]"#, r#"["0x123"]"#, r#"["0x12"]"#, r#"["0x"]"#];
+        for (latency_ms, params) in [10, 30, 20, 5].into_iter().zip(params) {
+            let meta = call_meta("eth_call", Duration::from_millis(latency_ms));
+            sampler.observe(&meta, Some(params.into()));
+        }
+        sampler.observe(&call_meta("eth_chainId", Duration::from_millis(1)), None);
+        sampler.observe(&call_meta("", Duration::from_secs(1)), None);
+
+        let calls = sampler.calls(Some("eth_call"));
+        let params: Vec<_> = calls.iter().map(|call| call.params.clone()).collect();
+        assert_eq!(
+            params,
+            [
+                serde_json::json!(["[redacted; 5 bytes]"]),
+                serde_json::json!(["[redacted; 4 bytes]"])
+            ]
+        );
+        assert!(calls[0].latency_ms >= 30, "{calls:?}");
+
+        let all_calls = sampler.calls(None);
+        let methods: Vec<_> = all_calls.iter().map(|call| call.method.as_str()).collect();
+        assert_eq!(methods, ["eth_call", "eth_call", "eth_chainId"]);
+        assert_eq!(all_calls[2].params, serde_json::Value::Null);
+
+        assert!(sampler.calls(Some("eth_getLogs")).is_empty());
+    }
+
+    #[test]
+    fn redacting_params() {
+        let params = serde_json::json!([
+            { "from": "0x0102", "data": "0xdeadbeef", "gas": 21_000, "topics": [null, "0x01"] },
+            "latest",
+            true,
+        ]);
+        assert_eq!(
+            redact_params(params),
+            serde_json::json!([
+                {
+                    "from": "[redacted; 6 bytes]",
+                    "data": "[redacted; 10 bytes]",
+                    "gas": "[redacted]",
+                    "topics": [null, "[redacted; 4 bytes]"],
+                },
+                "[redacted; 6 bytes]",
+                true,
+            ])
+        );
+    }
+}
@@ -2,7 +2,7 @@ use std::{
     future::Future,
     sync::{
         atomic::{AtomicU32, Ordering},
-        Arc,
+        Arc, RwLock,
     },
     time::{Duration, Instant},
 };
@@ -18,21 +18,25 @@ use zksync_config::{
 };
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError};
 use zksync_metadata_calculator::api_server::TreeApiClient;
+use zksync_node_block_data_archive::{ArchivedL2Block, BlockDataArchiveReader};
 use zksync_node_sync::SyncState;
 use zksync_types::{
     api, commitment::L1BatchCommitmentMode, l2::L2Tx, transaction_request::CallRequest, Address,
     L1BatchNumber, L1ChainId, L2BlockNumber, L2ChainId, H256, U256, U64,
 };
+use zksync_utils::{h256_to_u256, u256_to_h256};
 use zksync_web3_decl::{error::Web3Error, types::Filter};
 
 use super::{
     backend_jsonrpsee::MethodTracer,
+    fee_history::FeeHistoryCache,
     mempool_cache::MempoolCache,
     metrics::{FilterType, FILTER_METRICS},
-    TypedFilter,
+    response_cache::ResponseCache,
+    FiltersStorage, TypedFilter,
 };
 use crate::{
-    execution_sandbox::{BlockArgs, BlockArgsError, BlockStartInfo},
+    execution_sandbox::{ApiTracerRegistry, BlockArgs, BlockArgsError, BlockStartInfo},
     tx_sender::{tx_sink::TxSink, TxSender},
 };
 
@@ -110,6 +114,7 @@ pub struct InternalApiConfig {
     pub fee_history_limit: u64,
     pub base_token_address: Option<Address>,
     pub filters_disabled: bool,
+    pub logs_pagination_enabled: bool,
     pub dummy_verifier: bool,
     pub l1_batch_commit_data_generator_mode: L1BatchCommitmentMode,
 }
@@ -153,12 +158,56 @@ impl InternalApiConfig {
             fee_history_limit: web3_config.fee_history_limit(),
             base_token_address: contracts_config.base_token_addr,
             filters_disabled: web3_config.filters_disabled,
+            logs_pagination_enabled: web3_config.logs_pagination_enabled,
             dummy_verifier: genesis_config.dummy_verifier,
             l1_batch_commit_data_generator_mode: genesis_config.l1_batch_commit_data_generator_mode,
         }
     }
 }
 
+/// Values of [`InternalApiConfig`] that can change while the API server is running, e.g. bridge addresses
+/// updated via governance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UpdatableApiConfigValues {
+    pub bridge_addresses: api::BridgeAddresses,
+    pub l2_testnet_paymaster_addr: Option<Address>,
+}
+
+impl From<&InternalApiConfig> for UpdatableApiConfigValues {
+    fn from(config: &InternalApiConfig) -> Self {
+        Self {
+            bridge_addresses: config.bridge_addresses.clone(),
+            l2_testnet_paymaster_addr: config.l2_testnet_paymaster_addr,
+        }
+    }
+}
+
+/// Thread-safe handle to [`UpdatableApiConfigValues`] that can be shared among API servers.
+#[derive(Debug, Clone)]
+pub struct UpdatableApiConfig(Arc<RwLock<UpdatableApiConfigValues>>);
+
+impl UpdatableApiConfig {
+    pub fn new(values: UpdatableApiConfigValues) -> Self {
+        Self(Arc::new(RwLock::new(values)))
+    }
+
+    /// Returns the current values.
+    pub fn get(&self) -> UpdatableApiConfigValues {
+        self.0.read().expect("config is poisoned").clone()
+    }
+
+    /// Updates values. Returns `true` if the values have changed.
+    pub fn update(&self, values: UpdatableApiConfigValues) -> bool {
+        let mut current = self.0.write().expect("config is poisoned");
+        if *current == values {
+            false
+        } else {
+            *current = values;
+            true
+        }
+    }
+}
+
 /// Thread-safe updatable information about the last sealed L2 block number.
 ///
 /// The information may be temporarily outdated and thus should only be used where this is OK
@@ -236,16 +285,26 @@ impl SealedL2BlockNumber {
 #[derive(Debug, Clone)]
 pub(crate) struct RpcState {
     pub(super) current_method: Arc<MethodTracer>,
-    pub(super) installed_filters: Option<Arc<Mutex<Filters>>>,
+    pub(super) installed_filters: Option<InstalledFilters>,
     pub(super) connection_pool: ConnectionPool<Core>,
     pub(super) tree_api: Option<Arc<dyn TreeApiClient>>,
+    /// Archive used to serve pruned block data, if any.
+    pub(super) block_data_archive: Option<Arc<BlockDataArchiveReader>>,
     pub(super) tx_sender: TxSender,
     pub(super) sync_state: Option<SyncState>,
     pub(super) api_config: InternalApiConfig,
+    /// Values of `api_config` that can be updated while the server is running. These values should be used
+    /// instead of the corresponding `api_config` fields.
+    pub(super) updatable_config: UpdatableApiConfig,
     /// Number of the first locally available L2 block / L1 batch. May differ from 0 if the node state was recovered
     /// from a snapshot.
     pub(super) start_info: BlockStartInfo,
     pub(super) mempool_cache: Option<MempoolCache>,
+    /// Cache for responses that don't change once the corresponding block is final, if any.
+    pub(super) response_cache: Option<ResponseCache>,
+    /// Custom tracers that can be selected in `debug_trace*` methods.
+    pub(super) tracer_registry: ApiTracerRegistry,
+    pub(super) fee_history_cache: FeeHistoryCache,
     pub(super) last_sealed_l2_block: SealedL2BlockNumber,
 }
 
@@ -272,16 +331,25 @@ impl RpcState {
         self.tx_sender.0.tx_sink.as_ref()
     }
 
-    /// Acquires a DB connection mapping possible errors.
+    /// Acquires a read-only DB connection mapping possible errors. The connection may be served by a read replica
+    /// of the database, so it must not be used for writes.
     // `track_caller` is necessary to correctly record call location. `async fn`s don't support it yet,
     // thus manual de-sugaring.
     #[track_caller]
     pub(crate) fn acquire_connection(
         &self,
     ) -> impl Future<Output = Result<Connection<'_, Core>, Web3Error>> + '_ {
-        self.connection_pool
-            .connection_tagged("api")
-            .map_err(|err| err.generalize().into())
+        let is_overloaded = self.connection_pool.is_overloaded();
+        let connection = self
+            .connection_pool
+            .read_connection_tagged("api")
+            .map_err(|err| err.generalize().into());
+        async move {
+            if is_overloaded {
+                return Err(Web3Error::ServerOverloaded);
+            }
+            connection.await
+        }
     }
 
     /// Resolves the specified block ID to a block number, which is guaranteed to be present in the node storage.
@@ -299,6 +367,38 @@ impl RpcState {
             .ok_or(Web3Error::NoBlock)
     }
 
+    /// Checks that the specified block is not pruned. If the block is pruned and the block data archive is configured,
+    /// returns the archived block data instead of the pruning error. Returns `Ok(None)` if the block is not pruned.
+    pub(crate) async fn ensure_not_pruned_or_archived(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        block: api::BlockId,
+    ) -> Result<Option<ArchivedL2Block>, Web3Error> {
+        let err = match self.start_info.ensure_not_pruned(block, connection).await {
+            Ok(()) => return Ok(None),
+            Err(err @ Web3Error::PrunedBlock(_)) => err,
+            Err(err) => return Err(err),
+        };
+        let Some(archive) = &self.block_data_archive else {
+            return Err(err);
+        };
+        let number = match block {
+            api::BlockId::Number(api::BlockNumber::Number(number)) => {
+                let Ok(number) = u32::try_from(number) else {
+                    return Err(err);
+                };
+                L2BlockNumber(number)
+            }
+            api::BlockId::Number(api::BlockNumber::Earliest) => L2BlockNumber(0),
+            _ => return Err(err),
+        };
+        archive
+            .get_l2_block(connection, number)
+            .await?
+            .map(Some)
+            .ok_or(err)
+    }
+
     /// Resolves the specified block ID to a block number, which is **not** guaranteed to be present in the node storage.
     /// Returns `None` if the block is known to not be present in the storage (e.g., it's a "finalized" block ID and no blocks
     /// were finalized yet).
@@ -325,6 +425,21 @@ impl RpcState {
         }
     }
 
+    /// Checks whether the specified L2 block is final, i.e., its L1 batch is executed on L1.
+    pub(crate) async fn is_l2_block_final(
+        &self,
+        connection: &mut Connection<'_, Core>,
+        block_number: L2BlockNumber,
+    ) -> Result<bool, Web3Error> {
+        let finalized_block_id = api::BlockId::Number(api::BlockNumber::Finalized);
+        let last_finalized_block = connection
+            .blocks_web3_dal()
+            .resolve_block_id(finalized_block_id)
+            .await
+            .map_err(DalError::generalize)?;
+        Ok(last_finalized_block.is_some_and(|number| block_number <= number))
+    }
+
     pub(crate) async fn resolve_block_args(
         &self,
         connection: &mut Connection<'_, Core>,
@@ -518,6 +633,188 @@ impl Filters {
     }
 }
 
+/// Storage of installed filters used by an API server.
+#[derive(Debug, Clone)]
+pub(crate) enum InstalledFilters {
+    /// Filters are stored in memory of this server.
+    InMemory(Arc<Mutex<Filters>>),
+    /// Filters are stored in Postgres and are shared with other API servers using the same database.
+    /// Filters not accessed for longer than `ttl` are considered expired. If `filters_limit` is set,
+    /// least recently used filters are removed once the limit is exceeded, similar to in-memory storage.
+    Postgres {
+        pool: ConnectionPool<Core>,
+        ttl: Duration,
+        filters_limit: Option<usize>,
+    },
+}
+
+impl InstalledFilters {
+    pub fn new(
+        storage: FiltersStorage,
+        filters_limit: Option<usize>,
+        pool: ConnectionPool<Core>,
+    ) -> Self {
+        match storage {
+            FiltersStorage::Memory => {
+                Self::InMemory(Arc::new(Mutex::new(Filters::new(filters_limit))))
+            }
+            FiltersStorage::Postgres { ttl } => Self::Postgres {
+                pool,
+                ttl,
+                filters_limit,
+            },
+        }
+    }
+
+    async fn connection(pool: &ConnectionPool<Core>) -> Result<Connection<'_, Core>, Web3Error> {
+        pool.connection_tagged("api")
+            .await
+            .map_err(|err| err.generalize().into())
+    }
+
+    /// Adds a filter and returns its key.
+    pub async fn add(&self, filter: TypedFilter) -> Result<U256, Web3Error> {
+        let (pool, filters_limit) = match self {
+            Self::InMemory(filters) => return Ok(filters.lock().await.add(filter)),
+            Self::Postgres {
+                pool,
+                filters_limit,
+                ..
+            } => (pool, *filters_limit),
+        };
+
+        let serialized_filter =
+            serde_json::to_value(&filter).context("failed serializing filter")?;
+        let mut connection = Self::connection(pool).await?;
+        let id = loop {
+            let id = H256::random();
+            let is_inserted = connection
+                .api_filters_dal()
+                .insert_filter(id, &serialized_filter)
+                .await
+                .map_err(DalError::generalize)?;
+            if is_inserted {
+                break id;
+            }
+        };
+
+        if let Some(filters_limit) = filters_limit {
+            let removed_count = connection
+                .api_filters_dal()
+                .remove_least_recently_used_filters(filters_limit)
+                .await
+                .map_err(DalError::generalize)?;
+            if removed_count > 0 {
+                tracing::debug!("Removed {removed_count} least recently used filters");
+            }
+        }
+        Ok(h256_to_u256(id))
+    }
+
+    /// Retrieves a filter. For in-memory storage, also updates filter stats.
+    pub async fn get_and_update_stats(
+        &self,
+        index: U256,
+    ) -> Result<Option<TypedFilter>, Web3Error> {
+        let (pool, ttl) = match self {
+            Self::InMemory(filters) => return Ok(filters.lock().await.get_and_update_stats(index)),
+            Self::Postgres { pool, ttl } => (pool, *ttl),
+        };
+
+        let mut connection = Self::connection(pool).await?;
+        let serialized_filter = connection
+            .api_filters_dal()
+            .get_filter(u256_to_h256(index), ttl)
+            .await
+            .map_err(DalError::generalize)?;
+        let Some(serialized_filter) = serialized_filter else {
+            return Ok(None);
+        };
+        let filter = serde_json::from_value(serialized_filter)
+            .with_context(|| format!("failed deserializing filter {index:#x}"))?;
+        Ok(Some(filter))
+    }
+
+    /// Updates a filter previously retrieved as `prev_filter`. Returns `false` if the filter was removed
+    /// or concurrently updated by another API server, in which case the filter should be re-retrieved.
+    /// In-memory filters are always updated (if they exist).
+    pub async fn update(
+        &self,
+        index: U256,
+        prev_filter: &TypedFilter,
+        new_filter: TypedFilter,
+    ) -> Result<bool, Web3Error> {
+        let pool = match self {
+            Self::InMemory(filters) => {
+                filters.lock().await.update(index, new_filter);
+                return Ok(true);
+            }
+            Self::Postgres { pool, .. } => pool,
+        };
+
+        let serialized_prev_filter =
+            serde_json::to_value(prev_filter).context("failed serializing filter")?;
+        let serialized_filter =
+            serde_json::to_value(&new_filter).context("failed serializing filter")?;
+        let mut connection = Self::connection(pool).await?;
+        Ok(connection
+            .api_filters_dal()
+            .update_filter(
+                u256_to_h256(index),
+                &serialized_prev_filter,
+                &serialized_filter,
+            )
+            .await
+            .map_err(DalError::generalize)?)
+    }
+
+    /// Removes a filter. Returns `false` if the filter doesn't exist.
+    pub async fn remove(&self, index: U256) -> Result<bool, Web3Error> {
+        let pool = match self {
+            Self::InMemory(filters) => return Ok(filters.lock().await.remove(index)),
+            Self::Postgres { pool, .. } => pool,
+        };
+
+        let mut connection = Self::connection(pool).await?;
+        Ok(connection
+            .api_filters_dal()
+            .remove_filter(u256_to_h256(index))
+            .await
+            .map_err(DalError::generalize)?)
+    }
+}
+
+/// Periodically removes expired filters stored in Postgres.
+pub(super) async fn prune_expired_filters(
+    pool: ConnectionPool<Core>,
+    ttl: Duration,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    const MAX_PRUNING_INTERVAL: Duration = Duration::from_secs(60);
+
+    let pruning_interval = ttl.min(MAX_PRUNING_INTERVAL);
+    while !*stop_receiver.borrow() {
+        let mut connection = pool.connection_tagged("api").await?;
+        let removed_count = connection
+            .api_filters_dal()
+            .remove_expired_filters(ttl)
+            .await?;
+        drop(connection);
+        if removed_count > 0 {
+            tracing::debug!("Removed {removed_count} expired filters");
+        }
+
+        if tokio::time::timeout(pruning_interval, stop_receiver.changed())
+            .await
+            .is_ok()
+        {
+            break;
+        }
+    }
+    tracing::debug!("Stopping expired filters pruning");
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use chrono::NaiveDateTime;
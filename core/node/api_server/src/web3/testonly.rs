@@ -101,6 +101,31 @@ pub async fn spawn_http_server(
     tx_executor: MockTransactionExecutor,
     method_tracer: Arc<MethodTracer>,
     stop_receiver: watch::Receiver<bool>,
+) -> ApiServerHandles {
+    spawn_http_server_with_block_data_archive(
+        api_config,
+        pool,
+        tx_executor,
+        method_tracer,
+        None,
+        None,
+        None,
+        stop_receiver,
+    )
+    .await
+}
+
+/// Spawns an HTTP server optionally serving pruned block data from the specified archive,
+/// caching final responses in the specified cache and serving proofs from the specified tree API.
+pub(crate) async fn spawn_http_server_with_block_data_archive(
+    api_config: InternalApiConfig,
+    pool: ConnectionPool<Core>,
+    tx_executor: MockTransactionExecutor,
+    method_tracer: Arc<MethodTracer>,
+    block_data_archive: Option<Arc<BlockDataArchiveReader>>,
+    response_cache: Option<ResponseCache>,
+    tree_api: Option<Arc<dyn TreeApiClient>>,
+    stop_receiver: watch::Receiver<bool>,
 ) -> ApiServerHandles {
     spawn_server(
         ApiTransportLabel::Http,
@@ -109,6 +134,34 @@ pub async fn spawn_http_server(
         None,
         tx_executor,
         method_tracer,
+        FiltersStorage::Memory,
+        block_data_archive,
+        response_cache,
+        tree_api,
+        stop_receiver,
+    )
+    .await
+    .0
+}
+
+/// Spawns an HTTP server with the specified storage for installed filters.
+pub(crate) async fn spawn_http_server_with_filters_storage(
+    api_config: InternalApiConfig,
+    pool: ConnectionPool<Core>,
+    filters_storage: FiltersStorage,
+    stop_receiver: watch::Receiver<bool>,
+) -> ApiServerHandles {
+    spawn_server(
+        ApiTransportLabel::Http,
+        api_config,
+        pool,
+        None,
+        MockTransactionExecutor::default(),
+        Arc::default(),
+        filters_storage,
+        None,
+        None,
+        None,
         stop_receiver,
     )
     .await
@@ -128,6 +181,10 @@ pub async fn spawn_ws_server(
         websocket_requests_per_minute_limit,
         MockTransactionExecutor::default(),
         Arc::default(),
+        FiltersStorage::Memory,
+        None,
+        None,
+        None,
         stop_receiver,
     )
     .await
@@ -140,6 +197,10 @@ async fn spawn_server(
     websocket_requests_per_minute_limit: Option<NonZeroU32>,
     tx_executor: MockTransactionExecutor,
     method_tracer: Arc<MethodTracer>,
+    filters_storage: FiltersStorage,
+    block_data_archive: Option<Arc<BlockDataArchiveReader>>,
+    response_cache: Option<ResponseCache>,
+    tree_api: Option<Arc<dyn TreeApiClient>>,
     stop_receiver: watch::Receiver<bool>,
 ) -> (ApiServerHandles, mpsc::UnboundedReceiver<PubSubEvent>) {
     let (tx_sender, vm_barrier) =
@@ -149,7 +210,7 @@ async fn spawn_server(
     let mut namespaces = Namespace::DEFAULT.to_vec();
     namespaces.extend([Namespace::Debug, Namespace::Snapshots]);
 
-    let server_builder = match transport {
+    let mut server_builder = match transport {
         ApiTransportLabel::Http => ApiBuilder::jsonrpsee_backend(api_config, pool).http(0),
         ApiTransportLabel::Ws => {
             let mut builder = ApiBuilder::jsonrpsee_backend(api_config, pool)
@@ -162,12 +223,22 @@ async fn spawn_server(
             builder
         }
     };
+    if let Some(archive) = block_data_archive {
+        server_builder = server_builder.with_block_data_archive(archive);
+    }
+    if let Some(cache) = response_cache {
+        server_builder = server_builder.with_response_cache(cache);
+    }
+    if let Some(tree_api) = tree_api {
+        server_builder = server_builder.with_tree_api(tree_api);
+    }
     let server_handles = server_builder
         .with_polling_interval(POLL_INTERVAL)
         .with_tx_sender(tx_sender)
         .with_vm_barrier(vm_barrier)
         .with_pub_sub_events(pub_sub_events_sender)
         .with_method_tracer(method_tracer)
+        .with_filters_storage(filters_storage)
         .enable_api_namespaces(namespaces)
         .build()
         .expect("Unable to build API server")
@@ -1,12 +1,16 @@
 //! Tests for the `debug` Web3 namespace.
 
-use zksync_types::{tx::TransactionExecutionResult, vm_trace::Call, BOOTLOADER_ADDRESS};
+use multivm::interface::ExecutionResult;
+use zksync_types::{
+    tx::TransactionExecutionResult, vm_trace::Call, Transaction, BOOTLOADER_ADDRESS,
+};
 use zksync_web3_decl::{
     client::{DynClient, L2},
     namespaces::DebugNamespaceClient,
 };
 
 use super::*;
+use crate::execution_sandbox::BlockArgs;
 
 fn execute_l2_transaction_with_traces(index_in_block: u8) -> TransactionExecutionResult {
     let first_call_trace = Call {
@@ -61,7 +65,12 @@ impl HttpTest for TraceBlockTest {
 
             assert_eq!(block_traces.len(), tx_results.len()); // equals to the number of transactions in the block
             for (trace, tx_result) in block_traces.iter().zip(&tx_results) {
-                let api::ResultDebugCall { result } = trace;
+                let api::ResultDebugTrace {
+                    result: api::DebugTrace::Call(result),
+                } = trace
+                else {
+                    panic!("Unexpected trace: {trace:?}");
+                };
                 assert_eq!(result.from, Address::zero());
                 assert_eq!(result.to, BOOTLOADER_ADDRESS);
                 assert_eq!(result.gas, tx_result.transaction.gas_limit());
@@ -85,7 +94,7 @@ impl HttpTest for TraceBlockTest {
                 error.message().contains("Block") && error.message().contains("doesn't exist"),
                 "{error:?}"
             );
-            assert!(error.data().is_none(), "{error:?}");
+            assert_error_code(&error, "API-001");
         } else {
             panic!("Unexpected error: {error:?}");
         }
@@ -165,7 +174,7 @@ impl HttpTest for TraceBlockFlatTest {
                 error.message().contains("Block") && error.message().contains("doesn't exist"),
                 "{error:?}"
             );
-            assert!(error.data().is_none(), "{error:?}");
+            assert_error_code(&error, "API-001");
         } else {
             panic!("Unexpected error: {error:?}");
         }
@@ -204,6 +213,9 @@ impl HttpTest for TraceTransactionTest {
             .trace_transaction(tx_results[0].hash, None)
             .await?
             .context("no transaction traces")?;
+        let api::DebugTrace::Call(result) = result else {
+            panic!("Unexpected trace: {result:?}");
+        };
         assert_eq!(result.from, Address::zero());
         assert_eq!(result.to, BOOTLOADER_ADDRESS);
         assert_eq!(result.gas, tx_results[0].transaction.gas_limit());
@@ -258,3 +270,112 @@ impl HttpTest for TraceBlockTestWithSnapshotRecovery {
 async fn tracing_block_after_snapshot_recovery() {
     test_http_server(TraceBlockTestWithSnapshotRecovery).await;
 }
+
+#[derive(Debug)]
+struct TraceWithReplayTest;
+
+impl TraceWithReplayTest {
+    const BLOCK_NUMBER: L2BlockNumber = L2BlockNumber(1);
+}
+
+#[async_trait]
+impl HttpTest for TraceWithReplayTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        let responses = |_: &Transaction, block_args: &BlockArgs| {
+            assert_eq!(block_args.resolved_block_number(), Self::BLOCK_NUMBER);
+            ExecutionResult::Success {
+                output: b"output".to_vec(),
+            }
+        };
+        tx_executor.set_call_responses(responses);
+        tx_executor.set_tx_responses(responses);
+        tx_executor
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        // Transactions don't have persisted call traces, so they should be re-executed.
+        let tx_results = [
+            execute_l2_transaction(create_l2_transaction(1, 2)),
+            execute_l2_transaction(create_l2_transaction(1, 2)),
+        ];
+        let mut storage = pool.connection().await?;
+        store_l2_block(&mut storage, Self::BLOCK_NUMBER, &tx_results).await?;
+        drop(storage);
+
+        let block_traces = client
+            .trace_block_by_number(Self::BLOCK_NUMBER.0.into(), None)
+            .await?;
+        assert_eq!(block_traces.len(), tx_results.len());
+        for (trace, tx_result) in block_traces.iter().zip(&tx_results) {
+            let api::DebugTrace::Call(result) = &trace.result else {
+                panic!("Unexpected trace: {trace:?}");
+            };
+            assert_eq!(result.to, BOOTLOADER_ADDRESS);
+            assert_eq!(result.gas, tx_result.transaction.gas_limit());
+            assert_eq!(result.output.0, b"output");
+            assert!(result.calls.is_empty());
+        }
+
+        let result = client
+            .trace_transaction(tx_results[1].hash, None)
+            .await?
+            .context("no transaction traces")?;
+        let api::DebugTrace::Call(result) = result else {
+            panic!("Unexpected trace: {result:?}");
+        };
+        assert_eq!(result.output.0, b"output");
+
+        let struct_log_options = api::TracerConfig {
+            tracer: api::SupportedTracers::StructLogTracer,
+            tracer_config: api::CallTracerConfig::default(),
+        };
+        let result = client
+            .trace_transaction(tx_results[0].hash, Some(struct_log_options.clone()))
+            .await?
+            .context("no transaction traces")?;
+        let api::DebugTrace::StructLogs(result) = result else {
+            panic!("Unexpected trace: {result:?}");
+        };
+        assert!(!result.failed);
+        assert_eq!(result.return_value.0, b"output");
+
+        let block_traces = client
+            .trace_block_by_number(Self::BLOCK_NUMBER.0.into(), Some(struct_log_options))
+            .await?;
+        assert_eq!(block_traces.len(), tx_results.len());
+        for trace in &block_traces {
+            assert_matches!(&trace.result, api::DebugTrace::StructLogs(_));
+        }
+
+        let unknown_tracer_options = api::TracerConfig {
+            tracer: api::SupportedTracers::Custom("unknownTracer".to_owned()),
+            tracer_config: api::CallTracerConfig::default(),
+        };
+        let err = client
+            .trace_transaction(tx_results[0].hash, Some(unknown_tracer_options))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(err) = err {
+            assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+            assert!(err.message().contains("unknownTracer"), "{err:?}");
+        } else {
+            panic!("Unexpected error: {err:?}");
+        }
+
+        let missing_tx_trace = client
+            .trace_transaction(H256::repeat_byte(0xff), None)
+            .await?;
+        assert_eq!(missing_tx_trace, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn tracing_with_replay() {
+    test_http_server(TraceWithReplayTest).await;
+}
@@ -324,3 +324,90 @@ impl HttpTest for DisableFiltersTest {
 async fn disable_filters() {
     test_http_server(DisableFiltersTest).await;
 }
+
+#[tokio::test]
+async fn filters_shared_among_servers_via_postgres() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.connection().await.unwrap();
+    StorageInitialization::Genesis
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+    drop(storage);
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    let filters_storage = FiltersStorage::Postgres {
+        ttl: Duration::from_secs(60),
+    };
+    let mut clients = vec![];
+    let mut servers = vec![];
+    for _ in 0..2 {
+        let mut server_handles = crate::web3::testonly::spawn_http_server_with_filters_storage(
+            api_config.clone(),
+            pool.clone(),
+            filters_storage,
+            stop_receiver.clone(),
+        )
+        .await;
+        let local_addr = server_handles.wait_until_ready().await;
+        let client = Client::<L2>::http(format!("http://{local_addr}/").parse().unwrap())
+            .unwrap()
+            .build();
+        clients.push(client);
+        servers.push(server_handles);
+    }
+
+    let block_filter_id = clients[0].new_block_filter().await.unwrap();
+    let new_l2_block = store_l2_block(&mut pool.connection().await.unwrap(), L2BlockNumber(1), &[])
+        .await
+        .unwrap();
+
+    // The filter should be accessible via another server, and its state should be shared.
+    let block_filter_changes = clients[1]
+        .get_filter_changes(block_filter_id)
+        .await
+        .unwrap();
+    assert_matches!(
+        block_filter_changes,
+        FilterChanges::Hashes(hashes) if hashes == [new_l2_block.hash]
+    );
+    let block_filter_changes = clients[0]
+        .get_filter_changes(block_filter_id)
+        .await
+        .unwrap();
+    assert_matches!(block_filter_changes, FilterChanges::Hashes(hashes) if hashes.is_empty());
+
+    // Concurrent polling via different servers should return each change exactly once.
+    let new_l2_block = store_l2_block(&mut pool.connection().await.unwrap(), L2BlockNumber(2), &[])
+        .await
+        .unwrap();
+    let polls = (0..10).map(|i| clients[i % 2].get_filter_changes(block_filter_id));
+    let all_changes = futures::future::try_join_all(polls).await.unwrap();
+    let all_hashes: Vec<_> = all_changes
+        .into_iter()
+        .flat_map(|changes| match changes {
+            FilterChanges::Hashes(hashes) => hashes,
+            other => panic!("Unexpected filter changes: {other:?}"),
+        })
+        .collect();
+    assert_eq!(all_hashes, [new_l2_block.hash]);
+
+    let removed = clients[1].uninstall_filter(block_filter_id).await.unwrap();
+    assert!(removed);
+    let err = clients[0]
+        .get_filter_changes(block_filter_id)
+        .await
+        .unwrap_err();
+    assert_matches!(err, RpcError::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+
+    stop_sender.send_replace(true);
+    for server_handles in servers {
+        server_handles.shutdown().await;
+    }
+}
@@ -5,9 +5,11 @@ use std::{
     slice,
 };
 
+use anyhow::Context as _;
 use assert_matches::assert_matches;
 use async_trait::async_trait;
 use multivm::zk_evm_latest::ethereum_types::U256;
+use test_casing::test_casing;
 use tokio::sync::watch;
 use zksync_config::{
     configs::{
@@ -18,12 +20,15 @@ use zksync_config::{
     GenesisConfig,
 };
 use zksync_dal::{transactions_dal::L2TxSubmissionResult, Connection, ConnectionPool, CoreDal};
+use zksync_node_block_data_archive::BlockDataExporter;
 use zksync_node_genesis::{insert_genesis_batch, mock_genesis_config, GenesisParams};
 use zksync_node_test_utils::{
     create_l1_batch, create_l1_batch_metadata, create_l2_block, create_l2_transaction,
     l1_batch_metadata_to_commitment_artifacts, prepare_recovery_snapshot,
 };
+use zksync_object_store::{ObjectStore, ObjectStoreFactory};
 use zksync_types::{
+    aggregated_operations::AggregatedActionType,
     api,
     block::L2BlockHeader,
     fee::TransactionExecutionMetrics,
@@ -36,8 +41,8 @@ use zksync_types::{
         TransactionExecutionResult,
     },
     utils::{storage_key_for_eth_balance, storage_key_for_standard_token_balance},
-    AccountTreeId, Address, L1BatchNumber, Nonce, ProtocolVersionId, StorageKey, StorageLog,
-    VmEvent, H256, U64,
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, Nonce, ProtocolVersionId, StorageKey,
+    StorageLog, VmEvent, H256, U64,
 };
 use zksync_utils::u256_to_h256;
 use zksync_web3_decl::{
@@ -57,11 +62,12 @@ use zksync_web3_decl::{
 use super::*;
 use crate::{
     execution_sandbox::testonly::MockTransactionExecutor,
-    web3::testonly::{spawn_http_server, spawn_ws_server},
+    web3::testonly::{spawn_http_server_with_block_data_archive, spawn_ws_server},
 };
 
 mod debug;
 mod filters;
+mod proofs;
 mod snapshots;
 mod vm;
 mod ws;
@@ -129,6 +135,82 @@ async fn setting_response_size_limits() {
     server_handle.stop().ok();
 }
 
+#[tokio::test]
+async fn filtering_rpc_methods() {
+    let mut rpc_module = RpcModule::new(());
+    let method_names = [
+        "eth_call",
+        "eth_getLogs",
+        "debug_traceCall",
+        "debug_traceTransaction",
+        "zks_getProof",
+    ];
+    for method_name in method_names {
+        rpc_module
+            .register_method(method_name, |_params, _ctx| Ok::<_, ErrorObjectOwned>(()))
+            .unwrap();
+    }
+
+    let filter = MethodFilter {
+        enabled: None,
+        disabled: vec!["eth_getLogs".to_owned(), "debug_trace*".to_owned()],
+    };
+    filter.validate().unwrap();
+    let mut filtered_module = rpc_module.clone();
+    filter.apply(&mut filtered_module);
+    let remaining_methods: HashSet<_> = filtered_module.method_names().collect();
+    assert_eq!(
+        remaining_methods,
+        HashSet::from(["eth_call", "zks_getProof"])
+    );
+
+    let filter = MethodFilter {
+        enabled: Some(vec!["eth_*".to_owned(), "debug_traceCall".to_owned()]),
+        disabled: vec!["eth_getLogs".to_owned()],
+    };
+    filter.validate().unwrap();
+    let mut filtered_module = rpc_module.clone();
+    filter.apply(&mut filtered_module);
+    let remaining_methods: HashSet<_> = filtered_module.method_names().collect();
+    assert_eq!(
+        remaining_methods,
+        HashSet::from(["eth_call", "debug_traceCall"])
+    );
+
+    // Calling a filtered method should result in a "method not found" error.
+    let server = ServerBuilder::default()
+        .http_only()
+        .build((Ipv4Addr::LOCALHOST, 0))
+        .await
+        .unwrap();
+    let local_addr = server.local_addr().unwrap();
+    let server_handle = server.start(filtered_module);
+    let client = <HttpClient>::builder()
+        .build(format!("http://{local_addr}/"))
+        .unwrap();
+    client
+        .request::<(), _>("eth_call", rpc_params![])
+        .await
+        .unwrap();
+    let err = client
+        .request::<(), _>("eth_getLogs", rpc_params![])
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        ClientError::Call(err) if err.code() == ErrorCode::MethodNotFound.code()
+    );
+    server_handle.stop().ok();
+
+    for invalid_pattern in ["", "*eth_call", "eth_*_call"] {
+        let filter = MethodFilter {
+            enabled: None,
+            disabled: vec![invalid_pattern.to_owned()],
+        };
+        filter.validate().unwrap_err();
+    }
+}
+
 #[async_trait]
 trait HttpTest: Send + Sync {
     /// Prepares the storage before the server is started. The default implementation performs genesis.
@@ -144,6 +226,21 @@ trait HttpTest: Send + Sync {
         Arc::default()
     }
 
+    /// Returns the block data archive used to serve pruned blocks and transactions.
+    fn block_data_archive(&self) -> Option<Arc<BlockDataArchiveReader>> {
+        None
+    }
+
+    /// Returns the cache for final API responses.
+    fn response_cache(&self) -> Option<ResponseCache> {
+        None
+    }
+
+    /// Returns the Merkle tree API used to serve storage proofs.
+    fn tree_api(&self) -> Option<Arc<dyn TreeApiClient>> {
+        None
+    }
+
     async fn test(&self, client: &DynClient<L2>, pool: &ConnectionPool<Core>)
         -> anyhow::Result<()>;
 
@@ -151,6 +248,16 @@ trait HttpTest: Send + Sync {
     fn filters_disabled(&self) -> bool {
         false
     }
+
+    /// Overrides the `req_entities_limit` configuration parameter for HTTP server startup
+    fn req_entities_limit(&self) -> Option<usize> {
+        None
+    }
+
+    /// Overrides the `logs_pagination_enabled` configuration parameter for HTTP server startup
+    fn logs_pagination_enabled(&self) -> bool {
+        false
+    }
 }
 
 /// Storage initialization strategy.
@@ -228,11 +335,18 @@ async fn test_http_server(test: impl HttpTest) {
     let genesis = GenesisConfig::for_tests();
     let mut api_config = InternalApiConfig::new(&web3_config, &contracts_config, &genesis);
     api_config.filters_disabled = test.filters_disabled();
-    let mut server_handles = spawn_http_server(
+    api_config.logs_pagination_enabled = test.logs_pagination_enabled();
+    if let Some(limit) = test.req_entities_limit() {
+        api_config.req_entities_limit = limit;
+    }
+    let mut server_handles = spawn_http_server_with_block_data_archive(
         api_config,
         pool.clone(),
         test.transaction_executor(),
         test.method_tracer(),
+        test.block_data_archive(),
+        test.response_cache(),
+        test.tree_api(),
         stop_receiver,
     )
     .await;
@@ -479,6 +593,15 @@ impl HttpTest for BlockMethodsWithSnapshotRecovery {
     }
 }
 
+/// Checks that `error` reports the specified stable error code in its data.
+fn assert_error_code(error: &ErrorObjectOwned, expected_code: &str) {
+    let data = error
+        .data()
+        .unwrap_or_else(|| panic!("no error data: {error:?}"));
+    let data: serde_json::Value = serde_json::from_str(data.get()).unwrap();
+    assert_eq!(data, serde_json::json!({ "errorCode": expected_code }));
+}
+
 fn assert_pruned_block_error(error: &ClientError, first_retained_block: L2BlockNumber) {
     if let ClientError::Call(error) = error {
         assert_eq!(error.code(), ErrorCode::InvalidParams.code());
@@ -488,7 +611,7 @@ fn assert_pruned_block_error(error: &ClientError, first_retained_block: L2BlockN
                 .contains(&format!("first retained block is {first_retained_block}")),
             "{error:?}"
         );
-        assert!(error.data().is_none(), "{error:?}");
+        assert_error_code(error, "API-002");
     } else {
         panic!("Unexpected error: {error:?}");
     }
@@ -570,7 +693,7 @@ fn assert_pruned_l1_batch_error(error: &ClientError, first_retained_l1_batch: L1
             )),
             "{error:?}"
         );
-        assert!(error.data().is_none(), "{error:?}");
+        assert_error_code(error, "API-002");
     } else {
         panic!("Unexpected error: {error:?}");
     }
@@ -862,6 +985,203 @@ async fn transaction_receipts() {
     test_http_server(TransactionReceiptsTest).await;
 }
 
+#[derive(Debug)]
+struct ArchivedTransactionTest {
+    blob_store: Arc<dyn ObjectStore>,
+}
+
+#[async_trait]
+impl HttpTest for ArchivedTransactionTest {
+    fn block_data_archive(&self) -> Option<Arc<BlockDataArchiveReader>> {
+        let reader = BlockDataArchiveReader::new(self.blob_store.clone(), NonZeroUsize::MIN);
+        Some(Arc::new(reader))
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let tx_results = [execute_l2_transaction(create_l2_transaction(10, 200))];
+        let tx_hash = tx_results[0].hash;
+        store_l2_block(&mut storage, L2BlockNumber(1), &tx_results).await?;
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &tx_results)
+            .await?;
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Execute,
+                H256::repeat_byte(1),
+                chrono::Utc::now(),
+            )
+            .await?;
+
+        let expected_tx = client
+            .get_transaction_by_hash(tx_hash)
+            .await?
+            .context("no transaction")?;
+        let expected_receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .context("no receipt")?;
+
+        let exporter =
+            BlockDataExporter::new(pool.clone(), self.blob_store.clone(), L2ChainId::default());
+        while exporter.export_next_l1_batch().await?.is_some() {
+            // Export all executed L1 batches
+        }
+        storage
+            .pruning_dal()
+            .soft_prune_batches_range(L1BatchNumber(1), L2BlockNumber(1))
+            .await?;
+        storage
+            .pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(1), L2BlockNumber(1))
+            .await?;
+        // Sanity check: the transaction data is erased from Postgres.
+        let pruned_tx = storage
+            .transactions_web3_dal()
+            .get_transaction_by_hash(tx_hash, L2ChainId::default())
+            .await?;
+        assert!(pruned_tx.is_none());
+
+        let tx = client
+            .get_transaction_by_hash(tx_hash)
+            .await?
+            .context("pruned transaction is not loaded from the archive")?;
+        assert_eq!(tx.hash, expected_tx.hash);
+        assert_eq!(tx.block_number, expected_tx.block_number);
+        assert_eq!(tx.input, expected_tx.input);
+        let receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .context("pruned receipt is not loaded from the archive")?;
+        assert_eq!(receipt.transaction_hash, expected_receipt.transaction_hash);
+        assert_eq!(receipt.block_number, expected_receipt.block_number);
+
+        let missing_tx = client
+            .get_transaction_by_hash(H256::repeat_byte(0xff))
+            .await?;
+        assert!(missing_tx.is_none());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn archived_transactions() {
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    test_http_server(ArchivedTransactionTest { blob_store }).await;
+}
+
+#[derive(Debug)]
+struct ResponseCacheTest {
+    cache: ResponseCache,
+}
+
+#[async_trait]
+impl HttpTest for ResponseCacheTest {
+    fn response_cache(&self) -> Option<ResponseCache> {
+        Some(self.cache.clone())
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let tx_results = [execute_l2_transaction(create_l2_transaction(10, 200))];
+        let tx_hash = tx_results[0].hash;
+        let block_hash = store_l2_block(&mut storage, L2BlockNumber(1), &tx_results)
+            .await?
+            .hash;
+
+        // The block is not final, so neither it nor the receipt must be cached.
+        let block = client
+            .get_block_by_hash(block_hash, false)
+            .await?
+            .context("no block")?;
+        assert_eq!(block.l1_batch_number, None);
+        let receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .context("no receipt")?;
+        assert_eq!(receipt.l1_batch_number, None);
+        assert!(self.cache.get_block(block_hash, false).await.is_none());
+        assert!(self.cache.get_transaction_receipt(tx_hash).await.is_none());
+
+        // Seal the L1 batch; the block is still not final since the batch is not executed on L1.
+        seal_l1_batch(&mut storage, L1BatchNumber(1)).await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l1_batch(L1BatchNumber(1), &tx_results)
+            .await?;
+        let block = client
+            .get_block_by_hash(block_hash, false)
+            .await?
+            .context("no block")?;
+        assert_eq!(block.l1_batch_number, Some(1.into()));
+        let receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .context("no receipt")?;
+        assert_eq!(receipt.l1_batch_number, Some(1.into()));
+        assert!(self.cache.get_block(block_hash, false).await.is_none());
+        assert!(self.cache.get_transaction_receipt(tx_hash).await.is_none());
+
+        storage
+            .eth_sender_dal()
+            .insert_bogus_confirmed_eth_tx(
+                L1BatchNumber(1),
+                AggregatedActionType::Execute,
+                H256::repeat_byte(1),
+                chrono::Utc::now(),
+            )
+            .await?;
+        let final_block = client
+            .get_block_by_hash(block_hash, false)
+            .await?
+            .context("no block")?;
+        let final_receipt = client
+            .get_transaction_receipt(tx_hash)
+            .await?
+            .context("no receipt")?;
+        let cached_block = self
+            .cache
+            .get_block(block_hash, false)
+            .await
+            .context("final block is not cached")?;
+        assert_eq!(cached_block, final_block);
+        let cached_receipt = self
+            .cache
+            .get_transaction_receipt(tx_hash)
+            .await
+            .context("final receipt is not cached")?;
+        assert_eq!(cached_receipt, final_receipt);
+
+        // Blocks with full transactions are cached separately.
+        assert!(self.cache.get_block(block_hash, true).await.is_none());
+        let full_block = client
+            .get_block_by_hash(block_hash, true)
+            .await?
+            .context("no block")?;
+        assert_eq!(full_block.transactions.len(), 1);
+        assert!(self.cache.get_block(block_hash, true).await.is_some());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn response_cache_skips_non_final_blocks() {
+    let cache = ResponseCache::new(1 << 20, Duration::from_secs(60));
+    test_http_server(ResponseCacheTest { cache }).await;
+}
+
 #[derive(Debug)]
 struct AllAccountBalancesTest;
 
@@ -972,6 +1292,7 @@ impl HttpTest for RpcCallsTracingTest {
             Some(api::BlockId::Number(api::BlockNumber::Latest))
         );
         assert_eq!(calls[0].metadata.block_diff, Some(0));
+        assert!(calls[0].metadata.db_time > Duration::ZERO);
 
         let block_number = api::BlockNumber::Number(1.into());
         client.get_block_by_number(block_number, false).await?;
@@ -1054,6 +1375,68 @@ async fn tracing_rpc_calls() {
     test_http_server(RpcCallsTracingTest::default()).await;
 }
 
+#[derive(Debug)]
+struct SlowCallsSamplingTest {
+    sampler: Arc<SlowCallsSampler>,
+}
+
+impl SlowCallsSamplingTest {
+    fn new() -> Self {
+        let calls_per_method = NonZeroUsize::new(2).unwrap();
+        Self {
+            sampler: Arc::new(SlowCallsSampler::new(calls_per_method)),
+        }
+    }
+}
+
+#[async_trait]
+impl HttpTest for SlowCallsSamplingTest {
+    fn method_tracer(&self) -> Arc<MethodTracer> {
+        Arc::new(MethodTracer::with_slow_calls_sampler(self.sampler.clone()))
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        for block_number in [api::BlockNumber::Latest, api::BlockNumber::Number(1.into())] {
+            client.get_block_by_number(block_number, false).await?;
+        }
+        client.get_block_number().await?;
+
+        let calls = self.sampler.calls(Some("eth_getBlockByNumber"));
+        assert_eq!(calls.len(), 2, "{calls:?}");
+        let mut sampled_params: Vec<_> = calls.iter().map(|call| call.params.clone()).collect();
+        sampled_params.sort_by_key(|params| params.to_string());
+        // Params are redacted; only their structure is retained.
+        assert_eq!(
+            sampled_params,
+            [
+                serde_json::json!(["[redacted; 3 bytes]", false]),
+                serde_json::json!(["[redacted; 6 bytes]", false])
+            ]
+        );
+        for call in &calls {
+            assert_eq!(call.method, "eth_getBlockByNumber");
+            assert!(call.latency_ms >= call.db_time_ms, "{call:?}");
+        }
+
+        let all_calls = self.sampler.calls(None);
+        let methods: HashSet<_> = all_calls.iter().map(|call| call.method.as_str()).collect();
+        assert_eq!(
+            methods,
+            HashSet::from(["eth_blockNumber", "eth_getBlockByNumber"])
+        );
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn sampling_slow_rpc_calls() {
+    test_http_server(SlowCallsSamplingTest::new()).await;
+}
+
 #[derive(Debug, Default)]
 struct GenesisConfigTest;
 
@@ -1075,3 +1458,302 @@ impl HttpTest for GenesisConfigTest {
 async fn tracing_genesis_config() {
     test_http_server(GenesisConfigTest).await;
 }
+
+#[derive(Debug)]
+struct SnapshotRecoveryStatusTest {
+    snapshot_recovery: bool,
+}
+
+#[async_trait]
+impl HttpTest for SnapshotRecoveryStatusTest {
+    fn storage_initialization(&self) -> StorageInitialization {
+        if self.snapshot_recovery {
+            StorageInitialization::empty_recovery()
+        } else {
+            StorageInitialization::Genesis
+        }
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let status = client.snapshot_recovery_status().await?;
+        if !self.snapshot_recovery {
+            assert_eq!(status, None);
+            return Ok(());
+        }
+
+        let status = status.context("no snapshot recovery status")?;
+        assert_eq!(
+            status.l1_batch_number,
+            StorageInitialization::SNAPSHOT_RECOVERY_BATCH
+        );
+        assert_eq!(
+            status.l2_block_number,
+            StorageInitialization::SNAPSHOT_RECOVERY_BLOCK
+        );
+        assert!(status.storage_logs_chunk_count > 0);
+        assert_eq!(
+            status.storage_logs_chunks_processed,
+            status.storage_logs_chunk_count
+        );
+        Ok(())
+    }
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn getting_snapshot_recovery_status(snapshot_recovery: bool) {
+    test_http_server(SnapshotRecoveryStatusTest { snapshot_recovery }).await;
+}
+
+#[derive(Debug)]
+struct PaginatedLogsTest {
+    pagination_enabled: bool,
+}
+
+impl PaginatedLogsTest {
+    const LIMIT: usize = 3;
+}
+
+#[async_trait]
+impl HttpTest for PaginatedLogsTest {
+    fn req_entities_limit(&self) -> Option<usize> {
+        Some(Self::LIMIT)
+    }
+
+    fn logs_pagination_enabled(&self) -> bool {
+        self.pagination_enabled
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let (_, mut events) = store_events(&mut storage, 1, 0).await?;
+        let (_, new_events) = store_events(&mut storage, 2, 4).await?;
+        events.extend(new_events);
+        drop(storage);
+        let events: Vec<_> = events.iter().collect();
+
+        let filter = Filter {
+            from_block: Some(api::BlockNumber::Number(1.into())),
+            to_block: Some(api::BlockNumber::Number(2.into())),
+            ..Filter::default()
+        };
+        let err = client.get_logs(filter.clone()).await.unwrap_err();
+        let ClientError::Call(err) = err else {
+            panic!("Unexpected error: {err:?}");
+        };
+        assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+
+        if !self.pagination_enabled {
+            assert_error_code(&err, "API-008");
+            let err = client.get_logs_paged(filter, None).await.unwrap_err();
+            assert_matches!(err, ClientError::Call(err) if err.code() == ErrorCode::MethodNotFound.code());
+            return Ok(());
+        }
+
+        let cursor: api::LogsCursor = serde_json::from_str(err.data().unwrap().get())?;
+        assert_eq!(
+            cursor,
+            api::LogsCursor {
+                block_number: L2BlockNumber(1),
+                index_in_block: 0,
+            }
+        );
+
+        // Check that the cursor returned by `eth_getLogs` can be used to fetch all logs.
+        let mut all_logs = vec![];
+        let mut cursor = Some(cursor);
+        loop {
+            let page = client.get_logs_paged(filter.clone(), cursor).await?;
+            assert!(page.logs.len() <= Self::LIMIT, "{page:?}");
+            all_logs.extend(page.logs);
+            cursor = page.cursor;
+            if cursor.is_none() {
+                break;
+            }
+        }
+        assert_logs_match(&all_logs, &events);
+
+        // Check that a cursor pointing to the start of a block can be used to continue fetching logs.
+        let second_block_page = client
+            .get_logs_paged(
+                filter,
+                Some(api::LogsCursor {
+                    block_number: L2BlockNumber(2),
+                    index_in_block: 0,
+                }),
+            )
+            .await?;
+        assert_eq!(second_block_page.logs.as_slice(), &all_logs[4..7]);
+        assert!(second_block_page.cursor.is_some());
+        Ok(())
+    }
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn paginated_logs(pagination_enabled: bool) {
+    test_http_server(PaginatedLogsTest { pagination_enabled }).await;
+}
+
+#[derive(Debug)]
+struct PaginatedAccountTransactionsTest;
+
+impl PaginatedAccountTransactionsTest {
+    const LIMIT: usize = 2;
+    const INITIATOR: Address = Address::repeat_byte(0x23);
+
+    fn create_transaction(nonce: u32) -> L2Tx {
+        let mut tx = create_l2_transaction(10, 200);
+        tx.common_data.initiator_address = Self::INITIATOR;
+        tx.common_data.nonce = Nonce(nonce);
+        tx
+    }
+}
+
+#[async_trait]
+impl HttpTest for PaginatedAccountTransactionsTest {
+    fn req_entities_limit(&self) -> Option<usize> {
+        Some(Self::LIMIT)
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let first_block_txs = [
+            execute_l2_transaction(Self::create_transaction(0)),
+            execute_l2_transaction(Self::create_transaction(1)),
+            execute_l2_transaction(create_l2_transaction(10, 200)),
+        ];
+        store_l2_block(&mut storage, L2BlockNumber(1), &first_block_txs).await?;
+        let second_block_txs = [execute_l2_transaction(Self::create_transaction(2))];
+        store_l2_block(&mut storage, L2BlockNumber(2), &second_block_txs).await?;
+        drop(storage);
+
+        let page = client
+            .get_account_transactions_paged(Self::INITIATOR, None)
+            .await?;
+        let hashes: Vec<_> = page.transactions.iter().map(|tx| tx.hash).collect();
+        assert_eq!(hashes, [second_block_txs[0].hash, first_block_txs[1].hash]);
+        let cursor = page.cursor.context("no cursor")?;
+        assert_eq!(
+            cursor,
+            api::PageCursor {
+                block_number: L2BlockNumber(1),
+                index_in_block: 0,
+            }
+        );
+
+        let page = client
+            .get_account_transactions_paged(Self::INITIATOR, Some(cursor))
+            .await?;
+        let hashes: Vec<_> = page.transactions.iter().map(|tx| tx.hash).collect();
+        assert_eq!(hashes, [first_block_txs[0].hash]);
+        assert_eq!(page.cursor, None);
+
+        let page = client
+            .get_account_transactions_paged(Address::repeat_byte(1), None)
+            .await?;
+        assert!(page.transactions.is_empty());
+        assert_eq!(page.cursor, None);
+
+        // Token transfers are paginated in the same way; there are no transfers in this test.
+        let page = client
+            .get_token_transfers_paged(Self::INITIATOR, None)
+            .await?;
+        assert!(page.logs.is_empty());
+        assert_eq!(page.cursor, None);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn paginated_account_transactions() {
+    test_http_server(PaginatedAccountTransactionsTest).await;
+}
+
+#[derive(Debug)]
+struct FeeHistoryTest;
+
+#[async_trait]
+impl HttpTest for FeeHistoryTest {
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let mut storage = pool.connection().await?;
+        let mut tx_results = vec![];
+        for priority_fee in [20_u64, 10, 30] {
+            let mut tx = create_l2_transaction(1_000, 200);
+            tx.common_data.fee.max_priority_fee_per_gas = priority_fee.into();
+            storage
+                .transactions_dal()
+                .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+                .await?;
+            tx_results.push(execute_l2_transaction(tx));
+        }
+        let l2_block = create_l2_block(1);
+        storage.blocks_dal().insert_l2_block(&l2_block).await?;
+        storage
+            .transactions_dal()
+            .mark_txs_as_executed_in_l2_block(
+                l2_block.number,
+                &tx_results,
+                l2_block.base_fee_per_gas.into(),
+                ProtocolVersionId::latest(),
+                false,
+            )
+            .await?;
+        store_l2_block(&mut storage, L2BlockNumber(2), &[]).await?;
+        drop(storage);
+
+        let percentiles = vec![0.0, 50.0, 100.0];
+        let expected_rewards: Vec<Vec<U256>> = vec![
+            vec![0.into(); 3],
+            vec![10.into(), 20.into(), 30.into()],
+            vec![0.into(); 3],
+        ];
+        // The second call should use cached data for older blocks.
+        for _ in 0..2 {
+            let fee_history = client
+                .fee_history(3.into(), api::BlockNumber::Latest, percentiles.clone())
+                .await?;
+            assert_eq!(
+                fee_history.oldest_block,
+                zksync_types::web3::BlockNumber::Number(0.into())
+            );
+            assert_eq!(fee_history.reward.as_ref(), Some(&expected_rewards));
+        }
+
+        let fee_history = client
+            .fee_history(2.into(), api::BlockNumber::Number(1.into()), vec![50.0])
+            .await?;
+        assert_eq!(
+            fee_history.reward,
+            Some(vec![vec![0.into()], vec![20.into()]])
+        );
+
+        let err = client
+            .fee_history(3.into(), api::BlockNumber::Latest, vec![50.0, 10.0])
+            .await
+            .unwrap_err();
+        assert_matches!(err, ClientError::Call(err) if err.code() == ErrorCode::InvalidParams.code());
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn getting_fee_history() {
+    test_http_server(FeeHistoryTest).await;
+}
@@ -0,0 +1,194 @@
+//! Tests for the `zks_getProof` and `zks_getProofs` methods.
+
+use zksync_config::configs::database::MerkleTreeMode;
+use zksync_merkle_tree::{NoVersionError, TreeMultiProof};
+use zksync_metadata_calculator::{
+    api_server::{TreeApiError, TreeEntryWithProof},
+    MerkleTreeInfo,
+};
+use zksync_types::api::ProofRequest;
+
+use super::*;
+
+/// Mock tree API retaining versions in the specified range. Proofs contain hashed keys as values,
+/// which allows checking that proofs are matched with the requested keys.
+#[derive(Debug)]
+struct MockTreeApi {
+    first_retained_l1_batch: L1BatchNumber,
+    next_l1_batch: L1BatchNumber,
+}
+
+#[async_trait]
+impl TreeApiClient for MockTreeApi {
+    async fn get_info(&self) -> Result<MerkleTreeInfo, TreeApiError> {
+        Ok(MerkleTreeInfo {
+            mode: MerkleTreeMode::Full,
+            root_hash: H256::zero(),
+            next_l1_batch_number: self.next_l1_batch,
+            min_l1_batch_number: Some(self.first_retained_l1_batch),
+            leaf_count: 0,
+        })
+    }
+
+    async fn get_proofs(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<Vec<TreeEntryWithProof>, TreeApiError> {
+        if l1_batch_number < self.first_retained_l1_batch || l1_batch_number >= self.next_l1_batch {
+            return Err(TreeApiError::NoVersion(NoVersionError {
+                missing_version: l1_batch_number.0.into(),
+                version_count: self.next_l1_batch.0.into(),
+            }));
+        }
+
+        let entries = hashed_keys
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| TreeEntryWithProof {
+                value: u256_to_h256(key),
+                index: i as u64 + 1,
+                merkle_path: vec![H256::repeat_byte(l1_batch_number.0 as u8)],
+            })
+            .collect();
+        Ok(entries)
+    }
+
+    async fn get_multiproof(
+        &self,
+        _l1_batch_number: L1BatchNumber,
+        _hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, TreeApiError> {
+        Err(TreeApiError::Internal(anyhow::anyhow!("not implemented")))
+    }
+}
+
+fn assert_proof(proof: &api::Proof, address: Address, keys: &[H256]) {
+    assert_eq!(proof.address, address);
+    assert_eq!(proof.storage_proof.len(), keys.len());
+    for (storage_proof, &key) in proof.storage_proof.iter().zip(keys) {
+        assert_eq!(storage_proof.key, key);
+        let hashed_key = StorageKey::new(AccountTreeId::new(address), key).hashed_key_u256();
+        assert_eq!(storage_proof.value, u256_to_h256(hashed_key));
+        assert_eq!(storage_proof.proof, [H256::repeat_byte(1)]);
+    }
+}
+
+#[derive(Debug)]
+struct StorageProofsTest;
+
+impl StorageProofsTest {
+    const KEYS_LIMIT: usize = 4;
+}
+
+#[async_trait]
+impl HttpTest for StorageProofsTest {
+    fn tree_api(&self) -> Option<Arc<dyn TreeApiClient>> {
+        Some(Arc::new(MockTreeApi {
+            first_retained_l1_batch: L1BatchNumber(1),
+            next_l1_batch: L1BatchNumber(3),
+        }))
+    }
+
+    fn req_entities_limit(&self) -> Option<usize> {
+        Some(Self::KEYS_LIMIT)
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let first_address = Address::repeat_byte(1);
+        let first_keys = [H256::repeat_byte(1), H256::repeat_byte(2)];
+        let second_address = Address::repeat_byte(2);
+        let second_keys = [H256::repeat_byte(1), H256::repeat_byte(3)];
+
+        let proof = client
+            .get_proof(first_address, first_keys.to_vec(), L1BatchNumber(1))
+            .await?
+            .context("no proof")?;
+        assert_proof(&proof, first_address, &first_keys);
+
+        let accounts = vec![
+            ProofRequest {
+                address: first_address,
+                keys: first_keys.to_vec(),
+            },
+            ProofRequest {
+                address: second_address,
+                keys: second_keys.to_vec(),
+            },
+        ];
+        let proofs = client
+            .get_proofs(accounts.clone(), L1BatchNumber(1))
+            .await?
+            .context("no proofs")?;
+        assert_eq!(proofs.len(), 2);
+        assert_proof(&proofs[0], first_address, &first_keys);
+        assert_proof(&proofs[1], second_address, &second_keys);
+
+        // Proofs for an L1 batch not yet processed by the tree.
+        let proofs = client.get_proofs(accounts, L1BatchNumber(3)).await?;
+        assert!(proofs.is_none());
+
+        // Proofs for an L1 batch pruned in the tree.
+        let err = client
+            .get_proof(first_address, first_keys.to_vec(), L1BatchNumber(0))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(err) = &err {
+            assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+            assert!(
+                err.message().contains("first retained L1 batch is 1"),
+                "{err:?}"
+            );
+            assert_error_code(err, "API-002");
+        } else {
+            panic!("Unexpected error: {err:?}");
+        }
+
+        // Requests exceeding the keys limit, both for a single account and across accounts.
+        let too_many_keys: Vec<_> = (0..=Self::KEYS_LIMIT as u8)
+            .map(H256::repeat_byte)
+            .collect();
+        let err = client
+            .get_proof(first_address, too_many_keys, L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert_too_many_keys_error(&err);
+
+        let accounts: Vec<_> = [first_address, second_address, Address::repeat_byte(3)]
+            .into_iter()
+            .map(|address| ProofRequest {
+                address,
+                keys: first_keys.to_vec(),
+            })
+            .collect();
+        let err = client
+            .get_proofs(accounts, L1BatchNumber(1))
+            .await
+            .unwrap_err();
+        assert_too_many_keys_error(&err);
+        Ok(())
+    }
+}
+
+fn assert_too_many_keys_error(err: &ClientError) {
+    if let ClientError::Call(err) = err {
+        assert_eq!(err.code(), ErrorCode::InvalidParams.code());
+        assert!(
+            err.message()
+                .contains(&format!("limit is {}", StorageProofsTest::KEYS_LIMIT)),
+            "{err:?}"
+        );
+        assert_error_code(err, "API-017");
+    } else {
+        panic!("Unexpected error: {err:?}");
+    }
+}
+
+#[tokio::test]
+async fn storage_proofs() {
+    test_http_server(StorageProofsTest).await;
+}
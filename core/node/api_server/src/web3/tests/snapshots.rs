@@ -55,7 +55,12 @@ impl HttpTest for SnapshotBasicsTest {
             let path = format!("file:///storage_logs/chunk{chunk_id}");
             storage
                 .snapshots_dal()
-                .add_storage_logs_filepath_for_snapshot(L1BatchNumber(1), chunk_id, &path)
+                .add_storage_logs_filepath_for_snapshot(
+                    L1BatchNumber(1),
+                    chunk_id,
+                    &path,
+                    H256::from_low_u64_be(chunk_id),
+                )
                 .await?;
         }
 
@@ -90,6 +95,7 @@ impl HttpTest for SnapshotBasicsTest {
         for chunk in &snapshot_header.storage_logs_chunks {
             assert!(self.chunk_ids.contains(&chunk.chunk_id));
             assert!(chunk.filepath.starts_with("file:///storage_logs/"));
+            assert_eq!(chunk.checksum, Some(H256::from_low_u64_be(chunk.chunk_id)));
         }
         Ok(())
     }
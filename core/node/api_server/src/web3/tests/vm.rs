@@ -536,6 +536,110 @@ async fn trace_call_after_snapshot_recovery() {
     test_http_server(TraceCallTestAfterSnapshotRecovery).await;
 }
 
+#[derive(Debug)]
+struct SimulateTransactionTest;
+
+impl SimulateTransactionTest {
+    fn assert_call_trace(
+        call_request: &CallRequest,
+        simulation: &api::TransactionSimulation,
+    ) -> api::DebugCall {
+        let api::DebugTrace::Call(call) = &simulation.trace else {
+            panic!("Unexpected trace: {:?}", simulation.trace);
+        };
+        TraceCallTest::assert_debug_call(call_request, call);
+        call.clone()
+    }
+}
+
+#[async_trait]
+impl HttpTest for SimulateTransactionTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        CallTest::create_executor(L2BlockNumber(0))
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let call_request = CallTest::call_request(b"pending");
+        let pending_block = api::SimulationBlockId::Block(api::BlockIdVariant::BlockNumber(
+            api::BlockNumber::Pending,
+        ));
+        let simulation = client
+            .simulate_transaction(call_request.clone(), pending_block, None)
+            .await?;
+        assert_eq!(simulation.l2_block_number, L2BlockNumber(1));
+        Self::assert_call_trace(&call_request, &simulation);
+
+        let call_request = CallTest::call_request(b"first");
+        let genesis_batch = api::SimulationBlockId::L1Batch(api::L1BatchNumberObject {
+            l1_batch_number: L1BatchNumber(0),
+        });
+        let options = api::SimulationOptions {
+            tracer: Some(api::SupportedTracers::CallTracer),
+            tracer_config: api::CallTracerConfig {
+                only_top_call: true,
+            },
+            state_override: Some(api::StateOverride::from([(
+                Address::repeat_byte(1),
+                api::OverrideAccount {
+                    balance: Some(U256::from(1_000)),
+                    ..api::OverrideAccount::default()
+                },
+            )])),
+        };
+        let simulation = client
+            .simulate_transaction(call_request.clone(), genesis_batch, Some(options))
+            .await?;
+        assert_eq!(simulation.l2_block_number, L2BlockNumber(0));
+        let call = Self::assert_call_trace(&call_request, &simulation);
+        assert!(call.calls.is_empty());
+
+        let invalid_override = api::SimulationOptions {
+            state_override: Some(api::StateOverride::from([(
+                Address::repeat_byte(1),
+                api::OverrideAccount {
+                    state: Some(HashMap::new()),
+                    state_diff: Some(HashMap::new()),
+                    ..api::OverrideAccount::default()
+                },
+            )])),
+            ..api::SimulationOptions::default()
+        };
+        let error = client
+            .simulate_transaction(call_request.clone(), genesis_batch, Some(invalid_override))
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+            assert!(error.message().contains("state override"), "{error:?}");
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+
+        let missing_batch = api::SimulationBlockId::L1Batch(api::L1BatchNumberObject {
+            l1_batch_number: L1BatchNumber(100),
+        });
+        let error = client
+            .simulate_transaction(CallTest::call_request(b"100"), missing_batch, None)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn simulate_transaction_basics() {
+    test_http_server(SimulateTransactionTest).await;
+}
+
 #[derive(Debug)]
 struct EstimateGasTest {
     gas_limit_threshold: Arc<AtomicU32>,
@@ -643,3 +747,75 @@ async fn estimate_gas_basics() {
 async fn estimate_gas_after_snapshot_recovery() {
     test_http_server(EstimateGasTest::new(true)).await;
 }
+
+#[derive(Debug)]
+struct EstimateFeeBatchTest;
+
+impl EstimateFeeBatchTest {
+    const GAS_LIMIT_THRESHOLDS: [u64; 2] = [10_000, 100_000];
+}
+
+#[async_trait]
+impl HttpTest for EstimateFeeBatchTest {
+    fn transaction_executor(&self) -> MockTransactionExecutor {
+        let mut tx_executor = MockTransactionExecutor::default();
+        tx_executor.set_call_responses(|tx, block_args| {
+            assert_eq!(block_args.resolved_block_number(), L2BlockNumber(1));
+            // Calldata is used to distinguish transactions in the batch.
+            let gas_limit_threshold = match tx.execute.calldata() {
+                [] => Self::GAS_LIMIT_THRESHOLDS[0],
+                [1] => Self::GAS_LIMIT_THRESHOLDS[1],
+                data => panic!("Unexpected calldata: {data:?}"),
+            };
+            if tx.gas_limit() >= U256::from(gas_limit_threshold) {
+                ExecutionResult::Success { output: vec![] }
+            } else {
+                ExecutionResult::Revert {
+                    output: VmRevertReason::VmError,
+                }
+            }
+        });
+        tx_executor
+    }
+
+    async fn test(
+        &self,
+        client: &DynClient<L2>,
+        _pool: &ConnectionPool<Core>,
+    ) -> anyhow::Result<()> {
+        let l2_transaction = create_l2_transaction(10, 100);
+        let mut requests = vec![CallRequest::from(l2_transaction); 2];
+        requests[1].data = Some(vec![1].into());
+
+        let fees = client.estimate_fee_batch(requests.clone()).await?;
+        assert_eq!(fees.len(), 2);
+        for (fee, threshold) in fees.iter().zip(Self::GAS_LIMIT_THRESHOLDS) {
+            assert!(fee.gas_limit >= U256::from(threshold), "{fee:?}");
+            assert!(fee.gas_limit < U256::from(threshold) * 2, "{fee:?}");
+        }
+        for (request, batch_fee) in requests.iter().zip(&fees) {
+            let fee = client.estimate_fee(request.clone()).await?;
+            assert_eq!(fee, *batch_fee);
+        }
+
+        let fees = client.estimate_fee_batch(vec![]).await?;
+        assert!(fees.is_empty());
+
+        let too_many_requests = vec![requests[0].clone(); 17];
+        let error = client
+            .estimate_fee_batch(too_many_requests)
+            .await
+            .unwrap_err();
+        if let ClientError::Call(error) = error {
+            assert_eq!(error.code(), ErrorCode::InvalidParams.code());
+        } else {
+            panic!("Unexpected error: {error:?}");
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn estimate_fee_batch() {
+    test_http_server(EstimateFeeBatchTest).await;
+}
@@ -742,3 +742,65 @@ impl WsTest for BatchGetsRateLimitedTest {
 async fn batch_rate_limiting() {
     test_ws_server(BatchGetsRateLimitedTest).await;
 }
+
+#[tokio::test]
+async fn mempool_cache_subscription_to_main_node() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let network_config = NetworkConfig::for_tests();
+    let mut storage = pool.connection().await.unwrap();
+    StorageInitialization::Genesis
+        .prepare_storage(&network_config, &mut storage)
+        .await
+        .unwrap();
+
+    let api_config = InternalApiConfig::new(
+        &Web3JsonRpcConfig::for_tests(),
+        &ContractsConfig::for_tests(),
+        &GenesisConfig::for_tests(),
+    );
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let (mut server_handles, mut pub_sub_events) =
+        spawn_ws_server(api_config, pool.clone(), stop_receiver.clone(), None).await;
+    let local_addr = server_handles.wait_until_ready().await;
+
+    let mempool_cache = MempoolCache::new(10);
+    let main_node_ws_url = format!("ws://{local_addr}").parse().unwrap();
+    let cache_task = mempool_cache.main_node_subscription_task(main_node_ws_url);
+    let cache_task_handle = tokio::spawn(cache_task.run(stop_receiver));
+    wait_for_subscription(&mut pub_sub_events, SubscriptionType::Txs).await;
+
+    let mut tx_hashes = vec![];
+    let mut timestamps = vec![];
+    for _ in 0..2 {
+        let tx = create_l2_transaction(10, 200);
+        tx_hashes.push(tx.hash());
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(&tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+
+        let timestamp = tokio::time::timeout(TEST_TIMEOUT, async {
+            loop {
+                let timestamp = mempool_cache.last_timestamp().await;
+                if timestamp > timestamps.last().copied() {
+                    return timestamp.unwrap();
+                }
+                tokio::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+        .await
+        .expect("Timed out waiting for mempool cache update");
+        timestamps.push(timestamp);
+    }
+
+    let cached_txs = mempool_cache
+        .get_tx_hashes_after(timestamps[0])
+        .await
+        .expect("cache miss");
+    assert_eq!(cached_txs, [(timestamps[1], tx_hashes[1])]);
+
+    stop_sender.send_replace(true);
+    cache_task_handle.await.unwrap().unwrap();
+    server_handles.shutdown().await;
+}
@@ -0,0 +1,87 @@
+//! Per-L1 batch bundle of block data stored in the archive.
+
+use std::io::{Read, Write};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+use zksync_object_store::{Bucket, StoredObject, _reexports::BoxedError};
+use zksync_types::{api, L1BatchNumber, L2BlockNumber, H256};
+
+/// Archived data for a single L2 block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedL2Block {
+    /// Block with transaction hashes, as returned by the `eth_getBlockByNumber` method.
+    pub block: api::Block<H256>,
+    /// Transactions in the block ordered by their index in the block.
+    pub transactions: Vec<api::Transaction>,
+    /// Receipts for the block transactions ordered by the transaction index in the block.
+    pub receipts: Vec<api::TransactionReceipt>,
+}
+
+impl ArchivedL2Block {
+    pub fn number(&self) -> L2BlockNumber {
+        L2BlockNumber(self.block.number.as_u32())
+    }
+}
+
+/// Block data for all L2 blocks in an L1 batch.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BlockDataBundle {
+    pub l1_batch_number: L1BatchNumber,
+    /// L2 blocks in the batch ordered by number.
+    pub l2_blocks: Vec<ArchivedL2Block>,
+}
+
+impl BlockDataBundle {
+    pub fn l2_block(&self, number: L2BlockNumber) -> Option<&ArchivedL2Block> {
+        let first_number = self.l2_blocks.first()?.number();
+        let idx = number.0.checked_sub(first_number.0)?;
+        self.l2_blocks
+            .get(idx as usize)
+            .filter(|block| block.number() == number)
+    }
+
+    /// Checks whether the L2 block with the specified number precedes L2 blocks in this bundle.
+    pub(crate) fn is_after(&self, number: L2BlockNumber) -> bool {
+        self.l2_blocks
+            .first()
+            .map_or(false, |block| block.number() > number)
+    }
+
+    pub fn transaction(&self, hash: H256) -> Option<&api::Transaction> {
+        self.l2_blocks
+            .iter()
+            .flat_map(|block| &block.transactions)
+            .find(|tx| tx.hash == hash)
+    }
+
+    pub fn receipt(&self, hash: H256) -> Option<&api::TransactionReceipt> {
+        self.l2_blocks
+            .iter()
+            .flat_map(|block| &block.receipts)
+            .find(|receipt| receipt.transaction_hash == hash)
+    }
+}
+
+impl StoredObject for BlockDataBundle {
+    const BUCKET: Bucket = Bucket::BlockDataArchive;
+    type Key<'a> = L1BatchNumber;
+
+    fn encode_key(key: Self::Key<'_>) -> String {
+        format!("l1_batch_{key}_block_data.json.gzip")
+    }
+
+    fn serialize(&self) -> Result<Vec<u8>, BoxedError> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        serde_json::to_writer(&mut encoder, self)?;
+        encoder.flush()?;
+        encoder.finish().map_err(From::from)
+    }
+
+    fn deserialize(bytes: Vec<u8>) -> Result<Self, BoxedError> {
+        let mut decoder = GzDecoder::new(&bytes[..]);
+        let mut decompressed_bytes = Vec::new();
+        decoder.read_to_end(&mut decompressed_bytes)?;
+        serde_json::from_slice(&decompressed_bytes).map_err(From::from)
+    }
+}
@@ -0,0 +1,184 @@
+//! Exporter of block data to the archive.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_object_store::{ObjectStore, StoredObject};
+use zksync_types::{L1BatchNumber, L2BlockNumber, L2ChainId};
+
+use crate::{
+    bundle::{ArchivedL2Block, BlockDataBundle},
+    metrics::METRICS,
+};
+
+/// Exports block data for L1 batches executed on L1 to the archive, one bundle per L1 batch.
+///
+/// Progress is persisted in Postgres, so the exporter resumes from the next L1 batch after a restart. Only L1 batches
+/// executed on L1 are exported, so that exported data is never reverted.
+#[derive(Debug)]
+pub struct BlockDataExporter {
+    pool: ConnectionPool<Core>,
+    blob_store: Arc<dyn ObjectStore>,
+    l2_chain_id: L2ChainId,
+    poll_interval: Duration,
+}
+
+impl BlockDataExporter {
+    const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        blob_store: Arc<dyn ObjectStore>,
+        l2_chain_id: L2ChainId,
+    ) -> Self {
+        Self {
+            pool,
+            blob_store,
+            l2_chain_id,
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    #[must_use]
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    async fn next_l1_batch_to_export(
+        storage: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<Option<L1BatchNumber>> {
+        let last_archived_l1_batch = storage
+            .block_data_archive_dal()
+            .get_last_archived_l1_batch()
+            .await?;
+        let Some(earliest_l1_batch) = storage.blocks_dal().get_earliest_l1_batch_number().await?
+        else {
+            return Ok(None); // Storage is not initialized yet
+        };
+        // If the exporter was enabled after pruning, some L1 batches may be irrecoverably lost.
+        let next_l1_batch = last_archived_l1_batch.map_or(earliest_l1_batch, |number| number + 1);
+        if next_l1_batch < earliest_l1_batch {
+            tracing::warn!(
+                "Block data for L1 batches {next_l1_batch}..{earliest_l1_batch} is pruned and cannot be exported"
+            );
+        }
+        let next_l1_batch = next_l1_batch.max(earliest_l1_batch);
+
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+        Ok(last_executed_l1_batch
+            .filter(|&last_executed| last_executed >= next_l1_batch)
+            .map(|_| next_l1_batch))
+    }
+
+    pub(crate) async fn load_bundle(
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+        l2_chain_id: L2ChainId,
+    ) -> anyhow::Result<BlockDataBundle> {
+        let (first_l2_block, last_l2_block) = storage
+            .blocks_web3_dal()
+            .get_l2_block_range_of_l1_batch(l1_batch_number)
+            .await?
+            .with_context(|| format!("L1 batch #{l1_batch_number} has no L2 blocks"))?;
+
+        let mut l2_blocks = Vec::with_capacity((last_l2_block.0 - first_l2_block.0 + 1) as usize);
+        for number in first_l2_block.0..=last_l2_block.0 {
+            let number = L2BlockNumber(number);
+            let block = storage
+                .blocks_web3_dal()
+                .get_api_block(number)
+                .await?
+                .with_context(|| format!("L2 block #{number} is missing"))?;
+            let mut transactions = storage
+                .transactions_web3_dal()
+                .get_transactions(&block.transactions, l2_chain_id)
+                .await?;
+            transactions.sort_unstable_by_key(|tx| tx.transaction_index);
+            let mut receipts = storage
+                .transactions_web3_dal()
+                .get_transaction_receipts(&block.transactions)
+                .await?;
+            receipts.sort_unstable_by_key(|receipt| receipt.transaction_index);
+
+            anyhow::ensure!(
+                transactions.len() == block.transactions.len()
+                    && receipts.len() == block.transactions.len(),
+                "storage inconsistency: L2 block #{number} has {} transactions, but {} transactions \
+                 and {} receipts were loaded",
+                block.transactions.len(),
+                transactions.len(),
+                receipts.len()
+            );
+            l2_blocks.push(ArchivedL2Block {
+                block,
+                transactions,
+                receipts,
+            });
+        }
+
+        Ok(BlockDataBundle {
+            l1_batch_number,
+            l2_blocks,
+        })
+    }
+
+    /// Exports the next L1 batch if it's available. Returns the exported L1 batch number.
+    pub(crate) async fn export_next_l1_batch(&self) -> anyhow::Result<Option<L1BatchNumber>> {
+        let mut storage = self.pool.connection_tagged("block_data_archive").await?;
+        let Some(l1_batch_number) = Self::next_l1_batch_to_export(&mut storage).await? else {
+            return Ok(None);
+        };
+
+        let latency = METRICS.export_latency.start();
+        let bundle = Self::load_bundle(&mut storage, l1_batch_number, self.l2_chain_id).await?;
+        drop(storage);
+        let bundle_bytes = bundle
+            .serialize()
+            .map_err(|err| anyhow::anyhow!("failed serializing bundle: {err}"))?;
+        METRICS.bundle_size.observe(bundle_bytes.len());
+        let key = BlockDataBundle::encode_key(l1_batch_number);
+        self.blob_store
+            .put_raw(BlockDataBundle::BUCKET, &key, bundle_bytes)
+            .await
+            .with_context(|| format!("failed putting bundle for L1 batch #{l1_batch_number}"))?;
+
+        let mut storage = self.pool.connection_tagged("block_data_archive").await?;
+        storage
+            .block_data_archive_dal()
+            .insert_archived_l1_batch(l1_batch_number)
+            .await?;
+        let latency = latency.observe();
+        METRICS.last_exported_l1_batch.set(l1_batch_number.0.into());
+        tracing::info!(
+            "Exported block data for L1 batch #{l1_batch_number} with {} L2 blocks in {latency:?}",
+            bundle.l2_blocks.len()
+        );
+        Ok(Some(l1_batch_number))
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting block data exporter with poll interval {:?}",
+            self.poll_interval
+        );
+        while !*stop_receiver.borrow_and_update() {
+            if self.export_next_l1_batch().await?.is_some() {
+                continue; // There may be more L1 batches to export
+            }
+            if tokio::time::timeout(self.poll_interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, block data exporter is shutting down");
+        Ok(())
+    }
+}
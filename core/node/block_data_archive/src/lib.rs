@@ -0,0 +1,15 @@
+//! Archive of historical block data (L2 blocks, transactions and receipts) stored in an object store,
+//! which allows serving this data after it's pruned from Postgres.
+
+pub use self::{
+    bundle::{ArchivedL2Block, BlockDataBundle},
+    exporter::BlockDataExporter,
+    reader::BlockDataArchiveReader,
+};
+
+mod bundle;
+mod exporter;
+mod metrics;
+mod reader;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,38 @@
+//! Metrics for the block data archive.
+
+use std::time::Duration;
+
+use vise::{
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics, Unit,
+};
+
+const BUNDLE_SIZE_BUCKETS: Buckets = Buckets::exponential(1_024.0..=67_108_864.0, 4.0);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(crate) enum BundleFetchResult {
+    CacheHit,
+    Fetched,
+    Missing,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "block_data_archive")]
+pub(crate) struct BlockDataArchiveMetrics {
+    /// Number of the latest L1 batch exported to the archive.
+    pub last_exported_l1_batch: Gauge<u64>,
+    /// Latency of exporting a single L1 batch bundle.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub export_latency: Histogram<Duration>,
+    /// Size of exported bundles.
+    #[metrics(buckets = BUNDLE_SIZE_BUCKETS, unit = Unit::Bytes)]
+    pub bundle_size: Histogram<usize>,
+    /// Latency of fetching a bundle from the object store.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub fetch_latency: Histogram<Duration>,
+    /// Number of bundle lookups performed by the reader grouped by the result.
+    pub bundle_lookups: Family<BundleFetchResult, Counter>,
+}
+
+#[vise::register]
+pub(crate) static METRICS: vise::Global<BlockDataArchiveMetrics> = vise::Global::new();
@@ -0,0 +1,148 @@
+//! Reader of block data from the archive.
+
+use std::{
+    num::NonZeroUsize,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use lru::LruCache;
+use zksync_dal::{Connection, Core, CoreDal};
+use zksync_object_store::{ObjectStore, ObjectStoreError};
+use zksync_types::{api, L1BatchNumber, L2BlockNumber, H256};
+
+use crate::{
+    bundle::{ArchivedL2Block, BlockDataBundle},
+    metrics::{BundleFetchResult, METRICS},
+};
+
+/// Maximum number of L1 batches probed after the lower bound when looking up an L2 block. The lower bound is precise
+/// unless the L2 block has no transactions, so a few extra probes are usually enough.
+const MAX_L1_BATCH_PROBES: u32 = 2;
+
+/// Reads block data for pruned L2 blocks from the archive populated by [`BlockDataExporter`](crate::BlockDataExporter).
+///
+/// Recently used bundles are cached in memory, so that consecutive requests for blocks / transactions
+/// in the same L1 batch don't hit the object store.
+#[derive(Debug)]
+pub struct BlockDataArchiveReader {
+    blob_store: Arc<dyn ObjectStore>,
+    cache: Mutex<LruCache<L1BatchNumber, Arc<BlockDataBundle>>>,
+}
+
+impl BlockDataArchiveReader {
+    pub fn new(blob_store: Arc<dyn ObjectStore>, cache_capacity: NonZeroUsize) -> Self {
+        Self {
+            blob_store,
+            cache: Mutex::new(LruCache::new(cache_capacity)),
+        }
+    }
+
+    /// Returns a bundle for the specified L1 batch, or `None` if the L1 batch is not archived.
+    pub async fn bundle(
+        &self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Option<Arc<BlockDataBundle>>> {
+        let cached = self
+            .cache
+            .lock()
+            .expect("block data cache is poisoned")
+            .get(&l1_batch_number)
+            .cloned();
+        if let Some(bundle) = cached {
+            METRICS.bundle_lookups[&BundleFetchResult::CacheHit].inc();
+            return Ok(Some(bundle));
+        }
+
+        let latency = METRICS.fetch_latency.start();
+        let bundle = match self
+            .blob_store
+            .get::<BlockDataBundle>(l1_batch_number)
+            .await
+        {
+            Ok(bundle) => bundle,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                METRICS.bundle_lookups[&BundleFetchResult::Missing].inc();
+                return Ok(None);
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!("failed fetching block data for L1 batch #{l1_batch_number}")
+                });
+            }
+        };
+        latency.observe();
+        METRICS.bundle_lookups[&BundleFetchResult::Fetched].inc();
+
+        let bundle = Arc::new(bundle);
+        self.cache
+            .lock()
+            .expect("block data cache is poisoned")
+            .put(l1_batch_number, bundle.clone());
+        Ok(Some(bundle))
+    }
+
+    /// Returns archived data for the specified L2 block. The L1 batch containing the block is located using
+    /// transactions retained in Postgres after pruning.
+    pub async fn get_l2_block(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        number: L2BlockNumber,
+    ) -> anyhow::Result<Option<ArchivedL2Block>> {
+        let lower_bound = storage
+            .block_data_archive_dal()
+            .get_l1_batch_lower_bound_for_l2_block(number)
+            .await?;
+        // If there are no transactions before the block, it can only be in the genesis batch or after it.
+        let mut l1_batch_number = lower_bound.unwrap_or(L1BatchNumber(0));
+        let last_l1_batch_to_probe = l1_batch_number + MAX_L1_BATCH_PROBES;
+        while l1_batch_number <= last_l1_batch_to_probe {
+            let Some(bundle) = self.bundle(l1_batch_number).await? else {
+                return Ok(None);
+            };
+            if let Some(block) = bundle.l2_block(number) {
+                return Ok(Some(block.clone()));
+            }
+            if bundle.is_after(number) {
+                break;
+            }
+            l1_batch_number += 1;
+        }
+        Ok(None)
+    }
+
+    async fn bundle_for_transaction(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        hash: H256,
+    ) -> anyhow::Result<Option<Arc<BlockDataBundle>>> {
+        let tx_location = storage
+            .blocks_web3_dal()
+            .get_l1_batch_info_for_tx(hash)
+            .await?;
+        let Some((l1_batch_number, _)) = tx_location else {
+            return Ok(None);
+        };
+        self.bundle(l1_batch_number).await
+    }
+
+    /// Returns an archived transaction with the specified hash.
+    pub async fn get_transaction(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        hash: H256,
+    ) -> anyhow::Result<Option<api::Transaction>> {
+        let bundle = self.bundle_for_transaction(storage, hash).await?;
+        Ok(bundle.and_then(|bundle| bundle.transaction(hash).cloned()))
+    }
+
+    /// Returns an archived receipt for the transaction with the specified hash.
+    pub async fn get_transaction_receipt(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        hash: H256,
+    ) -> anyhow::Result<Option<api::TransactionReceipt>> {
+        let bundle = self.bundle_for_transaction(storage, hash).await?;
+        Ok(bundle.and_then(|bundle| bundle.receipt(hash).cloned()))
+    }
+}
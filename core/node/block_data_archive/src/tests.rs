@@ -0,0 +1,185 @@
+//! Tests for the block data archive.
+
+use std::num::NonZeroUsize;
+
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_node_test_utils::{
+    create_l1_batch, create_l2_block, create_l2_transaction, execute_l2_transaction,
+};
+use zksync_object_store::ObjectStoreFactory;
+use zksync_types::{
+    aggregated_operations::AggregatedActionType, fee::TransactionExecutionMetrics, L1BatchNumber,
+    L2BlockNumber, L2ChainId, ProtocolVersionId, H256,
+};
+
+use super::*;
+
+/// Stores an L1 batch with a single L2 block containing the specified number of transactions.
+/// L1 batch and L2 block numbers coincide. Returns hashes of the stored transactions.
+async fn store_l1_batch(
+    storage: &mut Connection<'_, Core>,
+    number: u32,
+    tx_count: u64,
+) -> Vec<H256> {
+    let tx_results: Vec<_> = (0..tx_count)
+        .map(|i| {
+            let fee_per_gas = u64::from(number) * 10 + i + 1; // ensures that transaction hashes are unique
+            execute_l2_transaction(create_l2_transaction(fee_per_gas, 100))
+        })
+        .collect();
+    for result in &tx_results {
+        let l2_tx = result.transaction.clone().try_into().unwrap();
+        storage
+            .transactions_dal()
+            .insert_transaction_l2(&l2_tx, TransactionExecutionMetrics::default())
+            .await
+            .unwrap();
+    }
+
+    let l2_block = create_l2_block(number);
+    storage
+        .blocks_dal()
+        .insert_l2_block(&l2_block)
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_l2_block(
+            l2_block.number,
+            &tx_results,
+            1.into(),
+            ProtocolVersionId::latest(),
+            false,
+        )
+        .await
+        .unwrap();
+    let l1_batch = create_l1_batch(number);
+    storage
+        .blocks_dal()
+        .insert_mock_l1_batch(&l1_batch)
+        .await
+        .unwrap();
+    storage
+        .blocks_dal()
+        .mark_l2_blocks_as_executed_in_l1_batch(l1_batch.number)
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_l1_batch(l1_batch.number, &tx_results)
+        .await
+        .unwrap();
+    tx_results.iter().map(|result| result.hash).collect()
+}
+
+async fn mark_l1_batch_as_executed(storage: &mut Connection<'_, Core>, number: u32) {
+    storage
+        .eth_sender_dal()
+        .insert_bogus_confirmed_eth_tx(
+            L1BatchNumber(number),
+            AggregatedActionType::Execute,
+            H256::from_low_u64_be(number.into()),
+            chrono::Utc::now(),
+        )
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn exporting_and_reading_pruned_block_data() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let tx_hashes = store_l1_batch(&mut storage, 1, 2).await;
+    store_l1_batch(&mut storage, 2, 0).await;
+    store_l1_batch(&mut storage, 3, 1).await;
+
+    let blob_store = ObjectStoreFactory::mock().create_store().await;
+    let exporter = BlockDataExporter::new(pool.clone(), blob_store.clone(), L2ChainId::default());
+    // No L1 batches are executed on L1 yet, so nothing should be exported.
+    assert_eq!(exporter.export_next_l1_batch().await.unwrap(), None);
+
+    mark_l1_batch_as_executed(&mut storage, 2).await;
+    for expected_number in 0..=2 {
+        let exported = exporter.export_next_l1_batch().await.unwrap();
+        assert_eq!(exported, Some(L1BatchNumber(expected_number)));
+    }
+    assert_eq!(exporter.export_next_l1_batch().await.unwrap(), None);
+    let last_archived_l1_batch = storage
+        .block_data_archive_dal()
+        .get_last_archived_l1_batch()
+        .await
+        .unwrap();
+    assert_eq!(last_archived_l1_batch, Some(L1BatchNumber(2)));
+
+    let expected_bundle =
+        BlockDataExporter::load_bundle(&mut storage, L1BatchNumber(1), L2ChainId::default())
+            .await
+            .unwrap();
+    assert_eq!(expected_bundle.l2_blocks.len(), 1);
+    assert_eq!(expected_bundle.l2_blocks[0].transactions.len(), 2);
+    assert_eq!(expected_bundle.l2_blocks[0].receipts.len(), 2);
+
+    storage
+        .pruning_dal()
+        .soft_prune_batches_range(L1BatchNumber(2), L2BlockNumber(2))
+        .await
+        .unwrap();
+    storage
+        .pruning_dal()
+        .hard_prune_batches_range(L1BatchNumber(2), L2BlockNumber(2))
+        .await
+        .unwrap();
+    let pruned_block = storage
+        .blocks_web3_dal()
+        .get_api_block(L2BlockNumber(1))
+        .await
+        .unwrap();
+    assert!(pruned_block.is_none());
+
+    let reader = BlockDataArchiveReader::new(blob_store, NonZeroUsize::new(2).unwrap());
+    for number in 0..=2 {
+        let block = reader
+            .get_l2_block(&mut storage, L2BlockNumber(number))
+            .await
+            .unwrap()
+            .unwrap_or_else(|| panic!("L2 block #{number} is not archived"));
+        assert_eq!(block.number(), L2BlockNumber(number));
+    }
+    let block = reader
+        .get_l2_block(&mut storage, L2BlockNumber(1))
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(&block, &expected_bundle.l2_blocks[0]);
+    // The L1 batch containing the block is not archived.
+    let block = reader
+        .get_l2_block(&mut storage, L2BlockNumber(3))
+        .await
+        .unwrap();
+    assert!(block.is_none());
+
+    for hash in tx_hashes {
+        let tx = reader
+            .get_transaction(&mut storage, hash)
+            .await
+            .unwrap()
+            .expect("transaction is not archived");
+        assert_eq!(tx.hash, hash);
+        let receipt = reader
+            .get_transaction_receipt(&mut storage, hash)
+            .await
+            .unwrap()
+            .expect("receipt is not archived");
+        assert_eq!(receipt.transaction_hash, hash);
+        assert_eq!(receipt.block_number, 1.into());
+    }
+    let missing_tx = reader
+        .get_transaction(&mut storage, H256::repeat_byte(0xff))
+        .await
+        .unwrap();
+    assert!(missing_tx.is_none());
+}
@@ -239,7 +239,7 @@ async fn create_mock_snapshot(
             .unwrap();
         storage
             .snapshots_dal()
-            .add_storage_logs_filepath_for_snapshot(l1_batch_number, chunk_id, &key)
+            .add_storage_logs_filepath_for_snapshot(l1_batch_number, chunk_id, &key, H256::zero())
             .await
             .unwrap();
     }
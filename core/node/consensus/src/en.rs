@@ -1,22 +1,72 @@
+use std::{num::NonZeroUsize, path::PathBuf, sync::Arc};
+
 use anyhow::Context as _;
-use zksync_concurrency::{ctx, error::Wrap as _, scope, time};
+use serde::Serialize;
+use zksync_concurrency::{ctx, error::Wrap as _, scope, sync, time};
+use zksync_consensus_crypto::TextFmt as _;
 use zksync_consensus_executor as executor;
 use zksync_consensus_roles::validator;
 use zksync_consensus_storage::BlockStore;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater};
 use zksync_node_sync::{
     fetcher::FetchedBlock, sync_action::ActionQueueSender, MainNodeClient, SyncState,
 };
-use zksync_types::L2BlockNumber;
+use zksync_types::{api::en::SyncBlock, L2BlockNumber};
 use zksync_web3_decl::client::{DynClient, L2};
 
-use super::{config, storage::Store, ConnectionPool, ConsensusConfig, ConsensusSecrets};
+use super::{
+    config,
+    metrics::{FetchStage, FETCHER_METRICS, NETWORK_METRICS},
+    peers::{self, PeersHandle},
+    storage::Store,
+    watermark::{WatermarkFile, WatermarkedReplicaStore},
+    ConnectionPool, ConsensusConfig, ConsensusSecrets,
+};
 use crate::storage;
 
+/// Role of the external node in consensus.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Role {
+    /// Fetches blocks from the main node via JSON-RPC without running a consensus node.
+    Fetcher,
+    /// Runs a consensus node fetching blocks and certificates via gossip.
+    FullNode,
+    /// Runs a consensus node that signs blocks proposed by the main node.
+    Validator,
+}
+
+/// Health details of the consensus component of the external node.
+#[derive(Debug, Serialize)]
+struct ConsensusHealthDetails {
+    role: Role,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validator_key: Option<String>,
+    /// Whether the validator key belongs to the validator committee of the current genesis.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    in_committee: Option<bool>,
+}
+
+impl From<ConsensusHealthDetails> for Health {
+    fn from(details: ConsensusHealthDetails) -> Self {
+        // A validator outside of the committee still follows the chain, but doesn't sign anything.
+        let status = if details.in_committee == Some(false) {
+            HealthStatus::Affected
+        } else {
+            HealthStatus::Ready
+        };
+        Health::from(status).with_details(details)
+    }
+}
+
 /// External node.
 pub(super) struct EN {
     pub(super) pool: ConnectionPool,
     pub(super) sync_state: SyncState,
     pub(super) client: Box<DynClient<L2>>,
+    pub(super) health_updater: HealthUpdater,
+    /// Maximum number of blocks fetched from the main node ahead of the state keeper.
+    pub(super) prefetch_depth: NonZeroUsize,
 }
 
 impl EN {
@@ -25,19 +75,44 @@ impl EN {
     ///
     /// NOTE: Before starting the consensus node if fetches all the blocks
     /// older than consensus genesis from the main node using json RPC.
+    ///
+    /// If the node is a validator, `validator_watermark_path` must be specified; it's used to persist
+    /// the highest signed view, so that the node never signs twice in the same view across restarts.
+    ///
+    /// Gossip peers can be banned via `peers`; if it's not specified, a new handle is created from `cfg`.
     pub async fn run(
         self,
         ctx: &ctx::Ctx,
         actions: ActionQueueSender,
         cfg: ConsensusConfig,
         secrets: ConsensusSecrets,
+        validator_watermark_path: Option<PathBuf>,
+        peers: Option<PeersHandle>,
     ) -> anyhow::Result<()> {
+        let peers = match peers {
+            Some(peers) => peers,
+            None => PeersHandle::new(&cfg).context("PeersHandle::new()")?,
+        };
+        let validator_key = config::validator_key(&secrets).context("validator_key")?;
+        let validator_watermark = validator_key
+            .as_ref()
+            .map(|_| {
+                validator_watermark_path.map(WatermarkFile::new).context(
+                    "validator watermark path must be specified for validators to prevent double-signing",
+                )
+            })
+            .transpose()?;
+
         let res: ctx::Result<()> = scope::run!(ctx, |ctx, s| async {
             // Update sync state in the background.
             s.spawn_bg(self.fetch_state_loop(ctx));
 
             // Initialize genesis.
             let genesis = self.fetch_genesis(ctx).await.wrap("fetch_genesis()")?;
+            let fork_number = genesis.fork_number;
+            let in_committee = validator_key
+                .as_ref()
+                .map(|key| genesis.committee.contains(&key.public()));
             let mut conn = self.pool.connection(ctx).await.wrap("connection()")?;
             conn.try_update_genesis(ctx, &genesis)
                 .await
@@ -77,19 +152,40 @@ impl EN {
                 .await
                 .wrap("BlockStore::new()")?;
             s.spawn_bg(async { Ok(runner.run(ctx).await?) });
-            let executor = executor::Executor {
-                config: config::executor(&cfg, &secrets)?,
-                block_store,
-                validator: config::validator_key(&secrets)
-                    .context("validator_key")?
-                    .map(|key| executor::Validator {
-                        key,
-                        replica_store: Box::new(store.clone()),
-                        payload_manager: Box::new(store.clone()),
-                    }),
+            let health = ConsensusHealthDetails {
+                role: if validator_key.is_some() {
+                    Role::Validator
+                } else {
+                    Role::FullNode
+                },
+                validator_key: validator_key.as_ref().map(|key| key.public().encode()),
+                in_committee,
             };
-            executor.run(ctx).await?;
-            Ok(())
+            if in_committee == Some(false) {
+                tracing::warn!(
+                    "Validator key of the node is not in the validator committee; the node won't sign blocks"
+                );
+            }
+            self.health_updater.update(health.into());
+
+            let make_validator = || -> anyhow::Result<Option<executor::Validator>> {
+                let (Some(key), Some(watermark_file)) = (&validator_key, &validator_watermark) else {
+                    return Ok(None);
+                };
+                let replica_store =
+                    WatermarkedReplicaStore::new(store.clone(), watermark_file.clone(), fork_number)
+                        .context("WatermarkedReplicaStore::new()")?;
+                tracing::info!(
+                    "Running consensus validator; last signed view: {:?}",
+                    replica_store.watermark()
+                );
+                Ok(Some(executor::Validator {
+                    key: key.clone(),
+                    replica_store: Box::new(replica_store),
+                    payload_manager: Box::new(store.clone()),
+                }))
+            };
+            Self::run_node(ctx, &cfg, &secrets, &block_store, &peers, make_validator).await
         })
         .await;
         match res {
@@ -98,6 +194,37 @@ impl EN {
         }
     }
 
+    /// Runs the consensus node, restarting it each time the set of banned peers changes,
+    /// so that banned peers are disconnected and aren't connected to again.
+    async fn run_node(
+        ctx: &ctx::Ctx,
+        cfg: &ConsensusConfig,
+        secrets: &ConsensusSecrets,
+        block_store: &Arc<BlockStore>,
+        peers: &PeersHandle,
+        make_validator: impl Fn() -> anyhow::Result<Option<executor::Validator>>,
+    ) -> ctx::Result<()> {
+        let mut banned_peers = peers.subscribe();
+        loop {
+            let banned = banned_peers.borrow_and_update().clone();
+            let mut config = config::executor(cfg, secrets)?;
+            peers::remove_banned_peers(&mut config, &banned);
+            let executor = executor::Executor {
+                config,
+                block_store: block_store.clone(),
+                validator: make_validator()?,
+            };
+            scope::run!(ctx, |ctx, s| async {
+                s.spawn_bg(async { Ok(executor.run(ctx).await?) });
+                sync::wait_for(ctx, &mut banned_peers, |new_banned| *new_banned != banned).await?;
+                Ok(())
+            })
+            .await?;
+            tracing::info!("Set of banned consensus peers has changed; restarting consensus node");
+            NETWORK_METRICS.node_restarts.inc();
+        }
+    }
+
     /// Task fetching L2 blocks using JSON-RPC endpoint of the main node.
     pub async fn run_fetcher(
         self,
@@ -107,6 +234,12 @@ impl EN {
         let res: ctx::Result<()> = scope::run!(ctx, |ctx, s| async {
             // Update sync state in the background.
             s.spawn_bg(self.fetch_state_loop(ctx));
+            let health = ConsensusHealthDetails {
+                role: Role::Fetcher,
+                validator_key: None,
+                in_committee: None,
+            };
+            self.health_updater.update(health.into());
             let mut payload_queue = self
                 .pool
                 .connection(ctx)
@@ -133,6 +266,10 @@ impl EN {
             match ctx.wait(self.client.fetch_l2_block_number()).await? {
                 Ok(head) => {
                     self.sync_state.set_main_node_block(head);
+                    let local_block = self.sync_state.get_local_block();
+                    NETWORK_METRICS
+                        .block_lag
+                        .set(head.0.saturating_sub(local_block.0).into());
                     ctx.sleep(DELAY_INTERVAL).await?;
                 }
                 Err(err) => {
@@ -154,13 +291,13 @@ impl EN {
     }
 
     /// Fetches (with retries) the given block from the main node.
-    async fn fetch_block(&self, ctx: &ctx::Ctx, n: L2BlockNumber) -> ctx::Result<FetchedBlock> {
+    async fn fetch_block(&self, ctx: &ctx::Ctx, n: L2BlockNumber) -> ctx::Result<SyncBlock> {
         const RETRY_INTERVAL: time::Duration = time::Duration::seconds(5);
 
         loop {
             let res = ctx.wait(self.client.fetch_l2_block(n, true)).await?;
             match res {
-                Ok(Some(block)) => return Ok(block.try_into()?),
+                Ok(Some(block)) => return Ok(block),
                 Ok(None) => {}
                 Err(err) if err.is_transient() => {}
                 Err(err) => {
@@ -172,30 +309,74 @@ impl EN {
     }
 
     /// Fetches blocks from the main node in range `[cursor.next()..end)`.
+    ///
+    /// Blocks are processed by a pipeline of 3 stages connected with bounded channels:
+    /// - fetching blocks from the main node; up to `prefetch_depth` requests are executed concurrently,
+    /// - decoding blocks into sync actions,
+    /// - passing the actions to the state keeper.
+    ///
+    /// Each stage stalls once the channel to the next stage is full, so the number of blocks
+    /// held in memory is bounded.
     pub(super) async fn fetch_blocks(
         &self,
         ctx: &ctx::Ctx,
         queue: &mut storage::PayloadQueue,
         end: Option<validator::BlockNumber>,
     ) -> ctx::Result<()> {
-        const MAX_CONCURRENT_REQUESTS: usize = 30;
+        let metrics = &FETCHER_METRICS;
+        let prefetch_depth = self.prefetch_depth.get();
         let first = queue.next();
-        let mut next = first;
+        let in_range = |n: validator::BlockNumber| end.map_or(true, |end| n < end);
         scope::run!(ctx, |ctx, s| async {
-            let (send, mut recv) = ctx::channel::bounded(MAX_CONCURRENT_REQUESTS);
+            let (fetch_send, mut fetch_recv) = ctx::channel::bounded(prefetch_depth);
             s.spawn(async {
-                let send = send;
-                while end.map_or(true, |end| next < end) {
+                let send = fetch_send;
+                let mut next = first;
+                while in_range(next) {
                     let n = L2BlockNumber(next.0.try_into().unwrap());
                     self.sync_state.wait_for_main_node_block(ctx, n).await?;
-                    send.send(ctx, s.spawn(self.fetch_block(ctx, n))).await?;
+                    let block = s.spawn(async move {
+                        let block = self.fetch_block(ctx, n).await?;
+                        metrics.observe(
+                            FetchStage::Fetched,
+                            n,
+                            self.sync_state.get_main_node_block(),
+                        );
+                        Ok(block)
+                    });
+                    send.send(ctx, block).await?;
                     next = next.next();
                 }
                 Ok(())
             });
-            while end.map_or(true, |end| queue.next() < end) {
-                let block = recv.recv(ctx).await?.join(ctx).await?;
+
+            let (decode_send, mut decode_recv) = ctx::channel::bounded(prefetch_depth);
+            s.spawn(async {
+                let send = decode_send;
+                let mut next = first;
+                while in_range(next) {
+                    let block = fetch_recv.recv(ctx).await?.join(ctx).await?;
+                    let block = FetchedBlock::try_from(block)?;
+                    metrics.observe(
+                        FetchStage::Decoded,
+                        block.number,
+                        self.sync_state.get_main_node_block(),
+                    );
+                    send.send(ctx, block).await?;
+                    next = next.next();
+                }
+                Ok(())
+            });
+
+            while in_range(queue.next()) {
+                let block = decode_recv.recv(ctx).await?;
+                let number = block.number;
                 queue.send(block).await?;
+                metrics.observe(
+                    FetchStage::Applied,
+                    number,
+                    self.sync_state.get_main_node_block(),
+                );
             }
             Ok(())
         })
@@ -4,13 +4,16 @@
 //! This module simply glues APIs that are already publicly exposed by the `consensus` module,
 //! so in case any custom behavior is needed, these APIs should be used directly.
 
+use std::{num::NonZeroUsize, path::PathBuf};
+
 use zksync_concurrency::ctx;
 use zksync_config::configs::consensus::{ConsensusConfig, ConsensusSecrets};
 use zksync_dal::Core;
+use zksync_health_check::HealthUpdater;
 use zksync_node_sync::{sync_action::ActionQueueSender, SyncState};
 use zksync_web3_decl::client::{DynClient, L2};
 
-use super::{en, storage::ConnectionPool};
+use super::{en, storage::ConnectionPool, PeersHandle};
 
 /// Runs the consensus task in the main node mode.
 pub async fn run_main_node(
@@ -33,21 +36,35 @@ pub async fn run_main_node(
 /// Runs the consensus node for the external node.
 /// If `cfg` is `None`, it will just fetch blocks from the main node
 /// using JSON RPC, without starting the consensus node.
+/// If `secrets` contain a validator key, the node runs as a validator, which requires
+/// `validator_watermark_path` to be set. The role of the node is reported via `health_updater`.
+/// Up to `prefetch_depth` blocks are fetched from the main node ahead of the state keeper.
+/// `peers` allows banning gossip peers of the consensus node; it's ignored if `cfg` is `None`.
+#[allow(clippy::too_many_arguments)]
 pub async fn run_en(
     ctx: &ctx::Ctx,
     cfg: Option<(ConsensusConfig, ConsensusSecrets)>,
+    validator_watermark_path: Option<PathBuf>,
     pool: zksync_dal::ConnectionPool<Core>,
     sync_state: SyncState,
     main_node_client: Box<DynClient<L2>>,
     actions: ActionQueueSender,
+    health_updater: HealthUpdater,
+    prefetch_depth: NonZeroUsize,
+    peers: Option<PeersHandle>,
 ) -> anyhow::Result<()> {
     let en = en::EN {
         pool: ConnectionPool(pool),
         sync_state: sync_state.clone(),
         client: main_node_client.for_component("block_fetcher"),
+        health_updater,
+        prefetch_depth,
     };
     let res = match cfg {
-        Some((cfg, secrets)) => en.run(ctx, actions, cfg, secrets).await,
+        Some((cfg, secrets)) => {
+            en.run(ctx, actions, cfg, secrets, validator_watermark_path, peers)
+                .await
+        }
         None => en.run_fetcher(ctx, actions).await,
     };
     tracing::info!("Consensus actor stopped");
@@ -10,15 +10,22 @@ use zksync_consensus_roles::validator;
 use zksync_consensus_storage::BlockStore;
 
 use crate::storage::{ConnectionPool, Store};
+pub use crate::{
+    metrics::PeerDirection,
+    peers::{PeerInfo, PeersHandle},
+};
 
 mod config;
 mod en;
 pub mod era;
+mod metrics;
+mod peers;
 mod storage;
 #[cfg(test)]
 pub(crate) mod testonly;
 #[cfg(test)]
 mod tests;
+mod watermark;
 
 /// Task running a consensus validator for the main node.
 /// Main node is currently the only leader of the consensus - i.e. it proposes all the
@@ -0,0 +1,69 @@
+//! Metrics for the consensus component.
+
+use serde::Serialize;
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+use zksync_types::L2BlockNumber;
+
+/// Stage of the block fetching pipeline of the external node.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum FetchStage {
+    /// Block has been fetched from the main node.
+    Fetched,
+    /// Block has been decoded into sync actions.
+    Decoded,
+    /// Sync actions for the block have been passed to the state keeper.
+    Applied,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "consensus_en_fetcher")]
+pub(crate) struct FetcherMetrics {
+    /// Number of the last L2 block that has passed a certain stage of the pipeline.
+    pub last_block: Family<FetchStage, Gauge<u64>>,
+    /// Number of L2 blocks that a certain stage of the pipeline lags behind the main node.
+    pub lag: Family<FetchStage, Gauge<u64>>,
+}
+
+impl FetcherMetrics {
+    pub fn observe(&self, stage: FetchStage, block: L2BlockNumber, main_node_block: L2BlockNumber) {
+        self.last_block[&stage].set(block.0.into());
+        self.lag[&stage].set(main_node_block.0.saturating_sub(block.0).into());
+    }
+}
+
+#[vise::register]
+pub(crate) static FETCHER_METRICS: vise::Global<FetcherMetrics> = vise::Global::new();
+
+/// Direction of the gossip connection with a statically configured peer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, EncodeLabelValue)]
+#[serde(rename_all = "snake_case")]
+#[metrics(rename_all = "snake_case")]
+pub enum PeerDirection {
+    /// Peer is allowed to connect to the node.
+    Inbound,
+    /// Node connects to the peer.
+    Outbound,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(crate) struct PeerLabels {
+    pub peer: String,
+    pub direction: PeerDirection,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "consensus_en_network")]
+pub(crate) struct NetworkMetrics {
+    /// Number of L2 blocks that the last block received by the node lags behind the main node.
+    pub block_lag: Gauge<u64>,
+    /// Whether a statically configured gossip peer is banned (1) or not (0).
+    pub peer_banned: Family<PeerLabels, Gauge<u64>>,
+    /// Total number of banned gossip peers, including ones not present in the node config.
+    pub banned_peers: Gauge<usize>,
+    /// Number of consensus node restarts caused by changes in the set of banned peers.
+    pub node_restarts: Counter,
+}
+
+#[vise::register]
+pub(crate) static NETWORK_METRICS: vise::Global<NetworkMetrics> = vise::Global::new();
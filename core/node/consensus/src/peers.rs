@@ -0,0 +1,161 @@
+//! Control over gossip peers of the consensus node run by the external node.
+
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use zksync_concurrency::sync;
+use zksync_config::configs::consensus::ConsensusConfig;
+use zksync_consensus_crypto::{Text, TextFmt as _};
+use zksync_consensus_executor as executor;
+use zksync_consensus_roles::node;
+
+use crate::metrics::{PeerDirection, PeerLabels, NETWORK_METRICS};
+
+/// Information about a gossip peer of the consensus node.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PeerInfo {
+    /// Public key of the peer.
+    pub key: String,
+    pub direction: PeerDirection,
+    /// Address the node connects to; only set for outbound peers.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub addr: Option<String>,
+    pub banned: bool,
+}
+
+/// Handle allowing to list and ban / unban gossip peers of the consensus node.
+///
+/// Banning a peer removes it from the static gossip peers of the node; the consensus node is restarted
+/// with the updated configuration, which drops the existing connection with the peer.
+#[derive(Debug, Clone)]
+pub struct PeersHandle {
+    static_peers: Arc<[(node::PublicKey, PeerDirection, Option<String>)]>,
+    banned: Arc<sync::watch::Sender<HashSet<node::PublicKey>>>,
+}
+
+impl PeersHandle {
+    /// Creates a handle for the static gossip peers specified in the provided config.
+    pub fn new(cfg: &ConsensusConfig) -> anyhow::Result<Self> {
+        let inbound = cfg
+            .gossip_static_inbound
+            .iter()
+            .enumerate()
+            .map(|(i, key)| {
+                let key =
+                    decode_key(&key.0).with_context(|| format!("gossip_static_inbound[{i}]"))?;
+                anyhow::Ok((key, PeerDirection::Inbound, None))
+            });
+        let outbound = cfg
+            .gossip_static_outbound
+            .iter()
+            .enumerate()
+            .map(|(i, (key, addr))| {
+                let key =
+                    decode_key(&key.0).with_context(|| format!("gossip_static_outbound[{i}]"))?;
+                anyhow::Ok((key, PeerDirection::Outbound, Some(addr.0.clone())))
+            });
+        let static_peers = inbound.chain(outbound).collect::<anyhow::Result<_>>()?;
+        let this = Self {
+            static_peers,
+            banned: Arc::new(sync::watch::channel(HashSet::new()).0),
+        };
+        this.report_metrics(&HashSet::new());
+        Ok(this)
+    }
+
+    /// Lists static gossip peers of the node together with their ban status.
+    pub fn peers(&self) -> Vec<PeerInfo> {
+        let banned = self.banned.borrow();
+        let mut peers: Vec<_> = self
+            .static_peers
+            .iter()
+            .map(|(key, direction, addr)| PeerInfo {
+                key: key.encode(),
+                direction: *direction,
+                addr: addr.clone(),
+                banned: banned.contains(key),
+            })
+            .collect();
+        peers.sort_unstable_by(|a, b| (&a.key, a.direction).cmp(&(&b.key, b.direction)));
+        peers
+    }
+
+    /// Returns public keys of all banned peers, including ones not present in the node config.
+    pub fn banned_peers(&self) -> Vec<String> {
+        let mut keys: Vec<_> = self
+            .banned
+            .borrow()
+            .iter()
+            .map(|key| key.encode())
+            .collect();
+        keys.sort_unstable();
+        keys
+    }
+
+    /// Bans the peer with the specified public key. Returns `false` if the peer is already banned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be parsed.
+    pub fn ban(&self, key: &str) -> anyhow::Result<bool> {
+        let key = decode_key(key)?;
+        Ok(self.update(|banned| banned.insert(key)))
+    }
+
+    /// Unbans the peer with the specified public key. Returns `false` if the peer isn't banned.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be parsed.
+    pub fn unban(&self, key: &str) -> anyhow::Result<bool> {
+        let key = decode_key(key)?;
+        Ok(self.update(|banned| banned.remove(&key)))
+    }
+
+    fn update(&self, action: impl FnOnce(&mut HashSet<node::PublicKey>) -> bool) -> bool {
+        let mut changed = false;
+        self.banned.send_if_modified(|banned| {
+            changed = action(banned);
+            if changed {
+                self.report_metrics(banned);
+            }
+            changed
+        });
+        changed
+    }
+
+    fn report_metrics(&self, banned: &HashSet<node::PublicKey>) {
+        for (key, direction, _) in self.static_peers.iter() {
+            let labels = PeerLabels {
+                peer: key.encode(),
+                direction: *direction,
+            };
+            NETWORK_METRICS.peer_banned[&labels].set(banned.contains(key).into());
+        }
+        NETWORK_METRICS.banned_peers.set(banned.len());
+    }
+
+    pub(crate) fn subscribe(&self) -> sync::watch::Receiver<HashSet<node::PublicKey>> {
+        self.banned.subscribe()
+    }
+}
+
+/// Removes banned peers from the gossip config of the executor.
+pub(crate) fn remove_banned_peers(
+    config: &mut executor::Config,
+    banned: &HashSet<node::PublicKey>,
+) {
+    config
+        .gossip_static_inbound
+        .retain(|key| !banned.contains(key));
+    config
+        .gossip_static_outbound
+        .retain(|key, _| !banned.contains(key));
+}
+
+fn decode_key(key: &str) -> anyhow::Result<node::PublicKey> {
+    Text::new(key)
+        .decode()
+        .map_err(|_| anyhow::format_err!("invalid node public key: {key}"))
+}
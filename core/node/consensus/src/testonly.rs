@@ -1,6 +1,6 @@
 //! Utilities for testing the consensus module.
 
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
 
 use anyhow::Context as _;
 use rand::Rng;
@@ -10,6 +10,7 @@ use zksync_consensus_crypto::TextFmt as _;
 use zksync_consensus_network as network;
 use zksync_consensus_roles::validator;
 use zksync_dal::{CoreDal, DalError};
+use zksync_health_check::ReactiveHealthCheck;
 use zksync_node_api_server::web3::{state::InternalApiConfig, testonly::spawn_http_server};
 use zksync_node_genesis::GenesisParams;
 use zksync_node_sync::{
@@ -28,7 +29,7 @@ use zksync_state_keeper::{
 use zksync_types::{Address, L1BatchNumber, L2BlockNumber, L2ChainId, ProtocolVersionId};
 use zksync_web3_decl::client::{Client, DynClient, L2};
 
-use crate::{en, ConnectionPool};
+use crate::{en, ConnectionPool, PeersHandle};
 
 /// Fake StateKeeper for tests.
 pub(super) struct StateKeeper {
@@ -243,6 +244,8 @@ impl StateKeeper {
             pool: self.pool,
             client,
             sync_state: self.sync_state.clone(),
+            health_updater: ReactiveHealthCheck::new("consensus").1,
+            prefetch_depth: NonZeroUsize::new(30).unwrap(),
         }
         .run_fetcher(ctx, self.actions_sender)
         .await
@@ -254,14 +257,35 @@ impl StateKeeper {
         ctx: &ctx::Ctx,
         client: Box<DynClient<L2>>,
         cfg: &network::Config,
+    ) -> anyhow::Result<()> {
+        self.run_consensus_with_peers(ctx, client, cfg, None).await
+    }
+
+    /// Runs consensus node for the external node with the specified handle for its gossip peers.
+    pub async fn run_consensus_with_peers(
+        self,
+        ctx: &ctx::Ctx,
+        client: Box<DynClient<L2>>,
+        cfg: &network::Config,
+        peers: Option<PeersHandle>,
     ) -> anyhow::Result<()> {
         let (cfg, secrets) = config(cfg);
+        let watermark_dir = tempfile::TempDir::new().context("TempDir::new()")?;
         en::EN {
             pool: self.pool,
             client,
             sync_state: self.sync_state.clone(),
+            health_updater: ReactiveHealthCheck::new("consensus").1,
+            prefetch_depth: NonZeroUsize::new(30).unwrap(),
         }
-        .run(ctx, self.actions_sender, cfg, secrets)
+        .run(
+            ctx,
+            self.actions_sender,
+            cfg,
+            secrets,
+            Some(watermark_dir.path().join("watermark.json")),
+            peers,
+        )
         .await
     }
 }
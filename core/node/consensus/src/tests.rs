@@ -1,7 +1,7 @@
 use anyhow::Context as _;
 use test_casing::test_casing;
 use tracing::Instrument as _;
-use zksync_concurrency::{ctx, scope};
+use zksync_concurrency::{ctx, scope, time};
 use zksync_config::configs::consensus::{ValidatorPublicKey, WeightedValidator};
 use zksync_consensus_crypto::TextFmt as _;
 use zksync_consensus_network::testonly::{new_configs, new_fullnode};
@@ -9,10 +9,12 @@ use zksync_consensus_roles::{
     validator,
     validator::testonly::{Setup, SetupSpec},
 };
+use zksync_consensus_storage::{ReplicaState, ReplicaStore as _};
 use zksync_node_test_utils::Snapshot;
 use zksync_types::{L1BatchNumber, L2BlockNumber};
 
 use super::*;
+use crate::watermark::{Watermark, WatermarkFile, WatermarkedReplicaStore};
 
 async fn new_pool(from_snapshot: bool) -> ConnectionPool {
     match from_snapshot {
@@ -86,6 +88,72 @@ async fn test_validator_block_store() {
     }
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn test_validator_watermark() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::RealClock);
+    let rng = &mut ctx.rng();
+    let pool = new_pool(false).await;
+    let setup = Setup::new(rng, 1);
+    let fork_number = setup.genesis.fork_number;
+    let dir = tempfile::TempDir::new().unwrap();
+    let watermark_path = dir.path().join("watermark.json");
+    let new_replica_store = |store: Store| {
+        WatermarkedReplicaStore::new(
+            store,
+            WatermarkFile::new(watermark_path.clone()),
+            fork_number,
+        )
+    };
+
+    scope::run!(ctx, |ctx, _| async {
+        pool.connection(ctx)
+            .await
+            .wrap("connection()")?
+            .try_update_genesis(ctx, &setup.genesis)
+            .await
+            .wrap("try_update_genesis()")?;
+        let (store, _runner) = Store::new(ctx, pool.clone(), None)
+            .await
+            .wrap("Store::new()")?;
+
+        let replica_store = new_replica_store(store.clone())?;
+        assert_eq!(replica_store.watermark(), None);
+        let mut state = replica_store.state(ctx).await?;
+        state.view = validator::ViewNumber(5);
+        replica_store.set_state(ctx, &state).await?;
+        let want = Watermark {
+            fork_number: fork_number.0,
+            view: 5,
+        };
+        assert_eq!(replica_store.watermark(), Some(want));
+
+        // Restarting the node with the same replica state shouldn't skip any views.
+        let replica_store = new_replica_store(store.clone())?;
+        assert_eq!(replica_store.watermark(), Some(want));
+        assert_eq!(
+            replica_store.state(ctx).await?.view,
+            validator::ViewNumber(5)
+        );
+
+        // Emulate losing the replica state, e.g. after the database was recreated.
+        store.set_state(ctx, &ReplicaState::default()).await?;
+        let replica_store = new_replica_store(store.clone())?;
+        let state = replica_store.state(ctx).await?;
+        assert_eq!(state.view, validator::ViewNumber(6));
+
+        // Signing in an earlier fork is not allowed.
+        WatermarkFile::new(watermark_path.clone()).store(Watermark {
+            fork_number: fork_number.0 + 1,
+            view: 0,
+        })?;
+        new_replica_store(store).unwrap_err();
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
+
 // In the current implementation, consensus certificates are created asynchronously
 // for the L2 blocks constructed by the StateKeeper. This means that consensus actor
 // is effectively just back filling the consensus certificates for the L2 blocks in storage.
@@ -515,3 +583,98 @@ async fn test_centralized_fetcher(from_snapshot: bool) {
     .await
     .unwrap();
 }
+
+#[test]
+fn test_banning_peers() {
+    let ctx = &ctx::test_root(&ctx::RealClock);
+    let rng = &mut ctx.rng();
+    let setup = Setup::new(rng, 1);
+    let validator_cfg = new_configs(rng, &setup, 0)[0].clone();
+    let node_cfg = new_fullnode(rng, &validator_cfg);
+    let (cfg, _) = testonly::config(&node_cfg);
+    let validator_key = validator_cfg.gossip.key.public().encode();
+    let unknown_key = new_fullnode(rng, &validator_cfg)
+        .gossip
+        .key
+        .public()
+        .encode();
+
+    let peers = PeersHandle::new(&cfg).unwrap();
+    assert_eq!(
+        peers.peers(),
+        [PeerInfo {
+            key: validator_key.clone(),
+            direction: PeerDirection::Outbound,
+            addr: Some(validator_cfg.public_addr.0.clone()),
+            banned: false,
+        }]
+    );
+    assert!(peers.banned_peers().is_empty());
+
+    assert!(peers.ban(&validator_key).unwrap());
+    assert!(!peers.ban(&validator_key).unwrap());
+    assert!(peers.ban(&unknown_key).unwrap());
+    assert!(peers.peers()[0].banned);
+    let mut expected_banned = vec![validator_key.clone(), unknown_key.clone()];
+    expected_banned.sort_unstable();
+    assert_eq!(peers.banned_peers(), expected_banned);
+
+    assert!(peers.unban(&validator_key).unwrap());
+    assert!(!peers.unban(&validator_key).unwrap());
+    assert!(!peers.peers()[0].banned);
+    assert_eq!(peers.banned_peers(), [unknown_key]);
+    peers.ban("not a key").unwrap_err();
+}
+
+// Test that a full node doesn't receive blocks from a banned peer until it's unbanned.
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_fetcher_with_banned_peer() {
+    zksync_concurrency::testonly::abort_on_panic();
+    let ctx = &ctx::test_root(&ctx::AffineClock::new(10.));
+    let rng = &mut ctx.rng();
+    let setup = Setup::new(rng, 1);
+    let validator_cfg = new_configs(rng, &setup, 0)[0].clone();
+    let node_cfg = new_fullnode(rng, &validator_cfg);
+
+    scope::run!(ctx, |ctx, s| async {
+        tracing::info!("Spawn validator.");
+        let validator_pool = new_pool(false).await;
+        let (mut validator, runner) =
+            testonly::StateKeeper::new(ctx, validator_pool.clone()).await?;
+        s.spawn_bg(runner.run(ctx));
+        let (cfg, secrets) = testonly::config(&validator_cfg);
+        s.spawn_bg(run_main_node(ctx, cfg, secrets, validator_pool.clone()));
+        // API server needs at least 1 L1 batch to start.
+        validator.seal_batch().await;
+        let client = validator.connect(ctx).await?;
+
+        tracing::info!("Run p2p fetcher with the validator banned.");
+        let node_pool = new_pool(false).await;
+        let peers = PeersHandle::new(&testonly::config(&node_cfg).0)?;
+        assert!(peers.ban(&validator_cfg.gossip.key.public().encode())?);
+        let (node, runner) = testonly::StateKeeper::new(ctx, node_pool.clone()).await?;
+        s.spawn_bg(runner.run(ctx));
+        s.spawn_bg(node.run_consensus_with_peers(ctx, client, &node_cfg, Some(peers.clone())));
+        validator.push_random_blocks(rng, 3).await;
+        validator_pool
+            .wait_for_certificate(ctx, validator.last_block())
+            .await?;
+        ctx.sleep(time::Duration::seconds(5)).await?;
+        let mut conn = node_pool.connection(ctx).await?;
+        assert!(conn.last_certificate(ctx).await?.is_none());
+        drop(conn);
+
+        tracing::info!("Unban the validator.");
+        assert!(peers.unban(&validator_cfg.gossip.key.public().encode())?);
+        let want = validator_pool
+            .wait_for_certificates_and_verify(ctx, validator.last_block())
+            .await?;
+        let got = node_pool
+            .wait_for_certificates_and_verify(ctx, validator.last_block())
+            .await?;
+        assert_eq!(want, got);
+        Ok(())
+    })
+    .await
+    .unwrap();
+}
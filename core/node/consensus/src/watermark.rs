@@ -0,0 +1,153 @@
+//! High-watermark of consensus views signed by a validator, persisted outside of Postgres.
+//!
+//! Consensus safety relies on a validator never signing two different messages in the same view. The replica state
+//! stored in Postgres already tracks the current view, but it is lost if the database is recreated (e.g., recovered
+//! from a snapshot) while the node keeps its validator key. The watermark stored in a local file allows detecting
+//! such a situation and skipping all views that could have been signed before.
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use anyhow::Context as _;
+use serde::{Deserialize, Serialize};
+use zksync_concurrency::ctx;
+use zksync_consensus_roles::validator;
+use zksync_consensus_storage::{self as storage, ReplicaStore as _};
+
+use crate::storage::Store;
+
+/// Highest view signed by the validator. Ordered by the fork number first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub(super) struct Watermark {
+    pub fork_number: u64,
+    pub view: u64,
+}
+
+/// Local file persisting the [`Watermark`].
+#[derive(Debug, Clone)]
+pub(super) struct WatermarkFile {
+    path: PathBuf,
+}
+
+impl WatermarkFile {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Loads the watermark. Returns `None` if the file doesn't exist yet.
+    pub fn load(&self) -> anyhow::Result<Option<Watermark>> {
+        let raw = match fs::read(&self.path) {
+            Ok(raw) => raw,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => {
+                return Err(
+                    anyhow::Error::new(err).context(format!("failed reading {:?}", self.path))
+                );
+            }
+        };
+        let watermark = serde_json::from_slice(&raw)
+            .with_context(|| format!("failed parsing watermark from {:?}", self.path))?;
+        Ok(Some(watermark))
+    }
+
+    /// Atomically replaces the persisted watermark, making sure that it's flushed to the disk.
+    pub fn store(&self, watermark: Watermark) -> anyhow::Result<()> {
+        let tmp_path = self.path.with_extension("tmp");
+        let raw = serde_json::to_vec(&watermark).expect("failed serializing watermark");
+        let file =
+            fs::File::create(&tmp_path).with_context(|| format!("failed creating {tmp_path:?}"))?;
+        io::Write::write_all(&mut &file, &raw)
+            .and_then(|()| file.sync_all())
+            .with_context(|| format!("failed writing {tmp_path:?}"))?;
+        fs::rename(&tmp_path, &self.path)
+            .with_context(|| format!("failed renaming {tmp_path:?} to {:?}", self.path))?;
+        Ok(())
+    }
+}
+
+/// Replica store advancing the persisted [`Watermark`] before any replica state (and thus any signed message)
+/// is persisted, and skipping views below the watermark if the replica state in Postgres is behind it.
+#[derive(Debug)]
+pub(super) struct WatermarkedReplicaStore {
+    inner: Store,
+    file: WatermarkFile,
+    fork_number: validator::ForkNumber,
+    watermark: Mutex<Option<Watermark>>,
+}
+
+impl WatermarkedReplicaStore {
+    /// Creates a store for the fork with the specified number. Fails if the validator has already signed
+    /// messages in a later fork.
+    pub fn new(
+        inner: Store,
+        file: WatermarkFile,
+        fork_number: validator::ForkNumber,
+    ) -> anyhow::Result<Self> {
+        let watermark = file.load()?;
+        if let Some(watermark) = watermark {
+            anyhow::ensure!(
+                watermark.fork_number <= fork_number.0,
+                "validator has signed messages in fork #{} according to {:?}, refusing to sign in \
+                 the earlier fork #{}",
+                watermark.fork_number,
+                file.path(),
+                fork_number.0
+            );
+        }
+        Ok(Self {
+            inner,
+            file,
+            fork_number,
+            watermark: Mutex::new(watermark),
+        })
+    }
+
+    /// Returns the watermark as of the last persisted replica state.
+    pub fn watermark(&self) -> Option<Watermark> {
+        *self.watermark.lock().unwrap()
+    }
+}
+
+#[async_trait::async_trait]
+impl storage::ReplicaStore for WatermarkedReplicaStore {
+    async fn state(&self, ctx: &ctx::Ctx) -> ctx::Result<storage::ReplicaState> {
+        let mut state = self.inner.state(ctx).await?;
+        let Some(watermark) = self.watermark() else {
+            return Ok(state);
+        };
+        if watermark.fork_number == self.fork_number.0 && state.view.0 < watermark.view {
+            tracing::warn!(
+                "Stored replica state (view {}) is behind the validator watermark {watermark:?}; \
+                 skipping views up to the watermark to prevent double-signing",
+                state.view.0
+            );
+            state.view = validator::ViewNumber(watermark.view + 1);
+            state.phase = validator::Phase::Prepare;
+        }
+        Ok(state)
+    }
+
+    async fn set_state(&self, ctx: &ctx::Ctx, state: &storage::ReplicaState) -> ctx::Result<()> {
+        let watermark = Watermark {
+            fork_number: self.fork_number.0,
+            view: state.view.0,
+        };
+        {
+            let mut persisted = self.watermark.lock().unwrap();
+            if persisted.map_or(true, |persisted| persisted < watermark) {
+                self.file
+                    .store(watermark)
+                    .context("WatermarkFile::store()")?;
+                *persisted = Some(watermark);
+            }
+        }
+        self.inner.set_state(ctx, state).await
+    }
+}
@@ -11,7 +11,10 @@ use zksync_eth_client::{
 };
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_l1_contract_interface::{
-    i_executor::{commit::kzg::ZK_SYNC_BYTES_PER_BLOB, structures::CommitBatchInfo},
+    i_executor::{
+        commit::kzg::{KzgInfo, ZK_SYNC_BYTES_PER_BLOB},
+        structures::CommitBatchInfo,
+    },
     Tokenizable,
 };
 use zksync_shared_metrics::{CheckerComponent, EN_METRICS};
@@ -20,6 +23,7 @@ use zksync_types::{
     ethabi,
     ethabi::Token,
     pubdata_da::PubdataDA,
+    web3::Log,
     Address, L1BatchNumber, ProtocolVersionId, H256, U256,
 };
 
@@ -47,6 +51,19 @@ impl CheckError {
     }
 }
 
+/// Mismatch between blobs attached to the L1 commit transaction and the locally computed pubdata.
+/// Such mismatches are additionally reported in a dedicated health check field.
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "blobs attached to commit tx {commit_tx_hash:?} differ from locally computed pubdata; \
+     local versioned hashes: {local:?}, L1 versioned hashes: {reference:?}"
+)]
+struct BlobMismatch {
+    commit_tx_hash: H256,
+    local: Vec<H256>,
+    reference: Vec<H256>,
+}
+
 /// Handler of life cycle events emitted by [`ConsistencyChecker`].
 trait HandleConsistencyCheckerEvent: fmt::Debug + Send + Sync {
     fn initialize(&mut self);
@@ -67,6 +84,9 @@ struct ConsistencyCheckerDetails {
     last_checked_batch: Option<L1BatchNumber>,
     #[serde(skip_serializing_if = "Vec::is_empty")]
     inconsistent_batches: Vec<L1BatchNumber>,
+    /// Subset of `inconsistent_batches` for which pubdata published in blobs on L1 differs from the local one.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    inconsistent_blob_batches: Vec<L1BatchNumber>,
 }
 
 impl ConsistencyCheckerDetails {
@@ -119,6 +139,9 @@ impl HandleConsistencyCheckerEvent for ConsistencyCheckerHealthUpdater {
     fn report_inconsistent_batch(&mut self, number: L1BatchNumber, err: &anyhow::Error) {
         tracing::warn!("L1 batch #{number} is inconsistent with L1: {err:?}");
         self.current_details.inconsistent_batches.push(number);
+        if err.downcast_ref::<BlobMismatch>().is_some() {
+            self.current_details.inconsistent_blob_batches.push(number);
+        }
         self.inner.update(self.current_details.health());
     }
 }
@@ -205,15 +228,15 @@ impl LocalL1BatchCommitData {
             .map_or(true, |version| version.is_pre_boojum())
     }
 
-    fn is_pre_shared_bridge(&self) -> bool {
-        self.l1_batch
-            .header
-            .protocol_version
-            .map_or(true, |version| version.is_pre_shared_bridge())
+    fn pubdata(&self) -> Cow<'_, [u8]> {
+        match &self.l1_batch.header.pubdata_input {
+            Some(pubdata) => Cow::Borrowed(pubdata.as_slice()),
+            None => Cow::Owned(self.l1_batch.construct_pubdata()),
+        }
     }
 
     /// All returned errors are validation errors.
-    fn verify_commitment(&self, reference: &ethabi::Token) -> anyhow::Result<()> {
+    fn verify_commitment(&self, reference: &ethabi::Token) -> anyhow::Result<PubdataDA> {
         let protocol_version = self
             .l1_batch
             .header
@@ -224,12 +247,7 @@ impl LocalL1BatchCommitData {
 
         // For `PubdataDA::Calldata`, it's required that the pubdata fits into a single blob.
         if matches!(da, PubdataDA::Calldata) {
-            let pubdata_len = self
-                .l1_batch
-                .header
-                .pubdata_input
-                .as_ref()
-                .map_or_else(|| self.l1_batch.construct_pubdata().len(), Vec::len);
+            let pubdata_len = self.pubdata().len();
             anyhow::ensure!(
                 pubdata_len <= ZK_SYNC_BYTES_PER_BLOB,
                 "pubdata size is too large when using calldata DA source: expected <={ZK_SYNC_BYTES_PER_BLOB} bytes, \
@@ -244,6 +262,35 @@ impl LocalL1BatchCommitData {
             "Locally reproduced commitment differs from the reference obtained from L1; \
              local: {local_token:?}, reference: {reference:?}"
         );
+        Ok(da)
+    }
+
+    /// Verifies that blobs attached to the commit transaction correspond to the locally computed pubdata.
+    /// Blobs are posted both in the rollup and validium modes, so the check is independent of the commitment mode.
+    /// Since blobs are committed to by their versioned hashes, it is sufficient to compare versioned hashes
+    /// rather than fetching blob sidecars.
+    ///
+    /// All returned errors are validation errors.
+    fn verify_blobs(&self, reference: Option<&[H256]>) -> anyhow::Result<()> {
+        let reference = reference.with_context(|| {
+            format!(
+                "commit tx {:?} uses blobs DA source, but has no blob versioned hashes",
+                self.commit_tx_hash
+            )
+        })?;
+        let local: Vec<_> = self
+            .pubdata()
+            .chunks(ZK_SYNC_BYTES_PER_BLOB)
+            .map(|blob| H256(KzgInfo::new(blob).versioned_hash))
+            .collect();
+        if local != reference {
+            return Err(BlobMismatch {
+                commit_tx_hash: self.commit_tx_hash,
+                local,
+                reference: reference.to_vec(),
+            }
+            .into());
+        }
         Ok(())
     }
 }
@@ -299,6 +346,55 @@ pub fn detect_da(
     }
 }
 
+/// Returns the L1 contract function used to commit L1 batches with the specified protocol version.
+/// Batches with an unknown protocol version are considered to be pre-Boojum.
+pub fn commit_function(
+    contract: &ethabi::Contract,
+    protocol_version: Option<ProtocolVersionId>,
+) -> anyhow::Result<&ethabi::Function> {
+    let protocol_version = match protocol_version {
+        Some(version) if !version.is_pre_boojum() => version,
+        _ => return Ok(&*PRE_BOOJUM_COMMIT_FUNCTION),
+    };
+    if protocol_version.is_pre_shared_bridge() {
+        contract
+            .function("commitBatches")
+            .context("L1 contract does not have `commitBatches` function")
+    } else {
+        contract
+            .function("commitBatchesSharedBridge")
+            .context("L1 contract does not have `commitBatchesSharedBridge` function")
+    }
+}
+
+/// Returns numbers of L1 batches committed according to the `BlockCommit` event logs emitted by the diamond proxy.
+/// Logs emitted by other contracts are ignored, so that the commit transaction may be routed via another contract
+/// (e.g., `ValidatorTimelock`).
+pub fn committed_batch_numbers_by_logs(
+    block_commit_event: &ethabi::Event,
+    diamond_proxy_addr: Address,
+    logs: Vec<Log>,
+) -> HashSet<U256> {
+    let batch_numbers = logs.into_iter().filter_map(|log| {
+        if log.address != diamond_proxy_addr {
+            return None;
+        }
+        let parsed_log = block_commit_event
+            .parse_log_whole(ethabi::RawLog {
+                topics: log.topics,
+                data: log.data.0,
+            })
+            .ok()?;
+
+        parsed_log.params.into_iter().find_map(|param| {
+            (param.name == "batchNumber")
+                .then_some(param.value)
+                .and_then(ethabi::Token::into_uint)
+        })
+    });
+    batch_numbers.collect()
+}
+
 #[derive(Debug)]
 pub struct ConsistencyChecker {
     /// ABI of the zkSync contract
@@ -384,26 +480,11 @@ impl ConsistencyChecker {
                 .context("`BlockCommit` event not found for zkSync L1 contract")
                 .map_err(CheckError::Internal)?;
 
-            let committed_batch_numbers_by_logs =
-                commit_tx_status.receipt.logs.into_iter().filter_map(|log| {
-                    if log.address != diamond_proxy_addr {
-                        return None;
-                    }
-                    let parsed_log = event
-                        .parse_log_whole(ethabi::RawLog {
-                            topics: log.topics,
-                            data: log.data.0,
-                        })
-                        .ok()?;
-
-                    parsed_log.params.into_iter().find_map(|param| {
-                        (param.name == "batchNumber")
-                            .then_some(param.value)
-                            .and_then(ethabi::Token::into_uint)
-                    })
-                });
-            let committed_batch_numbers_by_logs: HashSet<_> =
-                committed_batch_numbers_by_logs.collect();
+            let committed_batch_numbers_by_logs = committed_batch_numbers_by_logs(
+                event,
+                diamond_proxy_addr,
+                commit_tx_status.receipt.logs,
+            );
             tracing::debug!(
                 "Commit transaction {commit_tx_hash:?} has `BlockCommit` event logs with the following batch numbers: \
                  {committed_batch_numbers_by_logs:?}"
@@ -417,19 +498,9 @@ impl ConsistencyChecker {
             }
         }
 
-        let commit_function = if local.is_pre_boojum() {
-            &*PRE_BOOJUM_COMMIT_FUNCTION
-        } else if local.is_pre_shared_bridge() {
-            self.contract
-                .function("commitBatches")
-                .context("L1 contract does not have `commitBatches` function")
-                .map_err(CheckError::Internal)?
-        } else {
-            self.contract
-                .function("commitBatchesSharedBridge")
-                .context("L1 contract does not have `commitBatchesSharedBridge` function")
-                .map_err(CheckError::Internal)?
-        };
+        let commit_function =
+            commit_function(&self.contract, local.l1_batch.header.protocol_version)
+                .map_err(CheckError::Internal)?;
 
         let commitment =
             Self::extract_commit_data(&commit_tx.input.0, commit_function, batch_number)
@@ -437,13 +508,21 @@ impl ConsistencyChecker {
                     format!("failed extracting commit data for transaction {commit_tx_hash:?}")
                 })
                 .map_err(CheckError::Validation)?;
-        local
+        let da = local
             .verify_commitment(&commitment)
-            .map_err(CheckError::Validation)
+            .map_err(CheckError::Validation)?;
+        if matches!(da, PubdataDA::Blobs) {
+            local
+                .verify_blobs(commit_tx.blob_versioned_hashes.as_deref())
+                .map_err(CheckError::Validation)?;
+        }
+        Ok(())
     }
 
+    /// Extracts the commitment for the specified L1 batch from the calldata of a commit transaction.
+    ///
     /// All returned errors are validation errors.
-    fn extract_commit_data(
+    pub fn extract_commit_data(
         commit_tx_input_data: &[u8],
         commit_function: &ethabi::Function,
         batch_number: L1BatchNumber,
@@ -250,6 +250,45 @@ fn extracting_commit_data_for_pre_boojum_batch() {
     );
 }
 
+#[test]
+fn verifying_blobs() {
+    let mut l1_batch = create_l1_batch_with_metadata(1);
+    l1_batch.header.pubdata_input = Some(vec![1; ZK_SYNC_BYTES_PER_BLOB + 100]);
+    let local = LocalL1BatchCommitData {
+        l1_batch,
+        commit_tx_hash: H256::repeat_byte(1),
+        commitment_mode: L1BatchCommitmentMode::Rollup,
+    };
+    let versioned_hashes: Vec<_> = local
+        .pubdata()
+        .chunks(ZK_SYNC_BYTES_PER_BLOB)
+        .map(|blob| H256(KzgInfo::new(blob).versioned_hash))
+        .collect();
+    assert_eq!(versioned_hashes.len(), 2);
+
+    local.verify_blobs(Some(&versioned_hashes)).unwrap();
+    let err = local.verify_blobs(None).unwrap_err();
+    assert!(err.downcast_ref::<BlobMismatch>().is_none(), "{err:?}");
+    let err = local
+        .verify_blobs(Some(&versioned_hashes[..1]))
+        .unwrap_err();
+    assert_matches!(err.downcast_ref::<BlobMismatch>(), Some(_));
+    let err = local
+        .verify_blobs(Some(&[versioned_hashes[1], versioned_hashes[0]]))
+        .unwrap_err();
+    assert_matches!(err.downcast_ref::<BlobMismatch>(), Some(_));
+
+    let (_, mut health_updater) = ConsistencyCheckerHealthUpdater::new();
+    health_updater.report_inconsistent_batch(L1BatchNumber(1), &err);
+    health_updater.report_inconsistent_batch(L1BatchNumber(2), &anyhow::anyhow!("oops"));
+    let details = &health_updater.current_details;
+    assert_eq!(
+        details.inconsistent_batches,
+        [L1BatchNumber(1), L1BatchNumber(2)]
+    );
+    assert_eq!(details.inconsistent_blob_batches, [L1BatchNumber(1)]);
+}
+
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum SaveAction<'a> {
     InsertBatch(&'a L1BatchWithMetadata),
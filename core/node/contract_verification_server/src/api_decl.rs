@@ -50,6 +50,10 @@ impl RestApi {
                 "/contract_verification/info/:address",
                 axum::routing::get(Self::verification_info),
             )
+            .route(
+                "/contract_verification/bytecode_info/:bytecode_hash",
+                axum::routing::get(Self::bytecode_verification_info),
+            )
             .layer(CorsLayer::permissive())
             .with_state(Arc::new(self))
     }
@@ -7,7 +7,11 @@ use axum::{
 };
 use serde::Serialize;
 use zksync_dal::CoreDal;
-use zksync_types::{contract_verification_api::VerificationIncomingRequest, Address};
+use zksync_types::{
+    contract_verification_api::{VerificationIncomingRequest, VerificationTarget},
+    system_contracts::is_system_contract_address,
+    Address, H256,
+};
 
 use super::{api_decl::RestApi, metrics::METRICS};
 
@@ -48,7 +52,7 @@ impl RestApi {
     #[tracing::instrument(skip(self_, request))]
     pub async fn verification(
         State(self_): State<Arc<Self>>,
-        Json(request): Json<VerificationIncomingRequest>,
+        Json(mut request): Json<VerificationIncomingRequest>,
     ) -> Response<String> {
         let method_latency = METRICS.call[&"contract_verification"].start();
         if let Err(res) = Self::validate_contract_verification_query(&request) {
@@ -60,12 +64,35 @@ impl RestApi {
             .await
             .unwrap();
 
-        if !storage
-            .storage_logs_dal()
-            .is_contract_deployed_at_address(request.contract_address)
-            .await
-        {
-            return bad_request("There is no deployed contract on this address");
+        match request.target {
+            VerificationTarget::Address { contract_address } => {
+                if !storage
+                    .storage_logs_dal()
+                    .is_contract_deployed_at_address(contract_address)
+                    .await
+                {
+                    return bad_request("There is no deployed contract on this address");
+                }
+                // System contracts can only be compiled in the system mode.
+                if is_system_contract_address(contract_address) {
+                    request.is_system = true;
+                }
+            }
+            VerificationTarget::BytecodeHash { bytecode_hash } => {
+                if !request.constructor_arguments.0.is_empty() {
+                    return bad_request(
+                        "Constructor arguments cannot be checked for a bytecode without a deployment transaction",
+                    );
+                }
+                let bytecode = storage
+                    .factory_deps_dal()
+                    .get_sealed_factory_dep(bytecode_hash)
+                    .await
+                    .unwrap();
+                if bytecode.is_none() {
+                    return bad_request("There is no bytecode with this hash");
+                }
+            }
         }
 
         let request_id = storage
@@ -192,4 +219,28 @@ impl RestApi {
             None => not_found(),
         }
     }
+
+    #[tracing::instrument(skip(self_))]
+    pub async fn bytecode_verification_info(
+        State(self_): State<Arc<Self>>,
+        bytecode_hash: Path<H256>,
+    ) -> Response<String> {
+        let method_latency = METRICS.call[&"contract_verification_bytecode_info"].start();
+
+        let info = self_
+            .replica_connection_pool
+            .connection_tagged("api")
+            .await
+            .unwrap()
+            .contract_verification_dal()
+            .get_bytecode_verification_info(*bytecode_hash)
+            .await
+            .unwrap();
+
+        method_latency.observe();
+        match info {
+            Some(info) => ok_json(info),
+            None => not_found(),
+        }
+    }
 }
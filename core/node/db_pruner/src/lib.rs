@@ -1,19 +1,20 @@
 //! Postgres pruning component.
 
-use std::{fmt, sync::Arc, time::Duration};
+use std::{fmt, ops, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
 use serde::Serialize;
-use tokio::sync::watch;
-use zksync_dal::{pruning_dal::PruningInfo, Connection, ConnectionPool, Core, CoreDal};
+use tokio::sync::{watch, Mutex, Notify};
+use zksync_dal::{pruning_dal::PruningInfo, Connection, ConnectionPool, Core, CoreDal, DalError};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{L1BatchNumber, L2BlockNumber};
 
 use self::{
     metrics::{MetricPruneType, METRICS},
     prune_conditions::{
-        ConsistencyCheckerProcessedBatch, L1BatchExistsCondition, L1BatchOlderThanPruneCondition,
+        ConsistencyCheckerProcessedBatch, L1BatchArchivedCondition, L1BatchExistsCondition,
+        L1BatchNotProtectedCondition, L1BatchOlderThanPruneCondition,
         NextL1BatchHasMetadataCondition, NextL1BatchWasExecutedCondition,
     },
 };
@@ -66,6 +67,95 @@ pub struct DbPruner {
     connection_pool: ConnectionPool<Core>,
     health_updater: HealthUpdater,
     prune_conditions: Vec<Arc<dyn PruneCondition>>,
+    trigger: Arc<Notify>,
+    protection_lock: Arc<Mutex<()>>,
+}
+
+/// Errors that can occur when protecting an L1 batch range from pruning.
+#[derive(Debug, thiserror::Error)]
+pub enum ProtectRangeError {
+    #[error("L1 batch range {0:?} is empty")]
+    EmptyRange(ops::RangeInclusive<L1BatchNumber>),
+    #[error("L1 batch range {range:?} cannot be protected: L1 batches up to {last_pruned_l1_batch} are already pruned")]
+    AlreadyPruned {
+        range: ops::RangeInclusive<L1BatchNumber>,
+        last_pruned_l1_batch: L1BatchNumber,
+    },
+    #[error(transparent)]
+    Internal(#[from] DalError),
+}
+
+/// Handle allowing to control [`DbPruner`] at runtime (e.g., from an admin endpoint).
+#[derive(Debug, Clone)]
+pub struct DbPrunerHandle {
+    connection_pool: ConnectionPool<Core>,
+    trigger: Arc<Notify>,
+    /// Serializes protecting L1 batch ranges with soft pruning, so that a range cannot be protected
+    /// after the pruner has checked protections, but before it has soft-pruned batches.
+    protection_lock: Arc<Mutex<()>>,
+}
+
+impl DbPrunerHandle {
+    /// Makes the pruner run a pruning iteration immediately instead of waiting for the next scheduled one.
+    /// If the pruner is currently running an iteration, the next iteration will start right after it.
+    pub fn trigger_pruning(&self) {
+        self.trigger.notify_one();
+    }
+
+    /// Returns all L1 batch ranges protected from pruning.
+    pub async fn protected_l1_batch_ranges(
+        &self,
+    ) -> anyhow::Result<Vec<ops::RangeInclusive<L1BatchNumber>>> {
+        let mut storage = self.connection_pool.connection_tagged("db_pruner").await?;
+        Ok(storage
+            .pruning_dal()
+            .get_protected_l1_batch_ranges()
+            .await?)
+    }
+
+    /// Protects the specified L1 batch range from pruning. Returns `false` if the range is already protected.
+    ///
+    /// Since pruning removes all data up to and including the pruned L1 batch, protecting a range also prevents
+    /// pruning of all L1 batches following it.
+    pub async fn protect_l1_batch_range(
+        &self,
+        range: ops::RangeInclusive<L1BatchNumber>,
+    ) -> Result<bool, ProtectRangeError> {
+        if range.is_empty() {
+            return Err(ProtectRangeError::EmptyRange(range));
+        }
+
+        let _guard = self.protection_lock.lock().await;
+        let mut storage = self.connection_pool.connection_tagged("db_pruner").await?;
+        let mut transaction = storage.start_transaction().await?;
+        let pruning_info = transaction.pruning_dal().get_pruning_info().await?;
+        if let Some(last_pruned_l1_batch) = pruning_info.last_soft_pruned_l1_batch {
+            if *range.start() <= last_pruned_l1_batch {
+                return Err(ProtectRangeError::AlreadyPruned {
+                    range,
+                    last_pruned_l1_batch,
+                });
+            }
+        }
+        let inserted = transaction
+            .pruning_dal()
+            .protect_l1_batch_range(range)
+            .await?;
+        transaction.commit().await?;
+        Ok(inserted)
+    }
+
+    /// Removes protection from the specified L1 batch range. Returns `false` if the range was not protected.
+    pub async fn unprotect_l1_batch_range(
+        &self,
+        range: ops::RangeInclusive<L1BatchNumber>,
+    ) -> anyhow::Result<bool> {
+        let mut storage = self.connection_pool.connection_tagged("db_pruner").await?;
+        Ok(storage
+            .pruning_dal()
+            .unprotect_l1_batch_range(range)
+            .await?)
+    }
 }
 
 /// Interface to be used for health checks.
@@ -89,6 +179,9 @@ impl DbPruner {
             Arc::new(ConsistencyCheckerProcessedBatch {
                 conn: connection_pool.clone(),
             }),
+            Arc::new(L1BatchNotProtectedCondition {
+                conn: connection_pool.clone(),
+            }),
         ];
         if config.minimum_l1_batch_age > Duration::ZERO {
             // Do not add a condition if it's trivial in order to not clutter logs.
@@ -111,13 +204,34 @@ impl DbPruner {
             connection_pool,
             health_updater: ReactiveHealthCheck::new("db_pruner").1,
             prune_conditions,
+            trigger: Arc::new(Notify::new()),
+            protection_lock: Arc::default(),
         }
     }
 
+    /// Requires L1 batches to be exported to the block data archive before they are pruned.
+    #[must_use]
+    pub fn require_archived_block_data(mut self) -> Self {
+        self.prune_conditions
+            .push(Arc::new(L1BatchArchivedCondition {
+                conn: self.connection_pool.clone(),
+            }));
+        self
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
 
+    /// Returns a handle that can be used to control this pruner at runtime.
+    pub fn handle(&self) -> DbPrunerHandle {
+        DbPrunerHandle {
+            connection_pool: self.connection_pool.clone(),
+            trigger: self.trigger.clone(),
+            protection_lock: self.protection_lock.clone(),
+        }
+    }
+
     async fn is_l1_batch_prunable(&self, l1_batch_number: L1BatchNumber) -> bool {
         let mut successful_conditions = vec![];
         let mut failed_conditions = vec![];
@@ -169,6 +283,8 @@ impl DbPruner {
 
     async fn soft_prune(&self, storage: &mut Connection<'_, Core>) -> anyhow::Result<bool> {
         let latency = METRICS.pruning_chunk_duration[&MetricPruneType::Soft].start();
+        // Prevent protecting L1 batch ranges between checking prune conditions and committing soft pruning.
+        let _guard = self.protection_lock.lock().await;
         let mut transaction = storage.start_transaction().await?;
 
         let mut current_pruning_info = transaction.pruning_dal().get_pruning_info().await?;
@@ -293,14 +409,18 @@ impl DbPruner {
                 Ok(pruning_done) => !pruning_done,
             };
 
-            if should_sleep
-                && tokio::time::timeout(next_iteration_delay, stop_receiver.changed())
-                    .await
-                    .is_ok()
-            {
-                // The pruner either received a stop signal, or the stop receiver was dropped. In any case,
-                // the pruner should exit.
-                break;
+            if should_sleep {
+                tokio::select! {
+                    () = tokio::time::sleep(next_iteration_delay) => { /* continue pruning */ }
+                    () = self.trigger.notified() => {
+                        tracing::info!("Pruning iteration was triggered manually");
+                    }
+                    _ = stop_receiver.changed() => {
+                        // The pruner either received a stop signal, or the stop receiver was dropped.
+                        // In any case, the pruner should exit.
+                        break;
+                    }
+                }
             }
         }
         tracing::info!("Stop signal received, shutting down DB pruning");
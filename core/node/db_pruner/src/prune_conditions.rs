@@ -139,3 +139,56 @@ impl PruneCondition for ConsistencyCheckerProcessedBatch {
         Ok(l1_batch_number <= last_processed_l1_batch)
     }
 }
+
+/// Checks that the L1 batch doesn't belong to or precede any of L1 batch ranges protected from pruning.
+/// Since pruning removes all data up to and including the pruned L1 batch, batches following a protected range
+/// cannot be pruned either, until the range is unprotected.
+#[derive(Debug)]
+pub(super) struct L1BatchNotProtectedCondition {
+    pub conn: ConnectionPool<Core>,
+}
+
+impl fmt::Display for L1BatchNotProtectedCondition {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "L1 batch is not protected from pruning")
+    }
+}
+
+#[async_trait]
+impl PruneCondition for L1BatchNotProtectedCondition {
+    async fn is_batch_prunable(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        let mut storage = self.conn.connection().await?;
+        let protected_ranges = storage
+            .pruning_dal()
+            .get_protected_l1_batch_ranges()
+            .await?;
+        Ok(protected_ranges
+            .iter()
+            .all(|range| *range.start() > l1_batch_number))
+    }
+}
+
+/// Checks that block data for the L1 batch was exported to the block data archive, so that it can be served
+/// after the L1 batch is pruned.
+#[derive(Debug)]
+pub(super) struct L1BatchArchivedCondition {
+    pub conn: ConnectionPool<Core>,
+}
+
+impl fmt::Display for L1BatchArchivedCondition {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "L1 batch block data is archived")
+    }
+}
+
+#[async_trait]
+impl PruneCondition for L1BatchArchivedCondition {
+    async fn is_batch_prunable(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        let mut storage = self.conn.connection().await?;
+        let last_archived_l1_batch = storage
+            .block_data_archive_dal()
+            .get_last_archived_l1_batch()
+            .await?;
+        Ok(last_archived_l1_batch.map_or(false, |last| l1_batch_number <= last))
+    }
+}
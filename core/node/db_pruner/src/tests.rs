@@ -324,3 +324,220 @@ async fn pruner_is_resistant_to_errors() {
     stop_sender.send_replace(true);
     pruner_task_handle.await.unwrap().unwrap();
 }
+
+#[test(tokio::test)]
+async fn protected_l1_batch_ranges_are_not_pruned() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 10, 2).await;
+
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            removal_delay: Duration::ZERO,
+            pruned_batch_chunk_size: 3,
+            minimum_l1_batch_age: Duration::ZERO,
+        },
+        pool.clone(),
+        vec![Arc::new(L1BatchNotProtectedCondition {
+            conn: pool.clone(),
+        })],
+    );
+    let handle = pruner.handle();
+    let protected_range = L1BatchNumber(5)..=L1BatchNumber(6);
+    assert!(handle
+        .protect_l1_batch_range(protected_range.clone())
+        .await
+        .unwrap());
+    assert_eq!(
+        handle.protected_l1_batch_ranges().await.unwrap(),
+        [protected_range.clone()]
+    );
+
+    assert!(pruner.run_single_iteration().await.unwrap());
+    let pruning_info = conn.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(
+        pruning_info.last_hard_pruned_l1_batch,
+        Some(L1BatchNumber(3))
+    );
+    // Batches preceding the protected range cannot be pruned either.
+    assert!(!pruner.run_single_iteration().await.unwrap());
+    let pruning_info = conn.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(
+        pruning_info.last_hard_pruned_l1_batch,
+        Some(L1BatchNumber(3))
+    );
+
+    // Pruned batches cannot be protected.
+    let err = handle
+        .protect_l1_batch_range(L1BatchNumber(2)..=L1BatchNumber(4))
+        .await
+        .unwrap_err();
+    assert_matches!(
+        err,
+        ProtectRangeError::AlreadyPruned { last_pruned_l1_batch, .. }
+            if last_pruned_l1_batch == L1BatchNumber(3)
+    );
+
+    assert!(handle
+        .unprotect_l1_batch_range(protected_range)
+        .await
+        .unwrap());
+    assert!(pruner.run_single_iteration().await.unwrap());
+    let pruning_info = conn.pruning_dal().get_pruning_info().await.unwrap();
+    assert_eq!(
+        pruning_info.last_hard_pruned_l1_batch,
+        Some(L1BatchNumber(6))
+    );
+}
+
+/// Prune condition that blocks until released, allowing to interleave pruning with other actions.
+#[derive(Debug)]
+struct BlockingCondition {
+    started: Arc<Notify>,
+    released: Arc<Notify>,
+}
+
+impl fmt::Display for BlockingCondition {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        formatter.write_str("blocking")
+    }
+}
+
+#[async_trait]
+impl PruneCondition for BlockingCondition {
+    async fn is_batch_prunable(&self, _l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        self.started.notify_one();
+        self.released.notified().await;
+        Ok(true)
+    }
+}
+
+#[test(tokio::test)]
+async fn protecting_l1_batch_range_waits_for_soft_pruning() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 10, 2).await;
+
+    let started = Arc::new(Notify::new());
+    let released = Arc::new(Notify::new());
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            removal_delay: Duration::ZERO,
+            pruned_batch_chunk_size: 3,
+            minimum_l1_batch_age: Duration::ZERO,
+        },
+        pool.clone(),
+        vec![
+            Arc::new(L1BatchNotProtectedCondition { conn: pool.clone() }),
+            Arc::new(BlockingCondition {
+                started: started.clone(),
+                released: released.clone(),
+            }),
+        ],
+    );
+    let handle = pruner.handle();
+
+    let protect_range = async {
+        // Wait until the pruner has checked that the batches are not protected.
+        started.notified().await;
+        let protect_task = tokio::spawn(async move {
+            handle
+                .protect_l1_batch_range(L1BatchNumber(1)..=L1BatchNumber(2))
+                .await
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!protect_task.is_finished());
+        released.notify_one();
+        protect_task.await.unwrap()
+    };
+    let (pruning_result, protect_result) =
+        tokio::join!(pruner.run_single_iteration(), protect_range);
+
+    assert!(pruning_result.unwrap());
+    assert_matches!(
+        protect_result.unwrap_err(),
+        ProtectRangeError::AlreadyPruned { last_pruned_l1_batch, .. }
+            if last_pruned_l1_batch == L1BatchNumber(3)
+    );
+    assert_eq!(
+        conn.pruning_dal()
+            .get_protected_l1_batch_ranges()
+            .await
+            .unwrap(),
+        []
+    );
+}
+
+/// Wrapper for a prune condition that reports the number of its evaluations.
+#[derive(Debug)]
+struct ObservedCondition<C> {
+    inner: C,
+    evaluation_count: watch::Sender<usize>,
+}
+
+impl<C: fmt::Display> fmt::Display for ObservedCondition<C> {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.inner, formatter)
+    }
+}
+
+#[async_trait]
+impl<C: PruneCondition> PruneCondition for ObservedCondition<C> {
+    async fn is_batch_prunable(&self, l1_batch_number: L1BatchNumber) -> anyhow::Result<bool> {
+        let result = self.inner.is_batch_prunable(l1_batch_number).await;
+        self.evaluation_count.send_modify(|count| *count += 1);
+        result
+    }
+}
+
+#[test(tokio::test)]
+async fn pruning_iteration_can_be_triggered_manually() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    insert_l2_blocks(&mut conn, 10, 2).await;
+
+    let (evaluation_count_sender, mut evaluation_count) = watch::channel(0);
+    let condition = ObservedCondition {
+        inner: L1BatchNotProtectedCondition { conn: pool.clone() },
+        evaluation_count: evaluation_count_sender,
+    };
+    let pruner = DbPruner::with_conditions(
+        DbPrunerConfig {
+            // Large enough for the pruner not to run the next iteration on its own during the test.
+            removal_delay: Duration::from_secs(3_600),
+            pruned_batch_chunk_size: 3,
+            minimum_l1_batch_age: Duration::ZERO,
+        },
+        pool.clone(),
+        vec![Arc::new(condition)],
+    );
+    let handle = pruner.handle();
+    let protected_range = L1BatchNumber(0)..=L1BatchNumber(9);
+    handle
+        .protect_l1_batch_range(protected_range.clone())
+        .await
+        .unwrap();
+
+    let mut health_check = pruner.health_check();
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    let pruner_task_handle = tokio::spawn(pruner.run(stop_receiver));
+
+    // Wait until the pruner checks prune conditions and goes to sleep.
+    evaluation_count.wait_for(|&count| count > 0).await.unwrap();
+    handle
+        .unprotect_l1_batch_range(protected_range)
+        .await
+        .unwrap();
+    handle.trigger_pruning();
+
+    health_check
+        .wait_for(|health| {
+            let Some(details) = health.details() else {
+                return false;
+            };
+            details["last_soft_pruned_l1_batch"] == 3
+        })
+        .await;
+    // The pruner now waits for the removal delay before hard pruning, so we don't wait for it to stop.
+    pruner_task_handle.abort();
+}
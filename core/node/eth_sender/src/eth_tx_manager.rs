@@ -9,6 +9,7 @@ use zksync_eth_client::{
     encode_blob_tx_with_sidecar, BoundEthInterface, ClientError, EnrichedClientError, Error,
     EthInterface, ExecutedTxStatus, Options, RawTransactionBytes, SignedCallResult,
 };
+use zksync_health_check::ReactiveHealthCheck;
 use zksync_node_fee_model::l1_gas_price::L1TxParamsProvider;
 use zksync_shared_metrics::BlockL1Stage;
 use zksync_types::{
@@ -19,7 +20,11 @@ use zksync_types::{
 };
 use zksync_utils::time::seconds_since_epoch;
 
-use super::{metrics::METRICS, ETHSenderError};
+use super::{
+    metrics::METRICS,
+    resubmission::{ResubmissionPolicy, ResubmissionTracker},
+    ETHSenderError,
+};
 
 #[derive(Debug)]
 struct EthFee {
@@ -47,7 +52,7 @@ pub(super) struct L1BlockNumbers {
 /// Based on eth_tx queue the component generates new attempt with the minimum possible fee,
 /// save it to the database, and send it to Ethereum.
 /// Based on eth_tx_history queue the component can mark txs as stuck and create the new attempt
-/// with higher gas price
+/// with higher gas price according to the [`ResubmissionPolicy`].
 #[derive(Debug)]
 pub struct EthTxManager {
     /// A gateway through which the operator normally sends all its transactions.
@@ -58,6 +63,9 @@ pub struct EthTxManager {
     config: SenderConfig,
     gas_adjuster: Arc<dyn L1TxParamsProvider>,
     pool: ConnectionPool<Core>,
+    resubmission_policy: ResubmissionPolicy,
+    resubmission_tracker: ResubmissionTracker,
+    health_check: ReactiveHealthCheck,
 }
 
 impl EthTxManager {
@@ -68,16 +76,25 @@ impl EthTxManager {
         ethereum_gateway: Box<dyn BoundEthInterface>,
         ethereum_gateway_blobs: Option<Box<dyn BoundEthInterface>>,
     ) -> Self {
+        let (health_check, resubmission_tracker) = ResubmissionTracker::new();
         Self {
             ethereum_gateway: ethereum_gateway.for_component("eth_tx_manager"),
             ethereum_gateway_blobs: ethereum_gateway_blobs
                 .map(|eth| eth.for_component("eth_tx_manager")),
+            resubmission_policy: ResubmissionPolicy::new(&config),
             config,
             gas_adjuster,
             pool,
+            resubmission_tracker,
+            health_check,
         }
     }
 
+    /// Returns the health check reporting stuck transactions.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_check.clone()
+    }
+
     pub(crate) fn query_client(&self) -> &DynClient<L1> {
         (*self.ethereum_gateway).as_ref()
     }
@@ -231,8 +248,10 @@ impl EthTxManager {
             return Err(ETHSenderError::from(Error::EthereumGateway(err)));
         }
 
-        // Increase `priority_fee_per_gas` by at least 20% to prevent "replacement transaction under-priced" error.
-        Ok((previous_priority_fee + (previous_priority_fee / 5) + 1)
+        // Increase `priority_fee_per_gas` by at least 10% to prevent "replacement transaction under-priced" error.
+        Ok(self
+            .resubmission_policy
+            .bump_priority_fee(previous_priority_fee)
             .max(self.gas_adjuster.get_priority_fee()))
     }
 
@@ -489,6 +508,11 @@ impl EthTxManager {
             // that `tx` is not mined and we should resend it.
             // We only resend the first un-mined transaction.
             if operator_nonce.latest <= tx.nonce {
+                let tx = if operator_nonce.latest < tx.nonce {
+                    self.handle_nonce_gap(storage, tx, operator_nonce).await
+                } else {
+                    tx
+                };
                 // None means txs hasn't been sent yet
                 let first_sent_at_block = storage
                     .eth_sender_dal()
@@ -496,6 +520,12 @@ impl EthTxManager {
                     .await
                     .unwrap()
                     .unwrap_or(l1_block_numbers.latest.0);
+                self.resubmission_tracker.observe_pending_tx(
+                    &self.resubmission_policy,
+                    &tx,
+                    first_sent_at_block,
+                    l1_block_numbers.latest,
+                );
                 return Ok(Some((tx, first_sent_at_block)));
             }
 
@@ -531,9 +561,53 @@ impl EthTxManager {
                 }
             }
         }
+        self.resubmission_tracker.clear_stuck_tx(operator_address);
         Ok(None)
     }
 
+    /// Handles the first un-mined transaction of the operator having a nonce greater than
+    /// the operator nonce on L1. Such a transaction can never be mined; this usually happens
+    /// after the operator key is rotated. If enabled in the config, nonces of all pending
+    /// transactions of the operator are reassigned starting from the operator nonce.
+    async fn handle_nonce_gap(
+        &mut self,
+        storage: &mut Connection<'_, Core>,
+        tx: EthTx,
+        operator_nonce: OperatorNonce,
+    ) -> EthTx {
+        METRICS.nonce_gaps_detected.inc();
+        if !self.resubmission_policy.repair_nonce_gaps() {
+            tracing::error!(
+                "Nonce gap detected: operator nonce on L1 is {}, while the first pending eth_tx {} has nonce {}; \
+                 enable `repair_nonce_gaps` in the eth_sender config to reassign nonces",
+                operator_nonce.latest,
+                tx.id,
+                tx.nonce
+            );
+            return tx;
+        }
+
+        let updated_count = storage
+            .eth_sender_dal()
+            .reassign_nonces(tx.from_addr, operator_nonce.latest.0.into())
+            .await
+            .unwrap();
+        tracing::warn!(
+            "Nonce gap detected: operator nonce on L1 is {}, while the first pending eth_tx {} had nonce {}; \
+             reassigned nonces of {updated_count} pending transactions",
+            operator_nonce.latest,
+            tx.id,
+            tx.nonce
+        );
+        self.resubmission_tracker.observe_nonce_gap_repair();
+        storage
+            .eth_sender_dal()
+            .get_eth_tx(tx.id)
+            .await
+            .unwrap()
+            .expect("eth_tx must exist after nonce reassignment")
+    }
+
     async fn sign_tx(
         &self,
         tx: &EthTx,
@@ -730,6 +804,7 @@ impl EthTxManager {
 
     pub async fn run(mut self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         let pool = self.pool.clone();
+        self.resubmission_tracker.update_health();
         {
             let l1_block_numbers = self
                 .get_l1_block_numbers()
@@ -816,6 +891,24 @@ impl EthTxManager {
         {
             // New gas price depends on the time this tx spent in mempool.
             let time_in_mempool = l1_block_numbers.latest.0 - sent_at_block;
+            if time_in_mempool != 0 {
+                let last_sent_at_block = storage
+                    .eth_sender_dal()
+                    .get_last_sent_eth_tx(tx.id)
+                    .await
+                    .unwrap()
+                    .and_then(|history| history.sent_at_block);
+                if !self
+                    .resubmission_policy
+                    .should_resubmit(last_sent_at_block, l1_block_numbers.latest)
+                {
+                    tracing::debug!(
+                        "Postponing resubmission of eth_tx {}: last attempt was sent at block {last_sent_at_block:?}",
+                        tx.id
+                    );
+                    return Ok(l1_block_numbers.latest);
+                }
+            }
 
             // We don't want to return early in case resend does not succeed -
             // the error is logged anyway, but early returns will prevent
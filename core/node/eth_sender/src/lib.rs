@@ -5,6 +5,7 @@ mod eth_tx_aggregator;
 mod eth_tx_manager;
 mod metrics;
 mod publish_criterion;
+mod resubmission;
 mod utils;
 mod zksync_functions;
 
@@ -82,6 +82,10 @@ pub(super) struct EthSenderMetrics {
     pub block_range_size: Family<ActionTypeLabel, Histogram<u64>>,
     /// Number of transactions resent by the Ethereum sender.
     pub transaction_resent: Counter,
+    /// Number of operators whose first pending transaction is considered stuck.
+    pub stuck_txs: Gauge<usize>,
+    /// Number of detected gaps between the operator nonce on L1 and the nonce of the first pending transaction.
+    pub nonce_gaps_detected: Counter,
     #[metrics(buckets = FEE_BUCKETS)]
     pub used_base_fee_per_gas: Histogram<u64>,
     #[metrics(buckets = FEE_BUCKETS)]
@@ -0,0 +1,158 @@
+//! Policy for resubmitting L1 transactions that weren't mined in time, and the related health reporting.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use zksync_config::configs::eth_sender::SenderConfig;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::{eth_sender::EthTx, Address, L1BlockNumber};
+
+use crate::metrics::METRICS;
+
+/// Determines when and how transactions that weren't mined are resubmitted.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ResubmissionPolicy {
+    fee_bump_percent: u64,
+    resubmission_interval_blocks: u64,
+    stuck_tx_threshold_blocks: u64,
+    repair_nonce_gaps: bool,
+}
+
+impl ResubmissionPolicy {
+    pub fn new(config: &SenderConfig) -> Self {
+        Self {
+            fee_bump_percent: config.fee_bump_percent(),
+            resubmission_interval_blocks: config.resubmission_interval_blocks(),
+            stuck_tx_threshold_blocks: config.stuck_tx_threshold_blocks(),
+            repair_nonce_gaps: config.repair_nonce_gaps,
+        }
+    }
+
+    /// Checks whether a transaction should be resubmitted. `last_sent_at_block` is the block
+    /// at which the latest attempt was sent, or `None` if the transaction wasn't sent yet.
+    pub fn should_resubmit(
+        &self,
+        last_sent_at_block: Option<u32>,
+        current_block: L1BlockNumber,
+    ) -> bool {
+        last_sent_at_block.map_or(true, |sent_at_block| {
+            u64::from(current_block.0.saturating_sub(sent_at_block))
+                >= self.resubmission_interval_blocks
+        })
+    }
+
+    /// Returns the priority fee for the next attempt given the one used by the previous attempt.
+    pub fn bump_priority_fee(&self, previous_priority_fee: u64) -> u64 {
+        let bump = previous_priority_fee.saturating_mul(self.fee_bump_percent) / 100;
+        previous_priority_fee.saturating_add(bump).saturating_add(1)
+    }
+
+    pub fn is_stuck(&self, first_sent_at_block: u32, current_block: L1BlockNumber) -> bool {
+        u64::from(current_block.0.saturating_sub(first_sent_at_block))
+            >= self.stuck_tx_threshold_blocks
+    }
+
+    pub fn repair_nonce_gaps(&self) -> bool {
+        self.repair_nonce_gaps
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub(crate) struct StuckTxDetails {
+    eth_tx_id: u32,
+    nonce: u64,
+    /// `None` for the main operator.
+    from_addr: Option<Address>,
+    first_sent_at_block: u32,
+    blocks_in_mempool: u32,
+}
+
+#[derive(Debug, Default, Serialize)]
+struct EthTxManagerHealthDetails {
+    stuck_txs: Vec<StuckTxDetails>,
+    repaired_nonce_gaps: u64,
+}
+
+/// Tracks stuck transactions and nonce gap repairs, reporting them via the health check and metrics.
+#[derive(Debug)]
+pub(crate) struct ResubmissionTracker {
+    health_updater: HealthUpdater,
+    /// Stuck transactions keyed by the operator address.
+    stuck_txs: HashMap<Option<Address>, StuckTxDetails>,
+    repaired_nonce_gaps: u64,
+}
+
+impl ResubmissionTracker {
+    pub fn new() -> (ReactiveHealthCheck, Self) {
+        let (health_check, health_updater) = ReactiveHealthCheck::new("eth_tx_manager");
+        let this = Self {
+            health_updater,
+            stuck_txs: HashMap::new(),
+            repaired_nonce_gaps: 0,
+        };
+        (health_check, this)
+    }
+
+    /// Records the state of the first transaction of the operator that wasn't mined yet.
+    pub fn observe_pending_tx(
+        &mut self,
+        policy: &ResubmissionPolicy,
+        tx: &EthTx,
+        first_sent_at_block: u32,
+        current_block: L1BlockNumber,
+    ) {
+        if !policy.is_stuck(first_sent_at_block, current_block) {
+            self.clear_stuck_tx(tx.from_addr);
+            return;
+        }
+
+        let details = StuckTxDetails {
+            eth_tx_id: tx.id,
+            nonce: tx.nonce.0.into(),
+            from_addr: tx.from_addr,
+            first_sent_at_block,
+            blocks_in_mempool: current_block.0.saturating_sub(first_sent_at_block),
+        };
+        let is_new = self
+            .stuck_txs
+            .get(&tx.from_addr)
+            .map_or(true, |prev| prev.eth_tx_id != tx.id);
+        if is_new {
+            tracing::error!(
+                "eth_tx {} with nonce {} wasn't mined for {} blocks since it was first sent",
+                tx.id,
+                tx.nonce,
+                details.blocks_in_mempool
+            );
+        }
+        self.stuck_txs.insert(tx.from_addr, details);
+        self.update_health();
+    }
+
+    /// Records that the operator has no transactions that weren't mined.
+    pub fn clear_stuck_tx(&mut self, operator_address: Option<Address>) {
+        if self.stuck_txs.remove(&operator_address).is_some() {
+            self.update_health();
+        }
+    }
+
+    pub fn observe_nonce_gap_repair(&mut self) {
+        self.repaired_nonce_gaps += 1;
+        self.update_health();
+    }
+
+    pub fn update_health(&self) {
+        METRICS.stuck_txs.set(self.stuck_txs.len());
+        let status = if self.stuck_txs.is_empty() {
+            HealthStatus::Ready
+        } else {
+            HealthStatus::Affected
+        };
+        let details = EthTxManagerHealthDetails {
+            stuck_txs: self.stuck_txs.values().cloned().collect(),
+            repaired_nonce_gaps: self.repaired_nonce_gaps,
+        };
+        self.health_updater
+            .update(Health::from(status).with_details(details));
+    }
+}
@@ -10,6 +10,7 @@ use zksync_config::{
 use zksync_contracts::BaseSystemContractsHashes;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_eth_client::{clients::MockEthereum, EthInterface};
+use zksync_health_check::{CheckHealth, HealthStatus};
 use zksync_l1_contract_interface::i_executor::methods::{ExecuteBatches, ProveBatches};
 use zksync_node_fee_model::l1_gas_price::GasAdjuster;
 use zksync_node_test_utils::{create_l1_batch, l1_batch_metadata_to_commitment_artifacts};
@@ -19,17 +20,18 @@ use zksync_types::{
     commitment::{
         L1BatchCommitmentMode, L1BatchMetaParameters, L1BatchMetadata, L1BatchWithMetadata,
     },
+    eth_sender::EthTx,
     ethabi::Token,
     helpers::unix_timestamp_ms,
     l2_to_l1_log::{L2ToL1Log, UserL2ToL1Log},
     pubdata_da::PubdataDA,
     web3::contract::Error,
-    Address, L1BatchNumber, ProtocolVersion, ProtocolVersionId, H256,
+    Address, L1BatchNumber, L1BlockNumber, Nonce, ProtocolVersion, ProtocolVersionId, H256,
 };
 
 use crate::{
-    aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers, Aggregator,
-    ETHSenderError, EthTxAggregator, EthTxManager,
+    aggregated_operations::AggregatedOperation, eth_tx_manager::L1BlockNumbers,
+    resubmission::ResubmissionPolicy, Aggregator, ETHSenderError, EthTxAggregator, EthTxManager,
 };
 
 // Alias to conveniently call static methods of `ETHSender`.
@@ -204,6 +206,17 @@ impl EthSenderTester {
         }
     }
 
+    /// Replaces the transaction manager with the one using the provided sender config.
+    fn recreate_manager(&mut self, config: SenderConfig) {
+        self.manager = EthTxManager::new(
+            self.conn.clone(),
+            config,
+            self.gas_adjuster.clone(),
+            self.gateway.clone(),
+            None,
+        );
+    }
+
     async fn storage(&self) -> Connection<'_, Core> {
         self.conn.connection().await.unwrap()
     }
@@ -1124,6 +1137,184 @@ async fn get_multicall_data(commitment_mode: L1BatchCommitmentMode) {
     assert!(multicall_data.is_ok());
 }
 
+#[test]
+fn resubmission_policy() {
+    let config = SenderConfig {
+        fee_bump_percent: Some(50),
+        resubmission_interval_blocks: Some(3),
+        stuck_tx_threshold_blocks: Some(10),
+        ..EthConfig::for_tests().sender.unwrap()
+    };
+    let policy = ResubmissionPolicy::new(&config);
+
+    assert_eq!(policy.bump_priority_fee(100), 151);
+    assert_eq!(policy.bump_priority_fee(u64::MAX), u64::MAX);
+
+    assert!(policy.should_resubmit(None, L1BlockNumber(5)));
+    assert!(!policy.should_resubmit(Some(5), L1BlockNumber(7)));
+    assert!(policy.should_resubmit(Some(5), L1BlockNumber(8)));
+
+    assert!(!policy.is_stuck(5, L1BlockNumber(14)));
+    assert!(policy.is_stuck(5, L1BlockNumber(15)));
+
+    // Fee bumps lower than the replacement threshold of L1 nodes are not allowed.
+    let config = SenderConfig {
+        fee_bump_percent: Some(1),
+        ..config
+    };
+    assert_eq!(ResubmissionPolicy::new(&config).bump_priority_fee(100), 111);
+}
+
+async fn send_dummy_eth_tx(tester: &mut EthSenderTester) -> EthTx {
+    insert_genesis_protocol_version(tester).await;
+    tester
+        .storage()
+        .await
+        .blocks_dal()
+        .insert_mock_l1_batch(&mock_l1_batch_header(1))
+        .await
+        .unwrap();
+
+    let tx = tester
+        .aggregator
+        .save_eth_tx(
+            &mut tester.conn.connection().await.unwrap(),
+            &get_dummy_operation(1),
+            false,
+        )
+        .await
+        .unwrap();
+    let block = tester.get_block_numbers().await.latest;
+    tester
+        .manager
+        .send_eth_tx(&mut tester.conn.connection().await.unwrap(), &tx, 0, block)
+        .await
+        .unwrap();
+    tx
+}
+
+#[tokio::test]
+async fn stuck_tx_is_reported_in_health_check() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut tester = EthSenderTester::new(
+        connection_pool,
+        vec![10; 100],
+        false,
+        false,
+        L1BatchCommitmentMode::Rollup,
+    )
+    .await;
+    tester.recreate_manager(SenderConfig {
+        stuck_tx_threshold_blocks: Some(3),
+        ..EthConfig::for_tests().sender.unwrap()
+    });
+    let health_check = tester.manager.health_check();
+
+    let tx = send_dummy_eth_tx(&mut tester).await;
+    tester.gateway.advance_block_number(1);
+    let (pending_tx, _) = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.connection().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await
+        .unwrap()
+        .expect("no tx to resend");
+    assert_eq!(pending_tx.id, tx.id);
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::NotReady
+    );
+
+    tester.gateway.advance_block_number(3);
+    tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.connection().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await
+        .unwrap()
+        .expect("no tx to resend");
+    let health = health_check.check_health().await;
+    assert_eq!(health.status(), HealthStatus::Affected);
+    let stuck_txs = &health.details().unwrap()["stuck_txs"];
+    assert_eq!(stuck_txs[0]["eth_tx_id"], tx.id);
+
+    let hash = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_last_sent_eth_tx(tx.id)
+        .await
+        .unwrap()
+        .unwrap()
+        .tx_hash;
+    confirm_tx(&mut tester, hash).await;
+    assert_eq!(
+        health_check.check_health().await.status(),
+        HealthStatus::Ready
+    );
+}
+
+#[test_casing(2, [false, true])]
+#[tokio::test]
+async fn nonce_gap_repair(repair_nonce_gaps: bool) {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut tester = EthSenderTester::new(
+        connection_pool,
+        vec![10; 100],
+        false,
+        false,
+        L1BatchCommitmentMode::Rollup,
+    )
+    .await;
+    tester.recreate_manager(SenderConfig {
+        repair_nonce_gaps,
+        ..EthConfig::for_tests().sender.unwrap()
+    });
+
+    let tx = send_dummy_eth_tx(&mut tester).await;
+    assert_eq!(tx.nonce, Nonce(0));
+    // Emulate the state after the operator key rotation: the nonce of the pending tx
+    // is ahead of the operator nonce on L1.
+    tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .reassign_nonces(None, 5)
+        .await
+        .unwrap();
+
+    tester.gateway.advance_block_number(1);
+    let (pending_tx, _) = tester
+        .manager
+        .monitor_inflight_transactions(
+            &mut tester.conn.connection().await.unwrap(),
+            tester.get_block_numbers().await,
+        )
+        .await
+        .unwrap()
+        .expect("no tx to resend");
+    let stored_tx = tester
+        .storage()
+        .await
+        .eth_sender_dal()
+        .get_eth_tx(tx.id)
+        .await
+        .unwrap()
+        .unwrap();
+
+    let expected_nonce = if repair_nonce_gaps {
+        Nonce(0)
+    } else {
+        Nonce(5)
+    };
+    assert_eq!(pending_tx.nonce, expected_nonce);
+    assert_eq!(stored_tx.nonce, expected_nonce);
+}
+
 async fn insert_genesis_protocol_version(tester: &EthSenderTester) {
     tester
         .storage()
@@ -77,6 +77,21 @@ impl EventProcessor for GovernanceUpgradesEventProcessor {
             }
         }
 
+        if upgrades.is_empty() {
+            return Ok(());
+        }
+        // Upgrades may have been persisted already if they were observed via another watched source.
+        let last_persisted_version_id = storage
+            .protocol_versions_dal()
+            .last_version_id()
+            .await
+            .map_err(DalError::generalize)?;
+        if let Some(version_id) = last_persisted_version_id {
+            if version_id as u16 > self.last_seen_version_id as u16 {
+                self.last_seen_version_id = version_id;
+            }
+        }
+
         let new_upgrades: Vec<_> = upgrades
             .into_iter()
             .skip_while(|(v, _)| v.id as u16 <= self.last_seen_version_id as u16)
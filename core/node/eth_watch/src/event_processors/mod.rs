@@ -1,7 +1,7 @@
 use std::fmt;
 
 use zksync_dal::{Connection, Core};
-use zksync_types::{web3::Log, H256};
+use zksync_types::{web3::Log, PriorityOpId, H256};
 
 pub(crate) use self::{
     governance_upgrades::GovernanceUpgradesEventProcessor, priority_ops::PriorityOpsEventProcessor,
@@ -20,6 +20,13 @@ pub(super) enum EventProcessorError {
         #[source]
         source: anyhow::Error,
     },
+    /// Received priority operations are not contiguous with the persisted ones. This may happen
+    /// if operations are observed via multiple sources, and one of the sources lags behind.
+    #[error("gap in priority operations: expected serial ID {expected}, received {received}")]
+    PriorityOpGap {
+        expected: PriorityOpId,
+        received: PriorityOpId,
+    },
     #[error("Eth client error: {0}")]
     Client(#[from] EthClientError),
     /// Internal errors are considered fatal (i.e., they bubble up and lead to the watcher termination).
@@ -51,6 +51,17 @@ impl EventProcessor for PriorityOpsEventProcessor {
             return Ok(());
         }
 
+        // Operations may have been persisted already if they were observed via another watched source.
+        let next_persisted_priority_id = storage
+            .transactions_dal()
+            .last_priority_id()
+            .await
+            .map_err(DalError::generalize)?
+            .map_or(PriorityOpId(0), |id| id + 1);
+        self.next_expected_priority_id = self
+            .next_expected_priority_id
+            .max(next_persisted_priority_id);
+
         let first = &priority_ops[0];
         let last = &priority_ops[priority_ops.len() - 1];
         tracing::debug!(
@@ -60,39 +71,38 @@ impl EventProcessor for PriorityOpsEventProcessor {
             last.serial_id(),
             last.eth_block(),
         );
-        assert_eq!(
-            last.serial_id().0 - first.serial_id().0 + 1,
-            priority_ops.len() as u64,
-            "There is a gap in priority ops received"
-        );
-
-        let new_ops: Vec<_> = priority_ops
-            .into_iter()
-            .skip_while(|tx| tx.serial_id() < self.next_expected_priority_id)
-            .collect();
-        let (Some(first_new), Some(last_new)) = (new_ops.first(), new_ops.last()) else {
-            return Ok(());
-        };
-        assert_eq!(
-            first_new.serial_id(),
-            self.next_expected_priority_id,
-            "priority transaction serial id mismatch"
-        );
-        let next_expected_priority_id = last_new.serial_id().next();
 
         let stage_latency = METRICS.poll_eth_node[&PollStage::PersistL1Txs].start();
-        APP_METRICS.processed_txs[&TxStage::added_to_mempool()].inc();
-        APP_METRICS.processed_l1_txs[&TxStage::added_to_mempool()].inc();
-        for new_op in new_ops {
-            let eth_block = new_op.eth_block();
+        let mut has_new_ops = false;
+        for op in priority_ops {
+            let serial_id = op.serial_id();
+            if serial_id < self.next_expected_priority_id {
+                continue; // The operation is already persisted
+            }
+            // With multiple watched sources, missing operations may be emitted by another source
+            // that hasn't processed them yet. We return a recoverable error, so that the caller doesn't
+            // advance its cursor and retries processing later.
+            if serial_id > self.next_expected_priority_id {
+                return Err(EventProcessorError::PriorityOpGap {
+                    expected: self.next_expected_priority_id,
+                    received: serial_id,
+                });
+            }
+
+            if !has_new_ops {
+                APP_METRICS.processed_txs[&TxStage::added_to_mempool()].inc();
+                APP_METRICS.processed_l1_txs[&TxStage::added_to_mempool()].inc();
+                has_new_ops = true;
+            }
+            let eth_block = op.eth_block();
             storage
                 .transactions_dal()
-                .insert_transaction_l1(&new_op, eth_block)
+                .insert_transaction_l1(&op, eth_block)
                 .await
                 .map_err(DalError::generalize)?;
+            self.next_expected_priority_id = serial_id.next();
         }
         stage_latency.observe();
-        self.next_expected_priority_id = next_expected_priority_id;
         Ok(())
     }
 
@@ -6,7 +6,7 @@ use std::time::Duration;
 
 use anyhow::Context as _;
 use tokio::sync::watch;
-use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError};
 use zksync_system_constants::PRIORITY_EXPIRATION;
 use zksync_types::{
     ethabi::Contract, web3::BlockNumber as Web3BlockNumber, Address, PriorityOpId,
@@ -33,32 +33,33 @@ mod tests;
 struct EthWatchState {
     last_seen_version_id: ProtocolVersionId,
     next_expected_priority_id: PriorityOpId,
-    last_processed_ethereum_block: u64,
 }
 
-/// Ethereum watcher component.
+/// L1 contract polled by [`EthWatch`]. Each source has its own client (and thus its own confirmation depth)
+/// and its own cursor persisted in Postgres.
 #[derive(Debug)]
-pub struct EthWatch {
+struct EthWatchSource {
+    /// Address of the diamond proxy emitting priority operations; identifies the persisted cursor.
+    diamond_proxy_addr: Address,
+    /// Whether this is the main source. Only the main source can fall back to inferring its cursor
+    /// from the persisted priority operations.
+    is_main: bool,
     client: Box<dyn EthClient>,
-    poll_interval: Duration,
     event_processors: Vec<Box<dyn EventProcessor>>,
     last_processed_ethereum_block: u64,
-    pool: ConnectionPool<Core>,
 }
 
-impl EthWatch {
-    pub async fn new(
+impl EthWatchSource {
+    async fn new(
         diamond_proxy_addr: Address,
         state_transition_manager_address: Option<Address>,
         governance_contract: &Contract,
         mut client: Box<dyn EthClient>,
-        pool: ConnectionPool<Core>,
-        poll_interval: Duration,
+        is_main: bool,
+        storage: &mut Connection<'_, Core>,
     ) -> anyhow::Result<Self> {
-        let mut storage = pool.connection_tagged("eth_watch").await?;
-        let state = Self::initialize_state(&*client, &mut storage).await?;
-        tracing::info!("initialized state: {state:?}");
-        drop(storage);
+        let state = EthWatch::initialize_state(storage).await?;
+        tracing::info!("initialized state for source {diamond_proxy_addr:?}: {state:?}");
 
         let priority_ops_processor =
             PriorityOpsEventProcessor::new(state.next_expected_priority_id)?;
@@ -78,19 +79,165 @@ impl EthWatch {
             .collect();
         client.set_topics(topics);
 
-        Ok(Self {
+        let mut this = Self {
+            diamond_proxy_addr,
+            is_main,
             client,
-            poll_interval,
             event_processors,
-            last_processed_ethereum_block: state.last_processed_ethereum_block,
-            pool,
+            last_processed_ethereum_block: 0,
+        };
+        this.last_processed_ethereum_block = this.initial_cursor(storage).await?;
+        tracing::info!(
+            "Source {diamond_proxy_addr:?} will be polled starting from L1 block #{}",
+            this.last_processed_ethereum_block
+        );
+        Ok(this)
+    }
+
+    async fn initial_cursor(&self, storage: &mut Connection<'_, Core>) -> anyhow::Result<u64> {
+        let persisted_cursor = storage
+            .eth_watcher_dal()
+            .get_last_processed_l1_block(self.diamond_proxy_addr)
+            .await?;
+        if let Some(block) = persisted_cursor {
+            return Ok(block);
+        }
+
+        let last_processed_priority_op_block = if self.is_main {
+            storage
+                .transactions_dal()
+                .get_last_processed_l1_block()
+                .await?
+        } else {
+            None
+        };
+        Ok(match last_processed_priority_op_block {
+            // There are some priority ops processed - start from the last processed eth block
+            // but subtract 1 in case the server stopped mid-block.
+            Some(block) => block.0.saturating_sub(1).into(),
+            // There are no priority ops processed - to be safe, scan the last 50k blocks.
+            None => self
+                .client
+                .finalized_block_number()
+                .await
+                .context("cannot get current Ethereum block")?
+                .saturating_sub(PRIORITY_EXPIRATION),
         })
     }
 
-    async fn initialize_state(
-        client: &dyn EthClient,
+    #[tracing::instrument(skip_all, fields(source = ?self.diamond_proxy_addr))]
+    async fn loop_iteration(
+        &mut self,
         storage: &mut Connection<'_, Core>,
-    ) -> anyhow::Result<EthWatchState> {
+    ) -> Result<(), EventProcessorError> {
+        let stage_latency = METRICS.poll_eth_node[&PollStage::Request].start();
+        let to_block = self.client.finalized_block_number().await?;
+        if to_block <= self.last_processed_ethereum_block {
+            return Ok(());
+        }
+
+        let events = self
+            .client
+            .get_events(
+                Web3BlockNumber::Number(self.last_processed_ethereum_block.into()),
+                Web3BlockNumber::Number(to_block.into()),
+                RETRY_LIMIT,
+            )
+            .await?;
+        stage_latency.observe();
+
+        for processor in &mut self.event_processors {
+            let relevant_topic = processor.relevant_topic();
+            let processor_events = events
+                .iter()
+                .filter(|event| event.topics.get(0) == Some(&relevant_topic))
+                .cloned()
+                .collect();
+            processor
+                .process_events(storage, &*self.client, processor_events)
+                .await?;
+        }
+        storage
+            .eth_watcher_dal()
+            .set_last_processed_l1_block(self.diamond_proxy_addr, to_block)
+            .await
+            .map_err(DalError::generalize)?;
+        self.last_processed_ethereum_block = to_block;
+        Ok(())
+    }
+}
+
+/// Ethereum watcher component.
+#[derive(Debug)]
+pub struct EthWatch {
+    sources: Vec<EthWatchSource>,
+    governance_contract: Contract,
+    poll_interval: Duration,
+    pool: ConnectionPool<Core>,
+}
+
+impl EthWatch {
+    pub async fn new(
+        diamond_proxy_addr: Address,
+        state_transition_manager_address: Option<Address>,
+        governance_contract: &Contract,
+        client: Box<dyn EthClient>,
+        pool: ConnectionPool<Core>,
+        poll_interval: Duration,
+    ) -> anyhow::Result<Self> {
+        let mut storage = pool.connection_tagged("eth_watch").await?;
+        let main_source = EthWatchSource::new(
+            diamond_proxy_addr,
+            state_transition_manager_address,
+            governance_contract,
+            client,
+            true,
+            &mut storage,
+        )
+        .await?;
+        drop(storage);
+
+        Ok(Self {
+            sources: vec![main_source],
+            governance_contract: governance_contract.clone(),
+            poll_interval,
+            pool,
+        })
+    }
+
+    /// Adds another L1 contract to poll for priority operations and governance upgrades, e.g. during
+    /// bridgehub migrations. The source is polled using the provided `client`, which determines the confirmation depth
+    /// for its events, and has its own persisted cursor.
+    pub async fn with_additional_source(
+        mut self,
+        diamond_proxy_addr: Address,
+        state_transition_manager_address: Option<Address>,
+        client: Box<dyn EthClient>,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            self.sources
+                .iter()
+                .all(|source| source.diamond_proxy_addr != diamond_proxy_addr),
+            "source {diamond_proxy_addr:?} is already watched"
+        );
+
+        let mut storage = self.pool.connection_tagged("eth_watch").await?;
+        let source = EthWatchSource::new(
+            diamond_proxy_addr,
+            state_transition_manager_address,
+            &self.governance_contract,
+            client,
+            false,
+            &mut storage,
+        )
+        .await?;
+        drop(storage);
+
+        self.sources.push(source);
+        Ok(self)
+    }
+
+    async fn initialize_state(storage: &mut Connection<'_, Core>) -> anyhow::Result<EthWatchState> {
         let next_expected_priority_id: PriorityOpId = storage
             .transactions_dal()
             .last_priority_id()
@@ -103,26 +250,9 @@ impl EthWatch {
             .await?
             .context("expected at least one (genesis) version to be present in DB")?;
 
-        let last_processed_ethereum_block = match storage
-            .transactions_dal()
-            .get_last_processed_l1_block()
-            .await?
-        {
-            // There are some priority ops processed - start from the last processed eth block
-            // but subtract 1 in case the server stopped mid-block.
-            Some(block) => block.0.saturating_sub(1).into(),
-            // There are no priority ops processed - to be safe, scan the last 50k blocks.
-            None => client
-                .finalized_block_number()
-                .await
-                .context("cannot get current Ethereum block")?
-                .saturating_sub(PRIORITY_EXPIRATION),
-        };
-
         Ok(EthWatchState {
             next_expected_priority_id,
             last_seen_version_id,
-            last_processed_ethereum_block,
         })
     }
 
@@ -138,20 +268,26 @@ impl EthWatch {
             METRICS.eth_poll.inc();
 
             let mut storage = pool.connection_tagged("eth_watch").await?;
-            match self.loop_iteration(&mut storage).await {
-                Ok(()) => { /* everything went fine */ }
-                Err(EventProcessorError::Internal(err)) => {
-                    tracing::error!("Internal error processing new blocks: {err:?}");
-                    return Err(err);
-                }
-                Err(err) => {
-                    // This is an error because otherwise we could potentially miss a priority operation
-                    // thus entering priority mode, which is not desired.
-                    tracing::error!("Failed to process new blocks: {err}");
-                    self.last_processed_ethereum_block =
-                        Self::initialize_state(&*self.client, &mut storage)
-                            .await?
-                            .last_processed_ethereum_block;
+            for source in &mut self.sources {
+                match source.loop_iteration(&mut storage).await {
+                    Ok(()) => { /* everything went fine */ }
+                    Err(EventProcessorError::Internal(err)) => {
+                        tracing::error!(
+                            "Internal error processing new blocks for source {:?}: {err:?}",
+                            source.diamond_proxy_addr
+                        );
+                        return Err(err);
+                    }
+                    Err(err) => {
+                        // This is an error because otherwise we could potentially miss a priority operation
+                        // thus entering priority mode, which is not desired.
+                        tracing::error!(
+                            "Failed to process new blocks for source {:?}: {err}",
+                            source.diamond_proxy_addr
+                        );
+                        source.last_processed_ethereum_block =
+                            source.initial_cursor(&mut storage).await?;
+                    }
                 }
             }
         }
@@ -160,39 +296,14 @@ impl EthWatch {
         Ok(())
     }
 
-    #[tracing::instrument(skip_all)]
+    #[cfg(test)]
     async fn loop_iteration(
         &mut self,
         storage: &mut Connection<'_, Core>,
     ) -> Result<(), EventProcessorError> {
-        let stage_latency = METRICS.poll_eth_node[&PollStage::Request].start();
-        let to_block = self.client.finalized_block_number().await?;
-        if to_block <= self.last_processed_ethereum_block {
-            return Ok(());
+        for source in &mut self.sources {
+            source.loop_iteration(storage).await?;
         }
-
-        let events = self
-            .client
-            .get_events(
-                Web3BlockNumber::Number(self.last_processed_ethereum_block.into()),
-                Web3BlockNumber::Number(to_block.into()),
-                RETRY_LIMIT,
-            )
-            .await?;
-        stage_latency.observe();
-
-        for processor in &mut self.event_processors {
-            let relevant_topic = processor.relevant_topic();
-            let processor_events = events
-                .iter()
-                .filter(|event| event.topics.get(0) == Some(&relevant_topic))
-                .cloned()
-                .collect();
-            processor
-                .process_events(storage, &*self.client, processor_events)
-                .await?;
-        }
-        self.last_processed_ethereum_block = to_block;
         Ok(())
     }
 }
@@ -14,6 +14,7 @@ use zksync_types::{
 
 use crate::{
     client::{EthClient, EthClientError},
+    event_processors::EventProcessorError,
     EthWatch,
 };
 
@@ -336,8 +337,18 @@ async fn test_normal_operation_governance_upgrades() {
     assert_eq!(tx.common_data.upgrade_id, ProtocolVersionId::next());
 }
 
+fn assert_priority_op_gap(err: EventProcessorError, expected: u64, received: u64) {
+    assert!(
+        matches!(
+            err,
+            EventProcessorError::PriorityOpGap { expected: PriorityOpId(exp), received: PriorityOpId(rec) }
+                if exp == expected && rec == received
+        ),
+        "{err:?}"
+    );
+}
+
 #[tokio::test]
-#[should_panic]
 async fn test_gap_in_single_batch() {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
     setup_db(&connection_pool).await;
@@ -354,11 +365,21 @@ async fn test_gap_in_single_batch() {
         ])
         .await;
     client.set_last_finalized_block_number(15).await;
-    watcher.loop_iteration(&mut storage).await.unwrap();
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert_priority_op_gap(err, 4, 5);
+
+    // Operations before the gap are persisted, but the cursor must not be advanced.
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1, 2, 3]);
+    assert_eq!(watcher.sources[0].last_processed_ethereum_block, 0);
+    let persisted_cursor = storage
+        .eth_watcher_dal()
+        .get_last_processed_l1_block(Address::default())
+        .await
+        .unwrap();
+    assert_eq!(persisted_cursor, None);
 }
 
 #[tokio::test]
-#[should_panic]
 async fn test_gap_between_batches() {
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
     setup_db(&connection_pool).await;
@@ -382,7 +403,10 @@ async fn test_gap_between_batches() {
     let db_txs = get_all_db_txs(&mut storage).await;
     assert_eq!(db_txs.len(), 3);
     client.set_last_finalized_block_number(25).await;
-    watcher.loop_iteration(&mut storage).await.unwrap();
+    let err = watcher.loop_iteration(&mut storage).await.unwrap_err();
+    assert_priority_op_gap(err, 3, 4);
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1, 2]);
+    assert_eq!(watcher.sources[0].last_processed_ethereum_block, 15);
 }
 
 #[tokio::test]
@@ -427,6 +451,175 @@ async fn test_overlapping_batches() {
     assert_eq!(tx.common_data.serial_id.0, 4);
 }
 
+async fn get_l1_tx_serial_ids(storage: &mut Connection<'_, Core>) -> Vec<u64> {
+    let mut serial_ids: Vec<_> = get_all_db_txs(storage)
+        .await
+        .into_iter()
+        .map(|tx| L1Tx::try_from(tx).unwrap().common_data.serial_id.0)
+        .collect();
+    serial_ids.sort_unstable();
+    serial_ids
+}
+
+#[tokio::test]
+async fn multiple_sources_with_independent_cursors() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+    let mut other_client = MockEthClient::new();
+    let other_source_addr = Address::repeat_byte(1);
+    let mut watcher = watcher
+        .with_additional_source(other_source_addr, None, Box::new(other_client.clone()))
+        .await
+        .unwrap();
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client.set_last_finalized_block_number(20).await;
+    // The other source requires more confirmations, so its operation isn't processed yet.
+    other_client.add_transactions(&[build_l1_tx(2, 18)]).await;
+    other_client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1]);
+
+    let mut eth_watcher_dal = storage.eth_watcher_dal();
+    assert_eq!(
+        eth_watcher_dal
+            .get_last_processed_l1_block(Address::default())
+            .await
+            .unwrap(),
+        Some(20)
+    );
+    assert_eq!(
+        eth_watcher_dal
+            .get_last_processed_l1_block(other_source_addr)
+            .await
+            .unwrap(),
+        Some(15)
+    );
+
+    other_client.set_last_finalized_block_number(20).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1, 2]);
+
+    // Cursors should be restored after a restart.
+    let watcher = EthWatch::new(
+        Address::default(),
+        None,
+        &governance_contract(),
+        Box::new(client),
+        connection_pool.clone(),
+        std::time::Duration::from_nanos(1),
+    )
+    .await
+    .unwrap()
+    .with_additional_source(other_source_addr, None, Box::new(other_client))
+    .await
+    .unwrap();
+    let cursors: Vec<_> = watcher
+        .sources
+        .iter()
+        .map(|source| source.last_processed_ethereum_block)
+        .collect();
+    assert_eq!(cursors, [20, 20]);
+}
+
+#[tokio::test]
+async fn overlapping_sources_do_not_duplicate_operations() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+    // Both sources observe the same events.
+    let mut watcher = watcher
+        .with_additional_source(Address::repeat_byte(1), None, Box::new(client.clone()))
+        .await
+        .unwrap();
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 14)])
+        .await;
+    client
+        .add_governance_upgrades(&[(
+            ProtocolUpgrade {
+                id: ProtocolVersionId::latest(),
+                tx: None,
+                ..Default::default()
+            },
+            12,
+        )])
+        .await;
+    client.set_last_finalized_block_number(15).await;
+    watcher.loop_iteration(&mut storage).await.unwrap();
+
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1]);
+    let db_ids = storage.protocol_versions_dal().all_version_ids().await;
+    assert_eq!(db_ids.len(), 2);
+    assert_eq!(db_ids[1], ProtocolVersionId::latest());
+}
+
+#[tokio::test]
+async fn interleaved_operations_from_multiple_sources() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (watcher, mut client) = create_test_watcher(connection_pool.clone()).await;
+    let mut other_client = MockEthClient::new();
+    let other_source_addr = Address::repeat_byte(1);
+    let mut watcher = watcher
+        .with_additional_source(other_source_addr, None, Box::new(other_client.clone()))
+        .await
+        .unwrap();
+
+    let mut storage = connection_pool.connection().await.unwrap();
+    client
+        .add_transactions(&[build_l1_tx(0, 10), build_l1_tx(1, 12), build_l1_tx(3, 16)])
+        .await;
+    client.set_last_finalized_block_number(20).await;
+    // Operation #2 is emitted by the other source, which lags behind.
+    other_client.add_transactions(&[build_l1_tx(2, 14)]).await;
+    other_client.set_last_finalized_block_number(10).await;
+
+    let err = watcher.sources[0]
+        .loop_iteration(&mut storage)
+        .await
+        .unwrap_err();
+    assert_priority_op_gap(err, 2, 3);
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1]);
+    assert_eq!(watcher.sources[0].last_processed_ethereum_block, 0);
+    watcher.sources[1]
+        .loop_iteration(&mut storage)
+        .await
+        .unwrap();
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1]);
+
+    // Once the other source catches up, the main source should be able to proceed.
+    other_client.set_last_finalized_block_number(20).await;
+    watcher.sources[1]
+        .loop_iteration(&mut storage)
+        .await
+        .unwrap();
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1, 2]);
+    watcher.sources[0]
+        .loop_iteration(&mut storage)
+        .await
+        .unwrap();
+    assert_eq!(get_l1_tx_serial_ids(&mut storage).await, [0, 1, 2, 3]);
+    assert_eq!(watcher.sources[0].last_processed_ethereum_block, 20);
+}
+
+#[tokio::test]
+async fn adding_same_source_twice_is_an_error() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    setup_db(&connection_pool).await;
+    let (watcher, client) = create_test_watcher(connection_pool).await;
+    watcher
+        .with_additional_source(Address::default(), None, Box::new(client))
+        .await
+        .unwrap_err();
+}
+
 async fn get_all_db_txs(storage: &mut Connection<'_, Core>) -> Vec<Transaction> {
     storage.transactions_dal().reset_mempool().await.unwrap();
     storage
@@ -0,0 +1,226 @@
+//! Explorer API router and handlers.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Deserialize;
+use tower_http::cors::CorsLayer;
+use zksync_dal::{
+    pagination::KeysetPagination, Connection, ConnectionPool, Core, CoreDal, DalError,
+};
+use zksync_types::{
+    api::{self, PageCursor},
+    AccountTreeId, Address, L2BlockNumber, L2ChainId, H256, L2_BASE_TOKEN_ADDRESS,
+};
+
+use crate::{
+    metrics::METRICS,
+    types::{AccountInfo, Page, TokenTransfer},
+};
+
+/// Page size used if the `limit` query param is not specified.
+const DEFAULT_PAGE_SIZE: usize = 20;
+
+#[derive(Debug)]
+pub(crate) enum ApiError {
+    BadRequest(String),
+    NotFound,
+    Internal(anyhow::Error),
+}
+
+impl From<DalError> for ApiError {
+    fn from(err: DalError) -> Self {
+        Self::Internal(err.generalize())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status_code, message) = match self {
+            Self::BadRequest(message) => (StatusCode::BAD_REQUEST, message),
+            Self::NotFound => (StatusCode::NOT_FOUND, "not found".to_owned()),
+            Self::Internal(err) => {
+                tracing::error!("Internal error in explorer API: {err:#}");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    "internal error".to_owned(),
+                )
+            }
+        };
+        let body = serde_json::json!({ "error": message });
+        (status_code, Json(body)).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct PaginationQuery {
+    /// Cursor returned with the previous page.
+    cursor: Option<PageCursor>,
+    limit: Option<usize>,
+}
+
+#[derive(Debug)]
+pub(crate) struct ExplorerApi {
+    pool: ConnectionPool<Core>,
+    l2_chain_id: L2ChainId,
+    max_page_size: usize,
+}
+
+impl ExplorerApi {
+    pub fn new(pool: ConnectionPool<Core>, l2_chain_id: L2ChainId, max_page_size: usize) -> Self {
+        Self {
+            pool,
+            l2_chain_id,
+            max_page_size,
+        }
+    }
+
+    pub fn into_router(self) -> axum::Router<()> {
+        axum::Router::new()
+            .route("/api/accounts/:address", axum::routing::get(Self::account))
+            .route(
+                "/api/accounts/:address/transactions",
+                axum::routing::get(Self::account_transactions),
+            )
+            .route(
+                "/api/accounts/:address/transfers",
+                axum::routing::get(Self::account_transfers),
+            )
+            .route(
+                "/api/transactions/:hash",
+                axum::routing::get(Self::transaction),
+            )
+            .layer(CorsLayer::permissive())
+            .with_state(Arc::new(self))
+    }
+
+    async fn connection(&self) -> Result<Connection<'_, Core>, ApiError> {
+        Ok(self.pool.connection_tagged("explorer_api").await?)
+    }
+
+    /// Validates pagination params.
+    fn pagination(&self, query: &PaginationQuery) -> Result<KeysetPagination, ApiError> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_PAGE_SIZE.min(self.max_page_size));
+        if limit == 0 || limit > self.max_page_size {
+            return Err(ApiError::BadRequest(format!(
+                "`limit` must be in 1..={} range",
+                self.max_page_size
+            )));
+        }
+        Ok(KeysetPagination::new(query.cursor, limit))
+    }
+
+    #[tracing::instrument(skip(self_))]
+    async fn account(
+        State(self_): State<Arc<Self>>,
+        Path(address): Path<Address>,
+    ) -> Result<Json<AccountInfo>, ApiError> {
+        let latency = METRICS.call[&"account"].start();
+        let mut connection = self_.connection().await?;
+        let sealed_l2_block = connection
+            .blocks_dal()
+            .get_sealed_l2_block_number()
+            .await?
+            .unwrap_or(L2BlockNumber(0));
+
+        let balance = connection
+            .storage_web3_dal()
+            .standard_token_historical_balance(
+                AccountTreeId::new(L2_BASE_TOKEN_ADDRESS),
+                AccountTreeId::new(address),
+                sealed_l2_block,
+            )
+            .await?;
+        let nonce = connection
+            .storage_web3_dal()
+            .get_address_historical_nonce(address, sealed_l2_block)
+            .await?;
+        let code = connection
+            .storage_web3_dal()
+            .get_contract_code_unchecked(address, sealed_l2_block)
+            .await?;
+
+        latency.observe();
+        Ok(Json(AccountInfo {
+            address,
+            balance,
+            nonce: nonce.low_u32(),
+            is_contract: code.is_some(),
+            sealed_l2_block,
+        }))
+    }
+
+    #[tracing::instrument(skip(self_))]
+    async fn account_transactions(
+        State(self_): State<Arc<Self>>,
+        Path(address): Path<Address>,
+        Query(query): Query<PaginationQuery>,
+    ) -> Result<Json<Page<api::Transaction>>, ApiError> {
+        let latency = METRICS.call[&"account_transactions"].start();
+        let pagination = self_.pagination(&query)?;
+        let mut connection = self_.connection().await?;
+        let page = connection
+            .transactions_web3_dal()
+            .get_account_transactions(address, pagination, self_.l2_chain_id)
+            .await?;
+
+        latency.observe();
+        Ok(Json(Page {
+            items: page.items,
+            next_cursor: page.next,
+            limit: pagination.limit,
+        }))
+    }
+
+    #[tracing::instrument(skip(self_))]
+    async fn account_transfers(
+        State(self_): State<Arc<Self>>,
+        Path(address): Path<Address>,
+        Query(query): Query<PaginationQuery>,
+    ) -> Result<Json<Page<TokenTransfer>>, ApiError> {
+        let latency = METRICS.call[&"account_transfers"].start();
+        let pagination = self_.pagination(&query)?;
+        let mut connection = self_.connection().await?;
+        let page = connection
+            .events_web3_dal()
+            .get_token_transfer_logs(address, pagination)
+            .await?;
+        // Malformed transfer events (e.g., emitted by non-standard contracts) are skipped, so a page may contain
+        // less than `limit` items even if there are more transfers.
+        let items = page
+            .items
+            .iter()
+            .filter_map(TokenTransfer::from_log)
+            .collect();
+
+        latency.observe();
+        Ok(Json(Page {
+            items,
+            next_cursor: page.next,
+            limit: pagination.limit,
+        }))
+    }
+
+    #[tracing::instrument(skip(self_))]
+    async fn transaction(
+        State(self_): State<Arc<Self>>,
+        Path(hash): Path<H256>,
+    ) -> Result<Json<api::TransactionDetails>, ApiError> {
+        let latency = METRICS.call[&"transaction"].start();
+        let mut connection = self_.connection().await?;
+        let details = connection
+            .transactions_web3_dal()
+            .get_transaction_details(hash)
+            .await?;
+
+        latency.observe();
+        details.map(Json).ok_or(ApiError::NotFound)
+    }
+}
@@ -0,0 +1,45 @@
+//! Minimal block explorer REST API embedded into the node. Intended for devnets, where running a full explorer stack
+//! is overkill.
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::api::ExplorerApiConfig;
+use zksync_dal::{ConnectionPool, Core};
+use zksync_types::L2ChainId;
+
+use self::api::ExplorerApi;
+pub use self::types::{AccountInfo, Page, TokenTransfer};
+
+mod api;
+mod metrics;
+#[cfg(test)]
+mod tests;
+mod types;
+
+/// Runs the explorer API server until a stop signal is received.
+pub async fn start_server(
+    replica_connection_pool: ConnectionPool<Core>,
+    config: ExplorerApiConfig,
+    l2_chain_id: L2ChainId,
+    mut stop_receiver: watch::Receiver<bool>,
+) -> anyhow::Result<()> {
+    let bind_address = config.bind_addr();
+    let api =
+        ExplorerApi::new(replica_connection_pool, l2_chain_id, config.max_page_size).into_router();
+
+    tracing::info!("Starting explorer API server on {bind_address}");
+    axum::Server::bind(&bind_address)
+        .serve(api.into_make_service())
+        .with_graceful_shutdown(async move {
+            if stop_receiver.changed().await.is_err() {
+                tracing::warn!(
+                    "Stop signal sender for explorer API server was dropped without sending a signal"
+                );
+            }
+            tracing::info!("Stop signal received, explorer API server is shutting down");
+        })
+        .await
+        .context("Explorer API server failed")?;
+    tracing::info!("Explorer API server shut down");
+    Ok(())
+}
@@ -0,0 +1,16 @@
+//! Metrics for the explorer API.
+
+use std::time::Duration;
+
+use vise::{Buckets, Histogram, LabeledFamily, Metrics};
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "api_explorer")]
+pub(crate) struct ExplorerApiMetrics {
+    /// Latency of explorer API calls.
+    #[metrics(buckets = Buckets::LATENCIES, labels = ["method"])]
+    pub call: LabeledFamily<&'static str, Histogram<Duration>>,
+}
+
+#[vise::register]
+pub(crate) static METRICS: vise::Global<ExplorerApiMetrics> = vise::Global::new();
@@ -0,0 +1,176 @@
+//! Tests for the explorer API.
+
+use axum::{body::Body, http::Request, Router};
+use hyper::StatusCode;
+use tower::ServiceExt;
+use zksync_dal::{Connection, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_node_test_utils::{create_l2_block, create_l2_transaction, execute_l2_transaction};
+use zksync_types::{
+    api, ethabi, tx::IncludedTxLocation, Address, L1BatchNumber, L2BlockNumber, L2ChainId,
+    ProtocolVersionId, VmEvent, H256, L2_BASE_TOKEN_ADDRESS, U256,
+};
+use zksync_utils::{address_to_h256, u256_to_h256};
+
+use super::*;
+
+const MAX_PAGE_SIZE: usize = 10;
+
+async fn get<T: serde::de::DeserializeOwned>(
+    router: &Router,
+    uri: &str,
+) -> (StatusCode, Option<T>) {
+    let request = Request::get(uri).body(Body::empty()).unwrap();
+    let response = router.clone().oneshot(request).await.unwrap();
+    let status = response.status();
+    let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+    let body = status
+        .is_success()
+        .then(|| serde_json::from_slice(&body).unwrap());
+    (status, body)
+}
+
+/// Stores an L2 block with a single transaction emitting a single transfer event.
+async fn store_transfer(
+    storage: &mut Connection<'_, Core>,
+    token: Address,
+    to: Address,
+    amount: U256,
+) -> (Address, H256) {
+    let tx = create_l2_transaction(10, 100);
+    let from = tx.initiator_account();
+    let tx_hash = tx.hash();
+    let l2_block = create_l2_block(1);
+    storage
+        .blocks_dal()
+        .insert_l2_block(&l2_block)
+        .await
+        .unwrap();
+    storage
+        .transactions_dal()
+        .mark_txs_as_executed_in_l2_block(
+            l2_block.number,
+            &[execute_l2_transaction(tx)],
+            1.into(),
+            ProtocolVersionId::latest(),
+            true,
+        )
+        .await
+        .unwrap();
+
+    let transfer_signature = ethabi::long_signature(
+        "Transfer",
+        &[
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Address,
+            ethabi::ParamType::Uint(256),
+        ],
+    );
+    let event = VmEvent {
+        location: (L1BatchNumber(1), 0),
+        address: token,
+        indexed_topics: vec![
+            transfer_signature,
+            address_to_h256(&from),
+            address_to_h256(&to),
+        ],
+        value: u256_to_h256(amount).as_bytes().to_vec(),
+    };
+    let location = IncludedTxLocation {
+        tx_hash,
+        tx_index_in_l2_block: 0,
+        tx_initiator_address: from,
+    };
+    storage
+        .events_dal()
+        .save_events(l2_block.number, &[(location, vec![&event])])
+        .await
+        .unwrap();
+    (from, tx_hash)
+}
+
+#[tokio::test]
+async fn explorer_api_basics() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut storage = pool.connection().await.unwrap();
+    insert_genesis_batch(&mut storage, &GenesisParams::mock())
+        .await
+        .unwrap();
+    let token = Address::repeat_byte(0x11);
+    let recipient = Address::repeat_byte(0x22);
+    let (sender, tx_hash) = store_transfer(&mut storage, token, recipient, 1_000.into()).await;
+    drop(storage);
+
+    let router = ExplorerApi::new(pool, L2ChainId::default(), MAX_PAGE_SIZE).into_router();
+
+    let (status, account) = get::<AccountInfo>(&router, &format!("/api/accounts/{sender:?}")).await;
+    assert_eq!(status, StatusCode::OK);
+    let account = account.unwrap();
+    assert_eq!(account.address, sender);
+    assert_eq!(account.nonce, 0);
+    assert!(!account.is_contract);
+    assert_eq!(account.sealed_l2_block, L2BlockNumber(1));
+
+    let uri = format!("/api/accounts/{L2_BASE_TOKEN_ADDRESS:?}");
+    let (status, account) = get::<AccountInfo>(&router, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(account.unwrap().is_contract);
+
+    let uri = format!("/api/accounts/{sender:?}/transactions");
+    let (status, page) = get::<Page<api::Transaction>>(&router, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let page = page.unwrap();
+    assert_eq!(page.limit, MAX_PAGE_SIZE);
+    assert_eq!(page.items.len(), 1);
+    assert_eq!(page.items[0].hash, tx_hash);
+    assert_eq!(page.next_cursor, None);
+
+    // Cursor pointing to the genesis block, i.e. before the transaction.
+    let uri = format!("/api/accounts/{sender:?}/transactions?cursor=0x00000000ffffffff&limit=5");
+    let (status, page) = get::<Page<api::Transaction>>(&router, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let page = page.unwrap();
+    assert_eq!(page.limit, 5);
+    assert!(page.items.is_empty());
+
+    for account in [sender, recipient] {
+        let uri = format!("/api/accounts/{account:?}/transfers");
+        let (status, page) = get::<Page<TokenTransfer>>(&router, &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        let transfers = page.unwrap().items;
+        assert_eq!(transfers.len(), 1);
+        let transfer = &transfers[0];
+        assert_eq!(transfer.token, token);
+        assert_eq!((transfer.from, transfer.to), (sender, recipient));
+        assert_eq!(transfer.value, 1_000.into());
+        assert_eq!(transfer.transaction_hash, tx_hash);
+        assert_eq!(transfer.block_number, L2BlockNumber(1));
+    }
+    let uri = format!("/api/accounts/{token:?}/transfers");
+    let (status, page) = get::<Page<TokenTransfer>>(&router, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(page.unwrap().items.is_empty());
+
+    let uri = format!("/api/transactions/{tx_hash:?}");
+    let (status, details) = get::<api::TransactionDetails>(&router, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(details.unwrap().initiator_address, sender);
+    let uri = format!("/api/transactions/{:?}", H256::zero());
+    let (status, _) = get::<serde_json::Value>(&router, &uri).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn invalid_pagination_params() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let router = ExplorerApi::new(pool, L2ChainId::default(), MAX_PAGE_SIZE).into_router();
+    let address = Address::repeat_byte(1);
+
+    for query in ["limit=0", "limit=11", "cursor=0x01", "limit=foo"] {
+        let uri = format!("/api/accounts/{address:?}/transactions?{query}");
+        let (status, _) = get::<serde_json::Value>(&router, &uri).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{query}");
+    }
+    let (status, _) = get::<serde_json::Value>(&router, "/api/accounts/0x01").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
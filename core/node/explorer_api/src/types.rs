@@ -0,0 +1,68 @@
+//! Types returned by the explorer API.
+
+use serde::{Deserialize, Serialize};
+use zksync_types::{api, Address, L2BlockNumber, H256, U256};
+use zksync_utils::h256_to_account_address;
+
+/// Information about an account as of the latest sealed L2 block.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInfo {
+    pub address: Address,
+    /// Balance of the account in the base token.
+    pub balance: U256,
+    /// Stored (i.e., not accounting for pending transactions) nonce of the account.
+    pub nonce: u32,
+    /// Whether the account has deployed bytecode.
+    pub is_contract: bool,
+    /// L2 block the information is returned for.
+    pub sealed_l2_block: L2BlockNumber,
+}
+
+/// Token transfer from or to an account.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTransfer {
+    /// Address of the token contract.
+    pub token: Address,
+    pub from: Address,
+    pub to: Address,
+    /// Transferred amount for ERC-20 transfers, or the token ID for ERC-721 transfers.
+    pub value: U256,
+    pub transaction_hash: H256,
+    pub block_number: L2BlockNumber,
+    /// Index of the transfer event in the L2 block.
+    pub log_index: u32,
+}
+
+impl TokenTransfer {
+    /// Parses a transfer from a `Transfer` event log. Returns `None` if the log is malformed.
+    pub(crate) fn from_log(log: &api::Log) -> Option<Self> {
+        let value = match (log.topics.as_slice(), log.data.0.len()) {
+            // ERC-20 transfer: `Transfer(address indexed from, address indexed to, uint256 value)`
+            ([_, _, _], 32) => U256::from_big_endian(&log.data.0),
+            // ERC-721 transfer: `Transfer(address indexed from, address indexed to, uint256 indexed tokenId)`
+            ([_, _, _, token_id], 0) => U256::from_big_endian(token_id.as_bytes()),
+            _ => return None,
+        };
+        Some(Self {
+            token: log.address,
+            from: h256_to_account_address(&log.topics[1]),
+            to: h256_to_account_address(&log.topics[2]),
+            value,
+            transaction_hash: log.transaction_hash?,
+            block_number: L2BlockNumber(log.block_number?.as_u32()),
+            log_index: log.log_index?.as_u32(),
+        })
+    }
+}
+
+/// Page of items returned by paginated endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor to pass as the `cursor` query param to get the next page; `None` if this is the last page.
+    pub next_cursor: Option<api::PageCursor>,
+    pub limit: usize,
+}
@@ -0,0 +1,146 @@
+//! Strategies deriving the blob base fee from the samples collected by `GasAdjuster`.
+
+use std::fmt;
+
+use zksync_config::{configs::eth_sender::BlobFeeStrategy, GasAdjusterConfig};
+use zksync_types::U256;
+
+/// Derives the blob base fee to be used from the recent blob base fee samples.
+pub(super) trait BlobFeeEstimator: fmt::Debug + Send + Sync {
+    /// Name of the strategy, used in logs and metrics.
+    fn name(&self) -> &'static str;
+
+    /// Estimates the blob base fee given samples ordered from the oldest to the latest one.
+    /// Returns `None` if there are no samples.
+    fn estimate(&self, samples: &[U256]) -> Option<U256>;
+}
+
+/// Creates the estimator for the strategy selected in the config.
+pub(super) fn estimator_from_config(config: &GasAdjusterConfig) -> Box<dyn BlobFeeEstimator> {
+    estimator_for(config.blob_fee_strategy, config)
+}
+
+/// Creates estimators for all the supported strategies, parametrized from the config.
+/// Used to simulate what each strategy would have paid.
+pub(super) fn all_estimators(config: &GasAdjusterConfig) -> Vec<Box<dyn BlobFeeEstimator>> {
+    [
+        BlobFeeStrategy::Median,
+        BlobFeeStrategy::Percentile,
+        BlobFeeStrategy::Ewma,
+        BlobFeeStrategy::Capped,
+    ]
+    .into_iter()
+    .map(|strategy| estimator_for(strategy, config))
+    .collect()
+}
+
+fn estimator_for(
+    strategy: BlobFeeStrategy,
+    config: &GasAdjusterConfig,
+) -> Box<dyn BlobFeeEstimator> {
+    match strategy {
+        BlobFeeStrategy::Median => Box::new(MedianEstimator),
+        BlobFeeStrategy::Percentile => Box::new(PercentileEstimator {
+            percentile: config.blob_fee_percentile(),
+        }),
+        BlobFeeStrategy::Ewma => Box::new(EwmaEstimator::new(config.blob_fee_ewma_alpha())),
+        BlobFeeStrategy::Capped => Box::new(CappedEstimator {
+            cap: config.blob_fee_cap().into(),
+        }),
+    }
+}
+
+/// Median of the samples; matches the median maintained by `GasStatistics`.
+#[derive(Debug)]
+pub(super) struct MedianEstimator;
+
+impl BlobFeeEstimator for MedianEstimator {
+    fn name(&self) -> &'static str {
+        "median"
+    }
+
+    fn estimate(&self, samples: &[U256]) -> Option<U256> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut samples = samples.to_vec();
+        let len = samples.len();
+        let (_, &mut median, _) = samples.select_nth_unstable(len / 2);
+        Some(median)
+    }
+}
+
+/// Nearest-rank percentile of the samples.
+#[derive(Debug)]
+pub(super) struct PercentileEstimator {
+    pub percentile: u32,
+}
+
+impl BlobFeeEstimator for PercentileEstimator {
+    fn name(&self) -> &'static str {
+        "percentile"
+    }
+
+    fn estimate(&self, samples: &[U256]) -> Option<U256> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut samples = samples.to_vec();
+        let index = (samples.len() - 1) * self.percentile.min(100) as usize / 100;
+        let (_, &mut value, _) = samples.select_nth_unstable(index);
+        Some(value)
+    }
+}
+
+/// Exponentially weighted moving average of the samples, with later samples having more weight.
+#[derive(Debug)]
+pub(super) struct EwmaEstimator {
+    /// Smoothing factor in parts per million, so that the computation can be performed on `U256`.
+    alpha_ppm: u64,
+}
+
+impl EwmaEstimator {
+    const PPM: u64 = 1_000_000;
+
+    pub fn new(alpha: f64) -> Self {
+        let alpha_ppm = (alpha.clamp(0.0, 1.0) * Self::PPM as f64) as u64;
+        Self {
+            alpha_ppm: alpha_ppm.max(1),
+        }
+    }
+}
+
+impl BlobFeeEstimator for EwmaEstimator {
+    fn name(&self) -> &'static str {
+        "ewma"
+    }
+
+    fn estimate(&self, samples: &[U256]) -> Option<U256> {
+        let (&first, rest) = samples.split_first()?;
+        let alpha = U256::from(self.alpha_ppm);
+        let complement = U256::from(Self::PPM - self.alpha_ppm);
+        let average = rest.iter().fold(first, |average, &sample| {
+            sample
+                .saturating_mul(alpha)
+                .saturating_add(average.saturating_mul(complement))
+                / U256::from(Self::PPM)
+        });
+        Some(average)
+    }
+}
+
+/// Latest sample, capped by the configured value. Follows the market closely while bounding the price.
+#[derive(Debug)]
+pub(super) struct CappedEstimator {
+    pub cap: U256,
+}
+
+impl BlobFeeEstimator for CappedEstimator {
+    fn name(&self) -> &'static str {
+        "capped"
+    }
+
+    fn estimate(&self, samples: &[U256]) -> Option<U256> {
+        samples.last().map(|&latest| latest.min(self.cap))
+    }
+}
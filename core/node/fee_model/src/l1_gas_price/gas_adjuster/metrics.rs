@@ -1,6 +1,6 @@
 //! Gas adjuster metrics.
 
-use vise::{Gauge, Metrics};
+use vise::{Gauge, LabeledFamily, Metrics};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_gas_adjuster")]
@@ -10,6 +10,9 @@ pub(super) struct GasAdjusterMetrics {
     pub median_base_fee_per_gas: Gauge<u64>,
     pub median_blob_base_fee_per_gas: Gauge<u64>,
     pub median_blob_base_fee: Gauge<u64>,
+    /// Blob base fee each of the supported strategies would have used.
+    #[metrics(labels = ["strategy"])]
+    pub simulated_blob_base_fee: LabeledFamily<&'static str, Gauge<u64>>,
 }
 
 #[vise::register]
@@ -9,12 +9,19 @@ use std::{
 use tokio::sync::watch;
 use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
 use zksync_eth_client::{Error, EthInterface};
-use zksync_types::{commitment::L1BatchCommitmentMode, L1_GAS_PER_PUBDATA_BYTE, U256, U64};
+use zksync_types::{
+    commitment::L1BatchCommitmentMode, fee_model::L1PricesBreakdown, L1_GAS_PER_PUBDATA_BYTE, U256,
+    U64,
+};
 use zksync_web3_decl::client::{DynClient, L1};
 
-use self::metrics::METRICS;
+use self::{
+    blob_fee_strategy::{all_estimators, estimator_from_config, BlobFeeEstimator},
+    metrics::METRICS,
+};
 use super::L1TxParamsProvider;
 
+mod blob_fee_strategy;
 mod metrics;
 #[cfg(test)]
 mod tests;
@@ -30,6 +37,10 @@ pub struct GasAdjuster {
     // But it's still possible and code shouldn't panic if that happens. One more argument is that geth uses big int type for blob prices.
     pub(super) blob_base_fee_statistics: GasStatistics<U256>,
     pub(super) config: GasAdjusterConfig,
+    /// Strategy used to derive the blob base fee from `blob_base_fee_statistics`.
+    blob_fee_estimator: Box<dyn BlobFeeEstimator>,
+    /// All supported strategies; only used to report what each of them would have paid.
+    simulated_blob_fee_estimators: Vec<Box<dyn BlobFeeEstimator>>,
     pubdata_sending_mode: PubdataSendingMode,
     eth_client: Box<DynClient<L1>>,
     commitment_mode: L1BatchCommitmentMode,
@@ -72,6 +83,8 @@ impl GasAdjuster {
                 current_block,
                 &last_block_blob_base_fee,
             ),
+            blob_fee_estimator: estimator_from_config(&config),
+            simulated_blob_fee_estimators: all_estimators(&config),
             config,
             pubdata_sending_mode,
             eth_client,
@@ -123,10 +136,37 @@ impl GasAdjuster {
             }
             self.blob_base_fee_statistics
                 .add_samples(&blob_base_fee_history);
+            self.simulate_blob_fee_strategies();
         }
         Ok(())
     }
 
+    /// Reports the blob base fee each of the supported strategies would have used.
+    fn simulate_blob_fee_strategies(&self) {
+        let samples = self.blob_base_fee_statistics.samples();
+        for estimator in &self.simulated_blob_fee_estimators {
+            let Some(estimate) = estimator.estimate(&samples) else {
+                continue;
+            };
+            let is_active = estimator.name() == self.blob_fee_estimator.name();
+            tracing::debug!(
+                "Blob base fee estimated by `{}` strategy: {estimate} (active: {is_active})",
+                estimator.name()
+            );
+            if estimate <= U256::from(u64::MAX) {
+                METRICS.simulated_blob_base_fee[&estimator.name()].set(estimate.as_u64());
+            }
+        }
+    }
+
+    /// Returns the blob base fee derived from the collected samples by the configured strategy.
+    fn estimate_blob_base_fee(&self) -> U256 {
+        let samples = self.blob_base_fee_statistics.samples();
+        self.blob_fee_estimator
+            .estimate(&samples)
+            .unwrap_or_else(|| self.blob_base_fee_statistics.median())
+    }
+
     fn bound_gas_price(&self, gas_price: u64) -> u64 {
         let max_l1_gas_price = self.config.max_l1_gas_price();
         if gas_price > max_l1_gas_price {
@@ -180,18 +220,16 @@ impl GasAdjuster {
             PubdataSendingMode::Blobs => {
                 const BLOB_GAS_PER_BYTE: u64 = 1; // `BYTES_PER_BLOB` = `GAS_PER_BLOB` = 2 ^ 17.
 
-                let blob_base_fee_median = self.blob_base_fee_statistics.median();
+                let blob_base_fee = self.estimate_blob_base_fee();
 
                 // Check if blob base fee overflows `u64` before converting. Can happen only in very extreme cases.
-                if blob_base_fee_median > U256::from(u64::MAX) {
+                if blob_base_fee > U256::from(u64::MAX) {
                     let max_allowed = self.config.max_blob_base_fee();
-                    tracing::error!("Blob base fee is too high: {blob_base_fee_median}, using max allowed: {max_allowed}");
+                    tracing::error!("Blob base fee is too high: {blob_base_fee}, using max allowed: {max_allowed}");
                     return max_allowed;
                 }
-                METRICS
-                    .median_blob_base_fee
-                    .set(blob_base_fee_median.as_u64());
-                let calculated_price = blob_base_fee_median.as_u64() as f64
+                METRICS.median_blob_base_fee.set(blob_base_fee.as_u64());
+                let calculated_price = blob_base_fee.as_u64() as f64
                     * BLOB_GAS_PER_BYTE as f64
                     * self.config.internal_pubdata_pricing_multiplier;
 
@@ -203,6 +241,26 @@ impl GasAdjuster {
         }
     }
 
+    /// Returns inputs used to derive the effective L1 gas and pubdata prices.
+    pub(crate) fn l1_prices_breakdown(&self) -> L1PricesBreakdown {
+        L1PricesBreakdown {
+            median_base_fee: self.base_fee_statistics.median(),
+            last_base_fee: self.base_fee_statistics.last_added_value(),
+            priority_fee: self.get_priority_fee(),
+            l1_pricing_multiplier: self.config.internal_l1_pricing_multiplier,
+            enforced_l1_gas_price: self.config.internal_enforced_l1_gas_price,
+            max_l1_gas_price: self.config.max_l1_gas_price(),
+            effective_l1_gas_price: self.estimate_effective_gas_price(),
+            uses_blobs: matches!(self.pubdata_sending_mode, PubdataSendingMode::Blobs),
+            blob_fee_strategy: self.blob_fee_estimator.name().to_owned(),
+            blob_base_fee: self.estimate_blob_base_fee(),
+            pubdata_pricing_multiplier: self.config.internal_pubdata_pricing_multiplier,
+            enforced_pubdata_price: self.config.internal_enforced_pubdata_price,
+            max_blob_base_fee: self.config.max_blob_base_fee(),
+            effective_pubdata_price: self.estimate_effective_pubdata_price(),
+        }
+    }
+
     fn pubdata_byte_gas(&self) -> u64 {
         match self.commitment_mode {
             L1BatchCommitmentMode::Validium => 0,
@@ -311,9 +369,11 @@ impl L1TxParamsProvider for GasAdjuster {
         // The alternative is a linear one:
         // `let scale_factor = a + b * time_in_mempool as f64;`
         let scale_factor = a * b.powf(0.0);
-        let median = self.blob_base_fee_statistics.median();
-        METRICS.median_blob_base_fee_per_gas.set(median.as_u64());
-        let new_fee = median.as_u64() as f64 * scale_factor;
+        let blob_base_fee = self.estimate_blob_base_fee();
+        METRICS
+            .median_blob_base_fee_per_gas
+            .set(blob_base_fee.as_u64());
+        let new_fee = blob_base_fee.as_u64() as f64 * scale_factor;
         new_fee as u64
     }
 
@@ -367,6 +427,10 @@ impl<T: Ord + Copy + Default> GasStatisticsInner<T> {
         self.median_cached
     }
 
+    fn samples(&self) -> Vec<T> {
+        self.samples.iter().copied().collect()
+    }
+
     fn last_added_value(&self) -> T {
         self.samples.back().copied().unwrap_or(self.median_cached)
     }
@@ -403,6 +467,11 @@ impl<T: Ord + Copy + Default> GasStatistics<T> {
         self.0.read().unwrap().median()
     }
 
+    /// Returns the collected samples, ordered from the oldest to the latest one.
+    pub fn samples(&self) -> Vec<T> {
+        self.0.read().unwrap().samples()
+    }
+
     pub fn last_added_value(&self) -> T {
         self.0.read().unwrap().last_added_value()
     }
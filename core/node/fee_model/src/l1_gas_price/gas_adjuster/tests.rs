@@ -1,11 +1,19 @@
 use std::collections::VecDeque;
 
 use test_casing::test_casing;
-use zksync_config::{configs::eth_sender::PubdataSendingMode, GasAdjusterConfig};
+use zksync_config::{
+    configs::eth_sender::{BlobFeeStrategy, PubdataSendingMode},
+    GasAdjusterConfig,
+};
 use zksync_eth_client::clients::MockEthereum;
-use zksync_types::commitment::L1BatchCommitmentMode;
+use zksync_types::{commitment::L1BatchCommitmentMode, U256};
 
-use super::{GasAdjuster, GasStatisticsInner};
+use super::{
+    blob_fee_strategy::{
+        BlobFeeEstimator, CappedEstimator, EwmaEstimator, MedianEstimator, PercentileEstimator,
+    },
+    GasAdjuster, GasStatisticsInner,
+};
 
 /// Check that we compute the median correctly
 #[test]
@@ -63,6 +71,10 @@ async fn kept_updated(commitment_mode: L1BatchCommitmentMode) {
             num_samples_for_blob_base_fee_estimate: 3,
             internal_pubdata_pricing_multiplier: 1.0,
             max_blob_base_fee: None,
+            blob_fee_strategy: BlobFeeStrategy::Median,
+            blob_fee_percentile: None,
+            blob_fee_ewma_alpha: None,
+            blob_fee_cap: None,
         },
         PubdataSendingMode::Calldata,
         commitment_mode,
@@ -126,3 +138,91 @@ fn blob_base_fee_formula() {
     let blob_base_fee = GasAdjuster::blob_base_fee(EXCESS_BLOB_GAS);
     assert_eq!(blob_base_fee.as_u64(), EXPECTED_BLOB_BASE_FEE);
 }
+
+fn u256_samples(samples: &[u64]) -> Vec<U256> {
+    samples.iter().copied().map(U256::from).collect()
+}
+
+#[test]
+fn blob_fee_estimators() {
+    let samples = u256_samples(&[6, 4, 7, 8, 4]);
+
+    assert_eq!(MedianEstimator.estimate(&samples), Some(6.into()));
+    assert_eq!(MedianEstimator.estimate(&[]), None);
+
+    // sorted: 4 4 6 7 8
+    let percentile = |percentile| PercentileEstimator { percentile }.estimate(&samples);
+    assert_eq!(percentile(0), Some(4.into()));
+    assert_eq!(percentile(50), Some(6.into()));
+    assert_eq!(percentile(75), Some(7.into()));
+    assert_eq!(percentile(100), Some(8.into()));
+
+    // Latest sample is 4; the cap only applies if it's lower.
+    let capped = |cap: u64| CappedEstimator { cap: cap.into() }.estimate(&samples);
+    assert_eq!(capped(5), Some(4.into()));
+    assert_eq!(capped(3), Some(3.into()));
+}
+
+#[test]
+fn ewma_blob_fee_estimator() {
+    let samples = u256_samples(&[100, 200, 200]);
+
+    // With `alpha = 1`, only the latest sample matters.
+    assert_eq!(EwmaEstimator::new(1.0).estimate(&samples), Some(200.into()));
+    // 100 -> 150 -> 175
+    assert_eq!(EwmaEstimator::new(0.5).estimate(&samples), Some(175.into()));
+    assert_eq!(EwmaEstimator::new(0.5).estimate(&[]), None);
+
+    // Huge values must not panic.
+    let huge = vec![U256::MAX; 3];
+    assert!(EwmaEstimator::new(0.5).estimate(&huge).is_some());
+}
+
+#[tokio::test]
+async fn pubdata_price_uses_configured_blob_fee_strategy() {
+    let eth_client = MockEthereum::builder()
+        .with_fee_history(vec![0, 4, 6, 8, 7, 5, 5, 8])
+        .with_excess_blob_gas_history((1..=8).map(|i| 2 * 3338477 * i).collect())
+        .build();
+    // 3 sampled blocks + additional block to account for latest block subtraction
+    eth_client.advance_block_number(4);
+
+    let config = GasAdjusterConfig {
+        default_priority_fee_per_gas: 5,
+        max_base_fee_samples: 5,
+        pricing_formula_parameter_a: 1.0,
+        pricing_formula_parameter_b: 1.0,
+        internal_l1_pricing_multiplier: 1.0,
+        internal_enforced_l1_gas_price: None,
+        internal_enforced_pubdata_price: None,
+        poll_period: 5,
+        max_l1_gas_price: None,
+        num_samples_for_blob_base_fee_estimate: 3,
+        internal_pubdata_pricing_multiplier: 1.0,
+        max_blob_base_fee: None,
+        blob_fee_strategy: BlobFeeStrategy::Percentile,
+        blob_fee_percentile: Some(100),
+        blob_fee_ewma_alpha: None,
+        blob_fee_cap: None,
+    };
+    let adjuster = GasAdjuster::new(
+        Box::new(eth_client.clone().into_client()),
+        config,
+        PubdataSendingMode::Blobs,
+        L1BatchCommitmentMode::Rollup,
+    )
+    .await
+    .unwrap();
+    eth_client.advance_block_number(3);
+    adjuster.keep_updated().await.unwrap();
+
+    let samples = adjuster.blob_base_fee_statistics.samples();
+    assert_eq!(samples.len(), 3);
+    // Blob base fee grows with each block, so the 100th percentile is the latest sample, unlike the median.
+    let max_sample = *samples.last().unwrap();
+    assert_ne!(max_sample, adjuster.blob_base_fee_statistics.median());
+    assert_eq!(
+        adjuster.estimate_effective_pubdata_price(),
+        max_sample.as_u64()
+    );
+}
@@ -4,8 +4,9 @@ use anyhow::Context as _;
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_types::{
     fee_model::{
-        BatchFeeInput, FeeModelConfig, FeeModelConfigV2, FeeParams, FeeParamsV1, FeeParamsV2,
-        L1PeggedBatchFeeModelInput, PubdataIndependentBatchFeeModelInput,
+        BatchFeeInput, BatchOverheadBreakdown, FeeModelBreakdown, FeeModelConfig, FeeModelConfigV2,
+        FeeParams, FeeParamsV1, FeeParamsV2, L1PeggedBatchFeeModelInput, L1PricesBreakdown,
+        PubdataIndependentBatchFeeModelInput,
     },
     U256,
 };
@@ -26,24 +27,39 @@ pub trait BatchFeeModelInputProvider: fmt::Debug + 'static + Send + Sync {
         l1_pubdata_price_scale_factor: f64,
     ) -> anyhow::Result<BatchFeeInput> {
         let params = self.get_fee_model_params();
-
-        Ok(match params {
-            FeeParams::V1(params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
-                params,
-                l1_gas_price_scale_factor,
-            )),
-            FeeParams::V2(params) => {
-                BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
-                    params,
-                    l1_gas_price_scale_factor,
-                    l1_pubdata_price_scale_factor,
-                ))
-            }
-        })
+        Ok(compute_batch_fee_model_input(
+            params,
+            l1_gas_price_scale_factor,
+            l1_pubdata_price_scale_factor,
+        ))
     }
 
     /// Returns the fee model parameters.
     fn get_fee_model_params(&self) -> FeeParams;
+
+    /// Returns inputs used to derive the L1 gas and pubdata prices. Returns `None` if the provider
+    /// doesn't estimate these prices itself (e.g., if it takes them from the main node).
+    fn get_l1_prices_breakdown(&self) -> Option<L1PricesBreakdown> {
+        None
+    }
+
+    /// Returns the detailed derivation of the current fee parameters.
+    fn get_fee_model_breakdown(&self) -> FeeModelBreakdown {
+        let fee_params = self.get_fee_model_params();
+        let batch_overhead = match fee_params {
+            FeeParams::V1(_) => None,
+            FeeParams::V2(params) => {
+                Some(compute_batch_overhead(params.config, params.l1_gas_price))
+            }
+        };
+        FeeModelBreakdown {
+            fee_params,
+            batch_fee_input: compute_batch_fee_model_input(fee_params, 1.0, 1.0)
+                .into_pubdata_independent(),
+            batch_overhead,
+            l1_prices: self.get_l1_prices_breakdown(),
+        }
+    }
 }
 
 impl dyn BatchFeeModelInputProvider {
@@ -76,6 +92,10 @@ impl BatchFeeModelInputProvider for MainNodeFeeInputProvider {
             }),
         }
     }
+
+    fn get_l1_prices_breakdown(&self) -> Option<L1PricesBreakdown> {
+        Some(self.provider.l1_prices_breakdown())
+    }
 }
 
 impl MainNodeFeeInputProvider {
@@ -133,6 +153,30 @@ impl BatchFeeModelInputProvider for ApiFeeInputProvider {
     fn get_fee_model_params(&self) -> FeeParams {
         self.inner.get_fee_model_params()
     }
+
+    fn get_l1_prices_breakdown(&self) -> Option<L1PricesBreakdown> {
+        self.inner.get_l1_prices_breakdown()
+    }
+}
+
+fn compute_batch_fee_model_input(
+    params: FeeParams,
+    l1_gas_price_scale_factor: f64,
+    l1_pubdata_price_scale_factor: f64,
+) -> BatchFeeInput {
+    match params {
+        FeeParams::V1(params) => BatchFeeInput::L1Pegged(compute_batch_fee_model_input_v1(
+            params,
+            l1_gas_price_scale_factor,
+        )),
+        FeeParams::V2(params) => {
+            BatchFeeInput::PubdataIndependent(compute_batch_fee_model_input_v2(
+                params,
+                l1_gas_price_scale_factor,
+                l1_pubdata_price_scale_factor,
+            ))
+        }
+    }
 }
 
 /// Calculates the batch fee input based on the main node parameters.
@@ -162,24 +206,41 @@ fn compute_batch_fee_model_input_v2(
         l1_pubdata_price,
     } = params;
 
+    // Firstly, we scale the gas price and pubdata price in case it is needed.
+    let l1_gas_price = (l1_gas_price as f64 * l1_gas_price_scale_factor) as u64;
+    let l1_pubdata_price = (l1_pubdata_price as f64 * l1_pubdata_price_scale_factor) as u64;
+
+    let BatchOverheadBreakdown {
+        overhead_per_l2_gas,
+        overhead_per_pubdata_byte,
+        ..
+    } = compute_batch_overhead(config, l1_gas_price);
+
+    PubdataIndependentBatchFeeModelInput {
+        l1_gas_price,
+        // We sum up the minimal L2 gas price (i.e. the raw prover/compute cost of a single L2 gas) and the overhead for batch being closed.
+        fair_l2_gas_price: config.minimal_l2_gas_price + overhead_per_l2_gas,
+        // We sum up the raw L1 pubdata price (i.e. the expected price of publishing a single pubdata byte) and the overhead for batch being closed.
+        fair_pubdata_price: l1_pubdata_price + overhead_per_pubdata_byte,
+    }
+}
+
+/// Splits the cost of closing the batch among L2 gas and pubdata for the `V2` fee model.
+fn compute_batch_overhead(config: FeeModelConfigV2, l1_gas_price: u64) -> BatchOverheadBreakdown {
     let FeeModelConfigV2 {
-        minimal_l2_gas_price,
         compute_overhead_part,
         pubdata_overhead_part,
         batch_overhead_l1_gas,
         max_gas_per_batch,
         max_pubdata_per_batch,
+        ..
     } = config;
 
-    // Firstly, we scale the gas price and pubdata price in case it is needed.
-    let l1_gas_price = (l1_gas_price as f64 * l1_gas_price_scale_factor) as u64;
-    let l1_pubdata_price = (l1_pubdata_price as f64 * l1_pubdata_price_scale_factor) as u64;
-
     // While the final results of the calculations are not expected to have any overflows, the intermediate computations
     // might, so we use U256 for them.
     let l1_batch_overhead_wei = U256::from(l1_gas_price) * U256::from(batch_overhead_l1_gas);
 
-    let fair_l2_gas_price = {
+    let overhead_per_l2_gas = {
         // Firstly, we calculate which part of the overall overhead overhead each unit of L2 gas should cover.
         let l1_batch_overhead_per_gas =
             ceil_div_u256(l1_batch_overhead_wei, U256::from(max_gas_per_batch));
@@ -187,14 +248,10 @@ fn compute_batch_fee_model_input_v2(
         // Then, we multiply by the `compute_overhead_part` to get the overhead for the computation for each gas.
         // Also, this means that if we almost never close batches because of compute, the `compute_overhead_part` should be zero and so
         // it is possible that the computation costs include for no overhead.
-        let gas_overhead_wei =
-            (l1_batch_overhead_per_gas.as_u64() as f64 * compute_overhead_part) as u64;
-
-        // We sum up the minimal L2 gas price (i.e. the raw prover/compute cost of a single L2 gas) and the overhead for batch being closed.
-        minimal_l2_gas_price + gas_overhead_wei
+        (l1_batch_overhead_per_gas.as_u64() as f64 * compute_overhead_part) as u64
     };
 
-    let fair_pubdata_price = {
+    let overhead_per_pubdata_byte = {
         // Firstly, we calculate which part of the overall overhead overhead each pubdata byte should cover.
         let l1_batch_overhead_per_pubdata =
             ceil_div_u256(l1_batch_overhead_wei, U256::from(max_pubdata_per_batch));
@@ -202,17 +259,13 @@ fn compute_batch_fee_model_input_v2(
         // Then, we multiply by the `pubdata_overhead_part` to get the overhead for each pubdata byte.
         // Also, this means that if we almost never close batches because of pubdata, the `pubdata_overhead_part` should be zero and so
         // it is possible that the pubdata costs include no overhead.
-        let pubdata_overhead_wei =
-            (l1_batch_overhead_per_pubdata.as_u64() as f64 * pubdata_overhead_part) as u64;
-
-        // We sum up the raw L1 pubdata price (i.e. the expected price of publishing a single pubdata byte) and the overhead for batch being closed.
-        l1_pubdata_price + pubdata_overhead_wei
+        (l1_batch_overhead_per_pubdata.as_u64() as f64 * pubdata_overhead_part) as u64
     };
 
-    PubdataIndependentBatchFeeModelInput {
-        l1_gas_price,
-        fair_l2_gas_price,
-        fair_pubdata_price,
+    BatchOverheadBreakdown {
+        l1_batch_overhead_wei,
+        overhead_per_l2_gas,
+        overhead_per_pubdata_byte,
     }
 }
 
@@ -458,4 +511,45 @@ mod tests {
             "Max pubdata increase lowers pubdata price"
         );
     }
+
+    #[test]
+    fn fee_model_breakdown_matches_batch_fee_input() {
+        let config = FeeModelConfigV2 {
+            minimal_l2_gas_price: 100_000_000_000,
+            compute_overhead_part: 0.5,
+            pubdata_overhead_part: 0.5,
+            batch_overhead_l1_gas: 700_000,
+            max_gas_per_batch: 500_000_000,
+            max_pubdata_per_batch: 100_000,
+        };
+        let params = FeeParamsV2 {
+            config,
+            l1_gas_price: 1_000_000_000,
+            l1_pubdata_price: 2_000_000_000,
+        };
+        let provider = MockBatchFeeParamsProvider(FeeParams::V2(params));
+
+        let breakdown = provider.get_fee_model_breakdown();
+        assert!(breakdown.l1_prices.is_none());
+        let input = compute_batch_fee_model_input_v2(params, 1.0, 1.0);
+        assert_eq!(breakdown.batch_fee_input, input);
+
+        let overhead = breakdown.batch_overhead.unwrap();
+        assert_eq!(
+            overhead.l1_batch_overhead_wei,
+            U256::from(params.l1_gas_price) * U256::from(config.batch_overhead_l1_gas)
+        );
+        assert_eq!(
+            input.fair_l2_gas_price,
+            config.minimal_l2_gas_price + overhead.overhead_per_l2_gas
+        );
+        assert_eq!(
+            input.fair_pubdata_price,
+            params.l1_pubdata_price + overhead.overhead_per_pubdata_byte
+        );
+
+        let provider = MockBatchFeeParamsProvider::default();
+        let breakdown = provider.get_fee_model_breakdown();
+        assert!(breakdown.batch_overhead.is_none());
+    }
 }
@@ -1,3 +1,4 @@
 pub mod blocks_state_reporter;
+pub mod partition_manager;
 pub mod periodic_job;
 pub mod prover;
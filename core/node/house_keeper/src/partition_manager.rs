@@ -0,0 +1,291 @@
+use async_trait::async_trait;
+use zksync_dal::{
+    partitions_dal::{PartitionedTable, TablePartition},
+    Connection, ConnectionPool, Core, CoreDal,
+};
+use zksync_types::L2BlockNumber;
+
+use crate::periodic_job::PeriodicJob;
+
+/// Manages partitions of the tables partitioned by L2 block number (`events` and `storage_logs`).
+///
+/// - Creates partitions covering fixed-size L2 block ranges in advance, so that the partition for the next range
+///   exists by the time the node starts processing L2 blocks in it. Rows that ended up in the default partition
+///   are moved to the created partitions.
+/// - Drops `events` partitions fully covered by hard pruning. This is much cheaper than deleting the corresponding rows
+///   and doesn't leave dead tuples to vacuum. `storage_logs` partitions are never dropped since hard pruning retains
+///   the latest log for each storage slot.
+#[derive(Debug)]
+pub struct PartitionManager {
+    connection_pool: ConnectionPool<Core>,
+    partition_size: u32,
+    polling_interval_ms: u64,
+}
+
+impl PartitionManager {
+    /// Default number of L2 blocks covered by a single partition.
+    pub const DEFAULT_PARTITION_SIZE: u32 = 100_000;
+
+    pub fn new(
+        connection_pool: ConnectionPool<Core>,
+        partition_size: u32,
+        polling_interval_ms: u64,
+    ) -> Self {
+        assert!(partition_size > 0, "Partition size must be positive");
+        Self {
+            connection_pool,
+            partition_size,
+            polling_interval_ms,
+        }
+    }
+
+    async fn create_partitions(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        table: PartitionedTable,
+        partitions: &[TablePartition],
+        next_l2_block: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        let mut next_start = partitions
+            .last()
+            .map_or(L2BlockNumber(0), |partition| partition.l2_blocks.end);
+        // Rows in the default partition are moved to the created partitions. L2 block ranges without any rows
+        // are skipped; this can happen after snapshot recovery.
+        let min_l2_block_in_default = conn
+            .partitions_dal()
+            .get_min_l2_block_in_default_partition(table)
+            .await?;
+        next_start = match min_l2_block_in_default {
+            Some(number) => next_start.max(number),
+            None => next_start.max(next_l2_block),
+        };
+
+        while next_start < next_l2_block + self.partition_size {
+            let l2_blocks = next_start..next_start + self.partition_size;
+            let partition = match conn
+                .partitions_dal()
+                .create_partition(table, l2_blocks.clone())
+                .await
+            {
+                Ok(partition) => partition,
+                Err(err) => {
+                    // Can happen if rows for the new partition were inserted into the default partition concurrently.
+                    // Creating the partition will be retried on the next iteration.
+                    tracing::warn!(
+                        "Failed creating partition of `{}` for L2 blocks {l2_blocks:?}: {err}",
+                        table.name()
+                    );
+                    break;
+                }
+            };
+            tracing::info!(
+                "Created partition `{}` for L2 blocks {l2_blocks:?}",
+                partition.name
+            );
+            next_start = l2_blocks.end;
+        }
+        Ok(())
+    }
+
+    async fn drop_pruned_partitions(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        table: PartitionedTable,
+        partitions: &[TablePartition],
+        last_hard_pruned_l2_block: L2BlockNumber,
+    ) -> anyhow::Result<()> {
+        let pruned_partitions = partitions
+            .iter()
+            .filter(|partition| partition.l2_blocks.end <= last_hard_pruned_l2_block + 1);
+        for partition in pruned_partitions {
+            conn.partitions_dal()
+                .drop_partition(table, partition)
+                .await?;
+            tracing::info!(
+                "Dropped partition `{}` for L2 blocks {:?} since it is fully pruned",
+                partition.name,
+                partition.l2_blocks
+            );
+        }
+        Ok(())
+    }
+
+    async fn manage_partitions(&self) -> anyhow::Result<()> {
+        let mut conn = self
+            .connection_pool
+            .connection_tagged("partition_manager")
+            .await?;
+        let sealed_l2_block = conn.blocks_dal().get_sealed_l2_block_number().await?;
+        let next_l2_block = sealed_l2_block.map_or(L2BlockNumber(0), |number| number + 1);
+        let pruning_info = conn.pruning_dal().get_pruning_info().await?;
+
+        for table in PartitionedTable::ALL {
+            let partitions = conn.partitions_dal().get_partitions(table).await?;
+            let last_pruned = pruning_info
+                .last_hard_pruned_l2_block
+                .filter(|_| table.can_drop_pruned_partitions());
+            if let Some(last_pruned) = last_pruned {
+                self.drop_pruned_partitions(&mut conn, table, &partitions, last_pruned)
+                    .await?;
+            }
+            self.create_partitions(&mut conn, table, &partitions, next_l2_block)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PeriodicJob for PartitionManager {
+    const SERVICE_NAME: &'static str = "PartitionManager";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        self.manage_partitions().await
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.polling_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_node_test_utils::create_l2_block;
+    use zksync_types::{
+        AccountTreeId, Address, L1BatchNumber, ProtocolVersion, StorageKey, StorageLog, H256,
+    };
+
+    use super::*;
+
+    async fn insert_l2_block(
+        conn: &mut Connection<'_, Core>,
+        number: u32,
+        l1_batch_number: L1BatchNumber,
+        storage_logs: Vec<StorageLog>,
+    ) {
+        conn.blocks_dal()
+            .insert_l2_block(&create_l2_block(number))
+            .await
+            .unwrap();
+        conn.blocks_dal()
+            .mark_l2_blocks_as_executed_in_l1_batch(l1_batch_number)
+            .await
+            .unwrap();
+        conn.storage_logs_dal()
+            .insert_storage_logs(L2BlockNumber(number), &[(H256::zero(), storage_logs)])
+            .await
+            .unwrap();
+    }
+
+    async fn partition_ranges(
+        conn: &mut Connection<'_, Core>,
+        table: PartitionedTable,
+    ) -> Vec<std::ops::Range<L2BlockNumber>> {
+        let partitions = conn.partitions_dal().get_partitions(table).await.unwrap();
+        partitions.into_iter().map(|p| p.l2_blocks).collect()
+    }
+
+    #[tokio::test]
+    async fn managing_partitions() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let manager = PartitionManager::new(pool.clone(), 10, 1_000);
+        manager.manage_partitions().await.unwrap();
+
+        let mut conn = pool.connection().await.unwrap();
+        for table in PartitionedTable::ALL {
+            assert_eq!(
+                partition_ranges(&mut conn, table).await,
+                [
+                    L2BlockNumber(0)..L2BlockNumber(0), // legacy partition created by the migration
+                    L2BlockNumber(0)..L2BlockNumber(10),
+                ]
+            );
+        }
+
+        // Repeated runs should be idempotent.
+        manager.manage_partitions().await.unwrap();
+        let partitions = conn
+            .partitions_dal()
+            .get_partitions(PartitionedTable::Events)
+            .await
+            .unwrap();
+        assert_eq!(partitions.len(), 2);
+
+        conn.protocol_versions_dal()
+            .save_protocol_version_with_tx(&ProtocolVersion::default())
+            .await
+            .unwrap();
+        let key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(1)), H256::zero());
+        let other_key = StorageKey::new(AccountTreeId::new(Address::repeat_byte(2)), H256::zero());
+        insert_l2_block(
+            &mut conn,
+            1,
+            L1BatchNumber(0),
+            vec![StorageLog::new_write_log(key, H256::repeat_byte(1))],
+        )
+        .await;
+        insert_l2_block(
+            &mut conn,
+            9,
+            L1BatchNumber(0),
+            vec![StorageLog::new_write_log(key, H256::repeat_byte(2))],
+        )
+        .await;
+        // Logs for this L2 block are not covered by partitions, so they are inserted into the default partition.
+        insert_l2_block(
+            &mut conn,
+            15,
+            L1BatchNumber(1),
+            vec![StorageLog::new_write_log(other_key, H256::repeat_byte(3))],
+        )
+        .await;
+
+        manager.manage_partitions().await.unwrap();
+        // Logs are moved from the default partition to the created partition.
+        assert_eq!(
+            partition_ranges(&mut conn, PartitionedTable::StorageLogs).await,
+            [
+                L2BlockNumber(0)..L2BlockNumber(0),
+                L2BlockNumber(0)..L2BlockNumber(10),
+                L2BlockNumber(15)..L2BlockNumber(25),
+                L2BlockNumber(25)..L2BlockNumber(35),
+            ]
+        );
+        // There are no events, so the sealed L2 blocks not covered by partitions are skipped.
+        assert_eq!(
+            partition_ranges(&mut conn, PartitionedTable::Events).await,
+            [
+                L2BlockNumber(0)..L2BlockNumber(0),
+                L2BlockNumber(0)..L2BlockNumber(10),
+                L2BlockNumber(16)..L2BlockNumber(26),
+            ]
+        );
+        for table in PartitionedTable::ALL {
+            let min_l2_block_in_default = conn
+                .partitions_dal()
+                .get_min_l2_block_in_default_partition(table)
+                .await
+                .unwrap();
+            assert_eq!(min_l2_block_in_default, None);
+        }
+
+        conn.pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(0), L2BlockNumber(9))
+            .await
+            .unwrap();
+        manager.manage_partitions().await.unwrap();
+
+        // Pruned `events` partitions are dropped.
+        assert_eq!(
+            partition_ranges(&mut conn, PartitionedTable::Events).await,
+            [L2BlockNumber(16)..L2BlockNumber(26)]
+        );
+        // `storage_logs` partitions are retained together with the latest values of storage slots.
+        let ranges = partition_ranges(&mut conn, PartitionedTable::StorageLogs).await;
+        assert_eq!(ranges.len(), 4, "{ranges:?}");
+        let value = conn.storage_web3_dal().get_value(&key).await.unwrap();
+        assert_eq!(value, H256::repeat_byte(2));
+        let value = conn.storage_web3_dal().get_value(&other_key).await.unwrap();
+        assert_eq!(value, H256::repeat_byte(3));
+    }
+}
@@ -0,0 +1,231 @@
+use std::{collections::HashSet, time::Duration};
+
+use async_trait::async_trait;
+use prover_dal::{Connection, Prover, ProverDal};
+use zksync_config::configs::house_keeper::ProverStageSlas;
+use zksync_dal::ConnectionPool;
+use zksync_types::{
+    basic_fri_types::AggregationRound,
+    prover_dal::{PendingJobsLatencyStats, StuckJobs},
+};
+
+use crate::{
+    periodic_job::PeriodicJob,
+    prover::metrics::{ProverStage, PROVER_JOB_MONITOR_METRICS},
+};
+
+const WITNESS_GENERATION_ROUNDS: [AggregationRound; 5] = [
+    AggregationRound::BasicCircuits,
+    AggregationRound::LeafAggregation,
+    AggregationRound::NodeAggregation,
+    AggregationRound::RecursionTip,
+    AggregationRound::Scheduler,
+];
+
+/// Maximum number of attempts for jobs of each stage of the prover pipeline.
+/// Stuck jobs that have exhausted their attempts are not requeued.
+#[derive(Debug, Clone, Copy)]
+pub struct ProverStageMaxAttempts {
+    pub witness_generation: u32,
+    pub proving: u32,
+    pub proof_compression: u32,
+}
+
+/// `ProverJobMonitor` is a task that periodically reports latencies of the prover pipeline stages
+/// (witness generation rounds, proving per circuit and proof compression) against the configured SLAs.
+/// If a stuck job threshold is configured, it also requeues jobs that are in progress for longer than
+/// the threshold.
+#[derive(Debug)]
+pub struct ProverJobMonitor {
+    pool: ConnectionPool<Prover>,
+    slas: ProverStageSlas,
+    stuck_job_threshold: Option<Duration>,
+    max_attempts: ProverStageMaxAttempts,
+    monitoring_interval_ms: u64,
+    /// Circuits reported during the previous run; used to reset metrics for circuits without pending jobs.
+    reported_circuits: HashSet<(u8, u8)>,
+}
+
+impl ProverJobMonitor {
+    pub fn new(
+        pool: ConnectionPool<Prover>,
+        slas: ProverStageSlas,
+        stuck_job_threshold: Option<Duration>,
+        max_attempts: ProverStageMaxAttempts,
+        monitoring_interval_ms: u64,
+    ) -> Self {
+        Self {
+            pool,
+            slas,
+            stuck_job_threshold,
+            max_attempts,
+            monitoring_interval_ms,
+            reported_circuits: HashSet::new(),
+        }
+    }
+
+    async fn report_latencies(&mut self, conn: &mut Connection<'_, Prover>) {
+        for round in WITNESS_GENERATION_ROUNDS {
+            let stats = conn
+                .fri_witness_generator_dal()
+                .get_witness_jobs_latency_stats(round, self.slas.witness_generation)
+                .await;
+            report_stage(ProverStage::witness_generation(round), stats);
+        }
+
+        let circuit_stats = conn
+            .fri_prover_jobs_dal()
+            .get_prover_jobs_latency_stats(self.slas.proving)
+            .await;
+        let mut proving_stats = PendingJobsLatencyStats::default();
+        for (&(circuit_id, aggregation_round), stats) in &circuit_stats {
+            report_circuit(circuit_id, aggregation_round, *stats);
+            proving_stats = merge_stats(proving_stats, *stats);
+        }
+        for &(circuit_id, aggregation_round) in &self.reported_circuits {
+            if !circuit_stats.contains_key(&(circuit_id, aggregation_round)) {
+                report_circuit(
+                    circuit_id,
+                    aggregation_round,
+                    PendingJobsLatencyStats::default(),
+                );
+            }
+        }
+        self.reported_circuits = circuit_stats.into_keys().collect();
+        report_stage(ProverStage::Proving, proving_stats);
+
+        let stats = conn
+            .fri_proof_compressor_dal()
+            .get_jobs_latency_stats(self.slas.proof_compression)
+            .await;
+        report_stage(ProverStage::ProofCompression, stats);
+    }
+
+    async fn requeue_stuck_jobs(
+        &self,
+        conn: &mut Connection<'_, Prover>,
+        stuck_job_threshold: Duration,
+    ) {
+        let max_attempts = self.max_attempts.witness_generation;
+        let mut dal = conn.fri_witness_generator_dal();
+        let stuck_jobs = dal
+            .requeue_stuck_jobs(stuck_job_threshold, max_attempts)
+            .await;
+        report_requeued(ProverStage::BasicWitnessGeneration, &stuck_jobs);
+        let stuck_jobs = dal
+            .requeue_stuck_leaf_aggregations_jobs(stuck_job_threshold, max_attempts)
+            .await;
+        report_requeued(ProverStage::LeafWitnessGeneration, &stuck_jobs);
+        let stuck_jobs = dal
+            .requeue_stuck_node_aggregations_jobs(stuck_job_threshold, max_attempts)
+            .await;
+        report_requeued(ProverStage::NodeWitnessGeneration, &stuck_jobs);
+        let stuck_jobs = dal
+            .requeue_stuck_recursion_tip_jobs(stuck_job_threshold, max_attempts)
+            .await;
+        report_requeued(ProverStage::RecursionTipWitnessGeneration, &stuck_jobs);
+        let stuck_jobs = dal
+            .requeue_stuck_scheduler_jobs(stuck_job_threshold, max_attempts)
+            .await;
+        report_requeued(ProverStage::SchedulerWitnessGeneration, &stuck_jobs);
+
+        let stuck_jobs = conn
+            .fri_prover_jobs_dal()
+            .requeue_stuck_jobs(stuck_job_threshold, self.max_attempts.proving)
+            .await;
+        report_requeued(ProverStage::Proving, &stuck_jobs);
+
+        let stuck_jobs = conn
+            .fri_proof_compressor_dal()
+            .requeue_stuck_jobs(stuck_job_threshold, self.max_attempts.proof_compression)
+            .await;
+        report_requeued(ProverStage::ProofCompression, &stuck_jobs);
+    }
+}
+
+fn merge_stats(
+    lhs: PendingJobsLatencyStats,
+    rhs: PendingJobsLatencyStats,
+) -> PendingJobsLatencyStats {
+    PendingJobsLatencyStats {
+        pending: lhs.pending + rhs.pending,
+        oldest_age_secs: lhs.oldest_age_secs.max(rhs.oldest_age_secs),
+        over_sla: lhs.over_sla + rhs.over_sla,
+    }
+}
+
+fn report_stage(stage: ProverStage, stats: PendingJobsLatencyStats) {
+    if stats.over_sla > 0 {
+        tracing::warn!(
+            "{} {stage:?} jobs exceed the SLA; the oldest pending job was created {}s ago",
+            stats.over_sla,
+            stats.oldest_age_secs
+        );
+    }
+    let metrics = &PROVER_JOB_MONITOR_METRICS;
+    metrics.pending_jobs[&stage].set(stats.pending as u64);
+    metrics.oldest_pending_job_age[&stage].set(Duration::from_secs(stats.oldest_age_secs));
+    metrics.sla_violations[&stage].set(stats.over_sla as u64);
+}
+
+fn report_circuit(circuit_id: u8, aggregation_round: u8, stats: PendingJobsLatencyStats) {
+    let labels = (circuit_id.to_string(), aggregation_round.to_string());
+    let metrics = &PROVER_JOB_MONITOR_METRICS;
+    metrics.circuit_pending_jobs[&labels].set(stats.pending as u64);
+    metrics.circuit_oldest_pending_job_age[&labels].set(Duration::from_secs(stats.oldest_age_secs));
+    metrics.circuit_sla_violations[&labels].set(stats.over_sla as u64);
+}
+
+fn report_requeued(stage: ProverStage, stuck_jobs: &[StuckJobs]) {
+    for stuck_job in stuck_jobs {
+        tracing::info!("Re-queuing stuck {stage:?} job {stuck_job:?}");
+    }
+    PROVER_JOB_MONITOR_METRICS.requeued_stuck_jobs[&stage].inc_by(stuck_jobs.len() as u64);
+}
+
+#[async_trait]
+impl PeriodicJob for ProverJobMonitor {
+    const SERVICE_NAME: &'static str = "ProverJobMonitor";
+
+    async fn run_routine_task(&mut self) -> anyhow::Result<()> {
+        let mut conn = self.pool.connection().await.unwrap();
+        self.report_latencies(&mut conn).await;
+        if let Some(stuck_job_threshold) = self.stuck_job_threshold {
+            self.requeue_stuck_jobs(&mut conn, stuck_job_threshold)
+                .await;
+        }
+        Ok(())
+    }
+
+    fn polling_interval_ms(&self) -> u64 {
+        self.monitoring_interval_ms
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merging_latency_stats() {
+        let first = PendingJobsLatencyStats {
+            pending: 3,
+            oldest_age_secs: 100,
+            over_sla: 1,
+        };
+        let second = PendingJobsLatencyStats {
+            pending: 2,
+            oldest_age_secs: 50,
+            over_sla: 0,
+        };
+        let merged = merge_stats(merge_stats(Default::default(), first), second);
+        assert_eq!(
+            merged,
+            PendingJobsLatencyStats {
+                pending: 5,
+                oldest_age_secs: 100,
+                over_sla: 1,
+            }
+        );
+    }
+}
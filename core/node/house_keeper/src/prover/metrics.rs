@@ -1,5 +1,9 @@
-use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, LabeledFamily, Metrics};
-use zksync_types::ProtocolVersionId;
+use std::time::Duration;
+
+use vise::{
+    Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, LabeledFamily, Metrics, Unit,
+};
+use zksync_types::{basic_fri_types::AggregationRound, ProtocolVersionId};
 
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "house_keeper")]
@@ -122,3 +126,56 @@ pub(crate) struct ServerMetrics {
 
 #[vise::register]
 pub(crate) static SERVER_METRICS: vise::Global<ServerMetrics> = vise::Global::new();
+
+/// Stage of the prover pipeline tracked by the prover job monitor.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "stage", rename_all = "snake_case")]
+pub(crate) enum ProverStage {
+    BasicWitnessGeneration,
+    LeafWitnessGeneration,
+    NodeWitnessGeneration,
+    RecursionTipWitnessGeneration,
+    SchedulerWitnessGeneration,
+    Proving,
+    ProofCompression,
+}
+
+impl ProverStage {
+    pub fn witness_generation(round: AggregationRound) -> Self {
+        match round {
+            AggregationRound::BasicCircuits => Self::BasicWitnessGeneration,
+            AggregationRound::LeafAggregation => Self::LeafWitnessGeneration,
+            AggregationRound::NodeAggregation => Self::NodeWitnessGeneration,
+            AggregationRound::RecursionTip => Self::RecursionTipWitnessGeneration,
+            AggregationRound::Scheduler => Self::SchedulerWitnessGeneration,
+        }
+    }
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "prover_job_monitor")]
+pub(crate) struct ProverJobMonitorMetrics {
+    /// Number of queued and in-progress jobs.
+    pub pending_jobs: Family<ProverStage, Gauge<u64>>,
+    /// Age of the oldest queued or in-progress job, measured from job creation.
+    #[metrics(unit = Unit::Seconds)]
+    pub oldest_pending_job_age: Family<ProverStage, Gauge<Duration>>,
+    /// Number of queued and in-progress jobs older than the SLA of the stage.
+    pub sla_violations: Family<ProverStage, Gauge<u64>>,
+    /// Number of stuck jobs requeued by the monitor.
+    pub requeued_stuck_jobs: Family<ProverStage, Counter>,
+
+    /// Number of queued and in-progress prover jobs per circuit.
+    #[metrics(labels = ["circuit_id", "aggregation_round"])]
+    pub circuit_pending_jobs: LabeledFamily<(String, String), Gauge<u64>, 2>,
+    /// Age of the oldest queued or in-progress prover job per circuit.
+    #[metrics(labels = ["circuit_id", "aggregation_round"], unit = Unit::Seconds)]
+    pub circuit_oldest_pending_job_age: LabeledFamily<(String, String), Gauge<Duration>, 2>,
+    /// Number of queued and in-progress prover jobs per circuit older than the proving SLA.
+    #[metrics(labels = ["circuit_id", "aggregation_round"])]
+    pub circuit_sla_violations: LabeledFamily<(String, String), Gauge<u64>, 2>,
+}
+
+#[vise::register]
+pub(crate) static PROVER_JOB_MONITOR_METRICS: vise::Global<ProverJobMonitorMetrics> =
+    vise::Global::new();
@@ -1,10 +1,12 @@
 mod archiver;
+mod job_monitor;
 mod metrics;
 mod queue_reporter;
 mod retry_manager;
 mod waiting_to_queued_fri_witness_job_mover;
 
 pub use archiver::{FriGpuProverArchiver, FriProverJobsArchiver};
+pub use job_monitor::{ProverJobMonitor, ProverStageMaxAttempts};
 pub use queue_reporter::{
     FriProofCompressorQueueReporter, FriProverQueueReporter, FriWitnessGeneratorQueueReporter,
 };
@@ -0,0 +1,15 @@
+//! Generates gRPC server and client code for the Merkle tree API.
+//!
+//! Proto files are parsed with `protox`, so that the build doesn't depend on a system-wide `protoc` installation.
+
+const PROTO_PATH: &str = "src/proto/tree_api.proto";
+
+fn main() {
+    println!("cargo:rerun-if-changed={PROTO_PATH}");
+    let descriptors = protox::compile([PROTO_PATH], ["src/proto"])
+        .expect("failed parsing proto files for Merkle tree API");
+    prost_build::Config::new()
+        .service_generator(tonic_build::configure().service_generator())
+        .compile_fds(descriptors)
+        .expect("failed generating gRPC code for Merkle tree API");
+}
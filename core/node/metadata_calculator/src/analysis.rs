@@ -0,0 +1,172 @@
+//! Periodic analysis of the Merkle tree structure.
+
+use std::time::Duration;
+
+use tokio::sync::watch;
+use zksync_merkle_tree::TreeStructureStats;
+use zksync_types::L1BatchNumber;
+
+use super::{
+    helpers::{AsyncTreeReader, LazyAsyncTreeReader},
+    metrics::{TreeLevelLabels, TreeNodeKind, TREE_STRUCTURE_METRICS},
+};
+
+/// Low-priority task periodically walking the latest Merkle tree version and exporting its structural statistics
+/// (node counts by level, average leaf level and leaf distribution skew) as metrics. The statistics can be used
+/// for capacity planning and for validating changes to hashing / keying.
+///
+/// Each analysis loads all nodes of the tree version, so the interval between analyses should be large.
+#[derive(Debug)]
+#[must_use = "Task should `run()` in a managed Tokio task"]
+pub struct MerkleTreeAnalysisTask {
+    tree_reader: LazyAsyncTreeReader,
+    interval: Duration,
+    /// Number of levels for which node counts were reported. Used to reset counts for levels no longer present in the tree.
+    reported_levels: usize,
+}
+
+impl MerkleTreeAnalysisTask {
+    pub(super) fn new(tree_reader: LazyAsyncTreeReader, interval: Duration) -> Self {
+        Self {
+            tree_reader,
+            interval,
+            reported_levels: 0,
+        }
+    }
+
+    pub async fn run(mut self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let reader;
+        tokio::select! {
+            res = self.tree_reader.clone().wait() => {
+                match res {
+                    Ok(tree_reader) => reader = tree_reader,
+                    Err(_) => {
+                        tracing::info!("Merkle tree dropped; shutting down tree analysis");
+                        return Ok(());
+                    }
+                }
+            }
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received before Merkle tree is initialized; shutting down tree analysis");
+                return Ok(());
+            }
+        }
+
+        while !*stop_receiver.borrow_and_update() {
+            if let Some((l1_batch_number, stats)) = Self::analyze(&reader).await? {
+                self.report(l1_batch_number, &stats);
+            }
+
+            if tokio::time::timeout(self.interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, Merkle tree analysis is shutting down");
+        Ok(())
+    }
+
+    /// Analyzes the latest tree version. Returns `None` if the tree is empty or the version was pruned
+    /// or reverted concurrently.
+    async fn analyze(
+        reader: &AsyncTreeReader,
+    ) -> anyhow::Result<Option<(L1BatchNumber, TreeStructureStats)>> {
+        let tree_info = reader.clone().info().await;
+        let Some(l1_batch_number) = tree_info.next_l1_batch_number.0.checked_sub(1) else {
+            tracing::debug!("Merkle tree is empty; skipping analysis");
+            return Ok(None);
+        };
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let pinned = match reader.clone().pin_l1_batch(l1_batch_number).await {
+            Ok(pinned) => pinned,
+            Err(err) => {
+                tracing::info!("Cannot pin Merkle tree for L1 batch #{l1_batch_number}: {err}; skipping analysis");
+                return Ok(None);
+            }
+        };
+
+        let latency = TREE_STRUCTURE_METRICS.latency.start();
+        let stats = match pinned.structure_stats().await {
+            Ok(stats) => stats,
+            Err(err) => {
+                // The only expected error is the tree being reverted concurrently with the analysis.
+                tracing::info!("Failed analyzing Merkle tree for L1 batch #{l1_batch_number}: {err:#}; skipping");
+                return Ok(None);
+            }
+        };
+        let latency = latency.observe();
+        tracing::info!(
+            "Analyzed Merkle tree structure for L1 batch #{l1_batch_number} in {latency:?}: {} internal nodes, \
+             {} leaves, avg leaf level {:.2}, leaf distribution skew {:.3}",
+            stats.internal_node_count(),
+            stats.leaf_count(),
+            stats.avg_leaf_level(),
+            stats.leaf_distribution_skew()
+        );
+        Ok(Some((l1_batch_number, stats)))
+    }
+
+    fn report(&mut self, l1_batch_number: L1BatchNumber, stats: &TreeStructureStats) {
+        let metrics = &TREE_STRUCTURE_METRICS;
+        metrics.l1_batch.set(l1_batch_number.0.into());
+        let counts = [
+            (TreeNodeKind::Internal, &stats.internal_nodes_by_level),
+            (TreeNodeKind::Leaf, &stats.leaves_by_level),
+        ];
+        let levels = counts
+            .iter()
+            .map(|(_, counts)| counts.len())
+            .max()
+            .unwrap_or(0);
+        for (kind, counts) in counts {
+            for level in 0..levels.max(self.reported_levels) {
+                let count = counts.get(level).copied().unwrap_or(0);
+                metrics.node_count[&TreeLevelLabels { kind, level }].set(count);
+            }
+        }
+        self.reported_levels = levels;
+        metrics.avg_leaf_level.set(stats.avg_leaf_level());
+        metrics
+            .leaf_distribution_skew
+            .set(stats.leaf_distribution_skew());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use zksync_dal::{ConnectionPool, Core};
+
+    use super::*;
+    use crate::tests::{reset_db_state, run_calculator, setup_calculator};
+
+    #[tokio::test]
+    async fn analyzing_tree_structure() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let (calculator, _) = setup_calculator(temp_dir.path(), pool.clone()).await;
+        reset_db_state(&pool, 5).await;
+        run_calculator(calculator).await;
+
+        let (calculator, _) = setup_calculator(temp_dir.path(), pool).await;
+        let tree_reader = calculator.tree_reader();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let calculator_handle = tokio::spawn(calculator.run(stop_receiver));
+        let reader = tree_reader.wait().await.unwrap();
+
+        let (l1_batch_number, stats) = MerkleTreeAnalysisTask::analyze(&reader)
+            .await
+            .unwrap()
+            .expect("tree is not analyzed");
+        assert_eq!(l1_batch_number, L1BatchNumber(5));
+        let tree_info = reader.info().await;
+        assert_eq!(stats.leaf_count(), tree_info.leaf_count);
+        assert_eq!(stats.internal_nodes_by_level[0], 1);
+        assert!(stats.avg_leaf_level() > 0.0);
+
+        stop_sender.send_replace(true);
+        calculator_handle.await.unwrap().unwrap();
+    }
+}
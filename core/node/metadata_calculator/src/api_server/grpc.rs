@@ -0,0 +1,267 @@
+//! gRPC transport for the Merkle tree API.
+
+use std::{io, mem, net::SocketAddr, pin::Pin};
+
+use anyhow::Context as _;
+use futures::{channel::mpsc, executor, stream, SinkExt, Stream};
+use tokio::{net::TcpListener, sync::watch};
+use tonic::{Request, Response, Status};
+use zksync_merkle_tree::{NoVersionError, TreeTransferError};
+use zksync_types::{L1BatchNumber, U256};
+
+use super::{MerkleTreeServer, TreeEntryWithProof};
+use crate::AsyncTreeReader;
+
+#[allow(clippy::all)]
+pub(crate) mod proto {
+    tonic::include_proto!("zksync.tree_api");
+}
+
+/// Default number of proofs in a single chunk streamed by `GetProofs`.
+const DEFAULT_PROOFS_CHUNK_SIZE: usize = 100;
+/// Maximum number of proofs in a single chunk streamed by `GetProofs`.
+const MAX_PROOFS_CHUNK_SIZE: usize = 1_000;
+/// Number of tree nodes in a single chunk of the exported tree data.
+const EXPORT_CHUNK_SIZE: usize = 10_000;
+/// Approximate byte size of a single chunk streamed by `DumpNodes`.
+const DUMP_CHUNK_BYTE_SIZE: usize = 1 << 20;
+
+type ResponseStream<T> = Pin<Box<dyn Stream<Item = Result<T, Status>> + Send>>;
+
+fn no_version_status(err: &NoVersionError) -> Status {
+    Status::not_found(err.to_string())
+}
+
+impl From<TreeEntryWithProof> for proto::TreeEntryWithProof {
+    fn from(entry: TreeEntryWithProof) -> Self {
+        Self {
+            value: entry.value.as_bytes().to_vec(),
+            index: entry.index,
+            merkle_path: entry
+                .merkle_path
+                .iter()
+                .map(|hash| hash.as_bytes().to_vec())
+                .collect(),
+        }
+    }
+}
+
+/// [`io::Write`] implementation streaming written data to a gRPC client in chunks. Must be used
+/// from a blocking context.
+#[derive(Debug)]
+struct StreamingWriter {
+    sender: mpsc::Sender<Result<proto::NodeDumpChunk, Status>>,
+    buffer: Vec<u8>,
+}
+
+impl io::Write for StreamingWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        if self.buffer.len() >= DUMP_CHUNK_BYTE_SIZE {
+            self.flush()?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.buffer.is_empty() {
+            return Ok(());
+        }
+        let chunk = proto::NodeDumpChunk {
+            data: mem::take(&mut self.buffer),
+        };
+        executor::block_on(self.sender.send(Ok(chunk)))
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "gRPC client disconnected"))
+    }
+}
+
+#[derive(Debug)]
+struct GrpcTreeApi {
+    reader: AsyncTreeReader,
+}
+
+impl GrpcTreeApi {
+    async fn check_l1_batch_number(&self, l1_batch_number: L1BatchNumber) -> Result<(), Status> {
+        let info = self.reader.clone().info().await;
+        let min_l1_batch_number = info.min_l1_batch_number.unwrap_or(L1BatchNumber(0));
+        if l1_batch_number < min_l1_batch_number || l1_batch_number >= info.next_l1_batch_number {
+            let err = NoVersionError {
+                missing_version: l1_batch_number.0.into(),
+                version_count: info.next_l1_batch_number.0.into(),
+            };
+            return Err(no_version_status(&err));
+        }
+        Ok(())
+    }
+}
+
+#[tonic::async_trait]
+impl proto::tree_api_server::TreeApi for GrpcTreeApi {
+    type GetProofsStream = ResponseStream<proto::ProofsChunk>;
+    type DumpNodesStream = ResponseStream<proto::NodeDumpChunk>;
+
+    async fn get_info(
+        &self,
+        _request: Request<proto::GetInfoRequest>,
+    ) -> Result<Response<proto::TreeInfo>, Status> {
+        let info = self.reader.clone().info().await;
+        Ok(Response::new(proto::TreeInfo {
+            root_hash: info.root_hash.as_bytes().to_vec(),
+            next_l1_batch_number: info.next_l1_batch_number.0,
+            min_l1_batch_number: info.min_l1_batch_number.map(|number| number.0),
+            leaf_count: info.leaf_count,
+        }))
+    }
+
+    async fn get_proofs(
+        &self,
+        request: Request<proto::GetProofsRequest>,
+    ) -> Result<Response<Self::GetProofsStream>, Status> {
+        let request = request.into_inner();
+        let l1_batch_number = L1BatchNumber(request.l1_batch_number);
+        let chunk_size = request
+            .chunk_size
+            .map_or(DEFAULT_PROOFS_CHUNK_SIZE, |size| size as usize);
+        if chunk_size == 0 || chunk_size > MAX_PROOFS_CHUNK_SIZE {
+            return Err(Status::invalid_argument(format!(
+                "chunk size must be in 1..={MAX_PROOFS_CHUNK_SIZE}"
+            )));
+        }
+        let hashed_keys = request
+            .hashed_keys
+            .iter()
+            .map(|key| {
+                if key.len() == 32 {
+                    Ok(U256::from_big_endian(key))
+                } else {
+                    Err(Status::invalid_argument("hashed keys must have 32 bytes"))
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.check_l1_batch_number(l1_batch_number).await?;
+
+        let (mut sender, receiver) = mpsc::channel(1);
+        let reader = self.reader.clone();
+        tokio::spawn(async move {
+            for chunk in hashed_keys.chunks(chunk_size) {
+                let response = reader
+                    .clone()
+                    .entries_with_proofs(l1_batch_number, chunk.to_vec())
+                    .await;
+                let response = response
+                    .map(|proofs| proto::ProofsChunk {
+                        entries: proofs
+                            .into_iter()
+                            .map(|proof| TreeEntryWithProof::new(proof).into())
+                            .collect(),
+                    })
+                    .map_err(|err| no_version_status(&err));
+                // The tree may be truncated or pruned while streaming proofs, in which case we stop streaming.
+                let is_err = response.is_err();
+                if sender.send(response).await.is_err() || is_err {
+                    break;
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(receiver)))
+    }
+
+    async fn dump_nodes(
+        &self,
+        request: Request<proto::DumpNodesRequest>,
+    ) -> Result<Response<Self::DumpNodesStream>, Status> {
+        let l1_batch_number = L1BatchNumber(request.into_inner().l1_batch_number);
+        self.check_l1_batch_number(l1_batch_number).await?;
+
+        let (mut sender, receiver) = mpsc::channel(1);
+        let writer = StreamingWriter {
+            sender: sender.clone(),
+            buffer: Vec::with_capacity(DUMP_CHUNK_BYTE_SIZE),
+        };
+        let reader = self.reader.clone();
+        tokio::spawn(async move {
+            let result = reader
+                .export_l1_batch(l1_batch_number, writer, EXPORT_CHUNK_SIZE)
+                .await;
+            match result {
+                Ok(info) => {
+                    tracing::info!(
+                        "Dumped {} nodes of Merkle tree for L1 batch #{l1_batch_number} via gRPC",
+                        info.node_count
+                    );
+                }
+                Err(TreeTransferError::Io(err)) if err.kind() == io::ErrorKind::BrokenPipe => {
+                    tracing::info!("gRPC client disconnected while dumping Merkle tree for L1 batch #{l1_batch_number}");
+                }
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed dumping Merkle tree for L1 batch #{l1_batch_number}: {err}"
+                    );
+                    let status = Status::internal(format!("failed dumping Merkle tree: {err}"));
+                    sender.send(Err(status)).await.ok();
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(receiver)))
+    }
+}
+
+impl AsyncTreeReader {
+    pub(super) async fn create_grpc_server(
+        self,
+        bind_address: &SocketAddr,
+        mut stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<MerkleTreeServer> {
+        tracing::debug!("Starting Merkle tree gRPC API server on {bind_address}");
+
+        let listener = TcpListener::bind(bind_address).await.with_context(|| {
+            format!("Failed binding Merkle tree gRPC API server to {bind_address}")
+        })?;
+        let local_addr = listener
+            .local_addr()
+            .context("Failed getting local address of Merkle tree gRPC API server")?;
+        let incoming = stream::unfold(listener, |listener| async move {
+            let connection = listener.accept().await.map(|(stream, _)| stream);
+            Some((connection, listener))
+        });
+        let service = proto::tree_api_server::TreeApiServer::new(GrpcTreeApi { reader: self });
+
+        let server_future = async move {
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming_shutdown(incoming, async move {
+                    if stop_receiver.changed().await.is_err() {
+                        tracing::warn!(
+                            "Stop signal sender for Merkle tree gRPC API server was dropped without sending a signal"
+                        );
+                    }
+                    tracing::info!(
+                        "Stop signal received, Merkle tree gRPC API server is shutting down"
+                    );
+                })
+                .await
+                .context("Merkle tree gRPC API server failed")?;
+
+            tracing::info!("Merkle tree gRPC API server shut down");
+            Ok(())
+        };
+
+        Ok(MerkleTreeServer {
+            local_addr,
+            server_future: Box::pin(server_future),
+        })
+    }
+
+    /// Runs the gRPC API server. The server runs independently of the HTTP API server
+    /// (see [`Self::run_api_server()`]) and supports streaming proofs and tree data.
+    pub async fn run_grpc_server(
+        self,
+        bind_address: SocketAddr,
+        stop_receiver: watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        self.create_grpc_server(&bind_address, stop_receiver)
+            .await?
+            .run()
+            .await
+    }
+}
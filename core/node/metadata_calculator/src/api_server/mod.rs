@@ -10,15 +10,17 @@ use axum::{
     response::{IntoResponse, Response},
     routing, Json, Router,
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tokio::sync::watch;
+use zksync_error_codes::{ErrorCode, HasErrorCode};
 use zksync_health_check::{CheckHealth, Health, HealthStatus};
-use zksync_merkle_tree::NoVersionError;
-use zksync_types::{L1BatchNumber, H256, U256};
+use zksync_merkle_tree::{NoVersionError, TreeMultiProof};
+use zksync_types::{web3::Bytes, L1BatchNumber, H256, U256};
 
 use self::metrics::{MerkleTreeApiMethod, API_METRICS};
 use crate::{AsyncTreeReader, LazyAsyncTreeReader, MerkleTreeInfo};
 
+mod grpc;
 mod metrics;
 #[cfg(test)]
 mod tests;
@@ -34,6 +36,12 @@ struct TreeProofsResponse {
     entries: Vec<TreeEntryWithProof>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct TreeMultiProofResponse {
+    /// Multiproof in the compact binary form; see [`TreeMultiProof::serialize()`].
+    proof: Bytes,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TreeEntryWithProof {
     #[serde(default, skip_serializing_if = "H256::is_zero")]
@@ -98,6 +106,7 @@ struct Problem<T> {
     r#type: &'static str,
     title: &'static str,
     detail: String,
+    error_code: ErrorCode,
     #[serde(flatten)]
     data: T,
 }
@@ -109,10 +118,13 @@ impl IntoResponse for TreeApiServerError {
         let headers = [(header::CONTENT_TYPE, PROBLEM_CONTENT_TYPE)];
         match self {
             Self::NoTreeVersion(err) => {
+                let error_code = ErrorCode::TreeNoVersion;
+                error_code.observe();
                 let body = Problem {
                     r#type: "/errors#l1-batch-not-found",
                     title: "L1 batch not found",
                     detail: err.to_string(),
+                    error_code,
                     data: NoVersionErrorData::from(err),
                 };
                 (StatusCode::NOT_FOUND, headers, Json(body)).into_response()
@@ -134,6 +146,16 @@ pub enum TreeApiError {
     Internal(#[from] anyhow::Error),
 }
 
+impl HasErrorCode for TreeApiError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::NoVersion(_) => ErrorCode::TreeNoVersion,
+            Self::NotReady(_) => ErrorCode::TreeApiUnavailable,
+            Self::Internal(_) => ErrorCode::TreeInternal,
+        }
+    }
+}
+
 impl TreeApiError {
     fn for_request(err: reqwest::Error, request_description: impl fmt::Display) -> Self {
         let is_not_ready = err.is_timeout() || err.is_connect();
@@ -159,6 +181,15 @@ pub trait TreeApiClient: 'static + Send + Sync + fmt::Debug {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> Result<Vec<TreeEntryWithProof>, TreeApiError>;
+
+    /// Obtains a multiproof for the specified `hashed_keys` at the specified tree version (= L1 batch number).
+    /// Unlike [`Self::get_proofs()`], the proof is deduplicated across keys, so this method should be preferred
+    /// when requesting proofs for many keys.
+    async fn get_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, TreeApiError>;
 }
 
 /// In-memory client implementation.
@@ -186,6 +217,21 @@ impl TreeApiClient for LazyAsyncTreeReader {
             Err(TreeApiError::NotReady(None))
         }
     }
+
+    async fn get_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, TreeApiError> {
+        if let Some(reader) = self.read() {
+            reader
+                .entries_with_multiproof(l1_batch_number, hashed_keys)
+                .await
+                .map_err(TreeApiError::NoVersion)
+        } else {
+            Err(TreeApiError::NotReady(None))
+        }
+    }
 }
 
 /// [`TreeApiClient`] implementation requesting data from a Merkle tree API server.
@@ -194,6 +240,7 @@ pub struct TreeApiHttpClient {
     inner: reqwest::Client,
     info_url: String,
     proofs_url: String,
+    multiproof_url: String,
 }
 
 impl TreeApiHttpClient {
@@ -208,8 +255,52 @@ impl TreeApiHttpClient {
             inner: client,
             info_url: url_base.to_owned(),
             proofs_url: format!("{url_base}/proofs"),
+            multiproof_url: format!("{url_base}/multiproof"),
         }
     }
+
+    async fn post_proofs_request<R: DeserializeOwned>(
+        &self,
+        url: &str,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<R, TreeApiError> {
+        let response = self
+            .inner
+            .post(url)
+            .json(&TreeProofsRequest {
+                l1_batch_number,
+                hashed_keys,
+            })
+            .send()
+            .await
+            .map_err(|err| {
+                TreeApiError::for_request(
+                    err,
+                    format_args!("proofs for L1 batch #{l1_batch_number}"),
+                )
+            })?;
+
+        let is_problem = response
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map_or(false, |header| *header == PROBLEM_CONTENT_TYPE);
+        if response.status() == StatusCode::NOT_FOUND && is_problem {
+            // Try to parse `NoVersionError` from the response body.
+            let problem_data: NoVersionErrorData = response
+                .json()
+                .await
+                .context("failed parsing error response")?;
+            return Err(TreeApiError::NoVersion(problem_data.into()));
+        }
+
+        let response = response.error_for_status().with_context(|| {
+            format!("requesting proofs for L1 batch #{l1_batch_number} returned non-OK response")
+        })?;
+        Ok(response.json().await.with_context(|| {
+            format!("failed deserializing proofs for L1 batch #{l1_batch_number}")
+        })?)
+    }
 }
 
 #[async_trait]
@@ -229,6 +320,10 @@ impl CheckHealth for TreeApiHttpClient {
             })),
         }
     }
+
+    fn gates_readiness(&self) -> bool {
+        false
+    }
 }
 
 #[async_trait]
@@ -254,42 +349,24 @@ impl TreeApiClient for TreeApiHttpClient {
         l1_batch_number: L1BatchNumber,
         hashed_keys: Vec<U256>,
     ) -> Result<Vec<TreeEntryWithProof>, TreeApiError> {
-        let response = self
-            .inner
-            .post(&self.proofs_url)
-            .json(&TreeProofsRequest {
-                l1_batch_number,
-                hashed_keys,
-            })
-            .send()
-            .await
-            .map_err(|err| {
-                TreeApiError::for_request(
-                    err,
-                    format_args!("proofs for L1 batch #{l1_batch_number}"),
-                )
-            })?;
-
-        let is_problem = response
-            .headers()
-            .get(header::CONTENT_TYPE)
-            .map_or(false, |header| *header == PROBLEM_CONTENT_TYPE);
-        if response.status() == StatusCode::NOT_FOUND && is_problem {
-            // Try to parse `NoVersionError` from the response body.
-            let problem_data: NoVersionErrorData = response
-                .json()
-                .await
-                .context("failed parsing error response")?;
-            return Err(TreeApiError::NoVersion(problem_data.into()));
-        }
+        let response: TreeProofsResponse = self
+            .post_proofs_request(&self.proofs_url, l1_batch_number, hashed_keys)
+            .await?;
+        Ok(response.entries)
+    }
 
-        let response = response.error_for_status().with_context(|| {
-            format!("requesting proofs for L1 batch #{l1_batch_number} returned non-OK response")
-        })?;
-        let response: TreeProofsResponse = response.json().await.with_context(|| {
-            format!("failed deserializing proofs for L1 batch #{l1_batch_number}")
+    async fn get_multiproof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        hashed_keys: Vec<U256>,
+    ) -> Result<TreeMultiProof, TreeApiError> {
+        let response: TreeMultiProofResponse = self
+            .post_proofs_request(&self.multiproof_url, l1_batch_number, hashed_keys)
+            .await?;
+        let proof = TreeMultiProof::deserialize(&response.proof.0).with_context(|| {
+            format!("failed deserializing multiproof for L1 batch #{l1_batch_number}")
         })?;
-        Ok(response.entries)
+        Ok(proof)
     }
 }
 
@@ -327,6 +404,24 @@ impl AsyncTreeReader {
         Ok(Json(response))
     }
 
+    async fn get_multiproof_handler(
+        State(this): State<Self>,
+        Json(request): Json<TreeProofsRequest>,
+    ) -> Result<Json<TreeMultiProofResponse>, TreeApiServerError> {
+        let latency = API_METRICS.latency[&MerkleTreeApiMethod::GetMultiproof].start();
+        let proof = this
+            .entries_with_multiproof(request.l1_batch_number, request.hashed_keys)
+            .await
+            .map_err(TreeApiServerError::NoTreeVersion)?;
+        let mut proof_bytes = vec![];
+        proof.serialize(&mut proof_bytes);
+        let response = TreeMultiProofResponse {
+            proof: Bytes(proof_bytes),
+        };
+        latency.observe();
+        Ok(Json(response))
+    }
+
     fn create_api_server(
         self,
         bind_address: &SocketAddr,
@@ -337,6 +432,7 @@ impl AsyncTreeReader {
         let app = Router::new()
             .route("/", routing::get(Self::info_handler))
             .route("/proofs", routing::post(Self::get_proofs_handler))
+            .route("/multiproof", routing::post(Self::get_multiproof_handler))
             .with_state(self);
 
         let server = axum::Server::try_bind(bind_address)
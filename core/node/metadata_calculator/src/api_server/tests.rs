@@ -9,6 +9,7 @@ use tokio::{
     net::{TcpListener, TcpSocket},
 };
 use zksync_dal::{ConnectionPool, Core};
+use zksync_merkle_tree::{MerkleTree, RocksDBWrapper};
 
 use super::*;
 use crate::tests::{gen_storage_logs, reset_db_state, run_calculator, setup_calculator};
@@ -51,7 +52,7 @@ async fn merkle_tree_api() {
     hashed_keys.extend((0_u8..10).map(|byte| U256::from_big_endian(&[byte; 32])));
 
     let proofs = api_client
-        .get_proofs(L1BatchNumber(5), hashed_keys)
+        .get_proofs(L1BatchNumber(5), hashed_keys.clone())
         .await
         .unwrap();
     assert_eq!(proofs.len(), 20);
@@ -61,6 +62,18 @@ async fn merkle_tree_api() {
         assert!(!proof.merkle_path.is_empty());
     }
 
+    let multiproof = api_client
+        .get_multiproof(L1BatchNumber(5), hashed_keys)
+        .await
+        .unwrap();
+    assert_eq!(multiproof.entries.len(), 20);
+    let present_count = multiproof
+        .entries
+        .iter()
+        .filter(|entry| !entry.is_empty())
+        .count();
+    assert_eq!(present_count, 10);
+
     let err = api_client
         .get_proofs(L1BatchNumber(10), vec![])
         .await
@@ -139,3 +152,88 @@ async fn local_merkle_tree_client() {
     assert_eq!(err.version_count, 6);
     assert_eq!(err.missing_version, 10);
 }
+
+#[tokio::test]
+async fn merkle_tree_grpc_api() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let (calculator, _) = setup_calculator(temp_dir.path(), pool.clone()).await;
+    let api_addr = (Ipv4Addr::LOCALHOST, 0).into();
+
+    reset_db_state(&pool, 5).await;
+    let tree_reader = calculator.tree_reader();
+    run_calculator(calculator).await;
+
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let api_server = tree_reader
+        .wait()
+        .await
+        .unwrap()
+        .create_grpc_server(&api_addr, stop_receiver)
+        .await
+        .unwrap();
+    let local_addr = *api_server.local_addr();
+    let api_server_task = tokio::spawn(api_server.run());
+    let mut client =
+        grpc::proto::tree_api_client::TreeApiClient::connect(format!("http://{local_addr}"))
+            .await
+            .unwrap();
+
+    let tree_info = client
+        .get_info(grpc::proto::GetInfoRequest {})
+        .await
+        .unwrap()
+        .into_inner();
+    assert!(tree_info.leaf_count > 20);
+    assert_eq!(tree_info.next_l1_batch_number, 6);
+
+    let mut hashed_keys: Vec<_> = gen_storage_logs(20..30, 1)[0]
+        .iter()
+        .map(|log| log.key.hashed_key().as_bytes().to_vec())
+        .collect();
+    hashed_keys.extend((0_u8..10).map(|byte| vec![byte; 32]));
+    let request = grpc::proto::GetProofsRequest {
+        l1_batch_number: 5,
+        hashed_keys,
+        chunk_size: Some(3),
+    };
+    let mut proofs_stream = client.get_proofs(request).await.unwrap().into_inner();
+    let mut chunk_count = 0;
+    let mut proofs = vec![];
+    while let Some(chunk) = proofs_stream.message().await.unwrap() {
+        chunk_count += 1;
+        proofs.extend(chunk.entries);
+    }
+    assert_eq!(chunk_count, 7);
+    assert_eq!(proofs.len(), 20);
+    for (i, proof) in proofs.into_iter().enumerate() {
+        let should_be_present = i < 10;
+        assert_eq!(proof.index == 0, !should_be_present);
+        assert!(!proof.merkle_path.is_empty());
+    }
+
+    let request = grpc::proto::GetProofsRequest {
+        l1_batch_number: 10,
+        hashed_keys: vec![],
+        chunk_size: None,
+    };
+    let err = client.get_proofs(request).await.unwrap_err();
+    assert_eq!(err.code(), tonic::Code::NotFound);
+
+    // Dump the tree and import it into another RocksDB instance.
+    let request = grpc::proto::DumpNodesRequest { l1_batch_number: 5 };
+    let mut dump_stream = client.dump_nodes(request).await.unwrap().into_inner();
+    let mut dump = vec![];
+    while let Some(chunk) = dump_stream.message().await.unwrap() {
+        dump.extend(chunk.data);
+    }
+    let import_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+    let mut db = RocksDBWrapper::new(import_dir.path()).unwrap();
+    let info = db.import_version(dump.as_slice()).unwrap();
+    assert_eq!(info.version, 5);
+    assert_eq!(info.root_hash.as_bytes(), tree_info.root_hash);
+    MerkleTree::new(db).verify_consistency(5, true).unwrap();
+
+    stop_sender.send_replace(true);
+    api_server_task.await.unwrap().unwrap();
+}
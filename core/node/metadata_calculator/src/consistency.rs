@@ -0,0 +1,217 @@
+//! Incremental Merkle tree consistency check.
+
+use std::{num::NonZeroU32, time::Duration};
+
+use anyhow::Context as _;
+use serde::Serialize;
+use tokio::sync::watch;
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::L1BatchNumber;
+
+use super::{helpers::LazyAsyncTreeReader, metrics::CONSISTENCY_METRICS};
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "stage", rename_all = "snake_case")]
+enum ConsistencyCheckHealth {
+    Initialization,
+    Checking { next_l1_batch: L1BatchNumber },
+    ShuttingDown,
+}
+
+impl From<ConsistencyCheckHealth> for Health {
+    fn from(health: ConsistencyCheckHealth) -> Self {
+        let status = match &health {
+            ConsistencyCheckHealth::Initialization => HealthStatus::Affected,
+            ConsistencyCheckHealth::Checking { .. } => HealthStatus::Ready,
+            ConsistencyCheckHealth::ShuttingDown => HealthStatus::ShuttingDown,
+        };
+        Health::from(status).with_details(health)
+    }
+}
+
+/// Low-priority task incrementally checking Merkle tree consistency. The task checks the tree in chunks
+/// of L1 batches, with a delay between chunks. Its progress is persisted in the tree RocksDB by the metadata
+/// calculator (the only tree writer), so that the check is resumed after a restart. Once all L1 batches in the tree are checked, the task checks
+/// new L1 batches as they are added to the tree.
+///
+/// If the tree is pruned, only retained L1 batches are checked. If an inconsistency is found, the task
+/// returns an error.
+#[derive(Debug)]
+#[must_use = "Task should `run()` in a managed Tokio task"]
+pub struct MerkleTreeConsistencyTask {
+    tree_reader: LazyAsyncTreeReader,
+    cursor_sender: watch::Sender<Option<L1BatchNumber>>,
+    health_updater: HealthUpdater,
+    chunk_size: NonZeroU32,
+    delay: Duration,
+}
+
+impl MerkleTreeConsistencyTask {
+    pub(super) fn new(
+        tree_reader: LazyAsyncTreeReader,
+        cursor_sender: watch::Sender<Option<L1BatchNumber>>,
+        chunk_size: NonZeroU32,
+        delay: Duration,
+    ) -> Self {
+        Self {
+            tree_reader,
+            cursor_sender,
+            health_updater: ReactiveHealthCheck::new("tree_consistency_checker").1,
+            chunk_size,
+            delay,
+        }
+    }
+
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_updater.subscribe()
+    }
+
+    pub async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.health_updater
+            .update(ConsistencyCheckHealth::Initialization.into());
+
+        let reader;
+        tokio::select! {
+            res = self.tree_reader.wait() => {
+                match res {
+                    Ok(tree_reader) => reader = tree_reader,
+                    Err(_) => {
+                        tracing::info!("Merkle tree dropped; shutting down tree consistency check");
+                        return Ok(());
+                    }
+                }
+            }
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received before Merkle tree is initialized; shutting down tree consistency check");
+                return Ok(());
+            }
+        }
+
+        let mut next_l1_batch = reader
+            .clone()
+            .consistency_cursor()
+            .await?
+            .unwrap_or(L1BatchNumber(0));
+        tracing::info!(
+            "Starting incremental Merkle tree consistency check from L1 batch #{next_l1_batch}"
+        );
+
+        while !*stop_receiver.borrow_and_update() {
+            let tree_info = reader.clone().info().await;
+            if let Some(min_l1_batch_number) = tree_info.min_l1_batch_number {
+                if next_l1_batch < min_l1_batch_number {
+                    tracing::info!(
+                        "L1 batches #{next_l1_batch}..#{min_l1_batch_number} are pruned from the tree; skipping their consistency check"
+                    );
+                    next_l1_batch = min_l1_batch_number;
+                }
+            }
+
+            let latency = CONSISTENCY_METRICS.chunk_latency.start();
+            let check_result = reader
+                .clone()
+                .verify_consistency_incremental(next_l1_batch, self.chunk_size.get())
+                .await;
+            let new_next_l1_batch = match check_result {
+                Ok(number) => number,
+                Err(err) => {
+                    // The error may be caused by the tree being pruned or truncated concurrently with the check.
+                    let tree_info = reader.clone().info().await;
+                    let is_pruned = tree_info
+                        .min_l1_batch_number
+                        .is_some_and(|number| number > next_l1_batch);
+                    let is_truncated = tree_info.next_l1_batch_number <= next_l1_batch;
+                    if is_pruned || is_truncated {
+                        tracing::info!(
+                            "Tree was pruned or truncated during consistency check from L1 batch #{next_l1_batch}: {err:#}; retrying"
+                        );
+                        continue;
+                    }
+                    return Err(err).with_context(|| {
+                        format!(
+                            "Merkle tree is inconsistent; checked from L1 batch #{next_l1_batch}"
+                        )
+                    });
+                }
+            };
+            let latency = latency.observe();
+
+            if new_next_l1_batch != next_l1_batch {
+                if new_next_l1_batch > next_l1_batch {
+                    tracing::info!(
+                        "Checked consistency of Merkle tree for L1 batches #{next_l1_batch}..#{} in {latency:?}",
+                        new_next_l1_batch - 1
+                    );
+                } else {
+                    tracing::info!(
+                        "Merkle tree was truncated to L1 batch #{new_next_l1_batch}; resuming consistency check from it"
+                    );
+                }
+                self.cursor_sender.send_replace(Some(new_next_l1_batch));
+                next_l1_batch = new_next_l1_batch;
+            }
+            CONSISTENCY_METRICS
+                .next_l1_batch
+                .set(next_l1_batch.0.into());
+            self.health_updater
+                .update(ConsistencyCheckHealth::Checking { next_l1_batch }.into());
+
+            if tokio::time::timeout(self.delay, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+
+        self.health_updater
+            .update(ConsistencyCheckHealth::ShuttingDown.into());
+        tracing::info!("Stop signal received, Merkle tree consistency check is shutting down");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+    use zksync_dal::{ConnectionPool, Core};
+
+    use super::*;
+    use crate::tests::{reset_db_state, run_calculator, setup_calculator};
+
+    #[tokio::test]
+    async fn consistency_check_basics() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let (calculator, _) = setup_calculator(temp_dir.path(), pool.clone()).await;
+        reset_db_state(&pool, 5).await;
+        run_calculator(calculator).await;
+
+        let (mut calculator, _) = setup_calculator(temp_dir.path(), pool).await;
+        let tree_reader = calculator.tree_reader();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let task = calculator.consistency_task(NonZeroU32::new(2).unwrap(), Duration::ZERO);
+        let mut health_check = task.health_check();
+        let task_handle = tokio::spawn(task.run(stop_receiver));
+        let calculator_handle = tokio::spawn(calculator.run(stop_sender.subscribe()));
+
+        health_check
+            .wait_for(|health| {
+                if !matches!(health.status(), HealthStatus::Ready) {
+                    return false;
+                }
+                let details = health.details().unwrap();
+                details["next_l1_batch"] == 6
+            })
+            .await;
+        // The cursor is persisted asynchronously by the tree updater.
+        let reader = tree_reader.read().unwrap();
+        while reader.clone().consistency_cursor().await.unwrap() != Some(L1BatchNumber(6)) {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+
+        stop_sender.send_replace(true);
+        task_handle.await.unwrap().unwrap();
+        calculator_handle.await.unwrap().unwrap();
+    }
+}
@@ -3,6 +3,7 @@
 use std::{
     collections::{BTreeMap, HashSet},
     future::Future,
+    io,
     path::Path,
     sync::Arc,
     time::Duration,
@@ -19,10 +20,11 @@ use zksync_config::configs::database::MerkleTreeMode;
 use zksync_dal::{Connection, Core, CoreDal};
 use zksync_health_check::{CheckHealth, Health, HealthStatus, ReactiveHealthCheck};
 use zksync_merkle_tree::{
-    domain::{TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
-    recovery::MerkleTreeRecovery,
-    Database, Key, MerkleTreeColumnFamily, NoVersionError, RocksDBWrapper, TreeEntry,
-    TreeEntryWithProof, TreeInstruction,
+    domain::{PinnedTreeVersion, TreeMetadata, ZkSyncTree, ZkSyncTreeReader},
+    recovery::{MerkleTreeRecovery, RecoveryChunkStats},
+    Database, ExportedVersionInfo, Key, MerkleTreeColumnFamily, NoVersionError, ParallelDatabase,
+    RangeProofError, RocksDBWrapper, TreeEntry, TreeEntryWithProof, TreeInstruction,
+    TreeMultiProof, TreeRangeProof, TreeStructureStats, TreeTransferError,
 };
 use zksync_storage::{RocksDB, RocksDBOptions, StalledWritesRetries, WeakRocksDB};
 use zksync_types::{block::L1BatchHeader, L1BatchNumber, StorageKey, H256};
@@ -182,6 +184,7 @@ fn create_db_sync(config: &MetadataCalculatorConfig) -> anyhow::Result<RocksDBWr
 pub(super) struct AsyncTree {
     inner: Option<ZkSyncTree>,
     mode: MerkleTreeMode,
+    parallel_persistence: bool,
 }
 
 impl AsyncTree {
@@ -196,6 +199,7 @@ impl AsyncTree {
         Self {
             inner: Some(tree),
             mode,
+            parallel_persistence: false,
         }
     }
 
@@ -211,8 +215,22 @@ impl AsyncTree {
         self.mode
     }
 
+    pub fn use_parallel_persistence(&mut self, buffer_capacity: usize) {
+        self.as_mut().use_parallel_persistence(buffer_capacity);
+        self.parallel_persistence = true;
+    }
+
+    pub fn is_parallel_persistence_enabled(&self) -> bool {
+        self.parallel_persistence
+    }
+
     pub fn pruner(&mut self) -> PruningHandles {
-        self.as_mut().pruner()
+        let (pruner, handle) = self.as_mut().pruner();
+        PruningHandles {
+            pruner,
+            handle,
+            reader: self.reader(),
+        }
     }
 
     pub fn reader(&self) -> AsyncTreeReader {
@@ -276,9 +294,42 @@ impl AsyncTree {
         Ok(())
     }
 
+    /// Waits until all saved changes are persisted to RocksDB.
+    ///
+    /// Returned errors are unrecoverable; the tree must not be used after an error is returned.
+    pub async fn wait_for_persistence(&mut self) -> anyhow::Result<()> {
+        let mut tree = self.inner.take().context(Self::INCONSISTENT_MSG)?;
+        self.inner = Some(
+            tokio::task::spawn_blocking(|| {
+                tree.wait_for_persistence();
+                tree
+            })
+            .await
+            .context("Merkle tree panicked during persistence")?,
+        );
+        Ok(())
+    }
+
     pub fn revert_logs(&mut self, last_l1_batch_to_keep: L1BatchNumber) {
         self.as_mut().roll_back_logs(last_l1_batch_to_keep);
     }
+
+    /// Returned errors are unrecoverable; the tree must not be used after an error is returned.
+    pub async fn save_consistency_cursor(
+        &mut self,
+        next_l1_batch: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        let mut tree = self.inner.take().context(Self::INCONSISTENT_MSG)?;
+        self.inner = Some(
+            tokio::task::spawn_blocking(move || {
+                tree.save_consistency_cursor(next_l1_batch);
+                tree
+            })
+            .await
+            .context("Merkle tree panicked saving consistency check cursor")?,
+        );
+        Ok(())
+    }
 }
 
 /// Async version of [`ZkSyncTreeReader`].
@@ -296,6 +347,14 @@ impl AsyncTreeReader {
         }
     }
 
+    /// Returns the total size of live SST files in the tree RocksDB instance, in bytes.
+    pub(crate) async fn live_sst_size(&self) -> u64 {
+        let db = self.inner.db().clone().into_inner();
+        tokio::task::spawn_blocking(move || db.live_sst_size())
+            .await
+            .unwrap()
+    }
+
     pub async fn info(self) -> MerkleTreeInfo {
         tokio::task::spawn_blocking(move || MerkleTreeInfo {
             mode: self.mode,
@@ -316,6 +375,28 @@ impl AsyncTreeReader {
             .map_err(Into::into)
     }
 
+    /// Incrementally verifies tree consistency; see [`ZkSyncTreeReader::verify_consistency_incremental()`].
+    /// Returns the next L1 batch to be checked.
+    pub(super) async fn verify_consistency_incremental(
+        self,
+        start_l1_batch: L1BatchNumber,
+        max_l1_batches: u32,
+    ) -> anyhow::Result<L1BatchNumber> {
+        tokio::task::spawn_blocking(move || {
+            self.inner
+                .verify_consistency_incremental(start_l1_batch, max_l1_batches)
+        })
+        .await
+        .context("tree consistency verification panicked")?
+        .map_err(Into::into)
+    }
+
+    pub(super) async fn consistency_cursor(self) -> anyhow::Result<Option<L1BatchNumber>> {
+        tokio::task::spawn_blocking(move || self.inner.consistency_cursor())
+            .await
+            .context("panicked loading consistency check cursor")
+    }
+
     pub async fn entries_with_proofs(
         self,
         l1_batch_number: L1BatchNumber,
@@ -325,6 +406,100 @@ impl AsyncTreeReader {
             .await
             .unwrap()
     }
+
+    pub async fn entries_with_multiproof(
+        self,
+        l1_batch_number: L1BatchNumber,
+        keys: Vec<Key>,
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        tokio::task::spawn_blocking(move || {
+            self.inner.entries_with_multiproof(l1_batch_number, &keys)
+        })
+        .await
+        .unwrap()
+    }
+
+    pub async fn range_proof(
+        self,
+        l1_batch_number: L1BatchNumber,
+        start_key: Key,
+        end_key: Key,
+    ) -> Result<TreeRangeProof, RangeProofError> {
+        tokio::task::spawn_blocking(move || {
+            self.inner.range_proof(l1_batch_number, start_key, end_key)
+        })
+        .await
+        .unwrap()
+    }
+
+    /// Returns a read-only view of the tree for the specified L1 batch, which is guaranteed not to be pruned
+    /// while the view is alive; see [`ZkSyncTreeReader::pin_l1_batch()`].
+    pub async fn pin_l1_batch(
+        self,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<AsyncPinnedTreeVersion, NoVersionError> {
+        tokio::task::spawn_blocking(move || self.inner.pin_l1_batch(l1_batch_number))
+            .await
+            .unwrap()
+            .map(|pinned| AsyncPinnedTreeVersion(Arc::new(pinned)))
+    }
+
+    pub(crate) async fn export_l1_batch(
+        self,
+        l1_batch_number: L1BatchNumber,
+        writer: impl io::Write + Send + 'static,
+        chunk_size: usize,
+    ) -> Result<ExportedVersionInfo, TreeTransferError> {
+        tokio::task::spawn_blocking(move || {
+            self.inner
+                .export_l1_batch(l1_batch_number, writer, chunk_size)
+        })
+        .await
+        .unwrap()
+    }
+}
+
+/// Async version of [`PinnedTreeVersion`]. Clones refer to the same pinned version; the version is unpinned
+/// once all clones are dropped.
+#[derive(Debug, Clone)]
+pub struct AsyncPinnedTreeVersion(Arc<PinnedTreeVersion>);
+
+impl AsyncPinnedTreeVersion {
+    pub fn l1_batch_number(&self) -> L1BatchNumber {
+        self.0.l1_batch_number()
+    }
+
+    pub fn root_hash(&self) -> H256 {
+        self.0.root_hash()
+    }
+
+    pub async fn entries_with_proofs(
+        &self,
+        keys: Vec<Key>,
+    ) -> Result<Vec<TreeEntryWithProof>, NoVersionError> {
+        let pinned = self.0.clone();
+        tokio::task::spawn_blocking(move || pinned.entries_with_proofs(&keys))
+            .await
+            .unwrap()
+    }
+
+    pub async fn entries_with_multiproof(
+        &self,
+        keys: Vec<Key>,
+    ) -> Result<TreeMultiProof, NoVersionError> {
+        let pinned = self.0.clone();
+        tokio::task::spawn_blocking(move || pinned.entries_with_multiproof(&keys))
+            .await
+            .unwrap()
+    }
+    /// Walks the pinned version and collects its structural statistics. This is an expensive operation.
+    pub async fn structure_stats(&self) -> anyhow::Result<TreeStructureStats> {
+        let pinned = self.0.clone();
+        tokio::task::spawn_blocking(move || pinned.structure_stats())
+            .await
+            .context("panicked collecting tree structure stats")?
+            .map_err(Into::into)
+    }
 }
 
 /// Version of async tree reader that holds a weak reference to RocksDB. Used in [`MerkleTreeHealthCheck`].
@@ -344,7 +519,7 @@ impl WeakAsyncTreeReader {
 }
 
 /// Lazily initialized [`AsyncTreeReader`].
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LazyAsyncTreeReader(pub(super) watch::Receiver<Option<AsyncTreeReader>>);
 
 impl LazyAsyncTreeReader {
@@ -367,24 +542,45 @@ impl LazyAsyncTreeReader {
     }
 }
 
-/// Async wrapper for [`MerkleTreeRecovery`].
+/// Async wrapper for [`MerkleTreeRecovery`]. Recovered chunks are persisted in a background thread.
 #[derive(Debug, Default)]
 pub(super) struct AsyncTreeRecovery {
-    inner: Option<MerkleTreeRecovery<RocksDBWrapper>>,
+    inner: Option<MerkleTreeRecovery<ParallelDatabase<RocksDBWrapper>>>,
     mode: MerkleTreeMode,
+    /// Multi-get chunk size of the tree database before recovery. It is restored once recovery is finished,
+    /// since the chunk size may be changed during recovery.
+    multi_get_chunk_size: usize,
 }
 
 impl AsyncTreeRecovery {
     const INCONSISTENT_MSG: &'static str =
         "`AsyncTreeRecovery` is in inconsistent state, which could occur after one of its async methods was cancelled";
+    /// Initial maximum number of chunks queued for persistence.
+    pub const INITIAL_PERSISTENCE_BUFFER_CAPACITY: usize = 1;
 
     pub fn new(db: RocksDBWrapper, recovered_version: u64, mode: MerkleTreeMode) -> Self {
+        let multi_get_chunk_size = db.multi_get_chunk_size();
+        let recovery = MerkleTreeRecovery::new(db, recovered_version)
+            .parallelize(Self::INITIAL_PERSISTENCE_BUFFER_CAPACITY);
         Self {
-            inner: Some(MerkleTreeRecovery::new(db, recovered_version)),
+            inner: Some(recovery),
             mode,
+            multi_get_chunk_size,
         }
     }
 
+    /// Returns the multi-get chunk size of the tree database before recovery.
+    pub fn initial_multi_get_chunk_size(&self) -> usize {
+        self.multi_get_chunk_size
+    }
+
+    /// Changes the multi-get chunk size and the maximum number of chunks queued for persistence.
+    pub fn tune(&mut self, multi_get_chunk_size: usize, persistence_buffer_capacity: usize) {
+        let tree = self.inner.as_mut().expect(Self::INCONSISTENT_MSG);
+        tree.set_multi_get_chunk_size(multi_get_chunk_size);
+        tree.set_persistence_buffer_capacity(persistence_buffer_capacity);
+    }
+
     pub fn recovered_version(&self) -> u64 {
         self.inner
             .as_ref()
@@ -413,23 +609,29 @@ impl AsyncTreeRecovery {
     }
 
     /// Extends the tree with a chunk of recovery entries.
-    pub async fn extend(&mut self, entries: Vec<TreeEntry>) {
+    pub async fn extend(&mut self, entries: Vec<TreeEntry>) -> RecoveryChunkStats {
         let mut tree = self.inner.take().expect(Self::INCONSISTENT_MSG);
-        let tree = tokio::task::spawn_blocking(move || {
-            tree.extend_random(entries);
-            tree
+        let (stats, tree) = tokio::task::spawn_blocking(move || {
+            let stats = tree.extend_random(entries);
+            (stats, tree)
         })
         .await
         .unwrap();
 
         self.inner = Some(tree);
+        stats
     }
 
     pub async fn finalize(self) -> AsyncTree {
         let tree = self.inner.expect(Self::INCONSISTENT_MSG);
-        let db = tokio::task::spawn_blocking(|| tree.finalize())
-            .await
-            .unwrap();
+        let multi_get_chunk_size = self.multi_get_chunk_size;
+        let db = tokio::task::spawn_blocking(move || {
+            let mut db = tree.finalize().into_inner();
+            db.set_multi_get_chunk_size(multi_get_chunk_size);
+            db
+        })
+        .await
+        .unwrap();
         AsyncTree::new(db, self.mode)
     }
 }
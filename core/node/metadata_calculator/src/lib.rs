@@ -17,18 +17,22 @@ use zksync_dal::{ConnectionPool, Core};
 use zksync_health_check::{CheckHealth, HealthUpdater, ReactiveHealthCheck};
 use zksync_object_store::ObjectStore;
 
+pub use self::{
+    analysis::MerkleTreeAnalysisTask,
+    consistency::MerkleTreeConsistencyTask,
+    helpers::{AsyncPinnedTreeVersion, AsyncTreeReader, LazyAsyncTreeReader, MerkleTreeInfo},
+    pruning::MerkleTreePruningTask,
+};
 use self::{
     helpers::{create_db, Delayer, GenericAsyncTree, MerkleTreeHealth, MerkleTreeHealthCheck},
     metrics::{ConfigLabels, METRICS},
     pruning::PruningHandles,
     updater::TreeUpdater,
 };
-pub use self::{
-    helpers::{AsyncTreeReader, LazyAsyncTreeReader, MerkleTreeInfo},
-    pruning::MerkleTreePruningTask,
-};
 
+mod analysis;
 pub mod api_server;
+mod consistency;
 mod helpers;
 mod metrics;
 mod pruning;
@@ -65,6 +69,9 @@ pub struct MetadataCalculatorConfig {
     pub memtable_capacity: usize,
     /// Timeout to wait for the Merkle tree database to run compaction on stalled writes.
     pub stalled_writes_timeout: Duration,
+    /// If specified, Merkle tree changes will be persisted to RocksDB in a background thread, with the specified
+    /// maximum number of L1 batches queued for persistence.
+    pub tree_persistence_buffer: Option<usize>,
 }
 
 impl MetadataCalculatorConfig {
@@ -83,6 +90,7 @@ impl MetadataCalculatorConfig {
             include_indices_and_filters_in_block_cache: false,
             memtable_capacity: merkle_tree_config.memtable_capacity(),
             stalled_writes_timeout: merkle_tree_config.stalled_writes_timeout(),
+            tree_persistence_buffer: None,
         }
     }
 }
@@ -92,6 +100,7 @@ pub struct MetadataCalculator {
     config: MetadataCalculatorConfig,
     tree_reader: watch::Sender<Option<AsyncTreeReader>>,
     pruning_handles_sender: oneshot::Sender<PruningHandles>,
+    consistency_cursor: Option<watch::Receiver<Option<L1BatchNumber>>>,
     object_store: Option<Arc<dyn ObjectStore>>,
     pool: ConnectionPool<Core>,
     recovery_pool: ConnectionPool<Core>,
@@ -133,6 +142,7 @@ impl MetadataCalculator {
         Ok(Self {
             tree_reader: watch::channel(None).0,
             pruning_handles_sender: oneshot::channel().0,
+            consistency_cursor: None,
             object_store,
             recovery_pool: pool.clone(),
             pool,
@@ -169,6 +179,26 @@ impl MetadataCalculator {
         MerkleTreePruningTask::new(pruning_handles, self.pool.clone(), poll_interval)
     }
 
+    /// Returns a task that incrementally checks Merkle tree consistency in the background, checking
+    /// at most `chunk_size` L1 batches at a time and waiting `delay` between chunks. The check progress
+    /// is persisted by this calculator. This method should be called once; progress of all previously
+    /// returned tasks is ignored.
+    pub fn consistency_task(
+        &mut self,
+        chunk_size: NonZeroU32,
+        delay: Duration,
+    ) -> MerkleTreeConsistencyTask {
+        let (cursor_sender, cursor_receiver) = watch::channel(None);
+        self.consistency_cursor = Some(cursor_receiver);
+        MerkleTreeConsistencyTask::new(self.tree_reader(), cursor_sender, chunk_size, delay)
+    }
+
+    /// Returns a task that periodically walks the latest Merkle tree version and exports its structural statistics
+    /// as metrics, waiting `interval` between analyses.
+    pub fn analysis_task(&self, interval: Duration) -> MerkleTreeAnalysisTask {
+        MerkleTreeAnalysisTask::new(self.tree_reader(), interval)
+    }
+
     async fn create_tree(&self) -> anyhow::Result<GenericAsyncTree> {
         self.health_updater
             .update(MerkleTreeHealth::Initialization.into());
@@ -202,6 +232,12 @@ impl MetadataCalculator {
         let Some(mut tree) = tree else {
             return Ok(()); // recovery was aborted because a stop signal was received
         };
+        if let Some(buffer_capacity) = self.config.tree_persistence_buffer {
+            tracing::info!(
+                "Using parallel persistence for Merkle tree with buffer capacity {buffer_capacity}"
+            );
+            tree.use_parallel_persistence(buffer_capacity);
+        }
 
         let tree_reader = tree.reader();
         let tree_info = tree_reader.clone().info().await;
@@ -213,7 +249,10 @@ impl MetadataCalculator {
         self.health_updater
             .update(MerkleTreeHealth::MainLoop(tree_info).into());
 
-        let updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        let mut updater = TreeUpdater::new(tree, self.max_l1_batches_per_iter, self.object_store);
+        if let Some(consistency_cursor) = self.consistency_cursor {
+            updater = updater.with_consistency_cursor(consistency_cursor);
+        }
         updater
             .loop_updating_tree(self.delayer, &self.pool, stop_receiver)
             .await
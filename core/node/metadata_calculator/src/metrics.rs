@@ -234,6 +234,16 @@ pub(super) enum ChunkRecoveryStage {
     ExtendTree,
 }
 
+/// Recovery parameter adjusted at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "param", rename_all = "snake_case")]
+pub(super) enum RecoveryParam {
+    ConcurrencyLimit,
+    ExtendChunkSize,
+    MultiGetChunkSize,
+    PersistenceBufferCapacity,
+}
+
 /// Metrics for Merkle tree recovery driven by the metadata calculator.
 #[derive(Debug, Metrics)]
 #[metrics(prefix = "server_metadata_calculator_recovery")]
@@ -247,8 +257,59 @@ pub(super) struct MetadataCalculatorRecoveryMetrics {
     /// Latency of a chunk recovery stage.
     #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
     pub chunk_latency: Family<ChunkRecoveryStage, Histogram<Duration>>,
+    /// Current values of recovery parameters chosen by adaptive tuning.
+    pub tuned_params: Family<RecoveryParam, Gauge<usize>>,
 }
 
 #[vise::register]
 pub(super) static RECOVERY_METRICS: vise::Global<MetadataCalculatorRecoveryMetrics> =
     vise::Global::new();
+
+/// Metrics for the incremental Merkle tree consistency check.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_consistency")]
+pub(super) struct ConsistencyCheckMetrics {
+    /// Next L1 batch to be checked.
+    pub next_l1_batch: Gauge<u64>,
+    /// Latency of checking a chunk of L1 batches.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub chunk_latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static CONSISTENCY_METRICS: vise::Global<ConsistencyCheckMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
+#[metrics(rename_all = "snake_case")]
+pub(super) enum TreeNodeKind {
+    Internal,
+    Leaf,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelSet)]
+pub(super) struct TreeLevelLabels {
+    pub kind: TreeNodeKind,
+    /// Tree level in nibbles; the root node is at level 0.
+    pub level: usize,
+}
+
+/// Structural statistics for the latest Merkle tree version collected by the analysis task.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_metadata_calculator_tree_structure")]
+pub(super) struct TreeStructureMetrics {
+    /// L1 batch for which the tree structure was last analyzed.
+    pub l1_batch: Gauge<u64>,
+    /// Number of tree nodes by kind and level.
+    pub node_count: Family<TreeLevelLabels, Gauge<u64>>,
+    /// Average leaf level, i.e. the average number of internal nodes loaded to create a proof for an existing entry.
+    pub avg_leaf_level: Gauge<f64>,
+    /// Ratio of the maximum number of leaves in a subtree rooted at a child of the root node
+    /// to the average number of leaves in such a subtree.
+    pub leaf_distribution_skew: Gauge<f64>,
+    /// Latency of walking the tree to collect statistics.
+    #[metrics(buckets = Buckets::LATENCIES, unit = Unit::Seconds)]
+    pub latency: Histogram<Duration>,
+}
+
+#[vise::register]
+pub(super) static TREE_STRUCTURE_METRICS: vise::Global<TreeStructureMetrics> = vise::Global::new();
@@ -1,16 +1,35 @@
 //! Merkle tree pruning logic.
 
-use std::time::Duration;
+use std::{mem, ops, sync::Arc, time::Duration};
 
 use anyhow::Context as _;
 use serde::Serialize;
-use tokio::sync::{oneshot, watch};
+use tokio::sync::{oneshot, watch, Mutex};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_merkle_tree::{MerkleTreePruner, MerkleTreePrunerHandle, RocksDBWrapper};
 use zksync_types::L1BatchNumber;
 
-pub(super) type PruningHandles = (MerkleTreePruner<RocksDBWrapper>, MerkleTreePrunerHandle);
+use crate::helpers::AsyncTreeReader;
+
+/// Handles passed to [`MerkleTreePruningTask`] once the tree is initialized.
+#[derive(Debug)]
+pub(super) struct PruningHandles {
+    pub pruner: MerkleTreePruner<RocksDBWrapper>,
+    pub handle: MerkleTreePrunerHandle,
+    pub reader: AsyncTreeReader,
+}
+
+/// Source of [`PruningHandles`] shared among instances of [`MerkleTreePruningTask`].
+#[derive(Debug)]
+enum PruningHandlesSource {
+    /// Handles will be sent by the metadata calculator once the tree is initialized.
+    Pending(oneshot::Receiver<PruningHandles>),
+    /// Handles returned by a stopped task instance.
+    Returned(PruningHandles),
+    /// Handles are unavailable, e.g. because the tree was dropped before initialization.
+    Closed,
+}
 
 #[derive(Debug, Serialize)]
 #[serde(tag = "stage", rename_all = "snake_case")]
@@ -36,14 +55,68 @@ impl From<MerkleTreePruningTaskHealth> for Health {
     }
 }
 
-/// Task performing Merkle tree pruning according to the pruning entries in Postgres.
-#[derive(Debug)]
+/// Returns the target retained tree version necessary to fit the tree into `max_tree_size` bytes, or `None`
+/// if the tree already fits or pruning to the `current_target_version` is still in progress (in which case,
+/// the tree size doesn't reflect the current target yet).
+///
+/// The target is capped by `max_target_version`, which corresponds to the last L1 batch executed on L1;
+/// versions for later batches must be retained since the node can be reverted to the last executed batch.
+fn size_based_target_version(
+    tree_size: u64,
+    max_tree_size: u64,
+    retained_versions: ops::RangeInclusive<u64>,
+    current_target_version: u64,
+    max_target_version: u64,
+) -> Option<u64> {
+    if tree_size <= max_tree_size {
+        return None;
+    }
+    let (first_retained_version, latest_version) = retained_versions.into_inner();
+    if first_retained_version < current_target_version {
+        return None;
+    }
+    let retained_count = (latest_version + 1).checked_sub(first_retained_version)?;
+    if retained_count <= 1 {
+        tracing::warn!(
+            "Merkle tree size ({tree_size} bytes) exceeds the configured maximum ({max_tree_size} bytes), \
+             but the tree retains a single version"
+        );
+        return None;
+    }
+
+    // Assume that the tree size is proportional to the number of retained versions. This underestimates
+    // the number of versions to prune since the latest tree state cannot be pruned, so the target converges
+    // over several iterations.
+    let excess_size = tree_size - max_tree_size;
+    let step =
+        (u128::from(retained_count) * u128::from(excess_size)).div_ceil(u128::from(tree_size));
+    let step = u64::try_from(step).expect("step is less than retained version count");
+    let target_version = (first_retained_version + step)
+        .min(latest_version)
+        .min(max_target_version);
+    if target_version <= first_retained_version {
+        tracing::warn!(
+            "Merkle tree size ({tree_size} bytes) exceeds the configured maximum ({max_tree_size} bytes), \
+             but all retained versions after the last executed L1 batch (#{max_target_version}) must be kept"
+        );
+        return None;
+    }
+    Some(target_version)
+}
+
+/// Task performing Merkle tree pruning according to the pruning entries in Postgres, and optionally
+/// according to the maximum tree size.
+///
+/// The task can be cloned in order to restart pruning after the previous instance has stopped. Clones share
+/// the tree pruner, so only a single instance prunes the tree at a time; other instances wait until it stops.
+#[derive(Debug, Clone)]
 #[must_use = "Task should `run()` in a managed Tokio task"]
 pub struct MerkleTreePruningTask {
-    handles: oneshot::Receiver<PruningHandles>,
+    handles: Arc<Mutex<PruningHandlesSource>>,
     pool: ConnectionPool<Core>,
-    health_updater: HealthUpdater,
+    health_updater: Arc<HealthUpdater>,
     poll_interval: Duration,
+    max_tree_size: Option<u64>,
 }
 
 impl MerkleTreePruningTask {
@@ -53,13 +126,66 @@ impl MerkleTreePruningTask {
         poll_interval: Duration,
     ) -> Self {
         Self {
-            handles,
+            handles: Arc::new(Mutex::new(PruningHandlesSource::Pending(handles))),
             pool,
-            health_updater: ReactiveHealthCheck::new("tree_pruner").1,
+            health_updater: Arc::new(ReactiveHealthCheck::new("tree_pruner").1),
             poll_interval,
+            max_tree_size: None,
         }
     }
 
+    /// Sets the maximum size of the tree RocksDB instance in bytes, as measured by the size of live SST files.
+    /// If the tree exceeds this size, the oldest tree versions are pruned even if they are retained in Postgres.
+    /// Versions after the last L1 batch executed on L1 are never pruned, so that the node can be reverted.
+    /// Since pruned data is removed from disk by RocksDB compaction, the tree size can exceed the maximum
+    /// for some time.
+    pub fn with_max_tree_size(mut self, max_tree_size: u64) -> Self {
+        self.max_tree_size = Some(max_tree_size);
+        self
+    }
+
+    async fn size_based_target_version(
+        &self,
+        reader: &AsyncTreeReader,
+        max_tree_size: u64,
+        current_target_version: u64,
+    ) -> anyhow::Result<Option<u64>> {
+        let tree_size = reader.live_sst_size().await;
+        if tree_size <= max_tree_size {
+            return Ok(None);
+        }
+
+        let mut storage = self.pool.connection_tagged("metadata_calculator").await?;
+        let last_executed_l1_batch = storage
+            .blocks_dal()
+            .get_number_of_last_l1_batch_executed_on_eth()
+            .await?;
+        drop(storage);
+        let Some(last_executed_l1_batch) = last_executed_l1_batch else {
+            tracing::warn!(
+                "Merkle tree size ({tree_size} bytes) exceeds the configured maximum ({max_tree_size} bytes), \
+                 but no L1 batches are executed on L1 yet"
+            );
+            return Ok(None);
+        };
+
+        let tree_info = reader.clone().info().await;
+        let Some(first_retained_l1_batch) = tree_info.min_l1_batch_number else {
+            return Ok(None);
+        };
+        let Some(latest_version) = u64::from(tree_info.next_l1_batch_number.0).checked_sub(1)
+        else {
+            return Ok(None);
+        };
+        Ok(size_based_target_version(
+            tree_size,
+            max_tree_size,
+            u64::from(first_retained_l1_batch.0)..=latest_version,
+            current_target_version,
+            u64::from(last_executed_l1_batch.0),
+        ))
+    }
+
     pub fn health_check(&self) -> ReactiveHealthCheck {
         self.health_updater.subscribe()
     }
@@ -69,53 +195,95 @@ impl MerkleTreePruningTask {
         self.health_updater
             .update(MerkleTreePruningTaskHealth::Initialization.into());
 
-        let (mut pruner, pruner_handle);
-        tokio::select! {
-            res = self.handles => {
-                match res {
-                    Ok(handles) => (pruner, pruner_handle) = handles,
-                    Err(_) => {
-                        tracing::info!("Merkle tree dropped; shutting down tree pruning");
+        let mut handles_source = tokio::select! {
+            source = self.handles.lock() => source,
+            _ = stop_receiver.changed() => {
+                tracing::info!("Stop signal received while waiting for another pruning task to stop; shutting down tree pruning");
+                return Ok(());
+            }
+        };
+        let handles = match mem::replace(&mut *handles_source, PruningHandlesSource::Closed) {
+            PruningHandlesSource::Returned(handles) => handles,
+            PruningHandlesSource::Closed => {
+                tracing::info!("Pruning handles are unavailable; shutting down tree pruning");
+                return Ok(());
+            }
+            PruningHandlesSource::Pending(mut handles_receiver) => {
+                tokio::select! {
+                    res = &mut handles_receiver => {
+                        match res {
+                            Ok(res) => res,
+                            Err(_) => {
+                                tracing::info!("Merkle tree dropped; shutting down tree pruning");
+                                return Ok(());
+                            }
+                        }
+                    }
+                    _ = stop_receiver.changed() => {
+                        // Allow the next task instance to receive the handles.
+                        *handles_source = PruningHandlesSource::Pending(handles_receiver);
+                        tracing::info!("Stop signal received before Merkle tree is initialized; shutting down tree pruning");
                         return Ok(());
                     }
                 }
             }
-            _ = stop_receiver.changed() => {
-                tracing::info!("Stop signal received before Merkle tree is initialized; shutting down tree pruning");
-                return Ok(());
-            }
-        }
+        };
         let health = MerkleTreePruningTaskHealth::Pruning {
             target_retained_l1_batch_number: None,
         };
         self.health_updater.update(health.into());
         tracing::info!("Obtained pruning handles; starting Merkle tree pruning");
 
+        let PruningHandles {
+            mut pruner,
+            handle: pruner_handle,
+            reader: tree_reader,
+        } = handles;
         // Pruner is not allocated a managed task because it is blocking; its cancellation awareness inherently
         // depends on the pruner handle (i.e., this task).
         pruner.set_poll_interval(self.poll_interval);
         let pruner_task_handle = tokio::task::spawn_blocking(|| pruner.run());
 
+        let mut current_target_version = 0;
         while !*stop_receiver.borrow_and_update() {
             let mut storage = self.pool.connection_tagged("metadata_calculator").await?;
             let pruning_info = storage.pruning_dal().get_pruning_info().await?;
             drop(storage);
 
-            if let Some(l1_batch_number) = pruning_info.last_hard_pruned_l1_batch {
-                let target_retained_l1_batch_number = l1_batch_number + 1;
-                let target_retained_version = u64::from(target_retained_l1_batch_number.0);
+            let mut new_target_version = pruning_info
+                .last_hard_pruned_l1_batch
+                .map(|l1_batch_number| u64::from(l1_batch_number.0) + 1);
+            if let Some(max_tree_size) = self.max_tree_size {
+                let size_based_target = self
+                    .size_based_target_version(&tree_reader, max_tree_size, current_target_version)
+                    .await?;
+                if let Some(version) = size_based_target {
+                    tracing::info!(
+                        "Merkle tree exceeds the maximum size ({max_tree_size} bytes); pruning it up to version {version}"
+                    );
+                }
+                new_target_version = new_target_version.max(size_based_target);
+            }
+
+            if let Some(target_retained_version) = new_target_version {
                 let Ok(prev_target_version) =
                     pruner_handle.set_target_retained_version(target_retained_version)
                 else {
                     self.health_updater
                         .update(MerkleTreePruningTaskHealth::PruningStopped.into());
                     tracing::error!("Merkle tree pruning thread unexpectedly stopped");
-                    return pruner_task_handle
+                    pruner_task_handle
                         .await
-                        .context("Merkle tree pruning thread panicked");
+                        .context("Merkle tree pruning thread panicked")?;
+                    return Ok(());
                 };
 
-                if prev_target_version != target_retained_version {
+                current_target_version = prev_target_version.max(target_retained_version);
+                if prev_target_version < target_retained_version {
+                    let target_retained_l1_batch_number = L1BatchNumber(
+                        u32::try_from(target_retained_version)
+                            .expect("integer overflow for L1 batch number"),
+                    );
                     let health = MerkleTreePruningTaskHealth::Pruning {
                         target_retained_l1_batch_number: Some(target_retained_l1_batch_number),
                     };
@@ -136,9 +304,17 @@ impl MerkleTreePruningTask {
             .update(MerkleTreePruningTaskHealth::ShuttingDown.into());
         tracing::info!("Stop signal received, Merkle tree pruning is shutting down");
         drop(pruner_handle);
-        pruner_task_handle
+        let mut pruner = pruner_task_handle
             .await
-            .context("Merkle tree pruning thread panicked")
+            .context("Merkle tree pruning thread panicked")?;
+        // Return the pruner so that pruning can be restarted by another task instance.
+        let handle = pruner.renew_handle();
+        *handles_source = PruningHandlesSource::Returned(PruningHandles {
+            pruner,
+            handle,
+            reader: tree_reader,
+        });
+        Ok(())
     }
 }
 
@@ -158,6 +334,27 @@ mod tests {
 
     const POLL_INTERVAL: Duration = Duration::from_millis(50);
 
+    #[test]
+    fn computing_size_based_target_version() {
+        // Tree fits into the budget.
+        assert_eq!(size_based_target_version(100, 100, 0..=9, 0, 9), None);
+        // Pruning to the current target is still in progress.
+        assert_eq!(size_based_target_version(200, 100, 0..=9, 5, 9), None);
+        // Only a single version is retained.
+        assert_eq!(size_based_target_version(200, 100, 9..=9, 9, 9), None);
+
+        assert_eq!(size_based_target_version(200, 100, 0..=9, 0, 9), Some(5));
+        assert_eq!(size_based_target_version(101, 100, 0..=9, 0, 9), Some(1));
+        assert_eq!(size_based_target_version(1_000, 1, 5..=9, 5, 9), Some(9));
+
+        // The target is capped by the last executed L1 batch.
+        assert_eq!(size_based_target_version(200, 100, 0..=9, 0, 3), Some(3));
+        assert_eq!(size_based_target_version(1_000, 1, 5..=9, 5, 7), Some(7));
+        // No versions before the last executed L1 batch are retained.
+        assert_eq!(size_based_target_version(200, 100, 5..=9, 5, 5), None);
+        assert_eq!(size_based_target_version(200, 100, 5..=9, 5, 2), None);
+    }
+
     #[tokio::test]
     async fn basic_tree_pruning_workflow() {
         let pool = ConnectionPool::<Core>::test_pool().await;
@@ -209,6 +406,62 @@ mod tests {
             .await;
     }
 
+    #[tokio::test]
+    async fn tree_pruning_can_be_restarted() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
+        let config = mock_config(temp_dir.path());
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        reset_db_state(&pool, 5).await;
+
+        let mut calculator = MetadataCalculator::new(config, None, pool.clone())
+            .await
+            .unwrap();
+        let reader = calculator.tree_reader();
+        let pruning_task = calculator.pruning_task(POLL_INTERVAL);
+        let restarted_pruning_task = pruning_task.clone();
+        let mut health_check = pruning_task.health_check();
+        let (stop_sender, stop_receiver) = watch::channel(false);
+        let calculator_handle = tokio::spawn(calculator.run(stop_receiver.clone()));
+        let (pruning_stop_sender, pruning_stop_receiver) = watch::channel(false);
+        let pruning_task_handle = tokio::spawn(pruning_task.run(pruning_stop_receiver));
+
+        health_check
+            .wait_for(|health| matches!(health.status(), HealthStatus::Ready))
+            .await;
+        pruning_stop_sender.send_replace(true);
+        pruning_task_handle.await.unwrap().unwrap();
+
+        let pruning_task_handle = tokio::spawn(restarted_pruning_task.run(stop_receiver.clone()));
+        health_check
+            .wait_for(|health| matches!(health.status(), HealthStatus::Ready))
+            .await;
+        let reader = reader.wait().await.unwrap();
+        while reader.clone().info().await.next_l1_batch_number < L1BatchNumber(6) {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        storage
+            .pruning_dal()
+            .hard_prune_batches_range(L1BatchNumber(3), L2BlockNumber(3))
+            .await
+            .unwrap();
+        while reader.clone().info().await.min_l1_batch_number.unwrap() <= L1BatchNumber(3) {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        reader.verify_consistency(L1BatchNumber(5)).await.unwrap();
+
+        stop_sender.send_replace(true);
+        calculator_handle.await.unwrap().unwrap();
+        pruning_task_handle.await.unwrap().unwrap();
+        health_check
+            .wait_for(|health| matches!(health.status(), HealthStatus::ShutDown))
+            .await;
+    }
+
     #[derive(Debug)]
     enum PrematureExitScenario {
         CalculatorDrop,
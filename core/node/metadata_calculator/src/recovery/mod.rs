@@ -13,11 +13,15 @@
 //! If recovery is necessary, it starts / resumes by loading the Postgres snapshot in chunks
 //! and feeding each chunk to the tree. Chunks are loaded concurrently since this is the most
 //! I/O-heavy operation; the concurrency is naturally limited by the number of connections to
-//! Postgres in the supplied connection pool, but we explicitly use a [`Semaphore`] to control it
+//! Postgres in the supplied connection pool, but we explicitly use a semaphore to control it
 //! in order to not run into DB timeout errors. Before starting recovery in chunks, we filter out
 //! chunks that have already been recovered by checking if the first key in a chunk is present
 //! in the tree. (Note that for this to work, chunks **must** always be defined in the same way.)
 //!
+//! Recovery parameters (the concurrency limit, the number of entries the tree is extended with at once,
+//! the multi-get chunk size and the persistence buffer capacity) are adjusted at runtime; see the [`tuning`]
+//! module for details.
+//!
 //! The recovery logic is fault-tolerant and supports graceful shutdown. If recovery is interrupted,
 //! recovery of the remaining chunks will continue when Metadata calculator is restarted.
 //!
@@ -34,7 +38,7 @@ use std::{
 use anyhow::Context as _;
 use async_trait::async_trait;
 use futures::future;
-use tokio::sync::{watch, Mutex, Semaphore};
+use tokio::sync::{watch, Mutex};
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
 use zksync_health_check::HealthUpdater;
 use zksync_merkle_tree::TreeEntry;
@@ -44,6 +48,7 @@ use zksync_types::{
     L2BlockNumber, H256,
 };
 
+use self::tuning::{AdjustableSemaphore, RecoveryTuner};
 use super::{
     helpers::{AsyncTree, AsyncTreeRecovery, GenericAsyncTree, MerkleTreeHealth},
     metrics::{ChunkRecoveryStage, RecoveryStage, RECOVERY_METRICS},
@@ -51,6 +56,7 @@ use super::{
 
 #[cfg(test)]
 mod tests;
+mod tuning;
 
 /// Handler of recovery life cycle events. This functionality is encapsulated in a trait to be able
 /// to control recovery behavior in tests.
@@ -147,6 +153,27 @@ impl SnapshotParameters {
     }
 }
 
+/// Tree recovery together with the adaptive tuning of its parameters.
+#[derive(Debug)]
+struct RecoveryState {
+    tree: AsyncTreeRecovery,
+    tuner: RecoveryTuner,
+}
+
+impl RecoveryState {
+    async fn extend(&mut self, entries: Vec<TreeEntry>, semaphore: &AdjustableSemaphore) {
+        let prev_params = self.tuner.params();
+        let stats = self.tree.extend(entries).await;
+        if let Some(params) = self.tuner.observe_extend(stats) {
+            self.tree.tune(
+                params.multi_get_chunk_size,
+                params.persistence_buffer_capacity,
+            );
+            semaphore.adjust(prev_params.concurrency_limit, params.concurrency_limit);
+        }
+    }
+}
+
 /// Options for tree recovery.
 #[derive(Debug)]
 struct RecoveryOptions<'a> {
@@ -250,16 +277,33 @@ impl AsyncTreeRecovery {
             remaining_chunks.len()
         );
 
-        let tree = Mutex::new(self);
-        let semaphore = Semaphore::new(options.concurrency_limit);
+        let tuner = RecoveryTuner::new(
+            options.concurrency_limit,
+            SnapshotParameters::DESIRED_CHUNK_SIZE as usize,
+            self.initial_multi_get_chunk_size(),
+            AsyncTreeRecovery::INITIAL_PERSISTENCE_BUFFER_CAPACITY,
+        );
+        let params = tuner.params();
+        self.tune(
+            params.multi_get_chunk_size,
+            params.persistence_buffer_capacity,
+        );
+        let state = Mutex::new(RecoveryState { tree: self, tuner });
+        let semaphore = AdjustableSemaphore::new(params.concurrency_limit);
         let chunk_tasks = remaining_chunks.into_iter().map(|chunk| async {
-            let _permit = semaphore
-                .acquire()
-                .await
-                .context("semaphore is never closed")?;
+            let permit = semaphore.acquire().await;
             options.events.chunk_started().await;
-            Self::recover_key_chunk(&tree, snapshot.l2_block, chunk, pool, stop_receiver).await?;
+            Self::recover_key_chunk(
+                &state,
+                &semaphore,
+                snapshot.l2_block,
+                chunk,
+                pool,
+                stop_receiver,
+            )
+            .await?;
             options.events.chunk_recovered().await;
+            semaphore.release(permit);
             anyhow::Ok(())
         });
         future::try_join_all(chunk_tasks).await?;
@@ -269,7 +313,7 @@ impl AsyncTreeRecovery {
         }
 
         let finalize_latency = RECOVERY_METRICS.latency[&RecoveryStage::Finalize].start();
-        let mut tree = tree.into_inner();
+        let mut tree = state.into_inner().tree;
         let actual_root_hash = tree.root_hash().await;
         anyhow::ensure!(
             actual_root_hash == snapshot.expected_root_hash,
@@ -331,7 +375,8 @@ impl AsyncTreeRecovery {
     }
 
     async fn recover_key_chunk(
-        tree: &Mutex<AsyncTreeRecovery>,
+        state: &Mutex<RecoveryState>,
+        semaphore: &AdjustableSemaphore,
         snapshot_l2_block: L2BlockNumber,
         key_chunk: ops::RangeInclusive<H256>,
         pool: &ConnectionPool<Core>,
@@ -376,7 +421,7 @@ impl AsyncTreeRecovery {
             );
         }
 
-        let all_entries = all_entries
+        let mut all_entries: Vec<_> = all_entries
             .into_iter()
             .map(|entry| TreeEntry {
                 key: entry.tree_key(),
@@ -386,16 +431,24 @@ impl AsyncTreeRecovery {
             .collect();
         let lock_tree_latency =
             RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::LockTree].start();
-        let mut tree = tree.lock().await;
-        lock_tree_latency.observe();
-
-        if *stop_receiver.borrow() {
-            return Ok(());
-        }
+        let mut state = state.lock().await;
+        let lock_tree_latency = lock_tree_latency.observe();
+        state.tuner.observe_lock_tree(lock_tree_latency);
 
         let extend_tree_latency =
             RECOVERY_METRICS.chunk_latency[&ChunkRecoveryStage::ExtendTree].start();
-        tree.extend(all_entries).await;
+        // The tree is extended with sub-chunks in the reverse key order, so that the first entry in the chunk
+        // is persisted last. Thus, if recovery is interrupted in the middle of the chunk, the chunk won't be filtered out
+        // by `Self::filter_chunks()` after a restart.
+        while !all_entries.is_empty() {
+            if *stop_receiver.borrow() {
+                return Ok(());
+            }
+            let extend_chunk_size = state.tuner.params().extend_chunk_size;
+            let sub_chunk_start = all_entries.len().saturating_sub(extend_chunk_size);
+            let sub_chunk = all_entries.split_off(sub_chunk_start);
+            state.extend(sub_chunk, semaphore).await;
+        }
         let extend_tree_latency = extend_tree_latency.observe();
         tracing::debug!(
             "Extended Merkle tree with entries for chunk {key_chunk:?} in {extend_tree_latency:?}"
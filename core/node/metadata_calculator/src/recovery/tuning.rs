@@ -0,0 +1,400 @@
+//! Adaptive tuning of Merkle tree recovery parameters.
+//!
+//! Optimal recovery parameters depend heavily on the environment (e.g., I/O capacity of the node, Postgres latency
+//! and the tree size), so instead of exposing them as configuration, they are adjusted at runtime based on
+//! measurements collected during recovery:
+//!
+//! - If the tree spends a significant share of time waiting for RocksDB writes (i.e., writes are stalled),
+//!   the persistence buffer is increased; once it reaches its maximum capacity, the tree is extended with smaller
+//!   sub-chunks. Conversely, if writes are fast, sub-chunks are increased, and the buffer is shrunk.
+//! - The multi-get chunk size is adjusted using hill climbing: it is periodically changed in one direction,
+//!   and the change is kept only if it improves the multi-get latency per entry.
+//! - If chunks loaded from Postgres wait for the tree for a long time, the number of concurrently loaded chunks
+//!   is decreased since loading is faster than processing. If the tree is starved for data, the concurrency
+//!   is increased.
+//!
+//! Chunk boundaries are never changed since they must be the same for the entire recovery.
+
+use std::{sync::Mutex, time::Duration};
+
+use tokio::sync::{Semaphore, SemaphorePermit};
+use zksync_merkle_tree::recovery::RecoveryChunkStats;
+
+use crate::metrics::{RecoveryParam, RECOVERY_METRICS};
+
+/// Recovery parameters adjusted at runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct RecoveryParams {
+    /// Maximum number of chunks loaded from Postgres concurrently.
+    pub concurrency_limit: usize,
+    /// Maximum number of entries the tree is extended with at once. Chunks loaded from Postgres
+    /// are split into sub-chunks of this size.
+    pub extend_chunk_size: usize,
+    /// Chunk size for RocksDB multi-get operations.
+    pub multi_get_chunk_size: usize,
+    /// Maximum number of sub-chunks queued for persistence.
+    pub persistence_buffer_capacity: usize,
+}
+
+impl RecoveryParams {
+    fn report(&self) {
+        let params = [
+            (RecoveryParam::ConcurrencyLimit, self.concurrency_limit),
+            (RecoveryParam::ExtendChunkSize, self.extend_chunk_size),
+            (RecoveryParam::MultiGetChunkSize, self.multi_get_chunk_size),
+            (
+                RecoveryParam::PersistenceBufferCapacity,
+                self.persistence_buffer_capacity,
+            ),
+        ];
+        for (param, value) in params {
+            RECOVERY_METRICS.tuned_params[&param].set(value);
+        }
+    }
+}
+
+/// Direction in which the multi-get chunk size is changed during hill climbing.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ProbeDirection {
+    Increase,
+    Decrease,
+}
+
+impl ProbeDirection {
+    fn reverse(self) -> Self {
+        match self {
+            Self::Increase => Self::Decrease,
+            Self::Decrease => Self::Increase,
+        }
+    }
+}
+
+/// Change of the multi-get chunk size that is being evaluated.
+#[derive(Debug, Clone, Copy)]
+struct MultiGetProbe {
+    prev_chunk_size: usize,
+    prev_latency_per_entry: f64,
+}
+
+/// Measurements accumulated since the last adjustment of parameters.
+#[derive(Debug, Default)]
+struct MeasurementWindow {
+    extend_count: usize,
+    entry_count: usize,
+    total_latency: Duration,
+    multi_get_latency: Duration,
+    write_latency: Duration,
+    lock_tree_latency: Duration,
+}
+
+/// Adaptive controller for recovery parameters.
+#[derive(Debug)]
+pub(super) struct RecoveryTuner {
+    params: RecoveryParams,
+    max_concurrency_limit: usize,
+    max_extend_chunk_size: usize,
+    window: MeasurementWindow,
+    multi_get_direction: ProbeDirection,
+    multi_get_probe: Option<MultiGetProbe>,
+}
+
+impl RecoveryTuner {
+    /// Number of tree extensions after which parameters are adjusted.
+    const WINDOW_SIZE: usize = 4;
+    /// If the share of time spent waiting for writes exceeds this value, writes are considered stalled.
+    const HIGH_WRITE_RATIO: f64 = 0.25;
+    /// If the share of time spent waiting for writes is lower than this value, writes are considered fast.
+    const LOW_WRITE_RATIO: f64 = 0.05;
+    /// Relative improvement of the multi-get latency required to keep a multi-get chunk size change.
+    const MULTI_GET_IMPROVEMENT: f64 = 0.1;
+
+    const MIN_EXTEND_CHUNK_SIZE: usize = 5_000;
+    const MIN_MULTI_GET_CHUNK_SIZE: usize = 100;
+    const MAX_MULTI_GET_CHUNK_SIZE: usize = 100_000;
+    const MAX_PERSISTENCE_BUFFER_CAPACITY: usize = 8;
+
+    /// Creates a tuner with the initial parameters. `max_extend_chunk_size` should be equal to the size of chunks
+    /// loaded from Postgres.
+    pub fn new(
+        max_concurrency_limit: usize,
+        max_extend_chunk_size: usize,
+        multi_get_chunk_size: usize,
+        persistence_buffer_capacity: usize,
+    ) -> Self {
+        let params = RecoveryParams {
+            concurrency_limit: max_concurrency_limit,
+            extend_chunk_size: max_extend_chunk_size,
+            multi_get_chunk_size: multi_get_chunk_size.clamp(
+                Self::MIN_MULTI_GET_CHUNK_SIZE,
+                Self::MAX_MULTI_GET_CHUNK_SIZE,
+            ),
+            persistence_buffer_capacity,
+        };
+        params.report();
+        Self {
+            params,
+            max_concurrency_limit,
+            max_extend_chunk_size,
+            window: MeasurementWindow::default(),
+            multi_get_direction: ProbeDirection::Increase,
+            multi_get_probe: None,
+        }
+    }
+
+    pub fn params(&self) -> RecoveryParams {
+        self.params
+    }
+
+    /// Records the time a chunk loaded from Postgres has waited for the tree.
+    pub fn observe_lock_tree(&mut self, latency: Duration) {
+        self.window.lock_tree_latency += latency;
+    }
+
+    /// Records stats for extending the tree. Returns new parameters if they were adjusted.
+    pub fn observe_extend(&mut self, stats: RecoveryChunkStats) -> Option<RecoveryParams> {
+        let window = &mut self.window;
+        window.extend_count += 1;
+        window.entry_count += stats.entry_count;
+        window.total_latency += stats.total_latency;
+        window.multi_get_latency += stats.multi_get_latency;
+        window.write_latency += stats.write_latency;
+        if window.extend_count < Self::WINDOW_SIZE {
+            return None;
+        }
+
+        let window = std::mem::take(&mut self.window);
+        let prev_params = self.params;
+        self.adjust(&window);
+        if self.params == prev_params {
+            return None;
+        }
+        tracing::debug!(
+            "Adjusted recovery params based on {window:?}: {prev_params:?} -> {:?}",
+            self.params
+        );
+        self.params.report();
+        Some(self.params)
+    }
+
+    fn adjust(&mut self, window: &MeasurementWindow) {
+        if window.total_latency.is_zero() || window.entry_count == 0 {
+            return;
+        }
+        self.adjust_persistence(window);
+        self.adjust_multi_get_chunk_size(window);
+        self.adjust_concurrency(window);
+    }
+
+    fn adjust_persistence(&mut self, window: &MeasurementWindow) {
+        let params = &mut self.params;
+        let write_ratio = window.write_latency.as_secs_f64() / window.total_latency.as_secs_f64();
+        if write_ratio > Self::HIGH_WRITE_RATIO {
+            if params.persistence_buffer_capacity < Self::MAX_PERSISTENCE_BUFFER_CAPACITY {
+                params.persistence_buffer_capacity = (params.persistence_buffer_capacity * 2)
+                    .clamp(1, Self::MAX_PERSISTENCE_BUFFER_CAPACITY);
+            } else {
+                params.extend_chunk_size =
+                    (params.extend_chunk_size / 2).max(Self::MIN_EXTEND_CHUNK_SIZE);
+            }
+        } else if write_ratio < Self::LOW_WRITE_RATIO {
+            if params.extend_chunk_size < self.max_extend_chunk_size {
+                params.extend_chunk_size =
+                    (params.extend_chunk_size * 2).min(self.max_extend_chunk_size);
+            } else if params.persistence_buffer_capacity > 1 {
+                params.persistence_buffer_capacity -= 1;
+            }
+        }
+    }
+
+    fn adjust_multi_get_chunk_size(&mut self, window: &MeasurementWindow) {
+        let latency_per_entry = window.multi_get_latency.as_secs_f64() / window.entry_count as f64;
+        let chunk_size = &mut self.params.multi_get_chunk_size;
+
+        if let Some(probe) = self.multi_get_probe.take() {
+            let threshold = probe.prev_latency_per_entry * (1.0 - Self::MULTI_GET_IMPROVEMENT);
+            if latency_per_entry > threshold {
+                // The change didn't lead to an improvement; revert it and probe in the other direction next time.
+                *chunk_size = probe.prev_chunk_size;
+                self.multi_get_direction = self.multi_get_direction.reverse();
+            }
+            return;
+        }
+
+        let new_chunk_size = match self.multi_get_direction {
+            ProbeDirection::Increase => (*chunk_size * 2).min(Self::MAX_MULTI_GET_CHUNK_SIZE),
+            ProbeDirection::Decrease => (*chunk_size / 2).max(Self::MIN_MULTI_GET_CHUNK_SIZE),
+        };
+        if new_chunk_size == *chunk_size {
+            self.multi_get_direction = self.multi_get_direction.reverse();
+            return;
+        }
+        self.multi_get_probe = Some(MultiGetProbe {
+            prev_chunk_size: *chunk_size,
+            prev_latency_per_entry: latency_per_entry,
+        });
+        *chunk_size = new_chunk_size;
+    }
+
+    fn adjust_concurrency(&mut self, window: &MeasurementWindow) {
+        let concurrency_limit = &mut self.params.concurrency_limit;
+        if window.lock_tree_latency > window.total_latency {
+            // Chunks are loaded faster than they are processed by the tree.
+            *concurrency_limit = concurrency_limit.saturating_sub(1).max(1);
+        } else if window.lock_tree_latency < window.total_latency / 10 {
+            // The tree is starved for data.
+            *concurrency_limit = (*concurrency_limit + 1).min(self.max_concurrency_limit);
+        }
+    }
+}
+
+/// Semaphore with the number of permits that can be changed at runtime.
+#[derive(Debug)]
+pub(super) struct AdjustableSemaphore {
+    inner: Semaphore,
+    /// Number of permits that should be forgotten once they are released.
+    excess_permits: Mutex<usize>,
+}
+
+impl AdjustableSemaphore {
+    pub fn new(permits: usize) -> Self {
+        Self {
+            inner: Semaphore::new(permits),
+            excess_permits: Mutex::new(0),
+        }
+    }
+
+    pub async fn acquire(&self) -> SemaphorePermit<'_> {
+        self.inner
+            .acquire()
+            .await
+            .expect("semaphore is never closed")
+    }
+
+    pub fn release(&self, permit: SemaphorePermit<'_>) {
+        let mut excess_permits = self.excess_permits.lock().expect("semaphore is poisoned");
+        if *excess_permits > 0 {
+            *excess_permits -= 1;
+            permit.forget();
+        }
+    }
+
+    /// Changes the number of permits from `prev_permits` to `new_permits`.
+    pub fn adjust(&self, prev_permits: usize, new_permits: usize) {
+        let mut excess_permits = self.excess_permits.lock().expect("semaphore is poisoned");
+        let prev_permits = prev_permits + *excess_permits;
+        // ^ Excess permits are still alive, so they must be accounted for
+        if new_permits >= prev_permits {
+            *excess_permits = 0;
+            self.inner.add_permits(new_permits - prev_permits);
+        } else {
+            *excess_permits = prev_permits - new_permits;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stats(total_ms: u64, multi_get_ms: u64, write_ms: u64) -> RecoveryChunkStats {
+        RecoveryChunkStats {
+            entry_count: 200_000,
+            total_latency: Duration::from_millis(total_ms),
+            multi_get_latency: Duration::from_millis(multi_get_ms),
+            write_latency: Duration::from_millis(write_ms),
+        }
+    }
+
+    fn observe_window(
+        tuner: &mut RecoveryTuner,
+        stats: RecoveryChunkStats,
+    ) -> Option<RecoveryParams> {
+        for _ in 1..RecoveryTuner::WINDOW_SIZE {
+            assert_eq!(tuner.observe_extend(stats), None);
+        }
+        tuner.observe_extend(stats)
+    }
+
+    #[test]
+    fn tuning_on_stalled_writes() {
+        let mut tuner = RecoveryTuner::new(4, 200_000, 500, 1);
+        let params = observe_window(&mut tuner, stats(1_000, 100, 500)).unwrap();
+        assert_eq!(params.persistence_buffer_capacity, 2);
+        assert_eq!(params.extend_chunk_size, 200_000);
+        assert_eq!(params.multi_get_chunk_size, 1_000); // probing a larger chunk size
+
+        for expected_capacity in [4, 8] {
+            let params = observe_window(&mut tuner, stats(1_000, 100, 500)).unwrap();
+            assert_eq!(params.persistence_buffer_capacity, expected_capacity);
+        }
+        let params = observe_window(&mut tuner, stats(1_000, 100, 500)).unwrap();
+        assert_eq!(params.persistence_buffer_capacity, 8);
+        assert_eq!(params.extend_chunk_size, 100_000);
+
+        // Writes are fast now.
+        let params = observe_window(&mut tuner, stats(1_000, 100, 10)).unwrap();
+        assert_eq!(params.extend_chunk_size, 200_000);
+        assert_eq!(params.persistence_buffer_capacity, 8);
+        let params = observe_window(&mut tuner, stats(1_000, 100, 10)).unwrap();
+        assert_eq!(params.extend_chunk_size, 200_000);
+        assert_eq!(params.persistence_buffer_capacity, 7);
+    }
+
+    #[test]
+    fn tuning_multi_get_chunk_size() {
+        let mut tuner = RecoveryTuner::new(4, 200_000, 500, 1);
+        let moderate_stats = stats(1_000, 400, 100);
+        let params = observe_window(&mut tuner, moderate_stats).unwrap();
+        assert_eq!(params.multi_get_chunk_size, 1_000);
+
+        // Multi-get latency has improved; the chunk size should be retained.
+        let params = observe_window(&mut tuner, stats(1_000, 200, 100));
+        assert_eq!(params, None);
+        let params = observe_window(&mut tuner, stats(1_000, 200, 100)).unwrap();
+        assert_eq!(params.multi_get_chunk_size, 2_000);
+
+        // Multi-get latency has worsened; the chunk size should be reverted.
+        let params = observe_window(&mut tuner, stats(1_000, 300, 100)).unwrap();
+        assert_eq!(params.multi_get_chunk_size, 1_000);
+        // ...and the next probe should decrease the chunk size.
+        let params = observe_window(&mut tuner, stats(1_000, 200, 100)).unwrap();
+        assert_eq!(params.multi_get_chunk_size, 500);
+    }
+
+    #[test]
+    fn tuning_concurrency() {
+        let mut tuner = RecoveryTuner::new(4, 200_000, 100_000, 1);
+        // Chunks wait for the tree for a long time.
+        tuner.observe_lock_tree(Duration::from_secs(10));
+        let params = observe_window(&mut tuner, stats(1_000, 0, 100)).unwrap();
+        assert_eq!(params.concurrency_limit, 3);
+        tuner.observe_lock_tree(Duration::from_secs(10));
+        let params = observe_window(&mut tuner, stats(1_000, 0, 100)).unwrap();
+        assert_eq!(params.concurrency_limit, 2);
+
+        // The tree is starved.
+        tuner.observe_lock_tree(Duration::from_millis(1));
+        let params = observe_window(&mut tuner, stats(1_000, 0, 100)).unwrap();
+        assert_eq!(params.concurrency_limit, 3);
+    }
+
+    #[tokio::test]
+    async fn adjustable_semaphore() {
+        let semaphore = AdjustableSemaphore::new(2);
+        let first_permit = semaphore.acquire().await;
+        let second_permit = semaphore.acquire().await;
+        semaphore.adjust(2, 1);
+        semaphore.release(first_permit);
+        assert_eq!(semaphore.inner.available_permits(), 0);
+        semaphore.release(second_permit);
+        assert_eq!(semaphore.inner.available_permits(), 1);
+
+        semaphore.adjust(1, 3);
+        assert_eq!(semaphore.inner.available_permits(), 3);
+        let permit = semaphore.acquire().await;
+        semaphore.adjust(3, 2);
+        semaphore.adjust(2, 3);
+        semaphore.release(permit);
+        assert_eq!(semaphore.inner.available_permits(), 3);
+    }
+}
@@ -5,6 +5,7 @@ use std::{future::Future, ops, panic, path::Path, sync::Arc, time::Duration};
 use assert_matches::assert_matches;
 use itertools::Itertools;
 use tempfile::TempDir;
+use test_casing::test_casing;
 use tokio::sync::{mpsc, watch};
 use zksync_config::configs::{
     chain::OperationsManagerConfig,
@@ -53,6 +54,7 @@ pub(super) fn mock_config(db_path: &Path) -> MetadataCalculatorConfig {
         include_indices_and_filters_in_block_cache: false,
         memtable_capacity: 16 << 20,            // 16 MiB
         stalled_writes_timeout: Duration::ZERO, // writes should never be stalled in tests
+        tree_persistence_buffer: None,
     }
 }
 
@@ -175,8 +177,9 @@ async fn status_receiver_has_correct_states() {
         .unwrap();
 }
 
+#[test_casing(2, [false, true])]
 #[tokio::test]
-async fn multi_l1_batch_workflow() {
+async fn multi_l1_batch_workflow(parallel_persistence: bool) {
     let pool = ConnectionPool::<Core>::test_pool().await;
 
     // Collect all storage logs in a single L1 batch
@@ -187,11 +190,23 @@ async fn multi_l1_batch_workflow() {
 
     // Collect the same logs in multiple L1 batches
     let temp_dir = TempDir::new().expect("failed get temporary directory for RocksDB");
-    let (calculator, object_store) = setup_calculator(temp_dir.path(), pool.clone()).await;
+    let (mut calculator, object_store) = setup_calculator(temp_dir.path(), pool.clone()).await;
+    if parallel_persistence {
+        calculator.config.tree_persistence_buffer = Some(3);
+    }
     reset_db_state(&pool, 10).await;
     let multi_block_root_hash = run_calculator(calculator).await;
     assert_eq!(multi_block_root_hash, root_hash);
 
+    // Check that all changes were persisted.
+    let (calculator, _) = setup_calculator(temp_dir.path(), pool).await;
+    let tree = calculator.create_tree().await.unwrap();
+    let GenericAsyncTree::Ready(tree) = tree else {
+        panic!("Unexpected tree state: {tree:?}");
+    };
+    assert_eq!(tree.next_l1_batch_number(), L1BatchNumber(11));
+    assert_eq!(tree.root_hash(), root_hash);
+
     let mut prev_index = None;
     for l1_batch_number in 1..=10 {
         let l1_batch_number = L1BatchNumber(l1_batch_number);
@@ -24,6 +24,7 @@ pub(super) struct TreeUpdater {
     tree: AsyncTree,
     max_l1_batches_per_iter: usize,
     object_store: Option<Arc<dyn ObjectStore>>,
+    consistency_cursor: Option<watch::Receiver<Option<L1BatchNumber>>>,
 }
 
 impl TreeUpdater {
@@ -36,9 +37,44 @@ impl TreeUpdater {
             tree,
             max_l1_batches_per_iter,
             object_store,
+            consistency_cursor: None,
         }
     }
 
+    /// Persists progress reported by the incremental consistency check. The cursor is persisted by the updater
+    /// since it's the only component writing to the tree.
+    pub fn with_consistency_cursor(
+        mut self,
+        cursor: watch::Receiver<Option<L1BatchNumber>>,
+    ) -> Self {
+        self.consistency_cursor = Some(cursor);
+        self
+    }
+
+    async fn save_consistency_cursor(&mut self) -> anyhow::Result<()> {
+        let Some(cursor) = &mut self.consistency_cursor else {
+            return Ok(());
+        };
+        match cursor.has_changed() {
+            Ok(true) => { /* Save the updated cursor */ }
+            Ok(false) => return Ok(()),
+            Err(_) => {
+                // The consistency check has terminated; save its last reported progress and stop tracking it.
+                let next_l1_batch = *cursor.borrow();
+                self.consistency_cursor = None;
+                if let Some(next_l1_batch) = next_l1_batch {
+                    self.tree.save_consistency_cursor(next_l1_batch).await?;
+                }
+                return Ok(());
+            }
+        }
+        let next_l1_batch = *cursor.borrow_and_update();
+        if let Some(next_l1_batch) = next_l1_batch {
+            self.tree.save_consistency_cursor(next_l1_batch).await?;
+        }
+        Ok(())
+    }
+
     async fn process_l1_batch(
         &mut self,
         l1_batch: L1BatchWithLogs,
@@ -88,6 +124,9 @@ impl TreeUpdater {
         l1_batch_numbers: ops::RangeInclusive<u32>,
     ) -> anyhow::Result<L1BatchNumber> {
         let tree_mode = self.tree.mode();
+        // With parallel persistence, saving the tree only queues changes for persistence, so we save the tree
+        // after each L1 batch. This allows persisting changes concurrently with processing subsequent L1 batches.
+        let save_after_each_batch = self.tree.is_parallel_persistence_enabled();
         let start = Instant::now();
         tracing::info!("Processing L1 batches #{l1_batch_numbers:?} in {tree_mode:?} mode");
         let first_l1_batch_number = L1BatchNumber(*l1_batch_numbers.start());
@@ -155,13 +194,16 @@ impl TreeUpdater {
             save_postgres_latency.observe();
             tracing::info!("Updated metadata for L1 batch #{l1_batch_number} in Postgres");
 
+            if save_after_each_batch {
+                self.save_tree().await?;
+            }
             updated_headers.push(header);
             l1_batch_data = next_l1_batch_data;
         }
 
-        let save_rocksdb_latency = METRICS.start_stage(TreeUpdateStage::SaveRocksdb);
-        self.tree.save().await?;
-        save_rocksdb_latency.observe();
+        if !save_after_each_batch {
+            self.save_tree().await?;
+        }
         MetadataCalculator::update_metrics(&updated_headers, total_logs, start);
 
         Ok(last_l1_batch_number + 1)
@@ -198,6 +240,13 @@ impl TreeUpdater {
         Ok(())
     }
 
+    async fn save_tree(&mut self) -> anyhow::Result<()> {
+        let save_rocksdb_latency = METRICS.start_stage(TreeUpdateStage::SaveRocksdb);
+        self.tree.save().await?;
+        save_rocksdb_latency.observe();
+        Ok(())
+    }
+
     /// The processing loop for this updater.
     pub async fn loop_updating_tree(
         mut self,
@@ -280,6 +329,7 @@ impl TreeUpdater {
 
             let snapshot = *next_l1_batch_to_seal;
             self.step(storage, &mut next_l1_batch_to_seal).await?;
+            self.save_consistency_cursor().await?;
             let delay = if snapshot == *next_l1_batch_to_seal {
                 tracing::trace!(
                     "Metadata calculator (next L1 batch: #{next_l1_batch_to_seal}) \
@@ -303,6 +353,7 @@ impl TreeUpdater {
                 () = delay => { /* The delay has passed */ }
             }
         }
-        Ok(())
+        self.save_consistency_cursor().await?;
+        self.tree.wait_for_persistence().await
     }
 }
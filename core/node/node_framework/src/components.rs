@@ -0,0 +1,237 @@
+//! Resolution of components selected by the node operator.
+//!
+//! A node binary typically allows selecting components to run (e.g., via a CLI arg), with each component
+//! corresponding to one or more wiring layers. Some components cannot run without other components
+//! (e.g., a Merkle tree API cannot run without a Merkle tree), and some use resources provided by other components
+//! if they are present. This module resolves the selected components into a complete list ordered so that
+//! the wiring layers can be added in this order.
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    hash::Hash,
+};
+
+/// Dependencies of a component.
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentDeps<C: 'static> {
+    /// Components that must run together with this component. They are included automatically if not selected.
+    pub requires: &'static [C],
+    /// Components that must be wired before this component if they are selected; they are not included automatically.
+    /// Used for components that optionally use resources provided by other components.
+    pub after: &'static [C],
+}
+
+impl<C: 'static> ComponentDeps<C> {
+    /// Component without dependencies.
+    pub const NONE: Self = Self {
+        requires: &[],
+        after: &[],
+    };
+}
+
+/// Errors that can occur during component resolution.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum ComponentResolutionError<C: fmt::Debug> {
+    /// Component requires another component, which was explicitly excluded.
+    #[error("component {component:?} requires {required:?}, which is explicitly excluded")]
+    ExcludedDependency { component: C, required: C },
+    /// Components depend on each other. The cycle is listed so that each component depends on the next one,
+    /// and the last one depends on the first one.
+    #[error("components have cyclic dependencies: {0:?}")]
+    Cycle(Vec<C>),
+    /// No components are selected.
+    #[error("no components are selected")]
+    Empty,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum VisitState {
+    InProgress,
+    Done,
+}
+
+/// Resolves selected components.
+///
+/// - `selected` are components selected by the operator. Components from `excluded` are removed from them.
+/// - Components required by the selected ones are included automatically, unless they are excluded;
+///   in the latter case, an error is returned.
+///
+/// Returns the resolved components in the order in which they should be wired: each component comes after
+/// the components it depends on.
+pub fn resolve_components<C>(
+    selected: &[C],
+    excluded: &[C],
+    deps: impl Fn(C) -> ComponentDeps<C>,
+) -> Result<Vec<C>, ComponentResolutionError<C>>
+where
+    C: Copy + Eq + Hash + fmt::Debug,
+{
+    let excluded: HashSet<_> = excluded.iter().copied().collect();
+    // Use a `Vec` to make the output order deterministic.
+    let mut included = Vec::new();
+    let mut included_set = HashSet::new();
+    for &component in selected {
+        if !excluded.contains(&component) && included_set.insert(component) {
+            included.push(component);
+        }
+    }
+
+    let mut idx = 0;
+    while idx < included.len() {
+        let component = included[idx];
+        for &required in deps(component).requires {
+            if excluded.contains(&required) {
+                return Err(ComponentResolutionError::ExcludedDependency {
+                    component,
+                    required,
+                });
+            }
+            if included_set.insert(required) {
+                tracing::info!("Including component {required:?} required by {component:?}");
+                included.push(required);
+            }
+        }
+        idx += 1;
+    }
+    if included.is_empty() {
+        return Err(ComponentResolutionError::Empty);
+    }
+
+    let mut sorter = TopologicalSorter {
+        deps: &deps,
+        included: &included_set,
+        states: HashMap::new(),
+        path: Vec::new(),
+        output: Vec::with_capacity(included.len()),
+    };
+    for &component in &included {
+        sorter.visit(component)?;
+    }
+    Ok(sorter.output)
+}
+
+/// Depth-first topological sort of components.
+struct TopologicalSorter<'a, C, F> {
+    deps: &'a F,
+    included: &'a HashSet<C>,
+    states: HashMap<C, VisitState>,
+    path: Vec<C>,
+    output: Vec<C>,
+}
+
+impl<C, F> TopologicalSorter<'_, C, F>
+where
+    C: Copy + Eq + Hash + fmt::Debug,
+    F: Fn(C) -> ComponentDeps<C>,
+{
+    fn visit(&mut self, component: C) -> Result<(), ComponentResolutionError<C>> {
+        match self.states.get(&component) {
+            Some(VisitState::Done) => return Ok(()),
+            Some(VisitState::InProgress) => {
+                let cycle_start = self
+                    .path
+                    .iter()
+                    .position(|&c| c == component)
+                    .expect("in-progress component is not on the path");
+                return Err(ComponentResolutionError::Cycle(
+                    self.path[cycle_start..].to_vec(),
+                ));
+            }
+            None => { /* continue processing */ }
+        }
+
+        self.states.insert(component, VisitState::InProgress);
+        self.path.push(component);
+        let deps = (self.deps)(component);
+        let optional_deps = deps.after.iter().filter(|&dep| self.included.contains(dep));
+        for &dep in deps.requires.iter().chain(optional_deps) {
+            self.visit(dep)?;
+        }
+        self.path.pop();
+        self.states.insert(component, VisitState::Done);
+        self.output.push(component);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    enum TestComponent {
+        Api,
+        Tree,
+        TreeApi,
+        StateKeeper,
+        Cyclic,
+        OtherCyclic,
+    }
+
+    fn deps(component: TestComponent) -> ComponentDeps<TestComponent> {
+        use TestComponent::*;
+
+        match component {
+            Api => ComponentDeps {
+                requires: &[],
+                after: &[StateKeeper, TreeApi],
+            },
+            TreeApi => ComponentDeps {
+                requires: &[Tree],
+                after: &[],
+            },
+            Cyclic => ComponentDeps {
+                requires: &[OtherCyclic],
+                after: &[],
+            },
+            OtherCyclic => ComponentDeps {
+                requires: &[Tree],
+                after: &[Cyclic],
+            },
+            Tree | StateKeeper => ComponentDeps::NONE,
+        }
+    }
+
+    #[test]
+    fn resolving_components() {
+        use TestComponent::*;
+
+        let resolved = resolve_components(&[Api, StateKeeper], &[], deps).unwrap();
+        assert_eq!(resolved, [StateKeeper, Api]);
+
+        let resolved = resolve_components(&[Api, TreeApi], &[], deps).unwrap();
+        assert_eq!(resolved, [Tree, TreeApi, Api]);
+        let resolved = resolve_components(&[TreeApi, Tree, TreeApi], &[], deps).unwrap();
+        assert_eq!(resolved, [Tree, TreeApi]);
+
+        let resolved = resolve_components(&[Api, StateKeeper], &[StateKeeper], deps).unwrap();
+        assert_eq!(resolved, [Api]);
+    }
+
+    #[test]
+    fn resolution_errors() {
+        use TestComponent::*;
+
+        let err = resolve_components(&[Api, TreeApi], &[Tree], deps).unwrap_err();
+        assert_eq!(
+            err,
+            ComponentResolutionError::ExcludedDependency {
+                component: TreeApi,
+                required: Tree,
+            }
+        );
+
+        let err = resolve_components(&[Api], &[Api], deps).unwrap_err();
+        assert_eq!(err, ComponentResolutionError::Empty);
+
+        let err = resolve_components(&[Cyclic], &[], deps).unwrap_err();
+        assert_eq!(
+            err,
+            ComponentResolutionError::Cycle(vec![Cyclic, OtherCyclic])
+        );
+        // The optional dependency doesn't create a cycle if `Cyclic` is not selected.
+        let resolved = resolve_components(&[OtherCyclic], &[], deps).unwrap();
+        assert_eq!(resolved, [Tree, OtherCyclic]);
+    }
+}
@@ -1,7 +1,10 @@
+use std::{num::NonZeroUsize, path::PathBuf};
+
 use anyhow::Context as _;
 use zksync_concurrency::{ctx, scope};
 use zksync_config::configs::consensus::{ConsensusConfig, ConsensusSecrets};
 use zksync_dal::{ConnectionPool, Core};
+use zksync_health_check::{HealthUpdater, ReactiveHealthCheck};
 use zksync_node_consensus as consensus;
 use zksync_node_sync::{ActionQueueSender, SyncState};
 use zksync_web3_decl::client::{DynClient, L2};
@@ -9,6 +12,7 @@ use zksync_web3_decl::client::{DynClient, L2};
 use crate::{
     implementations::resources::{
         action_queue::ActionQueueSenderResource,
+        healthcheck::AppHealthCheckResource,
         main_node_client::MainNodeClientResource,
         pools::{MasterPool, PoolResource},
         sync_state::SyncStateResource,
@@ -29,6 +33,12 @@ pub struct ConsensusLayer {
     pub mode: Mode,
     pub config: Option<ConsensusConfig>,
     pub secrets: Option<ConsensusSecrets>,
+    /// Path to the file persisting the highest view signed by the validator. Required for external nodes
+    /// with a validator key.
+    pub validator_watermark_path: Option<PathBuf>,
+    /// Maximum number of L2 blocks fetched from the main node ahead of the state keeper. Only used
+    /// by external nodes.
+    pub fetcher_prefetch_depth: NonZeroUsize,
 }
 
 #[async_trait::async_trait]
@@ -87,12 +97,21 @@ impl WiringLayer for ConsensusLayer {
                     }
                 };
 
+                let (health_check, health_updater) = ReactiveHealthCheck::new("consensus");
+                let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+                app_health
+                    .insert_component(health_check)
+                    .map_err(WiringError::internal)?;
+
                 let task = FetcherTask {
                     config,
+                    validator_watermark_path: self.validator_watermark_path,
+                    prefetch_depth: self.fetcher_prefetch_depth,
                     pool,
                     main_node_client,
                     sync_state,
                     action_queue_sender,
+                    health_updater,
                 };
                 context.add_task(Box::new(task));
             }
@@ -139,10 +158,13 @@ impl Task for MainNodeConsensusTask {
 #[derive(Debug)]
 pub struct FetcherTask {
     config: Option<(ConsensusConfig, ConsensusSecrets)>,
+    validator_watermark_path: Option<PathBuf>,
+    prefetch_depth: NonZeroUsize,
     pool: ConnectionPool<Core>,
     main_node_client: Box<DynClient<L2>>,
     sync_state: SyncState,
     action_queue_sender: ActionQueueSender,
+    health_updater: HealthUpdater,
 }
 
 #[async_trait::async_trait]
@@ -163,10 +185,14 @@ impl Task for FetcherTask {
             s.spawn_bg(consensus::era::run_en(
                 &root_ctx,
                 self.config,
+                self.validator_watermark_path,
                 self.pool,
                 self.sync_state,
                 self.main_node_client,
                 self.action_queue_sender,
+                self.health_updater,
+                self.prefetch_depth,
+                None,
             ));
             ctx.wait(stop_receiver.0.wait_for(|stop| *stop)).await??;
             Ok(())
@@ -4,7 +4,7 @@ use zksync_dal::{ConnectionPool, Core};
 use crate::{
     implementations::resources::pools::{MasterPool, PoolResource, ReplicaPool},
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{ShutdownPhase, Task},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -50,6 +50,10 @@ impl Task for ContractVerificationApiTask {
         "contract_verification_api"
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Ingress
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         zksync_contract_verification_server::start_server(
             self.master_pool,
@@ -9,6 +9,7 @@ use crate::{
     implementations::resources::{
         circuit_breakers::CircuitBreakersResource,
         eth_interface::{BoundEthInterfaceForBlobsResource, BoundEthInterfaceResource},
+        healthcheck::AppHealthCheckResource,
         l1_tx_params::L1TxParamsResource,
         object_store::ObjectStoreResource,
         pools::{MasterPool, PoolResource, ReplicaPool},
@@ -64,6 +65,11 @@ impl WiringLayer for EthTxManagerLayer {
             eth_client_blobs,
         );
 
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(eth_tx_manager_actor.health_check())
+            .map_err(WiringError::internal)?;
+
         context.add_task(Box::new(EthTxManagerTask {
             eth_tx_manager_actor,
         }));
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use zksync_config::{ContractsConfig, EthWatchConfig};
+use zksync_config::{configs::EthWatchSourceConfig, ContractsConfig, EthWatchConfig};
 use zksync_contracts::governance_contract;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_eth_watch::{EthHttpQueryClient, EthWatch};
@@ -50,7 +50,7 @@ impl WiringLayer for EthWatchLayer {
             .map(|a| a.state_transition_proxy_addr);
 
         let eth_client = EthHttpQueryClient::new(
-            client,
+            client.clone(),
             self.contracts_config.diamond_proxy_addr,
             self.contracts_config
                 .ecosystem_contracts
@@ -58,9 +58,26 @@ impl WiringLayer for EthWatchLayer {
             self.contracts_config.governance_addr,
             self.eth_watch_config.confirmations_for_eth_event,
         );
+        let additional_sources = self
+            .eth_watch_config
+            .additional_sources
+            .iter()
+            .map(|source| {
+                let source_client = EthHttpQueryClient::new(
+                    client.clone(),
+                    source.diamond_proxy_addr,
+                    source.state_transition_manager_addr,
+                    source.governance_addr,
+                    source.confirmations_for_eth_event,
+                );
+                (source.clone(), source_client)
+            })
+            .collect();
+
         context.add_task(Box::new(EthWatchTask {
             main_pool,
             client: eth_client,
+            additional_sources,
             governance_contract: governance_contract(),
             state_transition_manager_address,
             diamond_proxy_address: self.contracts_config.diamond_proxy_addr,
@@ -75,6 +92,7 @@ impl WiringLayer for EthWatchLayer {
 struct EthWatchTask {
     main_pool: ConnectionPool<Core>,
     client: EthHttpQueryClient,
+    additional_sources: Vec<(EthWatchSourceConfig, EthHttpQueryClient)>,
     governance_contract: Contract,
     state_transition_manager_address: Option<Address>,
     diamond_proxy_address: Address,
@@ -88,7 +106,7 @@ impl Task for EthWatchTask {
     }
 
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
-        let eth_watch = EthWatch::new(
+        let mut eth_watch = EthWatch::new(
             self.diamond_proxy_address,
             self.state_transition_manager_address,
             &self.governance_contract,
@@ -97,6 +115,15 @@ impl Task for EthWatchTask {
             self.poll_interval,
         )
         .await?;
+        for (source, source_client) in self.additional_sources {
+            eth_watch = eth_watch
+                .with_additional_source(
+                    source.diamond_proxy_addr,
+                    source.state_transition_manager_addr,
+                    Box::new(source_client),
+                )
+                .await?;
+        }
 
         eth_watch.run(stop_receiver.0).await
     }
@@ -0,0 +1,75 @@
+use zksync_config::configs::api::ExplorerApiConfig;
+use zksync_dal::{ConnectionPool, Core};
+use zksync_types::L2ChainId;
+
+use crate::{
+    implementations::resources::pools::{PoolResource, ReplicaPool},
+    service::{ServiceContext, StopReceiver},
+    task::{ShutdownPhase, Task},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the embedded block explorer API.
+#[derive(Debug)]
+pub struct ExplorerApiLayer {
+    config: ExplorerApiConfig,
+    l2_chain_id: L2ChainId,
+}
+
+impl ExplorerApiLayer {
+    pub fn new(config: ExplorerApiConfig, l2_chain_id: L2ChainId) -> Self {
+        Self {
+            config,
+            l2_chain_id,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ExplorerApiLayer {
+    fn layer_name(&self) -> &'static str {
+        "explorer_api_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let replica_pool = context
+            .get_resource::<PoolResource<ReplicaPool>>()
+            .await?
+            .get()
+            .await?;
+        context.add_task(Box::new(ExplorerApiTask {
+            replica_pool,
+            config: self.config,
+            l2_chain_id: self.l2_chain_id,
+        }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub struct ExplorerApiTask {
+    replica_pool: ConnectionPool<Core>,
+    config: ExplorerApiConfig,
+    l2_chain_id: L2ChainId,
+}
+
+#[async_trait::async_trait]
+impl Task for ExplorerApiTask {
+    fn name(&self) -> &'static str {
+        "explorer_api"
+    }
+
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Ingress
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        zksync_node_explorer_api::start_server(
+            self.replica_pool,
+            self.config,
+            self.l2_chain_id,
+            stop_receiver.0,
+        )
+        .await
+    }
+}
@@ -7,7 +7,7 @@ use zksync_node_api_server::healthcheck::HealthCheckHandle;
 use crate::{
     implementations::resources::healthcheck::AppHealthCheckResource,
     service::{ServiceContext, StopReceiver},
-    task::UnconstrainedTask,
+    task::{ShutdownPhase, UnconstrainedTask},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -57,6 +57,10 @@ impl UnconstrainedTask for HealthCheckTask {
         "healthcheck_server"
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Auxiliary
+    }
+
     async fn run_unconstrained(
         mut self: Box<Self>,
         mut stop_receiver: StopReceiver,
@@ -7,23 +7,25 @@ use zksync_config::configs::{
 use zksync_dal::{metrics::PostgresMetrics, ConnectionPool, Core};
 use zksync_house_keeper::{
     blocks_state_reporter::L1BatchMetricsReporter,
+    partition_manager::PartitionManager,
     periodic_job::PeriodicJob,
     prover::{
         FriGpuProverArchiver, FriProofCompressorJobRetryManager, FriProofCompressorQueueReporter,
         FriProverJobRetryManager, FriProverJobsArchiver, FriProverQueueReporter,
-        FriWitnessGeneratorJobRetryManager, FriWitnessGeneratorQueueReporter,
-        WaitingToQueuedFriWitnessJobMover,
+        FriWitnessGeneratorJobRetryManager, FriWitnessGeneratorQueueReporter, ProverJobMonitor,
+        ProverStageMaxAttempts, WaitingToQueuedFriWitnessJobMover,
     },
 };
 
 use crate::{
-    implementations::resources::pools::{PoolResource, ProverPool, ReplicaPool},
+    implementations::resources::pools::{MasterPool, PoolResource, ProverPool, ReplicaPool},
     service::{ServiceContext, StopReceiver},
     task::Task,
     wiring_layer::{WiringError, WiringLayer},
 };
 
 const SCRAPE_INTERVAL: Duration = Duration::from_secs(60);
+const PARTITION_MANAGER_INTERVAL_MS: u64 = 60_000;
 
 #[derive(Debug)]
 pub struct HouseKeeperLayer {
@@ -63,6 +65,10 @@ impl WiringLayer for HouseKeeperLayer {
         let replica_pool_resource = context.get_resource::<PoolResource<ReplicaPool>>().await?;
         let replica_pool = replica_pool_resource.get().await?;
 
+        // Partitions are managed using DDL statements, so they require the master pool.
+        let master_pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let master_pool = master_pool_resource.get_singleton().await?;
+
         let prover_pool_resource = context.get_resource::<PoolResource<ProverPool>>().await?;
         let prover_pool = prover_pool_resource.get().await?;
 
@@ -79,6 +85,13 @@ impl WiringLayer for HouseKeeperLayer {
             l1_batch_metrics_reporter,
         }));
 
+        let partition_manager = PartitionManager::new(
+            master_pool,
+            PartitionManager::DEFAULT_PARTITION_SIZE,
+            PARTITION_MANAGER_INTERVAL_MS,
+        );
+        context.add_task(Box::new(PartitionManagerTask { partition_manager }));
+
         let fri_prover_job_retry_manager = FriProverJobRetryManager::new(
             self.fri_prover_config.max_attempts,
             self.fri_prover_config.proof_generation_timeout(),
@@ -129,6 +142,25 @@ impl WiringLayer for HouseKeeperLayer {
             }));
         }
 
+        if let Some(monitoring_interval_ms) =
+            self.house_keeper_config.prover_job_monitor_interval_ms
+        {
+            let max_attempts = ProverStageMaxAttempts {
+                witness_generation: self.fri_witness_generator_config.max_attempts,
+                proving: self.fri_prover_config.max_attempts,
+                proof_compression: self.fri_proof_compressor_config.max_attempts,
+            };
+            let prover_job_monitor = ProverJobMonitor::new(
+                prover_pool.clone(),
+                self.house_keeper_config.prover_stage_slas(),
+                self.house_keeper_config
+                    .prover_job_monitor_stuck_job_threshold(),
+                max_attempts,
+                monitoring_interval_ms,
+            );
+            context.add_task(Box::new(ProverJobMonitorTask { prover_job_monitor }));
+        }
+
         let fri_witness_generator_stats_reporter = FriWitnessGeneratorQueueReporter::new(
             prover_pool.clone(),
             self.house_keeper_config
@@ -212,6 +244,22 @@ impl Task for L1BatchMetricsReporterTask {
     }
 }
 
+#[derive(Debug)]
+struct PartitionManagerTask {
+    partition_manager: PartitionManager,
+}
+
+#[async_trait::async_trait]
+impl Task for PartitionManagerTask {
+    fn name(&self) -> &'static str {
+        "partition_manager"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.partition_manager.run(stop_receiver.0).await
+    }
+}
+
 #[derive(Debug)]
 struct FriProverJobRetryManagerTask {
     fri_prover_job_retry_manager: FriProverJobRetryManager,
@@ -350,6 +398,22 @@ impl Task for FriProverJobArchiverTask {
     }
 }
 
+#[derive(Debug)]
+struct ProverJobMonitorTask {
+    prover_job_monitor: ProverJobMonitor,
+}
+
+#[async_trait::async_trait]
+impl Task for ProverJobMonitorTask {
+    fn name(&self) -> &'static str {
+        "prover_job_monitor"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.prover_job_monitor.run(stop_receiver.0).await
+    }
+}
+
 struct FriProverGpuArchiverTask {
     fri_prover_gpu_archiver: FriGpuProverArchiver,
 }
@@ -1,12 +1,13 @@
 use std::{
     net::{Ipv4Addr, SocketAddr},
     sync::Arc,
+    time::Duration,
 };
 
 use anyhow::Context as _;
 use zksync_config::configs::{api::MerkleTreeApiConfig, database::MerkleTreeMode};
 use zksync_metadata_calculator::{
-    LazyAsyncTreeReader, MetadataCalculator, MetadataCalculatorConfig,
+    LazyAsyncTreeReader, MerkleTreePruningTask, MetadataCalculator, MetadataCalculatorConfig,
 };
 use zksync_storage::RocksDB;
 
@@ -18,7 +19,7 @@ use crate::{
         web3_api::TreeApiClientResource,
     },
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{ShutdownPhase, Task},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -31,10 +32,12 @@ use crate::{
 /// - Resolves `ObjectStoreResource` (optional).
 /// - Adds `tree_health_check` to the `ResourceCollection<HealthCheckResource>`.
 /// - Adds `metadata_calculator` to the node.
+/// - Adds `tree_pruning` to the node (if pruning is configured).
 #[derive(Debug)]
 pub struct MetadataCalculatorLayer {
     config: MetadataCalculatorConfig,
     tree_api_config: Option<MerkleTreeApiConfig>,
+    pruning_poll_interval: Option<Duration>,
 }
 
 impl MetadataCalculatorLayer {
@@ -42,6 +45,7 @@ impl MetadataCalculatorLayer {
         Self {
             config,
             tree_api_config: None,
+            pruning_poll_interval: None,
         }
     }
 
@@ -49,6 +53,12 @@ impl MetadataCalculatorLayer {
         self.tree_api_config = Some(tree_api_config);
         self
     }
+
+    /// Enables Merkle tree pruning according to the pruning logs in Postgres.
+    pub fn with_pruning_config(mut self, poll_interval: Duration) -> Self {
+        self.pruning_poll_interval = Some(poll_interval);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -76,7 +86,7 @@ impl WiringLayer for MetadataCalculatorLayer {
             }
         };
 
-        let metadata_calculator = MetadataCalculator::new(
+        let mut metadata_calculator = MetadataCalculator::new(
             self.config,
             object_store.map(|store_resource| store_resource.0),
             main_pool,
@@ -89,6 +99,14 @@ impl WiringLayer for MetadataCalculatorLayer {
             .insert_custom_component(Arc::new(metadata_calculator.tree_health_check()))
             .map_err(WiringError::internal)?;
 
+        if let Some(poll_interval) = self.pruning_poll_interval {
+            let pruning_task = metadata_calculator.pruning_task(poll_interval);
+            app_health
+                .insert_component(pruning_task.health_check())
+                .map_err(WiringError::internal)?;
+            context.add_task(Box::new(TreePruningTask(pruning_task)));
+        }
+
         if let Some(tree_api_config) = self.tree_api_config {
             let bind_addr = (Ipv4Addr::UNSPECIFIED, tree_api_config.port).into();
             let tree_reader = metadata_calculator.tree_reader();
@@ -96,6 +114,15 @@ impl WiringLayer for MetadataCalculatorLayer {
                 bind_addr,
                 tree_reader,
             }));
+
+            if let Some(grpc_port) = tree_api_config.grpc_port {
+                let bind_addr = (Ipv4Addr::UNSPECIFIED, grpc_port).into();
+                let tree_reader = metadata_calculator.tree_reader();
+                context.add_task(Box::new(TreeGrpcApiTask {
+                    bind_addr,
+                    tree_reader,
+                }));
+            }
         }
 
         context.insert_resource(TreeApiClientResource(Arc::new(
@@ -122,6 +149,10 @@ impl Task for MetadataCalculatorTask {
         "metadata_calculator"
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Storage
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         let result = self.metadata_calculator.run(stop_receiver.0).await;
 
@@ -133,7 +164,26 @@ impl Task for MetadataCalculatorTask {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+pub struct TreePruningTask(MerkleTreePruningTask);
+
+#[async_trait::async_trait]
+impl Task for TreePruningTask {
+    fn name(&self) -> &'static str {
+        "tree_pruning"
+    }
+
+    // Pruning can be disabled and re-enabled at runtime; restarted instances reuse the tree pruner.
+    fn restart(&self) -> Option<Box<dyn Task>> {
+        Some(Box::new(self.clone()))
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(stop_receiver.0).await
+    }
+}
+
+#[derive(Debug, Clone)]
 pub struct TreeApiTask {
     bind_addr: SocketAddr,
     tree_reader: LazyAsyncTreeReader,
@@ -145,6 +195,15 @@ impl Task for TreeApiTask {
         "tree_api"
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Ingress
+    }
+
+    // The server can be disabled and re-enabled at runtime.
+    fn restart(&self) -> Option<Box<dyn Task>> {
+        Some(Box::new(self.clone()))
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         self.tree_reader
             .wait()
@@ -154,3 +213,34 @@ impl Task for TreeApiTask {
             .await
     }
 }
+
+#[derive(Debug, Clone)]
+pub struct TreeGrpcApiTask {
+    bind_addr: SocketAddr,
+    tree_reader: LazyAsyncTreeReader,
+}
+
+#[async_trait::async_trait]
+impl Task for TreeGrpcApiTask {
+    fn name(&self) -> &'static str {
+        "tree_grpc_api"
+    }
+
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Ingress
+    }
+
+    // The server can be disabled and re-enabled at runtime.
+    fn restart(&self) -> Option<Box<dyn Task>> {
+        Some(Box::new(self.clone()))
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.tree_reader
+            .wait()
+            .await
+            .context("Cannot initialize tree reader")?
+            .run_grpc_server(self.bind_addr, stop_receiver.0)
+            .await
+    }
+}
@@ -5,6 +5,7 @@ pub mod consistency_checker;
 pub mod contract_verification_api;
 pub mod eth_sender;
 pub mod eth_watch;
+pub mod explorer_api;
 pub mod healtcheck_server;
 pub mod house_keeper;
 pub mod l1_gas;
@@ -14,8 +15,11 @@ pub mod pk_signing_eth_client;
 pub mod pools_layer;
 pub mod prometheus_exporter;
 pub mod proof_data_handler;
+pub mod protective_reads_verifier;
 pub mod query_eth_client;
 pub mod sigint;
 pub mod state_keeper;
+pub mod task_control_server;
 pub mod tee_verifier_input_producer;
+pub mod vm_runner_replay;
 pub mod web3_api;
@@ -4,7 +4,7 @@ use zksync_health_check::{HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use crate::{
     implementations::resources::healthcheck::AppHealthCheckResource,
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{ShutdownPhase, Task},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -54,6 +54,10 @@ impl Task for PrometheusExporterTask {
         "prometheus_exporter"
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Auxiliary
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         let prometheus_task = self.config.run(stop_receiver.0);
         self.prometheus_health_updater
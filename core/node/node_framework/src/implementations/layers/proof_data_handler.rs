@@ -11,7 +11,7 @@ use crate::{
         pools::{MasterPool, PoolResource},
     },
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{ShutdownPhase, Task},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -77,6 +77,10 @@ impl Task for ProofDataHandlerTask {
         "proof_data_handler"
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Ingress
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         zksync_proof_data_handler::run_server(
             self.proof_data_handler_config,
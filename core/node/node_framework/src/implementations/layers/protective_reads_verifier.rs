@@ -0,0 +1,104 @@
+use zksync_vm_runner::{
+    CheckpointedIo, ConcurrentOutputHandlerFactoryTask, ProtectiveReadsIo, ProtectiveReadsVerifier,
+    ProtectiveReadsVerifierConfig, ProtectiveReadsVerifierTasks, StorageSyncTask,
+};
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{MasterPool, PoolResource},
+    },
+    service::{ServiceContext, StopReceiver},
+    task::Task,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the protective reads verifier, a VM runner component that recomputes protective reads
+/// for sealed L1 batches and compares them with the ones persisted by the state keeper.
+///
+/// ## Effects
+///
+/// - Resolves `PoolResource<MasterPool>`.
+/// - Adds a `protective_reads_verifier` health check to the `AppHealthCheckResource`.
+/// - Adds `protective_reads_verifier`, `protective_reads_verifier_storage_sync`
+///   and `protective_reads_verifier_output_handler` tasks to the node.
+#[derive(Debug)]
+pub struct ProtectiveReadsVerifierLayer {
+    config: ProtectiveReadsVerifierConfig,
+}
+
+impl ProtectiveReadsVerifierLayer {
+    pub fn new(config: ProtectiveReadsVerifierConfig) -> Self {
+        Self { config }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ProtectiveReadsVerifierLayer {
+    fn layer_name(&self) -> &'static str {
+        "protective_reads_verifier_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let main_pool = pool_resource.get().await?;
+
+        let (verifier, tasks) = ProtectiveReadsVerifier::new(main_pool, self.config).await?;
+        let ProtectiveReadsVerifierTasks {
+            loader_task,
+            output_handler_factory_task,
+        } = tasks;
+
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(verifier.health_check())
+            .map_err(WiringError::internal)?;
+
+        context.add_task(Box::new(StorageSyncTaskWrapper(loader_task)));
+        context.add_task(Box::new(OutputHandlerTask(output_handler_factory_task)));
+        context.add_task(Box::new(ProtectiveReadsVerifierTask(verifier)));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ProtectiveReadsVerifierTask(ProtectiveReadsVerifier);
+
+#[async_trait::async_trait]
+impl Task for ProtectiveReadsVerifierTask {
+    fn name(&self) -> &'static str {
+        "protective_reads_verifier"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(&stop_receiver.0).await
+    }
+}
+
+#[derive(Debug)]
+struct StorageSyncTaskWrapper(StorageSyncTask<CheckpointedIo<ProtectiveReadsIo>>);
+
+#[async_trait::async_trait]
+impl Task for StorageSyncTaskWrapper {
+    fn name(&self) -> &'static str {
+        "protective_reads_verifier_storage_sync"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(stop_receiver.0).await
+    }
+}
+
+#[derive(Debug)]
+struct OutputHandlerTask(ConcurrentOutputHandlerFactoryTask<CheckpointedIo<ProtectiveReadsIo>>);
+
+#[async_trait::async_trait]
+impl Task for OutputHandlerTask {
+    fn name(&self) -> &'static str {
+        "protective_reads_verifier_output_handler"
+    }
+
+    async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.0.run(stop_receiver.0).await
+    }
+}
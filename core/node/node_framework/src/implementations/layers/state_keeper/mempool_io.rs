@@ -9,7 +9,8 @@ use zksync_config::{
     ContractsConfig,
 };
 use zksync_state_keeper::{
-    MempoolFetcher, MempoolGuard, MempoolIO, OutputHandler, SequencerSealer, StateKeeperPersistence,
+    AdaptiveSealQueueConfig, MempoolFetcher, MempoolGuard, MempoolIO, OutputHandler,
+    SequencerSealer, StateKeeperPersistence,
 };
 use zksync_types::L2ChainId;
 
@@ -81,14 +82,24 @@ impl WiringLayer for MempoolIOLayer {
         let master_pool = context.get_resource::<PoolResource<MasterPool>>().await?;
 
         // Create miniblock sealer task.
-        let (persistence, l2_block_sealer) = StateKeeperPersistence::new(
-            master_pool
-                .get_singleton()
-                .await
-                .context("Get master pool")?,
-            self.contracts_config.l2_shared_bridge_addr.unwrap(),
-            self.state_keeper_config.l2_block_seal_queue_capacity,
-        );
+        let sealer_pool = master_pool
+            .get_singleton()
+            .await
+            .context("Get master pool")?;
+        let l2_shared_bridge_addr = self.contracts_config.l2_shared_bridge_addr.unwrap();
+        let (persistence, l2_block_sealer) =
+            match AdaptiveSealQueueConfig::from_state_keeper_config(&self.state_keeper_config) {
+                Some(queue_config) => StateKeeperPersistence::new_adaptive(
+                    sealer_pool,
+                    l2_shared_bridge_addr,
+                    queue_config,
+                ),
+                None => StateKeeperPersistence::new(
+                    sealer_pool,
+                    l2_shared_bridge_addr,
+                    self.state_keeper_config.l2_block_seal_queue_capacity,
+                ),
+            };
         let output_handler = OutputHandler::new(Box::new(persistence));
         context.insert_resource(OutputHandlerResource(Unique::new(output_handler)))?;
         context.add_task(Box::new(L2BlockSealerTask(l2_block_sealer)));
@@ -0,0 +1,136 @@
+use std::{
+    collections::BTreeMap,
+    net::{Ipv4Addr, SocketAddr},
+};
+
+use anyhow::Context as _;
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    routing::{get, post},
+    Json, Router,
+};
+
+use crate::{
+    implementations::resources::task_control::TaskControllerResource,
+    service::{ServiceContext, StopReceiver, TaskControlError, TaskController},
+    task::{ShutdownPhase, UnconstrainedTask},
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Builder for an HTTP server allowing to disable and re-enable tasks at runtime.
+///
+/// The server exposes the following endpoints:
+///
+/// - `GET /tasks`: returns a JSON object mapping names of controllable tasks to whether they are enabled.
+/// - `POST /tasks/{name}/disable`: stops the task until it's enabled again.
+/// - `POST /tasks/{name}/enable`: starts a new instance of a previously disabled task.
+///
+/// The server has no authentication, so it only listens on the loopback interface.
+///
+/// ## Effects
+///
+/// - Resolves `TaskControllerResource`.
+/// - Adds `task_control_server` to the node.
+#[derive(Debug)]
+pub struct TaskControlServerLayer {
+    port: u16,
+}
+
+impl TaskControlServerLayer {
+    pub fn new(port: u16) -> Self {
+        Self { port }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for TaskControlServerLayer {
+    fn layer_name(&self) -> &'static str {
+        "task_control_server_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        let TaskControllerResource(controller) = node.get_resource().await?;
+        let task = TaskControlServerTask {
+            bind_addr: (Ipv4Addr::LOCALHOST, self.port).into(),
+            controller,
+        };
+        node.add_unconstrained_task(Box::new(task));
+        Ok(())
+    }
+}
+
+async fn get_tasks(controller: State<TaskController>) -> Json<BTreeMap<&'static str, bool>> {
+    Json(controller.tasks())
+}
+
+fn set_task_enabled(
+    controller: &TaskController,
+    name: &str,
+    enabled: bool,
+) -> Result<StatusCode, (StatusCode, String)> {
+    match controller.set_enabled(name, enabled) {
+        Ok(true) => {
+            let action = if enabled { "enabled" } else { "disabled" };
+            tracing::info!("Task {name} was {action} via task control server");
+            Ok(StatusCode::ACCEPTED)
+        }
+        Ok(false) => Ok(StatusCode::OK),
+        Err(err @ TaskControlError::UnknownTask(_)) => {
+            Err((StatusCode::NOT_FOUND, err.to_string()))
+        }
+    }
+}
+
+async fn enable_task(
+    controller: State<TaskController>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    set_task_enabled(&controller, &name, true)
+}
+
+async fn disable_task(
+    controller: State<TaskController>,
+    Path(name): Path<String>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    set_task_enabled(&controller, &name, false)
+}
+
+#[derive(Debug)]
+struct TaskControlServerTask {
+    bind_addr: SocketAddr,
+    controller: TaskController,
+}
+
+#[async_trait::async_trait]
+impl UnconstrainedTask for TaskControlServerTask {
+    fn name(&self) -> &'static str {
+        "task_control_server"
+    }
+
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Auxiliary
+    }
+
+    async fn run_unconstrained(
+        self: Box<Self>,
+        mut stop_receiver: StopReceiver,
+    ) -> anyhow::Result<()> {
+        let router = Router::new()
+            .route("/tasks", get(get_tasks))
+            .route("/tasks/:name/enable", post(enable_task))
+            .route("/tasks/:name/disable", post(disable_task))
+            .with_state(self.controller);
+
+        tracing::info!("Starting task control server on {}", self.bind_addr);
+        axum::Server::try_bind(&self.bind_addr)
+            .with_context(|| format!("failed binding task control server to {}", self.bind_addr))?
+            .serve(router.into_make_service())
+            .with_graceful_shutdown(async move {
+                stop_receiver.0.changed().await.ok();
+                tracing::info!("Stop signal received, task control server is shutting down");
+            })
+            .await
+            .context("task control server failed")
+    }
+}
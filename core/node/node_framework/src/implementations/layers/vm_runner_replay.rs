@@ -0,0 +1,107 @@
+use zksync_state_keeper::MainBatchExecutor;
+use zksync_vm_runner::{
+    OutputHandlerFactory, ProtectiveReadsOutputHandlerFactory, VmReplayConfig, VmReplayRunner,
+};
+
+use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource,
+        pools::{MasterPool, PoolResource},
+    },
+    service::{ServiceContext, StopReceiver},
+    task::OneshotTask,
+    wiring_layer::{WiringError, WiringLayer},
+};
+
+/// Wiring layer for the VM runner replaying a range of historical L1 batches.
+/// Execution results of each replayed batch are fed to the provided [`OutputHandlerFactory`], or are used
+/// to verify protective reads persisted for the batch if the layer is created with
+/// [`Self::with_protective_reads_verification()`].
+///
+/// ## Effects
+///
+/// - Resolves `PoolResource<MasterPool>`.
+/// - Adds a `vm_runner_replay` health check to the `AppHealthCheckResource`, reporting the replay progress.
+/// - Adds a oneshot task `vm_runner_replay` that finishes once all batches in the range are replayed.
+#[derive(Debug)]
+pub struct VmReplayLayer {
+    config: VmReplayConfig,
+    output: ReplayOutput,
+}
+
+#[derive(Debug)]
+enum ReplayOutput {
+    Custom(Box<dyn OutputHandlerFactory>),
+    ProtectiveReadsVerification,
+}
+
+impl VmReplayLayer {
+    pub fn new(
+        config: VmReplayConfig,
+        output_handler_factory: Box<dyn OutputHandlerFactory>,
+    ) -> Self {
+        Self {
+            config,
+            output: ReplayOutput::Custom(output_handler_factory),
+        }
+    }
+
+    /// Creates a layer verifying protective reads of replayed batches against the ones persisted
+    /// by the state keeper.
+    pub fn with_protective_reads_verification(config: VmReplayConfig) -> Self {
+        Self {
+            config,
+            output: ReplayOutput::ProtectiveReadsVerification,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for VmReplayLayer {
+    fn layer_name(&self) -> &'static str {
+        "vm_runner_replay_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let pool_resource = context.get_resource::<PoolResource<MasterPool>>().await?;
+        let main_pool = pool_resource.get().await?;
+
+        let output_handler_factory = match self.output {
+            ReplayOutput::Custom(factory) => factory,
+            ReplayOutput::ProtectiveReadsVerification => {
+                Box::new(ProtectiveReadsOutputHandlerFactory::new(main_pool.clone()))
+            }
+        };
+        let runner = VmReplayRunner::new(
+            main_pool,
+            self.config,
+            output_handler_factory,
+            Box::new(MainBatchExecutor::new(false, false)),
+        )
+        .map_err(|err| WiringError::Configuration(format!("{err:#}")))?;
+
+        let AppHealthCheckResource(app_health) = context.get_resource_or_default().await;
+        app_health
+            .insert_component(runner.health_check())
+            .map_err(WiringError::internal)?;
+
+        context.add_oneshot_task(Box::new(VmReplayTask { runner }));
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct VmReplayTask {
+    runner: VmReplayRunner<Box<dyn OutputHandlerFactory>>,
+}
+
+#[async_trait::async_trait]
+impl OneshotTask for VmReplayTask {
+    fn name(&self) -> &'static str {
+        "vm_runner_replay"
+    }
+
+    async fn run_oneshot(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.runner.run(stop_receiver.0).await
+    }
+}
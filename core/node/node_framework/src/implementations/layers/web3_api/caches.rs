@@ -1,11 +1,15 @@
 use std::time::Duration;
 
-use zksync_node_api_server::web3::mempool_cache::{self, MempoolCache};
+use zksync_node_api_server::web3::{
+    mempool_cache::{self, MempoolCache},
+    response_cache::ResponseCache,
+};
+use zksync_types::{url::SensitiveUrl, L2ChainId};
 
 use crate::{
     implementations::resources::{
         pools::{PoolResource, ReplicaPool},
-        web3_api::MempoolCacheResource,
+        web3_api::{MempoolCacheResource, ResponseCacheResource},
     },
     service::{ServiceContext, StopReceiver},
     task::Task,
@@ -44,6 +48,47 @@ impl WiringLayer for MempoolCacheLayer {
     }
 }
 
+/// Wiring layer for the API response cache. Unlike the mempool cache, the response cache is optional;
+/// API servers use it if this layer is added.
+#[derive(Debug)]
+pub struct ResponseCacheLayer {
+    max_size: u64,
+    ttl: Duration,
+    redis: Option<(SensitiveUrl, L2ChainId)>,
+}
+
+impl ResponseCacheLayer {
+    pub fn new(max_size: u64, ttl: Duration) -> Self {
+        Self {
+            max_size,
+            ttl,
+            redis: None,
+        }
+    }
+
+    /// Shares the cache among nodes via Redis.
+    pub fn with_redis(mut self, url: SensitiveUrl, chain_id: L2ChainId) -> Self {
+        self.redis = Some((url, chain_id));
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ResponseCacheLayer {
+    fn layer_name(&self) -> &'static str {
+        "response_cache_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut context: ServiceContext<'_>) -> Result<(), WiringError> {
+        let mut response_cache = ResponseCache::new(self.max_size, self.ttl);
+        if let Some((url, chain_id)) = &self.redis {
+            response_cache = response_cache.with_redis(url, *chain_id).await?;
+        }
+        context.insert_resource(ResponseCacheResource(response_cache))?;
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct MempoolCacheUpdateTask(mempool_cache::MempoolCacheUpdateTask);
 
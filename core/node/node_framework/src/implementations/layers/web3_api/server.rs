@@ -11,10 +11,13 @@ use crate::{
         healthcheck::AppHealthCheckResource,
         pools::{PoolResource, ReplicaPool},
         sync_state::SyncStateResource,
-        web3_api::{MempoolCacheResource, TreeApiClientResource, TxSenderResource},
+        web3_api::{
+            ApiTracerRegistryResource, MempoolCacheResource, ResponseCacheResource,
+            TreeApiClientResource, TxSenderResource,
+        },
     },
     service::{ServiceContext, StopReceiver},
-    task::Task,
+    task::{ShutdownPhase, Task},
     wiring_layer::{WiringError, WiringLayer},
 };
 
@@ -125,17 +128,27 @@ impl WiringLayer for Web3ServerLayer {
             Err(WiringError::ResourceLacking { .. }) => None,
             Err(err) => return Err(err),
         };
+        let response_cache = match context.get_resource::<ResponseCacheResource>().await {
+            Ok(cache) => Some(cache.0),
+            Err(WiringError::ResourceLacking { .. }) => None,
+            Err(err) => return Err(err),
+        };
         let MempoolCacheResource(mempool_cache) = context.get_resource().await?;
+        let ApiTracerRegistryResource(tracer_registry) = context.get_resource_or_default().await;
 
         // Build server.
         let mut api_builder =
             ApiBuilder::jsonrpsee_backend(self.internal_api_config, replica_pool.clone())
                 .with_updaters_pool(updaters_pool)
                 .with_tx_sender(tx_sender)
-                .with_mempool_cache(mempool_cache);
+                .with_mempool_cache(mempool_cache)
+                .with_tracer_registry(tracer_registry);
         if let Some(client) = tree_api_client {
             api_builder = api_builder.with_tree_api(client);
         }
+        if let Some(response_cache) = response_cache {
+            api_builder = api_builder.with_response_cache(response_cache);
+        }
         match self.transport {
             Transport::Http => {
                 api_builder = api_builder.http(self.port);
@@ -213,6 +226,10 @@ impl Task for Web3ApiTask {
         }
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Ingress
+    }
+
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()> {
         let tasks = self.server.run(stop_receiver.0).await?;
         // Wait for the first task to finish to be able to signal the service.
@@ -236,6 +253,10 @@ impl Task for ApiTaskGarbageCollector {
         "api_task_garbage_collector"
     }
 
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::Ingress
+    }
+
     async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
         // We can ignore the stop signal here, since we're tied to the main API task through the channel:
         // it'll either get dropped if API cannot be built or will send something through the channel.
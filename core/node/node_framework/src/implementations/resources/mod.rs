@@ -9,4 +9,5 @@ pub mod object_store;
 pub mod pools;
 pub mod state_keeper;
 pub mod sync_state;
+pub mod task_control;
 pub mod web3_api;
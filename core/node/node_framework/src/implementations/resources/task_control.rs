@@ -0,0 +1,11 @@
+use crate::{resource::Resource, service::TaskController};
+
+/// A resource that provides [`TaskController`] to the service. Always available.
+#[derive(Debug, Clone, Default)]
+pub struct TaskControllerResource(pub TaskController);
+
+impl Resource for TaskControllerResource {
+    fn name() -> String {
+        "common/task_controller".into()
+    }
+}
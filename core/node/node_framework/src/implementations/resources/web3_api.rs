@@ -2,8 +2,9 @@ use std::sync::Arc;
 
 use zksync_metadata_calculator::api_server::TreeApiClient;
 use zksync_node_api_server::{
+    execution_sandbox::ApiTracerRegistry,
     tx_sender::{tx_sink::TxSink, TxSender},
-    web3::mempool_cache::MempoolCache,
+    web3::{mempool_cache::MempoolCache, response_cache::ResponseCache},
 };
 
 use crate::resource::Resource;
@@ -43,3 +44,24 @@ impl Resource for MempoolCacheResource {
         "api/mempool_cache".into()
     }
 }
+
+/// Cache for API responses that don't change once the corresponding block is final.
+#[derive(Debug, Clone)]
+pub struct ResponseCacheResource(pub ResponseCache);
+
+impl Resource for ResponseCacheResource {
+    fn name() -> String {
+        "api/response_cache".into()
+    }
+}
+
+/// Registry of custom tracers selectable in the `debug` namespace. Components may register their tracers
+/// in the registry during wiring.
+#[derive(Debug, Clone, Default)]
+pub struct ApiTracerRegistryResource(pub ApiTracerRegistry);
+
+impl Resource for ApiTracerRegistryResource {
+    fn name() -> String {
+        "api/tracer_registry".into()
+    }
+}
@@ -18,6 +18,7 @@
 //! - Add tasks to the node.
 //! - Run it.
 
+pub mod components;
 pub mod implementations;
 pub mod precondition;
 pub mod resource;
@@ -3,7 +3,10 @@ use std::any::type_name;
 use crate::{
     precondition::Precondition,
     resource::{Resource, ResourceId, StoredResource},
-    service::ZkStackService,
+    service::{
+        describe::{LayerDescription, ResourceRequest, RunnableDescription, RunnableKind},
+        ZkStackService,
+    },
     task::{OneshotTask, Task, UnconstrainedOneshotTask, UnconstrainedTask},
     wiring_layer::WiringError,
 };
@@ -21,6 +24,30 @@ impl<'a> ServiceContext<'a> {
         Self { layer, service }
     }
 
+    fn description(&mut self) -> &mut LayerDescription {
+        self.service
+            .description
+            .layers
+            .last_mut()
+            .expect("layer description is not initialized")
+    }
+
+    fn record_runnable(&mut self, name: &str, kind: RunnableKind) {
+        self.description().runnables.push(RunnableDescription {
+            name: name.to_owned(),
+            kind,
+        });
+    }
+
+    fn record_resource_request<T: Resource>(&mut self, available: bool) {
+        self.description()
+            .requested_resources
+            .push(ResourceRequest {
+                name: T::name(),
+                available,
+            });
+    }
+
     /// Provides access to the runtime used by the service.
     /// Can be used to spawn additional tasks within the same runtime.
     /// If some tasks stores the handle to spawn additional tasks, it is expected to do all the required
@@ -40,6 +67,7 @@ impl<'a> ServiceContext<'a> {
     /// are met.
     pub fn add_task(&mut self, task: Box<dyn Task>) -> &mut Self {
         tracing::info!("Layer {} has added a new task: {}", self.layer, task.name());
+        self.record_runnable(task.name(), RunnableKind::Task);
         self.service.runnables.tasks.push(task);
         self
     }
@@ -52,6 +80,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.record_runnable(task.name(), RunnableKind::UnconstrainedTask);
         self.service.runnables.unconstrained_tasks.push(task);
         self
     }
@@ -63,6 +92,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             precondition.name()
         );
+        self.record_runnable(precondition.name(), RunnableKind::Precondition);
         self.service.runnables.preconditions.push(precondition);
         self
     }
@@ -74,6 +104,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.record_runnable(task.name(), RunnableKind::OneshotTask);
         self.service.runnables.oneshot_tasks.push(task);
         self
     }
@@ -88,6 +119,7 @@ impl<'a> ServiceContext<'a> {
             self.layer,
             task.name()
         );
+        self.record_runnable(task.name(), RunnableKind::UnconstrainedOneshotTask);
         self.service
             .runnables
             .unconstrained_oneshot_tasks
@@ -125,7 +157,9 @@ impl<'a> ServiceContext<'a> {
                 T::name(),
                 type_name::<T>()
             );
-            return Ok(downcast_clone(resource));
+            let resource = downcast_clone(resource);
+            self.record_resource_request::<T>(true);
+            return Ok(resource);
         }
 
         tracing::info!(
@@ -134,6 +168,7 @@ impl<'a> ServiceContext<'a> {
             T::name(),
             type_name::<T>()
         );
+        self.record_resource_request::<T>(false);
 
         // No such resource.
         // The requester is allowed to decide whether this is an error or not.
@@ -158,6 +193,7 @@ impl<'a> ServiceContext<'a> {
         self.service
             .resources
             .insert(ResourceId::of::<T>(), Box::new(resource.clone()));
+        self.description().provided_resources.push(T::name());
         tracing::info!(
             "Layer {} has created a new resource {}",
             self.layer,
@@ -189,6 +225,7 @@ impl<'a> ServiceContext<'a> {
             });
         }
         self.service.resources.insert(id, Box::new(resource));
+        self.description().provided_resources.push(T::name());
         tracing::info!(
             "Layer {} has provided a new resource {}",
             self.layer,
@@ -0,0 +1,61 @@
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use tokio::sync::watch;
+
+/// Error returned by [`TaskController`].
+#[derive(Debug, thiserror::Error)]
+pub enum TaskControlError {
+    #[error("task `{0}` does not exist or cannot be enabled / disabled at runtime")]
+    UnknownTask(String),
+}
+
+/// Allows disabling and later re-enabling tasks at runtime without restarting the node.
+///
+/// Only [`Task`](crate::task::Task)s supporting restarts (i.e., implementing [`Task::restart()`](crate::task::Task::restart()))
+/// can be controlled. A disabled task receives a stop signal; once the task is enabled again, a new instance
+/// of the task is started. The controller is available to wiring layers
+/// as [`TaskControllerResource`](crate::implementations::resources::task_control::TaskControllerResource).
+#[derive(Debug, Clone, Default)]
+pub struct TaskController {
+    tasks: Arc<Mutex<BTreeMap<&'static str, watch::Sender<bool>>>>,
+}
+
+impl TaskController {
+    /// Registers a controllable task. Returns a receiver for the enabled / disabled state of the task.
+    pub(super) fn register(&self, name: &'static str) -> watch::Receiver<bool> {
+        let mut tasks = self.tasks.lock().expect("`TaskController` is poisoned");
+        tasks
+            .entry(name)
+            .or_insert_with(|| watch::channel(true).0)
+            .subscribe()
+    }
+
+    /// Returns the enabled / disabled state of all controllable tasks, keyed by the task name.
+    pub fn tasks(&self) -> BTreeMap<&'static str, bool> {
+        let tasks = self.tasks.lock().expect("`TaskController` is poisoned");
+        tasks
+            .iter()
+            .map(|(&name, sender)| (name, *sender.borrow()))
+            .collect()
+    }
+
+    /// Enables or disables the specified task. Returns `true` if the task state has changed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the task doesn't exist or is not controllable.
+    pub fn set_enabled(&self, name: &str, enabled: bool) -> Result<bool, TaskControlError> {
+        let tasks = self.tasks.lock().expect("`TaskController` is poisoned");
+        let sender = tasks
+            .get(name)
+            .ok_or_else(|| TaskControlError::UnknownTask(name.to_owned()))?;
+        Ok(sender.send_if_modified(|state| {
+            let is_modified = *state != enabled;
+            *state = enabled;
+            is_modified
+        }))
+    }
+}
@@ -0,0 +1,210 @@
+//! Description of the wiring performed by [`ZkStackService`](super::ZkStackService).
+
+use std::{collections::BTreeSet, fmt, str::FromStr};
+
+use serde::Serialize;
+
+/// Format of the rendered [`ServiceDescription`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DescriptionFormat {
+    /// [Graphviz DOT](https://graphviz.org/doc/info/lang.html) graph.
+    Dot,
+    /// Pretty-printed JSON.
+    Json,
+}
+
+impl FromStr for DescriptionFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(Self::Dot),
+            "json" => Ok(Self::Json),
+            _ => Err(format!(
+                "unknown description format `{s}`; expected `dot` or `json`"
+            )),
+        }
+    }
+}
+
+/// Kind of a runnable added to the service by a wiring layer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RunnableKind {
+    Precondition,
+    Task,
+    OneshotTask,
+    UnconstrainedTask,
+    UnconstrainedOneshotTask,
+}
+
+impl RunnableKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Precondition => "precondition",
+            Self::Task => "task",
+            Self::OneshotTask => "oneshot_task",
+            Self::UnconstrainedTask => "unconstrained_task",
+            Self::UnconstrainedOneshotTask => "unconstrained_oneshot_task",
+        }
+    }
+}
+
+/// Runnable (a task or a precondition) added by a wiring layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct RunnableDescription {
+    pub name: String,
+    pub kind: RunnableKind,
+}
+
+/// Resource requested by a wiring layer.
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourceRequest {
+    pub name: String,
+    /// Whether the resource was available at the time of the request.
+    pub available: bool,
+}
+
+/// Everything a single wiring layer has done during wiring.
+#[derive(Debug, Clone, Serialize)]
+pub struct LayerDescription {
+    pub name: String,
+    pub requested_resources: Vec<ResourceRequest>,
+    pub provided_resources: Vec<String>,
+    pub runnables: Vec<RunnableDescription>,
+    /// Error returned by the layer, if any.
+    pub error: Option<String>,
+}
+
+impl LayerDescription {
+    pub(super) fn new(name: String) -> Self {
+        Self {
+            name,
+            requested_resources: Vec::new(),
+            provided_resources: Vec::new(),
+            runnables: Vec::new(),
+            error: None,
+        }
+    }
+}
+
+/// Resolved dependency graph of the service: layers in the wiring order together with
+/// the resources they request and provide, and the runnables they add.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ServiceDescription {
+    /// Resources provided by the service itself rather than by wiring layers.
+    pub builtin_resources: Vec<String>,
+    pub layers: Vec<LayerDescription>,
+}
+
+impl ServiceDescription {
+    /// Returns names of resources that were requested, but not provided by any layer.
+    pub fn missing_resources(&self) -> BTreeSet<&str> {
+        let provided: BTreeSet<_> = self
+            .layers
+            .iter()
+            .flat_map(|layer| &layer.provided_resources)
+            .chain(&self.builtin_resources)
+            .map(String::as_str)
+            .collect();
+        self.layers
+            .iter()
+            .flat_map(|layer| &layer.requested_resources)
+            .map(|request| request.name.as_str())
+            .filter(|name| !provided.contains(name))
+            .collect()
+    }
+
+    /// Renders this description in the specified format.
+    pub fn render(&self, format: DescriptionFormat) -> String {
+        match format {
+            DescriptionFormat::Dot => self.to_string(),
+            DescriptionFormat::Json => {
+                serde_json::to_string_pretty(self).expect("failed serializing service description")
+            }
+        }
+    }
+}
+
+/// Outputs the description as a Graphviz DOT graph. Layers are rendered as boxes, resources as ellipses
+/// and runnables as diamonds. Resources that are requested, but never provided, and layers that failed wiring
+/// are highlighted.
+impl fmt::Display for ServiceDescription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph zk_stack_service {{")?;
+        writeln!(f, "    rankdir=LR;")?;
+
+        let missing_resources = self.missing_resources();
+        let all_resources: BTreeSet<_> = self
+            .layers
+            .iter()
+            .flat_map(|layer| {
+                let requested = layer.requested_resources.iter().map(|req| &req.name);
+                requested.chain(&layer.provided_resources)
+            })
+            .collect();
+        for resource in all_resources {
+            let style = if missing_resources.contains(resource.as_str()) {
+                ", color=red, fontcolor=red"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "    {} [shape=ellipse, label={}{style}];",
+                dot_id("resource", resource),
+                dot_string(resource)
+            )?;
+        }
+
+        for layer in &self.layers {
+            let layer_id = dot_id("layer", &layer.name);
+            let style = if layer.error.is_some() {
+                ", color=red, fontcolor=red"
+            } else {
+                ""
+            };
+            writeln!(
+                f,
+                "    {layer_id} [shape=box, label={}{style}];",
+                dot_string(&layer.name)
+            )?;
+
+            for resource in &layer.provided_resources {
+                writeln!(
+                    f,
+                    "    {layer_id} -> {} [label=\"provides\"];",
+                    dot_id("resource", resource)
+                )?;
+            }
+            // The same resource may be requested multiple times; render a single edge for it.
+            let requested: BTreeSet<_> = layer
+                .requested_resources
+                .iter()
+                .map(|req| &req.name)
+                .collect();
+            for resource in requested {
+                writeln!(f, "    {} -> {layer_id};", dot_id("resource", resource))?;
+            }
+            for runnable in &layer.runnables {
+                let runnable_id = dot_id("runnable", &runnable.name);
+                let label = format!("{} ({})", runnable.name, runnable.kind.as_str());
+                writeln!(
+                    f,
+                    "    {runnable_id} [shape=diamond, label={}];",
+                    dot_string(&label)
+                )?;
+                writeln!(f, "    {layer_id} -> {runnable_id} [label=\"adds\"];")?;
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+fn dot_id(prefix: &str, name: &str) -> String {
+    dot_string(&format!("{prefix}:{name}"))
+}
+
+fn dot_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
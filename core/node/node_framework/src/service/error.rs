@@ -1,3 +1,5 @@
+use std::fmt::Write as _;
+
 use crate::wiring_layer::WiringError;
 
 #[derive(Debug, thiserror::Error)]
@@ -6,8 +8,42 @@ pub enum ZkStackServiceError {
     RuntimeDetected,
     #[error("No tasks have been added to the service")]
     NoTasks,
-    #[error("One or more wiring layers failed to initialize: {0:?}")]
+    #[error("One or more wiring layers failed to initialize:{}", describe_wiring_errors(.0))]
     Wiring(Vec<(String, WiringError)>),
     #[error(transparent)]
     Task(#[from] anyhow::Error),
 }
+
+/// Lists errors for each layer, followed by a summary of missing resources, which are usually caused
+/// by a layer providing the resource not being added to the service.
+fn describe_wiring_errors(errors: &[(String, WiringError)]) -> String {
+    let mut description = String::new();
+    let mut missing_resources: Vec<(&str, Vec<&str>)> = vec![];
+    for (layer, err) in errors {
+        write!(description, "\n- {layer}: {err}").unwrap();
+        if let WiringError::ResourceLacking { name, .. } = err {
+            let pos = missing_resources
+                .iter()
+                .position(|(resource, _)| resource == name);
+            match pos {
+                Some(pos) => missing_resources[pos].1.push(layer),
+                None => missing_resources.push((name, vec![layer])),
+            }
+        }
+    }
+
+    if !missing_resources.is_empty() {
+        description.push_str(
+            "\nMissing resources (check that components / layers providing them are enabled):",
+        );
+        for (resource, layers) in missing_resources {
+            write!(
+                description,
+                "\n- {resource}, requested by {}",
+                layers.join(", ")
+            )
+            .unwrap();
+        }
+    }
+    description
+}
@@ -1,28 +1,44 @@
-use std::{collections::HashMap, time::Duration};
+use std::collections::HashMap;
 
 use anyhow::Context;
-use futures::{future::BoxFuture, FutureExt};
-use tokio::{runtime::Runtime, sync::watch};
+use futures::future::BoxFuture;
+use tokio::runtime::Runtime;
 use zksync_utils::panic_extractor::try_extract_panic_message;
 
-use self::runnables::Runnables;
-pub use self::{context::ServiceContext, error::ZkStackServiceError, stop_receiver::StopReceiver};
+pub use self::{
+    context::ServiceContext,
+    control::{TaskControlError, TaskController},
+    describe::{
+        DescriptionFormat, LayerDescription, ResourceRequest, RunnableDescription, RunnableKind,
+        ServiceDescription,
+    },
+    error::ZkStackServiceError,
+    stop_receiver::StopReceiver,
+};
+use self::{
+    runnables::Runnables,
+    shutdown::{LongRunningTask, ShutdownSignals, SpawnedTask},
+};
 use crate::{
-    resource::{ResourceId, StoredResource},
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource, task_control::TaskControllerResource,
+    },
+    resource::{Resource, ResourceId, StoredResource},
     service::runnables::TaskReprs,
+    task::{ShutdownPhase, DEFAULT_SHUTDOWN_TIMEOUT},
     wiring_layer::{WiringError, WiringLayer},
 };
 
 mod context;
+mod control;
+mod describe;
 mod error;
 mod runnables;
+mod shutdown;
 mod stop_receiver;
 #[cfg(test)]
 mod tests;
 
-// A reasonable amount of time for any task to finish the shutdown process
-const TASK_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
-
 /// A builder for [`ZkStackService`].
 #[derive(Default, Debug)]
 pub struct ZkStackServiceBuilder {
@@ -63,13 +79,23 @@ impl ZkStackServiceBuilder {
             .build()
             .unwrap();
 
-        let (stop_sender, _stop_receiver) = watch::channel(false);
+        let task_controller = TaskController::default();
+        let mut resources: HashMap<_, Box<dyn StoredResource>> = HashMap::new();
+        resources.insert(
+            ResourceId::of::<TaskControllerResource>(),
+            Box::new(TaskControllerResource(task_controller.clone())),
+        );
 
         Ok(ZkStackService {
             layers: std::mem::take(&mut self.layers),
-            resources: Default::default(),
+            resources,
             runnables: Default::default(),
-            stop_sender,
+            description: ServiceDescription {
+                builtin_resources: vec![TaskControllerResource::name()],
+                ..ServiceDescription::default()
+            },
+            task_controller,
+            stop_signals: ShutdownSignals::new(),
             runtime,
         })
     }
@@ -85,17 +111,21 @@ pub struct ZkStackService {
     layers: Vec<Box<dyn WiringLayer>>,
     /// Different kinds of tasks for the service.
     runnables: Runnables,
+    /// Controller for tasks that can be disabled / enabled at runtime.
+    task_controller: TaskController,
+    /// Record of what each wiring layer has done during wiring.
+    description: ServiceDescription,
 
-    /// Sender used to stop the tasks.
-    stop_sender: watch::Sender<bool>,
+    /// Senders used to stop the tasks.
+    stop_signals: ShutdownSignals,
     /// Tokio runtime used to spawn tasks.
     runtime: Runtime,
 }
 
 impl ZkStackService {
-    /// Runs the system.
-    pub fn run(mut self) -> Result<(), ZkStackServiceError> {
-        // Initialize tasks.
+    /// Invokes `wire` method of every wiring layer in the order they were added, recording
+    /// the resources and tasks they request, provide and add. Returns errors produced by the layers.
+    fn wire_layers(&mut self) -> Vec<(String, WiringError)> {
         let wiring_layers = std::mem::take(&mut self.layers);
 
         let mut errors: Vec<(String, WiringError)> = Vec::new();
@@ -103,10 +133,15 @@ impl ZkStackService {
         let runtime_handle = self.runtime.handle().clone();
         for layer in wiring_layers {
             let name = layer.layer_name().to_string();
+            self.description
+                .layers
+                .push(LayerDescription::new(name.clone()));
             // We must process wiring layers sequentially and in the same order as they were added.
-            let task_result =
-                runtime_handle.block_on(layer.wire(ServiceContext::new(&name, &mut self)));
+            let task_result = runtime_handle.block_on(layer.wire(ServiceContext::new(&name, self)));
             if let Err(err) = task_result {
+                if let Some(layer_description) = self.description.layers.last_mut() {
+                    layer_description.error = Some(err.to_string());
+                }
                 // We don't want to bail on the first error, since it'll provide worse DevEx:
                 // People likely want to fix as much problems as they can in one go, rather than have
                 // to fix them one by one.
@@ -114,6 +149,27 @@ impl ZkStackService {
                 continue;
             };
         }
+        errors
+    }
+
+    /// Performs wiring without running any tasks, and returns the resolved dependency graph of layers,
+    /// resources and tasks. Wiring errors do not prevent the description from being produced; failed layers
+    /// are marked in it instead, which is useful to debug missing resources.
+    ///
+    /// Note that wiring layers are still invoked, so they may perform initialization (e.g., create
+    /// connection pools) as a part of this call.
+    pub fn describe(mut self) -> ServiceDescription {
+        let errors = self.wire_layers();
+        for (layer, error) in &errors {
+            tracing::warn!("Wiring layer {layer} can't be initialized: {error}");
+        }
+        self.description
+    }
+
+    /// Runs the system.
+    pub fn run(mut self) -> Result<(), ZkStackServiceError> {
+        // Initialize tasks.
+        let errors = self.wire_layers();
 
         // Report all the errors we've met during the init.
         if !errors.is_empty() {
@@ -134,13 +190,20 @@ impl ZkStackService {
         let task_barrier = self.runnables.task_barrier();
 
         // Collect long-running tasks.
-        let stop_receiver = StopReceiver(self.stop_sender.subscribe());
+        let app_health = self
+            .resources
+            .get(&ResourceId::of::<AppHealthCheckResource>())
+            .and_then(|resource| resource.downcast_ref::<AppHealthCheckResource>())
+            .map(|AppHealthCheckResource(app_health)| app_health.clone());
         let TaskReprs {
             mut long_running_tasks,
             oneshot_tasks,
-        } = self
-            .runnables
-            .prepare_tasks(task_barrier.clone(), stop_receiver.clone());
+        } = self.runnables.prepare_tasks(
+            task_barrier.clone(),
+            &self.stop_signals,
+            app_health.as_deref(),
+            &self.task_controller,
+        );
 
         // Wiring is now complete.
         for resource in self.resources.values_mut() {
@@ -151,21 +214,37 @@ impl ZkStackService {
 
         // Create a system task that is cancellation-aware and will only exit on either oneshot task failure or
         // stop signal.
-        let oneshot_runner_system_task =
-            oneshot_runner_task(oneshot_tasks, stop_receiver, only_oneshot_tasks);
-        long_running_tasks.push(oneshot_runner_system_task);
+        let oneshot_runner_system_task = oneshot_runner_task(
+            oneshot_tasks,
+            self.stop_signals.receiver(ShutdownPhase::Processing),
+            only_oneshot_tasks,
+        );
+        long_running_tasks.push(LongRunningTask {
+            name: "oneshot_runner",
+            shutdown_phase: ShutdownPhase::Processing,
+            shutdown_timeout: DEFAULT_SHUTDOWN_TIMEOUT,
+            future: oneshot_runner_system_task,
+        });
 
         // Prepare tasks for running.
         let rt_handle = self.runtime.handle().clone();
-        let join_handles: Vec<_> = long_running_tasks
+        let mut spawned_tasks: Vec<_> = long_running_tasks
             .into_iter()
-            .map(|task| rt_handle.spawn(task).fuse())
+            .map(|task| SpawnedTask {
+                name: task.name,
+                shutdown_phase: task.shutdown_phase,
+                shutdown_timeout: task.shutdown_timeout,
+                handle: rt_handle.spawn(task.future),
+            })
             .collect();
 
         // Run the tasks until one of them exits.
-        let (resolved, _, remaining) = self
+        let join_handles = spawned_tasks.iter_mut().map(|task| &mut task.handle);
+        let (resolved, resolved_idx, _) = self
             .runtime
             .block_on(futures::future::select_all(join_handles));
+        let resolved_task = spawned_tasks.swap_remove(resolved_idx);
+        tracing::info!("Task {} has exited", resolved_task.name);
         let result = match resolved {
             Ok(Ok(())) => Ok(()),
             Ok(Err(err)) => Err(err).context("Task failed"),
@@ -177,21 +256,13 @@ impl ZkStackService {
             }
         };
 
-        let remaining_tasks_with_timeout: Vec<_> = remaining
-            .into_iter()
-            .map(|task| async { tokio::time::timeout(TASK_SHUTDOWN_TIMEOUT, task).await })
-            .collect();
-
-        // Send stop signal to remaining tasks and wait for them to finish.
-        // Given that we are shutting down, we do not really care about returned values.
-        self.stop_sender.send(true).ok();
-        let execution_results = self
-            .runtime
-            .block_on(futures::future::join_all(remaining_tasks_with_timeout));
-        let execution_timeouts_count = execution_results.iter().filter(|&r| r.is_err()).count();
-        if execution_timeouts_count > 0 {
+        // Send stop signals to remaining tasks phase by phase and wait for them to finish.
+        let aborted_tasks =
+            shutdown::shutdown_tasks(&self.runtime, &self.stop_signals, spawned_tasks);
+        if !aborted_tasks.is_empty() {
             tracing::warn!(
-                "{execution_timeouts_count} tasks didn't finish in {TASK_SHUTDOWN_TIMEOUT:?} and were dropped"
+                "{} tasks didn't finish in time and were aborted: {aborted_tasks:?}",
+                aborted_tasks.len()
             );
         } else {
             tracing::info!("Remaining tasks finished without reaching timeouts");
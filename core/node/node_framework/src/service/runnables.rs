@@ -3,11 +3,15 @@ use std::{fmt, sync::Arc};
 use anyhow::Context as _;
 use futures::future::BoxFuture;
 use tokio::sync::Barrier;
+use zksync_health_check::{AppHealthCheck, HealthUpdater, ReactiveHealthCheck};
 
-use super::StopReceiver;
+use super::{
+    shutdown::{LongRunningTask, ShutdownSignals},
+    StopReceiver, TaskController,
+};
 use crate::{
     precondition::Precondition,
-    task::{OneshotTask, Task, UnconstrainedOneshotTask, UnconstrainedTask},
+    task::{OneshotTask, ShutdownPhase, Task, UnconstrainedOneshotTask, UnconstrainedTask},
 };
 
 /// A collection of different flavors of tasks.
@@ -50,7 +54,7 @@ impl fmt::Debug for Runnables {
 
 /// A unified representation of tasks that can be run by the service.
 pub(super) struct TaskReprs {
-    pub(super) long_running_tasks: Vec<BoxFuture<'static, anyhow::Result<()>>>,
+    pub(super) long_running_tasks: Vec<LongRunningTask>,
     pub(super) oneshot_tasks: Vec<BoxFuture<'static, anyhow::Result<()>>>,
 }
 
@@ -89,19 +93,29 @@ impl Runnables {
     }
 
     /// Transforms the collection of tasks into a set of universal futures.
+    /// If `app_health` is provided, a health check component is inserted into it for each [`Task`].
+    /// Tasks supporting restarts are registered in `task_controller`.
+    /// Long-running tasks receive the stop signal for their shutdown phase; other runnables are stopped
+    /// in [`ShutdownPhase::Processing`].
     pub(super) fn prepare_tasks(
         mut self,
         task_barrier: Arc<Barrier>,
-        stop_receiver: StopReceiver,
+        stop_signals: &ShutdownSignals,
+        app_health: Option<&AppHealthCheck>,
+        task_controller: &TaskController,
     ) -> TaskReprs {
         let mut long_running_tasks = Vec::new();
-        self.collect_unconstrained_tasks(&mut long_running_tasks, stop_receiver.clone());
+        self.collect_unconstrained_tasks(&mut long_running_tasks, stop_signals);
         self.collect_tasks(
             &mut long_running_tasks,
             task_barrier.clone(),
-            stop_receiver.clone(),
+            stop_signals,
+            app_health,
+            task_controller,
         );
 
+        let stop_receiver = stop_signals.receiver(ShutdownPhase::Processing);
+
         let mut oneshot_tasks = Vec::new();
         self.collect_preconditions(
             &mut oneshot_tasks,
@@ -123,37 +137,64 @@ impl Runnables {
 
     fn collect_unconstrained_tasks(
         &mut self,
-        tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
-        stop_receiver: StopReceiver,
+        tasks: &mut Vec<LongRunningTask>,
+        stop_signals: &ShutdownSignals,
     ) {
         for task in std::mem::take(&mut self.unconstrained_tasks) {
             let name = task.name();
-            let stop_receiver = stop_receiver.clone();
+            let shutdown_phase = task.shutdown_phase();
+            let shutdown_timeout = task.shutdown_timeout();
+            let stop_receiver = stop_signals.receiver(shutdown_phase);
             let task_future = Box::pin(async move {
                 task.run_unconstrained(stop_receiver)
                     .await
                     .with_context(|| format!("Task {name} failed"))
             });
-            tasks.push(task_future);
+            tasks.push(LongRunningTask {
+                name,
+                shutdown_phase,
+                shutdown_timeout,
+                future: task_future,
+            });
         }
     }
 
     fn collect_tasks(
         &mut self,
-        tasks: &mut Vec<BoxFuture<'static, anyhow::Result<()>>>,
+        tasks: &mut Vec<LongRunningTask>,
         task_barrier: Arc<Barrier>,
-        stop_receiver: StopReceiver,
+        stop_signals: &ShutdownSignals,
+        app_health: Option<&AppHealthCheck>,
+        task_controller: &TaskController,
     ) {
         for task in std::mem::take(&mut self.tasks) {
             let name = task.name();
-            let stop_receiver = stop_receiver.clone();
+            let shutdown_phase = task.shutdown_phase();
+            let shutdown_timeout = task.shutdown_timeout();
+            let stop_receiver = stop_signals.receiver(shutdown_phase);
             let task_barrier = task_barrier.clone();
+            let health_updater = app_health.and_then(|app_health| task_health(app_health, name));
+            let next_instance = task.restart();
+            let control = next_instance
+                .is_some()
+                .then(|| task_controller.register(name));
             let task_future = Box::pin(async move {
-                task.run_with_barrier(stop_receiver, task_barrier)
-                    .await
-                    .with_context(|| format!("Task {name} failed"))
+                task.run_supervised(
+                    stop_receiver,
+                    task_barrier,
+                    health_updater,
+                    next_instance,
+                    control,
+                )
+                .await
+                .with_context(|| format!("Task {name} failed"))
+            });
+            tasks.push(LongRunningTask {
+                name,
+                shutdown_phase,
+                shutdown_timeout,
+                future: task_future,
             });
-            tasks.push(task_future);
         }
     }
 
@@ -215,3 +256,15 @@ impl Runnables {
         }
     }
 }
+
+/// Creates a health check component for a [`Task`](crate::task::Task) and inserts it into `app_health`.
+fn task_health(app_health: &AppHealthCheck, task_name: &str) -> Option<HealthUpdater> {
+    // Health check names are static. Tasks are only collected once per service, so leaking names is fine.
+    let health_name: &'static str = Box::leak(format!("task/{task_name}").into_boxed_str());
+    let (health_check, health_updater) = ReactiveHealthCheck::new(health_name);
+    if let Err(err) = app_health.insert_component(health_check) {
+        tracing::warn!("Cannot insert health check for task {task_name}: {err}");
+        return None;
+    }
+    Some(health_updater)
+}
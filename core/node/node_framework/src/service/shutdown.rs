@@ -0,0 +1,99 @@
+//! Phased shutdown of the service tasks.
+
+use std::time::Duration;
+
+use futures::future::BoxFuture;
+use tokio::{runtime::Runtime, sync::watch, task::JoinHandle};
+
+use super::StopReceiver;
+use crate::task::ShutdownPhase;
+
+/// Stop signals for all [shutdown phases](ShutdownPhase).
+#[derive(Debug)]
+pub(super) struct ShutdownSignals {
+    senders: Vec<watch::Sender<bool>>,
+}
+
+impl ShutdownSignals {
+    pub(super) fn new() -> Self {
+        Self {
+            senders: ShutdownPhase::ALL
+                .iter()
+                .map(|_| watch::channel(false).0)
+                .collect(),
+        }
+    }
+
+    /// Returns a receiver for the stop signal sent in the specified phase.
+    pub(super) fn receiver(&self, phase: ShutdownPhase) -> StopReceiver {
+        StopReceiver(self.senders[phase as usize].subscribe())
+    }
+
+    fn send(&self, phase: ShutdownPhase) {
+        self.senders[phase as usize].send_replace(true);
+    }
+}
+
+/// Long-running task together with the information necessary to shut it down.
+pub(super) struct LongRunningTask {
+    pub(super) name: &'static str,
+    pub(super) shutdown_phase: ShutdownPhase,
+    pub(super) shutdown_timeout: Duration,
+    pub(super) future: BoxFuture<'static, anyhow::Result<()>>,
+}
+
+/// Running long-running task.
+#[derive(Debug)]
+pub(super) struct SpawnedTask {
+    pub(super) name: &'static str,
+    pub(super) shutdown_phase: ShutdownPhase,
+    pub(super) shutdown_timeout: Duration,
+    pub(super) handle: JoinHandle<anyhow::Result<()>>,
+}
+
+/// Sends stop signals to the remaining tasks phase by phase. Tasks in each phase are awaited
+/// (but no longer than their shutdown timeouts) before the next phase starts. Tasks that don't finish
+/// in time are aborted.
+///
+/// Returns names of the aborted tasks.
+pub(super) fn shutdown_tasks(
+    runtime: &Runtime,
+    signals: &ShutdownSignals,
+    mut tasks: Vec<SpawnedTask>,
+) -> Vec<&'static str> {
+    let mut aborted_tasks = vec![];
+    for phase in ShutdownPhase::ALL {
+        signals.send(phase);
+        let (phase_tasks, remaining_tasks): (Vec<_>, Vec<_>) = tasks
+            .into_iter()
+            .partition(|task| task.shutdown_phase == phase);
+        tasks = remaining_tasks;
+        if phase_tasks.is_empty() {
+            continue;
+        }
+
+        tracing::info!(
+            "Stopping {} task(s) in shutdown phase {phase:?}",
+            phase_tasks.len()
+        );
+        let phase_tasks = phase_tasks.into_iter().map(|mut task| async move {
+            // Given that we are shutting down, we do not really care about returned values.
+            if tokio::time::timeout(task.shutdown_timeout, &mut task.handle)
+                .await
+                .is_ok()
+            {
+                return None;
+            }
+            task.handle.abort();
+            tracing::error!(
+                "Task {} didn't finish in {:?} during shutdown phase {phase:?} and was aborted",
+                task.name,
+                task.shutdown_timeout
+            );
+            Some(task.name)
+        });
+        let phase_results = runtime.block_on(futures::future::join_all(phase_tasks));
+        aborted_tasks.extend(phase_results.into_iter().flatten());
+    }
+    aborted_tasks
+}
@@ -1,15 +1,26 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
 
 use anyhow::anyhow;
 use assert_matches::assert_matches;
 use tokio::{runtime::Runtime, sync::Barrier};
+use zksync_health_check::AppHealthCheck;
 
 use crate::{
+    implementations::resources::{
+        healthcheck::AppHealthCheckResource, task_control::TaskControllerResource,
+    },
+    resource::Resource,
     service::{
-        ServiceContext, StopReceiver, WiringError, WiringLayer, ZkStackServiceBuilder,
-        ZkStackServiceError,
+        DescriptionFormat, RunnableKind, ServiceContext, StopReceiver, TaskControlError,
+        TaskController, WiringError, WiringLayer, ZkStackServiceBuilder, ZkStackServiceError,
     },
-    task::Task,
+    task::{RestartBackoff, RestartPolicy, ShutdownPhase, Task},
 };
 
 // `ZkStack` Service's `new()` method has to have a check for nested runtime.
@@ -232,3 +243,437 @@ fn test_task_run() {
     let res2 = *remaining_task_was_run.lock().unwrap();
     assert!(res2, "Incorrect resource value");
 }
+
+#[derive(Debug, Clone)]
+struct DummyResource;
+
+impl Resource for DummyResource {
+    fn name() -> String {
+        "common/dummy".into()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LackingResource;
+
+impl Resource for LackingResource {
+    fn name() -> String {
+        "common/lacking".into()
+    }
+}
+
+#[derive(Debug)]
+struct ProviderLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for ProviderLayer {
+    fn layer_name(&self) -> &'static str {
+        "provider_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.insert_resource(DummyResource)?;
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ConsumerLayer;
+
+#[async_trait::async_trait]
+impl WiringLayer for ConsumerLayer {
+    fn layer_name(&self) -> &'static str {
+        "consumer_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.get_resource::<DummyResource>().await?;
+        node.add_task(Box::new(ErrorTask));
+        node.get_resource::<LackingResource>().await?;
+        Ok(())
+    }
+}
+
+// `ZkStack` Service's `describe()` method has to record the wiring without running tasks.
+#[test]
+fn test_describe() {
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service
+        .add_layer(ProviderLayer)
+        .add_layer(ConsumerLayer);
+    let description = zk_stack_service.build().unwrap().describe();
+
+    assert_eq!(description.layers.len(), 2);
+    let provider = &description.layers[0];
+    assert_eq!(provider.name, "provider_layer");
+    assert_eq!(provider.provided_resources, ["common/dummy"]);
+    assert!(provider.error.is_none());
+
+    let consumer = &description.layers[1];
+    assert_eq!(consumer.name, "consumer_layer");
+    let requests: Vec<_> = consumer
+        .requested_resources
+        .iter()
+        .map(|req| (req.name.as_str(), req.available))
+        .collect();
+    assert_eq!(
+        requests,
+        [("common/dummy", true), ("common/lacking", false)]
+    );
+    assert_eq!(consumer.runnables.len(), 1);
+    assert_eq!(consumer.runnables[0].name, "error_task");
+    assert_eq!(consumer.runnables[0].kind, RunnableKind::Task);
+    assert!(consumer.error.is_some());
+
+    assert_eq!(
+        description
+            .missing_resources()
+            .into_iter()
+            .collect::<Vec<_>>(),
+        ["common/lacking"]
+    );
+
+    let dot = description.render(DescriptionFormat::Dot);
+    assert!(dot.starts_with("digraph"), "{dot}");
+    assert!(
+        dot.contains(r#""layer:provider_layer" -> "resource:common/dummy""#),
+        "{dot}"
+    );
+    assert!(
+        dot.contains(r#""resource:common/dummy" -> "layer:consumer_layer""#),
+        "{dot}"
+    );
+
+    let json = description.render(DescriptionFormat::Json);
+    let json: serde_json::Value = serde_json::from_str(&json).unwrap();
+    assert_eq!(json["layers"][1]["runnables"][0]["kind"], "task");
+}
+
+#[test]
+fn wiring_error_lists_missing_resources() {
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service
+        .add_layer(ProviderLayer)
+        .add_layer(ConsumerLayer)
+        .add_layer(WireErrorLayer);
+    let err = zk_stack_service.build().unwrap().run().unwrap_err();
+    assert_matches!(&err, ZkStackServiceError::Wiring(errors) if errors.len() == 2);
+
+    let err = err.to_string();
+    assert!(
+        err.contains("- consumer_layer: Resource common/lacking is not provided"),
+        "{err}"
+    );
+    assert!(err.contains("- wire_error_layer: "), "{err}");
+    assert!(
+        err.contains("- common/lacking, requested by consumer_layer"),
+        "{err}"
+    );
+}
+
+#[derive(Debug, Clone)]
+struct FlakyTask {
+    runs: Arc<AtomicUsize>,
+    failures: usize,
+    max_restarts: usize,
+}
+
+#[async_trait::async_trait]
+impl Task for FlakyTask {
+    fn name(&self) -> &'static str {
+        "flaky_task"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        let run = self.runs.fetch_add(1, Ordering::SeqCst);
+        if run < self.failures {
+            anyhow::bail!("flaky task failed on run #{run}");
+        }
+        Ok(())
+    }
+
+    fn restart_policy(&self) -> RestartPolicy {
+        let backoff = RestartBackoff {
+            initial_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(10),
+            max_restarts: None,
+        };
+        RestartPolicy::OnFailure(backoff.with_max_restarts(self.max_restarts))
+    }
+
+    fn restart(&self) -> Option<Box<dyn Task>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug)]
+struct FlakyTaskLayer {
+    task: FlakyTask,
+    app_health: Arc<AppHealthCheck>,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for FlakyTaskLayer {
+    fn layer_name(&self) -> &'static str {
+        "flaky_task_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        node.insert_resource(AppHealthCheckResource(self.app_health))?;
+        node.add_task(Box::new(self.task));
+        Ok(())
+    }
+}
+
+// Tasks with a restart policy have to be restarted after failures.
+#[test]
+fn test_task_restarts() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let app_health = Arc::new(AppHealthCheck::default());
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(FlakyTaskLayer {
+        task: FlakyTask {
+            runs: runs.clone(),
+            failures: 2,
+            max_restarts: 3,
+        },
+        app_health: app_health.clone(),
+    });
+    zk_stack_service.build().unwrap().run().unwrap();
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+
+    let health = Runtime::new().unwrap().block_on(app_health.check_health());
+    assert!(
+        health.components().contains_key("task/flaky_task"),
+        "{health:?}"
+    );
+}
+
+// Tasks have to shut down the node once they exhaust restarts.
+#[test]
+fn test_task_exhausting_restarts() {
+    let runs = Arc::new(AtomicUsize::new(0));
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(FlakyTaskLayer {
+        task: FlakyTask {
+            runs: runs.clone(),
+            failures: 5,
+            max_restarts: 2,
+        },
+        app_health: Arc::default(),
+    });
+    let result = zk_stack_service.build().unwrap().run();
+    assert_matches!(result.unwrap_err(), ZkStackServiceError::Task(_));
+    assert_eq!(runs.load(Ordering::SeqCst), 3);
+}
+
+#[derive(Debug)]
+struct PhasedTask {
+    name: &'static str,
+    phase: ShutdownPhase,
+    stopped_tasks: Arc<Mutex<Vec<&'static str>>>,
+}
+
+#[async_trait::async_trait]
+impl Task for PhasedTask {
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        self.phase
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        stop_receiver.0.changed().await?;
+        // Give tasks from the later phases a chance to stop if they've received the signal prematurely.
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        self.stopped_tasks.lock().unwrap().push(self.name);
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct HangingTask;
+
+#[async_trait::async_trait]
+impl Task for HangingTask {
+    fn name(&self) -> &'static str {
+        "hanging_task"
+    }
+
+    fn shutdown_timeout(&self) -> Duration {
+        Duration::from_millis(10)
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        futures::future::pending().await
+    }
+}
+
+#[derive(Debug)]
+struct PhasedTasksLayer {
+    stopped_tasks: Arc<Mutex<Vec<&'static str>>>,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for PhasedTasksLayer {
+    fn layer_name(&self) -> &'static str {
+        "phased_tasks_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        for (name, phase) in [
+            ("storage_task", ShutdownPhase::Storage),
+            ("ingress_task", ShutdownPhase::Ingress),
+            ("processing_task", ShutdownPhase::Processing),
+        ] {
+            node.add_task(Box::new(PhasedTask {
+                name,
+                phase,
+                stopped_tasks: self.stopped_tasks.clone(),
+            }));
+        }
+        node.add_task(Box::new(HangingTask));
+        node.add_task(Box::new(ErrorTask));
+        Ok(())
+    }
+}
+
+// Tasks have to be stopped according to their shutdown phases; tasks not finishing in time have to be aborted.
+#[test]
+fn test_shutdown_phases() {
+    let stopped_tasks = Arc::default();
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(PhasedTasksLayer {
+        stopped_tasks: Arc::clone(&stopped_tasks),
+    });
+    let result = zk_stack_service.build().unwrap().run();
+    assert_matches!(result.unwrap_err(), ZkStackServiceError::Task(_));
+
+    let stopped_tasks = stopped_tasks.lock().unwrap();
+    assert_eq!(
+        *stopped_tasks,
+        ["ingress_task", "processing_task", "storage_task"]
+    );
+}
+
+#[derive(Debug, Clone)]
+struct ControllableTask {
+    runs: Arc<AtomicUsize>,
+    stops: Arc<AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl Task for ControllableTask {
+    fn name(&self) -> &'static str {
+        "controllable_task"
+    }
+
+    async fn run(self: Box<Self>, mut stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        self.runs.fetch_add(1, Ordering::SeqCst);
+        stop_receiver.0.changed().await?;
+        self.stops.fetch_add(1, Ordering::SeqCst);
+        Ok(())
+    }
+
+    fn restart(&self) -> Option<Box<dyn Task>> {
+        Some(Box::new(self.clone()))
+    }
+}
+
+#[derive(Debug)]
+struct ControllingTask {
+    task: ControllableTask,
+    controller: TaskController,
+}
+
+impl ControllingTask {
+    async fn wait_for(counter: &AtomicUsize, value: usize) {
+        while counter.load(Ordering::SeqCst) < value {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for ControllingTask {
+    fn name(&self) -> &'static str {
+        "controlling_task"
+    }
+
+    async fn run(self: Box<Self>, _stop_receiver: StopReceiver) -> anyhow::Result<()> {
+        Self::wait_for(&self.task.runs, 1).await;
+        assert_eq!(
+            self.controller.tasks().into_iter().collect::<Vec<_>>(),
+            [("controllable_task", true)]
+        );
+        assert!(self.controller.set_enabled("controllable_task", false)?);
+        Self::wait_for(&self.task.stops, 1).await;
+        assert!(!self.controller.set_enabled("controllable_task", false)?);
+        assert_eq!(self.task.runs.load(Ordering::SeqCst), 1);
+
+        assert!(self.controller.set_enabled("controllable_task", true)?);
+        Self::wait_for(&self.task.runs, 2).await;
+        assert_matches!(
+            self.controller.set_enabled("controlling_task", false),
+            Err(TaskControlError::UnknownTask(_))
+        );
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+struct ControlLayer {
+    task: ControllableTask,
+}
+
+#[async_trait::async_trait]
+impl WiringLayer for ControlLayer {
+    fn layer_name(&self) -> &'static str {
+        "control_layer"
+    }
+
+    async fn wire(self: Box<Self>, mut node: ServiceContext<'_>) -> Result<(), WiringError> {
+        let TaskControllerResource(controller) = node.get_resource().await?;
+        node.add_task(Box::new(self.task.clone()));
+        node.add_task(Box::new(ControllingTask {
+            task: self.task,
+            controller,
+        }));
+        Ok(())
+    }
+}
+
+// Tasks supporting restarts have to be stoppable and restartable via `TaskController`.
+#[test]
+fn test_task_control() {
+    let task = ControllableTask {
+        runs: Arc::default(),
+        stops: Arc::default(),
+    };
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(ControlLayer { task: task.clone() });
+    zk_stack_service.build().unwrap().run().unwrap();
+
+    assert_eq!(task.runs.load(Ordering::SeqCst), 2);
+    assert_eq!(task.stops.load(Ordering::SeqCst), 2);
+}
+
+// Resources provided by the service itself must not be reported as missing.
+#[test]
+fn builtin_resources_are_not_missing() {
+    let task = ControllableTask {
+        runs: Arc::default(),
+        stops: Arc::default(),
+    };
+    let mut zk_stack_service = ZkStackServiceBuilder::new();
+    zk_stack_service.add_layer(ControlLayer { task });
+    let description = zk_stack_service.build().unwrap().describe();
+
+    assert_eq!(
+        description.builtin_resources,
+        [TaskControllerResource::name()]
+    );
+    assert!(description.missing_resources().is_empty());
+}
@@ -27,13 +27,136 @@
 //! The unrestricted tasks are rarely needed, but two common cases for them are:
 //! - A task that must be started as soon as possible, e.g. healthcheck server.
 //! - A task that may be a driving force for some precondition to be met.
+//!
+//! ## Restarting tasks
+//!
+//! By default, the node shuts down once any [`Task`] exits. Non-critical tasks may instead declare
+//! a [`RestartPolicy`] and implement [`Task::restart()`], in which case the service restarts them with
+//! an exponential backoff. If the service has an [`AppHealthCheckResource`] at the end of wiring,
+//! each [`Task`] gets a `task/{name}` health check component reporting its restart count.
+//!
+//!
+//! ## Shutdown
+//!
+//! Long-running tasks are stopped in [shutdown phases](ShutdownPhase), which allows expressing teardown
+//! dependencies between tasks (e.g., API servers stop accepting requests before the tasks they rely on are stopped).
+//! Each task has a [timeout](Task::shutdown_timeout()) to finish after receiving the stop signal;
+//! tasks that don't finish in time are aborted.
+//!
+//! [`AppHealthCheckResource`]: crate::implementations::resources::healthcheck::AppHealthCheckResource
 
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use tokio::sync::Barrier;
+use futures::future;
+use serde::Serialize;
+use tokio::sync::{watch, Barrier};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater};
 
 use crate::service::StopReceiver;
 
+/// Default amount of time a long-running task has to finish after receiving the stop signal.
+pub const DEFAULT_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Phase of the node shutdown in which a long-running task receives the stop signal.
+///
+/// Phases are processed in the order of declaration: the service sends the stop signal to all tasks in a phase
+/// and waits until they finish (or until their shutdown timeouts expire) before proceeding to the next phase.
+/// Thus, a task may rely on tasks from the later phases (and resources they use) during its shutdown.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ShutdownPhase {
+    /// Tasks accepting external requests, e.g. API servers. Stopped first, so that no new requests are accepted
+    /// during the shutdown.
+    Ingress,
+    /// Default phase for most tasks.
+    #[default]
+    Processing,
+    /// Tasks owning persistent storage that may need to be flushed on shutdown, e.g. the metadata calculator.
+    Storage,
+    /// Auxiliary tasks, e.g. the healthcheck server. Stopped last, so that they can report the shutdown progress.
+    Auxiliary,
+}
+
+impl ShutdownPhase {
+    /// All phases in the shutdown order.
+    pub const ALL: [Self; 4] = [
+        Self::Ingress,
+        Self::Processing,
+        Self::Storage,
+        Self::Auxiliary,
+    ];
+}
+
+/// Backoff parameters for restarting a [`Task`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RestartBackoff {
+    /// Delay before the first restart. Each consecutive restart doubles the delay.
+    pub initial_delay: Duration,
+    /// Maximum delay between restarts. If a task instance runs for at least this long before exiting,
+    /// the delay is reset to [`Self::initial_delay`].
+    pub max_delay: Duration,
+    /// Maximum total number of restarts. If `None`, the task is restarted indefinitely.
+    pub max_restarts: Option<usize>,
+}
+
+impl Default for RestartBackoff {
+    fn default() -> Self {
+        Self {
+            initial_delay: Duration::from_secs(1),
+            max_delay: Duration::from_secs(60),
+            max_restarts: None,
+        }
+    }
+}
+
+impl RestartBackoff {
+    /// Sets the maximum total number of restarts.
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts = Some(max_restarts);
+        self
+    }
+
+    fn delay(&self, consecutive_restarts: u32) -> Duration {
+        let multiplier = 1_u32.checked_shl(consecutive_restarts).unwrap_or(u32::MAX);
+        self.initial_delay
+            .saturating_mul(multiplier)
+            .min(self.max_delay)
+    }
+}
+
+/// Policy determining whether the service restarts a [`Task`] after it exits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// The task is never restarted; the node shuts down once the task exits.
+    #[default]
+    Never,
+    /// The task is restarted after it returns an error. If the task exits successfully, the node shuts down.
+    OnFailure(RestartBackoff),
+    /// The task is restarted after it exits, regardless of the outcome.
+    Always(RestartBackoff),
+}
+
+impl RestartPolicy {
+    fn backoff(&self, task_result: &anyhow::Result<()>) -> Option<&RestartBackoff> {
+        match self {
+            Self::Never => None,
+            Self::OnFailure(backoff) => task_result.is_err().then_some(backoff),
+            Self::Always(backoff) => Some(backoff),
+        }
+    }
+}
+
+/// Details of the health check component for a [`Task`].
+#[derive(Debug, Serialize)]
+struct TaskHealthDetails {
+    restarts: usize,
+    disabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    last_error: Option<String>,
+}
+
 /// A task implementation.
 ///
 /// Note: any `Task` added to the service will only start after all the [preconditions](crate::precondition::Precondition)
@@ -45,7 +168,8 @@ pub trait Task: 'static + Send {
 
     /// Runs the task.
     ///
-    /// Once any of the task returns, the node will shutdown.
+    /// Once any of the task returns, the node will shutdown, unless the task is restarted according to
+    /// its [restart policy](Self::restart_policy()).
     /// If the task returns an error, the node will spawn an error-level log message and will return a non-zero
     /// exit code.
     ///
@@ -55,26 +179,198 @@ pub trait Task: 'static + Send {
     ///
     /// Each task is expected to perform the required cleanup after receiving the stop signal.
     async fn run(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()>;
+
+    /// Returns the restart policy for this task. By default, the task is never restarted.
+    ///
+    /// Tasks with a policy other than [`RestartPolicy::Never`] must also implement [`Self::restart()`].
+    fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy::Never
+    }
+
+    /// Creates a new instance of the task that will be run after the current instance exits, according to
+    /// [`Self::restart_policy()`]. Since [`Self::run()`] consumes the task, this method is called before
+    /// the current instance is started.
+    ///
+    /// Tasks supporting restarts can also be disabled and re-enabled at runtime via
+    /// [`TaskController`](crate::service::TaskController).
+    ///
+    /// By default, returns `None`, meaning that the task cannot be restarted regardless of its policy.
+    fn restart(&self) -> Option<Box<dyn Task>> {
+        None
+    }
+
+    /// Returns the shutdown phase in which the task receives the stop signal.
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::default()
+    }
+
+    /// Returns the amount of time the task has to finish after receiving the stop signal.
+    /// If the task doesn't finish in time, it is aborted.
+    fn shutdown_timeout(&self) -> Duration {
+        DEFAULT_SHUTDOWN_TIMEOUT
+    }
 }
 
 impl dyn Task {
-    /// An internal helper method that guards running the task with a tokio Barrier.
-    /// Used to make sure that the task is not started until all the preconditions are met.
-    pub(super) async fn run_with_barrier(
+    /// An internal helper method that guards running the task with a tokio Barrier, and restarts the task
+    /// according to its [`RestartPolicy`].
+    /// The barrier is used to make sure that the task is not started until all the preconditions are met.
+    ///
+    /// `next_instance` is the instance to run after the initial one exits (see [`Task::restart()`]).
+    /// If `control` is provided, the task is stopped once it's disabled, and restarted once it's enabled again.
+    pub(super) async fn run_supervised(
         self: Box<Self>,
         mut stop_receiver: StopReceiver,
         preconditions_barrier: Arc<Barrier>,
+        health_updater: Option<HealthUpdater>,
+        mut next_instance: Option<Box<dyn Task>>,
+        mut control: Option<watch::Receiver<bool>>,
     ) -> anyhow::Result<()> {
         // Wait either for barrier to be lifted or for the stop signal to be received.
         tokio::select! {
-            _ = preconditions_barrier.wait() => {
-                self.run(stop_receiver).await
+            _ = preconditions_barrier.wait() => {}
+            _ = stop_receiver.0.changed() => return Ok(()),
+        }
+
+        let name = self.name();
+        let policy = self.restart_policy();
+        let update_health = |status: HealthStatus, details: TaskHealthDetails| {
+            if let Some(updater) = &health_updater {
+                updater.update(Health::from(status).with_details(details));
             }
-            _ = stop_receiver.0.changed() => {
-                Ok(())
+        };
+
+        let mut task = self;
+        let mut restarts = 0;
+        let mut consecutive_restarts = 0;
+        let mut last_error = None;
+        loop {
+            if !control.as_ref().map_or(true, |control| *control.borrow()) {
+                tracing::info!("Task {name} is disabled; waiting for it to be enabled");
+                update_health(
+                    HealthStatus::Affected,
+                    TaskHealthDetails {
+                        restarts,
+                        disabled: true,
+                        last_error: last_error.clone(),
+                    },
+                );
+                tokio::select! {
+                    () = wait_for_control(&mut control, true) => {
+                        tracing::info!("Task {name} was enabled; starting it");
+                    }
+                    () = wait_for_stop(&mut stop_receiver) => return Ok(()),
+                }
             }
+
+            if next_instance.is_none() {
+                next_instance = task.restart();
+            }
+            update_health(
+                HealthStatus::Ready,
+                TaskHealthDetails {
+                    restarts,
+                    disabled: false,
+                    last_error: last_error.clone(),
+                },
+            );
+
+            // The task instance has a dedicated stop signal, so that it can be stopped when the task is disabled.
+            let (instance_stop_sender, instance_stop_receiver) = watch::channel(false);
+            let started_at = Instant::now();
+            let mut run = task.run(StopReceiver(instance_stop_receiver));
+            let mut is_disabled = false;
+            let result = tokio::select! {
+                result = &mut run => result,
+                () = wait_for_stop(&mut stop_receiver) => {
+                    instance_stop_sender.send_replace(true);
+                    run.await
+                }
+                () = wait_for_control(&mut control, false) => {
+                    tracing::info!("Task {name} was disabled; stopping it");
+                    is_disabled = true;
+                    instance_stop_sender.send_replace(true);
+                    run.await
+                }
+            };
+            if *stop_receiver.0.borrow() {
+                return result;
+            }
+
+            if is_disabled {
+                if let Err(err) = &result {
+                    tracing::warn!("Task {name} failed while being disabled: {err:#}");
+                }
+                let Some(instance) = next_instance.take() else {
+                    tracing::error!("Task {name} was disabled, but does not support restarting");
+                    return result;
+                };
+                task = instance;
+                consecutive_restarts = 0;
+                continue;
+            }
+
+            let Some(backoff) = policy.backoff(&result) else {
+                return result;
+            };
+            if backoff.max_restarts.is_some_and(|max| restarts >= max) {
+                tracing::error!("Task {name} has exited and exhausted {restarts} restarts");
+                return result;
+            }
+            let Some(instance) = next_instance.take() else {
+                tracing::error!(
+                    "Task {name} has restart policy {policy:?}, but does not support restarting"
+                );
+                return result;
+            };
+
+            if started_at.elapsed() >= backoff.max_delay {
+                consecutive_restarts = 0;
+            }
+            let delay = backoff.delay(consecutive_restarts);
+            consecutive_restarts += 1;
+            restarts += 1;
+            match &result {
+                Ok(()) => tracing::warn!("Task {name} has exited; restarting it in {delay:?}"),
+                Err(err) => {
+                    tracing::error!("Task {name} failed: {err:#}; restarting it in {delay:?}");
+                    last_error = Some(format!("{err:#}"));
+                }
+            }
+            update_health(
+                HealthStatus::Affected,
+                TaskHealthDetails {
+                    restarts,
+                    disabled: false,
+                    last_error: last_error.clone(),
+                },
+            );
+
+            if tokio::time::timeout(delay, wait_for_stop(&mut stop_receiver))
+                .await
+                .is_ok()
+            {
+                return Ok(());
+            }
+            task = instance;
+        }
+    }
+}
+
+async fn wait_for_stop(stop_receiver: &mut StopReceiver) {
+    // Ignore the error: the stop signal sender is held by the service.
+    stop_receiver.0.wait_for(|&stop| stop).await.ok();
+}
+
+/// Waits until the task is enabled / disabled. Never resolves if the task is not controllable.
+async fn wait_for_control(control: &mut Option<watch::Receiver<bool>>, enabled: bool) {
+    if let Some(control) = control {
+        if control.wait_for(|&value| value == enabled).await.is_ok() {
+            return;
         }
     }
+    // The task is not controllable, or the controller was dropped.
+    future::pending().await
 }
 
 /// A oneshot task implementation.
@@ -134,6 +430,17 @@ pub trait UnconstrainedTask: 'static + Send {
 
     /// Runs the task without waiting for any precondition to be met.
     async fn run_unconstrained(self: Box<Self>, stop_receiver: StopReceiver) -> anyhow::Result<()>;
+
+    /// Returns the shutdown phase in which the task receives the stop signal.
+    fn shutdown_phase(&self) -> ShutdownPhase {
+        ShutdownPhase::default()
+    }
+
+    /// Returns the amount of time the task has to finish after receiving the stop signal.
+    /// If the task doesn't finish in time, it is aborted.
+    fn shutdown_timeout(&self) -> Duration {
+        DEFAULT_SHUTDOWN_TIMEOUT
+    }
 }
 
 /// An unconstrained analog of [`OneshotTask`].
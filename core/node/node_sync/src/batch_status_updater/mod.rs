@@ -10,6 +10,7 @@ use serde::Serialize;
 use tokio::sync::mpsc;
 use tokio::sync::watch;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_error_codes::{ErrorCode, HasErrorCode};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_shared_metrics::EN_METRICS;
 use zksync_types::{
@@ -71,6 +72,15 @@ impl From<zksync_dal::DalError> for UpdaterError {
     }
 }
 
+impl HasErrorCode for UpdaterError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Web3(_) => ErrorCode::SyncMainNodeRpc,
+            Self::Internal(_) => ErrorCode::SyncInternal,
+        }
+    }
+}
+
 #[async_trait]
 trait MainNodeClient: fmt::Debug + Send + Sync {
     /// Returns any L2 block in the specified L1 batch.
@@ -296,10 +306,19 @@ impl BatchStatusUpdater {
             // Note that we don't update `cursor` here (it is copied), but rather only in `apply_status_changes`.
             match self.get_status_changes(&mut status_changes, cursor).await {
                 Ok(()) => { /* everything went smoothly */ }
-                Err(UpdaterError::Web3(err)) => {
-                    tracing::warn!("Failed to get status changes from the main node: {err}");
+                Err(err) => {
+                    let error_code = err.error_code();
+                    error_code.observe();
+                    match err {
+                        UpdaterError::Web3(err) => {
+                            tracing::warn!(
+                                error_code = error_code.as_str(),
+                                "Failed to get status changes from the main node: {err}"
+                            );
+                        }
+                        UpdaterError::Internal(err) => return Err(err),
+                    }
                 }
-                Err(UpdaterError::Internal(err)) => return Err(err),
             }
 
             if status_changes.is_empty() {
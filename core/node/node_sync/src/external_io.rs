@@ -3,6 +3,7 @@ use std::{collections::HashMap, time::Duration};
 use anyhow::Context as _;
 use async_trait::async_trait;
 use vm_utils::storage::L1BatchParamsProvider;
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_contracts::{BaseSystemContracts, BaseSystemContractsHashes, SystemContractCode};
 use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_state_keeper::{
@@ -12,7 +13,7 @@ use zksync_state_keeper::{
         L1BatchParams, L2BlockParams, PendingBatchData, StateKeeperIO,
     },
     metrics::KEEPER_METRICS,
-    seal_criteria::IoSealCriteria,
+    seal_criteria::{IoSealCriteria, L2BlockSealVerifier},
     updates::UpdatesManager,
 };
 use zksync_types::{
@@ -23,6 +24,7 @@ use zksync_utils::bytes_to_be_words;
 
 use super::{
     client::MainNodeClient,
+    l1_commit_verifier::L1CommitVerifier,
     sync_action::{ActionQueue, SyncAction},
 };
 
@@ -39,6 +41,8 @@ pub struct ExternalIO {
     actions: ActionQueue,
     main_node_client: Box<dyn MainNodeClient>,
     chain_id: L2ChainId,
+    l2_block_seal_verifier: Option<L2BlockSealVerifier>,
+    l1_commit_verifier: Option<L1CommitVerifier>,
 }
 
 impl ExternalIO {
@@ -60,9 +64,29 @@ impl ExternalIO {
             actions,
             main_node_client,
             chain_id,
+            l2_block_seal_verifier: None,
+            l1_commit_verifier: None,
         })
     }
 
+    /// Enables verification of L2 blocks received from the main node against the locally configured
+    /// seal criteria. L1 batches are verified separately by [`VerifyingSealer`](zksync_state_keeper::seal_criteria::VerifyingSealer).
+    #[must_use]
+    pub fn with_seal_verification(mut self, config: &StateKeeperConfig) -> Self {
+        self.l2_block_seal_verifier = Some(L2BlockSealVerifier::new(config));
+        self
+    }
+
+    /// Enables verification of L1 batches received from the main node against their commit transactions on L1
+    /// before the batches are executed. The state keeper stops on a detected divergence. Executed batches
+    /// are compared with the committed data by [`L1CommitVerifier::executed_batch_checker()`], which should be
+    /// added to the state keeper output handler.
+    #[must_use]
+    pub fn with_l1_commit_verification(mut self, verifier: L1CommitVerifier) -> Self {
+        self.l1_commit_verifier = Some(verifier);
+        self
+    }
+
     async fn get_base_system_contract(
         &self,
         hash: H256,
@@ -118,9 +142,16 @@ impl IoSealCriteria for ExternalIO {
         true
     }
 
-    fn should_seal_l2_block(&mut self, _manager: &UpdatesManager) -> bool {
-        if !matches!(self.actions.peek_action(), Some(SyncAction::SealL2Block)) {
-            return false;
+    fn should_seal_l2_block(&mut self, manager: &UpdatesManager) -> bool {
+        match self.actions.peek_action() {
+            Some(SyncAction::SealL2Block) => {}
+            Some(SyncAction::Tx(_)) => {
+                if let Some(verifier) = &mut self.l2_block_seal_verifier {
+                    verifier.verify_l2_block_continued(manager);
+                }
+                return false;
+            }
+            _ => return false,
         }
         self.actions.pop_action();
         true
@@ -236,6 +267,9 @@ impl StateKeeperIO for ExternalIO {
                     "L2 block number mismatch: expected {}, got {first_l2_block_number}",
                     cursor.next_l2_block
                 );
+                if let Some(verifier) = &self.l1_commit_verifier {
+                    verifier.verify(number, &params).await?;
+                }
                 return Ok(Some(params));
             }
             other => {
@@ -0,0 +1,589 @@
+//! Verification of L1 batches received from the main node against their commit transactions on L1.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use zksync_consistency_checker::{
+    commit_function, committed_batch_numbers_by_logs, ConsistencyChecker,
+};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
+use zksync_eth_client::{EthInterface, ExecutedTxStatus};
+use zksync_state_keeper::{io::L1BatchParams, StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{ethabi, web3::Transaction, Address, L1BatchNumber, H256, U256};
+use zksync_web3_decl::{
+    client::{DynClient, L1, L2},
+    error::ClientRpcContext,
+    namespaces::ZksNamespaceClient,
+};
+
+use crate::metrics::{L1CommitCheckResult, L1_COMMIT_VERIFIER_METRICS};
+
+/// Maximum number of L1 batches awaiting the state root comparison. If the Merkle tree lags further behind
+/// the state keeper, the oldest batches are not compared; they are still checked by the consistency checker.
+const MAX_PENDING_ROOT_HASHES: usize = 1_000;
+
+/// Data committed on L1 for an L1 batch that is compared with the batch executed by the node.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct CommittedBatch {
+    l1_tx_count: U256,
+    root_hash: H256,
+}
+
+type CommittedBatches = Arc<Mutex<BTreeMap<L1BatchNumber, CommittedBatch>>>;
+
+/// Verifies L1 batches received from the main node against the calldata of their commit transactions on L1
+/// *before* the batches are executed by the state keeper.
+///
+/// The verifier checks the L1 batch number and timestamp before the batch is executed. The number of L1 transactions
+/// and the state root are compared with the executed batch by [`ExecutedBatchChecker`], which should be plugged
+/// into the state keeper. Batches that are not committed on L1 yet (e.g., when the node is close
+/// to the chain tip), as well as batches for which the check fails because of network errors, are not verified;
+/// such batches are still checked post-hoc by the consistency checker.
+#[derive(Debug)]
+pub struct L1CommitVerifier {
+    main_node_client: Box<DynClient<L2>>,
+    l1_client: Box<DynClient<L1>>,
+    diamond_proxy_addr: Address,
+    contract: ethabi::Contract,
+    committed_batches: CommittedBatches,
+}
+
+impl L1CommitVerifier {
+    pub fn new(
+        main_node_client: Box<DynClient<L2>>,
+        l1_client: Box<DynClient<L1>>,
+        diamond_proxy_addr: Address,
+    ) -> Self {
+        Self {
+            main_node_client: main_node_client.for_component("l1_commit_verifier"),
+            l1_client: l1_client.for_component("l1_commit_verifier"),
+            diamond_proxy_addr,
+            contract: zksync_contracts::hyperchain_contract(),
+            committed_batches: CommittedBatches::default(),
+        }
+    }
+
+    /// Returns the state keeper output handler comparing executed L1 batches with the data committed on L1.
+    /// The handler must run before the state keeper persistence so that divergent batches aren't persisted.
+    pub fn executed_batch_checker(&self, pool: ConnectionPool<Core>) -> ExecutedBatchChecker {
+        ExecutedBatchChecker {
+            pool,
+            committed_batches: self.committed_batches.clone(),
+            pending_root_hashes: BTreeMap::new(),
+        }
+    }
+
+    /// Verifies the L1 batch with the specified number and params. Returns an error if the batch data
+    /// diverges from the data committed on L1.
+    pub(crate) async fn verify(
+        &self,
+        number: L1BatchNumber,
+        params: &L1BatchParams,
+    ) -> anyhow::Result<()> {
+        let (commit_tx_status, commit_tx) = match self.fetch_commit_tx(number).await {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                tracing::debug!(
+                    "L1 batch #{number} is not committed on L1 yet; skipping verification"
+                );
+                L1_COMMIT_VERIFIER_METRICS.batches[&L1CommitCheckResult::Skipped].inc();
+                return Ok(());
+            }
+            Err(err) => {
+                tracing::warn!(
+                    "Failed fetching commit transaction for L1 batch #{number}; skipping verification: {err:#}"
+                );
+                L1_COMMIT_VERIFIER_METRICS.batches[&L1CommitCheckResult::Skipped].inc();
+                return Ok(());
+            }
+        };
+
+        let committed_batch = self
+            .verify_commit_tx(number, params, commit_tx_status, &commit_tx)
+            .with_context(|| {
+                format!(
+                    "L1 batch #{number} received from the main node diverges from the data committed on L1 \
+                     in transaction {:?}",
+                    commit_tx.hash
+                )
+            })?;
+        self.committed_batches
+            .lock()
+            .unwrap()
+            .insert(number, committed_batch);
+        tracing::info!("L1 batch #{number} is consistent with its commit transaction on L1");
+        L1_COMMIT_VERIFIER_METRICS.batches[&L1CommitCheckResult::Verified].inc();
+        Ok(())
+    }
+
+    /// Returns the status and contents of the successfully executed commit transaction for the batch,
+    /// or `None` if the batch is not committed on L1.
+    async fn fetch_commit_tx(
+        &self,
+        number: L1BatchNumber,
+    ) -> anyhow::Result<Option<(ExecutedTxStatus, Transaction)>> {
+        let details = self
+            .main_node_client
+            .get_l1_batch_details(number)
+            .rpc_context("get_l1_batch_details")
+            .with_arg("number", &number)
+            .await?;
+        let Some(commit_tx_hash) = details.and_then(|details| details.base.commit_tx_hash) else {
+            return Ok(None);
+        };
+
+        // The commit transaction may not be mined yet, or may be reverted if it's retried by the main node.
+        let Some(commit_tx_status) = self.l1_client.get_tx_status(commit_tx_hash).await? else {
+            return Ok(None);
+        };
+        if !commit_tx_status.success {
+            return Ok(None);
+        }
+        let commit_tx = self
+            .l1_client
+            .get_tx(commit_tx_hash)
+            .await?
+            .with_context(|| format!("commit transaction {commit_tx_hash:?} not found on L1"))?;
+        Ok(Some((commit_tx_status, commit_tx)))
+    }
+
+    fn verify_commit_tx(
+        &self,
+        number: L1BatchNumber,
+        params: &L1BatchParams,
+        commit_tx_status: ExecutedTxStatus,
+        commit_tx: &Transaction,
+    ) -> anyhow::Result<CommittedBatch> {
+        // Commit transactions are usually sent via `ValidatorTimelock`, so we check event logs rather than the recipient.
+        let event = self
+            .contract
+            .event("BlockCommit")
+            .context("`BlockCommit` event not found for zkSync L1 contract")?;
+        let committed_batch_numbers = committed_batch_numbers_by_logs(
+            event,
+            self.diamond_proxy_addr,
+            commit_tx_status.receipt.logs,
+        );
+        anyhow::ensure!(
+            committed_batch_numbers.contains(&U256::from(number.0)),
+            "commit transaction does not contain `BlockCommit` event log emitted by the diamond proxy {:?}",
+            self.diamond_proxy_addr
+        );
+
+        let commit_function = commit_function(&self.contract, Some(params.protocol_version))?;
+        let commitment =
+            ConsistencyChecker::extract_commit_data(&commit_tx.input.0, commit_function, number)
+                .context("failed extracting commit data")?;
+        verify_commitment(number, params.first_l2_block.timestamp, &commitment)
+    }
+}
+
+/// Compares the batch commitment from L1 with the batch that is about to be executed. Returns the committed data
+/// that can only be compared with the batch after it's executed.
+fn verify_commitment(
+    number: L1BatchNumber,
+    timestamp: u64,
+    commitment: &ethabi::Token,
+) -> anyhow::Result<CommittedBatch> {
+    // All commitment formats start with the same fields.
+    let ethabi::Token::Tuple(tokens) = commitment else {
+        anyhow::bail!("unexpected commitment shape: {commitment:?}");
+    };
+    let [committed_number, committed_timestamp, _, committed_root_hash, committed_l1_tx_count, ..] =
+        tokens.as_slice()
+    else {
+        anyhow::bail!("unexpected commitment shape: {commitment:?}");
+    };
+    let as_uint = |token: &ethabi::Token, name: &str| {
+        token
+            .clone()
+            .into_uint()
+            .with_context(|| format!("unexpected `{name}` in commitment: {token:?}"))
+    };
+
+    let committed_number = as_uint(committed_number, "batchNumber")?;
+    anyhow::ensure!(
+        committed_number == U256::from(number.0),
+        "commitment is for L1 batch #{committed_number}"
+    );
+    let committed_timestamp = as_uint(committed_timestamp, "timestamp")?;
+    anyhow::ensure!(
+        committed_timestamp == U256::from(timestamp),
+        "timestamp mismatch: main node provided {timestamp}, L1 has {committed_timestamp}"
+    );
+    let l1_tx_count = as_uint(committed_l1_tx_count, "numberOfLayer1Txs")?;
+
+    let committed_root_hash = committed_root_hash
+        .clone()
+        .into_fixed_bytes()
+        .filter(|bytes| bytes.len() == 32)
+        .with_context(|| {
+            format!("unexpected `newStateRoot` in commitment: {committed_root_hash:?}")
+        })?;
+    Ok(CommittedBatch {
+        l1_tx_count,
+        root_hash: H256::from_slice(&committed_root_hash),
+    })
+}
+
+/// State keeper output handler comparing L1 batches executed by the node with the data committed on L1
+/// for batches verified by [`L1CommitVerifier`].
+///
+/// The number of L1 transactions is compared when the batch is sealed, before it's persisted. The state root
+/// is compared once it's computed by the node's Merkle tree; since the tree runs asynchronously, the comparison
+/// is performed when one of the following batches is sealed.
+#[derive(Debug)]
+pub struct ExecutedBatchChecker {
+    pool: ConnectionPool<Core>,
+    committed_batches: CommittedBatches,
+    pending_root_hashes: BTreeMap<L1BatchNumber, H256>,
+}
+
+impl ExecutedBatchChecker {
+    async fn check_root_hashes(&mut self) -> anyhow::Result<()> {
+        if self.pending_root_hashes.is_empty() {
+            return Ok(());
+        }
+
+        let mut storage = self.pool.connection_tagged("sync_layer").await?;
+        while let Some(entry) = self.pending_root_hashes.first_entry() {
+            let number = *entry.key();
+            let Some(local_root_hash) =
+                storage.blocks_dal().get_l1_batch_state_root(number).await?
+            else {
+                // The tree processes batches sequentially, so later batches don't have root hashes either.
+                break;
+            };
+            let committed_root_hash = entry.remove();
+            anyhow::ensure!(
+                local_root_hash == committed_root_hash,
+                "state root mismatch for L1 batch #{number}: computed {local_root_hash:?}, L1 has {committed_root_hash:?}"
+            );
+            tracing::debug!("State root for L1 batch #{number} matches the one committed on L1");
+        }
+
+        while self.pending_root_hashes.len() > MAX_PENDING_ROOT_HASHES {
+            let (number, _) = self.pending_root_hashes.pop_first().unwrap();
+            tracing::debug!(
+                "Merkle tree lags too much; not comparing state root for L1 batch #{number}"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// Compares the number of L1 transactions in an executed batch with the committed one.
+fn verify_l1_tx_count(
+    number: L1BatchNumber,
+    l1_tx_count: usize,
+    committed: &CommittedBatch,
+) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        U256::from(l1_tx_count) == committed.l1_tx_count,
+        "L1 transaction count mismatch for L1 batch #{number}: executed {l1_tx_count}, L1 has {}",
+        committed.l1_tx_count
+    );
+    Ok(())
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for ExecutedBatchChecker {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let number = updates_manager.l1_batch.number;
+        let committed = {
+            let mut committed_batches = self.committed_batches.lock().unwrap();
+            // Drop entries for batches that weren't executed (e.g., if the state keeper was restarted).
+            *committed_batches = committed_batches.split_off(&number);
+            committed_batches.remove(&number)
+        };
+
+        if let Some(committed) = committed {
+            let l1_tx_count = updates_manager
+                .l1_batch
+                .executed_transactions
+                .iter()
+                .filter(|tx| tx.transaction.is_l1())
+                .count();
+            verify_l1_tx_count(number, l1_tx_count, &committed)?;
+            self.pending_root_hashes.insert(number, committed.root_hash);
+        }
+        self.check_root_hashes().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_eth_client::{clients::MockEthereum, Options};
+    use zksync_l1_contract_interface::{
+        i_executor::{methods::CommitBatches, structures::CommitBatchInfo},
+        Tokenizable, Tokenize,
+    };
+    use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+    use zksync_node_test_utils::{create_l1_batch, create_l1_batch_metadata};
+    use zksync_types::{
+        api::{self, BlockDetailsBase, BlockStatus},
+        commitment::{L1BatchCommitmentMode, L1BatchWithMetadata},
+        fee_model::BatchFeeInput,
+        pubdata_da::PubdataDA,
+        web3::Log,
+        ProtocolVersionId,
+    };
+    use zksync_web3_decl::client::MockClient;
+
+    use super::*;
+
+    const DIAMOND_PROXY_ADDR: Address = Address::repeat_byte(1);
+    const VALIDATOR_TIMELOCK_ADDR: Address = Address::repeat_byte(23);
+    const CHAIN_ID: u32 = 270;
+
+    fn create_l1_batch_with_metadata(number: u32) -> L1BatchWithMetadata {
+        let mut header = create_l1_batch(number);
+        header.l1_tx_count = 2;
+        header.protocol_version = Some(ProtocolVersionId::latest());
+        L1BatchWithMetadata {
+            header,
+            metadata: create_l1_batch_metadata(number),
+            raw_published_factory_deps: vec![],
+        }
+    }
+
+    fn batch_params(l1_batch: &L1BatchWithMetadata) -> L1BatchParams {
+        L1BatchParams {
+            protocol_version: ProtocolVersionId::latest(),
+            validation_computational_gas_limit: u32::MAX,
+            operator_address: Address::default(),
+            fee_input: BatchFeeInput::default(),
+            first_l2_block: zksync_state_keeper::io::L2BlockParams {
+                timestamp: l1_batch.header.timestamp,
+                virtual_blocks: 1,
+            },
+        }
+    }
+
+    fn commit_tx_input_data(l1_batch: &L1BatchWithMetadata) -> Vec<u8> {
+        let tokens = CommitBatches {
+            last_committed_l1_batch: l1_batch,
+            l1_batches: std::slice::from_ref(l1_batch),
+            pubdata_da: PubdataDA::Calldata,
+            mode: L1BatchCommitmentMode::Rollup,
+        }
+        .into_tokens();
+        let tokens: Vec<_> = [ethabi::Token::Uint(CHAIN_ID.into())]
+            .into_iter()
+            .chain(tokens)
+            .collect();
+        zksync_contracts::hyperchain_contract()
+            .function("commitBatchesSharedBridge")
+            .unwrap()
+            .encode_input(&tokens)
+            .unwrap()
+    }
+
+    fn block_commit_log(address: Address, l1_batch: &L1BatchWithMetadata) -> Log {
+        let event_signature = zksync_contracts::hyperchain_contract()
+            .event("BlockCommit")
+            .unwrap()
+            .signature();
+        Log {
+            address,
+            topics: vec![
+                event_signature,
+                H256::from_low_u64_be(l1_batch.header.number.0.into()),
+                l1_batch.metadata.root_hash,
+                l1_batch.metadata.commitment,
+            ],
+            data: vec![].into(),
+            block_hash: None,
+            block_number: None,
+            transaction_hash: None,
+            transaction_index: None,
+            log_index: None,
+            transaction_log_index: None,
+            log_type: Some("mined".into()),
+            removed: None,
+        }
+    }
+
+    fn batch_details(number: L1BatchNumber, commit_tx_hash: H256) -> api::L1BatchDetails {
+        api::L1BatchDetails {
+            number,
+            base: BlockDetailsBase {
+                timestamp: number.0.into(),
+                l1_tx_count: 2,
+                l2_tx_count: 0,
+                root_hash: None,
+                status: BlockStatus::Sealed,
+                commit_tx_hash: Some(commit_tx_hash),
+                committed_at: None,
+                prove_tx_hash: None,
+                proven_at: None,
+                execute_tx_hash: None,
+                executed_at: None,
+                l1_gas_price: 0,
+                l2_fair_gas_price: 0,
+                base_system_contracts_hashes: Default::default(),
+            },
+            commitment: None,
+        }
+    }
+
+    /// Creates a verifier with a single commit transaction for `l1_batch` sent via `ValidatorTimelock`.
+    async fn create_verifier(
+        l1_batch: &L1BatchWithMetadata,
+        log_origin: Address,
+    ) -> L1CommitVerifier {
+        let l1_client = MockEthereum::default();
+        let signed_tx = l1_client
+            .sign_prepared_tx(
+                commit_tx_input_data(l1_batch),
+                VALIDATOR_TIMELOCK_ADDR,
+                Options {
+                    nonce: Some(0.into()),
+                    ..Options::default()
+                },
+            )
+            .unwrap();
+        l1_client
+            .as_ref()
+            .send_raw_tx(signed_tx.raw_tx)
+            .await
+            .unwrap();
+        l1_client
+            .execute_tx(signed_tx.hash, true, 1)
+            .with_logs(vec![block_commit_log(log_origin, l1_batch)]);
+
+        let number = l1_batch.header.number;
+        let commit_tx_hash = signed_tx.hash;
+        let main_node_client = MockClient::builder(L2::default())
+            .method("zks_getL1BatchDetails", move |requested: L1BatchNumber| {
+                Ok((requested == number).then(|| batch_details(number, commit_tx_hash)))
+            })
+            .build();
+        L1CommitVerifier::new(
+            Box::new(main_node_client),
+            Box::new(l1_client.into_client()),
+            DIAMOND_PROXY_ADDR,
+        )
+    }
+
+    #[tokio::test]
+    async fn verifying_commit_sent_via_validator_timelock() {
+        let l1_batch = create_l1_batch_with_metadata(1);
+        let number = l1_batch.header.number;
+        let verifier = create_verifier(&l1_batch, DIAMOND_PROXY_ADDR).await;
+
+        let params = batch_params(&l1_batch);
+        verifier.verify(number, &params).await.unwrap();
+        let committed_batches = verifier.committed_batches.lock().unwrap().clone();
+        assert_eq!(
+            committed_batches,
+            BTreeMap::from([(
+                number,
+                CommittedBatch {
+                    l1_tx_count: 2.into(),
+                    root_hash: l1_batch.metadata.root_hash,
+                }
+            )])
+        );
+
+        let mut bogus_params = batch_params(&l1_batch);
+        bogus_params.first_l2_block.timestamp += 1;
+        let err = verifier.verify(number, &bogus_params).await.unwrap_err();
+        assert!(format!("{err:#}").contains("timestamp mismatch"), "{err:#}");
+    }
+
+    #[tokio::test]
+    async fn commit_without_diamond_proxy_log_is_rejected() {
+        let l1_batch = create_l1_batch_with_metadata(1);
+        let verifier = create_verifier(&l1_batch, VALIDATOR_TIMELOCK_ADDR).await;
+
+        let params = batch_params(&l1_batch);
+        let err = verifier
+            .verify(l1_batch.header.number, &params)
+            .await
+            .unwrap_err();
+        assert!(format!("{err:#}").contains("`BlockCommit`"), "{err:#}");
+    }
+
+    #[test]
+    fn verifying_commitment() {
+        let l1_batch = create_l1_batch_with_metadata(5);
+        let number = l1_batch.header.number;
+        let commitment = CommitBatchInfo::new(
+            L1BatchCommitmentMode::Rollup,
+            &l1_batch,
+            PubdataDA::Calldata,
+        )
+        .into_token();
+
+        let committed = verify_commitment(number, 5, &commitment).unwrap();
+        assert_eq!(
+            committed,
+            CommittedBatch {
+                l1_tx_count: 2.into(),
+                root_hash: l1_batch.metadata.root_hash,
+            }
+        );
+
+        let err = verify_commitment(number, 6, &commitment).unwrap_err();
+        assert!(err.to_string().contains("timestamp mismatch"), "{err}");
+        let err = verify_commitment(L1BatchNumber(6), 5, &commitment).unwrap_err();
+        assert!(
+            err.to_string().contains("commitment is for L1 batch #5"),
+            "{err}"
+        );
+
+        verify_l1_tx_count(number, 2, &committed).unwrap();
+        let err = verify_l1_tx_count(number, 3, &committed).unwrap_err();
+        assert!(err.to_string().contains("L1 transaction count"), "{err}");
+    }
+
+    #[tokio::test]
+    async fn comparing_state_roots_with_tree_output() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let mut storage = pool.connection().await.unwrap();
+        insert_genesis_batch(&mut storage, &GenesisParams::mock())
+            .await
+            .unwrap();
+        let l1_batch = create_l1_batch_with_metadata(1);
+        storage
+            .blocks_dal()
+            .insert_mock_l1_batch(&l1_batch.header)
+            .await
+            .unwrap();
+
+        let verifier = create_verifier(&l1_batch, DIAMOND_PROXY_ADDR).await;
+        let mut checker = verifier.executed_batch_checker(pool.clone());
+        checker
+            .pending_root_hashes
+            .insert(l1_batch.header.number, H256::repeat_byte(0x42));
+        // The tree hasn't processed the batch yet.
+        checker.check_root_hashes().await.unwrap();
+        assert_eq!(checker.pending_root_hashes.len(), 1);
+
+        storage
+            .blocks_dal()
+            .set_l1_batch_hash(l1_batch.header.number, l1_batch.metadata.root_hash)
+            .await
+            .unwrap();
+        let err = checker.check_root_hashes().await.unwrap_err();
+        assert!(err.to_string().contains("state root mismatch"), "{err}");
+
+        checker
+            .pending_root_hashes
+            .insert(l1_batch.header.number, l1_batch.metadata.root_hash);
+        checker.check_root_hashes().await.unwrap();
+        assert!(checker.pending_root_hashes.is_empty());
+    }
+}
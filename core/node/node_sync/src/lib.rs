@@ -3,6 +3,7 @@ mod client;
 pub mod external_io;
 pub mod fetcher;
 pub mod genesis;
+mod l1_commit_verifier;
 mod metrics;
 pub mod sync_action;
 mod sync_state;
@@ -14,6 +15,7 @@ pub mod tree_data_fetcher;
 pub use self::{
     client::MainNodeClient,
     external_io::ExternalIO,
+    l1_commit_verifier::{ExecutedBatchChecker, L1CommitVerifier},
     sync_action::{ActionQueue, ActionQueueSender},
     sync_state::SyncState,
 };
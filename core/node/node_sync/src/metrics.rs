@@ -2,7 +2,7 @@
 
 use std::time::Duration;
 
-use vise::{Buckets, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
+use vise::{Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, Metrics};
 use zksync_types::aggregated_operations::AggregatedActionType;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
@@ -57,3 +57,24 @@ pub(super) struct ActionQueueMetrics {
 
 #[vise::register]
 pub(super) static QUEUE_METRICS: vise::Global<ActionQueueMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(super) enum L1CommitCheckResult {
+    /// L1 batch is consistent with its commit transaction on L1.
+    Verified,
+    /// L1 batch is not committed on L1 yet, or commit data couldn't be fetched.
+    Skipped,
+}
+
+/// Metrics for verifying L1 batches against their commit transactions before execution.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "external_node_l1_commit_verifier")]
+pub(super) struct L1CommitVerifierMetrics {
+    /// Number of L1 batches processed by the verifier.
+    pub batches: Family<L1CommitCheckResult, Counter>,
+}
+
+#[vise::register]
+pub(super) static L1_COMMIT_VERIFIER_METRICS: vise::Global<L1CommitVerifierMetrics> =
+    vise::Global::new();
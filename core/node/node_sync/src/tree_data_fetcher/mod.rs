@@ -9,6 +9,7 @@ use serde::Serialize;
 use tokio::sync::mpsc;
 use tokio::sync::watch;
 use zksync_dal::{Connection, ConnectionPool, Core, CoreDal, DalError};
+use zksync_error_codes::{ErrorCode, HasErrorCode};
 use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
 use zksync_types::{api, block::L1BatchTreeData, L1BatchNumber};
 use zksync_web3_decl::{
@@ -67,6 +68,15 @@ impl TreeDataFetcherError {
     }
 }
 
+impl HasErrorCode for TreeDataFetcherError {
+    fn error_code(&self) -> ErrorCode {
+        match self {
+            Self::Rpc(_) => ErrorCode::SyncMainNodeRpc,
+            Self::Internal(_) => ErrorCode::SyncInternal,
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 #[serde(untagged)]
 enum TreeDataFetcherHealth {
@@ -266,7 +276,10 @@ impl TreeDataFetcher {
                     true
                 }
                 Err(err) if err.is_transient() => {
+                    let error_code = err.error_code();
+                    error_code.observe();
                     tracing::warn!(
+                        error_code = error_code.as_str(),
                         "Transient error in tree data fetcher, will retry after a delay: {err:?}"
                     );
                     let health = TreeDataFetcherHealth::Affected {
@@ -276,7 +289,12 @@ impl TreeDataFetcher {
                     true
                 }
                 Err(err) => {
-                    tracing::error!("Fatal error in tree data fetcher: {err:?}");
+                    let error_code = err.error_code();
+                    error_code.observe();
+                    tracing::error!(
+                        error_code = error_code.as_str(),
+                        "Fatal error in tree data fetcher: {err:?}"
+                    );
                     return Err(err.into());
                 }
             };
@@ -55,6 +55,7 @@ fn mock_l1_batch_details(number: L1BatchNumber, root_hash: Option<H256>) -> api:
             l2_fair_gas_price: 456,
             base_system_contracts_hashes: Default::default(),
         },
+        commitment: None,
     }
 }
 
@@ -1,17 +1,37 @@
 use std::{net::SocketAddr, sync::Arc};
 
 use anyhow::Context as _;
-use axum::{extract::Path, routing::post, Json, Router};
+use axum::{
+    body::Bytes,
+    extract::{ws::WebSocketUpgrade, DefaultBodyLimit, Path},
+    http::HeaderMap,
+    routing::{get, post},
+    Json, Router,
+};
+use sha2::{Digest, Sha256};
 use tokio::sync::watch;
 use zksync_config::configs::ProofDataHandlerConfig;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_object_store::ObjectStore;
-use zksync_prover_interface::api::{ProofGenerationDataRequest, SubmitProofRequest};
-use zksync_types::commitment::L1BatchCommitmentMode;
+use zksync_prover_interface::api::{
+    ProofGenerationDataRequest, StartProofUploadRequest, PROOF_STREAM_PATH,
+};
+use zksync_types::{commitment::L1BatchCommitmentMode, H256};
 
-use crate::request_processor::RequestProcessor;
+use crate::{
+    reconciler::ProofArtifactsReconciler, request_processor::RequestProcessor,
+    stream::ProofStreamHandler, upload::ProofUploadProcessor,
+};
 
+mod metrics;
+mod reconciler;
 mod request_processor;
+mod stream;
+mod upload;
+
+pub(crate) fn sha256_digest(bytes: &[u8]) -> H256 {
+    H256(Sha256::digest(bytes).into())
+}
 
 pub async fn run_server(
     config: ProofDataHandlerConfig,
@@ -22,8 +42,24 @@ pub async fn run_server(
 ) -> anyhow::Result<()> {
     let bind_address = SocketAddr::from(([0, 0, 0, 0], config.http_port));
     tracing::debug!("Starting proof data handler server on {bind_address}");
-    let get_proof_gen_processor = RequestProcessor::new(blob_store, pool, config, commitment_mode);
+    let reconciler =
+        ProofArtifactsReconciler::new(blob_store.clone(), pool.clone(), config.clone());
+    let reconciler_task = tokio::spawn(reconciler.run(stop_receiver.clone()));
+
+    let chunk_size_limit = config.upload_chunk_size_limit_bytes();
+    let get_proof_gen_processor = RequestProcessor::new(
+        blob_store.clone(),
+        pool.clone(),
+        config.clone(),
+        commitment_mode,
+    );
     let submit_proof_processor = get_proof_gen_processor.clone();
+    let proof_stream_handler = ProofStreamHandler::new(Arc::new(get_proof_gen_processor.clone()));
+    let proof_stream_stop_receiver = stop_receiver.clone();
+    let start_upload_processor =
+        ProofUploadProcessor::new(blob_store, pool, config, get_proof_gen_processor.clone());
+    let upload_chunk_processor = start_upload_processor.clone();
+    let finalize_upload_processor = start_upload_processor.clone();
     let app = Router::new()
         .route(
             "/proof_generation_data",
@@ -40,12 +76,47 @@ pub async fn run_server(
         .route(
             "/submit_proof/:l1_batch_number",
             post(
-                move |l1_batch_number: Path<u32>, payload: Json<SubmitProofRequest>| async move {
+                move |l1_batch_number: Path<u32>, headers: HeaderMap, body: Bytes| async move {
                     submit_proof_processor
-                        .submit_proof(l1_batch_number, payload)
+                        .submit_proof(l1_batch_number, headers, body)
+                        .await
+                },
+            ),
+        )
+        .route(
+            "/proof_upload/:l1_batch_number",
+            post(
+                move |l1_batch_number: Path<u32>, payload: Json<StartProofUploadRequest>| async move {
+                    start_upload_processor
+                        .start_upload(l1_batch_number, payload)
                         .await
                 },
             ),
+        )
+        .route(
+            "/proof_upload/:l1_batch_number/chunk/:offset",
+            post(
+                move |path: Path<(u32, u64)>, chunk: Bytes| async move {
+                    upload_chunk_processor.upload_chunk(path, chunk).await
+                },
+            )
+            .layer(DefaultBodyLimit::max(chunk_size_limit)),
+        )
+        .route(
+            "/proof_upload/:l1_batch_number/finalize",
+            post(move |l1_batch_number: Path<u32>| async move {
+                finalize_upload_processor
+                    .finalize_upload(l1_batch_number)
+                    .await
+            }),
+        )
+        .route(
+            PROOF_STREAM_PATH,
+            get(move |ws: WebSocketUpgrade| async move {
+                ws.on_upgrade(move |socket| {
+                    proof_stream_handler.handle(socket, proof_stream_stop_receiver)
+                })
+            }),
         );
 
     axum::Server::bind(&bind_address)
@@ -59,5 +130,7 @@ pub async fn run_server(
         .await
         .context("Proof data handler server failed")?;
     tracing::info!("Proof data handler server shut down");
-    Ok(())
+    reconciler_task
+        .await
+        .context("Proof artifacts reconciler panicked")?
 }
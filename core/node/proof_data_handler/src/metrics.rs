@@ -0,0 +1,20 @@
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "reason", rename_all = "snake_case")]
+pub(crate) enum RequeueReason {
+    MissingArtifact,
+    CorruptedArtifact,
+}
+
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_proof_data_handler")]
+pub(crate) struct ProofDataHandlerMetrics {
+    /// Number of proof generation jobs requeued because of a problem with the stored proof.
+    pub requeued_jobs: Family<RequeueReason, Counter>,
+    /// Number of resumable proof uploads discarded because they didn't make progress in time.
+    pub discarded_stale_uploads: Counter,
+}
+
+#[vise::register]
+pub(crate) static METRICS: vise::Global<ProofDataHandlerMetrics> = vise::Global::new();
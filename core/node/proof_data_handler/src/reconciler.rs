@@ -0,0 +1,166 @@
+//! Periodic reconciliation of the proof generation state in Postgres with the object store.
+
+use std::sync::Arc;
+
+use anyhow::Context as _;
+use tokio::sync::watch;
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_dal::{proof_generation_dal::GeneratedProofArtifacts, ConnectionPool, Core, CoreDal};
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
+use zksync_prover_interface::outputs::L1BatchProofForL1;
+
+use crate::{
+    metrics::{RequeueReason, METRICS},
+    sha256_digest,
+    upload::remove_upload_chunks,
+};
+
+/// Detects stored proofs that are missing from the object store or don't match their recorded checksum,
+/// and requeues the corresponding proof generation jobs. Also discards resumable uploads that were abandoned
+/// by provers.
+#[derive(Debug)]
+pub(crate) struct ProofArtifactsReconciler {
+    blob_store: Arc<dyn ObjectStore>,
+    pool: ConnectionPool<Core>,
+    config: ProofDataHandlerConfig,
+}
+
+impl ProofArtifactsReconciler {
+    pub(crate) fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        pool: ConnectionPool<Core>,
+        config: ProofDataHandlerConfig,
+    ) -> Self {
+        Self {
+            blob_store,
+            pool,
+            config,
+        }
+    }
+
+    pub(crate) async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        let interval = self.config.reconciliation_interval();
+        while !*stop_receiver.borrow() {
+            if let Err(err) = self.reconcile().await {
+                // Object store errors are likely transient, so we just retry on the next iteration.
+                tracing::warn!("Failed reconciling proof artifacts: {err:#}");
+            }
+            // Both the stop signal and the dropped sender mean that the reconciler should stop.
+            if tokio::time::timeout(interval, stop_receiver.changed())
+                .await
+                .is_ok()
+            {
+                break;
+            }
+        }
+        tracing::info!("Stop signal received, proof artifacts reconciler is shutting down");
+        Ok(())
+    }
+
+    async fn reconcile(&self) -> anyhow::Result<()> {
+        self.discard_stale_uploads().await?;
+
+        let generated_proofs = self
+            .pool
+            .connection_tagged("proof_data_handler")
+            .await?
+            .proof_generation_dal()
+            .get_generated_proofs_not_proven_on_l1()
+            .await
+            .context("get_generated_proofs_not_proven_on_l1()")?;
+        for artifacts in generated_proofs {
+            if let Some(reason) = self.check_artifacts(&artifacts).await? {
+                self.requeue(&artifacts, reason).await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn discard_stale_uploads(&self) -> anyhow::Result<()> {
+        let mut storage = self.pool.connection_tagged("proof_data_handler").await?;
+        let stale_uploads = storage
+            .proof_generation_dal()
+            .get_stale_proof_uploads(self.config.upload_session_timeout())
+            .await
+            .context("get_stale_proof_uploads()")?;
+        for session in stale_uploads {
+            tracing::info!(
+                "Discarding stale proof upload for batch {} ({} out of {} bytes received)",
+                session.l1_batch_number,
+                session.received_bytes,
+                session.total_size
+            );
+            remove_upload_chunks(&*self.blob_store, &session)
+                .await
+                .with_context(|| {
+                    format!(
+                        "failed removing upload chunks for batch {}",
+                        session.l1_batch_number
+                    )
+                })?;
+            storage
+                .proof_generation_dal()
+                .remove_proof_upload(session.l1_batch_number)
+                .await
+                .context("remove_proof_upload()")?;
+            METRICS.discarded_stale_uploads.inc();
+        }
+        Ok(())
+    }
+
+    /// Returns `Some(_)` if the stored proof is unusable.
+    async fn check_artifacts(
+        &self,
+        artifacts: &GeneratedProofArtifacts,
+    ) -> anyhow::Result<Option<RequeueReason>> {
+        let blob = match self
+            .blob_store
+            .get_raw(L1BatchProofForL1::BUCKET, &artifacts.proof_blob_url)
+            .await
+        {
+            Ok(blob) => blob,
+            Err(ObjectStoreError::KeyNotFound(_)) => {
+                return Ok(Some(RequeueReason::MissingArtifact))
+            }
+            Err(err) => {
+                return Err(err).with_context(|| {
+                    format!(
+                        "failed fetching proof for batch {}",
+                        artifacts.l1_batch_number
+                    )
+                })
+            }
+        };
+
+        let is_corrupted = match artifacts.proof_blob_sha256 {
+            Some(expected) => sha256_digest(&blob) != expected,
+            // Proofs saved before checksums were introduced can only be checked for being deserializable.
+            None => L1BatchProofForL1::deserialize(blob).is_err(),
+        };
+        Ok(is_corrupted.then_some(RequeueReason::CorruptedArtifact))
+    }
+
+    async fn requeue(
+        &self,
+        artifacts: &GeneratedProofArtifacts,
+        reason: RequeueReason,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = artifacts.l1_batch_number;
+        tracing::warn!(
+            "Proof for batch {l1_batch_number} at `{}` is unusable ({reason:?}); requeueing proof generation",
+            artifacts.proof_blob_url
+        );
+        let requeued = self
+            .pool
+            .connection_tagged("proof_data_handler")
+            .await?
+            .proof_generation_dal()
+            .requeue_generated_proof(l1_batch_number)
+            .await
+            .context("requeue_generated_proof()")?;
+        if requeued {
+            METRICS.requeued_jobs[&reason].inc();
+        }
+        Ok(())
+    }
+}
@@ -1,17 +1,22 @@
 use std::sync::Arc;
 
 use axum::{
+    body::Bytes,
     extract::Path,
-    http::StatusCode,
+    http::{header, HeaderMap, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
+use serde::Serialize;
 use zksync_config::configs::ProofDataHandlerConfig;
 use zksync_dal::{ConnectionPool, Core, CoreDal, SqlxError};
-use zksync_object_store::{ObjectStore, ObjectStoreError};
-use zksync_prover_interface::api::{
-    ProofGenerationData, ProofGenerationDataRequest, ProofGenerationDataResponse,
-    SubmitProofRequest, SubmitProofResponse,
+use zksync_object_store::{ObjectStore, ObjectStoreError, StoredObject};
+use zksync_prover_interface::{
+    api::{
+        ProofGenerationData, ProofGenerationDataRequest, ProofGenerationDataResponse,
+        SubmitProofRequest, SubmitProofResponse, CONTENT_SHA256_HEADER,
+    },
+    outputs::L1BatchProofForL1,
 };
 use zksync_types::{
     basic_fri_types::Eip4844Blobs,
@@ -20,6 +25,8 @@ use zksync_types::{
     L1BatchNumber, H256,
 };
 
+use crate::sha256_digest;
+
 #[derive(Clone)]
 pub(crate) struct RequestProcessor {
     blob_store: Arc<dyn ObjectStore>,
@@ -31,11 +38,15 @@ pub(crate) struct RequestProcessor {
 pub(crate) enum RequestProcessorError {
     ObjectStore(ObjectStoreError),
     Sqlx(SqlxError),
+    InvalidRequest(String),
+    ChecksumMismatch { expected: H256, actual: H256 },
+    UploadOffsetMismatch { expected: u64, actual: u64 },
 }
 
-impl IntoResponse for RequestProcessorError {
-    fn into_response(self) -> Response {
-        let (status_code, message) = match self {
+impl RequestProcessorError {
+    /// Converts this error into an HTTP status code and a message that can be returned to the client.
+    pub(crate) fn into_status_and_message(self) -> (StatusCode, String) {
+        match self {
             RequestProcessorError::ObjectStore(err) => {
                 tracing::error!("GCS error: {:?}", err);
                 (
@@ -55,8 +66,22 @@ impl IntoResponse for RequestProcessorError {
                     ),
                 }
             }
-        };
-        (status_code, message).into_response()
+            RequestProcessorError::InvalidRequest(message) => (StatusCode::BAD_REQUEST, message),
+            RequestProcessorError::ChecksumMismatch { expected, actual } => (
+                StatusCode::BAD_REQUEST,
+                format!("SHA-256 checksum mismatch: expected {expected:?}, got {actual:?}"),
+            ),
+            RequestProcessorError::UploadOffsetMismatch { expected, actual } => (
+                StatusCode::CONFLICT,
+                format!("Unexpected chunk offset {actual}, expected {expected}"),
+            ),
+        }
+    }
+}
+
+impl IntoResponse for RequestProcessorError {
+    fn into_response(self) -> Response {
+        self.into_status_and_message().into_response()
     }
 }
 
@@ -78,9 +103,19 @@ impl RequestProcessor {
     pub(crate) async fn get_proof_generation_data(
         &self,
         request: Json<ProofGenerationDataRequest>,
-    ) -> Result<Json<ProofGenerationDataResponse>, RequestProcessorError> {
+    ) -> Result<Response, RequestProcessorError> {
         tracing::info!("Received request for proof generation data: {:?}", request);
+        let proof_gen_data = self.next_proof_generation_data().await?;
+        Ok(json_with_checksum(&ProofGenerationDataResponse::Success(
+            proof_gen_data.map(Box::new),
+        )))
+    }
 
+    /// Locks the next L1 batch to be proven and returns its proof generation data, or `None` if there are
+    /// no batches pending to be proven.
+    pub(crate) async fn next_proof_generation_data(
+        &self,
+    ) -> Result<Option<ProofGenerationData>, RequestProcessorError> {
         let l1_batch_number_result = self
             .pool
             .connection()
@@ -90,9 +125,9 @@ impl RequestProcessor {
             .get_next_block_to_be_proven(self.config.proof_generation_timeout())
             .await;
 
-        let l1_batch_number = match l1_batch_number_result {
-            Some(number) => number,
-            None => return Ok(Json(ProofGenerationDataResponse::Success(None))), // no batches pending to be proven
+        let Some(l1_batch_number) = l1_batch_number_result else {
+            // No batches pending to be proven.
+            return Ok(None);
         };
 
         let blob = self
@@ -155,23 +190,56 @@ impl RequestProcessor {
             l1_verifier_config,
             eip_4844_blobs,
         };
-        Ok(Json(ProofGenerationDataResponse::Success(Some(Box::new(
-            proof_gen_data,
-        )))))
+        Ok(Some(proof_gen_data))
     }
 
+    /// Accepts a JSON-serialized [`SubmitProofRequest`]. If the request has the SHA-256 header,
+    /// the body is checked against it.
     pub(crate) async fn submit_proof(
         &self,
         Path(l1_batch_number): Path<u32>,
-        Json(payload): Json<SubmitProofRequest>,
+        headers: HeaderMap,
+        body: Bytes,
     ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
         tracing::info!("Received proof for block number: {:?}", l1_batch_number);
-        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        if let Some(expected) = headers.get(CONTENT_SHA256_HEADER) {
+            let expected = expected
+                .to_str()
+                .ok()
+                .and_then(parse_sha256)
+                .ok_or_else(|| {
+                    RequestProcessorError::InvalidRequest(format!(
+                        "Invalid `{CONTENT_SHA256_HEADER}` header"
+                    ))
+                })?;
+            let actual = sha256_digest(&body);
+            if actual != expected {
+                return Err(RequestProcessorError::ChecksumMismatch { expected, actual });
+            }
+        }
+        let payload = serde_json::from_slice(&body).map_err(|err| {
+            RequestProcessorError::InvalidRequest(format!("Invalid proof request: {err}"))
+        })?;
+        self.save_proof(L1BatchNumber(l1_batch_number), payload)
+            .await?;
+        Ok(Json(SubmitProofResponse::Success))
+    }
+
+    pub(crate) async fn save_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        payload: SubmitProofRequest,
+    ) -> Result<(), RequestProcessorError> {
         match payload {
             SubmitProofRequest::Proof(proof) => {
-                let blob_url = self
-                    .blob_store
-                    .put(l1_batch_number, &*proof)
+                // The checksum is computed over the stored bytes, so that the stored blob can be verified later.
+                let blob_url = L1BatchProofForL1::encode_key(l1_batch_number);
+                let blob = proof.serialize().map_err(|err| {
+                    RequestProcessorError::ObjectStore(ObjectStoreError::Serialization(err))
+                })?;
+                let blob_sha256 = sha256_digest(&blob);
+                self.blob_store
+                    .put_raw(L1BatchProofForL1::BUCKET, &blob_url, blob)
                     .await
                     .map_err(RequestProcessorError::ObjectStore)?;
 
@@ -247,7 +315,7 @@ impl RequestProcessor {
                 }
                 storage
                     .proof_generation_dal()
-                    .save_proof_artifacts_metadata(l1_batch_number, &blob_url)
+                    .save_proof_artifacts_metadata(l1_batch_number, &blob_url, blob_sha256)
                     .await
                     .map_err(RequestProcessorError::Sqlx)?;
             }
@@ -262,7 +330,36 @@ impl RequestProcessor {
                     .map_err(RequestProcessorError::Sqlx)?;
             }
         }
-
-        Ok(Json(SubmitProofResponse::Success))
+        Ok(())
     }
 }
+
+/// Serializes the response to JSON, attaching the SHA-256 digest of the body as a header.
+fn json_with_checksum<T: Serialize>(value: &T) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(err) => {
+            tracing::error!("Failed serializing response: {err}");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let checksum = hex::encode(sha256_digest(&body));
+    (
+        [
+            (header::CONTENT_TYPE, "application/json".to_owned()),
+            (
+                header::HeaderName::from_static(CONTENT_SHA256_HEADER),
+                checksum,
+            ),
+        ],
+        body,
+    )
+        .into_response()
+}
+
+/// Parses a hex-encoded SHA-256 digest, with an optional `0x` prefix.
+pub(crate) fn parse_sha256(value: &str) -> Option<H256> {
+    let value = value.strip_prefix("0x").unwrap_or(value);
+    let bytes = hex::decode(value).ok()?;
+    (bytes.len() == 32).then(|| H256::from_slice(&bytes))
+}
@@ -0,0 +1,201 @@
+//! Streaming protocol between the proof data handler and the prover gateway.
+//!
+//! Instead of polling the HTTP API, the gateway opens a WebSocket connection, over which the server pushes
+//! new proof generation jobs, and the gateway submits proofs. Both jobs and proofs are explicitly acknowledged.
+//! The server periodically pings the gateway and closes the connection if nothing is received from the gateway
+//! for a while, so that half-open connections don't keep jobs locked.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use axum::extract::ws::{Message, WebSocket};
+use futures::{Sink, SinkExt, Stream, StreamExt};
+use tokio::{sync::watch, time::Instant};
+use zksync_prover_interface::api::{
+    GatewayStreamMessage, ProofGenerationData, ServerStreamMessage, SubmitProofRequest,
+    SubmitProofResponse,
+};
+use zksync_types::L1BatchNumber;
+
+use crate::request_processor::{RequestProcessor, RequestProcessorError};
+
+#[cfg(test)]
+mod tests;
+
+/// Source of proof generation jobs and consumer of proofs for [`ProofStreamHandler`].
+#[async_trait]
+pub(crate) trait ProofStreamBackend: Send + Sync {
+    /// Locks the next L1 batch to be proven and returns its proof generation data.
+    async fn next_job(&self) -> Result<Option<ProofGenerationData>, RequestProcessorError>;
+
+    /// Saves a proof submitted by the gateway.
+    async fn save_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        request: SubmitProofRequest,
+    ) -> Result<(), RequestProcessorError>;
+}
+
+#[async_trait]
+impl ProofStreamBackend for RequestProcessor {
+    async fn next_job(&self) -> Result<Option<ProofGenerationData>, RequestProcessorError> {
+        self.next_proof_generation_data().await
+    }
+
+    async fn save_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        request: SubmitProofRequest,
+    ) -> Result<(), RequestProcessorError> {
+        RequestProcessor::save_proof(self, l1_batch_number, request).await
+    }
+}
+
+/// Handles streaming connections with the prover gateway.
+#[derive(Clone)]
+pub(crate) struct ProofStreamHandler {
+    backend: Arc<dyn ProofStreamBackend>,
+    /// Interval between checks for new batches to be proven while there is no pending job.
+    poll_interval: Duration,
+    /// Interval between keepalive pings sent to the gateway.
+    ping_interval: Duration,
+    /// Maximum period without any messages (including pongs) from the gateway after which the connection is closed.
+    read_timeout: Duration,
+}
+
+impl ProofStreamHandler {
+    const POLL_INTERVAL: Duration = Duration::from_secs(1);
+    const PING_INTERVAL: Duration = Duration::from_secs(30);
+    const READ_TIMEOUT: Duration = Duration::from_secs(90);
+
+    pub(crate) fn new(backend: Arc<dyn ProofStreamBackend>) -> Self {
+        Self {
+            backend,
+            poll_interval: Self::POLL_INTERVAL,
+            ping_interval: Self::PING_INTERVAL,
+            read_timeout: Self::READ_TIMEOUT,
+        }
+    }
+
+    pub(crate) async fn handle(self, socket: WebSocket, mut stop_receiver: watch::Receiver<bool>) {
+        tracing::info!("Prover gateway opened a proof stream");
+        match self.serve(socket, &mut stop_receiver).await {
+            Ok(()) => tracing::info!("Proof stream closed"),
+            Err(err) => tracing::warn!("Proof stream terminated: {err:#}"),
+        }
+    }
+
+    async fn serve<S>(
+        &self,
+        mut socket: S,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<()>
+    where
+        S: Stream<Item = Result<Message, axum::Error>> + Sink<Message, Error = axum::Error> + Unpin,
+    {
+        // L1 batch for which a job was pushed, but not acknowledged yet.
+        let mut pending_job = None::<L1BatchNumber>;
+        let mut next_poll_at = Instant::now();
+        let mut next_ping_at = Instant::now() + self.ping_interval;
+        let mut last_received_at = Instant::now();
+
+        loop {
+            tokio::select! {
+                // Messages from the gateway are prioritized, so that the read timeout doesn't fire
+                // if a message was received while the handler was busy.
+                biased;
+
+                _ = stop_receiver.changed() => {
+                    tracing::info!("Stop signal received, closing proof stream");
+                    socket.send(Message::Close(None)).await.ok();
+                    return Ok(());
+                }
+                message = socket.next() => {
+                    let Some(message) = message else {
+                        return Ok(()); // The connection was closed by the gateway
+                    };
+                    last_received_at = Instant::now();
+                    let message = match message.context("failed receiving message")? {
+                        Message::Text(text) => serde_json::from_str(&text),
+                        Message::Binary(bytes) => serde_json::from_slice(&bytes),
+                        Message::Close(_) => return Ok(()),
+                        Message::Ping(_) | Message::Pong(_) => continue,
+                    };
+                    let message = message.context("failed parsing message")?;
+                    match message {
+                        GatewayStreamMessage::JobAck { l1_batch_number } => {
+                            anyhow::ensure!(
+                                pending_job == Some(l1_batch_number),
+                                "unexpected ack for L1 batch #{l1_batch_number}; pending job: {pending_job:?}"
+                            );
+                            pending_job = None;
+                            // Push the next job (if any) immediately.
+                            next_poll_at = Instant::now();
+                        }
+                        GatewayStreamMessage::SubmitProof { l1_batch_number, request } => {
+                            let response = self.submit_proof(l1_batch_number, request).await;
+                            let message = ServerStreamMessage::ProofAck { l1_batch_number, response };
+                            Self::send(&mut socket, &message).await?;
+                        }
+                    }
+                }
+                () = tokio::time::sleep_until(last_received_at + self.read_timeout) => {
+                    anyhow::bail!(
+                        "no messages received from the gateway in {:?}; pending job: {pending_job:?}",
+                        self.read_timeout
+                    );
+                }
+                () = tokio::time::sleep_until(next_ping_at) => {
+                    next_ping_at = Instant::now() + self.ping_interval;
+                    socket
+                        .send(Message::Ping(vec![]))
+                        .await
+                        .context("failed sending ping")?;
+                }
+                () = tokio::time::sleep_until(next_poll_at), if pending_job.is_none() => {
+                    next_poll_at = Instant::now() + self.poll_interval;
+                    match self.backend.next_job().await {
+                        Ok(Some(data)) => {
+                            let l1_batch_number = data.l1_batch_number;
+                            tracing::info!("Pushing proof generation data for L1 batch #{l1_batch_number}");
+                            Self::send(&mut socket, &ServerStreamMessage::Job(Box::new(data))).await?;
+                            pending_job = Some(l1_batch_number);
+                        }
+                        Ok(None) => { /* No batches pending to be proven */ }
+                        Err(err) => {
+                            let (_, message) = err.into_status_and_message();
+                            tracing::error!("Failed getting proof generation data: {message}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    async fn submit_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        request: SubmitProofRequest,
+    ) -> SubmitProofResponse {
+        tracing::info!("Received proof for L1 batch #{l1_batch_number} via proof stream");
+        match self.backend.save_proof(l1_batch_number, request).await {
+            Ok(()) => SubmitProofResponse::Success,
+            Err(err) => {
+                let (_, message) = err.into_status_and_message();
+                SubmitProofResponse::Error(message)
+            }
+        }
+    }
+
+    async fn send<S>(socket: &mut S, message: &ServerStreamMessage) -> anyhow::Result<()>
+    where
+        S: Sink<Message, Error = axum::Error> + Unpin,
+    {
+        let message = serde_json::to_string(message).context("failed serializing message")?;
+        socket
+            .send(Message::Text(message))
+            .await
+            .context("failed sending message")
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll},
+};
+
+use assert_matches::assert_matches;
+use tokio::sync::mpsc;
+use zksync_prover_interface::inputs::PrepareBasicCircuitsJob;
+use zksync_types::{
+    basic_fri_types::Eip4844Blobs, protocol_version::L1VerifierConfig, ProtocolVersionId,
+};
+
+use super::*;
+
+const TEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// In-memory WebSocket connection to the handler.
+#[derive(Debug)]
+struct MockSocket {
+    incoming: mpsc::UnboundedReceiver<Result<Message, axum::Error>>,
+    outgoing: mpsc::UnboundedSender<Message>,
+}
+
+impl Stream for MockSocket {
+    type Item = Result<Message, axum::Error>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.incoming.poll_recv(cx)
+    }
+}
+
+impl Sink<Message> for MockSocket {
+    type Error = axum::Error;
+
+    fn poll_ready(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, item: Message) -> Result<(), Self::Error> {
+        self.outgoing.send(item).map_err(axum::Error::new)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[derive(Debug, Default)]
+struct MockBackend {
+    jobs: Mutex<VecDeque<L1BatchNumber>>,
+    saved_proofs: Mutex<Vec<L1BatchNumber>>,
+    fail_saving_proofs: bool,
+}
+
+impl MockBackend {
+    fn with_jobs(jobs: impl IntoIterator<Item = u32>) -> Self {
+        Self {
+            jobs: Mutex::new(jobs.into_iter().map(L1BatchNumber).collect()),
+            ..Self::default()
+        }
+    }
+}
+
+#[async_trait]
+impl ProofStreamBackend for MockBackend {
+    async fn next_job(&self) -> Result<Option<ProofGenerationData>, RequestProcessorError> {
+        let Some(l1_batch_number) = self.jobs.lock().unwrap().pop_front() else {
+            return Ok(None);
+        };
+        Ok(Some(ProofGenerationData {
+            l1_batch_number,
+            data: PrepareBasicCircuitsJob::new(0),
+            protocol_version_id: ProtocolVersionId::latest(),
+            l1_verifier_config: L1VerifierConfig::default(),
+            eip_4844_blobs: Eip4844Blobs::empty(),
+        }))
+    }
+
+    async fn save_proof(
+        &self,
+        l1_batch_number: L1BatchNumber,
+        request: SubmitProofRequest,
+    ) -> Result<(), RequestProcessorError> {
+        assert_matches!(request, SubmitProofRequest::SkippedProofGeneration);
+        if self.fail_saving_proofs {
+            return Err(RequestProcessorError::InvalidRequest(
+                "test error".to_owned(),
+            ));
+        }
+        self.saved_proofs.lock().unwrap().push(l1_batch_number);
+        Ok(())
+    }
+}
+
+/// Gateway side of the connection.
+struct TestGateway {
+    sender: mpsc::UnboundedSender<Result<Message, axum::Error>>,
+    receiver: mpsc::UnboundedReceiver<Message>,
+    stop_sender: watch::Sender<bool>,
+    handler_task: tokio::task::JoinHandle<anyhow::Result<()>>,
+}
+
+impl TestGateway {
+    fn new(handler: ProofStreamHandler) -> Self {
+        let (sender, incoming) = mpsc::unbounded_channel();
+        let (outgoing, receiver) = mpsc::unbounded_channel();
+        let socket = MockSocket { incoming, outgoing };
+        let (stop_sender, mut stop_receiver) = watch::channel(false);
+        let handler_task =
+            tokio::spawn(async move { handler.serve(socket, &mut stop_receiver).await });
+        Self {
+            sender,
+            receiver,
+            stop_sender,
+            handler_task,
+        }
+    }
+
+    fn send(&self, message: &GatewayStreamMessage) {
+        let message = serde_json::to_string(message).unwrap();
+        self.sender.send(Ok(Message::Text(message))).unwrap();
+    }
+
+    /// Receives the next protocol message, skipping keepalive pings.
+    async fn recv(&mut self) -> ServerStreamMessage {
+        loop {
+            let message = tokio::time::timeout(TEST_TIMEOUT, self.receiver.recv())
+                .await
+                .expect("timed out waiting for message")
+                .expect("handler dropped socket");
+            match message {
+                Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+                Message::Ping(_) => continue,
+                other => panic!("unexpected message: {other:?}"),
+            }
+        }
+    }
+
+    async fn stop(self) -> anyhow::Result<()> {
+        self.stop_sender.send_replace(true);
+        self.handler_task.await.unwrap()
+    }
+}
+
+fn handler(backend: MockBackend) -> (ProofStreamHandler, Arc<MockBackend>) {
+    let backend = Arc::new(backend);
+    let mut handler = ProofStreamHandler::new(backend.clone());
+    handler.poll_interval = Duration::from_millis(10);
+    (handler, backend)
+}
+
+#[tokio::test]
+async fn next_job_is_pushed_only_after_ack() {
+    let (handler, _) = handler(MockBackend::with_jobs([1, 2]));
+    let mut gateway = TestGateway::new(handler);
+
+    let message = gateway.recv().await;
+    assert_matches!(message, ServerStreamMessage::Job(data) if data.l1_batch_number == L1BatchNumber(1));
+    // The next job must not be pushed until the first one is acknowledged.
+    tokio::time::sleep(Duration::from_millis(50)).await;
+    assert_matches!(gateway.receiver.try_recv(), Err(_));
+
+    gateway.send(&GatewayStreamMessage::JobAck {
+        l1_batch_number: L1BatchNumber(1),
+    });
+    let message = gateway.recv().await;
+    assert_matches!(message, ServerStreamMessage::Job(data) if data.l1_batch_number == L1BatchNumber(2));
+
+    gateway.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn unexpected_job_ack_terminates_stream() {
+    let (handler, _) = handler(MockBackend::with_jobs([1]));
+    let mut gateway = TestGateway::new(handler);
+    assert_matches!(gateway.recv().await, ServerStreamMessage::Job(_));
+
+    gateway.send(&GatewayStreamMessage::JobAck {
+        l1_batch_number: L1BatchNumber(2),
+    });
+    let err = gateway.handler_task.await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("unexpected ack"), "{err:#}");
+}
+
+#[tokio::test]
+async fn submitted_proofs_are_acknowledged() {
+    let (handler, backend) = handler(MockBackend::default());
+    let mut gateway = TestGateway::new(handler);
+
+    gateway.send(&GatewayStreamMessage::SubmitProof {
+        l1_batch_number: L1BatchNumber(5),
+        request: SubmitProofRequest::SkippedProofGeneration,
+    });
+    let message = gateway.recv().await;
+    assert_matches!(
+        message,
+        ServerStreamMessage::ProofAck {
+            l1_batch_number: L1BatchNumber(5),
+            response: SubmitProofResponse::Success,
+        }
+    );
+    assert_eq!(*backend.saved_proofs.lock().unwrap(), [L1BatchNumber(5)]);
+
+    gateway.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn proof_saving_errors_are_acknowledged() {
+    let (handler, backend) = handler(MockBackend {
+        fail_saving_proofs: true,
+        ..MockBackend::default()
+    });
+    let mut gateway = TestGateway::new(handler);
+
+    gateway.send(&GatewayStreamMessage::SubmitProof {
+        l1_batch_number: L1BatchNumber(5),
+        request: SubmitProofRequest::SkippedProofGeneration,
+    });
+    let message = gateway.recv().await;
+    assert_matches!(
+        message,
+        ServerStreamMessage::ProofAck {
+            l1_batch_number: L1BatchNumber(5),
+            response: SubmitProofResponse::Error(err),
+        } if err == "test error"
+    );
+    assert!(backend.saved_proofs.lock().unwrap().is_empty());
+
+    // The stream is still usable after the error.
+    gateway.send(&GatewayStreamMessage::SubmitProof {
+        l1_batch_number: L1BatchNumber(6),
+        request: SubmitProofRequest::SkippedProofGeneration,
+    });
+    assert_matches!(
+        gateway.recv().await,
+        ServerStreamMessage::ProofAck {
+            l1_batch_number: L1BatchNumber(6),
+            ..
+        }
+    );
+    gateway.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_is_kept_alive_with_pings() {
+    let (mut handler, _) = handler(MockBackend::default());
+    handler.ping_interval = Duration::from_millis(10);
+    handler.read_timeout = Duration::from_millis(500);
+    let mut gateway = TestGateway::new(handler);
+
+    for _ in 0..3 {
+        let message = tokio::time::timeout(TEST_TIMEOUT, gateway.receiver.recv())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_matches!(message, Message::Ping(_));
+        gateway.sender.send(Ok(Message::Pong(vec![]))).unwrap();
+    }
+    gateway.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn stream_is_closed_if_gateway_is_unresponsive() {
+    let (mut handler, _) = handler(MockBackend::with_jobs([1]));
+    handler.ping_interval = Duration::from_millis(10);
+    handler.read_timeout = Duration::from_millis(100);
+    let gateway = TestGateway::new(handler);
+
+    let err = tokio::time::timeout(TEST_TIMEOUT, gateway.handler_task)
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap_err();
+    assert!(err.to_string().contains("no messages received"), "{err:#}");
+}
@@ -0,0 +1,275 @@
+//! Resumable uploads of proofs that are too large to be submitted in a single request.
+//!
+//! An upload is started by declaring the size and SHA-256 digest of a JSON-serialized `SubmitProofRequest`.
+//! The data is then sent in sequential chunks, each persisted to the object store, so that an interrupted upload
+//! can be resumed from the last received byte. Once all data is received, the upload is finalized: the chunks
+//! are assembled, verified against the declared digest and processed as a regular proof submission.
+
+use std::sync::Arc;
+
+use axum::{body::Bytes, extract::Path, Json};
+use zksync_config::configs::ProofDataHandlerConfig;
+use zksync_dal::{
+    proof_generation_dal::ProofUploadSession, Connection, ConnectionPool, Core, CoreDal, SqlxError,
+};
+use zksync_object_store::{Bucket, ObjectStore, ObjectStoreError};
+use zksync_prover_interface::api::{
+    ProofUploadResponse, StartProofUploadRequest, SubmitProofResponse,
+};
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    request_processor::{RequestProcessor, RequestProcessorError},
+    sha256_digest,
+};
+
+/// Upper bound on the size of an uploaded proof request.
+const MAX_UPLOAD_SIZE: u64 = 1 << 30;
+
+fn chunk_key(l1_batch_number: L1BatchNumber, offset: u64) -> String {
+    format!("l1_batch_proof_{l1_batch_number}_chunk_{offset}.bin")
+}
+
+/// Removes all chunks uploaded as a part of the session from the object store.
+pub(crate) async fn remove_upload_chunks(
+    blob_store: &dyn ObjectStore,
+    session: &ProofUploadSession,
+) -> Result<(), ObjectStoreError> {
+    for &offset in &session.chunk_offsets {
+        let key = chunk_key(session.l1_batch_number, offset);
+        blob_store.remove_raw(Bucket::ProofUploads, &key).await?;
+    }
+    Ok(())
+}
+
+#[derive(Clone)]
+pub(crate) struct ProofUploadProcessor {
+    blob_store: Arc<dyn ObjectStore>,
+    pool: ConnectionPool<Core>,
+    config: ProofDataHandlerConfig,
+    request_processor: RequestProcessor,
+}
+
+impl ProofUploadProcessor {
+    pub(crate) fn new(
+        blob_store: Arc<dyn ObjectStore>,
+        pool: ConnectionPool<Core>,
+        config: ProofDataHandlerConfig,
+        request_processor: RequestProcessor,
+    ) -> Self {
+        Self {
+            blob_store,
+            pool,
+            config,
+            request_processor,
+        }
+    }
+
+    /// Starts an upload, or resumes the existing one if it has the same size and digest.
+    pub(crate) async fn start_upload(
+        &self,
+        Path(l1_batch_number): Path<u32>,
+        Json(request): Json<StartProofUploadRequest>,
+    ) -> Result<Json<ProofUploadResponse>, RequestProcessorError> {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        tracing::info!(
+            "Received request to upload {} bytes of proof for batch {l1_batch_number}",
+            request.total_size
+        );
+        if request.total_size == 0 || request.total_size > MAX_UPLOAD_SIZE {
+            return Err(RequestProcessorError::InvalidRequest(format!(
+                "Upload size must be in 1..={MAX_UPLOAD_SIZE} bytes"
+            )));
+        }
+
+        let mut storage = self.pool.connection().await.unwrap();
+        let existing_session = storage
+            .proof_generation_dal()
+            .get_proof_upload(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        if let Some(session) = existing_session {
+            if session.total_size == request.total_size && session.sha256 == request.sha256 {
+                tracing::info!(
+                    "Resuming upload of proof for batch {l1_batch_number} from byte {}",
+                    session.received_bytes
+                );
+                return Ok(Json(ProofUploadResponse::Success {
+                    received_bytes: session.received_bytes,
+                }));
+            }
+            // The prover uploads a different proof; the old chunks are useless.
+            remove_upload_chunks(&*self.blob_store, &session)
+                .await
+                .map_err(RequestProcessorError::ObjectStore)?;
+        }
+
+        storage
+            .proof_generation_dal()
+            .start_proof_upload(l1_batch_number, request.total_size, request.sha256)
+            .await
+            .map_err(|err| match err {
+                // The only foreign key is the L1 batch number.
+                SqlxError::Database(err) if err.is_foreign_key_violation() => {
+                    SqlxError::RowNotFound
+                }
+                err => err,
+            })
+            .map_err(RequestProcessorError::Sqlx)?;
+        Ok(Json(ProofUploadResponse::Success { received_bytes: 0 }))
+    }
+
+    pub(crate) async fn upload_chunk(
+        &self,
+        Path((l1_batch_number, offset)): Path<(u32, u64)>,
+        chunk: Bytes,
+    ) -> Result<Json<ProofUploadResponse>, RequestProcessorError> {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let chunk_size_limit = self.config.upload_chunk_size_limit_bytes();
+        if chunk.is_empty() || chunk.len() > chunk_size_limit {
+            return Err(RequestProcessorError::InvalidRequest(format!(
+                "Chunk size must be in 1..={chunk_size_limit} bytes"
+            )));
+        }
+
+        let mut storage = self.pool.connection().await.unwrap();
+        let session = self.get_session(&mut storage, l1_batch_number).await?;
+        if offset != session.received_bytes {
+            return Err(RequestProcessorError::UploadOffsetMismatch {
+                expected: session.received_bytes,
+                actual: offset,
+            });
+        }
+        let chunk_len = chunk.len() as u64;
+        if offset + chunk_len > session.total_size {
+            return Err(RequestProcessorError::InvalidRequest(format!(
+                "Chunk exceeds the declared upload size of {} bytes",
+                session.total_size
+            )));
+        }
+
+        self.blob_store
+            .put_raw(
+                Bucket::ProofUploads,
+                &chunk_key(l1_batch_number, offset),
+                chunk.to_vec(),
+            )
+            .await
+            .map_err(RequestProcessorError::ObjectStore)?;
+        let recorded = storage
+            .proof_generation_dal()
+            .record_proof_upload_chunk(l1_batch_number, offset, chunk_len)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?;
+        if !recorded {
+            // Another request has uploaded the chunk concurrently, or the upload was restarted.
+            let session = self.get_session(&mut storage, l1_batch_number).await?;
+            return Err(RequestProcessorError::UploadOffsetMismatch {
+                expected: session.received_bytes,
+                actual: offset,
+            });
+        }
+
+        Ok(Json(ProofUploadResponse::Success {
+            received_bytes: offset + chunk_len,
+        }))
+    }
+
+    /// Assembles the uploaded proof, verifies its digest and submits it. On a digest mismatch,
+    /// the upload is discarded and must be started anew.
+    pub(crate) async fn finalize_upload(
+        &self,
+        Path(l1_batch_number): Path<u32>,
+    ) -> Result<Json<SubmitProofResponse>, RequestProcessorError> {
+        let l1_batch_number = L1BatchNumber(l1_batch_number);
+        let mut storage = self.pool.connection().await.unwrap();
+        let session = self.get_session(&mut storage, l1_batch_number).await?;
+        drop(storage);
+        if session.received_bytes != session.total_size {
+            return Err(RequestProcessorError::InvalidRequest(format!(
+                "Upload is incomplete: received {} out of {} bytes",
+                session.received_bytes, session.total_size
+            )));
+        }
+
+        let mut offsets = session.chunk_offsets.clone();
+        offsets.sort_unstable();
+        let mut body = Vec::with_capacity(session.total_size as usize);
+        for offset in offsets {
+            let key = chunk_key(l1_batch_number, offset);
+            let chunk = self
+                .blob_store
+                .get_raw(Bucket::ProofUploads, &key)
+                .await
+                .map_err(RequestProcessorError::ObjectStore)?;
+            body.extend_from_slice(&chunk);
+        }
+
+        let actual = sha256_digest(&body);
+        let verification_result = if body.len() as u64 != session.total_size {
+            Err(RequestProcessorError::InvalidRequest(format!(
+                "Assembled upload has {} bytes, expected {}",
+                body.len(),
+                session.total_size
+            )))
+        } else if actual != session.sha256 {
+            Err(RequestProcessorError::ChecksumMismatch {
+                expected: session.sha256,
+                actual,
+            })
+        } else {
+            serde_json::from_slice(&body).map_err(|err| {
+                RequestProcessorError::InvalidRequest(format!("Invalid proof request: {err}"))
+            })
+        };
+
+        let payload = match verification_result {
+            Ok(payload) => payload,
+            Err(err) => {
+                tracing::warn!("Discarding corrupted proof upload for batch {l1_batch_number}");
+                self.discard_session(&session).await?;
+                return Err(err);
+            }
+        };
+        self.request_processor
+            .save_proof(l1_batch_number, payload)
+            .await?;
+        self.discard_session(&session).await?;
+        tracing::info!("Finalized proof upload for batch {l1_batch_number}");
+        Ok(Json(SubmitProofResponse::Success))
+    }
+
+    async fn get_session(
+        &self,
+        storage: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> Result<ProofUploadSession, RequestProcessorError> {
+        storage
+            .proof_generation_dal()
+            .get_proof_upload(l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)?
+            .ok_or_else(|| {
+                RequestProcessorError::InvalidRequest(format!(
+                    "No upload in progress for batch {l1_batch_number}"
+                ))
+            })
+    }
+
+    async fn discard_session(
+        &self,
+        session: &ProofUploadSession,
+    ) -> Result<(), RequestProcessorError> {
+        remove_upload_chunks(&*self.blob_store, session)
+            .await
+            .map_err(RequestProcessorError::ObjectStore)?;
+        self.pool
+            .connection()
+            .await
+            .unwrap()
+            .proof_generation_dal()
+            .remove_proof_upload(session.l1_batch_number)
+            .await
+            .map_err(RequestProcessorError::Sqlx)
+    }
+}
@@ -22,6 +22,7 @@ pub enum InitStage {
     HttpApi,
     WsApi,
     ContractVerificationApi,
+    ExplorerApi,
     StateKeeper,
     EthWatcher,
     EthTxAggregator,
@@ -37,6 +38,7 @@ impl fmt::Display for InitStage {
             Self::HttpApi => formatter.write_str("http_api"),
             Self::WsApi => formatter.write_str("ws_api"),
             Self::ContractVerificationApi => formatter.write_str("contract_verification_api"),
+            Self::ExplorerApi => formatter.write_str("explorer_api"),
             Self::StateKeeper => formatter.write_str("state_keeper"),
             Self::EthWatcher => formatter.write_str("eth_watcher"),
             Self::EthTxAggregator => formatter.write_str("eth_tx_aggregator"),
@@ -15,12 +15,16 @@ use tokio::{
     runtime::Handle,
     sync::{mpsc, watch},
 };
+use zksync_dal::{ConnectionPool, Core};
 use zksync_shared_metrics::{InteractionType, TxStage, APP_METRICS};
 use zksync_state::{ReadStorage, ReadStorageFactory, StorageView, WriteStorage};
-use zksync_types::{vm_trace::Call, Transaction};
+use zksync_types::{vm_trace::Call, Transaction, VmVersion};
 use zksync_utils::bytecode::CompressedBytecodeInfo;
 
-use super::{BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult};
+use super::{
+    shadow::{ShadowVm, ShadowVmConfig},
+    BatchExecutor, BatchExecutorHandle, Command, TxExecutionResult,
+};
 use crate::{
     metrics::{TxExecutionStage, BATCH_TIP_METRICS, EXECUTOR_METRICS, KEEPER_METRICS},
     types::ExecutionMetricsForCriteria,
@@ -32,6 +36,7 @@ use crate::{
 pub struct MainBatchExecutor {
     save_call_traces: bool,
     optional_bytecode_compression: bool,
+    shadow_vm: Option<ShadowVmConfig>,
 }
 
 impl MainBatchExecutor {
@@ -39,8 +44,18 @@ impl MainBatchExecutor {
         Self {
             save_call_traces,
             optional_bytecode_compression,
+            shadow_vm: None,
         }
     }
+
+    /// Enables shadow execution: each batch will additionally be executed by the VM of the specified version,
+    /// and divergences between its output and the output of the canonical VM will be persisted to Postgres.
+    /// Shadow execution doesn't influence the canonical execution results; if the shadow VM panics,
+    /// shadow execution is disabled.
+    pub fn with_shadow_vm(mut self, vm_version: VmVersion, pool: ConnectionPool<Core>) -> Self {
+        self.shadow_vm = Some(ShadowVmConfig::new(vm_version, pool));
+        self
+    }
 }
 
 #[async_trait]
@@ -60,19 +75,41 @@ impl BatchExecutor for MainBatchExecutor {
             optional_bytecode_compression: self.optional_bytecode_compression,
             commands: commands_receiver,
         };
+        let shadow_vm_config = self
+            .shadow_vm
+            .clone()
+            .filter(|config| !config.is_disabled());
 
         let stop_receiver = stop_receiver.clone();
         let handle = tokio::task::spawn_blocking(move || {
-            if let Some(storage) = Handle::current()
-                .block_on(
-                    storage_factory.access_storage(&stop_receiver, l1_batch_params.number - 1),
-                )
-                .expect("failed getting access to state keeper storage")
-            {
-                executor.run(storage, l1_batch_params, system_env);
-            } else {
+            let storage_factory = &*storage_factory;
+            let stop_receiver = &stop_receiver;
+            let prev_l1_batch_number = l1_batch_params.number - 1;
+            let access_storage = move || {
+                Handle::current()
+                    .block_on(storage_factory.access_storage(stop_receiver, prev_l1_batch_number))
+                    .expect("failed getting access to state keeper storage")
+            };
+
+            let Some(storage) = access_storage() else {
                 tracing::info!("Interrupted while trying to access state keeper storage");
-            }
+                return;
+            };
+            let shadow_vm = if let Some(config) = shadow_vm_config {
+                let Some(shadow_storage) = access_storage() else {
+                    tracing::info!("Interrupted while trying to access shadow VM storage");
+                    return;
+                };
+                Some(ShadowVm::new(
+                    config,
+                    shadow_storage,
+                    l1_batch_params.clone(),
+                    system_env.clone(),
+                ))
+            } else {
+                None
+            };
+            executor.run(storage, shadow_vm, l1_batch_params, system_env);
         });
         Some(BatchExecutorHandle {
             handle,
@@ -98,6 +135,7 @@ impl CommandReceiver {
     pub(super) fn run<S: ReadStorage>(
         mut self,
         secondary_storage: S,
+        mut shadow_vm: Option<ShadowVm<S>>,
         l1_batch_params: L1BatchEnv,
         system_env: SystemEnv,
     ) {
@@ -110,19 +148,30 @@ impl CommandReceiver {
         while let Some(cmd) = self.commands.blocking_recv() {
             match cmd {
                 Command::ExecuteTx(tx, resp) => {
-                    let result = self.execute_tx(&tx, &mut vm);
+                    let result = self.execute_tx(&tx, &mut vm, shadow_vm.as_mut());
                     resp.send(result).unwrap();
                 }
                 Command::RollbackLastTx(resp) => {
                     self.rollback_last_tx(&mut vm);
+                    if let Some(shadow_vm) = &mut shadow_vm {
+                        shadow_vm.rollback_last_tx();
+                    }
                     resp.send(()).unwrap();
                 }
                 Command::StartNextL2Block(l2_block_env, resp) => {
+                    if let Some(shadow_vm) = &mut shadow_vm {
+                        shadow_vm.start_next_l2_block(l2_block_env);
+                    }
                     self.start_next_l2_block(l2_block_env, &mut vm);
                     resp.send(()).unwrap();
                 }
                 Command::FinishBatch(resp) => {
                     let vm_block_result = self.finish_batch(&mut vm);
+                    // Finish the shadow batch before responding, so that divergences are persisted
+                    // by the time the batch is sealed.
+                    if let Some(shadow_vm) = shadow_vm.take() {
+                        shadow_vm.finish_batch(&vm_block_result);
+                    }
                     resp.send(vm_block_result).unwrap();
 
                     // `storage_view` cannot be accessed while borrowed by the VM,
@@ -140,10 +189,11 @@ impl CommandReceiver {
         tracing::info!("State keeper exited with an unfinished L1 batch");
     }
 
-    fn execute_tx<S: WriteStorage>(
+    fn execute_tx<S: WriteStorage, SS: ReadStorage>(
         &self,
         tx: &Transaction,
         vm: &mut VmInstance<S, HistoryEnabled>,
+        shadow_vm: Option<&mut ShadowVm<SS>>,
     ) -> TxExecutionResult {
         // Save pre-`execute_next_tx` VM snapshot.
         vm.make_snapshot();
@@ -160,6 +210,10 @@ impl CommandReceiver {
         APP_METRICS.processed_txs[&TxStage::StateKeeper].inc();
         APP_METRICS.processed_l1_txs[&TxStage::StateKeeper].inc_by(tx.is_l1().into());
 
+        if let Some(shadow_vm) = shadow_vm {
+            shadow_vm.execute_tx(tx, &tx_result, self.optional_bytecode_compression);
+        }
+
         if let ExecutionResult::Halt { reason } = tx_result.result {
             return match reason {
                 Halt::BootloaderOutOfGas => TxExecutionResult::BootloaderOutOfGasForTx,
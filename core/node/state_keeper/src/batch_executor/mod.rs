@@ -21,6 +21,7 @@ use crate::{
 mod tests;
 
 pub mod main_executor;
+mod shadow;
 
 /// Representation of a transaction executed in the virtual machine.
 #[derive(Debug, Clone)]
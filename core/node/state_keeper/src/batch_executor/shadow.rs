@@ -0,0 +1,560 @@
+//! Shadow VM executing L1 batches alongside the canonical VM in order to detect divergences
+//! between VM versions using real traffic.
+
+use std::{
+    any::Any,
+    collections::{BTreeMap, BTreeSet},
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use multivm::{
+    interface::{
+        ExecutionResult, FinishedL1Batch, Halt, L1BatchEnv, L2BlockEnv, SystemEnv,
+        VmExecutionResultAndLogs, VmInterface, VmInterfaceHistoryEnabled,
+    },
+    vm_latest::HistoryEnabled,
+    VmInstance,
+};
+use serde_json::json;
+use tokio::runtime::Handle;
+use zksync_dal::{vm_shadow_dal::VmDivergence, ConnectionPool, Core, CoreDal};
+use zksync_state::{ReadStorage, StorageView};
+use zksync_types::{
+    event::VmEvent, zk_evm_types::LogQuery, Address, L1BatchNumber, Transaction, VmVersion, H256,
+    U256,
+};
+
+use crate::metrics::{VmDivergenceKind, SHADOW_VM_METRICS};
+
+/// Maximum number of diverged storage slots included into a single divergence report.
+const MAX_REPORTED_ITEMS: usize = 10;
+
+/// Configuration of the shadow VM.
+#[derive(Debug, Clone)]
+pub(super) struct ShadowVmConfig {
+    pub vm_version: VmVersion,
+    pub pool: ConnectionPool<Core>,
+    /// Set if the shadow VM has panicked. Shadow execution is disabled for all subsequent batches in this case.
+    pub is_disabled: Arc<AtomicBool>,
+}
+
+impl ShadowVmConfig {
+    pub fn new(vm_version: VmVersion, pool: ConnectionPool<Core>) -> Self {
+        Self {
+            vm_version,
+            pool,
+            is_disabled: Arc::default(),
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.is_disabled.load(Ordering::Relaxed)
+    }
+}
+
+/// VM of a candidate version that executes the same commands as the canonical VM. Divergences between VM outputs
+/// are accumulated while the batch is executed and are persisted to Postgres once the batch is finished.
+///
+/// All calls to the shadow VM are guarded against panics. If the shadow VM panics, the panic is recorded
+/// as a divergence, and shadow execution is disabled (both for the remainder of the batch and for all following batches).
+pub(super) struct ShadowVm<S: ReadStorage> {
+    /// `None` if the shadow VM has panicked.
+    vm: Option<VmInstance<StorageView<S>, HistoryEnabled>>,
+    config: ShadowVmConfig,
+    canonical_vm_version: VmVersion,
+    l1_batch_number: L1BatchNumber,
+    divergences: DivergenceHandler,
+}
+
+impl<S: ReadStorage> ShadowVm<S> {
+    pub fn new(
+        config: ShadowVmConfig,
+        storage: S,
+        l1_batch_env: L1BatchEnv,
+        system_env: SystemEnv,
+    ) -> Self {
+        let canonical_vm_version = VmVersion::from(system_env.version);
+        let l1_batch_number = l1_batch_env.number;
+        let vm_version = config.vm_version;
+        let mut this = Self {
+            vm: None,
+            config,
+            canonical_vm_version,
+            l1_batch_number,
+            divergences: DivergenceHandler::default(),
+        };
+        this.vm = this.guard("initialization", move || {
+            let storage_view = StorageView::new(storage).to_rc_ptr();
+            VmInstance::new_with_specific_version(
+                l1_batch_env,
+                system_env,
+                storage_view,
+                vm_version,
+            )
+        });
+        this
+    }
+
+    /// Runs the provided closure catching a panic in it. If a panic occurs, it's recorded as a divergence,
+    /// and the shadow VM is disabled.
+    fn guard<T>(&mut self, stage: &'static str, action: impl FnOnce() -> T) -> Option<T> {
+        match panic::catch_unwind(AssertUnwindSafe(action)) {
+            Ok(output) => Some(output),
+            Err(panic) => {
+                let message = panic_message(&*panic);
+                tracing::error!(
+                    "Shadow VM {:?} panicked during {stage} in L1 batch #{}: {message}; disabling shadow execution",
+                    self.config.vm_version,
+                    self.l1_batch_number
+                );
+                SHADOW_VM_METRICS.panics.inc();
+                self.vm = None;
+                self.config.is_disabled.store(true, Ordering::Relaxed);
+                self.divergences.push_panic(stage, &message);
+                None
+            }
+        }
+    }
+
+    /// Executes a transaction in the shadow VM and compares the output with the one produced by the canonical VM.
+    pub fn execute_tx(
+        &mut self,
+        tx: &Transaction,
+        canonical_result: &VmExecutionResultAndLogs,
+        optional_bytecode_compression: bool,
+    ) {
+        let Some(mut vm) = self.vm.take() else {
+            return;
+        };
+        let shadow_result = self.guard("transaction execution", || {
+            // Mirrors the snapshot made by the canonical VM so that the transaction can be rolled back.
+            vm.make_snapshot();
+            let result = execute_tx_in_vm(&mut vm, tx, optional_bytecode_compression);
+            (vm, result)
+        });
+        let Some((vm, shadow_result)) = shadow_result else {
+            return;
+        };
+        self.vm = Some(vm);
+
+        self.divergences.start_tx();
+        self.divergences
+            .compare_results(Some(tx.hash()), canonical_result, &shadow_result);
+    }
+
+    /// Rolls back the last executed transaction, discarding divergences detected for it.
+    pub fn rollback_last_tx(&mut self) {
+        let Some(mut vm) = self.vm.take() else {
+            return;
+        };
+        self.vm = self.guard("transaction rollback", || {
+            vm.rollback_to_the_latest_snapshot();
+            vm
+        });
+        self.divergences.rollback_last_tx();
+    }
+
+    pub fn start_next_l2_block(&mut self, l2_block_env: L2BlockEnv) {
+        let Some(mut vm) = self.vm.take() else {
+            return;
+        };
+        self.vm = self.guard("starting L2 block", || {
+            vm.start_new_l2_block(l2_block_env);
+            vm
+        });
+        self.divergences.start_l2_block();
+    }
+
+    /// Finishes the batch in the shadow VM, compares the final VM state with the canonical one and persists
+    /// all detected divergences. Must be called from a blocking Tokio task.
+    pub fn finish_batch(mut self, canonical_batch: &FinishedL1Batch) {
+        if let Some(mut vm) = self.vm.take() {
+            if let Some(shadow_batch) = self.guard("finishing batch", || vm.finish_batch()) {
+                self.divergences
+                    .compare_batches(canonical_batch, &shadow_batch);
+                SHADOW_VM_METRICS.checked_batches.inc();
+            }
+        }
+
+        let divergences = &self.divergences.divergences;
+        if !divergences.is_empty() {
+            tracing::warn!(
+                "Shadow VM {:?} diverged from canonical VM {:?} in L1 batch #{}: {} divergence(s) detected",
+                self.config.vm_version,
+                self.canonical_vm_version,
+                self.l1_batch_number,
+                divergences.len()
+            );
+            SHADOW_VM_METRICS.diverged_batches.inc();
+            for (kind, _) in divergences {
+                SHADOW_VM_METRICS.divergences[kind].inc();
+            }
+        }
+
+        let persist_future = self.divergences.persist(
+            &self.config.pool,
+            self.l1_batch_number,
+            self.canonical_vm_version,
+            self.config.vm_version,
+        );
+        if let Err(err) = Handle::current().block_on(persist_future) {
+            tracing::warn!(
+                "Failed persisting shadow VM divergences for L1 batch #{}: {err:#}",
+                self.l1_batch_number
+            );
+            SHADOW_VM_METRICS.persistence_errors.inc();
+        }
+    }
+}
+
+fn execute_tx_in_vm<S: ReadStorage>(
+    vm: &mut VmInstance<StorageView<S>, HistoryEnabled>,
+    tx: &Transaction,
+    optional_bytecode_compression: bool,
+) -> VmExecutionResultAndLogs {
+    if optional_bytecode_compression {
+        vm.make_snapshot();
+        if let (Ok(()), result) = vm.execute_transaction_with_bytecode_compression(tx.clone(), true)
+        {
+            vm.pop_snapshot_no_rollback();
+            return result;
+        }
+        vm.rollback_to_the_latest_snapshot();
+        vm.execute_transaction_with_bytecode_compression(tx.clone(), false)
+            .1
+    } else {
+        let (published_bytecodes, mut result) =
+            vm.execute_transaction_with_bytecode_compression(tx.clone(), true);
+        if published_bytecodes.is_err() {
+            result.result = ExecutionResult::Halt {
+                reason: Halt::FailedToPublishCompressedBytecodes,
+            };
+        }
+        result
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> String {
+    if let Some(message) = panic.downcast_ref::<&str>() {
+        (*message).to_owned()
+    } else if let Some(message) = panic.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        "(non-string panic payload)".to_owned()
+    }
+}
+
+/// Accumulates divergences between the canonical and shadow VM outputs for a single L1 batch.
+#[derive(Debug, Default)]
+struct DivergenceHandler {
+    divergences: Vec<(VmDivergenceKind, VmDivergence)>,
+    last_tx_divergence_count: usize,
+}
+
+impl DivergenceHandler {
+    fn start_tx(&mut self) {
+        self.last_tx_divergence_count = 0;
+    }
+
+    fn rollback_last_tx(&mut self) {
+        let retained_count = self.divergences.len() - self.last_tx_divergence_count;
+        self.divergences.truncate(retained_count);
+        self.last_tx_divergence_count = 0;
+    }
+
+    fn start_l2_block(&mut self) {
+        self.last_tx_divergence_count = 0;
+    }
+
+    async fn persist(
+        &self,
+        pool: &ConnectionPool<Core>,
+        l1_batch_number: L1BatchNumber,
+        canonical_vm_version: VmVersion,
+        shadow_vm_version: VmVersion,
+    ) -> anyhow::Result<()> {
+        let divergences: Vec<_> = self
+            .divergences
+            .iter()
+            .map(|(_, divergence)| divergence.clone())
+            .collect();
+        let mut connection = pool.connection_tagged("state_keeper").await?;
+        connection
+            .vm_shadow_dal()
+            .insert_divergences(
+                l1_batch_number,
+                &format!("{canonical_vm_version:?}"),
+                &format!("{shadow_vm_version:?}"),
+                &divergences,
+            )
+            .await?;
+        Ok(())
+    }
+
+    fn push_divergence(
+        &mut self,
+        kind: VmDivergenceKind,
+        tx_hash: Option<H256>,
+        details: serde_json::Value,
+    ) {
+        let divergence = VmDivergence {
+            tx_hash,
+            kind: kind.as_str().to_owned(),
+            details,
+        };
+        self.divergences.push((kind, divergence));
+        self.last_tx_divergence_count += 1;
+    }
+
+    fn push_panic(&mut self, stage: &str, message: &str) {
+        let details = json!({
+            "stage": stage,
+            "message": message,
+        });
+        self.push_divergence(VmDivergenceKind::Panic, None, details);
+        // Panics are never discarded on rollback.
+        self.last_tx_divergence_count = 0;
+    }
+
+    fn compare_batches(&mut self, canonical: &FinishedL1Batch, shadow: &FinishedL1Batch) {
+        self.compare_results(
+            None,
+            &canonical.block_tip_execution_result,
+            &shadow.block_tip_execution_result,
+        );
+
+        let canonical_state = &canonical.final_execution_state;
+        let shadow_state = &shadow.final_execution_state;
+        self.compare_events(None, &canonical_state.events, &shadow_state.events);
+        self.compare_storage_writes(
+            None,
+            &canonical_state.deduplicated_storage_log_queries,
+            &shadow_state.deduplicated_storage_log_queries,
+        );
+    }
+
+    fn compare_results(
+        &mut self,
+        tx_hash: Option<H256>,
+        canonical: &VmExecutionResultAndLogs,
+        shadow: &VmExecutionResultAndLogs,
+    ) {
+        if canonical.result != shadow.result {
+            let details = json!({
+                "canonical": format!("{:?}", canonical.result),
+                "shadow": format!("{:?}", shadow.result),
+            });
+            self.push_divergence(VmDivergenceKind::ExecutionResult, tx_hash, details);
+        }
+
+        let canonical_gas = (
+            canonical.statistics.gas_used,
+            canonical.refunds.gas_refunded,
+        );
+        let shadow_gas = (shadow.statistics.gas_used, shadow.refunds.gas_refunded);
+        if canonical_gas != shadow_gas {
+            let details = json!({
+                "canonical": { "gas_used": canonical_gas.0, "gas_refunded": canonical_gas.1 },
+                "shadow": { "gas_used": shadow_gas.0, "gas_refunded": shadow_gas.1 },
+            });
+            self.push_divergence(VmDivergenceKind::Gas, tx_hash, details);
+        }
+
+        self.compare_events(tx_hash, &canonical.logs.events, &shadow.logs.events);
+        self.compare_storage_writes(
+            tx_hash,
+            canonical.logs.storage_logs.iter().map(|log| &log.log_query),
+            shadow.logs.storage_logs.iter().map(|log| &log.log_query),
+        );
+    }
+
+    fn compare_events(&mut self, tx_hash: Option<H256>, canonical: &[VmEvent], shadow: &[VmEvent]) {
+        if canonical == shadow {
+            return;
+        }
+        let first_diverged_index = canonical
+            .iter()
+            .zip(shadow)
+            .position(|(canonical, shadow)| canonical != shadow)
+            .unwrap_or(canonical.len().min(shadow.len()));
+        let details = json!({
+            "canonical_count": canonical.len(),
+            "shadow_count": shadow.len(),
+            "first_diverged_index": first_diverged_index,
+            "canonical": canonical.get(first_diverged_index),
+            "shadow": shadow.get(first_diverged_index),
+        });
+        self.push_divergence(VmDivergenceKind::Events, tx_hash, details);
+    }
+
+    fn compare_storage_writes<'a>(
+        &mut self,
+        tx_hash: Option<H256>,
+        canonical: impl IntoIterator<Item = &'a LogQuery>,
+        shadow: impl IntoIterator<Item = &'a LogQuery>,
+    ) {
+        let canonical = final_storage_writes(canonical);
+        let shadow = final_storage_writes(shadow);
+        if canonical == shadow {
+            return;
+        }
+
+        let all_slots: BTreeSet<_> = canonical.keys().chain(shadow.keys()).copied().collect();
+        let diverged_slots: Vec<_> = all_slots
+            .into_iter()
+            .filter(|slot| canonical.get(slot) != shadow.get(slot))
+            .collect();
+        let reported_slots: Vec<_> = diverged_slots
+            .iter()
+            .take(MAX_REPORTED_ITEMS)
+            .map(|slot| {
+                json!({
+                    "address": slot.0,
+                    "key": slot.1,
+                    "canonical": canonical.get(slot),
+                    "shadow": shadow.get(slot),
+                })
+            })
+            .collect();
+        let details = json!({
+            "diverged_slot_count": diverged_slots.len(),
+            "slots": reported_slots,
+        });
+        self.push_divergence(VmDivergenceKind::StorageWrites, tx_hash, details);
+    }
+}
+
+/// Returns final values written to each storage slot.
+fn final_storage_writes<'a>(
+    logs: impl IntoIterator<Item = &'a LogQuery>,
+) -> BTreeMap<(Address, U256), U256> {
+    logs.into_iter()
+        .filter(|log| log.rw_flag)
+        .map(|log| ((log.address, log.key), log.written_value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use zksync_types::{L1BatchNumber, U256};
+
+    use super::*;
+    use crate::{
+        testonly::default_vm_batch_result,
+        tests::{create_execution_result, Query},
+    };
+
+    fn diverged_result() -> VmExecutionResultAndLogs {
+        let mut result =
+            create_execution_result(0, [(U256::from(1), Query::InitialWrite(2.into()))]);
+        result.statistics.gas_used = 100;
+        result.logs.events.push(VmEvent::default());
+        result
+    }
+
+    #[test]
+    fn detecting_divergences() {
+        let canonical =
+            create_execution_result(0, [(U256::from(1), Query::InitialWrite(1.into()))]);
+        let mut handler = DivergenceHandler::default();
+        handler.start_tx();
+        handler.compare_results(Some(H256::repeat_byte(1)), &canonical, &canonical);
+        assert!(handler.divergences.is_empty());
+
+        handler.start_tx();
+        handler.compare_results(Some(H256::repeat_byte(2)), &canonical, &diverged_result());
+        let kinds: Vec<_> = handler.divergences.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                VmDivergenceKind::Gas,
+                VmDivergenceKind::Events,
+                VmDivergenceKind::StorageWrites
+            ]
+        );
+        for (_, divergence) in &handler.divergences {
+            assert_eq!(divergence.tx_hash, Some(H256::repeat_byte(2)));
+        }
+        let storage_details = &handler.divergences[2].1.details;
+        assert_eq!(storage_details["diverged_slot_count"], 1);
+
+        // Rolled back transactions must not be reported.
+        handler.rollback_last_tx();
+        assert!(handler.divergences.is_empty());
+    }
+
+    #[test]
+    fn panics_are_not_discarded_on_rollback() {
+        let canonical = create_execution_result(0, []);
+        let mut handler = DivergenceHandler::default();
+        handler.start_tx();
+        handler.compare_results(None, &canonical, &diverged_result());
+        handler.push_panic("transaction rollback", "oops");
+        handler.rollback_last_tx();
+
+        let kinds: Vec<_> = handler.divergences.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(kinds.last(), Some(&VmDivergenceKind::Panic));
+        assert_eq!(
+            handler.divergences.last().unwrap().1.details["message"],
+            "oops"
+        );
+    }
+
+    #[test]
+    fn extracting_panic_messages() {
+        let panic = panic::catch_unwind(|| panic!("static message")).unwrap_err();
+        assert_eq!(panic_message(&*panic), "static message");
+        let panic = panic::catch_unwind(|| panic!("formatted {}", 42)).unwrap_err();
+        assert_eq!(panic_message(&*panic), "formatted 42");
+    }
+
+    #[tokio::test]
+    async fn persisting_divergences() {
+        let pool = ConnectionPool::<Core>::test_pool().await;
+        let canonical_batch = default_vm_batch_result();
+        let mut shadow_batch = default_vm_batch_result();
+        shadow_batch.block_tip_execution_result.result = ExecutionResult::Halt {
+            reason: Halt::BootloaderOutOfGas,
+        };
+        shadow_batch
+            .final_execution_state
+            .events
+            .push(VmEvent::default());
+
+        let mut handler = DivergenceHandler::default();
+        handler.compare_batches(&canonical_batch, &canonical_batch);
+        assert!(handler.divergences.is_empty());
+        handler.compare_batches(&canonical_batch, &shadow_batch);
+        assert_eq!(handler.divergences.len(), 2);
+
+        let l1_batch_number = L1BatchNumber(1);
+        handler
+            .persist(
+                &pool,
+                l1_batch_number,
+                VmVersion::latest(),
+                VmVersion::latest(),
+            )
+            .await
+            .unwrap();
+
+        let persisted = pool
+            .connection()
+            .await
+            .unwrap()
+            .vm_shadow_dal()
+            .get_divergences(l1_batch_number)
+            .await
+            .unwrap();
+        let expected: Vec<_> = handler
+            .divergences
+            .iter()
+            .map(|(_, divergence)| divergence.clone())
+            .collect();
+        assert_eq!(persisted, expected);
+    }
+}
@@ -1,8 +1,10 @@
 use assert_matches::assert_matches;
 use test_casing::{test_casing, Product};
-use zksync_dal::{ConnectionPool, Core};
+use zksync_dal::{ConnectionPool, Core, CoreDal};
 use zksync_test_account::Account;
-use zksync_types::{get_nonce_key, utils::storage_key_for_eth_balance, PriorityOpId};
+use zksync_types::{
+    get_nonce_key, utils::storage_key_for_eth_balance, L1BatchNumber, PriorityOpId, VmVersion,
+};
 
 use self::tester::{AccountLoadNextExecutable, StorageSnapshot, TestConfig, Tester};
 use super::TxExecutionResult;
@@ -206,6 +208,42 @@ async fn rollback() {
     executor.finish_batch().await;
 }
 
+/// Checks that a shadow VM of the same version as the canonical VM doesn't report divergences,
+/// including for rolled back transactions.
+#[tokio::test]
+async fn shadow_vm_execution() {
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut alice = Account::random();
+
+    let mut tester = Tester::with_config(
+        connection_pool,
+        TestConfig {
+            shadow_vm_version: Some(VmVersion::latest()),
+            ..TestConfig::new()
+        },
+    );
+    tester.genesis().await;
+    tester.fund(&[alice.address()]).await;
+    let executor = tester
+        .create_batch_executor(StorageType::AsyncRocksdbCache)
+        .await;
+
+    let tx = alice.execute();
+    assert_executed(&executor.execute_tx(tx.clone()).await);
+    executor.rollback_last_tx().await;
+    assert_executed(&executor.execute_tx(tx).await);
+    assert_executed(&executor.execute_tx(alice.execute()).await);
+    executor.finish_batch().await;
+
+    let mut storage = tester.pool().connection().await.unwrap();
+    let divergences = storage
+        .vm_shadow_dal()
+        .get_divergences(L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(divergences, []);
+}
+
 /// Checks that incorrect transactions are marked as rejected.
 #[tokio::test]
 async fn reject_tx() {
@@ -388,6 +426,7 @@ async fn bootloader_out_of_gas_for_any_tx() {
             save_call_traces: false,
             vm_gas_limit: Some(10),
             validation_computational_gas_limit: u32::MAX,
+            shadow_vm_version: None,
         },
     );
 
@@ -433,6 +472,7 @@ async fn bootloader_tip_out_of_gas() {
                 - 10,
         ),
         validation_computational_gas_limit: u32::MAX,
+        shadow_vm_version: None,
     });
 
     let second_executor = tester
@@ -21,7 +21,7 @@ use zksync_types::{
     storage_writes_deduplicator::StorageWritesDeduplicator,
     system_contracts::get_system_smart_contracts, utils::storage_key_for_standard_token_balance,
     AccountTreeId, Address, Execute, L1BatchNumber, L2BlockNumber, PriorityOpId, ProtocolVersionId,
-    StorageKey, StorageLog, Transaction, H256, L2_BASE_TOKEN_ADDRESS,
+    StorageKey, StorageLog, Transaction, VmVersion, H256, L2_BASE_TOKEN_ADDRESS,
     SYSTEM_CONTEXT_MINIMAL_BASE_FEE, U256,
 };
 use zksync_utils::u256_to_h256;
@@ -46,6 +46,7 @@ pub(super) struct TestConfig {
     pub(super) save_call_traces: bool,
     pub(super) vm_gas_limit: Option<u32>,
     pub(super) validation_computational_gas_limit: u32,
+    pub(super) shadow_vm_version: Option<VmVersion>,
 }
 
 impl TestConfig {
@@ -56,6 +57,7 @@ impl TestConfig {
             vm_gas_limit: None,
             save_call_traces: false,
             validation_computational_gas_limit: config.validation_computational_gas_limit,
+            shadow_vm_version: None,
         }
     }
 }
@@ -146,6 +148,9 @@ impl Tester {
         system_env: SystemEnv,
     ) -> BatchExecutorHandle {
         let mut batch_executor = MainBatchExecutor::new(self.config.save_call_traces, false);
+        if let Some(vm_version) = self.config.shadow_vm_version {
+            batch_executor = batch_executor.with_shadow_vm(vm_version, self.pool());
+        }
         let (_stop_sender, stop_receiver) = watch::channel(false);
         batch_executor
             .init_batch(storage_factory, l1_batch_env, system_env, &stop_receiver)
@@ -1,10 +1,17 @@
 //! State keeper persistence logic.
 
-use std::{sync::Arc, time::Instant};
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
 
 use anyhow::Context as _;
 use async_trait::async_trait;
-use tokio::sync::{mpsc, oneshot};
+use tokio::sync::{mpsc, oneshot, OwnedSemaphorePermit, Semaphore};
+use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_shared_metrics::{BlockStage, APP_METRICS};
 use zksync_types::Address;
@@ -13,7 +20,7 @@ use crate::{
     io::{
         seal_logic::l2_block_seal_subtasks::L2BlockSealProcess, IoCursor, StateKeeperOutputHandler,
     },
-    metrics::{L2BlockQueueStage, L2_BLOCK_METRICS},
+    metrics::{L2BlockQueueStage, QueueCapacityAdjustment, L2_BLOCK_METRICS},
     updates::{L2BlockSealCommand, UpdatesManager},
 };
 
@@ -22,6 +29,170 @@ use crate::{
 struct Completable<T> {
     command: T,
     completion_sender: oneshot::Sender<()>,
+    submitted_at: Instant,
+    /// Slot in the adaptive seal queue; released once the command is taken by the sealer.
+    permit: Option<OwnedSemaphorePermit>,
+}
+
+/// Configuration of the adaptive L2 block seal queue, the capacity of which is adjusted at runtime
+/// based on the Postgres write latency.
+///
+/// The queue grows if it is full while Postgres keeps up (i.e., L2 blocks are produced in bursts), so that
+/// the state keeper doesn't stall. It shrinks if Postgres writes become slow (e.g., during catch-up), since
+/// a larger queue would only accumulate unsealed L2 blocks in memory, and slowly decays to the minimum capacity
+/// when the queue is mostly empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AdaptiveSealQueueConfig {
+    min_capacity: usize,
+    max_capacity: usize,
+    target_seal_latency: Duration,
+}
+
+impl AdaptiveSealQueueConfig {
+    /// Default Postgres write latency of a single L2 block above which the queue is shrunk.
+    pub const DEFAULT_TARGET_SEAL_LATENCY: Duration = Duration::from_millis(500);
+    /// Smoothing factor of the exponential moving average of the seal latency.
+    const LATENCY_SMOOTHING: f64 = 0.2;
+
+    /// Creates a config for the queue with the capacity adjusted within `min_capacity..=max_capacity`.
+    /// Returns `None` if the bounds leave no room for adjustments, or if sealing is synchronous (i.e., `min_capacity == 0`).
+    pub fn new(min_capacity: usize, max_capacity: usize) -> Option<Self> {
+        (min_capacity > 0 && max_capacity > min_capacity).then_some(Self {
+            min_capacity,
+            max_capacity,
+            target_seal_latency: Self::DEFAULT_TARGET_SEAL_LATENCY,
+        })
+    }
+
+    /// Extracts the adaptive queue config from the state keeper config, if it is enabled there.
+    pub fn from_state_keeper_config(config: &StateKeeperConfig) -> Option<Self> {
+        let max_capacity = config.l2_block_seal_queue_max_capacity?;
+        let this = Self::new(config.l2_block_seal_queue_capacity, max_capacity)?;
+        Some(match config.l2_block_seal_queue_target_latency_ms {
+            Some(latency_ms) => this.with_target_seal_latency(Duration::from_millis(latency_ms)),
+            None => this,
+        })
+    }
+
+    /// Sets the Postgres write latency of a single L2 block above which the queue is shrunk.
+    pub fn with_target_seal_latency(mut self, latency: Duration) -> Self {
+        self.target_seal_latency = latency;
+        self
+    }
+}
+
+/// Soft limit on the number of commands in the seal queue, shared between [`StateKeeperPersistence`]
+/// and [`L2BlockSealerTask`]. The underlying channel has `max_capacity` slots; the limit is enforced
+/// by a semaphore with the number of permits equal to the current capacity.
+#[derive(Debug)]
+struct AdaptiveCapacity {
+    config: AdaptiveSealQueueConfig,
+    semaphore: Arc<Semaphore>,
+    capacity: AtomicUsize,
+    /// Number of permits that should be forgotten rather than returned once released, in order to shrink the queue.
+    pending_shrink: AtomicUsize,
+}
+
+impl AdaptiveCapacity {
+    fn new(config: AdaptiveSealQueueConfig) -> Self {
+        Self {
+            config,
+            semaphore: Arc::new(Semaphore::new(config.min_capacity)),
+            capacity: AtomicUsize::new(config.min_capacity),
+            pending_shrink: AtomicUsize::new(0),
+        }
+    }
+
+    fn capacity(&self) -> usize {
+        self.capacity.load(Ordering::Relaxed)
+    }
+
+    fn available_capacity(&self) -> usize {
+        self.semaphore.available_permits()
+    }
+
+    async fn acquire(&self) -> OwnedSemaphorePermit {
+        self.semaphore
+            .clone()
+            .acquire_owned()
+            .await
+            .expect("seal queue semaphore is never closed")
+    }
+
+    fn release(&self, permit: OwnedSemaphorePermit) {
+        let should_forget = self
+            .pending_shrink
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |pending| {
+                pending.checked_sub(1)
+            })
+            .is_ok();
+        if should_forget {
+            permit.forget();
+        }
+    }
+
+    /// Computes the new capacity based on the smoothed seal latency and the current queue depth.
+    fn target_capacity(&self, seal_latency: Duration, queue_depth: usize) -> usize {
+        let AdaptiveSealQueueConfig {
+            min_capacity,
+            max_capacity,
+            target_seal_latency,
+        } = self.config;
+        let capacity = self.capacity();
+
+        if seal_latency > target_seal_latency {
+            // Postgres cannot keep up; growing the queue would only accumulate L2 blocks in memory.
+            (capacity / 2).max(min_capacity)
+        } else if queue_depth >= capacity {
+            // Postgres is healthy, but the queue is full; grow it so that the state keeper doesn't stall.
+            capacity.saturating_mul(2).min(max_capacity)
+        } else if queue_depth <= capacity / 4 {
+            capacity.saturating_sub(1).max(min_capacity)
+        } else {
+            capacity
+        }
+    }
+
+    fn adjust(&self, seal_latency: Duration, queue_depth: usize) {
+        let capacity = self.capacity();
+        let new_capacity = self.target_capacity(seal_latency, queue_depth);
+        if new_capacity > capacity {
+            let mut delta = new_capacity - capacity;
+            // Cancel pending shrinking first; it wasn't applied to the semaphore yet.
+            let cancelled_shrink = self
+                .pending_shrink
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |pending| {
+                    Some(pending.saturating_sub(delta))
+                })
+                .unwrap();
+            delta -= cancelled_shrink.min(delta);
+            self.semaphore.add_permits(delta);
+            L2_BLOCK_METRICS.seal_queue_capacity_adjustments[&QueueCapacityAdjustment::Grow].inc();
+        } else if new_capacity < capacity {
+            let mut delta = capacity - new_capacity;
+            // Remove available permits immediately; the remaining ones are held by queued commands
+            // and will be forgotten once released.
+            while delta > 0 {
+                let Ok(permit) = self.semaphore.try_acquire() else {
+                    break;
+                };
+                permit.forget();
+                delta -= 1;
+            }
+            self.pending_shrink.fetch_add(delta, Ordering::Relaxed);
+            L2_BLOCK_METRICS.seal_queue_capacity_adjustments[&QueueCapacityAdjustment::Shrink]
+                .inc();
+        } else {
+            return;
+        }
+
+        tracing::debug!(
+            "Adjusted L2 block seal queue capacity from {capacity} to {new_capacity} \
+             (smoothed seal latency: {seal_latency:?}, queue depth: {queue_depth})"
+        );
+        self.capacity.store(new_capacity, Ordering::Relaxed);
+        L2_BLOCK_METRICS.seal_queue_max_capacity.set(new_capacity);
+    }
 }
 
 /// Canonical [`HandleStateKeeperOutput`] implementation that stores processed L2 blocks and L1 batches to Postgres.
@@ -35,6 +206,7 @@ pub struct StateKeeperPersistence {
     latest_completion_receiver: Option<oneshot::Receiver<()>>,
     // If true, `submit_l2_block()` will wait for the operation to complete.
     is_sync: bool,
+    adaptive_capacity: Option<Arc<AdaptiveCapacity>>,
 }
 
 impl StateKeeperPersistence {
@@ -43,9 +215,34 @@ impl StateKeeperPersistence {
     /// Creates a sealer that will use the provided Postgres connection and will have the specified
     /// `command_capacity` for unprocessed sealing commands.
     pub fn new(
+        pool: ConnectionPool<Core>,
+        l2_shared_bridge_addr: Address,
+        command_capacity: usize,
+    ) -> (Self, L2BlockSealerTask) {
+        Self::new_inner(pool, l2_shared_bridge_addr, command_capacity, None)
+    }
+
+    /// Creates a sealer with the capacity of the sealing command queue adjusted at runtime
+    /// according to the provided `config`.
+    pub fn new_adaptive(
+        pool: ConnectionPool<Core>,
+        l2_shared_bridge_addr: Address,
+        config: AdaptiveSealQueueConfig,
+    ) -> (Self, L2BlockSealerTask) {
+        let adaptive_capacity = Arc::new(AdaptiveCapacity::new(config));
+        Self::new_inner(
+            pool,
+            l2_shared_bridge_addr,
+            config.max_capacity,
+            Some(adaptive_capacity),
+        )
+    }
+
+    fn new_inner(
         pool: ConnectionPool<Core>,
         l2_shared_bridge_addr: Address,
         mut command_capacity: usize,
+        adaptive_capacity: Option<Arc<AdaptiveCapacity>>,
     ) -> (Self, L2BlockSealerTask) {
         let is_sync = command_capacity == 0;
         command_capacity = command_capacity.max(1);
@@ -56,6 +253,7 @@ impl StateKeeperPersistence {
             is_sync,
             commands_sender: commands_sender.downgrade(),
             commands_receiver,
+            adaptive_capacity: adaptive_capacity.clone(),
         };
         let this = Self {
             pool,
@@ -65,6 +263,7 @@ impl StateKeeperPersistence {
             commands_sender,
             latest_completion_receiver: None,
             is_sync,
+            adaptive_capacity,
         };
         (this, sealer)
     }
@@ -94,11 +293,17 @@ impl StateKeeperPersistence {
         );
 
         let start = Instant::now();
+        let permit = match &self.adaptive_capacity {
+            Some(adaptive_capacity) => Some(adaptive_capacity.acquire().await),
+            None => None,
+        };
         let (completion_sender, completion_receiver) = oneshot::channel();
         self.latest_completion_receiver = Some(completion_receiver);
         let command = Completable {
             command,
             completion_sender,
+            submitted_at: start,
+            permit,
         };
         self.commands_sender
             .send(command)
@@ -106,7 +311,7 @@ impl StateKeeperPersistence {
             .expect(Self::SHUTDOWN_MSG);
 
         let elapsed = start.elapsed();
-        let queue_capacity = self.commands_sender.capacity();
+        let queue_capacity = self.available_capacity();
         tracing::debug!(
             "Enqueued sealing command for L2 block #{l2_block_number} (took {elapsed:?}; \
              available queue capacity: {queue_capacity})"
@@ -115,16 +320,27 @@ impl StateKeeperPersistence {
         if self.is_sync {
             self.wait_for_all_commands().await;
         } else {
-            L2_BLOCK_METRICS.seal_queue_capacity.set(queue_capacity);
+            self.report_queue_metrics();
             L2_BLOCK_METRICS.seal_queue_latency[&L2BlockQueueStage::Submit].observe(elapsed);
         }
     }
 
+    fn available_capacity(&self) -> usize {
+        match &self.adaptive_capacity {
+            Some(adaptive_capacity) => adaptive_capacity.available_capacity(),
+            None => self.commands_sender.capacity(),
+        }
+    }
+
+    fn report_queue_metrics(&self) {
+        report_queue_metrics(&self.commands_sender, self.adaptive_capacity.as_deref());
+    }
+
     /// Waits until all previously submitted commands are fully processed by the sealer.
     async fn wait_for_all_commands(&mut self) {
         tracing::debug!(
             "Requested waiting for L2 block seal queue to empty; current available capacity: {}",
-            self.commands_sender.capacity()
+            self.available_capacity()
         );
 
         let start = Instant::now();
@@ -139,15 +355,30 @@ impl StateKeeperPersistence {
         // Since this method called from outside is essentially a no-op if `self.is_sync`,
         // we don't report its metrics in this case.
         if !self.is_sync {
-            L2_BLOCK_METRICS
-                .seal_queue_capacity
-                .set(self.commands_sender.capacity());
+            self.report_queue_metrics();
             L2_BLOCK_METRICS.seal_queue_latency[&L2BlockQueueStage::WaitForAllCommands]
                 .observe(elapsed);
         }
     }
 }
 
+fn report_queue_metrics<T>(
+    commands_sender: &mpsc::Sender<T>,
+    adaptive_capacity: Option<&AdaptiveCapacity>,
+) {
+    let queue_depth = commands_sender.max_capacity() - commands_sender.capacity();
+    let (max_capacity, available_capacity) = match adaptive_capacity {
+        Some(adaptive_capacity) => (
+            adaptive_capacity.capacity(),
+            adaptive_capacity.available_capacity(),
+        ),
+        None => (commands_sender.max_capacity(), commands_sender.capacity()),
+    };
+    L2_BLOCK_METRICS.seal_queue_depth.set(queue_depth);
+    L2_BLOCK_METRICS.seal_queue_max_capacity.set(max_capacity);
+    L2_BLOCK_METRICS.seal_queue_capacity.set(available_capacity);
+}
+
 #[async_trait]
 impl StateKeeperOutputHandler for StateKeeperPersistence {
     async fn initialize(&mut self, cursor: &IoCursor) -> anyhow::Result<()> {
@@ -191,6 +422,7 @@ pub struct L2BlockSealerTask {
     // Weak sender handle to get queue capacity stats.
     commands_sender: mpsc::WeakSender<Completable<L2BlockSealCommand>>,
     commands_receiver: mpsc::Receiver<Completable<L2BlockSealCommand>>,
+    adaptive_capacity: Option<Arc<AdaptiveCapacity>>,
 }
 
 impl L2BlockSealerTask {
@@ -199,6 +431,15 @@ impl L2BlockSealerTask {
     pub async fn run(mut self) -> anyhow::Result<()> {
         if self.is_sync {
             tracing::info!("Starting synchronous L2 block sealer");
+        } else if let Some(adaptive_capacity) = &self.adaptive_capacity {
+            let config = &adaptive_capacity.config;
+            tracing::info!(
+                "Starting async L2 block sealer with adaptive queue capacity {}..={} \
+                 (target seal latency: {:?})",
+                config.min_capacity,
+                config.max_capacity,
+                config.target_seal_latency
+            );
         } else if let Some(sender) = self.commands_sender.upgrade() {
             tracing::info!(
                 "Starting async L2 block sealer with queue capacity {}",
@@ -209,14 +450,33 @@ impl L2BlockSealerTask {
         }
 
         let mut l2_block_seal_delta: Option<Instant> = None;
+        let mut smoothed_seal_latency: Option<Duration> = None;
         // Commands must be processed sequentially: a later L2 block cannot be saved before
         // an earlier one.
         while let Some(completable) = self.next_command().await {
+            let seal_start = Instant::now();
             completable.command.seal(self.pool.clone()).await?;
+            let seal_latency = seal_start.elapsed();
             if let Some(delta) = l2_block_seal_delta {
                 L2_BLOCK_METRICS.seal_delta.observe(delta.elapsed());
             }
             l2_block_seal_delta = Some(Instant::now());
+            if !self.is_sync {
+                L2_BLOCK_METRICS.seal_queue_latency[&L2BlockQueueStage::Roundtrip]
+                    .observe(completable.submitted_at.elapsed());
+            }
+
+            if let Some(adaptive_capacity) = &self.adaptive_capacity {
+                let latency = smooth_latency(smoothed_seal_latency, seal_latency);
+                smoothed_seal_latency = Some(latency);
+                L2_BLOCK_METRICS
+                    .seal_queue_smoothed_seal_latency
+                    .set(latency);
+                if let Some(sender) = self.commands_sender.upgrade() {
+                    let queue_depth = sender.max_capacity() - sender.capacity();
+                    adaptive_capacity.adjust(latency, queue_depth);
+                }
+            }
 
             completable.completion_sender.send(()).ok();
             // ^ We don't care whether anyone listens to the processing progress
@@ -227,26 +487,41 @@ impl L2BlockSealerTask {
     async fn next_command(&mut self) -> Option<Completable<L2BlockSealCommand>> {
         tracing::debug!("Polling L2 block seal queue for next command");
         let start = Instant::now();
-        let command = self.commands_receiver.recv().await;
+        let mut command = self.commands_receiver.recv().await;
         let elapsed = start.elapsed();
 
-        if let Some(completable) = &command {
+        if let Some(completable) = &mut command {
             tracing::debug!(
                 "Received command to seal L2 block #{} (polling took {elapsed:?})",
                 completable.command.l2_block.number
             );
+            // Free the queue slot, same as the channel does for non-adaptive queues.
+            if let (Some(adaptive_capacity), Some(permit)) =
+                (&self.adaptive_capacity, completable.permit.take())
+            {
+                adaptive_capacity.release(permit);
+            }
         }
 
         if !self.is_sync {
             L2_BLOCK_METRICS.seal_queue_latency[&L2BlockQueueStage::NextCommand].observe(elapsed);
             if let Some(sender) = self.commands_sender.upgrade() {
-                L2_BLOCK_METRICS.seal_queue_capacity.set(sender.capacity());
+                report_queue_metrics(&sender, self.adaptive_capacity.as_deref());
             }
         }
         command
     }
 }
 
+/// Computes an exponential moving average of the seal latency.
+fn smooth_latency(prev: Option<Duration>, latency: Duration) -> Duration {
+    let Some(prev) = prev else {
+        return latency;
+    };
+    let alpha = AdaptiveSealQueueConfig::LATENCY_SMOOTHING;
+    prev.mul_f64(1.0 - alpha) + latency.mul_f64(alpha)
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
@@ -521,4 +796,130 @@ mod tests {
 
         persistence.wait_for_all_commands().await;
     }
+
+    fn test_adaptive_config() -> AdaptiveSealQueueConfig {
+        AdaptiveSealQueueConfig::new(2, 8)
+            .unwrap()
+            .with_target_seal_latency(Duration::from_millis(100))
+    }
+
+    #[test]
+    fn adaptive_seal_queue_config_bounds() {
+        assert_eq!(AdaptiveSealQueueConfig::new(0, 10), None);
+        assert_eq!(AdaptiveSealQueueConfig::new(10, 10), None);
+        assert_eq!(AdaptiveSealQueueConfig::new(10, 5), None);
+        assert!(AdaptiveSealQueueConfig::new(1, 5).is_some());
+
+        let mut config = StateKeeperConfig {
+            l2_block_seal_queue_capacity: 5,
+            ..StateKeeperConfig::for_tests()
+        };
+        assert_eq!(
+            AdaptiveSealQueueConfig::from_state_keeper_config(&config),
+            None
+        );
+        config.l2_block_seal_queue_max_capacity = Some(20);
+        config.l2_block_seal_queue_target_latency_ms = Some(200);
+        assert_eq!(
+            AdaptiveSealQueueConfig::from_state_keeper_config(&config),
+            AdaptiveSealQueueConfig::new(5, 20)
+                .map(|config| config.with_target_seal_latency(Duration::from_millis(200)))
+        );
+    }
+
+    #[test]
+    fn adaptive_capacity_adjustments() {
+        let capacity = AdaptiveCapacity::new(test_adaptive_config());
+        let fast = Duration::from_millis(10);
+        let slow = Duration::from_millis(500);
+        assert_eq!(capacity.capacity(), 2);
+
+        // Full queue with healthy Postgres leads to growth up to the upper bound.
+        capacity.adjust(fast, 2);
+        assert_eq!(capacity.capacity(), 4);
+        assert_eq!(capacity.available_capacity(), 4);
+        capacity.adjust(fast, 4);
+        capacity.adjust(fast, 8);
+        assert_eq!(capacity.capacity(), 8);
+        assert_eq!(capacity.available_capacity(), 8);
+
+        // Partially filled queue keeps the capacity.
+        capacity.adjust(fast, 4);
+        assert_eq!(capacity.capacity(), 8);
+
+        // Slow Postgres leads to shrinking down to the lower bound.
+        capacity.adjust(slow, 8);
+        assert_eq!(capacity.capacity(), 4);
+        assert_eq!(capacity.available_capacity(), 4);
+        capacity.adjust(slow, 0);
+        capacity.adjust(slow, 0);
+        assert_eq!(capacity.capacity(), 2);
+        assert_eq!(capacity.available_capacity(), 2);
+
+        // Mostly empty queue slowly decays to the lower bound.
+        capacity.adjust(fast, 2);
+        assert_eq!(capacity.capacity(), 4);
+        capacity.adjust(fast, 0);
+        assert_eq!(capacity.capacity(), 3);
+        assert_eq!(capacity.available_capacity(), 3);
+    }
+
+    #[tokio::test]
+    async fn adaptive_capacity_shrinking_with_held_permits() {
+        let capacity = AdaptiveCapacity::new(test_adaptive_config());
+        capacity.adjust(Duration::ZERO, 2);
+        assert_eq!(capacity.capacity(), 4);
+
+        let permits: Vec<_> = futures::future::join_all((0..4).map(|_| capacity.acquire())).await;
+        assert_eq!(capacity.available_capacity(), 0);
+        capacity.adjust(Duration::from_secs(1), 4);
+        assert_eq!(capacity.capacity(), 2);
+
+        // Released permits should be forgotten until the capacity is reduced.
+        for permit in permits {
+            capacity.release(permit);
+        }
+        assert_eq!(capacity.available_capacity(), 2);
+
+        // Growing should cancel pending shrinking.
+        capacity.adjust(Duration::ZERO, 2);
+        assert_eq!(capacity.capacity(), 4);
+        let permits: Vec<_> = futures::future::join_all((0..4).map(|_| capacity.acquire())).await;
+        capacity.adjust(Duration::from_secs(1), 4);
+        assert_eq!(capacity.capacity(), 2);
+        capacity.adjust(Duration::ZERO, 4);
+        assert_eq!(capacity.capacity(), 4);
+        for permit in permits {
+            capacity.release(permit);
+        }
+        assert_eq!(capacity.available_capacity(), 4);
+    }
+
+    #[tokio::test]
+    async fn adaptive_l2_block_sealer_handle_blocking() {
+        let pool = ConnectionPool::constrained_test_pool(1).await;
+        let (mut persistence, mut sealer) =
+            StateKeeperPersistence::new_adaptive(pool, Address::default(), test_adaptive_config());
+
+        // The initial capacity is the lower bound, even though the channel has more slots.
+        let mut updates_manager = create_updates_manager();
+        for i in 1..=2 {
+            let seal_command = updates_manager.seal_l2_block_command(Address::default(), false);
+            updates_manager.push_l2_block(L2BlockParams {
+                timestamp: i,
+                virtual_blocks: 1,
+            });
+            persistence.submit_l2_block(seal_command).await;
+        }
+
+        let seal_command = updates_manager.seal_l2_block_command(Address::default(), false);
+        let submit_future = persistence.submit_l2_block(seal_command);
+        futures::pin_mut!(submit_future);
+        assert!((&mut submit_future).now_or_never().is_none());
+
+        // Taking a command from the queue should unblock submission.
+        let command = sealer.next_command().await.unwrap();
+        assert_eq!(command.command.l2_block.number, L2BlockNumber(1));
+        submit_future.await;
+    }
 }
@@ -4,7 +4,11 @@ use std::{slice, sync::Arc, time::Duration};
 
 use multivm::vm_latest::constants::BATCH_COMPUTATIONAL_GAS_LIMIT;
 use zksync_config::{
-    configs::{chain::StateKeeperConfig, eth_sender::PubdataSendingMode, wallets::Wallets},
+    configs::{
+        chain::StateKeeperConfig,
+        eth_sender::{BlobFeeStrategy, PubdataSendingMode},
+        wallets::Wallets,
+    },
     GasAdjusterConfig,
 };
 use zksync_contracts::BaseSystemContracts;
@@ -64,6 +68,10 @@ impl Tester {
             num_samples_for_blob_base_fee_estimate: 10,
             internal_pubdata_pricing_multiplier: 1.0,
             max_blob_base_fee: None,
+            blob_fee_strategy: BlobFeeStrategy::Median,
+            blob_fee_percentile: None,
+            blob_fee_ewma_alpha: None,
+            blob_fee_cap: None,
         };
 
         GasAdjuster::new(
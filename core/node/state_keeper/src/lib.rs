@@ -14,8 +14,8 @@ pub use self::{
         main_executor::MainBatchExecutor, BatchExecutor, BatchExecutorHandle, TxExecutionResult,
     },
     io::{
-        mempool::MempoolIO, L2BlockParams, L2BlockSealerTask, OutputHandler, StateKeeperIO,
-        StateKeeperOutputHandler, StateKeeperPersistence,
+        mempool::MempoolIO, AdaptiveSealQueueConfig, L2BlockParams, L2BlockSealerTask,
+        OutputHandler, StateKeeperIO, StateKeeperOutputHandler, StateKeeperPersistence,
     },
     keeper::ZkSyncStateKeeper,
     mempool_actor::MempoolFetcher,
@@ -7,8 +7,8 @@ use std::{
 
 use multivm::interface::VmExecutionResultAndLogs;
 use vise::{
-    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LatencyObserver,
-    Metrics,
+    Buckets, Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Histogram, LabeledFamily,
+    LatencyObserver, Metrics, Unit,
 };
 use zksync_mempool::MempoolStore;
 use zksync_shared_metrics::InteractionType;
@@ -172,6 +172,19 @@ impl TxAggregationMetrics {
 #[vise::register]
 pub(super) static AGGREGATION_METRICS: vise::Global<TxAggregationMetrics> = vise::Global::new();
 
+/// Metrics for verification of the main node's sealing decisions on the external node.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "server_state_keeper_seal_verification")]
+pub(super) struct SealVerificationMetrics {
+    /// Number of L1 batches / L2 blocks continued by the main node although a local seal criterion requested sealing.
+    #[metrics(labels = ["criterion"])]
+    pub deviations: LabeledFamily<&'static str, Counter>,
+}
+
+#[vise::register]
+pub(super) static SEAL_VERIFICATION_METRICS: vise::Global<SealVerificationMetrics> =
+    vise::Global::new();
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
 #[metrics(label = "stage", rename_all = "snake_case")]
 pub(super) enum L1BatchSealStage {
@@ -249,6 +262,15 @@ pub(super) enum L2BlockQueueStage {
     Submit,
     WaitForAllCommands,
     NextCommand,
+    /// Time from submitting a command to the queue until the L2 block is persisted.
+    Roundtrip,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "direction", rename_all = "snake_case")]
+pub(super) enum QueueCapacityAdjustment {
+    Grow,
+    Shrink,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue)]
@@ -283,6 +305,15 @@ pub(super) struct L2BlockMetrics {
     pub seal_delta: Histogram<Duration>,
     /// Current capacity of the seal queue for L2 blocks.
     pub seal_queue_capacity: Gauge<usize>,
+    /// Number of L2 blocks waiting in the seal queue.
+    pub seal_queue_depth: Gauge<usize>,
+    /// Total capacity of the seal queue for L2 blocks; changes over time if the queue is adaptive.
+    pub seal_queue_max_capacity: Gauge<usize>,
+    /// Number of adjustments of the adaptive seal queue capacity.
+    pub seal_queue_capacity_adjustments: Family<QueueCapacityAdjustment, Counter>,
+    /// Smoothed Postgres write latency of a single L2 block used to adjust the adaptive seal queue capacity.
+    #[metrics(unit = Unit::Seconds)]
+    pub seal_queue_smoothed_seal_latency: Gauge<Duration>,
     /// Latency of a certain operation concerning the seal queue for L2 blocks.
     #[metrics(buckets = Buckets::LATENCIES)]
     pub seal_queue_latency: Family<L2BlockQueueStage, Histogram<Duration>>,
@@ -441,3 +472,44 @@ impl BatchTipMetrics {
 
 #[vise::register]
 pub(crate) static BATCH_TIP_METRICS: vise::Global<BatchTipMetrics> = vise::Global::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(crate) enum VmDivergenceKind {
+    ExecutionResult,
+    Gas,
+    Events,
+    StorageWrites,
+    Panic,
+}
+
+impl VmDivergenceKind {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::ExecutionResult => "execution_result",
+            Self::Gas => "gas",
+            Self::Events => "events",
+            Self::StorageWrites => "storage_writes",
+            Self::Panic => "panic",
+        }
+    }
+}
+
+/// Metrics for the shadow VM executing L1 batches alongside the canonical VM.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "state_keeper_shadow_vm")]
+pub(crate) struct ShadowVmMetrics {
+    /// Number of L1 batches executed by the shadow VM.
+    pub checked_batches: Counter,
+    /// Number of L1 batches for which the shadow VM diverged from the canonical VM.
+    pub diverged_batches: Counter,
+    /// Number of detected divergences grouped by kind.
+    pub divergences: Family<VmDivergenceKind, Counter>,
+    /// Number of failures persisting divergence reports to Postgres.
+    pub persistence_errors: Counter,
+    /// Number of panics in the shadow VM. After a panic, shadow execution is disabled.
+    pub panics: Counter,
+}
+
+#[vise::register]
+pub(crate) static SHADOW_VM_METRICS: vise::Global<ShadowVmMetrics> = vise::Global::new();
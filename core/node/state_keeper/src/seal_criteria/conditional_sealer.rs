@@ -4,12 +4,13 @@
 //! The conditional sealer abstraction allows to implement different sealing strategies, e.g. the actual
 //! sealing strategy for the main node or noop sealer for the external node.
 
-use std::fmt;
+use std::{collections::HashSet, fmt, sync::Mutex};
 
 use zksync_config::configs::chain::StateKeeperConfig;
 use zksync_types::ProtocolVersionId;
 
 use super::{criteria, SealCriterion, SealData, SealResolution, AGGREGATION_METRICS};
+use crate::metrics::SEAL_VERIFICATION_METRICS;
 
 /// Checks if an L1 batch should be sealed after executing a transaction.
 pub trait ConditionalSealer: 'static + fmt::Debug + Send + Sync {
@@ -142,6 +143,125 @@ impl SequencerSealer {
     }
 }
 
+/// Implementation of [`ConditionalSealer`] used by the external node to validate seal criteria settings.
+///
+/// Like [`NoopSealer`], it never seals the batch since the sealing decisions are taken by the main node.
+/// Instead, it checks these decisions against the locally configured seal criteria and reports deviations,
+/// i.e., transactions included by the main node into a batch after a local criterion requested to seal it.
+#[derive(Debug)]
+pub struct VerifyingSealer {
+    config: StateKeeperConfig,
+    sealers: Vec<Box<dyn SealCriterion>>,
+    state: Mutex<VerificationState>,
+}
+
+#[derive(Debug, Default)]
+struct VerificationState {
+    l1_batch_number: u32,
+    /// Criteria that resolved to [`SealResolution::IncludeAndSeal`] for the previous transaction. They are deviations
+    /// only if the main node includes another transaction into the batch.
+    pending: Vec<&'static str>,
+    /// Criteria already reported for the batch, so that each deviation is reported once per batch.
+    reported: HashSet<&'static str>,
+}
+
+impl VerifyingSealer {
+    pub fn new(config: StateKeeperConfig) -> Self {
+        let sealers = SequencerSealer::default_sealers(&config);
+        Self::with_sealers(config, sealers)
+    }
+
+    fn with_sealers(config: StateKeeperConfig, sealers: Vec<Box<dyn SealCriterion>>) -> Self {
+        Self {
+            config,
+            sealers,
+            state: Mutex::default(),
+        }
+    }
+
+    fn report_deviation(
+        state: &mut VerificationState,
+        l1_batch_number: u32,
+        tx_count: usize,
+        criterion: &'static str,
+        resolution: &SealResolution,
+    ) {
+        if state.reported.insert(criterion) {
+            tracing::warn!(
+                "Main node included transaction #{tx_count} into L1 batch #{l1_batch_number} although local seal \
+                 criterion `{criterion}` resolved to {resolution:?}; check the configured seal criteria"
+            );
+            SEAL_VERIFICATION_METRICS.deviations[&criterion].inc();
+        }
+    }
+}
+
+impl ConditionalSealer for VerifyingSealer {
+    fn find_unexecutable_reason(
+        &self,
+        _data: &SealData,
+        _protocol_version: ProtocolVersionId,
+    ) -> Option<&'static str> {
+        // All transactions from the main node must be executed.
+        None
+    }
+
+    fn should_seal_l1_batch(
+        &self,
+        l1_batch_number: u32,
+        block_open_timestamp_ms: u128,
+        tx_count: usize,
+        block_data: &SealData,
+        tx_data: &SealData,
+        protocol_version: ProtocolVersionId,
+    ) -> SealResolution {
+        let mut state = self.state.lock().expect("verification state is poisoned");
+        if state.l1_batch_number != l1_batch_number {
+            *state = VerificationState {
+                l1_batch_number,
+                ..VerificationState::default()
+            };
+        }
+
+        // The main node has included a transaction after a criterion requested to seal the batch.
+        for criterion in std::mem::take(&mut state.pending) {
+            Self::report_deviation(
+                &mut state,
+                l1_batch_number,
+                tx_count,
+                criterion,
+                &SealResolution::IncludeAndSeal,
+            );
+        }
+
+        for sealer in &self.sealers {
+            let resolution = sealer.should_seal(
+                &self.config,
+                block_open_timestamp_ms,
+                tx_count,
+                block_data,
+                tx_data,
+                protocol_version,
+            );
+            let criterion = sealer.prom_criterion_name();
+            match &resolution {
+                SealResolution::NoSeal => {}
+                SealResolution::IncludeAndSeal => state.pending.push(criterion),
+                SealResolution::ExcludeAndSeal | SealResolution::Unexecutable(_) => {
+                    Self::report_deviation(
+                        &mut state,
+                        l1_batch_number,
+                        tx_count,
+                        criterion,
+                        &resolution,
+                    );
+                }
+            }
+        }
+        SealResolution::NoSeal
+    }
+}
+
 /// Implementation of [`ConditionalSealer`] that never seals the batch.
 /// Can be used in contexts where, for example, state keeper configuration is not available,
 /// or the decision to seal batch is taken by some other component.
@@ -169,3 +289,44 @@ impl ConditionalSealer for NoopSealer {
         SealResolution::NoSeal
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slots_sealer() -> VerifyingSealer {
+        let config = StateKeeperConfig {
+            transaction_slots: 2,
+            ..StateKeeperConfig::for_tests()
+        };
+        VerifyingSealer::with_sealers(config, vec![Box::new(criteria::SlotsCriterion)])
+    }
+
+    fn should_seal(sealer: &VerifyingSealer, l1_batch_number: u32, tx_count: usize) {
+        let resolution = sealer.should_seal_l1_batch(
+            l1_batch_number,
+            0,
+            tx_count,
+            &SealData::default(),
+            &SealData::default(),
+            ProtocolVersionId::latest(),
+        );
+        assert_eq!(resolution, SealResolution::NoSeal);
+    }
+
+    #[test]
+    fn verifying_sealer_reports_transactions_after_seal_request() {
+        let sealer = slots_sealer();
+        should_seal(&sealer, 1, 1);
+        should_seal(&sealer, 1, 2);
+        // The batch was sealed in accordance with the local criteria.
+        assert!(sealer.state.lock().unwrap().reported.is_empty());
+
+        should_seal(&sealer, 2, 1);
+        should_seal(&sealer, 2, 2);
+        should_seal(&sealer, 2, 3);
+        let state = sealer.state.lock().unwrap();
+        assert_eq!(state.l1_batch_number, 2);
+        assert_eq!(state.reported, HashSet::from(["slots"]));
+    }
+}
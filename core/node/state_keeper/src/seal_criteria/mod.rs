@@ -19,16 +19,18 @@ use zksync_types::{
     fee::TransactionExecutionMetrics,
     tx::tx_execution_info::{DeduplicatedWritesMetrics, ExecutionMetrics},
     utils::display_timestamp,
-    ProtocolVersionId, Transaction,
+    L2BlockNumber, ProtocolVersionId, Transaction,
 };
 use zksync_utils::time::millis_since;
 
 mod conditional_sealer;
 pub(super) mod criteria;
 
-pub use self::conditional_sealer::{ConditionalSealer, NoopSealer, SequencerSealer};
+pub use self::conditional_sealer::{
+    ConditionalSealer, NoopSealer, SequencerSealer, VerifyingSealer,
+};
 use super::{
-    metrics::AGGREGATION_METRICS,
+    metrics::{AGGREGATION_METRICS, SEAL_VERIFICATION_METRICS},
     updates::UpdatesManager,
     utils::{gas_count_from_tx_and_metrics, gas_count_from_writes},
 };
@@ -198,6 +200,43 @@ impl L2BlockMaxPayloadSizeSealer {
     }
 }
 
+/// Verifies that the main node doesn't extend L2 blocks beyond the locally configured payload size limit.
+/// Used by the external node to validate seal criteria settings; see [`VerifyingSealer`] for the L1 batch counterpart.
+#[derive(Debug)]
+pub struct L2BlockSealVerifier {
+    payload_size_sealer: L2BlockMaxPayloadSizeSealer,
+    last_reported_l2_block: Option<L2BlockNumber>,
+}
+
+impl L2BlockSealVerifier {
+    pub fn new(config: &StateKeeperConfig) -> Self {
+        Self {
+            payload_size_sealer: L2BlockMaxPayloadSizeSealer::new(config),
+            last_reported_l2_block: None,
+        }
+    }
+
+    /// Should be called once it's known that the main node adds another transaction to the current L2 block.
+    pub fn verify_l2_block_continued(&mut self, manager: &UpdatesManager) {
+        const CRITERION: &str = "l2_block_payload_size";
+
+        let l2_block_number = manager.l2_block.number;
+        if self.last_reported_l2_block == Some(l2_block_number)
+            || !self.payload_size_sealer.should_seal_l2_block(manager)
+        {
+            return;
+        }
+        self.last_reported_l2_block = Some(l2_block_number);
+        tracing::warn!(
+            "Main node continued L2 block #{l2_block_number} with payload size {} although it exceeds \
+             the local limit of {} bytes; check the configured seal criteria",
+            manager.l2_block.payload_encoding_size,
+            self.payload_size_sealer.max_payload_size
+        );
+        SEAL_VERIFICATION_METRICS.deviations[&CRITERION].inc();
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use zksync_utils::time::seconds_since_epoch;
@@ -6,8 +6,8 @@ use once_cell::sync::OnceCell;
 use tokio::sync::watch;
 use zksync_dal::{ConnectionPool, Core};
 use zksync_state::{
-    AsyncCatchupTask, PgOrRocksdbStorage, ReadStorageFactory, RocksdbStorageOptions,
-    StateKeeperColumnFamily,
+    AsyncCatchupTask, PgOrRocksdbStorage, ReadStorageFactory, RocksdbMaintenanceConfig,
+    RocksdbMaintenanceTask, RocksdbStorageOptions, StateKeeperColumnFamily,
 };
 use zksync_storage::RocksDB;
 use zksync_types::L1BatchNumber;
@@ -67,6 +67,12 @@ impl AsyncRocksdbCache {
         );
         (Self { pool, rocksdb_cell }, task)
     }
+
+    /// Creates a task performing background maintenance (e.g., compactions) of the RocksDB cache
+    /// once it is initialized.
+    pub fn maintenance_task(&self, config: RocksdbMaintenanceConfig) -> RocksdbMaintenanceTask {
+        RocksdbMaintenanceTask::new(config, self.rocksdb_cell.clone())
+    }
 }
 
 #[async_trait]
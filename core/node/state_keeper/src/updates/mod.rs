@@ -123,7 +123,9 @@ impl UpdatesManager {
         );
     }
 
-    pub(crate) fn finish_batch(&mut self, finished_batch: FinishedL1Batch) {
+    /// Finishes the L1 batch by applying the batch tip execution result. Called once the batch executor
+    /// has finished the batch.
+    pub fn finish_batch(&mut self, finished_batch: FinishedL1Batch) {
         assert!(
             self.l1_batch.finished.is_none(),
             "Cannot finish already finished batch"
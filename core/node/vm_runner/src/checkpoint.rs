@@ -0,0 +1,193 @@
+use std::sync::{Arc, Mutex};
+
+use async_trait::async_trait;
+use serde::Serialize;
+use zksync_dal::{vm_runner_dal::VmRunnerBatchPhase, Connection, Core, CoreDal};
+use zksync_health_check::{Health, HealthStatus, HealthUpdater, ReactiveHealthCheck};
+use zksync_types::L1BatchNumber;
+
+use crate::VmRunnerIo;
+
+/// Progress of a VM runner component reported via its health check.
+#[derive(Debug, Default, Serialize)]
+struct CheckpointHealthDetails {
+    latest_loaded_batch: Option<L1BatchNumber>,
+    latest_executed_batch: Option<L1BatchNumber>,
+    latest_committed_batch: Option<L1BatchNumber>,
+}
+
+#[derive(Debug)]
+struct CheckpointHealth {
+    updater: HealthUpdater,
+    details: Mutex<CheckpointHealthDetails>,
+}
+
+impl CheckpointHealth {
+    fn update(&self, l1_batch_number: L1BatchNumber, phase: VmRunnerBatchPhase) {
+        let mut details = self.details.lock().expect("health details are poisoned");
+        let latest_batch = match phase {
+            VmRunnerBatchPhase::Loaded => &mut details.latest_loaded_batch,
+            VmRunnerBatchPhase::Executed => &mut details.latest_executed_batch,
+            VmRunnerBatchPhase::Committed => &mut details.latest_committed_batch,
+        };
+        *latest_batch = Some(latest_batch.map_or(l1_batch_number, |n| n.max(l1_batch_number)));
+        let health = Health::from(HealthStatus::Ready).with_details(&*details);
+        drop(details);
+        self.updater.update(health);
+    }
+}
+
+/// [`VmRunnerIo`] wrapper persisting fine-grained progress of every L1 batch (loaded / executed / committed)
+/// in the `vm_runner_checkpoints` Postgres table.
+///
+/// On restart, processing resumes after the longest unbroken sequence of committed batches recorded in the table,
+/// even if the wrapped IO doesn't persist its progress. This includes batches with outputs committed out of order
+/// that weren't marked as completed before the restart. Batches that were only loaded or executed are processed again,
+/// since their outputs may not have been fully handled.
+///
+/// The progress is also exposed via a [health check](Self::health_check).
+#[derive(Debug, Clone)]
+pub struct CheckpointedIo<Io> {
+    inner: Io,
+    component: Arc<str>,
+    health: Arc<CheckpointHealth>,
+}
+
+impl<Io: VmRunnerIo> CheckpointedIo<Io> {
+    /// Wraps the provided IO. `component` is the key for checkpoints in Postgres; it must be unique
+    /// among VM runner components sharing the database.
+    pub fn new(inner: Io, component: impl Into<Arc<str>>) -> Self {
+        let (_, updater) = ReactiveHealthCheck::new(inner.name());
+        Self {
+            inner,
+            component: component.into(),
+            health: Arc::new(CheckpointHealth {
+                updater,
+                details: Mutex::default(),
+            }),
+        }
+    }
+
+    /// Returns a reference to the wrapped IO.
+    pub fn inner(&self) -> &Io {
+        &self.inner
+    }
+
+    /// Returns the health check reporting the progress of this VM runner component.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health.updater.subscribe()
+    }
+
+    async fn set_checkpoint(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+        phase: VmRunnerBatchPhase,
+    ) -> anyhow::Result<()> {
+        conn.vm_runner_dal()
+            .set_checkpoint(&self.component, l1_batch_number, phase)
+            .await?;
+        self.health.update(l1_batch_number, phase);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<Io: VmRunnerIo> VmRunnerIo for CheckpointedIo<Io> {
+    fn name(&self) -> &'static str {
+        self.inner.name()
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let mut latest_batch = self.inner.latest_processed_batch(conn).await?;
+        let checkpoints = conn
+            .vm_runner_dal()
+            .get_checkpoints(&self.component)
+            .await?;
+        // Restore progress reported via the health check after a restart.
+        for &(l1_batch_number, phase) in &checkpoints {
+            self.health.update(l1_batch_number, phase);
+        }
+
+        for (i, &(l1_batch_number, phase)) in checkpoints.iter().enumerate() {
+            if l1_batch_number <= latest_batch {
+                continue;
+            }
+            // Checkpoints before the latest completed batch are pruned, so the earliest checkpoint corresponds
+            // to the completed batch. Later batches are considered processed only if there are no gaps in committed outputs.
+            let is_next_batch = i == 0 || l1_batch_number == latest_batch + 1;
+            if phase != VmRunnerBatchPhase::Committed || !is_next_batch {
+                tracing::debug!(
+                    "Batch #{l1_batch_number} (phase: {phase}) is not the next committed batch; batches starting from #{} will be reprocessed",
+                    latest_batch + 1
+                );
+                break;
+            }
+            latest_batch = l1_batch_number;
+        }
+        Ok(latest_batch)
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        self.inner.last_ready_to_be_loaded_batch(conn).await
+    }
+
+    async fn mark_l1_batch_as_loaded(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .mark_l1_batch_as_loaded(conn, l1_batch_number)
+            .await?;
+        self.set_checkpoint(conn, l1_batch_number, VmRunnerBatchPhase::Loaded)
+            .await
+    }
+
+    async fn mark_l1_batch_as_executed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .mark_l1_batch_as_executed(conn, l1_batch_number)
+            .await?;
+        self.set_checkpoint(conn, l1_batch_number, VmRunnerBatchPhase::Executed)
+            .await
+    }
+
+    async fn mark_l1_batch_output_committed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .mark_l1_batch_output_committed(conn, l1_batch_number)
+            .await?;
+        self.set_checkpoint(conn, l1_batch_number, VmRunnerBatchPhase::Committed)
+            .await
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.inner
+            .mark_l1_batch_as_completed(conn, l1_batch_number)
+            .await?;
+        self.set_checkpoint(conn, l1_batch_number, VmRunnerBatchPhase::Committed)
+            .await?;
+        // Checkpoints for earlier batches are no longer needed; all of them are committed.
+        conn.vm_runner_dal()
+            .prune_checkpoints(&self.component, l1_batch_number)
+            .await?;
+        Ok(())
+    }
+}
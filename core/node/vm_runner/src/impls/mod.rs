@@ -0,0 +1,8 @@
+//! Implementations of VM runner components.
+
+pub use self::protective_reads::{
+    ProtectiveReadsIo, ProtectiveReadsOutputHandlerFactory, ProtectiveReadsVerifier,
+    ProtectiveReadsVerifierConfig, ProtectiveReadsVerifierTasks,
+};
+
+mod protective_reads;
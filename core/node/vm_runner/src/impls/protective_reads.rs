@@ -0,0 +1,313 @@
+use std::{collections::HashSet, sync::Arc};
+
+use anyhow::Context;
+use async_trait::async_trait;
+use tokio::sync::watch;
+use zksync_config::configs;
+use zksync_dal::{
+    vm_runner_dal::ProtectiveReadMismatchKind, Connection, ConnectionPool, Core, CoreDal,
+};
+use zksync_health_check::ReactiveHealthCheck;
+use zksync_state_keeper::{MainBatchExecutor, StateKeeperOutputHandler, UpdatesManager};
+use zksync_types::{AccountTreeId, L1BatchNumber, L2ChainId, StorageKey};
+use zksync_utils::u256_to_h256;
+
+use crate::{
+    metrics::{MismatchKind, VerificationResult, PROTECTIVE_READS_METRICS},
+    CheckpointedIo, ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask,
+    ExecutionWindow, OutputHandlerFactory, StorageSyncTask, VmRunner, VmRunnerIo, VmRunnerStorage,
+};
+
+/// Configuration of [`ProtectiveReadsVerifier`].
+#[derive(Debug, Clone)]
+pub struct ProtectiveReadsVerifierConfig {
+    /// Path to the RocksDB cache used by the verifier.
+    pub rocksdb_path: String,
+    /// Maximum number of L1 batches that can be loaded / processed ahead of the latest processed batch.
+    pub window_size: u32,
+    /// First L1 batch to verify if the verifier has no persisted progress. Must not be the genesis batch.
+    pub first_processed_batch: L1BatchNumber,
+    /// Window of concurrently executed batches (unbounded by default). Batches outside the loading window
+    /// are never executed, so the number of concurrently executed batches never exceeds `window_size`.
+    pub execution_window: ExecutionWindow,
+    /// L2 chain ID.
+    pub chain_id: L2ChainId,
+}
+
+impl ProtectiveReadsVerifierConfig {
+    /// Creates a verifier config from the node config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the execution window specified in the config is invalid.
+    pub fn from_node_config(
+        config: &configs::ProtectiveReadsVerifierConfig,
+        chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            rocksdb_path: config.db_path.clone(),
+            window_size: config.window_size,
+            first_processed_batch: config.first_processed_batch,
+            execution_window: ExecutionWindow::from_limits(
+                config.max_concurrent_batches,
+                config.execution_memory_budget_bytes(),
+            )?,
+            chain_id,
+        })
+    }
+}
+
+/// VM runner component that recomputes protective reads for L1 batches sealed by the state keeper
+/// and compares them with the protective reads persisted by the state keeper. Mismatches are reported
+/// to metrics and recorded in the `protective_reads_mismatches` Postgres table.
+///
+/// Batches for which the state keeper hasn't persisted protective reads (e.g., on nodes that never run
+/// a full Merkle tree) are skipped.
+#[derive(Debug)]
+pub struct ProtectiveReadsVerifier {
+    vm_runner: VmRunner,
+    health_check: ReactiveHealthCheck,
+}
+
+impl ProtectiveReadsVerifier {
+    /// Creates a new verifier together with auxiliary tasks that must be run alongside it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid. Propagates DB and RocksDB errors.
+    pub async fn new(
+        pool: ConnectionPool<Core>,
+        config: ProtectiveReadsVerifierConfig,
+    ) -> anyhow::Result<(Self, ProtectiveReadsVerifierTasks)> {
+        anyhow::ensure!(
+            config.first_processed_batch > L1BatchNumber(0),
+            "Protective reads cannot be verified for the genesis L1 batch"
+        );
+        anyhow::ensure!(config.window_size > 0, "Window size must be positive");
+
+        let io = ProtectiveReadsIo {
+            first_processed_batch: config.first_processed_batch,
+            window_size: config.window_size,
+        };
+        let io = CheckpointedIo::new(io, ProtectiveReadsIo::NAME);
+        let health_check = io.health_check();
+        let (loader, loader_task) = VmRunnerStorage::new(
+            pool.clone(),
+            config.rocksdb_path,
+            io.clone(),
+            config.chain_id,
+        )
+        .await?;
+        let output_handler_factory = ProtectiveReadsOutputHandlerFactory::new(pool.clone());
+        let (output_handler_factory, output_handler_factory_task) =
+            ConcurrentOutputHandlerFactory::new(pool.clone(), io.clone(), output_handler_factory);
+        let batch_processor = MainBatchExecutor::new(false, false);
+        let vm_runner = VmRunner::new(
+            pool,
+            Box::new(io),
+            Arc::new(loader),
+            Box::new(output_handler_factory),
+            Box::new(batch_processor),
+        )
+        .with_execution_window(config.execution_window);
+
+        let verifier = Self {
+            vm_runner,
+            health_check,
+        };
+        let tasks = ProtectiveReadsVerifierTasks {
+            loader_task,
+            output_handler_factory_task,
+        };
+        Ok((verifier, tasks))
+    }
+
+    /// Returns the health check reporting the verifier progress.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.health_check.clone()
+    }
+
+    /// Continuously verifies protective reads for new L1 batches until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors and errors during batch execution.
+    pub async fn run(self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
+        self.vm_runner.run(stop_receiver).await
+    }
+}
+
+/// Auxiliary tasks for [`ProtectiveReadsVerifier`] that must be run alongside it.
+#[derive(Debug)]
+pub struct ProtectiveReadsVerifierTasks {
+    /// Task keeping the RocksDB cache of the verifier in sync with Postgres.
+    pub loader_task: StorageSyncTask<CheckpointedIo<ProtectiveReadsIo>>,
+    /// Task committing verification results in order.
+    pub output_handler_factory_task:
+        ConcurrentOutputHandlerFactoryTask<CheckpointedIo<ProtectiveReadsIo>>,
+}
+
+/// [`VmRunnerIo`] for [`ProtectiveReadsVerifier`]. The progress is persisted as [`CheckpointedIo`]
+/// checkpoints keyed by the IO name; thus, this IO must always be wrapped in [`CheckpointedIo`].
+#[derive(Debug, Clone)]
+pub struct ProtectiveReadsIo {
+    first_processed_batch: L1BatchNumber,
+    window_size: u32,
+}
+
+impl ProtectiveReadsIo {
+    const NAME: &'static str = "protective_reads_verifier";
+}
+
+#[async_trait]
+impl VmRunnerIo for ProtectiveReadsIo {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let start_batch = self.first_processed_batch - 1;
+        let committed_batch = conn
+            .vm_runner_dal()
+            .get_latest_committed_batch(Self::NAME)
+            .await?;
+        Ok(committed_batch.map_or(start_batch, |batch| batch.max(start_batch)))
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let latest_processed_batch = self.latest_processed_batch(conn).await?;
+        let sealed_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        Ok(sealed_batch.min(latest_processed_batch + self.window_size))
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        // The progress is persisted by the wrapping `CheckpointedIo`.
+        Ok(())
+    }
+}
+
+/// [`OutputHandlerFactory`] verifying recomputed protective reads against the ones persisted by the state keeper.
+/// Used by [`ProtectiveReadsVerifier`]; can also be used with [`VmReplayRunner`](crate::VmReplayRunner)
+/// to verify a range of historical L1 batches.
+#[derive(Debug)]
+pub struct ProtectiveReadsOutputHandlerFactory {
+    pool: ConnectionPool<Core>,
+}
+
+impl ProtectiveReadsOutputHandlerFactory {
+    /// Creates a factory using the provided connection pool to load persisted protective reads and record mismatches.
+    pub fn new(pool: ConnectionPool<Core>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OutputHandlerFactory for ProtectiveReadsOutputHandlerFactory {
+    async fn create_handler(
+        &mut self,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        Ok(Box::new(ProtectiveReadsOutputHandler {
+            pool: self.pool.clone(),
+        }))
+    }
+}
+
+#[derive(Debug)]
+struct ProtectiveReadsOutputHandler {
+    pool: ConnectionPool<Core>,
+}
+
+#[async_trait]
+impl StateKeeperOutputHandler for ProtectiveReadsOutputHandler {
+    async fn handle_l2_block(&mut self, _updates_manager: &UpdatesManager) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn handle_l1_batch(
+        &mut self,
+        updates_manager: Arc<UpdatesManager>,
+    ) -> anyhow::Result<()> {
+        let l1_batch_number = updates_manager.l1_batch.number;
+        let finished_batch = updates_manager
+            .l1_batch
+            .finished
+            .as_ref()
+            .context("L1 batch is not actually finished")?;
+        let computed_reads: HashSet<_> = finished_batch
+            .final_execution_state
+            .deduplicated_storage_log_queries
+            .iter()
+            .filter(|log_query| !log_query.rw_flag)
+            .map(|log_query| {
+                StorageKey::new(
+                    AccountTreeId::new(log_query.address),
+                    u256_to_h256(log_query.key),
+                )
+            })
+            .collect();
+
+        let mut conn = self.pool.connection_tagged(ProtectiveReadsIo::NAME).await?;
+        let persisted_reads = conn
+            .storage_logs_dedup_dal()
+            .get_protective_reads_for_l1_batch(l1_batch_number)
+            .await?;
+        PROTECTIVE_READS_METRICS
+            .last_processed_batch
+            .set(l1_batch_number.0.into());
+        if persisted_reads.is_empty() {
+            tracing::debug!(
+                "Protective reads for L1 batch #{l1_batch_number} are not persisted; skipping verification"
+            );
+            PROTECTIVE_READS_METRICS.verified_batches[&VerificationResult::Skipped].inc();
+            return Ok(());
+        }
+
+        let missing_reads = computed_reads
+            .difference(&persisted_reads)
+            .map(|key| (*key, ProtectiveReadMismatchKind::Missing));
+        let unexpected_reads = persisted_reads
+            .difference(&computed_reads)
+            .map(|key| (*key, ProtectiveReadMismatchKind::Unexpected));
+        let mismatches: Vec<_> = missing_reads.chain(unexpected_reads).collect();
+        if mismatches.is_empty() {
+            PROTECTIVE_READS_METRICS.verified_batches[&VerificationResult::Match].inc();
+            return Ok(());
+        }
+
+        let (mut missing_count, mut unexpected_count) = (0_u64, 0_u64);
+        for (_, kind) in &mismatches {
+            match kind {
+                ProtectiveReadMismatchKind::Missing => missing_count += 1,
+                ProtectiveReadMismatchKind::Unexpected => unexpected_count += 1,
+            }
+        }
+        tracing::error!(
+            "Protective reads for L1 batch #{l1_batch_number} don't match the persisted ones: \
+             {missing_count} reads are missing, {unexpected_count} are unexpected"
+        );
+        PROTECTIVE_READS_METRICS.verified_batches[&VerificationResult::Mismatch].inc();
+        PROTECTIVE_READS_METRICS.mismatched_reads[&MismatchKind::Missing].inc_by(missing_count);
+        PROTECTIVE_READS_METRICS.mismatched_reads[&MismatchKind::Unexpected]
+            .inc_by(unexpected_count);
+
+        conn.vm_runner_dal()
+            .insert_protective_reads_mismatches(l1_batch_number, &mismatches)
+            .await?;
+        Ok(())
+    }
+}
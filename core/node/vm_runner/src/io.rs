@@ -31,6 +31,49 @@ pub trait VmRunnerIo: Debug + Send + Sync + 'static {
         conn: &mut Connection<'_, Core>,
     ) -> anyhow::Result<L1BatchNumber>;
 
+    /// Marks the specified batch as loaded; i.e., the batch is about to be executed. This is a fine-grained
+    /// progress notification; the default implementation does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn mark_l1_batch_as_loaded(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Marks the specified batch as executed; i.e., the batch output is about to be handled. This is a fine-grained
+    /// progress notification; the default implementation does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn mark_l1_batch_as_executed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Marks the output of the specified batch as committed. Unlike [`Self::mark_l1_batch_as_completed()`],
+    /// outputs may be committed out of order. This is a fine-grained progress notification; the default implementation
+    /// does nothing.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB errors.
+    async fn mark_l1_batch_output_committed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        _l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     /// Marks the specified batch as the latest completed batch. All earlier batches are considered
     /// to be completed too. No guarantees about later batches.
     ///
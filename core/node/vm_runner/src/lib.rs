@@ -3,17 +3,27 @@
 
 #![warn(missing_debug_implementations, missing_docs)]
 
+mod checkpoint;
+mod impls;
 mod io;
+mod metrics;
 mod output_handler;
 mod process;
+mod replay;
 mod storage;
 
 #[cfg(test)]
 mod tests;
 
+pub use checkpoint::CheckpointedIo;
+pub use impls::{
+    ProtectiveReadsIo, ProtectiveReadsOutputHandlerFactory, ProtectiveReadsVerifier,
+    ProtectiveReadsVerifierConfig, ProtectiveReadsVerifierTasks,
+};
 pub use io::VmRunnerIo;
 pub use output_handler::{
     ConcurrentOutputHandlerFactory, ConcurrentOutputHandlerFactoryTask, OutputHandlerFactory,
 };
-pub use process::VmRunner;
-pub use storage::{BatchExecuteData, VmRunnerStorage};
+pub use process::{ExecutionWindow, VmRunner};
+pub use replay::{VmReplayConfig, VmReplayRunner};
+pub use storage::{BatchExecuteData, StorageSyncTask, VmRunnerStorage};
@@ -0,0 +1,39 @@
+//! Metrics for VM runner components.
+
+use vise::{Counter, EncodeLabelSet, EncodeLabelValue, Family, Gauge, Metrics};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "result", rename_all = "snake_case")]
+pub(crate) enum VerificationResult {
+    /// Recomputed protective reads match the persisted ones.
+    Match,
+    /// Recomputed protective reads differ from the persisted ones.
+    Mismatch,
+    /// Protective reads were not persisted by the state keeper, so the batch wasn't verified.
+    Skipped,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EncodeLabelValue, EncodeLabelSet)]
+#[metrics(label = "kind", rename_all = "snake_case")]
+pub(crate) enum MismatchKind {
+    /// Read was recomputed, but wasn't persisted by the state keeper.
+    Missing,
+    /// Read was persisted by the state keeper, but wasn't recomputed.
+    Unexpected,
+}
+
+/// Metrics for the protective reads verifier.
+#[derive(Debug, Metrics)]
+#[metrics(prefix = "vm_runner_protective_reads")]
+pub(crate) struct ProtectiveReadsMetrics {
+    /// Number of L1 batches processed by the verifier, grouped by the verification result.
+    pub verified_batches: Family<VerificationResult, Counter>,
+    /// Number of mismatched protective reads, grouped by the mismatch kind.
+    pub mismatched_reads: Family<MismatchKind, Counter>,
+    /// Latest L1 batch processed by the verifier.
+    pub last_processed_batch: Gauge<u64>,
+}
+
+#[vise::register]
+pub(crate) static PROTECTIVE_READS_METRICS: vise::Global<ProtectiveReadsMetrics> =
+    vise::Global::new();
@@ -41,6 +41,16 @@ pub trait OutputHandlerFactory: Debug + Send {
     ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>>;
 }
 
+#[async_trait]
+impl OutputHandlerFactory for Box<dyn OutputHandlerFactory> {
+    async fn create_handler(
+        &mut self,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<Box<dyn StateKeeperOutputHandler>> {
+        (**self).create_handler(l1_batch_number).await
+    }
+}
+
 /// A delegator factory that requires an underlying factory `F` that does the actual work, however
 /// this struct is orchestrated such that any output handler it produces has a non-blocking
 /// `handle_l1_batch` implementation (where the heaviest work is expected to happen).
@@ -96,7 +106,7 @@ impl<Io: VmRunnerIo + Clone, F: OutputHandlerFactory> ConcurrentOutputHandlerFac
 }
 
 #[async_trait]
-impl<Io: VmRunnerIo, F: OutputHandlerFactory> OutputHandlerFactory
+impl<Io: VmRunnerIo + Clone, F: OutputHandlerFactory> OutputHandlerFactory
     for ConcurrentOutputHandlerFactory<Io, F>
 {
     async fn create_handler(
@@ -124,13 +134,22 @@ impl<Io: VmRunnerIo, F: OutputHandlerFactory> OutputHandlerFactory
         let handler = self.factory.create_handler(l1_batch_number).await?;
         let (sender, receiver) = oneshot::channel();
         self.state.insert(l1_batch_number, receiver);
-        Ok(Box::new(AsyncOutputHandler::Running { handler, sender }))
+        Ok(Box::new(AsyncOutputHandler::Running {
+            l1_batch_number,
+            handler,
+            pool: self.pool.clone(),
+            io: Arc::new(self.io.clone()),
+            sender,
+        }))
     }
 }
 
 enum AsyncOutputHandler {
     Running {
+        l1_batch_number: L1BatchNumber,
         handler: Box<dyn StateKeeperOutputHandler>,
+        pool: ConnectionPool<Core>,
+        io: Arc<dyn VmRunnerIo>,
         sender: oneshot::Sender<JoinHandle<anyhow::Result<()>>>,
     },
     Finished,
@@ -139,8 +158,13 @@ enum AsyncOutputHandler {
 impl Debug for AsyncOutputHandler {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
-            AsyncOutputHandler::Running { handler, .. } => f
+            AsyncOutputHandler::Running {
+                l1_batch_number,
+                handler,
+                ..
+            } => f
                 .debug_struct("AsyncOutputHandler::Running")
+                .field("l1_batch_number", l1_batch_number)
                 .field("handler", handler)
                 .finish(),
             AsyncOutputHandler::Finished => f.debug_struct("AsyncOutputHandler::Finished").finish(),
@@ -168,12 +192,18 @@ impl StateKeeperOutputHandler for AsyncOutputHandler {
         let state = mem::replace(self, AsyncOutputHandler::Finished);
         match state {
             AsyncOutputHandler::Running {
+                l1_batch_number,
                 mut handler,
+                pool,
+                io,
                 sender,
             } => {
                 sender
                     .send(tokio::task::spawn(async move {
-                        handler.handle_l1_batch(updates_manager).await
+                        handler.handle_l1_batch(updates_manager).await?;
+                        let mut conn = pool.connection_tagged(io.name()).await?;
+                        io.mark_l1_batch_output_committed(&mut conn, l1_batch_number)
+                            .await
                     }))
                     .ok();
                 Ok(())
@@ -1,4 +1,4 @@
-use std::{sync::Arc, time::Duration};
+use std::{mem, sync::Arc, time::Duration};
 
 use anyhow::Context;
 use multivm::interface::L2BlockEnv;
@@ -10,7 +10,115 @@ use zksync_state_keeper::{
 };
 use zksync_types::{block::L2BlockExecutionData, L1BatchNumber};
 
-use crate::{storage::StorageLoader, OutputHandlerFactory, VmRunnerIo};
+use crate::{storage::StorageLoader, BatchExecuteData, OutputHandlerFactory, VmRunnerIo};
+
+/// Limits on L1 batches executed by [`VmRunner`] concurrently.
+///
+/// Batches within the window are executed in parallel, but their outputs are still committed in order
+/// as long as the runner is used together with [`ConcurrentOutputHandlerFactory`](crate::ConcurrentOutputHandlerFactory).
+/// Regardless of the window, the runner only executes batches returned by [`VmRunnerIo`], which usually
+/// bounds the number of batches loaded ahead of the latest processed batch.
+///
+/// The default window is unbounded, i.e., all batches provided by [`VmRunnerIo`] are executed concurrently.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutionWindow {
+    max_batches: usize,
+    memory_budget: Option<usize>,
+}
+
+impl Default for ExecutionWindow {
+    fn default() -> Self {
+        Self {
+            max_batches: usize::MAX,
+            memory_budget: None,
+        }
+    }
+}
+
+impl ExecutionWindow {
+    /// Window executing a single batch at a time.
+    pub const fn sequential() -> Self {
+        Self {
+            max_batches: 1,
+            memory_budget: None,
+        }
+    }
+
+    /// Creates a window executing up to `max_batches` batches concurrently.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `max_batches` is zero.
+    pub fn new(max_batches: usize) -> Self {
+        assert!(max_batches > 0, "execution window cannot be empty");
+        Self {
+            max_batches,
+            memory_budget: None,
+        }
+    }
+
+    /// Creates a window from the optional limits specified in a component config. Unspecified limits
+    /// are left unbounded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `max_batches` is zero.
+    pub fn from_limits(
+        max_batches: Option<usize>,
+        memory_budget: Option<usize>,
+    ) -> anyhow::Result<Self> {
+        let mut window = match max_batches {
+            Some(0) => anyhow::bail!("execution window cannot be empty"),
+            Some(max_batches) => Self::new(max_batches),
+            None => Self::default(),
+        };
+        window.memory_budget = memory_budget;
+        Ok(window)
+    }
+
+    /// Sets the approximate memory budget (in bytes) for input data of concurrently executed batches.
+    /// A batch that doesn't fit into the budget is retained and only started once enough preceding batches
+    /// are executed; a single batch larger than the budget is started once all preceding batches are executed,
+    /// so that it doesn't stall the runner.
+    pub fn with_memory_budget(mut self, bytes: usize) -> Self {
+        self.memory_budget = Some(bytes);
+        self
+    }
+
+    /// Returns the maximum number of concurrently executed batches.
+    pub fn max_batches(&self) -> usize {
+        self.max_batches
+    }
+
+    fn fits_memory_budget(&self, in_flight: &[InFlightBatch], batch_size: usize) -> bool {
+        if in_flight.is_empty() {
+            return true;
+        }
+        self.memory_budget.map_or(true, |budget| {
+            let in_flight_size: usize = in_flight.iter().map(|batch| batch.size).sum();
+            in_flight_size + batch_size <= budget
+        })
+    }
+}
+
+#[derive(Debug)]
+struct InFlightBatch {
+    number: L1BatchNumber,
+    size: usize,
+    handle: JoinHandle<anyhow::Result<()>>,
+}
+
+/// Approximate size of the batch input data in memory. Only accounts for the variable-size transaction data,
+/// which dominates the size for all non-trivial batches.
+fn estimate_batch_size(data: &BatchExecuteData) -> usize {
+    let txs = data.l2_blocks.iter().flat_map(|block| &block.txs);
+    txs.map(|tx| {
+        let factory_deps_size: usize = tx.execute.factory_deps.iter().flatten().map(Vec::len).sum();
+        let raw_size = tx.raw_bytes.as_ref().map_or(0, |bytes| bytes.0.len());
+        mem::size_of_val(tx) + tx.execute.calldata.len() + factory_deps_size + raw_size
+    })
+    .sum()
+}
 
 /// VM runner represents a logic layer of L1 batch / L2 block processing flow akin to that of state
 /// keeper. The difference is that VM runner is designed to be run on batches/blocks that have
@@ -26,10 +134,11 @@ use crate::{storage::StorageLoader, OutputHandlerFactory, VmRunnerIo};
 #[derive(Debug)]
 pub struct VmRunner {
     pool: ConnectionPool<Core>,
-    io: Box<dyn VmRunnerIo>,
+    io: Arc<dyn VmRunnerIo>,
     loader: Arc<dyn StorageLoader>,
     output_handler_factory: Box<dyn OutputHandlerFactory>,
     batch_processor: Box<dyn BatchExecutor>,
+    execution_window: ExecutionWindow,
 }
 
 impl VmRunner {
@@ -48,14 +157,24 @@ impl VmRunner {
     ) -> Self {
         Self {
             pool,
-            io,
+            io: io.into(),
             loader,
             output_handler_factory,
             batch_processor,
+            execution_window: ExecutionWindow::default(),
         }
     }
 
+    /// Sets the window of concurrently executed batches. By default, the window is unbounded.
+    pub fn with_execution_window(mut self, window: ExecutionWindow) -> Self {
+        self.execution_window = window;
+        self
+    }
+
     async fn process_batch(
+        pool: ConnectionPool<Core>,
+        io: Arc<dyn VmRunnerIo>,
+        l1_batch_number: L1BatchNumber,
         batch_executor: BatchExecutorHandle,
         l2_blocks: Vec<L2BlockExecutionData>,
         mut updates_manager: UpdatesManager,
@@ -102,7 +221,12 @@ impl VmRunner {
                 .await
                 .context("VM runner failed to handle L2 block")?;
         }
-        batch_executor.finish_batch().await;
+        let finished_batch = batch_executor.finish_batch().await;
+        updates_manager.finish_batch(finished_batch);
+        let mut conn = pool.connection_tagged(io.name()).await?;
+        io.mark_l1_batch_as_executed(&mut conn, l1_batch_number)
+            .await?;
+        drop(conn);
         output_handler
             .handle_l1_batch(Arc::new(updates_manager))
             .await
@@ -115,43 +239,76 @@ impl VmRunner {
     pub async fn run(mut self, stop_receiver: &watch::Receiver<bool>) -> anyhow::Result<()> {
         const SLEEP_INTERVAL: Duration = Duration::from_millis(50);
 
-        // Join handles for asynchronous tasks that are being run in the background
-        let mut task_handles: Vec<(L1BatchNumber, JoinHandle<anyhow::Result<()>>)> = Vec::new();
+        // Batches that are being processed in the background
+        let mut in_flight: Vec<InFlightBatch> = Vec::new();
+        // Loaded batch that didn't fit into the memory budget, together with its estimated size
+        let mut pending_batch: Option<(BatchExecuteData, usize)> = None;
         let mut next_batch = self
             .io
             .latest_processed_batch(&mut self.pool.connection().await?)
             .await?
             + 1;
         loop {
-            // Traverse all handles and filter out tasks that have been finished. Also propagates
-            // any panic/error that might have happened during the task's execution.
-            let mut retained_handles = Vec::new();
-            for (l1_batch_number, handle) in task_handles {
-                if handle.is_finished() {
-                    handle
+            if *stop_receiver.borrow() {
+                tracing::info!("VM runner was interrupted");
+                break;
+            }
+
+            // Traverse all in-flight batches and filter out those that have been finished. Also propagates
+            // any panic/error that might have happened during the batch processing.
+            let mut retained_batches = Vec::new();
+            for batch in in_flight {
+                if batch.handle.is_finished() {
+                    let l1_batch_number = batch.number;
+                    batch
+                        .handle
                         .await
                         .with_context(|| format!("Processing batch #{} panicked", l1_batch_number))?
                         .with_context(|| format!("Failed to process batch #{}", l1_batch_number))?;
                 } else {
-                    retained_handles.push((l1_batch_number, handle));
+                    retained_batches.push(batch);
                 }
             }
-            task_handles = retained_handles;
-
-            let last_ready_batch = self
-                .io
-                .last_ready_to_be_loaded_batch(&mut self.pool.connection().await?)
-                .await?;
-            if next_batch > last_ready_batch {
-                // Next batch is not ready to be processed yet
+            in_flight = retained_batches;
+            if in_flight.len() >= self.execution_window.max_batches {
+                // Execution window is full
                 tokio::time::sleep(SLEEP_INTERVAL).await;
                 continue;
             }
-            let Some(batch_data) = self.loader.load_batch(next_batch).await? else {
-                // Next batch has not been loaded yet
+
+            let (batch_data, batch_size) = if let Some(pending_batch) = pending_batch.take() {
+                pending_batch
+            } else {
+                let last_ready_batch = self
+                    .io
+                    .last_ready_to_be_loaded_batch(&mut self.pool.connection().await?)
+                    .await?;
+                if next_batch > last_ready_batch {
+                    // Next batch is not ready to be processed yet
+                    tokio::time::sleep(SLEEP_INTERVAL).await;
+                    continue;
+                }
+                let Some(batch_data) = self.loader.load_batch(next_batch).await? else {
+                    // Next batch has not been loaded yet
+                    tokio::time::sleep(SLEEP_INTERVAL).await;
+                    continue;
+                };
+                let batch_size = estimate_batch_size(&batch_data);
+                (batch_data, batch_size)
+            };
+            if !self
+                .execution_window
+                .fits_memory_budget(&in_flight, batch_size)
+            {
+                // Batch doesn't fit into the memory budget; retain it and wait until some in-flight batches
+                // are processed
+                pending_batch = Some((batch_data, batch_size));
                 tokio::time::sleep(SLEEP_INTERVAL).await;
                 continue;
-            };
+            }
+            self.io
+                .mark_l1_batch_as_loaded(&mut self.pool.connection().await?, next_batch)
+                .await?;
             let updates_manager =
                 UpdatesManager::new(&batch_data.l1_batch_env, &batch_data.system_env);
             let Some(batch_executor) = self
@@ -173,12 +330,19 @@ impl VmRunner {
                 .await?;
 
             let handle = tokio::task::spawn(Self::process_batch(
+                self.pool.clone(),
+                self.io.clone(),
+                next_batch,
                 batch_executor,
                 batch_data.l2_blocks,
                 updates_manager,
                 output_handler,
             ));
-            task_handles.push((next_batch, handle));
+            in_flight.push(InFlightBatch {
+                number: next_batch,
+                size: batch_size,
+                handle,
+            });
 
             next_batch += 1;
         }
@@ -0,0 +1,288 @@
+use std::{ops, sync::Arc, time::Duration};
+
+use anyhow::Context as _;
+use async_trait::async_trait;
+use tokio::{sync::watch, task::JoinHandle};
+use zksync_config::configs;
+use zksync_dal::{Connection, ConnectionPool, Core, CoreDal};
+use zksync_health_check::ReactiveHealthCheck;
+use zksync_state_keeper::BatchExecutor;
+use zksync_types::{L1BatchNumber, L2ChainId};
+
+use crate::{
+    CheckpointedIo, ConcurrentOutputHandlerFactory, ExecutionWindow, OutputHandlerFactory,
+    VmRunner, VmRunnerIo, VmRunnerStorage,
+};
+
+/// Configuration of [`VmReplayRunner`].
+#[derive(Debug, Clone)]
+pub struct VmReplayConfig {
+    /// Inclusive range of L1 batches to replay. Must not include the genesis batch.
+    pub l1_batches: ops::RangeInclusive<L1BatchNumber>,
+    /// Maximum number of L1 batches that can be loaded / processed ahead of the latest processed batch.
+    pub window_size: u32,
+    /// Window of concurrently executed batches (unbounded by default). Batches outside the loading window
+    /// are never executed, so the number of concurrently executed batches never exceeds `window_size`.
+    pub execution_window: ExecutionWindow,
+    /// Path to the RocksDB cache used by the replay. The cache must not be ahead of the first replayed batch;
+    /// it is caught up to the batch preceding the replayed range before the replay starts.
+    pub rocksdb_path: String,
+    /// L2 chain ID.
+    pub chain_id: L2ChainId,
+}
+
+impl VmReplayConfig {
+    /// Creates a replay config from the node config.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the execution window specified in the config is invalid.
+    pub fn from_node_config(
+        config: &configs::VmReplayConfig,
+        chain_id: L2ChainId,
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            l1_batches: config.first_l1_batch..=config.last_l1_batch,
+            window_size: config.window_size,
+            execution_window: ExecutionWindow::from_limits(
+                config.max_concurrent_batches,
+                config.execution_memory_budget_bytes(),
+            )?,
+            rocksdb_path: config.db_path.clone(),
+            chain_id,
+        })
+    }
+}
+
+/// [`VmRunnerIo`] for a fixed range of L1 batches. The progress is kept in memory; it's persisted
+/// by wrapping the IO in [`CheckpointedIo`].
+#[derive(Debug, Clone)]
+struct ReplayIo {
+    last_l1_batch: L1BatchNumber,
+    window_size: u32,
+    latest_processed_batch: Arc<watch::Sender<L1BatchNumber>>,
+}
+
+impl ReplayIo {
+    fn new(config: &VmReplayConfig) -> Self {
+        let latest_processed_batch = *config.l1_batches.start() - 1;
+        Self {
+            last_l1_batch: *config.l1_batches.end(),
+            window_size: config.window_size,
+            latest_processed_batch: Arc::new(watch::channel(latest_processed_batch).0),
+        }
+    }
+}
+
+#[async_trait]
+impl VmRunnerIo for ReplayIo {
+    fn name(&self) -> &'static str {
+        "vm_runner_replay"
+    }
+
+    async fn latest_processed_batch(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        Ok(*self.latest_processed_batch.borrow())
+    }
+
+    async fn last_ready_to_be_loaded_batch(
+        &self,
+        conn: &mut Connection<'_, Core>,
+    ) -> anyhow::Result<L1BatchNumber> {
+        let sealed_l1_batch = conn
+            .blocks_dal()
+            .get_sealed_l1_batch_number()
+            .await?
+            .unwrap_or_default();
+        let window_end = *self.latest_processed_batch.borrow() + self.window_size;
+        Ok(self.last_l1_batch.min(window_end).min(sealed_l1_batch))
+    }
+
+    async fn mark_l1_batch_as_completed(
+        &self,
+        _conn: &mut Connection<'_, Core>,
+        l1_batch_number: L1BatchNumber,
+    ) -> anyhow::Result<()> {
+        self.latest_processed_batch.send_modify(|latest| {
+            *latest = (*latest).max(l1_batch_number);
+        });
+        Ok(())
+    }
+}
+
+/// VM runner mode replaying a fixed range of historical L1 batches and feeding execution results
+/// to the provided [`OutputHandlerFactory`]. Can be used e.g. to re-derive data produced during batch execution,
+/// or to validate a new VM version against the stored execution results.
+///
+/// Output handlers are invoked concurrently (see [`ConcurrentOutputHandlerFactory`]); it's guaranteed
+/// that each batch in the range is handled exactly once if the replay completes successfully.
+#[derive(Debug)]
+pub struct VmReplayRunner<F> {
+    pool: ConnectionPool<Core>,
+    config: VmReplayConfig,
+    io: CheckpointedIo<ReplayIo>,
+    output_handler_factory: F,
+    batch_executor: Box<dyn BatchExecutor>,
+}
+
+impl<F: OutputHandlerFactory + 'static> VmReplayRunner<F> {
+    /// Creates a new replay runner.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration is invalid.
+    pub fn new(
+        pool: ConnectionPool<Core>,
+        config: VmReplayConfig,
+        output_handler_factory: F,
+        batch_executor: Box<dyn BatchExecutor>,
+    ) -> anyhow::Result<Self> {
+        anyhow::ensure!(
+            !config.l1_batches.is_empty(),
+            "Replayed L1 batch range {:?} is empty",
+            config.l1_batches
+        );
+        anyhow::ensure!(
+            *config.l1_batches.start() > L1BatchNumber(0),
+            "Genesis L1 batch cannot be replayed"
+        );
+        anyhow::ensure!(config.window_size > 0, "Window size must be positive");
+        // Checkpoints are keyed by the replayed range, so that a restarted replay of the same range
+        // resumes from the latest committed batch.
+        let checkpoint_key = format!(
+            "vm_runner_replay/{}-{}",
+            config.l1_batches.start().0,
+            config.l1_batches.end().0
+        );
+        let io = CheckpointedIo::new(ReplayIo::new(&config), checkpoint_key);
+        Ok(Self {
+            pool,
+            config,
+            io,
+            output_handler_factory,
+            batch_executor,
+        })
+    }
+
+    /// Returns the health check reporting the replay progress.
+    pub fn health_check(&self) -> ReactiveHealthCheck {
+        self.io.health_check()
+    }
+
+    /// Replays the configured range of L1 batches. Returns once all batches in the range are processed,
+    /// or once a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates errors from VM runner components, such as DB errors or errors returned by output handlers.
+    pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+        let l1_batches = self.config.l1_batches.clone();
+        let last_l1_batch = *l1_batches.end();
+        tracing::info!("Starting VM replay of L1 batches {l1_batches:?}");
+
+        let io = self.io;
+        let mut conn = self.pool.connection_tagged(io.name()).await?;
+        let resumed_batch = io.latest_processed_batch(&mut conn).await?;
+        drop(conn);
+        let progress_sender = &io.inner().latest_processed_batch;
+        if resumed_batch > *progress_sender.borrow() {
+            tracing::info!("Resuming VM replay after L1 batch #{resumed_batch}");
+            progress_sender.send_replace(resumed_batch);
+        }
+        let mut progress = progress_sender.subscribe();
+        let (storage, storage_task) = VmRunnerStorage::new(
+            self.pool.clone(),
+            self.config.rocksdb_path,
+            io.clone(),
+            self.config.chain_id,
+        )
+        .await
+        .context("failed initializing VM runner storage")?;
+        let (output_factory, output_task) = ConcurrentOutputHandlerFactory::new(
+            self.pool.clone(),
+            io.clone(),
+            self.output_handler_factory,
+        );
+        let vm_runner = VmRunner::new(
+            self.pool,
+            Box::new(io),
+            Arc::new(storage),
+            Box::new(output_factory),
+            self.batch_executor,
+        )
+        .with_execution_window(self.config.execution_window);
+
+        // Replay components are stopped either by the external stop signal, or once the replay is completed.
+        let (replay_stop_sender, replay_stop_receiver) = watch::channel(false);
+        let runner_stop_receiver = replay_stop_receiver.clone();
+        let mut tasks: Vec<(&str, JoinHandle<anyhow::Result<()>>)> = vec![
+            (
+                "storage_sync",
+                tokio::spawn(storage_task.run(replay_stop_receiver.clone())),
+            ),
+            (
+                "output_handler",
+                tokio::spawn(output_task.run(replay_stop_receiver)),
+            ),
+            (
+                "vm_runner",
+                tokio::spawn(async move { vm_runner.run(&runner_stop_receiver).await }),
+            ),
+        ];
+
+        let is_completed = loop {
+            if *progress.borrow() >= last_l1_batch {
+                break true;
+            }
+            if *stop_receiver.borrow() {
+                break false;
+            }
+            if let Some(idx) = tasks.iter().position(|(_, handle)| handle.is_finished()) {
+                let (name, handle) = tasks.swap_remove(idx);
+                replay_stop_sender.send_replace(true);
+                let err = match handle.await {
+                    Ok(Ok(())) => anyhow::anyhow!("VM replay task `{name}` exited prematurely"),
+                    Ok(Err(err)) => err.context(format!("VM replay task `{name}` failed")),
+                    Err(err) => anyhow::anyhow!("VM replay task `{name}` panicked: {err}"),
+                };
+                for (_, handle) in tasks {
+                    handle.await.ok();
+                }
+                return Err(err);
+            }
+
+            tokio::select! {
+                _ = progress.changed() => {}
+                () = tokio::time::sleep(POLL_INTERVAL) => {}
+            }
+        };
+
+        replay_stop_sender.send_replace(true);
+        for (name, handle) in tasks {
+            let result = handle
+                .await
+                .with_context(|| format!("VM replay task `{name}` panicked"))?;
+            if let Err(err) = result {
+                if is_completed {
+                    return Err(err.context(format!("VM replay task `{name}` failed")));
+                }
+                // Tasks may fail if they are interrupted in the middle of processing a batch.
+                tracing::warn!("VM replay task `{name}` failed after being interrupted: {err:#}");
+            }
+        }
+
+        if is_completed {
+            tracing::info!("VM replay of L1 batches {l1_batches:?} has completed");
+        } else {
+            tracing::info!(
+                "VM replay was interrupted; latest processed L1 batch is #{}",
+                *progress.borrow()
+            );
+        }
+        Ok(())
+    }
+}
@@ -271,6 +271,11 @@ impl<Io: VmRunnerIo> StorageSyncTask<Io> {
         })
     }
 
+    /// Runs the task until a stop signal is received.
+    ///
+    /// # Errors
+    ///
+    /// Propagates DB and RocksDB errors.
     pub async fn run(self, stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
         const SLEEP_INTERVAL: Duration = Duration::from_millis(50);
 
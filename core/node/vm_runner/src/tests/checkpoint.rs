@@ -0,0 +1,143 @@
+use std::sync::Arc;
+
+use tokio::sync::RwLock;
+use zksync_dal::{vm_runner_dal::VmRunnerBatchPhase, ConnectionPool, Core, CoreDal};
+use zksync_health_check::{CheckHealth, HealthStatus};
+use zksync_types::L1BatchNumber;
+
+use crate::{tests::IoMock, CheckpointedIo, VmRunnerIo};
+
+fn mock_io() -> Arc<RwLock<IoMock>> {
+    Arc::new(RwLock::new(IoMock {
+        current: 0.into(),
+        max: 10,
+    }))
+}
+
+#[tokio::test]
+async fn checkpoints_are_persisted() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let io = CheckpointedIo::new(mock_io(), "test");
+    let health_check = io.health_check();
+
+    io.mark_l1_batch_as_loaded(&mut conn, L1BatchNumber(1))
+        .await
+        .unwrap();
+    io.mark_l1_batch_as_loaded(&mut conn, L1BatchNumber(2))
+        .await
+        .unwrap();
+    io.mark_l1_batch_as_executed(&mut conn, L1BatchNumber(1))
+        .await
+        .unwrap();
+    let checkpoints = conn.vm_runner_dal().get_checkpoints("test").await.unwrap();
+    assert_eq!(
+        checkpoints,
+        [
+            (L1BatchNumber(1), VmRunnerBatchPhase::Executed),
+            (L1BatchNumber(2), VmRunnerBatchPhase::Loaded)
+        ]
+    );
+
+    io.mark_l1_batch_as_completed(&mut conn, L1BatchNumber(1))
+        .await
+        .unwrap();
+    assert_eq!(io.inner().read().await.current, L1BatchNumber(1));
+    let checkpoints = conn.vm_runner_dal().get_checkpoints("test").await.unwrap();
+    assert_eq!(
+        checkpoints,
+        [
+            (L1BatchNumber(1), VmRunnerBatchPhase::Committed),
+            (L1BatchNumber(2), VmRunnerBatchPhase::Loaded)
+        ]
+    );
+
+    let health = health_check.check_health().await;
+    assert_eq!(health.status(), HealthStatus::Ready);
+    assert_eq!(
+        health.details().unwrap(),
+        &serde_json::json!({
+            "latest_loaded_batch": 2,
+            "latest_executed_batch": 1,
+            "latest_committed_batch": 1,
+        })
+    );
+}
+
+#[tokio::test]
+async fn resuming_from_checkpoint() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let io = CheckpointedIo::new(mock_io(), "test");
+    for number in 1..=3 {
+        io.mark_l1_batch_as_completed(&mut conn, L1BatchNumber(number))
+            .await
+            .unwrap();
+    }
+    io.mark_l1_batch_as_loaded(&mut conn, L1BatchNumber(4))
+        .await
+        .unwrap();
+
+    // Emulate a restart with an IO that doesn't persist progress.
+    let io = CheckpointedIo::new(mock_io(), "test");
+    let latest_batch = io.latest_processed_batch(&mut conn).await.unwrap();
+    assert_eq!(latest_batch, L1BatchNumber(3));
+
+    // Checkpoints of other components are not used.
+    let other_io = CheckpointedIo::new(mock_io(), "other");
+    let latest_batch = other_io.latest_processed_batch(&mut conn).await.unwrap();
+    assert_eq!(latest_batch, L1BatchNumber(0));
+}
+
+#[tokio::test]
+async fn resuming_after_out_of_order_commits() {
+    let pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = pool.connection().await.unwrap();
+    let io = CheckpointedIo::new(mock_io(), "test");
+    io.mark_l1_batch_as_completed(&mut conn, L1BatchNumber(1))
+        .await
+        .unwrap();
+    for number in 2..=5 {
+        io.mark_l1_batch_as_loaded(&mut conn, L1BatchNumber(number))
+            .await
+            .unwrap();
+    }
+    io.mark_l1_batch_as_executed(&mut conn, L1BatchNumber(2))
+        .await
+        .unwrap();
+    io.mark_l1_batch_output_committed(&mut conn, L1BatchNumber(3))
+        .await
+        .unwrap();
+    io.mark_l1_batch_output_committed(&mut conn, L1BatchNumber(5))
+        .await
+        .unwrap();
+
+    // Emulate a restart with an IO that doesn't persist progress. Batch #2 was only executed,
+    // so it and all later batches must be reprocessed.
+    let io = CheckpointedIo::new(mock_io(), "test");
+    let health_check = io.health_check();
+    let latest_batch = io.latest_processed_batch(&mut conn).await.unwrap();
+    assert_eq!(latest_batch, L1BatchNumber(1));
+    let health = health_check.check_health().await;
+    assert_eq!(
+        health.details().unwrap(),
+        &serde_json::json!({
+            "latest_loaded_batch": 4,
+            "latest_executed_batch": 2,
+            "latest_committed_batch": 5,
+        })
+    );
+
+    // Once batch #2 output is committed, batches up to #3 are considered processed; #4 is still only loaded.
+    io.mark_l1_batch_output_committed(&mut conn, L1BatchNumber(2))
+        .await
+        .unwrap();
+    let latest_batch = io.latest_processed_batch(&mut conn).await.unwrap();
+    assert_eq!(latest_batch, L1BatchNumber(3));
+
+    io.mark_l1_batch_output_committed(&mut conn, L1BatchNumber(4))
+        .await
+        .unwrap();
+    let latest_batch = io.latest_processed_batch(&mut conn).await.unwrap();
+    assert_eq!(latest_batch, L1BatchNumber(5));
+}
@@ -24,8 +24,11 @@ use zksync_utils::u256_to_h256;
 
 use super::{OutputHandlerFactory, VmRunnerIo};
 
+mod checkpoint;
 mod output_handler;
 mod process;
+mod protective_reads;
+mod replay;
 mod storage;
 
 #[derive(Debug, Default)]
@@ -10,7 +10,7 @@ use zksync_types::L2ChainId;
 
 use crate::{
     tests::{fund, store_l1_batches, wait, IoMock, TestOutputFactory},
-    ConcurrentOutputHandlerFactory, VmRunner, VmRunnerStorage,
+    ConcurrentOutputHandlerFactory, ExecutionWindow, VmRunner, VmRunnerStorage,
 };
 
 // Testing more than a one-batch scenario is pretty difficult as that requires storage to have
@@ -21,6 +21,28 @@ use crate::{
 // Instead, we rely on integration tests to verify the correctness of VM runner main process.
 #[tokio::test]
 async fn process_one_batch() -> anyhow::Result<()> {
+    test_processing_one_batch(ExecutionWindow::sequential()).await
+}
+
+#[tokio::test]
+async fn process_one_batch_with_default_window() -> anyhow::Result<()> {
+    test_processing_one_batch(ExecutionWindow::default()).await
+}
+
+#[tokio::test]
+async fn process_one_batch_with_concurrent_window() -> anyhow::Result<()> {
+    // The memory budget is smaller than any batch; the batch must still be processed.
+    let window = ExecutionWindow::new(4).with_memory_budget(1);
+    test_processing_one_batch(window).await
+}
+
+#[test]
+#[should_panic(expected = "execution window cannot be empty")]
+fn empty_execution_window() {
+    ExecutionWindow::new(0);
+}
+
+async fn test_processing_one_batch(window: ExecutionWindow) -> anyhow::Result<()> {
     let rocksdb_dir = TempDir::new()?;
     let connection_pool = ConnectionPool::<Core>::test_pool().await;
     let mut conn = connection_pool.connection().await.unwrap();
@@ -72,7 +94,8 @@ async fn process_one_batch() -> anyhow::Result<()> {
         storage,
         Box::new(output_factory),
         Box::new(batch_executor),
-    );
+    )
+    .with_execution_window(window);
     tokio::task::spawn(async move { vm_runner.run(&stop_receiver).await.unwrap() });
 
     for batch in batches {
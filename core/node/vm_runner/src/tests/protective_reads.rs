@@ -0,0 +1,110 @@
+use std::time::Duration;
+
+use tempfile::TempDir;
+use tokio::sync::watch;
+use zksync_dal::{vm_runner_dal::ProtectiveReadMismatchKind, ConnectionPool, Core, CoreDal};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_test_account::Account;
+use zksync_types::{
+    zk_evm_types::{LogQuery, Timestamp},
+    AccountTreeId, Address, L1BatchNumber, L2ChainId, StorageKey, H256, U256,
+};
+
+use crate::{
+    tests::{fund, store_l1_batches},
+    ExecutionWindow, ProtectiveReadsVerifier, ProtectiveReadsVerifierConfig,
+};
+
+#[tokio::test]
+async fn verifying_protective_reads() -> anyhow::Result<()> {
+    let rocksdb_dir = TempDir::new()?;
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let alice = Account::random();
+    let bob = Account::random();
+    let mut accounts = vec![alice, bob];
+    fund(&connection_pool, &accounts).await;
+
+    store_l1_batches(
+        &mut conn,
+        1..=1,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await?;
+    // Persist a single bogus protective read; it should be reported as unexpected, and all actual reads
+    // should be reported as missing.
+    let bogus_read = LogQuery {
+        timestamp: Timestamp(0),
+        tx_number_in_block: 0,
+        aux_byte: 0,
+        shard_id: 0,
+        address: Address::repeat_byte(0xff),
+        key: U256::one(),
+        read_value: U256::zero(),
+        written_value: U256::zero(),
+        rw_flag: false,
+        rollback: false,
+        is_service: false,
+    };
+    conn.storage_logs_dedup_dal()
+        .insert_protective_reads(L1BatchNumber(1), &[bogus_read])
+        .await?;
+
+    let config = ProtectiveReadsVerifierConfig {
+        rocksdb_path: rocksdb_dir.path().to_str().unwrap().to_owned(),
+        window_size: 1,
+        first_processed_batch: L1BatchNumber(1),
+        execution_window: ExecutionWindow::sequential(),
+        chain_id: L2ChainId::default(),
+    };
+    let (verifier, tasks) = ProtectiveReadsVerifier::new(connection_pool.clone(), config).await?;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let loader_task = tokio::spawn(tasks.loader_task.run(stop_receiver.clone()));
+    let output_task = tokio::spawn(tasks.output_handler_factory_task.run(stop_receiver.clone()));
+    let verifier_task = tokio::spawn(async move { verifier.run(&stop_receiver).await });
+
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let committed_batch = conn
+                .vm_runner_dal()
+                .get_latest_committed_batch("protective_reads_verifier")
+                .await
+                .unwrap();
+            if committed_batch == Some(L1BatchNumber(1)) {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+    })
+    .await?;
+
+    let mismatches = conn
+        .vm_runner_dal()
+        .get_protective_reads_mismatches(L1BatchNumber(1))
+        .await?;
+    let bogus_key = StorageKey::new(
+        AccountTreeId::new(bogus_read.address),
+        H256::from_low_u64_be(1),
+    );
+    assert!(
+        mismatches.contains(&(bogus_key, ProtectiveReadMismatchKind::Unexpected)),
+        "{mismatches:?}"
+    );
+    assert!(
+        mismatches
+            .iter()
+            .any(|(_, kind)| *kind == ProtectiveReadMismatchKind::Missing),
+        "{mismatches:?}"
+    );
+
+    stop_sender.send_replace(true);
+    loader_task.await??;
+    output_task.await??;
+    verifier_task.await??;
+    Ok(())
+}
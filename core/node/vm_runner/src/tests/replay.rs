@@ -0,0 +1,131 @@
+use std::{collections::HashMap, time::Duration};
+
+use tempfile::TempDir;
+use tokio::sync::watch;
+use zksync_dal::{ConnectionPool, Core};
+use zksync_node_genesis::{insert_genesis_batch, GenesisParams};
+use zksync_state_keeper::MainBatchExecutor;
+use zksync_test_account::Account;
+use zksync_types::{L1BatchNumber, L2ChainId};
+
+use crate::{
+    tests::{fund, store_l1_batches, TestOutputFactory},
+    ExecutionWindow, VmReplayConfig, VmReplayRunner,
+};
+
+fn replay_config(rocksdb_dir: &TempDir, last_l1_batch: u32) -> VmReplayConfig {
+    VmReplayConfig {
+        l1_batches: L1BatchNumber(1)..=L1BatchNumber(last_l1_batch),
+        window_size: 1,
+        execution_window: ExecutionWindow::sequential(),
+        rocksdb_path: rocksdb_dir.path().to_str().unwrap().to_owned(),
+        chain_id: L2ChainId::default(),
+    }
+}
+
+#[tokio::test]
+async fn replay_runner_validates_config() {
+    let rocksdb_dir = TempDir::new().unwrap();
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let test_factory = || TestOutputFactory {
+        delays: HashMap::new(),
+    };
+
+    let mut config = replay_config(&rocksdb_dir, 1);
+    config.l1_batches = L1BatchNumber(0)..=L1BatchNumber(1);
+    VmReplayRunner::new(
+        connection_pool.clone(),
+        config,
+        test_factory(),
+        Box::new(MainBatchExecutor::new(false, false)),
+    )
+    .unwrap_err();
+
+    let mut config = replay_config(&rocksdb_dir, 1);
+    config.window_size = 0;
+    VmReplayRunner::new(
+        connection_pool.clone(),
+        config,
+        test_factory(),
+        Box::new(MainBatchExecutor::new(false, false)),
+    )
+    .unwrap_err();
+
+    let mut config = replay_config(&rocksdb_dir, 1);
+    config.execution_window = ExecutionWindow::new(2);
+    VmReplayRunner::new(
+        connection_pool,
+        config,
+        test_factory(),
+        Box::new(MainBatchExecutor::new(false, false)),
+    )
+    .unwrap_err();
+}
+
+#[tokio::test]
+async fn replaying_one_batch() -> anyhow::Result<()> {
+    let rocksdb_dir = TempDir::new()?;
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await.unwrap();
+    let genesis_params = GenesisParams::mock();
+    insert_genesis_batch(&mut conn, &genesis_params)
+        .await
+        .unwrap();
+    let alice = Account::random();
+    let bob = Account::random();
+    let mut accounts = vec![alice, bob];
+    fund(&connection_pool, &accounts).await;
+
+    store_l1_batches(
+        &mut conn,
+        1..=1,
+        genesis_params.base_system_contracts().hashes(),
+        &mut accounts,
+    )
+    .await?;
+    drop(conn);
+
+    let test_factory = TestOutputFactory {
+        delays: HashMap::new(),
+    };
+    let runner = VmReplayRunner::new(
+        connection_pool,
+        replay_config(&rocksdb_dir, 1),
+        test_factory,
+        Box::new(MainBatchExecutor::new(false, false)),
+    )?;
+    let (_stop_sender, stop_receiver) = watch::channel(false);
+    // The runner is expected to return on its own once the batch is processed.
+    tokio::time::timeout(Duration::from_secs(10), runner.run(stop_receiver)).await??;
+    Ok(())
+}
+
+#[tokio::test]
+async fn replay_can_be_interrupted() -> anyhow::Result<()> {
+    let rocksdb_dir = TempDir::new()?;
+    let connection_pool = ConnectionPool::<Core>::test_pool().await;
+    let mut conn = connection_pool.connection().await.unwrap();
+    insert_genesis_batch(&mut conn, &GenesisParams::mock())
+        .await
+        .unwrap();
+    drop(conn);
+
+    // Batch #1 is never sealed, so the replay cannot complete on its own.
+    let test_factory = TestOutputFactory {
+        delays: HashMap::new(),
+    };
+    let runner = VmReplayRunner::new(
+        connection_pool,
+        replay_config(&rocksdb_dir, 1),
+        test_factory,
+        Box::new(MainBatchExecutor::new(false, false)),
+    )?;
+    let (stop_sender, stop_receiver) = watch::channel(false);
+    let runner_task = tokio::spawn(runner.run(stop_receiver));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(!runner_task.is_finished());
+
+    stop_sender.send_replace(true);
+    tokio::time::timeout(Duration::from_secs(5), runner_task).await???;
+    Ok(())
+}
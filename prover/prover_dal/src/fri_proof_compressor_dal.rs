@@ -5,7 +5,8 @@ use sqlx::Row;
 use zksync_basic_types::{
     protocol_version::ProtocolVersionId,
     prover_dal::{
-        JobCountStatistics, ProofCompressionJobInfo, ProofCompressionJobStatus, StuckJobs,
+        JobCountStatistics, PendingJobsLatencyStats, ProofCompressionJobInfo,
+        ProofCompressionJobStatus, StuckJobs,
     },
     L1BatchNumber,
 };
@@ -252,6 +253,36 @@ impl FriProofCompressorDal<'_, '_> {
         }
     }
 
+    /// Returns latency statistics for queued and in-progress compression jobs.
+    pub async fn get_jobs_latency_stats(&mut self, sla: Duration) -> PendingJobsLatencyStats {
+        let sla = pg_interval_from_duration(sla);
+        let row = sqlx::query!(
+            r#"
+            SELECT
+                COUNT(*) AS "pending!",
+                COUNT(*) FILTER (
+                    WHERE
+                        created_at < NOW() - $1::INTERVAL
+                ) AS "over_sla!",
+                COALESCE(EXTRACT(EPOCH FROM NOW() - MIN(created_at))::BIGINT, 0) AS "oldest_age_secs!"
+            FROM
+                proof_compression_jobs_fri
+            WHERE
+                status IN ('queued', 'in_progress')
+            "#,
+            &sla
+        )
+        .fetch_one(self.storage.conn())
+        .await
+        .unwrap();
+
+        PendingJobsLatencyStats {
+            pending: row.pending as usize,
+            oldest_age_secs: row.oldest_age_secs as u64,
+            over_sla: row.over_sla as usize,
+        }
+    }
+
     pub async fn get_oldest_not_compressed_batch(&mut self) -> Option<L1BatchNumber> {
         let result: Option<L1BatchNumber> = sqlx::query!(
             r#"
@@ -5,8 +5,8 @@ use zksync_basic_types::{
     basic_fri_types::{AggregationRound, CircuitIdRoundTuple},
     protocol_version::ProtocolVersionId,
     prover_dal::{
-        correct_circuit_id, FriProverJobMetadata, JobCountStatistics, ProverJobFriInfo,
-        ProverJobStatus, StuckJobs,
+        correct_circuit_id, FriProverJobMetadata, JobCountStatistics, PendingJobsLatencyStats,
+        ProverJobFriInfo, ProverJobStatus, StuckJobs,
     },
     L1BatchNumber,
 };
@@ -450,6 +450,48 @@ impl FriProverDal<'_, '_> {
         }
     }
 
+    /// Returns latency statistics for queued and in-progress prover jobs, keyed by `(circuit_id, aggregation_round)`.
+    pub async fn get_prover_jobs_latency_stats(
+        &mut self,
+        sla: Duration,
+    ) -> HashMap<(u8, u8), PendingJobsLatencyStats> {
+        let sla = pg_interval_from_duration(sla);
+        sqlx::query!(
+            r#"
+            SELECT
+                circuit_id,
+                aggregation_round,
+                COUNT(*) AS "pending!",
+                COUNT(*) FILTER (
+                    WHERE
+                        created_at < NOW() - $1::INTERVAL
+                ) AS "over_sla!",
+                EXTRACT(EPOCH FROM NOW() - MIN(created_at))::BIGINT AS "oldest_age_secs!"
+            FROM
+                prover_jobs_fri
+            WHERE
+                status IN ('queued', 'in_progress', 'in_gpu_proof')
+            GROUP BY
+                circuit_id,
+                aggregation_round
+            "#,
+            &sla
+        )
+        .fetch_all(self.storage.conn())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|row| {
+            let stats = PendingJobsLatencyStats {
+                pending: row.pending as usize,
+                oldest_age_secs: row.oldest_age_secs as u64,
+                over_sla: row.over_sla as usize,
+            };
+            ((row.circuit_id as u8, row.aggregation_round as u8), stats)
+        })
+        .collect()
+    }
+
     pub async fn min_unproved_l1_batch_number(&mut self) -> HashMap<(u8, u8), L1BatchNumber> {
         {
             sqlx::query!(
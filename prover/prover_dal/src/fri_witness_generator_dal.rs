@@ -8,7 +8,7 @@ use zksync_basic_types::{
     prover_dal::{
         correct_circuit_id, BasicWitnessGeneratorJobInfo, JobCountStatistics,
         LeafAggregationJobMetadata, LeafWitnessGeneratorJobInfo, NodeAggregationJobMetadata,
-        NodeWitnessGeneratorJobInfo, RecursionTipWitnessGeneratorJobInfo,
+        NodeWitnessGeneratorJobInfo, PendingJobsLatencyStats, RecursionTipWitnessGeneratorJobInfo,
         SchedulerWitnessGeneratorJobInfo, StuckJobs, WitnessJobStatus,
     },
     L1BatchNumber,
@@ -1371,6 +1371,37 @@ impl FriWitnessGeneratorDal<'_, '_> {
         }
     }
 
+    /// Returns latency statistics for queued and in-progress witness generation jobs of the given round.
+    pub async fn get_witness_jobs_latency_stats(
+        &mut self,
+        aggregation_round: AggregationRound,
+        sla: Duration,
+    ) -> PendingJobsLatencyStats {
+        let table_name = Self::input_table_name_for(aggregation_round);
+        let sql = format!(
+            r#"
+                SELECT
+                    COUNT(*) AS "pending",
+                    COUNT(*) FILTER (WHERE created_at < NOW() - $1::INTERVAL) AS "over_sla",
+                    COALESCE(EXTRACT(EPOCH FROM NOW() - MIN(created_at))::BIGINT, 0) AS "oldest_age_secs"
+                FROM {}
+                WHERE status IN ('queued', 'in_progress', 'in_gpu_proof')
+                "#,
+            table_name
+        );
+        let row = sqlx::query(&sql)
+            .bind(pg_interval_from_duration(sla))
+            .fetch_one(self.storage.conn())
+            .await
+            .unwrap();
+
+        PendingJobsLatencyStats {
+            pending: row.get::<i64, &str>("pending") as usize,
+            oldest_age_secs: row.get::<i64, &str>("oldest_age_secs") as u64,
+            over_sla: row.get::<i64, &str>("over_sla") as usize,
+        }
+    }
+
     fn input_table_name_for(aggregation_round: AggregationRound) -> &'static str {
         match aggregation_round {
             AggregationRound::BasicCircuits => "witness_inputs_fri",
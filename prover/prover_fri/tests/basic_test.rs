@@ -31,6 +31,9 @@ async fn prover_and_assert_base_layer(
             file_backed_base_path: "./tests/data/".to_owned(),
         },
         max_retries: 5,
+        integrity_envelope: false,
+        encryption_keys_file_path: None,
+        allow_plaintext_reads: false,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
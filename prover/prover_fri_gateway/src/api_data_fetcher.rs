@@ -45,6 +45,25 @@ impl PeriodicApiStruct {
             .await
     }
 
+    /// Performs a single iteration of the periodic job: sends the next request (if any) and handles the response.
+    pub(crate) async fn poll_once<Req>(&self)
+    where
+        Req: Send,
+        Self: PeriodicApi<Req>,
+    {
+        if let Some((job_id, request)) = self.get_next_request().await {
+            match self.send_request(job_id, request).await {
+                Ok(response) => {
+                    self.handle_response(job_id, response).await;
+                }
+                Err(err) => {
+                    METRICS.http_error[&Self::SERVICE_NAME].inc();
+                    tracing::error!("HTTP request failed due to error: {}", err);
+                }
+            }
+        }
+    }
+
     pub(crate) async fn run<Req>(
         self,
         mut stop_receiver: watch::Receiver<bool>,
@@ -65,17 +84,7 @@ impl PeriodicApiStruct {
                 return Ok(());
             }
 
-            if let Some((job_id, request)) = self.get_next_request().await {
-                match self.send_request(job_id, request).await {
-                    Ok(response) => {
-                        self.handle_response(job_id, response).await;
-                    }
-                    Err(err) => {
-                        METRICS.http_error[&Self::SERVICE_NAME].inc();
-                        tracing::error!("HTTP request failed due to error: {}", err);
-                    }
-                }
-            }
+            self.poll_once::<Req>().await;
             tokio::select! {
                 _ = stop_receiver.changed() => {
                     tracing::warn!("Stop signal received, shutting down {}", Self::SERVICE_NAME);
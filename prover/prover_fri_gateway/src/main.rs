@@ -13,11 +13,15 @@ use zksync_object_store::ObjectStoreFactory;
 use zksync_prover_interface::api::{ProofGenerationDataRequest, SubmitProofRequest};
 use zksync_utils::wait_for_tasks::ManagedTasks;
 
-use crate::api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH};
+use crate::{
+    api_data_fetcher::{PeriodicApiStruct, PROOF_GENERATION_DATA_PATH, SUBMIT_PROOF_PATH},
+    proof_stream::ProofStreamClient,
+};
 
 mod api_data_fetcher;
 mod metrics;
 mod proof_gen_data_fetcher;
+mod proof_stream;
 mod proof_submitter;
 
 #[tokio::main]
@@ -81,16 +85,21 @@ async fn main() -> anyhow::Result<()> {
 
     tracing::info!("Starting Fri Prover Gateway");
 
-    let tasks = vec![
-        tokio::spawn(
-            PrometheusExporterConfig::pull(config.prometheus_listener_port)
-                .run(stop_receiver.clone()),
-        ),
-        tokio::spawn(
-            proof_gen_data_fetcher.run::<ProofGenerationDataRequest>(stop_receiver.clone()),
-        ),
-        tokio::spawn(proof_submitter.run::<SubmitProofRequest>(stop_receiver)),
-    ];
+    let mut tasks = vec![tokio::spawn(
+        PrometheusExporterConfig::pull(config.prometheus_listener_port).run(stop_receiver.clone()),
+    )];
+    if config.streaming_enabled {
+        let proof_stream =
+            ProofStreamClient::new(&config.api_url, proof_gen_data_fetcher, proof_submitter)?;
+        tasks.push(tokio::spawn(proof_stream.run(stop_receiver)));
+    } else {
+        tasks.extend([
+            tokio::spawn(
+                proof_gen_data_fetcher.run::<ProofGenerationDataRequest>(stop_receiver.clone()),
+            ),
+            tokio::spawn(proof_submitter.run::<SubmitProofRequest>(stop_receiver)),
+        ]);
+    }
 
     let mut tasks = ManagedTasks::new(tasks);
     tokio::select! {
@@ -0,0 +1,247 @@
+use std::time::Duration;
+
+use anyhow::Context as _;
+use futures::{SinkExt, StreamExt};
+use tokio::{
+    net::TcpStream,
+    sync::watch,
+    time::{sleep, sleep_until, Instant},
+};
+use tokio_tungstenite::{
+    connect_async_with_config,
+    tungstenite::{protocol::WebSocketConfig, Message},
+    MaybeTlsStream, WebSocketStream,
+};
+use zksync_prover_interface::api::{
+    GatewayStreamMessage, ProofGenerationDataRequest, ProofGenerationDataResponse,
+    ServerStreamMessage, SubmitProofRequest, PROOF_STREAM_PATH,
+};
+use zksync_types::L1BatchNumber;
+
+use crate::{
+    api_data_fetcher::{PeriodicApi, PeriodicApiStruct},
+    metrics::METRICS,
+};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Interacts with the server using the streaming protocol: the server pushes new proof generation jobs
+/// and acknowledges submitted proofs over a single WebSocket connection.
+///
+/// If the server cannot be connected to via WebSocket (e.g., because it doesn't support streaming or is temporarily
+/// unavailable), the client polls the HTTP API using the wrapped periodic tasks and periodically retries connecting,
+/// with the retry interval exponentially increasing up to [`Self::MAX_RECONNECT_INTERVAL`].
+///
+/// The client pings the server to keep the connection alive and reconnects if nothing is received from the server
+/// for [`Self::READ_TIMEOUT`].
+pub(crate) struct ProofStreamClient {
+    stream_url: String,
+    reconnect_interval: Duration,
+    proof_gen_data_fetcher: PeriodicApiStruct,
+    proof_submitter: PeriodicApiStruct,
+}
+
+impl ProofStreamClient {
+    const SERVICE_NAME: &'static str = "ProofStream";
+    const MAX_RECONNECT_INTERVAL: Duration = Duration::from_secs(300);
+    const PING_INTERVAL: Duration = Duration::from_secs(30);
+    const READ_TIMEOUT: Duration = Duration::from_secs(90);
+
+    pub(crate) fn new(
+        api_url: &str,
+        proof_gen_data_fetcher: PeriodicApiStruct,
+        proof_submitter: PeriodicApiStruct,
+    ) -> anyhow::Result<Self> {
+        let stream_url = if let Some(rest) = api_url.strip_prefix("http://") {
+            format!("ws://{rest}{PROOF_STREAM_PATH}")
+        } else if let Some(rest) = api_url.strip_prefix("https://") {
+            format!("wss://{rest}{PROOF_STREAM_PATH}")
+        } else {
+            anyhow::bail!("unsupported API URL scheme: `{api_url}`");
+        };
+        Ok(Self {
+            stream_url,
+            reconnect_interval: proof_submitter.poll_duration,
+            proof_gen_data_fetcher,
+            proof_submitter,
+        })
+    }
+
+    pub(crate) async fn run(self, mut stop_receiver: watch::Receiver<bool>) -> anyhow::Result<()> {
+        tracing::info!(
+            "Starting {} with URL {}",
+            Self::SERVICE_NAME,
+            self.stream_url
+        );
+        let mut reconnect_interval = self.reconnect_interval;
+        loop {
+            if *stop_receiver.borrow() {
+                tracing::warn!("Stop signal received, shutting down {}", Self::SERVICE_NAME);
+                return Ok(());
+            }
+
+            let socket = match self.connect().await {
+                Ok(socket) => socket,
+                Err(err) => {
+                    tracing::warn!(
+                        "Failed opening proof stream: {err:#}; polling HTTP API for {reconnect_interval:?} \
+                         before reconnecting"
+                    );
+                    self.run_polling(reconnect_interval, &mut stop_receiver)
+                        .await;
+                    reconnect_interval = (reconnect_interval * 2).min(Self::MAX_RECONNECT_INTERVAL);
+                    continue;
+                }
+            };
+            reconnect_interval = self.reconnect_interval;
+            match self.run_session(socket, &mut stop_receiver).await {
+                Ok(()) => {
+                    tracing::warn!("Stop signal received, shutting down {}", Self::SERVICE_NAME);
+                    return Ok(());
+                }
+                Err(err) => {
+                    METRICS.http_error[&Self::SERVICE_NAME].inc();
+                    tracing::error!("Proof stream failed: {err:#}; reconnecting");
+                }
+            }
+
+            tokio::select! {
+                _ = stop_receiver.changed() => {}
+                _ = sleep(self.reconnect_interval) => {}
+            }
+        }
+    }
+
+    async fn connect(&self) -> anyhow::Result<Socket> {
+        // Proof generation data can be large, so we don't limit message sizes; the server is trusted.
+        let config = WebSocketConfig {
+            max_message_size: None,
+            max_frame_size: None,
+            ..WebSocketConfig::default()
+        };
+        let (socket, _) = connect_async_with_config(self.stream_url.as_str(), Some(config), false)
+            .await
+            .with_context(|| format!("failed connecting to `{}`", self.stream_url))?;
+        tracing::info!("Opened proof stream to {}", self.stream_url);
+        Ok(socket)
+    }
+
+    /// Polls the HTTP API for the specified duration or until a stop signal is received.
+    async fn run_polling(&self, duration: Duration, stop_receiver: &mut watch::Receiver<bool>) {
+        let deadline = Instant::now() + duration;
+        while Instant::now() < deadline && !*stop_receiver.borrow() {
+            // Iterations aren't interrupted, so that requests are never cancelled mid-flight.
+            tokio::join!(
+                self.proof_gen_data_fetcher
+                    .poll_once::<ProofGenerationDataRequest>(),
+                self.proof_submitter.poll_once::<SubmitProofRequest>()
+            );
+            let next_poll_at = (Instant::now() + self.proof_submitter.poll_duration).min(deadline);
+            tokio::select! {
+                _ = stop_receiver.changed() => {}
+                () = sleep_until(next_poll_at) => {}
+            }
+        }
+    }
+
+    /// Runs a single streaming session. Returns `Ok(())` if the session was terminated because of a stop signal.
+    async fn run_session(
+        &self,
+        mut socket: Socket,
+        stop_receiver: &mut watch::Receiver<bool>,
+    ) -> anyhow::Result<()> {
+        // L1 batch for which a proof was submitted, but not acknowledged yet.
+        let mut pending_proof = None::<L1BatchNumber>;
+        let mut next_ping_at = Instant::now() + Self::PING_INTERVAL;
+        let mut last_received_at = Instant::now();
+
+        loop {
+            if pending_proof.is_none() {
+                let next_request =
+                    PeriodicApi::<SubmitProofRequest>::get_next_request(&self.proof_submitter)
+                        .await;
+                if let Some((l1_batch_number, request)) = next_request {
+                    tracing::info!(
+                        "Submitting proof for L1 batch #{l1_batch_number} via proof stream"
+                    );
+                    let message = GatewayStreamMessage::SubmitProof {
+                        l1_batch_number,
+                        request,
+                    };
+                    Self::send(&mut socket, &message).await?;
+                    pending_proof = Some(l1_batch_number);
+                }
+            }
+
+            tokio::select! {
+                // Messages from the server are prioritized, so that the read timeout doesn't fire
+                // if a message was received while the client was busy.
+                biased;
+
+                _ = stop_receiver.changed() => {
+                    socket.close(None).await.ok();
+                    return Ok(());
+                }
+                message = socket.next() => {
+                    let message = message.context("proof stream closed by server")?;
+                    last_received_at = Instant::now();
+                    let message = match message.context("failed receiving message")? {
+                        Message::Text(text) => serde_json::from_str(&text),
+                        Message::Binary(bytes) => serde_json::from_slice(&bytes),
+                        Message::Close(_) => anyhow::bail!("proof stream closed by server"),
+                        _ => continue,
+                    };
+                    match message.context("failed parsing message")? {
+                        ServerStreamMessage::Job(data) => {
+                            let l1_batch_number = data.l1_batch_number;
+                            let response = ProofGenerationDataResponse::Success(Some(data));
+                            PeriodicApi::<ProofGenerationDataRequest>::handle_response(
+                                &self.proof_gen_data_fetcher,
+                                (),
+                                response,
+                            )
+                            .await;
+                            Self::send(&mut socket, &GatewayStreamMessage::JobAck { l1_batch_number }).await?;
+                        }
+                        ServerStreamMessage::ProofAck { l1_batch_number, response } => {
+                            anyhow::ensure!(
+                                pending_proof == Some(l1_batch_number),
+                                "unexpected ack for L1 batch #{l1_batch_number}; pending proof: {pending_proof:?}"
+                            );
+                            pending_proof = None;
+                            PeriodicApi::<SubmitProofRequest>::handle_response(
+                                &self.proof_submitter,
+                                l1_batch_number,
+                                response,
+                            )
+                            .await;
+                        }
+                    }
+                }
+                () = sleep_until(last_received_at + Self::READ_TIMEOUT) => {
+                    anyhow::bail!(
+                        "no messages received from server in {:?}; pending proof: {pending_proof:?}",
+                        Self::READ_TIMEOUT
+                    );
+                }
+                () = sleep_until(next_ping_at) => {
+                    next_ping_at = Instant::now() + Self::PING_INTERVAL;
+                    socket
+                        .send(Message::Ping(vec![]))
+                        .await
+                        .context("failed sending ping")?;
+                }
+                // Re-check whether there are proofs to submit.
+                _ = sleep(self.proof_submitter.poll_duration), if pending_proof.is_none() => {}
+            }
+        }
+    }
+
+    async fn send(socket: &mut Socket, message: &GatewayStreamMessage) -> anyhow::Result<()> {
+        let message = serde_json::to_string(message).context("failed serializing message")?;
+        socket
+            .send(Message::Text(message))
+            .await
+            .context("failed sending message")
+    }
+}
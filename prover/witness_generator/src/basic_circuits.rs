@@ -8,7 +8,7 @@ use std::{
 use anyhow::Context as _;
 use async_trait::async_trait;
 use circuit_definitions::{
-    circuit_definitions::base_layer::ZkSyncBaseLayerStorage,
+    circuit_definitions::base_layer::{ZkSyncBaseLayerCircuit, ZkSyncBaseLayerStorage},
     encodings::recursion_request::RecursionQueueSimulator,
     zkevm_circuits::fsm_input_output::ClosedFormInputCompactFormWitness,
 };
@@ -20,7 +20,7 @@ use tracing::Instrument;
 use zkevm_test_harness::geometry_config::get_geometry_config;
 use zksync_config::configs::FriWitnessGeneratorConfig;
 use zksync_dal::{Core, CoreDal};
-use zksync_object_store::{ObjectStore, ObjectStoreFactory};
+use zksync_object_store::{ObjectStore, ObjectStoreFactory, StoredObject};
 use zksync_prover_fri_types::{
     circuit_definitions::{
         boojum::{
@@ -32,8 +32,8 @@ use zksync_prover_fri_types::{
         },
     },
     get_current_pod_name,
-    keys::ClosedFormInputKey,
-    AuxOutputWitnessWrapper,
+    keys::{ClosedFormInputKey, FriCircuitKey},
+    AuxOutputWitnessWrapper, CircuitWrapper,
 };
 use zksync_prover_fri_utils::get_recursive_layer_circuit_id_for_base_layer;
 use zksync_prover_interface::inputs::{BasicCircuitWitnessGeneratorInput, PrepareBasicCircuitsJob};
@@ -50,10 +50,11 @@ use zksync_utils::{bytes_to_chunks, h256_to_u256, u256_to_h256};
 use crate::{
     metrics::WITNESS_GENERATOR_METRICS,
     precalculated_merkle_paths_provider::PrecalculatedMerklePathsProvider,
+    spill::{artifact_buffer, remove_stale_spill_dirs, BufferBudgets},
     storage_oracle::StorageOracle,
     utils::{
-        expand_bootloader_contents, save_circuit, ClosedFormInputWrapper,
-        SchedulerPartialInputWrapper, KZG_TRUSTED_SETUP_FILE,
+        expand_bootloader_contents, ClosedFormInputWrapper, SchedulerPartialInputWrapper,
+        KZG_TRUSTED_SETUP_FILE,
     },
 };
 
@@ -101,6 +102,9 @@ impl BasicWitnessGenerator {
         prover_connection_pool: ConnectionPool<Prover>,
         protocol_version: ProtocolVersionId,
     ) -> Self {
+        if let Err(err) = remove_stale_spill_dirs(config.spill_dir()) {
+            tracing::warn!("Failed removing stale spilled artifacts: {err:#}");
+        }
         Self {
             config: Arc::new(config),
             object_store: store_factory.create_store().await,
@@ -114,6 +118,7 @@ impl BasicWitnessGenerator {
     async fn process_job_impl(
         object_store: Arc<dyn ObjectStore>,
         connection_pool: ConnectionPool<Core>,
+        config: Arc<FriWitnessGeneratorConfig>,
         basic_job: BasicWitnessGeneratorJob,
         started_at: Instant,
     ) -> Option<BasicCircuitArtifacts> {
@@ -133,6 +138,7 @@ impl BasicWitnessGenerator {
             process_basic_circuits_job(
                 &*object_store,
                 connection_pool,
+                &config,
                 started_at,
                 block_number,
                 job,
@@ -201,10 +207,11 @@ impl JobProcessor for BasicWitnessGenerator {
     ) -> tokio::task::JoinHandle<anyhow::Result<Option<BasicCircuitArtifacts>>> {
         let object_store = Arc::clone(&self.object_store);
         let connection_pool = self.connection_pool.clone();
+        let config = self.config.clone();
         tokio::spawn(async move {
             let block_number = job.block_number;
             Ok(
-                Self::process_job_impl(object_store, connection_pool, job, started_at)
+                Self::process_job_impl(object_store, connection_pool, config, job, started_at)
                     .instrument(tracing::info_span!("basic_circuit", %block_number))
                     .await,
             )
@@ -273,6 +280,7 @@ impl JobProcessor for BasicWitnessGenerator {
 async fn process_basic_circuits_job(
     object_store: &dyn ObjectStore,
     connection_pool: ConnectionPool<Core>,
+    config: &FriWitnessGeneratorConfig,
     started_at: Instant,
     block_number: L1BatchNumber,
     job: PrepareBasicCircuitsJob,
@@ -284,6 +292,7 @@ async fn process_basic_circuits_job(
         block_number,
         object_store,
         connection_pool,
+        config,
         witness_gen_input,
         eip_4844_blobs,
     )
@@ -382,13 +391,34 @@ async fn save_scheduler_artifacts(
     object_store.put(block_number, &wrapper).await.unwrap()
 }
 
-async fn save_recursion_queue(
+/// Serializes a basic circuit. Returns the circuit ID, object store key and the serialized circuit.
+fn serialize_circuit(
+    block_number: L1BatchNumber,
+    circuit: ZkSyncBaseLayerCircuit,
+    sequence_number: usize,
+) -> (u8, String, Vec<u8>) {
+    let circuit_id = circuit.numeric_circuit_type();
+    let circuit_key = FriCircuitKey {
+        block_number,
+        sequence_number,
+        circuit_id,
+        aggregation_round: AggregationRound::BasicCircuits,
+        depth: 0,
+    };
+    let key = CircuitWrapper::encode_key(circuit_key);
+    let bytes = CircuitWrapper::Base(circuit)
+        .serialize()
+        .expect("failed serializing circuit");
+    (circuit_id, key, bytes)
+}
+
+/// Serializes a recursion queue. Returns the object store key, the number of basic circuits and the serialized queue.
+fn serialize_recursion_queue(
     block_number: L1BatchNumber,
     circuit_id: u8,
     recursion_queue_simulator: RecursionQueueSimulator<GoldilocksField>,
     closed_form_inputs: &[ClosedFormInputCompactFormWitness<GoldilocksField>],
-    object_store: &dyn ObjectStore,
-) -> (u8, String, usize) {
+) -> (String, usize, Vec<u8>) {
     let key = ClosedFormInputKey {
         block_number,
         circuit_id,
@@ -399,8 +429,14 @@ async fn save_recursion_queue(
         .map(|x| ZkSyncBaseLayerStorage::from_inner(circuit_id, x.clone()))
         .collect();
     let wrapper = ClosedFormInputWrapper(closed_form_inputs, recursion_queue_simulator);
-    let blob_url = object_store.put(key, &wrapper).await.unwrap();
-    (circuit_id, blob_url, basic_circuit_count)
+    let bytes = wrapper
+        .serialize()
+        .expect("failed serializing recursion queue");
+    (
+        ClosedFormInputWrapper::encode_key(key),
+        basic_circuit_count,
+        bytes,
+    )
 }
 
 // If making changes to this method, consider moving this logic to the DAL layer and make
@@ -450,6 +486,7 @@ async fn generate_witness(
     block_number: L1BatchNumber,
     object_store: &dyn ObjectStore,
     connection_pool: ConnectionPool<Core>,
+    config: &FriWitnessGeneratorConfig,
     input: BasicCircuitWitnessGeneratorInput,
     eip_4844_blobs: Eip4844Blobs,
 ) -> (
@@ -557,12 +594,22 @@ async fn generate_witness(
         hasher.finish()
     );
 
+    // Generated artifacts are buffered, so that witness generation isn't blocked on uploads. Artifacts
+    // not fitting into the memory budget are spilled to the local disk.
+    let budgets = BufferBudgets {
+        memory: config.artifacts_memory_budget_bytes(),
+        disk: config.artifacts_disk_budget_bytes(),
+    };
+    let (artifact_sender, mut artifact_receiver) = artifact_buffer(
+        AggregationRound::BasicCircuits.into(),
+        budgets,
+        config.spill_dir(),
+    )
+    .expect("failed creating buffer for generated artifacts");
+
     // The following part is CPU-heavy, so we move it to a separate thread.
     let rt_handle = tokio::runtime::Handle::current();
 
-    let (circuit_sender, mut circuit_receiver) = tokio::sync::mpsc::channel(1);
-    let (queue_sender, mut queue_receiver) = tokio::sync::mpsc::channel(1);
-
     let make_circuits = tokio::task::spawn_blocking(move || {
         let connection = rt_handle.block_on(connection_pool.connection()).unwrap();
 
@@ -582,6 +629,11 @@ async fn generate_witness(
             .to_str()
             .expect("Path to KZG trusted setup is not a UTF-8 string");
 
+        let mut circuit_urls = vec![];
+        let mut recursion_urls = vec![];
+        let mut circuits_present = HashSet::<u8>::new();
+        let artifact_sender = &artifact_sender;
+
         let (scheduler_witness, block_witness) = zkevm_test_harness::external_calls::run(
             Address::zero(),
             BOOTLOADER_ADDRESS,
@@ -600,38 +652,54 @@ async fn generate_witness(
             path,
             eip_4844_blobs.blobs(),
             |circuit| {
-                circuit_sender.blocking_send(circuit).unwrap();
+                let (circuit_id, key, bytes) =
+                    serialize_circuit(block_number, circuit, circuit_urls.len());
+                artifact_sender
+                    .send(CircuitWrapper::BUCKET, key.clone(), bytes)
+                    .expect("failed buffering circuit");
+                circuits_present.insert(circuit_id);
+                circuit_urls.push((circuit_id, key));
+            },
+            |circuit_id, queue, inputs| {
+                let circuit_id = circuit_id as u8;
+                let (key, basic_circuit_count, bytes) =
+                    serialize_recursion_queue(block_number, circuit_id, queue, &inputs);
+                artifact_sender
+                    .send(ClosedFormInputWrapper::BUCKET, key.clone(), bytes)
+                    .expect("failed buffering recursion queue");
+                recursion_urls.push((circuit_id, key, basic_circuit_count));
             },
-            |a, b, c| queue_sender.blocking_send((a as u8, b, c)).unwrap(),
         );
-        (scheduler_witness, block_witness)
+        (
+            scheduler_witness,
+            block_witness,
+            circuit_urls,
+            recursion_urls,
+            circuits_present,
+        )
     });
 
-    let mut circuit_urls = vec![];
-    let mut recursion_urls = vec![];
-
-    let mut circuits_present = HashSet::<u8>::new();
-
-    let save_circuits = async {
-        loop {
-            tokio::select! {
-                Some(circuit) = circuit_receiver.recv() => {
-                    circuits_present.insert(circuit.numeric_circuit_type());
-                    circuit_urls.push(
-                        save_circuit(block_number, circuit, circuit_urls.len(), object_store).await,
-                    );
-                }
-                Some((circuit_id, queue, inputs)) = queue_receiver.recv() => recursion_urls.push(
-                    save_recursion_queue(block_number, circuit_id, queue, &inputs, object_store)
-                        .await,
-                ),
-                else => break,
-            };
+    let upload_artifacts = async {
+        while let Some(artifact) = artifact_receiver
+            .recv()
+            .await
+            .expect("failed receiving buffered artifact")
+        {
+            object_store
+                .put_raw(artifact.bucket, &artifact.key, artifact.bytes)
+                .await
+                .unwrap();
         }
     };
 
-    let (witnesses, ()) = tokio::join!(make_circuits, save_circuits);
-    let (mut scheduler_witness, block_aux_witness) = witnesses.unwrap();
+    let (witnesses, ()) = tokio::join!(make_circuits, upload_artifacts);
+    let (
+        mut scheduler_witness,
+        block_aux_witness,
+        circuit_urls,
+        mut recursion_urls,
+        circuits_present,
+    ) = witnesses.unwrap();
 
     recursion_urls.retain(|(circuit_id, _, _)| circuits_present.contains(circuit_id));
 
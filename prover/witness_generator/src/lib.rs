@@ -6,6 +6,7 @@ pub mod node_aggregation;
 pub mod precalculated_merkle_paths_provider;
 pub mod recursion_tip;
 pub mod scheduler;
+mod spill;
 mod storage_oracle;
 pub mod utils;
 
@@ -1,6 +1,6 @@
 use std::time::Duration;
 
-use vise::{Buckets, Family, Gauge, Histogram, LabeledFamily, Metrics};
+use vise::{Buckets, Counter, Family, Gauge, Histogram, LabeledFamily, Metrics, Unit};
 use zksync_prover_fri_utils::metrics::StageLabel;
 
 #[derive(Debug, Metrics)]
@@ -14,6 +14,14 @@ pub(crate) struct WitnessGeneratorMetrics {
     pub witness_generation_time: Family<StageLabel, Histogram<Duration>>,
     #[metrics(buckets = Buckets::LATENCIES)]
     pub blob_save_time: Family<StageLabel, Histogram<Duration>>,
+    /// Size of generated artifacts buffered in memory before being uploaded to the object store.
+    #[metrics(unit = Unit::Bytes)]
+    pub buffered_artifacts_size: Family<StageLabel, Gauge<u64>>,
+    /// Total size of generated artifacts spilled to the local disk because the memory budget was exceeded.
+    #[metrics(unit = Unit::Bytes)]
+    pub spilled_artifacts_size: Family<StageLabel, Counter>,
+    /// Number of generated artifacts spilled to the local disk because the memory budget was exceeded.
+    pub spilled_artifacts: Family<StageLabel, Counter>,
 }
 
 #[vise::register]
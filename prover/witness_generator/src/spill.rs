@@ -0,0 +1,421 @@
+//! Memory-bounded buffer for generated artifacts awaiting upload to the object store.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Condvar, Mutex, MutexGuard,
+    },
+};
+
+use anyhow::Context as _;
+use tempfile::TempDir;
+use tokio::sync::mpsc;
+use zksync_object_store::Bucket;
+use zksync_prover_fri_utils::metrics::StageLabel;
+
+use crate::metrics::WITNESS_GENERATOR_METRICS;
+
+/// Prefix of the directories with spilled artifacts.
+const SPILL_DIR_PREFIX: &str = "witness-gen-spill-";
+
+/// Serialized artifact to be uploaded to the object store.
+#[derive(Debug)]
+pub(crate) struct Artifact {
+    pub bucket: Bucket,
+    pub key: String,
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Debug)]
+enum ArtifactData {
+    InMemory(Vec<u8>),
+    Spilled { path: PathBuf, len: usize },
+}
+
+#[derive(Debug)]
+struct BufferedArtifact {
+    bucket: Bucket,
+    key: String,
+    data: ArtifactData,
+}
+
+/// Budgets for the artifact buffer.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BufferBudgets {
+    /// Maximum total size of artifacts buffered in memory.
+    pub memory: usize,
+    /// Maximum total size of artifacts spilled to the local disk.
+    pub disk: usize,
+}
+
+#[derive(Debug, Default)]
+struct BufferUsage {
+    in_memory_bytes: usize,
+    spilled_bytes: usize,
+    is_receiver_dropped: bool,
+}
+
+impl BufferUsage {
+    fn is_empty(&self) -> bool {
+        self.in_memory_bytes == 0 && self.spilled_bytes == 0
+    }
+}
+
+#[derive(Debug)]
+struct BufferState {
+    stage: StageLabel,
+    budgets: BufferBudgets,
+    usage: Mutex<BufferUsage>,
+    /// Notified each time buffer usage decreases, or the receiver is dropped.
+    usage_decreased: Condvar,
+    next_spill_index: AtomicU64,
+    /// Directory with spilled artifacts. Removed together with all remaining artifacts once both the sender
+    /// and receiver are dropped, e.g. if witness generation fails.
+    spill_dir: TempDir,
+}
+
+impl BufferState {
+    fn lock_usage(&self) -> MutexGuard<'_, BufferUsage> {
+        self.usage.lock().expect("artifact buffer is poisoned")
+    }
+
+    fn report_usage(&self, usage: &BufferUsage) {
+        WITNESS_GENERATOR_METRICS.buffered_artifacts_size[&self.stage]
+            .set(usage.in_memory_bytes as u64);
+    }
+
+    fn release(&self, data: &ArtifactData) {
+        let mut usage = self.lock_usage();
+        match data {
+            ArtifactData::InMemory(bytes) => usage.in_memory_bytes -= bytes.len(),
+            ArtifactData::Spilled { len, .. } => usage.spilled_bytes -= *len,
+        }
+        self.report_usage(&usage);
+        drop(usage);
+        self.usage_decreased.notify_all();
+    }
+}
+
+/// Removes spill directories left by previous runs, e.g. if the witness generator was killed by the OOM killer.
+/// Must be called before any artifact buffers are created in `spill_dir_parent`. The spill directory must
+/// not be shared among multiple witness generator processes.
+pub(crate) fn remove_stale_spill_dirs(spill_dir_parent: Option<&Path>) -> anyhow::Result<()> {
+    let parent = spill_dir_parent.map_or_else(std::env::temp_dir, Path::to_owned);
+    let entries = match fs::read_dir(&parent) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed listing spill directory {parent:?}"))
+        }
+    };
+    for entry in entries {
+        let entry = entry.with_context(|| format!("failed listing spill directory {parent:?}"))?;
+        let is_spill_dir = entry
+            .file_name()
+            .to_str()
+            .map_or(false, |name| name.starts_with(SPILL_DIR_PREFIX));
+        if is_spill_dir && entry.file_type().map_or(false, |ty| ty.is_dir()) {
+            let path = entry.path();
+            tracing::info!("Removing stale directory with spilled artifacts {path:?}");
+            fs::remove_dir_all(&path)
+                .with_context(|| format!("failed removing stale spill directory {path:?}"))?;
+        }
+    }
+    Ok(())
+}
+
+/// Creates a buffer for artifacts with the specified budgets. Artifacts not fitting into the memory budget
+/// are spilled to a temporary directory created in `spill_dir_parent` (or in the system temporary directory
+/// if not specified), and are read back once they are received.
+///
+/// If both budgets are exhausted, [`ArtifactSender::send()`] blocks until the receiver catches up, so that
+/// artifact producers (e.g., witness generation) are only blocked on uploads if they get too far ahead.
+pub(crate) fn artifact_buffer(
+    stage: StageLabel,
+    budgets: BufferBudgets,
+    spill_dir_parent: Option<&Path>,
+) -> anyhow::Result<(ArtifactSender, ArtifactReceiver)> {
+    let mut spill_dir = tempfile::Builder::new();
+    spill_dir.prefix(SPILL_DIR_PREFIX);
+    let spill_dir = match spill_dir_parent {
+        Some(parent) => spill_dir.tempdir_in(parent),
+        None => spill_dir.tempdir(),
+    };
+    let spill_dir = spill_dir.context("failed creating directory for spilled artifacts")?;
+
+    let state = Arc::new(BufferState {
+        stage,
+        budgets,
+        usage: Mutex::default(),
+        usage_decreased: Condvar::new(),
+        next_spill_index: AtomicU64::new(0),
+        spill_dir,
+    });
+    // The channel is effectively bounded by the buffer budgets.
+    let (sender, receiver) = mpsc::unbounded_channel();
+    let sender = ArtifactSender {
+        state: state.clone(),
+        sender,
+    };
+    Ok((sender, ArtifactReceiver { state, receiver }))
+}
+
+#[derive(Debug)]
+pub(crate) struct ArtifactSender {
+    state: Arc<BufferState>,
+    sender: mpsc::UnboundedSender<BufferedArtifact>,
+}
+
+impl ArtifactSender {
+    /// Buffers an artifact. If the artifact doesn't fit into the memory budget, it's written to the local disk.
+    /// If it doesn't fit into the disk budget either (or cannot be written to disk), blocks until the receiver
+    /// frees the memory budget. Since this performs blocking I/O, it should only be called from blocking threads.
+    pub fn send(&self, bucket: Bucket, key: String, bytes: Vec<u8>) -> anyhow::Result<()> {
+        let state = &*self.state;
+        let budgets = state.budgets;
+        let len = bytes.len();
+        let mut is_spilling_possible = true;
+        let mut usage = state.lock_usage();
+        let data = loop {
+            anyhow::ensure!(!usage.is_receiver_dropped, "artifact receiver dropped");
+
+            let fits_in_memory = usage.in_memory_bytes + len <= budgets.memory;
+            let fits_on_disk = is_spilling_possible && usage.spilled_bytes + len <= budgets.disk;
+            // An artifact that cannot be buffered otherwise is kept in memory if the buffer is empty;
+            // otherwise, it would never be sent.
+            if fits_in_memory || (usage.is_empty() && !fits_on_disk) {
+                usage.in_memory_bytes += len;
+                state.report_usage(&usage);
+                break ArtifactData::InMemory(bytes);
+            }
+
+            if fits_on_disk {
+                usage.spilled_bytes += len;
+                drop(usage);
+                match self.spill(&key, &bytes) {
+                    Ok(path) => break ArtifactData::Spilled { path, len },
+                    Err(err) => {
+                        tracing::warn!(
+                            "{err:#}; waiting for the memory budget to buffer artifact `{key}`"
+                        );
+                        is_spilling_possible = false;
+                        usage = state.lock_usage();
+                        usage.spilled_bytes -= len;
+                        continue;
+                    }
+                }
+            }
+
+            tracing::debug!(
+                "Buffer budgets ({budgets:?}) are exhausted; waiting for uploads to buffer artifact `{key}`"
+            );
+            usage = state
+                .usage_decreased
+                .wait(usage)
+                .expect("artifact buffer is poisoned");
+        };
+
+        let artifact = BufferedArtifact { bucket, key, data };
+        self.sender.send(artifact).map_err(|err| {
+            state.release(&err.0.data);
+            anyhow::anyhow!("artifact receiver dropped")
+        })
+    }
+
+    fn spill(&self, key: &str, bytes: &[u8]) -> anyhow::Result<PathBuf> {
+        let state = &*self.state;
+        let spill_index = state.next_spill_index.fetch_add(1, Ordering::Relaxed);
+        let path = state.spill_dir.path().join(format!("{spill_index}.bin"));
+        if let Err(err) = fs::write(&path, bytes) {
+            fs::remove_file(&path).ok();
+            return Err(err)
+                .with_context(|| format!("failed spilling artifact `{key}` to {path:?}"));
+        }
+        tracing::debug!(
+            "Spilled artifact `{key}` ({} bytes) to {path:?} since memory budget of {} bytes is exceeded",
+            bytes.len(),
+            state.budgets.memory
+        );
+        WITNESS_GENERATOR_METRICS.spilled_artifacts[&state.stage].inc();
+        WITNESS_GENERATOR_METRICS.spilled_artifacts_size[&state.stage].inc_by(bytes.len() as u64);
+        Ok(path)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct ArtifactReceiver {
+    state: Arc<BufferState>,
+    receiver: mpsc::UnboundedReceiver<BufferedArtifact>,
+}
+
+impl Drop for ArtifactReceiver {
+    fn drop(&mut self) {
+        // Unblock the sender if it waits for the buffer budget.
+        self.state.lock_usage().is_receiver_dropped = true;
+        self.state.usage_decreased.notify_all();
+    }
+}
+
+impl ArtifactReceiver {
+    /// Receives the next artifact in the order they were sent. Returns `None` once the sender is dropped
+    /// and all artifacts are received.
+    pub async fn recv(&mut self) -> anyhow::Result<Option<Artifact>> {
+        let Some(artifact) = self.receiver.recv().await else {
+            return Ok(None);
+        };
+        if let ArtifactData::InMemory(_) = &artifact.data {
+            // The artifact is moved out of the buffer, so we release its memory budget.
+            self.state.release(&artifact.data);
+        }
+        let bytes = match artifact.data {
+            ArtifactData::InMemory(bytes) => bytes,
+            ArtifactData::Spilled { path, len } => {
+                let bytes = tokio::fs::read(&path)
+                    .await
+                    .with_context(|| format!("failed reading spilled artifact from {path:?}"))?;
+                anyhow::ensure!(
+                    bytes.len() == len,
+                    "spilled artifact at {path:?} has unexpected size: expected {len} bytes, got {}",
+                    bytes.len()
+                );
+                tokio::fs::remove_file(&path)
+                    .await
+                    .with_context(|| format!("failed removing spilled artifact at {path:?}"))?;
+                // Disk budget is released only after the artifact is removed, so that it's never exceeded.
+                self.state.release(&ArtifactData::Spilled { path, len });
+                bytes
+            }
+        };
+        Ok(Some(Artifact {
+            bucket: artifact.bucket,
+            key: artifact.key,
+            bytes,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use zksync_types::basic_fri_types::AggregationRound;
+
+    use super::*;
+
+    fn stage() -> StageLabel {
+        AggregationRound::BasicCircuits.into()
+    }
+
+    #[tokio::test]
+    async fn spilling_artifacts_exceeding_memory_budget() {
+        let temp_dir = TempDir::new().unwrap();
+        let budgets = BufferBudgets {
+            memory: 100,
+            disk: 1_000,
+        };
+        let (sender, mut receiver) =
+            artifact_buffer(stage(), budgets, Some(temp_dir.path())).unwrap();
+
+        let sizes = [60, 30, 50, 10, 20];
+        for (i, size) in sizes.into_iter().enumerate() {
+            let key = format!("artifact_{i}");
+            sender
+                .send(Bucket::ProverJobsFri, key, vec![i as u8; size])
+                .unwrap();
+        }
+        // Artifacts #2 and #4 should be spilled.
+        let spill_dir = receiver.state.spill_dir.path().to_owned();
+        assert_eq!(fs::read_dir(&spill_dir).unwrap().count(), 2);
+        {
+            let usage = receiver.state.lock_usage();
+            assert_eq!(usage.in_memory_bytes, 100);
+            assert_eq!(usage.spilled_bytes, 70);
+        }
+
+        for (i, size) in sizes.into_iter().enumerate() {
+            let artifact = receiver.recv().await.unwrap().unwrap();
+            assert_eq!(artifact.key, format!("artifact_{i}"));
+            assert_eq!(artifact.bytes, vec![i as u8; size]);
+        }
+        assert_eq!(fs::read_dir(&spill_dir).unwrap().count(), 0);
+        assert!(receiver.state.lock_usage().is_empty());
+
+        sender
+            .send(Bucket::ProverJobsFri, "spilled".to_owned(), vec![0; 200])
+            .unwrap();
+        drop(sender);
+        drop(receiver);
+        // The spill directory should be removed together with the remaining artifacts.
+        assert!(!spill_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn sender_is_blocked_if_budgets_are_exhausted() {
+        let temp_dir = TempDir::new().unwrap();
+        let budgets = BufferBudgets {
+            memory: 100,
+            disk: 100,
+        };
+        let (sender, mut receiver) =
+            artifact_buffer(stage(), budgets, Some(temp_dir.path())).unwrap();
+        sender
+            .send(Bucket::ProverJobsFri, "memory".to_owned(), vec![0; 100])
+            .unwrap();
+        sender
+            .send(Bucket::ProverJobsFri, "disk".to_owned(), vec![1; 100])
+            .unwrap();
+
+        let send_task = tokio::task::spawn_blocking(move || {
+            sender.send(Bucket::ProverJobsFri, "blocked".to_owned(), vec![2; 50])?;
+            // An artifact exceeding both budgets is buffered once the buffer is empty.
+            sender.send(Bucket::ProverJobsFri, "huge".to_owned(), vec![3; 500])
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(!send_task.is_finished());
+
+        for expected_key in ["memory", "disk", "blocked", "huge"] {
+            let artifact = receiver.recv().await.unwrap().unwrap();
+            assert_eq!(artifact.key, expected_key);
+        }
+        send_task.await.unwrap().unwrap();
+        assert!(receiver.recv().await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn blocked_sender_errors_if_receiver_is_dropped() {
+        let temp_dir = TempDir::new().unwrap();
+        let budgets = BufferBudgets {
+            memory: 100,
+            disk: 0,
+        };
+        let (sender, receiver) = artifact_buffer(stage(), budgets, Some(temp_dir.path())).unwrap();
+        sender
+            .send(Bucket::ProverJobsFri, "memory".to_owned(), vec![0; 100])
+            .unwrap();
+        let send_task = tokio::task::spawn_blocking(move || {
+            sender.send(Bucket::ProverJobsFri, "blocked".to_owned(), vec![1; 50])
+        });
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        drop(receiver);
+        let err = send_task.await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("receiver dropped"), "{err}");
+    }
+
+    #[test]
+    fn removing_stale_spill_dirs() {
+        let temp_dir = TempDir::new().unwrap();
+        let stale_dir = temp_dir.path().join(format!("{SPILL_DIR_PREFIX}stale"));
+        fs::create_dir(&stale_dir).unwrap();
+        fs::write(stale_dir.join("0.bin"), [1, 2, 3]).unwrap();
+        let other_dir = temp_dir.path().join("other");
+        fs::create_dir(&other_dir).unwrap();
+
+        remove_stale_spill_dirs(Some(temp_dir.path())).unwrap();
+        assert!(!stale_dir.exists());
+        assert!(other_dir.exists());
+
+        remove_stale_spill_dirs(Some(&temp_dir.path().join("missing"))).unwrap();
+    }
+}
@@ -30,6 +30,9 @@ async fn test_leaf_witness_gen() {
             file_backed_base_path: "./tests/data/leaf/".to_owned(),
         },
         max_retries: 5,
+        integrity_envelope: false,
+        encryption_keys_file_path: None,
+        allow_plaintext_reads: false,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
@@ -70,6 +73,9 @@ async fn test_node_witness_gen() {
             file_backed_base_path: "./tests/data/node/".to_owned(),
         },
         max_retries: 5,
+        integrity_envelope: false,
+        encryption_keys_file_path: None,
+        allow_plaintext_reads: false,
     };
     let object_store = ObjectStoreFactory::new(object_store_config)
         .create_store()
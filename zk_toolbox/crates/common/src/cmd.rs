@@ -1,4 +1,13 @@
-use anyhow::bail;
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, BufRead, BufReader, Read, Write},
+    path::Path,
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use anyhow::{bail, Context};
 use console::style;
 
 use crate::{
@@ -35,6 +44,45 @@ impl<'a> Cmd<'a> {
         Ok(())
     }
 
+    /// Run the command streaming its output to the console and appending it to `log_file`,
+    /// so that it can be inspected after the command finishes.
+    pub fn run_with_log_file(self, log_file: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = log_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(log_file)
+            .with_context(|| format!("Failed to open log file {log_file:?}"))?;
+        let file = Arc::new(Mutex::new(file));
+
+        if global_config().verbose {
+            logger::debug(format!("Running: {}", self.inner));
+        }
+        let description = self.inner.to_string();
+        let mut command: Command = self.inner.into();
+        let mut child = command
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .with_context(|| format!("Failed to spawn: {description}"))?;
+
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr = child.stderr.take().expect("stderr is piped");
+        let stdout_thread = tee_to_file(stdout, io::stdout(), file.clone());
+        let stderr_thread = tee_to_file(stderr, io::stderr(), file);
+
+        let status = child.wait()?;
+        for thread in [stdout_thread, stderr_thread] {
+            thread.join().expect("log copying thread panicked")?;
+        }
+        if !status.success() {
+            bail!("Command failed to run: {description}");
+        }
+        Ok(())
+    }
+
     /// Run the command and capture its output, logging the command
     /// and its output if verbose selected.
     fn run_cmd(&mut self) -> anyhow::Result<()> {
@@ -74,6 +122,26 @@ impl<'a> Cmd<'a> {
     }
 }
 
+/// Copies lines from `source` both to `console` and `file`.
+fn tee_to_file(
+    source: impl Read + Send + 'static,
+    mut console: impl Write + Send + 'static,
+    file: Arc<Mutex<File>>,
+) -> thread::JoinHandle<io::Result<()>> {
+    thread::spawn(move || {
+        let mut reader = BufReader::new(source);
+        let mut line = Vec::new();
+        while reader.read_until(b'\n', &mut line)? > 0 {
+            console.write_all(&line)?;
+            file.lock()
+                .expect("log file lock is poisoned")
+                .write_all(&line)?;
+            line.clear();
+        }
+        Ok(())
+    })
+}
+
 fn log_output(output: &std::process::Output) -> String {
     let (status, stdout, stderr) = get_indented_output(output, 4, 120);
     let status_header = style("  Status:").bold();
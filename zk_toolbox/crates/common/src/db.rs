@@ -1,13 +1,14 @@
 use std::{collections::HashMap, path::PathBuf};
 
-use crate::{config::global_config, logger};
 use sqlx::{
-    migrate::{Migrate, MigrateError, Migrator},
+    migrate::{AppliedMigration, Migrate, MigrateError, Migrator},
     Connection, PgConnection,
 };
 use url::Url;
 use xshell::Shell;
 
+use crate::{config::global_config, logger};
+
 pub async fn init_db(db_url: &Url, name: &str) -> anyhow::Result<()> {
     // Connect to the database.
     let mut connection = PgConnection::connect(db_url.as_ref()).await?;
@@ -39,10 +40,7 @@ pub async fn migrate_db(
     // https://github.com/launchbadge/sqlx/blob/main/sqlx-cli/src/migrate.rs
     // Warrants a refactoring if this tool makes it to production.
 
-    if !shell.path_exists(&migrations_folder) {
-        anyhow::bail!("Migrations folder {migrations_folder:?} doesn't exist");
-    }
-    let migrator = Migrator::new(migrations_folder).await?;
+    let migrator = load_migrator(shell, migrations_folder).await?;
 
     let mut conn = PgConnection::connect(db_url).await?;
     conn.ensure_migrations_table().await?;
@@ -52,12 +50,7 @@ pub async fn migrate_db(
         anyhow::bail!(MigrateError::Dirty(version));
     }
 
-    let applied_migrations: HashMap<_, _> = conn
-        .list_applied_migrations()
-        .await?
-        .into_iter()
-        .map(|m| (m.version, m))
-        .collect();
+    let applied_migrations = list_applied_migrations(&mut conn).await?;
 
     if global_config().verbose {
         logger::debug("Migrations result:")
@@ -103,3 +96,107 @@ pub async fn migrate_db(
 
     Ok(())
 }
+
+async fn load_migrator(shell: &Shell, migrations_folder: PathBuf) -> anyhow::Result<Migrator> {
+    if !shell.path_exists(&migrations_folder) {
+        anyhow::bail!("Migrations folder {migrations_folder:?} doesn't exist");
+    }
+    Ok(Migrator::new(migrations_folder).await?)
+}
+
+async fn list_applied_migrations(
+    conn: &mut PgConnection,
+) -> anyhow::Result<HashMap<i64, AppliedMigration>> {
+    Ok(conn
+        .list_applied_migrations()
+        .await?
+        .into_iter()
+        .map(|m| (m.version, m))
+        .collect())
+}
+
+/// Migration listed by [`migrations_status()`].
+#[derive(Debug, Clone)]
+pub struct MigrationInfo {
+    pub version: i64,
+    pub description: String,
+}
+
+/// State of migrations for a database, as compared to a migrations folder.
+#[derive(Debug, Clone, Default)]
+pub struct MigrationsStatus {
+    /// Applied migrations, ordered by version.
+    pub applied: Vec<MigrationInfo>,
+    /// Migrations from the folder that are not applied yet, ordered by version.
+    pub pending: Vec<MigrationInfo>,
+    /// Version of the migration that failed to apply, if any. Such a migration blocks applying further migrations.
+    pub dirty_version: Option<i64>,
+    /// Applied migrations whose checksum differs from the checksum of the migration file.
+    pub checksum_mismatches: Vec<i64>,
+    /// Applied migrations missing from the folder (e.g., because the database was migrated using another branch).
+    pub unknown: Vec<i64>,
+}
+
+/// Returns the state of migrations for the database at `db_url` without modifying the database.
+pub async fn migrations_status(
+    shell: &Shell,
+    migrations_folder: PathBuf,
+    db_url: &str,
+) -> anyhow::Result<MigrationsStatus> {
+    let migrator = load_migrator(shell, migrations_folder).await?;
+    let mut conn = PgConnection::connect(db_url).await?;
+
+    // Unlike migrating, checking the status shouldn't create the migrations table.
+    let (has_migrations_table,): (bool,) =
+        sqlx::query_as("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+            .fetch_one(&mut conn)
+            .await?;
+    let mut status = MigrationsStatus::default();
+    let mut applied_migrations = if has_migrations_table {
+        status.dirty_version = conn.dirty_version().await?;
+        list_applied_migrations(&mut conn).await?
+    } else {
+        HashMap::new()
+    };
+    let _ = conn.close().await;
+
+    for migration in migrator.iter() {
+        if migration.migration_type.is_down_migration() {
+            continue;
+        }
+        let info = MigrationInfo {
+            version: migration.version,
+            description: migration.description.to_string(),
+        };
+        match applied_migrations.remove(&migration.version) {
+            Some(applied_migration) => {
+                if migration.checksum != applied_migration.checksum {
+                    status.checksum_mismatches.push(migration.version);
+                }
+                status.applied.push(info);
+            }
+            None => status.pending.push(info),
+        }
+    }
+    status.unknown = applied_migrations.into_keys().collect();
+    status
+        .applied
+        .sort_unstable_by_key(|migration| migration.version);
+    status
+        .pending
+        .sort_unstable_by_key(|migration| migration.version);
+    status.unknown.sort_unstable();
+    Ok(status)
+}
+
+/// Returns the size of the database `name` in bytes, or `None` if the database doesn't exist.
+pub async fn database_size(db_url: &Url, name: &str) -> anyhow::Result<Option<i64>> {
+    let mut connection = PgConnection::connect(db_url.as_ref()).await?;
+    let size: Option<(i64,)> =
+        sqlx::query_as("SELECT pg_database_size(datname) FROM pg_database WHERE datname = $1")
+            .bind(name)
+            .fetch_optional(&mut connection)
+            .await?;
+    let _ = connection.close().await;
+    Ok(size.map(|(size,)| size))
+}
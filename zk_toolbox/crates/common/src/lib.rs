@@ -7,11 +7,15 @@ pub mod files;
 pub mod forge;
 mod prerequisites;
 mod prompt;
+pub mod secrets;
 mod slugify;
 mod term;
 pub mod wallets;
 
-pub use prerequisites::check_prerequisites;
+pub use prerequisites::{
+    check_prerequisites, prerequisites_report, PrerequisiteCheck, PrerequisiteStatus,
+    PrerequisitesReport,
+};
 pub use prompt::{init_prompt_theme, Prompt, PromptConfirm, PromptSelect};
 pub use slugify::slugify;
 pub use term::{logger, spinner};
@@ -1,55 +1,211 @@
-use crate::{cmd::Cmd, logger};
-use xshell::{cmd, Shell};
+use std::fmt;
 
-const PREREQUISITES: [Prerequisite; 6] = [
+use serde::Serialize;
+use xshell::Shell;
+
+use crate::logger;
+
+const PREREQUISITES: [Prerequisite; 7] = [
     Prerequisite {
         name: "git",
+        version_args: &["--version"],
+        min_version: None,
         download_link: "https://git-scm.com/book/en/v2/Getting-Started-Installing-Git",
+        fix_hint: None,
     },
     Prerequisite {
         name: "docker",
+        version_args: &["--version"],
+        min_version: Some(Version::new(20, 10, 0)),
         download_link: "https://docs.docker.com/get-docker/",
+        fix_hint: None,
     },
     Prerequisite {
         name: "docker-compose",
+        version_args: &["--version"],
+        min_version: Some(Version::new(1, 29, 0)),
         download_link: "https://docs.docker.com/compose/install/",
+        fix_hint: None,
     },
     Prerequisite {
         name: "forge",
+        version_args: &["--version"],
+        min_version: Some(Version::new(0, 2, 0)),
         download_link: "https://book.getfoundry.sh/getting-started/installation",
+        fix_hint: Some("curl -L https://foundry.paradigm.xyz | bash && foundryup"),
     },
     Prerequisite {
         name: "cargo",
+        version_args: &["--version"],
+        min_version: Some(Version::new(1, 78, 0)),
         download_link: "https://doc.rust-lang.org/cargo/getting-started/installation.html",
+        fix_hint: Some("rustup update stable"),
+    },
+    Prerequisite {
+        name: "psql",
+        version_args: &["--version"],
+        min_version: Some(Version::new(14, 0, 0)),
+        download_link: "https://www.postgresql.org/download/",
+        fix_hint: None,
     },
     Prerequisite {
         name: "yarn",
+        version_args: &["--version"],
+        min_version: Some(Version::new(1, 22, 0)),
         download_link: "https://yarnpkg.com/getting-started/install",
+        fix_hint: Some("npm install --global yarn"),
     },
 ];
 
 struct Prerequisite {
     name: &'static str,
+    /// Arguments making the binary print its version.
+    version_args: &'static [&'static str],
+    min_version: Option<Version>,
     download_link: &'static str,
+    /// Command fixing an outdated installation. Missing binaries always point to `download_link`.
+    fix_hint: Option<&'static str>,
+}
+
+/// Semantic version of a prerequisite, as parsed from its `--version` output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Version {
+    major: u32,
+    minor: u32,
+    patch: u32,
+}
+
+impl Version {
+    const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// Extracts the first `major.minor[.patch]` occurrence from the version output.
+    fn parse_from_output(output: &str) -> Option<Self> {
+        output
+            .split(|c: char| !c.is_ascii_digit() && c != '.')
+            .find_map(|token| {
+                let mut parts = token.split('.').map(|part| part.parse::<u32>().ok());
+                let major = parts.next()??;
+                let minor = parts.next()??;
+                let patch = parts.next().flatten().unwrap_or(0);
+                Some(Self::new(major, minor, patch))
+            })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+impl Serialize for Version {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PrerequisiteStatus {
+    Ok,
+    Missing,
+    Outdated,
+    /// Binary is present, but its version couldn't be determined.
+    UnknownVersion,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrerequisiteCheck {
+    pub name: &'static str,
+    pub status: PrerequisiteStatus,
+    pub found_version: Option<Version>,
+    pub required_version: Option<Version>,
+    /// Actionable remediation for a failed check.
+    pub fix: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PrerequisitesReport {
+    pub os: &'static str,
+    pub arch: &'static str,
+    pub checks: Vec<PrerequisiteCheck>,
+    /// Non-fatal issues, e.g. with the host architecture.
+    pub warnings: Vec<String>,
+}
+
+impl PrerequisitesReport {
+    /// Returns `true` if all the prerequisites are installed. Unknown versions are tolerated.
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|check| {
+            matches!(
+                check.status,
+                PrerequisiteStatus::Ok | PrerequisiteStatus::UnknownVersion
+            )
+        })
+    }
+
+    fn failed_checks(&self) -> impl Iterator<Item = &PrerequisiteCheck> {
+        self.checks.iter().filter(|check| {
+            matches!(
+                check.status,
+                PrerequisiteStatus::Missing | PrerequisiteStatus::Outdated
+            )
+        })
+    }
+}
+
+/// Checks the presence and versions of all prerequisites, as well as the host architecture.
+pub fn prerequisites_report(shell: &Shell) -> PrerequisitesReport {
+    let checks = PREREQUISITES
+        .iter()
+        .map(|prerequisite| check_prerequisite(shell, prerequisite))
+        .collect();
+
+    PrerequisitesReport {
+        os: std::env::consts::OS,
+        arch: std::env::consts::ARCH,
+        checks,
+        warnings: architecture_warnings(shell),
+    }
 }
 
 pub fn check_prerequisites(shell: &Shell) {
-    let mut missing_prerequisites = vec![];
+    let report = prerequisites_report(shell);
 
-    for prerequisite in &PREREQUISITES {
-        if !check_prerequisite(shell, prerequisite.name) {
-            missing_prerequisites.push(prerequisite);
+    for warning in &report.warnings {
+        logger::warn(warning);
+    }
+    for check in &report.checks {
+        if check.status == PrerequisiteStatus::UnknownVersion {
+            logger::warn(format!(
+                "Could not determine the version of {}, skipping the version check",
+                check.name
+            ));
         }
     }
 
-    if !missing_prerequisites.is_empty() {
+    if !report.is_ok() {
         logger::error("Prerequisite check has failed");
         logger::error_note(
-            "The following prerequisites are missing",
-            &missing_prerequisites
-                .iter()
-                .map(|prerequisite| {
-                    format!("- {} ({})", prerequisite.name, prerequisite.download_link)
+            "The following prerequisites are missing or outdated",
+            &report
+                .failed_checks()
+                .map(|check| {
+                    let problem = match (check.status, check.found_version, check.required_version)
+                    {
+                        (PrerequisiteStatus::Outdated, Some(found), Some(required)) => {
+                            format!("version {found} is older than required {required}")
+                        }
+                        _ => "not installed".to_string(),
+                    };
+                    let fix = check.fix.as_deref().unwrap_or_default();
+                    format!("- {} is {problem}. Fix: {fix}", check.name)
                 })
                 .collect::<Vec<_>>()
                 .join("\n"),
@@ -59,6 +215,90 @@ pub fn check_prerequisites(shell: &Shell) {
     }
 }
 
-fn check_prerequisite(shell: &Shell, name: &str) -> bool {
-    Cmd::new(cmd!(shell, "which {name}")).run().is_ok()
+fn check_prerequisite(shell: &Shell, prerequisite: &Prerequisite) -> PrerequisiteCheck {
+    let mut check = PrerequisiteCheck {
+        name: prerequisite.name,
+        status: PrerequisiteStatus::Ok,
+        found_version: None,
+        required_version: prerequisite.min_version,
+        fix: None,
+    };
+
+    let Some(output) = version_output(shell, prerequisite) else {
+        check.status = PrerequisiteStatus::Missing;
+        check.fix = Some(format!("install it from {}", prerequisite.download_link));
+        return check;
+    };
+    let Some(min_version) = prerequisite.min_version else {
+        return check;
+    };
+
+    check.found_version = Version::parse_from_output(&output);
+    match check.found_version {
+        None => check.status = PrerequisiteStatus::UnknownVersion,
+        Some(found) if found < min_version => {
+            check.status = PrerequisiteStatus::Outdated;
+            check.fix = Some(
+                prerequisite
+                    .fix_hint
+                    .map(str::to_owned)
+                    .unwrap_or_else(|| format!("upgrade it using {}", prerequisite.download_link)),
+            );
+        }
+        Some(_) => {}
+    }
+    check
+}
+
+/// Returns the combined stdout and stderr of the version command, or `None` if the binary can't be run.
+fn version_output(shell: &Shell, prerequisite: &Prerequisite) -> Option<String> {
+    let output = shell
+        .cmd(prerequisite.name)
+        .args(prerequisite.version_args)
+        .quiet()
+        .ignore_status()
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+    text.push_str(&String::from_utf8_lossy(&output.stderr));
+    Some(text)
+}
+
+fn architecture_warnings(shell: &Shell) -> Vec<String> {
+    let mut warnings = vec![];
+    match (std::env::consts::OS, std::env::consts::ARCH) {
+        ("macos", "x86_64") => {
+            // An x86_64 binary running on Apple Silicon is translated by Rosetta, which is slow
+            // and makes the toolchain build x86_64 artifacts.
+            let translated = shell
+                .cmd("sysctl")
+                .args(["-n", "sysctl.proc_translated"])
+                .quiet()
+                .ignore_status()
+                .read()
+                .is_ok_and(|value| value.trim() == "1");
+            if translated {
+                warnings.push(
+                    "The tool is running under Rosetta translation. Install the native arm64 \
+                     toolchain: `rustup toolchain install stable-aarch64-apple-darwin`"
+                        .to_string(),
+                );
+            }
+        }
+        ("linux" | "macos", "aarch64") => {
+            warnings.push(
+                "Some docker images used for local development are only published for amd64. \
+                 If containers fail to start, run `export DOCKER_DEFAULT_PLATFORM=linux/amd64`"
+                    .to_string(),
+            );
+        }
+        ("linux" | "macos", _) => {}
+        (os, arch) => {
+            warnings.push(format!("Platform {os}/{arch} is not officially supported"));
+        }
+    }
+    warnings
 }
@@ -0,0 +1,350 @@
+//! Secret providers allowing to reference secrets stored outside of config files.
+//!
+//! A config value can either contain a secret as is, or reference it with a URI:
+//!
+//! - `env://<VAR>` reads the environment variable `VAR`.
+//! - `file://<path>` reads the file at `path`, trimming whitespace around the contents.
+//! - `vault://<path>#<field>` reads the `field` of a HashiCorp Vault KV secret at `path` using the `vault` CLI,
+//!   which is configured via `VAULT_ADDR` / `VAULT_TOKEN` env variables as usual.
+//! - `aws-sm://<secret_id>[#<field>]` reads a secret from AWS Secrets Manager using the `aws` CLI.
+//! - `gcp-sm://<secret>[#<field>]` reads the latest version of a secret from GCP Secret Manager using
+//!   the `gcloud` CLI. `secret` can be either a secret name in the current project, or a full resource name
+//!   of a secret version (`projects/<project>/secrets/<secret>/versions/<version>`).
+//!
+//! For cloud secret managers, an optional `field` selects a field if the secret is a JSON object.
+
+use std::{fmt, str::FromStr};
+
+use anyhow::Context;
+use xshell::{cmd, Shell};
+
+/// Backend storing a secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SecretProvider {
+    Env,
+    File,
+    Vault,
+    AwsSecretsManager,
+    GcpSecretManager,
+}
+
+impl SecretProvider {
+    const ALL: [Self; 5] = [
+        Self::Env,
+        Self::File,
+        Self::Vault,
+        Self::AwsSecretsManager,
+        Self::GcpSecretManager,
+    ];
+
+    fn scheme(self) -> &'static str {
+        match self {
+            Self::Env => "env",
+            Self::File => "file",
+            Self::Vault => "vault",
+            Self::AwsSecretsManager => "aws-sm",
+            Self::GcpSecretManager => "gcp-sm",
+        }
+    }
+}
+
+/// Reference to a secret stored by a [`SecretProvider`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecretRef {
+    pub provider: SecretProvider,
+    pub path: String,
+    pub field: Option<String>,
+}
+
+impl fmt::Display for SecretRef {
+    fn fmt(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(formatter, "{}://{}", self.provider.scheme(), self.path)?;
+        if let Some(field) = &self.field {
+            write!(formatter, "#{field}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for SecretRef {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)?.with_context(|| format!("`{s}` is not a secret reference"))
+    }
+}
+
+impl SecretRef {
+    /// Parses a secret reference. Returns `Ok(None)` if the value doesn't use a secret provider scheme,
+    /// i.e. it contains the secret itself.
+    pub fn parse(value: &str) -> anyhow::Result<Option<Self>> {
+        let Some((scheme, rest)) = value.split_once("://") else {
+            return Ok(None);
+        };
+        let Some(provider) = SecretProvider::ALL
+            .into_iter()
+            .find(|provider| provider.scheme() == scheme)
+        else {
+            return Ok(None);
+        };
+
+        let (path, field) = match rest.split_once('#') {
+            Some((path, field)) => (path, Some(field.to_owned())),
+            None => (rest, None),
+        };
+        anyhow::ensure!(
+            !path.is_empty(),
+            "secret reference `{value}` has empty path"
+        );
+        match provider {
+            SecretProvider::Env | SecretProvider::File => anyhow::ensure!(
+                field.is_none(),
+                "secret reference `{value}` cannot specify a field"
+            ),
+            SecretProvider::Vault => anyhow::ensure!(
+                field.is_some(),
+                "Vault secret reference `{value}` must specify a field, e.g. `vault://secret/chain#db_url`"
+            ),
+            SecretProvider::AwsSecretsManager | SecretProvider::GcpSecretManager => {}
+        }
+        Ok(Some(Self {
+            provider,
+            path: path.to_owned(),
+            field,
+        }))
+    }
+
+    /// Reads the referenced secret.
+    pub fn resolve(&self, shell: &Shell) -> anyhow::Result<String> {
+        let path = &self.path;
+        let secret = match self.provider {
+            SecretProvider::Env => {
+                return std::env::var(path)
+                    .with_context(|| format!("Failed to read env variable {path}"));
+            }
+            SecretProvider::File => {
+                let contents = shell
+                    .read_file(path)
+                    .with_context(|| format!("Failed to read secret file {path}"))?;
+                return Ok(contents.trim().to_owned());
+            }
+            SecretProvider::Vault => {
+                let field = self.field.as_deref().unwrap_or_default();
+                // The field is selected by the CLI, so it shouldn't be extracted from JSON below.
+                return read_cmd_output(cmd!(shell, "vault kv get -field={field} {path}"), self);
+            }
+            SecretProvider::AwsSecretsManager => read_cmd_output(
+                cmd!(
+                    shell,
+                    "aws secretsmanager get-secret-value --secret-id {path} --query SecretString --output text"
+                ),
+                self,
+            )?,
+            SecretProvider::GcpSecretManager => {
+                let cmd = if path.contains('/') {
+                    cmd!(shell, "gcloud secrets versions access {path}")
+                } else {
+                    cmd!(shell, "gcloud secrets versions access latest --secret={path}")
+                };
+                read_cmd_output(cmd, self)?
+            }
+        };
+
+        let Some(field) = &self.field else {
+            return Ok(secret);
+        };
+        let secret: serde_json::Value = serde_json::from_str(&secret)
+            .with_context(|| format!("Secret {self} is not a JSON object"))?;
+        match secret.get(field) {
+            Some(serde_json::Value::String(value)) => Ok(value.clone()),
+            Some(value) => Ok(value.to_string()),
+            None => anyhow::bail!("Secret {self} doesn't have the requested field"),
+        }
+    }
+}
+
+fn read_cmd_output(cmd: xshell::Cmd<'_>, secret: &SecretRef) -> anyhow::Result<String> {
+    // The output is not logged, even in the verbose mode, since it contains the secret.
+    cmd.quiet()
+        .read()
+        .with_context(|| format!("Failed to read secret {secret}"))
+}
+
+/// Resolves a config value that may contain either a secret or a [reference](SecretRef) to it.
+pub fn resolve_secret(shell: &Shell, value: &str) -> anyhow::Result<String> {
+    match SecretRef::parse(value)? {
+        Some(secret_ref) => secret_ref.resolve(shell),
+        None => Ok(value.to_owned()),
+    }
+}
+
+/// Checks whether a config value references a secret rather than contains it. Malformed references
+/// (e.g., a Vault reference without a field) are considered references, so that they are reported on resolution.
+pub fn is_secret_ref(value: &str) -> bool {
+    !matches!(SecretRef::parse(value), Ok(None))
+}
+
+/// Resolves all [secret references](SecretRef) among string values in `value`, recursively.
+pub fn resolve_secrets_in_json(shell: &Shell, value: &mut serde_json::Value) -> anyhow::Result<()> {
+    match value {
+        serde_json::Value::String(s) => {
+            if let Some(secret_ref) = SecretRef::parse(s)? {
+                *s = secret_ref.resolve(shell)?;
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                resolve_secrets_in_json(shell, value)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                resolve_secrets_in_json(shell, value)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Resolves [secret references](SecretRef) that cannot be resolved by the node (i.e., ones other than `env://` and
+/// `file://` references) among string values in `value`, recursively. Resolved references are replaced with
+/// `env://{env_prefix}_{i}` references; the returned env variables containing the secrets must be passed
+/// to the node process. Thus, the resolved secrets are never written to disk.
+pub fn resolve_secrets_to_env(
+    shell: &Shell,
+    value: &mut serde_json::Value,
+    env_prefix: &str,
+) -> anyhow::Result<Vec<(String, String)>> {
+    let mut env = vec![];
+    resolve_secrets_to_env_inner(shell, value, env_prefix, &mut env)?;
+    Ok(env)
+}
+
+fn resolve_secrets_to_env_inner(
+    shell: &Shell,
+    value: &mut serde_json::Value,
+    env_prefix: &str,
+    env: &mut Vec<(String, String)>,
+) -> anyhow::Result<()> {
+    match value {
+        serde_json::Value::String(s) => {
+            let Some(secret_ref) = SecretRef::parse(s)? else {
+                return Ok(());
+            };
+            if !matches!(
+                secret_ref.provider,
+                SecretProvider::Env | SecretProvider::File
+            ) {
+                let var_name = format!("{env_prefix}_{}", env.len());
+                env.push((var_name.clone(), secret_ref.resolve(shell)?));
+                *s = format!("env://{var_name}");
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                resolve_secrets_to_env_inner(shell, value, env_prefix, env)?;
+            }
+        }
+        serde_json::Value::Object(map) => {
+            for value in map.values_mut() {
+                resolve_secrets_to_env_inner(shell, value, env_prefix, env)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Checks whether `value` contains [secret references](SecretRef) among its string values, recursively.
+pub fn contains_secret_refs(value: &serde_json::Value) -> bool {
+    match value {
+        serde_json::Value::String(s) => is_secret_ref(s),
+        serde_json::Value::Array(values) => values.iter().any(contains_secret_refs),
+        serde_json::Value::Object(map) => map.values().any(contains_secret_refs),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_secret_refs() {
+        assert_eq!(
+            SecretRef::parse("postgres://localhost/zksync").unwrap(),
+            None
+        );
+        assert_eq!(SecretRef::parse("plain secret").unwrap(), None);
+
+        let secret_ref = SecretRef::parse("env://DATABASE_URL").unwrap().unwrap();
+        assert_eq!(secret_ref.provider, SecretProvider::Env);
+        assert_eq!(secret_ref.path, "DATABASE_URL");
+        assert_eq!(secret_ref.field, None);
+
+        let secret_ref = SecretRef::parse("vault://secret/chain#db_url")
+            .unwrap()
+            .unwrap();
+        assert_eq!(secret_ref.provider, SecretProvider::Vault);
+        assert_eq!(secret_ref.path, "secret/chain");
+        assert_eq!(secret_ref.field.as_deref(), Some("db_url"));
+        assert_eq!(secret_ref.to_string(), "vault://secret/chain#db_url");
+
+        let secret_ref = SecretRef::parse("gcp-sm://projects/p/secrets/s/versions/2#url")
+            .unwrap()
+            .unwrap();
+        assert_eq!(secret_ref.provider, SecretProvider::GcpSecretManager);
+        assert_eq!(secret_ref.path, "projects/p/secrets/s/versions/2");
+
+        let secret_ref: SecretRef = "aws-sm://chain-secrets".parse().unwrap();
+        assert_eq!(secret_ref.provider, SecretProvider::AwsSecretsManager);
+        assert_eq!(secret_ref.field, None);
+    }
+
+    #[test]
+    fn parsing_invalid_secret_refs() {
+        let err = SecretRef::parse("vault://secret/chain").unwrap_err();
+        assert!(err.to_string().contains("must specify a field"), "{err}");
+        let err = SecretRef::parse("env://DATABASE_URL#field").unwrap_err();
+        assert!(err.to_string().contains("cannot specify a field"), "{err}");
+        let err = SecretRef::parse("file://").unwrap_err();
+        assert!(err.to_string().contains("empty path"), "{err}");
+        "postgres://localhost".parse::<SecretRef>().unwrap_err();
+
+        assert!(is_secret_ref("vault://secret/chain"));
+        assert!(!is_secret_ref("postgres://localhost"));
+    }
+
+    #[test]
+    fn resolving_secrets_in_json() {
+        let shell = Shell::new().unwrap();
+        std::env::set_var("ZK_TOOLBOX_TEST_SECRET", "secret");
+        let mut value = serde_json::json!({
+            "database": {
+                "server_url": "env://ZK_TOOLBOX_TEST_SECRET",
+                "prover_url": "postgres://localhost/prover",
+            },
+            "urls": ["env://ZK_TOOLBOX_TEST_SECRET"],
+        });
+        assert!(contains_secret_refs(&value));
+
+        // Env references are left as is, since they can be resolved by the node.
+        let env = resolve_secrets_to_env(&shell, &mut value, "SECRET").unwrap();
+        assert!(env.is_empty());
+        assert!(contains_secret_refs(&value));
+
+        resolve_secrets_in_json(&shell, &mut value).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "database": {
+                    "server_url": "secret",
+                    "prover_url": "postgres://localhost/prover",
+                },
+                "urls": ["secret"],
+            })
+        );
+        assert!(!contains_secret_refs(&value));
+    }
+}
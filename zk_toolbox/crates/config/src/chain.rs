@@ -7,10 +7,13 @@ use serde::{Deserialize, Serialize, Serializer};
 use xshell::Shell;
 
 use crate::{
-    configs::{ContractsConfig, GenesisConfig, ReadConfig, SaveConfig, WalletsConfig},
-    consts::{CONTRACTS_FILE, GENESIS_FILE, L1_CONTRACTS_FOUNDRY, WALLETS_FILE},
+    consts::{
+        CONTRACTS_FILE, GENERAL_FILE, GENESIS_FILE, L1_CONTRACTS_FOUNDRY, LOCAL_LOGS_PATH,
+        SECRETS_FILE, WALLETS_FILE,
+    },
     types::{BaseToken, ChainId, L1BatchCommitDataGeneratorMode, L1Network, ProverMode},
-    wallets::{create_localhost_wallets, WalletCreation},
+    wallet_creation::{create_localhost_wallets, WalletCreation},
+    ContractsConfig, GeneralConfig, GenesisConfig, ReadConfig, SaveConfig, Secrets, WalletsConfig,
 };
 
 /// Chain configuration file. This file is created in the chain
@@ -82,6 +85,26 @@ impl ChainConfig {
         ContractsConfig::read(self.get_shell(), self.configs.join(CONTRACTS_FILE))
     }
 
+    pub fn get_general_config(&self) -> anyhow::Result<GeneralConfig> {
+        GeneralConfig::read(self.get_shell(), self.configs.join(GENERAL_FILE))
+    }
+
+    /// Returns the secrets of the chain, or `None` if the chain has no secrets config (e.g., it wasn't initialized).
+    pub fn get_secrets_config(&self) -> anyhow::Result<Option<Secrets>> {
+        let path = self.configs.join(SECRETS_FILE);
+        if !self.get_shell().path_exists(&path) {
+            return Ok(None);
+        }
+        Secrets::read(self.get_shell(), path).map(Some)
+    }
+
+    /// Returns the folder the chain components write their logs to.
+    pub fn logs_path(&self) -> PathBuf {
+        // Configs are stored in a subfolder of the chain folder.
+        let chain_path = self.configs.parent().unwrap_or(&self.configs);
+        chain_path.join(LOCAL_LOGS_PATH)
+    }
+
     pub fn path_to_foundry(&self) -> PathBuf {
         self.link_to_code.join(L1_CONTRACTS_FOUNDRY)
     }
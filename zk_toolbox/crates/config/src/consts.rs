@@ -0,0 +1,24 @@
+/// Name of the main configuration file
+pub const CONFIG_NAME: &str = "ZkStack.yaml";
+/// Name of the wallets file
+pub const WALLETS_FILE: &str = "wallets.yaml";
+/// Name of the secrets config file
+pub const SECRETS_FILE: &str = "secrets.yaml";
+/// Name of the general config file
+pub const GENERAL_FILE: &str = "general.yaml";
+/// Name of the genesis config file
+pub const GENESIS_FILE: &str = "genesis.yaml";
+/// Name of the initial deployments config file
+pub const INITIAL_DEPLOYMENT_FILE: &str = "initial_deployments.yaml";
+/// Name of the erc20 deployments config file
+pub const ERC20_DEPLOYMENT_FILE: &str = "erc20_deployments.yaml";
+/// Name of the contracts file
+pub const CONTRACTS_FILE: &str = "contracts.yaml";
+pub const LOCAL_LOGS_PATH: &str = "logs/";
+
+/// Path to l1 contracts foundry folder inside zksync-era
+pub const L1_CONTRACTS_FOUNDRY: &str = "contracts/l1-contracts-foundry";
+
+/// Path to the config file with mnemonic for localhost wallets
+pub const TEST_CONFIG_PATH: &str = "etc/test_config/constant/eth.json";
+pub const BASE_PATH: &str = "m/44'/60'/0'";
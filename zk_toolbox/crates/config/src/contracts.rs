@@ -1,9 +1,7 @@
 use ethers::{addressbook::Address, types::H256};
 use serde::{Deserialize, Serialize};
 
-use crate::configs::{
-    forge_interface::deploy_ecosystem::output::DeployL1Output, ReadConfig, SaveConfig,
-};
+use crate::{forge_interface::deploy_ecosystem::output::DeployL1Output, ReadConfig, SaveConfig};
 
 #[derive(Debug, Deserialize, Serialize, Clone, Default)]
 pub struct ContractsConfig {
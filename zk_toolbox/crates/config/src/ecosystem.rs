@@ -5,18 +5,14 @@ use thiserror::Error;
 use xshell::Shell;
 
 use crate::{
-    configs::{
-        forge_interface::deploy_ecosystem::input::{
-            Erc20DeploymentConfig, InitialDeploymentConfig,
-        },
-        ChainConfig, ChainConfigInternal, ContractsConfig, ReadConfig, SaveConfig, WalletsConfig,
-    },
     consts::{
         CONFIG_NAME, CONTRACTS_FILE, ERC20_DEPLOYMENT_FILE, INITIAL_DEPLOYMENT_FILE,
         L1_CONTRACTS_FOUNDRY, WALLETS_FILE,
     },
+    forge_interface::deploy_ecosystem::input::{Erc20DeploymentConfig, InitialDeploymentConfig},
     types::{ChainId, L1Network, ProverMode},
-    wallets::{create_localhost_wallets, WalletCreation},
+    wallet_creation::{create_localhost_wallets, WalletCreation},
+    ChainConfig, ChainConfigInternal, ContractsConfig, ReadConfig, SaveConfig, WalletsConfig,
 };
 
 /// Ecosystem configuration file. This file is created in the chain
@@ -1,7 +1,7 @@
 use ethers::prelude::Address;
 use serde::{Deserialize, Serialize};
 
-use crate::configs::{ReadConfig, SaveConfig};
+use crate::{ReadConfig, SaveConfig};
 
 impl ReadConfig for AcceptOwnershipInput {}
 impl SaveConfig for AcceptOwnershipInput {}
@@ -8,11 +8,8 @@ use ethers::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    configs::{
-        ContractsConfig, GenesisConfig, ReadConfig, SaveConfig, SaveConfigWithComment,
-        WalletsConfig,
-    },
-    types::ChainId,
+    types::ChainId, ContractsConfig, GenesisConfig, ReadConfig, SaveConfig, SaveConfigWithComment,
+    WalletsConfig,
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
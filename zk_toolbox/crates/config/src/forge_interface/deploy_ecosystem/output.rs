@@ -3,7 +3,7 @@ use std::collections::HashMap;
 use ethers::{addressbook::Address, prelude::H256};
 use serde::{Deserialize, Serialize};
 
-use crate::configs::{ReadConfig, SaveConfig};
+use crate::{ReadConfig, SaveConfig};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct DeployL1Output {
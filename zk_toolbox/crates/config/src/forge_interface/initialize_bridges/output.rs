@@ -1,7 +1,7 @@
 use ethers::addressbook::Address;
 use serde::{Deserialize, Serialize};
 
-use crate::configs::ReadConfig;
+use crate::ReadConfig;
 
 impl ReadConfig for InitializeBridgeOutput {}
 
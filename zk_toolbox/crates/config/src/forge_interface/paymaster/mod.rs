@@ -1,10 +1,7 @@
 use ethers::addressbook::Address;
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    configs::{ChainConfig, ReadConfig, SaveConfig},
-    types::ChainId,
-};
+use crate::{types::ChainId, ChainConfig, ReadConfig, SaveConfig};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DeployPaymasterInput {
@@ -5,8 +5,8 @@ use ethers::{
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    configs::{ChainConfig, ContractsConfig, ReadConfig, SaveConfig},
     types::{ChainId, L1BatchCommitDataGeneratorMode},
+    ChainConfig, ContractsConfig, ReadConfig, SaveConfig,
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -1,7 +1,7 @@
 use ethers::addressbook::Address;
 use serde::{Deserialize, Serialize};
 
-use crate::configs::{ReadConfig, SaveConfig};
+use crate::{ReadConfig, SaveConfig};
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct RegisterChainOutput {
@@ -4,8 +4,8 @@ use ethers::types::{Address, H256};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    configs::{ReadConfig, SaveConfig},
     types::{ChainId, L1BatchCommitDataGeneratorMode},
+    ReadConfig, SaveConfig,
 };
 
 #[derive(Debug, Deserialize, Serialize, Clone)]
@@ -46,6 +46,7 @@ pub struct EthSender {
 pub struct GeneralConfig {
     pub db: RocksDBConfig,
     pub eth: EthConfig,
+    pub api: ApiConfig,
     #[serde(flatten)]
     pub other: serde_json::Value,
 }
@@ -65,5 +66,36 @@ pub struct MerkleTreeDB {
     pub other: serde_json::Value,
 }
 
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct ApiConfig {
+    pub web3_json_rpc: Web3JsonRpcConfig,
+    pub prometheus: PrometheusConfig,
+    pub healthcheck: HealthCheckConfig,
+    #[serde(flatten)]
+    pub other: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Web3JsonRpcConfig {
+    pub http_port: u16,
+    pub ws_port: u16,
+    #[serde(flatten)]
+    pub other: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct PrometheusConfig {
+    pub listener_port: u16,
+    #[serde(flatten)]
+    pub other: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct HealthCheckConfig {
+    pub port: u16,
+    #[serde(flatten)]
+    pub other: serde_json::Value,
+}
+
 impl ReadConfig for GeneralConfig {}
 impl SaveConfig for GeneralConfig {}
@@ -0,0 +1,22 @@
+//! Config files of a ZK Stack ecosystem and its chains, shared by the `zk_inception` and `zk_supervisor` tools.
+
+pub use chain::*;
+pub use contracts::*;
+pub use ecosystem::*;
+pub use general::*;
+pub use secrets::*;
+pub use traits::*;
+pub use wallet_creation::{create_localhost_wallets, create_wallets, WalletCreation};
+pub use wallets::*;
+
+mod chain;
+pub mod consts;
+pub mod contracts;
+mod ecosystem;
+pub mod forge_interface;
+mod general;
+mod secrets;
+mod traits;
+pub mod types;
+mod wallet_creation;
+mod wallets;
@@ -0,0 +1,114 @@
+use anyhow::Context;
+use common::secrets::{
+    contains_secret_refs, resolve_secret, resolve_secrets_in_json, resolve_secrets_to_env,
+};
+use serde::{Deserialize, Serialize};
+use url::Url;
+use xshell::Shell;
+
+use crate::{ReadConfig, SaveConfig};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DatabaseSecrets {
+    pub server_url: String,
+    pub prover_url: String,
+    #[serde(flatten)]
+    pub other: serde_json::Value,
+}
+
+impl DatabaseSecrets {
+    /// Returns a copy of these secrets with database urls [referencing](common::secrets::SecretRef) a secret provider
+    /// replaced with the referenced urls. Unlike [`Secrets::resolve()`], other secrets are left intact.
+    pub fn resolve(&self, shell: &Shell) -> anyhow::Result<Self> {
+        Ok(Self {
+            server_url: resolve_secret(shell, &self.server_url)
+                .context("Failed to resolve server database url")?,
+            prover_url: resolve_secret(shell, &self.prover_url)
+                .context("Failed to resolve prover database url")?,
+            other: self.other.clone(),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct L1Secret {
+    pub l1_rpc_url: String,
+    #[serde(flatten)]
+    pub other: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Secrets {
+    pub database: DatabaseSecrets,
+    pub l1: L1Secret,
+    #[serde(flatten)]
+    pub other: serde_json::Value,
+}
+
+impl Secrets {
+    /// Checks whether any of the secrets is a [reference](common::secrets::SecretRef) to a secret provider.
+    pub fn has_secret_refs(&self) -> anyhow::Result<bool> {
+        Ok(contains_secret_refs(&serde_json::to_value(self)?))
+    }
+
+    /// Returns a copy of these secrets with all [references](common::secrets::SecretRef) to secret providers
+    /// replaced with the referenced secrets.
+    pub fn resolve(&self, shell: &Shell) -> anyhow::Result<Self> {
+        let mut value = serde_json::to_value(self)?;
+        resolve_secrets_in_json(shell, &mut value).context("Failed to resolve secrets")?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Returns a copy of these secrets in which [references](common::secrets::SecretRef) that cannot be resolved
+    /// by the node are replaced with `env://` references, together with the env variables containing the secrets.
+    pub fn resolve_to_env(
+        &self,
+        shell: &Shell,
+        env_prefix: &str,
+    ) -> anyhow::Result<(Self, Vec<(String, String)>)> {
+        let mut value = serde_json::to_value(self)?;
+        let env = resolve_secrets_to_env(shell, &mut value, env_prefix)
+            .context("Failed to resolve secrets")?;
+        Ok((serde_json::from_value(value)?, env))
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabaseConfig {
+    pub base_url: Url,
+    pub database_name: String,
+}
+
+impl DatabaseConfig {
+    pub fn new(base_url: Url, database_name: String) -> Self {
+        Self {
+            base_url,
+            database_name,
+        }
+    }
+
+    /// Splits a full database url into the base url and the database name.
+    pub fn from_url(mut url: Url) -> anyhow::Result<Self> {
+        let database_name = url
+            .path_segments()
+            .and_then(|mut segments| segments.next_back())
+            .filter(|name| !name.is_empty())
+            .context("Database url doesn't contain a database name")?
+            .to_string();
+        url.set_path("");
+        Ok(Self::new(url, database_name))
+    }
+
+    pub fn full_url(&self) -> String {
+        format!("{}/{}", self.base_url, self.database_name)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DatabasesConfig {
+    pub server: DatabaseConfig,
+    pub prover: DatabaseConfig,
+}
+
+impl ReadConfig for Secrets {}
+impl SaveConfig for Secrets {}
@@ -1,15 +1,44 @@
 use std::path::{Path, PathBuf};
 
+use clap::ValueEnum;
 use common::wallets::Wallet;
 use ethers::core::rand::thread_rng;
+use serde::{Deserialize, Serialize};
+use strum_macros::EnumIter;
 use xshell::Shell;
 
 use crate::{
-    configs::{EthMnemonicConfig, ReadConfig, SaveConfig, WalletsConfig},
     consts::{BASE_PATH, TEST_CONFIG_PATH},
-    wallets::WalletCreation,
+    EthMnemonicConfig, ReadConfig, SaveConfig, WalletsConfig,
 };
 
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    ValueEnum,
+    EnumIter,
+    strum_macros::Display,
+)]
+pub enum WalletCreation {
+    /// Load wallets from localhost mnemonic, they are funded for localhost env
+    #[default]
+    Localhost,
+    /// Generate random wallets
+    Random,
+    /// Generate placeholder wallets
+    Empty,
+    /// Specify file with wallets
+    InFile,
+}
+
 pub fn create_wallets(
     shell: &Shell,
     dst_wallet_path: &Path,
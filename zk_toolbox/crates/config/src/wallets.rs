@@ -1,10 +1,8 @@
+use common::wallets::Wallet;
 use ethers::{core::rand::Rng, types::H256};
 use serde::{Deserialize, Serialize};
 
-use crate::{
-    configs::{ReadConfig, SaveConfig},
-    wallets::Wallet,
-};
+use crate::{ReadConfig, SaveConfig};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WalletsConfig {
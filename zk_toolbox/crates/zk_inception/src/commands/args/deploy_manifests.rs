@@ -0,0 +1,28 @@
+use std::path::PathBuf;
+
+use clap::{Parser, ValueEnum};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display, Serialize, Deserialize)]
+pub enum ManifestFormat {
+    /// Docker Compose file running the chain on a single host
+    #[strum(serialize = "docker-compose")]
+    DockerCompose,
+    /// Values file for a Helm chart deploying the chain to Kubernetes
+    #[strum(serialize = "helm")]
+    Helm,
+}
+
+#[derive(Debug, Serialize, Deserialize, Parser)]
+pub struct DeployManifestsArgs {
+    /// Format of the generated manifests
+    #[clap(long, value_enum, default_value_t = ManifestFormat::DockerCompose)]
+    pub format: ManifestFormat,
+    /// Directory to write the manifests to. Defaults to the `deploy` directory of the chain
+    #[clap(long)]
+    pub output: Option<PathBuf>,
+    /// Tag of the server, external node and prover images
+    #[clap(long, default_value = "latest")]
+    pub image_tag: String,
+}
@@ -1,3 +1,7 @@
+mod deploy_manifests;
+mod prerequisites;
 mod run_server;
 
+pub use deploy_manifests::*;
+pub use prerequisites::*;
 pub use run_server::*;
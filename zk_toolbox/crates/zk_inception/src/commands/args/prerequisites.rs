@@ -0,0 +1,9 @@
+use clap::Parser;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Parser)]
+pub struct PrerequisitesArgs {
+    /// Print the report as JSON to stdout, e.g. for consumption by CI
+    #[clap(long)]
+    pub json: bool,
+}
@@ -2,22 +2,19 @@ use std::{path::PathBuf, str::FromStr};
 
 use clap::Parser;
 use common::{slugify, Prompt, PromptConfirm, PromptSelect};
+use config::types::{BaseToken, L1BatchCommitDataGeneratorMode, ProverMode};
 use ethers::types::H160;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
 
-use crate::{
-    defaults::L2_CHAIN_ID,
-    types::{BaseToken, L1BatchCommitDataGeneratorMode, ProverMode},
-    wallets::WalletCreation,
-};
+use crate::{defaults::L2_CHAIN_ID, wallets::WalletCreation};
 
 #[derive(Debug, Serialize, Deserialize, Parser)]
 pub struct ChainCreateArgs {
     #[arg(long)]
     pub chain_name: Option<String>,
-    #[arg(value_parser = clap::value_parser!(u32).range(1..))]
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
     pub chain_id: Option<u32>,
     #[clap(long, help = "Prover options", value_enum)]
     pub prover_mode: Option<ProverMode>,
@@ -25,13 +22,23 @@ pub struct ChainCreateArgs {
     pub wallet_creation: Option<WalletCreation>,
     #[clap(long, help = "Wallet path")]
     pub wallet_path: Option<PathBuf>,
-    #[clap(long, help = "Commit data generation mode")]
+    #[clap(long, help = "Commit data generation mode", value_enum)]
     pub l1_batch_commit_data_generator_mode: Option<L1BatchCommitDataGeneratorMode>,
     #[clap(long, help = "Base token address")]
-    pub base_token_address: Option<String>,
-    #[clap(long, help = "Base token nominator")]
+    pub base_token_address: Option<H160>,
+    #[clap(
+        long,
+        help = "Base token nominator",
+        requires = "base_token_address",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
     pub base_token_price_nominator: Option<u64>,
-    #[clap(long, help = "Base token denominator")]
+    #[clap(
+        long,
+        help = "Base token denominator",
+        requires = "base_token_address",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
     pub base_token_price_denominator: Option<u64>,
     #[clap(long, help = "Set as default chain", default_missing_value = "true", num_args = 0..=1)]
     pub set_as_default: Option<bool>,
@@ -50,22 +57,28 @@ impl ChainCreateArgs {
                 .ask()
         });
 
-        let wallet_creation = PromptSelect::new(
-            "Select how do you want to create the wallet",
-            WalletCreation::iter(),
-        )
-        .ask();
+        let wallet_creation = self.wallet_creation.unwrap_or_else(|| {
+            PromptSelect::new(
+                "Select how do you want to create the wallet",
+                WalletCreation::iter(),
+            )
+            .ask()
+        });
 
-        let prover_version =
-            PromptSelect::new("Select the prover version", ProverMode::iter()).ask();
+        let prover_version = self.prover_mode.unwrap_or_else(|| {
+            PromptSelect::new("Select the prover version", ProverMode::iter()).ask()
+        });
 
-        let l1_batch_commit_data_generator_mode = PromptSelect::new(
-            "Select the commit data generator mode",
-            L1BatchCommitDataGeneratorMode::iter(),
-        )
-        .ask();
+        let l1_batch_commit_data_generator_mode =
+            self.l1_batch_commit_data_generator_mode.unwrap_or_else(|| {
+                PromptSelect::new(
+                    "Select the commit data generator mode",
+                    L1BatchCommitDataGeneratorMode::iter(),
+                )
+                .ask()
+            });
 
-        let wallet_path: Option<PathBuf> = if self.wallet_creation == Some(WalletCreation::InFile) {
+        let wallet_path: Option<PathBuf> = if wallet_creation == WalletCreation::InFile {
             Some(self.wallet_path.unwrap_or_else(|| {
                 Prompt::new("What is the wallet path?")
                     .validate_with(|val: &String| {
@@ -79,27 +92,36 @@ impl ChainCreateArgs {
             None
         };
 
-        let base_token_selection =
-            PromptSelect::new("Select the base token to use", BaseTokenSelection::iter()).ask();
+        let base_token_selection = if self.base_token_address.is_some() {
+            BaseTokenSelection::Custom
+        } else {
+            PromptSelect::new("Select the base token to use", BaseTokenSelection::iter()).ask()
+        };
         let base_token = match base_token_selection {
             BaseTokenSelection::Eth => BaseToken::eth(),
             BaseTokenSelection::Custom => {
                 let number_validator = |val: &String| -> Result<(), String> {
                     let Ok(val) = val.parse::<u64>() else {
-                        return Err("Numer is not zero".to_string());
+                        return Err("Not a valid number".to_string());
                     };
                     if val == 0 {
                         return Err("Number should be greater than 0".to_string());
                     }
                     Ok(())
                 };
-                let address: H160 = Prompt::new("What is the base token address?").ask();
-                let nominator = Prompt::new("What is the base token price nominator?")
-                    .validate_with(number_validator)
-                    .ask();
-                let denominator = Prompt::new("What is the base token price denominator?")
-                    .validate_with(number_validator)
-                    .ask();
+                let address = self
+                    .base_token_address
+                    .unwrap_or_else(|| Prompt::new("What is the base token address?").ask());
+                let nominator = self.base_token_price_nominator.unwrap_or_else(|| {
+                    Prompt::new("What is the base token price nominator?")
+                        .validate_with(number_validator)
+                        .ask()
+                });
+                let denominator = self.base_token_price_denominator.unwrap_or_else(|| {
+                    Prompt::new("What is the base token price denominator?")
+                        .validate_with(number_validator)
+                        .ask()
+                });
                 BaseToken {
                     address,
                     nominator,
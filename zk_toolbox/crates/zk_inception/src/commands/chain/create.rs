@@ -1,13 +1,13 @@
 use std::cell::OnceCell;
 
 use common::{logger, spinner::Spinner};
+use config::types::ChainId;
 use xshell::Shell;
 
 use crate::{
     commands::chain::args::create::{ChainCreateArgs, ChainCreateArgsFinal},
-    configs::{ChainConfig, EcosystemConfig, SaveConfig},
+    configs::{copy_configs, update_genesis, ChainConfig, EcosystemConfig, SaveConfig},
     consts::{CONFIG_NAME, LOCAL_CONFIGS_PATH, LOCAL_DB_PATH, WALLETS_FILE},
-    types::ChainId,
     wallets::create_wallets,
 };
 
@@ -76,6 +76,11 @@ pub(crate) fn create_chain_inner(
         args.wallet_path,
     )?;
 
+    // Seed the chain with the default configs, so that the genesis params reflect the
+    // selected commit data generator mode right after the chain is created.
+    copy_configs(shell, &ecosystem_config.link_to_code, &chain_config.configs)?;
+    update_genesis(shell, &chain_config)?;
+
     chain_config.save(shell, chain_path.join(CONFIG_NAME))?;
     Ok(())
 }
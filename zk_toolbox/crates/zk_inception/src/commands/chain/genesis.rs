@@ -18,7 +18,7 @@ use crate::{
     server::{RunServer, ServerMode},
 };
 
-const SERVER_MIGRATIONS: &str = "core/lib/dal/migrations";
+pub(crate) const SERVER_MIGRATIONS: &str = "core/lib/dal/migrations";
 const PROVER_MIGRATIONS: &str = "prover/prover_dal/migrations";
 
 pub async fn run(args: GenesisArgs, shell: &Shell) -> anyhow::Result<()> {
@@ -8,7 +8,6 @@ use common::{
 use xshell::Shell;
 
 use super::args::init::InitArgsFinal;
-use crate::forge_utils::check_the_balance;
 use crate::{
     accept_ownership::accept_admin,
     commands::chain::{
@@ -22,8 +21,8 @@ use crate::{
         update_genesis, update_l1_contracts, ChainConfig, ContractsConfig, EcosystemConfig,
         ReadConfig, SaveConfig,
     },
-    consts::{CONTRACTS_FILE, REGISTER_CHAIN},
-    forge_utils::fill_forge_private_key,
+    consts::{CONTRACTS_FILE, GENESIS_FILE, REGISTER_CHAIN},
+    forge_utils::{check_the_balance, fill_forge_private_key},
 };
 
 pub(crate) async fn run(args: InitArgs, shell: &Shell) -> anyhow::Result<()> {
@@ -47,7 +46,10 @@ pub async fn init(
     ecosystem_config: &EcosystemConfig,
     chain_config: &ChainConfig,
 ) -> anyhow::Result<()> {
-    copy_configs(shell, &ecosystem_config.link_to_code, &chain_config.configs)?;
+    // Chains created by older versions of the tool don't have configs copied yet.
+    if !shell.path_exists(chain_config.configs.join(GENESIS_FILE)) {
+        copy_configs(shell, &ecosystem_config.link_to_code, &chain_config.configs)?;
+    }
 
     update_genesis(shell, chain_config)?;
     let mut contracts_config =
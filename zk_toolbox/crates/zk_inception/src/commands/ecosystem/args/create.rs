@@ -2,6 +2,7 @@ use std::path::PathBuf;
 
 use clap::Parser;
 use common::{slugify, Prompt, PromptConfirm, PromptSelect};
+use config::types::L1Network;
 use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use strum_macros::{Display, EnumIter};
@@ -10,7 +11,6 @@ use url::Url;
 use crate::{
     commands::chain::{args::create::ChainCreateArgs, ChainCreateArgsFinal},
     defaults::LOCAL_RPC_URL,
-    types::L1Network,
     wallets::WalletCreation,
 };
 
@@ -54,10 +54,27 @@ pub struct EcosystemInitArgs {
     #[clap(flatten, next_help_heading = "Genesis options")]
     #[serde(flatten)]
     pub genesis_args: GenesisArgs,
+    /// Bring up the whole local stack in one go: start containers, deploy all contracts,
+    /// run genesis with default databases and start the server for the default chain
+    #[clap(long)]
+    pub dev: bool,
 }
 
 impl EcosystemInitArgs {
     pub fn fill_values_with_prompt(self) -> EcosystemInitArgsFinal {
+        if self.dev {
+            return EcosystemInitArgsFinal {
+                deploy_paymaster: true,
+                deploy_erc20: true,
+                ecosystem: EcosystemArgsFinal {
+                    deploy_ecosystem: true,
+                    ecosystem_contracts_path: self.ecosystem.ecosystem_contracts_path,
+                },
+                forge_args: self.forge_args,
+                dev: true,
+            };
+        }
+
         let deploy_paymaster = self.deploy_paymaster.unwrap_or_else(|| {
             PromptConfirm::new("Do you want to deploy paymaster?")
                 .default(true)
@@ -75,6 +92,7 @@ impl EcosystemInitArgs {
             deploy_erc20,
             ecosystem,
             forge_args: self.forge_args.clone(),
+            dev: false,
         }
     }
 }
@@ -85,4 +103,5 @@ pub struct EcosystemInitArgsFinal {
     pub deploy_erc20: bool,
     pub ecosystem: EcosystemArgsFinal,
     pub forge_args: ForgeScriptArgs,
+    pub dev: bool,
 }
@@ -12,14 +12,15 @@ use common::{
     spinner::Spinner,
     Prompt,
 };
+use config::types::{L1Network, ProverMode};
 use xshell::{cmd, Shell};
 
 use super::args::init::{EcosystemArgsFinal, EcosystemInitArgs, EcosystemInitArgsFinal};
-use crate::forge_utils::check_the_balance;
 use crate::{
     accept_ownership::accept_owner,
     commands::{
         chain,
+        containers::{initialize_docker, start_containers},
         ecosystem::create_configs::{
             create_erc20_deployment_config, create_initial_deployments_config,
         },
@@ -37,8 +38,8 @@ use crate::{
         AMOUNT_FOR_DISTRIBUTION_TO_WALLETS, CONFIGS_PATH, CONTRACTS_FILE, DEPLOY_ECOSYSTEM,
         DEPLOY_ERC20, ECOSYSTEM_PATH, ERC20_CONFIGS_FILE, GENESIS_FILE,
     },
-    forge_utils::fill_forge_private_key,
-    types::{L1Network, ProverMode},
+    forge_utils::{check_the_balance, fill_forge_private_key},
+    server::{RunServer, ServerMode},
     wallets::WalletCreation,
 };
 
@@ -50,9 +51,21 @@ pub async fn run(args: EcosystemInitArgs, shell: &Shell) -> anyhow::Result<()> {
         Err(_) => create_initial_deployments_config(shell, &ecosystem_config.config)?,
     };
 
-    let genesis_args = args.genesis_args.clone();
+    let mut genesis_args = args.genesis_args.clone();
     let mut final_ecosystem_args = args.fill_values_with_prompt();
 
+    if final_ecosystem_args.dev {
+        if ecosystem_config.l1_network != L1Network::Localhost {
+            anyhow::bail!("Dev mode is only supported for the localhost L1 network");
+        }
+        genesis_args.use_default = true;
+
+        initialize_docker(shell, &ecosystem_config)?;
+        let spinner = Spinner::new("Starting containers using docker...");
+        start_containers(shell)?;
+        spinner.finish();
+    }
+
     logger::info("Initializing ecosystem");
 
     let contracts_config = init(
@@ -114,6 +127,14 @@ pub async fn run(args: EcosystemInitArgs, shell: &Shell) -> anyhow::Result<()> {
         list_of_chains.join(",")
     ));
 
+    if final_ecosystem_args.dev {
+        let chain_config = ecosystem_config
+            .load_chain(global_config().chain_name.clone())
+            .context("Chain not initialized. Please create a chain first")?;
+        logger::info(format!("Starting server for chain {}", chain_config.name));
+        RunServer::new(None, &chain_config).run(shell, ServerMode::Normal)?;
+    }
+
     Ok(())
 }
 
@@ -10,6 +10,7 @@ mod change_default;
 mod create;
 pub mod create_configs;
 mod init;
+mod teardown;
 
 #[derive(Subcommand, Debug)]
 pub enum EcosystemCommands {
@@ -21,6 +22,9 @@ pub enum EcosystemCommands {
     Init(EcosystemInitArgs),
     /// Change the default chain
     ChangeDefaultChain(ChangeDefaultChain),
+    /// Drop the databases of all chains, clean up their RocksDB state
+    /// and stop the local containers
+    Teardown,
 }
 
 pub(crate) async fn run(shell: &Shell, args: EcosystemCommands) -> anyhow::Result<()> {
@@ -28,5 +32,6 @@ pub(crate) async fn run(shell: &Shell, args: EcosystemCommands) -> anyhow::Resul
         EcosystemCommands::Create(args) => create::run(args, shell),
         EcosystemCommands::Init(args) => init::run(args, shell).await,
         EcosystemCommands::ChangeDefaultChain(args) => change_default::run(args, shell),
+        EcosystemCommands::Teardown => teardown::run(shell).await,
     }
 }
@@ -0,0 +1,53 @@
+use anyhow::Context;
+use common::{db::drop_db_if_exists, docker, logger, spinner::Spinner};
+use url::Url;
+use xshell::Shell;
+
+use crate::{
+    configs::{ChainConfig, DatabaseConfig, EcosystemConfig, ReadConfig, Secrets},
+    consts::{DOCKER_COMPOSE_FILE, SECRETS_FILE},
+};
+
+pub async fn run(shell: &Shell) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+
+    logger::info("Tearing down local ecosystem");
+
+    for chain_name in ecosystem_config.list_of_chains() {
+        let chain_config = ecosystem_config
+            .load_chain(Some(chain_name.clone()))
+            .context("Chain config is malformed")?;
+        let spinner = Spinner::new(&format!("Cleaning up chain {chain_name}..."));
+        teardown_chain(shell, &chain_config).await?;
+        spinner.finish();
+    }
+
+    if shell.path_exists(DOCKER_COMPOSE_FILE) {
+        let spinner = Spinner::new("Stopping containers...");
+        docker::down(shell, DOCKER_COMPOSE_FILE)?;
+        spinner.finish();
+    }
+
+    logger::outro("Ecosystem torn down successfully");
+    Ok(())
+}
+
+async fn teardown_chain(shell: &Shell, chain_config: &ChainConfig) -> anyhow::Result<()> {
+    // Chains that never went through genesis don't have databases to drop.
+    let secrets_path = chain_config.configs.join(SECRETS_FILE);
+    if shell.path_exists(&secrets_path) {
+        let secrets = Secrets::read(shell, &secrets_path)?.resolve(shell)?;
+        for url in [&secrets.database.server_url, &secrets.database.prover_url] {
+            let db = DatabaseConfig::from_url(Url::parse(url)?)?;
+            if let Err(err) = drop_db_if_exists(&db.base_url, &db.database_name).await {
+                logger::warn(format!(
+                    "Failed to drop database {}: {err}",
+                    db.database_name
+                ));
+            }
+        }
+    }
+
+    shell.remove_path(&chain_config.rocks_db_path)?;
+    Ok(())
+}
@@ -0,0 +1,12 @@
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub struct ConsensusKeysArgs {
+    /// Also generate a validator key, so that the external node can sign blocks once the key is added
+    /// to the validator committee
+    #[clap(long)]
+    pub validator: bool,
+    /// Overwrite existing consensus secrets without asking for confirmation
+    #[clap(long, short)]
+    pub yes: bool,
+}
@@ -0,0 +1,2 @@
+pub mod consensus_keys;
+pub mod prepare_configs;
@@ -0,0 +1,86 @@
+use clap::Parser;
+use common::{slugify, Prompt, PromptConfirm};
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    configs::{ChainConfig, DatabaseConfig},
+    defaults::{generate_external_node_db_name, DATABASE_SERVER_URL},
+};
+
+#[derive(Debug, Serialize, Deserialize, Parser, Default)]
+pub struct PrepareConfigArgs {
+    #[clap(long, help = "External node database url without database name")]
+    pub db_url: Option<String>,
+    #[clap(long, help = "External node database name")]
+    pub db_name: Option<String>,
+    #[clap(long, short, help = "Use default database url and name")]
+    pub use_default: bool,
+    /// Create the external node database and run migrations
+    #[clap(long, default_missing_value = "true", num_args = 0..=1)]
+    pub provision_db: Option<bool>,
+    /// Drop the external node database if it already exists when provisioning it
+    #[clap(long)]
+    pub drop_existing_db: bool,
+}
+
+impl PrepareConfigArgs {
+    pub fn fill_values_with_prompt(self, config: &ChainConfig) -> PrepareConfigArgsFinal {
+        let default_db_name = generate_external_node_db_name(config);
+        let chain_name = config.name.clone();
+        if self.use_default {
+            return PrepareConfigArgsFinal {
+                db_url: DATABASE_SERVER_URL.to_string(),
+                db_name: default_db_name,
+                provision_db: self.provision_db.unwrap_or(true),
+                // Never drop an existing database without an explicit request in non-interactive mode.
+                drop_existing_db: Some(self.drop_existing_db),
+            };
+        }
+
+        let db_url = self.db_url.unwrap_or_else(|| {
+            Prompt::new(&format!(
+                "Please provide external node database url for chain {chain_name}"
+            ))
+            .default(DATABASE_SERVER_URL)
+            .ask()
+        });
+        let db_name = slugify(&self.db_name.unwrap_or_else(|| {
+            Prompt::new(&format!(
+                "Please provide external node database name for chain {chain_name}"
+            ))
+            .default(&default_db_name)
+            .ask()
+        }));
+        let provision_db = self.provision_db.unwrap_or_else(|| {
+            PromptConfirm::new("Do you want to create the external node database?")
+                .default(true)
+                .ask()
+        });
+
+        PrepareConfigArgsFinal {
+            db_url,
+            db_name,
+            provision_db,
+            drop_existing_db: self.drop_existing_db.then_some(true),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PrepareConfigArgsFinal {
+    pub db_url: String,
+    pub db_name: String,
+    pub provision_db: bool,
+    /// Whether to drop an existing external node database; `None` means asking the user.
+    pub drop_existing_db: Option<bool>,
+}
+
+impl PrepareConfigArgsFinal {
+    pub fn database_config(&self) -> anyhow::Result<DatabaseConfig> {
+        Ok(DatabaseConfig::new(
+            Url::parse(&self.db_url)?,
+            self.db_name.clone(),
+        ))
+    }
+}
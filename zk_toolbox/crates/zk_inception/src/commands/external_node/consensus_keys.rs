@@ -0,0 +1,87 @@
+use std::fs;
+
+use anyhow::Context;
+use common::{config::global_config, logger, PromptConfirm};
+use serde::Serialize;
+use xshell::Shell;
+use zksync_consensus_crypto::TextFmt;
+use zksync_consensus_roles::{node, validator};
+
+use crate::{
+    commands::external_node::args::consensus_keys::ConsensusKeysArgs, configs::EcosystemConfig,
+    consts::EXTERNAL_NODE_CONSENSUS_SECRETS_FILE,
+};
+
+/// Consensus secrets in the format read by the external node from `EN_CONSENSUS_SECRETS_PATH`.
+#[derive(Debug, Serialize)]
+struct ConsensusSecrets {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    validator_key: Option<String>,
+    node_key: String,
+}
+
+pub fn run(shell: &Shell, args: ConsensusKeysArgs) -> anyhow::Result<()> {
+    let chain_name = global_config().chain_name.clone();
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(chain_name)
+        .context("Chain not initialized. Please create a chain first")?;
+    let secrets_path = chain_config
+        .configs
+        .join(EXTERNAL_NODE_CONSENSUS_SECRETS_FILE);
+
+    if shell.path_exists(&secrets_path) && !args.yes {
+        // Replacing the validator key requires updating the validator committee, so it shouldn't happen by accident.
+        let overwrite = PromptConfirm::new(format!(
+            "Consensus secrets already exist at {}. Overwrite them with new keys?",
+            secrets_path.display()
+        ))
+        .default(false)
+        .ask();
+        if !overwrite {
+            logger::outro("Consensus secrets are left intact");
+            return Ok(());
+        }
+    }
+
+    let node_key = node::SecretKey::generate();
+    let validator_key = args.validator.then(validator::SecretKey::generate);
+    let secrets = ConsensusSecrets {
+        validator_key: validator_key.as_ref().map(TextFmt::encode),
+        node_key: node_key.encode(),
+    };
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        // Make the file readable only by the current user before writing the keys to it. `mode()` only applies
+        // to newly created files, so permissions of an existing file are tightened explicitly.
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&secrets_path)
+            .context("Failed to create consensus secrets file")?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))
+            .context("Failed to restrict consensus secrets file permissions")?;
+    }
+    fs::write(&secrets_path, serde_yaml::to_string(&secrets)?)
+        .context("Failed to write consensus secrets")?;
+
+    let mut public_keys = format!("Node public key: {}", node_key.public().encode());
+    if let Some(validator_key) = &validator_key {
+        public_keys += &format!(
+            "\nValidator public key: {}\nAdd it to the validator committee in the consensus genesis spec of \
+             the main node to make the external node a validator",
+            validator_key.public().encode()
+        );
+    }
+    logger::note("Consensus public keys", public_keys);
+    logger::outro(format!(
+        "Consensus secrets are saved to {}",
+        secrets_path.display()
+    ));
+    Ok(())
+}
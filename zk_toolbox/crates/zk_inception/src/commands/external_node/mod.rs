@@ -0,0 +1,31 @@
+use clap::Subcommand;
+use xshell::Shell;
+
+use crate::commands::external_node::args::{
+    consensus_keys::ConsensusKeysArgs, prepare_configs::PrepareConfigArgs,
+};
+
+mod args;
+mod consensus_keys;
+mod prepare_configs;
+mod run;
+
+pub(crate) use prepare_configs::external_node_env_vars;
+
+#[derive(Subcommand, Debug)]
+pub enum ExternalNodeCommands {
+    /// Generate the external node env file from the chain configs
+    Configs(PrepareConfigArgs),
+    /// Generate consensus node and (optionally) validator keys for the external node
+    ConsensusKeys(ConsensusKeysArgs),
+    /// Run the external node using the generated env file
+    Run,
+}
+
+pub(crate) async fn run(shell: &Shell, args: ExternalNodeCommands) -> anyhow::Result<()> {
+    match args {
+        ExternalNodeCommands::Configs(args) => prepare_configs::run(shell, args).await,
+        ExternalNodeCommands::ConsensusKeys(args) => consensus_keys::run(shell, args),
+        ExternalNodeCommands::Run => run::run(shell),
+    }
+}
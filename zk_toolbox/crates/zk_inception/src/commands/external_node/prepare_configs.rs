@@ -0,0 +1,206 @@
+use std::fmt::Write as _;
+
+use anyhow::Context;
+use common::{
+    config::global_config,
+    db::{database_size, drop_db_if_exists, init_db, migrate_db},
+    logger,
+    spinner::Spinner,
+    PromptConfirm,
+};
+use xshell::Shell;
+
+use crate::{
+    commands::{
+        chain::genesis::SERVER_MIGRATIONS, external_node::args::prepare_configs::PrepareConfigArgs,
+    },
+    configs::{ChainConfig, DatabaseConfig, EcosystemConfig, GeneralConfig, ReadConfig, Secrets},
+    consts::{
+        EXTERNAL_NODE_CONSENSUS_DIR, EXTERNAL_NODE_CONSENSUS_SECRETS_FILE,
+        EXTERNAL_NODE_CONSENSUS_WATERMARK_FILE, EXTERNAL_NODE_ENV_FILE, GENERAL_FILE, SECRETS_FILE,
+    },
+    defaults::{ROCKS_DB_EXTERNAL_NODE_STATE_KEEPER, ROCKS_DB_EXTERNAL_NODE_TREE},
+};
+
+/// Offset applied to the main node ports, so that the external node can run on the same host.
+const EXTERNAL_NODE_PORT_OFFSET: u16 = 10;
+const EXTERNAL_NODE_DB_POOL_SIZE: u32 = 50;
+const EXTERNAL_NODE_API_NAMESPACES: &str = "eth,web3,net,pubsub,zks,en,debug";
+
+pub async fn run(shell: &Shell, args: PrepareConfigArgs) -> anyhow::Result<()> {
+    let chain_name = global_config().chain_name.clone();
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(chain_name)
+        .context("Chain not initialized. Please create a chain first")?;
+    let args = args.fill_values_with_prompt(&chain_config);
+
+    logger::info("Preparing external node config");
+    let db_config = args
+        .database_config()
+        .context("Database config was not fully generated")?;
+    let env_path = chain_config.configs.join(EXTERNAL_NODE_ENV_FILE);
+    let env = external_node_env(shell, &chain_config, &db_config)?;
+    shell.write_file(&env_path, env)?;
+
+    if args.provision_db {
+        provision_database(shell, &chain_config, &db_config, args.drop_existing_db).await?;
+    }
+
+    logger::outro(format!(
+        "External node config is saved to {}",
+        env_path.display()
+    ));
+    Ok(())
+}
+
+fn external_node_env(
+    shell: &Shell,
+    chain_config: &ChainConfig,
+    db_config: &DatabaseConfig,
+) -> anyhow::Result<String> {
+    let mut env = String::new();
+    for (name, value) in external_node_env_vars(shell, chain_config, db_config)? {
+        writeln!(env, "{name}={value}")?;
+    }
+    Ok(env)
+}
+
+/// Returns env variables configuring the external node for the chain, as expected by the external node config loader.
+pub(crate) fn external_node_env_vars(
+    shell: &Shell,
+    chain_config: &ChainConfig,
+    db_config: &DatabaseConfig,
+) -> anyhow::Result<Vec<(&'static str, String)>> {
+    let general = GeneralConfig::read(shell, chain_config.configs.join(GENERAL_FILE))?;
+    let secrets = Secrets::read(shell, chain_config.configs.join(SECRETS_FILE))
+        .context("Secrets are not generated yet. Please run chain genesis first")?
+        .resolve(shell)?;
+    let api = &general.api;
+    let offset = |port: u16| {
+        port.checked_add(EXTERNAL_NODE_PORT_OFFSET)
+            .with_context(|| format!("Port {port} is too large to derive external node port"))
+    };
+
+    let mut vars = vec![
+        ("DATABASE_URL", db_config.full_url()),
+        ("DATABASE_POOL_SIZE", EXTERNAL_NODE_DB_POOL_SIZE.to_string()),
+        (
+            "EN_L1_CHAIN_ID",
+            chain_config.l1_network.chain_id().to_string(),
+        ),
+        ("EN_L2_CHAIN_ID", chain_config.chain_id.to_string()),
+        (
+            "EN_L1_BATCH_COMMIT_DATA_GENERATOR_MODE",
+            format!("{:?}", chain_config.l1_batch_commit_data_generator_mode),
+        ),
+        ("EN_ETH_CLIENT_URL", secrets.l1.l1_rpc_url.clone()),
+        (
+            "EN_MAIN_NODE_URL",
+            format!("http://127.0.0.1:{}", api.web3_json_rpc.http_port),
+        ),
+        (
+            "EN_HTTP_PORT",
+            offset(api.web3_json_rpc.http_port)?.to_string(),
+        ),
+        ("EN_WS_PORT", offset(api.web3_json_rpc.ws_port)?.to_string()),
+        (
+            "EN_HEALTHCHECK_PORT",
+            offset(api.healthcheck.port)?.to_string(),
+        ),
+        (
+            "EN_PROMETHEUS_PORT",
+            offset(api.prometheus.listener_port)?.to_string(),
+        ),
+        (
+            "EN_API_NAMESPACES",
+            EXTERNAL_NODE_API_NAMESPACES.to_string(),
+        ),
+        (
+            "EN_STATE_CACHE_PATH",
+            chain_config
+                .rocks_db_path
+                .join(ROCKS_DB_EXTERNAL_NODE_STATE_KEEPER)
+                .display()
+                .to_string(),
+        ),
+        (
+            "EN_MERKLE_TREE_PATH",
+            chain_config
+                .rocks_db_path
+                .join(ROCKS_DB_EXTERNAL_NODE_TREE)
+                .display()
+                .to_string(),
+        ),
+    ];
+
+    // Consensus secrets are generated by `external-node consensus-keys`.
+    let consensus_secrets_path = chain_config
+        .configs
+        .join(EXTERNAL_NODE_CONSENSUS_SECRETS_FILE);
+    if shell.path_exists(&consensus_secrets_path) {
+        // The watermark is stored outside of RocksDB directories, so that it survives node resets.
+        // The node doesn't create parent directories for it.
+        let consensus_dir = chain_config.configs.join(EXTERNAL_NODE_CONSENSUS_DIR);
+        shell.create_dir(&consensus_dir)?;
+        vars.extend([
+            (
+                "EN_CONSENSUS_SECRETS_PATH",
+                consensus_secrets_path.display().to_string(),
+            ),
+            (
+                "EN_EXPERIMENTAL_CONSENSUS_VALIDATOR_WATERMARK_PATH",
+                consensus_dir
+                    .join(EXTERNAL_NODE_CONSENSUS_WATERMARK_FILE)
+                    .display()
+                    .to_string(),
+            ),
+        ]);
+    }
+    Ok(vars)
+}
+
+async fn provision_database(
+    shell: &Shell,
+    chain_config: &ChainConfig,
+    db_config: &DatabaseConfig,
+    drop_existing_db: Option<bool>,
+) -> anyhow::Result<()> {
+    let db_name = &db_config.database_name;
+    let db_exists = database_size(&db_config.base_url, db_name)
+        .await
+        .context("Failed to check whether external node database exists")?
+        .is_some();
+    let drop_db = db_exists
+        && drop_existing_db.unwrap_or_else(|| {
+            PromptConfirm::new(format!(
+                "External node database {db_name} already exists. Do you want to drop it?"
+            ))
+            .default(false)
+            .ask()
+        });
+
+    let spinner = Spinner::new("Initializing external node database...");
+    if drop_db {
+        drop_db_if_exists(&db_config.base_url, db_name)
+            .await
+            .context("Failed to drop external node database")?;
+    }
+    if drop_db || !db_exists {
+        init_db(&db_config.base_url, db_name).await?;
+    }
+    migrate_db(
+        shell,
+        chain_config.link_to_code.join(SERVER_MIGRATIONS),
+        &db_config.full_url(),
+    )
+    .await?;
+    spinner.finish();
+
+    if db_exists && !drop_db {
+        logger::info(format!(
+            "Kept existing external node database {db_name}; pass --drop-existing-db to recreate it"
+        ));
+    }
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use anyhow::Context;
+use common::{cmd::Cmd, config::global_config, logger};
+use xshell::{cmd, Shell};
+
+use crate::{
+    configs::EcosystemConfig,
+    consts::{EXTERNAL_NODE_ENV_FILE, EXTERNAL_NODE_LOG_FILE},
+};
+
+pub fn run(shell: &Shell) -> anyhow::Result<()> {
+    let chain_name = global_config().chain_name.clone();
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(chain_name)
+        .context("Chain not initialized. Please create a chain first")?;
+
+    let env_path = chain_config.configs.join(EXTERNAL_NODE_ENV_FILE);
+    if !shell.path_exists(&env_path) {
+        anyhow::bail!(
+            "External node config is not generated yet. Please run `zk_inception external-node configs` first"
+        );
+    }
+    let env = shell.read_file(&env_path)?;
+    let env = parse_env_file(&env).with_context(|| format!("Failed parsing {env_path:?}"))?;
+    let log_file = chain_config.logs_path().join(EXTERNAL_NODE_LOG_FILE);
+
+    logger::info("Starting external node");
+    shell.change_dir(&chain_config.link_to_code);
+    Cmd::new(
+        cmd!(shell, "cargo run --release --bin zksync_external_node")
+            .envs(env)
+            .env_remove("RUSTUP_TOOLCHAIN"),
+    )
+    .run_with_log_file(&log_file)
+    .context("Failed to run external node")
+}
+
+/// Parses `NAME=value` lines of the env file generated by `external-node configs`.
+fn parse_env_file(contents: &str) -> anyhow::Result<Vec<(&str, &str)>> {
+    contents
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| {
+            line.split_once('=')
+                .with_context(|| format!("Invalid env file line: `{line}`"))
+        })
+        .collect()
+}
@@ -1,5 +1,8 @@
 pub mod args;
 pub mod chain;
 pub mod containers;
+pub mod deploy_manifests;
 pub mod ecosystem;
+pub mod external_node;
+pub mod prerequisites;
 pub mod server;
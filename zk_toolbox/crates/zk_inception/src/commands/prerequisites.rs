@@ -0,0 +1,20 @@
+use common::{check_prerequisites, logger, prerequisites_report};
+use xshell::Shell;
+
+use crate::commands::args::PrerequisitesArgs;
+
+pub fn run(shell: &Shell, args: PrerequisitesArgs) -> anyhow::Result<()> {
+    if !args.json {
+        // Exits the process on failure, printing remediation hints.
+        check_prerequisites(shell);
+        logger::outro("All prerequisites are installed");
+        return Ok(());
+    }
+
+    let report = prerequisites_report(shell);
+    println!("{}", serde_json::to_string_pretty(&report)?);
+    if !report.is_ok() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
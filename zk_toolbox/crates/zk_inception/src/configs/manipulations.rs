@@ -1,23 +1,21 @@
 use std::path::Path;
 
+use config::types::ProverMode;
 use xshell::Shell;
 
 use crate::{
     configs::{
-        chain::ChainConfig,
-        contracts::ContractsConfig,
         forge_interface::{
             initialize_bridges::output::InitializeBridgeOutput, paymaster::DeployPaymasterOutput,
             register_chain::output::RegisterChainOutput,
         },
-        DatabasesConfig, EcosystemConfig, GeneralConfig, GenesisConfig, ReadConfig, SaveConfig,
-        Secrets,
+        ChainConfig, ContractsConfig, DatabasesConfig, EcosystemConfig, GeneralConfig,
+        GenesisConfig, ReadConfig, SaveConfig, Secrets,
     },
     consts::{
         CONFIGS_PATH, CONTRACTS_FILE, GENERAL_FILE, GENESIS_FILE, SECRETS_FILE, WALLETS_FILE,
     },
     defaults::{ROCKS_DB_STATE_KEEPER, ROCKS_DB_TREE},
-    types::ProverMode,
 };
 
 pub(crate) fn copy_configs(
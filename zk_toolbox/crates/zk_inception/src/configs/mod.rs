@@ -1,18 +1,4 @@
-mod chain;
-pub mod contracts;
-mod ecosystem;
-pub mod forge_interface;
-mod general;
 mod manipulations;
-mod secrets;
-mod traits;
-mod wallets;
 
-pub use chain::*;
-pub use contracts::*;
-pub use ecosystem::*;
-pub use general::*;
+pub use config::*;
 pub use manipulations::*;
-pub use secrets::*;
-pub use traits::*;
-pub use wallets::*;
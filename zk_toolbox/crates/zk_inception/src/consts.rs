@@ -1,45 +1,46 @@
 use std::path::{Path, PathBuf};
 
-use crate::types::ChainId;
-
-/// Name of the main configuration file
-pub(super) const CONFIG_NAME: &str = "ZkStack.yaml";
-/// Name of the wallets file
-pub(super) const WALLETS_FILE: &str = "wallets.yaml";
-/// Name of the secrets config file
-pub(super) const SECRETS_FILE: &str = "secrets.yaml";
-/// Name of the general config file
-pub(super) const GENERAL_FILE: &str = "general.yaml";
-/// Name of the genesis config file
-pub(super) const GENESIS_FILE: &str = "genesis.yaml";
+pub(super) use config::consts::*;
+use config::types::ChainId;
+
+/// Name of the temporary file with resolved secrets passed to the server if secrets reference secret providers
+pub(super) const SERVER_SECRETS_FILE: &str = ".server_secrets.yaml";
+/// Name of the external node env file
+pub(super) const EXTERNAL_NODE_ENV_FILE: &str = "external_node.env";
+/// Name of the external node consensus secrets file
+pub(super) const EXTERNAL_NODE_CONSENSUS_SECRETS_FILE: &str =
+    "external_node_consensus_secrets.yaml";
+/// Name of the directory (inside the chain configs) with the external node consensus state that must survive
+/// database resets, i.e. the validator signing watermark
+pub(super) const EXTERNAL_NODE_CONSENSUS_DIR: &str = "external_node_consensus";
+/// Name of the validator signing watermark file inside [`EXTERNAL_NODE_CONSENSUS_DIR`]
+pub(super) const EXTERNAL_NODE_CONSENSUS_WATERMARK_FILE: &str = "watermark.json";
 
 pub(super) const ERC20_CONFIGS_FILE: &str = "erc20.yaml";
-/// Name of the initial deployments config file
-pub(super) const INITIAL_DEPLOYMENT_FILE: &str = "initial_deployments.yaml";
-/// Name of the erc20 deployments config file
-pub(super) const ERC20_DEPLOYMENT_FILE: &str = "erc20_deployments.yaml";
-/// Name of the contracts file
-pub(super) const CONTRACTS_FILE: &str = "contracts.yaml";
 /// Main repository for the zkSync project
 pub(super) const ZKSYNC_ERA_GIT_REPO: &str = "https://github.com/matter-labs/zksync-era";
 /// Name of the docker-compose file inside zksync repository
 pub(super) const DOCKER_COMPOSE_FILE: &str = "docker-compose.yml";
+/// Name of the chain directory the deployment manifests are written to by default
+pub(super) const DEPLOY_MANIFESTS_PATH: &str = "deploy/";
+/// Name of the Helm values file with the chain deployment
+pub(super) const HELM_VALUES_FILE: &str = "values.yaml";
 /// Path to the config file with mnemonic for localhost wallets
 pub(super) const CONFIGS_PATH: &str = "etc/env/file_based";
 pub(super) const LOCAL_CONFIGS_PATH: &str = "configs/";
 pub(super) const LOCAL_DB_PATH: &str = "db/";
+/// Name of the file inside the chain logs folder the server output is written to
+pub(super) const SERVER_LOG_FILE: &str = "server.log";
+/// Name of the file inside the chain logs folder the external node output is written to
+pub(super) const EXTERNAL_NODE_LOG_FILE: &str = "external_node.log";
 
 /// Path to ecosystem contacts
 pub(super) const ECOSYSTEM_PATH: &str = "etc/ecosystem";
 
-/// Path to l1 contracts foundry folder inside zksync-era
-pub(super) const L1_CONTRACTS_FOUNDRY: &str = "contracts/l1-contracts-foundry";
 /// Path to DeployL1.s.sol script inside zksync-era relative to `L1_CONTRACTS_FOUNDRY`
 
 pub(super) const ERA_CHAIN_ID: ChainId = ChainId(270);
 
-pub(super) const TEST_CONFIG_PATH: &str = "etc/test_config/constant/eth.json";
-pub(super) const BASE_PATH: &str = "m/44'/60'/0'";
 pub(super) const AMOUNT_FOR_DISTRIBUTION_TO_WALLETS: u128 = 1000000000000000000000;
 
 pub(super) const MINIMUM_BALANCE_FOR_WALLET: u128 = 5000000000000000000;
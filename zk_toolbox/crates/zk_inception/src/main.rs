@@ -7,7 +7,12 @@ use common::{
 use xshell::Shell;
 
 use crate::{
-    commands::{args::RunServerArgs, chain::ChainCommands, ecosystem::EcosystemCommands},
+    commands::{
+        args::{DeployManifestsArgs, PrerequisitesArgs, RunServerArgs},
+        chain::ChainCommands,
+        ecosystem::EcosystemCommands,
+        external_node::ExternalNodeCommands,
+    },
     configs::EcosystemConfig,
 };
 
@@ -18,7 +23,6 @@ mod consts;
 mod defaults;
 pub mod forge_utils;
 pub mod server;
-mod types;
 mod wallets;
 
 #[derive(Parser, Debug)]
@@ -40,8 +44,15 @@ pub enum InceptionSubcommands {
     Chain(ChainCommands),
     /// Run server
     Server(RunServerArgs),
+    /// External node related commands
+    #[command(subcommand)]
+    ExternalNode(ExternalNodeCommands),
     /// Run containers for local development
     Containers,
+    /// Generate Docker Compose or Helm deployment manifests for the chain
+    DeployManifests(DeployManifestsArgs),
+    /// Check that all prerequisites are installed and up to date
+    Prerequisites(PrerequisitesArgs),
 }
 
 #[derive(Parser, Debug)]
@@ -72,7 +83,12 @@ async fn main() -> anyhow::Result<()> {
 
     init_global_config_inner(&shell, &inception_args.global)?;
 
-    if !global_config().ignore_prerequisites {
+    // The prerequisites command runs the check itself, reporting the result in the requested format.
+    let is_prerequisites_command = matches!(
+        inception_args.command,
+        InceptionSubcommands::Prerequisites(_)
+    );
+    if !global_config().ignore_prerequisites && !is_prerequisites_command {
         check_prerequisites(&shell);
     }
 
@@ -105,7 +121,14 @@ async fn run_subcommand(inception_args: Inception, shell: &Shell) -> anyhow::Res
         InceptionSubcommands::Ecosystem(args) => commands::ecosystem::run(shell, args).await?,
         InceptionSubcommands::Chain(args) => commands::chain::run(shell, args).await?,
         InceptionSubcommands::Server(args) => commands::server::run(shell, args)?,
+        InceptionSubcommands::ExternalNode(args) => {
+            commands::external_node::run(shell, args).await?
+        }
         InceptionSubcommands::Containers => commands::containers::run(shell)?,
+        InceptionSubcommands::DeployManifests(args) => {
+            commands::deploy_manifests::run(shell, args)?
+        }
+        InceptionSubcommands::Prerequisites(args) => commands::prerequisites::run(shell, args)?,
     }
     Ok(())
 }
@@ -1,12 +1,18 @@
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use anyhow::Context;
 use common::cmd::Cmd;
 use xshell::{cmd, Shell};
 
 use crate::{
-    configs::ChainConfig,
-    consts::{CONTRACTS_FILE, GENERAL_FILE, GENESIS_FILE, SECRETS_FILE, WALLETS_FILE},
+    configs::{ChainConfig, ReadConfig, SaveConfig, Secrets},
+    consts::{
+        CONTRACTS_FILE, GENERAL_FILE, GENESIS_FILE, SECRETS_FILE, SERVER_LOG_FILE,
+        SERVER_SECRETS_FILE, WALLETS_FILE,
+    },
 };
 
 pub struct RunServer {
@@ -17,6 +23,7 @@ pub struct RunServer {
     general_config: PathBuf,
     genesis: PathBuf,
     secrets: PathBuf,
+    log_file: PathBuf,
 }
 
 pub enum ServerMode {
@@ -31,6 +38,7 @@ impl RunServer {
         let genesis = chain_config.configs.join(GENESIS_FILE);
         let contracts = chain_config.configs.join(CONTRACTS_FILE);
         let secrets = chain_config.configs.join(SECRETS_FILE);
+        let log_file = chain_config.logs_path().join(SERVER_LOG_FILE);
 
         Self {
             components,
@@ -40,16 +48,18 @@ impl RunServer {
             general_config,
             genesis,
             secrets,
+            log_file,
         }
     }
 
     pub fn run(&self, shell: &Shell, server_mode: ServerMode) -> anyhow::Result<()> {
+        let secrets = ServerSecrets::new(shell, &self.secrets)?;
         shell.change_dir(&self.code_path);
         let config_genesis = &self.genesis.to_str().unwrap();
         let config_wallets = &self.wallets.to_str().unwrap();
         let config_general_config = &self.general_config.to_str().unwrap();
         let config_contracts = &self.contracts.to_str().unwrap();
-        let secrets = &self.secrets.to_str().unwrap();
+        let secrets_path = &secrets.path.to_str().unwrap();
         let mut additional_args = vec![];
         if let Some(components) = self.components() {
             additional_args.push(format!("--components={}", components))
@@ -58,25 +68,28 @@ impl RunServer {
             additional_args.push("--genesis".to_string());
         }
 
-        let mut cmd = Cmd::new(
+        let cmd = Cmd::new(
             cmd!(
                 shell,
                 "cargo run --release --bin zksync_server --
                 --genesis-path {config_genesis}
                 --wallets-path {config_wallets}
                 --config-path {config_general_config}
-                --secrets-path {secrets}
+                --secrets-path {secrets_path}
                 --contracts-config-path {config_contracts}
                 "
             )
             .args(additional_args)
+            .envs(secrets.env.iter().map(|(name, value)| (name, value)))
             .env_remove("RUSTUP_TOOLCHAIN"),
         );
 
         // If we are running server in normal mode
-        // we need to get the output to the console
+        // we need to get the output to the console and persist it to the chain logs
         if let ServerMode::Normal = server_mode {
-            cmd = cmd.with_force_run();
+            return cmd
+                .run_with_log_file(&self.log_file)
+                .context("Failed to run server");
         }
 
         cmd.run().context("Failed to run server")?;
@@ -92,3 +105,46 @@ impl RunServer {
         })
     }
 }
+
+/// Secrets file passed to the server. The server can only resolve `env://` and `file://` secret references, so if
+/// the chain secrets contain other references, they are resolved and passed to the server via env variables.
+/// The secrets file passed to the server in this case references these variables, so that resolved secrets
+/// are never written to disk.
+struct ServerSecrets {
+    path: PathBuf,
+    env: Vec<(String, String)>,
+    is_temporary: bool,
+}
+
+impl ServerSecrets {
+    const ENV_PREFIX: &'static str = "ZKSYNC_SERVER_SECRET";
+
+    fn new(shell: &Shell, secrets_path: &Path) -> anyhow::Result<Self> {
+        let secrets = Secrets::read(shell, secrets_path)?;
+        let (server_secrets, env) = secrets.resolve_to_env(shell, Self::ENV_PREFIX)?;
+        if env.is_empty() {
+            return Ok(Self {
+                path: secrets_path.to_owned(),
+                env,
+                is_temporary: false,
+            });
+        }
+
+        let path = secrets_path.with_file_name(SERVER_SECRETS_FILE);
+        server_secrets.save(shell, &path)?;
+        Ok(Self {
+            path,
+            env,
+            is_temporary: true,
+        })
+    }
+}
+
+impl Drop for ServerSecrets {
+    fn drop(&mut self) {
+        // The file doesn't contain secrets, so it's not an issue if it's not removed (e.g., on a signal).
+        if self.is_temporary {
+            fs::remove_file(&self.path).ok();
+        }
+    }
+}
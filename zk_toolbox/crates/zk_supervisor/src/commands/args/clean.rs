@@ -0,0 +1,11 @@
+use clap::Parser;
+
+#[derive(Debug, Parser)]
+pub struct CleanArgs {
+    /// Keep the chain databases
+    #[clap(long)]
+    pub keep_databases: bool,
+    /// Drop the databases without asking for confirmation
+    #[clap(long, short)]
+    pub yes: bool,
+}
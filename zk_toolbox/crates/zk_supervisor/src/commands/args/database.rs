@@ -0,0 +1,84 @@
+use clap::{Parser, ValueEnum};
+use config::DatabaseSecrets;
+use strum_macros::Display;
+
+/// Path to the core DAL migrations relative to the code directory.
+const CORE_MIGRATIONS: &str = "core/lib/dal/migrations";
+/// Path to the prover DAL migrations relative to the code directory.
+const PROVER_MIGRATIONS: &str = "prover/prover_dal/migrations";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Dal {
+    Core,
+    Prover,
+}
+
+impl Dal {
+    pub const ALL: [Self; 2] = [Self::Core, Self::Prover];
+
+    pub fn migrations_path(self) -> &'static str {
+        match self {
+            Self::Core => CORE_MIGRATIONS,
+            Self::Prover => PROVER_MIGRATIONS,
+        }
+    }
+
+    pub fn database_url(self, secrets: &DatabaseSecrets) -> &str {
+        match self {
+            Self::Core => &secrets.server_url,
+            Self::Prover => &secrets.prover_url,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+pub struct DatabaseStatusArgs {
+    /// Only show the status of this database
+    #[clap(long, value_enum)]
+    pub dal: Option<Dal>,
+}
+
+impl DatabaseStatusArgs {
+    pub fn dals(&self) -> Vec<Dal> {
+        match self.dal {
+            Some(dal) => vec![dal],
+            None => Dal::ALL.to_vec(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display)]
+#[strum(serialize_all = "kebab-case")]
+pub enum MigrationTemplate {
+    /// Adds a nullable column to a table
+    AddColumn,
+    /// Creates an index without blocking writes to the table
+    AddIndexConcurrently,
+    /// Fills a column in batches, committing after each batch
+    BackfillWithBatching,
+}
+
+#[derive(Debug, Parser)]
+pub struct DatabaseNewMigrationArgs {
+    /// Database to create the migration for
+    #[clap(long, value_enum, default_value_t = Dal::Core)]
+    pub dal: Dal,
+    /// Migration name in snake case starting with a verb, e.g. `add_l1_batch_timestamp_index`
+    pub name: Option<String>,
+    /// Template to generate up and down migrations from. Empty migrations are created if not specified
+    #[clap(long, value_enum)]
+    pub template: Option<MigrationTemplate>,
+    /// Table changed by the template
+    #[clap(long)]
+    pub table: Option<String>,
+    /// Column changed by the template; comma-separated list of columns for `add-index-concurrently`
+    #[clap(long)]
+    pub column: Option<String>,
+    /// Type of the column added by `add-column`, e.g. `BIGINT`
+    #[clap(long)]
+    pub column_type: Option<String>,
+    /// Don't fail if the migration name contains words missing in the spellcheck dictionaries
+    #[clap(long)]
+    pub skip_spellcheck: bool,
+}
@@ -0,0 +1,23 @@
+use clap::{Parser, ValueEnum};
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Formatter {
+    /// `rustfmt` for all Rust workspaces, and sqlx query formatting for DAL crates
+    Rustfmt,
+    /// `prettier` for TS, JS and Markdown files
+    Prettier,
+    /// `prettier` for contracts, using the scripts defined in the `contracts` folder
+    Contracts,
+}
+
+#[derive(Debug, Parser)]
+pub struct FmtArgs {
+    /// Check that the code is formatted without changing it
+    #[clap(long)]
+    pub check: bool,
+    /// Formatters to run. If not specified, all formatters are run
+    #[clap(value_enum)]
+    pub formatters: Vec<Formatter>,
+}
@@ -0,0 +1,42 @@
+use clap::{Parser, ValueEnum};
+use strum_macros::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Component {
+    Server,
+    ExternalNode,
+}
+
+impl Component {
+    /// Name of the file inside the chain logs folder the component output is written to.
+    pub fn log_file(self) -> String {
+        format!("{self}.log")
+    }
+}
+
+/// Log severity, ordered from the least to the most verbose.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+#[derive(Debug, Parser)]
+pub struct LogsArgs {
+    /// Component to show the logs of
+    #[clap(long, value_enum, default_value_t = Component::Server)]
+    pub component: Component,
+    /// Keep printing new log lines as they are written
+    #[clap(long, short)]
+    pub follow: bool,
+    /// Show only lines with this or higher severity
+    #[clap(long, value_enum)]
+    pub level: Option<LogLevel>,
+    /// Number of last lines to show
+    #[clap(long, short = 'n', default_value_t = 100)]
+    pub lines: usize,
+}
@@ -0,0 +1,9 @@
+mod clean;
+mod database;
+mod fmt;
+mod logs;
+
+pub use clean::*;
+pub use database::*;
+pub use fmt::*;
+pub use logs::*;
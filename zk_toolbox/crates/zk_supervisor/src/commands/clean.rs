@@ -0,0 +1,149 @@
+use std::{collections::HashSet, path::PathBuf};
+
+use anyhow::Context;
+use common::{
+    config::global_config, db::drop_db_if_exists, logger, spinner::Spinner, PromptConfirm,
+};
+use config::{consts::GENERAL_FILE, ChainConfig, DatabaseConfig, EcosystemConfig};
+use url::Url;
+use xshell::Shell;
+
+use crate::commands::args::CleanArgs;
+
+pub async fn run(shell: &Shell, args: CleanArgs) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context("Chain not found")?;
+
+    logger::info(format!("Cleaning chain {}", chain_config.name));
+
+    let spinner = Spinner::new("Removing RocksDB state...");
+    shell.remove_path(&chain_config.rocks_db_path)?;
+    shell.create_dir(&chain_config.rocks_db_path)?;
+    spinner.finish();
+
+    let spinner = Spinner::new("Removing object store artifacts...");
+    clean_object_stores(shell, &ecosystem_config, &chain_config)?;
+    spinner.finish();
+
+    if !args.keep_databases {
+        drop_databases(shell, &chain_config, args.yes).await?;
+    }
+
+    logger::outro(format!(
+        "Chain {} was cleaned. Run genesis to start it from scratch",
+        chain_config.name
+    ));
+    Ok(())
+}
+
+fn clean_object_stores(
+    shell: &Shell,
+    ecosystem_config: &EcosystemConfig,
+    chain_config: &ChainConfig,
+) -> anyhow::Result<()> {
+    // Object stores may be shared with other chains (e.g., all chains use the `artifacts` folder in the code
+    // directory by default); removing them would break the chains that are still running.
+    let mut shared_paths = HashSet::new();
+    for name in ecosystem_config.list_of_chains() {
+        if name == chain_config.name {
+            continue;
+        }
+        if let Some(other_chain) = ecosystem_config.load_chain(Some(name)) {
+            shared_paths.extend(object_store_paths(shell, ecosystem_config, &other_chain)?);
+        }
+    }
+
+    for path in object_store_paths(shell, ecosystem_config, chain_config)? {
+        if path == ecosystem_config.link_to_code || !shell.path_exists(&path) {
+            continue;
+        }
+        if shared_paths.contains(&path) {
+            logger::warn(format!(
+                "Skipping {}, it's used by other chains of the ecosystem",
+                path.display()
+            ));
+            continue;
+        }
+        if global_config().verbose {
+            logger::debug(format!("Removing {}", path.display()));
+        }
+        shell.remove_path(&path)?;
+    }
+    Ok(())
+}
+
+/// Returns base paths of all file-backed object stores of the chain.
+fn object_store_paths(
+    shell: &Shell,
+    ecosystem_config: &EcosystemConfig,
+    chain_config: &ChainConfig,
+) -> anyhow::Result<HashSet<PathBuf>> {
+    if !shell.path_exists(chain_config.configs.join(GENERAL_FILE)) {
+        return Ok(HashSet::new());
+    }
+    let general = serde_json::to_value(chain_config.get_general_config()?)?;
+    let mut paths = HashSet::new();
+    collect_file_backed_paths(&general, &mut paths);
+    // Relative paths are resolved the same way as the server does, since it's run from the code directory.
+    Ok(paths
+        .into_iter()
+        .map(|path| ecosystem_config.link_to_code.join(path))
+        .collect())
+}
+
+fn collect_file_backed_paths(value: &serde_json::Value, paths: &mut HashSet<PathBuf>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                match (key.as_str(), value.as_str()) {
+                    ("file_backed_base_path", Some(path)) => {
+                        paths.insert(path.into());
+                    }
+                    _ => collect_file_backed_paths(value, paths),
+                }
+            }
+        }
+        serde_json::Value::Array(values) => {
+            for value in values {
+                collect_file_backed_paths(value, paths);
+            }
+        }
+        _ => {}
+    }
+}
+
+async fn drop_databases(
+    shell: &Shell,
+    chain_config: &ChainConfig,
+    skip_confirmation: bool,
+) -> anyhow::Result<()> {
+    let Some(secrets) = chain_config.get_secrets_config()? else {
+        logger::info("Chain has no databases yet, skipping");
+        return Ok(());
+    };
+    let database = secrets.database.resolve(shell)?;
+
+    for url in [&database.server_url, &database.prover_url] {
+        let url = Url::parse(url).context("Invalid database url")?;
+        let DatabaseConfig {
+            base_url: url,
+            database_name: name,
+        } = DatabaseConfig::from_url(url)?;
+
+        let confirmed = skip_confirmation
+            || PromptConfirm::new(format!("Do you want to drop database {name}?"))
+                .default(false)
+                .ask();
+        if !confirmed {
+            continue;
+        }
+        let spinner = Spinner::new(&format!("Dropping database {name}..."));
+        drop_db_if_exists(&url, &name)
+            .await
+            .with_context(|| format!("Failed to drop database {name}"))?;
+        spinner.finish();
+    }
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use clap::Subcommand;
+use xshell::Shell;
+
+use crate::commands::args::{DatabaseNewMigrationArgs, DatabaseStatusArgs};
+
+mod new_migration;
+mod status;
+
+#[derive(Subcommand, Debug)]
+pub enum DatabaseCommands {
+    /// Show applied and pending migrations, and sizes of the chain databases
+    Status(DatabaseStatusArgs),
+    /// Create up and down migrations, optionally generated from a template
+    NewMigration(DatabaseNewMigrationArgs),
+}
+
+pub async fn run(shell: &Shell, args: DatabaseCommands) -> anyhow::Result<()> {
+    match args {
+        DatabaseCommands::Status(args) => status::run(shell, args).await,
+        DatabaseCommands::NewMigration(args) => new_migration::run(shell, args),
+    }
+}
@@ -0,0 +1,342 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use common::{cmd::Cmd, logger, Prompt};
+use config::EcosystemConfig;
+use xshell::{cmd, Shell};
+
+use crate::commands::args::{DatabaseNewMigrationArgs, MigrationTemplate};
+
+/// Verbs a migration name may start with, so that the name describes the change made by the migration.
+const MIGRATION_VERBS: [&str; 13] = [
+    "add", "alter", "backfill", "change", "create", "drop", "fix", "make", "remove", "rename",
+    "revert", "set", "update",
+];
+/// Maximum length of a migration name.
+const MAX_MIGRATION_NAME_LEN: usize = 80;
+/// Maximum length of a Postgres identifier; longer identifiers are silently truncated by Postgres.
+const MAX_IDENTIFIER_LEN: usize = 63;
+/// Config of the spellcheck run in CI.
+const CSPELL_CONFIG: &str = "checks-config/cspell.json";
+
+pub fn run(shell: &Shell, args: DatabaseNewMigrationArgs) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let link_to_code = &ecosystem_config.link_to_code;
+    let migrations_dir = link_to_code.join(args.dal.migrations_path());
+
+    let name = match args.name {
+        Some(name) => name,
+        None => Prompt::new("Migration name, e.g. `add_l1_batch_timestamp_index`:").ask(),
+    };
+    validate_migration_name(&name)?;
+    ensure_name_is_unique(shell, &migrations_dir, &name)?;
+    if !args.skip_spellcheck {
+        spellcheck(shell, link_to_code, &name)?;
+    }
+
+    let sql = args
+        .template
+        .map(|template| {
+            let params = TemplateParams::new(template, args.table, args.column, args.column_type)?;
+            anyhow::Ok(render_template(template, &params))
+        })
+        .transpose()?;
+
+    // The migration is created with `sqlx` to get the same versioning as the existing migrations.
+    let dal_dir = migrations_dir
+        .parent()
+        .context("Migrations directory has no parent")?;
+    let _dir_guard = shell.push_dir(dal_dir);
+    Cmd::new(cmd!(shell, "cargo sqlx migrate add -r {name}")).run()?;
+
+    let (up_path, down_path) = find_migration_files(shell, &migrations_dir, &name)?;
+    if let Some((up, down)) = sql {
+        shell.write_file(&up_path, up)?;
+        shell.write_file(&down_path, down)?;
+    }
+    logger::outro(format!(
+        "Created migration {name}:\n  {}\n  {}",
+        up_path.display(),
+        down_path.display()
+    ));
+    Ok(())
+}
+
+/// Checks that the name is in snake case and starts with one of [`MIGRATION_VERBS`].
+fn validate_migration_name(name: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        name.len() <= MAX_MIGRATION_NAME_LEN,
+        "Migration name `{name}` is longer than {MAX_MIGRATION_NAME_LEN} characters"
+    );
+    anyhow::ensure!(
+        is_snake_case(name),
+        "Migration name `{name}` must be in snake case, i.e. consist of lowercase words, \
+         digits and single underscores, e.g. `add_l1_batch_timestamp_index`"
+    );
+    let verb = name.split('_').next().unwrap_or_default();
+    anyhow::ensure!(
+        MIGRATION_VERBS.contains(&verb),
+        "Migration name `{name}` must start with one of the verbs: {}",
+        MIGRATION_VERBS.join(", ")
+    );
+    anyhow::ensure!(
+        name.contains('_'),
+        "Migration name `{name}` must describe what is changed, e.g. `{verb}_<table>_<column>`"
+    );
+    Ok(())
+}
+
+fn is_snake_case(name: &str) -> bool {
+    name.starts_with(|ch: char| ch.is_ascii_lowercase())
+        && name.split('_').all(|word| {
+            !word.is_empty()
+                && word
+                    .chars()
+                    .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit())
+        })
+}
+
+fn ensure_name_is_unique(shell: &Shell, migrations_dir: &Path, name: &str) -> anyhow::Result<()> {
+    for path in shell.read_dir(migrations_dir)? {
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let existing_name = file_name.split_once('_').map(|(_, rest)| {
+            rest.trim_end_matches(".up.sql")
+                .trim_end_matches(".down.sql")
+        });
+        anyhow::ensure!(
+            existing_name != Some(name),
+            "Migration `{name}` already exists: {}",
+            path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Checks words of the name with `cspell` using the config from CI. The check is skipped with a warning
+/// if `cspell` is not installed.
+fn spellcheck(shell: &Shell, link_to_code: &Path, name: &str) -> anyhow::Result<()> {
+    let _dir_guard = shell.push_dir(link_to_code);
+    let words = name.replace('_', " ");
+    let output = cmd!(
+        shell,
+        "npx --no-install cspell --config {CSPELL_CONFIG} --no-progress --no-summary --words-only --unique stdin"
+    )
+    .stdin(words)
+    .quiet()
+    .ignore_status()
+    .output();
+
+    let output = match output {
+        Ok(output) if output.status.success() => return Ok(()),
+        // `cspell` exits with code 1 if it has found unknown words.
+        Ok(output) if output.status.code() == Some(1) && !output.stdout.is_empty() => output,
+        _ => {
+            logger::warn("Failed to run `cspell`, skipping the spellcheck of the migration name");
+            return Ok(());
+        }
+    };
+    let unknown_words: Vec<_> = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect();
+    anyhow::bail!(
+        "Migration name `{name}` contains unknown words: {}. Fix the spelling, add the words to \
+         `checks-config/era.dic` or pass `--skip-spellcheck`",
+        unknown_words.join(", ")
+    )
+}
+
+fn find_migration_files(
+    shell: &Shell,
+    migrations_dir: &Path,
+    name: &str,
+) -> anyhow::Result<(PathBuf, PathBuf)> {
+    let up_suffix = format!("_{name}.up.sql");
+    let up_path = shell
+        .read_dir(migrations_dir)?
+        .into_iter()
+        .find(|path| {
+            path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|file_name| file_name.ends_with(&up_suffix))
+        })
+        .with_context(|| format!("Up migration for `{name}` was not created"))?;
+    let down_path = up_path.with_file_name(
+        up_path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .context("Invalid migration file name")?
+            .replace(".up.sql", ".down.sql"),
+    );
+    anyhow::ensure!(
+        shell.path_exists(&down_path),
+        "Down migration for `{name}` was not created"
+    );
+    Ok((up_path, down_path))
+}
+
+/// Parameters of a migration template; missing ones are requested interactively.
+struct TemplateParams {
+    table: String,
+    columns: Vec<String>,
+    column_type: Option<String>,
+}
+
+impl TemplateParams {
+    fn new(
+        template: MigrationTemplate,
+        table: Option<String>,
+        column: Option<String>,
+        column_type: Option<String>,
+    ) -> anyhow::Result<Self> {
+        let table = table.unwrap_or_else(|| Prompt::new("Table:").ask());
+        validate_identifier(&table)?;
+
+        let column = column.unwrap_or_else(|| match template {
+            MigrationTemplate::AddIndexConcurrently => {
+                Prompt::new("Indexed columns (comma-separated):").ask()
+            }
+            _ => Prompt::new("Column:").ask(),
+        });
+        let columns: Vec<_> = column.split(',').map(|col| col.trim().to_owned()).collect();
+        for column in &columns {
+            validate_identifier(column)?;
+        }
+        anyhow::ensure!(
+            columns.len() == 1 || template == MigrationTemplate::AddIndexConcurrently,
+            "Template `{template}` changes a single column, got: {column}"
+        );
+
+        let column_type = match template {
+            MigrationTemplate::AddColumn => {
+                let column_type =
+                    column_type.unwrap_or_else(|| Prompt::new("Column type, e.g. `BIGINT`:").ask());
+                anyhow::ensure!(
+                    !column_type.trim().is_empty() && !column_type.contains(';'),
+                    "Invalid column type: `{column_type}`"
+                );
+                Some(column_type.trim().to_owned())
+            }
+            _ => None,
+        };
+
+        let params = Self {
+            table,
+            columns,
+            column_type,
+        };
+        if template == MigrationTemplate::AddIndexConcurrently {
+            validate_identifier(&params.index_name())?;
+        }
+        Ok(params)
+    }
+
+    fn index_name(&self) -> String {
+        format!("{}_{}_idx", self.table, self.columns.join("_"))
+    }
+}
+
+fn validate_identifier(identifier: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        identifier.len() <= MAX_IDENTIFIER_LEN,
+        "Identifier `{identifier}` is longer than {MAX_IDENTIFIER_LEN} characters"
+    );
+    anyhow::ensure!(
+        identifier.starts_with(|ch: char| ch.is_ascii_lowercase() || ch == '_')
+            && identifier
+                .chars()
+                .all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '_'),
+        "Identifier `{identifier}` must consist of lowercase letters, digits and underscores"
+    );
+    Ok(())
+}
+
+/// Returns up and down migrations generated from the template.
+fn render_template(template: MigrationTemplate, params: &TemplateParams) -> (String, String) {
+    let table = &params.table;
+    let column = &params.columns[0];
+    match template {
+        MigrationTemplate::AddColumn => {
+            let column_type = params.column_type.as_deref().unwrap_or_default();
+            let up = format!(
+                "\
+-- Adding a nullable column without a default is a metadata-only change that doesn't rewrite the table.
+-- Avoid `NOT NULL` constraints and volatile defaults (e.g., `NOW()`) for large tables: fill the column
+-- with a `backfill-with-batching` migration and add the constraint afterwards.
+ALTER TABLE {table} ADD COLUMN IF NOT EXISTS {column} {column_type};
+"
+            );
+            let down = format!(
+                "\
+-- Dropping the column loses its data. Make sure that the previous server version doesn't read it.
+ALTER TABLE {table} DROP COLUMN IF EXISTS {column};
+"
+            );
+            (up, down)
+        }
+        MigrationTemplate::AddIndexConcurrently => {
+            let index = params.index_name();
+            let columns = params.columns.join(", ");
+            let up = format!(
+                "\
+-- no-transaction
+-- `CREATE INDEX CONCURRENTLY` doesn't block writes to the table, but can't run in a transaction. The directive
+-- above must stay the first line of the file. Don't add other statements to the migration: multiple statements
+-- are executed in an implicit transaction.
+-- If the migration fails, it leaves an invalid index that must be dropped before the migration is retried.
+CREATE INDEX CONCURRENTLY IF NOT EXISTS {index} ON {table} ({columns});
+"
+            );
+            let down = format!(
+                "\
+-- no-transaction
+-- `DROP INDEX CONCURRENTLY` doesn't block queries to the table, but can't run in a transaction.
+DROP INDEX CONCURRENTLY IF EXISTS {index};
+"
+            );
+            (up, down)
+        }
+        MigrationTemplate::BackfillWithBatching => {
+            let up = format!(
+                "\
+-- no-transaction
+-- Fills `{column}` in batches committing each of them, so that row locks aren't held for the whole migration
+-- and an interrupted migration continues from the last committed batch. The directive above is required
+-- for `COMMIT` to work; keep the `DO` block the only statement of the migration.
+-- TODO: replace `TODO_BACKFILLED_VALUE` with the backfilled value; the migration fails until it's replaced.
+-- The value must never be `NULL`, otherwise the loop doesn't terminate. For large tables, add an index
+-- for selecting batches or iterate over the primary key instead.
+DO $$
+DECLARE
+    batch_size CONSTANT INTEGER := 10000;
+    updated_rows INTEGER;
+BEGIN
+    LOOP
+        UPDATE {table}
+        SET {column} = TODO_BACKFILLED_VALUE
+        WHERE ctid IN (
+            SELECT ctid FROM {table}
+            WHERE {column} IS NULL
+            LIMIT batch_size
+        );
+        GET DIAGNOSTICS updated_rows = ROW_COUNT;
+        EXIT WHEN updated_rows = 0;
+        COMMIT;
+    END LOOP;
+END $$;
+"
+            );
+            let down = format!(
+                "\
+-- Backfilled values can't be told apart from the ones written by the server afterwards, so they aren't reverted.
+-- If `{table}.{column}` was added in the same release, the migration adding it drops the column.
+"
+            );
+            (up, down)
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::{fmt::Write as _, path::Path};
+
+use anyhow::Context;
+use common::{
+    config::global_config,
+    db::{database_size, migrations_status, MigrationInfo},
+    logger,
+    spinner::Spinner,
+};
+use config::{DatabaseConfig, DatabaseSecrets, EcosystemConfig};
+use url::Url;
+use xshell::Shell;
+
+use crate::commands::args::{Dal, DatabaseStatusArgs};
+
+pub async fn run(shell: &Shell, args: DatabaseStatusArgs) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context("Chain not found")?;
+    let Some(secrets) = chain_config.get_secrets_config()? else {
+        logger::info(format!(
+            "Chain {} has no databases yet. Run genesis to create them",
+            chain_config.name
+        ));
+        return Ok(());
+    };
+
+    let database = secrets.database.resolve(shell)?;
+
+    for dal in args.dals() {
+        print_status(shell, &ecosystem_config.link_to_code, dal, &database).await?;
+    }
+    Ok(())
+}
+
+async fn print_status(
+    shell: &Shell,
+    link_to_code: &Path,
+    dal: Dal,
+    secrets: &DatabaseSecrets,
+) -> anyhow::Result<()> {
+    let url = dal.database_url(secrets);
+    let DatabaseConfig {
+        base_url: server_url,
+        database_name: name,
+    } = DatabaseConfig::from_url(Url::parse(url).context("Invalid database url")?)?;
+
+    let spinner = Spinner::new(&format!("Checking {dal} database {name}..."));
+    let size = database_size(&server_url, &name)
+        .await
+        .with_context(|| format!("Failed to get size of database {name}"))?;
+    let Some(size) = size else {
+        spinner.finish();
+        logger::warn(format!("{dal} database {name} doesn't exist"));
+        return Ok(());
+    };
+    let status = migrations_status(shell, link_to_code.join(dal.migrations_path()), url)
+        .await
+        .with_context(|| format!("Failed to get migrations status of database {name}"))?;
+    spinner.finish();
+
+    let mut report = format!("Size: {}", format_size(size));
+    write!(report, "\nApplied migrations: {}", status.applied.len())?;
+    if let Some(latest) = status.applied.last() {
+        write!(report, " (latest: {})", format_migration(latest))?;
+    }
+    if global_config().verbose {
+        write_migrations(&mut report, &status.applied)?;
+    }
+    write!(report, "\nPending migrations: {}", status.pending.len())?;
+    write_migrations(&mut report, &status.pending)?;
+
+    if let Some(version) = status.dirty_version {
+        write!(
+            report,
+            "\nDirty migration: {version}. It failed to apply; fix or drop the database before migrating"
+        )?;
+    }
+    if !status.checksum_mismatches.is_empty() {
+        write!(
+            report,
+            "\nChecksum mismatches (migration files were changed after applying): {:?}",
+            status.checksum_mismatches
+        )?;
+    }
+    if !status.unknown.is_empty() {
+        write!(
+            report,
+            "\nApplied migrations missing in the code: {:?}",
+            status.unknown
+        )?;
+    }
+
+    logger::note(format!("{dal} database {name}"), report);
+    Ok(())
+}
+
+fn format_migration(migration: &MigrationInfo) -> String {
+    format!("{} {}", migration.version, migration.description)
+}
+
+fn write_migrations(report: &mut String, migrations: &[MigrationInfo]) -> std::fmt::Result {
+    for migration in migrations {
+        write!(report, "\n  - {}", format_migration(migration))?;
+    }
+    Ok(())
+}
+
+fn format_size(size: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+
+    let mut size = size as f64;
+    let mut unit = UNITS[0];
+    for next_unit in &UNITS[1..] {
+        if size < 1024.0 {
+            break;
+        }
+        size /= 1024.0;
+        unit = next_unit;
+    }
+    format!("{size:.1} {unit}")
+}
@@ -0,0 +1,228 @@
+use std::{
+    path::{Path, PathBuf},
+    thread,
+};
+
+use common::{cmd::Cmd, logger, spinner::Spinner};
+use config::EcosystemConfig;
+use xshell::{cmd, Shell};
+
+use crate::commands::args::{FmtArgs, Formatter};
+
+/// Rust workspaces in the repository, relative to its root.
+const RUST_WORKSPACES: [&str; 3] = [".", "prover", "zk_toolbox"];
+/// `rustfmt` options used in CI. Unstable options are passed via CLI flags, so that they work on stable Rust.
+const RUSTFMT_CONFIG: [&str; 4] = [
+    "--config",
+    "imports_granularity=Crate",
+    "--config",
+    "group_imports=StdExternalCrate",
+];
+/// Extensions of files formatted with `prettier`.
+const PRETTIER_EXTENSIONS: [&str; 3] = ["ts", "js", "md"];
+/// Folder with `prettier` configs for each of the extensions.
+const PRETTIER_CONFIG_PATH: &str = "etc/prettier-config";
+/// Paths excluded from formatting with `prettier` in addition to the ones ignored by git.
+const PRETTIER_EXCLUDED_PATHS: [&str; 3] = [
+    ":(exclude)etc/prettier-config",
+    ":(exclude)etc/lint-config",
+    ":(exclude,glob)**/generated/**",
+];
+/// Rust workspace containing DAL crates with sqlx queries.
+const SQLX_WORKSPACE: &str = ".";
+/// Compiled sqlx query formatter of the `zk` tool (built with `yarn zk build`). It's reused as is, so that queries
+/// are formatted exactly as `zk fmt` does it in CI.
+const SQLX_FORMATTER_PATH: &str = "infrastructure/zk/build/format_sql.js";
+/// Folder with contracts, formatted with `prettier` using scripts from its `package.json`.
+const CONTRACTS_PATH: &str = "contracts";
+
+/// Single formatting job, run in a separate thread.
+struct FmtJob {
+    name: String,
+    dir: PathBuf,
+    kind: FmtJobKind,
+}
+
+enum FmtJobKind {
+    /// `rustfmt`, optionally followed by formatting sqlx queries (which requires running `rustfmt` once more).
+    Rustfmt {
+        format_sqlx_queries: bool,
+    },
+    Prettier {
+        extension: &'static str,
+    },
+    Contracts,
+}
+
+pub fn run(shell: &Shell, args: FmtArgs) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let root = &ecosystem_config.link_to_code;
+    let formatters = if args.formatters.is_empty() {
+        vec![
+            Formatter::Rustfmt,
+            Formatter::Prettier,
+            Formatter::Contracts,
+        ]
+    } else {
+        args.formatters
+    };
+
+    let mut jobs = vec![];
+    for formatter in formatters {
+        match formatter {
+            Formatter::Rustfmt => jobs.extend(RUST_WORKSPACES.into_iter().map(|workspace| {
+                let format_sqlx_queries = workspace == SQLX_WORKSPACE;
+                let name = if format_sqlx_queries {
+                    format!("rustfmt + sqlx queries ({workspace})")
+                } else {
+                    format!("rustfmt ({workspace})")
+                };
+                FmtJob {
+                    name,
+                    dir: root.join(workspace),
+                    kind: FmtJobKind::Rustfmt {
+                        format_sqlx_queries,
+                    },
+                }
+            })),
+            Formatter::Prettier => {
+                jobs.extend(PRETTIER_EXTENSIONS.into_iter().map(|extension| FmtJob {
+                    name: format!("prettier (*.{extension})"),
+                    dir: root.clone(),
+                    kind: FmtJobKind::Prettier { extension },
+                }))
+            }
+            Formatter::Contracts => jobs.extend(contracts_job(shell, root)),
+        }
+    }
+
+    let action = if args.check { "Checking" } else { "Formatting" };
+    let job_names: Vec<_> = jobs.iter().map(|job| job.name.as_str()).collect();
+    logger::info(format!("{action} code with: {}", job_names.join(", ")));
+
+    let spinner = Spinner::new(&format!("{action} code..."));
+    let results: Vec<_> = thread::scope(|scope| {
+        let handles: Vec<_> = jobs
+            .iter()
+            .map(|job| (job, scope.spawn(move || run_job(job, args.check))))
+            .collect();
+        handles
+            .into_iter()
+            .map(|(job, handle)| {
+                let result = handle.join().expect("formatting job panicked");
+                (job, result)
+            })
+            .collect()
+    });
+    spinner.finish();
+
+    let mut failed_jobs = vec![];
+    for (job, result) in results {
+        if let Err(err) = result {
+            logger::error(format!("{} failed: {err:#}", job.name));
+            failed_jobs.push(job.name.as_str());
+        }
+    }
+    if !failed_jobs.is_empty() {
+        let hint = if args.check {
+            ". Run `zk_supervisor fmt` to fix formatting"
+        } else {
+            ""
+        };
+        anyhow::bail!("Failed formatters: {}{hint}", failed_jobs.join(", "));
+    }
+
+    logger::outro(if args.check {
+        "Code is formatted"
+    } else {
+        "Code was formatted"
+    });
+    Ok(())
+}
+
+/// Creates a job formatting contracts, unless they are not checked out.
+fn contracts_job(shell: &Shell, root: &Path) -> Option<FmtJob> {
+    let contracts_path = root.join(CONTRACTS_PATH);
+    if !shell.path_exists(contracts_path.join("package.json")) {
+        logger::warn("Contracts are not checked out; skipping formatting them");
+        return None;
+    }
+    Some(FmtJob {
+        name: format!("prettier ({CONTRACTS_PATH})"),
+        dir: root.to_path_buf(),
+        kind: FmtJobKind::Contracts,
+    })
+}
+
+fn run_job(job: &FmtJob, check: bool) -> anyhow::Result<()> {
+    // `Shell` cannot be shared among threads, so each job uses its own one.
+    let shell = Shell::new()?;
+    shell.change_dir(&job.dir);
+    let check = check.then_some("--check");
+
+    match job.kind {
+        FmtJobKind::Rustfmt {
+            format_sqlx_queries,
+        } => {
+            let rustfmt =
+                || Cmd::new(cmd!(shell, "cargo fmt -- {check...} {RUSTFMT_CONFIG...}")).run();
+            rustfmt()?;
+            if format_sqlx_queries {
+                run_sqlx_formatter(&shell, check.is_some())?;
+                // The sqlx formatter doesn't produce `rustfmt`-compliant code, so it's formatted once more.
+                // In the check mode, nothing is changed, so there's no need to do this.
+                if check.is_none() {
+                    rustfmt()?;
+                }
+            }
+            Ok(())
+        }
+        FmtJobKind::Prettier { extension } => {
+            let pattern = format!("*.{extension}");
+            let files = cmd!(
+                shell,
+                "git ls-files --cached --others --exclude-standard -- {pattern} {PRETTIER_EXCLUDED_PATHS...}"
+            )
+            .read()?;
+            let files: Vec<_> = files.lines().collect();
+            if files.is_empty() {
+                return Ok(());
+            }
+
+            let config = format!("{PRETTIER_CONFIG_PATH}/{extension}.js");
+            let mode = if check.is_some() {
+                "--check"
+            } else {
+                "--write"
+            };
+            Cmd::new(cmd!(
+                shell,
+                "yarn --silent prettier --config {config} {mode} {files...}"
+            ))
+            .run()
+        }
+        FmtJobKind::Contracts => {
+            let script = if check.is_some() {
+                "prettier:check"
+            } else {
+                "prettier:fix"
+            };
+            Cmd::new(cmd!(shell, "yarn --silent --cwd {CONTRACTS_PATH} {script}")).run()
+        }
+    }
+}
+
+/// Formats sqlx queries in DAL crates using the `zk` tool implementation.
+fn run_sqlx_formatter(shell: &Shell, check: bool) -> anyhow::Result<()> {
+    if !shell.path_exists(SQLX_FORMATTER_PATH) {
+        anyhow::bail!("sqlx query formatter is not built; run `yarn zk build` first");
+    }
+    let mode = if check { "check" } else { "fix" };
+    let script = format!(
+        "require('./{SQLX_FORMATTER_PATH}').formatSqlxQueries(process.argv[1] === 'check')\
+         .catch((err) => {{ console.error(err.message); process.exit(1); }})"
+    );
+    // The formatter resolves files relative to `ZKSYNC_HOME`.
+    let root = shell.current_dir();
+    Cmd::new(cmd!(shell, "node -e {script} {mode}").env("ZKSYNC_HOME", root)).run()
+}
@@ -0,0 +1,295 @@
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{BufRead, BufReader, Seek, SeekFrom},
+    path::Path,
+    thread,
+    time::Duration,
+};
+
+use anyhow::Context;
+use common::{config::global_config, logger};
+use config::EcosystemConfig;
+use serde_json::Value;
+use xshell::Shell;
+
+use crate::commands::args::{LogLevel, LogsArgs};
+
+const FOLLOW_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub fn run(shell: &Shell, args: LogsArgs) -> anyhow::Result<()> {
+    let ecosystem_config = EcosystemConfig::from_file(shell)?;
+    let chain_config = ecosystem_config
+        .load_chain(global_config().chain_name.clone())
+        .context("Chain not found")?;
+    let log_file = chain_config.logs_path().join(args.component.log_file());
+    if !shell.path_exists(&log_file) {
+        anyhow::bail!(
+            "No logs found for {} of chain {} at {}. Is the component running?",
+            args.component,
+            chain_config.name,
+            log_file.display()
+        );
+    }
+
+    let mut filter = LineFilter::new(args.level);
+    let position = print_tail(&log_file, args.lines, &mut filter)?;
+    if args.follow {
+        follow(&log_file, position, &mut filter)?;
+    }
+    Ok(())
+}
+
+/// Prints the last `lines` matching lines of the file and returns the position the file was read up to.
+fn print_tail(path: &Path, lines: usize, filter: &mut LineFilter) -> anyhow::Result<u64> {
+    let reader = BufReader::new(File::open(path)?);
+    let (tail, position) = read_tail(reader, lines, filter)?;
+    for line in tail {
+        println!("{line}");
+    }
+    Ok(position)
+}
+
+/// Reads the last `lines` complete lines passing the filter, and returns them together with the number
+/// of bytes read.
+fn read_tail(
+    mut reader: impl BufRead,
+    lines: usize,
+    filter: &mut LineFilter,
+) -> anyhow::Result<(VecDeque<String>, u64)> {
+    let mut tail = VecDeque::with_capacity(lines);
+    let mut line = String::new();
+    let mut position = 0;
+    loop {
+        line.clear();
+        let read = reader.read_line(&mut line)?;
+        if read == 0 || !line.ends_with('\n') {
+            // Incomplete line is still being written, it will be printed when following.
+            break;
+        }
+        position += read as u64;
+        // Lines are filtered even if none of them are shown, so that the filter state is up to date.
+        if let Some(formatted) = filter.apply(&line) {
+            if lines == 0 {
+                continue;
+            }
+            if tail.len() == lines {
+                tail.pop_front();
+            }
+            tail.push_back(formatted);
+        }
+    }
+    Ok((tail, position))
+}
+
+fn follow(path: &Path, mut position: u64, filter: &mut LineFilter) -> anyhow::Result<()> {
+    let mut line = String::new();
+    loop {
+        let mut file = File::open(path)?;
+        let len = file.metadata()?.len();
+        if len < position {
+            logger::warn("Log file was truncated, reading it from the start");
+            position = 0;
+        }
+        file.seek(SeekFrom::Start(position))?;
+        let mut reader = BufReader::new(file);
+        loop {
+            line.clear();
+            let read = reader.read_line(&mut line)?;
+            if read == 0 || !line.ends_with('\n') {
+                break;
+            }
+            position += read as u64;
+            if let Some(formatted) = filter.apply(&line) {
+                println!("{formatted}");
+            }
+        }
+        thread::sleep(FOLLOW_POLL_INTERVAL);
+    }
+}
+
+/// Filters log lines by severity and pretty-prints JSON-formatted lines.
+struct LineFilter {
+    max_level: Option<LogLevel>,
+    /// Whether the last line with a known level passed the filter. Lines without a level
+    /// (e.g., multiline messages or backtraces) share the fate of the preceding line.
+    last_passed: bool,
+}
+
+impl LineFilter {
+    fn new(max_level: Option<LogLevel>) -> Self {
+        Self {
+            max_level,
+            last_passed: true,
+        }
+    }
+
+    fn apply(&mut self, raw_line: &str) -> Option<String> {
+        let raw_line = raw_line.trim_end();
+        let (level, line) = match parse_json_line(raw_line) {
+            Some((level, line)) => (level, line),
+            None => {
+                let line = strip_ansi_codes(raw_line);
+                (parse_plain_level(&line), line)
+            }
+        };
+
+        if let Some(level) = level {
+            self.last_passed = self.max_level.map_or(true, |max_level| level <= max_level);
+        }
+        self.last_passed.then_some(line)
+    }
+}
+
+fn parse_level(level: &str) -> Option<LogLevel> {
+    Some(match level.to_ascii_uppercase().as_str() {
+        "ERROR" | "CRITICAL" => LogLevel::Error,
+        "WARN" | "WARNING" => LogLevel::Warn,
+        "INFO" => LogLevel::Info,
+        "DEBUG" => LogLevel::Debug,
+        "TRACE" => LogLevel::Trace,
+        _ => return None,
+    })
+}
+
+/// Parses a line written by the JSON log formatter, returning its level and a human-readable representation.
+fn parse_json_line(line: &str) -> Option<(Option<LogLevel>, String)> {
+    if !line.starts_with('{') {
+        return None;
+    }
+    let Value::Object(mut object) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    let level_str = object.remove("level")?;
+    let level_str = level_str.as_str()?;
+    let timestamp = object.remove("timestamp");
+    let target = object.remove("target");
+    let mut fields = match object.remove("fields") {
+        Some(Value::Object(fields)) => fields,
+        _ => Default::default(),
+    };
+    let message = fields.remove("message");
+
+    let mut formatted = String::new();
+    if let Some(Value::String(timestamp)) = timestamp {
+        formatted.push_str(&timestamp);
+        formatted.push(' ');
+    }
+    formatted.push_str(&format!("{level_str:>5}"));
+    if let Some(Value::String(target)) = target {
+        formatted.push_str(&format!(" {target}:"));
+    }
+    if let Some(message) = message {
+        match message {
+            Value::String(message) => formatted.push_str(&format!(" {message}")),
+            other => formatted.push_str(&format!(" {other}")),
+        }
+    }
+    for (name, value) in fields {
+        formatted.push_str(&format!(" {name}={value}"));
+    }
+    Some((parse_level(level_str), formatted))
+}
+
+fn parse_plain_level(line: &str) -> Option<LogLevel> {
+    // The plain formatter puts the level right after the timestamp.
+    line.split_whitespace().take(3).find_map(parse_level)
+}
+
+fn strip_ansi_codes(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            // Skip the control sequence up to and including its final byte.
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PLAIN_LOGS: &str = "\
+2024-05-20T10:00:00.000000Z  INFO zksync_server: Starting server
+2024-05-20T10:00:01.000000Z  WARN zksync_core::eth_sender: Low balance
+2024-05-20T10:00:02.000000Z ERROR zksync_core::api_server: Failed to start
+  0: backtrace line
+2024-05-20T10:00:03.000000Z DEBUG zksync_core::state_keeper: Sealed batch
+";
+
+    fn tail(logs: &str, lines: usize, level: Option<LogLevel>) -> (Vec<String>, u64) {
+        let mut filter = LineFilter::new(level);
+        let (tail, position) = read_tail(logs.as_bytes(), lines, &mut filter).unwrap();
+        (tail.into(), position)
+    }
+
+    #[test]
+    fn tailing_plain_logs() {
+        let (lines, position) = tail(PLAIN_LOGS, 2, None);
+        assert_eq!(position, PLAIN_LOGS.len() as u64);
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].ends_with("backtrace line"), "{lines:?}");
+        assert!(lines[1].ends_with("Sealed batch"), "{lines:?}");
+
+        let (lines, _) = tail(PLAIN_LOGS, 100, None);
+        assert_eq!(lines.len(), 5);
+
+        let (lines, position) = tail(PLAIN_LOGS, 0, None);
+        assert!(lines.is_empty(), "{lines:?}");
+        assert_eq!(position, PLAIN_LOGS.len() as u64);
+    }
+
+    #[test]
+    fn filtering_plain_logs_by_level() {
+        let (lines, _) = tail(PLAIN_LOGS, 100, Some(LogLevel::Warn));
+        // The backtrace line follows an error, so it must be retained.
+        assert_eq!(lines.len(), 3, "{lines:?}");
+        assert!(lines[0].contains("Low balance"), "{lines:?}");
+        assert!(lines[1].contains("Failed to start"), "{lines:?}");
+        assert!(lines[2].ends_with("backtrace line"), "{lines:?}");
+
+        let (lines, _) = tail(PLAIN_LOGS, 100, Some(LogLevel::Error));
+        assert_eq!(lines.len(), 2, "{lines:?}");
+    }
+
+    #[test]
+    fn incomplete_line_is_not_consumed() {
+        let logs = "2024-05-20T10:00:00.000000Z  INFO zksync_server: Started\n2024-05-20T10:00:01";
+        let (lines, position) = tail(logs, 10, None);
+        assert_eq!(lines.len(), 1);
+        assert_eq!(position, logs.find('\n').unwrap() as u64 + 1);
+    }
+
+    #[test]
+    fn parsing_json_logs() {
+        let line = r#"{"timestamp":"2024-05-20T10:00:00Z","level":"WARN","target":"zksync_server","fields":{"message":"Low balance","balance":1}}"#;
+        let (level, formatted) = parse_json_line(line).unwrap();
+        assert_eq!(level, Some(LogLevel::Warn));
+        assert_eq!(
+            formatted,
+            "2024-05-20T10:00:00Z  WARN zksync_server: Low balance balance=1"
+        );
+
+        let mut filter = LineFilter::new(Some(LogLevel::Error));
+        assert_eq!(filter.apply(line), None);
+        assert!(parse_json_line("{not json").is_none());
+        assert!(parse_json_line("plain line").is_none());
+    }
+
+    #[test]
+    fn stripping_ansi_codes() {
+        let line = "\u{1b}[2m2024-05-20T10:00:00Z\u{1b}[0m \u{1b}[32m INFO\u{1b}[0m started";
+        let stripped = strip_ansi_codes(line);
+        assert_eq!(stripped, "2024-05-20T10:00:00Z  INFO started");
+        assert_eq!(parse_plain_level(&stripped), Some(LogLevel::Info));
+    }
+}
@@ -0,0 +1,5 @@
+pub mod args;
+pub mod clean;
+pub mod database;
+pub mod fmt;
+pub mod logs;
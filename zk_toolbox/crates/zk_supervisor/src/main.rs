@@ -1,4 +1,78 @@
-fn main() {
+use clap::{Parser, Subcommand};
+use common::{
+    config::{init_global_config, GlobalConfig},
+    init_prompt_theme, logger,
+};
+use xshell::Shell;
+
+use crate::commands::{
+    args::{CleanArgs, FmtArgs, LogsArgs},
+    database::DatabaseCommands,
+};
+
+mod commands;
+
+#[derive(Parser, Debug)]
+#[command(version, about)]
+struct Supervisor {
+    #[command(subcommand)]
+    command: SupervisorSubcommands,
+    #[clap(flatten)]
+    global: SupervisorGlobalArgs,
+}
+
+#[derive(Subcommand, Debug)]
+enum SupervisorSubcommands {
+    /// Remove generated artifacts of a chain, leaving its configs intact
+    Clean(CleanArgs),
+    /// Inspect chain databases
+    #[command(subcommand)]
+    Database(DatabaseCommands),
+    /// Format the code in the whole repository, or check that it is formatted
+    Fmt(FmtArgs),
+    /// Show logs of a chain component
+    Logs(LogsArgs),
+}
+
+#[derive(Parser, Debug)]
+#[clap(next_help_heading = "Global options")]
+struct SupervisorGlobalArgs {
+    /// Verbose mode
+    #[clap(short, long, global = true)]
+    verbose: bool,
+    /// Chain to use
+    #[clap(long, global = true)]
+    chain: Option<String>,
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
     human_panic::setup_panic!();
-    println!("Hello, world!");
+
+    init_prompt_theme();
+
+    logger::new_empty_line();
+    logger::intro();
+
+    let shell = Shell::new().unwrap();
+    let args = Supervisor::parse();
+
+    init_global_config(GlobalConfig {
+        verbose: args.global.verbose,
+        chain_name: args.global.chain,
+        ignore_prerequisites: true,
+    });
+
+    let result = match args.command {
+        SupervisorSubcommands::Clean(args) => commands::clean::run(&shell, args).await,
+        SupervisorSubcommands::Database(args) => commands::database::run(&shell, args).await,
+        SupervisorSubcommands::Fmt(args) => commands::fmt::run(&shell, args),
+        SupervisorSubcommands::Logs(args) => commands::logs::run(&shell, args),
+    };
+    if let Err(err) = result {
+        logger::error(err.to_string());
+        logger::outro("Failed");
+        std::process::exit(1);
+    }
+    Ok(())
 }